@@ -0,0 +1,344 @@
+//citrate/cli/src/commands/audit.rs
+
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+use colored::Colorize;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde_json::Value;
+use sha3::{Digest, Sha3_256};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use crate::config::Config;
+
+/// Archive layout versions this tool understands
+const SUPPORTED_FORMAT_VERSION: u64 = 1;
+
+#[derive(Subcommand)]
+pub enum AuditCommands {
+    /// Verify an archive written by `citrate audit export`; needs no node
+    Verify {
+        /// Archive file
+        archive: PathBuf,
+
+        /// Public key (hex) the archive must be signed by, e.g. the
+        /// validator's registered block signing key
+        #[arg(long)]
+        signer: Option<String>,
+    },
+}
+
+pub async fn execute(cmd: AuditCommands, _config: &Config) -> Result<()> {
+    match cmd {
+        AuditCommands::Verify { archive, signer } => {
+            let content = std::fs::read_to_string(&archive)
+                .with_context(|| format!("Failed to read {}", archive.display()))?;
+            let report = verify_archive(&content, signer.as_deref())?;
+            report.print();
+            if !report.is_valid() {
+                bail!("Audit archive {} failed verification", archive.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Outcome of checking one archive
+#[derive(Debug)]
+pub struct VerifyReport {
+    pub chain_id: u64,
+    pub height: u64,
+    pub block_hash: String,
+    pub exported_at: String,
+    pub public_key: String,
+    /// Section name, entry count and whether its digest matched
+    pub sections: Vec<(String, u64, bool)>,
+    /// Members of the archive the manifest does not cover
+    pub unlisted: Vec<String>,
+    pub signature_valid: bool,
+    /// Whether the signer is the expected one, when one was given
+    pub signer_matches: Option<bool>,
+    pub notes: Vec<String>,
+}
+
+impl VerifyReport {
+    pub fn is_valid(&self) -> bool {
+        self.signature_valid
+            && self.signer_matches != Some(false)
+            && self.unlisted.is_empty()
+            && self.sections.iter().all(|(_, _, ok)| *ok)
+    }
+
+    fn print(&self) {
+        let mark = |ok: bool| if ok { "✓".green() } else { "✗".red() };
+
+        println!("{}", "Audit archive".cyan().bold());
+        println!("  Chain ID:    {}", self.chain_id);
+        println!("  Block:       {} ({})", self.height, self.block_hash);
+        println!("  Exported at: {}", self.exported_at);
+        println!("  Signer:      {}", self.public_key);
+        println!();
+        for (name, entries, ok) in &self.sections {
+            println!("  {} {:<10} {} entries", mark(*ok), name, entries);
+        }
+        for name in &self.unlisted {
+            println!("  {} {:<10} not covered by the manifest", mark(false), name);
+        }
+        println!("  {} signature", mark(self.signature_valid));
+        if let Some(matches) = self.signer_matches {
+            println!("  {} expected signer", mark(matches));
+        }
+        for note in &self.notes {
+            println!("  {} {}", "note:".yellow(), note);
+        }
+        println!();
+        if self.is_valid() {
+            println!("{}", "✓ Archive is intact and signed".green());
+        } else {
+            println!(
+                "{}",
+                "✗ Archive has been modified or is not signed by the expected key".red()
+            );
+        }
+    }
+}
+
+/// JSON with object keys sorted and no whitespace; must match the node's
+/// `audit::canonical_json`, which the digests and signature are made over
+fn canonical_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let keys: BTreeSet<&String> = map.keys().collect();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+fn decode_hex<const N: usize>(field: &str, value: &str) -> Result<[u8; N]> {
+    let bytes = hex::decode(value.trim_start_matches("0x"))
+        .with_context(|| format!("Invalid hex in {}", field))?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("{} must be {} bytes", field, N))
+}
+
+/// Check an archive's section digests and manifest signature. Malformed
+/// archives are errors; failed checks are reported.
+pub fn verify_archive(content: &str, expected_signer: Option<&str>) -> Result<VerifyReport> {
+    let archive: Value = serde_json::from_str(content).context("Archive is not valid JSON")?;
+    let manifest = archive.get("manifest").context("Archive has no manifest")?;
+    let signature = archive
+        .get("signature")
+        .context("Archive has no signature")?;
+
+    let text = |value: &Value, field: &str| -> Result<String> {
+        value
+            .get(field)
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .with_context(|| format!("Missing {}", field))
+    };
+    let number = |field: &str| -> Result<u64> {
+        manifest
+            .get(field)
+            .and_then(|v| v.as_u64())
+            .with_context(|| format!("Missing manifest.{}", field))
+    };
+
+    let version = number("format_version")?;
+    if version != SUPPORTED_FORMAT_VERSION {
+        bail!("Unsupported archive format version {}", version);
+    }
+    if text(signature, "algorithm")? != "ed25519" {
+        bail!("Unsupported signature algorithm");
+    }
+
+    let mut listed = BTreeSet::new();
+    let mut sections = Vec::new();
+    for section in manifest
+        .get("sections")
+        .and_then(|v| v.as_array())
+        .context("Missing manifest.sections")?
+    {
+        let name = text(section, "name")?;
+        let expected = text(section, "sha3_256")?;
+        let entries = section.get("entries").and_then(|v| v.as_u64()).unwrap_or(0);
+        let ok = archive.get(&name).is_some_and(|body| {
+            hex::encode(Sha3_256::digest(canonical_json(body).as_bytes())) == expected
+        });
+        listed.insert(name.clone());
+        sections.push((name, entries, ok));
+    }
+    let unlisted = archive
+        .as_object()
+        .map(|members| {
+            members
+                .keys()
+                .filter(|k| *k != "manifest" && *k != "signature" && !listed.contains(*k))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let public_key = text(signature, "public_key")?;
+    let key = VerifyingKey::from_bytes(&decode_hex::<32>("signature.public_key", &public_key)?)
+        .context("Invalid signer public key")?;
+    let sig = Signature::from_bytes(&decode_hex::<64>(
+        "signature.signature",
+        &text(signature, "signature")?,
+    )?);
+    let signature_valid = key
+        .verify(canonical_json(manifest).as_bytes(), &sig)
+        .is_ok();
+
+    let signer_matches = expected_signer
+        .map(|expected| {
+            Ok::<_, anyhow::Error>(decode_hex::<32>("--signer", expected)? == key.to_bytes())
+        })
+        .transpose()?;
+
+    Ok(VerifyReport {
+        chain_id: number("chain_id")?,
+        height: number("height")?,
+        block_hash: text(manifest, "block_hash")?,
+        exported_at: text(manifest, "exported_at")?,
+        public_key,
+        sections,
+        unlisted,
+        signature_valid,
+        signer_matches,
+        notes: manifest
+            .get("notes")
+            .and_then(|v| v.as_array())
+            .map(|notes| {
+                notes
+                    .iter()
+                    .filter_map(|n| n.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use serde_json::json;
+
+    fn digest(value: &Value) -> String {
+        hex::encode(Sha3_256::digest(canonical_json(value).as_bytes()))
+    }
+
+    /// An archive laid out the way `citrate audit export` writes it
+    fn signed_archive(key: &SigningKey) -> Value {
+        let accounts = json!([{"address": "0x0303030303030303030303030303030303030303", "balance": "1000", "nonce": 1, "code_hash": "0x00"}]);
+        let governance =
+            json!({"proposals": [], "parameters": {"min_gas_price": "e803000000000000"}});
+        let manifest = json!({
+            "format_version": 1,
+            "chain_id": 1337,
+            "height": 42,
+            "block_hash": "0x0101",
+            "block_timestamp": 1_700_000_000u64,
+            "state_root": "0x0202",
+            "exported_at": "2026-01-01T00:00:00+00:00",
+            "sections": [
+                {"name": "accounts", "entries": 1, "sha3_256": digest(&accounts)},
+                {"name": "governance", "entries": 0, "sha3_256": digest(&governance)},
+            ],
+            "notes": [],
+        });
+        let signature = key.sign(canonical_json(&manifest).as_bytes());
+        json!({
+            "manifest": manifest,
+            "accounts": accounts,
+            "governance": governance,
+            "signature": {
+                "algorithm": "ed25519",
+                "public_key": hex::encode(key.verifying_key().to_bytes()),
+                "signature": hex::encode(signature.to_bytes()),
+            },
+        })
+    }
+
+    #[test]
+    fn test_canonical_json_sorts_keys() {
+        let value: Value =
+            serde_json::from_str(r#"{"b": 1, "a": [{"d": "x", "c": null}]}"#).unwrap();
+        assert_eq!(
+            canonical_json(&value),
+            r#"{"a":[{"c":null,"d":"x"}],"b":1}"#
+        );
+    }
+
+    #[test]
+    fn test_verify_archive() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let signer = hex::encode(key.verifying_key().to_bytes());
+        let archive = signed_archive(&key);
+
+        let report = verify_archive(
+            &serde_json::to_string_pretty(&archive).unwrap(),
+            Some(&signer),
+        )
+        .unwrap();
+        assert!(report.is_valid());
+        assert_eq!(report.height, 42);
+        assert_eq!(report.signer_matches, Some(true));
+
+        // Edited balance: the section digest no longer matches
+        let mut tampered = archive.clone();
+        tampered["accounts"][0]["balance"] = json!("2000");
+        let report = verify_archive(&tampered.to_string(), None).unwrap();
+        assert!(report.signature_valid);
+        assert!(!report.sections[0].2);
+        assert!(!report.is_valid());
+
+        // Edited manifest: the signature no longer matches
+        let mut tampered = archive.clone();
+        tampered["manifest"]["height"] = json!(41);
+        assert!(!verify_archive(&tampered.to_string(), None)
+            .unwrap()
+            .is_valid());
+
+        // Extra data the manifest does not cover
+        let mut tampered = archive.clone();
+        tampered["models"] = json!([]);
+        assert!(!verify_archive(&tampered.to_string(), None)
+            .unwrap()
+            .is_valid());
+
+        // Valid archive, but from someone else
+        let other = hex::encode(SigningKey::from_bytes(&[8; 32]).verifying_key().to_bytes());
+        let report = verify_archive(&archive.to_string(), Some(&other)).unwrap();
+        assert_eq!(report.signer_matches, Some(false));
+        assert!(!report.is_valid());
+    }
+}
@@ -1,6 +1,7 @@
 //citrate/cli/src/commands/mod.rs
 
 pub mod account;
+pub mod audit;
 pub mod advanced;
 pub mod contract;
 pub mod governance;
@@ -9,7 +9,7 @@ mod commands;
 mod config;
 mod utils;
 
-use commands::{account, advanced, contract, governance, model, network, wizard};
+use commands::{account, advanced, audit, contract, governance, model, network, wizard};
 
 #[derive(Parser)]
 #[command(
@@ -65,6 +65,10 @@ enum Commands {
     #[command(subcommand)]
     Wizard(wizard::WizardCommands),
 
+    /// Verify signed audit archives exported by a node
+    #[command(subcommand)]
+    Audit(audit::AuditCommands),
+
     /// Initialize configuration
     Init {
         /// Force overwrite existing config
@@ -103,6 +107,7 @@ async fn main() -> Result<()> {
         Commands::Governance(cmd) => governance::execute(cmd, &config).await?,
         Commands::Advanced(cmd) => advanced::execute(cmd, &config).await?,
         Commands::Wizard(cmd) => wizard::execute(cmd, &config).await?,
+        Commands::Audit(cmd) => audit::execute(cmd, &config).await?,
         Commands::Init { force } => {
             config::Config::init(force)?;
             println!("{}", "✓ Configuration initialized successfully".green());
@@ -0,0 +1,474 @@
+// citrate/core/api/src/access.rs
+
+// Rate limiting and API-key auth for the RPC and REST servers
+//
+// Callers identify themselves with an API key in `Authorization: Bearer` or
+// `x-api-key`. Keys are issued into the config and only stored as a hash;
+// requests with a key count against that key's budget, requests without one
+// against their IP address's. Callers over budget get a 429 with a
+// `Retry-After` header. When the JSON-RPC server listens on a public
+// address, only allowlisted method namespaces are served.
+use crate::billing;
+use jsonrpc_http_server::hyper::{self, Body};
+use jsonrpc_http_server::{RequestMiddleware, RequestMiddlewareAction};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha3::{Digest, Sha3_256};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Prefix of issued API keys
+pub const API_KEY_PREFIX: &str = "ctr_";
+
+/// JSON-RPC error code for rejected requests (EIP-1474 "limit exceeded")
+const LIMIT_EXCEEDED: i64 = -32005;
+
+/// JSON-RPC error code for missing or unknown API keys
+const UNAUTHORIZED: i64 = -32001;
+
+/// Rate limiter buckets kept before expired ones are swept
+const MAX_BUCKETS: usize = 10_000;
+
+/// At most `requests` requests per `window_secs` seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub window_secs: u64,
+}
+
+impl RateLimit {
+    fn window(&self) -> Duration {
+        Duration::from_secs(self.window_secs.max(1))
+    }
+}
+
+/// An issued API key; the key itself is never stored
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKeyEntry {
+    /// Fingerprint the key is billed and logged under, see `billing::key_id`
+    pub id: String,
+    /// Hex SHA3-256 of the key
+    pub hash: String,
+    #[serde(default)]
+    pub label: String,
+    /// Overrides `AccessConfig::per_key` for this key
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// Who may call the RPC and REST servers, and how often
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessConfig {
+    /// Reject requests that don't carry an issued API key
+    pub require_api_key: bool,
+    pub api_keys: Vec<ApiKeyEntry>,
+    /// Budget of each IP address calling without an API key
+    pub per_ip: Option<RateLimit>,
+    /// Budget of each API key without its own `rate_limit`
+    pub per_key: Option<RateLimit>,
+    /// Take the client IP from `X-Forwarded-For` / `X-Real-IP`; only enable
+    /// behind a reverse proxy that sets them. The JSON-RPC server can't see
+    /// peer addresses, so without this its keyless callers share one budget.
+    pub trust_proxy_headers: bool,
+    /// Namespaces (e.g. `eth`) or full method names served when the
+    /// JSON-RPC server listens on a non-loopback address; `None` serves all
+    /// but `private_namespaces`
+    pub public_methods: Option<Vec<String>>,
+    /// Namespaces never served on a non-loopback address
+    pub private_namespaces: Vec<String>,
+}
+
+impl Default for AccessConfig {
+    fn default() -> Self {
+        Self {
+            require_api_key: false,
+            api_keys: Vec::new(),
+            per_ip: None,
+            per_key: None,
+            trust_proxy_headers: false,
+            public_methods: None,
+            private_namespaces: vec!["admin".to_string(), "debug".to_string()],
+        }
+    }
+}
+
+impl AccessConfig {
+    /// Issue a new API key, store its hash and return the key. The key
+    /// can't be recovered later.
+    pub fn issue_api_key(&mut self, label: &str, rate_limit: Option<RateLimit>) -> String {
+        let mut secret = [0u8; 24];
+        rand::thread_rng().fill_bytes(&mut secret);
+        let key = format!("{}{}", API_KEY_PREFIX, hex::encode(secret));
+        self.api_keys.push(ApiKeyEntry {
+            id: billing::key_id(&key),
+            hash: key_hash(&key),
+            label: label.to_string(),
+            rate_limit,
+        });
+        key
+    }
+
+    /// Remove the key with fingerprint `id`; false if there is none
+    pub fn revoke_api_key(&mut self, id: &str) -> bool {
+        let before = self.api_keys.len();
+        self.api_keys.retain(|entry| entry.id != id);
+        self.api_keys.len() != before
+    }
+
+    /// Whether `method` is served on a non-loopback address
+    pub fn is_public_method(&self, method: &str) -> bool {
+        let namespace = method.split('_').next().unwrap_or(method);
+        if self.private_namespaces.iter().any(|ns| ns == namespace) {
+            return false;
+        }
+        match &self.public_methods {
+            Some(allowed) => allowed.iter().any(|m| m == namespace || m == method),
+            None => true,
+        }
+    }
+}
+
+fn key_hash(api_key: &str) -> String {
+    hex::encode(Sha3_256::digest(api_key.as_bytes()))
+}
+
+/// API key from `Authorization: Bearer` (OpenAI) or `x-api-key` (Anthropic),
+/// with `header` looking up a header value by name
+pub fn api_key_from<'a>(header: impl Fn(&str) -> Option<&'a str>) -> Option<&'a str> {
+    header("authorization")
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or_else(|| header("x-api-key"))
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+}
+
+/// Why a request was turned away
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessDenied {
+    MissingKey,
+    InvalidKey,
+    RateLimited { retry_after: Duration },
+}
+
+impl AccessDenied {
+    pub fn status(&self) -> u16 {
+        match self {
+            Self::MissingKey | Self::InvalidKey => 401,
+            Self::RateLimited { .. } => 429,
+        }
+    }
+
+    /// Whole seconds to send in `Retry-After`, rounded up
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        match self {
+            Self::RateLimited { retry_after } => {
+                Some(retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Self::MissingKey => "API key required".to_string(),
+            Self::InvalidKey => "Invalid API key".to_string(),
+            Self::RateLimited { .. } => format!(
+                "Rate limit exceeded, retry after {}s",
+                self.retry_after_secs().unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// Fixed-window request counters
+#[derive(Debug, Default)]
+struct RateLimiter {
+    buckets: HashMap<String, (Instant, u32)>,
+}
+
+impl RateLimiter {
+    /// Count a request against `bucket`, or return how long until it may
+    /// be retried
+    fn check(&mut self, bucket: &str, limit: RateLimit, now: Instant) -> Result<(), Duration> {
+        let window = limit.window();
+        if self.buckets.len() >= MAX_BUCKETS && !self.buckets.contains_key(bucket) {
+            self.buckets
+                .retain(|_, (start, _)| now.duration_since(*start) < window);
+        }
+        let (start, count) = self.buckets.entry(bucket.to_string()).or_insert((now, 0));
+        if now.duration_since(*start) >= window {
+            *start = now;
+            *count = 0;
+        }
+        if *count >= limit.requests {
+            return Err(window - now.duration_since(*start));
+        }
+        *count += 1;
+        Ok(())
+    }
+}
+
+/// Enforces an `AccessConfig`; shared by the RPC and REST servers so a key
+/// has one budget across both
+#[derive(Debug)]
+pub struct AccessControl {
+    config: AccessConfig,
+    keys: HashMap<String, ApiKeyEntry>,
+    limiter: Mutex<RateLimiter>,
+}
+
+impl AccessControl {
+    pub fn new(config: AccessConfig) -> Self {
+        let keys = config
+            .api_keys
+            .iter()
+            .map(|entry| (entry.hash.clone(), entry.clone()))
+            .collect();
+        Self {
+            config,
+            keys,
+            limiter: Mutex::new(RateLimiter::default()),
+        }
+    }
+
+    pub fn config(&self) -> &AccessConfig {
+        &self.config
+    }
+
+    /// Client IP: `peer`, or the proxy headers looked up by `header` when
+    /// they are trusted
+    pub fn client_ip<'a>(
+        &self,
+        peer: Option<IpAddr>,
+        header: impl Fn(&str) -> Option<&'a str>,
+    ) -> Option<IpAddr> {
+        if !self.config.trust_proxy_headers {
+            return peer;
+        }
+        header("x-forwarded-for")
+            .and_then(|v| v.split(',').next())
+            .or_else(|| header("x-real-ip"))
+            .and_then(|v| v.trim().parse().ok())
+            .or(peer)
+    }
+
+    /// Check a request's API key and count it against its budget
+    pub fn authorize(&self, api_key: Option<&str>, ip: Option<IpAddr>) -> Result<(), AccessDenied> {
+        self.authorize_at(api_key, ip, Instant::now())
+    }
+
+    fn authorize_at(
+        &self,
+        api_key: Option<&str>,
+        ip: Option<IpAddr>,
+        now: Instant,
+    ) -> Result<(), AccessDenied> {
+        let (bucket, limit) = match api_key {
+            Some(key) => {
+                let entry = self
+                    .keys
+                    .get(&key_hash(key))
+                    .ok_or(AccessDenied::InvalidKey)?;
+                (
+                    format!("key:{}", entry.id),
+                    entry.rate_limit.or(self.config.per_key),
+                )
+            }
+            None if self.config.require_api_key => return Err(AccessDenied::MissingKey),
+            None => (
+                ip.map_or_else(|| "ip:unknown".to_string(), |ip| format!("ip:{}", ip)),
+                self.config.per_ip,
+            ),
+        };
+        let Some(limit) = limit else {
+            return Ok(());
+        };
+        let mut limiter = self.limiter.lock().unwrap_or_else(|e| e.into_inner());
+        limiter
+            .check(&bucket, limit, now)
+            .map_err(|retry_after| AccessDenied::RateLimited { retry_after })
+    }
+}
+
+/// Request middleware for the JSON-RPC HTTP server
+pub struct RpcAccessMiddleware {
+    access: std::sync::Arc<AccessControl>,
+    cors_any: bool,
+}
+
+impl RpcAccessMiddleware {
+    pub fn new(access: std::sync::Arc<AccessControl>, cors_any: bool) -> Self {
+        Self { access, cors_any }
+    }
+
+    fn reject(&self, denied: AccessDenied) -> hyper::Response<Body> {
+        let code = match denied {
+            AccessDenied::RateLimited { .. } => LIMIT_EXCEEDED,
+            _ => UNAUTHORIZED,
+        };
+        let body = json!({
+            "jsonrpc": "2.0",
+            "error": { "code": code, "message": denied.message() },
+            "id": null,
+        });
+        let mut response = hyper::Response::builder()
+            .status(denied.status())
+            .header(hyper::header::CONTENT_TYPE, "application/json");
+        if let Some(secs) = denied.retry_after_secs() {
+            response = response.header(hyper::header::RETRY_AFTER, secs);
+        }
+        if self.cors_any {
+            response = response.header(hyper::header::ACCESS_CONTROL_ALLOW_ORIGIN, "*");
+        }
+        response
+            .body(Body::from(body.to_string()))
+            .unwrap_or_else(|_| hyper::Response::new(Body::empty()))
+    }
+}
+
+impl RequestMiddleware for RpcAccessMiddleware {
+    fn on_request(&self, request: hyper::Request<Body>) -> RequestMiddlewareAction {
+        // Let CORS preflights through; they carry no credentials
+        if request.method() == hyper::Method::OPTIONS {
+            return request.into();
+        }
+        let headers = request.headers();
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        let ip = self.access.client_ip(None, header);
+        match self.access.authorize(api_key_from(header), ip) {
+            Ok(()) => request.into(),
+            Err(denied) => self.reject(denied).into(),
+        }
+    }
+}
+
+/// Whether a server listening on `addr` is reachable from other hosts
+pub fn is_public(addr: &SocketAddr) -> bool {
+    !addr.ip().is_loopback()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(requests: u32, window_secs: u64) -> RateLimit {
+        RateLimit {
+            requests,
+            window_secs,
+        }
+    }
+
+    #[test]
+    fn test_issued_keys_authorize() {
+        let mut config = AccessConfig {
+            require_api_key: true,
+            ..Default::default()
+        };
+        let key = config.issue_api_key("ci", None);
+        assert!(key.starts_with(API_KEY_PREFIX));
+        assert!(!config.api_keys[0].hash.contains(&key));
+        let id = config.api_keys[0].id.clone();
+        assert_eq!(id, billing::key_id(&key));
+
+        let access = AccessControl::new(config.clone());
+        assert_eq!(access.authorize(Some(&key), None), Ok(()));
+        assert_eq!(access.authorize(None, None), Err(AccessDenied::MissingKey));
+        assert_eq!(
+            access.authorize(Some("ctr_nope"), None),
+            Err(AccessDenied::InvalidKey)
+        );
+
+        assert!(config.revoke_api_key(&id));
+        assert!(!config.revoke_api_key(&id));
+        let access = AccessControl::new(config);
+        assert_eq!(
+            access.authorize(Some(&key), None),
+            Err(AccessDenied::InvalidKey)
+        );
+    }
+
+    #[test]
+    fn test_rate_limits_per_ip_and_key() {
+        let mut config = AccessConfig {
+            per_ip: Some(limit(2, 10)),
+            per_key: Some(limit(1, 60)),
+            ..Default::default()
+        };
+        let key = config.issue_api_key("default", None);
+        let generous = config.issue_api_key("generous", Some(limit(3, 60)));
+        let access = AccessControl::new(config);
+        let now = Instant::now();
+        let a: IpAddr = "10.0.0.1".parse().unwrap();
+        let b: IpAddr = "10.0.0.2".parse().unwrap();
+
+        assert!(access.authorize_at(None, Some(a), now).is_ok());
+        assert!(access.authorize_at(None, Some(a), now).is_ok());
+        let denied = access
+            .authorize_at(None, Some(a), now + Duration::from_millis(1500))
+            .unwrap_err();
+        assert_eq!(denied.status(), 429);
+        assert_eq!(denied.retry_after_secs(), Some(9));
+        assert!(access.authorize_at(None, Some(b), now).is_ok());
+        assert!(access
+            .authorize_at(None, Some(a), now + Duration::from_secs(10))
+            .is_ok());
+
+        // Keyed requests use the key's budget, not the IP's
+        assert!(access.authorize_at(Some(&key), Some(a), now).is_ok());
+        assert!(access.authorize_at(Some(&key), Some(b), now).is_err());
+        for _ in 0..3 {
+            assert!(access.authorize_at(Some(&generous), Some(a), now).is_ok());
+        }
+        assert!(access.authorize_at(Some(&generous), Some(a), now).is_err());
+    }
+
+    #[test]
+    fn test_client_ip_and_api_key_headers() {
+        let headers: HashMap<&str, &str> = [
+            ("authorization", "Bearer ctr_abc"),
+            ("x-forwarded-for", "203.0.113.7, 10.0.0.1"),
+        ]
+        .into_iter()
+        .collect();
+        let header = |name: &str| headers.get(name).copied();
+        assert_eq!(api_key_from(header), Some("ctr_abc"));
+        assert_eq!(api_key_from(|_| None), None);
+        assert_eq!(
+            api_key_from(|name| (name == "x-api-key").then_some(" ctr_def ")),
+            Some("ctr_def")
+        );
+
+        let peer: IpAddr = "127.0.0.1".parse().unwrap();
+        let direct = AccessControl::new(AccessConfig::default());
+        assert_eq!(direct.client_ip(Some(peer), header), Some(peer));
+        let proxied = AccessControl::new(AccessConfig {
+            trust_proxy_headers: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            proxied.client_ip(Some(peer), header),
+            Some("203.0.113.7".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_public_methods() {
+        let config = AccessConfig::default();
+        assert!(config.is_public_method("eth_blockNumber"));
+        assert!(!config.is_public_method("admin_rotateSigningKey"));
+        assert!(!config.is_public_method("debug_traceTransaction"));
+
+        let config = AccessConfig {
+            public_methods: Some(vec!["eth".to_string(), "net_version".to_string()]),
+            ..Default::default()
+        };
+        assert!(config.is_public_method("eth_call"));
+        assert!(config.is_public_method("net_version"));
+        assert!(!config.is_public_method("net_peerCount"));
+        assert!(!config.is_public_method("citrate_getModel"));
+    }
+}
@@ -218,6 +218,10 @@ pub fn register_ai_methods(
                 max_tokens,
                 temperature,
                 top_p: None,
+                top_k: None,
+                min_p: None,
+                repeat_penalty: None,
+                logit_bias: None,
                 n: None,
                 stop: None,
                 stream: Some(false),
@@ -0,0 +1,138 @@
+// citrate/core/api/src/batch.rs
+
+// Batch JSON-RPC requests
+// An array of calls in one HTTP request is answered with an array of
+// responses, so indexers can pull many blocks or receipts in one round trip.
+// The IoHandler already executes batches; this middleware bounds their size,
+// answers an empty batch with an Invalid Request error as the JSON-RPC 2.0
+// spec requires, and records batch sizes.
+
+use crate::metrics::rpc_batch;
+use futures::future::{self, Either, Ready};
+use jsonrpc_core::middleware::NoopCallFuture;
+use jsonrpc_core::{Error, ErrorCode, Failure, Id, Middleware, Output, Request, Response, Version};
+use std::future::Future;
+
+/// Calls allowed in one batch request when not configured
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 1000;
+
+/// Enforces the batch size limit before any call in the batch runs
+#[derive(Debug, Clone)]
+pub struct BatchLimit {
+    max_batch_size: usize,
+}
+
+impl BatchLimit {
+    /// Limit batches to `max_batch_size` calls; 0 rejects every batch
+    pub fn new(max_batch_size: usize) -> Self {
+        Self { max_batch_size }
+    }
+
+    /// The error to answer `request` with instead of running it, if any
+    pub fn check(&self, request: &Request) -> Option<Error> {
+        let Request::Batch(calls) = request else {
+            return None;
+        };
+        if calls.is_empty() {
+            return Some(Error::invalid_request());
+        }
+        if calls.len() > self.max_batch_size {
+            return Some(Error {
+                code: ErrorCode::InvalidRequest,
+                message: format!(
+                    "Batch of {} calls exceeds the limit of {}",
+                    calls.len(),
+                    self.max_batch_size
+                ),
+                data: None,
+            });
+        }
+        None
+    }
+}
+
+impl Default for BatchLimit {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_BATCH_SIZE)
+    }
+}
+
+impl Middleware<()> for BatchLimit {
+    type Future = Ready<Option<Response>>;
+    type CallFuture = NoopCallFuture;
+
+    fn on_request<F, X>(&self, request: Request, meta: (), next: F) -> Either<Self::Future, X>
+    where
+        F: Fn(Request, ()) -> X + Send + Sync,
+        X: Future<Output = Option<Response>> + Send + 'static,
+    {
+        if let Some(error) = self.check(&request) {
+            return Either::Left(future::ready(Some(Response::Single(Output::Failure(
+                Failure {
+                    jsonrpc: Some(Version::V2),
+                    error,
+                    id: Id::Null,
+                },
+            )))));
+        }
+        if let Request::Batch(calls) = &request {
+            rpc_batch(calls.len());
+        }
+        Either::Right(next(request, meta))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::{IoHandler, MetaIoHandler, Value};
+    use serde_json::json;
+
+    fn handler(max_batch_size: usize) -> MetaIoHandler<(), BatchLimit> {
+        let mut io = IoHandler::new();
+        io.add_sync_method("eth_chainId", |_| Ok(Value::String("0x539".into())));
+        let mut limited = MetaIoHandler::with_middleware(BatchLimit::new(max_batch_size));
+        limited.extend_with(io);
+        limited
+    }
+
+    fn call(io: &MetaIoHandler<(), BatchLimit>, request: Value) -> Value {
+        let response = io.handle_request_sync(&request.to_string(), ()).unwrap();
+        serde_json::from_str(&response).unwrap()
+    }
+
+    #[test]
+    fn test_batch_answers_each_call() {
+        let io = handler(2);
+        let response = call(
+            &io,
+            json!([
+                {"jsonrpc": "2.0", "id": 1, "method": "eth_chainId", "params": []},
+                {"jsonrpc": "2.0", "id": 2, "method": "eth_missing", "params": []},
+            ]),
+        );
+        let outputs = response.as_array().unwrap();
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0]["result"], "0x539");
+        assert_eq!(outputs[1]["error"]["code"], -32601);
+    }
+
+    #[test]
+    fn test_batch_limits() {
+        let io = handler(2);
+        let single = json!({"jsonrpc": "2.0", "id": 1, "method": "eth_chainId"});
+
+        let response = call(&io, json!([single, single, single]));
+        assert_eq!(response["error"]["code"], -32600);
+        assert!(response["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("limit of 2"));
+
+        let response = call(&io, json!([]));
+        assert_eq!(response["error"]["code"], -32600);
+
+        // Single calls are never limited
+        assert_eq!(call(&handler(0), single)["result"], "0x539");
+    }
+}
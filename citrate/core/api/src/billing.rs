@@ -0,0 +1,360 @@
+// citrate/core/api/src/billing.rs
+
+// Usage-based billing for API-key consumers of the REST API
+//
+// Every completed request is appended to a JSON lines ledger with the key
+// it was made with, the model, token counts and cost. The ledger is rolled
+// up per calendar month (UTC), key and model for invoicing. Keys are only
+// stored as a fingerprint, see `key_id`.
+use anyhow::{Context, Result};
+use chrono::{TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+
+/// Key id recorded for requests made without an API key
+pub const ANONYMOUS_KEY: &str = "anonymous";
+
+/// Price per 1000 tokens, in the smallest unit of the billing currency
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    #[serde(with = "decimal")]
+    pub prompt_per_1k: u128,
+    #[serde(with = "decimal")]
+    pub completion_per_1k: u128,
+}
+
+impl ModelPrice {
+    /// Cost of one request, rounded down
+    pub fn cost(&self, prompt_tokens: u64, completion_tokens: u64) -> u128 {
+        let prompt = self.prompt_per_1k.saturating_mul(prompt_tokens as u128);
+        let completion = self
+            .completion_per_1k
+            .saturating_mul(completion_tokens as u128);
+        prompt.saturating_add(completion) / 1000
+    }
+}
+
+/// Token prices, per model with a default for unlisted models
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BillingPricing {
+    /// Label for the unit costs are expressed in, e.g. "wei" or "usd-micros"
+    pub currency: String,
+    pub default: ModelPrice,
+    pub models: HashMap<String, ModelPrice>,
+}
+
+impl BillingPricing {
+    /// Load pricing from a JSON file
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("reading billing pricing {}", path.display()))?;
+        serde_json::from_str(&data)
+            .with_context(|| format!("parsing billing pricing {}", path.display()))
+    }
+
+    pub fn price(&self, model: &str) -> ModelPrice {
+        self.models.get(model).copied().unwrap_or(self.default)
+    }
+}
+
+/// One billed request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    /// Unix seconds
+    pub timestamp: i64,
+    pub key_id: String,
+    pub model: String,
+    pub endpoint: String,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    #[serde(with = "decimal")]
+    pub cost: u128,
+}
+
+/// Usage of one key and model over a calendar month
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthlyUsage {
+    /// "YYYY-MM", UTC
+    pub month: String,
+    pub key_id: String,
+    pub model: String,
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+    #[serde(with = "decimal")]
+    pub cost: u128,
+}
+
+impl MonthlyUsage {
+    fn add(&mut self, record: &UsageRecord) {
+        self.requests += 1;
+        self.prompt_tokens += record.prompt_tokens;
+        self.completion_tokens += record.completion_tokens;
+        self.total_tokens += record.prompt_tokens + record.completion_tokens;
+        self.cost = self.cost.saturating_add(record.cost);
+    }
+}
+
+type RollupKey = (String, String, String);
+
+/// Append-only usage ledger with monthly rollups kept in memory
+pub struct BillingLedger {
+    path: PathBuf,
+    pricing: BillingPricing,
+    rollups: RwLock<BTreeMap<RollupKey, MonthlyUsage>>,
+    append_lock: Mutex<()>,
+}
+
+impl BillingLedger {
+    /// Open the ledger at `path`, creating it if needed, and rebuild the
+    /// rollups from the records already in it
+    pub fn open(path: impl Into<PathBuf>, pricing: BillingPricing) -> Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut rollups = BTreeMap::new();
+        if path.exists() {
+            let file = std::fs::File::open(&path)
+                .with_context(|| format!("opening billing ledger {}", path.display()))?;
+            for (n, line) in BufReader::new(file).lines().enumerate() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let record: UsageRecord = serde_json::from_str(&line)
+                    .with_context(|| format!("billing ledger {} line {}", path.display(), n + 1))?;
+                Self::roll_up(&mut rollups, &record);
+            }
+        }
+
+        Ok(Self {
+            path,
+            pricing,
+            rollups: RwLock::new(rollups),
+            append_lock: Mutex::new(()),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn pricing(&self) -> &BillingPricing {
+        &self.pricing
+    }
+
+    /// Price and append one request. `api_key` is the raw key the request
+    /// was made with.
+    pub fn record(
+        &self,
+        api_key: Option<&str>,
+        model: &str,
+        endpoint: &str,
+        prompt_tokens: u64,
+        completion_tokens: u64,
+    ) -> Result<UsageRecord> {
+        let record = UsageRecord {
+            timestamp: Utc::now().timestamp(),
+            key_id: api_key.map_or_else(|| ANONYMOUS_KEY.to_string(), key_id),
+            model: model.to_string(),
+            endpoint: endpoint.to_string(),
+            prompt_tokens,
+            completion_tokens,
+            cost: self
+                .pricing
+                .price(model)
+                .cost(prompt_tokens, completion_tokens),
+        };
+        self.append(&record)?;
+        Ok(record)
+    }
+
+    fn append(&self, record: &UsageRecord) -> Result<()> {
+        let line = serde_json::to_string(record)?;
+        let _guard = self.append_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", line)?;
+
+        let mut rollups = self.rollups.write().unwrap_or_else(|e| e.into_inner());
+        Self::roll_up(&mut rollups, record);
+        Ok(())
+    }
+
+    fn roll_up(rollups: &mut BTreeMap<RollupKey, MonthlyUsage>, record: &UsageRecord) {
+        let month = month_of(record.timestamp);
+        rollups
+            .entry((month.clone(), record.key_id.clone(), record.model.clone()))
+            .or_insert_with(|| MonthlyUsage {
+                month,
+                key_id: record.key_id.clone(),
+                model: record.model.clone(),
+                ..Default::default()
+            })
+            .add(record);
+    }
+
+    /// Monthly rollups ordered by month, key and model. `month` ("YYYY-MM")
+    /// and `key_id` narrow the result.
+    pub fn monthly_usage(&self, month: Option<&str>, key_id: Option<&str>) -> Vec<MonthlyUsage> {
+        self.rollups
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .filter(|usage| month.is_none_or(|m| usage.month == m))
+            .filter(|usage| key_id.is_none_or(|k| usage.key_id == k))
+            .cloned()
+            .collect()
+    }
+
+    /// `monthly_usage` as CSV with a header row
+    pub fn export_csv(&self, month: Option<&str>, key_id: Option<&str>) -> String {
+        let mut csv = String::from(
+            "month,key_id,model,requests,prompt_tokens,completion_tokens,total_tokens,cost\n",
+        );
+        for usage in self.monthly_usage(month, key_id) {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                usage.month,
+                usage.key_id,
+                csv_field(&usage.model),
+                usage.requests,
+                usage.prompt_tokens,
+                usage.completion_tokens,
+                usage.total_tokens,
+                usage.cost
+            ));
+        }
+        csv
+    }
+}
+
+/// Fingerprint an API key is billed under: "key_" and the first 8 bytes of
+/// its SHA3-256 hash. Providers compute the same id to match keys they
+/// issued to invoices.
+pub fn key_id(api_key: &str) -> String {
+    let digest = Sha3_256::digest(api_key.as_bytes());
+    format!("key_{}", hex::encode(&digest[..8]))
+}
+
+fn month_of(timestamp: i64) -> String {
+    Utc.timestamp_opt(timestamp, 0)
+        .single()
+        .unwrap_or_default()
+        .format("%Y-%m")
+        .to_string()
+}
+
+/// Model names come from the request body, so quote them when needed
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// u128 amounts as decimal strings, which JSON clients read without losing
+// precision
+mod decimal {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn pricing() -> BillingPricing {
+        BillingPricing {
+            currency: "wei".to_string(),
+            default: ModelPrice {
+                prompt_per_1k: 1000,
+                completion_per_1k: 2000,
+            },
+            models: HashMap::from([(
+                "large".to_string(),
+                ModelPrice {
+                    prompt_per_1k: 10_000,
+                    completion_per_1k: 30_000,
+                },
+            )]),
+        }
+    }
+
+    #[test]
+    fn test_record_rollup_and_reopen() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("billing").join("usage.jsonl");
+        let ledger = BillingLedger::open(&path, pricing()).unwrap();
+
+        let first = ledger
+            .record(Some("sk-a"), "small", "chat", 100, 50)
+            .unwrap();
+        assert_eq!(first.cost, 200);
+        assert_eq!(first.key_id, key_id("sk-a"));
+        ledger
+            .record(Some("sk-a"), "small", "chat", 400, 0)
+            .unwrap();
+        ledger
+            .record(Some("sk-b"), "large", "chat", 1000, 1000)
+            .unwrap();
+        ledger.record(None, "small", "embeddings", 10, 0).unwrap();
+
+        let usage = ledger.monthly_usage(None, Some(&key_id("sk-a")));
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].requests, 2);
+        assert_eq!(usage[0].total_tokens, 550);
+        assert_eq!(usage[0].cost, 600);
+        assert_eq!(ledger.monthly_usage(None, Some(ANONYMOUS_KEY)).len(), 1);
+
+        // Rollups are rebuilt from the ledger file
+        let reopened = BillingLedger::open(&path, pricing()).unwrap();
+        assert_eq!(
+            reopened.monthly_usage(None, None),
+            ledger.monthly_usage(None, None)
+        );
+
+        let month = month_of(first.timestamp);
+        let csv = reopened.export_csv(Some(&month), Some(&key_id("sk-b")));
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[1],
+            format!("{},{},large,1,1000,1000,2000,40000", month, key_id("sk-b"))
+        );
+        assert!(reopened.monthly_usage(Some("1999-01"), None).is_empty());
+    }
+
+    #[test]
+    fn test_cost_serializes_as_string() {
+        let usage = MonthlyUsage {
+            cost: u128::MAX,
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&usage).unwrap();
+        assert_eq!(json["cost"], u128::MAX.to_string());
+        assert_eq!(json["keyId"], "");
+    }
+}
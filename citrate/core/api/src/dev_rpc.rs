@@ -0,0 +1,140 @@
+// citrate/core/api/src/dev_rpc.rs
+
+// Devnet block production control
+// Lets contract developers drive block production deterministically, the
+// way Anvil and Hardhat do: `dev_setBlockInterval` changes or stops interval
+// mining, `dev_setAutomine` mines a block whenever a transaction arrives,
+// and `dev_mineBlocks` mines blocks on demand. Only built with the `devnet`
+// feature.
+
+use crate::metrics::rpc_request;
+use async_trait::async_trait;
+use citrate_consensus::types::Hash;
+use citrate_sequencer::mining_schedule::MAX_MINE_BLOCKS;
+use citrate_sequencer::MiningMode;
+use jsonrpc_core::{ErrorCode, IoHandler, Params, Value};
+use serde_json::json;
+use std::sync::Arc;
+use tokio::runtime::Handle;
+
+/// Controls how the node produces blocks; implemented by the node's block
+/// producer
+#[async_trait]
+pub trait DevChainControl: Send + Sync {
+    fn mining_mode(&self) -> MiningMode;
+
+    /// Seconds between interval-mined blocks; 0 stops interval mining
+    fn set_block_interval(&self, seconds: u64);
+
+    /// Mine a block as soon as a transaction enters the mempool
+    fn set_automine(&self, enabled: bool);
+
+    /// Mine `count` blocks now, one after another, returning their hashes
+    async fn mine_blocks(&self, count: u64) -> anyhow::Result<Vec<Hash>>;
+}
+
+/// Read a non-negative integer given as a JSON number or hex string
+fn parse_u64(value: Option<&Value>, field: &str) -> Result<u64, jsonrpc_core::Error> {
+    let invalid = || jsonrpc_core::Error::invalid_params(format!("Invalid {}", field));
+    match value {
+        Some(Value::Number(n)) => n.as_u64().ok_or_else(invalid),
+        Some(Value::String(s)) => {
+            let hex = s.strip_prefix("0x").ok_or_else(|| {
+                jsonrpc_core::Error::invalid_params(format!("{} must be 0x-prefixed", field))
+            })?;
+            u64::from_str_radix(hex, 16).map_err(|_| invalid())
+        }
+        _ => Err(jsonrpc_core::Error::invalid_params(format!(
+            "Missing {}",
+            field
+        ))),
+    }
+}
+
+fn parse_params(params: Params) -> Result<Vec<Value>, jsonrpc_core::Error> {
+    match params {
+        Params::None => Ok(Vec::new()),
+        params => params
+            .parse()
+            .map_err(|e| jsonrpc_core::Error::invalid_params(e.to_string())),
+    }
+}
+
+/// Blocks requested by `dev_mineBlocks`; one when no count is given
+pub fn parse_block_count(params: &[Value]) -> Result<u64, jsonrpc_core::Error> {
+    let count = match params.first() {
+        None | Some(Value::Null) => 1,
+        value => parse_u64(value, "block count")?,
+    };
+    if count == 0 || count > MAX_MINE_BLOCKS {
+        return Err(jsonrpc_core::Error::invalid_params(format!(
+            "Block count must be between 1 and {}",
+            MAX_MINE_BLOCKS
+        )));
+    }
+    Ok(count)
+}
+
+/// Add devnet block production methods to the IoHandler. Block production
+/// runs on `runtime`, since RPC handlers are served from plain threads.
+pub fn register_dev_methods(
+    io_handler: &mut IoHandler,
+    control: Arc<dyn DevChainControl>,
+    runtime: Handle,
+) {
+    // dev_setBlockInterval - [seconds]; 0 stops interval mining
+    let control_interval = control.clone();
+    io_handler.add_sync_method("dev_setBlockInterval", move |params: Params| {
+        rpc_request("dev_setBlockInterval");
+        let params = parse_params(params)?;
+        let seconds = parse_u64(params.first(), "block interval")?;
+        control_interval.set_block_interval(seconds);
+        Ok(json!(control_interval.mining_mode()))
+    });
+
+    // dev_setAutomine - [enabled]
+    let control_automine = control.clone();
+    io_handler.add_sync_method("dev_setAutomine", move |params: Params| {
+        rpc_request("dev_setAutomine");
+        let params = parse_params(params)?;
+        let enabled = params
+            .first()
+            .and_then(Value::as_bool)
+            .ok_or_else(|| jsonrpc_core::Error::invalid_params("Expected a boolean"))?;
+        control_automine.set_automine(enabled);
+        Ok(json!(control_automine.mining_mode()))
+    });
+
+    // dev_mineBlocks - [count?]; mines the blocks before returning their hashes
+    io_handler.add_sync_method("dev_mineBlocks", move |params: Params| {
+        rpc_request("dev_mineBlocks");
+        let count = parse_block_count(&parse_params(params)?)?;
+        let hashes =
+            runtime
+                .block_on(control.mine_blocks(count))
+                .map_err(|e| jsonrpc_core::Error {
+                    code: ErrorCode::InternalError,
+                    message: format!("Failed to mine blocks: {}", e),
+                    data: None,
+                })?;
+        Ok(json!(hashes
+            .iter()
+            .map(|hash| format!("0x{}", hex::encode(hash.as_bytes())))
+            .collect::<Vec<_>>()))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_block_count() {
+        assert_eq!(parse_block_count(&[]).unwrap(), 1);
+        assert_eq!(parse_block_count(&[json!(5)]).unwrap(), 5);
+        assert_eq!(parse_block_count(&[json!("0x10")]).unwrap(), 16);
+        assert!(parse_block_count(&[json!(0)]).is_err());
+        assert!(parse_block_count(&[json!(MAX_MINE_BLOCKS + 1)]).is_err());
+        assert!(parse_block_count(&[json!("10")]).is_err());
+    }
+}
@@ -3,13 +3,17 @@
 use crate::eth_tx_decoder;
 use crate::filter::{FilterRegistry, FilterType};
 use crate::methods::{ChainApi, StateApi, TransactionApi};
+use crate::types::{ApiError, BlockId, BlockTag};
 use futures::executor::block_on;
 use hex;
 use jsonrpc_core::{IoHandler, Params, Value};
 use citrate_consensus::types::{Hash, Transaction};
+use citrate_execution::address_utils::address_from_hex;
 use citrate_execution::executor::Executor;
-use citrate_execution::types::Address;
+use citrate_execution::types::{Address, TransactionReceipt};
+use citrate_execution::units::{parse_units, Unit};
 use citrate_sequencer::mempool::{Mempool, TxClass};
+use citrate_storage::chain::{LogFilter, MAX_LOGS_PER_QUERY};
 use citrate_storage::StorageManager;
 use primitive_types::U256;
 use serde_json::json;
@@ -23,6 +27,7 @@ pub fn register_eth_methods(
     executor: Arc<Executor>,
     chain_id: u64,
     filter_registry: Arc<FilterRegistry>,
+    accounts: Vec<Address>,
 ) {
     // eth_blockNumber - Returns the latest block number
     let storage_bn = storage.clone();
@@ -117,7 +122,7 @@ pub fn register_eth_methods(
                     "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
                     "nonce": "0x0000000000000000",
                     "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
-                    "logsBloom": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+                    "logsBloom": block_logs_bloom(&storage_gbn, block.height, &block.hash),
                     "transactionsRoot": format!("0x{}", hex::encode(block.tx_root.as_bytes())),
                     "stateRoot": format!("0x{}", hex::encode(block.state_root.as_bytes())),
                     "receiptsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
@@ -207,7 +212,7 @@ pub fn register_eth_methods(
                     "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
                     "nonce": "0x0000000000000000",
                     "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
-                    "logsBloom": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+                    "logsBloom": block_logs_bloom(&storage_gbh, block.height, &block.hash),
                     "transactionsRoot": format!("0x{}", hex::encode(block.tx_root.as_bytes())),
                     "stateRoot": format!("0x{}", hex::encode(block.state_root.as_bytes())),
                     "receiptsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
@@ -333,47 +338,43 @@ pub fn register_eth_methods(
         };
         
         match block_on(api.get_receipt(Hash::new(hash_bytes))) {
-            Ok(receipt) => {
-                // Derive contractAddress if deployment output encodes address
-                let contract_address = if receipt.to.is_none() && receipt.output.len() == 20 {
-                    Some(format!("0x{}", hex::encode(&receipt.output)))
-                } else {
-                    None
-                };
-
-                Ok(json!({
-                    "transactionHash": format!("0x{}", hex::encode(receipt.tx_hash.as_bytes())),
-                    "transactionIndex": "0x0",
-                    "blockHash": format!("0x{}", hex::encode(receipt.block_hash.as_bytes())),
-                    "blockNumber": format!("0x{:x}", receipt.block_number),
-                    "from": format!("0x{}", hex::encode(receipt.from.0)),
-                    "to": receipt.to.as_ref().map(|t| format!("0x{}", hex::encode(t.0))),
-                    "cumulativeGasUsed": format!("0x{:x}", receipt.gas_used),
-                    "gasUsed": format!("0x{:x}", receipt.gas_used),
-                    "contractAddress": contract_address,
-                    "logs": receipt.logs.iter().map(|log| json!({
-                        "address": format!("0x{}", hex::encode(log.address.0)),
-                        "topics": log.topics.iter()
-                            .map(|t| format!("0x{}", hex::encode(t.as_bytes())))
-                            .collect::<Vec<_>>(),
-                        "data": format!("0x{}", hex::encode(&log.data)),
-                        "logIndex": "0x0",
-                        "transactionIndex": "0x0",
-                        "transactionHash": format!("0x{}", hex::encode(receipt.tx_hash.as_bytes())),
-                        "blockHash": format!("0x{}", hex::encode(receipt.block_hash.as_bytes())),
-                        "blockNumber": format!("0x{:x}", receipt.block_number),
-                        "removed": false
-                    })).collect::<Vec<_>>(),
-                    "status": if receipt.status { "0x1" } else { "0x0" },
-                    "logsBloom": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
-                    "type": "0x0",
-                    "effectiveGasPrice": "0x0"
-                }))
-            },
+            Ok(receipt) => Ok(receipt_json(&receipt, 0, receipt.gas_used, 0)),
             Err(_) => Ok(Value::Null),
         }
     });
 
+    // eth_getBlockReceipts - Returns the receipts of every transaction in a block
+    let storage_block_rcpt = storage.clone();
+    io_handler.add_sync_method("eth_getBlockReceipts", move |params: Params| {
+        let api = ChainApi::new(storage_block_rcpt.clone());
+
+        let params: Vec<Value> = match params.parse() {
+            Ok(p) => p,
+            Err(e) => return Err(jsonrpc_core::Error::invalid_params(e.to_string())),
+        };
+        let block_id = parse_block_id(params.first())?;
+
+        let receipts = match block_on(api.get_block_receipts(block_id)) {
+            Ok(receipts) => receipts,
+            Err(ApiError::BlockNotFound(_)) => return Ok(Value::Null),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut cumulative_gas_used = 0u64;
+        let mut log_index = 0usize;
+        let receipts = receipts
+            .iter()
+            .enumerate()
+            .map(|(index, receipt)| {
+                cumulative_gas_used = cumulative_gas_used.saturating_add(receipt.gas_used);
+                let json = receipt_json(receipt, index, cumulative_gas_used, log_index);
+                log_index += receipt.logs.len();
+                json
+            })
+            .collect::<Vec<_>>();
+        Ok(Value::Array(receipts))
+    });
+
     // eth_chainId - Returns the chain ID
     io_handler.add_sync_method("eth_chainId", move |_params: Params| {
         // Return configured chain ID in hex
@@ -409,9 +410,8 @@ pub fn register_eth_methods(
             return Err(jsonrpc_core::Error::invalid_params("Missing address"));
         }
 
-        let addr_str = match params[0].as_str() {
-            Some(a) if a.starts_with("0x") => &a[2..],
-            Some(a) => a,
+        let addr_bytes = match params[0].as_str() {
+            Some(a) => parse_address(a)?.0,
             None => {
                 return Err(jsonrpc_core::Error::invalid_params(
                     "Invalid address format",
@@ -419,18 +419,12 @@ pub fn register_eth_methods(
             }
         };
 
-        let addr_bytes = match hex::decode(addr_str) {
-            Ok(b) if b.len() == 20 => {
-                let mut arr = [0u8; 20];
-                arr.copy_from_slice(&b);
-                arr
-            }
-            _ => {
-                return Err(jsonrpc_core::Error::invalid_params(
-                    "Invalid address length",
-                ))
-            }
-        };
+        if let Some(height) = parse_state_block(params.get(1), &storage_bal)? {
+            let account = executor_bal
+                .account_at(&Address(addr_bytes), height)
+                .map_err(|e| jsonrpc_core::Error::invalid_params(e.to_string()))?;
+            return Ok(Value::String(format!("0x{:x}", account.balance)));
+        }
 
         match block_on(state_api.get_balance(Address(addr_bytes))) {
             Ok(balance) => Ok(Value::String(format!("0x{:x}", balance))),
@@ -438,6 +432,115 @@ pub fn register_eth_methods(
         }
     });
 
+    // eth_getStorageAt - Returns the value of a contract storage slot
+    let storage_slot = storage.clone();
+    let executor_slot = executor.clone();
+    io_handler.add_sync_method("eth_getStorageAt", move |params: Params| {
+        let params: Vec<Value> = match params.parse() {
+            Ok(p) => p,
+            Err(e) => return Err(jsonrpc_core::Error::invalid_params(e.to_string())),
+        };
+
+        let address = match params.first().and_then(|v| v.as_str()) {
+            Some(a) => parse_address(a)?,
+            None => return Err(jsonrpc_core::Error::invalid_params("Missing address")),
+        };
+        let slot = match params.get(1).and_then(|v| v.as_str()) {
+            Some(p) => parse_storage_slot(p)?,
+            None => return Err(jsonrpc_core::Error::invalid_params("Missing storage position")),
+        };
+
+        let value = match parse_state_block(params.get(2), &storage_slot)? {
+            Some(height) => executor_slot
+                .storage_at(&address, &slot, height)
+                .map_err(|e| jsonrpc_core::Error::invalid_params(e.to_string()))?,
+            None => executor_slot.state_db().get_storage(&address, &slot),
+        };
+
+        // Slots hold 32-byte words; missing slots read as zero
+        let value = value.unwrap_or_default();
+        let mut word = [0u8; 32];
+        let len = value.len().min(32);
+        word[32 - len..].copy_from_slice(&value[value.len() - len..]);
+        Ok(Value::String(format!("0x{}", hex::encode(word))))
+    });
+
+    // eth_getProof - Merkle proofs of an account and some of its storage
+    // slots (EIP-1186). Nodes are Keccak-addressed RLP as in Ethereum, but the
+    // tries are keyed by raw address and slot and accounts are bincode
+    // encoded, so proofs are checked with `citrate_execution::state::verify_proof`.
+    let storage_proof = storage.clone();
+    let executor_proof = executor.clone();
+    io_handler.add_sync_method("eth_getProof", move |params: Params| {
+        let params: Vec<Value> = match params.parse() {
+            Ok(p) => p,
+            Err(e) => return Err(jsonrpc_core::Error::invalid_params(e.to_string())),
+        };
+
+        let address = match params.first().and_then(|v| v.as_str()) {
+            Some(a) => parse_address(a)?,
+            None => return Err(jsonrpc_core::Error::invalid_params("Missing address")),
+        };
+        let keys = match params.get(1) {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::Array(keys)) => keys
+                .iter()
+                .map(|key| match key.as_str() {
+                    Some(k) => parse_storage_slot(k),
+                    None => Err(jsonrpc_core::Error::invalid_params("Invalid storage key")),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            Some(_) => {
+                return Err(jsonrpc_core::Error::invalid_params(
+                    "Storage keys must be an array",
+                ))
+            }
+        };
+
+        let historical;
+        let state = match parse_state_block(params.get(2), &storage_proof)? {
+            Some(height) => {
+                historical = executor_proof
+                    .state_at(height)
+                    .map_err(|e| jsonrpc_core::Error::invalid_params(e.to_string()))?;
+                &historical
+            }
+            None => executor_proof.state_db().as_ref(),
+        };
+
+        let to_hex = |nodes: Vec<Vec<u8>>| -> Vec<String> {
+            nodes
+                .iter()
+                .map(|node| format!("0x{}", hex::encode(node)))
+                .collect()
+        };
+        // The account proof brings the storage root up to date, so take it first
+        let account_proof = to_hex(state.account_proof(&address));
+        let account = state.accounts.get_account(&address);
+        let slot_proofs: Vec<Value> = keys
+            .iter()
+            .map(|key| {
+                let value = state.get_storage(&address, key).unwrap_or_default();
+                let word = &value[value.len().saturating_sub(32)..];
+                json!({
+                    "key": format!("0x{}", hex::encode(key)),
+                    "value": format!("0x{:x}", U256::from_big_endian(word)),
+                    "proof": to_hex(state.storage_proof(&address, key)),
+                })
+            })
+            .collect();
+
+        Ok(json!({
+            "address": format!("0x{}", hex::encode(address.0)),
+            "accountProof": account_proof,
+            "balance": format!("0x{:x}", account.balance),
+            "codeHash": format!("0x{}", hex::encode(account.code_hash.as_bytes())),
+            "nonce": format!("0x{:x}", account.nonce),
+            "storageHash": format!("0x{}", hex::encode(account.storage_root.as_bytes())),
+            "storageProof": slot_proofs,
+        }))
+    });
+
     // eth_getCode - Returns contract code
     let storage_code = storage.clone();
     let executor_code = executor.clone();
@@ -453,9 +556,8 @@ pub fn register_eth_methods(
             return Err(jsonrpc_core::Error::invalid_params("Missing address"));
         }
 
-        let addr_str = match params[0].as_str() {
-            Some(a) if a.starts_with("0x") => &a[2..],
-            Some(a) => a,
+        let addr_bytes = match params[0].as_str() {
+            Some(a) => parse_address(a)?.0,
             None => {
                 return Err(jsonrpc_core::Error::invalid_params(
                     "Invalid address format",
@@ -463,19 +565,6 @@ pub fn register_eth_methods(
             }
         };
 
-        let addr_bytes = match hex::decode(addr_str) {
-            Ok(b) if b.len() == 20 => {
-                let mut arr = [0u8; 20];
-                arr.copy_from_slice(&b);
-                arr
-            }
-            _ => {
-                return Err(jsonrpc_core::Error::invalid_params(
-                    "Invalid address length",
-                ))
-            }
-        };
-
         match block_on(state_api.get_code(Address(addr_bytes))) {
             Ok(code) => Ok(Value::String(format!("0x{}", hex::encode(code)))),
             Err(_) => Ok(Value::String("0x".to_string())),
@@ -498,9 +587,8 @@ pub fn register_eth_methods(
             return Err(jsonrpc_core::Error::invalid_params("Missing address"));
         }
 
-        let addr_str = match params[0].as_str() {
-            Some(a) if a.starts_with("0x") => &a[2..],
-            Some(a) => a,
+        let addr_bytes = match params[0].as_str() {
+            Some(a) => parse_address(a)?.0,
             None => {
                 return Err(jsonrpc_core::Error::invalid_params(
                     "Invalid address format",
@@ -508,19 +596,6 @@ pub fn register_eth_methods(
             }
         };
 
-        let addr_bytes = match hex::decode(addr_str) {
-            Ok(b) if b.len() == 20 => {
-                let mut arr = [0u8; 20];
-                arr.copy_from_slice(&b);
-                arr
-            }
-            _ => {
-                return Err(jsonrpc_core::Error::invalid_params(
-                    "Invalid address length",
-                ))
-            }
-        };
-
         // Optional second param: block tag ("latest" | "pending" | "earliest")
         let tag = params.get(1).and_then(|v| v.as_str()).unwrap_or("latest");
 
@@ -549,6 +624,15 @@ pub fn register_eth_methods(
         Ok(Value::String(format!("0x{:x}", base_nonce)))
     });
 
+    // eth_accounts - Pre-funded dev accounts (empty outside devnet)
+    io_handler.add_sync_method("eth_accounts", move |_params: Params| {
+        let accounts: Vec<Value> = accounts
+            .iter()
+            .map(|a| Value::String(format!("0x{}", hex::encode(a.0))))
+            .collect();
+        Ok(Value::Array(accounts))
+    });
+
     // eth_sendTransaction - Submit transaction (object form)
     let mempool_send_tx = mempool.clone();
     let executor_send_tx = executor.clone();
@@ -579,30 +663,12 @@ pub fn register_eth_methods(
             .get("from")
             .and_then(|v| v.as_str())
             .ok_or_else(|| jsonrpc_core::Error::invalid_params("Missing 'from'"))?;
-        let from_hex = from_s.trim().trim_start_matches("0x");
-        let from_bytes = hex::decode(from_hex)
-            .map_err(|_| jsonrpc_core::Error::invalid_params("Invalid 'from' hex"))?;
-        if from_bytes.len() != 20 {
-            return Err(jsonrpc_core::Error::invalid_params(
-                "'from' must be 20 bytes",
-            ));
-        }
-        let mut from20 = [0u8; 20];
-        from20.copy_from_slice(&from_bytes);
+        let from20 = parse_address(from_s)?.0;
 
         // to (optional)
-        let to20_opt = if let Some(to_s) = obj.get("to").and_then(|v| v.as_str()) {
-            let to_hex = to_s.trim().trim_start_matches("0x");
-            let to_bytes = hex::decode(to_hex)
-                .map_err(|_| jsonrpc_core::Error::invalid_params("Invalid 'to' hex"))?;
-            if to_bytes.len() != 20 {
-                return Err(jsonrpc_core::Error::invalid_params("'to' must be 20 bytes"));
-            }
-            let mut to20 = [0u8; 20];
-            to20.copy_from_slice(&to_bytes);
-            Some(to20)
-        } else {
-            None
+        let to20_opt = match obj.get("to").and_then(|v| v.as_str()) {
+            Some(to_s) => Some(parse_address(to_s)?.0),
+            None => None,
         };
 
         // value (hex string) optional
@@ -755,6 +821,7 @@ pub fn register_eth_methods(
     });
 
     // eth_call - Execute call without creating transaction
+    let storage_call = storage.clone();
     let executor_call = executor.clone();
     io_handler.add_sync_method("eth_call", move |params: Params| {
         use citrate_consensus::types::{Block, BlockHeader, PublicKey, Signature, VrfProof};
@@ -771,6 +838,9 @@ pub fn register_eth_methods(
             return Err(jsonrpc_core::Error::invalid_params("Missing call object"));
         }
 
+        // Past blocks run on a copy of the state rebuilt from the archive
+        let at_height = parse_state_block(params.get(1), &storage_call)?;
+
         // call object
         let obj = match &params[0] {
             Value::Object(map) => map,
@@ -778,29 +848,17 @@ pub fn register_eth_methods(
         };
 
         // to (required)
-        let to_str = match obj.get("to").and_then(|v| v.as_str()) {
-            Some(s) => s.trim().trim_start_matches("0x"),
+        let to_bytes = match obj.get("to").and_then(|v| v.as_str()) {
+            Some(s) => parse_address(s)?.0,
             None => return Err(jsonrpc_core::Error::invalid_params("Missing 'to' address")),
         };
-        let to_bytes = match hex::decode(to_str) {
-            Ok(b) if b.len() == 20 => b,
-            _ => return Err(jsonrpc_core::Error::invalid_params("Invalid 'to' address")),
-        };
         let mut to_pk_bytes = [0u8; 32];
         to_pk_bytes[..20].copy_from_slice(&to_bytes);
         let to_pk = Some(citrate_consensus::types::PublicKey::new(to_pk_bytes));
 
         // from (optional)
         let from_pk = if let Some(from_s) = obj.get("from").and_then(|v| v.as_str()) {
-            let fs = from_s.trim().trim_start_matches("0x");
-            let fbytes = match hex::decode(fs) {
-                Ok(b) if b.len() == 20 => b,
-                _ => {
-                    return Err(jsonrpc_core::Error::invalid_params(
-                        "Invalid 'from' address",
-                    ))
-                }
-            };
+            let fbytes = parse_address(from_s)?.0;
             let mut pkb = [0u8; 32];
             pkb[..20].copy_from_slice(&fbytes);
             PublicKey::new(pkb)
@@ -821,15 +879,9 @@ pub fn register_eth_methods(
         };
 
         // value (optional)
-        let value_u128: u128 = if let Some(vs) = obj.get("value").and_then(|v| v.as_str()) {
-            let s = vs.trim();
-            if let Some(hexs) = s.strip_prefix("0x") {
-                u128::from_str_radix(hexs, 16).unwrap_or(0u128)
-            } else {
-                s.parse::<u128>().unwrap_or(0u128)
-            }
-        } else {
-            0u128
+        let value_u128: u128 = match obj.get("value").and_then(|v| v.as_str()) {
+            Some(vs) => parse_value(vs)?,
+            None => 0,
         };
 
         // gas and gasPrice (optional)
@@ -863,7 +915,7 @@ pub fn register_eth_methods(
                 selected_parent_hash: citrate_consensus::types::Hash::default(),
                 merge_parent_hashes: vec![],
                 timestamp: 0,
-                height: 0,
+                height: at_height.unwrap_or(0),
                 blue_score: 0,
                 blue_work: 0,
                 pruning_point: citrate_consensus::types::Hash::default(),
@@ -875,6 +927,7 @@ pub fn register_eth_methods(
                 base_fee_per_gas: 1_000_000_000, // 1 gwei
                 gas_used: 0,
                 gas_limit: 30_000_000,
+                epoch_commitment: citrate_consensus::types::Hash::default(),
             },
             state_root: citrate_consensus::types::Hash::default(),
             tx_root: citrate_consensus::types::Hash::default(),
@@ -904,10 +957,24 @@ pub fn register_eth_methods(
         // Determine transaction type from data
         tx.determine_type();
 
-        // Snapshot state, execute, then restore
-        let snapshot = exec.state_db().snapshot();
-        let res = block_on(exec.execute_transaction(&blk, &tx));
-        exec.state_db().restore(snapshot);
+        let res = match at_height {
+            Some(height) => {
+                let state = exec
+                    .state_at(height)
+                    .map_err(|e| jsonrpc_core::Error::invalid_params(e.to_string()))?;
+                block_on(
+                    exec.with_state(Arc::new(state))
+                        .execute_transaction(&blk, &tx),
+                )
+            }
+            None => {
+                // Snapshot state, execute, then restore
+                let snapshot = exec.state_db().snapshot();
+                let res = block_on(exec.execute_transaction(&blk, &tx));
+                exec.state_db().restore(snapshot);
+                res
+            }
+        };
 
         match res {
             Ok(receipt) => Ok(Value::String(format!("0x{}", hex::encode(receipt.output)))),
@@ -949,39 +1016,23 @@ pub fn register_eth_methods(
         };
 
         // to (optional for contract deployment)
-        let to_pk = if let Some(to_s) = obj.get("to").and_then(|v| v.as_str()) {
-            let ts = to_s.trim().trim_start_matches("0x");
-            if let Ok(to_bytes) = hex::decode(ts) {
-                if to_bytes.len() == 20 {
-                    let mut to_pk_bytes = [0u8; 32];
-                    to_pk_bytes[..20].copy_from_slice(&to_bytes);
-                    Some(PublicKey::new(to_pk_bytes))
-                } else {
-                    None
-                }
-            } else {
-                None
+        let to_pk = match obj.get("to").and_then(|v| v.as_str()) {
+            Some(to_s) => {
+                let mut to_pk_bytes = [0u8; 32];
+                to_pk_bytes[..20].copy_from_slice(&parse_address(to_s)?.0);
+                Some(PublicKey::new(to_pk_bytes))
             }
-        } else {
-            None
+            None => None,
         };
 
         // from (optional)
-        let from_pk = if let Some(from_s) = obj.get("from").and_then(|v| v.as_str()) {
-            let fs = from_s.trim().trim_start_matches("0x");
-            if let Ok(fbytes) = hex::decode(fs) {
-                if fbytes.len() == 20 {
-                    let mut pkb = [0u8; 32];
-                    pkb[..20].copy_from_slice(&fbytes);
-                    PublicKey::new(pkb)
-                } else {
-                    PublicKey::new([0u8; 32])
-                }
-            } else {
-                PublicKey::new([0u8; 32])
+        let from_pk = match obj.get("from").and_then(|v| v.as_str()) {
+            Some(from_s) => {
+                let mut pkb = [0u8; 32];
+                pkb[..20].copy_from_slice(&parse_address(from_s)?.0);
+                PublicKey::new(pkb)
             }
-        } else {
-            PublicKey::new([0u8; 32])
+            None => PublicKey::new([0u8; 32]),
         };
 
         // data (optional)
@@ -993,15 +1044,9 @@ pub fn register_eth_methods(
         };
 
         // value (optional)
-        let value_u128: u128 = if let Some(vs) = obj.get("value").and_then(|v| v.as_str()) {
-            let s = vs.trim();
-            if let Some(hexs) = s.strip_prefix("0x") {
-                u128::from_str_radix(hexs, 16).unwrap_or(0)
-            } else {
-                s.parse::<u128>().unwrap_or(0)
-            }
-        } else {
-            0
+        let value_u128: u128 = match obj.get("value").and_then(|v| v.as_str()) {
+            Some(vs) => parse_value(vs)?,
+            None => 0,
         };
 
         // Use a high gas limit for estimation (will return actual used)
@@ -1045,6 +1090,7 @@ pub fn register_eth_methods(
                 base_fee_per_gas: 1_000_000_000, // 1 gwei
                 gas_used: 0,
                 gas_limit: 30_000_000,
+                epoch_commitment: citrate_consensus::types::Hash::default(),
             },
             state_root: citrate_consensus::types::Hash::default(),
             tx_root: citrate_consensus::types::Hash::default(),
@@ -1306,10 +1352,6 @@ pub fn register_eth_methods(
             _ => current_height,
         };
 
-        // Limit block range to prevent excessive queries
-        let max_block_range = 1000u64;
-        let effective_to = to_block.min(from_block.saturating_add(max_block_range));
-
         // Parse address filter (single address or array)
         let address_filter: Vec<Address> = match filter.get("address") {
             Some(Value::String(addr_str)) => {
@@ -1400,75 +1442,36 @@ pub fn register_eth_methods(
             _ => vec![],
         };
 
-        // Collect matching logs
-        let mut result_logs: Vec<Value> = Vec::new();
-        let mut log_index_global = 0usize;
-
-        for height in from_block..=effective_to {
-            // Get block hash at this height
-            let block_hash = match storage_logs.blocks.get_block_by_height(height) {
-                Ok(Some(hash)) => hash,
-                _ => continue,
-            };
-
-            // Get all transaction hashes in this block
-            let tx_hashes = match storage_logs.transactions.get_block_transactions(&block_hash) {
-                Ok(hashes) => hashes,
-                Err(_) => continue,
-            };
-
-            for (tx_index, tx_hash) in tx_hashes.iter().enumerate() {
-                // Get receipt for this transaction
-                let receipt = match storage_logs.transactions.get_receipt(tx_hash) {
-                    Ok(Some(r)) => r,
-                    _ => continue,
-                };
-
-                // Filter and collect logs from this receipt
-                for (log_index_in_tx, log) in receipt.logs.iter().enumerate() {
-                    // Check address filter
-                    if !address_filter.is_empty() && !address_filter.contains(&log.address) {
-                        continue;
-                    }
-
-                    // Check topics filter
-                    let topics_match = topics_filter.iter().enumerate().all(|(i, topic_filter)| {
-                        match topic_filter {
-                            None => true, // null means any
-                            Some(allowed_topics) => {
-                                if i >= log.topics.len() {
-                                    false // Log doesn't have this topic position
-                                } else {
-                                    allowed_topics.contains(&log.topics[i])
-                                }
-                            }
-                        }
-                    });
-
-                    if !topics_match {
-                        continue;
-                    }
-
-                    // Log matches all filters
-                    result_logs.push(json!({
-                        "address": format!("0x{}", hex::encode(log.address.0)),
-                        "topics": log.topics.iter()
-                            .map(|t| format!("0x{}", hex::encode(t.as_bytes())))
-                            .collect::<Vec<_>>(),
-                        "data": format!("0x{}", hex::encode(&log.data)),
-                        "blockNumber": format!("0x{:x}", height),
-                        "blockHash": format!("0x{}", hex::encode(block_hash.as_bytes())),
-                        "transactionHash": format!("0x{}", hex::encode(tx_hash.as_bytes())),
-                        "transactionIndex": format!("0x{:x}", tx_index),
-                        "logIndex": format!("0x{:x}", log_index_global),
-                        "removed": false
-                    }));
-
-                    log_index_global += 1;
-                    let _ = log_index_in_tx; // Suppress unused warning
-                }
-            }
-        }
+        // Query the log index; blocks whose bloom cannot match are skipped
+        let log_filter = LogFilter {
+            from_block,
+            to_block,
+            addresses: address_filter,
+            topics: topics_filter,
+        };
+        let logs = storage_logs
+            .logs
+            .get_logs(&log_filter, MAX_LOGS_PER_QUERY)
+            .map_err(|e| jsonrpc_core::Error::invalid_params(e.to_string()))?;
+
+        let result_logs: Vec<Value> = logs
+            .iter()
+            .map(|log| {
+                json!({
+                    "address": format!("0x{}", hex::encode(log.address.0)),
+                    "topics": log.topics.iter()
+                        .map(|t| format!("0x{}", hex::encode(t.as_bytes())))
+                        .collect::<Vec<_>>(),
+                    "data": format!("0x{}", hex::encode(&log.data)),
+                    "blockNumber": format!("0x{:x}", log.block_number),
+                    "blockHash": format!("0x{}", hex::encode(log.block_hash.as_bytes())),
+                    "transactionHash": format!("0x{}", hex::encode(log.tx_hash.as_bytes())),
+                    "transactionIndex": format!("0x{:x}", log.tx_index),
+                    "logIndex": format!("0x{:x}", log.log_index),
+                    "removed": false
+                })
+            })
+            .collect();
 
         Ok(Value::Array(result_logs))
     });
@@ -1900,8 +1903,8 @@ pub fn register_eth_methods(
         let stats = block_on(mp.stats());
         let total = stats.total_transactions;
 
-        // Get all transactions from mempool
-        let txs = block_on(mp.get_transactions(total));
+        // Get all publicly visible transactions (private submissions stay hidden)
+        let txs = block_on(mp.get_public_transactions(total));
 
         // Convert to JSON format
         let mut tx_list = Vec::new();
@@ -2087,3 +2090,175 @@ pub fn register_eth_methods(
         }))
     });
 }
+
+/// Hex logs bloom of a block, zero when none has been recorded
+fn block_logs_bloom(storage: &StorageManager, height: u64, hash: &Hash) -> String {
+    let bloom = storage
+        .logs
+        .get_block_bloom(height, hash)
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    format!("0x{}", hex::encode(bloom.as_bytes()))
+}
+
+/// Ethereum receipt object for the transaction at `transaction_index` of its
+/// block, whose logs start at `first_log_index` within the block
+fn receipt_json(
+    receipt: &TransactionReceipt,
+    transaction_index: usize,
+    cumulative_gas_used: u64,
+    first_log_index: usize,
+) -> Value {
+    let tx_hash = format!("0x{}", hex::encode(receipt.tx_hash.as_bytes()));
+    let block_hash = format!("0x{}", hex::encode(receipt.block_hash.as_bytes()));
+    let block_number = format!("0x{:x}", receipt.block_number);
+    let transaction_index = format!("0x{:x}", transaction_index);
+
+    // Derive contractAddress if deployment output encodes address
+    let contract_address = if receipt.to.is_none() && receipt.output.len() == 20 {
+        Some(format!("0x{}", hex::encode(&receipt.output)))
+    } else {
+        None
+    };
+
+    json!({
+        "transactionHash": tx_hash,
+        "transactionIndex": transaction_index,
+        "blockHash": block_hash,
+        "blockNumber": block_number,
+        "from": format!("0x{}", hex::encode(receipt.from.0)),
+        "to": receipt.to.as_ref().map(|t| format!("0x{}", hex::encode(t.0))),
+        "cumulativeGasUsed": format!("0x{:x}", cumulative_gas_used),
+        "gasUsed": format!("0x{:x}", receipt.gas_used),
+        "contractAddress": contract_address,
+        "logs": receipt.logs.iter().enumerate().map(|(i, log)| json!({
+            "address": format!("0x{}", hex::encode(log.address.0)),
+            "topics": log.topics.iter()
+                .map(|t| format!("0x{}", hex::encode(t.as_bytes())))
+                .collect::<Vec<_>>(),
+            "data": format!("0x{}", hex::encode(&log.data)),
+            "logIndex": format!("0x{:x}", first_log_index + i),
+            "transactionIndex": transaction_index,
+            "transactionHash": tx_hash,
+            "blockHash": block_hash,
+            "blockNumber": block_number,
+            "removed": false
+        })).collect::<Vec<_>>(),
+        "status": if receipt.status { "0x1" } else { "0x0" },
+        "logsBloom": format!("0x{}", hex::encode(receipt.logs_bloom().as_bytes())),
+        "type": "0x0",
+        "effectiveGasPrice": "0x0"
+    })
+}
+
+/// Block selected by a block parameter: a tag, a block number, a block hash
+/// or an EIP-1898 `{ blockNumber }` / `{ blockHash }` object
+fn parse_block_id(value: Option<&Value>) -> Result<BlockId, jsonrpc_core::Error> {
+    let invalid = || jsonrpc_core::Error::invalid_params("Invalid block parameter");
+    match value {
+        None => Err(jsonrpc_core::Error::invalid_params("Missing block parameter")),
+        Some(Value::String(tag)) => match tag.as_str() {
+            "latest" | "safe" | "finalized" => Ok(BlockId::Tag(BlockTag::Latest)),
+            "earliest" => Ok(BlockId::Tag(BlockTag::Earliest)),
+            "pending" => Ok(BlockId::Tag(BlockTag::Pending)),
+            other => {
+                let digits = other.strip_prefix("0x").ok_or_else(invalid)?;
+                if digits.len() == 64 {
+                    let bytes = hex::decode(digits).map_err(|_| invalid())?;
+                    Ok(BlockId::Hash(Hash::from_bytes(&bytes)))
+                } else {
+                    u64::from_str_radix(digits, 16)
+                        .map(BlockId::Number)
+                        .map_err(|_| invalid())
+                }
+            }
+        },
+        Some(Value::Number(n)) => n.as_u64().map(BlockId::Number).ok_or_else(invalid),
+        Some(Value::Object(obj)) => match obj.get("blockHash") {
+            Some(hash) => parse_block_id(Some(hash)),
+            None => parse_block_id(obj.get("blockNumber")),
+        },
+        _ => Err(invalid()),
+    }
+}
+
+/// Parse an address parameter; mixed-case addresses must pass their EIP-55
+/// checksum
+fn parse_address(s: &str) -> Result<Address, jsonrpc_core::Error> {
+    address_from_hex(s.trim()).map_err(jsonrpc_core::Error::invalid_params)
+}
+
+/// Height a block parameter asks state for, or `None` for the current
+/// state. Accepts tags, block numbers and EIP-1898 `{ blockNumber }` /
+/// `{ blockHash }` objects; blocks past the head are rejected.
+fn parse_state_block(
+    value: Option<&Value>,
+    storage: &StorageManager,
+) -> Result<Option<u64>, jsonrpc_core::Error> {
+    let invalid = || jsonrpc_core::Error::invalid_params("Invalid block parameter");
+    let height = match value {
+        None | Some(Value::Null) => return Ok(None),
+        Some(Value::String(tag)) => match tag.as_str() {
+            "latest" | "pending" | "safe" | "finalized" => return Ok(None),
+            "earliest" => 0,
+            number => match number.strip_prefix("0x") {
+                Some(digits) => u64::from_str_radix(digits, 16).map_err(|_| invalid())?,
+                None => number.parse().map_err(|_| invalid())?,
+            },
+        },
+        Some(Value::Number(n)) => n.as_u64().ok_or_else(invalid)?,
+        Some(Value::Object(obj)) => match obj.get("blockHash").and_then(|v| v.as_str()) {
+            Some(hash) => {
+                let bytes = hex::decode(hash.trim_start_matches("0x")).map_err(|_| invalid())?;
+                if bytes.len() != 32 {
+                    return Err(invalid());
+                }
+                match storage.blocks.get_header(&Hash::from_bytes(&bytes)) {
+                    Ok(Some(header)) => header.height,
+                    _ => return Err(jsonrpc_core::Error::invalid_params("Block not found")),
+                }
+            }
+            None => return parse_state_block(obj.get("blockNumber"), storage),
+        },
+        _ => return Err(invalid()),
+    };
+
+    let head = storage.blocks.get_latest_height().unwrap_or(0);
+    if height > head {
+        return Err(jsonrpc_core::Error::invalid_params(format!(
+            "Block {} is past the chain head {}",
+            height, head
+        )));
+    }
+    Ok((height < head).then_some(height))
+}
+
+/// Parse a storage position into the 32-byte big-endian slot key
+fn parse_storage_slot(s: &str) -> Result<[u8; 32], jsonrpc_core::Error> {
+    let hexs = s.trim().trim_start_matches("0x");
+    let hexs = if hexs.len() % 2 == 1 {
+        format!("0{}", hexs)
+    } else {
+        hexs.to_string()
+    };
+    let bytes = hex::decode(&hexs)
+        .map_err(|_| jsonrpc_core::Error::invalid_params("Invalid storage position"))?;
+    if bytes.len() > 32 {
+        return Err(jsonrpc_core::Error::invalid_params("Invalid storage position"));
+    }
+    let mut slot = [0u8; 32];
+    slot[32 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(slot)
+}
+
+/// Parse a wei `value` given as 0x-hex or a decimal string
+fn parse_value(s: &str) -> Result<u128, jsonrpc_core::Error> {
+    let s = s.trim();
+    match s.strip_prefix("0x") {
+        Some(hexs) => u128::from_str_radix(hexs, 16)
+            .map_err(|_| jsonrpc_core::Error::invalid_params("Invalid hex value")),
+        None => parse_units(s, Unit::Wei)
+            .map_err(|e| jsonrpc_core::Error::invalid_params(format!("Invalid value: {}", e))),
+    }
+}
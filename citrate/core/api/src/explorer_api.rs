@@ -0,0 +1,599 @@
+// citrate/core/api/src/explorer_api.rs
+
+// Block explorer REST API
+//
+// Read-only endpoints for a public block explorer frontend, served next to
+// the OpenAI-compatible API and read straight from `StorageManager`. Lists
+// are newest first and paginated by height: a page's `next_cursor` is passed
+// back as `before` to fetch the following page. Hashes and addresses are
+// 0x-prefixed hex and amounts are decimal strings, so JavaScript clients do
+// not lose precision.
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+    routing::get,
+    Router,
+};
+use citrate_consensus::types::{Block, Hash, Transaction};
+use citrate_execution::types::{Address, TransactionReceipt};
+use citrate_storage::StorageManager;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::openai_api::{ErrorDetail, ErrorResponse};
+use crate::types::error::ApiError;
+
+/// Items per page when the request does not set `limit`
+const DEFAULT_PAGE_SIZE: usize = 20;
+
+const MAX_PAGE_SIZE: usize = 100;
+
+/// Heights scanned per request when listing an address's transactions.
+/// There is no address index, so history is found by walking back blocks.
+const MAX_ADDRESS_SCAN_BLOCKS: u64 = 1000;
+
+type ExplorerResult<T> = Result<Json<T>, (StatusCode, Json<ErrorResponse>)>;
+
+/// Pagination parameters shared by the list endpoints
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PageQuery {
+    /// Only return entries below this height
+    pub before: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+impl PageQuery {
+    fn limit(&self) -> usize {
+        self.limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE)
+    }
+}
+
+/// One page of a list, newest first
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Value of `before` for the next page; absent on the last page
+    pub next_cursor: Option<u64>,
+}
+
+/// Block as shown in block lists
+#[derive(Debug, Serialize)]
+pub struct ExplorerBlock {
+    pub hash: String,
+    pub height: u64,
+    pub parent_hash: String,
+    pub merge_parents: Vec<String>,
+    pub timestamp: u64,
+    pub blue_score: u64,
+    pub blue_work: String,
+    pub proposer: String,
+    pub tx_count: usize,
+    pub gas_used: u64,
+    pub gas_limit: u64,
+    pub base_fee_per_gas: u64,
+}
+
+impl From<&Block> for ExplorerBlock {
+    fn from(block: &Block) -> Self {
+        let header = &block.header;
+        Self {
+            hash: hash_hex(&header.block_hash),
+            height: header.height,
+            parent_hash: hash_hex(&header.selected_parent_hash),
+            merge_parents: header.merge_parent_hashes.iter().map(hash_hex).collect(),
+            timestamp: header.timestamp,
+            blue_score: header.blue_score,
+            blue_work: header.blue_work.to_string(),
+            proposer: format!("0x{}", hex::encode(header.proposer_pubkey.as_bytes())),
+            tx_count: block.transactions.len(),
+            gas_used: header.gas_used,
+            gas_limit: header.gas_limit,
+            base_fee_per_gas: header.base_fee_per_gas,
+        }
+    }
+}
+
+/// Block with its roots, children and transactions
+#[derive(Debug, Serialize)]
+pub struct ExplorerBlockDetail {
+    #[serde(flatten)]
+    pub block: ExplorerBlock,
+    pub state_root: String,
+    pub tx_root: String,
+    pub receipt_root: String,
+    pub children: Vec<String>,
+    pub transactions: Vec<ExplorerTx>,
+}
+
+/// Transaction with the outcome from its receipt, once it has one
+#[derive(Debug, Serialize)]
+pub struct ExplorerTx {
+    pub hash: String,
+    pub block_hash: Option<String>,
+    pub block_height: Option<u64>,
+    pub timestamp: Option<u64>,
+    pub from: String,
+    pub to: Option<String>,
+    pub value: String,
+    pub nonce: u64,
+    pub gas_limit: u64,
+    pub gas_price: u64,
+    pub gas_used: Option<u64>,
+    pub status: Option<bool>,
+    pub tx_type: Option<String>,
+    pub log_count: usize,
+    pub input: String,
+}
+
+impl ExplorerTx {
+    fn new(tx: &Transaction, receipt: Option<&TransactionReceipt>, timestamp: Option<u64>) -> Self {
+        Self {
+            hash: hash_hex(&tx.hash),
+            block_hash: receipt.map(|r| hash_hex(&r.block_hash)),
+            block_height: receipt.map(|r| r.block_number),
+            timestamp,
+            from: address_hex(&Address::from_public_key(&tx.from)),
+            to: tx
+                .to
+                .as_ref()
+                .map(|to| address_hex(&Address::from_public_key(to))),
+            value: tx.value.to_string(),
+            nonce: tx.nonce,
+            gas_limit: tx.gas_limit,
+            gas_price: tx.gas_price,
+            gas_used: receipt.map(|r| r.gas_used),
+            status: receipt.map(|r| r.status),
+            tx_type: tx.tx_type.map(|t| format!("{:?}", t)),
+            log_count: receipt.map_or(0, |r| r.logs.len()),
+            input: format!("0x{}", hex::encode(&tx.data)),
+        }
+    }
+}
+
+/// Account state and recent transactions of an address
+#[derive(Debug, Serialize)]
+pub struct ExplorerAddress {
+    pub address: String,
+    pub balance: String,
+    pub nonce: u64,
+    pub is_contract: bool,
+    pub transactions: Page<ExplorerTx>,
+}
+
+/// Current DAG tips
+#[derive(Debug, Serialize)]
+pub struct ExplorerTips {
+    pub height: u64,
+    pub tips: Vec<ExplorerBlock>,
+}
+
+/// Explorer endpoints, to be merged into the REST server's router
+pub fn router(storage: Arc<StorageManager>) -> Router {
+    Router::new()
+        .route("/explorer/blocks", get(list_blocks))
+        .route("/explorer/blocks/:id", get(get_block))
+        .route("/explorer/tx/:hash", get(get_transaction))
+        .route("/explorer/address/:address", get(get_address))
+        .route("/explorer/dag/tips", get(get_dag_tips))
+        .with_state(storage)
+}
+
+/// GET /explorer/blocks - Blocks by height, newest first
+async fn list_blocks(
+    State(storage): State<Arc<StorageManager>>,
+    Query(query): Query<PageQuery>,
+) -> ExplorerResult<Page<ExplorerBlock>> {
+    block_page(&storage, &query)
+        .map(Json)
+        .map_err(error_response)
+}
+
+/// GET /explorer/blocks/:id - Block by hash or height
+async fn get_block(
+    State(storage): State<Arc<StorageManager>>,
+    Path(id): Path<String>,
+) -> ExplorerResult<ExplorerBlockDetail> {
+    block_detail(&storage, &id)
+        .map(Json)
+        .map_err(error_response)
+}
+
+/// GET /explorer/tx/:hash - Transaction and receipt
+async fn get_transaction(
+    State(storage): State<Arc<StorageManager>>,
+    Path(hash): Path<String>,
+) -> ExplorerResult<ExplorerTx> {
+    transaction_detail(&storage, &hash)
+        .map(Json)
+        .map_err(error_response)
+}
+
+/// GET /explorer/address/:address - Balance, nonce and transactions
+async fn get_address(
+    State(storage): State<Arc<StorageManager>>,
+    Path(address): Path<String>,
+    Query(query): Query<PageQuery>,
+) -> ExplorerResult<ExplorerAddress> {
+    address_detail(&storage, &address, &query)
+        .map(Json)
+        .map_err(error_response)
+}
+
+/// GET /explorer/dag/tips - Current DAG tips
+async fn get_dag_tips(State(storage): State<Arc<StorageManager>>) -> ExplorerResult<ExplorerTips> {
+    dag_tips(&storage).map(Json).map_err(error_response)
+}
+
+fn block_page(
+    storage: &StorageManager,
+    query: &PageQuery,
+) -> Result<Page<ExplorerBlock>, ApiError> {
+    let limit = query.limit();
+    let mut items = Vec::new();
+    let mut next_cursor = None;
+    if let Some(top) = top_height(storage, query.before)? {
+        for height in (0..=top).rev() {
+            if items.len() == limit {
+                next_cursor = Some(height + 1);
+                break;
+            }
+            if let Some(block) = block_at_height(storage, height)? {
+                items.push(ExplorerBlock::from(&block));
+            }
+        }
+    }
+    Ok(Page { items, next_cursor })
+}
+
+fn block_detail(storage: &StorageManager, id: &str) -> Result<ExplorerBlockDetail, ApiError> {
+    let block = match id.parse::<u64>() {
+        Ok(height) => block_at_height(storage, height)?
+            .ok_or_else(|| ApiError::BlockNotFound(format!("height {}", height)))?,
+        Err(_) => {
+            let hash = parse_hash(id)?;
+            storage
+                .blocks
+                .get_block(&hash)
+                .map_err(internal)?
+                .ok_or_else(|| ApiError::BlockNotFound(id.to_string()))?
+        }
+    };
+
+    let children = storage
+        .blocks
+        .get_children(&block.hash())
+        .map_err(internal)?;
+    let transactions = block_transactions(storage, &block)?;
+    Ok(ExplorerBlockDetail {
+        block: ExplorerBlock::from(&block),
+        state_root: hash_hex(&block.state_root),
+        tx_root: hash_hex(&block.tx_root),
+        receipt_root: hash_hex(&block.receipt_root),
+        children: children.iter().map(hash_hex).collect(),
+        transactions,
+    })
+}
+
+fn transaction_detail(storage: &StorageManager, hash: &str) -> Result<ExplorerTx, ApiError> {
+    let hash = parse_hash(hash)?;
+    let tx = storage
+        .transactions
+        .get_transaction(&hash)
+        .map_err(internal)?
+        .ok_or_else(|| ApiError::TransactionNotFound(hash_hex(&hash)))?;
+    let receipt = storage.transactions.get_receipt(&hash).map_err(internal)?;
+    let timestamp = match &receipt {
+        Some(receipt) => storage
+            .blocks
+            .get_header(&receipt.block_hash)
+            .map_err(internal)?
+            .map(|header| header.timestamp),
+        None => None,
+    };
+    Ok(ExplorerTx::new(&tx, receipt.as_ref(), timestamp))
+}
+
+/// An address's account and the transactions sent from or to it, found by
+/// scanning back from `before`. A page holds whole blocks, so it can run
+/// past `limit` by part of a block; it can also come back short with a
+/// cursor when the scan budget ran out before `limit` was reached.
+fn address_detail(
+    storage: &StorageManager,
+    address: &str,
+    query: &PageQuery,
+) -> Result<ExplorerAddress, ApiError> {
+    let address = parse_address(address)?;
+    let account = storage
+        .state
+        .get_account(&address)
+        .map_err(internal)?
+        .unwrap_or_default();
+
+    let limit = query.limit();
+    let mut items = Vec::new();
+    let mut next_cursor = None;
+    if let Some(top) = top_height(storage, query.before)? {
+        let bottom = top.saturating_sub(MAX_ADDRESS_SCAN_BLOCKS - 1);
+        for height in (bottom..=top).rev() {
+            if items.len() >= limit {
+                next_cursor = Some(height + 1);
+                break;
+            }
+            let Some(block) = block_at_height(storage, height)? else {
+                continue;
+            };
+            for tx in block.transactions.iter().rev() {
+                let involved = Address::from_public_key(&tx.from) == address
+                    || tx
+                        .to
+                        .as_ref()
+                        .is_some_and(|to| Address::from_public_key(to) == address);
+                if involved {
+                    let receipt = storage
+                        .transactions
+                        .get_receipt(&tx.hash)
+                        .map_err(internal)?;
+                    items.push(ExplorerTx::new(
+                        tx,
+                        receipt.as_ref(),
+                        Some(block.header.timestamp),
+                    ));
+                }
+            }
+        }
+        if next_cursor.is_none() && bottom > 0 {
+            next_cursor = Some(bottom);
+        }
+    }
+
+    Ok(ExplorerAddress {
+        address: address_hex(&address),
+        balance: account.balance.to_string(),
+        nonce: account.nonce,
+        is_contract: account.code_hash != Hash::default(),
+        transactions: Page { items, next_cursor },
+    })
+}
+
+fn dag_tips(storage: &StorageManager) -> Result<ExplorerTips, ApiError> {
+    let height = storage.blocks.get_latest_height().map_err(internal)?;
+    let mut tips = Vec::new();
+    for hash in storage.blocks.get_tips().map_err(internal)? {
+        if let Some(block) = storage.blocks.get_block(&hash).map_err(internal)? {
+            tips.push(ExplorerBlock::from(&block));
+        }
+    }
+    Ok(ExplorerTips { height, tips })
+}
+
+/// Highest height a page starting `before` covers, or `None` when there is
+/// nothing below it
+fn top_height(storage: &StorageManager, before: Option<u64>) -> Result<Option<u64>, ApiError> {
+    let latest = storage.blocks.get_latest_height().map_err(internal)?;
+    Ok(match before {
+        None => Some(latest),
+        Some(0) => None,
+        Some(before) => Some((before - 1).min(latest)),
+    })
+}
+
+fn block_at_height(storage: &StorageManager, height: u64) -> Result<Option<Block>, ApiError> {
+    match storage
+        .blocks
+        .get_block_by_height(height)
+        .map_err(internal)?
+    {
+        Some(hash) => storage.blocks.get_block(&hash).map_err(internal),
+        None => Ok(None),
+    }
+}
+
+fn block_transactions(
+    storage: &StorageManager,
+    block: &Block,
+) -> Result<Vec<ExplorerTx>, ApiError> {
+    block
+        .transactions
+        .iter()
+        .map(|tx| {
+            let receipt = storage
+                .transactions
+                .get_receipt(&tx.hash)
+                .map_err(internal)?;
+            Ok(ExplorerTx::new(
+                tx,
+                receipt.as_ref(),
+                Some(block.header.timestamp),
+            ))
+        })
+        .collect()
+}
+
+fn parse_hash(s: &str) -> Result<Hash, ApiError> {
+    let hex_str = s.strip_prefix("0x").unwrap_or(s);
+    let bytes: [u8; 32] = hex::decode(hex_str)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| ApiError::InvalidParams(format!("Invalid hash {}", s)))?;
+    Ok(Hash::new(bytes))
+}
+
+fn parse_address(s: &str) -> Result<Address, ApiError> {
+    let hex_str = s.strip_prefix("0x").unwrap_or(s);
+    let bytes: [u8; 20] = hex::decode(hex_str)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| ApiError::InvalidParams(format!("Invalid address {}", s)))?;
+    Ok(Address(bytes))
+}
+
+fn hash_hex(hash: &Hash) -> String {
+    format!("0x{}", hash.to_hex())
+}
+
+fn address_hex(address: &Address) -> String {
+    format!("0x{}", hex::encode(address.0))
+}
+
+fn internal(e: anyhow::Error) -> ApiError {
+    ApiError::InternalError(e.to_string())
+}
+
+fn error_response(err: ApiError) -> (StatusCode, Json<ErrorResponse>) {
+    let (status, kind) = match &err {
+        ApiError::BlockNotFound(_)
+        | ApiError::TransactionNotFound(_)
+        | ApiError::AccountNotFound(_) => (StatusCode::NOT_FOUND, "not_found"),
+        ApiError::InvalidParams(_) => (StatusCode::BAD_REQUEST, "invalid_request_error"),
+        _ => {
+            error!("Explorer request failed: {}", err);
+            (StatusCode::INTERNAL_SERVER_ERROR, "internal_error")
+        }
+    };
+    (
+        status,
+        Json(ErrorResponse {
+            error: ErrorDetail {
+                message: err.to_string(),
+                r#type: kind.to_string(),
+                code: None,
+            },
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use citrate_consensus::types::{BlockHeader, PublicKey, Signature, VrfProof};
+    use citrate_storage::pruning::PruningConfig;
+    use tempfile::TempDir;
+
+    fn transfer(nonce: u64, from: PublicKey, to: PublicKey) -> Transaction {
+        Transaction {
+            hash: Hash::new([0x40 + nonce as u8; 32]),
+            nonce,
+            from,
+            to: Some(to),
+            value: 1_000,
+            gas_limit: 21_000,
+            gas_price: 1,
+            data: vec![],
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        }
+    }
+
+    fn block(height: u64, parent: Hash, transactions: Vec<Transaction>) -> Block {
+        Block {
+            header: BlockHeader {
+                version: 1,
+                block_hash: Hash::new([height as u8 + 1; 32]),
+                selected_parent_hash: parent,
+                merge_parent_hashes: vec![],
+                timestamp: 1_000 + height,
+                height,
+                blue_score: height,
+                blue_work: height as u128,
+                pruning_point: Hash::default(),
+                proposer_pubkey: PublicKey::new([1; 32]),
+                vrf_reveal: VrfProof {
+                    proof: vec![],
+                    output: Hash::default(),
+                },
+                base_fee_per_gas: 0,
+                gas_used: 0,
+                gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
+            },
+            state_root: Hash::default(),
+            tx_root: Hash::default(),
+            receipt_root: Hash::default(),
+            artifact_root: Hash::default(),
+            ghostdag_params: Default::default(),
+            transactions,
+            signature: Signature::new([0; 64]),
+            embedded_models: vec![],
+            required_pins: vec![],
+        }
+    }
+
+    #[test]
+    fn test_pages_blocks_and_address_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = StorageManager::new(temp_dir.path(), PruningConfig::default()).unwrap();
+        let alice = PublicKey::new([0xa1; 32]);
+        let bob = PublicKey::new([0xb2; 32]);
+
+        let mut parent = Hash::default();
+        for height in 0..5 {
+            let txs = if height % 2 == 1 {
+                vec![transfer(height, alice, bob)]
+            } else {
+                vec![]
+            };
+            let block = block(height, parent, txs);
+            for tx in &block.transactions {
+                storage.transactions.put_transaction(tx).unwrap();
+            }
+            storage.blocks.put_block(&block).unwrap();
+            parent = block.hash();
+        }
+
+        let first = block_page(
+            &storage,
+            &PageQuery {
+                before: None,
+                limit: Some(2),
+            },
+        )
+        .unwrap();
+        let heights: Vec<u64> = first.items.iter().map(|b| b.height).collect();
+        assert_eq!(heights, vec![4, 3]);
+        assert_eq!(first.next_cursor, Some(3));
+
+        let rest = block_page(
+            &storage,
+            &PageQuery {
+                before: first.next_cursor,
+                limit: Some(10),
+            },
+        )
+        .unwrap();
+        let heights: Vec<u64> = rest.items.iter().map(|b| b.height).collect();
+        assert_eq!(heights, vec![2, 1, 0]);
+        assert_eq!(rest.next_cursor, None);
+
+        let bob_address = address_hex(&Address::from_public_key(&bob));
+        let history = address_detail(&storage, &bob_address, &PageQuery::default()).unwrap();
+        let nonces: Vec<u64> = history
+            .transactions
+            .items
+            .iter()
+            .map(|tx| tx.nonce)
+            .collect();
+        assert_eq!(nonces, vec![3, 1]);
+        assert_eq!(history.transactions.items[0].timestamp, Some(1_003));
+        assert_eq!(history.balance, "0");
+
+        let tx = transaction_detail(&storage, &history.transactions.items[0].hash).unwrap();
+        assert_eq!(tx.to.as_deref(), Some(bob_address.as_str()));
+        assert!(tx.status.is_none());
+
+        let detail = block_detail(&storage, "3").unwrap();
+        assert_eq!(detail.transactions.len(), 1);
+        assert_eq!(detail.children, vec![hash_hex(&Hash::new([5; 32]))]);
+
+        let tips = dag_tips(&storage).unwrap();
+        assert_eq!(tips.height, 4);
+        assert_eq!(tips.tips.len(), 1);
+        assert!(matches!(
+            block_detail(&storage, "0x1234"),
+            Err(ApiError::InvalidParams(_))
+        ));
+    }
+}
@@ -0,0 +1,249 @@
+// citrate/core/api/src/governance_rpc.rs
+
+// Governance proposal RPC methods
+// Reads come straight from the proposals precompile's state. Proposing,
+// voting, executing and canceling are transactions to the precompile, so
+// those methods take a signed raw transaction like `eth_sendRawTransaction`
+// and check it is the call they are named for before admitting it.
+
+use crate::eth_tx_decoder;
+use citrate_consensus::types::Transaction;
+use citrate_execution::executor::Executor;
+use citrate_execution::precompiles::proposals::{
+    self, Proposal, CANCEL_SIGNATURE, EXECUTE_SIGNATURE, PROPOSALS_PRECOMPILE, PROPOSE_SIGNATURE,
+    VOTE_SIGNATURE,
+};
+use citrate_execution::types::Address;
+use citrate_sequencer::mempool::{Mempool, TxClass};
+use citrate_storage::StorageManager;
+use futures::executor::block_on;
+use jsonrpc_core::{IoHandler, Params, Value};
+use serde_json::json;
+use std::sync::Arc;
+
+/// Most proposals returned by one `gov_listProposals` call
+const MAX_LIST: u64 = 100;
+
+fn proposal_json(proposal: &Proposal, height: u64) -> Value {
+    json!({
+        "id": proposal.id,
+        "proposer": proposal.proposer.to_string(),
+        "parameter": proposal.parameter,
+        "value": format!("0x{}", hex::encode(&proposal.value)),
+        "description": proposal.description,
+        "state": proposal.state(height),
+        "createdHeight": proposal.created_height,
+        "endHeight": proposal.end_height,
+        "executableHeight": proposal.executable_height(),
+        "expiryHeight": proposal.expiry_height(),
+        "executedHeight": proposal.executed_height,
+        "quorum": format!("0x{:x}", proposal.quorum),
+        "votesFor": format!("0x{:x}", proposal.votes_for),
+        "votesAgainst": format!("0x{:x}", proposal.votes_against),
+        "votesAbstain": format!("0x{:x}", proposal.votes_abstain),
+    })
+}
+
+fn tally_json(proposal: &Proposal, height: u64) -> Value {
+    let total = proposal.total_votes();
+    json!({
+        "id": proposal.id,
+        "state": proposal.state(height),
+        "votesFor": format!("0x{:x}", proposal.votes_for),
+        "votesAgainst": format!("0x{:x}", proposal.votes_against),
+        "votesAbstain": format!("0x{:x}", proposal.votes_abstain),
+        "totalVotes": format!("0x{:x}", total),
+        "quorum": format!("0x{:x}", proposal.quorum),
+        "quorumReached": !total.is_zero() && total >= proposal.quorum,
+        "passed": proposal.passed(),
+    })
+}
+
+fn parse_params(params: Params) -> Result<Vec<Value>, jsonrpc_core::Error> {
+    params
+        .parse()
+        .map_err(|e| jsonrpc_core::Error::invalid_params(e.to_string()))
+}
+
+/// Proposal id given as a number or a hex quantity
+fn parse_id(value: Option<&Value>) -> Result<u64, jsonrpc_core::Error> {
+    let invalid = || jsonrpc_core::Error::invalid_params("Invalid proposal id");
+    match value {
+        Some(Value::Number(n)) => n.as_u64().ok_or_else(invalid),
+        Some(Value::String(s)) => match s.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).map_err(|_| invalid()),
+            None => s.parse().map_err(|_| invalid()),
+        },
+        _ => Err(jsonrpc_core::Error::invalid_params("Missing proposal id")),
+    }
+}
+
+fn parse_address(value: Option<&Value>) -> Result<Address, jsonrpc_core::Error> {
+    let s = value
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| jsonrpc_core::Error::invalid_params("Missing address parameter"))?;
+    let bytes = hex::decode(s.trim_start_matches("0x"))
+        .map_err(|_| jsonrpc_core::Error::invalid_params("Invalid hex address"))?;
+    if bytes.len() != 20 {
+        return Err(jsonrpc_core::Error::invalid_params(
+            "Address must be 20 bytes",
+        ));
+    }
+    let mut addr = [0u8; 20];
+    addr.copy_from_slice(&bytes);
+    Ok(Address(addr))
+}
+
+/// Decode a signed raw transaction and check it calls `signature` on the
+/// proposals precompile
+fn decode_call(params: Params, signature: &[u8]) -> Result<Transaction, jsonrpc_core::Error> {
+    let params = parse_params(params)?;
+    let raw = params
+        .first()
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| jsonrpc_core::Error::invalid_params("Missing transaction data"))?;
+    let bytes = hex::decode(raw.trim_start_matches("0x"))
+        .map_err(|_| jsonrpc_core::Error::invalid_params("Invalid hex data"))?;
+    let tx = eth_tx_decoder::decode_eth_transaction(&bytes).map_err(|e| {
+        jsonrpc_core::Error::invalid_params(format!("Failed to parse transaction: {}", e))
+    })?;
+
+    let to = tx.to.as_ref().map(Address::from_public_key);
+    if to != Some(PROPOSALS_PRECOMPILE) {
+        return Err(jsonrpc_core::Error::invalid_params(format!(
+            "Transaction must be sent to the proposals precompile {}",
+            PROPOSALS_PRECOMPILE
+        )));
+    }
+    if tx.data.len() < 4 || tx.data[..4] != proposals::selector(signature) {
+        return Err(jsonrpc_core::Error::invalid_params(format!(
+            "Transaction does not call {}",
+            String::from_utf8_lossy(signature)
+        )));
+    }
+    Ok(tx)
+}
+
+fn submit(mempool: &Mempool, tx: Transaction) -> Result<Value, jsonrpc_core::Error> {
+    let tx_hash = tx.hash;
+    match block_on(mempool.add_transaction(tx, TxClass::Standard)) {
+        Ok(_) => Ok(Value::String(format!(
+            "0x{}",
+            hex::encode(tx_hash.as_bytes())
+        ))),
+        Err(e) => Err(jsonrpc_core::Error::invalid_params(format!(
+            "Failed to submit transaction: {:?}",
+            e
+        ))),
+    }
+}
+
+/// Add governance proposal RPC methods to the IoHandler
+pub fn register_governance_methods(
+    io_handler: &mut IoHandler,
+    storage: Arc<StorageManager>,
+    mempool: Arc<Mempool>,
+    executor: Arc<Executor>,
+) {
+    // gov_getConfig - Thresholds and periods proposals are held to
+    io_handler.add_sync_method("gov_getConfig", move |_params: Params| {
+        Ok(json!({
+            "precompile": PROPOSALS_PRECOMPILE.to_string(),
+            "proposalThreshold": format!("0x{:x}", proposals::proposal_threshold()),
+            "voteThreshold": format!("0x{:x}", proposals::vote_threshold()),
+            "votingPeriod": proposals::VOTING_PERIOD,
+            "executionDelay": proposals::EXECUTION_DELAY,
+            "gracePeriod": proposals::GRACE_PERIOD,
+            "quorumPercentage": proposals::QUORUM_PERCENTAGE,
+            "approvalThreshold": proposals::APPROVAL_THRESHOLD,
+        }))
+    });
+
+    // gov_listProposals - Newest first: [offset?, limit?]
+    let storage_list = storage.clone();
+    let executor_list = executor.clone();
+    io_handler.add_sync_method("gov_listProposals", move |params: Params| {
+        let params: Vec<Value> = match params {
+            Params::None => Vec::new(),
+            params => parse_params(params)?,
+        };
+        let offset = params.first().and_then(|v| v.as_u64()).unwrap_or(0);
+        let limit = params
+            .get(1)
+            .and_then(|v| v.as_u64())
+            .unwrap_or(MAX_LIST)
+            .min(MAX_LIST);
+
+        let state_db = executor_list.state_db();
+        let height = storage_list.blocks.get_latest_height().unwrap_or(0);
+        let count = proposals::proposal_count(state_db);
+        let items: Vec<Value> = (0..count)
+            .rev()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .filter_map(|id| proposals::proposal(state_db, id))
+            .map(|p| proposal_json(&p, height))
+            .collect();
+        Ok(json!({ "total": count, "proposals": items }))
+    });
+
+    // gov_getProposal - [id]
+    let storage_get = storage.clone();
+    let executor_get = executor.clone();
+    io_handler.add_sync_method("gov_getProposal", move |params: Params| {
+        let params = parse_params(params)?;
+        let id = parse_id(params.first())?;
+        let height = storage_get.blocks.get_latest_height().unwrap_or(0);
+        Ok(proposals::proposal(executor_get.state_db(), id)
+            .map(|p| proposal_json(&p, height))
+            .unwrap_or(Value::Null))
+    });
+
+    // gov_getTally - [id]
+    let storage_tally = storage.clone();
+    let executor_tally = executor.clone();
+    io_handler.add_sync_method("gov_getTally", move |params: Params| {
+        let params = parse_params(params)?;
+        let id = parse_id(params.first())?;
+        let height = storage_tally.blocks.get_latest_height().unwrap_or(0);
+        Ok(proposals::proposal(executor_tally.state_db(), id)
+            .map(|p| tally_json(&p, height))
+            .unwrap_or(Value::Null))
+    });
+
+    // gov_getVote - [id, address]
+    let executor_vote = executor.clone();
+    io_handler.add_sync_method("gov_getVote", move |params: Params| {
+        let params = parse_params(params)?;
+        let id = parse_id(params.first())?;
+        let voter = parse_address(params.get(1))?;
+        Ok(
+            match proposals::vote_of(executor_vote.state_db(), id, &voter) {
+                Some(vote) => json!({
+                    "support": vote.support,
+                    "weight": format!("0x{:x}", vote.weight),
+                }),
+                None => Value::Null,
+            },
+        )
+    });
+
+    // gov_submitProposal / gov_vote / gov_execute / gov_cancel - [signedRawTx]
+    for (method, signature) in [
+        ("gov_submitProposal", PROPOSE_SIGNATURE),
+        ("gov_vote", VOTE_SIGNATURE),
+        ("gov_execute", EXECUTE_SIGNATURE),
+        ("gov_cancel", CANCEL_SIGNATURE),
+    ] {
+        let mempool = mempool.clone();
+        io_handler.add_sync_method(method, move |params: Params| {
+            let tx = decode_call(params, signature)?;
+            tracing::info!(
+                "{} admitted transaction 0x{}",
+                method,
+                hex::encode(tx.hash.as_bytes())
+            );
+            submit(&mempool, tx)
+        });
+    }
+}
@@ -0,0 +1,310 @@
+// citrate/core/api/src/ipfs_gateway.rs
+
+// Read-only HTTP gateway for content in the node's IPFS service
+//
+// Serves `GET /ipfs/<cid>[/<path>]` so model cards and generated images
+// referenced on-chain open directly in a browser. Single byte ranges are
+// honoured for media players and resumable downloads. Content is addressed
+// by hash, so responses are cached as immutable.
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use citrate_storage::ipfs::IPFSService;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+/// Bytes read from the start of a file to guess its type
+const SNIFF_LEN: u64 = 512;
+
+pub struct IpfsGateway {
+    addr: SocketAddr,
+    ipfs: Arc<IPFSService>,
+}
+
+impl IpfsGateway {
+    pub fn new(addr: SocketAddr, ipfs: Arc<IPFSService>) -> Self {
+        Self { addr, ipfs }
+    }
+
+    /// Start the gateway
+    pub async fn start(self) -> anyhow::Result<()> {
+        let app = Router::new()
+            .route("/ipfs/*path", get(content_handler).head(content_handler))
+            .with_state(self.ipfs);
+
+        info!("Starting IPFS gateway on {}", self.addr);
+
+        let listener = tokio::net::TcpListener::bind(self.addr).await?;
+        axum::serve(listener, app).await?;
+
+        Ok(())
+    }
+}
+
+async fn content_handler(
+    State(ipfs): State<Arc<IPFSService>>,
+    Path(path): Path<String>,
+    method: Method,
+    headers: HeaderMap,
+) -> Response {
+    let path = path.trim_end_matches('/');
+    if !is_valid_path(path) {
+        return error(StatusCode::BAD_REQUEST, "invalid IPFS path");
+    }
+
+    let stat = match ipfs.stat(path).await {
+        Ok(stat) => stat,
+        Err(e) => {
+            debug!("IPFS gateway stat {} failed: {}", path, e);
+            return error(StatusCode::NOT_FOUND, "content not found");
+        }
+    };
+    if stat.is_directory {
+        return error(StatusCode::BAD_REQUEST, "directory listings are not served");
+    }
+
+    let range = match headers.get(header::RANGE).and_then(|v| v.to_str().ok()) {
+        Some(value) => match parse_range(value, stat.size) {
+            Some(range) => Some(range),
+            None => {
+                return Response::builder()
+                    .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                    .header(header::CONTENT_RANGE, format!("bytes */{}", stat.size))
+                    .body(Body::empty())
+                    .unwrap()
+            }
+        },
+        None => None,
+    };
+
+    let content_type = match content_type_from_extension(path) {
+        Some(content_type) => content_type,
+        None => sniff(&ipfs, path).await,
+    };
+
+    let (status, start, len) = match range {
+        Some((start, end)) => (StatusCode::PARTIAL_CONTENT, start, end - start + 1),
+        None => (StatusCode::OK, 0, stat.size),
+    };
+
+    let mut response = Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::CONTENT_LENGTH, len)
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::ETAG, format!("\"{}\"", path))
+        .header(header::CACHE_CONTROL, "public, max-age=31536000, immutable")
+        .header(header::X_CONTENT_TYPE_OPTIONS, "nosniff");
+    if let Some((start, end)) = range {
+        response = response.header(
+            header::CONTENT_RANGE,
+            format!("bytes {}-{}/{}", start, end, stat.size),
+        );
+    }
+    if is_active_content(content_type) {
+        // Keep uploaded pages and SVG from running scripts on this origin
+        response = response.header(header::CONTENT_SECURITY_POLICY, "sandbox");
+    }
+
+    if method == Method::HEAD || len == 0 {
+        return response.body(Body::empty()).unwrap();
+    }
+
+    match ipfs.cat_range(path, start, Some(len)).await {
+        Ok(content) => response
+            .body(Body::from_stream(content.bytes_stream()))
+            .unwrap(),
+        Err(e) => {
+            debug!("IPFS gateway cat {} failed: {}", path, e);
+            error(StatusCode::BAD_GATEWAY, "failed to read content")
+        }
+    }
+}
+
+fn error(status: StatusCode, message: &'static str) -> Response {
+    (
+        status,
+        [(header::CONTENT_TYPE, HeaderValue::from_static("text/plain"))],
+        message,
+    )
+        .into_response()
+}
+
+/// A CID followed by optional path segments, none of them relative
+fn is_valid_path(path: &str) -> bool {
+    let mut segments = path.split('/');
+    let cid_ok = segments
+        .next()
+        .is_some_and(|cid| !cid.is_empty() && cid.chars().all(|c| c.is_ascii_alphanumeric()));
+    cid_ok && segments.all(|s| !s.is_empty() && s != "." && s != "..")
+}
+
+/// Inclusive byte range for a single-range `Range` header, or `None` when
+/// it can't be satisfied. Multiple ranges are not supported.
+fn parse_range(value: &str, size: u64) -> Option<(u64, u64)> {
+    let spec = value.trim().strip_prefix("bytes=")?;
+    if spec.contains(',') || size == 0 {
+        return None;
+    }
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
+
+    if start.is_empty() {
+        // Suffix range: the last N bytes
+        let suffix: u64 = end.parse().ok()?;
+        if suffix == 0 {
+            return None;
+        }
+        return Some((size.saturating_sub(suffix), size - 1));
+    }
+
+    let start: u64 = start.parse().ok()?;
+    let end = if end.is_empty() {
+        size - 1
+    } else {
+        end.parse::<u64>().ok()?.min(size - 1)
+    };
+    (start <= end).then_some((start, end))
+}
+
+fn content_type_from_extension(path: &str) -> Option<&'static str> {
+    let name = path.rsplit('/').next()?;
+    let (_, extension) = name.rsplit_once('.')?;
+    let content_type = match extension.to_ascii_lowercase().as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "json" => "application/json",
+        "md" => "text/markdown; charset=utf-8",
+        "txt" => "text/plain; charset=utf-8",
+        "html" | "htm" => "text/html; charset=utf-8",
+        "pdf" => "application/pdf",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "wav" => "audio/wav",
+        "mp3" => "audio/mpeg",
+        _ => return None,
+    };
+    Some(content_type)
+}
+
+async fn sniff(ipfs: &IPFSService, path: &str) -> &'static str {
+    match ipfs.cat_range(path, 0, Some(SNIFF_LEN)).await {
+        Ok(response) => match response.bytes().await {
+            Ok(head) => sniff_content_type(&head),
+            Err(_) => "application/octet-stream",
+        },
+        Err(_) => "application/octet-stream",
+    }
+}
+
+/// Guess a content type from the first bytes of a file
+fn sniff_content_type(head: &[u8]) -> &'static str {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"%PDF-", "application/pdf"),
+    ];
+    if let Some((_, content_type)) = SIGNATURES.iter().find(|(sig, _)| head.starts_with(sig)) {
+        return content_type;
+    }
+    if head.len() >= 12 && &head[..4] == b"RIFF" && &head[8..12] == b"WEBP" {
+        return "image/webp";
+    }
+
+    // Text detection tolerates a multi-byte character cut off at the end
+    let text = match std::str::from_utf8(head) {
+        Ok(text) => text,
+        Err(e) if e.error_len().is_none() => std::str::from_utf8(&head[..e.valid_up_to()]).unwrap(),
+        Err(_) => return "application/octet-stream",
+    };
+    if text.contains('\0') {
+        return "application/octet-stream";
+    }
+    let trimmed = text.trim_start();
+    let lower: String = trimmed
+        .chars()
+        .take(64)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        "application/json"
+    } else if lower.starts_with("<svg") || (lower.starts_with("<?xml") && text.contains("<svg")) {
+        "image/svg+xml"
+    } else if lower.starts_with("<!doctype html") || lower.starts_with("<html") {
+        "text/html; charset=utf-8"
+    } else {
+        "text/plain; charset=utf-8"
+    }
+}
+
+fn is_active_content(content_type: &str) -> bool {
+    content_type.starts_with("text/html") || content_type.starts_with("image/svg")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-99", 1000), Some((0, 99)));
+        assert_eq!(parse_range("bytes=900-", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=-100", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=-5000", 1000), Some((0, 999)));
+        assert_eq!(parse_range("bytes=990-2000", 1000), Some((990, 999)));
+        assert_eq!(parse_range("bytes=1000-", 1000), None);
+        assert_eq!(parse_range("bytes=50-10", 1000), None);
+        assert_eq!(parse_range("bytes=0-1,5-6", 1000), None);
+        assert_eq!(parse_range("items=0-1", 1000), None);
+        assert_eq!(parse_range("bytes=0-0", 0), None);
+    }
+
+    #[test]
+    fn test_content_type_detection() {
+        assert_eq!(
+            content_type_from_extension("Qm123/card.JSON"),
+            Some("application/json")
+        );
+        assert_eq!(content_type_from_extension("Qm123"), None);
+
+        assert_eq!(sniff_content_type(b"\x89PNG\r\n\x1a\n\0\0"), "image/png");
+        assert_eq!(sniff_content_type(b"RIFF\0\0\0\0WEBPVP8 "), "image/webp");
+        assert_eq!(
+            sniff_content_type(b"  {\"name\": \"model\"}"),
+            "application/json"
+        );
+        assert_eq!(
+            sniff_content_type(b"<svg xmlns=\"\"></svg>"),
+            "image/svg+xml"
+        );
+        assert_eq!(
+            sniff_content_type(b"<!DOCTYPE html><p>"),
+            "text/html; charset=utf-8"
+        );
+        assert_eq!(
+            sniff_content_type("# Model caf\u{e9}".as_bytes()),
+            "text/plain; charset=utf-8"
+        );
+        // Cut in the middle of a two-byte character
+        assert_eq!(sniff_content_type(b"caf\xc3"), "text/plain; charset=utf-8");
+        assert_eq!(
+            sniff_content_type(b"\0\x01\x02\xff"),
+            "application/octet-stream"
+        );
+
+        assert!(is_valid_path("QmAbc123/images/0.png"));
+        assert!(!is_valid_path("QmAbc123/../secret"));
+        assert!(!is_valid_path("Qm?arg=x"));
+    }
+}
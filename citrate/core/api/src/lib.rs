@@ -1,7 +1,12 @@
 
 // citrate/core/api/src/lib.rs
 
+pub mod access;
 pub mod ai_rpc;
+pub mod batch;
+pub mod billing;
+#[cfg(feature = "devnet")]
+pub mod dev_rpc;
 pub mod economics_rpc;
 pub mod eip1559_decoder;
 pub mod enhanced_tx_decoder;
@@ -9,9 +14,13 @@ pub mod eth_rpc;
 pub mod eth_rpc_simple;
 pub mod eth_subscriptions;
 pub mod eth_tx_decoder;
+pub mod explorer_api;
 pub mod filter;
+pub mod governance_rpc;
+pub mod ipfs_gateway;
 pub mod methods;
 pub mod metrics;
+pub mod mining_rpc;
 pub mod metrics_server;
 pub mod openai_api;
 pub mod server;
@@ -19,10 +28,12 @@ pub mod types;
 pub mod unified_tx_decoder;
 pub mod websocket;
 
+pub use access::{AccessConfig, AccessControl, ApiKeyEntry, RateLimit};
 pub use eip1559_decoder::{Eip1559Decoder, TransactionStats};
 pub use enhanced_tx_decoder::{EnhancedTransactionDecoder, DecodedTransaction, DecoderConfig, TransactionType};
 pub use eth_subscriptions::EthSubscriptionServer;
 pub use filter::FilterRegistry;
+pub use mining_rpc::{BlockSubmission, BlockSubmitError, BlockTemplate, BlockTemplateProvider};
 pub use openai_api::OpenAiRestServer;
 pub use server::{RpcConfig, RpcServer};
 pub use jsonrpc_http_server::CloseHandle as RpcCloseHandle;
@@ -68,7 +79,8 @@ impl ApiService {
         );
 
         let ws_server = WebSocketServer::new(ws_addr);
-        let rest_server = OpenAiRestServer::new(storage, mempool, executor);
+        let rest_server = OpenAiRestServer::new(storage, mempool, executor)
+            .with_access(rpc_server.access_control());
 
         Self {
             rpc_server,
@@ -78,6 +90,20 @@ impl ApiService {
         }
     }
 
+    /// Apply a content-safety pipeline to the REST API's chat endpoints
+    pub fn with_safety(mut self, safety: Arc<citrate_mcp::safety::SafetyPipeline>) -> Self {
+        self.rest_server = self.rest_server.with_safety(safety);
+        self
+    }
+
+    /// Bill REST API usage per API key in `billing` and expose the monthly
+    /// rollups over admin RPC
+    pub fn with_billing(mut self, billing: Arc<billing::BillingLedger>) -> Self {
+        self.rest_server = self.rest_server.with_billing(billing.clone());
+        self.rpc_server = self.rpc_server.with_billing(billing);
+        self
+    }
+
     /// Start RPC, WebSocket, and REST API servers
     pub async fn start(self) -> Result<()> {
         // Start RPC server on a dedicated OS thread
@@ -6,11 +6,13 @@ use citrate_execution::executor::Executor;
 use citrate_execution::types::{
     AccessPolicy, Address, JobId, ModelId, ModelMetadata, ModelState, TrainingJob,
 };
+use citrate_mcp::safety::SafetyPipeline;
 use citrate_sequencer::{Mempool, TxClass};
 use citrate_storage::StorageManager;
 use primitive_types::U256;
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Sha3_256};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 /// OpenAI-compatible chat completion request
@@ -21,11 +23,32 @@ pub struct ChatCompletionRequest {
     pub max_tokens: Option<u32>,
     pub temperature: Option<f32>,
     pub top_p: Option<f32>,
+    pub top_k: Option<u32>,
+    pub min_p: Option<f32>,
+    #[serde(alias = "repetition_penalty")]
+    pub repeat_penalty: Option<f32>,
+    /// Bias added to the logits of the given token ids, from -100 to 100
+    pub logit_bias: Option<BTreeMap<u32, f32>>,
     pub n: Option<u32>,
     pub stop: Option<Vec<String>>,
     pub stream: Option<bool>,
 }
 
+impl ChatCompletionRequest {
+    /// Sampler settings for the GGUF engine
+    pub fn sampling(&self) -> citrate_mcp::gguf_engine::SamplingParams {
+        citrate_mcp::gguf_engine::SamplingParams {
+            temperature: self.temperature.unwrap_or(0.7),
+            top_k: self.top_k,
+            top_p: self.top_p,
+            min_p: self.min_p,
+            repeat_penalty: self.repeat_penalty,
+            stop: self.stop.clone().unwrap_or_default(),
+            logit_bias: self.logit_bias.clone().unwrap_or_default(),
+        }
+    }
+}
+
 /// Chat message
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
@@ -169,6 +192,8 @@ pub struct AiApi {
     storage: Arc<StorageManager>,
     mempool: Arc<Mempool>,
     executor: Arc<Executor>,
+    /// Classifies chat prompts and outputs when configured
+    safety: Option<Arc<SafetyPipeline>>,
 }
 
 impl AiApi {
@@ -181,9 +206,16 @@ impl AiApi {
             storage,
             mempool,
             executor,
+            safety: None,
         }
     }
 
+    /// Check chat prompts and outputs against a safety pipeline
+    pub fn with_safety(mut self, safety: Arc<SafetyPipeline>) -> Self {
+        self.safety = Some(safety);
+        self
+    }
+
     // ========== Model Management ==========
 
     /// Deploy a new model to the network
@@ -695,6 +727,23 @@ impl AiApi {
             ));
         }
 
+        let sampling = request.sampling();
+        sampling
+            .validate()
+            .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+
+        let source = match from {
+            Some(from) => format!("chat_completions:{}", hex::encode(from.0)),
+            None => "chat_completions".to_string(),
+        };
+        if let Some(safety) = &self.safety {
+            for msg in request.messages.iter().filter(|m| m.role != "assistant") {
+                if safety.check_prompt(&source, &msg.content).await.is_blocked() {
+                    return Err(ApiError::ContentBlocked(safety.block_message().to_string()));
+                }
+            }
+        }
+
         // Use Mistral 7B model from IPFS (well-known model ID)
         // In production, would look up model by name from request.model
         let llm_model_id = ModelId(Hash::new([0x02; 32])); // Placeholder for Mistral 7B
@@ -717,6 +766,11 @@ impl AiApi {
             "max_tokens": request.max_tokens.unwrap_or(512),
             "temperature": request.temperature.unwrap_or(0.7),
             "top_p": request.top_p.unwrap_or(1.0),
+            "top_k": request.top_k,
+            "min_p": request.min_p,
+            "repeat_penalty": request.repeat_penalty,
+            "stop": &sampling.stop,
+            "logit_bias": &sampling.logit_bias,
         }))
         .map_err(|e| ApiError::InternalError(e.to_string()))?;
 
@@ -788,12 +842,13 @@ impl AiApi {
             .map_err(|e| ApiError::InternalError(format!("Failed to initialize GGUF engine: {}", e)))?;
 
         // Generate text using llama.cpp
-        let generated_text = gguf_engine
-            .generate_text(
+        let mut generated_text = gguf_engine
+            .generate_text_with(
                 &model_path,
                 &prompt,
                 request.max_tokens.unwrap_or(512) as usize,
-                request.temperature.unwrap_or(0.7),
+                &sampling,
+                None,
             )
             .await
             .map_err(|e| ApiError::InternalError(format!("GGUF inference failed: {}", e)))?;
@@ -801,6 +856,14 @@ impl AiApi {
         // Estimate actual token counts from the response
         let completion_tokens = (generated_text.len() / 4) as u32;
 
+        let mut finish_reason = "stop";
+        if let Some(safety) = &self.safety {
+            if safety.check_output(&source, &generated_text).await.is_blocked() {
+                generated_text = safety.block_message().to_string();
+                finish_reason = "content_filter";
+            }
+        }
+
         Ok(ChatCompletionResponse {
             id: response_id,
             object: "chat.completion".to_string(),
@@ -812,7 +875,7 @@ impl AiApi {
                     role: "assistant".to_string(),
                     content: generated_text,
                 },
-                finish_reason: "stop".to_string(),
+                finish_reason: finish_reason.to_string(),
             }],
             usage: TokenUsage {
                 prompt_tokens,
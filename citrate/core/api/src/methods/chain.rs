@@ -2,12 +2,28 @@
 use crate::types::{
     error::ApiError,
     request::{BlockId, BlockTag},
-    response::{BlockResponse, TransactionResponse},
+    response::{BlockResponse, ConflictStatsResponse, TransactionResponse},
 };
-use citrate_consensus::types::{BlockHeader, Hash};
+use citrate_consensus::ordering::ConflictDetector;
+use citrate_consensus::types::{Block, BlockHeader, Hash};
 use citrate_execution::types::TransactionReceipt;
+use citrate_storage::chain::{ChainStatsSnapshot, DagStatsBucket};
 use citrate_storage::StorageManager;
+use std::collections::{HashSet, VecDeque};
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Upper bound on blocks scanned per height when collecting parallel blocks
+const MAX_BLOCKS_PER_HEIGHT: u64 = 16;
+
+/// Default look-back for DAG statistics series (one hour)
+const DEFAULT_DAG_STATS_WINDOW_SECS: u64 = 3600;
+
+/// Default DAG statistics bucket width
+const DEFAULT_DAG_STATS_BUCKET_SECS: u64 = 60;
+
+/// Default look-back for the hourly statistics history (seven days)
+const DEFAULT_STATS_HISTORY_WINDOW_SECS: u64 = 7 * 24 * 3600;
 
 /// Chain-related API methods
 pub struct ChainApi {
@@ -144,6 +160,20 @@ impl ChainApi {
             .ok_or_else(|| ApiError::TransactionNotFound(format!("Receipt for {:?}", hash)))
     }
 
+    /// Receipts of every transaction in a block, in block order. Fails if
+    /// any of them has been pruned.
+    pub async fn get_block_receipts(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Vec<TransactionReceipt>, ApiError> {
+        let block = self.get_block(block_id).await?;
+        let mut receipts = Vec::with_capacity(block.transactions.len());
+        for tx in &block.transactions {
+            receipts.push(self.get_receipt(tx.hash).await?);
+        }
+        Ok(receipts)
+    }
+
     /// Get current chain height
     pub async fn get_height(&self) -> Result<u64, ApiError> {
         self.get_latest_height().await
@@ -172,6 +202,130 @@ impl ChainApi {
         Ok(tips)
     }
 
+    /// DAG health time series (tips, merge parents, anticone sizes and
+    /// blue/red ratio) bucketed over `[from, to)` in unix seconds.
+    ///
+    /// Defaults to the last hour in one-minute buckets.
+    pub async fn get_dag_stats_series(
+        &self,
+        from: Option<u64>,
+        to: Option<u64>,
+        bucket_secs: Option<u64>,
+    ) -> Result<Vec<DagStatsBucket>, ApiError> {
+        let to = to.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+                + 1
+        });
+        let from = from.unwrap_or_else(|| to.saturating_sub(DEFAULT_DAG_STATS_WINDOW_SECS));
+        if from >= to {
+            return Err(ApiError::InvalidParams(
+                "from must be earlier than to".to_string(),
+            ));
+        }
+        let bucket_secs = bucket_secs.unwrap_or(DEFAULT_DAG_STATS_BUCKET_SECS);
+        self.storage
+            .dag_stats
+            .series(from, to, bucket_secs)
+            .map_err(|e| ApiError::InternalError(e.to_string()))
+    }
+
+    /// Hourly chain statistics snapshots (height, tips, throughput, peers,
+    /// mempool depth) with timestamps in `[from, to)`, unix seconds.
+    ///
+    /// Defaults to the last seven days.
+    pub async fn get_stats_history(
+        &self,
+        from: Option<u64>,
+        to: Option<u64>,
+    ) -> Result<Vec<ChainStatsSnapshot>, ApiError> {
+        let to = to.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+                + 1
+        });
+        let from = from.unwrap_or_else(|| to.saturating_sub(DEFAULT_STATS_HISTORY_WINDOW_SECS));
+        if from >= to {
+            return Err(ApiError::InvalidParams(
+                "from must be earlier than to".to_string(),
+            ));
+        }
+        self.storage
+            .stats_history
+            .get_range(from, to)
+            .map_err(|e| ApiError::InternalError(e.to_string()))
+    }
+
+    /// Detect conflicting transactions between parallel blocks in the last
+    /// `depth` heights.
+    ///
+    /// Blocks are collected by walking children from the block at the start
+    /// height, then fed to the detector ordered by height, blue score (desc)
+    /// and hash, the same tie-break the total ordering uses for mergesets.
+    pub async fn get_conflict_stats(&self, depth: u64) -> Result<ConflictStatsResponse, ApiError> {
+        let to_height = self.get_latest_height().await?;
+        let from_height = to_height.saturating_sub(depth);
+        let start = self
+            .storage
+            .blocks
+            .get_block_by_height(from_height)
+            .map_err(|e| ApiError::InternalError(e.to_string()))?
+            .ok_or_else(|| ApiError::BlockNotFound(format!("height {}", from_height)))?;
+
+        let max_blocks = (depth + 1).saturating_mul(MAX_BLOCKS_PER_HEIGHT) as usize;
+        let mut blocks: Vec<Block> = Vec::new();
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(hash) = queue.pop_front() {
+            if blocks.len() >= max_blocks {
+                break;
+            }
+            if !visited.insert(hash) {
+                continue;
+            }
+            let Some(block) = self
+                .storage
+                .blocks
+                .get_block(&hash)
+                .map_err(|e| ApiError::InternalError(e.to_string()))?
+            else {
+                continue;
+            };
+            queue.extend(
+                self.storage
+                    .blocks
+                    .get_children(&hash)
+                    .map_err(|e| ApiError::InternalError(e.to_string()))?,
+            );
+            blocks.push(block);
+        }
+
+        blocks.sort_by_key(|b| {
+            (
+                b.header.height,
+                std::cmp::Reverse(b.header.blue_score),
+                b.hash(),
+            )
+        });
+
+        let mut detector = ConflictDetector::new();
+        for block in &blocks {
+            detector.scan_block(block);
+        }
+        let (conflicts, stats) = detector.finish();
+
+        Ok(ConflictStatsResponse {
+            from_height,
+            to_height,
+            stats,
+            conflicts,
+        })
+    }
+
     // Helper method
     async fn get_latest_height(&self) -> Result<u64, ApiError> {
         self.storage
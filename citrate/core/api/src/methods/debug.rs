@@ -0,0 +1,143 @@
+// citrate/core/api/src/methods/debug.rs
+use crate::types::{
+    error::ApiError,
+    request::{BlockId, BlockTag},
+};
+use citrate_consensus::types::{Block, Hash, Transaction};
+use citrate_execution::address_utils::normalize_address;
+use citrate_execution::executor::Executor;
+use citrate_execution::tracer::{TraceOptions, TransactionTrace};
+use citrate_storage::StorageManager;
+use std::sync::Arc;
+
+/// Transaction replay and tracing
+pub struct DebugApi {
+    storage: Arc<StorageManager>,
+    executor: Arc<Executor>,
+}
+
+impl DebugApi {
+    pub fn new(storage: Arc<StorageManager>, executor: Arc<Executor>) -> Self {
+        Self { storage, executor }
+    }
+
+    /// Replay a transaction, after the transactions preceding it in its
+    /// block, and return its call trace
+    pub async fn trace_transaction(
+        &self,
+        hash: Hash,
+        options: TraceOptions,
+    ) -> Result<TransactionTrace, ApiError> {
+        let receipt = self
+            .storage
+            .transactions
+            .get_receipt(&hash)
+            .map_err(|e| ApiError::InternalError(e.to_string()))?
+            .ok_or_else(|| ApiError::TransactionNotFound(format!("{:?}", hash)))?;
+        let block = self.load_block(&receipt.block_hash)?;
+        let index = block
+            .transactions
+            .iter()
+            .position(|tx| tx.hash == hash)
+            .ok_or_else(|| {
+                ApiError::TransactionNotFound(format!(
+                    "{:?} in block {:?}",
+                    hash, receipt.block_hash
+                ))
+            })?;
+
+        let replay = self.replay_executor()?;
+        for tx in &block.transactions[..index] {
+            align_nonce(&replay, tx);
+            // Failed predecessors still leave their gas and nonce effects
+            let _ = replay.execute_transaction(&block, tx).await;
+        }
+
+        let tx = &block.transactions[index];
+        align_nonce(&replay, tx);
+        let (_, trace) = replay
+            .trace_transaction(&block, tx, options)
+            .await
+            .map_err(|e| ApiError::ExecutionFailed(e.to_string()))?;
+        Ok(trace)
+    }
+
+    /// Replay every transaction of a block in order and return their traces
+    pub async fn trace_block(
+        &self,
+        block_id: BlockId,
+        options: TraceOptions,
+    ) -> Result<Vec<TransactionTrace>, ApiError> {
+        let hash = self.resolve_block(block_id)?;
+        let block = self.load_block(&hash)?;
+
+        let replay = self.replay_executor()?;
+        let mut traces = Vec::with_capacity(block.transactions.len());
+        for tx in &block.transactions {
+            align_nonce(&replay, tx);
+            let (_, trace) = replay
+                .trace_transaction(&block, tx, options.clone())
+                .await
+                .map_err(|e| ApiError::ExecutionFailed(format!("{:?}: {}", tx.hash, e)))?;
+            traces.push(trace);
+        }
+        Ok(traces)
+    }
+
+    /// Executor over a private copy of the state. Accounts are reloaded from
+    /// the `StateStore`; contract code and storage come from the live state,
+    /// which is the only place they are kept. The store holds no history, so
+    /// accounts touched by later blocks start from their latest values.
+    fn replay_executor(&self) -> Result<Executor, ApiError> {
+        let state = self.executor.state_db().fork();
+        let accounts = self
+            .storage
+            .state
+            .get_all_accounts()
+            .map_err(|e| ApiError::InternalError(e.to_string()))?;
+        for (address, account) in accounts {
+            state.accounts.set_account(address, account);
+        }
+        Ok(Executor::with_chain_id(
+            Arc::new(state),
+            self.executor.chain_id(),
+        ))
+    }
+
+    fn resolve_block(&self, block_id: BlockId) -> Result<Hash, ApiError> {
+        let height = match block_id {
+            BlockId::Hash(hash) => return Ok(hash),
+            BlockId::Number(height) => height,
+            BlockId::Tag(BlockTag::Latest) => self
+                .storage
+                .blocks
+                .get_latest_height()
+                .map_err(|e| ApiError::InternalError(e.to_string()))?,
+            BlockId::Tag(BlockTag::Earliest) => 0,
+            BlockId::Tag(BlockTag::Pending) => {
+                return Err(ApiError::InvalidParams(
+                    "Pending blocks cannot be traced".into(),
+                ))
+            }
+        };
+        self.storage
+            .blocks
+            .get_block_by_height(height)
+            .map_err(|e| ApiError::InternalError(e.to_string()))?
+            .ok_or_else(|| ApiError::BlockNotFound(format!("height {}", height)))
+    }
+
+    fn load_block(&self, hash: &Hash) -> Result<Block, ApiError> {
+        self.storage
+            .blocks
+            .get_block(hash)
+            .map_err(|e| ApiError::InternalError(e.to_string()))?
+            .ok_or_else(|| ApiError::BlockNotFound(format!("{:?}", hash)))
+    }
+}
+
+/// Rewind the sender's nonce to the one the transaction was sent with, so a
+/// replay does not depend on how far the sender has moved on since
+fn align_nonce(executor: &Executor, tx: &Transaction) {
+    executor.set_nonce(&normalize_address(&tx.from), tx.nonce);
+}
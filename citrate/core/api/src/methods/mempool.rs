@@ -44,7 +44,7 @@ impl MempoolApi {
         limit: Option<usize>,
     ) -> Result<Vec<TransactionResponse>, ApiError> {
         let limit = limit.unwrap_or(100).min(1000);
-        let txs = self.mempool.get_transactions(limit).await;
+        let txs = self.mempool.get_public_transactions(limit).await;
         Ok(txs.into_iter().map(Into::into).collect())
     }
 
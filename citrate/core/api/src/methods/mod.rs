@@ -1,6 +1,7 @@
 // citrate/core/api/src/methods/mod.rs
 pub mod ai;
 pub mod chain;
+pub mod debug;
 pub mod mempool;
 pub mod network;
 pub mod state;
@@ -8,6 +9,7 @@ pub mod transaction;
 
 pub use ai::AiApi;
 pub use chain::ChainApi;
+pub use debug::DebugApi;
 pub use mempool::MempoolApi;
 pub use network::NetworkApi;
 pub use state::StateApi;
@@ -1,8 +1,14 @@
 // citrate/core/api/src/methods/network.rs
-use crate::types::{error::ApiError, response::NodeInfo};
+use crate::types::{
+    error::ApiError,
+    response::{NodeInfo, PeerInfoResponse, PeerReputationResponse},
+};
 use citrate_consensus::types::Hash;
-use citrate_network::peer::PeerManager;
+use citrate_network::peer::{Direction, PeerManager, PeerState};
+use std::collections::HashSet;
+use std::net::IpAddr;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Network-related API methods
 pub struct NetworkApi {
@@ -59,4 +65,72 @@ impl NetworkApi {
 
         Ok(peer_ids)
     }
+
+    /// Get connection details of each peer
+    pub async fn get_peer_info(&self) -> Result<Vec<PeerInfoResponse>, ApiError> {
+        let peers = self.peer_manager.get_all_peers();
+        let mut out = Vec::with_capacity(peers.len());
+
+        for peer in peers {
+            let info = peer.info.read().await;
+            let direction = match info.direction {
+                Direction::Inbound => "inbound",
+                Direction::Outbound => "outbound",
+            };
+            let state = match info.state {
+                PeerState::Connecting => "connecting",
+                PeerState::Handshaking => "handshaking",
+                PeerState::Connected => "connected",
+                PeerState::Disconnecting => "disconnecting",
+                PeerState::Disconnected => "disconnected",
+            };
+            out.push(PeerInfoResponse {
+                id: info.id.to_string(),
+                addr: info.addr.to_string(),
+                direction: direction.to_string(),
+                state: state.to_string(),
+                score: info.score,
+                last_seen_secs: info.last_seen.elapsed().as_secs(),
+                client_version: info.client_version.clone(),
+            });
+        }
+
+        Ok(out)
+    }
+
+    /// Get persisted peer reputation, optionally for a single host
+    pub async fn get_peer_reputation(
+        &self,
+        ip: Option<IpAddr>,
+    ) -> Result<Vec<PeerReputationResponse>, ApiError> {
+        let reputation = self.peer_manager.reputation();
+        let records = match ip {
+            Some(ip) => reputation.get(&ip).into_iter().collect(),
+            None => reputation.all(),
+        };
+
+        let mut connected = HashSet::new();
+        for peer in self.peer_manager.get_all_peers() {
+            connected.insert(peer.info.read().await.addr.ip());
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Ok(records
+            .into_iter()
+            .map(|r| PeerReputationResponse {
+                ip: r.ip.to_string(),
+                connected: connected.contains(&r.ip),
+                banned: r.is_banned_at(now),
+                peer_id: r.peer_id,
+                score: r.score,
+                banned_until: r.banned_until,
+                ban_reason: r.ban_reason,
+                ban_count: r.ban_count,
+                events: r.events.into_iter().collect(),
+            })
+            .collect())
+    }
 }
@@ -1,11 +1,18 @@
 // citrate/core/api/src/methods/transaction.rs
 use crate::types::{
     error::ApiError,
-    request::{CallRequest, TransactionRequest},
+    request::{CallRequest, SimulationRequest, StateOverrideRequest, TransactionRequest},
+    response::SimulationResponse,
+};
+use citrate_consensus::types::{
+    Block, BlockHeader, Hash, PublicKey, Signature, Transaction, VrfProof,
 };
-use citrate_consensus::types::{Hash, PublicKey, Signature, Transaction};
 use citrate_execution::executor::Executor;
+use citrate_execution::simulation::{AccountOverride, StateOverrides};
+use citrate_execution::types::Address;
 use citrate_sequencer::mempool::{Mempool, TxClass};
+use primitive_types::U256;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// Transaction-related API methods
@@ -93,6 +100,68 @@ impl TransactionApi {
         Ok(base_gas + data_gas)
     }
 
+    /// Execute a transaction against a copy of the current state, with
+    /// `overrides` applied first, and report its gas, logs, return data and
+    /// state changes. Nothing reaches the mempool or the live state.
+    pub async fn simulate_transaction(
+        &self,
+        request: SimulationRequest,
+        overrides: HashMap<String, StateOverrideRequest>,
+    ) -> Result<SimulationResponse, ApiError> {
+        let overrides = parse_overrides(overrides)?;
+        let from = request.from.as_deref().map(parse_address).transpose()?;
+        let to = request.to.as_deref().map(parse_address).transpose()?;
+        let nonce = match request.nonce.as_deref() {
+            Some(nonce) => parse_quantity(nonce)?.low_u64(),
+            None => from
+                .and_then(|from| overrides.get(&from).and_then(|o| o.nonce))
+                .or_else(|| from.map(|from| self.executor.get_nonce(&from)))
+                .unwrap_or(0),
+        };
+        let value = match request.value.as_deref() {
+            Some(value) => parse_quantity(value)?,
+            None => U256::zero(),
+        };
+        if value > U256::from(u128::MAX) {
+            return Err(ApiError::InvalidParams("value out of range".into()));
+        }
+
+        let mut tx = Transaction {
+            hash: Hash::default(),
+            nonce,
+            from: from.map_or(PublicKey::new([0; 32]), address_key),
+            to: to.map(address_key),
+            value: value.as_u128(),
+            gas_limit: match request.gas.as_deref() {
+                Some(gas) => parse_quantity(gas)?.low_u64(),
+                None => 30_000_000,
+            },
+            gas_price: match request.gas_price.as_deref() {
+                Some(price) => parse_quantity(price)?.low_u64(),
+                None => 0,
+            },
+            data: match request.data.as_deref() {
+                Some(data) => parse_bytes(data)?,
+                None => Vec::new(),
+            },
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        };
+        tx.determine_type();
+
+        let simulation = self
+            .executor
+            .simulate_transaction(
+                self.executor.state_db().fork(),
+                &simulation_block(),
+                &tx,
+                &overrides,
+            )
+            .await
+            .map_err(|e| ApiError::ExecutionFailed(e.to_string()))?;
+        Ok(simulation.into())
+    }
+
     /// Get current gas price
     pub async fn get_gas_price(&self) -> Result<u64, ApiError> {
         // Return minimum gas price for now
@@ -107,3 +176,115 @@ impl TransactionApi {
         Ok(self.executor.get_nonce(&address))
     }
 }
+
+/// Public key field embedding a 20-byte address, as `eth_call` builds them
+fn address_key(address: Address) -> PublicKey {
+    let mut key = [0u8; 32];
+    key[..20].copy_from_slice(&address.0);
+    PublicKey::new(key)
+}
+
+/// Block context simulations run in
+fn simulation_block() -> Block {
+    Block {
+        header: BlockHeader {
+            version: 1,
+            block_hash: Hash::default(),
+            selected_parent_hash: Hash::default(),
+            merge_parent_hashes: vec![],
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            height: 0,
+            blue_score: 0,
+            blue_work: 0,
+            pruning_point: Hash::default(),
+            proposer_pubkey: PublicKey::new([0; 32]),
+            vrf_reveal: VrfProof {
+                proof: vec![],
+                output: Hash::default(),
+            },
+            base_fee_per_gas: 0,
+            gas_used: 0,
+            gas_limit: 30_000_000,
+            epoch_commitment: Hash::default(),
+        },
+        state_root: Hash::default(),
+        tx_root: Hash::default(),
+        receipt_root: Hash::default(),
+        artifact_root: Hash::default(),
+        ghostdag_params: Default::default(),
+        transactions: vec![],
+        signature: Signature::new([0; 64]),
+        embedded_models: vec![],
+        required_pins: vec![],
+    }
+}
+
+fn parse_overrides(
+    overrides: HashMap<String, StateOverrideRequest>,
+) -> Result<StateOverrides, ApiError> {
+    overrides
+        .into_iter()
+        .map(|(address, account)| {
+            let account = AccountOverride {
+                balance: account.balance.as_deref().map(parse_quantity).transpose()?,
+                nonce: account
+                    .nonce
+                    .as_deref()
+                    .map(|n| parse_quantity(n).map(|n| n.low_u64()))
+                    .transpose()?,
+                code: account.code.as_deref().map(parse_bytes).transpose()?,
+                storage: account
+                    .storage
+                    .iter()
+                    .map(|(slot, value)| Ok((parse_word(slot)?, parse_word(value)?)))
+                    .collect::<Result<_, ApiError>>()?,
+            };
+            Ok((parse_address(&address)?, account))
+        })
+        .collect()
+}
+
+/// A 0x-hex or decimal quantity
+fn parse_quantity(s: &str) -> Result<U256, ApiError> {
+    let s = s.trim();
+    let parsed = match s.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).map_err(|e| e.to_string()),
+        None => U256::from_dec_str(s).map_err(|e| e.to_string()),
+    };
+    parsed.map_err(|e| ApiError::InvalidParams(format!("Invalid quantity {}: {}", s, e)))
+}
+
+fn parse_bytes(s: &str) -> Result<Vec<u8>, ApiError> {
+    let s = s.trim();
+    hex::decode(s.strip_prefix("0x").unwrap_or(s))
+        .map_err(|_| ApiError::InvalidParams(format!("Invalid hex data {}", s)))
+}
+
+fn parse_address(s: &str) -> Result<Address, ApiError> {
+    let bytes = parse_bytes(s)?;
+    let bytes: [u8; 20] = bytes
+        .try_into()
+        .map_err(|_| ApiError::InvalidParams(format!("Invalid address {}", s)))?;
+    Ok(Address(bytes))
+}
+
+/// A storage slot or value, left-padded to 32 bytes
+fn parse_word(s: &str) -> Result<[u8; 32], ApiError> {
+    let s = s.trim();
+    let hex = s.strip_prefix("0x").unwrap_or(s);
+    let hex = if hex.len() % 2 == 1 {
+        format!("0{}", hex)
+    } else {
+        hex.to_string()
+    };
+    let bytes = hex::decode(&hex)
+        .ok()
+        .filter(|b| b.len() <= 32)
+        .ok_or_else(|| ApiError::InvalidParams(format!("Invalid storage word {}", s)))?;
+    let mut word = [0u8; 32];
+    word[32 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(word)
+}
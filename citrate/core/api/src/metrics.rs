@@ -1,7 +1,11 @@
 // citrate/core/api/src/metrics.rs
 
+use citrate_consensus::metrics::{set_recorder, MetricEvent};
 use once_cell::sync::Lazy;
-use prometheus::{register_int_counter_vec, IntCounterVec};
+use prometheus::{
+    exponential_buckets, register_histogram, register_int_counter_vec, register_int_gauge_vec,
+    Histogram, IntCounterVec, IntGaugeVec,
+};
 
 pub static RPC_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -12,7 +16,199 @@ pub static RPC_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
     .expect("register citrate_rpc_requests_total")
 });
 
+pub static RPC_BATCH_SIZE: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "citrate_rpc_batch_size",
+        "Calls per batch JSON-RPC request",
+        exponential_buckets(1.0, 2.0, 11).expect("valid buckets")
+    )
+    .expect("register citrate_rpc_batch_size")
+});
+
+pub static MEMPOOL_EVICTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "citrate_mempool_evictions_total",
+        "Transactions dropped from the mempool without being included, by reason",
+        &["reason"]
+    )
+    .expect("register citrate_mempool_evictions_total")
+});
+
+pub static SYNC_RECEIVED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "citrate_sync_received_total",
+        "Headers and blocks received from sync peers",
+        &["kind"]
+    )
+    .expect("register citrate_sync_received_total")
+});
+
+pub static SYNC_PENDING_REQUESTS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "citrate_sync_pending_requests",
+        "Sync requests awaiting a response",
+        &["kind"]
+    )
+    .expect("register citrate_sync_pending_requests")
+});
+
+pub static GHOSTDAG_BLOCKS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "citrate_ghostdag_blocks_total",
+        "Parents of added blocks colored by GhostDAG",
+        &["color"]
+    )
+    .expect("register citrate_ghostdag_blocks_total")
+});
+
+pub static REORG_DEPTH: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "citrate_reorg_depth",
+        "Blocks unwound by selected chain reorganizations",
+        vec![1.0, 2.0, 3.0, 5.0, 8.0, 13.0, 21.0, 50.0, 100.0]
+    )
+    .expect("register citrate_reorg_depth")
+});
+
+pub static BLOCK_BUILD_DURATION: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "citrate_block_build_duration_seconds",
+        "Time to select, execute and store a locally produced block",
+        exponential_buckets(0.005, 2.0, 12).expect("valid buckets")
+    )
+    .expect("register citrate_block_build_duration_seconds")
+});
+
+pub static NETWORK_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "citrate_network_bytes_total",
+        "Bytes sent and received over peer connections by message type",
+        &["direction", "kind"]
+    )
+    .expect("register citrate_network_bytes_total")
+});
+
+pub static NETWORK_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "citrate_network_messages_total",
+        "Messages sent and received over peer connections by message type",
+        &["direction", "kind"]
+    )
+    .expect("register citrate_network_messages_total")
+});
+
+pub static GOSSIP_DUPLICATES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "citrate_gossip_duplicates_total",
+        "Gossip announcements dropped as already seen",
+        &["kind"]
+    )
+    .expect("register citrate_gossip_duplicates_total")
+});
+
 #[inline]
 pub fn rpc_request(method: &str) {
     RPC_REQUESTS.with_label_values(&[method]).inc();
 }
+
+#[inline]
+pub fn rpc_batch(calls: usize) {
+    RPC_BATCH_SIZE.observe(calls as f64);
+}
+
+/// Route metric events from core modules into the metrics above. Call once
+/// at startup; returns `false` if a recorder was already installed.
+pub fn install() -> bool {
+    Lazy::force(&REORG_DEPTH);
+    Lazy::force(&BLOCK_BUILD_DURATION);
+    Lazy::force(&RPC_BATCH_SIZE);
+    set_recorder(record)
+}
+
+fn record(event: &MetricEvent) {
+    match event {
+        MetricEvent::MempoolEviction { reason } => {
+            MEMPOOL_EVICTIONS.with_label_values(&[reason]).inc();
+        }
+        MetricEvent::SyncReceived { kind, count } => {
+            SYNC_RECEIVED
+                .with_label_values(&[kind])
+                .inc_by(*count as u64);
+        }
+        MetricEvent::SyncPending { headers, blocks } => {
+            SYNC_PENDING_REQUESTS
+                .with_label_values(&["headers"])
+                .set(*headers as i64);
+            SYNC_PENDING_REQUESTS
+                .with_label_values(&["blocks"])
+                .set(*blocks as i64);
+        }
+        MetricEvent::BlockColored { blue, red } => {
+            GHOSTDAG_BLOCKS
+                .with_label_values(&["blue"])
+                .inc_by(*blue as u64);
+            GHOSTDAG_BLOCKS
+                .with_label_values(&["red"])
+                .inc_by(*red as u64);
+        }
+        MetricEvent::Reorg { depth } => REORG_DEPTH.observe(*depth as f64),
+        MetricEvent::BlockBuilt { seconds } => BLOCK_BUILD_DURATION.observe(*seconds),
+        MetricEvent::NetworkBytes {
+            direction,
+            kind,
+            bytes,
+        } => {
+            NETWORK_BYTES
+                .with_label_values(&[direction, kind])
+                .inc_by(*bytes as u64);
+            NETWORK_MESSAGES.with_label_values(&[direction, kind]).inc();
+        }
+        MetricEvent::GossipDuplicate { kind } => {
+            GOSSIP_DUPLICATES.with_label_values(&[kind]).inc();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_update_metrics() {
+        record(&MetricEvent::MempoolEviction { reason: "capacity" });
+        record(&MetricEvent::SyncReceived {
+            kind: "blocks",
+            count: 5,
+        });
+        record(&MetricEvent::SyncPending {
+            headers: 2,
+            blocks: 1,
+        });
+        record(&MetricEvent::BlockColored { blue: 2, red: 1 });
+        record(&MetricEvent::Reorg { depth: 3 });
+        record(&MetricEvent::BlockBuilt { seconds: 0.02 });
+        record(&MetricEvent::NetworkBytes {
+            direction: "out",
+            kind: "new_block",
+            bytes: 300,
+        });
+        record(&MetricEvent::GossipDuplicate {
+            kind: "transaction",
+        });
+
+        assert!(MEMPOOL_EVICTIONS.with_label_values(&["capacity"]).get() >= 1);
+        assert!(SYNC_RECEIVED.with_label_values(&["blocks"]).get() >= 5);
+        assert_eq!(SYNC_PENDING_REQUESTS.with_label_values(&["headers"]).get(), 2);
+        assert!(GHOSTDAG_BLOCKS.with_label_values(&["red"]).get() >= 1);
+        assert!(REORG_DEPTH.get_sample_count() >= 1);
+        assert!(BLOCK_BUILD_DURATION.get_sample_count() >= 1);
+        assert!(NETWORK_BYTES.with_label_values(&["out", "new_block"]).get() >= 300);
+        assert!(
+            NETWORK_MESSAGES
+                .with_label_values(&["out", "new_block"])
+                .get()
+                >= 1
+        );
+        assert!(GOSSIP_DUPLICATES.with_label_values(&["transaction"]).get() >= 1);
+    }
+}
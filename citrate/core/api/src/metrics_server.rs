@@ -1,13 +1,22 @@
 // citrate/core/api/src/metrics_server.rs
 
-use axum::{body::Body, http::StatusCode, response::Response, routing::get, Router};
+use axum::{
+    body::Body, extract::State, http::StatusCode, response::Response, routing::get, Router,
+};
+use citrate_sequencer::mempool::{Mempool, MempoolStats, TxClass};
+use citrate_storage::chain::{dag_stats::aggregate, DagStatsStore};
 use once_cell::sync::Lazy;
 use prometheus::{
     register_counter_vec, register_gauge_vec, register_histogram_vec, Encoder, TextEncoder,
 };
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::info;
 
+/// Trailing window summarized into the DAG health gauges on each scrape
+const DAG_STATS_WINDOW_SECS: u64 = 60;
+
 // RPC Metrics
 pub static RPC_REQUEST_DURATION: Lazy<prometheus::HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
@@ -88,6 +97,42 @@ pub static DAG_BLUE_SCORE: Lazy<prometheus::GaugeVec> = Lazy::new(|| {
         .expect("Failed to register DAG blue score metric")
 });
 
+pub static DAG_BLOCKS_PER_SECOND: Lazy<prometheus::GaugeVec> = Lazy::new(|| {
+    register_gauge_vec!(
+        "citrate_dag_blocks_per_second",
+        "Blocks stored per second over the last minute",
+        &[]
+    )
+    .expect("Failed to register DAG block rate metric")
+});
+
+pub static DAG_MERGE_PARENTS: Lazy<prometheus::GaugeVec> = Lazy::new(|| {
+    register_gauge_vec!(
+        "citrate_dag_merge_parents",
+        "Merge parents per block over the last minute",
+        &["stat"]
+    )
+    .expect("Failed to register DAG merge parents metric")
+});
+
+pub static DAG_ANTICONE_SIZE: Lazy<prometheus::GaugeVec> = Lazy::new(|| {
+    register_gauge_vec!(
+        "citrate_dag_anticone_size",
+        "Parallel tips per block over the last minute",
+        &["stat"]
+    )
+    .expect("Failed to register DAG anticone size metric")
+});
+
+pub static DAG_BLUE_RATIO: Lazy<prometheus::GaugeVec> = Lazy::new(|| {
+    register_gauge_vec!(
+        "citrate_dag_blue_ratio",
+        "Share of merge parents that were blue over the last minute",
+        &[]
+    )
+    .expect("Failed to register DAG blue ratio metric")
+});
+
 // Execution Metrics
 pub static EXECUTION_TIME: Lazy<prometheus::HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
@@ -135,21 +180,45 @@ pub static NETWORK_BYTES_SENT: Lazy<prometheus::CounterVec> = Lazy::new(|| {
     .expect("Failed to register network bytes sent metric")
 });
 
+/// Sources refreshed into gauges on each scrape
+#[derive(Clone, Default)]
+struct ScrapeSources {
+    dag_stats: Option<Arc<DagStatsStore>>,
+    mempool: Option<Arc<Mempool>>,
+}
+
 /// Metrics server configuration
 pub struct MetricsServer {
     addr: SocketAddr,
+    sources: ScrapeSources,
 }
 
 impl MetricsServer {
     pub fn new(addr: SocketAddr) -> Self {
-        Self { addr }
+        Self {
+            addr,
+            sources: ScrapeSources::default(),
+        }
+    }
+
+    /// Refresh DAG health gauges from stored per-block statistics on each scrape
+    pub fn with_dag_stats(mut self, dag_stats: Arc<DagStatsStore>) -> Self {
+        self.sources.dag_stats = Some(dag_stats);
+        self
+    }
+
+    /// Refresh mempool size gauges on each scrape
+    pub fn with_mempool(mut self, mempool: Arc<Mempool>) -> Self {
+        self.sources.mempool = Some(mempool);
+        self
     }
 
     /// Start the metrics server
     pub async fn start(self) -> anyhow::Result<()> {
         let app = Router::new()
             .route("/metrics", get(metrics_handler))
-            .route("/health", get(health_handler));
+            .route("/health", get(health_handler))
+            .with_state(self.sources);
 
         info!("Starting metrics server on {}", self.addr);
 
@@ -161,7 +230,14 @@ impl MetricsServer {
 }
 
 /// Handler for /metrics endpoint
-async fn metrics_handler() -> Response<Body> {
+async fn metrics_handler(State(sources): State<ScrapeSources>) -> Response<Body> {
+    if let Some(dag_stats) = sources.dag_stats {
+        update_dag_stats_metrics(&dag_stats);
+    }
+    if let Some(mempool) = sources.mempool {
+        update_mempool_stats_metrics(&mempool.stats().await);
+    }
+
     let encoder = TextEncoder::new();
     let metric_families = prometheus::gather();
 
@@ -198,6 +274,25 @@ pub fn update_mempool_metrics(standard: usize, model: usize, inference: usize) {
         .set(inference as f64);
 }
 
+/// Update mempool gauges from a stats snapshot, one series per class
+pub fn update_mempool_stats_metrics(stats: &MempoolStats) {
+    for (class, label) in [
+        (TxClass::Standard, "standard"),
+        (TxClass::ModelUpdate, "model_update"),
+        (TxClass::Inference, "inference"),
+        (TxClass::Training, "training"),
+        (TxClass::Storage, "storage"),
+        (TxClass::System, "system"),
+        (TxClass::Compute, "compute"),
+    ] {
+        let count = stats.by_class.get(&class).copied().unwrap_or(0);
+        MEMPOOL_SIZE.with_label_values(&[label]).set(count as f64);
+    }
+    MEMPOOL_BYTES
+        .with_label_values(&["all"])
+        .set(stats.total_size as f64);
+}
+
 /// Update cache metrics
 pub fn update_cache_metrics(cache_type: &str, hit_rate: f64, size: usize) {
     CACHE_HIT_RATE
@@ -213,6 +308,47 @@ pub fn update_dag_metrics(height: u64, tips: usize, blue_score: u64) {
     DAG_BLUE_SCORE.with_label_values(&[]).set(blue_score as f64);
 }
 
+/// Summarize the trailing window of per-block DAG statistics into gauges
+pub fn update_dag_stats_metrics(dag_stats: &DagStatsStore) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let from = now.saturating_sub(DAG_STATS_WINDOW_SECS);
+    let Ok(stats) = dag_stats.get_range(from, now + 1, usize::MAX) else {
+        return;
+    };
+    if let Some(latest) = stats.last() {
+        DAG_TIPS_COUNT
+            .with_label_values(&[])
+            .set(latest.tips_count as f64);
+        DAG_BLUE_SCORE
+            .with_label_values(&[])
+            .set(latest.blue_score as f64);
+        DAG_HEIGHT.with_label_values(&[]).set(latest.height as f64);
+    }
+
+    let Some(window) = aggregate(&stats, from, now + 1, DAG_STATS_WINDOW_SECS + 1).pop() else {
+        return;
+    };
+    DAG_BLOCKS_PER_SECOND
+        .with_label_values(&[])
+        .set(window.blocks_per_sec);
+    DAG_MERGE_PARENTS
+        .with_label_values(&["avg"])
+        .set(window.avg_merge_parents);
+    DAG_MERGE_PARENTS
+        .with_label_values(&["max"])
+        .set(window.max_merge_parents as f64);
+    DAG_ANTICONE_SIZE
+        .with_label_values(&["avg"])
+        .set(window.avg_anticone_size);
+    DAG_ANTICONE_SIZE
+        .with_label_values(&["max"])
+        .set(window.max_anticone_size as f64);
+    DAG_BLUE_RATIO.with_label_values(&[]).set(window.blue_ratio);
+}
+
 /// Record RPC request
 pub fn record_rpc_request(method: &str, duration: f64, success: bool) {
     RPC_REQUEST_DURATION
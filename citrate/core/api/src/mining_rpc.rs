@@ -0,0 +1,241 @@
+// citrate/core/api/src/mining_rpc.rs
+
+// Block template RPC methods for producers running outside the node
+// `citrate_getBlockTemplate` hands out the parents, scores and ordered
+// transactions for the next block together with the VRF challenge and the
+// header hash to sign. The producer proves over the challenge, signs the
+// header hash and hands both back with `citrate_submitBlock`. The node
+// revalidates the template against its current DAG and state before the
+// block is executed and inserted, so a template can go stale while it is
+// being worked on.
+
+use crate::metrics::rpc_request;
+use async_trait::async_trait;
+use citrate_consensus::types::{Hash, PublicKey, Signature, Transaction, VrfProof};
+use jsonrpc_core::{ErrorCode, IoHandler, Params, Value};
+use serde_json::json;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::runtime::Handle;
+
+/// Work for the next block, handed out by `citrate_getBlockTemplate`
+#[derive(Debug, Clone)]
+pub struct BlockTemplate {
+    /// Identifies the template on submission
+    pub id: Hash,
+    pub selected_parent: Hash,
+    pub merge_parents: Vec<Hash>,
+    pub height: u64,
+    pub blue_score: u64,
+    pub blue_work: u128,
+    pub pruning_point: Hash,
+    pub timestamp: u64,
+    pub base_fee_per_gas: u64,
+    pub gas_limit: u64,
+    /// Input the proposer's VRF proof is computed over
+    pub vrf_challenge: Hash,
+    /// Commitment of the latest signed epoch summary, zero if none
+    pub epoch_commitment: Hash,
+    /// Hash the proposer signs
+    pub header_hash: Hash,
+    /// Transactions in execution order
+    pub transactions: Vec<Transaction>,
+    /// Seconds after which the template is no longer accepted
+    pub expires_at: u64,
+}
+
+/// A solved template, sent back with `citrate_submitBlock`
+#[derive(Debug, Clone)]
+pub struct BlockSubmission {
+    pub template_id: Hash,
+    pub proposer: PublicKey,
+    pub vrf_proof: VrfProof,
+    /// Proposer's signature over the template's header hash
+    pub signature: Signature,
+}
+
+/// Why a submitted block was not inserted
+#[derive(Debug, Error)]
+pub enum BlockSubmitError {
+    #[error("Unknown or already used block template")]
+    UnknownTemplate,
+
+    #[error("Block template is stale: {0}")]
+    Stale(String),
+
+    #[error("Invalid block: {0}")]
+    Invalid(String),
+
+    #[error("Failed to insert block: {0}")]
+    Internal(String),
+}
+
+impl From<BlockSubmitError> for jsonrpc_core::Error {
+    fn from(err: BlockSubmitError) -> Self {
+        let (code, reason) = match err {
+            BlockSubmitError::UnknownTemplate => (ErrorCode::InvalidParams, "unknown"),
+            BlockSubmitError::Stale(_) => (ErrorCode::InvalidParams, "stale"),
+            BlockSubmitError::Invalid(_) => (ErrorCode::InvalidParams, "invalid"),
+            BlockSubmitError::Internal(_) => (ErrorCode::InternalError, "internal"),
+        };
+        jsonrpc_core::Error {
+            code,
+            message: err.to_string(),
+            data: Some(json!({ "reason": reason })),
+        }
+    }
+}
+
+/// Builds block templates and inserts solved ones; implemented by the
+/// node's block producer
+#[async_trait]
+pub trait BlockTemplateProvider: Send + Sync {
+    /// Template for a block on top of the current tips
+    async fn block_template(&self) -> anyhow::Result<BlockTemplate>;
+
+    /// Revalidate and insert a solved template, returning the block hash
+    async fn submit_block(&self, submission: BlockSubmission) -> Result<Hash, BlockSubmitError>;
+}
+
+fn hex_hash(hash: &Hash) -> String {
+    format!("0x{}", hex::encode(hash.as_bytes()))
+}
+
+fn transaction_json(tx: &Transaction) -> Value {
+    json!({
+        "hash": hex_hash(&tx.hash),
+        "from": format!("0x{}", hex::encode(tx.from.as_bytes())),
+        "to": tx.to.map(|to| format!("0x{}", hex::encode(to.as_bytes()))),
+        "nonce": format!("0x{:x}", tx.nonce),
+        "value": format!("0x{:x}", tx.value),
+        "gasLimit": format!("0x{:x}", tx.gas_limit),
+        "gasPrice": format!("0x{:x}", tx.gas_price),
+        "data": format!("0x{}", hex::encode(&tx.data)),
+    })
+}
+
+pub fn template_json(template: &BlockTemplate) -> Value {
+    json!({
+        "id": hex_hash(&template.id),
+        "selectedParent": hex_hash(&template.selected_parent),
+        "mergeParents": template.merge_parents.iter().map(hex_hash).collect::<Vec<_>>(),
+        "height": template.height,
+        "blueScore": template.blue_score,
+        "blueWork": format!("0x{:x}", template.blue_work),
+        "pruningPoint": hex_hash(&template.pruning_point),
+        "timestamp": template.timestamp,
+        "baseFeePerGas": format!("0x{:x}", template.base_fee_per_gas),
+        "gasLimit": format!("0x{:x}", template.gas_limit),
+        "vrfChallenge": hex_hash(&template.vrf_challenge),
+        "epochCommitment": hex_hash(&template.epoch_commitment),
+        "headerHash": hex_hash(&template.header_hash),
+        "transactions": template.transactions.iter().map(transaction_json).collect::<Vec<_>>(),
+        "expiresAt": template.expires_at,
+    })
+}
+
+fn parse_bytes<const N: usize>(
+    value: Option<&Value>,
+    field: &str,
+) -> Result<[u8; N], jsonrpc_core::Error> {
+    let s = value
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| jsonrpc_core::Error::invalid_params(format!("Missing {}", field)))?;
+    let bytes = hex::decode(s.trim_start_matches("0x"))
+        .map_err(|_| jsonrpc_core::Error::invalid_params(format!("Invalid hex in {}", field)))?;
+    bytes
+        .try_into()
+        .map_err(|_| jsonrpc_core::Error::invalid_params(format!("{} must be {} bytes", field, N)))
+}
+
+/// Parse `{templateId, proposer, vrfProof: {proof, output}, signature}`
+pub fn parse_submission(value: &Value) -> Result<BlockSubmission, jsonrpc_core::Error> {
+    let vrf = value
+        .get("vrfProof")
+        .ok_or_else(|| jsonrpc_core::Error::invalid_params("Missing vrfProof"))?;
+    Ok(BlockSubmission {
+        template_id: Hash::new(parse_bytes(value.get("templateId"), "templateId")?),
+        proposer: PublicKey::new(parse_bytes(value.get("proposer"), "proposer")?),
+        vrf_proof: VrfProof {
+            proof: parse_bytes::<32>(vrf.get("proof"), "vrfProof.proof")?.to_vec(),
+            output: Hash::new(parse_bytes(vrf.get("output"), "vrfProof.output")?),
+        },
+        signature: Signature::new(parse_bytes(value.get("signature"), "signature")?),
+    })
+}
+
+/// Add block template RPC methods to the IoHandler. Provider calls run on
+/// `runtime`, since RPC handlers are served from plain threads.
+pub fn register_mining_methods(
+    io_handler: &mut IoHandler,
+    provider: Arc<dyn BlockTemplateProvider>,
+    runtime: Handle,
+) {
+    // citrate_getBlockTemplate - Parents, scores and transactions for the next block
+    let provider_template = provider.clone();
+    let runtime_template = runtime.clone();
+    io_handler.add_sync_method("citrate_getBlockTemplate", move |_params: Params| {
+        rpc_request("citrate_getBlockTemplate");
+        let template = runtime_template
+            .block_on(provider_template.block_template())
+            .map_err(|e| jsonrpc_core::Error {
+                code: ErrorCode::InternalError,
+                message: format!("Failed to build block template: {}", e),
+                data: None,
+            })?;
+        Ok(template_json(&template))
+    });
+
+    // citrate_submitBlock - [{templateId, proposer, vrfProof, signature}]
+    io_handler.add_sync_method("citrate_submitBlock", move |params: Params| {
+        rpc_request("citrate_submitBlock");
+        let params: Vec<Value> = params
+            .parse()
+            .map_err(|e| jsonrpc_core::Error::invalid_params(e.to_string()))?;
+        let submission = parse_submission(
+            params
+                .first()
+                .ok_or_else(|| jsonrpc_core::Error::invalid_params("Missing block submission"))?,
+        )?;
+        let hash = runtime.block_on(provider.submit_block(submission))?;
+        Ok(Value::String(hex_hash(&hash)))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_submission() {
+        let submission = parse_submission(&json!({
+            "templateId": format!("0x{}", "11".repeat(32)),
+            "proposer": format!("0x{}", "22".repeat(32)),
+            "vrfProof": {
+                "proof": format!("0x{}", "33".repeat(32)),
+                "output": format!("0x{}", "44".repeat(32)),
+            },
+            "signature": format!("0x{}", "55".repeat(64)),
+        }))
+        .unwrap();
+        assert_eq!(submission.template_id, Hash::new([0x11; 32]));
+        assert_eq!(submission.proposer, PublicKey::new([0x22; 32]));
+        assert_eq!(submission.vrf_proof.proof, vec![0x33; 32]);
+        assert_eq!(submission.signature, Signature::new([0x55; 64]));
+
+        let short = parse_submission(&json!({
+            "templateId": "0x11",
+            "proposer": format!("0x{}", "22".repeat(32)),
+            "vrfProof": { "proof": "0x", "output": "0x" },
+            "signature": "0x",
+        }));
+        assert!(short.is_err());
+    }
+
+    #[test]
+    fn test_submit_errors_carry_a_reason() {
+        let err: jsonrpc_core::Error = BlockSubmitError::Stale("parent pruned".into()).into();
+        assert_eq!(err.code, ErrorCode::InvalidParams);
+        assert_eq!(err.data, Some(json!({ "reason": "stale" })));
+    }
+}
@@ -1,27 +1,34 @@
 // citrate/core/api/src/openai_api.rs
 
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::Json,
+    extract::{ConnectInfo, Path, Query, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Json, Response},
     routing::{get, post},
     Router,
 };
 use serde::Serialize;
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tower::ServiceBuilder;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 use tracing::{error, info};
 
+use crate::access::{self, AccessControl, AccessDenied};
+use crate::billing::BillingLedger;
+use crate::explorer_api;
 use crate::methods::ai::{
     AiApi, ChatCompletionRequest, ChatCompletionResponse, CreateLoRARequest,
     CreateTrainingJobRequest, DeployModelRequest, EmbeddingsRequest, EmbeddingsResponse,
-    InferenceRequest,
+    InferenceRequest, TokenUsage,
 };
+use crate::types::error::ApiError;
 use citrate_execution::executor::Executor;
 use citrate_execution::types::Address;
+use citrate_mcp::safety::SafetyPipeline;
 use citrate_sequencer::mempool::Mempool;
 use citrate_storage::StorageManager;
 
@@ -30,12 +37,84 @@ pub struct OpenAiRestServer {
     storage: Arc<StorageManager>,
     mempool: Arc<Mempool>,
     executor: Arc<Executor>,
+    safety: Option<Arc<SafetyPipeline>>,
+    billing: Option<Arc<BillingLedger>>,
+    access: Option<Arc<AccessControl>>,
 }
 
 /// Server state for Axum handlers
 #[derive(Clone)]
 pub struct AppState {
     ai_api: AiApi,
+    billing: Option<Arc<BillingLedger>>,
+}
+
+impl AppState {
+    /// Bill a completed request to the API key it was made with
+    fn bill(&self, headers: &HeaderMap, endpoint: &str, model: &str, usage: &TokenUsage) {
+        let Some(billing) = &self.billing else {
+            return;
+        };
+        if let Err(e) = billing.record(
+            api_key(headers),
+            model,
+            endpoint,
+            usage.prompt_tokens as u64,
+            usage.completion_tokens as u64,
+        ) {
+            error!("Failed to record billing usage for {}: {}", endpoint, e);
+        }
+    }
+}
+
+/// API key from `Authorization: Bearer` (OpenAI) or `x-api-key` (Anthropic)
+fn api_key(headers: &HeaderMap) -> Option<&str> {
+    access::api_key_from(|name| headers.get(name).and_then(|v| v.to_str().ok()))
+}
+
+/// Turn away requests without a valid API key or over their rate limit.
+/// The health check is always served.
+async fn enforce_access(
+    State(access): State<Arc<AccessControl>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if request.uri().path() == "/health" {
+        return next.run(request).await;
+    }
+    let headers = request.headers();
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let ip = access.client_ip(peer, header);
+    match access.authorize(api_key(headers), ip) {
+        Ok(()) => next.run(request).await,
+        Err(denied) => access_denied(denied),
+    }
+}
+
+fn access_denied(denied: AccessDenied) -> Response {
+    let (r#type, code) = match denied {
+        AccessDenied::RateLimited { .. } => ("rate_limit_error", "rate_limit_exceeded"),
+        _ => ("authentication_error", "invalid_api_key"),
+    };
+    let status = StatusCode::from_u16(denied.status()).unwrap_or(StatusCode::FORBIDDEN);
+    let body = ErrorResponse {
+        error: ErrorDetail {
+            message: denied.message(),
+            r#type: r#type.to_string(),
+            code: Some(code.to_string()),
+        },
+    };
+    let mut response = (status, Json(body)).into_response();
+    if let Some(secs) = denied.retry_after_secs() {
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+    }
+    response
 }
 
 /// Error response format
@@ -78,19 +157,49 @@ impl OpenAiRestServer {
             storage,
             mempool,
             executor,
+            safety: None,
+            billing: None,
+            access: None,
         }
     }
 
-    /// Create the Axum router with all API endpoints
+    /// Classify chat prompts and outputs with `safety` before serving them
+    pub fn with_safety(mut self, safety: Arc<SafetyPipeline>) -> Self {
+        self.safety = Some(safety);
+        self
+    }
+
+    /// Record token usage and cost of completed requests in `billing`,
+    /// keyed by the request's API key
+    pub fn with_billing(mut self, billing: Arc<BillingLedger>) -> Self {
+        self.billing = Some(billing);
+        self
+    }
+
+    /// Require API keys and enforce rate limits with `access`, usually the
+    /// RPC server's so a key has one budget across both
+    pub fn with_access(mut self, access: Arc<AccessControl>) -> Self {
+        self.access = Some(access);
+        self
+    }
+
+    /// Create the Axum router with all API endpoints, including the block
+    /// explorer endpoints
     pub fn router(&self) -> Router {
-        let ai_api = AiApi::new(
+        let mut ai_api = AiApi::new(
             self.storage.clone(),
             self.mempool.clone(),
             self.executor.clone(),
         );
-        let state = AppState { ai_api };
+        if let Some(safety) = &self.safety {
+            ai_api = ai_api.with_safety(safety.clone());
+        }
+        let state = AppState {
+            ai_api,
+            billing: self.billing.clone(),
+        };
 
-        Router::new()
+        let mut router = Router::new()
             // OpenAI-compatible endpoints
             .route("/v1/models", get(list_models))
             .route("/v1/chat/completions", post(chat_completions))
@@ -121,17 +230,25 @@ impl OpenAiRestServer {
             // Health check
             .route("/health", get(health_check))
             .route("/", get(root))
-            .layer(
-                ServiceBuilder::new()
-                    .layer(TraceLayer::new_for_http())
-                    .layer(
-                        CorsLayer::new()
-                            .allow_origin(Any)
-                            .allow_methods(Any)
-                            .allow_headers(Any),
-                    ),
-            )
             .with_state(state)
+            // Block explorer endpoints
+            .merge(explorer_api::router(self.storage.clone()));
+        if let Some(access) = &self.access {
+            router = router.layer(middleware::from_fn_with_state(
+                access.clone(),
+                enforce_access,
+            ));
+        }
+        router.layer(
+            ServiceBuilder::new()
+                .layer(TraceLayer::new_for_http())
+                .layer(
+                    CorsLayer::new()
+                        .allow_origin(Any)
+                        .allow_methods(Any)
+                        .allow_headers(Any),
+                ),
+        )
     }
 
     /// Start the REST API server
@@ -141,7 +258,12 @@ impl OpenAiRestServer {
         info!("Starting OpenAI-compatible REST API server on {}", addr);
 
         let listener = tokio::net::TcpListener::bind(addr).await?;
-        axum::serve(listener, app).await?;
+        // Peer addresses feed the per-IP rate limits
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await?;
 
         Ok(())
     }
@@ -178,10 +300,22 @@ async fn list_models(State(state): State<AppState>) -> Result<Json<ModelListResp
 /// POST /v1/chat/completions - OpenAI chat completions
 async fn chat_completions(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(request): Json<ChatCompletionRequest>,
 ) -> Result<Json<ChatCompletionResponse>, StatusCode> {
     match state.ai_api.chat_completions(request, None).await {
-        Ok(response) => Ok(Json(response)),
+        Ok(response) => {
+            state.bill(&headers, "/v1/chat/completions", &response.model, &response.usage);
+            Ok(Json(response))
+        }
+        Err(ApiError::ContentBlocked(e)) => {
+            info!("Chat completion blocked by safety policy: {}", e);
+            Err(StatusCode::FORBIDDEN)
+        }
+        Err(ApiError::InvalidParams(e)) => {
+            error!("Chat completion rejected: {}", e);
+            Err(StatusCode::BAD_REQUEST)
+        }
         Err(e) => {
             error!("Chat completion failed: {}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -192,6 +326,7 @@ async fn chat_completions(
 /// POST /v1/completions - OpenAI text completions (legacy)
 async fn completions(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(payload): Json<serde_json::Value>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     // Convert text completion to chat completion
@@ -218,6 +353,22 @@ async fn completions(
                 .get("top_p")
                 .and_then(|t| t.as_f64())
                 .map(|t| t as f32),
+            top_k: payload
+                .get("top_k")
+                .and_then(|k| k.as_u64())
+                .map(|k| k as u32),
+            min_p: payload
+                .get("min_p")
+                .and_then(|p| p.as_f64())
+                .map(|p| p as f32),
+            repeat_penalty: payload
+                .get("repeat_penalty")
+                .or_else(|| payload.get("repetition_penalty"))
+                .and_then(|p| p.as_f64())
+                .map(|p| p as f32),
+            logit_bias: payload
+                .get("logit_bias")
+                .and_then(|b| serde_json::from_value(b.clone()).ok()),
             n: payload.get("n").and_then(|n| n.as_u64()).map(|n| n as u32),
             stop: payload.get("stop").and_then(|s| {
                 if s.is_array() {
@@ -235,6 +386,12 @@ async fn completions(
 
         match state.ai_api.chat_completions(chat_request, None).await {
             Ok(chat_response) => {
+                state.bill(
+                    &headers,
+                    "/v1/completions",
+                    &chat_response.model,
+                    &chat_response.usage,
+                );
                 // Convert back to completions format
                 let completions_response = serde_json::json!({
                     "id": chat_response.id,
@@ -252,6 +409,14 @@ async fn completions(
                 });
                 Ok(Json(completions_response))
             }
+            Err(ApiError::ContentBlocked(e)) => {
+                info!("Completion blocked by safety policy: {}", e);
+                Err(StatusCode::FORBIDDEN)
+            }
+            Err(ApiError::InvalidParams(e)) => {
+                error!("Completion rejected: {}", e);
+                Err(StatusCode::BAD_REQUEST)
+            }
             Err(e) => {
                 error!("Completion failed: {}", e);
                 Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -265,10 +430,14 @@ async fn completions(
 /// POST /v1/embeddings - OpenAI embeddings
 async fn embeddings(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(request): Json<EmbeddingsRequest>,
 ) -> Result<Json<EmbeddingsResponse>, StatusCode> {
     match state.ai_api.embeddings(request, None).await {
-        Ok(response) => Ok(Json(response)),
+        Ok(response) => {
+            state.bill(&headers, "/v1/embeddings", &response.model, &response.usage);
+            Ok(Json(response))
+        }
         Err(e) => {
             error!("Embeddings failed: {}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -281,6 +450,7 @@ async fn embeddings(
 /// POST /v1/messages - Anthropic messages API
 async fn messages(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(payload): Json<serde_json::Value>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     // Convert Anthropic messages format to OpenAI chat format
@@ -316,13 +486,32 @@ async fn messages(
                 .get("top_p")
                 .and_then(|t| t.as_f64())
                 .map(|t| t as f32),
+            top_k: payload
+                .get("top_k")
+                .and_then(|k| k.as_u64())
+                .map(|k| k as u32),
+            min_p: None,
+            repeat_penalty: None,
+            logit_bias: None,
             n: Some(1),
-            stop: None,
+            stop: payload.get("stop_sequences").and_then(|s| {
+                s.as_array().map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                        .collect()
+                })
+            }),
             stream: payload.get("stream").and_then(|s| s.as_bool()),
         };
 
         match state.ai_api.chat_completions(chat_request, None).await {
             Ok(chat_response) => {
+                state.bill(
+                    &headers,
+                    "/v1/messages",
+                    &chat_response.model,
+                    &chat_response.usage,
+                );
                 // Convert to Anthropic format
                 let anthropic_response = serde_json::json!({
                     "id": chat_response.id,
@@ -339,6 +528,14 @@ async fn messages(
                 });
                 Ok(Json(anthropic_response))
             }
+            Err(ApiError::ContentBlocked(e)) => {
+                info!("Anthropic message blocked by safety policy: {}", e);
+                Err(StatusCode::FORBIDDEN)
+            }
+            Err(ApiError::InvalidParams(e)) => {
+                error!("Anthropic message rejected: {}", e);
+                Err(StatusCode::BAD_REQUEST)
+            }
             Err(e) => {
                 error!("Anthropic message failed: {}", e);
                 Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -644,6 +841,12 @@ async fn root() -> Json<serde_json::Value> {
                 "inference": "/v1/citrate/inference",
                 "training": "/v1/citrate/training",
                 "lora": "/v1/citrate/lora"
+            },
+            "explorer": {
+                "blocks": "/explorer/blocks",
+                "transaction": "/explorer/tx/{hash}",
+                "address": "/explorer/address/{address}",
+                "dag_tips": "/explorer/dag/tips"
             }
         }
     }))
@@ -667,4 +870,29 @@ mod tests {
         assert!(json.contains("Test error"));
         assert!(json.contains("invalid_request_error"));
     }
+
+    #[test]
+    fn test_api_key_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(api_key(&headers), None);
+
+        headers.insert("x-api-key", "sk-anthropic".parse().unwrap());
+        assert_eq!(api_key(&headers), Some("sk-anthropic"));
+
+        headers.insert("authorization", "Bearer sk-openai".parse().unwrap());
+        assert_eq!(api_key(&headers), Some("sk-openai"));
+    }
+
+    #[test]
+    fn test_access_denied_response() {
+        let response = access_denied(AccessDenied::RateLimited {
+            retry_after: std::time::Duration::from_millis(2500),
+        });
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "3");
+
+        let response = access_denied(AccessDenied::MissingKey);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(response.headers().get(header::RETRY_AFTER).is_none());
+    }
 }
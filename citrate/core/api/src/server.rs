@@ -1,25 +1,37 @@
 // citrate/core/api/src/server.rs
 
+use crate::access::{self, AccessConfig, AccessControl, RateLimit, RpcAccessMiddleware};
+use crate::batch::BatchLimit;
+use crate::billing::{self, BillingLedger};
+#[cfg(feature = "devnet")]
+use crate::dev_rpc::{self, DevChainControl};
 use crate::filter::FilterRegistry;
-use crate::{ai_rpc, economics_rpc, eth_rpc};
-use crate::methods::{AiApi, ChainApi, MempoolApi, NetworkApi, StateApi, TransactionApi};
+use crate::mining_rpc::{self, BlockTemplateProvider};
+use crate::{ai_rpc, economics_rpc, eth_rpc, governance_rpc};
+use crate::methods::{
+    AiApi, ChainApi, DebugApi, MempoolApi, NetworkApi, StateApi, TransactionApi,
+};
 use crate::metrics::rpc_request;
 use crate::types::{
     error::ApiError,
-    request::{BlockId, CallRequest},
+    request::{BlockId, BlockTag, CallRequest, SimulationRequest, StateOverrideRequest},
+    response::{InternalTransactionResponse, NodeStatusResponse, TransactionTraceResponse},
     TransactionRequest,
 };
 use anyhow::Result;
 use base64::{engine::general_purpose::STANDARD, Engine as _};
 use futures::executor::block_on;
-use jsonrpc_core::{IoHandler, Params, Value};
+use jsonrpc_core::{IoHandler, MetaIoHandler, Params, Value};
 use jsonrpc_http_server::CloseHandle;
 use jsonrpc_http_server::{AccessControlAllowOrigin, DomainsValidation, ServerBuilder};
 use citrate_consensus::types::Hash;
 use citrate_execution::executor::Executor;
+use citrate_execution::tracer::TraceOptions;
 use citrate_execution::types::{AccessPolicy, Address};
 use citrate_network::peer::PeerManager;
-use citrate_sequencer::mempool::Mempool;
+use citrate_network::{GossipProtocol, CLIENT_VERSION};
+use citrate_sequencer::mempool::{Mempool, TxClass};
+use citrate_sequencer::signing_key::{BlockSigningKey, ROTATION_GAS_LIMIT};
 use citrate_storage::StorageManager;
 use once_cell::sync::Lazy;
 use serde_json::json;
@@ -27,6 +39,7 @@ use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::sync::RwLock as StdRwLock;
+use std::time::Instant;
 use tracing::info;
 
 /// Helper function to parse optional u64 field from JSON Value
@@ -48,6 +61,74 @@ fn parse_optional_u64_field(value: Option<&Value>, field_name: &str) -> Result<O
     }
 }
 
+/// Helper: parse a 0x-prefixed 32-byte hash
+fn parse_hash_value(value: Option<&Value>, field_name: &str) -> Result<Hash, jsonrpc_core::Error> {
+    let s = value
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| jsonrpc_core::Error::invalid_params(format!("Missing {}", field_name)))?;
+    match hex::decode(s.trim_start_matches("0x")) {
+        Ok(bytes) if bytes.len() == 32 => Ok(Hash::from_bytes(&bytes)),
+        _ => Err(jsonrpc_core::Error::invalid_params(format!("Invalid {}", field_name))),
+    }
+}
+
+/// Helper: parse a block hash, number or tag
+fn parse_block_id_value(value: Option<&Value>) -> Result<BlockId, jsonrpc_core::Error> {
+    match value {
+        Some(Value::String(s)) if s == "latest" => Ok(BlockId::Tag(BlockTag::Latest)),
+        Some(Value::String(s)) if s == "earliest" => Ok(BlockId::Tag(BlockTag::Earliest)),
+        Some(Value::String(s)) if s == "pending" => Ok(BlockId::Tag(BlockTag::Pending)),
+        Some(Value::String(s)) if s.trim_start_matches("0x").len() == 64 => {
+            parse_hash_value(value, "block hash").map(BlockId::Hash)
+        }
+        Some(v) => parse_optional_u64_field(Some(v), "block number")
+            .map(|n| BlockId::Number(n.unwrap_or_default())),
+        None => Ok(BlockId::Tag(BlockTag::Latest)),
+    }
+}
+
+/// Month and key id filter for the billing admin methods
+fn parse_billing_filter(
+    params: Params,
+) -> Result<(Option<String>, Option<String>), jsonrpc_core::Error> {
+    let (month, key) = match params {
+        Params::Array(arr) => (
+            arr.first().and_then(|v| v.as_str()).map(str::to_string),
+            arr.get(1).and_then(|v| v.as_str()).map(str::to_string),
+        ),
+        Params::Map(map) => (
+            map.get("month").and_then(|v| v.as_str()).map(str::to_string),
+            map.get("keyId")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+                .or_else(|| map.get("apiKey").and_then(|v| v.as_str()).map(billing::key_id)),
+        ),
+        Params::None => (None, None),
+    };
+    if let Some(month) = &month {
+        if chrono::NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").is_err() {
+            return Err(jsonrpc_core::Error::invalid_params(format!(
+                "invalid month {:?}, expected YYYY-MM",
+                month
+            )));
+        }
+    }
+    Ok((month, key))
+}
+
+/// Helper: parse trace options `{ opcodes, disableStack, maxSteps }`
+fn parse_trace_options(value: Option<&Value>) -> Result<TraceOptions, jsonrpc_core::Error> {
+    let mut options = TraceOptions::default();
+    if let Some(obj) = value.and_then(|v| v.as_object()) {
+        options.opcodes = obj.get("opcodes").and_then(|v| v.as_bool()).unwrap_or(false);
+        options.stack = !obj.get("disableStack").and_then(|v| v.as_bool()).unwrap_or(false);
+        if let Some(max) = parse_optional_u64_field(obj.get("maxSteps"), "maxSteps")? {
+            options.max_steps = max as usize;
+        }
+    }
+    Ok(options)
+}
+
 // In-memory verification store (address -> record)
 static VERIFICATIONS: Lazy<StdRwLock<HashMap<String, serde_json::Value>>> =
     Lazy::new(|| StdRwLock::new(HashMap::new()));
@@ -257,6 +338,13 @@ pub struct RpcConfig {
     pub max_connections: u32,
     pub cors_domains: Vec<String>,
     pub threads: usize,
+    /// Accounts reported by `eth_accounts` (pre-funded dev accounts)
+    pub accounts: Vec<Address>,
+    /// API keys, rate limits and the methods served publicly
+    pub access: AccessConfig,
+    /// Calls allowed in one batch request. A batch counts as a single
+    /// request against the access rate limits.
+    pub max_batch_size: usize,
 }
 
 impl Default for RpcConfig {
@@ -266,10 +354,20 @@ impl Default for RpcConfig {
             max_connections: 100,
             cors_domains: vec!["*".to_string()],
             threads: 4,
+            accounts: Vec::new(),
+            access: AccessConfig::default(),
+            max_batch_size: crate::batch::DEFAULT_MAX_BATCH_SIZE,
         }
     }
 }
 
+impl RpcConfig {
+    /// Issue an API key for this server, see `AccessConfig::issue_api_key`
+    pub fn issue_api_key(&mut self, label: &str, rate_limit: Option<RateLimit>) -> String {
+        self.access.issue_api_key(label, rate_limit)
+    }
+}
+
 /// RPC Server
 pub struct RpcServer {
     config: RpcConfig,
@@ -282,6 +380,7 @@ pub struct RpcServer {
     #[allow(dead_code)]
     executor: Arc<Executor>,
     io_handler: IoHandler,
+    access: Arc<AccessControl>,
 }
 
 impl RpcServer {
@@ -326,11 +425,20 @@ impl RpcServer {
             executor.clone(),
             chain_id,
             filter_registry,
+            config.accounts.clone(),
         );
 
         // Register economics-related RPC methods
         economics_rpc::register_economics_methods(&mut io_handler, economics_manager, Some(mempool.clone()));
 
+        // Register governance proposal RPC methods
+        governance_rpc::register_governance_methods(
+            &mut io_handler,
+            storage.clone(),
+            mempool.clone(),
+            executor.clone(),
+        );
+
         // Register AI-related RPC methods
         ai_rpc::register_ai_methods(
             &mut io_handler,
@@ -523,15 +631,95 @@ impl RpcServer {
             }
         });
 
+        // citrate_getConflictStats: conflicting transactions between parallel
+        // blocks over the last `depth` heights (default 100, max 1000)
+        let storage_conflicts = storage.clone();
+        io_handler.add_sync_method("citrate_getConflictStats", move |params: Params| {
+            rpc_request("citrate_getConflictStats");
+            let api = ChainApi::new(storage_conflicts.clone());
+
+            let depth = match params {
+                Params::Array(values) => match values.first() {
+                    Some(value) => parse_optional_u64_field(Some(value), "depth")?,
+                    None => None,
+                },
+                Params::Map(obj) => parse_optional_u64_field(obj.get("depth"), "depth")?,
+                Params::None => None,
+            }
+            .unwrap_or(100)
+            .min(1000);
+
+            match block_on(api.get_conflict_stats(depth)) {
+                Ok(stats) => Ok(serde_json::to_value(stats).unwrap_or(Value::Null)),
+                Err(e) => Err(e.into()),
+            }
+        });
+
+        // citrate_getDagStatsSeries: bucketed DAG health metrics over a time
+        // range; params {from, to, bucketSecs} in unix seconds, all optional
+        let storage_dag_series = storage.clone();
+        io_handler.add_sync_method("citrate_getDagStatsSeries", move |params: Params| {
+            rpc_request("citrate_getDagStatsSeries");
+            let api = ChainApi::new(storage_dag_series.clone());
+
+            let (from, to, bucket) = match params {
+                Params::Array(values) => (
+                    parse_optional_u64_field(values.first(), "from")?,
+                    parse_optional_u64_field(values.get(1), "to")?,
+                    parse_optional_u64_field(values.get(2), "bucketSecs")?,
+                ),
+                Params::Map(obj) => (
+                    parse_optional_u64_field(obj.get("from"), "from")?,
+                    parse_optional_u64_field(obj.get("to"), "to")?,
+                    parse_optional_u64_field(obj.get("bucketSecs"), "bucketSecs")?,
+                ),
+                Params::None => (None, None, None),
+            };
+
+            match block_on(api.get_dag_stats_series(from, to, bucket)) {
+                Ok(series) => Ok(serde_json::to_value(series).unwrap_or(Value::Null)),
+                Err(e) => Err(e.into()),
+            }
+        });
+
+        // citrate_getStatsHistory: hourly chain statistics snapshots over a
+        // time range; params {from, to} in unix seconds, both optional
+        let storage_stats_history = storage.clone();
+        io_handler.add_sync_method("citrate_getStatsHistory", move |params: Params| {
+            rpc_request("citrate_getStatsHistory");
+            let api = ChainApi::new(storage_stats_history.clone());
+
+            let (from, to) = match params {
+                Params::Array(values) => (
+                    parse_optional_u64_field(values.first(), "from")?,
+                    parse_optional_u64_field(values.get(1), "to")?,
+                ),
+                Params::Map(obj) => (
+                    parse_optional_u64_field(obj.get("from"), "from")?,
+                    parse_optional_u64_field(obj.get("to"), "to")?,
+                ),
+                Params::None => (None, None),
+            };
+
+            match block_on(api.get_stats_history(from, to)) {
+                Ok(history) => Ok(serde_json::to_value(history).unwrap_or(Value::Null)),
+                Err(e) => Err(e.into()),
+            }
+        });
+
         // chain_getBlock
         let storage_b = storage.clone();
         io_handler.add_sync_method("chain_getBlock", move |params: Params| {
             rpc_request("chain_getBlock");
             let api = ChainApi::new(storage_b.clone());
 
-            let block_id: BlockId = match params.parse() {
+            // A bare block id, or one wrapped in a positional array
+            let block_id: BlockId = match params.clone().parse() {
                 Ok(id) => id,
-                Err(e) => return Err(jsonrpc_core::Error::invalid_params(e.to_string())),
+                Err(e) => match params.parse::<(BlockId,)>() {
+                    Ok((id,)) => id,
+                    Err(_) => return Err(jsonrpc_core::Error::invalid_params(e.to_string())),
+                },
             };
 
             match block_on(api.get_block(block_id)) {
@@ -665,6 +853,41 @@ impl RpcServer {
             }
         });
 
+        // citrate_simulateTransaction
+        let mempool_sim = mempool.clone();
+        let executor_sim = executor.clone();
+        io_handler.add_sync_method("citrate_simulateTransaction", move |params: Params| {
+            rpc_request("citrate_simulateTransaction");
+            let api = TransactionApi::new(mempool_sim.clone(), executor_sim.clone());
+
+            // [callObject, stateOverrides?]
+            let params: Vec<Value> = match params {
+                Params::Array(arr) => arr,
+                _ => {
+                    return Err(jsonrpc_core::Error::invalid_params(
+                        "Expected [callObject, stateOverrides?]",
+                    ))
+                }
+            };
+            let request: SimulationRequest = match params.first() {
+                Some(value) => serde_json::from_value(value.clone())
+                    .map_err(|e| jsonrpc_core::Error::invalid_params(e.to_string()))?,
+                None => {
+                    return Err(jsonrpc_core::Error::invalid_params("Missing call object"))
+                }
+            };
+            let overrides: HashMap<String, StateOverrideRequest> = match params.get(1) {
+                Some(Value::Null) | None => HashMap::new(),
+                Some(value) => serde_json::from_value(value.clone())
+                    .map_err(|e| jsonrpc_core::Error::invalid_params(e.to_string()))?,
+            };
+
+            match block_on(api.simulate_transaction(request, overrides)) {
+                Ok(result) => Ok(serde_json::to_value(result).unwrap_or(Value::Null)),
+                Err(e) => Err(e.into()),
+            }
+        });
+
         // tx_getGasPrice
         let mempool_price = mempool.clone();
         let executor_price = executor.clone();
@@ -678,6 +901,86 @@ impl RpcServer {
             }
         });
 
+        // ========== Debug Methods ==========
+
+        // debug_traceTransaction
+        let storage_trace_tx = storage.clone();
+        let executor_trace_tx = executor.clone();
+        io_handler.add_sync_method("debug_traceTransaction", move |params: Params| {
+            rpc_request("debug_traceTransaction");
+            let api = DebugApi::new(storage_trace_tx.clone(), executor_trace_tx.clone());
+
+            // [txHash, options?]
+            let params: Vec<Value> = match params {
+                Params::Array(arr) => arr,
+                _ => {
+                    return Err(jsonrpc_core::Error::invalid_params(
+                        "Expected [txHash, options?]",
+                    ))
+                }
+            };
+            let hash = parse_hash_value(params.first(), "transaction hash")?;
+            let options = parse_trace_options(params.get(1))?;
+
+            match block_on(api.trace_transaction(hash, options)) {
+                Ok(trace) => {
+                    let trace = TransactionTraceResponse::from(trace);
+                    Ok(serde_json::to_value(trace).unwrap_or(Value::Null))
+                }
+                Err(e) => Err(e.into()),
+            }
+        });
+
+        // debug_traceBlock
+        let storage_trace_block = storage.clone();
+        let executor_trace_block = executor.clone();
+        io_handler.add_sync_method("debug_traceBlock", move |params: Params| {
+            rpc_request("debug_traceBlock");
+            let api = DebugApi::new(storage_trace_block.clone(), executor_trace_block.clone());
+
+            // [blockHash | blockNumber | tag, options?]
+            let params: Vec<Value> = match params {
+                Params::Array(arr) => arr,
+                Params::None => Vec::new(),
+                _ => {
+                    return Err(jsonrpc_core::Error::invalid_params(
+                        "Expected [block, options?]",
+                    ))
+                }
+            };
+            let block_id = parse_block_id_value(params.first())?;
+            let options = parse_trace_options(params.get(1))?;
+
+            match block_on(api.trace_block(block_id, options)) {
+                Ok(traces) => {
+                    let traces: Vec<TransactionTraceResponse> =
+                        traces.into_iter().map(Into::into).collect();
+                    Ok(serde_json::to_value(traces).unwrap_or(Value::Null))
+                }
+                Err(e) => Err(e.into()),
+            }
+        });
+
+        // citrate_getInternalTransactions: value transfers made by contracts in a transaction
+        let storage_internal = storage.clone();
+        io_handler.add_sync_method("citrate_getInternalTransactions", move |params: Params| {
+            rpc_request("citrate_getInternalTransactions");
+            let params: Vec<Value> = match params {
+                Params::Array(arr) => arr,
+                _ => return Err(jsonrpc_core::Error::invalid_params("Expected [txHash]")),
+            };
+            let hash = parse_hash_value(params.first(), "transaction hash")?;
+
+            match storage_internal.transactions.get_internal_transactions(&hash) {
+                Ok(internal) => {
+                    let internal: Vec<InternalTransactionResponse> =
+                        internal.into_iter().map(Into::into).collect();
+                    Ok(serde_json::to_value(internal).unwrap_or(Value::Null))
+                }
+                Err(_e) => Err(jsonrpc_core::Error::internal_error()),
+            }
+        });
+
         // ========== Mempool Methods ==========
 
         // mempool_getStatus
@@ -791,6 +1094,148 @@ impl RpcServer {
             }
         });
 
+        // citrate_sendPrivateTransaction: accept a signed raw tx into the node-local
+        // private pool. It is only included in blocks produced by this node and is
+        // never broadcast, so it cannot be front-run from the public mempool.
+        let mempool_private = mempool.clone();
+        io_handler.add_sync_method("citrate_sendPrivateTransaction", move |params: Params| {
+            rpc_request("citrate_sendPrivateTransaction");
+            use crate::eth_tx_decoder;
+
+            let params: Vec<Value> = match params.parse() {
+                Ok(p) => p,
+                Err(e) => return Err(jsonrpc_core::Error::invalid_params(e.to_string())),
+            };
+            if params.is_empty() {
+                return Err(jsonrpc_core::Error::invalid_params(
+                    "Missing transaction data",
+                ));
+            }
+            let tx_hex = params[0]
+                .as_str()
+                .ok_or_else(|| jsonrpc_core::Error::invalid_params("Invalid tx hex"))?;
+            let tx_bytes = match hex::decode(tx_hex.trim().trim_start_matches("0x")) {
+                Ok(b) => b,
+                Err(_) => return Err(jsonrpc_core::Error::invalid_params("Invalid hex")),
+            };
+            let tx = match eth_tx_decoder::decode_eth_transaction(&tx_bytes) {
+                Ok(t) => t,
+                Err(e) => {
+                    return Err(jsonrpc_core::Error::invalid_params(format!(
+                        "Failed to parse transaction: {}",
+                        e
+                    )))
+                }
+            };
+            let hash = tx.hash;
+            match block_on(
+                mempool_private
+                    .add_private_transaction(tx, citrate_sequencer::mempool::TxClass::Standard),
+            ) {
+                Ok(_) => {
+                    info!("Accepted private transaction 0x{}", hex::encode(hash.as_bytes()));
+                    Ok(Value::String(format!("0x{}", hex::encode(hash.as_bytes()))))
+                }
+                Err(e) => Err(jsonrpc_core::Error::invalid_params(format!(
+                    "Failed to submit private transaction: {:?}",
+                    e
+                ))),
+            }
+        });
+
+        // citrate_replaceTransaction: submit a signed raw tx that replaces a pending
+        // one with the same sender and nonce (speed up, or cancel with a self-transfer).
+        // Params: [replacedHash, rawTx]. The replacement must pay at least the
+        // mempool's replacement factor over the pending gas price.
+        let mempool_replace = mempool.clone();
+        let peer_mgr_replace = peer_manager.clone();
+        io_handler.add_sync_method("citrate_replaceTransaction", move |params: Params| {
+            rpc_request("citrate_replaceTransaction");
+            use crate::eth_tx_decoder;
+            use citrate_network::NetworkMessage;
+
+            let params: Vec<Value> = match params.parse() {
+                Ok(p) => p,
+                Err(e) => return Err(jsonrpc_core::Error::invalid_params(e.to_string())),
+            };
+            let replaced_hash = parse_hash_value(params.first(), "replaced transaction hash")?;
+            let tx_hex = params
+                .get(1)
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| jsonrpc_core::Error::invalid_params("Missing transaction data"))?;
+            let tx_bytes = match hex::decode(tx_hex.trim().trim_start_matches("0x")) {
+                Ok(b) => b,
+                Err(_) => return Err(jsonrpc_core::Error::invalid_params("Invalid hex")),
+            };
+            let tx = match eth_tx_decoder::decode_eth_transaction(&tx_bytes) {
+                Ok(t) => t,
+                Err(e) => {
+                    return Err(jsonrpc_core::Error::invalid_params(format!(
+                        "Failed to parse transaction: {}",
+                        e
+                    )))
+                }
+            };
+
+            if !mempool_replace.allows_replacement() {
+                return Err(jsonrpc_core::Error::invalid_params(
+                    "Transaction replacement is disabled on this node",
+                ));
+            }
+            let pending = block_on(mempool_replace.pending_by_nonce(&tx.from, tx.nonce))
+                .filter(|pending| pending.tx.hash == replaced_hash)
+                .ok_or_else(|| {
+                    jsonrpc_core::Error::invalid_params(
+                        "No pending transaction with this hash from the same sender and nonce",
+                    )
+                })?;
+
+            let hash = tx.hash;
+            let result = if pending.private {
+                block_on(mempool_replace.add_private_transaction(tx.clone(), pending.class))
+            } else {
+                block_on(mempool_replace.add_transaction(tx.clone(), pending.class))
+            };
+            match result {
+                Ok(_) => {
+                    // A private transaction's replacement stays private
+                    if !pending.private {
+                        let _ = block_on(
+                            peer_mgr_replace
+                                .broadcast(&NetworkMessage::NewTransaction { transaction: tx }),
+                        );
+                    }
+                    Ok(json!({
+                        "hash": format!("0x{}", hex::encode(hash.as_bytes())),
+                        "replaced": format!("0x{}", hex::encode(replaced_hash.as_bytes())),
+                    }))
+                }
+                Err(e) => Err(jsonrpc_core::Error::invalid_params(format!(
+                    "Failed to replace transaction: {}",
+                    e
+                ))),
+            }
+        });
+
+        // citrate_getReplacementGasPrice: lowest gas price that replaces a pending tx
+        let mempool_replace_price = mempool.clone();
+        io_handler.add_sync_method("citrate_getReplacementGasPrice", move |params: Params| {
+            rpc_request("citrate_getReplacementGasPrice");
+            let params: Vec<Value> = match params.parse() {
+                Ok(p) => p,
+                Err(e) => return Err(jsonrpc_core::Error::invalid_params(e.to_string())),
+            };
+            let hash = parse_hash_value(params.first(), "transaction hash")?;
+            let pending = block_on(mempool_replace_price.get_transaction(&hash))
+                .ok_or_else(|| jsonrpc_core::Error::invalid_params("Transaction is not pending"))?;
+            Ok(json!({
+                "nonce": pending.nonce,
+                "gasPrice": pending.gas_price,
+                "minGasPrice": mempool_replace_price.min_replacement_price(pending.gas_price),
+                "replaceable": mempool_replace_price.allows_replacement(),
+            }))
+        });
+
         // Override eth_sendTransaction: enqueue via TransactionApi, then broadcast the tx if retrievable
         let mempool_send_broadcast = mempool.clone();
         let executor_send_broadcast = executor.clone();
@@ -900,13 +1345,8 @@ impl RpcServer {
             } else {
                 return Err(jsonrpc_core::Error::invalid_params("unreachable"));
             };
-            let addr_hex = address_str.trim().trim_start_matches("0x");
-            if addr_hex.len() != 40 { return Err(jsonrpc_core::Error::invalid_params("Invalid address length")); }
-            let onchain_addr = {
-                let mut a = [0u8; 20];
-                match hex::decode(addr_hex) { Ok(b) if b.len()==20 => a.copy_from_slice(&b), _ => return Err(jsonrpc_core::Error::invalid_params("Invalid address")), }
-                Address(a)
-            };
+            let onchain_addr = citrate_execution::address_utils::address_from_hex(address_str.trim())
+                .map_err(jsonrpc_core::Error::invalid_params)?;
 
             // Fetch on-chain runtime code via StateApi
             let api = StateApi::new(storage_v.clone(), executor_v.clone());
@@ -1514,6 +1954,7 @@ impl RpcServer {
                     base_fee_per_gas: 1_000_000_000, // 1 gwei
                     gas_used: 0,
                     gas_limit: 30_000_000,
+                    epoch_commitment: citrate_consensus::types::Hash::default(),
                 },
                 state_root: citrate_consensus::types::Hash::default(),
                 tx_root: citrate_consensus::types::Hash::default(),
@@ -1569,25 +2010,132 @@ impl RpcServer {
             }
         });
 
-        // net_peerInfo: detailed peer info (id, addr, direction)
+        // net_peerInfo: detailed peer info (id, addr, direction, state,
+        // score, last seen, client version)
         let peers_info_mgr = peer_manager.clone();
         io_handler.add_sync_method("net_peerInfo", move |_params: Params| {
             rpc_request("net_peerInfo");
-            let peers = peers_info_mgr.get_all_peers();
-            let mut arr = Vec::new();
-            for p in peers {
-                let info = block_on(p.info.read());
-                let dir = match info.direction {
-                    citrate_network::peer::Direction::Inbound => "inbound",
-                    citrate_network::peer::Direction::Outbound => "outbound",
-                };
-                arr.push(serde_json::json!({
-                    "id": info.id.to_string(),
-                    "addr": info.addr.to_string(),
-                    "direction": dir,
-                }));
+            let api = NetworkApi::new(peers_info_mgr.clone());
+            match block_on(api.get_peer_info()) {
+                Ok(peers) => Ok(serde_json::to_value(peers).unwrap_or(Value::Array(vec![]))),
+                Err(_) => Ok(Value::Array(vec![])),
             }
-            Ok(Value::Array(arr))
+        });
+
+        // admin_peerReputation: persisted scores, bans and recent misbehavior,
+        // optionally filtered to one host ("1.2.3.4" or "1.2.3.4:30303")
+        let peers_reputation = peer_manager.clone();
+        io_handler.add_sync_method("admin_peerReputation", move |params: Params| {
+            rpc_request("admin_peerReputation");
+            let filter = match params {
+                Params::Array(arr) => arr.first().and_then(|v| v.as_str()).map(str::to_string),
+                Params::Map(map) => map
+                    .get("address")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string),
+                Params::None => None,
+            };
+            let ip = match filter {
+                Some(addr) => match addr
+                    .parse::<std::net::IpAddr>()
+                    .or_else(|_| addr.parse::<std::net::SocketAddr>().map(|a| a.ip()))
+                {
+                    Ok(ip) => Some(ip),
+                    Err(_) => {
+                        return Err(jsonrpc_core::Error::invalid_params(format!(
+                            "invalid peer address: {}",
+                            addr
+                        )))
+                    }
+                },
+                None => None,
+            };
+            let api = NetworkApi::new(peers_reputation.clone());
+            match block_on(api.get_peer_reputation(ip)) {
+                Ok(records) => Ok(serde_json::to_value(records).unwrap_or(Value::Array(vec![]))),
+                Err(e) => Err(e.into()),
+            }
+        });
+
+        // admin_dbStats: per column family sizes, compaction progress,
+        // block cache usage and write-stall status
+        let storage_db_stats = storage.clone();
+        io_handler.add_sync_method("admin_dbStats", move |_params: Params| {
+            rpc_request("admin_dbStats");
+            let stats = storage_db_stats
+                .db
+                .db_stats()
+                .map_err(|e| ApiError::InternalError(e.to_string()))?;
+            Ok(serde_json::to_value(stats).unwrap_or(Value::Null))
+        });
+
+        // admin_compactDb: start a background compaction of one column
+        // family ("state") or, without a parameter, all of them
+        let storage_compact = storage.clone();
+        io_handler.add_sync_method("admin_compactDb", move |params: Params| {
+            rpc_request("admin_compactDb");
+            let cf = match params {
+                Params::Array(arr) => arr.first().and_then(|v| v.as_str()).map(str::to_string),
+                Params::Map(map) => map
+                    .get("columnFamily")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string),
+                Params::None => None,
+            };
+            let compacting = storage_compact
+                .db
+                .compact(cf.as_deref())
+                .map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+            Ok(json!({ "compacting": compacting }))
+        });
+
+        // admin_prunePreview: what the next prune would remove under the
+        // node's retention settings, with bytes per column family
+        let storage_prune = storage.clone();
+        io_handler.add_sync_method("admin_prunePreview", move |_params: Params| {
+            rpc_request("admin_prunePreview");
+            let preview = block_on(storage_prune.pruner.preview())
+                .map_err(|e| ApiError::InternalError(e.to_string()))?;
+            Ok(serde_json::to_value(preview).unwrap_or(Value::Null))
+        });
+
+        // admin_nodeStatus: head, DAG, peer and mempool summary for
+        // dashboards attached to this node
+        let storage_node_status = storage.clone();
+        let peers_node_status = peer_manager.clone();
+        let mempool_node_status = mempool.clone();
+        let started = Instant::now();
+        io_handler.add_sync_method("admin_nodeStatus", move |_params: Params| {
+            rpc_request("admin_nodeStatus");
+            let chain = ChainApi::new(storage_node_status.clone());
+            let height = block_on(chain.get_height())?;
+            let head = if height > 0 {
+                block_on(chain.get_block(BlockId::Number(height))).ok()
+            } else {
+                None
+            };
+            let dag_tips = block_on(chain.get_tips()).map(|t| t.len()).unwrap_or(0);
+            let network = NetworkApi::new(peers_node_status.clone());
+            let peer_count = block_on(network.get_peer_count())?;
+            let mempool_pending = block_on(mempool_node_status.stats()).total_transactions;
+            let upgrade_available =
+                block_on(peers_node_status.version_distribution()).upgrade_available;
+
+            let status = NodeStatusResponse {
+                version: CLIENT_VERSION.to_string(),
+                chain_id,
+                height,
+                head_hash: head.as_ref().map(|b| hex::encode(b.hash.as_bytes())),
+                head_timestamp: head.as_ref().map(|b| b.timestamp),
+                blue_score: head.as_ref().map_or(0, |b| b.blue_score),
+                dag_tips,
+                peer_count,
+                syncing: false,
+                uptime_secs: started.elapsed().as_secs(),
+                mempool_pending,
+                upgrade_available,
+            };
+            Ok(serde_json::to_value(status).unwrap_or(Value::Null))
         });
 
         // citrate_getModel
@@ -2015,6 +2563,7 @@ impl RpcServer {
             }
         });
 
+        let access = Arc::new(AccessControl::new(config.access.clone()));
         Self {
             config,
             storage,
@@ -2022,23 +2571,212 @@ impl RpcServer {
             peer_manager,
             executor,
             io_handler,
+            access,
         }
     }
 
+    /// Access control enforced by this server, to share with the REST API
+    pub fn access_control(&self) -> Arc<AccessControl> {
+        self.access.clone()
+    }
+
+    /// Expose the producer's block signing key to admin RPC:
+    ///
+    /// - `admin_signingKey` reports the active key and the key file
+    /// - `admin_rotateSigningKey` submits a key-change record signed by the
+    ///   active key, then swaps in the new key without a restart
+    ///
+    /// Only register this on a listener reachable by the node operator.
+    pub fn with_signing_key(
+        mut self,
+        signing_key: Arc<BlockSigningKey>,
+        chain_id: u64,
+        gas_price: u64,
+    ) -> Self {
+        let key_info = signing_key.clone();
+        self.io_handler
+            .add_sync_method("admin_signingKey", move |_params: Params| {
+                rpc_request("admin_signingKey");
+                let public_key = key_info.public_key();
+                Ok(json!({
+                    "publicKey": format!("0x{}", hex::encode(public_key.as_bytes())),
+                    "address": format!(
+                        "0x{}",
+                        hex::encode(Address::from_public_key(&public_key).0)
+                    ),
+                    "keyFile": key_info.path().map(|p| p.display().to_string()),
+                }))
+            });
+
+        let mempool = self.mempool.clone();
+        let executor = self.executor.clone();
+        self.io_handler
+            .add_sync_method("admin_rotateSigningKey", move |_params: Params| {
+                rpc_request("admin_rotateSigningKey");
+                let retiring = Address::from_public_key(&signing_key.public_key());
+                // The record is paid for by the retiring key
+                let fee = primitive_types::U256::from(ROTATION_GAS_LIMIT) * gas_price;
+                if executor.get_balance(&retiring) < fee {
+                    return Err(ApiError::InvalidTransaction(format!(
+                        "signing key account 0x{} needs {} wei for the key-change record",
+                        hex::encode(retiring.0),
+                        fee
+                    ))
+                    .into());
+                }
+                let nonce = executor.get_nonce(&retiring);
+                let rotation = signing_key.prepare_rotation(nonce, gas_price, chain_id);
+                let tx_hash = rotation.transaction.hash;
+                let old_key = rotation.retiring;
+
+                // Keep the current key unless the record can be included
+                block_on(mempool.add_transaction(rotation.transaction.clone(), TxClass::System))
+                    .map_err(|e| ApiError::InvalidTransaction(e.to_string()))?;
+                let new_key = signing_key
+                    .complete_rotation(rotation)
+                    .map_err(|e| ApiError::InternalError(e.to_string()))?;
+                info!(
+                    "Rotated block signing key 0x{} -> 0x{}",
+                    hex::encode(&old_key.as_bytes()[..8]),
+                    hex::encode(&new_key.as_bytes()[..8])
+                );
+
+                Ok(json!({
+                    "oldKey": format!("0x{}", hex::encode(old_key.as_bytes())),
+                    "newKey": format!("0x{}", hex::encode(new_key.as_bytes())),
+                    "transactionHash": format!("0x{}", hex::encode(tx_hash.as_bytes())),
+                }))
+            });
+        self
+    }
+
+    /// Expose the REST API's usage ledger to admin RPC:
+    ///
+    /// - `admin_billingUsage` returns monthly usage and cost per API key and
+    ///   model
+    /// - `admin_exportBillingCsv` returns the same rollups as CSV
+    ///
+    /// Both take `[month?, keyId?]` or `{month, keyId, apiKey}`, where
+    /// `month` is "YYYY-MM" and `apiKey` is a raw key to look up.
+    pub fn with_billing(mut self, billing: Arc<BillingLedger>) -> Self {
+        let ledger = billing.clone();
+        self.io_handler
+            .add_sync_method("admin_billingUsage", move |params: Params| {
+                rpc_request("admin_billingUsage");
+                let (month, key) = parse_billing_filter(params)?;
+                let usage = ledger.monthly_usage(month.as_deref(), key.as_deref());
+                Ok(json!({
+                    "currency": ledger.pricing().currency,
+                    "usage": usage,
+                }))
+            });
+
+        self.io_handler
+            .add_sync_method("admin_exportBillingCsv", move |params: Params| {
+                rpc_request("admin_exportBillingCsv");
+                let (month, key) = parse_billing_filter(params)?;
+                Ok(Value::String(
+                    billing.export_csv(month.as_deref(), key.as_deref()),
+                ))
+            });
+        self
+    }
+
+    /// Expose gossip and bandwidth counters to admin RPC:
+    ///
+    /// - `admin_networkStats` returns bytes and messages in and out per
+    ///   message type, gossip relay and deduplication counts, and peer
+    ///   counts
+    pub fn with_gossip(mut self, gossip: Arc<GossipProtocol>) -> Self {
+        let peer_manager = self.peer_manager.clone();
+        self.io_handler
+            .add_sync_method("admin_networkStats", move |_params: Params| {
+                rpc_request("admin_networkStats");
+                let (total, inbound, outbound) = block_on(peer_manager.get_peer_counts());
+                Ok(json!({
+                    "peers": {
+                        "total": total,
+                        "inbound": inbound,
+                        "outbound": outbound,
+                    },
+                    "bandwidth": peer_manager.bandwidth().stats(),
+                    "gossip": block_on(gossip.get_stats()),
+                }))
+            });
+        self
+    }
+
+    /// Serve block templates to producers outside the node:
+    ///
+    /// - `citrate_getBlockTemplate` returns the next block's parents, scores,
+    ///   transactions and VRF challenge
+    /// - `citrate_submitBlock` revalidates a solved template and inserts it
+    ///
+    /// Must be called from within the node's tokio runtime, which the
+    /// provider runs on.
+    pub fn with_block_templates(mut self, provider: Arc<dyn BlockTemplateProvider>) -> Self {
+        mining_rpc::register_mining_methods(
+            &mut self.io_handler,
+            provider,
+            tokio::runtime::Handle::current(),
+        );
+        self
+    }
+
+    /// Let clients control block production on a devnet:
+    ///
+    /// - `dev_setBlockInterval` sets the seconds between blocks, 0 to stop
+    ///   interval mining
+    /// - `dev_setAutomine` mines a block whenever a transaction arrives
+    /// - `dev_mineBlocks` mines a number of blocks right away
+    ///
+    /// Must be called from within the node's tokio runtime.
+    #[cfg(feature = "devnet")]
+    pub fn with_dev_control(mut self, control: Arc<dyn DevChainControl>) -> Self {
+        dev_rpc::register_dev_methods(
+            &mut self.io_handler,
+            control,
+            tokio::runtime::Handle::current(),
+        );
+        self
+    }
+
     /// Spawn the RPC server on a dedicated OS thread and return a CloseHandle and JoinHandle.
     /// If startup fails (e.g., port already in use), returns an error instead of panicking.
     pub fn spawn(self) -> Result<(CloseHandle, std::thread::JoinHandle<()>)> {
         let listen_addr = self.config.listen_addr;
         let threads = self.config.threads;
         let cors_any = !self.config.cors_domains.is_empty();
-        let io = self.io_handler;
+        let access = self.access;
+        let batch_limit = BatchLimit::new(self.config.max_batch_size);
+        let mut io = self.io_handler;
+
+        // Only serve allowlisted namespaces to other hosts
+        if access::is_public(&listen_addr) {
+            let (public, private): (Vec<_>, Vec<_>) = io
+                .into_iter()
+                .partition(|(name, _)| access.config().is_public_method(name));
+            if !private.is_empty() {
+                info!(
+                    "RPC server on public address {}: not serving {} private methods",
+                    listen_addr,
+                    private.len()
+                );
+            }
+            io = IoHandler::new();
+            io.extend_with(public);
+        }
+
+        let mut limited = MetaIoHandler::with_middleware(batch_limit);
+        limited.extend_with(io);
 
         // Channel to report startup result (CloseHandle or error string)
         let (result_tx, result_rx) =
             std::sync::mpsc::sync_channel::<Result<CloseHandle, String>>(1);
 
         let join_handle = std::thread::spawn(move || {
-            let mut builder = ServerBuilder::new(io);
+            let mut builder = ServerBuilder::new(limited)
+                .request_middleware(RpcAccessMiddleware::new(access, cors_any));
             if cors_any {
                 builder = builder.cors(DomainsValidation::AllowOnly(vec![
                     AccessControlAllowOrigin::Any,
@@ -35,6 +35,9 @@ pub enum ApiError {
 
     #[error("Execution failed: {0}")]
     ExecutionFailed(String),
+
+    #[error("Content blocked: {0}")]
+    ContentBlocked(String),
 }
 
 impl From<ApiError> for Error {
@@ -48,7 +51,9 @@ impl From<ApiError> for Error {
                 message: err.to_string(),
                 data: None,
             },
-            ApiError::InvalidParams(_) | ApiError::InvalidTransaction(_) => Error {
+            ApiError::InvalidParams(_)
+            | ApiError::InvalidTransaction(_)
+            | ApiError::ContentBlocked(_) => Error {
                 code: ErrorCode::InvalidParams,
                 message: err.to_string(),
                 data: None,
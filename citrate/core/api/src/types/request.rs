@@ -63,3 +63,31 @@ pub enum SubscriptionType {
     Logs(LogFilter),
     Syncing,
 }
+
+/// Transaction to simulate, with 0x-hex or decimal quantities and 0x-hex
+/// addresses and data as in `eth_call`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationRequest {
+    pub from: Option<String>,
+    /// Omitted for contract creation
+    pub to: Option<String>,
+    pub value: Option<String>,
+    pub gas: Option<String>,
+    pub gas_price: Option<String>,
+    pub nonce: Option<String>,
+    #[serde(alias = "input")]
+    pub data: Option<String>,
+}
+
+/// Account state assumed while simulating
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateOverrideRequest {
+    pub balance: Option<String>,
+    pub nonce: Option<String>,
+    pub code: Option<String>,
+    /// Storage slot to value; slots not listed keep their values
+    #[serde(default, alias = "stateDiff")]
+    pub storage: std::collections::HashMap<String, String>,
+}
@@ -1,6 +1,12 @@
 // citrate/core/api/src/types/response.rs
+use citrate_consensus::ordering::{ConflictStats, TransactionConflict};
 use citrate_consensus::types::{Block, Hash, Transaction};
-use citrate_execution::types::Address;
+use citrate_execution::simulation::{AccountDiff, Change, Simulation, SlotChange};
+use citrate_execution::tracer::{
+    CallFrame, CallKind, InternalTransaction, InternalTxKind, StructLog, TransactionTrace,
+};
+use citrate_execution::types::{Address, Log};
+use citrate_network::ReputationEvent;
 use primitive_types::U256;
 use serde::{Deserialize, Serialize};
 
@@ -10,12 +16,19 @@ pub struct BlockResponse {
     pub hash: Hash,
     pub height: u64,
     pub parent_hash: Hash,
+    #[serde(default)]
+    pub merge_parents: Vec<Hash>,
     pub timestamp: u64,
     pub blue_score: u64,
     pub blue_work: u128,
+    /// Proposer public key, hex
+    #[serde(default)]
+    pub proposer: String,
     pub transactions: Vec<TransactionResponse>,
     pub state_root: Hash,
     pub tx_root: Hash,
+    #[serde(default)]
+    pub receipt_root: Hash,
 }
 
 impl From<Block> for BlockResponse {
@@ -24,12 +37,15 @@ impl From<Block> for BlockResponse {
             hash: block.header.block_hash,
             height: block.header.height,
             parent_hash: block.header.selected_parent_hash,
+            merge_parents: block.header.merge_parent_hashes,
             timestamp: block.header.timestamp,
             blue_score: block.header.blue_score,
             blue_work: block.header.blue_work,
+            proposer: hex::encode(block.header.proposer_pubkey.as_bytes()),
             transactions: block.transactions.into_iter().map(Into::into).collect(),
             state_root: block.state_root,
             tx_root: block.tx_root,
+            receipt_root: block.receipt_root,
         }
     }
 }
@@ -103,6 +119,51 @@ pub struct NodeInfo {
     pub peer_count: usize,
 }
 
+/// Node summary reported by `admin_nodeStatus`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeStatusResponse {
+    pub version: String,
+    pub chain_id: u64,
+    pub height: u64,
+    /// Hex, `None` before the first block
+    pub head_hash: Option<String>,
+    pub head_timestamp: Option<u64>,
+    pub blue_score: u64,
+    pub dag_tips: usize,
+    pub peer_count: usize,
+    pub syncing: bool,
+    pub uptime_secs: u64,
+    pub mempool_pending: usize,
+    /// Newer client version that a majority of peers run, if any
+    pub upgrade_available: Option<String>,
+}
+
+/// A connected peer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerInfoResponse {
+    pub id: String,
+    pub addr: String,
+    pub direction: String,
+    pub state: String,
+    pub score: i32,
+    pub last_seen_secs: u64,
+    pub client_version: Option<String>,
+}
+
+/// Persisted reputation of a peer host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerReputationResponse {
+    pub ip: String,
+    pub peer_id: Option<String>,
+    pub score: f64,
+    pub connected: bool,
+    pub banned: bool,
+    pub banned_until: Option<u64>,
+    pub ban_reason: Option<String>,
+    pub ban_count: u32,
+    pub events: Vec<ReputationEvent>,
+}
+
 /// Mempool status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MempoolStatus {
@@ -111,3 +172,233 @@ pub struct MempoolStatus {
     pub total_size: usize,
     pub max_size: usize,
 }
+
+/// Transaction conflicts between parallel blocks over a recent height window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictStatsResponse {
+    pub from_height: u64,
+    pub to_height: u64,
+    #[serde(flatten)]
+    pub stats: ConflictStats,
+    pub conflicts: Vec<TransactionConflict>,
+}
+
+/// Call frame in the layout of geth's `callTracer`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrameResponse {
+    #[serde(rename = "type")]
+    pub call_type: CallKind,
+    pub from: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    pub value: String,
+    pub gas: String,
+    pub gas_used: String,
+    pub input: String,
+    pub output: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<CallFrameResponse>,
+}
+
+impl From<CallFrame> for CallFrameResponse {
+    fn from(frame: CallFrame) -> Self {
+        Self {
+            call_type: frame.call_type,
+            from: format!("0x{}", hex::encode(frame.from.0)),
+            to: frame.to.map(|a| format!("0x{}", hex::encode(a.0))),
+            value: format!("0x{:x}", frame.value),
+            gas: format!("0x{:x}", frame.gas),
+            gas_used: format!("0x{:x}", frame.gas_used),
+            input: format!("0x{}", hex::encode(&frame.input)),
+            output: format!("0x{}", hex::encode(&frame.output)),
+            error: frame.error,
+            revert_reason: frame.revert_reason,
+            calls: frame.calls.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Value transfer made by a contract during execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InternalTransactionResponse {
+    #[serde(rename = "type")]
+    pub kind: InternalTxKind,
+    pub from: String,
+    pub to: String,
+    pub value: String,
+    pub depth: usize,
+}
+
+impl From<InternalTransaction> for InternalTransactionResponse {
+    fn from(tx: InternalTransaction) -> Self {
+        Self {
+            kind: tx.kind,
+            from: format!("0x{}", hex::encode(tx.from.0)),
+            to: format!("0x{}", hex::encode(tx.to.0)),
+            value: format!("0x{:x}", tx.value),
+            depth: tx.depth,
+        }
+    }
+}
+
+/// Executed opcode in the layout of geth's struct logger
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLogResponse {
+    pub pc: usize,
+    pub op: String,
+    pub gas: u64,
+    pub gas_cost: u64,
+    pub depth: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<Vec<String>>,
+}
+
+impl From<StructLog> for StructLogResponse {
+    fn from(log: StructLog) -> Self {
+        Self {
+            pc: log.pc,
+            op: log.op,
+            gas: log.gas,
+            gas_cost: log.gas_cost,
+            depth: log.depth,
+            stack: log
+                .stack
+                .map(|stack| stack.iter().map(|v| format!("0x{:x}", v)).collect()),
+        }
+    }
+}
+
+/// Replayed transaction with its call tree and optional opcode steps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionTraceResponse {
+    pub tx_hash: String,
+    pub failed: bool,
+    pub gas: u64,
+    pub return_value: String,
+    pub call_frame: CallFrameResponse,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub struct_logs: Vec<StructLogResponse>,
+}
+
+impl From<TransactionTrace> for TransactionTraceResponse {
+    fn from(trace: TransactionTrace) -> Self {
+        Self {
+            tx_hash: format!("0x{}", hex::encode(trace.tx_hash.as_bytes())),
+            failed: !trace.status,
+            gas: trace.gas_used,
+            return_value: format!("0x{}", hex::encode(&trace.output)),
+            call_frame: trace.call.into(),
+            struct_logs: trace.struct_logs.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Event emitted during a simulation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatedLogResponse {
+    pub address: String,
+    pub topics: Vec<String>,
+    pub data: String,
+}
+
+impl From<Log> for SimulatedLogResponse {
+    fn from(log: Log) -> Self {
+        Self {
+            address: format!("0x{}", hex::encode(log.address.0)),
+            topics: log
+                .topics
+                .iter()
+                .map(|t| format!("0x{}", hex::encode(t.as_bytes())))
+                .collect(),
+            data: format!("0x{}", hex::encode(&log.data)),
+        }
+    }
+}
+
+/// Value before and after a simulated transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeResponse {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+impl From<SlotChange> for ChangeResponse {
+    fn from(change: SlotChange) -> Self {
+        let word = |value: Option<Vec<u8>>| value.map(|v| format!("0x{}", hex::encode(v)));
+        Self {
+            from: word(change.from),
+            to: word(change.to),
+        }
+    }
+}
+
+/// Account fields and storage slots a simulated transaction changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountDiffResponse {
+    pub address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<ChangeResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<ChangeResponse>,
+    /// Code hash before and after
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<ChangeResponse>,
+    /// Slot to its values before and after
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub storage: std::collections::BTreeMap<String, ChangeResponse>,
+}
+
+impl From<AccountDiff> for AccountDiffResponse {
+    fn from(diff: AccountDiff) -> Self {
+        fn change<T>(change: Change<T>, show: impl Fn(T) -> String) -> ChangeResponse {
+            ChangeResponse {
+                from: Some(show(change.from)),
+                to: Some(show(change.to)),
+            }
+        }
+        Self {
+            address: format!("0x{}", hex::encode(diff.address.0)),
+            balance: diff.balance.map(|c| change(c, |v| format!("0x{:x}", v))),
+            nonce: diff.nonce.map(|c| change(c, |v| format!("0x{:x}", v))),
+            code: diff
+                .code
+                .map(|c| change(c, |h| format!("0x{}", hex::encode(h.as_bytes())))),
+            storage: diff
+                .storage
+                .into_iter()
+                .map(|slot| (format!("0x{}", hex::encode(&slot.key)), slot.into()))
+                .collect(),
+        }
+    }
+}
+
+/// Result of `citrate_simulateTransaction`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationResponse {
+    pub success: bool,
+    pub gas_used: u64,
+    pub return_data: String,
+    pub logs: Vec<SimulatedLogResponse>,
+    pub state_diff: Vec<AccountDiffResponse>,
+}
+
+impl From<Simulation> for SimulationResponse {
+    fn from(simulation: Simulation) -> Self {
+        let receipt = simulation.receipt;
+        Self {
+            success: receipt.status,
+            gas_used: receipt.gas_used,
+            return_data: format!("0x{}", hex::encode(&receipt.output)),
+            logs: receipt.logs.into_iter().map(Into::into).collect(),
+            state_diff: simulation.state_diff.into_iter().map(Into::into).collect(),
+        }
+    }
+}
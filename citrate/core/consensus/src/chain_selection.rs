@@ -1,8 +1,10 @@
 // citrate/core/consensus/src/chain_selection.rs
 
+use crate::checkpoint::{CheckpointError, CheckpointPool};
 use crate::dag_store::DagStore;
 use crate::finality::{FinalityError, FinalityTracker};
 use crate::ghostdag::GhostDag;
+use crate::metrics::{self, MetricEvent};
 use crate::tip_selection::TipSelector;
 use crate::types::{Block, Hash};
 use std::collections::HashSet;
@@ -25,6 +27,9 @@ pub enum ChainSelectionError {
     #[error("Reorganization past finalized block: {0}")]
     ReorgPastFinalized(Hash),
 
+    #[error("Reorganization past finality checkpoint {0}")]
+    ReorgPastCheckpoint(Hash),
+
     #[error("DAG error: {0}")]
     DagError(String),
 
@@ -61,6 +66,8 @@ pub struct ChainSelector {
     reorg_history: Arc<RwLock<Vec<ReorgEvent>>>,
     /// Optional finality tracker for reorg protection
     finality_tracker: Option<Arc<FinalityTracker>>,
+    /// Optional signed checkpoints for reorg protection
+    checkpoints: Option<Arc<CheckpointPool>>,
 }
 
 #[derive(Debug, Clone)]
@@ -93,6 +100,7 @@ impl ChainSelector {
             max_reorg_depth,
             reorg_history: Arc::new(RwLock::new(Vec::new())),
             finality_tracker: None,
+            checkpoints: None,
         }
     }
 
@@ -118,6 +126,7 @@ impl ChainSelector {
             max_reorg_depth,
             reorg_history: Arc::new(RwLock::new(Vec::new())),
             finality_tracker: Some(finality_tracker),
+            checkpoints: None,
         }
     }
 
@@ -131,6 +140,11 @@ impl ChainSelector {
         self.finality_tracker.as_ref()
     }
 
+    /// Refuse reorganizations below the latest checkpoint enforced by `pool`
+    pub fn set_checkpoints(&mut self, pool: Arc<CheckpointPool>) {
+        self.checkpoints = Some(pool);
+    }
+
     /// Update chain selection based on new block
     pub async fn on_new_block(&self, block: &Block) -> Result<bool, ChainSelectionError> {
         let new_blue_score = self
@@ -252,6 +266,32 @@ impl ChainSelector {
             }
         }
 
+        // Nor past a checkpoint signed by a quorum of validators
+        if let Some(ref checkpoints) = self.checkpoints {
+            let ancestor_height = if common_ancestor == Hash::default() {
+                0
+            } else {
+                self.dag_store
+                    .get_block(&common_ancestor)
+                    .await
+                    .map_err(|_| ChainSelectionError::BlockNotFound(common_ancestor))?
+                    .header
+                    .height
+            };
+            if let Err(CheckpointError::ReorgPastCheckpoint { hash, height }) =
+                checkpoints.check_reorg_allowed(ancestor_height).await
+            {
+                warn!(
+                    "Reorg to {} rejected: ancestor height {} is below checkpoint {} at {}",
+                    new_tip_block.hash(),
+                    ancestor_height,
+                    hash,
+                    height
+                );
+                return Err(ChainSelectionError::ReorgPastCheckpoint(hash));
+            }
+        }
+
         // Build new chain from common ancestor to new tip
         let new_chain = self
             .build_chain(common_ancestor, new_tip_block.hash())
@@ -405,6 +445,7 @@ impl ChainSelector {
         };
 
         self.reorg_history.write().await.push(event);
+        metrics::record(MetricEvent::Reorg { depth });
 
         Ok(())
     }
@@ -518,6 +559,7 @@ mod tests {
                 base_fee_per_gas: 0,
                 gas_used: 0,
                 gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
             },
             state_root: Hash::default(),
             tx_root: Hash::default(),
@@ -540,6 +582,85 @@ mod tests {
         assert_eq!(chain_state.height, 0);
     }
 
+    fn test_block(hash: u8, parent: Hash, height: u64) -> Block {
+        Block {
+            header: BlockHeader {
+                version: 1,
+                block_hash: Hash::new([hash; 32]),
+                selected_parent_hash: parent,
+                merge_parent_hashes: vec![],
+                timestamp: height,
+                height,
+                blue_score: height + 1,
+                blue_work: (height + 1) as u128,
+                pruning_point: Hash::default(),
+                proposer_pubkey: PublicKey::new([0; 32]),
+                vrf_reveal: VrfProof {
+                    proof: vec![],
+                    output: Hash::default(),
+                },
+                base_fee_per_gas: 0,
+                gas_used: 0,
+                gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
+            },
+            state_root: Hash::default(),
+            tx_root: Hash::default(),
+            receipt_root: Hash::default(),
+            artifact_root: Hash::default(),
+            ghostdag_params: GhostDagParams::default(),
+            transactions: vec![],
+            signature: Signature::new([0; 64]),
+            embedded_models: vec![],
+            required_pins: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reorg_past_checkpoint_rejected() {
+        use crate::checkpoint::{CheckpointConfig, FinalityCheckpoint};
+        use crate::crypto::{generate_keypair, sign_message};
+
+        let dag_store = Arc::new(DagStore::new());
+        let ghostdag = Arc::new(GhostDag::new(GhostDagParams::default(), dag_store.clone()));
+        let tip_selector = Arc::new(TipSelector::new(
+            dag_store.clone(),
+            ghostdag.clone(),
+            SelectionStrategy::HighestBlueScore,
+        ));
+        let mut chain_selector = ChainSelector::new(dag_store.clone(), ghostdag, tip_selector, 100);
+
+        let key = generate_keypair();
+        let validator = PublicKey::new(key.verifying_key().to_bytes());
+        let pool = Arc::new(CheckpointPool::new(CheckpointConfig::new(
+            1,
+            1,
+            vec![validator],
+        )));
+        chain_selector.set_checkpoints(pool.clone());
+
+        let genesis = test_block(0xFF, Hash::default(), 0);
+        let a1 = test_block(0xA1, genesis.hash(), 1);
+        let b1 = test_block(0xB1, genesis.hash(), 1);
+        for block in [&genesis, &a1, &b1] {
+            dag_store.store_block(block.clone()).await.unwrap();
+        }
+        chain_selector.extend_chain(&genesis).await.unwrap();
+        chain_selector.extend_chain(&a1).await.unwrap();
+
+        let vote = FinalityCheckpoint::sign_with(1, 1, a1.hash(), validator, |msg| {
+            sign_message(&key, msg)
+        });
+        pool.add_vote(vote).await.unwrap().unwrap();
+
+        let result = chain_selector.attempt_reorganization(&b1).await;
+        assert!(matches!(
+            result,
+            Err(ChainSelectionError::ReorgPastCheckpoint(hash)) if hash == a1.hash()
+        ));
+        assert_eq!(chain_selector.get_chain_state().await.tip, a1.hash());
+    }
+
     #[tokio::test]
     async fn test_chain_validation() {
         let (_, _, _, chain_selector) = setup_test_env().await;
@@ -0,0 +1,356 @@
+// citrate/core/consensus/src/checkpoint.rs
+
+//! Signed Finality Checkpoints
+//!
+//! Depth-based finality (see [`crate::finality`]) is local to each node: two
+//! nodes that saw different histories can disagree on what is final. Finality
+//! checkpoints make it a network-wide decision. Every `interval` blocks,
+//! validators sign the hash of the finalized block at that height and gossip
+//! the signature. Once a quorum of the validator set has signed the same
+//! block, the checkpoint is enforced and no reorganization may replace any
+//! block at or below its height.
+//!
+//! Votes for heights at or below the enforced checkpoint are ignored, and a
+//! validator signing two different blocks at the same height is rejected.
+
+use crate::crypto::verify_signature;
+use crate::types::{Hash, PublicKey, Signature};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+use tokio::sync::RwLock;
+use tracing::info;
+
+/// Domain separator so checkpoint signatures can't be replayed as anything else
+const CHECKPOINT_DOMAIN: &[u8] = b"citrate-finality-checkpoint";
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CheckpointError {
+    #[error("Checkpoint for chain {0}")]
+    WrongChain(u64),
+
+    #[error("Height {0} is not a checkpoint height")]
+    NotCheckpointHeight(u64),
+
+    #[error("Height {0} is at or below the enforced checkpoint")]
+    Stale(u64),
+
+    #[error("Signer is not a validator: {0:?}")]
+    UnknownValidator(PublicKey),
+
+    #[error("Invalid checkpoint signature")]
+    InvalidSignature,
+
+    #[error("Checkpoint vote already known")]
+    Duplicate,
+
+    #[error("Validator {validator:?} signed conflicting checkpoints at height {height}")]
+    Conflicting { validator: PublicKey, height: u64 },
+
+    #[error("Reorganization past checkpoint {hash} at height {height}")]
+    ReorgPastCheckpoint { hash: Hash, height: u64 },
+}
+
+/// One validator's signature over the finalized block at a checkpoint height
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FinalityCheckpoint {
+    pub chain_id: u64,
+    pub height: u64,
+    pub block_hash: Hash,
+    pub validator: PublicKey,
+    pub signature: Signature,
+}
+
+impl FinalityCheckpoint {
+    /// Bytes a validator signs for a checkpoint
+    pub fn signing_bytes(chain_id: u64, height: u64, block_hash: &Hash) -> Vec<u8> {
+        let mut data = Vec::with_capacity(CHECKPOINT_DOMAIN.len() + 48);
+        data.extend_from_slice(CHECKPOINT_DOMAIN);
+        data.extend_from_slice(&chain_id.to_le_bytes());
+        data.extend_from_slice(&height.to_le_bytes());
+        data.extend_from_slice(block_hash.as_bytes());
+        data
+    }
+
+    /// Build a checkpoint, signing it with `sign`
+    pub fn sign_with(
+        chain_id: u64,
+        height: u64,
+        block_hash: Hash,
+        validator: PublicKey,
+        sign: impl FnOnce(&[u8]) -> Signature,
+    ) -> Self {
+        let signature = sign(&Self::signing_bytes(chain_id, height, &block_hash));
+        Self {
+            chain_id,
+            height,
+            block_hash,
+            validator,
+            signature,
+        }
+    }
+
+    pub fn verify(&self) -> bool {
+        verify_signature(
+            &self.validator,
+            &Self::signing_bytes(self.chain_id, self.height, &self.block_hash),
+            &self.signature,
+        )
+    }
+}
+
+/// Checkpoint configuration
+#[derive(Debug, Clone)]
+pub struct CheckpointConfig {
+    pub chain_id: u64,
+    /// Blocks between checkpoints
+    pub interval: u64,
+    /// Validators whose signatures count towards a checkpoint
+    pub validators: Vec<PublicKey>,
+    /// Signatures needed to enforce a checkpoint
+    pub quorum: usize,
+}
+
+impl CheckpointConfig {
+    /// Config requiring signatures from more than two thirds of `validators`
+    pub fn new(chain_id: u64, interval: u64, validators: Vec<PublicKey>) -> Self {
+        let quorum = validators.len() * 2 / 3 + 1;
+        Self {
+            chain_id,
+            interval,
+            validators,
+            quorum,
+        }
+    }
+
+    /// Whether a checkpoint is taken at `height`
+    pub fn is_checkpoint_height(&self, height: u64) -> bool {
+        self.interval > 0 && height > 0 && height.is_multiple_of(self.interval)
+    }
+
+    /// Latest checkpoint height at or below `height`
+    pub fn checkpoint_at_or_below(&self, height: u64) -> Option<u64> {
+        if self.interval == 0 {
+            return None;
+        }
+        let checkpoint = height - height % self.interval;
+        (checkpoint > 0).then_some(checkpoint)
+    }
+}
+
+/// A checkpoint signed by a quorum of validators
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnforcedCheckpoint {
+    pub height: u64,
+    pub block_hash: Hash,
+    pub signatures: Vec<FinalityCheckpoint>,
+}
+
+/// Collects checkpoint votes and tracks the latest enforced checkpoint
+pub struct CheckpointPool {
+    config: CheckpointConfig,
+    validators: HashSet<PublicKey>,
+    /// Votes by height, then by signer
+    votes: RwLock<HashMap<u64, HashMap<PublicKey, FinalityCheckpoint>>>,
+    enforced: RwLock<Option<EnforcedCheckpoint>>,
+}
+
+impl CheckpointPool {
+    pub fn new(config: CheckpointConfig) -> Self {
+        Self {
+            validators: config.validators.iter().copied().collect(),
+            config,
+            votes: RwLock::new(HashMap::new()),
+            enforced: RwLock::new(None),
+        }
+    }
+
+    pub fn config(&self) -> &CheckpointConfig {
+        &self.config
+    }
+
+    pub fn is_validator(&self, key: &PublicKey) -> bool {
+        self.validators.contains(key)
+    }
+
+    /// Latest enforced checkpoint, if any
+    pub async fn enforced(&self) -> Option<EnforcedCheckpoint> {
+        self.enforced.read().await.clone()
+    }
+
+    /// Height of the latest enforced checkpoint, 0 if none
+    pub async fn enforced_height(&self) -> u64 {
+        self.enforced
+            .read()
+            .await
+            .as_ref()
+            .map_or(0, |checkpoint| checkpoint.height)
+    }
+
+    /// Add a vote. Returns the checkpoint if this vote brought it to quorum.
+    pub async fn add_vote(
+        &self,
+        vote: FinalityCheckpoint,
+    ) -> Result<Option<EnforcedCheckpoint>, CheckpointError> {
+        if vote.chain_id != self.config.chain_id {
+            return Err(CheckpointError::WrongChain(vote.chain_id));
+        }
+        if !self.config.is_checkpoint_height(vote.height) {
+            return Err(CheckpointError::NotCheckpointHeight(vote.height));
+        }
+        if !self.is_validator(&vote.validator) {
+            return Err(CheckpointError::UnknownValidator(vote.validator));
+        }
+
+        let mut enforced = self.enforced.write().await;
+        if enforced.as_ref().is_some_and(|e| vote.height <= e.height) {
+            return Err(CheckpointError::Stale(vote.height));
+        }
+
+        let mut votes = self.votes.write().await;
+        let at_height = votes.entry(vote.height).or_default();
+        if let Some(existing) = at_height.get(&vote.validator) {
+            if existing.block_hash == vote.block_hash {
+                return Err(CheckpointError::Duplicate);
+            }
+            return Err(CheckpointError::Conflicting {
+                validator: vote.validator,
+                height: vote.height,
+            });
+        }
+        // Checked last, it's the expensive part
+        if !vote.verify() {
+            return Err(CheckpointError::InvalidSignature);
+        }
+
+        let (height, block_hash) = (vote.height, vote.block_hash);
+        at_height.insert(vote.validator, vote);
+        let signatures: Vec<FinalityCheckpoint> = at_height
+            .values()
+            .filter(|v| v.block_hash == block_hash)
+            .cloned()
+            .collect();
+        if signatures.len() < self.config.quorum {
+            return Ok(None);
+        }
+
+        info!(
+            "Finality checkpoint enforced at height {}: {} ({} signatures)",
+            height,
+            block_hash,
+            signatures.len()
+        );
+        let checkpoint = EnforcedCheckpoint {
+            height,
+            block_hash,
+            signatures,
+        };
+        *enforced = Some(checkpoint.clone());
+        votes.retain(|h, _| *h > height);
+        Ok(Some(checkpoint))
+    }
+
+    /// Whether a reorganization whose common ancestor is at
+    /// `ancestor_height` keeps the enforced checkpoint
+    pub async fn check_reorg_allowed(&self, ancestor_height: u64) -> Result<(), CheckpointError> {
+        match self.enforced.read().await.as_ref() {
+            Some(checkpoint) if ancestor_height < checkpoint.height => {
+                Err(CheckpointError::ReorgPastCheckpoint {
+                    hash: checkpoint.block_hash,
+                    height: checkpoint.height,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{generate_keypair, sign_message};
+    use ed25519_dalek::SigningKey;
+
+    fn vote(key: &SigningKey, height: u64, block_hash: Hash) -> FinalityCheckpoint {
+        let validator = PublicKey::new(key.verifying_key().to_bytes());
+        FinalityCheckpoint::sign_with(1, height, block_hash, validator, |msg| {
+            sign_message(key, msg)
+        })
+    }
+
+    #[tokio::test]
+    async fn test_quorum_enforces_checkpoint() {
+        let keys: Vec<SigningKey> = (0..4).map(|_| generate_keypair()).collect();
+        let validators = keys
+            .iter()
+            .map(|k| PublicKey::new(k.verifying_key().to_bytes()))
+            .collect();
+        let config = CheckpointConfig::new(1, 10, validators);
+        assert_eq!(config.quorum, 3);
+        assert_eq!(config.checkpoint_at_or_below(29), Some(20));
+        assert_eq!(config.checkpoint_at_or_below(9), None);
+        let pool = CheckpointPool::new(config);
+
+        let block = Hash::new([1; 32]);
+        let other = Hash::new([2; 32]);
+        assert_eq!(
+            pool.add_vote(vote(&keys[0], 15, block)).await,
+            Err(CheckpointError::NotCheckpointHeight(15))
+        );
+        assert_eq!(pool.add_vote(vote(&keys[0], 10, block)).await, Ok(None));
+        assert_eq!(
+            pool.add_vote(vote(&keys[0], 10, block)).await,
+            Err(CheckpointError::Duplicate)
+        );
+        assert!(matches!(
+            pool.add_vote(vote(&keys[0], 10, other)).await,
+            Err(CheckpointError::Conflicting { height: 10, .. })
+        ));
+        assert_eq!(pool.add_vote(vote(&keys[1], 10, other)).await, Ok(None));
+        assert_eq!(pool.add_vote(vote(&keys[2], 10, block)).await, Ok(None));
+
+        let mut forged = vote(&keys[3], 10, block);
+        forged.block_hash = other;
+        assert_eq!(
+            pool.add_vote(forged).await,
+            Err(CheckpointError::InvalidSignature)
+        );
+        let outsider = vote(&generate_keypair(), 10, block);
+        assert!(matches!(
+            pool.add_vote(outsider).await,
+            Err(CheckpointError::UnknownValidator(_))
+        ));
+
+        let enforced = pool
+            .add_vote(vote(&keys[3], 10, block))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(enforced.block_hash, block);
+        assert_eq!(enforced.signatures.len(), 3);
+        assert!(enforced.signatures.iter().all(FinalityCheckpoint::verify));
+        assert_eq!(pool.enforced_height().await, 10);
+        assert_eq!(
+            pool.add_vote(vote(&keys[1], 10, block)).await,
+            Err(CheckpointError::Stale(10))
+        );
+
+        assert!(pool.check_reorg_allowed(10).await.is_ok());
+        assert_eq!(
+            pool.check_reorg_allowed(9).await,
+            Err(CheckpointError::ReorgPastCheckpoint {
+                hash: block,
+                height: 10
+            })
+        );
+    }
+
+    #[test]
+    fn test_signature_is_bound_to_chain() {
+        let key = generate_keypair();
+        let mut checkpoint = vote(&key, 10, Hash::new([3; 32]));
+        assert!(checkpoint.verify());
+        checkpoint.chain_id = 2;
+        assert!(!checkpoint.verify());
+    }
+}
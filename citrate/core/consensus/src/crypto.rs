@@ -1,7 +1,9 @@
 // citrate/core/consensus/src/crypto.rs
 
 use crate::types::{PublicKey, Signature, Transaction};
-use ed25519_dalek::{Signature as DalekSignature, Signer, SigningKey, Verifier, VerifyingKey};
+use ed25519_dalek::{
+    verify_batch, Signature as DalekSignature, Signer, SigningKey, Verifier, VerifyingKey,
+};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -54,8 +56,67 @@ pub fn verify_transaction(tx: &Transaction) -> Result<bool, CryptoError> {
     }
 }
 
+/// Verify the signatures of many transactions at once
+///
+/// Native ed25519 signatures are checked with a single batch verification,
+/// which is much cheaper than verifying them one by one. If the batch fails,
+/// each signature is re-checked on its own to find the offenders. Returns one
+/// result per transaction, in order, with the same meaning as
+/// [`verify_transaction`].
+pub fn verify_transactions<'a>(
+    txs: impl IntoIterator<Item = &'a Transaction>,
+) -> Vec<Result<bool, CryptoError>> {
+    let mut results = Vec::new();
+    let mut batch = Vec::new();
+
+    for (index, tx) in txs.into_iter().enumerate() {
+        if is_ecdsa_transaction(tx) {
+            // Verified during address recovery, see `verify_transaction`
+            results.push(Ok(true));
+            continue;
+        }
+        match ed25519_parts(tx) {
+            Ok(parts) => {
+                results.push(Ok(true));
+                batch.push((index, parts));
+            }
+            Err(e) => results.push(Err(e)),
+        }
+    }
+
+    if batch.is_empty() {
+        return results;
+    }
+
+    let messages: Vec<&[u8]> = batch.iter().map(|(_, (msg, _, _))| msg.as_slice()).collect();
+    let signatures: Vec<DalekSignature> = batch.iter().map(|(_, (_, _, sig))| *sig).collect();
+    let keys: Vec<VerifyingKey> = batch.iter().map(|(_, (_, key, _))| *key).collect();
+
+    if verify_batch(&messages, &signatures, &keys).is_err() {
+        // Fall back to single verification to identify the offenders
+        for (index, (message, key, signature)) in &batch {
+            results[*index] = Ok(key.verify(message, signature).is_ok());
+        }
+    }
+
+    results
+}
+
 /// Verify an ed25519 native transaction signature
 fn verify_ed25519_transaction(tx: &Transaction) -> Result<bool, CryptoError> {
+    let (message, public_key, signature) = ed25519_parts(tx)?;
+
+    // Verify the signature
+    match public_key.verify(&message, &signature) {
+        Ok(_) => Ok(true),
+        Err(_) => Ok(false),
+    }
+}
+
+/// Signed message, public key and signature of a native transaction
+fn ed25519_parts(
+    tx: &Transaction,
+) -> Result<(Vec<u8>, VerifyingKey, DalekSignature), CryptoError> {
     // Get canonical bytes to verify (everything except signature)
     let message = canonical_tx_bytes(tx)?;
 
@@ -65,11 +126,7 @@ fn verify_ed25519_transaction(tx: &Transaction) -> Result<bool, CryptoError> {
 
     let signature = DalekSignature::from_bytes(tx.signature.as_bytes());
 
-    // Verify the signature
-    match public_key.verify(&message, &signature) {
-        Ok(_) => Ok(true),
-        Err(_) => Ok(false),
-    }
+    Ok((message, public_key, signature))
 }
 
 /// Sign a transaction (for testing and dev tools)
@@ -89,6 +146,20 @@ pub fn sign_transaction(tx: &mut Transaction, signing_key: &SigningKey) -> Resul
     Ok(())
 }
 
+/// Sign an arbitrary message
+pub fn sign_message(signing_key: &SigningKey, message: &[u8]) -> Signature {
+    Signature::new(signing_key.sign(message).to_bytes())
+}
+
+/// Verify an ed25519 signature over an arbitrary message
+pub fn verify_signature(public_key: &PublicKey, message: &[u8], signature: &Signature) -> bool {
+    let Ok(key) = VerifyingKey::from_bytes(public_key.as_bytes()) else {
+        return false;
+    };
+    key.verify(message, &DalekSignature::from_bytes(signature.as_bytes()))
+        .is_ok()
+}
+
 /// Get canonical bytes for transaction signing/verification
 /// This excludes the signature field and uses a deterministic encoding
 fn canonical_tx_bytes(tx: &Transaction) -> Result<Vec<u8>, CryptoError> {
@@ -160,6 +231,39 @@ mod tests {
         assert!(!verify_transaction(&tx).unwrap());
     }
 
+    fn signed_tx(nonce: u64) -> Transaction {
+        let mut tx = Transaction {
+            hash: Hash::new([nonce as u8; 32]),
+            nonce,
+            from: PublicKey::new([0; 32]),
+            to: Some(PublicKey::new([2; 32])),
+            value: 1000,
+            gas_limit: 21000,
+            gas_price: 1_000_000_000,
+            data: vec![],
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        };
+        sign_transaction(&mut tx, &generate_keypair()).unwrap();
+        tx
+    }
+
+    #[test]
+    fn test_batch_verification() {
+        let mut txs: Vec<_> = (0..8).map(signed_tx).collect();
+        let results = verify_transactions(&txs);
+        assert!(results.iter().all(|r| matches!(r, Ok(true))));
+
+        // A tampered transaction is singled out by the fallback
+        txs[5].value += 1;
+        let results = verify_transactions(&txs);
+        for (i, result) in results.iter().enumerate() {
+            assert_eq!(*result.as_ref().unwrap(), i != 5);
+        }
+
+        assert!(verify_transactions(&txs[..0]).is_empty());
+    }
+
     #[test]
     fn test_canonical_bytes_deterministic() {
         let tx = Transaction {
@@ -0,0 +1,463 @@
+// citrate/core/consensus/src/epoch.rs
+
+//! Epoch Summaries
+//!
+//! Every `length` blocks the validator set signs a summary of the chain at
+//! the epoch boundary block: its state root, its blue score, and the hash of
+//! the validator set that signs the next summary. Once a quorum has signed,
+//! the summary's commitment is carried in the headers of later blocks.
+//!
+//! A light client that trusts the genesis validator set can follow the
+//! summaries one epoch at a time, checking a quorum of signatures per epoch
+//! instead of every header, and ends up with a trusted state root to check
+//! account proofs against.
+
+use crate::crypto::verify_signature;
+use crate::types::{Block, Hash, PublicKey, Signature};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use thiserror::Error;
+use tokio::sync::RwLock;
+use tracing::info;
+
+/// Domain separator so epoch signatures can't be replayed as anything else
+const EPOCH_DOMAIN: &[u8] = b"citrate-epoch-summary";
+
+/// Quorum-signed summaries kept by a pool; the oldest are dropped first
+const MAX_SIGNED_SUMMARIES: usize = 256;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum EpochError {
+    #[error("Epoch summary for chain {0}")]
+    WrongChain(u64),
+
+    #[error("Height {0} is not an epoch boundary")]
+    NotEpochBoundary(u64),
+
+    #[error("Epoch {0} is already signed")]
+    Stale(u64),
+
+    #[error("Signer is not a validator: {0:?}")]
+    UnknownValidator(PublicKey),
+
+    #[error("Invalid epoch signature")]
+    InvalidSignature,
+
+    #[error("Epoch vote already known")]
+    Duplicate,
+
+    #[error("Validator {validator:?} signed conflicting summaries for epoch {epoch}")]
+    Conflicting { validator: PublicKey, epoch: u64 },
+
+    #[error("Epoch {epoch} has {signatures} valid signatures, {quorum} needed")]
+    QuorumNotMet {
+        epoch: u64,
+        signatures: usize,
+        quorum: usize,
+    },
+
+    #[error("Validator set does not match the hash committed by epoch {0}")]
+    ValidatorSetMismatch(u64),
+
+    #[error("Expected epoch {expected}, got {got}")]
+    OutOfOrder { expected: u64, got: u64 },
+
+    #[error("Header commits to {got}, expected {expected}")]
+    CommitmentMismatch { expected: Hash, got: Hash },
+}
+
+/// Hash of a validator set, independent of the order keys are listed in
+pub fn validator_set_hash(validators: &[PublicKey]) -> Hash {
+    let mut keys: Vec<&PublicKey> = validators.iter().collect();
+    keys.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+    keys.dedup();
+    let mut hasher = Sha3_256::new();
+    hasher.update((keys.len() as u64).to_le_bytes());
+    for key in keys {
+        hasher.update(key.as_bytes());
+    }
+    Hash::from_bytes(&hasher.finalize())
+}
+
+/// The chain at an epoch boundary block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EpochSummary {
+    pub chain_id: u64,
+    pub epoch: u64,
+    /// Height of the boundary block
+    pub height: u64,
+    pub block_hash: Hash,
+    pub state_root: Hash,
+    pub blue_score: u64,
+    /// Hash of the validator set that signs the next epoch's summary
+    pub validator_set_hash: Hash,
+}
+
+impl EpochSummary {
+    /// Summary of `block`, which must be at an epoch boundary.
+    /// `next_validators` is the set that will sign the next summary.
+    pub fn for_block(
+        config: &EpochConfig,
+        block: &Block,
+        next_validators: &[PublicKey],
+    ) -> Result<Self, EpochError> {
+        let height = block.header.height;
+        if !config.is_boundary(height) {
+            return Err(EpochError::NotEpochBoundary(height));
+        }
+        Ok(Self {
+            chain_id: config.chain_id,
+            epoch: height / config.length,
+            height,
+            block_hash: block.hash(),
+            state_root: block.state_root,
+            blue_score: block.header.blue_score,
+            validator_set_hash: validator_set_hash(next_validators),
+        })
+    }
+
+    /// Hash committed in block headers and signed by validators
+    pub fn commitment(&self) -> Hash {
+        let mut hasher = Sha3_256::new();
+        hasher.update(EPOCH_DOMAIN);
+        hasher.update(self.chain_id.to_le_bytes());
+        hasher.update(self.epoch.to_le_bytes());
+        hasher.update(self.height.to_le_bytes());
+        hasher.update(self.block_hash.as_bytes());
+        hasher.update(self.state_root.as_bytes());
+        hasher.update(self.blue_score.to_le_bytes());
+        hasher.update(self.validator_set_hash.as_bytes());
+        Hash::from_bytes(&hasher.finalize())
+    }
+}
+
+/// One validator's signature over an epoch summary
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EpochVote {
+    pub summary: EpochSummary,
+    pub validator: PublicKey,
+    pub signature: Signature,
+}
+
+impl EpochVote {
+    /// Build a vote, signing the summary's commitment with `sign`
+    pub fn sign_with(
+        summary: EpochSummary,
+        validator: PublicKey,
+        sign: impl FnOnce(&[u8]) -> Signature,
+    ) -> Self {
+        let signature = sign(summary.commitment().as_bytes());
+        Self {
+            summary,
+            validator,
+            signature,
+        }
+    }
+
+    pub fn verify(&self) -> bool {
+        verify_signature(
+            &self.validator,
+            self.summary.commitment().as_bytes(),
+            &self.signature,
+        )
+    }
+}
+
+/// An epoch summary with the signatures collected for it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedEpochSummary {
+    pub summary: EpochSummary,
+    pub signatures: Vec<(PublicKey, Signature)>,
+}
+
+impl SignedEpochSummary {
+    /// Check that at least `quorum` distinct members of `validators` signed
+    pub fn verify(&self, validators: &[PublicKey], quorum: usize) -> Result<(), EpochError> {
+        let members: HashSet<&PublicKey> = validators.iter().collect();
+        let commitment = self.summary.commitment();
+        let mut signers = HashSet::new();
+        for (validator, signature) in &self.signatures {
+            if members.contains(validator)
+                && !signers.contains(validator)
+                && verify_signature(validator, commitment.as_bytes(), signature)
+            {
+                signers.insert(validator);
+            }
+        }
+        if signers.len() < quorum.max(1) {
+            return Err(EpochError::QuorumNotMet {
+                epoch: self.summary.epoch,
+                signatures: signers.len(),
+                quorum,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Epoch configuration
+#[derive(Debug, Clone)]
+pub struct EpochConfig {
+    pub chain_id: u64,
+    /// Blocks per epoch
+    pub length: u64,
+    /// Validators whose signatures count towards a summary
+    pub validators: Vec<PublicKey>,
+    /// Signatures needed for a summary to be committed
+    pub quorum: usize,
+}
+
+impl EpochConfig {
+    /// Config requiring signatures from more than two thirds of `validators`
+    pub fn new(chain_id: u64, length: u64, validators: Vec<PublicKey>) -> Self {
+        let quorum = validators.len() * 2 / 3 + 1;
+        Self {
+            chain_id,
+            length,
+            validators,
+            quorum,
+        }
+    }
+
+    /// Whether the block at `height` closes an epoch
+    pub fn is_boundary(&self, height: u64) -> bool {
+        self.length > 0 && height > 0 && height.is_multiple_of(self.length)
+    }
+
+    /// Latest boundary height at or below `height`
+    pub fn boundary_at_or_below(&self, height: u64) -> Option<u64> {
+        if self.length == 0 {
+            return None;
+        }
+        let boundary = height - height % self.length;
+        (boundary > 0).then_some(boundary)
+    }
+}
+
+/// Collects epoch votes and keeps the summaries that reached quorum
+pub struct EpochSummaryPool {
+    config: EpochConfig,
+    validators: HashSet<PublicKey>,
+    /// Votes by epoch, then by signer
+    votes: RwLock<HashMap<u64, HashMap<PublicKey, EpochVote>>>,
+    signed: RwLock<BTreeMap<u64, SignedEpochSummary>>,
+}
+
+impl EpochSummaryPool {
+    pub fn new(config: EpochConfig) -> Self {
+        Self {
+            validators: config.validators.iter().copied().collect(),
+            config,
+            votes: RwLock::new(HashMap::new()),
+            signed: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &EpochConfig {
+        &self.config
+    }
+
+    pub fn is_validator(&self, key: &PublicKey) -> bool {
+        self.validators.contains(key)
+    }
+
+    /// Most recent summary signed by a quorum
+    pub async fn latest(&self) -> Option<SignedEpochSummary> {
+        self.signed.read().await.values().next_back().cloned()
+    }
+
+    /// Signed summary of `epoch`, if it is still kept
+    pub async fn get(&self, epoch: u64) -> Option<SignedEpochSummary> {
+        self.signed.read().await.get(&epoch).cloned()
+    }
+
+    /// Commitment for new block headers: that of the latest signed summary,
+    /// or zero before the first one
+    pub async fn header_commitment(&self) -> Hash {
+        self.latest()
+            .await
+            .map(|signed| signed.summary.commitment())
+            .unwrap_or_default()
+    }
+
+    /// Add a vote. Returns the signed summary if this vote brought it to
+    /// quorum.
+    pub async fn add_vote(
+        &self,
+        vote: EpochVote,
+    ) -> Result<Option<SignedEpochSummary>, EpochError> {
+        let summary = &vote.summary;
+        if summary.chain_id != self.config.chain_id {
+            return Err(EpochError::WrongChain(summary.chain_id));
+        }
+        if !self.config.is_boundary(summary.height)
+            || summary.epoch != summary.height / self.config.length
+        {
+            return Err(EpochError::NotEpochBoundary(summary.height));
+        }
+        if !self.is_validator(&vote.validator) {
+            return Err(EpochError::UnknownValidator(vote.validator));
+        }
+
+        let mut signed = self.signed.write().await;
+        if signed.contains_key(&summary.epoch) {
+            return Err(EpochError::Stale(summary.epoch));
+        }
+
+        let mut votes = self.votes.write().await;
+        let epoch = summary.epoch;
+        let at_epoch = votes.entry(epoch).or_default();
+        if let Some(existing) = at_epoch.get(&vote.validator) {
+            if existing.summary == vote.summary {
+                return Err(EpochError::Duplicate);
+            }
+            return Err(EpochError::Conflicting {
+                validator: vote.validator,
+                epoch,
+            });
+        }
+        // Checked last, it's the expensive part
+        if !vote.verify() {
+            return Err(EpochError::InvalidSignature);
+        }
+
+        let summary = vote.summary.clone();
+        at_epoch.insert(vote.validator, vote);
+        let signatures: Vec<(PublicKey, Signature)> = at_epoch
+            .values()
+            .filter(|v| v.summary == summary)
+            .map(|v| (v.validator, v.signature))
+            .collect();
+        if signatures.len() < self.config.quorum {
+            return Ok(None);
+        }
+
+        info!(
+            "Epoch {} summary signed at height {}: state root {} ({} signatures)",
+            epoch,
+            summary.height,
+            summary.state_root,
+            signatures.len()
+        );
+        let summary = SignedEpochSummary {
+            summary,
+            signatures,
+        };
+        signed.insert(epoch, summary.clone());
+        while signed.len() > MAX_SIGNED_SUMMARIES {
+            signed.pop_first();
+        }
+        votes.retain(|e, _| *e > epoch);
+        Ok(Some(summary))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{generate_keypair, sign_message};
+    use ed25519_dalek::SigningKey;
+
+    fn summary(epoch: u64, state_root: Hash, validators: &[PublicKey]) -> EpochSummary {
+        EpochSummary {
+            chain_id: 1,
+            epoch,
+            height: epoch * 10,
+            block_hash: Hash::new([epoch as u8; 32]),
+            state_root,
+            blue_score: epoch * 10,
+            validator_set_hash: validator_set_hash(validators),
+        }
+    }
+
+    fn vote(key: &SigningKey, summary: EpochSummary) -> EpochVote {
+        let validator = PublicKey::new(key.verifying_key().to_bytes());
+        EpochVote::sign_with(summary, validator, |msg| sign_message(key, msg))
+    }
+
+    #[tokio::test]
+    async fn test_quorum_signs_summary() {
+        let keys: Vec<SigningKey> = (0..4).map(|_| generate_keypair()).collect();
+        let validators: Vec<PublicKey> = keys
+            .iter()
+            .map(|k| PublicKey::new(k.verifying_key().to_bytes()))
+            .collect();
+        let pool = EpochSummaryPool::new(EpochConfig::new(1, 10, validators.clone()));
+        assert_eq!(pool.config().quorum, 3);
+        assert_eq!(pool.header_commitment().await, Hash::default());
+
+        let good = summary(2, Hash::new([7; 32]), &validators);
+        let other = summary(2, Hash::new([8; 32]), &validators);
+        let mut misplaced = good.clone();
+        misplaced.height = 25;
+        assert_eq!(
+            pool.add_vote(vote(&keys[0], misplaced)).await,
+            Err(EpochError::NotEpochBoundary(25))
+        );
+        assert_eq!(pool.add_vote(vote(&keys[0], good.clone())).await, Ok(None));
+        assert_eq!(
+            pool.add_vote(vote(&keys[0], good.clone())).await,
+            Err(EpochError::Duplicate)
+        );
+        assert!(matches!(
+            pool.add_vote(vote(&keys[0], other.clone())).await,
+            Err(EpochError::Conflicting { epoch: 2, .. })
+        ));
+        assert_eq!(pool.add_vote(vote(&keys[1], other)).await, Ok(None));
+        assert_eq!(pool.add_vote(vote(&keys[2], good.clone())).await, Ok(None));
+
+        let signed = pool
+            .add_vote(vote(&keys[3], good.clone()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(signed.signatures.len(), 3);
+        assert!(signed.verify(&validators, 3).is_ok());
+        assert_eq!(pool.header_commitment().await, good.commitment());
+        assert_eq!(
+            pool.add_vote(vote(&keys[1], good)).await,
+            Err(EpochError::Stale(2))
+        );
+    }
+
+    #[test]
+    fn test_signed_summary_verification() {
+        let keys: Vec<SigningKey> = (0..3).map(|_| generate_keypair()).collect();
+        let validators: Vec<PublicKey> = keys
+            .iter()
+            .map(|k| PublicKey::new(k.verifying_key().to_bytes()))
+            .collect();
+        let mut reversed = validators.clone();
+        reversed.reverse();
+        assert_eq!(
+            validator_set_hash(&validators),
+            validator_set_hash(&reversed)
+        );
+
+        let epoch = summary(1, Hash::new([1; 32]), &validators);
+        let sign = |key: &SigningKey| {
+            let v = vote(key, epoch.clone());
+            (v.validator, v.signature)
+        };
+        let mut signed = SignedEpochSummary {
+            summary: epoch.clone(),
+            signatures: vec![sign(&keys[0]), sign(&keys[0]), sign(&generate_keypair())],
+        };
+        // Repeated and outside signers don't count
+        assert_eq!(
+            signed.verify(&validators, 2),
+            Err(EpochError::QuorumNotMet {
+                epoch: 1,
+                signatures: 1,
+                quorum: 2
+            })
+        );
+        signed.signatures.push(sign(&keys[1]));
+        assert!(signed.verify(&validators, 2).is_ok());
+
+        // Signatures don't carry over to a different state root
+        signed.summary.state_root = Hash::new([2; 32]);
+        assert!(signed.verify(&validators, 1).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 // citrate/core/consensus/src/ghostdag.rs
 
 use crate::dag_store::DagStore;
+use crate::metrics::{self, MetricEvent};
 use crate::types::{Block, BlueSet, DagRelation, GhostDagParams, Hash};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
@@ -21,6 +22,29 @@ pub enum GhostDagError {
 
     #[error("K-cluster violation")]
     KClusterViolation,
+
+    #[error("Block {0} is below the pruning point")]
+    BelowPruningPoint(Hash),
+}
+
+/// Finalized chain block below which blue-set bookkeeping is compacted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PruningPoint {
+    pub hash: Hash,
+    pub blue_score: u64,
+}
+
+/// Size of the in-memory GhostDAG bookkeeping
+#[derive(Debug, Clone, Default)]
+pub struct GhostDagMemoryStats {
+    /// Blocks with a stored DAG relation
+    pub relations: usize,
+    /// Blocks with a cached blue set
+    pub cached_blue_sets: usize,
+    /// Hashes held across all cached and relation blue sets
+    pub blue_set_entries: usize,
+    /// Blocks compacted away since startup
+    pub pruned_blocks: u64,
 }
 
 /// GhostDAG consensus engine
@@ -39,6 +63,12 @@ pub struct GhostDag {
 
     /// Current tips of the DAG
     tips: Arc<RwLock<HashSet<Hash>>>,
+
+    /// Current pruning point, once the DAG is deeper than the pruning window
+    pruning_point: Arc<RwLock<Option<PruningPoint>>>,
+
+    /// Number of blocks compacted away below the pruning point
+    pruned_blocks: Arc<RwLock<u64>>,
 }
 
 impl GhostDag {
@@ -49,6 +79,8 @@ impl GhostDag {
             relations: Arc::new(RwLock::new(HashMap::new())),
             blue_cache: Arc::new(RwLock::new(HashMap::new())),
             tips: Arc::new(RwLock::new(HashSet::new())),
+            pruning_point: Arc::new(RwLock::new(None)),
+            pruned_blocks: Arc::new(RwLock::new(0)),
         }
     }
 
@@ -90,15 +122,22 @@ impl GhostDag {
             .calculate_blue_merge_parents(block, &selected_parent_blue)
             .await?;
 
-        // Add blue merge parents to blue set
+        // Add blue merge parents to blue set. The score grows by the number of
+        // newly added blocks rather than the set size, so it stays correct
+        // once blocks below the pruning point are compacted out of the sets.
         for parent_hash in &blue_merge_parents {
             let parent_blue_set = self.get_or_calculate_blue_set(parent_hash).await?;
-            blue_set.blocks.extend(parent_blue_set.blocks);
+            for hash in parent_blue_set.blocks {
+                if blue_set.blocks.insert(hash) {
+                    blue_set.score += 1;
+                }
+            }
         }
 
-        // Add current block to blue set and recompute score from set size
-        blue_set.blocks.insert(block.hash());
-        blue_set.score = blue_set.blocks.len() as u64;
+        // Add current block to blue set
+        if blue_set.blocks.insert(block.hash()) {
+            blue_set.score += 1;
+        }
 
         // Cache the result
         self.blue_cache
@@ -124,6 +163,12 @@ impl GhostDag {
         let mut red_parents = Vec::new();
 
         for merge_parent in &block.header.merge_parent_hashes {
+            // Finalized blocks cannot be merged back in as blue
+            if self.is_below_pruning_point(merge_parent).await {
+                red_parents.push(*merge_parent);
+                continue;
+            }
+
             if self
                 .is_blue_candidate(merge_parent, selected_parent_blue, &blue_parents)
                 .await?
@@ -258,6 +303,13 @@ impl GhostDag {
                 .await
                 .map_err(|_| GhostDagError::BlockNotFound(*hash))?;
 
+            // Never rebuild bookkeeping that was compacted away
+            if let Some(pruning_point) = *self.pruning_point.read().await {
+                if block.header.blue_score < pruning_point.blue_score {
+                    return Err(GhostDagError::BelowPruningPoint(*hash));
+                }
+            }
+
             // Genesis: blue set is itself
             if block.is_genesis() {
                 let mut blue = BlueSet::new();
@@ -277,16 +329,20 @@ impl GhostDag {
                 .calculate_blue_merge_parents(&block, &selected_parent_blue)
                 .await?;
 
-            let mut all_blocks = selected_parent_blue.blocks.clone();
+            let mut blue = BlueSet::new();
+            blue.blocks = selected_parent_blue.blocks.clone();
+            blue.score = selected_parent_blue.score;
             for p in &blue_merge_parents {
                 let pset = self.get_or_calculate_blue_set(p).await?;
-                all_blocks.extend(pset.blocks);
+                for h in pset.blocks {
+                    if blue.blocks.insert(h) {
+                        blue.score += 1;
+                    }
+                }
+            }
+            if blue.blocks.insert(*hash) {
+                blue.score += 1;
             }
-            all_blocks.insert(*hash);
-
-            let mut blue = BlueSet::new();
-            blue.blocks = all_blocks;
-            blue.score = blue.blocks.len() as u64;
             self.blue_cache.write().await.insert(*hash, blue.clone());
             Ok(blue)
         })
@@ -302,6 +358,19 @@ impl GhostDag {
         // Calculate blue set
         let blue_set = self.calculate_blue_set(block).await?;
 
+        if !block.is_genesis() {
+            let red = block
+                .header
+                .merge_parent_hashes
+                .iter()
+                .filter(|parent| !blue_set.contains(parent))
+                .count();
+            metrics::record(MetricEvent::BlockColored {
+                blue: 1 + block.header.merge_parent_hashes.len() - red,
+                red,
+            });
+        }
+
         // Create DAG relation
         let relation = DagRelation {
             block: block.hash(),
@@ -338,15 +407,169 @@ impl GhostDag {
 
         // Add new block as tip
         tips.insert(block.hash());
+        drop(tips);
 
         info!(
             "Added block {} to DAG with blue score {}",
             block.hash(),
             blue_set.score
         );
+
+        self.maybe_advance_pruning_point(&block.hash(), blue_set.score)
+            .await;
         Ok(())
     }
 
+    /// Advance the pruning point once the DAG has grown a full pruning window
+    /// past it, then compact everything below the new point.
+    ///
+    /// Compaction runs at most once per window, so the walk back along the
+    /// selected chain is amortized to O(1) per block and memory stays bounded
+    /// by roughly two windows of blocks.
+    async fn maybe_advance_pruning_point(&self, tip: &Hash, tip_score: u64) {
+        let window = self.params.pruning_window;
+        if window == 0 || tip_score <= window {
+            return;
+        }
+
+        let current_score = self
+            .pruning_point
+            .read()
+            .await
+            .map(|p| p.blue_score)
+            .unwrap_or(0);
+        if tip_score < current_score + 2 * window {
+            return;
+        }
+
+        // Walk the selected-parent chain back to the deepest block that is
+        // still at least `window` blue blocks behind the tip
+        let target_score = tip_score - window;
+        let new_point = {
+            let relations = self.relations.read().await;
+            let mut current = *tip;
+            loop {
+                let Some(relation) = relations.get(&current) else {
+                    return;
+                };
+                if relation.blue_set.score <= target_score {
+                    break PruningPoint {
+                        hash: current,
+                        blue_score: relation.blue_set.score,
+                    };
+                }
+                current = relation.selected_parent;
+            }
+        };
+
+        if new_point.blue_score <= current_score {
+            return;
+        }
+
+        self.prune_below(new_point).await;
+    }
+
+    /// Finalize `pruning_point` and drop bookkeeping for every block with a
+    /// lower blue score (its past and the anticone below it).
+    ///
+    /// Blue scores strictly increase along parent edges, so any ancestry path
+    /// between two retained blocks only passes through retained blocks and
+    /// ancestry queries above the pruning point are unaffected.
+    pub async fn prune_below(&self, pruning_point: PruningPoint) {
+        let mut relations = self.relations.write().await;
+        let mut blue_cache = self.blue_cache.write().await;
+
+        let mut pruned: HashSet<Hash> = relations
+            .iter()
+            .filter(|(_, r)| r.blue_set.score < pruning_point.blue_score)
+            .map(|(h, _)| *h)
+            .collect();
+        pruned.extend(
+            blue_cache
+                .iter()
+                .filter(|(_, b)| b.score < pruning_point.blue_score)
+                .map(|(h, _)| *h),
+        );
+        pruned.remove(&pruning_point.hash);
+
+        if pruned.is_empty() {
+            *self.pruning_point.write().await = Some(pruning_point);
+            return;
+        }
+
+        relations.retain(|h, _| !pruned.contains(h));
+        blue_cache.retain(|h, _| !pruned.contains(h));
+
+        for relation in relations.values_mut() {
+            relation.blue_set.blocks.retain(|h| !pruned.contains(h));
+            relation.blue_set.blocks.shrink_to_fit();
+        }
+        for blue_set in blue_cache.values_mut() {
+            blue_set.blocks.retain(|h| !pruned.contains(h));
+            blue_set.blocks.shrink_to_fit();
+        }
+        relations.shrink_to_fit();
+        blue_cache.shrink_to_fit();
+        drop(blue_cache);
+        drop(relations);
+
+        self.tips.write().await.retain(|h| !pruned.contains(h));
+        *self.pruning_point.write().await = Some(pruning_point);
+        *self.pruned_blocks.write().await += pruned.len() as u64;
+
+        if let Err(e) = self
+            .dag_store
+            .update_pruning_point(pruning_point.hash)
+            .await
+        {
+            debug!("DAG store did not record pruning point: {}", e);
+        }
+
+        info!(
+            "Advanced pruning point to {} (blue score {}), compacted {} blocks",
+            pruning_point.hash,
+            pruning_point.blue_score,
+            pruned.len()
+        );
+    }
+
+    /// Check whether a block's bookkeeping was compacted below the pruning point
+    async fn is_below_pruning_point(&self, hash: &Hash) -> bool {
+        let Some(pruning_point) = *self.pruning_point.read().await else {
+            return false;
+        };
+        if self.blue_cache.read().await.contains_key(hash)
+            || self.relations.read().await.contains_key(hash)
+        {
+            return false;
+        }
+        match self.dag_store.get_block(hash).await {
+            Ok(block) => block.header.blue_score < pruning_point.blue_score,
+            Err(_) => false,
+        }
+    }
+
+    /// Get the current pruning point, if one has been established
+    pub async fn pruning_point(&self) -> Option<PruningPoint> {
+        *self.pruning_point.read().await
+    }
+
+    /// Report how much blue-set bookkeeping is held in memory
+    pub async fn memory_stats(&self) -> GhostDagMemoryStats {
+        let relations = self.relations.read().await;
+        let blue_cache = self.blue_cache.read().await;
+        GhostDagMemoryStats {
+            relations: relations.len(),
+            cached_blue_sets: blue_cache.len(),
+            blue_set_entries: relations
+                .values()
+                .map(|r| r.blue_set.size())
+                .chain(blue_cache.values().map(|b| b.size()))
+                .sum(),
+            pruned_blocks: *self.pruned_blocks.read().await,
+        }
+    }
+
     /// Select the best tip based on blue score
     pub async fn select_tip(&self) -> Result<Hash, GhostDagError> {
         let tips = self.tips.read().await;
@@ -413,6 +636,7 @@ mod tests {
                 base_fee_per_gas: 0,
                 gas_used: 0,
                 gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
             },
             state_root: Hash::default(),
             tx_root: Hash::default(),
@@ -585,4 +809,98 @@ mod tests {
         assert!(blue.contains(&d.hash()));
         assert!(blue.score >= 4);
     }
+
+    fn numbered_hash(n: u64, tag: u8) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes[..8].copy_from_slice(&n.to_le_bytes());
+        bytes[8] = tag;
+        bytes[31] = 1; // never collides with the all-zero default hash
+        bytes
+    }
+
+    /// Build a chain where every `merge_every` heights a side block is mined
+    /// next to the chain block and merged by the following one.
+    async fn extend_synthetic_dag(ghostdag: &GhostDag, blocks: u64, merge_every: u64) -> Hash {
+        let genesis = create_test_block_with_parents([0; 32], Hash::default(), vec![], 0);
+        ghostdag.add_block(&genesis).await.unwrap();
+
+        let mut tip = genesis.hash();
+        let mut pending_side = None;
+        for n in 1..blocks {
+            let merge_parents: Vec<Hash> = pending_side.take().into_iter().collect();
+            let block = create_test_block_with_parents(numbered_hash(n, 0), tip, merge_parents, n);
+            ghostdag.add_block(&block).await.unwrap();
+
+            if n % merge_every == 0 {
+                let side = create_test_block_with_parents(numbered_hash(n, 1), tip, vec![], n);
+                ghostdag.add_block(&side).await.unwrap();
+                pending_side = Some(side.hash());
+            }
+            tip = block.hash();
+        }
+        tip
+    }
+
+    #[tokio::test]
+    async fn test_pruning_preserves_blue_scores() {
+        let unpruned = GhostDag::new(
+            GhostDagParams {
+                pruning_window: 0,
+                ..Default::default()
+            },
+            Arc::new(DagStore::new()),
+        );
+        let pruned = GhostDag::new(
+            GhostDagParams {
+                pruning_window: 32,
+                ..Default::default()
+            },
+            Arc::new(DagStore::new()),
+        );
+
+        let tip = extend_synthetic_dag(&unpruned, 500, 7).await;
+        assert_eq!(extend_synthetic_dag(&pruned, 500, 7).await, tip);
+
+        assert_eq!(
+            pruned.get_blue_score(&tip).await.unwrap(),
+            unpruned.get_blue_score(&tip).await.unwrap()
+        );
+        assert_eq!(pruned.select_tip().await.unwrap(), tip);
+
+        let pruning_point = pruned.pruning_point().await.unwrap();
+        let tip_score = pruned.get_blue_score(&tip).await.unwrap();
+        assert!(tip_score - pruning_point.blue_score >= 32);
+        assert!(unpruned.pruning_point().await.is_none());
+
+        // Blocks below the pruning point are gone, the point itself is kept
+        let genesis = Hash::new([0; 32]);
+        assert!(pruned.get_blue_score(&genesis).await.is_err());
+        assert!(pruned.get_blue_score(&pruning_point.hash).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_pruning_bounds_memory_on_million_block_dag() {
+        let window = 8;
+        let ghostdag = GhostDag::new(
+            GhostDagParams {
+                pruning_window: window,
+                ..Default::default()
+            },
+            Arc::new(DagStore::new()),
+        );
+
+        extend_synthetic_dag(&ghostdag, 1_000_000, 1_000).await;
+
+        let stats = ghostdag.memory_stats().await;
+        let bound = 2 * window as usize + 8;
+        assert!(stats.relations <= bound, "relations: {}", stats.relations);
+        assert!(
+            stats.cached_blue_sets <= bound,
+            "cached blue sets: {}",
+            stats.cached_blue_sets
+        );
+        // Each retained blue set only references blocks above the pruning point
+        assert!(stats.blue_set_entries <= 2 * bound * bound);
+        assert!(stats.pruned_blocks >= 1_000_000 - bound as u64);
+    }
 }
@@ -1,20 +1,35 @@
 // citrate/core/consensus/src/lib.rs
 
 pub mod chain_selection;
+pub mod checkpoint;
 pub mod crypto;
 pub mod dag_store;
+pub mod epoch;
 pub mod finality;
 pub mod ghostdag;
+pub mod limits;
+pub mod metrics;
 pub mod ordering;
 pub mod tip_selection;
 pub mod types;
 pub mod vrf;
 
 pub use chain_selection::{ChainSelectionError, ChainSelector, ChainState, ReorgEvent};
+pub use checkpoint::{
+    CheckpointConfig, CheckpointError, CheckpointPool, EnforcedCheckpoint, FinalityCheckpoint,
+};
 pub use dag_store::{DagStats, DagStore, DagStoreError};
+pub use epoch::{
+    validator_set_hash, EpochConfig, EpochError, EpochSummary, EpochSummaryPool, EpochVote,
+    SignedEpochSummary,
+};
 pub use finality::{FinalityConfig, FinalityError, FinalityEvent, FinalityStatus, FinalityTracker};
-pub use ghostdag::{GhostDag, GhostDagError};
-pub use ordering::{OrderedBlockRange, OrderingError, TotalOrdering, TransactionRef};
+pub use ghostdag::{GhostDag, GhostDagError, GhostDagMemoryStats, PruningPoint};
+pub use limits::{BlockLimitError, BlockLimits, BlockUsage};
+pub use ordering::{
+    ConflictDetector, ConflictKind, ConflictStats, OrderedBlockRange, OrderingError, TotalOrdering,
+    TransactionConflict, TransactionRef,
+};
 pub use tip_selection::{ParentSelector, SelectionStrategy, TipSelectionError, TipSelector};
 pub use types::*;
 pub use vrf::{LeaderElection, Validator, VrfError, VrfProposerSelector};
@@ -0,0 +1,310 @@
+// citrate/core/consensus/src/limits.rs
+
+//! Per-block size limits
+//!
+//! Besides gas, a block is bounded by the bytes of its transactions, by the
+//! bytes of model metadata it carries and by the number of required model
+//! pins it declares. Model deployments and updates carry their metadata in
+//! the transaction payload, so without a separate budget one model-heavy
+//! block could fill the whole byte limit and take far longer to propagate
+//! and verify than its gas suggests.
+//!
+//! The limits are checked by the block builder when filling a block and by
+//! every node when accepting one. Governance can adjust each value by
+//! writing a little-endian integer under `PARAM:<name>` in the parameter
+//! store; see [`BlockLimits::with_overrides`]. The genesis block is exempt,
+//! since its embedded models are fixed by the chain configuration.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::types::{Block, EmbeddedModel, Transaction, TransactionType};
+
+/// Governance parameter for [`BlockLimits::max_tx_bytes`]
+pub const PARAM_MAX_TX_BYTES: &str = "max_block_tx_bytes";
+
+/// Governance parameter for [`BlockLimits::max_model_metadata_bytes`]
+pub const PARAM_MAX_MODEL_METADATA_BYTES: &str = "max_block_model_metadata_bytes";
+
+/// Governance parameter for [`BlockLimits::max_required_pins`]
+pub const PARAM_MAX_REQUIRED_PINS: &str = "max_block_required_pins";
+
+/// Limits on the contents of a single block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockLimits {
+    /// Total encoded size of the block's transactions
+    pub max_tx_bytes: usize,
+    /// Bytes of model metadata, counted within `max_tx_bytes`
+    pub max_model_metadata_bytes: usize,
+    /// Required model pin entries declared by the block
+    pub max_required_pins: usize,
+}
+
+impl Default for BlockLimits {
+    fn default() -> Self {
+        Self {
+            max_tx_bytes: 1_000_000,              // 1 MB
+            max_model_metadata_bytes: 256 * 1024, // 256 KB
+            max_required_pins: 16,
+        }
+    }
+}
+
+/// How much of each limit a block uses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockUsage {
+    pub tx_bytes: usize,
+    pub model_metadata_bytes: usize,
+    pub required_pins: usize,
+}
+
+impl BlockUsage {
+    /// Usage of `block`
+    pub fn of(block: &Block) -> Self {
+        let mut usage = Self {
+            required_pins: block.required_pins.len(),
+            model_metadata_bytes: block.embedded_models.iter().map(embedded_model_bytes).sum(),
+            ..Self::default()
+        };
+        for tx in &block.transactions {
+            usage.add(tx);
+        }
+        usage
+    }
+
+    /// Count `tx` towards this usage
+    pub fn add(&mut self, tx: &Transaction) {
+        self.tx_bytes += transaction_bytes(tx);
+        self.model_metadata_bytes += model_metadata_bytes(tx);
+    }
+}
+
+/// A block over one of its limits
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BlockLimitError {
+    #[error("transactions take {used} bytes, limit is {limit}")]
+    TxBytes { used: usize, limit: usize },
+
+    #[error("model metadata takes {used} bytes, limit is {limit}")]
+    ModelMetadataBytes { used: usize, limit: usize },
+
+    #[error("{used} required model pins, limit is {limit}")]
+    RequiredPins { used: usize, limit: usize },
+}
+
+impl BlockLimits {
+    /// These limits with each one that `lookup` returns a value for
+    /// replaced. `lookup` is given a governance parameter name and returns
+    /// the raw stored value: a little-endian `u64`, or `u32` when shorter.
+    /// Zero and malformed values are ignored.
+    pub fn with_overrides(mut self, lookup: impl Fn(&str) -> Option<Vec<u8>>) -> Self {
+        let read = |name: &str| lookup(name).and_then(|bytes| decode_param(&bytes));
+        if let Some(value) = read(PARAM_MAX_TX_BYTES) {
+            self.max_tx_bytes = value;
+        }
+        if let Some(value) = read(PARAM_MAX_MODEL_METADATA_BYTES) {
+            self.max_model_metadata_bytes = value;
+        }
+        if let Some(value) = read(PARAM_MAX_REQUIRED_PINS) {
+            self.max_required_pins = value;
+        }
+        self
+    }
+
+    /// Whether `usage` is within every limit
+    pub fn allows(&self, usage: &BlockUsage) -> bool {
+        self.check_usage(usage).is_ok()
+    }
+
+    /// Check `block` against the limits. The genesis block always passes.
+    pub fn check(&self, block: &Block) -> Result<(), BlockLimitError> {
+        if block.is_genesis() {
+            return Ok(());
+        }
+        self.check_usage(&BlockUsage::of(block))
+    }
+
+    fn check_usage(&self, usage: &BlockUsage) -> Result<(), BlockLimitError> {
+        if usage.tx_bytes > self.max_tx_bytes {
+            return Err(BlockLimitError::TxBytes {
+                used: usage.tx_bytes,
+                limit: self.max_tx_bytes,
+            });
+        }
+        if usage.model_metadata_bytes > self.max_model_metadata_bytes {
+            return Err(BlockLimitError::ModelMetadataBytes {
+                used: usage.model_metadata_bytes,
+                limit: self.max_model_metadata_bytes,
+            });
+        }
+        if usage.required_pins > self.max_required_pins {
+            return Err(BlockLimitError::RequiredPins {
+                used: usage.required_pins,
+                limit: self.max_required_pins,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Encoded size of a transaction: fixed fields, payload and signature
+pub fn transaction_bytes(tx: &Transaction) -> usize {
+    32 + 8 + 32 + 32 + 16 + 8 + 8 + tx.data.len() + 64
+}
+
+/// Bytes of model metadata a transaction carries: the whole payload of a
+/// model deployment, model update or LoRA adapter transaction
+pub fn model_metadata_bytes(tx: &Transaction) -> usize {
+    match TransactionType::from_data(&tx.data) {
+        TransactionType::ModelDeploy
+        | TransactionType::ModelUpdate
+        | TransactionType::LoraAdapter => tx.data.len(),
+        _ => 0,
+    }
+}
+
+fn embedded_model_bytes(model: &EmbeddedModel) -> usize {
+    let metadata = &model.metadata;
+    model.weights.len()
+        + metadata.name.len()
+        + metadata.version.len()
+        + metadata.license.len()
+        + metadata.framework.as_ref().map_or(0, String::len)
+}
+
+fn decode_param(bytes: &[u8]) -> Option<usize> {
+    let value = if bytes.len() >= 8 {
+        u64::from_le_bytes(bytes[..8].try_into().ok()?)
+    } else if bytes.len() >= 4 {
+        u32::from_le_bytes(bytes[..4].try_into().ok()?) as u64
+    } else {
+        return None;
+    };
+    usize::try_from(value).ok().filter(|&v| v > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{
+        BlockHeader, GhostDagParams, Hash, ModelId, PublicKey, RequiredModel, Signature, VrfProof,
+    };
+
+    fn tx(data: Vec<u8>) -> Transaction {
+        Transaction {
+            hash: Hash::default(),
+            nonce: 0,
+            from: PublicKey::new([1; 32]),
+            to: None,
+            value: 0,
+            gas_limit: 21_000,
+            gas_price: 1,
+            data,
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        }
+    }
+
+    fn block(height: u64, transactions: Vec<Transaction>) -> Block {
+        Block {
+            header: BlockHeader {
+                version: 1,
+                block_hash: Hash::new([height as u8 + 1; 32]),
+                selected_parent_hash: Hash::new([height as u8; 32]),
+                merge_parent_hashes: vec![],
+                timestamp: height,
+                height,
+                blue_score: height,
+                blue_work: height as u128,
+                pruning_point: Hash::default(),
+                proposer_pubkey: PublicKey::new([1; 32]),
+                vrf_reveal: VrfProof {
+                    proof: vec![],
+                    output: Hash::default(),
+                },
+                base_fee_per_gas: 0,
+                gas_used: 0,
+                gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
+            },
+            state_root: Hash::default(),
+            tx_root: Hash::default(),
+            receipt_root: Hash::default(),
+            artifact_root: Hash::default(),
+            ghostdag_params: GhostDagParams::default(),
+            transactions,
+            signature: Signature::new([0; 64]),
+            embedded_models: vec![],
+            required_pins: vec![],
+        }
+    }
+
+    fn model_deploy(payload: usize) -> Transaction {
+        let mut data = vec![0x01, 0x00, 0x00, 0x00];
+        data.resize(payload, 0xab);
+        tx(data)
+    }
+
+    #[test]
+    fn test_model_metadata_budget_is_separate_from_tx_bytes() {
+        let limits = BlockLimits {
+            max_tx_bytes: 10_000,
+            max_model_metadata_bytes: 1_000,
+            max_required_pins: 1,
+        };
+
+        let light = block(1, vec![tx(vec![0; 4_000]), model_deploy(900)]);
+        assert!(limits.check(&light).is_ok());
+        assert_eq!(BlockUsage::of(&light).model_metadata_bytes, 900);
+
+        let heavy = block(2, vec![model_deploy(600), model_deploy(600)]);
+        assert_eq!(
+            limits.check(&heavy),
+            Err(BlockLimitError::ModelMetadataBytes {
+                used: 1_200,
+                limit: 1_000
+            })
+        );
+
+        let big = block(3, vec![tx(vec![0; 9_900])]);
+        assert!(matches!(
+            limits.check(&big),
+            Err(BlockLimitError::TxBytes { limit: 10_000, .. })
+        ));
+
+        let mut pinned = block(4, vec![]);
+        let pin = RequiredModel::new(
+            ModelId::from_name("pin"),
+            "Qm".to_string(),
+            Hash::default(),
+            1,
+            0,
+        );
+        pinned.required_pins = vec![pin.clone(), pin];
+        assert!(matches!(
+            limits.check(&pinned),
+            Err(BlockLimitError::RequiredPins { used: 2, limit: 1 })
+        ));
+
+        // Genesis is fixed by the chain configuration
+        let genesis = block(0, vec![model_deploy(5_000)]);
+        assert!(genesis.is_genesis());
+        assert!(limits.check(&genesis).is_ok());
+    }
+
+    #[test]
+    fn test_governance_overrides() {
+        let limits = BlockLimits::default().with_overrides(|name| match name {
+            PARAM_MAX_TX_BYTES => Some(2_000_000u64.to_le_bytes().to_vec()),
+            PARAM_MAX_REQUIRED_PINS => Some(4u32.to_le_bytes().to_vec()),
+            PARAM_MAX_MODEL_METADATA_BYTES => Some(vec![0; 8]),
+            _ => None,
+        });
+        assert_eq!(limits.max_tx_bytes, 2_000_000);
+        assert_eq!(limits.max_required_pins, 4);
+        assert_eq!(
+            limits.max_model_metadata_bytes,
+            BlockLimits::default().max_model_metadata_bytes
+        );
+    }
+}
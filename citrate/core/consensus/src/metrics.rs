@@ -0,0 +1,52 @@
+// citrate/core/consensus/src/metrics.rs
+
+//! Metric events reported by core modules
+//!
+//! The Prometheus registry lives in the API crate, which depends on the
+//! mempool, sync and consensus code rather than the other way round. Core
+//! modules therefore report through a process-wide recorder that the node
+//! installs at startup; until then events are dropped.
+
+use std::sync::OnceLock;
+
+/// Something a metrics backend may want to count or time
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetricEvent {
+    /// A transaction left the mempool without being included
+    MempoolEviction { reason: &'static str },
+    /// Headers or blocks arrived from a sync peer
+    SyncReceived { kind: &'static str, count: usize },
+    /// Header and block requests awaiting a response
+    SyncPending { headers: usize, blocks: usize },
+    /// A block was added to the DAG; its selected parent and blue merge
+    /// parents are blue, the remaining merge parents red
+    BlockColored { blue: usize, red: usize },
+    /// The selected chain switched tips, unwinding `depth` blocks
+    Reorg { depth: u64 },
+    /// The local producer built and stored a block
+    BlockBuilt { seconds: f64 },
+    /// A frame of `bytes` carrying a `kind` message was sent ("out") or
+    /// received ("in") over a peer connection
+    NetworkBytes {
+        direction: &'static str,
+        kind: &'static str,
+        bytes: usize,
+    },
+    /// Gossip dropped an announcement it had already handled
+    GossipDuplicate { kind: &'static str },
+}
+
+static RECORDER: OnceLock<fn(&MetricEvent)> = OnceLock::new();
+
+/// Install the process-wide recorder; returns `false` if one is already set
+pub fn set_recorder(recorder: fn(&MetricEvent)) -> bool {
+    RECORDER.set(recorder).is_ok()
+}
+
+/// Report an event to the installed recorder, if any
+#[inline]
+pub fn record(event: MetricEvent) {
+    if let Some(recorder) = RECORDER.get() {
+        recorder(&event);
+    }
+}
@@ -12,10 +12,16 @@
 //! 5. Each block is yielded exactly once (first occurrence wins)
 //!
 //! This ensures all nodes produce identical orderings for the same DAG state.
+//!
+//! Parallel blocks are built from divergent mempools, so the same transaction or
+//! two transactions spending the same sender nonce can appear in more than one
+//! block. When the transaction order is built, the first occurrence in total
+//! order wins and later ones are reported as conflicts and left out.
 
 use crate::dag_store::DagStore;
 use crate::ghostdag::GhostDag;
-use crate::types::Hash;
+use crate::types::{Block, Hash, PublicKey};
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use thiserror::Error;
@@ -42,9 +48,15 @@ pub struct OrderedBlockRange {
     /// Blocks in total order (oldest first)
     pub blocks: Vec<Hash>,
 
-    /// Transactions in total order (for execution)
+    /// Transactions in total order (for execution), with conflicts removed
     pub transaction_order: Vec<TransactionRef>,
 
+    /// Transactions dropped because an earlier ordered transaction conflicts
+    pub conflicts: Vec<TransactionConflict>,
+
+    /// Conflict statistics for this range
+    pub conflict_stats: ConflictStats,
+
     /// Starting block hash
     pub from: Hash,
 
@@ -53,7 +65,7 @@ pub struct OrderedBlockRange {
 }
 
 /// Reference to a transaction within a block
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TransactionRef {
     /// Block containing the transaction
     pub block_hash: Hash,
@@ -65,6 +77,134 @@ pub struct TransactionRef {
     pub tx_hash: Hash,
 }
 
+/// Kind of conflict between transactions included by parallel blocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConflictKind {
+    /// The same transaction was included more than once
+    DuplicateTransaction,
+    /// A different transaction already spent this sender nonce (double-spend)
+    NonceConflict,
+}
+
+/// A transaction dropped from the total order in favour of an earlier one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionConflict {
+    pub kind: ConflictKind,
+    /// Transaction that keeps its place in the order
+    pub kept: TransactionRef,
+    /// Transaction left out of the order
+    pub dropped: TransactionRef,
+}
+
+/// Conflict statistics over an ordered range of blocks
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConflictStats {
+    pub blocks_scanned: u64,
+    pub transactions_scanned: u64,
+    pub duplicate_transactions: u64,
+    pub nonce_conflicts: u64,
+    /// Blocks containing at least one dropped transaction
+    pub conflicting_blocks: u64,
+}
+
+impl ConflictStats {
+    /// Total number of dropped transactions
+    pub fn total_conflicts(&self) -> u64 {
+        self.duplicate_transactions + self.nonce_conflicts
+    }
+}
+
+/// Detects conflicting transactions across blocks fed in total order.
+///
+/// Detection only depends on the order blocks are fed in, so every node that
+/// agrees on the total order drops exactly the same transactions.
+#[derive(Debug, Default)]
+pub struct ConflictDetector {
+    seen_hashes: HashMap<Hash, TransactionRef>,
+    seen_nonces: HashMap<(PublicKey, u64), TransactionRef>,
+    conflicts: Vec<TransactionConflict>,
+    stats: ConflictStats,
+}
+
+impl ConflictDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scan the next block in total order, returning its non-conflicting
+    /// transactions in block order
+    pub fn scan_block(&mut self, block: &Block) -> Vec<TransactionRef> {
+        let block_hash = block.hash();
+        let mut accepted = Vec::with_capacity(block.transactions.len());
+        let mut block_conflicts = false;
+
+        self.stats.blocks_scanned += 1;
+
+        for (tx_index, tx) in block.transactions.iter().enumerate() {
+            self.stats.transactions_scanned += 1;
+            let tx_ref = TransactionRef {
+                block_hash,
+                tx_index,
+                tx_hash: tx.hash,
+            };
+
+            let conflict = if let Some(kept) = self.seen_hashes.get(&tx.hash) {
+                Some((ConflictKind::DuplicateTransaction, kept.clone()))
+            } else {
+                self.seen_nonces
+                    .get(&(tx.from, tx.nonce))
+                    .map(|kept| (ConflictKind::NonceConflict, kept.clone()))
+            };
+
+            match conflict {
+                Some((kind, kept)) => {
+                    match kind {
+                        ConflictKind::DuplicateTransaction => {
+                            self.stats.duplicate_transactions += 1
+                        }
+                        ConflictKind::NonceConflict => self.stats.nonce_conflicts += 1,
+                    }
+                    debug!(
+                        "Dropping {:?} transaction {} in block {} (kept {} in block {})",
+                        kind, tx.hash, block_hash, kept.tx_hash, kept.block_hash
+                    );
+                    self.conflicts.push(TransactionConflict {
+                        kind,
+                        kept,
+                        dropped: tx_ref,
+                    });
+                    block_conflicts = true;
+                }
+                None => {
+                    self.seen_hashes.insert(tx.hash, tx_ref.clone());
+                    self.seen_nonces.insert((tx.from, tx.nonce), tx_ref.clone());
+                    accepted.push(tx_ref);
+                }
+            }
+        }
+
+        if block_conflicts {
+            self.stats.conflicting_blocks += 1;
+        }
+        accepted
+    }
+
+    /// Conflicts found so far, in detection order
+    pub fn conflicts(&self) -> &[TransactionConflict] {
+        &self.conflicts
+    }
+
+    /// Statistics over all blocks scanned so far
+    pub fn stats(&self) -> &ConflictStats {
+        &self.stats
+    }
+
+    /// Consume the detector, returning the conflicts and statistics
+    pub fn finish(self) -> (Vec<TransactionConflict>, ConflictStats) {
+        (self.conflicts, self.stats)
+    }
+}
+
 /// Block ordering metadata for sorting
 #[derive(Debug, Clone)]
 struct BlockOrderingInfo {
@@ -361,8 +501,9 @@ impl TotalOrdering {
         // Slice the relevant portion
         let blocks: Vec<Hash> = full_order[from_idx..].to_vec();
 
-        // Build transaction order
+        // Build transaction order, dropping conflicts between parallel blocks
         let mut transaction_order = Vec::new();
+        let mut detector = ConflictDetector::new();
         for block_hash in &blocks {
             let block = self
                 .dag_store
@@ -370,19 +511,15 @@ impl TotalOrdering {
                 .await
                 .map_err(|_| OrderingError::BlockNotFound(*block_hash))?;
 
-            for (tx_index, tx) in block.transactions.iter().enumerate() {
-                transaction_order.push(TransactionRef {
-                    block_hash: *block_hash,
-                    tx_index,
-                    tx_hash: tx.hash,
-                });
-            }
+            transaction_order.extend(detector.scan_block(&block));
         }
+        let (conflicts, conflict_stats) = detector.finish();
 
         debug!(
-            "Ordered {} blocks with {} transactions from {} to {}",
+            "Ordered {} blocks with {} transactions ({} conflicts) from {} to {}",
             blocks.len(),
             transaction_order.len(),
+            conflicts.len(),
             from,
             to
         );
@@ -390,6 +527,8 @@ impl TotalOrdering {
         Ok(OrderedBlockRange {
             blocks,
             transaction_order,
+            conflicts,
+            conflict_stats,
             from,
             to,
         })
@@ -459,6 +598,7 @@ mod tests {
                 base_fee_per_gas: 0,
                 gas_used: 0,
                 gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
             },
             state_root: Hash::default(),
             tx_root: Hash::default(),
@@ -668,4 +808,126 @@ mod tests {
         assert_eq!(tx_order[1].tx_hash, tx2.hash);
         assert_eq!(tx_order[1].block_hash, block_a.hash());
     }
+
+    fn create_test_tx(tag: u8, from: u8, nonce: u64) -> Transaction {
+        Transaction {
+            hash: Hash::new([tag; 32]),
+            nonce,
+            from: PublicKey::new([from; 32]),
+            to: Some(PublicKey::new([0xEE; 32])),
+            value: 100,
+            gas_limit: 21000,
+            gas_price: 1,
+            data: vec![],
+            signature: Signature::new([0; 64]),
+            tx_type: Some(TransactionType::Standard),
+        }
+    }
+
+    /// Two miners build parallel blocks from divergent mempools:
+    ///
+    ///     G
+    ///    / \
+    ///   A   B      A = [t1, t2], B = [t1 (duplicate), t2' (same nonce as t2), t3]
+    ///    \ /
+    ///     C (selects A, merges B)
+    async fn build_divergent_dag(store_b_first: bool) -> (Arc<DagStore>, Block, Block, Block) {
+        let dag_store = Arc::new(DagStore::new());
+
+        let t1 = create_test_tx(0x11, 1, 0);
+        let t2 = create_test_tx(0x22, 2, 5);
+        let t2_double_spend = create_test_tx(0x23, 2, 5);
+        let t3 = create_test_tx(0x33, 3, 0);
+
+        let genesis = create_test_block([0xFF; 32], Hash::default(), vec![], 0, 1);
+        let mut block_a = create_test_block([1; 32], genesis.hash(), vec![], 1, 2);
+        block_a.transactions = vec![t1.clone(), t2];
+        let mut block_b = create_test_block([2; 32], genesis.hash(), vec![], 1, 2);
+        block_b.transactions = vec![t1, t2_double_spend, t3];
+        let block_c = create_test_block([3; 32], block_a.hash(), vec![block_b.hash()], 2, 4);
+
+        dag_store.store_block(genesis.clone()).await.unwrap();
+        if store_b_first {
+            dag_store.store_block(block_b.clone()).await.unwrap();
+            dag_store.store_block(block_a.clone()).await.unwrap();
+        } else {
+            dag_store.store_block(block_a.clone()).await.unwrap();
+            dag_store.store_block(block_b.clone()).await.unwrap();
+        }
+        dag_store.store_block(block_c.clone()).await.unwrap();
+
+        (dag_store, block_a, block_b, block_c)
+    }
+
+    #[tokio::test]
+    async fn test_conflicts_between_parallel_blocks_are_dropped() {
+        let (dag_store, block_a, block_b, block_c) = build_divergent_dag(false).await;
+        let ghostdag = Arc::new(GhostDag::new(GhostDagParams::default(), dag_store.clone()));
+        let ordering = TotalOrdering::new(dag_store, ghostdag);
+
+        let range = ordering
+            .get_ordered_blocks(Hash::default(), block_c.hash())
+            .await
+            .unwrap();
+
+        // A is on the selected chain, so its transactions win
+        let ordered: Vec<Hash> = range.transaction_order.iter().map(|t| t.tx_hash).collect();
+        assert_eq!(
+            ordered,
+            vec![
+                Hash::new([0x11; 32]),
+                Hash::new([0x22; 32]),
+                Hash::new([0x33; 32])
+            ]
+        );
+
+        assert_eq!(range.conflicts.len(), 2);
+        assert_eq!(range.conflicts[0].kind, ConflictKind::DuplicateTransaction);
+        assert_eq!(range.conflicts[0].kept.block_hash, block_a.hash());
+        assert_eq!(range.conflicts[0].dropped.block_hash, block_b.hash());
+        assert_eq!(range.conflicts[1].kind, ConflictKind::NonceConflict);
+        assert_eq!(range.conflicts[1].dropped.tx_hash, Hash::new([0x23; 32]));
+
+        assert_eq!(range.conflict_stats.blocks_scanned, 4);
+        assert_eq!(range.conflict_stats.transactions_scanned, 5);
+        assert_eq!(range.conflict_stats.duplicate_transactions, 1);
+        assert_eq!(range.conflict_stats.nonce_conflicts, 1);
+        assert_eq!(range.conflict_stats.conflicting_blocks, 1);
+        assert_eq!(range.conflict_stats.total_conflicts(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_conflict_detection_is_independent_of_arrival_order() {
+        let mut results = Vec::new();
+        for store_b_first in [false, true] {
+            let (dag_store, _, _, block_c) = build_divergent_dag(store_b_first).await;
+            let ghostdag = Arc::new(GhostDag::new(GhostDagParams::default(), dag_store.clone()));
+            let ordering = TotalOrdering::new(dag_store, ghostdag);
+            results.push(
+                ordering
+                    .get_ordered_blocks(Hash::default(), block_c.hash())
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        assert_eq!(results[0].transaction_order, results[1].transaction_order);
+        assert_eq!(results[0].conflicts, results[1].conflicts);
+        assert_eq!(results[0].conflict_stats, results[1].conflict_stats);
+    }
+
+    #[test]
+    fn test_detector_flags_duplicates_within_one_block() {
+        let mut block = create_test_block([7; 32], Hash::new([1; 32]), vec![], 1, 1);
+        let tx = create_test_tx(0x44, 4, 0);
+        block.transactions = vec![tx.clone(), tx];
+
+        let mut detector = ConflictDetector::new();
+        let accepted = detector.scan_block(&block);
+
+        assert_eq!(accepted.len(), 1);
+        assert_eq!(accepted[0].tx_index, 0);
+        assert_eq!(detector.conflicts()[0].dropped.tx_index, 1);
+        assert_eq!(detector.stats().duplicate_transactions, 1);
+    }
 }
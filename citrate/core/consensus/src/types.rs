@@ -160,6 +160,11 @@ pub struct BlockHeader {
     /// Block gas limit. Default is 30M gas.
     #[serde(default = "default_gas_limit")]
     pub gas_limit: u64,
+
+    /// Commitment of the latest epoch summary signed by a validator quorum
+    /// (see [`crate::epoch`]). Zero before the first summary is signed.
+    #[serde(default)]
+    pub epoch_commitment: Hash,
 }
 
 fn default_gas_limit() -> u64 {
@@ -585,6 +590,7 @@ mod tests {
                 base_fee_per_gas: 0,
                 gas_used: 0,
                 gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
             },
             state_root: Hash::default(),
             tx_root: Hash::default(),
@@ -23,6 +23,27 @@ pub enum VrfError {
     CryptoError(String),
 }
 
+/// Check that a VRF proof is well formed and its output is derived from
+/// the proof bytes. Needs no validator state, so it can run during sync.
+pub fn proof_output_matches(proof: &VrfProof) -> bool {
+    if proof.proof.len() != 32 {
+        return false;
+    }
+
+    let mut output_hasher = Sha3_256::new();
+    output_hasher.update(&proof.proof);
+    proof.output == Hash::from_bytes(&output_hasher.finalize())
+}
+
+/// The VRF input for a block at `slot` following a block whose VRF output
+/// was `previous_vrf`. A proposer proves over this challenge.
+pub fn vrf_challenge(previous_vrf: &Hash, slot: u64) -> Hash {
+    let mut hasher = Sha3_256::new();
+    hasher.update(previous_vrf.as_bytes());
+    hasher.update(slot.to_le_bytes());
+    Hash::from_bytes(&hasher.finalize())
+}
+
 /// Validator information for VRF
 #[derive(Debug, Clone)]
 pub struct Validator {
@@ -47,7 +68,7 @@ impl VrfProposerSelector {
         }
     }
 
-    /// Register a validator
+    /// Register a validator, replacing any existing entry for the same key
     pub async fn register_validator(&self, validator: Validator) {
         let mut validators = self.validators.write().await;
         let mut total_stake = self.total_stake.write().await;
@@ -56,7 +77,11 @@ impl VrfProposerSelector {
             *total_stake += validator.stake;
         }
 
-        validators.insert(validator.pubkey, validator.clone());
+        if let Some(previous) = validators.insert(validator.pubkey, validator.clone()) {
+            if previous.is_active {
+                *total_stake = total_stake.saturating_sub(previous.stake);
+            }
+        }
         info!("Registered validator with stake {}", validator.stake);
     }
 
@@ -83,15 +108,12 @@ impl VrfProposerSelector {
         slot: u64,
     ) -> Result<VrfProof, VrfError> {
         // Create input for VRF
-        let mut hasher = Sha3_256::new();
-        hasher.update(previous_vrf.as_bytes());
-        hasher.update(slot.to_le_bytes());
-        let input = hasher.finalize();
+        let input = vrf_challenge(previous_vrf, slot);
 
         // Generate VRF proof (simplified - in production use proper VRF like ECVRF)
         let mut proof_hasher = Sha3_256::new();
         proof_hasher.update(secret_key);
-        proof_hasher.update(input);
+        proof_hasher.update(input.as_bytes());
         let proof_bytes = proof_hasher.finalize();
 
         // Generate VRF output
@@ -114,23 +136,10 @@ impl VrfProposerSelector {
         slot: u64,
     ) -> Result<bool, VrfError> {
         // Create expected input
-        let mut hasher = Sha3_256::new();
-        hasher.update(previous_vrf.as_bytes());
-        hasher.update(slot.to_le_bytes());
-        let _input = hasher.finalize();
+        let _input = vrf_challenge(previous_vrf, slot);
 
-        // Verify proof matches expected format
         // In production, use proper VRF verification
-        if proof.proof.len() != 32 {
-            return Ok(false);
-        }
-
-        // Verify output matches proof
-        let mut output_hasher = Sha3_256::new();
-        output_hasher.update(&proof.proof);
-        let expected_output = Hash::from_bytes(&output_hasher.finalize());
-
-        Ok(proof.output == expected_output)
+        Ok(proof_output_matches(proof))
     }
 
     /// Check if a validator is eligible to propose for a slot
@@ -196,7 +205,7 @@ impl VrfProposerSelector {
 
         // Each validator computes their VRF and the lowest wins
         for (pubkey, validator) in validators.iter() {
-            if !validator.is_active {
+            if !validator.is_active || validator.stake == 0 {
                 continue;
             }
 
@@ -209,8 +218,9 @@ impl VrfProposerSelector {
 
             let vrf_value = self.vrf_output_to_float(&vrf_output);
 
-            // Weight by stake
-            let weighted_value = vrf_value / (validator.stake as f64).sqrt();
+            // Exponential race: -ln(u) / stake is lowest for a validator with
+            // probability proportional to its share of the total stake
+            let weighted_value = -vrf_value.max(f64::MIN_POSITIVE).ln() / validator.stake as f64;
 
             if weighted_value < best_vrf_value {
                 best_vrf_value = weighted_value;
@@ -373,6 +383,42 @@ mod tests {
         assert!(proposer.is_some());
     }
 
+    #[tokio::test]
+    async fn test_proposer_selection_is_stake_weighted() {
+        let selector = VrfProposerSelector::new();
+        let light = PublicKey::new([1; 32]);
+        let heavy = PublicKey::new([2; 32]);
+        for (pubkey, stake) in [(light, 1000), (heavy, 3000)] {
+            selector
+                .register_validator(Validator {
+                    pubkey,
+                    stake,
+                    is_active: true,
+                })
+                .await;
+        }
+
+        // Re-registering replaces the previous stake instead of adding to it
+        selector
+            .register_validator(Validator {
+                pubkey: light,
+                stake: 1000,
+                is_active: true,
+            })
+            .await;
+        assert_eq!(selector.total_stake().await, 4000);
+
+        let previous_vrf = Hash::new([7; 32]);
+        let mut heavy_wins = 0;
+        for slot in 0..4000 {
+            if selector.select_proposer(slot, &previous_vrf).await.unwrap() == Some(heavy) {
+                heavy_wins += 1;
+            }
+        }
+        // Expected share is 75%
+        assert!((2800..3200).contains(&heavy_wins), "heavy won {heavy_wins}");
+    }
+
     #[tokio::test]
     async fn test_leader_election() {
         let vrf_selector = Arc::new(VrfProposerSelector::new());
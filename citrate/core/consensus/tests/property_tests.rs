@@ -45,6 +45,7 @@ fn create_block_with_params(
             base_fee_per_gas: 0,
             gas_used: 0,
             gas_limit: 30_000_000,
+            epoch_commitment: Hash::default(),
         },
         state_root: Hash::default(),
         tx_root: Hash::default(),
@@ -540,6 +541,7 @@ mod edge_cases {
                     base_fee_per_gas: 0,
                     gas_used: 0,
                     gas_limit: 30_000_000,
+                    epoch_commitment: Hash::default(),
                 },
                 state_root: Hash::default(),
                 tx_root: Hash::default(),
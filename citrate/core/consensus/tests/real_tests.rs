@@ -33,6 +33,7 @@ fn create_test_block(block_num: u8, height: u64, parent: Option<Hash>) -> Block
             base_fee_per_gas: 0,
             gas_used: 0,
             gas_limit: 30_000_000,
+            epoch_commitment: Hash::default(),
         },
         state_root: Hash::default(),
         tx_root: Hash::default(),
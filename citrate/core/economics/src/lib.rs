@@ -8,6 +8,7 @@ pub mod dynamic_pricing;
 pub mod enhanced_rewards;
 pub mod revenue_sharing;
 pub mod unified_economics;
+pub mod staking;
 
 pub use genesis::{GenesisAccount, GenesisConfig};
 pub use rewards::{BlockReward, RewardCalculator, RewardConfig};
@@ -27,11 +28,15 @@ pub use enhanced_rewards::{
 pub use revenue_sharing::{
     RevenueShareConfig, RevenueShareManager, RevenuePool, StakeholderType,
     RevenueDistribution, StakeholderContribution, PerformanceMetrics, RevenueEvent,
+    ModelEarnings,
 };
 pub use unified_economics::{
     UnifiedEconomicsConfig, UnifiedEconomicsManager, VotingPower, EconomicState,
     BlockEconomicUpdate,
 };
+pub use staking::{
+    StakingConfig, StakingManager, ValidatorStake, UnbondingEntry, SlashReason, SlashEvent,
+};
 
 use primitive_types::U256;
 
@@ -198,6 +198,7 @@ mod tests {
                 base_fee_per_gas: 0,
                 gas_used: 0,
                 gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
             },
             state_root: Hash::new([0; 32]),
             tx_root: Hash::new([0; 32]),
@@ -246,6 +247,7 @@ mod tests {
                 base_fee_per_gas: 0,
                 gas_used: 0,
                 gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
             },
             state_root: Hash::new([0; 32]),
             tx_root: Hash::new([0; 32]),
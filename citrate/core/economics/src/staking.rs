@@ -0,0 +1,404 @@
+// citrate/core/economics/src/staking.rs
+
+use citrate_consensus::types::PublicKey;
+use citrate_consensus::{Validator, VrfProposerSelector};
+use citrate_execution::types::Address;
+use primitive_types::U256;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use anyhow::{Result, anyhow};
+
+/// Validator staking configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StakingConfig {
+    /// Minimum bonded stake for a validator to take part in leader election
+    pub min_validator_stake: U256,
+
+    /// Blocks between unbonding and the stake becoming withdrawable
+    pub unbonding_period: u64,
+
+    /// Share of stake slashed for signing two blocks at the same height (basis points)
+    pub equivocation_slash_bps: u16,
+
+    /// Share of stake slashed when a validator misses too many slots (basis points)
+    pub unavailability_slash_bps: u16,
+
+    /// Consecutive missed slots before an unavailability slash
+    pub max_missed_slots: u64,
+}
+
+impl Default for StakingConfig {
+    fn default() -> Self {
+        Self {
+            min_validator_stake: U256::from(10_000) * U256::from(10).pow(U256::from(18)), // 10,000 LATT
+            unbonding_period: 100_800,     // matches the staking precompile
+            equivocation_slash_bps: 500,   // 5%
+            unavailability_slash_bps: 10,  // 0.1%
+            max_missed_slots: 500,
+        }
+    }
+}
+
+/// Stake waiting out the unbonding period
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UnbondingEntry {
+    pub amount: U256,
+    pub release_height: u64,
+}
+
+/// Stake and status of a single validator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidatorStake {
+    pub address: Address,
+    pub pubkey: PublicKey,
+    pub bonded: U256,
+    pub unbonding: Vec<UnbondingEntry>,
+    /// Jailed validators are excluded from leader election until unjailed
+    pub jailed: bool,
+    pub missed_slots: u64,
+    pub total_slashed: U256,
+}
+
+impl ValidatorStake {
+    /// Bonded plus unbonding stake, all of which can still be slashed
+    pub fn slashable(&self) -> U256 {
+        self.unbonding
+            .iter()
+            .fold(self.bonded, |acc, e| acc + e.amount)
+    }
+}
+
+/// Misbehaviour that triggers a slash
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum SlashReason {
+    /// Two conflicting blocks signed for the same slot
+    Equivocation { slot: u64 },
+    /// Missed `max_missed_slots` consecutive slots
+    Unavailability { missed_slots: u64 },
+}
+
+/// Record of an applied slash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashEvent {
+    pub validator: Address,
+    pub reason: SlashReason,
+    pub amount: U256,
+    pub block_height: u64,
+}
+
+/// Tracks validator stake deposits, unbonding and slashing
+pub struct StakingManager {
+    config: StakingConfig,
+    validators: HashMap<Address, ValidatorStake>,
+    by_pubkey: HashMap<PublicKey, Address>,
+    slash_history: Vec<SlashEvent>,
+}
+
+impl StakingManager {
+    pub fn new(config: StakingConfig) -> Self {
+        Self {
+            config,
+            validators: HashMap::new(),
+            by_pubkey: HashMap::new(),
+            slash_history: Vec::new(),
+        }
+    }
+
+    /// Bond `amount` for a validator, registering it on first deposit
+    pub fn deposit(&mut self, address: Address, pubkey: PublicKey, amount: U256) -> Result<()> {
+        if amount.is_zero() {
+            return Err(anyhow!("Stake amount must be positive"));
+        }
+        if let Some(owner) = self.by_pubkey.get(&pubkey) {
+            if *owner != address {
+                return Err(anyhow!("Validator key is registered to another address"));
+            }
+        }
+
+        let validator = self.validators.entry(address).or_insert_with(|| ValidatorStake {
+            address,
+            pubkey,
+            bonded: U256::zero(),
+            unbonding: Vec::new(),
+            jailed: false,
+            missed_slots: 0,
+            total_slashed: U256::zero(),
+        });
+        if validator.pubkey != pubkey {
+            return Err(anyhow!("Address is registered with a different validator key"));
+        }
+        validator.bonded += amount;
+        self.by_pubkey.insert(pubkey, address);
+        Ok(())
+    }
+
+    /// Start unbonding `amount`; returns the height it becomes withdrawable
+    pub fn unbond(&mut self, address: Address, amount: U256, block_height: u64) -> Result<u64> {
+        let validator = self
+            .validators
+            .get_mut(&address)
+            .ok_or_else(|| anyhow!("Validator not found"))?;
+        if amount.is_zero() || validator.bonded < amount {
+            return Err(anyhow!("Insufficient bonded stake"));
+        }
+
+        let release_height = block_height + self.config.unbonding_period;
+        validator.bonded -= amount;
+        validator.unbonding.push(UnbondingEntry {
+            amount,
+            release_height,
+        });
+        Ok(release_height)
+    }
+
+    /// Release all unbonding entries that have matured; returns the amount freed
+    pub fn withdraw_unbonded(&mut self, address: Address, block_height: u64) -> Result<U256> {
+        let validator = self
+            .validators
+            .get_mut(&address)
+            .ok_or_else(|| anyhow!("Validator not found"))?;
+
+        let mut released = U256::zero();
+        validator.unbonding.retain(|entry| {
+            if entry.release_height <= block_height {
+                released += entry.amount;
+                false
+            } else {
+                true
+            }
+        });
+        Ok(released)
+    }
+
+    /// Slashing hook for a validator that signed conflicting blocks.
+    /// The validator is also jailed.
+    pub fn on_equivocation(
+        &mut self,
+        pubkey: &PublicKey,
+        slot: u64,
+        block_height: u64,
+    ) -> Result<SlashEvent> {
+        let address = self.address_of(pubkey)?;
+        let event = self.slash(address, SlashReason::Equivocation { slot }, block_height)?;
+        if let Some(validator) = self.validators.get_mut(&address) {
+            validator.jailed = true;
+        }
+        Ok(event)
+    }
+
+    /// Slashing hook for a missed slot. Returns a slash once the validator
+    /// has missed `max_missed_slots` in a row.
+    pub fn on_missed_slot(
+        &mut self,
+        pubkey: &PublicKey,
+        block_height: u64,
+    ) -> Result<Option<SlashEvent>> {
+        let address = self.address_of(pubkey)?;
+        let validator = self
+            .validators
+            .get_mut(&address)
+            .ok_or_else(|| anyhow!("Validator not found"))?;
+        validator.missed_slots += 1;
+        if validator.missed_slots < self.config.max_missed_slots {
+            return Ok(None);
+        }
+
+        let missed_slots = validator.missed_slots;
+        validator.missed_slots = 0;
+        self.slash(address, SlashReason::Unavailability { missed_slots }, block_height)
+            .map(Some)
+    }
+
+    /// Reset the missed-slot counter after a validator produces a block
+    pub fn on_block_produced(&mut self, pubkey: &PublicKey) {
+        if let Some(address) = self.by_pubkey.get(pubkey) {
+            if let Some(validator) = self.validators.get_mut(address) {
+                validator.missed_slots = 0;
+            }
+        }
+    }
+
+    /// Allow a jailed validator back into leader election
+    pub fn unjail(&mut self, address: Address) -> Result<()> {
+        let validator = self
+            .validators
+            .get_mut(&address)
+            .ok_or_else(|| anyhow!("Validator not found"))?;
+        validator.jailed = false;
+        validator.missed_slots = 0;
+        Ok(())
+    }
+
+    /// Take the configured share of a validator's slashable stake, bonded
+    /// stake first and then the most recent unbonding entries
+    pub fn slash(
+        &mut self,
+        address: Address,
+        reason: SlashReason,
+        block_height: u64,
+    ) -> Result<SlashEvent> {
+        let bps = match reason {
+            SlashReason::Equivocation { .. } => self.config.equivocation_slash_bps,
+            SlashReason::Unavailability { .. } => self.config.unavailability_slash_bps,
+        };
+        let validator = self
+            .validators
+            .get_mut(&address)
+            .ok_or_else(|| anyhow!("Validator not found"))?;
+
+        let amount = validator.slashable() * U256::from(bps) / U256::from(10_000);
+        let mut remaining = amount;
+
+        let from_bonded = remaining.min(validator.bonded);
+        validator.bonded -= from_bonded;
+        remaining -= from_bonded;
+
+        for entry in validator.unbonding.iter_mut().rev() {
+            if remaining.is_zero() {
+                break;
+            }
+            let taken = remaining.min(entry.amount);
+            entry.amount -= taken;
+            remaining -= taken;
+        }
+        validator.unbonding.retain(|e| !e.amount.is_zero());
+        validator.total_slashed += amount;
+
+        let event = SlashEvent {
+            validator: address,
+            reason,
+            amount,
+            block_height,
+        };
+        self.slash_history.push(event.clone());
+        Ok(event)
+    }
+
+    /// Whether a validator currently qualifies for leader election
+    pub fn is_active(&self, address: &Address) -> bool {
+        self.validators
+            .get(address)
+            .map(|v| !v.jailed && v.bonded >= self.config.min_validator_stake)
+            .unwrap_or(false)
+    }
+
+    /// Push stake weights and active status into the proposer selector so
+    /// leader election probability follows bonded stake
+    pub async fn sync_selector(&self, selector: &VrfProposerSelector) {
+        for validator in self.validators.values() {
+            let stake = if validator.bonded > U256::from(u128::MAX) {
+                u128::MAX
+            } else {
+                validator.bonded.as_u128()
+            };
+            selector
+                .register_validator(Validator {
+                    pubkey: validator.pubkey,
+                    stake,
+                    is_active: self.is_active(&validator.address),
+                })
+                .await;
+        }
+    }
+
+    pub fn get_validator(&self, address: &Address) -> Option<&ValidatorStake> {
+        self.validators.get(address)
+    }
+
+    /// Total bonded stake across all validators
+    pub fn total_bonded(&self) -> U256 {
+        self.validators
+            .values()
+            .fold(U256::zero(), |acc, v| acc + v.bonded)
+    }
+
+    pub fn slash_history(&self) -> &[SlashEvent] {
+        &self.slash_history
+    }
+
+    fn address_of(&self, pubkey: &PublicKey) -> Result<Address> {
+        self.by_pubkey
+            .get(pubkey)
+            .copied()
+            .ok_or_else(|| anyhow!("Validator not found"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> StakingConfig {
+        StakingConfig {
+            min_validator_stake: U256::from(1_000),
+            unbonding_period: 10,
+            equivocation_slash_bps: 1_000,
+            unavailability_slash_bps: 100,
+            max_missed_slots: 3,
+        }
+    }
+
+    #[test]
+    fn test_unbonding_period() {
+        let mut staking = StakingManager::new(config());
+        let addr = Address([1; 20]);
+        staking.deposit(addr, PublicKey::new([1; 32]), U256::from(5_000)).unwrap();
+
+        let release = staking.unbond(addr, U256::from(4_500), 100).unwrap();
+        assert_eq!(release, 110);
+        assert!(!staking.is_active(&addr));
+        assert!(staking.unbond(addr, U256::from(1_000), 100).is_err());
+
+        assert_eq!(staking.withdraw_unbonded(addr, 109).unwrap(), U256::zero());
+        assert_eq!(staking.withdraw_unbonded(addr, 110).unwrap(), U256::from(4_500));
+        assert!(staking.get_validator(&addr).unwrap().unbonding.is_empty());
+    }
+
+    #[test]
+    fn test_equivocation_slashes_unbonding_stake_and_jails() {
+        let mut staking = StakingManager::new(config());
+        let addr = Address([1; 20]);
+        let pubkey = PublicKey::new([1; 32]);
+        staking.deposit(addr, pubkey, U256::from(10_000)).unwrap();
+        staking.unbond(addr, U256::from(9_500), 100).unwrap();
+
+        // 10% of 10,000 slashable: 500 from bonded, 500 from unbonding
+        let event = staking.on_equivocation(&pubkey, 7, 101).unwrap();
+        assert_eq!(event.amount, U256::from(1_000));
+        let validator = staking.get_validator(&addr).unwrap();
+        assert_eq!(validator.bonded, U256::zero());
+        assert_eq!(validator.unbonding[0].amount, U256::from(9_000));
+        assert!(validator.jailed);
+    }
+
+    #[test]
+    fn test_unavailability_after_missed_slots() {
+        let mut staking = StakingManager::new(config());
+        let addr = Address([1; 20]);
+        let pubkey = PublicKey::new([1; 32]);
+        staking.deposit(addr, pubkey, U256::from(10_000)).unwrap();
+
+        assert!(staking.on_missed_slot(&pubkey, 1).unwrap().is_none());
+        staking.on_block_produced(&pubkey);
+        assert!(staking.on_missed_slot(&pubkey, 2).unwrap().is_none());
+        assert!(staking.on_missed_slot(&pubkey, 3).unwrap().is_none());
+        let event = staking.on_missed_slot(&pubkey, 4).unwrap().unwrap();
+        assert_eq!(event.amount, U256::from(100));
+        assert!(staking.is_active(&addr));
+    }
+
+    #[tokio::test]
+    async fn test_sync_selector() {
+        let mut staking = StakingManager::new(config());
+        let jailed = PublicKey::new([2; 32]);
+        staking.deposit(Address([1; 20]), PublicKey::new([1; 32]), U256::from(3_000)).unwrap();
+        staking.deposit(Address([2; 20]), jailed, U256::from(2_000)).unwrap();
+        staking.deposit(Address([3; 20]), PublicKey::new([3; 32]), U256::from(500)).unwrap();
+        staking.on_equivocation(&jailed, 1, 1).unwrap();
+
+        let selector = VrfProposerSelector::new();
+        staking.sync_selector(&selector).await;
+        assert_eq!(selector.active_validator_count().await, 1);
+        assert_eq!(selector.total_stake().await, 3_000);
+    }
+}
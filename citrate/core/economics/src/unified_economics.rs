@@ -7,6 +7,7 @@ use crate::{
     revenue_sharing::{RevenueShareManager, RevenueShareConfig, RevenuePool, StakeholderType, RevenueDistribution},
     token::{Token, TokenConfig},
 };
+use citrate_execution::precompiles::compute::ComputeSettlement;
 use citrate_execution::types::Address;
 use primitive_types::U256;
 use serde::{Deserialize, Serialize};
@@ -461,6 +462,11 @@ impl UnifiedEconomicsManager {
         self.revenue_sharing.collect_revenue(pool, amount, source)
     }
 
+    /// Credit a provider for a compute job settled on-chain
+    pub fn record_compute_settlement(&mut self, settlement: &ComputeSettlement) -> Result<()> {
+        self.revenue_sharing.record_compute_settlement(settlement)
+    }
+
     /// Process revenue distributions and update stakeholder contributions
     pub fn process_revenue_distributions(
         &mut self,
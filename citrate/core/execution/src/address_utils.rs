@@ -3,6 +3,7 @@
 /// Address utilities for handling both Ethereum-style addresses and public keys
 use crate::types::Address;
 use citrate_consensus::types::PublicKey;
+use sha3::{Digest, Keccak256};
 
 /// Convert various input formats to a proper 20-byte Ethereum address
 /// This function delegates to Address::from_public_key to ensure consistency
@@ -11,7 +12,9 @@ pub fn normalize_address(input: &PublicKey) -> Address {
 }
 
 
-/// Convert a hex string (with or without 0x prefix) to Address
+/// Convert a hex string (with or without 0x prefix) to Address.
+/// Mixed-case input must carry a valid EIP-55 checksum; all-lowercase and
+/// all-uppercase input is accepted as unchecksummed.
 pub fn address_from_hex(hex: &str) -> Result<Address, String> {
     let hex = hex.trim_start_matches("0x").trim_start_matches("0X");
 
@@ -33,7 +36,46 @@ pub fn address_from_hex(hex: &str) -> Result<Address, String> {
 
     let mut addr = [0u8; 20];
     addr.copy_from_slice(&bytes);
-    Ok(Address(addr))
+    let addr = Address(addr);
+
+    if is_mixed_case(hex) && to_checksum_address(&addr)[2..] != *hex {
+        return Err(format!("Invalid address checksum: 0x{}", hex));
+    }
+
+    Ok(addr)
+}
+
+/// Format an address with its EIP-55 mixed-case checksum
+pub fn to_checksum_address(addr: &Address) -> String {
+    let lower = hex::encode(addr.0);
+    let hash = Keccak256::digest(lower.as_bytes());
+
+    let mut out = String::with_capacity(42);
+    out.push_str("0x");
+    for (i, c) in lower.chars().enumerate() {
+        // Uppercase a letter when the matching hash nibble is >= 8
+        let nibble = if i % 2 == 0 {
+            hash[i / 2] >> 4
+        } else {
+            hash[i / 2] & 0x0f
+        };
+        if nibble >= 8 {
+            out.push(c.to_ascii_uppercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Whether `hex` is a well-formed address whose checksum, if it has one,
+/// is valid
+pub fn is_valid_address(hex: &str) -> bool {
+    address_from_hex(hex).is_ok()
+}
+
+fn is_mixed_case(hex: &str) -> bool {
+    hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase())
 }
 
 /// Convert Address to a 32-byte PublicKey format for compatibility
@@ -83,12 +125,39 @@ mod tests {
 
     #[test]
     fn test_hex_conversion() {
-        let hex_with_prefix = "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb1";
-        let hex_without = "742d35Cc6634C0532925a3b844Bc9e7595f0bEb1";
+        let hex_with_prefix = "0x742d35cc6634c0532925a3b844bc9e7595f0beb1";
+        let hex_without = "742D35CC6634C0532925A3B844BC9E7595F0BEB1";
 
         let addr1 = address_from_hex(hex_with_prefix).unwrap();
         let addr2 = address_from_hex(hex_without).unwrap();
 
         assert_eq!(addr1, addr2);
     }
+
+    #[test]
+    fn test_checksum_formatting() {
+        // Test vectors from EIP-55
+        for expected in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            let addr = address_from_hex(expected).unwrap();
+            assert_eq!(to_checksum_address(&addr), expected);
+        }
+    }
+
+    #[test]
+    fn test_checksum_validation() {
+        // Mixed case must match the checksum
+        assert!(is_valid_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"));
+        assert!(!is_valid_address("0x5aAeb6053f3E94C9b9A09f33669435E7Ef1BeAed"));
+        let err = address_from_hex("0x5AAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").unwrap_err();
+        assert!(err.contains("checksum"));
+
+        // Single-case input carries no checksum
+        assert!(is_valid_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"));
+        assert!(is_valid_address("0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED"));
+    }
 }
@@ -0,0 +1,152 @@
+// citrate/core/execution/src/bloom.rs
+
+// Ethereum-compatible 2048-bit logs bloom
+use crate::types::{Log, TransactionReceipt};
+use sha3::{Digest, Keccak256};
+
+/// Size of a logs bloom in bytes
+pub const BLOOM_SIZE: usize = 256;
+
+/// 2048-bit bloom over log addresses and topics, with the same layout as the
+/// Ethereum `logsBloom` field (3 bits per entry taken from its keccak hash)
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bloom(pub [u8; BLOOM_SIZE]);
+
+impl Bloom {
+    pub fn new() -> Self {
+        Self([0u8; BLOOM_SIZE])
+    }
+
+    pub fn from_slice(bytes: &[u8]) -> Option<Self> {
+        let bytes: [u8; BLOOM_SIZE] = bytes.try_into().ok()?;
+        Some(Self(bytes))
+    }
+
+    /// Bloom of a set of logs
+    pub fn from_logs<'a>(logs: impl IntoIterator<Item = &'a Log>) -> Self {
+        let mut bloom = Self::new();
+        for log in logs {
+            bloom.accrue_log(log);
+        }
+        bloom
+    }
+
+    pub fn as_bytes(&self) -> &[u8; BLOOM_SIZE] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|b| *b == 0)
+    }
+
+    /// Add a raw entry (an address or topic)
+    pub fn accrue(&mut self, input: &[u8]) {
+        for (byte, mask) in bloom_bits(input) {
+            self.0[byte] |= mask;
+        }
+    }
+
+    /// Add a log's address and every topic
+    pub fn accrue_log(&mut self, log: &Log) {
+        self.accrue(&log.address.0);
+        for topic in &log.topics {
+            self.accrue(topic.as_bytes());
+        }
+    }
+
+    /// Merge another bloom into this one
+    pub fn accrue_bloom(&mut self, other: &Bloom) {
+        for (a, b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a |= b;
+        }
+    }
+
+    /// Whether `input` may have been added. False positives are possible,
+    /// false negatives are not.
+    pub fn contains_input(&self, input: &[u8]) -> bool {
+        bloom_bits(input)
+            .iter()
+            .all(|(byte, mask)| self.0[*byte] & mask == *mask)
+    }
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for Bloom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Bloom(0x{})", hex::encode(self.0))
+    }
+}
+
+impl TransactionReceipt {
+    /// Bloom of the receipt's logs
+    pub fn logs_bloom(&self) -> Bloom {
+        Bloom::from_logs(&self.logs)
+    }
+}
+
+/// Byte index and bit mask of the three bits selected by `input`
+fn bloom_bits(input: &[u8]) -> [(usize, u8); 3] {
+    let hash = Keccak256::digest(input);
+    let mut bits = [(0usize, 0u8); 3];
+    for (i, bit) in bits.iter_mut().enumerate() {
+        let index = (((hash[2 * i] as usize) << 8) | hash[2 * i + 1] as usize) & 0x7ff;
+        *bit = (BLOOM_SIZE - 1 - index / 8, 1u8 << (index % 8));
+    }
+    bits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Address;
+    use citrate_consensus::types::Hash;
+
+    fn log(address: u8, topics: &[u8]) -> Log {
+        Log {
+            address: Address([address; 20]),
+            topics: topics.iter().map(|t| Hash::new([*t; 32])).collect(),
+            data: vec![],
+        }
+    }
+
+    #[test]
+    fn test_bloom_contains_log_entries() {
+        let bloom = Bloom::from_logs(&[log(1, &[2, 3])]);
+        assert!(bloom.contains_input(&[1; 20]));
+        assert!(bloom.contains_input(&[2; 32]));
+        assert!(bloom.contains_input(&[3; 32]));
+        assert!(!bloom.contains_input(&[4; 32]));
+        assert!(Bloom::new().is_empty());
+    }
+
+    #[test]
+    fn test_bloom_matches_ethereum_layout() {
+        use revm_primitives::alloy_primitives::{Bloom as EthBloom, BloomInput};
+
+        let entries: [&[u8]; 3] = [&[0u8; 20], &[0xab; 20], &[0x42; 32]];
+        let mut ours = Bloom::new();
+        let mut reference = EthBloom::default();
+        for entry in entries {
+            ours.accrue(entry);
+            reference.accrue(BloomInput::Raw(entry));
+        }
+        assert_eq!(ours.as_bytes(), reference.as_slice());
+    }
+
+    #[test]
+    fn test_accrue_bloom_merges() {
+        let a = Bloom::from_logs(&[log(1, &[])]);
+        let b = Bloom::from_logs(&[log(2, &[])]);
+        let mut merged = a;
+        merged.accrue_bloom(&b);
+        assert!(merged.contains_input(&[1; 20]));
+        assert!(merged.contains_input(&[2; 20]));
+        assert_eq!(Bloom::from_slice(merged.as_bytes()), Some(merged));
+        assert_eq!(Bloom::from_slice(&[0u8; 3]), None);
+    }
+}
@@ -1,34 +1,162 @@
 // citrate/core/execution/src/executor.rs
 
 use crate::metrics::{PRECOMPILE_CALLS_TOTAL, VM_EXECUTIONS_TOTAL, VM_GAS_USED};
+use crate::precompiles::compute::{self, COMPUTE_PRECOMPILE};
+use crate::precompiles::multisig::{self, MULTISIG_PRECOMPILE};
+use crate::precompiles::names::{self, NAMES_PRECOMPILE};
+use crate::precompiles::proposals::{self, PROPOSALS_PRECOMPILE};
+use crate::precompiles::randomness::{self, RANDOMNESS_PRECOMPILE};
+use crate::precompiles::staking::{self, STAKING_PRECOMPILE};
+use crate::precompiles::validator_keys::{self, VALIDATOR_KEYS_PRECOMPILE};
 use crate::precompiles::{PrecompileExecutor, inference::InferencePrecompile};
 use crate::inference::metal_runtime::MetalRuntime;
-use crate::state::StateDB;
+use crate::simulation::{self, Simulation, StateOverrides};
+use crate::state::{BlockStateDiff, ChangedKeys, StateDB, StorageChange};
+use crate::tracer::{
+    CallFrame, CallKind, CallTracer, InternalTransaction, TraceOptions, TransactionTrace,
+};
 use crate::types::{
     AccessPolicy, Address, ExecutionError, GasSchedule, JobId, JobStatus, Log, ModelId,
-    ModelMetadata, ModelState, TransactionReceipt, TransactionType,
+    ModelMetadata, ModelState, RoyaltySchedule, RoyaltySplit, TransactionReceipt,
+    TransactionType,
 };
 use crate::vm::VM;
 use async_trait::async_trait;
 use hex;
-use citrate_consensus::types::{Block, Hash, Transaction};
+use citrate_consensus::limits::BlockLimits;
+use citrate_consensus::types::{Block, Hash, PublicKey, Transaction, VrfProof};
 use primitive_types::U256;
 use std::time::Instant;
 use serde_json;
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
+/// First topic of the log emitted when a model is registered. Model logs are
+/// emitted under the owner's address, with the model hash as second topic.
+pub const MODEL_REGISTERED_TOPIC: [u8; 32] = *b"ModelRegistered00000000000000000";
+
+/// First topic of the log emitted when a model's weights are updated
+pub const MODEL_UPDATED_TOPIC: [u8; 32] = *b"ModelUpdated00000000000000000000";
+
+/// First topic of the log emitted when an owner deprecates a model
+pub const MODEL_DEPRECATED_TOPIC: [u8; 32] = *b"ModelDeprecated00000000000000000";
+
+/// First topic of the log emitted when an owner sets a model's royalty
+/// schedule. Data is the three shares as 32-byte words.
+pub const MODEL_ROYALTY_SET_TOPIC: [u8; 32] = *b"ModelRoyaltySet00000000000000000";
+
+/// First topic of the log emitted for each paid inference, see
+/// [`RoyaltyPayment`]
+pub const INFERENCE_ROYALTY_PAID_TOPIC: [u8; 32] = *b"InferenceRoyaltyPaid000000000000";
+
+/// Account receiving the treasury share of inference fees
+pub const INFERENCE_TREASURY_ADDRESS: Address = Address([0x11; 20]);
+
+/// Data of registration and update logs: the new version as a 32-byte word,
+/// followed by the artifact CID if there is one
+pub fn model_event_data(version: u32, artifact_cid: Option<&str>) -> Vec<u8> {
+    let mut data = vec![0u8; 32];
+    data[28..].copy_from_slice(&version.to_be_bytes());
+    if let Some(cid) = artifact_cid {
+        data.extend_from_slice(cid.as_bytes());
+    }
+    data
+}
+
+/// One inference fee split among owner, provider and treasury. Emitted as a
+/// log under the owner's address so marketplaces can track earnings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoyaltyPayment {
+    pub model_hash: Hash,
+    pub payer: Address,
+    pub owner: Address,
+    pub provider: Option<Address>,
+    pub split: RoyaltySplit,
+}
+
+impl RoyaltyPayment {
+    /// Topics are the event topic, model hash and payer. Data holds the
+    /// owner, provider (zero when none) and treasury addresses followed by
+    /// the amount each received, one 32-byte word each.
+    pub fn to_log(&self) -> Log {
+        let mut data = Vec::with_capacity(6 * 32);
+        for address in [
+            self.owner,
+            self.provider.unwrap_or(Address([0u8; 20])),
+            INFERENCE_TREASURY_ADDRESS,
+        ] {
+            data.extend_from_slice(&address_word(&address));
+        }
+        for amount in [self.split.owner, self.split.provider, self.split.treasury] {
+            let mut word = [0u8; 32];
+            amount.to_big_endian(&mut word);
+            data.extend_from_slice(&word);
+        }
+        Log {
+            address: self.owner,
+            topics: vec![
+                Hash::new(INFERENCE_ROYALTY_PAID_TOPIC),
+                self.model_hash,
+                Hash::new(address_word(&self.payer)),
+            ],
+            data,
+        }
+    }
+
+    /// Decode a royalty log, or `None` if it is some other log
+    pub fn from_log(log: &Log) -> Option<Self> {
+        if log.topics.len() != 3
+            || log.topics[0] != Hash::new(INFERENCE_ROYALTY_PAID_TOPIC)
+            || log.data.len() != 6 * 32
+        {
+            return None;
+        }
+        let word = |i: usize| &log.data[i * 32..(i + 1) * 32];
+        let address = |bytes: &[u8]| {
+            let mut a = [0u8; 20];
+            a.copy_from_slice(&bytes[12..32]);
+            Address(a)
+        };
+        let provider = address(word(1));
+        Some(Self {
+            model_hash: log.topics[1],
+            payer: address(log.topics[2].as_bytes()),
+            owner: address(word(0)),
+            provider: (provider != Address([0u8; 20])).then_some(provider),
+            split: RoyaltySplit {
+                owner: U256::from_big_endian(word(3)),
+                provider: U256::from_big_endian(word(4)),
+                treasury: U256::from_big_endian(word(5)),
+            },
+        })
+    }
+}
+
+/// Address left-padded to a 32-byte word
+fn address_word(address: &Address) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(&address.0);
+    word
+}
+
 /// Execution context for a transaction
 pub struct ExecutionContext {
     pub block_number: u64,
     pub block_hash: Hash,
     pub timestamp: u64,
+    /// Block proposer and the VRF output it revealed for this block
+    pub proposer: PublicKey,
+    pub vrf_reveal: VrfProof,
     pub gas_limit: u64,
     pub gas_used: u64,
     pub gas_price: u64,
     pub origin: Address,
     pub logs: Vec<Log>,
     pub output: Vec<u8>,
+    /// Set when the transaction is being replayed for a trace
+    pub tracer: Option<CallTracer>,
+    /// Value transfers made by contract code
+    pub internal_transactions: Vec<InternalTransaction>,
 }
 
 impl ExecutionContext {
@@ -37,12 +165,16 @@ impl ExecutionContext {
             block_number: block.header.height,
             block_hash: block.hash(),
             timestamp: block.header.timestamp,
+            proposer: block.header.proposer_pubkey,
+            vrf_reveal: block.header.vrf_reveal.clone(),
             gas_limit: tx.gas_limit,
             gas_used: 0,
             gas_price: tx.gas_price,
             origin: crate::address_utils::normalize_address(&tx.from),
             logs: Vec::new(),
             output: Vec::new(),
+            tracer: None,
+            internal_transactions: Vec::new(),
         }
     }
 
@@ -88,6 +220,52 @@ pub trait StateStoreTrait: Send + Sync {
     ) -> anyhow::Result<()>;
     fn get_account(&self, address: &Address) -> anyhow::Result<Option<crate::types::AccountState>>;
     fn put_code(&self, code_hash: &Hash, code: &[u8]) -> anyhow::Result<()>;
+
+    /// Whether the store keeps per-block state history (archive mode). The
+    /// methods below only do something on archive stores.
+    fn is_archive(&self) -> bool {
+        false
+    }
+
+    /// Record the state left by a block
+    fn archive_block(&self, _diff: &BlockStateDiff) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// First and last archived heights
+    fn archived_range(&self) -> anyhow::Result<Option<(u64, u64)>> {
+        Ok(None)
+    }
+
+    /// State root after the block at `height`
+    fn state_root_at(&self, _height: u64) -> anyhow::Result<Option<Hash>> {
+        Ok(None)
+    }
+
+    /// Account state after the block at `height`; `None` if the account did
+    /// not exist yet
+    fn account_at(
+        &self,
+        _address: &Address,
+        _height: u64,
+    ) -> anyhow::Result<Option<crate::types::AccountState>> {
+        Ok(None)
+    }
+
+    /// Storage value after the block at `height`
+    fn storage_at(
+        &self,
+        _address: &Address,
+        _key: &[u8],
+        _height: u64,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    /// Accounts and storage slots changed by blocks after `height`
+    fn changed_since(&self, _height: u64) -> anyhow::Result<ChangedKeys> {
+        Ok(ChangedKeys::default())
+    }
 }
 
 /// Bridge trait to persist AI model metadata & artifacts in external storage layers.
@@ -124,6 +302,14 @@ pub trait ModelRegistryAdapter: Send + Sync {
     ) -> anyhow::Result<()> {
         Ok(())
     }
+
+    async fn set_royalty_schedule(
+        &self,
+        _model_id: ModelId,
+        _schedule: &RoyaltySchedule,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 /// Trait to delegate AI inference to an external service (e.g., MCP)
@@ -307,6 +493,157 @@ impl Executor {
         }
     }
 
+    /// Record the state left by the block at `height` when the state store
+    /// is an archive. Call after the block's transactions and before
+    /// `persist_state_changes`.
+    pub fn archive_block_state(&self, height: u64) -> anyhow::Result<()> {
+        let Some(store) = self.state_store.as_ref().filter(|s| s.is_archive()) else {
+            return Ok(());
+        };
+
+        // Computing the root first folds storage roots into the accounts
+        let state_root = self.state_db.calculate_state_root();
+        let changed_accounts = self.state_db.accounts.take_changed();
+        let changed_storage = self.state_db.take_changed_storage();
+
+        // The first archived block records the whole state, so history is
+        // complete from there on
+        let diff = if store.archived_range()?.is_none() {
+            BlockStateDiff {
+                height,
+                state_root,
+                accounts: self.state_db.accounts.all_accounts(),
+                storage: self
+                    .state_db
+                    .all_storage()
+                    .into_iter()
+                    .map(|(address, key, value)| StorageChange {
+                        address,
+                        key,
+                        value: Some(value),
+                    })
+                    .collect(),
+            }
+        } else {
+            BlockStateDiff {
+                height,
+                state_root,
+                accounts: changed_accounts
+                    .into_iter()
+                    .map(|address| (address, self.state_db.accounts.get_account(&address)))
+                    .collect(),
+                storage: changed_storage
+                    .into_iter()
+                    .map(|(address, key)| StorageChange {
+                        value: self.state_db.get_storage(&address, &key),
+                        address,
+                        key,
+                    })
+                    .collect(),
+            }
+        };
+
+        debug!(
+            "Archiving state at height {}: {} accounts, {} storage slots",
+            height,
+            diff.accounts.len(),
+            diff.storage.len()
+        );
+        store.archive_block(&diff)
+    }
+
+    /// The archive, if `height` is before its last block; `None` means the
+    /// current state answers for `height`
+    fn archive_for(&self, height: u64) -> anyhow::Result<Option<&Arc<dyn StateStoreTrait>>> {
+        let Some(store) = self.state_store.as_ref().filter(|s| s.is_archive()) else {
+            return Err(anyhow::anyhow!(
+                "State at block {} is not available; historical state requires archive mode",
+                height
+            ));
+        };
+        match store.archived_range()? {
+            Some((_, last)) if height >= last => Ok(None),
+            Some((first, _)) if height >= first => Ok(Some(store)),
+            Some((first, _)) => Err(anyhow::anyhow!(
+                "State at block {} is not available; the archive starts at block {}",
+                height,
+                first
+            )),
+            None => Err(anyhow::anyhow!(
+                "State at block {} is not available; no blocks archived yet",
+                height
+            )),
+        }
+    }
+
+    /// Account state after the block at `height`. Heights at or past the
+    /// archive's last block read the current state.
+    pub fn account_at(
+        &self,
+        address: &Address,
+        height: u64,
+    ) -> anyhow::Result<crate::types::AccountState> {
+        match self.archive_for(height)? {
+            Some(store) => Ok(store.account_at(address, height)?.unwrap_or_default()),
+            None => Ok(self.state_db.accounts.get_account(address)),
+        }
+    }
+
+    /// Storage value after the block at `height`
+    pub fn storage_at(
+        &self,
+        address: &Address,
+        key: &[u8],
+        height: u64,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        match self.archive_for(height)? {
+            Some(store) => store.storage_at(address, key, height),
+            None => Ok(self.state_db.get_storage(address, key)),
+        }
+    }
+
+    /// Copy of the state after the block at `height`, built by rolling back
+    /// every account and storage slot changed since. Models and training
+    /// jobs are not archived and keep their current state.
+    pub fn state_at(&self, height: u64) -> anyhow::Result<StateDB> {
+        let state = self.state_db.fork();
+        let Some(store) = self.archive_for(height)? else {
+            return Ok(state);
+        };
+
+        let changed = store.changed_since(height)?;
+        for address in &changed.accounts {
+            match store.account_at(address, height)? {
+                Some(account) => state.accounts.set_account(*address, account),
+                None => state.accounts.remove_account(address),
+            }
+        }
+        for (address, key) in changed.storage {
+            match store.storage_at(&address, &key, height)? {
+                Some(value) => state.set_storage(address, key, value),
+                None => state.delete_storage(address, &key),
+            }
+        }
+        Ok(state)
+    }
+
+    /// Executor over `state_db` with this executor's chain ID, gas schedule
+    /// and inference service. Storage bridges are left off, so nothing it
+    /// executes is persisted.
+    pub fn with_state(&self, state_db: Arc<StateDB>) -> Executor {
+        Executor {
+            state_db,
+            state_store: None,
+            gas_schedule: self.gas_schedule.clone(),
+            inference_service: self.inference_service.clone(),
+            artifact_service: None,
+            ai_storage: None,
+            model_registry: None,
+            precompile_executor: self.precompile_executor.clone(),
+            chain_id: self.chain_id,
+        }
+    }
+
     /// Store raw artifact bytes via configured artifact service
     pub async fn add_artifact(&self, data: &[u8]) -> Result<String, ExecutionError> {
         if let Some(svc) = &self.artifact_service {
@@ -407,7 +744,98 @@ impl Executor {
         block: &Block,
         tx: &Transaction,
     ) -> Result<TransactionReceipt, ExecutionError> {
+        let (receipt, _, _) = self.run_transaction(block, tx, None).await?;
+        Ok(receipt)
+    }
+
+    /// Execute a transaction and also return the value transfers made by the
+    /// contracts it called, which the receipt does not carry
+    pub async fn execute_transaction_with_internal(
+        &self,
+        block: &Block,
+        tx: &Transaction,
+    ) -> Result<(TransactionReceipt, Vec<InternalTransaction>), ExecutionError> {
+        let (receipt, _, internal) = self.run_transaction(block, tx, None).await?;
+        Ok((receipt, internal))
+    }
+
+    /// Execute a transaction while recording its call frames and, optionally,
+    /// every executed opcode. State changes are applied exactly as in
+    /// `execute_transaction`, so replays should run on a forked state.
+    pub async fn trace_transaction(
+        &self,
+        block: &Block,
+        tx: &Transaction,
+        options: TraceOptions,
+    ) -> Result<(TransactionReceipt, TransactionTrace), ExecutionError> {
+        let tracer = CallTracer::new(options);
+        let (receipt, tracer, _) = self.run_transaction(block, tx, Some(tracer)).await?;
+
+        let kind = if tx.to.is_none() {
+            CallKind::Create
+        } else {
+            CallKind::Call
+        };
+        let mut fallback = CallFrame::new(kind, receipt.from, receipt.to, U256::from(tx.value));
+        fallback.gas = tx.gas_limit;
+        fallback.input = tx.data.clone();
+        if kind == CallKind::Create && receipt.status {
+            fallback.to = (receipt.output.len() == 20).then(|| {
+                let mut a = [0u8; 20];
+                a.copy_from_slice(&receipt.output);
+                Address(a)
+            });
+        }
+
+        let trace = tracer.unwrap_or_default().into_trace(
+            tx.hash,
+            receipt.status,
+            receipt.gas_used,
+            receipt.output.clone(),
+            fallback,
+        );
+        Ok((receipt, trace))
+    }
+
+    /// Execute a transaction on `state` after applying `overrides`, and
+    /// report what it changed. `state` is consumed, so pass a fork such as
+    /// `state_db().fork()` or `state_at(height)`; nothing reaches this
+    /// executor's state or storage.
+    pub async fn simulate_transaction(
+        &self,
+        state: StateDB,
+        block: &Block,
+        tx: &Transaction,
+        overrides: &StateOverrides,
+    ) -> Result<Simulation, ExecutionError> {
+        simulation::apply_overrides(&state, overrides);
+        simulation::reset_changes(&state);
+        let before = state.fork();
+
+        let executor = self.with_state(Arc::new(state));
+        let receipt = executor.execute_transaction(block, tx).await?;
+        let state_diff = simulation::diff(&before, executor.state_db());
+        Ok(Simulation {
+            receipt,
+            state_diff,
+        })
+    }
+
+    async fn run_transaction(
+        &self,
+        block: &Block,
+        tx: &Transaction,
+        tracer: Option<CallTracer>,
+    ) -> Result<
+        (
+            TransactionReceipt,
+            Option<CallTracer>,
+            Vec<InternalTransaction>,
+        ),
+        ExecutionError,
+    > {
         let mut context = ExecutionContext::new(block, tx);
+        context.tracer = tracer;
         let from = crate::address_utils::normalize_address(&tx.from);
 
         // Create snapshot for potential rollback
@@ -449,8 +877,12 @@ impl Executor {
             }
             Err(e) => {
                 warn!("Transaction execution failed: {}", e);
+                if let Some(tracer) = context.tracer.as_mut() {
+                    tracer.set_error(e.to_string());
+                }
                 // Rollback state changes but keep gas consumed
                 self.state_db.restore(snapshot);
+                context.internal_transactions.clear();
                 self.state_db
                     .accounts
                     .check_and_increment_nonce(&from, tx.nonce)?;
@@ -477,7 +909,7 @@ impl Executor {
             tx.hash, status, context.gas_used
         );
 
-        Ok(receipt)
+        Ok((receipt, context.tracer, context.internal_transactions))
     }
 
     /// Parse transaction data into type
@@ -849,6 +1281,8 @@ impl Executor {
             self.chain_id,
             context.block_number,
             context.timestamp,
+            context.tracer.as_mut(),
+            &mut context.internal_transactions,
         );
 
         match result {
@@ -909,7 +1343,7 @@ impl Executor {
 
         // Precompile dispatch first
         if self.is_precompile_address(&to) {
-            self.execute_precompile(&to, &data, from, context).await?;
+            self.execute_precompile(&to, &data, from, value, context).await?;
             return Ok(());
         }
 
@@ -941,7 +1375,10 @@ impl Executor {
             );
             let available_gas = context.gas_limit.saturating_sub(context.gas_used);
             let mut vm = VM::new(available_gas);
-            let vm_output = match vm.execute_with_input(&code, &data) {
+            vm.tracer = context.tracer.take();
+            let vm_result = vm.execute_with_input(&code, &data);
+            context.tracer = vm.tracer.take();
+            let vm_output = match vm_result {
                 Ok(out) => {
                     VM_EXECUTIONS_TOTAL.with_label_values(&["ok"]).inc();
                     out
@@ -974,7 +1411,16 @@ impl Executor {
         let model = Self::model_precompile_address();
         let artifact = Self::artifact_precompile_address();
         let governance = Self::governance_precompile_address();
-        *addr == model || *addr == artifact || *addr == governance
+        *addr == model
+            || *addr == artifact
+            || *addr == governance
+            || *addr == STAKING_PRECOMPILE
+            || *addr == VALIDATOR_KEYS_PRECOMPILE
+            || *addr == PROPOSALS_PRECOMPILE
+            || *addr == NAMES_PRECOMPILE
+            || *addr == RANDOMNESS_PRECOMPILE
+            || *addr == MULTISIG_PRECOMPILE
+            || *addr == COMPUTE_PRECOMPILE
     }
 
     fn model_precompile_address() -> Address {
@@ -1006,6 +1452,7 @@ impl Executor {
         to: &Address,
         data: &[u8],
         from: Address,
+        value: U256,
         context: &mut ExecutionContext,
     ) -> Result<(), ExecutionError> {
         if *to == Self::model_precompile_address() {
@@ -1037,6 +1484,83 @@ impl Executor {
                     .inc(),
             }
             res
+        } else if *to == STAKING_PRECOMPILE {
+            let res = staking::execute(&self.state_db, data, from, value, context);
+            match &res {
+                Ok(()) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["staking", "unknown", "ok"])
+                    .inc(),
+                Err(_) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["staking", "unknown", "err"])
+                    .inc(),
+            }
+            res
+        } else if *to == VALIDATOR_KEYS_PRECOMPILE {
+            let res = validator_keys::execute(&self.state_db, data, from, value, context);
+            match &res {
+                Ok(()) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["validator_keys", "unknown", "ok"])
+                    .inc(),
+                Err(_) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["validator_keys", "unknown", "err"])
+                    .inc(),
+            }
+            res
+        } else if *to == PROPOSALS_PRECOMPILE {
+            let res = proposals::execute(&self.state_db, data, from, value, context);
+            match &res {
+                Ok(()) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["proposals", "unknown", "ok"])
+                    .inc(),
+                Err(_) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["proposals", "unknown", "err"])
+                    .inc(),
+            }
+            res
+        } else if *to == NAMES_PRECOMPILE {
+            let res = names::execute(&self.state_db, data, from, value, context);
+            match &res {
+                Ok(()) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["names", "unknown", "ok"])
+                    .inc(),
+                Err(_) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["names", "unknown", "err"])
+                    .inc(),
+            }
+            res
+        } else if *to == RANDOMNESS_PRECOMPILE {
+            let res = randomness::execute(data, from, value, context);
+            match &res {
+                Ok(()) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["randomness", "unknown", "ok"])
+                    .inc(),
+                Err(_) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["randomness", "unknown", "err"])
+                    .inc(),
+            }
+            res
+        } else if *to == MULTISIG_PRECOMPILE {
+            let res = multisig::execute(&self.state_db, data, from, value, context);
+            match &res {
+                Ok(()) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["multisig", "unknown", "ok"])
+                    .inc(),
+                Err(_) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["multisig", "unknown", "err"])
+                    .inc(),
+            }
+            res
+        } else if *to == COMPUTE_PRECOMPILE {
+            let res = compute::execute(&self.state_db, data, from, value, context);
+            match &res {
+                Ok(()) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["compute", "unknown", "ok"])
+                    .inc(),
+                Err(_) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["compute", "unknown", "err"])
+                    .inc(),
+            }
+            res
         } else {
             Err(ExecutionError::InvalidInput)
         }
@@ -1205,6 +1729,9 @@ impl Executor {
         let sel_infer = &Keccak256::digest(b"executeInference(bytes32,bytes)")[..4];
         let sel_pin = &Keccak256::digest(b"pin(string,uint256)")[..4];
         let sel_status = &Keccak256::digest(b"status(string)")[..4];
+        let sel_deprecate = &Keccak256::digest(b"deprecateModel(bytes32)")[..4];
+        let sel_royalty =
+            &Keccak256::digest(b"setRoyaltySchedule(bytes32,uint16,uint16,uint16)")[..4];
 
         if selector == sel_register || selector == sel_register_ex {
             if args.len() < 64 {
@@ -1402,67 +1929,251 @@ impl Executor {
             };
             context.output = status.into_bytes();
             Ok(())
+        } else if selector == sel_deprecate {
+            // deprecateModel(bytes32 modelHash): owner only, once
+            if args.len() < 32 {
+                return Err(ExecutionError::InvalidInput);
+            }
+            let mut mh = [0u8; 32];
+            mh.copy_from_slice(&args[0..32]);
+            let model_hash = Hash::new(mh);
+            let res = self.execute_deprecate_model(from, model_hash, context);
+            match &res {
+                Ok(()) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["model", "deprecateModel", "ok"])
+                    .inc(),
+                Err(_) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["model", "deprecateModel", "err"])
+                    .inc(),
+            }
+            res
+        } else if selector == sel_royalty {
+            // setRoyaltySchedule(bytes32 modelHash, uint16 ownerBps,
+            // uint16 providerBps, uint16 treasuryBps): owner only
+            if args.len() < 128 {
+                return Err(ExecutionError::InvalidInput);
+            }
+            let mut mh = [0u8; 32];
+            mh.copy_from_slice(&args[0..32]);
+            let model_hash = Hash::new(mh);
+            let share = |i: usize| -> Result<u16, ExecutionError> {
+                primitive_types::U256::from_big_endian(&args[i * 32..(i + 1) * 32])
+                    .try_into()
+                    .map_err(|_| ExecutionError::InvalidInput)
+            };
+            let schedule = RoyaltySchedule {
+                owner_bps: share(1)?,
+                provider_bps: share(2)?,
+                treasury_bps: share(3)?,
+            };
+            let res = self
+                .execute_set_royalty_schedule(from, model_hash, schedule, context)
+                .await;
+            match &res {
+                Ok(()) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["model", "setRoyaltySchedule", "ok"])
+                    .inc(),
+                Err(_) => PRECOMPILE_CALLS_TOTAL
+                    .with_label_values(&["model", "setRoyaltySchedule", "err"])
+                    .inc(),
+            }
+            res
         } else {
             Err(ExecutionError::InvalidInput)
         }
     }
 
-    fn artifact_index_key(model_hash: &Hash) -> Vec<u8> {
-        let mut k = b"MODEL_ARTS:".to_vec();
+    fn deprecation_key(model_hash: &Hash) -> Vec<u8> {
+        let mut k = b"MODEL_DEPRECATED:".to_vec();
         k.extend_from_slice(model_hash.as_bytes());
         k
     }
 
-    fn add_model_artifact(&self, model_hash: &Hash, cid: &str) {
-        let addr = Self::artifact_precompile_address();
-        let key = Self::artifact_index_key(model_hash);
-        let mut list: Vec<String> = if let Some(bytes) = self.state_db.get_storage(&addr, &key) {
-            serde_json::from_slice(&bytes).unwrap_or_default()
-        } else {
-            Vec::new()
-        };
-        if !list.iter().any(|c| c == cid) {
-            list.push(cid.to_string());
-        }
-        if let Ok(bytes) = serde_json::to_vec(&list) {
-            self.state_db.set_storage(addr, key, bytes);
-        }
-    }
-
-    pub fn list_model_artifacts(&self, model_hash: &Hash) -> Vec<String> {
-        let addr = Self::artifact_precompile_address();
-        let key = Self::artifact_index_key(model_hash);
-        if let Some(bytes) = self.state_db.get_storage(&addr, &key) {
-            serde_json::from_slice(&bytes).unwrap_or_default()
-        } else {
-            Vec::new()
-        }
+    /// Height at which the owner deprecated a model, if they have
+    pub fn model_deprecated_at(&self, model_hash: &Hash) -> Option<u64> {
+        self.state_db
+            .get_storage(
+                &Self::model_precompile_address(),
+                &Self::deprecation_key(model_hash),
+            )
+            .filter(|v| v.len() == 8)
+            .map(|v| {
+                let mut height = [0u8; 8];
+                height.copy_from_slice(&v);
+                u64::from_le_bytes(height)
+            })
     }
 
-    fn proof_index_key(model_hash: &Hash) -> Vec<u8> {
-        let mut k = b"MODEL_PROOFS:".to_vec();
+    fn royalty_key(model_hash: &Hash) -> Vec<u8> {
+        let mut k = b"MODEL_ROYALTY:".to_vec();
         k.extend_from_slice(model_hash.as_bytes());
         k
     }
 
-    fn add_model_proof_artifact(&self, model_hash: &Hash, cid: &str) {
-        let addr = Self::artifact_precompile_address();
-        let key = Self::proof_index_key(model_hash);
-        let mut list: Vec<String> = if let Some(bytes) = self.state_db.get_storage(&addr, &key) {
-            serde_json::from_slice(&bytes).unwrap_or_default()
-        } else {
-            Vec::new()
-        };
-        if !list.iter().any(|c| c == cid) {
-            list.push(cid.to_string());
-        }
-        if let Ok(bytes) = serde_json::to_vec(&list) {
-            self.state_db.set_storage(addr, key, bytes);
-        }
+    /// Royalty schedule of a model, or the default split if the owner has
+    /// not set one
+    pub fn model_royalty_schedule(&self, model_hash: &Hash) -> RoyaltySchedule {
+        self.state_db
+            .get_storage(
+                &Self::model_precompile_address(),
+                &Self::royalty_key(model_hash),
+            )
+            .and_then(|v| RoyaltySchedule::from_bytes(&v))
+            .unwrap_or_default()
     }
 
-    pub fn list_model_proofs(&self, model_hash: &Hash) -> Vec<String> {
-        let addr = Self::artifact_precompile_address();
+    /// Store the royalty schedule applied to a model's pay-per-use fees
+    async fn execute_set_royalty_schedule(
+        &self,
+        from: Address,
+        model_hash: Hash,
+        schedule: RoyaltySchedule,
+        context: &mut ExecutionContext,
+    ) -> Result<(), ExecutionError> {
+        context.use_gas(self.gas_schedule.model_update)?;
+
+        let model_id = ModelId(model_hash);
+        let model = self
+            .state_db
+            .get_model(&model_id)
+            .ok_or(ExecutionError::ModelNotFound(model_id))?;
+        if model.owner != from {
+            return Err(ExecutionError::AccessDenied);
+        }
+        if !schedule.is_valid() {
+            return Err(ExecutionError::Reverted(
+                "Royalty shares must add up to 10000 bps".into(),
+            ));
+        }
+
+        self.state_db.set_storage(
+            Self::model_precompile_address(),
+            Self::royalty_key(&model_hash),
+            schedule.to_bytes(),
+        );
+
+        if let Some(adapter) = &self.model_registry {
+            if let Err(err) = adapter.set_royalty_schedule(model_id, &schedule).await {
+                warn!(
+                    "Model registry adapter royalty update failed for {:?}: {}",
+                    model_id, err
+                );
+            }
+        }
+
+        let mut data = Vec::with_capacity(3 * 32);
+        for bps in [
+            schedule.owner_bps,
+            schedule.provider_bps,
+            schedule.treasury_bps,
+        ] {
+            let mut word = [0u8; 32];
+            word[30..].copy_from_slice(&bps.to_be_bytes());
+            data.extend_from_slice(&word);
+        }
+        context.add_log(Log {
+            address: from,
+            topics: vec![Hash::new(MODEL_ROYALTY_SET_TOPIC), model_hash],
+            data,
+        });
+
+        info!("Royalty schedule set for {:?}: {:?}", model_id, schedule);
+        Ok(())
+    }
+
+    /// Mark a model deprecated. It stays usable; the flag and log tell
+    /// consumers to move to a newer model.
+    fn execute_deprecate_model(
+        &self,
+        from: Address,
+        model_hash: Hash,
+        context: &mut ExecutionContext,
+    ) -> Result<(), ExecutionError> {
+        context.use_gas(self.gas_schedule.model_update)?;
+
+        let model_id = ModelId(model_hash);
+        let model = self
+            .state_db
+            .get_model(&model_id)
+            .ok_or(ExecutionError::ModelNotFound(model_id))?;
+        if model.owner != from {
+            return Err(ExecutionError::AccessDenied);
+        }
+        if self.model_deprecated_at(&model_hash).is_some() {
+            return Err(ExecutionError::Reverted("Model already deprecated".into()));
+        }
+
+        self.state_db.set_storage(
+            Self::model_precompile_address(),
+            Self::deprecation_key(&model_hash),
+            context.block_number.to_le_bytes().to_vec(),
+        );
+        context.add_log(Log {
+            address: from,
+            topics: vec![Hash::new(MODEL_DEPRECATED_TOPIC), model_hash],
+            data: Vec::new(),
+        });
+
+        info!("Model deprecated: {:?} by {}", model_id, from);
+        Ok(())
+    }
+
+    fn artifact_index_key(model_hash: &Hash) -> Vec<u8> {
+        let mut k = b"MODEL_ARTS:".to_vec();
+        k.extend_from_slice(model_hash.as_bytes());
+        k
+    }
+
+    fn add_model_artifact(&self, model_hash: &Hash, cid: &str) {
+        let addr = Self::artifact_precompile_address();
+        let key = Self::artifact_index_key(model_hash);
+        let mut list: Vec<String> = if let Some(bytes) = self.state_db.get_storage(&addr, &key) {
+            serde_json::from_slice(&bytes).unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        if !list.iter().any(|c| c == cid) {
+            list.push(cid.to_string());
+        }
+        if let Ok(bytes) = serde_json::to_vec(&list) {
+            self.state_db.set_storage(addr, key, bytes);
+        }
+    }
+
+    pub fn list_model_artifacts(&self, model_hash: &Hash) -> Vec<String> {
+        let addr = Self::artifact_precompile_address();
+        let key = Self::artifact_index_key(model_hash);
+        if let Some(bytes) = self.state_db.get_storage(&addr, &key) {
+            serde_json::from_slice(&bytes).unwrap_or_default()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn proof_index_key(model_hash: &Hash) -> Vec<u8> {
+        let mut k = b"MODEL_PROOFS:".to_vec();
+        k.extend_from_slice(model_hash.as_bytes());
+        k
+    }
+
+    fn add_model_proof_artifact(&self, model_hash: &Hash, cid: &str) {
+        let addr = Self::artifact_precompile_address();
+        let key = Self::proof_index_key(model_hash);
+        let mut list: Vec<String> = if let Some(bytes) = self.state_db.get_storage(&addr, &key) {
+            serde_json::from_slice(&bytes).unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        if !list.iter().any(|c| c == cid) {
+            list.push(cid.to_string());
+        }
+        if let Ok(bytes) = serde_json::to_vec(&list) {
+            self.state_db.set_storage(addr, key, bytes);
+        }
+    }
+
+    pub fn list_model_proofs(&self, model_hash: &Hash) -> Vec<String> {
+        let addr = Self::artifact_precompile_address();
         let key = Self::proof_index_key(model_hash);
         if let Some(bytes) = self.state_db.get_storage(&addr, &key) {
             serde_json::from_slice(&bytes).unwrap_or_default()
@@ -1509,6 +2220,16 @@ impl Executor {
         1
     }
 
+    /// Per-block limits with any governance overrides (PARAM:max_block_*) applied on top of
+    /// `defaults`.
+    pub fn governance_block_limits(&self, defaults: BlockLimits) -> BlockLimits {
+        let gov_addr = Self::governance_precompile_address();
+        defaults.with_overrides(|name| {
+            let key = format!("PARAM:{}", name);
+            self.state_db.get_storage(&gov_addr, key.as_bytes())
+        })
+    }
+
     /// Execute an inference using the configured inference service without mutating state.
     pub async fn run_inference_preview(
         &self,
@@ -1828,8 +2549,8 @@ impl Executor {
         // Add registration log
         context.add_log(Log {
             address: from,
-            topics: vec![Hash::new(*b"ModelRegistered00000000000000000"), model_hash],
-            data: vec![],
+            topics: vec![Hash::new(MODEL_REGISTERED_TOPIC), model_hash],
+            data: model_event_data(1, artifact_cid.as_deref()),
         });
 
         info!("Model registered: {:?} by {}", model_id, from);
@@ -1901,6 +2622,12 @@ impl Executor {
             }
         }
 
+        context.add_log(Log {
+            address: from,
+            topics: vec![Hash::new(MODEL_UPDATED_TOPIC), updated_model.model_hash],
+            data: model_event_data(updated_model.version, artifact_cid.as_deref()),
+        });
+
         info!(
             "Model updated: {:?} to version {}",
             model_id, updated_model.version
@@ -1935,30 +2662,22 @@ impl Executor {
             .ok_or(ExecutionError::ModelNotFound(model_id))?;
 
         // Check access policy
-        match &model.access_policy {
-            AccessPolicy::Public => {}
-            AccessPolicy::Private if model.owner == from => {}
-            AccessPolicy::Restricted(allowed) if allowed.contains(&from) => {}
+        let fee = match &model.access_policy {
+            AccessPolicy::Public => None,
+            AccessPolicy::Private if model.owner == from => None,
+            AccessPolicy::Restricted(allowed) if allowed.contains(&from) => None,
             AccessPolicy::PayPerUse { fee } => {
-                // Split fee: 10% protocol treasury, 90% to model owner
-                let treasury_address = Address([0x11; 20]);
-                let treasury_cut = *fee / U256::from(10u8);
-                let owner_cut = *fee - treasury_cut;
-                // Perform transfers
-                self.state_db
-                    .accounts
-                    .transfer(&from, &model.owner, owner_cut)?;
-                if treasury_cut > U256::zero() {
-                    self.state_db
-                        .accounts
-                        .transfer(&from, &treasury_address, treasury_cut)?;
+                let have = self.state_db.accounts.get_balance(&from);
+                if have < *fee {
+                    return Err(ExecutionError::InsufficientBalance { need: *fee, have });
                 }
-                model.usage_stats.total_fees_earned += *fee;
+                Some(*fee)
             }
             _ => return Err(ExecutionError::AccessDenied),
-        }
+        };
 
         // Delegate to inference service if configured, otherwise simulate
+        let mut provider = None;
         if let Some(svc) = &self.inference_service {
             let remaining = context.gas_limit.saturating_sub(context.gas_used);
             let (out, gas_used, provider_addr, provider_fee, proof_bytes_opt) = svc
@@ -1968,6 +2687,7 @@ impl Executor {
             if gas_used > 0 {
                 context.use_gas(gas_used)?;
             }
+            provider = Some(provider_addr);
             // Pay provider
             if provider_fee > U256::zero() {
                 self.state_db
@@ -1990,6 +2710,11 @@ impl Executor {
             context.output = vec![0x01, 0x02, 0x03, 0x04];
         }
 
+        if let Some(fee) = fee {
+            self.pay_royalties(from, &model, provider, fee, context)?;
+            model.usage_stats.total_fees_earned += fee;
+        }
+
         // Update usage stats
         model.usage_stats.total_inferences += 1;
         model.usage_stats.total_gas_used += context.gas_used;
@@ -2000,6 +2725,42 @@ impl Executor {
         Ok(())
     }
 
+    /// Split a pay-per-use fee per the model's royalty schedule and log it
+    fn pay_royalties(
+        &self,
+        from: Address,
+        model: &ModelState,
+        provider: Option<Address>,
+        fee: U256,
+        context: &mut ExecutionContext,
+    ) -> Result<(), ExecutionError> {
+        let schedule = self.model_royalty_schedule(&model.model_hash);
+        let split = schedule.split(fee, provider.is_some());
+
+        let payouts = [
+            (model.owner, split.owner),
+            (provider.unwrap_or(model.owner), split.provider),
+            (INFERENCE_TREASURY_ADDRESS, split.treasury),
+        ];
+        for (to, amount) in payouts {
+            if amount > U256::zero() {
+                self.state_db.accounts.transfer(&from, &to, amount)?;
+            }
+        }
+
+        context.add_log(
+            RoyaltyPayment {
+                model_hash: model.model_hash,
+                payer: from,
+                owner: model.owner,
+                provider,
+                split,
+            }
+            .to_log(),
+        );
+        Ok(())
+    }
+
     /// Execute gradient submission
     async fn execute_submit_gradient(
         &self,
@@ -2159,6 +2920,7 @@ mod tests {
                 base_fee_per_gas: 0,
                 gas_used: 0,
                 gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
             },
             state_root: Hash::default(),
             tx_root: Hash::default(),
@@ -2216,6 +2978,159 @@ mod tests {
         assert_eq!(state_db.accounts.get_balance(&bob_addr), U256::from(1000));
     }
 
+    #[tokio::test]
+    async fn test_simulate_transaction_with_overrides() {
+        let state_db = Arc::new(StateDB::new());
+        let executor = Executor::new(state_db.clone());
+
+        let alice = PublicKey::new([1; 32]);
+        let bob = PublicKey::new([2; 32]);
+        let alice_addr = Address::from_public_key(&alice);
+        let bob_addr = Address::from_public_key(&bob);
+
+        let block = create_test_block();
+        let tx = create_test_tx(alice, Some(bob), 1000, 0);
+
+        // Alice has no funds until they are overridden
+        let no_overrides = StateOverrides::new();
+        assert!(executor
+            .simulate_transaction(state_db.fork(), &block, &tx, &no_overrides)
+            .await
+            .is_err());
+
+        let funded = U256::from(1_000_000_000_000_000u128);
+        let mut overrides = StateOverrides::new();
+        overrides.insert(
+            alice_addr,
+            crate::simulation::AccountOverride {
+                balance: Some(funded),
+                ..Default::default()
+            },
+        );
+        let simulation = executor
+            .simulate_transaction(state_db.fork(), &block, &tx, &overrides)
+            .await
+            .unwrap();
+
+        assert!(simulation.receipt.status);
+        let gas_cost = U256::from(simulation.receipt.gas_used) * U256::from(tx.gas_price);
+        let alice_diff = simulation
+            .state_diff
+            .iter()
+            .find(|diff| diff.address == alice_addr)
+            .unwrap();
+        assert_eq!(alice_diff.balance.as_ref().unwrap().from, funded);
+        assert_eq!(
+            alice_diff.balance.as_ref().unwrap().to,
+            funded - gas_cost - U256::from(1000)
+        );
+        assert_eq!(alice_diff.nonce.as_ref().unwrap().to, 1);
+        let bob_diff = simulation
+            .state_diff
+            .iter()
+            .find(|diff| diff.address == bob_addr)
+            .unwrap();
+        assert_eq!(bob_diff.balance.as_ref().unwrap().to, U256::from(1000));
+
+        // The live state is untouched
+        assert_eq!(state_db.accounts.get_balance(&alice_addr), U256::zero());
+        assert_eq!(state_db.accounts.get_balance(&bob_addr), U256::zero());
+        assert_eq!(state_db.accounts.get_nonce(&alice_addr), 0);
+    }
+
+    #[tokio::test]
+    async fn test_constructor_selfdestruct_is_internal_transaction() {
+        let state_db = Arc::new(StateDB::new());
+        let executor = Executor::new(state_db.clone());
+
+        let deployer = PublicKey::new([6; 32]);
+        let deployer_addr = Address::from_public_key(&deployer);
+        state_db
+            .accounts
+            .set_balance(deployer_addr, U256::from(1_000_000_000_000_000u128));
+
+        // Pre-fund the address the contract will be created at; revm derives
+        // it from the nonce after the executor's increment
+        let created = revm::primitives::Address::from_slice(&deployer_addr.0).create(1);
+        let contract = Address(created.0 .0);
+        state_db.accounts.set_balance(contract, U256::from(5_000));
+
+        // PUSH20 beneficiary SELFDESTRUCT
+        let beneficiary = Address([0xbe; 20]);
+        let mut code = vec![0x73];
+        code.extend_from_slice(&beneficiary.0);
+        code.push(0xff);
+        let mut tx = create_test_tx(deployer, None, 0, 0);
+        tx.data = code;
+        // Covers the new-account charge for funding the beneficiary
+        tx.gas_limit = 200_000;
+
+        let block = create_test_block();
+        let (receipt, internal) = executor
+            .execute_transaction_with_internal(&block, &tx)
+            .await
+            .unwrap();
+        assert!(receipt.status);
+        assert_eq!(
+            internal,
+            vec![InternalTransaction {
+                kind: crate::tracer::InternalTxKind::SelfDestruct,
+                from: contract,
+                to: beneficiary,
+                value: U256::from(5_000),
+                depth: 0,
+            }]
+        );
+        assert_eq!(state_db.accounts.get_balance(&beneficiary), U256::from(5_000));
+    }
+
+    #[tokio::test]
+    async fn test_trace_contract_deploy() {
+        let state_db = Arc::new(StateDB::new());
+        let executor = Executor::new(state_db.clone());
+
+        let deployer = PublicKey::new([5; 32]);
+        state_db.accounts.set_balance(
+            Address::from_public_key(&deployer),
+            U256::from(1_000_000_000_000_000u128),
+        );
+        let block = create_test_block();
+
+        // PUSH1 0 PUSH1 0 RETURN: deploys empty runtime code
+        let mut tx = create_test_tx(deployer, None, 0, 0);
+        tx.data = vec![0x60, 0x00, 0x60, 0x00, 0xf3];
+        let options = TraceOptions {
+            opcodes: true,
+            stack: true,
+            ..Default::default()
+        };
+        let (receipt, trace) = executor
+            .trace_transaction(&block, &tx, options.clone())
+            .await
+            .unwrap();
+        assert!(receipt.status);
+        assert_eq!(trace.call.call_type, CallKind::Create);
+        assert!(trace.call.to.is_some());
+        assert!(trace.call.error.is_none());
+        let ops: Vec<&str> = trace.struct_logs.iter().map(|l| l.op.as_str()).collect();
+        assert_eq!(ops, vec!["PUSH1", "PUSH1", "RETURN"]);
+        assert_eq!(trace.struct_logs[2].stack.as_ref().unwrap().len(), 2);
+
+        // PUSH1 0 PUSH1 0 REVERT
+        let nonce = state_db
+            .accounts
+            .get_nonce(&Address::from_public_key(&deployer));
+        let mut tx = create_test_tx(deployer, None, 0, nonce);
+        tx.data = vec![0x60, 0x00, 0x60, 0x00, 0xfd];
+        let (receipt, trace) = executor
+            .trace_transaction(&block, &tx, TraceOptions::default())
+            .await
+            .unwrap();
+        assert!(!receipt.status);
+        assert_eq!(trace.call.error.as_deref(), Some("execution reverted"));
+        assert!(trace.struct_logs.is_empty());
+    }
+
     #[tokio::test]
     async fn test_register_model_via_transaction_payload() {
         let state_db = Arc::new(StateDB::new());
@@ -2528,4 +3443,1169 @@ mod tests {
         assert!(rcpt_get.status);
         assert_eq!(rcpt_get.output, value);
     }
+
+    #[tokio::test]
+    async fn test_staking_precompile_unbonding() {
+        use sha3::{Digest, Keccak256};
+
+        let state_db = Arc::new(StateDB::new());
+        let executor = Executor::new(state_db.clone());
+
+        let staker = Address([0x22; 20]);
+        let mut staker_pk = [0u8; 32];
+        staker_pk[..20].copy_from_slice(&staker.0);
+        let staker_pk = PublicKey::new(staker_pk);
+        state_db
+            .accounts
+            .set_balance(staker, U256::from(10_000_000_000_000_000_000u128));
+
+        let mut staking_pk = [0u8; 32];
+        staking_pk[..20].copy_from_slice(&STAKING_PRECOMPILE.0);
+        let staking_pk = PublicKey::new(staking_pk);
+
+        let call = |nonce: u64, value: u128, data: Vec<u8>| Transaction {
+            hash: Hash::new([30 + nonce as u8; 32]),
+            nonce,
+            from: staker_pk,
+            to: Some(staking_pk),
+            value,
+            gas_limit: 200000,
+            gas_price: 1,
+            data,
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        };
+
+        let mut block = create_test_block();
+
+        // stake() bonds the attached value
+        let stake = Keccak256::digest(b"stake()")[..4].to_vec();
+        let rcpt = executor
+            .execute_transaction(&block, &call(0, 5_000, stake))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(staking::bonded(&state_db, &staker), U256::from(5_000));
+        assert_eq!(
+            state_db.accounts.get_balance(&STAKING_PRECOMPILE),
+            U256::from(5_000)
+        );
+
+        // unstake(2000) starts the unbonding period
+        let mut unstake = Keccak256::digest(b"unstake(uint256)")[..4].to_vec();
+        let mut amount = [0u8; 32];
+        U256::from(2_000).to_big_endian(&mut amount);
+        unstake.extend_from_slice(&amount);
+        let rcpt = executor
+            .execute_transaction(&block, &call(1, 0, unstake))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(staking::bonded(&state_db, &staker), U256::from(3_000));
+        let (pending, release) = staking::unbonding(&state_db, &staker);
+        assert_eq!(pending, U256::from(2_000));
+        assert_eq!(release, block.header.height + staking::UNBONDING_PERIOD);
+
+        // withdraw() is rejected until the period has passed
+        let withdraw = Keccak256::digest(b"withdraw()")[..4].to_vec();
+        let rcpt = executor
+            .execute_transaction(&block, &call(2, 0, withdraw.clone()))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+
+        block.header.height = release;
+        let before = state_db.accounts.get_balance(&staker);
+        let (rcpt, internal) = executor
+            .execute_transaction_with_internal(&block, &call(3, 0, withdraw))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(staking::unbonding(&state_db, &staker).0, U256::zero());
+        assert_eq!(
+            state_db.accounts.get_balance(&staker),
+            before + U256::from(2_000) - U256::from(rcpt.gas_used)
+        );
+        assert_eq!(internal.len(), 1);
+        assert_eq!(internal[0].to, staker);
+        assert_eq!(internal[0].value, U256::from(2_000));
+    }
+
+    #[tokio::test]
+    async fn test_validator_key_rotation_precompile() {
+        use citrate_consensus::crypto::{generate_keypair, sign_message};
+
+        let state_db = Arc::new(StateDB::new());
+        let executor = Executor::new(state_db.clone());
+
+        let old_key = generate_keypair();
+        let old_pk = PublicKey::new(old_key.verifying_key().to_bytes());
+        let old_addr = Address::from_public_key(&old_pk);
+        state_db
+            .accounts
+            .set_balance(old_addr, U256::from(1_000_000_000u64));
+        let new_key = generate_keypair();
+        let new_pk = PublicKey::new(new_key.verifying_key().to_bytes());
+
+        let mut registry_pk = [0u8; 32];
+        registry_pk[..20].copy_from_slice(&VALIDATOR_KEYS_PRECOMPILE.0);
+        let registry_pk = PublicKey::new(registry_pk);
+
+        let call = |nonce: u64, data: Vec<u8>| Transaction {
+            hash: Hash::new([40 + nonce as u8; 32]),
+            nonce,
+            from: old_pk,
+            to: Some(registry_pk),
+            value: 0,
+            gas_limit: 100000,
+            gas_price: 1,
+            data,
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        };
+        let block = create_test_block();
+
+        // A proof signed by some other key is rejected
+        let forged = sign_message(&old_key, &validator_keys::rotation_message(&old_addr));
+        let forged = validator_keys::rotate_call_data(&new_pk, &forged);
+        let rcpt = executor
+            .execute_transaction(&block, &call(0, forged))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+        assert!(validator_keys::rotated_to(&state_db, &old_addr).is_none());
+
+        let proof = sign_message(&new_key, &validator_keys::rotation_message(&old_addr));
+        let rotate = validator_keys::rotate_call_data(&new_pk, &proof);
+        let rcpt = executor
+            .execute_transaction(&block, &call(1, rotate.clone()))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(rcpt.logs.len(), 1);
+        assert_eq!(
+            validator_keys::rotated_to(&state_db, &old_addr),
+            Some((new_pk, block.header.height))
+        );
+
+        // A retired key cannot be rotated a second time
+        let rcpt = executor
+            .execute_transaction(&block, &call(2, rotate))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+
+        let mut query = Keccak256::digest(b"rotatedTo(address)")[..4].to_vec();
+        query.extend_from_slice(&[0u8; 12]);
+        query.extend_from_slice(&old_addr.0);
+        let rcpt = executor
+            .execute_transaction(&block, &call(3, query))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(&rcpt.output[..32], new_pk.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_randomness_precompile() {
+        let state_db = Arc::new(StateDB::new());
+        let executor = Executor::new(state_db.clone());
+
+        let caller_pk = PublicKey::new([0x51; 32]);
+        let caller = Address::from_public_key(&caller_pk);
+        state_db
+            .accounts
+            .set_balance(caller, U256::from(1_000_000_000u64));
+
+        let mut precompile_pk = [0u8; 32];
+        precompile_pk[..20].copy_from_slice(&RANDOMNESS_PRECOMPILE.0);
+        let precompile_pk = PublicKey::new(precompile_pk);
+        let call = |nonce: u64, data: Vec<u8>| Transaction {
+            hash: Hash::new([60 + nonce as u8; 32]),
+            nonce,
+            from: caller_pk,
+            to: Some(precompile_pk),
+            value: 0,
+            gas_limit: 100000,
+            gas_price: 1,
+            data,
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        };
+        let sel = |signature: &[u8]| Keccak256::digest(signature)[..4].to_vec();
+
+        // Blocks without a VRF reveal have no randomness to offer
+        let rcpt = executor
+            .execute_transaction(&create_test_block(), &call(0, sel(b"randomness()")))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+
+        let mut block = create_test_block();
+        block.header.proposer_pubkey = PublicKey::new([0x52; 32]);
+        block.header.vrf_reveal = VrfProof {
+            proof: vec![0x53; 32],
+            output: Hash::new([0x54; 32]),
+        };
+
+        let rcpt = executor
+            .execute_transaction(&block, &call(1, sel(b"randomness()")))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(rcpt.output, vec![0x54; 32]);
+
+        let seed = [0x55; 32];
+        let mut seeded = sel(b"randomWithSeed(bytes32)");
+        seeded.extend_from_slice(&seed);
+        let rcpt = executor
+            .execute_transaction(&block, &call(2, seeded))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        let seeded_gas = rcpt.gas_used;
+        let derived = randomness::derive_with_seed(&Hash::new([0x54; 32]), &caller, &seed);
+        assert_eq!(rcpt.output, derived.to_vec());
+        assert_ne!(
+            derived,
+            randomness::derive_with_seed(&Hash::new([0x54; 32]), &caller, &[0; 32])
+        );
+        assert_ne!(
+            derived,
+            randomness::derive_with_seed(&Hash::new([0x54; 32]), &Address([1; 20]), &seed)
+        );
+
+        let rcpt = executor
+            .execute_transaction(&block, &call(3, sel(b"vrfProof()")))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(rcpt.output.len(), 160);
+        assert_eq!(&rcpt.output[..32], &[0x52; 32]);
+        assert_eq!(&rcpt.output[32..64], &[0x54; 32]);
+        assert_eq!(&rcpt.output[128..], &[0x53; 32]);
+
+        // One gas short of paying for the derivation
+        let mut starved = call(4, sel(b"randomWithSeed(bytes32)"));
+        starved.data.extend_from_slice(&seed);
+        starved.gas_limit = seeded_gas - 1;
+        let rcpt = executor.execute_transaction(&block, &starved).await.unwrap();
+        assert!(!rcpt.status);
+    }
+
+    #[tokio::test]
+    async fn test_names_precompile() {
+        let state_db = Arc::new(StateDB::new());
+        let executor = Executor::new(state_db.clone());
+
+        let alice_pk = PublicKey::new([0x31; 32]);
+        let alice = Address::from_public_key(&alice_pk);
+        let bob = Address([0x32; 20]);
+        state_db
+            .accounts
+            .set_balance(alice, U256::from(1_000_000_000u64));
+
+        let mut names_pk = [0u8; 32];
+        names_pk[..20].copy_from_slice(&NAMES_PRECOMPILE.0);
+        let names_pk = PublicKey::new(names_pk);
+
+        let call = |nonce: u64, data: Vec<u8>| Transaction {
+            hash: Hash::new([60 + nonce as u8; 32]),
+            nonce,
+            from: alice_pk,
+            to: Some(names_pk),
+            value: 0,
+            gas_limit: 100000,
+            gas_price: 1,
+            data,
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        };
+        let block = create_test_block();
+
+        assert_eq!(names::normalize_name(" Alice.CTR "), Some("alice".to_string()));
+        assert!(names::encode_name("al").is_none());
+        assert!(names::encode_name("-alice").is_none());
+        let label = names::encode_name("alice.ctr").unwrap();
+
+        let rcpt = executor
+            .execute_transaction(&block, &call(0, names::register_call_data(&label)))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(names::resolve(&state_db, "Alice"), Some(alice));
+        assert_eq!(names::name_of(&state_db, &alice), Some("alice".to_string()));
+
+        // Names are first come first served
+        let rcpt = executor
+            .execute_transaction(&block, &call(1, names::register_call_data(&label)))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+
+        let rcpt = executor
+            .execute_transaction(&block, &call(2, names::transfer_call_data(&label, &bob)))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(names::resolve(&state_db, "alice"), Some(bob));
+        assert_eq!(names::name_of(&state_db, &alice), None);
+        assert_eq!(names::name_of(&state_db, &bob), Some("alice".to_string()));
+
+        // Only the owner can move a name
+        let rcpt = executor
+            .execute_transaction(&block, &call(3, names::transfer_call_data(&label, &alice)))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+
+        let mut query = Keccak256::digest(names::RESOLVE_SIGNATURE)[..4].to_vec();
+        query.extend_from_slice(&label);
+        let rcpt = executor
+            .execute_transaction(&block, &call(4, query))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(&rcpt.output[12..32], &bob.0);
+    }
+
+    #[tokio::test]
+    async fn test_proposals_precompile_lifecycle() {
+        use sha3::{Digest, Keccak256};
+
+        let state_db = Arc::new(StateDB::new());
+        let executor = Executor::new(state_db.clone());
+        let latt = |n: u64| U256::from(n) * U256::exp10(18);
+
+        let mut proposals_pk = [0u8; 32];
+        proposals_pk[..20].copy_from_slice(&PROPOSALS_PRECOMPILE.0);
+        let proposals_pk = PublicKey::new(proposals_pk);
+
+        // Two stakers: the proposer holds most of the stake
+        let mut stakers = Vec::new();
+        for (i, amount) in [(0x31u8, 20_000u64), (0x32, 5_000)] {
+            let addr = Address([i; 20]);
+            let mut pk = [0u8; 32];
+            pk[..20].copy_from_slice(&addr.0);
+            state_db.accounts.set_balance(addr, latt(1_000_000));
+            state_db.accounts.set_balance(
+                STAKING_PRECOMPILE,
+                state_db.accounts.get_balance(&STAKING_PRECOMPILE) + latt(amount),
+            );
+            let mut bonded = vec![0u8; 32];
+            latt(amount).to_big_endian(&mut bonded);
+            let mut key = b"BONDED:".to_vec();
+            key.extend_from_slice(&addr.0);
+            state_db.set_storage(STAKING_PRECOMPILE, key, bonded);
+            stakers.push((addr, PublicKey::new(pk)));
+        }
+        let (proposer, proposer_pk) = stakers[0];
+        let (voter, voter_pk) = stakers[1];
+
+        let call = |from: PublicKey, nonce: u64, data: Vec<u8>| Transaction {
+            hash: Hash::new([50 + nonce as u8; 32]),
+            nonce,
+            from,
+            to: Some(proposals_pk),
+            value: 0,
+            gas_limit: 200000,
+            gas_price: 1,
+            data,
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        };
+        let mut block = create_test_block();
+
+        // The smaller staker is below the proposal threshold
+        let propose = proposals::propose_call_data(
+            "min_gas_price",
+            &2_000_000_000u64.to_le_bytes(),
+            "Raise the gas price floor",
+        );
+        let rcpt = executor
+            .execute_transaction(&block, &call(voter_pk, 0, propose.clone()))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+
+        let rcpt = executor
+            .execute_transaction(&block, &call(proposer_pk, 0, propose))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(proposals::proposal_count(&state_db), 1);
+        let proposal = proposals::proposal(&state_db, 0).unwrap();
+        assert_eq!(proposal.proposer, proposer);
+        assert_eq!(proposal.parameter, "min_gas_price");
+        assert_eq!(proposal.quorum, latt(2_500));
+
+        // Each staker votes once, weighted by bonded stake
+        let vote_for = proposals::vote_call_data(0, proposals::VoteSupport::For);
+        let vote_against = proposals::vote_call_data(0, proposals::VoteSupport::Against);
+        let rcpt = executor
+            .execute_transaction(&block, &call(proposer_pk, 1, vote_for.clone()))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        let rcpt = executor
+            .execute_transaction(&block, &call(proposer_pk, 2, vote_for))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+        let rcpt = executor
+            .execute_transaction(&block, &call(voter_pk, 1, vote_against))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        let proposal = proposals::proposal(&state_db, 0).unwrap();
+        assert_eq!(proposal.votes_for, latt(20_000));
+        assert_eq!(proposal.votes_against, latt(5_000));
+        assert_eq!(
+            proposals::vote_of(&state_db, 0, &voter).map(|v| v.support),
+            Some(proposals::VoteSupport::Against)
+        );
+
+        // Execution waits for the voting period and the delay
+        let execute = proposals::execute_call_data(0);
+        block.header.height = proposal.end_height + 1;
+        assert_eq!(
+            proposal.state(block.header.height),
+            proposals::ProposalState::Queued
+        );
+        let rcpt = executor
+            .execute_transaction(&block, &call(voter_pk, 2, execute.clone()))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+
+        block.header.height = proposal.executable_height();
+        let rcpt = executor
+            .execute_transaction(&block, &call(voter_pk, 3, execute))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(
+            state_db.get_storage(
+                &Executor::governance_precompile_address(),
+                b"PARAM:min_gas_price"
+            ),
+            Some(2_000_000_000u64.to_le_bytes().to_vec())
+        );
+        assert_eq!(
+            proposals::proposal(&state_db, 0)
+                .unwrap()
+                .state(block.header.height),
+            proposals::ProposalState::Executed
+        );
+
+        let mut query = Keccak256::digest(b"stateOf(uint256)")[..4].to_vec();
+        query.extend_from_slice(&[0u8; 32]);
+        let rcpt = executor
+            .execute_transaction(&block, &call(voter_pk, 4, query))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(rcpt.output[31], proposals::ProposalState::Executed.as_u8());
+    }
+
+    #[tokio::test]
+    async fn test_multisig_precompile_lifecycle() {
+        let state_db = Arc::new(StateDB::new());
+        let executor = Executor::new(state_db.clone());
+        let latt = |n: u64| U256::from(n) * U256::exp10(18);
+
+        let mut multisig_pk = [0u8; 32];
+        multisig_pk[..20].copy_from_slice(&MULTISIG_PRECOMPILE.0);
+        let multisig_pk = PublicKey::new(multisig_pk);
+
+        // Three owners and an outsider
+        let mut accounts = Vec::new();
+        for i in [0x41u8, 0x42, 0x43, 0x44] {
+            let addr = Address([i; 20]);
+            let mut pk = [0u8; 32];
+            pk[..20].copy_from_slice(&addr.0);
+            state_db.accounts.set_balance(addr, latt(1_000));
+            accounts.push((addr, PublicKey::new(pk)));
+        }
+        let owners: Vec<Address> = accounts[..3].iter().map(|(addr, _)| *addr).collect();
+        let (_, alice_pk) = accounts[0];
+        let (_, bob_pk) = accounts[1];
+        let (outsider, outsider_pk) = accounts[3];
+
+        let call = |from: PublicKey, nonce: u64, value: u128, data: Vec<u8>| Transaction {
+            hash: Hash::new([90 + nonce as u8; 32]),
+            nonce,
+            from,
+            to: Some(multisig_pk),
+            value,
+            gas_limit: 200000,
+            gas_price: 1,
+            data,
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        };
+        let block = create_test_block();
+        let ten = 10_000_000_000_000_000_000u128;
+
+        // Thresholds above the owner count and duplicate owners are rejected
+        let rcpt = executor
+            .execute_transaction(
+                &block,
+                &call(alice_pk, 0, 0, multisig::create_call_data(&owners, 4)),
+            )
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+        let rcpt = executor
+            .execute_transaction(
+                &block,
+                &call(alice_pk, 1, 0, multisig::create_call_data(&[owners[0], owners[0]], 1)),
+            )
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+
+        // A 2-of-3 wallet funded at creation
+        let rcpt = executor
+            .execute_transaction(
+                &block,
+                &call(alice_pk, 2, ten, multisig::create_call_data(&owners, 2)),
+            )
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(rcpt.output[31], 0);
+        let wallet = multisig::wallet(&state_db, 0).unwrap();
+        assert_eq!(wallet.owners, owners);
+        assert_eq!(wallet.threshold, 2);
+        assert_eq!(wallet.balance, latt(10));
+        assert_eq!(state_db.accounts.get_balance(&MULTISIG_PRECOMPILE), latt(10));
+
+        // Only owners propose; proposing counts as an approval
+        let transfer = multisig::propose_call_data(0, &outsider, latt(4));
+        let rcpt = executor
+            .execute_transaction(&block, &call(outsider_pk, 0, 0, transfer.clone()))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+        let rcpt = executor
+            .execute_transaction(&block, &call(alice_pk, 3, 0, transfer))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        let proposal = multisig::proposal(&state_db, 0).unwrap();
+        assert_eq!(proposal.approvals, vec![owners[0]]);
+        assert_eq!(
+            multisig::proposals_of(&state_db, &multisig::wallet(&state_db, 0).unwrap()),
+            vec![proposal]
+        );
+
+        // One approval is not enough
+        let execute = multisig::execute_call_data(0);
+        let rcpt = executor
+            .execute_transaction(&block, &call(alice_pk, 4, 0, execute.clone()))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+
+        let approve = multisig::approve_call_data(0);
+        let rcpt = executor
+            .execute_transaction(&block, &call(outsider_pk, 1, 0, approve.clone()))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+        let rcpt = executor
+            .execute_transaction(&block, &call(bob_pk, 0, 0, approve.clone()))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        let rcpt = executor
+            .execute_transaction(&block, &call(bob_pk, 1, 0, approve))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+
+        let before = state_db.accounts.get_balance(&outsider);
+        let rcpt = executor
+            .execute_transaction(&block, &call(bob_pk, 2, 0, execute.clone()))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(state_db.accounts.get_balance(&outsider), before + latt(4));
+        assert_eq!(multisig::wallet(&state_db, 0).unwrap().balance, latt(6));
+        assert_eq!(state_db.accounts.get_balance(&MULTISIG_PRECOMPILE), latt(6));
+        assert!(multisig::proposal(&state_db, 0).unwrap().is_executed());
+
+        // Executed once only
+        let rcpt = executor
+            .execute_transaction(&block, &call(bob_pk, 3, 0, execute))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+    }
+
+    #[tokio::test]
+    async fn test_compute_settlement_precompile() {
+        let state_db = Arc::new(StateDB::new());
+        let executor = Executor::new(state_db.clone());
+        let latt = |n: u64| U256::from(n) * U256::exp10(18);
+
+        let mut compute_pk = [0u8; 32];
+        compute_pk[..20].copy_from_slice(&COMPUTE_PRECOMPILE.0);
+        let compute_pk = PublicKey::new(compute_pk);
+
+        let mut accounts = Vec::new();
+        for i in [0x51u8, 0x52] {
+            let addr = Address([i; 20]);
+            let mut pk = [0u8; 32];
+            pk[..20].copy_from_slice(&addr.0);
+            state_db.accounts.set_balance(addr, latt(1_000));
+            accounts.push((addr, PublicKey::new(pk)));
+        }
+        let (requester, requester_pk) = accounts[0];
+        let (provider, provider_pk) = accounts[1];
+
+        let call = |from: PublicKey, nonce: u64, value: u128, data: Vec<u8>| Transaction {
+            hash: Hash::new([110 + nonce as u8; 32]),
+            nonce,
+            from,
+            to: Some(compute_pk),
+            value,
+            gas_limit: 200000,
+            gas_price: 1,
+            data,
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        };
+        let block = create_test_block();
+        let job = Hash::new([0x77; 32]);
+        let ten = 10_000_000_000_000_000_000u128;
+
+        let rcpt = executor
+            .execute_transaction(
+                &block,
+                &call(requester_pk, 0, ten, compute::escrow_call_data(&job, &provider)),
+            )
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(state_db.accounts.get_balance(&COMPUTE_PRECOMPILE), latt(10));
+
+        // The same job cannot be escrowed twice, nor refunded early
+        let rcpt = executor
+            .execute_transaction(
+                &block,
+                &call(requester_pk, 1, ten, compute::escrow_call_data(&job, &provider)),
+            )
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+        let rcpt = executor
+            .execute_transaction(&block, &call(requester_pk, 2, 0, compute::refund_call_data(&job)))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+
+        let output_hash = Hash::new([0x01; 32]);
+        let fingerprint = Hash::new([0x02; 32]);
+        // Only the named provider settles, and never above the escrow
+        let settle = |amount: U256| {
+            compute::settle_call_data(&job, &output_hash, 42, &fingerprint, amount)
+        };
+        let rcpt = executor
+            .execute_transaction(&block, &call(requester_pk, 3, 0, settle(latt(4))))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+        let rcpt = executor
+            .execute_transaction(&block, &call(provider_pk, 0, 0, settle(latt(11))))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+
+        let provider_before = state_db.accounts.get_balance(&provider);
+        let requester_before = state_db.accounts.get_balance(&requester);
+        let rcpt = executor
+            .execute_transaction(&block, &call(provider_pk, 1, 0, settle(latt(4))))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        let gas = U256::from(rcpt.gas_used);
+        assert_eq!(
+            state_db.accounts.get_balance(&provider),
+            provider_before + latt(4) - gas
+        );
+        assert_eq!(state_db.accounts.get_balance(&requester), requester_before + latt(6));
+        assert!(state_db.accounts.get_balance(&COMPUTE_PRECOMPILE).is_zero());
+
+        let settlement = rcpt
+            .logs
+            .iter()
+            .find_map(compute::ComputeSettlement::from_log)
+            .unwrap();
+        assert_eq!(
+            settlement,
+            compute::ComputeSettlement {
+                job_id: job,
+                requester,
+                provider,
+                output_hash,
+                execution_secs: 42,
+                device_fingerprint: fingerprint,
+                amount: latt(4),
+                refund: latt(6),
+            }
+        );
+        let escrow = compute::job(&state_db, &job).unwrap();
+        assert!(!escrow.is_open());
+        assert_eq!(escrow.settlement, Some(settlement));
+
+        // Settled once only
+        let rcpt = executor
+            .execute_transaction(&block, &call(provider_pk, 2, 0, settle(latt(4))))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+    }
+
+    #[tokio::test]
+    async fn test_model_precompile_events_and_deprecation() {
+        let state_db = Arc::new(StateDB::new());
+        let executor = Executor::new(state_db.clone());
+
+        let owner_pk = PublicKey::new([6; 32]);
+        let owner = Address::from_public_key(&owner_pk);
+        let other_pk = PublicKey::new([7; 32]);
+        for pk in [&owner_pk, &other_pk] {
+            state_db.accounts.set_balance(
+                Address::from_public_key(pk),
+                U256::from(1_000_000_000_000_000u128),
+            );
+        }
+
+        let mut pc_bytes = [0u8; 32];
+        pc_bytes[18] = 0x10;
+        let precompile_pk = PublicKey::new(pc_bytes);
+        let call = |from: PublicKey, nonce: u64, data: Vec<u8>| Transaction {
+            hash: Hash::new([60 + nonce as u8; 32]),
+            nonce,
+            from,
+            to: Some(precompile_pk),
+            value: 0,
+            gas_limit: 200000,
+            gas_price: 1,
+            data,
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        };
+        let block = create_test_block();
+
+        let model_hash = [0x5a; 32];
+        let mut reg_data = Keccak256::digest(b"registerModel(bytes32,string)")[..4].to_vec();
+        reg_data.extend_from_slice(&model_hash);
+        reg_data.extend_from_slice(&[0u8; 31]);
+        reg_data.push(64);
+        reg_data.extend_from_slice(&[0u8; 31]);
+        reg_data.push(3);
+        reg_data.extend_from_slice(b"cid");
+        reg_data.extend_from_slice(&[0u8; 29]);
+        let rcpt = executor
+            .execute_transaction(&block, &call(owner_pk, 0, reg_data))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(rcpt.logs.len(), 1);
+        assert_eq!(rcpt.logs[0].address, owner);
+        assert_eq!(
+            rcpt.logs[0].topics,
+            vec![Hash::new(MODEL_REGISTERED_TOPIC), Hash::new(model_hash)]
+        );
+        assert_eq!(rcpt.logs[0].data, model_event_data(1, Some("cid")));
+
+        // Only the owner can deprecate, and only once
+        let mut deprecate = Keccak256::digest(b"deprecateModel(bytes32)")[..4].to_vec();
+        deprecate.extend_from_slice(&model_hash);
+        let rcpt = executor
+            .execute_transaction(&block, &call(other_pk, 0, deprecate.clone()))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+        assert_eq!(executor.model_deprecated_at(&Hash::new(model_hash)), None);
+
+        let rcpt = executor
+            .execute_transaction(&block, &call(owner_pk, 1, deprecate.clone()))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(
+            rcpt.logs[0].topics,
+            vec![Hash::new(MODEL_DEPRECATED_TOPIC), Hash::new(model_hash)]
+        );
+        assert_eq!(
+            executor.model_deprecated_at(&Hash::new(model_hash)),
+            Some(block.header.height)
+        );
+
+        let rcpt = executor
+            .execute_transaction(&block, &call(owner_pk, 2, deprecate))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+    }
+
+    struct FixedProvider(Address);
+
+    #[async_trait]
+    impl InferenceService for FixedProvider {
+        async fn run_inference(
+            &self,
+            _model_id: ModelId,
+            _input: Vec<u8>,
+            _max_gas: u64,
+        ) -> Result<(Vec<u8>, u64, Address, U256, Option<Vec<u8>>), ExecutionError> {
+            Ok((vec![0xaa], 0, self.0, U256::zero(), None))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pay_per_use_inference_pays_royalties() {
+        let state_db = Arc::new(StateDB::new());
+        let provider = Address([0x77; 20]);
+        let executor = Executor::new(state_db.clone())
+            .with_inference_service(Arc::new(FixedProvider(provider)));
+
+        let owner_pk = PublicKey::new([8; 32]);
+        let owner = Address::from_public_key(&owner_pk);
+        let user_pk = PublicKey::new([9; 32]);
+        let user = Address::from_public_key(&user_pk);
+        for address in [owner, user] {
+            state_db
+                .accounts
+                .set_balance(address, U256::from(1_000_000_000_000_000u128));
+        }
+
+        let mut pc_bytes = [0u8; 32];
+        pc_bytes[18] = 0x10;
+        let precompile_pk = PublicKey::new(pc_bytes);
+        let call = |from: PublicKey, nonce: u64, data: Vec<u8>| Transaction {
+            hash: Hash::new([80 + nonce as u8; 32]),
+            nonce,
+            from,
+            to: Some(precompile_pk),
+            value: 0,
+            gas_limit: 200000,
+            gas_price: 0,
+            data,
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        };
+        let block = create_test_block();
+        let word = |v: u64| {
+            let mut w = [0u8; 32];
+            U256::from(v).to_big_endian(&mut w);
+            w
+        };
+
+        // Register as pay-per-use with a fee of 10_000 wei
+        let model_hash = [0x6b; 32];
+        let mut reg =
+            Keccak256::digest(b"registerModel(bytes32,string,uint8,uint256)")[..4].to_vec();
+        reg.extend_from_slice(&model_hash);
+        reg.extend_from_slice(&word(128));
+        reg.extend_from_slice(&word(3));
+        reg.extend_from_slice(&word(10_000));
+        reg.extend_from_slice(&word(3));
+        reg.extend_from_slice(b"cid");
+        reg.extend_from_slice(&[0u8; 29]);
+        assert!(
+            executor
+                .execute_transaction(&block, &call(owner_pk, 0, reg))
+                .await
+                .unwrap()
+                .status
+        );
+        assert_eq!(
+            executor.model_royalty_schedule(&Hash::new(model_hash)),
+            RoyaltySchedule::default()
+        );
+
+        let set_royalty = |shares: [u64; 3]| {
+            let mut data = Keccak256::digest(b"setRoyaltySchedule(bytes32,uint16,uint16,uint16)")
+                [..4]
+                .to_vec();
+            data.extend_from_slice(&model_hash);
+            for bps in shares {
+                data.extend_from_slice(&word(bps));
+            }
+            data
+        };
+        // Shares must add up to 100%, and only the owner may set them
+        let rcpt = executor
+            .execute_transaction(&block, &call(owner_pk, 1, set_royalty([7000, 2000, 500])))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+        let rcpt = executor
+            .execute_transaction(&block, &call(user_pk, 0, set_royalty([0, 0, 10_000])))
+            .await
+            .unwrap();
+        assert!(!rcpt.status);
+        let rcpt = executor
+            .execute_transaction(&block, &call(owner_pk, 2, set_royalty([7000, 2000, 1000])))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(
+            rcpt.logs[0].topics,
+            vec![Hash::new(MODEL_ROYALTY_SET_TOPIC), Hash::new(model_hash)]
+        );
+        let schedule = RoyaltySchedule {
+            owner_bps: 7000,
+            provider_bps: 2000,
+            treasury_bps: 1000,
+        };
+        assert_eq!(
+            executor.model_royalty_schedule(&Hash::new(model_hash)),
+            schedule
+        );
+
+        let owner_before = state_db.accounts.get_balance(&owner);
+        let user_before = state_db.accounts.get_balance(&user);
+        let mut infer = Keccak256::digest(b"executeInference(bytes32,bytes)")[..4].to_vec();
+        infer.extend_from_slice(&model_hash);
+        infer.extend_from_slice(&word(64));
+        infer.extend_from_slice(&word(1));
+        infer.push(1);
+        infer.extend_from_slice(&[0u8; 31]);
+        let rcpt = executor
+            .execute_transaction(&block, &call(user_pk, 1, infer))
+            .await
+            .unwrap();
+        assert!(rcpt.status);
+        assert_eq!(rcpt.output, vec![0xaa]);
+
+        assert_eq!(state_db.accounts.get_balance(&user), user_before - 10_000);
+        assert_eq!(state_db.accounts.get_balance(&owner), owner_before + 7_000);
+        assert_eq!(state_db.accounts.get_balance(&provider), U256::from(2_000));
+        assert_eq!(
+            state_db.accounts.get_balance(&INFERENCE_TREASURY_ADDRESS),
+            U256::from(1_000)
+        );
+
+        let payment = rcpt
+            .logs
+            .iter()
+            .find_map(RoyaltyPayment::from_log)
+            .expect("royalty log");
+        assert_eq!(rcpt.logs.last().unwrap().address, owner);
+        assert_eq!(
+            payment,
+            RoyaltyPayment {
+                model_hash: Hash::new(model_hash),
+                payer: user,
+                owner,
+                provider: Some(provider),
+                split: schedule.split(U256::from(10_000), true),
+            }
+        );
+    }
+
+    #[test]
+    fn test_royalty_split_without_provider() {
+        let schedule = RoyaltySchedule {
+            owner_bps: 6000,
+            provider_bps: 3000,
+            treasury_bps: 1000,
+        };
+        assert!(schedule.is_valid());
+        assert_eq!(
+            RoyaltySchedule::from_bytes(&schedule.to_bytes()),
+            Some(schedule)
+        );
+
+        // The provider share and rounding dust stay with the owner
+        let split = schedule.split(U256::from(1_005), false);
+        assert_eq!(split.provider, U256::zero());
+        assert_eq!(split.treasury, U256::from(100));
+        assert_eq!(split.owner, U256::from(905));
+
+        let payment = RoyaltyPayment {
+            model_hash: Hash::new([1; 32]),
+            payer: Address([2; 20]),
+            owner: Address([3; 20]),
+            provider: None,
+            split,
+        };
+        assert_eq!(RoyaltyPayment::from_log(&payment.to_log()), Some(payment));
+    }
+
+    /// Archive store answering queries by scanning the recorded diffs
+    #[derive(Default)]
+    struct MemoryArchive {
+        diffs: Mutex<Vec<BlockStateDiff>>,
+    }
+
+    impl StateStoreTrait for MemoryArchive {
+        fn put_account(
+            &self,
+            _address: &Address,
+            _account: &crate::types::AccountState,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn get_account(
+            &self,
+            _address: &Address,
+        ) -> anyhow::Result<Option<crate::types::AccountState>> {
+            Ok(None)
+        }
+
+        fn put_code(&self, _code_hash: &Hash, _code: &[u8]) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn is_archive(&self) -> bool {
+            true
+        }
+
+        fn archive_block(&self, diff: &BlockStateDiff) -> anyhow::Result<()> {
+            self.diffs.lock().push(diff.clone());
+            Ok(())
+        }
+
+        fn archived_range(&self) -> anyhow::Result<Option<(u64, u64)>> {
+            let diffs = self.diffs.lock();
+            Ok(diffs
+                .first()
+                .zip(diffs.last())
+                .map(|(first, last)| (first.height, last.height)))
+        }
+
+        fn account_at(
+            &self,
+            address: &Address,
+            height: u64,
+        ) -> anyhow::Result<Option<crate::types::AccountState>> {
+            Ok(self
+                .diffs
+                .lock()
+                .iter()
+                .filter(|d| d.height <= height)
+                .flat_map(|d| d.accounts.iter())
+                .filter(|(a, _)| a == address)
+                .last()
+                .map(|(_, account)| account.clone()))
+        }
+
+        fn storage_at(
+            &self,
+            address: &Address,
+            key: &[u8],
+            height: u64,
+        ) -> anyhow::Result<Option<Vec<u8>>> {
+            Ok(self
+                .diffs
+                .lock()
+                .iter()
+                .filter(|d| d.height <= height)
+                .flat_map(|d| d.storage.iter())
+                .filter(|c| c.address == *address && c.key == key)
+                .last()
+                .and_then(|c| c.value.clone()))
+        }
+
+        fn changed_since(&self, height: u64) -> anyhow::Result<ChangedKeys> {
+            let mut changed = ChangedKeys::default();
+            for diff in self.diffs.lock().iter().filter(|d| d.height > height) {
+                changed.merge(ChangedKeys::of(diff));
+            }
+            Ok(changed)
+        }
+    }
+
+    #[test]
+    fn test_archive_block_state_and_historical_queries() {
+        let state_db = Arc::new(StateDB::new());
+        let archive = Arc::new(MemoryArchive::default());
+        let executor = Executor::with_storage(state_db.clone(), Some(archive.clone()));
+
+        let alice = Address([1; 20]);
+        let bob = Address([2; 20]);
+
+        // Block 1: the first archived block records the whole state
+        state_db.accounts.set_balance(alice, U256::from(100));
+        state_db.set_storage(alice, b"slot".to_vec(), b"one".to_vec());
+        executor.archive_block_state(1).unwrap();
+        executor.persist_state_changes().unwrap();
+
+        // Block 2 changes alice's balance and storage and creates bob
+        state_db.accounts.set_balance(alice, U256::from(70));
+        state_db.accounts.set_balance(bob, U256::from(30));
+        state_db.delete_storage(alice, b"slot");
+        executor.archive_block_state(2).unwrap();
+        executor.persist_state_changes().unwrap();
+
+        // Block 3 only touches bob
+        state_db.accounts.set_balance(bob, U256::from(40));
+        executor.archive_block_state(3).unwrap();
+        executor.persist_state_changes().unwrap();
+
+        {
+            let diffs = archive.diffs.lock();
+            assert_eq!(diffs.len(), 3);
+            assert_eq!(diffs[2].accounts.len(), 1);
+            assert!(diffs[2].storage.is_empty());
+            assert_eq!(diffs[2].state_root, state_db.calculate_state_root());
+        }
+
+        assert_eq!(executor.account_at(&alice, 1).unwrap().balance, U256::from(100));
+        assert_eq!(executor.account_at(&alice, 2).unwrap().balance, U256::from(70));
+        assert_eq!(executor.account_at(&bob, 1).unwrap().balance, U256::zero());
+        assert_eq!(executor.account_at(&bob, 3).unwrap().balance, U256::from(40));
+        assert_eq!(
+            executor.storage_at(&alice, b"slot", 1).unwrap(),
+            Some(b"one".to_vec())
+        );
+        assert_eq!(executor.storage_at(&alice, b"slot", 2).unwrap(), None);
+        assert!(executor.account_at(&alice, 0).is_err());
+
+        // Rebuilt state at block 1 matches what block 1 left behind
+        let at_one = executor.state_at(1).unwrap();
+        assert_eq!(at_one.accounts.get_balance(&alice), U256::from(100));
+        assert_eq!(at_one.accounts.get_balance(&bob), U256::zero());
+        assert_eq!(at_one.get_storage(&alice, b"slot"), Some(b"one".to_vec()));
+        assert_eq!(
+            at_one.calculate_state_root(),
+            archive.diffs.lock()[0].state_root
+        );
+
+        // The live state is untouched
+        assert_eq!(state_db.accounts.get_balance(&alice), U256::from(70));
+    }
+
+    #[test]
+    fn test_historical_queries_require_archive() {
+        let executor = Executor::new(Arc::new(StateDB::new()));
+        let err = executor.account_at(&Address([1; 20]), 1).unwrap_err();
+        assert!(err.to_string().contains("archive mode"));
+        assert!(executor.state_at(1).is_err());
+    }
+
+    #[test]
+    fn test_governance_block_limits() {
+        let state_db = Arc::new(StateDB::new());
+        let executor = Executor::new(state_db.clone());
+        let defaults = BlockLimits::default();
+        assert_eq!(executor.governance_block_limits(defaults), defaults);
+
+        state_db.set_storage(
+            Executor::governance_precompile_address(),
+            b"PARAM:max_block_required_pins".to_vec(),
+            4u64.to_le_bytes().to_vec(),
+        );
+        let limits = executor.governance_block_limits(defaults);
+        assert_eq!(limits.max_required_pins, 4);
+        assert_eq!(limits.max_tx_bytes, defaults.max_tx_bytes);
+    }
 }
@@ -2,6 +2,7 @@
 
 // Re-export modules
 pub mod address_utils;
+pub mod bloom;
 pub mod crypto;
 pub mod executor;
 pub mod inference;
@@ -9,9 +10,12 @@ pub mod metrics;
 pub mod parallel;
 pub mod precompiles;
 pub mod revm_adapter;
+pub mod simulation;
 pub mod state;
 pub mod tensor;
+pub mod tracer;
 pub mod types;
+pub mod units;
 pub mod vm;
 pub mod zkp;
 
@@ -21,16 +25,22 @@ mod address_derivation_integration_test;
 
 pub use types::{
     AccessPolicy, AccountState, Address, ExecutionError, GasSchedule, JobId, JobStatus, Log,
-    ModelId, ModelMetadata, ModelState, TrainingJob, TransactionReceipt, TransactionType,
-    UsageStats,
+    ModelId, ModelMetadata, ModelState, RoyaltySchedule, RoyaltySplit, TrainingJob,
+    TransactionReceipt, TransactionType, UsageStats,
 };
 
 // Re-export Hash from consensus for MCP to use
 pub use citrate_consensus::types::Hash;
 
-pub use state::{AccountManager, StateDB, StateRoot, Trie};
+pub use bloom::Bloom;
+pub use state::{AccountManager, StateDB, StateRoot, StateRootPipeline, Trie};
 
 pub use executor::{ExecutionContext, Executor, InferenceService, DEFAULT_CHAIN_ID};
 pub use parallel::ParallelExecutor;
+pub use simulation::{AccountDiff, AccountOverride, Simulation, StateOverrides};
 pub use precompiles::{PrecompileExecutor, PrecompileResult};
+pub use tracer::{
+    CallFrame, CallKind, CallTracer, InternalTransaction, InternalTxKind, StructLog, TraceOptions,
+    TransactionTrace,
+};
 pub use inference::metal_runtime::{MetalRuntime, MetalCapabilities};
@@ -0,0 +1,406 @@
+// citrate/core/execution/src/precompiles/compute.rs
+
+// Compute job settlement precompile at 0x100A
+// A requester escrows the payment for an off-chain compute job, naming the
+// provider that will run it. When the job completes, the provider submits
+// the result attestation (output hash, execution time, device fingerprint)
+// in a transaction it signs, which releases the claimed amount to it and
+// refunds the rest of the escrow to the requester. Each settlement is logged
+// as a [`ComputeSettlement`] for revenue accounting.
+//
+// A job that is never settled can be refunded to the requester once
+// `REFUND_DELAY` blocks have passed since it was escrowed.
+
+use citrate_consensus::types::Hash;
+use primitive_types::U256;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+use crate::executor::ExecutionContext;
+use crate::state::StateDB;
+use crate::tracer::{InternalTransaction, InternalTxKind};
+use crate::types::{Address, ExecutionError, Log};
+
+/// 0x000000000000000000000000000000000000100A
+pub const COMPUTE_PRECOMPILE: Address = Address([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10, 0x0A,
+]);
+
+pub const ESCROW_SIGNATURE: &[u8] = b"escrow(bytes32,address)";
+pub const SETTLE_SIGNATURE: &[u8] = b"settle(bytes32,bytes32,uint256,bytes32,uint256)";
+pub const REFUND_SIGNATURE: &[u8] = b"refund(bytes32)";
+
+/// First topic of the log emitted for each settled job, see
+/// [`ComputeSettlement`]
+pub const COMPUTE_JOB_SETTLED_TOPIC: [u8; 32] = *b"ComputeJobSettled000000000000000";
+
+/// Blocks after escrow before an unsettled job can be refunded
+pub const REFUND_DELAY: u64 = 7_200;
+
+const JOB_PREFIX: &[u8] = b"JOB:";
+
+/// Four-byte selector of a function signature
+pub fn selector(signature: &[u8]) -> [u8; 4] {
+    let digest = Keccak256::digest(signature);
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+fn job_key(job_id: &Hash) -> Vec<u8> {
+    let mut key = JOB_PREFIX.to_vec();
+    key.extend_from_slice(job_id.as_bytes());
+    key
+}
+
+fn word(value: U256) -> Vec<u8> {
+    let mut out = vec![0u8; 32];
+    value.to_big_endian(&mut out);
+    out
+}
+
+fn address_word(address: &Address) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(&address.0);
+    word
+}
+
+fn word_address(bytes: &[u8]) -> Address {
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&bytes[12..32]);
+    Address(address)
+}
+
+fn word_arg(args: &[u8], index: usize) -> Result<U256, ExecutionError> {
+    let start = index * 32;
+    args.get(start..start + 32)
+        .map(U256::from_big_endian)
+        .ok_or(ExecutionError::InvalidInput)
+}
+
+fn hash_arg(args: &[u8], index: usize) -> Result<Hash, ExecutionError> {
+    let start = index * 32;
+    let bytes = args
+        .get(start..start + 32)
+        .ok_or(ExecutionError::InvalidInput)?;
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(bytes);
+    Ok(Hash::new(hash))
+}
+
+fn address_arg(args: &[u8], index: usize) -> Result<Address, ExecutionError> {
+    let start = index * 32;
+    let word = args
+        .get(start..start + 32)
+        .ok_or(ExecutionError::InvalidInput)?;
+    if word[..12].iter().any(|&b| b != 0) {
+        return Err(ExecutionError::InvalidInput);
+    }
+    Ok(word_address(word))
+}
+
+/// Payment of one completed job, released from escrow against the
+/// provider's result attestation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComputeSettlement {
+    pub job_id: Hash,
+    pub requester: Address,
+    pub provider: Address,
+    /// Hash of the job output
+    pub output_hash: Hash,
+    pub execution_secs: u64,
+    /// Hash identifying the device that ran the job
+    pub device_fingerprint: Hash,
+    /// Paid to the provider
+    pub amount: U256,
+    /// Returned to the requester
+    pub refund: U256,
+}
+
+impl ComputeSettlement {
+    /// Topics are the event topic, job id and provider. Data holds the
+    /// requester, output hash, device fingerprint, execution time, amount
+    /// and refund, one 32-byte word each.
+    pub fn to_log(&self) -> Log {
+        let mut data = Vec::with_capacity(6 * 32);
+        data.extend_from_slice(&address_word(&self.requester));
+        data.extend_from_slice(self.output_hash.as_bytes());
+        data.extend_from_slice(self.device_fingerprint.as_bytes());
+        data.extend_from_slice(&word(U256::from(self.execution_secs)));
+        data.extend_from_slice(&word(self.amount));
+        data.extend_from_slice(&word(self.refund));
+        Log {
+            address: COMPUTE_PRECOMPILE,
+            topics: vec![
+                Hash::new(COMPUTE_JOB_SETTLED_TOPIC),
+                self.job_id,
+                Hash::new(address_word(&self.provider)),
+            ],
+            data,
+        }
+    }
+
+    /// Decode a settlement log, or `None` if it is some other log
+    pub fn from_log(log: &Log) -> Option<Self> {
+        if log.address != COMPUTE_PRECOMPILE
+            || log.topics.len() != 3
+            || log.topics[0] != Hash::new(COMPUTE_JOB_SETTLED_TOPIC)
+            || log.data.len() != 6 * 32
+        {
+            return None;
+        }
+        let word = |i: usize| &log.data[i * 32..(i + 1) * 32];
+        let hash = |bytes: &[u8]| {
+            let mut h = [0u8; 32];
+            h.copy_from_slice(bytes);
+            Hash::new(h)
+        };
+        Some(Self {
+            job_id: log.topics[1],
+            requester: word_address(word(0)),
+            provider: word_address(log.topics[2].as_bytes()),
+            output_hash: hash(word(1)),
+            device_fingerprint: hash(word(2)),
+            execution_secs: U256::from_big_endian(word(3)).try_into().ok()?,
+            amount: U256::from_big_endian(word(4)),
+            refund: U256::from_big_endian(word(5)),
+        })
+    }
+}
+
+/// An escrowed job and how it ended
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComputeJobEscrow {
+    pub job_id: Hash,
+    pub requester: Address,
+    pub provider: Address,
+    pub amount: U256,
+    pub escrowed_height: u64,
+    pub settlement: Option<ComputeSettlement>,
+    pub settled_height: Option<u64>,
+    pub refunded_height: Option<u64>,
+}
+
+impl ComputeJobEscrow {
+    pub fn is_open(&self) -> bool {
+        self.settled_height.is_none() && self.refunded_height.is_none()
+    }
+}
+
+pub fn job(state_db: &StateDB, job_id: &Hash) -> Option<ComputeJobEscrow> {
+    state_db
+        .get_storage(&COMPUTE_PRECOMPILE, &job_key(job_id))
+        .and_then(|v| bincode::deserialize(&v).ok())
+}
+
+fn put_job(state_db: &StateDB, job: &ComputeJobEscrow) -> Result<(), ExecutionError> {
+    let encoded = bincode::serialize(job)
+        .map_err(|e| ExecutionError::Reverted(format!("Failed to encode job escrow: {}", e)))?;
+    state_db.set_storage(COMPUTE_PRECOMPILE, job_key(&job.job_id), encoded);
+    Ok(())
+}
+
+fn load_open(state_db: &StateDB, job_id: &Hash) -> Result<ComputeJobEscrow, ExecutionError> {
+    let job =
+        job(state_db, job_id).ok_or_else(|| ExecutionError::Reverted("No such job".into()))?;
+    if !job.is_open() {
+        return Err(ExecutionError::Reverted("Job already closed".into()));
+    }
+    Ok(job)
+}
+
+fn pay(
+    state_db: &StateDB,
+    context: &mut ExecutionContext,
+    to: &Address,
+    value: U256,
+) -> Result<(), ExecutionError> {
+    if value.is_zero() {
+        return Ok(());
+    }
+    state_db.accounts.transfer(&COMPUTE_PRECOMPILE, to, value)?;
+    context.internal_transactions.push(InternalTransaction {
+        kind: InternalTxKind::Call,
+        from: COMPUTE_PRECOMPILE,
+        to: *to,
+        value,
+        depth: 1,
+    });
+    Ok(())
+}
+
+/// Call data for `escrow(bytes32,address)`
+pub fn escrow_call_data(job_id: &Hash, provider: &Address) -> Vec<u8> {
+    let mut data = selector(ESCROW_SIGNATURE).to_vec();
+    data.extend_from_slice(job_id.as_bytes());
+    data.extend_from_slice(&address_word(provider));
+    data
+}
+
+/// Call data for `settle(bytes32,bytes32,uint256,bytes32,uint256)`
+pub fn settle_call_data(
+    job_id: &Hash,
+    output_hash: &Hash,
+    execution_secs: u64,
+    device_fingerprint: &Hash,
+    amount: U256,
+) -> Vec<u8> {
+    let mut data = selector(SETTLE_SIGNATURE).to_vec();
+    data.extend_from_slice(job_id.as_bytes());
+    data.extend_from_slice(output_hash.as_bytes());
+    data.extend_from_slice(&word(U256::from(execution_secs)));
+    data.extend_from_slice(device_fingerprint.as_bytes());
+    data.extend_from_slice(&word(amount));
+    data
+}
+
+/// Call data for `refund(bytes32)`
+pub fn refund_call_data(job_id: &Hash) -> Vec<u8> {
+    let mut data = selector(REFUND_SIGNATURE).to_vec();
+    data.extend_from_slice(job_id.as_bytes());
+    data
+}
+
+/// Dispatch a call to the compute settlement precompile. `value` has
+/// already been credited to the precompile account by the caller.
+///
+/// - `escrow(bytes32,address)` locks the attached value as payment for a
+///   job run by the given provider
+/// - `settle(bytes32,bytes32,uint256,bytes32,uint256)` is called by the
+///   provider with the job's output hash, execution time in seconds, device
+///   fingerprint and the amount it claims; the amount is paid to the
+///   provider and the rest of the escrow refunded to the requester
+/// - `refund(bytes32)` returns an unsettled escrow to the requester after
+///   [`REFUND_DELAY`] blocks
+/// - `escrowOf(bytes32)` is a read-only query of the open escrow of a job
+pub(crate) fn execute(
+    state_db: &StateDB,
+    data: &[u8],
+    from: Address,
+    value: U256,
+    context: &mut ExecutionContext,
+) -> Result<(), ExecutionError> {
+    if data.len() < 4 {
+        return Err(ExecutionError::InvalidInput);
+    }
+    let sel = &data[0..4];
+    let args = &data[4..];
+    let height = context.block_number;
+
+    if sel == selector(ESCROW_SIGNATURE) {
+        let job_id = hash_arg(args, 0)?;
+        let provider = address_arg(args, 1)?;
+        if value.is_zero() {
+            return Err(ExecutionError::Reverted("No value attached".into()));
+        }
+        if provider == Address::zero() || provider == from {
+            return Err(ExecutionError::Reverted("Invalid provider".into()));
+        }
+        if job(state_db, &job_id).is_some() {
+            return Err(ExecutionError::Reverted("Job already escrowed".into()));
+        }
+
+        put_job(
+            state_db,
+            &ComputeJobEscrow {
+                job_id,
+                requester: from,
+                provider,
+                amount: value,
+                escrowed_height: height,
+                settlement: None,
+                settled_height: None,
+                refunded_height: None,
+            },
+        )?;
+        let topic: [u8; 32] =
+            Keccak256::digest(b"JobEscrowed(bytes32,address,address,uint256)").into();
+        context.add_log(Log {
+            address: COMPUTE_PRECOMPILE,
+            topics: vec![Hash::new(topic), job_id],
+            data: [
+                &address_word(&from)[..],
+                &address_word(&provider),
+                &word(value),
+            ]
+            .concat(),
+        });
+        return Ok(());
+    }
+
+    // Only escrow accepts value
+    if !value.is_zero() {
+        return Err(ExecutionError::Reverted("Function is not payable".into()));
+    }
+
+    if sel == selector(SETTLE_SIGNATURE) {
+        let job_id = hash_arg(args, 0)?;
+        let output_hash = hash_arg(args, 1)?;
+        let execution_secs: u64 = word_arg(args, 2)?
+            .try_into()
+            .map_err(|_| ExecutionError::InvalidInput)?;
+        let device_fingerprint = hash_arg(args, 3)?;
+        let amount = word_arg(args, 4)?;
+
+        let mut escrow = load_open(state_db, &job_id)?;
+        if from != escrow.provider {
+            return Err(ExecutionError::AccessDenied);
+        }
+        if amount > escrow.amount {
+            return Err(ExecutionError::Reverted("Amount exceeds escrow".into()));
+        }
+
+        let settlement = ComputeSettlement {
+            job_id,
+            requester: escrow.requester,
+            provider: escrow.provider,
+            output_hash,
+            execution_secs,
+            device_fingerprint,
+            amount,
+            refund: escrow.amount - amount,
+        };
+        escrow.settlement = Some(settlement.clone());
+        escrow.settled_height = Some(height);
+        put_job(state_db, &escrow)?;
+        pay(state_db, context, &settlement.provider, settlement.amount)?;
+        pay(state_db, context, &settlement.requester, settlement.refund)?;
+
+        context.add_log(settlement.to_log());
+        context.output = word(amount);
+        return Ok(());
+    }
+
+    if sel == selector(REFUND_SIGNATURE) {
+        let job_id = hash_arg(args, 0)?;
+        let mut escrow = load_open(state_db, &job_id)?;
+        if from != escrow.requester {
+            return Err(ExecutionError::AccessDenied);
+        }
+        if height < escrow.escrowed_height + REFUND_DELAY {
+            return Err(ExecutionError::Reverted("Refund delay not over".into()));
+        }
+
+        escrow.refunded_height = Some(height);
+        put_job(state_db, &escrow)?;
+        pay(state_db, context, &escrow.requester, escrow.amount)?;
+
+        let topic: [u8; 32] = Keccak256::digest(b"JobRefunded(bytes32,address,uint256)").into();
+        context.add_log(Log {
+            address: COMPUTE_PRECOMPILE,
+            topics: vec![Hash::new(topic), job_id],
+            data: [&address_word(&escrow.requester)[..], &word(escrow.amount)].concat(),
+        });
+        context.output = word(escrow.amount);
+        return Ok(());
+    }
+
+    if sel == selector(b"escrowOf(bytes32)") {
+        let amount = job(state_db, &hash_arg(args, 0)?)
+            .filter(ComputeJobEscrow::is_open)
+            .map(|job| job.amount)
+            .unwrap_or_default();
+        context.output = word(amount);
+        return Ok(());
+    }
+
+    Err(ExecutionError::InvalidInput)
+}
@@ -3,7 +3,14 @@
 // EVM Precompiles Module
 // Standard Ethereum precompiles + Citrate AI extensions
 
+pub mod compute;
 pub mod inference;
+pub mod multisig;
+pub mod names;
+pub mod proposals;
+pub mod randomness;
+pub mod staking;
+pub mod validator_keys;
 
 use anyhow::Result;
 use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
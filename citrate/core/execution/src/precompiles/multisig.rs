@@ -0,0 +1,530 @@
+// citrate/core/execution/src/precompiles/multisig.rs
+
+// Multi-signature wallets precompile at 0x1009
+// An m-of-n wallet is a set of owner addresses and a threshold. Deposits are
+// held in the precompile account's balance and accounted per wallet in its
+// storage. Any owner can propose a transfer out of the wallet; it is paid
+// once `threshold` owners have approved it and one of them executes it.
+// Proposing counts as the proposer's approval.
+//
+// Transfers are plain value transfers: calling into a contract from a
+// multisig is not supported.
+
+use citrate_consensus::types::Hash;
+use primitive_types::U256;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+use crate::executor::ExecutionContext;
+use crate::state::StateDB;
+use crate::tracer::{InternalTransaction, InternalTxKind};
+use crate::types::{Address, ExecutionError, Log};
+
+/// 0x0000000000000000000000000000000000001009
+pub const MULTISIG_PRECOMPILE: Address = Address([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10, 0x09,
+]);
+
+pub const CREATE_SIGNATURE: &[u8] = b"createWallet(address[],uint256)";
+pub const DEPOSIT_SIGNATURE: &[u8] = b"deposit(uint256)";
+pub const PROPOSE_SIGNATURE: &[u8] = b"propose(uint256,address,uint256)";
+pub const APPROVE_SIGNATURE: &[u8] = b"approve(uint256)";
+pub const REVOKE_SIGNATURE: &[u8] = b"revoke(uint256)";
+pub const EXECUTE_SIGNATURE: &[u8] = b"execute(uint256)";
+
+/// Most owners a wallet can have
+pub const MAX_OWNERS: usize = 20;
+
+const WALLET_PREFIX: &[u8] = b"WALLET:";
+const PROPOSAL_PREFIX: &[u8] = b"PROPOSAL:";
+/// Index of a wallet's proposals: wallet id and position to proposal id
+const WALLET_PROPOSAL_PREFIX: &[u8] = b"WPROPOSAL:";
+/// Storage key of the next wallet id
+pub const NEXT_WALLET_KEY: &[u8] = b"NEXT_WALLET";
+/// Storage key of the next proposal id
+pub const NEXT_PROPOSAL_KEY: &[u8] = b"NEXT_PROPOSAL";
+
+/// Four-byte selector of a function signature
+pub fn selector(signature: &[u8]) -> [u8; 4] {
+    let digest = Keccak256::digest(signature);
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+fn id_key(prefix: &[u8], id: u64) -> Vec<u8> {
+    let mut key = prefix.to_vec();
+    key.extend_from_slice(&id.to_be_bytes());
+    key
+}
+
+fn wallet_proposal_key(wallet: u64, index: u64) -> Vec<u8> {
+    let mut key = id_key(WALLET_PROPOSAL_PREFIX, wallet);
+    key.extend_from_slice(&index.to_be_bytes());
+    key
+}
+
+fn word(value: U256) -> Vec<u8> {
+    let mut out = vec![0u8; 32];
+    value.to_big_endian(&mut out);
+    out
+}
+
+fn address_word(address: &Address) -> Vec<u8> {
+    let mut out = vec![0u8; 12];
+    out.extend_from_slice(&address.0);
+    out
+}
+
+fn word_arg(args: &[u8], index: usize) -> Result<U256, ExecutionError> {
+    let start = index * 32;
+    args.get(start..start + 32)
+        .map(U256::from_big_endian)
+        .ok_or(ExecutionError::InvalidInput)
+}
+
+fn u64_arg(args: &[u8], index: usize) -> Result<u64, ExecutionError> {
+    word_arg(args, index)?
+        .try_into()
+        .map_err(|_| ExecutionError::InvalidInput)
+}
+
+fn address_at(args: &[u8], start: usize) -> Result<Address, ExecutionError> {
+    let word = args
+        .get(start..start + 32)
+        .ok_or(ExecutionError::InvalidInput)?;
+    if word[..12].iter().any(|&b| b != 0) {
+        return Err(ExecutionError::InvalidInput);
+    }
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&word[12..]);
+    Ok(Address(address))
+}
+
+/// The `index`-th head word of `args` read as the offset of an `address[]`
+fn address_array_arg(args: &[u8], index: usize) -> Result<Vec<Address>, ExecutionError> {
+    let offset = u64_arg(args, index)? as usize;
+    let len = u64_arg(args.get(offset..).ok_or(ExecutionError::InvalidInput)?, 0)? as usize;
+    if len > MAX_OWNERS {
+        return Err(ExecutionError::Reverted("Too many owners".into()));
+    }
+    (0..len)
+        .map(|i| address_at(args, offset + 32 + i * 32))
+        .collect()
+}
+
+fn read_u64(state_db: &StateDB, key: &[u8]) -> u64 {
+    state_db
+        .get_storage(&MULTISIG_PRECOMPILE, key)
+        .filter(|v| v.len() == 8)
+        .map(|v| {
+            let mut id = [0u8; 8];
+            id.copy_from_slice(&v);
+            u64::from_le_bytes(id)
+        })
+        .unwrap_or_default()
+}
+
+fn write_u64(state_db: &StateDB, key: Vec<u8>, value: u64) {
+    state_db.set_storage(MULTISIG_PRECOMPILE, key, value.to_le_bytes().to_vec());
+}
+
+/// An m-of-n wallet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigWallet {
+    pub id: u64,
+    pub owners: Vec<Address>,
+    /// Approvals a transfer needs
+    pub threshold: u64,
+    /// Funds held for the wallet
+    pub balance: U256,
+    /// Proposals made on the wallet; indexes run from 0 to this minus one
+    pub proposal_count: u64,
+    pub created_height: u64,
+}
+
+impl MultisigWallet {
+    pub fn is_owner(&self, address: &Address) -> bool {
+        self.owners.contains(address)
+    }
+}
+
+/// A proposed transfer out of a wallet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigProposal {
+    pub id: u64,
+    pub wallet: u64,
+    pub proposer: Address,
+    pub to: Address,
+    pub value: U256,
+    /// Owners that approved, in order of approval
+    pub approvals: Vec<Address>,
+    pub created_height: u64,
+    pub executed_height: Option<u64>,
+}
+
+impl MultisigProposal {
+    pub fn approved_by(&self, owner: &Address) -> bool {
+        self.approvals.contains(owner)
+    }
+
+    pub fn is_executed(&self) -> bool {
+        self.executed_height.is_some()
+    }
+}
+
+/// Number of wallets ever created; ids run from 0 to this minus one
+pub fn wallet_count(state_db: &StateDB) -> u64 {
+    read_u64(state_db, NEXT_WALLET_KEY)
+}
+
+/// Number of proposals ever made across all wallets
+pub fn proposal_count(state_db: &StateDB) -> u64 {
+    read_u64(state_db, NEXT_PROPOSAL_KEY)
+}
+
+pub fn wallet(state_db: &StateDB, id: u64) -> Option<MultisigWallet> {
+    state_db
+        .get_storage(&MULTISIG_PRECOMPILE, &id_key(WALLET_PREFIX, id))
+        .and_then(|v| bincode::deserialize(&v).ok())
+}
+
+pub fn proposal(state_db: &StateDB, id: u64) -> Option<MultisigProposal> {
+    state_db
+        .get_storage(&MULTISIG_PRECOMPILE, &id_key(PROPOSAL_PREFIX, id))
+        .and_then(|v| bincode::deserialize(&v).ok())
+}
+
+/// Proposals made on `wallet`, oldest first
+pub fn proposals_of(state_db: &StateDB, wallet: &MultisigWallet) -> Vec<MultisigProposal> {
+    (0..wallet.proposal_count)
+        .map(|index| read_u64(state_db, &wallet_proposal_key(wallet.id, index)))
+        .filter_map(|id| proposal(state_db, id))
+        .collect()
+}
+
+fn put<T: Serialize>(state_db: &StateDB, key: Vec<u8>, value: &T) -> Result<(), ExecutionError> {
+    let encoded = bincode::serialize(value)
+        .map_err(|e| ExecutionError::Reverted(format!("Failed to encode multisig state: {}", e)))?;
+    state_db.set_storage(MULTISIG_PRECOMPILE, key, encoded);
+    Ok(())
+}
+
+fn put_wallet(state_db: &StateDB, wallet: &MultisigWallet) -> Result<(), ExecutionError> {
+    put(state_db, id_key(WALLET_PREFIX, wallet.id), wallet)
+}
+
+fn put_proposal(state_db: &StateDB, proposal: &MultisigProposal) -> Result<(), ExecutionError> {
+    put(state_db, id_key(PROPOSAL_PREFIX, proposal.id), proposal)
+}
+
+fn load_wallet(state_db: &StateDB, id: u64) -> Result<MultisigWallet, ExecutionError> {
+    wallet(state_db, id).ok_or_else(|| ExecutionError::Reverted("No such wallet".into()))
+}
+
+/// Load an open proposal and its wallet, checking that `from` is an owner
+fn load_open(
+    state_db: &StateDB,
+    id: u64,
+    from: &Address,
+) -> Result<(MultisigWallet, MultisigProposal), ExecutionError> {
+    let proposal = proposal(state_db, id)
+        .ok_or_else(|| ExecutionError::Reverted("No such proposal".into()))?;
+    let wallet = load_wallet(state_db, proposal.wallet)?;
+    if !wallet.is_owner(from) {
+        return Err(ExecutionError::AccessDenied);
+    }
+    if proposal.is_executed() {
+        return Err(ExecutionError::Reverted("Proposal already executed".into()));
+    }
+    Ok((wallet, proposal))
+}
+
+fn emit(context: &mut ExecutionContext, event: &[u8], id: u64, data: Vec<u8>) {
+    let topic: [u8; 32] = Keccak256::digest(event).into();
+    let mut id_topic = [0u8; 32];
+    id_topic[24..].copy_from_slice(&id.to_be_bytes());
+    context.add_log(Log {
+        address: MULTISIG_PRECOMPILE,
+        topics: vec![Hash::new(topic), Hash::new(id_topic)],
+        data,
+    });
+}
+
+/// Call data for `createWallet(address[],uint256)`
+pub fn create_call_data(owners: &[Address], threshold: u64) -> Vec<u8> {
+    let mut data = selector(CREATE_SIGNATURE).to_vec();
+    data.extend_from_slice(&word(U256::from(64)));
+    data.extend_from_slice(&word(U256::from(threshold)));
+    data.extend_from_slice(&word(U256::from(owners.len())));
+    for owner in owners {
+        data.extend_from_slice(&address_word(owner));
+    }
+    data
+}
+
+/// Call data for `deposit(uint256)`
+pub fn deposit_call_data(wallet: u64) -> Vec<u8> {
+    let mut data = selector(DEPOSIT_SIGNATURE).to_vec();
+    data.extend_from_slice(&word(U256::from(wallet)));
+    data
+}
+
+/// Call data for `propose(uint256,address,uint256)`
+pub fn propose_call_data(wallet: u64, to: &Address, value: U256) -> Vec<u8> {
+    let mut data = selector(PROPOSE_SIGNATURE).to_vec();
+    data.extend_from_slice(&word(U256::from(wallet)));
+    data.extend_from_slice(&address_word(to));
+    data.extend_from_slice(&word(value));
+    data
+}
+
+/// Call data for `approve(uint256)`
+pub fn approve_call_data(proposal: u64) -> Vec<u8> {
+    let mut data = selector(APPROVE_SIGNATURE).to_vec();
+    data.extend_from_slice(&word(U256::from(proposal)));
+    data
+}
+
+/// Call data for `revoke(uint256)`
+pub fn revoke_call_data(proposal: u64) -> Vec<u8> {
+    let mut data = selector(REVOKE_SIGNATURE).to_vec();
+    data.extend_from_slice(&word(U256::from(proposal)));
+    data
+}
+
+/// Call data for `execute(uint256)`
+pub fn execute_call_data(proposal: u64) -> Vec<u8> {
+    let mut data = selector(EXECUTE_SIGNATURE).to_vec();
+    data.extend_from_slice(&word(U256::from(proposal)));
+    data
+}
+
+/// Dispatch a call to the multisig precompile. `value` has already been
+/// credited to the precompile account by the caller.
+///
+/// - `createWallet(address[],uint256)` creates an m-of-n wallet from distinct,
+///   non-zero owners and returns its id; attached value is deposited into it
+/// - `deposit(uint256)` adds the attached value to a wallet
+/// - `propose(uint256,address,uint256)` proposes a transfer out of a wallet
+///   and returns the proposal id; only owners can propose
+/// - `approve(uint256)` / `revoke(uint256)` add or withdraw the caller's
+///   approval of an open proposal
+/// - `execute(uint256)` pays out a proposal once enough owners approved it
+/// - `walletCount()` / `balanceOf(uint256)` / `approvalCount(uint256)` are
+///   read-only queries
+pub(crate) fn execute(
+    state_db: &StateDB,
+    data: &[u8],
+    from: Address,
+    value: U256,
+    context: &mut ExecutionContext,
+) -> Result<(), ExecutionError> {
+    if data.len() < 4 {
+        return Err(ExecutionError::InvalidInput);
+    }
+    let sel = &data[0..4];
+    let args = &data[4..];
+    let height = context.block_number;
+
+    if sel == selector(CREATE_SIGNATURE) {
+        let owners = address_array_arg(args, 0)?;
+        let threshold = u64_arg(args, 1)?;
+        if owners.is_empty() {
+            return Err(ExecutionError::Reverted("No owners".into()));
+        }
+        if owners.iter().any(|owner| *owner == Address::zero()) {
+            return Err(ExecutionError::Reverted("Zero address owner".into()));
+        }
+        if owners
+            .iter()
+            .enumerate()
+            .any(|(i, owner)| owners[..i].contains(owner))
+        {
+            return Err(ExecutionError::Reverted("Duplicate owner".into()));
+        }
+        if threshold == 0 || threshold > owners.len() as u64 {
+            return Err(ExecutionError::Reverted("Invalid threshold".into()));
+        }
+
+        let id = wallet_count(state_db);
+        let wallet = MultisigWallet {
+            id,
+            owners,
+            threshold,
+            balance: value,
+            proposal_count: 0,
+            created_height: height,
+        };
+        put_wallet(state_db, &wallet)?;
+        write_u64(state_db, NEXT_WALLET_KEY.to_vec(), id + 1);
+
+        emit(
+            context,
+            b"WalletCreated(uint256,address,uint256)",
+            id,
+            [address_word(&from), word(U256::from(threshold))].concat(),
+        );
+        context.output = word(U256::from(id));
+        return Ok(());
+    }
+
+    if sel == selector(DEPOSIT_SIGNATURE) {
+        if value.is_zero() {
+            return Err(ExecutionError::Reverted("No value attached".into()));
+        }
+        let mut wallet = load_wallet(state_db, u64_arg(args, 0)?)?;
+        wallet.balance += value;
+        put_wallet(state_db, &wallet)?;
+
+        emit(
+            context,
+            b"Deposit(uint256,address,uint256)",
+            wallet.id,
+            [address_word(&from), word(value)].concat(),
+        );
+        context.output = word(wallet.balance);
+        return Ok(());
+    }
+
+    // Only createWallet and deposit accept value
+    if !value.is_zero() {
+        return Err(ExecutionError::Reverted("Function is not payable".into()));
+    }
+
+    if sel == selector(PROPOSE_SIGNATURE) {
+        let mut wallet = load_wallet(state_db, u64_arg(args, 0)?)?;
+        let to = address_at(args, 32)?;
+        let amount = word_arg(args, 2)?;
+        if !wallet.is_owner(&from) {
+            return Err(ExecutionError::AccessDenied);
+        }
+        if amount.is_zero() {
+            return Err(ExecutionError::Reverted("Nothing to transfer".into()));
+        }
+
+        let id = proposal_count(state_db);
+        let proposal = MultisigProposal {
+            id,
+            wallet: wallet.id,
+            proposer: from,
+            to,
+            value: amount,
+            approvals: vec![from],
+            created_height: height,
+            executed_height: None,
+        };
+        put_proposal(state_db, &proposal)?;
+        write_u64(state_db, NEXT_PROPOSAL_KEY.to_vec(), id + 1);
+        write_u64(
+            state_db,
+            wallet_proposal_key(wallet.id, wallet.proposal_count),
+            id,
+        );
+        wallet.proposal_count += 1;
+        put_wallet(state_db, &wallet)?;
+
+        emit(
+            context,
+            b"TransferProposed(uint256,uint256,address,uint256)",
+            id,
+            [word(U256::from(wallet.id)), address_word(&to), word(amount)].concat(),
+        );
+        context.output = word(U256::from(id));
+        return Ok(());
+    }
+
+    if sel == selector(APPROVE_SIGNATURE) {
+        let id = u64_arg(args, 0)?;
+        let (_, mut proposal) = load_open(state_db, id, &from)?;
+        if proposal.approved_by(&from) {
+            return Err(ExecutionError::Reverted("Already approved".into()));
+        }
+        proposal.approvals.push(from);
+        put_proposal(state_db, &proposal)?;
+
+        emit(
+            context,
+            b"Approved(uint256,address)",
+            id,
+            address_word(&from),
+        );
+        context.output = word(U256::from(proposal.approvals.len()));
+        return Ok(());
+    }
+
+    if sel == selector(REVOKE_SIGNATURE) {
+        let id = u64_arg(args, 0)?;
+        let (_, mut proposal) = load_open(state_db, id, &from)?;
+        if !proposal.approved_by(&from) {
+            return Err(ExecutionError::Reverted("Not approved".into()));
+        }
+        proposal.approvals.retain(|owner| *owner != from);
+        put_proposal(state_db, &proposal)?;
+
+        emit(
+            context,
+            b"Revoked(uint256,address)",
+            id,
+            address_word(&from),
+        );
+        context.output = word(U256::from(proposal.approvals.len()));
+        return Ok(());
+    }
+
+    if sel == selector(EXECUTE_SIGNATURE) {
+        let id = u64_arg(args, 0)?;
+        let (mut wallet, mut proposal) = load_open(state_db, id, &from)?;
+        // Approvals of owners are never removed, so the count is current
+        if (proposal.approvals.len() as u64) < wallet.threshold {
+            return Err(ExecutionError::Reverted("Not enough approvals".into()));
+        }
+        if wallet.balance < proposal.value {
+            return Err(ExecutionError::Reverted(
+                "Insufficient wallet balance".into(),
+            ));
+        }
+
+        wallet.balance -= proposal.value;
+        put_wallet(state_db, &wallet)?;
+        proposal.executed_height = Some(height);
+        put_proposal(state_db, &proposal)?;
+        state_db
+            .accounts
+            .transfer(&MULTISIG_PRECOMPILE, &proposal.to, proposal.value)?;
+        context.internal_transactions.push(InternalTransaction {
+            kind: InternalTxKind::Call,
+            from: MULTISIG_PRECOMPILE,
+            to: proposal.to,
+            value: proposal.value,
+            depth: 1,
+        });
+
+        emit(
+            context,
+            b"TransferExecuted(uint256,address,uint256)",
+            id,
+            [address_word(&proposal.to), word(proposal.value)].concat(),
+        );
+        return Ok(());
+    }
+
+    if sel == selector(b"walletCount()") {
+        context.output = word(U256::from(wallet_count(state_db)));
+        return Ok(());
+    }
+
+    if sel == selector(b"balanceOf(uint256)") {
+        let wallet = load_wallet(state_db, u64_arg(args, 0)?)?;
+        context.output = word(wallet.balance);
+        return Ok(());
+    }
+
+    if sel == selector(b"approvalCount(uint256)") {
+        let id = u64_arg(args, 0)?;
+        let proposal = proposal(state_db, id)
+            .ok_or_else(|| ExecutionError::Reverted("No such proposal".into()))?;
+        context.output = word(U256::from(proposal.approvals.len()));
+        return Ok(());
+    }
+
+    Err(ExecutionError::InvalidInput)
+}
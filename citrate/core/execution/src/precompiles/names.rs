@@ -0,0 +1,244 @@
+// citrate/core/execution/src/precompiles/names.rs
+
+// Name registry precompile at 0x1007
+// Maps short human-readable names (`alice`, written `alice.ctr` by wallets)
+// to addresses, first come first served. A name is passed on the wire as a
+// bytes32 holding its normalized label, left-aligned and zero-padded. The
+// owner of a name is the address it resolves to; transferring the name
+// re-points it. Each address also has a primary name for reverse lookups,
+// set to the first name it comes to own.
+
+use citrate_consensus::types::Hash;
+use primitive_types::U256;
+use sha3::{Digest, Keccak256};
+
+use crate::executor::ExecutionContext;
+use crate::state::StateDB;
+use crate::types::{Address, ExecutionError, Log};
+
+/// 0x0000000000000000000000000000000000001007
+pub const NAMES_PRECOMPILE: Address = Address([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10, 0x07,
+]);
+
+/// Suffix wallets display after a registered name
+pub const NAME_SUFFIX: &str = ".ctr";
+
+/// Shortest accepted name, in bytes
+pub const MIN_NAME_LEN: usize = 3;
+
+/// Longest accepted name, in bytes
+pub const MAX_NAME_LEN: usize = 32;
+
+pub const REGISTER_SIGNATURE: &[u8] = b"register(bytes32)";
+pub const TRANSFER_SIGNATURE: &[u8] = b"transfer(bytes32,address)";
+pub const RESOLVE_SIGNATURE: &[u8] = b"resolve(bytes32)";
+pub const NAME_OF_SIGNATURE: &[u8] = b"nameOf(address)";
+
+const NAME_PREFIX: &[u8] = b"NAME:";
+const PRIMARY_PREFIX: &[u8] = b"PRIMARY:";
+
+fn selector(signature: &[u8]) -> [u8; 4] {
+    let digest = Keccak256::digest(signature);
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+fn name_key(label: &[u8; 32]) -> Vec<u8> {
+    let mut key = NAME_PREFIX.to_vec();
+    key.extend_from_slice(label);
+    key
+}
+
+fn primary_key(address: &Address) -> Vec<u8> {
+    let mut key = PRIMARY_PREFIX.to_vec();
+    key.extend_from_slice(&address.0);
+    key
+}
+
+fn address_word(address: &Address) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(&address.0);
+    word
+}
+
+fn valid_label(label: &str) -> bool {
+    (MIN_NAME_LEN..=MAX_NAME_LEN).contains(&label.len())
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+        && label
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+}
+
+/// Normalize a name as typed by a user: trimmed, lowercased and with the
+/// optional [`NAME_SUFFIX`] removed. Returns `None` if what is left is not
+/// a valid name.
+pub fn normalize_name(name: &str) -> Option<String> {
+    let name = name.trim().to_ascii_lowercase();
+    let label = name.strip_suffix(NAME_SUFFIX).unwrap_or(&name);
+    valid_label(label).then(|| label.to_string())
+}
+
+/// The bytes32 form of a name
+pub fn encode_name(name: &str) -> Option<[u8; 32]> {
+    let label = normalize_name(name)?;
+    let mut out = [0u8; 32];
+    out[..label.len()].copy_from_slice(label.as_bytes());
+    Some(out)
+}
+
+/// The name held in a bytes32, if it is a valid one
+pub fn decode_name(encoded: &[u8; 32]) -> Option<String> {
+    let len = encoded.iter().position(|&b| b == 0).unwrap_or(32);
+    if encoded[len..].iter().any(|&b| b != 0) {
+        return None;
+    }
+    let label = std::str::from_utf8(&encoded[..len]).ok()?;
+    valid_label(label).then(|| label.to_string())
+}
+
+fn owner_of(state_db: &StateDB, label: &[u8; 32]) -> Option<Address> {
+    state_db
+        .get_storage(&NAMES_PRECOMPILE, &name_key(label))
+        .filter(|v| v.len() == 20)
+        .map(|v| {
+            let mut address = [0u8; 20];
+            address.copy_from_slice(&v);
+            Address(address)
+        })
+}
+
+/// Address a name resolves to
+pub fn resolve(state_db: &StateDB, name: &str) -> Option<Address> {
+    owner_of(state_db, &encode_name(name)?)
+}
+
+/// Primary name of an address, without the suffix
+pub fn name_of(state_db: &StateDB, address: &Address) -> Option<String> {
+    state_db
+        .get_storage(&NAMES_PRECOMPILE, &primary_key(address))
+        .filter(|v| v.len() == 32)
+        .and_then(|v| {
+            let mut label = [0u8; 32];
+            label.copy_from_slice(&v);
+            decode_name(&label)
+        })
+}
+
+/// Call data for `register(bytes32)`
+pub fn register_call_data(name: &[u8; 32]) -> Vec<u8> {
+    let mut data = selector(REGISTER_SIGNATURE).to_vec();
+    data.extend_from_slice(name);
+    data
+}
+
+/// Call data for `transfer(bytes32,address)`
+pub fn transfer_call_data(name: &[u8; 32], to: &Address) -> Vec<u8> {
+    let mut data = selector(TRANSFER_SIGNATURE).to_vec();
+    data.extend_from_slice(name);
+    data.extend_from_slice(&address_word(to));
+    data
+}
+
+fn label_arg(args: &[u8]) -> Result<[u8; 32], ExecutionError> {
+    let mut label = [0u8; 32];
+    label.copy_from_slice(args.get(..32).ok_or(ExecutionError::InvalidInput)?);
+    decode_name(&label)
+        .map(|_| label)
+        .ok_or_else(|| ExecutionError::Reverted("Invalid name".into()))
+}
+
+fn set_owner(state_db: &StateDB, label: &[u8; 32], owner: &Address) {
+    state_db.set_storage(NAMES_PRECOMPILE, name_key(label), owner.0.to_vec());
+    if name_of(state_db, owner).is_none() {
+        state_db.set_storage(NAMES_PRECOMPILE, primary_key(owner), label.to_vec());
+    }
+}
+
+fn emit(context: &mut ExecutionContext, event: &[u8], label: &[u8; 32], owner: &Address) {
+    let topic: [u8; 32] = Keccak256::digest(event).into();
+    context.add_log(Log {
+        address: NAMES_PRECOMPILE,
+        topics: vec![Hash::new(topic), Hash::new(*label)],
+        data: address_word(owner).to_vec(),
+    });
+}
+
+/// Dispatch a call to the name registry
+///
+/// - `register(bytes32)` claims an unowned name for the caller
+/// - `transfer(bytes32,address)` hands the caller's name to another address
+/// - `resolve(bytes32)` / `nameOf(address)` are read-only lookups that
+///   return zeros when nothing is registered
+pub(crate) fn execute(
+    state_db: &StateDB,
+    data: &[u8],
+    from: Address,
+    value: U256,
+    context: &mut ExecutionContext,
+) -> Result<(), ExecutionError> {
+    if data.len() < 4 {
+        return Err(ExecutionError::InvalidInput);
+    }
+    if !value.is_zero() {
+        return Err(ExecutionError::Reverted("Function is not payable".into()));
+    }
+    let sel = &data[0..4];
+    let args = &data[4..];
+
+    if sel == selector(REGISTER_SIGNATURE) {
+        let label = label_arg(args)?;
+        if owner_of(state_db, &label).is_some() {
+            return Err(ExecutionError::Reverted("Name already registered".into()));
+        }
+        set_owner(state_db, &label, &from);
+        emit(context, b"NameRegistered(bytes32,address)", &label, &from);
+        return Ok(());
+    }
+
+    if sel == selector(TRANSFER_SIGNATURE) {
+        let label = label_arg(args)?;
+        if args.len() < 64 {
+            return Err(ExecutionError::InvalidInput);
+        }
+        let mut to = [0u8; 20];
+        to.copy_from_slice(&args[44..64]);
+        let to = Address(to);
+        if owner_of(state_db, &label) != Some(from) {
+            return Err(ExecutionError::Reverted("Not the name owner".into()));
+        }
+        if to == Address([0u8; 20]) {
+            return Err(ExecutionError::Reverted("Invalid new owner".into()));
+        }
+
+        let primary = state_db.get_storage(&NAMES_PRECOMPILE, &primary_key(&from));
+        if primary.as_deref() == Some(&label[..]) {
+            state_db.set_storage(NAMES_PRECOMPILE, primary_key(&from), Vec::new());
+        }
+        set_owner(state_db, &label, &to);
+        emit(context, b"NameTransferred(bytes32,address)", &label, &to);
+        return Ok(());
+    }
+
+    if sel == selector(RESOLVE_SIGNATURE) {
+        let label = label_arg(args)?;
+        let owner = owner_of(state_db, &label).unwrap_or(Address([0u8; 20]));
+        context.output = address_word(&owner).to_vec();
+        return Ok(());
+    }
+
+    if sel == selector(NAME_OF_SIGNATURE) {
+        if args.len() < 32 {
+            return Err(ExecutionError::InvalidInput);
+        }
+        let mut who = [0u8; 20];
+        who.copy_from_slice(&args[12..32]);
+        context.output = name_of(state_db, &Address(who))
+            .and_then(|name| encode_name(&name))
+            .unwrap_or([0u8; 32])
+            .to_vec();
+        return Ok(());
+    }
+
+    Err(ExecutionError::InvalidInput)
+}
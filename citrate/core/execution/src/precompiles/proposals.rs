@@ -0,0 +1,550 @@
+// citrate/core/execution/src/precompiles/proposals.rs
+
+// Governance proposals precompile at 0x1006
+// Token holders with bonded stake propose parameter changes and vote on them
+// with their bonded stake as weight. A proposal that reaches quorum and the
+// approval threshold can be executed by anyone once its delay has passed,
+// which writes the new value into the governance parameter store at 0x1003
+// (`PARAM:<name>`), where the node and executor already read it. Thresholds
+// mirror the defaults of citrate-economics' `GovernanceConfig`.
+//
+// Stake cannot be moved to a second address within a voting period: unstaked
+// funds sit in unbonding for longer than proposals stay open.
+
+use citrate_consensus::types::Hash;
+use primitive_types::U256;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+use crate::executor::ExecutionContext;
+use crate::precompiles::staking::{self, STAKING_PRECOMPILE};
+use crate::state::StateDB;
+use crate::types::{Address, ExecutionError, Log};
+
+/// 0x0000000000000000000000000000000000001006
+pub const PROPOSALS_PRECOMPILE: Address = Address([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10, 0x06,
+]);
+
+/// Parameter store that executed proposals write to
+pub const GOVERNANCE_PARAMS: Address = Address([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10, 0x03,
+]);
+
+pub const PROPOSE_SIGNATURE: &[u8] = b"propose(string,bytes,string)";
+pub const VOTE_SIGNATURE: &[u8] = b"vote(uint256,uint8)";
+pub const EXECUTE_SIGNATURE: &[u8] = b"execute(uint256)";
+pub const CANCEL_SIGNATURE: &[u8] = b"cancel(uint256)";
+
+/// Blocks a proposal stays open for voting (~7 days at 2s blocks)
+pub const VOTING_PERIOD: u64 = 50_400;
+
+/// Blocks between the end of voting and a passed proposal becoming executable
+pub const EXECUTION_DELAY: u64 = 7_200;
+
+/// Blocks a passed proposal stays executable before it expires
+pub const GRACE_PERIOD: u64 = 50_400;
+
+/// Share of staked LATT that must vote, in percent
+pub const QUORUM_PERCENTAGE: u64 = 10;
+
+/// Share of votes cast that must be in favour, in percent
+pub const APPROVAL_THRESHOLD: u64 = 60;
+
+/// Longest accepted parameter name, in bytes
+pub const MAX_PARAMETER_LEN: usize = 64;
+
+/// Longest accepted parameter value, in bytes
+pub const MAX_VALUE_LEN: usize = 1024;
+
+/// Longest accepted description, in bytes
+pub const MAX_DESCRIPTION_LEN: usize = 4096;
+
+const PROPOSAL_PREFIX: &[u8] = b"PROPOSAL:";
+const VOTE_PREFIX: &[u8] = b"VOTE:";
+/// Storage key of the next proposal id
+pub const NEXT_ID_KEY: &[u8] = b"NEXT_ID";
+
+fn lat(amount: u64) -> U256 {
+    U256::from(amount) * U256::exp10(18)
+}
+
+/// Bonded stake required to create a proposal (10,000 LATT)
+pub fn proposal_threshold() -> U256 {
+    lat(10_000)
+}
+
+/// Bonded stake required to vote (1 LATT)
+pub fn vote_threshold() -> U256 {
+    lat(1)
+}
+
+/// Four-byte selector of a function signature
+pub fn selector(signature: &[u8]) -> [u8; 4] {
+    let digest = Keccak256::digest(signature);
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+/// Storage key of proposal `id`
+pub fn proposal_key(id: u64) -> Vec<u8> {
+    let mut key = PROPOSAL_PREFIX.to_vec();
+    key.extend_from_slice(&id.to_be_bytes());
+    key
+}
+
+fn vote_key(id: u64, voter: &Address) -> Vec<u8> {
+    let mut key = VOTE_PREFIX.to_vec();
+    key.extend_from_slice(&id.to_be_bytes());
+    key.extend_from_slice(&voter.0);
+    key
+}
+
+fn word(value: U256) -> Vec<u8> {
+    let mut out = vec![0u8; 32];
+    value.to_big_endian(&mut out);
+    out
+}
+
+fn padded(bytes: &[u8]) -> Vec<u8> {
+    let mut out = word(U256::from(bytes.len()));
+    out.extend_from_slice(bytes);
+    out.resize(32 + bytes.len().div_ceil(32) * 32, 0);
+    out
+}
+
+fn u64_arg(args: &[u8], index: usize) -> Result<u64, ExecutionError> {
+    let start = index * 32;
+    if args.len() < start + 32 {
+        return Err(ExecutionError::InvalidInput);
+    }
+    U256::from_big_endian(&args[start..start + 32])
+        .try_into()
+        .map_err(|_| ExecutionError::InvalidInput)
+}
+
+/// The `index`-th head word of `args` read as the offset of a dynamic
+/// `bytes`/`string` argument
+fn dynamic_arg(args: &[u8], index: usize) -> Result<&[u8], ExecutionError> {
+    let offset = u64_arg(args, index)? as usize;
+    let len_end = offset.checked_add(32).ok_or(ExecutionError::InvalidInput)?;
+    if args.len() < len_end {
+        return Err(ExecutionError::InvalidInput);
+    }
+    let len: usize = U256::from_big_endian(&args[offset..len_end])
+        .try_into()
+        .map_err(|_| ExecutionError::InvalidInput)?;
+    let end = len_end
+        .checked_add(len)
+        .ok_or(ExecutionError::InvalidInput)?;
+    args.get(len_end..end).ok_or(ExecutionError::InvalidInput)
+}
+
+/// How a vote was cast
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VoteSupport {
+    Against,
+    For,
+    Abstain,
+}
+
+impl VoteSupport {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Against),
+            1 => Some(Self::For),
+            2 => Some(Self::Abstain),
+            _ => None,
+        }
+    }
+
+    pub fn as_u8(self) -> u8 {
+        match self {
+            Self::Against => 0,
+            Self::For => 1,
+            Self::Abstain => 2,
+        }
+    }
+}
+
+/// Where a proposal is in its lifecycle at a given height
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProposalState {
+    /// Voting is open
+    Active,
+    /// Passed, waiting out the execution delay
+    Queued,
+    /// Passed and executable
+    Succeeded,
+    /// Voting ended without quorum or approval
+    Failed,
+    /// Withdrawn by the proposer
+    Canceled,
+    /// Passed but not executed within the grace period
+    Expired,
+    Executed,
+}
+
+impl ProposalState {
+    pub fn as_u8(self) -> u8 {
+        match self {
+            Self::Active => 0,
+            Self::Queued => 1,
+            Self::Succeeded => 2,
+            Self::Failed => 3,
+            Self::Canceled => 4,
+            Self::Expired => 5,
+            Self::Executed => 6,
+        }
+    }
+}
+
+/// A parameter change proposal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Address,
+    /// Governance parameter to set, e.g. `min_gas_price`
+    pub parameter: String,
+    /// Raw value stored under `PARAM:<parameter>` on execution
+    pub value: Vec<u8>,
+    pub description: String,
+    pub created_height: u64,
+    /// Last height at which votes are accepted
+    pub end_height: u64,
+    /// Votes needed for quorum, fixed when the proposal is created
+    pub quorum: U256,
+    pub votes_for: U256,
+    pub votes_against: U256,
+    pub votes_abstain: U256,
+    pub canceled: bool,
+    pub executed_height: Option<u64>,
+}
+
+impl Proposal {
+    pub fn total_votes(&self) -> U256 {
+        self.votes_for + self.votes_against + self.votes_abstain
+    }
+
+    /// Whether the votes cast meet quorum and the approval threshold
+    pub fn passed(&self) -> bool {
+        let total = self.total_votes();
+        !total.is_zero()
+            && total >= self.quorum
+            && self.votes_for >= total * U256::from(APPROVAL_THRESHOLD) / U256::from(100)
+    }
+
+    /// First height at which a passed proposal can be executed
+    pub fn executable_height(&self) -> u64 {
+        self.end_height.saturating_add(EXECUTION_DELAY)
+    }
+
+    /// Last height at which a passed proposal can be executed
+    pub fn expiry_height(&self) -> u64 {
+        self.executable_height().saturating_add(GRACE_PERIOD)
+    }
+
+    pub fn state(&self, height: u64) -> ProposalState {
+        if self.executed_height.is_some() {
+            ProposalState::Executed
+        } else if self.canceled {
+            ProposalState::Canceled
+        } else if height <= self.end_height {
+            ProposalState::Active
+        } else if !self.passed() {
+            ProposalState::Failed
+        } else if height < self.executable_height() {
+            ProposalState::Queued
+        } else if height <= self.expiry_height() {
+            ProposalState::Succeeded
+        } else {
+            ProposalState::Expired
+        }
+    }
+}
+
+/// A recorded vote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoteRecord {
+    pub support: VoteSupport,
+    pub weight: U256,
+}
+
+/// Number of proposals ever created; ids run from 0 to this minus one
+pub fn proposal_count(state_db: &StateDB) -> u64 {
+    state_db
+        .get_storage(&PROPOSALS_PRECOMPILE, NEXT_ID_KEY)
+        .filter(|v| v.len() == 8)
+        .map(|v| {
+            let mut id = [0u8; 8];
+            id.copy_from_slice(&v);
+            u64::from_le_bytes(id)
+        })
+        .unwrap_or_default()
+}
+
+pub fn proposal(state_db: &StateDB, id: u64) -> Option<Proposal> {
+    state_db
+        .get_storage(&PROPOSALS_PRECOMPILE, &proposal_key(id))
+        .and_then(|v| bincode::deserialize(&v).ok())
+}
+
+/// Vote cast by `voter` on proposal `id`, if any
+pub fn vote_of(state_db: &StateDB, id: u64, voter: &Address) -> Option<VoteRecord> {
+    state_db
+        .get_storage(&PROPOSALS_PRECOMPILE, &vote_key(id, voter))
+        .filter(|v| v.len() == 33)
+        .and_then(|v| {
+            Some(VoteRecord {
+                support: VoteSupport::from_u8(v[0])?,
+                weight: U256::from_big_endian(&v[1..33]),
+            })
+        })
+}
+
+fn put_proposal(state_db: &StateDB, proposal: &Proposal) -> Result<(), ExecutionError> {
+    let encoded = bincode::serialize(proposal)
+        .map_err(|e| ExecutionError::Reverted(format!("Failed to encode proposal: {}", e)))?;
+    state_db.set_storage(PROPOSALS_PRECOMPILE, proposal_key(proposal.id), encoded);
+    Ok(())
+}
+
+fn load(state_db: &StateDB, id: u64) -> Result<Proposal, ExecutionError> {
+    proposal(state_db, id).ok_or_else(|| ExecutionError::Reverted("No such proposal".into()))
+}
+
+fn valid_parameter(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_PARAMETER_LEN
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_')
+}
+
+fn emit(context: &mut ExecutionContext, event: &[u8], id: u64, data: Vec<u8>) {
+    let topic: [u8; 32] = Keccak256::digest(event).into();
+    let mut id_topic = [0u8; 32];
+    id_topic[24..].copy_from_slice(&id.to_be_bytes());
+    context.add_log(Log {
+        address: PROPOSALS_PRECOMPILE,
+        topics: vec![Hash::new(topic), Hash::new(id_topic)],
+        data,
+    });
+}
+
+/// Call data for `propose(string,bytes,string)`
+pub fn propose_call_data(parameter: &str, value: &[u8], description: &str) -> Vec<u8> {
+    let parameter = padded(parameter.as_bytes());
+    let value = padded(value);
+    let description = padded(description.as_bytes());
+
+    let mut data = selector(PROPOSE_SIGNATURE).to_vec();
+    data.extend_from_slice(&word(U256::from(96)));
+    data.extend_from_slice(&word(U256::from(96 + parameter.len())));
+    data.extend_from_slice(&word(U256::from(96 + parameter.len() + value.len())));
+    data.extend_from_slice(&parameter);
+    data.extend_from_slice(&value);
+    data.extend_from_slice(&description);
+    data
+}
+
+/// Call data for `vote(uint256,uint8)`
+pub fn vote_call_data(id: u64, support: VoteSupport) -> Vec<u8> {
+    let mut data = selector(VOTE_SIGNATURE).to_vec();
+    data.extend_from_slice(&word(U256::from(id)));
+    data.extend_from_slice(&word(U256::from(support.as_u8())));
+    data
+}
+
+/// Call data for `execute(uint256)`
+pub fn execute_call_data(id: u64) -> Vec<u8> {
+    let mut data = selector(EXECUTE_SIGNATURE).to_vec();
+    data.extend_from_slice(&word(U256::from(id)));
+    data
+}
+
+/// Call data for `cancel(uint256)`
+pub fn cancel_call_data(id: u64) -> Vec<u8> {
+    let mut data = selector(CANCEL_SIGNATURE).to_vec();
+    data.extend_from_slice(&word(U256::from(id)));
+    data
+}
+
+/// Dispatch a call to the proposals precompile
+///
+/// - `propose(string,bytes,string)` opens a proposal to set a governance
+///   parameter; the caller needs [`proposal_threshold`] bonded
+/// - `vote(uint256,uint8)` votes against (0), for (1) or abstaining (2) with
+///   the caller's bonded stake; each address votes once per proposal
+/// - `execute(uint256)` applies a passed proposal once its delay is over
+/// - `cancel(uint256)` withdraws the caller's own proposal while voting is open
+/// - `proposalCount()` / `stateOf(uint256)` are read-only queries
+pub(crate) fn execute(
+    state_db: &StateDB,
+    data: &[u8],
+    from: Address,
+    value: U256,
+    context: &mut ExecutionContext,
+) -> Result<(), ExecutionError> {
+    if data.len() < 4 {
+        return Err(ExecutionError::InvalidInput);
+    }
+    if !value.is_zero() {
+        return Err(ExecutionError::Reverted("Function is not payable".into()));
+    }
+    let sel = &data[0..4];
+    let args = &data[4..];
+    let height = context.block_number;
+
+    if sel == selector(PROPOSE_SIGNATURE) {
+        let parameter = std::str::from_utf8(dynamic_arg(args, 0)?)
+            .map_err(|_| ExecutionError::InvalidInput)?
+            .to_string();
+        let new_value = dynamic_arg(args, 1)?.to_vec();
+        let description = std::str::from_utf8(dynamic_arg(args, 2)?)
+            .map_err(|_| ExecutionError::InvalidInput)?
+            .to_string();
+        if !valid_parameter(&parameter) {
+            return Err(ExecutionError::Reverted("Invalid parameter name".into()));
+        }
+        if new_value.len() > MAX_VALUE_LEN || description.len() > MAX_DESCRIPTION_LEN {
+            return Err(ExecutionError::Reverted("Proposal too large".into()));
+        }
+        if staking::bonded(state_db, &from) < proposal_threshold() {
+            return Err(ExecutionError::Reverted(
+                "Insufficient stake to propose".into(),
+            ));
+        }
+
+        let id = proposal_count(state_db);
+        let staked = state_db.accounts.get_balance(&STAKING_PRECOMPILE);
+        let proposal = Proposal {
+            id,
+            proposer: from,
+            parameter,
+            value: new_value,
+            description,
+            created_height: height,
+            end_height: height.saturating_add(VOTING_PERIOD),
+            quorum: staked * U256::from(QUORUM_PERCENTAGE) / U256::from(100),
+            votes_for: U256::zero(),
+            votes_against: U256::zero(),
+            votes_abstain: U256::zero(),
+            canceled: false,
+            executed_height: None,
+        };
+        put_proposal(state_db, &proposal)?;
+        state_db.set_storage(
+            PROPOSALS_PRECOMPILE,
+            NEXT_ID_KEY.to_vec(),
+            (id + 1).to_le_bytes().to_vec(),
+        );
+
+        let mut proposer = [0u8; 32];
+        proposer[12..].copy_from_slice(&from.0);
+        emit(
+            context,
+            b"ProposalCreated(uint256,address)",
+            id,
+            proposer.to_vec(),
+        );
+        context.output = word(U256::from(id));
+        return Ok(());
+    }
+
+    if sel == selector(VOTE_SIGNATURE) {
+        let id = u64_arg(args, 0)?;
+        let support = u8::try_from(u64_arg(args, 1)?)
+            .ok()
+            .and_then(VoteSupport::from_u8)
+            .ok_or(ExecutionError::InvalidInput)?;
+        let mut proposal = load(state_db, id)?;
+        if proposal.state(height) != ProposalState::Active {
+            return Err(ExecutionError::Reverted("Voting is closed".into()));
+        }
+        if vote_of(state_db, id, &from).is_some() {
+            return Err(ExecutionError::Reverted("Already voted".into()));
+        }
+        let weight = staking::bonded(state_db, &from);
+        if weight < vote_threshold() {
+            return Err(ExecutionError::Reverted(
+                "Insufficient stake to vote".into(),
+            ));
+        }
+
+        match support {
+            VoteSupport::For => proposal.votes_for += weight,
+            VoteSupport::Against => proposal.votes_against += weight,
+            VoteSupport::Abstain => proposal.votes_abstain += weight,
+        }
+        put_proposal(state_db, &proposal)?;
+        let mut stored = vec![support.as_u8()];
+        stored.extend_from_slice(&word(weight));
+        state_db.set_storage(PROPOSALS_PRECOMPILE, vote_key(id, &from), stored);
+
+        let mut log_data = vec![0u8; 12];
+        log_data.extend_from_slice(&from.0);
+        log_data.extend_from_slice(&word(U256::from(support.as_u8())));
+        log_data.extend_from_slice(&word(weight));
+        emit(
+            context,
+            b"VoteCast(uint256,address,uint8,uint256)",
+            id,
+            log_data,
+        );
+        context.output = word(weight);
+        return Ok(());
+    }
+
+    if sel == selector(EXECUTE_SIGNATURE) {
+        let id = u64_arg(args, 0)?;
+        let mut proposal = load(state_db, id)?;
+        match proposal.state(height) {
+            ProposalState::Succeeded => {}
+            ProposalState::Queued => {
+                return Err(ExecutionError::Reverted("Execution delay not over".into()))
+            }
+            _ => return Err(ExecutionError::Reverted("Proposal not executable".into())),
+        }
+
+        let mut param_key = b"PARAM:".to_vec();
+        param_key.extend_from_slice(proposal.parameter.as_bytes());
+        state_db.set_storage(GOVERNANCE_PARAMS, param_key, proposal.value.clone());
+        proposal.executed_height = Some(height);
+        put_proposal(state_db, &proposal)?;
+
+        emit(context, b"ProposalExecuted(uint256)", id, Vec::new());
+        return Ok(());
+    }
+
+    if sel == selector(CANCEL_SIGNATURE) {
+        let id = u64_arg(args, 0)?;
+        let mut proposal = load(state_db, id)?;
+        if proposal.proposer != from {
+            return Err(ExecutionError::AccessDenied);
+        }
+        if proposal.state(height) != ProposalState::Active {
+            return Err(ExecutionError::Reverted("Voting is closed".into()));
+        }
+        proposal.canceled = true;
+        put_proposal(state_db, &proposal)?;
+
+        emit(context, b"ProposalCanceled(uint256)", id, Vec::new());
+        return Ok(());
+    }
+
+    if sel == selector(b"proposalCount()") {
+        context.output = word(U256::from(proposal_count(state_db)));
+        return Ok(());
+    }
+
+    if sel == selector(b"stateOf(uint256)") {
+        let id = u64_arg(args, 0)?;
+        let proposal = load(state_db, id)?;
+        context.output = word(U256::from(proposal.state(height).as_u8()));
+        return Ok(());
+    }
+
+    Err(ExecutionError::InvalidInput)
+}
@@ -0,0 +1,124 @@
+// citrate/core/execution/src/precompiles/randomness.rs
+
+// Randomness precompile at 0x1008
+// Exposes the VRF output the block proposer revealed for the current block.
+// The output is fixed by the proposer's key and the previous block's output,
+// so a proposer can't grind it by reordering or withholding transactions.
+// Contracts that need several values per block derive them with a seed; the
+// caller's address is mixed in so contracts get independent streams.
+
+use citrate_consensus::types::{Hash, PublicKey, VrfProof};
+use primitive_types::U256;
+use sha3::{Digest, Keccak256};
+
+use crate::executor::ExecutionContext;
+use crate::types::{Address, ExecutionError};
+
+/// 0x0000000000000000000000000000000000001008
+pub const RANDOMNESS_PRECOMPILE: Address = Address([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10, 0x08,
+]);
+
+/// Gas for `randomness()`
+pub const RANDOMNESS_GAS: u64 = 100;
+/// Gas for `randomWithSeed(bytes32)`
+pub const RANDOM_WITH_SEED_GAS: u64 = 150;
+/// Gas for `vrfProof()`
+pub const VRF_PROOF_GAS: u64 = 200;
+
+/// Domain separator for seeded derivations
+pub const DERIVE_DOMAIN: &[u8] = b"citrate-vrf-derive";
+
+fn selector(signature: &[u8]) -> [u8; 4] {
+    let digest = Keccak256::digest(signature);
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+fn word(value: U256) -> Vec<u8> {
+    let mut out = vec![0u8; 32];
+    value.to_big_endian(&mut out);
+    out
+}
+
+/// Value returned by `randomWithSeed(seed)` when called by `caller` in a
+/// block whose VRF output is `vrf_output`
+pub fn derive_with_seed(vrf_output: &Hash, caller: &Address, seed: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(DERIVE_DOMAIN);
+    hasher.update(vrf_output.as_bytes());
+    hasher.update(caller.0);
+    hasher.update(seed);
+    hasher.finalize().into()
+}
+
+/// Dispatch a call to the randomness precompile
+///
+/// - `randomness()` returns the block's VRF output
+/// - `randomWithSeed(bytes32)` returns [`derive_with_seed`] for the caller
+/// - `vrfProof()` returns the proposer key, the VRF proof and the output, so
+///   a contract or off-chain verifier can check where the value came from
+///
+/// Calls revert when the block carries no VRF output.
+pub(crate) fn execute(
+    data: &[u8],
+    from: Address,
+    value: U256,
+    context: &mut ExecutionContext,
+) -> Result<(), ExecutionError> {
+    if data.len() < 4 {
+        return Err(ExecutionError::InvalidInput);
+    }
+    if !value.is_zero() {
+        return Err(ExecutionError::Reverted("Function is not payable".into()));
+    }
+    let sel = &data[0..4];
+    let args = &data[4..];
+
+    let vrf_output = context.vrf_reveal.output;
+    let unavailable = || ExecutionError::Reverted("No VRF output for this block".into());
+
+    if sel == selector(b"randomness()") {
+        context.use_gas(RANDOMNESS_GAS)?;
+        if vrf_output == Hash::default() {
+            return Err(unavailable());
+        }
+        context.output = vrf_output.as_bytes().to_vec();
+        return Ok(());
+    }
+
+    if sel == selector(b"randomWithSeed(bytes32)") {
+        if args.len() < 32 {
+            return Err(ExecutionError::InvalidInput);
+        }
+        context.use_gas(RANDOM_WITH_SEED_GAS)?;
+        if vrf_output == Hash::default() {
+            return Err(unavailable());
+        }
+        let mut seed = [0u8; 32];
+        seed.copy_from_slice(&args[0..32]);
+        context.output = derive_with_seed(&vrf_output, &from, &seed).to_vec();
+        return Ok(());
+    }
+
+    if sel == selector(b"vrfProof()") {
+        context.use_gas(VRF_PROOF_GAS)?;
+        if vrf_output == Hash::default() {
+            return Err(unavailable());
+        }
+        context.output = proof_output(&context.proposer, &context.vrf_reveal);
+        return Ok(());
+    }
+
+    Err(ExecutionError::InvalidInput)
+}
+
+/// ABI encoding of `(bytes32 proposer, bytes32 output, bytes proof)`
+fn proof_output(proposer: &PublicKey, vrf: &VrfProof) -> Vec<u8> {
+    let mut output = proposer.as_bytes().to_vec();
+    output.extend_from_slice(vrf.output.as_bytes());
+    output.extend_from_slice(&word(U256::from(96)));
+    output.extend_from_slice(&word(U256::from(vrf.proof.len())));
+    output.extend_from_slice(&vrf.proof);
+    output.resize(output.len() + (32 - vrf.proof.len() % 32) % 32, 0);
+    output
+}
@@ -0,0 +1,186 @@
+// citrate/core/execution/src/precompiles/staking.rs
+
+// Validator staking precompile at 0x1004
+// Deposits are held in the precompile account's balance; bonded and unbonding
+// amounts are tracked in its storage. Selection weights and slashing live in
+// citrate-economics, which reads the bonded amounts recorded here.
+
+use primitive_types::U256;
+use sha3::{Digest, Keccak256};
+
+use crate::executor::ExecutionContext;
+use crate::state::StateDB;
+use crate::tracer::{InternalTransaction, InternalTxKind};
+use crate::types::{Address, ExecutionError};
+
+/// 0x0000000000000000000000000000000000001004
+pub const STAKING_PRECOMPILE: Address = Address([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10, 0x04,
+]);
+
+/// Blocks between `unstake` and the funds becoming withdrawable
+pub const UNBONDING_PERIOD: u64 = 100_800;
+
+const BONDED_PREFIX: &[u8] = b"BONDED:";
+const UNBONDING_PREFIX: &[u8] = b"UNBONDING:";
+
+fn selector(signature: &[u8]) -> [u8; 4] {
+    let digest = Keccak256::digest(signature);
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+fn key(prefix: &[u8], address: &Address) -> Vec<u8> {
+    let mut key = prefix.to_vec();
+    key.extend_from_slice(&address.0);
+    key
+}
+
+fn word(value: U256) -> Vec<u8> {
+    let mut out = vec![0u8; 32];
+    value.to_big_endian(&mut out);
+    out
+}
+
+fn address_arg(args: &[u8]) -> Result<Address, ExecutionError> {
+    if args.len() < 32 {
+        return Err(ExecutionError::InvalidInput);
+    }
+    let mut addr = [0u8; 20];
+    addr.copy_from_slice(&args[12..32]);
+    Ok(Address(addr))
+}
+
+/// Amount currently bonded by `address`
+pub fn bonded(state_db: &StateDB, address: &Address) -> U256 {
+    state_db
+        .get_storage(&STAKING_PRECOMPILE, &key(BONDED_PREFIX, address))
+        .filter(|v| v.len() == 32)
+        .map(|v| U256::from_big_endian(&v))
+        .unwrap_or_default()
+}
+
+/// Amount waiting to be released and the height it becomes withdrawable
+pub fn unbonding(state_db: &StateDB, address: &Address) -> (U256, u64) {
+    state_db
+        .get_storage(&STAKING_PRECOMPILE, &key(UNBONDING_PREFIX, address))
+        .filter(|v| v.len() == 40)
+        .map(|v| {
+            let mut release = [0u8; 8];
+            release.copy_from_slice(&v[32..40]);
+            (U256::from_big_endian(&v[..32]), u64::from_le_bytes(release))
+        })
+        .unwrap_or_default()
+}
+
+fn set_bonded(state_db: &StateDB, address: &Address, amount: U256) {
+    let key = key(BONDED_PREFIX, address);
+    if amount.is_zero() {
+        state_db.delete_storage(STAKING_PRECOMPILE, &key);
+    } else {
+        state_db.set_storage(STAKING_PRECOMPILE, key, word(amount));
+    }
+}
+
+fn set_unbonding(state_db: &StateDB, address: &Address, amount: U256, release_height: u64) {
+    let key = key(UNBONDING_PREFIX, address);
+    if amount.is_zero() {
+        state_db.delete_storage(STAKING_PRECOMPILE, &key);
+    } else {
+        let mut stored = word(amount);
+        stored.extend_from_slice(&release_height.to_le_bytes());
+        state_db.set_storage(STAKING_PRECOMPILE, key, stored);
+    }
+}
+
+/// Dispatch a call to the staking precompile. `value` has already been
+/// credited to the precompile account by the caller.
+///
+/// - `stake()` bonds the attached value
+/// - `unstake(uint256)` moves bonded stake into unbonding; a second unstake
+///   before withdrawal restarts the unbonding period for the combined amount
+/// - `withdraw()` pays out unbonded stake once the period has passed
+/// - `stakeOf(address)` / `unbondingOf(address)` are read-only queries
+pub(crate) fn execute(
+    state_db: &StateDB,
+    data: &[u8],
+    from: Address,
+    value: U256,
+    context: &mut ExecutionContext,
+) -> Result<(), ExecutionError> {
+    if data.len() < 4 {
+        return Err(ExecutionError::InvalidInput);
+    }
+    let sel = &data[0..4];
+    let args = &data[4..];
+
+    if sel == selector(b"stake()") {
+        if value.is_zero() {
+            return Err(ExecutionError::Reverted("No stake attached".into()));
+        }
+        set_bonded(state_db, &from, bonded(state_db, &from) + value);
+        context.output = word(bonded(state_db, &from));
+        return Ok(());
+    }
+
+    // Only stake() accepts value
+    if !value.is_zero() {
+        return Err(ExecutionError::Reverted("Function is not payable".into()));
+    }
+
+    if sel == selector(b"unstake(uint256)") {
+        if args.len() < 32 {
+            return Err(ExecutionError::InvalidInput);
+        }
+        let amount = U256::from_big_endian(&args[0..32]);
+        let current = bonded(state_db, &from);
+        if amount.is_zero() || amount > current {
+            return Err(ExecutionError::Reverted("Insufficient bonded stake".into()));
+        }
+        let (pending, _) = unbonding(state_db, &from);
+        let release_height = context.block_number.saturating_add(UNBONDING_PERIOD);
+        set_bonded(state_db, &from, current - amount);
+        set_unbonding(state_db, &from, pending + amount, release_height);
+        context.output = word(U256::from(release_height));
+        return Ok(());
+    }
+
+    if sel == selector(b"withdraw()") {
+        let (amount, release_height) = unbonding(state_db, &from);
+        if amount.is_zero() {
+            return Err(ExecutionError::Reverted("Nothing to withdraw".into()));
+        }
+        if context.block_number < release_height {
+            return Err(ExecutionError::Reverted("Stake is still unbonding".into()));
+        }
+        set_unbonding(state_db, &from, U256::zero(), 0);
+        state_db
+            .accounts
+            .transfer(&STAKING_PRECOMPILE, &from, amount)?;
+        context.internal_transactions.push(InternalTransaction {
+            kind: InternalTxKind::Call,
+            from: STAKING_PRECOMPILE,
+            to: from,
+            value: amount,
+            depth: 1,
+        });
+        context.output = word(amount);
+        return Ok(());
+    }
+
+    if sel == selector(b"stakeOf(address)") {
+        let who = address_arg(args)?;
+        context.output = word(bonded(state_db, &who));
+        return Ok(());
+    }
+
+    if sel == selector(b"unbondingOf(address)") {
+        let who = address_arg(args)?;
+        let (amount, release_height) = unbonding(state_db, &who);
+        let mut output = word(amount);
+        output.extend_from_slice(&word(U256::from(release_height)));
+        context.output = output;
+        return Ok(());
+    }
+
+    Err(ExecutionError::InvalidInput)
+}
@@ -0,0 +1,151 @@
+// citrate/core/execution/src/precompiles/validator_keys.rs
+
+// Validator key registry precompile at 0x1005
+// Records block signing key rotations so peers can tell a retired producer
+// key from the one that replaced it. A rotation is sent by the retiring key
+// and carries a proof that the caller also holds the new key. Each key can
+// be retired once; the first rotation recorded for it is final.
+
+use citrate_consensus::crypto::verify_signature;
+use citrate_consensus::types::{Hash, PublicKey, Signature};
+use primitive_types::U256;
+use sha3::{Digest, Keccak256};
+
+use crate::executor::ExecutionContext;
+use crate::state::StateDB;
+use crate::types::{Address, ExecutionError, Log};
+
+/// 0x0000000000000000000000000000000000001005
+pub const VALIDATOR_KEYS_PRECOMPILE: Address = Address([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10, 0x05,
+]);
+
+/// Domain separator for the new key's proof of possession
+pub const ROTATION_DOMAIN: &[u8] = b"citrate-validator-key-rotation";
+
+const ROTATED_PREFIX: &[u8] = b"ROTATED:";
+
+fn selector(signature: &[u8]) -> [u8; 4] {
+    let digest = Keccak256::digest(signature);
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+fn key(address: &Address) -> Vec<u8> {
+    let mut key = ROTATED_PREFIX.to_vec();
+    key.extend_from_slice(&address.0);
+    key
+}
+
+fn word(value: U256) -> Vec<u8> {
+    let mut out = vec![0u8; 32];
+    value.to_big_endian(&mut out);
+    out
+}
+
+/// Message the new key signs to prove the retiring key's owner holds it
+pub fn rotation_message(retiring: &Address) -> Vec<u8> {
+    let mut message = ROTATION_DOMAIN.to_vec();
+    message.extend_from_slice(&retiring.0);
+    message
+}
+
+/// Call data for `rotateKey(bytes32,bytes32,bytes32)`: the new public key
+/// followed by the two halves of its proof signature
+pub fn rotate_call_data(new_key: &PublicKey, proof: &Signature) -> Vec<u8> {
+    let mut data = selector(b"rotateKey(bytes32,bytes32,bytes32)").to_vec();
+    data.extend_from_slice(new_key.as_bytes());
+    data.extend_from_slice(proof.as_bytes());
+    data
+}
+
+/// Key that replaced the one behind `retired`, and the height it was recorded at
+pub fn rotated_to(state_db: &StateDB, retired: &Address) -> Option<(PublicKey, u64)> {
+    state_db
+        .get_storage(&VALIDATOR_KEYS_PRECOMPILE, &key(retired))
+        .filter(|v| v.len() == 40)
+        .map(|v| {
+            let mut new_key = [0u8; 32];
+            new_key.copy_from_slice(&v[..32]);
+            let mut height = [0u8; 8];
+            height.copy_from_slice(&v[32..40]);
+            (PublicKey::new(new_key), u64::from_le_bytes(height))
+        })
+}
+
+/// Dispatch a call to the validator key registry
+///
+/// - `rotateKey(bytes32,bytes32,bytes32)` retires the caller's key in favour
+///   of the given key; the proof must be the new key's signature over
+///   [`rotation_message`] for the caller
+/// - `rotatedTo(address)` returns the replacement key and height, or zeros
+pub(crate) fn execute(
+    state_db: &StateDB,
+    data: &[u8],
+    from: Address,
+    value: U256,
+    context: &mut ExecutionContext,
+) -> Result<(), ExecutionError> {
+    if data.len() < 4 {
+        return Err(ExecutionError::InvalidInput);
+    }
+    if !value.is_zero() {
+        return Err(ExecutionError::Reverted("Function is not payable".into()));
+    }
+    let sel = &data[0..4];
+    let args = &data[4..];
+
+    if sel == selector(b"rotateKey(bytes32,bytes32,bytes32)") {
+        if args.len() < 96 {
+            return Err(ExecutionError::InvalidInput);
+        }
+        let mut new_key = [0u8; 32];
+        new_key.copy_from_slice(&args[0..32]);
+        let new_key = PublicKey::new(new_key);
+        let mut proof = [0u8; 64];
+        proof.copy_from_slice(&args[32..96]);
+
+        if rotated_to(state_db, &from).is_some() {
+            return Err(ExecutionError::Reverted("Key already rotated".into()));
+        }
+        if Address::from_public_key(&new_key) == from {
+            return Err(ExecutionError::Reverted(
+                "New key matches retiring key".into(),
+            ));
+        }
+        if !verify_signature(&new_key, &rotation_message(&from), &Signature::new(proof)) {
+            return Err(ExecutionError::Reverted(
+                "Invalid proof of possession".into(),
+            ));
+        }
+
+        let mut stored = new_key.as_bytes().to_vec();
+        stored.extend_from_slice(&context.block_number.to_le_bytes());
+        state_db.set_storage(VALIDATOR_KEYS_PRECOMPILE, key(&from), stored);
+
+        let mut retiring = [0u8; 32];
+        retiring[12..].copy_from_slice(&from.0);
+        let topic: [u8; 32] = Keccak256::digest(b"KeyRotated(address,bytes32)").into();
+        context.add_log(Log {
+            address: VALIDATOR_KEYS_PRECOMPILE,
+            topics: vec![Hash::new(topic), Hash::new(retiring)],
+            data: new_key.as_bytes().to_vec(),
+        });
+        return Ok(());
+    }
+
+    if sel == selector(b"rotatedTo(address)") {
+        if args.len() < 32 {
+            return Err(ExecutionError::InvalidInput);
+        }
+        let mut who = [0u8; 20];
+        who.copy_from_slice(&args[12..32]);
+        let (new_key, height) =
+            rotated_to(state_db, &Address(who)).unwrap_or((PublicKey::new([0; 32]), 0));
+        let mut output = new_key.as_bytes().to_vec();
+        output.extend_from_slice(&word(U256::from(height)));
+        context.output = output;
+        return Ok(());
+    }
+
+    Err(ExecutionError::InvalidInput)
+}
@@ -1,12 +1,14 @@
 // citrate/core/execution/src/revm_adapter.rs
 
 use crate::state::StateDB;
+use crate::tracer::{CallTracer, ExecutionInspector, InternalTransaction, TransferTracer};
 use crate::types::{Address, ExecutionError};
 use primitive_types::U256;
 use revm::{
+    inspector_handle_register,
     primitives::{
-        AccountInfo, Address as RevmAddress, Bytecode, Bytes, ExecutionResult, Output,
-        TransactTo, TxEnv, B256, U256 as RevmU256, SpecId, KECCAK_EMPTY,
+        AccountInfo, Address as RevmAddress, Bytecode, Bytes, Env, ExecutionResult, Output,
+        TransactTo, B256, U256 as RevmU256, SpecId, KECCAK_EMPTY,
     },
     Database, DatabaseCommit, Evm,
 };
@@ -117,7 +119,65 @@ impl DatabaseCommit for StateDBAdapter {
     }
 }
 
+/// Build the revm environment for a single transaction
+#[allow(clippy::too_many_arguments)]
+fn build_env(
+    caller: Address,
+    transact_to: TransactTo,
+    data: Vec<u8>,
+    value: U256,
+    gas_limit: u64,
+    gas_price: U256,
+    chain_id: u64,
+    block_number: u64,
+    block_timestamp: u64,
+) -> Box<Env> {
+    let mut env = Box::<Env>::default();
+    env.cfg.chain_id = chain_id;
+    env.tx.caller = RevmAddress::from_slice(&caller.0);
+    env.tx.transact_to = transact_to;
+    env.tx.data = Bytes::from(data);
+    env.tx.value = RevmU256::from_limbs(value.0);
+    env.tx.gas_limit = gas_limit;
+    env.tx.gas_price = RevmU256::from_limbs(gas_price.0);
+    env.tx.chain_id = Some(chain_id);
+    env.block.number = RevmU256::from(block_number);
+    env.block.timestamp = RevmU256::from(block_timestamp);
+    env
+}
+
+/// Run and commit a transaction. Internal value transfers of a successful
+/// run are appended to `internal`; the tracer, if given, records call
+/// frames and opcodes.
+fn transact(
+    db: &mut StateDBAdapter,
+    env: Box<Env>,
+    tracer: Option<&mut CallTracer>,
+    internal: &mut Vec<InternalTransaction>,
+) -> Result<ExecutionResult, ExecutionError> {
+    let mut transfers = TransferTracer::new();
+    let inspector = ExecutionInspector {
+        transfers: &mut transfers,
+        tracer,
+    };
+    // SHANGHAI includes all opcodes needed by Solidity 0.8.x
+    let result = Evm::builder()
+        .with_db(db)
+        .with_external_context(inspector)
+        .with_env(env)
+        .with_spec_id(SpecId::SHANGHAI)
+        .append_handler_register(inspector_handle_register)
+        .build()
+        .transact_commit()
+        .map_err(|e| ExecutionError::Reverted(format!("revm execution failed: {:?}", e)))?;
+    if result.is_success() {
+        internal.extend(transfers.into_transfers());
+    }
+    Ok(result)
+}
+
 /// Execute contract creation using revm
+#[allow(clippy::too_many_arguments)]
 pub fn execute_contract_create(
     state_db: Arc<StateDB>,
     deployer: Address,
@@ -128,6 +188,8 @@ pub fn execute_contract_create(
     chain_id: u64,
     block_number: u64,
     block_timestamp: u64,
+    tracer: Option<&mut CallTracer>,
+    internal: &mut Vec<InternalTransaction>,
 ) -> Result<(Address, Vec<u8>, u64), ExecutionError> {
     debug!("Executing contract creation with revm");
     debug!("  Deployer: {}", deployer);
@@ -137,34 +199,20 @@ pub fn execute_contract_create(
     // Create database adapter
     let mut db = StateDBAdapter::new(state_db.clone());
 
-    // Build EVM with transaction
-    // Use LONDON spec for EVM compatibility
-    // LONDON includes all necessary opcodes for Solidity 0.8.x
-    let mut evm = Evm::builder()
-        .with_db(&mut db)
-        .modify_cfg_env(|cfg| {
-            cfg.chain_id = chain_id;
-        })
-        .with_spec_id(SpecId::SHANGHAI)
-        .modify_tx_env(|tx| {
-            tx.caller = RevmAddress::from_slice(&deployer.0);
-            tx.transact_to = TransactTo::Create;
-            tx.data = Bytes::from(init_code);
-            tx.value = RevmU256::from_limbs(value.0);
-            tx.gas_limit = gas_limit;
-            tx.gas_price = RevmU256::from_limbs(gas_price.0);
-            tx.chain_id = Some(chain_id);
-        })
-        .modify_block_env(|block| {
-            block.number = RevmU256::from(block_number);
-            block.timestamp = RevmU256::from(block_timestamp);
-        })
-        .build();
+    let env = build_env(
+        deployer,
+        TransactTo::Create,
+        init_code,
+        value,
+        gas_limit,
+        gas_price,
+        chain_id,
+        block_number,
+        block_timestamp,
+    );
 
     // Execute transaction
-    let result = evm.transact_commit().map_err(|e| {
-        ExecutionError::Reverted(format!("revm execution failed: {:?}", e))
-    })?;
+    let result = transact(&mut db, env, tracer, internal)?;
 
     match result {
         ExecutionResult::Success {
@@ -214,6 +262,7 @@ pub fn execute_contract_create(
 }
 
 /// Execute contract call using revm
+#[allow(clippy::too_many_arguments)]
 pub fn execute_contract_call(
     state_db: Arc<StateDB>,
     caller: Address,
@@ -225,6 +274,8 @@ pub fn execute_contract_call(
     chain_id: u64,
     block_number: u64,
     block_timestamp: u64,
+    tracer: Option<&mut CallTracer>,
+    internal: &mut Vec<InternalTransaction>,
 ) -> Result<(Vec<u8>, u64), ExecutionError> {
     debug!("Executing contract call with revm");
     debug!("  Caller: {}", caller);
@@ -234,33 +285,20 @@ pub fn execute_contract_call(
     // Create database adapter
     let mut db = StateDBAdapter::new(state_db);
 
-    // Build EVM with transaction
-    // Use LONDON spec for EVM compatibility
-    let mut evm = Evm::builder()
-        .with_db(&mut db)
-        .modify_cfg_env(|cfg| {
-            cfg.chain_id = chain_id;
-        })
-        .with_spec_id(SpecId::SHANGHAI)
-        .modify_tx_env(|tx| {
-            tx.caller = RevmAddress::from_slice(&caller.0);
-            tx.transact_to = TransactTo::Call(RevmAddress::from_slice(&contract.0));
-            tx.data = Bytes::from(calldata);
-            tx.value = RevmU256::from_limbs(value.0);
-            tx.gas_limit = gas_limit;
-            tx.gas_price = RevmU256::from_limbs(gas_price.0);
-            tx.chain_id = Some(chain_id);
-        })
-        .modify_block_env(|block| {
-            block.number = RevmU256::from(block_number);
-            block.timestamp = RevmU256::from(block_timestamp);
-        })
-        .build();
+    let env = build_env(
+        caller,
+        TransactTo::Call(RevmAddress::from_slice(&contract.0)),
+        calldata,
+        value,
+        gas_limit,
+        gas_price,
+        chain_id,
+        block_number,
+        block_timestamp,
+    );
 
     // Execute transaction
-    let result = evm.transact_commit().map_err(|e| {
-        ExecutionError::Reverted(format!("revm execution failed: {:?}", e))
-    })?;
+    let result = transact(&mut db, env, tracer, internal)?;
 
     match result {
         ExecutionResult::Success {
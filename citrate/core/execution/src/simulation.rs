@@ -0,0 +1,205 @@
+// citrate/core/execution/src/simulation.rs
+
+// Transaction simulation against a private copy of the state
+//
+// The caller may replace account balances, nonces, code and storage slots
+// before the transaction runs. The result carries the receipt and every
+// account field and storage slot the transaction changed, measured against
+// the overridden state.
+use crate::state::StateDB;
+use crate::types::{Address, TransactionReceipt};
+use citrate_consensus::types::Hash;
+use primitive_types::U256;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// State to assume for one account while simulating
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccountOverride {
+    pub balance: Option<U256>,
+    pub nonce: Option<u64>,
+    pub code: Option<Vec<u8>>,
+    /// Storage slots to set; other slots keep their current values
+    pub storage: BTreeMap<[u8; 32], [u8; 32]>,
+}
+
+/// Overrides by account
+pub type StateOverrides = HashMap<Address, AccountOverride>;
+
+/// A value before and after the transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change<T> {
+    pub from: T,
+    pub to: T,
+}
+
+/// Storage slot written by the transaction. `None` is an unset slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotChange {
+    pub key: Vec<u8>,
+    pub from: Option<Vec<u8>>,
+    pub to: Option<Vec<u8>>,
+}
+
+/// Everything the transaction changed on one account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountDiff {
+    pub address: Address,
+    pub balance: Option<Change<U256>>,
+    pub nonce: Option<Change<u64>>,
+    /// Code hash, when the code was replaced
+    pub code: Option<Change<Hash>>,
+    pub storage: Vec<SlotChange>,
+}
+
+impl AccountDiff {
+    fn is_empty(&self) -> bool {
+        self.balance.is_none()
+            && self.nonce.is_none()
+            && self.code.is_none()
+            && self.storage.is_empty()
+    }
+}
+
+/// Outcome of a simulated transaction
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    pub receipt: TransactionReceipt,
+    /// Changed accounts, ordered by address
+    pub state_diff: Vec<AccountDiff>,
+}
+
+/// Write `overrides` into `state`
+pub fn apply_overrides(state: &StateDB, overrides: &StateOverrides) {
+    for (address, account) in overrides {
+        if let Some(balance) = account.balance {
+            state.accounts.set_balance(*address, balance);
+        }
+        if let Some(nonce) = account.nonce {
+            state.accounts.set_nonce(*address, nonce);
+        }
+        if let Some(code) = &account.code {
+            state.set_code(*address, code.clone());
+        }
+        for (key, value) in &account.storage {
+            state.set_storage(*address, key.to_vec(), value.to_vec());
+        }
+    }
+}
+
+/// Forget which accounts and slots have changed, so the next `diff` only
+/// reports what happens afterwards
+pub(crate) fn reset_changes(state: &StateDB) {
+    state.accounts.take_changed();
+    state.take_changed_storage();
+}
+
+/// Accounts and storage slots that differ between `before` and `after`,
+/// limited to those `after` recorded as changed since `reset_changes`
+pub(crate) fn diff(before: &StateDB, after: &StateDB) -> Vec<AccountDiff> {
+    let mut addresses: HashSet<Address> = after.accounts.take_changed().into_iter().collect();
+    let mut storage: HashMap<Address, Vec<Vec<u8>>> = HashMap::new();
+    for (address, key) in after.take_changed_storage() {
+        addresses.insert(address);
+        storage.entry(address).or_default().push(key);
+    }
+
+    let mut diffs: Vec<AccountDiff> = addresses
+        .into_iter()
+        .map(|address| {
+            let old = before.accounts.get_account(&address);
+            let new = after.accounts.get_account(&address);
+            let mut keys = storage.remove(&address).unwrap_or_default();
+            keys.sort();
+            keys.dedup();
+            AccountDiff {
+                address,
+                balance: (old.balance != new.balance).then_some(Change {
+                    from: old.balance,
+                    to: new.balance,
+                }),
+                nonce: (old.nonce != new.nonce).then_some(Change {
+                    from: old.nonce,
+                    to: new.nonce,
+                }),
+                code: (old.code_hash != new.code_hash).then_some(Change {
+                    from: old.code_hash,
+                    to: new.code_hash,
+                }),
+                storage: keys
+                    .into_iter()
+                    .filter_map(|key| {
+                        let from = before.get_storage(&address, &key);
+                        let to = after.get_storage(&address, &key);
+                        (from != to).then_some(SlotChange { key, from, to })
+                    })
+                    .collect(),
+            }
+        })
+        .filter(|diff| !diff.is_empty())
+        .collect();
+    diffs.sort_by_key(|diff| diff.address.0);
+    diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_and_diff() {
+        let alice = Address([1; 20]);
+        let bob = Address([2; 20]);
+        let state = StateDB::new();
+        state.accounts.set_balance(alice, U256::from(10));
+
+        let mut overrides = StateOverrides::new();
+        overrides.insert(
+            bob,
+            AccountOverride {
+                balance: Some(U256::from(500)),
+                code: Some(vec![0x00]),
+                storage: BTreeMap::from([([0u8; 32], [7u8; 32])]),
+                ..Default::default()
+            },
+        );
+        apply_overrides(&state, &overrides);
+        assert_eq!(state.accounts.get_balance(&bob), U256::from(500));
+        assert_eq!(
+            state.get_code(&state.accounts.get_code_hash(&bob)),
+            Some(vec![0x00])
+        );
+        assert_eq!(state.get_storage(&bob, &[0u8; 32]), Some(vec![7u8; 32]));
+
+        reset_changes(&state);
+        let before = state.fork();
+        state
+            .accounts
+            .transfer(&bob, &alice, U256::from(100))
+            .unwrap();
+        state.set_storage(bob, vec![1u8; 32], vec![9u8; 32]);
+        // Rewritten with the same value: not a change
+        state.set_storage(bob, vec![0u8; 32], vec![7u8; 32]);
+
+        let diffs = diff(&before, &state);
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[0].address, alice);
+        assert_eq!(
+            diffs[0].balance,
+            Some(Change {
+                from: U256::from(10),
+                to: U256::from(110)
+            })
+        );
+        assert!(diffs[0].storage.is_empty());
+        assert_eq!(diffs[1].address, bob);
+        assert_eq!(
+            diffs[1].storage,
+            vec![SlotChange {
+                key: vec![1u8; 32],
+                from: None,
+                to: Some(vec![9u8; 32]),
+            }]
+        );
+        assert!(diffs[1].code.is_none());
+    }
+}
@@ -5,6 +5,7 @@ use crate::types::{AccountState, Address, ExecutionError, ModelId};
 use dashmap::DashMap;
 use citrate_consensus::types::Hash;
 use primitive_types::U256;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{debug, info};
 
@@ -12,6 +13,10 @@ use tracing::{debug, info};
 pub struct AccountManager {
     accounts: Arc<DashMap<Address, AccountState>>,
     dirty: Arc<DashMap<Address, bool>>,
+    /// Accounts whose state trie entry is out of date
+    trie_dirty: Arc<DashMap<Address, bool>>,
+    /// Accounts changed since the last block was archived
+    changed: Arc<DashMap<Address, bool>>,
 }
 
 impl AccountManager {
@@ -19,6 +24,8 @@ impl AccountManager {
         Self {
             accounts: Arc::new(DashMap::new()),
             dirty: Arc::new(DashMap::new()),
+            trie_dirty: Arc::new(DashMap::new()),
+            changed: Arc::new(DashMap::new()),
         }
     }
 
@@ -34,6 +41,17 @@ impl AccountManager {
     pub fn set_account(&self, address: Address, state: AccountState) {
         self.accounts.insert(address, state);
         self.dirty.insert(address, true);
+        self.trie_dirty.insert(address, true);
+        self.changed.insert(address, true);
+    }
+
+    /// Remove an account entirely
+    pub fn remove_account(&self, address: &Address) {
+        if self.accounts.remove(address).is_some() {
+            self.dirty.insert(*address, true);
+            self.trie_dirty.insert(*address, true);
+            self.changed.insert(*address, true);
+        }
     }
 
     /// Get balance
@@ -167,6 +185,51 @@ impl AccountManager {
     /// Clear dirty flags
     pub fn clear_dirty(&self) {
         self.dirty.clear();
+        self.changed.clear();
+    }
+
+    /// Take the accounts changed since they were last taken or cleared.
+    /// Unlike the dirty flags these survive a `restore`.
+    pub fn take_changed(&self) -> Vec<Address> {
+        let addresses: Vec<Address> = self.changed.iter().map(|e| *e.key()).collect();
+        for address in &addresses {
+            self.changed.remove(address);
+        }
+        addresses
+    }
+
+    /// All accounts with their state
+    pub fn all_accounts(&self) -> Vec<(Address, AccountState)> {
+        self.accounts
+            .iter()
+            .map(|e| (*e.key(), e.value().clone()))
+            .collect()
+    }
+
+    /// Schedule an account's state trie entry to be recomputed
+    pub fn mark_trie_dirty(&self, address: Address) {
+        self.trie_dirty.insert(address, true);
+    }
+
+    /// Take the accounts changed since they were last written to the state trie.
+    /// Changes made after an address is taken mark it again.
+    pub fn take_trie_dirty(&self) -> Vec<Address> {
+        let addresses: Vec<Address> = self.trie_dirty.iter().map(|e| *e.key()).collect();
+        for address in &addresses {
+            self.trie_dirty.remove(address);
+        }
+        addresses
+    }
+
+    /// Set an account's storage root in place without scheduling it for re-hashing
+    pub fn set_storage_root(&self, address: &Address, storage_root: Hash) {
+        if let Some(mut account) = self.accounts.get_mut(address) {
+            if account.storage_root != storage_root {
+                account.storage_root = storage_root;
+                self.dirty.insert(*address, true);
+                self.changed.insert(*address, true);
+            }
+        }
     }
 
     /// Create snapshot for rollback
@@ -182,8 +245,23 @@ impl AccountManager {
 
     /// Restore from snapshot
     pub fn restore(&self, snapshot: AccountSnapshot) {
+        // Accounts that differ from the snapshot need their trie entry recomputed
+        let restored: HashMap<Address, AccountState> = snapshot.accounts.into_iter().collect();
+        for entry in self.accounts.iter() {
+            if restored.get(entry.key()) != Some(entry.value()) {
+                self.trie_dirty.insert(*entry.key(), true);
+                self.changed.insert(*entry.key(), true);
+            }
+        }
+        for addr in restored.keys() {
+            if !self.accounts.contains_key(addr) {
+                self.trie_dirty.insert(*addr, true);
+                self.changed.insert(*addr, true);
+            }
+        }
+
         self.accounts.clear();
-        for (addr, state) in snapshot.accounts {
+        for (addr, state) in restored {
             self.accounts.insert(addr, state);
         }
         self.dirty.clear();
@@ -265,4 +343,28 @@ mod tests {
         let result = manager.check_and_increment_nonce(&addr, 0);
         assert!(matches!(result, Err(ExecutionError::InvalidNonce { .. })));
     }
+
+    #[test]
+    fn test_trie_dirty_tracking() {
+        let manager = AccountManager::new();
+        let alice = Address([1; 20]);
+        let bob = Address([2; 20]);
+
+        manager.set_balance(alice, U256::from(100));
+        assert_eq!(manager.take_trie_dirty(), vec![alice]);
+        assert!(manager.take_trie_dirty().is_empty());
+
+        // Storage root updates do not re-schedule the account
+        manager.set_storage_root(&alice, Hash::new([7; 32]));
+        assert!(manager.take_trie_dirty().is_empty());
+        assert_eq!(manager.get_account(&alice).storage_root, Hash::new([7; 32]));
+
+        // Restoring marks accounts that changed or disappeared since the snapshot
+        let snapshot = manager.snapshot();
+        manager.set_balance(bob, U256::from(5));
+        manager.take_trie_dirty();
+        manager.restore(snapshot);
+        assert_eq!(manager.take_trie_dirty(), vec![bob]);
+        assert!(!manager.exists(&bob));
+    }
 }
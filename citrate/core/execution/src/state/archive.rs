@@ -0,0 +1,81 @@
+// citrate/core/execution/src/state/archive.rs
+
+// Per-block state history kept by archive nodes
+use crate::types::{AccountState, Address};
+use citrate_consensus::types::Hash;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// State a block left behind: every account and storage slot it changed
+#[derive(Debug, Clone)]
+pub struct BlockStateDiff {
+    pub height: u64,
+    pub state_root: Hash,
+    pub accounts: Vec<(Address, AccountState)>,
+    pub storage: Vec<StorageChange>,
+}
+
+/// New value of one storage slot; `None` when the slot was deleted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageChange {
+    pub address: Address,
+    pub key: Vec<u8>,
+    pub value: Option<Vec<u8>>,
+}
+
+/// Accounts and storage slots changed by one or more blocks
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangedKeys {
+    pub accounts: Vec<Address>,
+    pub storage: Vec<(Address, Vec<u8>)>,
+}
+
+impl ChangedKeys {
+    /// Keys changed by `diff`
+    pub fn of(diff: &BlockStateDiff) -> Self {
+        Self {
+            accounts: diff.accounts.iter().map(|(address, _)| *address).collect(),
+            storage: diff
+                .storage
+                .iter()
+                .map(|change| (change.address, change.key.clone()))
+                .collect(),
+        }
+    }
+
+    /// Add the keys of `other` not already present
+    pub fn merge(&mut self, other: ChangedKeys) {
+        let accounts: HashSet<Address> = self.accounts.iter().copied().collect();
+        self.accounts
+            .extend(other.accounts.into_iter().filter(|a| !accounts.contains(a)));
+
+        let storage: HashSet<(Address, Vec<u8>)> = self.storage.iter().cloned().collect();
+        self.storage
+            .extend(other.storage.into_iter().filter(|s| !storage.contains(s)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_deduplicates() {
+        let alice = Address([1; 20]);
+        let bob = Address([2; 20]);
+        let mut keys = ChangedKeys {
+            accounts: vec![alice],
+            storage: vec![(alice, b"a".to_vec())],
+        };
+        keys.merge(ChangedKeys {
+            accounts: vec![alice, bob],
+            storage: vec![(alice, b"a".to_vec()), (bob, b"a".to_vec())],
+        });
+
+        assert_eq!(keys.accounts, vec![alice, bob]);
+        assert_eq!(
+            keys.storage,
+            vec![(alice, b"a".to_vec()), (bob, b"a".to_vec())]
+        );
+    }
+}
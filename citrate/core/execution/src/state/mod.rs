@@ -4,10 +4,14 @@
 // Handles account states, caching, and trie operations
 
 pub mod account;
+pub mod archive;
 pub mod cache;
+pub mod root_pipeline;
 pub mod state_db;
 pub mod trie;
 
 pub use account::AccountManager;
+pub use archive::{BlockStateDiff, ChangedKeys, StorageChange};
+pub use root_pipeline::{PendingStateRoot, StateRootPipeline};
 pub use state_db::{StateDB, StateRoot};
-pub use trie::{Trie, TrieNode};
+pub use trie::{verify_proof, ProofError, Trie, TrieNode};
@@ -0,0 +1,143 @@
+// citrate/core/execution/src/state/root_pipeline.rs
+
+// Background state root computation between execution and sealing
+use crate::state::{StateDB, StateRoot};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use tokio::sync::oneshot;
+use tracing::debug;
+
+enum Command {
+    /// Write accounts changed so far into the state trie
+    Update,
+    /// Write the remaining changes and report the root
+    Finalize(oneshot::Sender<StateRoot>),
+}
+
+/// Keeps the state trie up to date on a background thread while transactions
+/// execute, so sealing a block only waits for the last changes to be hashed.
+///
+/// Call [`StateRootPipeline::update`] after each executed transaction and
+/// [`StateRootPipeline::finalize`] once the block's transactions are done.
+pub struct StateRootPipeline {
+    commands: Option<mpsc::Sender<Command>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl StateRootPipeline {
+    /// Start the background worker for `state_db`
+    pub fn spawn(state_db: Arc<StateDB>) -> Self {
+        let (commands, receiver) = mpsc::channel();
+        let worker = std::thread::Builder::new()
+            .name("state-root".to_string())
+            .spawn(move || Self::run(state_db, receiver))
+            .expect("failed to spawn state root worker");
+
+        Self {
+            commands: Some(commands),
+            worker: Some(worker),
+        }
+    }
+
+    /// Hash the accounts touched so far in the background
+    pub fn update(&self) {
+        if let Some(commands) = &self.commands {
+            let _ = commands.send(Command::Update);
+        }
+    }
+
+    /// Request the state root covering every change made before this call.
+    /// Other sealing work can run while the worker finishes hashing.
+    pub fn finalize(&self) -> PendingStateRoot {
+        let (reply, receiver) = oneshot::channel();
+        if let Some(commands) = &self.commands {
+            let _ = commands.send(Command::Finalize(reply));
+        }
+        PendingStateRoot(receiver)
+    }
+
+    fn run(state_db: Arc<StateDB>, commands: mpsc::Receiver<Command>) {
+        while let Ok(command) = commands.recv() {
+            match command {
+                Command::Update => {
+                    let updated = state_db.update_state_trie();
+                    if updated > 0 {
+                        debug!("State root pipeline hashed {} accounts", updated);
+                    }
+                }
+                Command::Finalize(reply) => {
+                    let _ = reply.send(state_db.calculate_state_root());
+                }
+            }
+        }
+    }
+}
+
+impl Drop for StateRootPipeline {
+    fn drop(&mut self) {
+        // Closing the channel stops the worker once queued commands are done
+        self.commands.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// State root being finalized by a [`StateRootPipeline`]
+pub struct PendingStateRoot(oneshot::Receiver<StateRoot>);
+
+impl PendingStateRoot {
+    /// Wait for the worker to report the root
+    pub async fn wait(self) -> anyhow::Result<StateRoot> {
+        self.0
+            .await
+            .map_err(|_| anyhow::anyhow!("state root worker stopped"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Address;
+    use primitive_types::U256;
+
+    #[tokio::test]
+    async fn test_pipeline_root_matches_direct_calculation() {
+        let db = Arc::new(StateDB::new());
+        let pipeline = StateRootPipeline::spawn(db.clone());
+
+        for i in 0..50u8 {
+            db.accounts
+                .set_balance(Address([i; 20]), U256::from(i as u64 + 1));
+            db.set_storage(Address([i; 20]), vec![i], vec![i, i]);
+            pipeline.update();
+        }
+        let root = pipeline.finalize().wait().await.unwrap();
+
+        let expected = StateDB::new();
+        for i in 0..50u8 {
+            expected
+                .accounts
+                .set_balance(Address([i; 20]), U256::from(i as u64 + 1));
+            expected.set_storage(Address([i; 20]), vec![i], vec![i, i]);
+        }
+        assert_eq!(root, expected.calculate_state_root());
+        assert_eq!(root, db.calculate_state_root());
+    }
+
+    #[tokio::test]
+    async fn test_finalize_includes_changes_after_update() {
+        let db = Arc::new(StateDB::new());
+        let pipeline = StateRootPipeline::spawn(db.clone());
+
+        db.accounts.set_balance(Address([1; 20]), U256::from(1));
+        pipeline.update();
+        let first = pipeline.finalize().wait().await.unwrap();
+
+        db.accounts.set_balance(Address([1; 20]), U256::from(2));
+        let second = pipeline.finalize().wait().await.unwrap();
+        assert_ne!(first, second);
+        assert_eq!(second, db.calculate_state_root());
+    }
+}
@@ -5,6 +5,7 @@ use crate::state::{AccountManager, Trie};
 use crate::types::{Address, ExecutionError, JobId, ModelId, ModelState, TrainingJob};
 use dashmap::DashMap;
 use citrate_consensus::types::Hash;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{debug, info};
 
@@ -30,6 +31,9 @@ pub struct StateDB {
 
     /// Global state trie
     state_trie: Arc<parking_lot::RwLock<Trie>>,
+
+    /// Storage slots changed since the last block was archived
+    changed_storage: Arc<DashMap<(Address, Vec<u8>), bool>>,
 }
 
 impl StateDB {
@@ -41,6 +45,7 @@ impl StateDB {
             models: Arc::new(DashMap::new()),
             training_jobs: Arc::new(DashMap::new()),
             state_trie: Arc::new(parking_lot::RwLock::new(Trie::new())),
+            changed_storage: Arc::new(DashMap::new()),
         }
     }
 
@@ -56,7 +61,9 @@ impl StateDB {
         self.storage_tries
             .entry(address)
             .or_default()
-            .insert(key, value);
+            .insert(key.clone(), value);
+        self.accounts.mark_trie_dirty(address);
+        self.changed_storage.insert((address, key), true);
     }
 
     /// Delete storage value
@@ -64,6 +71,36 @@ impl StateDB {
         if let Some(mut trie) = self.storage_tries.get_mut(&address) {
             trie.remove(key);
         }
+        self.accounts.mark_trie_dirty(address);
+        self.changed_storage.insert((address, key.to_vec()), true);
+    }
+
+    /// Take the storage slots changed since they were last taken or
+    /// committed
+    pub fn take_changed_storage(&self) -> Vec<(Address, Vec<u8>)> {
+        let keys: Vec<(Address, Vec<u8>)> = self
+            .changed_storage
+            .iter()
+            .map(|e| e.key().clone())
+            .collect();
+        for key in &keys {
+            self.changed_storage.remove(key);
+        }
+        keys
+    }
+
+    /// Every storage slot of every account
+    pub fn all_storage(&self) -> Vec<(Address, Vec<u8>, Vec<u8>)> {
+        self.storage_tries
+            .iter()
+            .flat_map(|e| {
+                let address = *e.key();
+                e.value()
+                    .entries()
+                    .map(|(key, value)| (address, key.to_vec(), value.to_vec()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
     }
 
     /// Get contract code
@@ -148,32 +185,65 @@ impl StateDB {
         Ok(())
     }
 
-    /// Calculate state root
-    pub fn calculate_state_root(&self) -> StateRoot {
+    /// Write the accounts changed since the last update into the state trie,
+    /// returning how many were written.
+    ///
+    /// Safe to call while transactions execute: accounts changed concurrently
+    /// are marked again and picked up by the next update.
+    pub fn update_state_trie(&self) -> usize {
+        let addresses = self.accounts.take_trie_dirty();
+        if addresses.is_empty() {
+            return 0;
+        }
+
         let mut state_trie = self.state_trie.write();
+        for address in &addresses {
+            if !self.accounts.exists(address) {
+                state_trie.remove(&address.0);
+                continue;
+            }
 
-        // Update state trie with account data
-        for address in self.accounts.get_dirty_accounts() {
-            let account = self.accounts.get_account(&address);
+            // Storage root first, so the account is encoded with it
+            if let Some(storage_trie) = self.storage_tries.get(address) {
+                self.accounts.set_storage_root(address, storage_trie.root_hash());
+            }
+            let account = self.accounts.get_account(address);
             let encoded = bincode::serialize(&account).unwrap();
             state_trie.insert(address.0.to_vec(), encoded);
-
-            // Update storage root for account
-            if let Some(storage_trie) = self.storage_tries.get(&address) {
-                let storage_root = storage_trie.root_hash();
-                let mut account = self.accounts.get_account(&address);
-                account.storage_root = storage_root;
-                self.accounts.set_account(address, account);
-            }
         }
 
-        state_trie.root_hash()
+        addresses.len()
+    }
+
+    /// Calculate state root
+    pub fn calculate_state_root(&self) -> StateRoot {
+        self.update_state_trie();
+        self.state_trie.read().root_hash()
+    }
+
+    /// Merkle proof of `address` against the state root, checked with
+    /// [`verify_proof`](crate::state::verify_proof). The proven value is the
+    /// bincode encoding of the account; absent accounts are proven absent.
+    pub fn account_proof(&self, address: &Address) -> Vec<Vec<u8>> {
+        self.update_state_trie();
+        self.state_trie.read().prove(&address.0)
+    }
+
+    /// Merkle proof of a storage slot against the account's storage root.
+    /// Accounts that never held storage have a zero storage root and an
+    /// empty proof.
+    pub fn storage_proof(&self, address: &Address, key: &[u8]) -> Vec<Vec<u8>> {
+        self.storage_tries
+            .get(address)
+            .map(|trie| trie.prove(key))
+            .unwrap_or_default()
     }
 
     /// Commit state changes
     pub fn commit(&self) -> StateRoot {
         let root = self.calculate_state_root();
         self.accounts.clear_dirty();
+        self.changed_storage.clear();
         debug!("State committed with root: {:?}", root);
         root
     }
@@ -213,9 +283,24 @@ impl StateDB {
         // Restore accounts
         self.accounts.restore(snapshot.accounts);
 
-        // Restore storage tries
+        // Restore storage tries, re-hashing accounts whose storage changed
+        let restored: HashMap<Address, Trie> = snapshot.storage_tries.into_iter().collect();
+        for entry in self.storage_tries.iter() {
+            let unchanged = restored
+                .get(entry.key())
+                .is_some_and(|trie| trie.root_hash() == entry.value().root_hash());
+            if !unchanged {
+                self.accounts.mark_trie_dirty(*entry.key());
+            }
+        }
+        for addr in restored.keys() {
+            if !self.storage_tries.contains_key(addr) {
+                self.accounts.mark_trie_dirty(*addr);
+            }
+        }
+
         self.storage_tries.clear();
-        for (addr, trie) in snapshot.storage_tries {
+        for (addr, trie) in restored {
             self.storage_tries.insert(addr, trie);
         }
 
@@ -234,6 +319,17 @@ impl StateDB {
         debug!("State restored from snapshot");
     }
 
+    /// Independent copy of the current state, including contract code,
+    /// for replays that must not touch the live state
+    pub fn fork(&self) -> StateDB {
+        let fork = StateDB::new();
+        fork.restore(self.snapshot());
+        for entry in self.code_storage.iter() {
+            fork.code_storage.insert(*entry.key(), entry.value().clone());
+        }
+        fork
+    }
+
     /// Hash code using Keccak256
     fn hash_code(code: &[u8]) -> Hash {
         use sha3::{Digest, Keccak256};
@@ -319,4 +415,94 @@ mod tests {
         assert_eq!(db.accounts.get_balance(&addr), U256::from(1000));
         assert_eq!(db.get_storage(&addr, b"key"), Some(b"value".to_vec()));
     }
+
+    #[test]
+    fn test_fork_is_independent() {
+        let db = StateDB::new();
+        let addr = Address([1; 20]);
+        db.accounts.set_balance(addr, U256::from(1000));
+        let code_hash = db.set_code(addr, vec![0x60, 0x00]);
+
+        let fork = db.fork();
+        assert_eq!(fork.get_code(&code_hash), Some(vec![0x60, 0x00]));
+
+        fork.accounts.set_balance(addr, U256::from(1));
+        fork.set_storage(addr, b"key".to_vec(), b"value".to_vec());
+        assert_eq!(db.accounts.get_balance(&addr), U256::from(1000));
+        assert_eq!(db.get_storage(&addr, b"key"), None);
+    }
+
+    #[test]
+    fn test_account_and_storage_proofs() {
+        use crate::state::verify_proof;
+        use crate::types::AccountState;
+
+        let db = StateDB::new();
+        let addr = Address([1; 20]);
+        for i in 0u8..20 {
+            db.accounts.set_balance(Address([i + 2; 20]), U256::from(i));
+        }
+        db.accounts.set_balance(addr, U256::from(1000));
+        db.set_storage(addr, vec![7; 32], vec![42; 32]);
+
+        let proof = db.account_proof(&addr);
+        let root = db.calculate_state_root();
+        let encoded = verify_proof(&root, &addr.0, &proof).unwrap().unwrap();
+        let account: AccountState = bincode::deserialize(&encoded).unwrap();
+        assert_eq!(account.balance, U256::from(1000));
+
+        let proof = db.storage_proof(&addr, &[7; 32]);
+        assert_eq!(
+            verify_proof(&account.storage_root, &[7; 32], &proof),
+            Ok(Some(vec![42; 32]))
+        );
+        let proof = db.storage_proof(&addr, &[8; 32]);
+        assert_eq!(verify_proof(&account.storage_root, &[8; 32], &proof), Ok(None));
+
+        let missing = Address([0xff; 20]);
+        let proof = db.account_proof(&missing);
+        assert_eq!(verify_proof(&root, &missing.0, &proof), Ok(None));
+        assert!(db.storage_proof(&missing, &[7; 32]).is_empty());
+    }
+
+    #[test]
+    fn test_incremental_root_matches_fresh_state() {
+        let db = StateDB::new();
+        let a = Address([1; 20]);
+        let b = Address([2; 20]);
+
+        db.accounts.set_balance(a, U256::from(10));
+        db.update_state_trie();
+        db.accounts.set_balance(b, U256::from(20));
+        db.set_storage(a, b"slot".to_vec(), b"value".to_vec());
+        let incremental = db.calculate_state_root();
+
+        let fresh = StateDB::new();
+        fresh.set_storage(a, b"slot".to_vec(), b"value".to_vec());
+        fresh.accounts.set_balance(a, U256::from(10));
+        fresh.accounts.set_balance(b, U256::from(20));
+        assert_eq!(incremental, fresh.calculate_state_root());
+        assert_eq!(db.update_state_trie(), 0);
+
+        // Storage-only changes move the root
+        db.set_storage(a, b"slot".to_vec(), b"other".to_vec());
+        assert_ne!(db.calculate_state_root(), incremental);
+    }
+
+    #[test]
+    fn test_restore_rehashes_reverted_accounts() {
+        let db = StateDB::new();
+        let a = Address([1; 20]);
+        db.accounts.set_balance(a, U256::from(10));
+        let before = db.calculate_state_root();
+
+        let snapshot = db.snapshot();
+        db.accounts.set_balance(a, U256::from(5));
+        db.accounts.set_balance(Address([2; 20]), U256::from(1));
+        db.set_storage(a, b"slot".to_vec(), b"value".to_vec());
+        assert_ne!(db.calculate_state_root(), before);
+
+        db.restore(snapshot);
+        assert_eq!(db.calculate_state_root(), before);
+    }
 }
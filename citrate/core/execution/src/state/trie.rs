@@ -1,10 +1,19 @@
 // citrate/core/execution/src/state/trie.rs
 
 // Merkle Patricia Trie implementation
+//
+// Nodes are RLP encoded as in Ethereum: leaf and extension paths use
+// hex-prefix encoding, and a child whose encoding is 32 bytes or longer is
+// referenced by its Keccak-256 hash rather than embedded. A key is therefore
+// proven by the encodings of the nodes on its path alone.
 use citrate_consensus::types::Hash;
+use rlp::Rlp;
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::OnceLock;
+use thiserror::Error;
 
 /// Merkle Patricia Trie node
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -18,20 +27,48 @@ pub enum TrieNode {
     Branch {
         children: [Box<TrieNode>; 16],
         value: Option<Vec<u8>>,
+        /// Encoding of this subtree, computed on first use. Nodes are rebuilt
+        /// on every change along a key's path, so untouched subtrees keep it.
+        #[serde(skip)]
+        encoded: OnceLock<Vec<u8>>,
     },
     Extension {
         prefix: Vec<u8>,
         node: Box<TrieNode>,
+        #[serde(skip)]
+        encoded: OnceLock<Vec<u8>>,
     },
 }
 
 // Default now derived above with Empty
 
+impl TrieNode {
+    fn branch(children: [Box<TrieNode>; 16], value: Option<Vec<u8>>) -> Self {
+        TrieNode::Branch {
+            children,
+            value,
+            encoded: OnceLock::new(),
+        }
+    }
+
+    fn extension(prefix: Vec<u8>, node: Box<TrieNode>) -> Self {
+        TrieNode::Extension {
+            prefix,
+            node,
+            encoded: OnceLock::new(),
+        }
+    }
+}
+
 /// Merkle Patricia Trie
+///
+/// Subtree encodings are cached in the nodes, so after a change only the
+/// nodes on the modified key's path are re-encoded when computing the root.
 #[derive(Clone)]
 pub struct Trie {
     root: TrieNode,
     cache: HashMap<Vec<u8>, Vec<u8>>,
+    root_hash: OnceLock<Hash>,
 }
 
 impl Trie {
@@ -39,14 +76,24 @@ impl Trie {
         Self {
             root: TrieNode::Empty,
             cache: HashMap::new(),
+            root_hash: OnceLock::new(),
         }
     }
 
+    /// All key-value pairs, in no particular order
+    pub fn entries(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.cache
+            .iter()
+            .map(|(key, value)| (key.as_slice(), value.as_slice()))
+    }
+
     /// Insert a key-value pair
     pub fn insert(&mut self, key: Vec<u8>, value: Vec<u8>) {
         let nibbles = to_nibbles(&key);
-        self.root = Self::insert_node(self.root.clone(), &nibbles, value.clone());
+        let root = std::mem::take(&mut self.root);
+        self.root = Self::insert_node(root, &nibbles, value.clone());
         self.cache.insert(key, value);
+        self.root_hash = OnceLock::new();
     }
 
     fn insert_node(node: TrieNode, key: &[u8], value: Vec<u8>) -> TrieNode {
@@ -75,36 +122,26 @@ impl Trie {
             TrieNode::Branch {
                 mut children,
                 value: branch_value,
+                ..
             } => {
                 if key.is_empty() {
                     // Update branch value
-                    TrieNode::Branch {
-                        children,
-                        value: Some(value),
-                    }
+                    TrieNode::branch(children, Some(value))
                 } else {
                     let index = key[0] as usize;
-                    children[index] = Box::new(Self::insert_node(
-                        *children[index].clone(),
-                        &key[1..],
-                        value,
-                    ));
-                    TrieNode::Branch {
-                        children,
-                        value: branch_value,
-                    }
+                    let child = std::mem::take(&mut *children[index]);
+                    *children[index] = Self::insert_node(child, &key[1..], value);
+                    TrieNode::branch(children, branch_value)
                 }
             }
 
-            TrieNode::Extension { prefix, node } => {
+            TrieNode::Extension { prefix, node, .. } => {
                 let common = common_prefix(&prefix, key);
 
                 if common.len() == prefix.len() {
                     // Entire prefix matches
-                    TrieNode::Extension {
-                        prefix: prefix.clone(),
-                        node: Box::new(Self::insert_node(*node, &key[common.len()..], value)),
-                    }
+                    let node = Box::new(Self::insert_node(*node, &key[common.len()..], value));
+                    TrieNode::extension(prefix, node)
                 } else {
                     // Partial match - split extension
                     Self::split_extension(prefix, *node, key.to_vec(), value, common.len())
@@ -139,7 +176,9 @@ impl Trie {
                 }
             }
 
-            TrieNode::Branch { children, value } => {
+            TrieNode::Branch {
+                children, value, ..
+            } => {
                 if key.is_empty() {
                     value.clone()
                 } else {
@@ -148,7 +187,7 @@ impl Trie {
                 }
             }
 
-            TrieNode::Extension { prefix, node } => {
+            TrieNode::Extension { prefix, node, .. } => {
                 if key.starts_with(prefix) {
                     Self::get_node(node, &key[prefix.len()..])
                 } else {
@@ -161,8 +200,10 @@ impl Trie {
     /// Remove a key
     pub fn remove(&mut self, key: &[u8]) {
         let nibbles = to_nibbles(key);
-        self.root = Self::remove_node(self.root.clone(), &nibbles);
+        let root = std::mem::take(&mut self.root);
+        self.root = Self::remove_node(root, &nibbles);
         self.cache.remove(key);
+        self.root_hash = OnceLock::new();
     }
 
     fn remove_node(node: TrieNode, key: &[u8]) -> TrieNode {
@@ -182,77 +223,117 @@ impl Trie {
             TrieNode::Branch {
                 mut children,
                 value,
+                ..
             } => {
                 if key.is_empty() {
                     // Remove branch value
-                    TrieNode::Branch {
-                        children,
-                        value: None,
-                    }
+                    TrieNode::branch(children, None)
                 } else {
                     let index = key[0] as usize;
-                    children[index] =
-                        Box::new(Self::remove_node(*children[index].clone(), &key[1..]));
+                    let child = std::mem::take(&mut *children[index]);
+                    *children[index] = Self::remove_node(child, &key[1..]);
 
                     // Check if branch can be simplified
                     Self::simplify_branch(children, value)
                 }
             }
 
-            TrieNode::Extension { prefix, node } => {
+            TrieNode::Extension {
+                prefix,
+                node,
+                encoded,
+            } => {
                 if key.starts_with(&prefix) {
                     let new_node = Self::remove_node(*node, &key[prefix.len()..]);
                     if matches!(new_node, TrieNode::Empty) {
                         TrieNode::Empty
                     } else {
-                        TrieNode::Extension {
-                            prefix,
-                            node: Box::new(new_node),
-                        }
+                        TrieNode::extension(prefix, Box::new(new_node))
                     }
                 } else {
-                    TrieNode::Extension { prefix, node }
+                    // Key not present - subtree unchanged, keep its encoding
+                    TrieNode::Extension {
+                        prefix,
+                        node,
+                        encoded,
+                    }
                 }
             }
         }
     }
 
     /// Calculate the root hash
+    ///
+    /// Only subtrees changed since the previous call are re-encoded; the
+    /// result is cached until the next insert or remove.
     pub fn root_hash(&self) -> Hash {
-        let encoded = self.encode_node(&self.root);
-        let mut hasher = Keccak256::new();
-        hasher.update(&encoded);
-        Hash::new(hasher.finalize().into())
+        *self
+            .root_hash
+            .get_or_init(|| keccak(&Self::encode_node(&self.root)))
     }
 
-    #[allow(clippy::only_used_in_recursion)]
-    fn encode_node(&self, node: &TrieNode) -> Vec<u8> {
+    fn encode_node(node: &TrieNode) -> Cow<'_, [u8]> {
         match node {
-            TrieNode::Empty => vec![],
+            TrieNode::Empty => Cow::Borrowed(&[]),
 
             TrieNode::Leaf { key, value } => {
-                let items: [&[u8]; 2] = [key.as_slice(), value.as_slice()];
-                rlp::encode_list::<&[u8], _>(&items).to_vec()
+                let path = compact_path(key, true);
+                let items: [&[u8]; 2] = [path.as_slice(), value.as_slice()];
+                Cow::Owned(rlp::encode_list::<&[u8], _>(&items).to_vec())
             }
 
-            TrieNode::Branch { children, value } => {
-                let mut items: Vec<Vec<u8>> = Vec::new();
-                for child in children.iter() {
-                    items.push(self.encode_node(child));
-                }
-                if let Some(v) = value {
-                    items.push(v.clone());
-                } else {
-                    items.push(vec![]);
-                }
-                let items_refs: Vec<&[u8]> = items.iter().map(|v| v.as_slice()).collect();
+            TrieNode::Branch {
+                children,
+                value,
+                encoded,
+            } => Cow::Borrowed(encoded.get_or_init(|| {
+                let mut items: Vec<Cow<'_, [u8]>> = children
+                    .iter()
+                    .map(|child| node_ref(Self::encode_node(child)))
+                    .collect();
+                items.push(Cow::Borrowed(value.as_deref().unwrap_or(&[])));
+                let items_refs: Vec<&[u8]> = items.iter().map(|v| v.as_ref()).collect();
                 rlp::encode_list::<&[u8], _>(&items_refs).to_vec()
-            }
+            })),
 
-            TrieNode::Extension { prefix, node } => {
-                let node_encoded = self.encode_node(node);
-                let items: [&[u8]; 2] = [prefix.as_slice(), node_encoded.as_slice()];
+            TrieNode::Extension {
+                prefix,
+                node,
+                encoded,
+            } => Cow::Borrowed(encoded.get_or_init(|| {
+                let path = compact_path(prefix, false);
+                let node_ref = node_ref(Self::encode_node(node));
+                let items: [&[u8]; 2] = [path.as_slice(), node_ref.as_ref()];
                 rlp::encode_list::<&[u8], _>(&items).to_vec()
+            })),
+        }
+    }
+
+    /// Encodings of the nodes on the path to `key`, root first, to be
+    /// checked with [`verify_proof`]. Nodes embedded in their parent are not
+    /// repeated. For a missing key the path ends where the key would be,
+    /// proving its absence.
+    pub fn prove(&self, key: &[u8]) -> Vec<Vec<u8>> {
+        let nibbles = to_nibbles(key);
+        let mut path = nibbles.as_slice();
+        let mut node = &self.root;
+        let mut proof = vec![Self::encode_node(node).into_owned()];
+        loop {
+            node = match node {
+                TrieNode::Branch { children, .. } if !path.is_empty() => {
+                    let child = &children[path[0] as usize];
+                    path = &path[1..];
+                    child
+                }
+                TrieNode::Extension { prefix, node, .. } if path.starts_with(prefix) => {
+                    path = &path[prefix.len()..];
+                    node
+                }
+                _ => return proof,
+            };
+            let encoded = Self::encode_node(node);
+            if encoded.len() >= 32 {
+                proof.push(encoded.into_owned());
             }
         }
     }
@@ -269,10 +350,7 @@ impl Trie {
                 key: key2[1..].to_vec(),
                 value: value2,
             });
-            TrieNode::Branch {
-                children,
-                value: Some(value1),
-            }
+            TrieNode::branch(children, Some(value1))
         } else if key2.is_empty() {
             // key2 goes to branch value
             let index = key1[0] as usize;
@@ -280,10 +358,7 @@ impl Trie {
                 key: key1[1..].to_vec(),
                 value: value1,
             });
-            TrieNode::Branch {
-                children,
-                value: Some(value2),
-            }
+            TrieNode::branch(children, Some(value2))
         } else {
             // Both go to children
             let index1 = key1[0] as usize;
@@ -307,10 +382,7 @@ impl Trie {
                 });
             }
 
-            TrieNode::Branch {
-                children,
-                value: None,
-            }
+            TrieNode::branch(children, None)
         }
     }
 
@@ -332,10 +404,10 @@ impl Trie {
             if remaining_prefix.len() == 1 {
                 children[index] = Box::new(node);
             } else {
-                children[index] = Box::new(TrieNode::Extension {
-                    prefix: remaining_prefix[1..].to_vec(),
-                    node: Box::new(node),
-                });
+                children[index] = Box::new(TrieNode::extension(
+                    remaining_prefix[1..].to_vec(),
+                    Box::new(node),
+                ));
             }
         }
 
@@ -350,47 +422,40 @@ impl Trie {
             None
         };
 
-        let branch = TrieNode::Branch {
-            children,
-            value: branch_value,
-        };
+        let branch = TrieNode::branch(children, branch_value);
 
         if common.is_empty() {
             branch
         } else {
-            TrieNode::Extension {
-                prefix: common.to_vec(),
-                node: Box::new(branch),
-            }
+            TrieNode::extension(common.to_vec(), Box::new(branch))
         }
     }
 
-    fn simplify_branch(children: [Box<TrieNode>; 16], value: Option<Vec<u8>>) -> TrieNode {
-        let non_empty: Vec<_> = children
+    fn simplify_branch(mut children: [Box<TrieNode>; 16], value: Option<Vec<u8>>) -> TrieNode {
+        let non_empty: Vec<usize> = children
             .iter()
             .enumerate()
             .filter(|(_, child)| !matches!(child.as_ref(), TrieNode::Empty))
+            .map(|(index, _)| index)
             .collect();
 
         if non_empty.len() == 1 && value.is_none() {
             // Only one child - convert to extension or leaf
-            let (index, child) = non_empty[0];
-            match child.as_ref() {
+            let index = non_empty[0];
+            let child = std::mem::take(&mut children[index]);
+            match *child {
                 TrieNode::Leaf { key, value } => {
                     let mut new_key = vec![index as u8];
                     new_key.extend(key);
                     TrieNode::Leaf {
                         key: new_key,
-                        value: value.clone(),
+                        value,
                     }
                 }
-                _ => TrieNode::Extension {
-                    prefix: vec![index as u8],
-                    node: child.clone(),
-                },
+                child => TrieNode::extension(vec![index as u8], Box::new(child)),
             }
         } else {
-            TrieNode::Branch { children, value }
+            TrieNode::branch(children, value)
         }
     }
 }
@@ -411,6 +476,130 @@ fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
     nibbles
 }
 
+fn keccak(data: &[u8]) -> Hash {
+    Hash::new(Keccak256::digest(data).into())
+}
+
+/// How a parent refers to a child: encodings shorter than a hash are
+/// embedded, longer ones replaced by their hash
+fn node_ref(encoded: Cow<'_, [u8]>) -> Cow<'_, [u8]> {
+    if encoded.len() < 32 {
+        encoded
+    } else {
+        Cow::Owned(keccak(&encoded).as_bytes().to_vec())
+    }
+}
+
+/// Hex-prefix encoding of a nibble path, flagging leaves and odd lengths
+fn compact_path(nibbles: &[u8], leaf: bool) -> Vec<u8> {
+    let flag = if leaf { 2 } else { 0 } + (nibbles.len() % 2) as u8;
+    let mut out = Vec::with_capacity(nibbles.len() / 2 + 1);
+    let rest = if nibbles.len() % 2 == 1 {
+        out.push((flag << 4) | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        out.push(flag << 4);
+        nibbles
+    };
+    out.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+    out
+}
+
+/// Nibble path and leaf flag of a hex-prefix encoded path
+fn decode_compact_path(bytes: &[u8]) -> Option<(Vec<u8>, bool)> {
+    let (&first, rest) = bytes.split_first()?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return None;
+    }
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    if flag % 2 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    nibbles.extend(to_nibbles(rest));
+    Some((nibbles, flag >= 2))
+}
+
+/// Why a trie proof was rejected
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ProofError {
+    #[error("Proof ends before reaching the key")]
+    Incomplete,
+
+    #[error("Proof node does not match the hash its parent commits to")]
+    HashMismatch,
+
+    #[error("Malformed proof node")]
+    Malformed,
+}
+
+impl From<rlp::DecoderError> for ProofError {
+    fn from(_: rlp::DecoderError) -> Self {
+        ProofError::Malformed
+    }
+}
+
+/// Check a [`Trie::prove`] proof for `key` against `root`, returning the
+/// proven value, or `None` when the proof shows the key is absent
+pub fn verify_proof(
+    root: &Hash,
+    key: &[u8],
+    proof: &[Vec<u8>],
+) -> Result<Option<Vec<u8>>, ProofError> {
+    let nibbles = to_nibbles(key);
+    let mut path = nibbles.as_slice();
+    let mut nodes = proof.iter();
+    let mut node = nodes.next().ok_or(ProofError::Incomplete)?.clone();
+    if keccak(&node) != *root {
+        return Err(ProofError::HashMismatch);
+    }
+
+    loop {
+        if node.is_empty() {
+            return Ok(None);
+        }
+        let rlp = Rlp::new(&node);
+        let child: Vec<u8> = match rlp.item_count()? {
+            17 => {
+                if path.is_empty() {
+                    let value: Vec<u8> = rlp.val_at(16)?;
+                    return Ok((!value.is_empty()).then_some(value));
+                }
+                let child = rlp.val_at(path[0] as usize)?;
+                path = &path[1..];
+                child
+            }
+            2 => {
+                let (prefix, leaf) =
+                    decode_compact_path(&rlp.val_at::<Vec<u8>>(0)?).ok_or(ProofError::Malformed)?;
+                if leaf {
+                    return if prefix == path {
+                        Ok(Some(rlp.val_at(1)?))
+                    } else {
+                        Ok(None)
+                    };
+                }
+                if !path.starts_with(&prefix) {
+                    return Ok(None);
+                }
+                path = &path[prefix.len()..];
+                rlp.val_at(1)?
+            }
+            _ => return Err(ProofError::Malformed),
+        };
+
+        node = if child.len() == 32 {
+            let next = nodes.next().ok_or(ProofError::Incomplete)?;
+            if keccak(next).as_bytes() != child.as_slice() {
+                return Err(ProofError::HashMismatch);
+            }
+            next.clone()
+        } else {
+            child
+        };
+    }
+}
+
 /// Find common prefix length
 fn common_prefix(a: &[u8], b: &[u8]) -> Vec<u8> {
     a.iter()
@@ -472,6 +661,150 @@ mod tests {
         assert_eq!(trie.get(b"key2"), Some(b"value2".to_vec()));
     }
 
+    /// Uncached encoding, as computed before subtree encodings were cached
+    fn reference_encode(node: &TrieNode) -> Vec<u8> {
+        let reference = |node: &TrieNode| {
+            let encoded = reference_encode(node);
+            if encoded.len() < 32 {
+                encoded
+            } else {
+                Keccak256::digest(&encoded).to_vec()
+            }
+        };
+        match node {
+            TrieNode::Empty => vec![],
+            TrieNode::Leaf { key, value } => {
+                let path = compact_path(key, true);
+                let items: [&[u8]; 2] = [path.as_slice(), value.as_slice()];
+                rlp::encode_list::<&[u8], _>(&items).to_vec()
+            }
+            TrieNode::Branch {
+                children, value, ..
+            } => {
+                let mut items: Vec<Vec<u8>> = children.iter().map(|c| reference(c)).collect();
+                items.push(value.clone().unwrap_or_default());
+                let items_refs: Vec<&[u8]> = items.iter().map(|v| v.as_slice()).collect();
+                rlp::encode_list::<&[u8], _>(&items_refs).to_vec()
+            }
+            TrieNode::Extension { prefix, node, .. } => {
+                let path = compact_path(prefix, false);
+                let node_ref = reference(node);
+                let items: [&[u8]; 2] = [path.as_slice(), node_ref.as_slice()];
+                rlp::encode_list::<&[u8], _>(&items).to_vec()
+            }
+        }
+    }
+
+    fn reference_root(trie: &Trie) -> Hash {
+        Hash::new(Keccak256::digest(reference_encode(&trie.root)).into())
+    }
+
+    #[test]
+    fn test_incremental_root_matches_full_encoding() {
+        let mut trie = Trie::new();
+        for i in 0u32..200 {
+            trie.insert(i.to_be_bytes().to_vec(), vec![i as u8; 40]);
+            if i % 7 == 0 {
+                // Interleave root computations so cached encodings are reused
+                assert_eq!(trie.root_hash(), reference_root(&trie));
+            }
+        }
+        for i in (0u32..200).step_by(3) {
+            trie.remove(&i.to_be_bytes());
+            trie.insert((i + 1).to_be_bytes().to_vec(), b"updated".to_vec());
+            assert_eq!(trie.root_hash(), reference_root(&trie));
+        }
+
+        // Clones share cached encodings but diverge independently
+        let mut fork = trie.clone();
+        fork.insert(b"fork".to_vec(), b"only".to_vec());
+        assert_eq!(fork.root_hash(), reference_root(&fork));
+        assert_eq!(trie.root_hash(), reference_root(&trie));
+        assert_ne!(trie.root_hash(), fork.root_hash());
+    }
+
+    #[test]
+    fn test_compact_path_roundtrip() {
+        for (nibbles, leaf) in [
+            (vec![], true),
+            (vec![1], false),
+            (vec![1, 2], true),
+            (vec![0, 15, 1], false),
+        ] {
+            let encoded = compact_path(&nibbles, leaf);
+            assert_eq!(decode_compact_path(&encoded), Some((nibbles, leaf)));
+        }
+        // Ethereum's hex-prefix examples
+        assert_eq!(
+            compact_path(&[1, 2, 3, 4, 5], false),
+            vec![0x11, 0x23, 0x45]
+        );
+        assert_eq!(
+            compact_path(&[0, 15, 1, 12, 11, 8], true),
+            vec![0x20, 0x0f, 0x1c, 0xb8]
+        );
+    }
+
+    #[test]
+    fn test_proofs_verify_against_root() {
+        let mut trie = Trie::new();
+        for i in 0u32..300 {
+            let key = Keccak256::digest(i.to_be_bytes()).to_vec();
+            trie.insert(key, vec![i as u8; 1 + (i % 50) as usize]);
+        }
+        // Short keys and values give nodes small enough to be embedded
+        trie.insert(vec![0xab], vec![1]);
+        trie.insert(vec![0xac], vec![2]);
+        let root = trie.root_hash();
+
+        for i in (0u32..300).step_by(7) {
+            let key = Keccak256::digest(i.to_be_bytes()).to_vec();
+            let proof = trie.prove(&key);
+            assert!(proof.len() > 1);
+            assert_eq!(
+                verify_proof(&root, &key, &proof),
+                Ok(Some(vec![i as u8; 1 + (i % 50) as usize]))
+            );
+        }
+        assert_eq!(
+            verify_proof(&root, &[0xac], &trie.prove(&[0xac])),
+            Ok(Some(vec![2]))
+        );
+
+        // Absent keys are proven absent
+        let missing = Keccak256::digest(b"missing").to_vec();
+        assert_eq!(
+            verify_proof(&root, &missing, &trie.prove(&missing)),
+            Ok(None)
+        );
+
+        // Tampered or truncated proofs are rejected
+        let key = Keccak256::digest(5u32.to_be_bytes()).to_vec();
+        let mut proof = trie.prove(&key);
+        let last = proof.last_mut().unwrap();
+        *last.last_mut().unwrap() ^= 1;
+        assert_eq!(
+            verify_proof(&root, &key, &proof),
+            Err(ProofError::HashMismatch)
+        );
+        proof.pop();
+        assert_eq!(
+            verify_proof(&root, &key, &proof),
+            Err(ProofError::Incomplete)
+        );
+        assert_eq!(
+            verify_proof(&Hash::default(), &key, &trie.prove(&key)),
+            Err(ProofError::HashMismatch)
+        );
+
+        // An empty trie proves every key absent
+        let empty = Trie::new();
+        assert_eq!(
+            verify_proof(&empty.root_hash(), &key, &empty.prove(&key)),
+            Ok(None)
+        );
+    }
+
     #[test]
     fn test_trie_root_hash() {
         let mut trie1 = Trie::new();
@@ -0,0 +1,729 @@
+// citrate/core/execution/src/tracer.rs
+
+// Call-frame and opcode tracing for transaction replay
+use crate::types::Address;
+use citrate_consensus::types::Hash;
+use primitive_types::U256;
+use revm::{
+    interpreter::{
+        CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, InstructionResult,
+        Interpreter, OpCode,
+    },
+    primitives::{Address as RevmAddress, Bytes, CreateScheme, U256 as RevmU256},
+    Database, EvmContext, Inspector,
+};
+use serde::{Deserialize, Serialize};
+
+/// Default cap on recorded opcode steps per transaction
+pub const DEFAULT_MAX_STEPS: usize = 100_000;
+
+/// Selector of `Error(string)` revert payloads
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Selector of `Panic(uint256)` revert payloads
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// What to record while tracing
+#[derive(Debug, Clone)]
+pub struct TraceOptions {
+    /// Record a step for every executed opcode
+    pub opcodes: bool,
+    /// Include the stack in each opcode step
+    pub stack: bool,
+    /// Stop recording opcode steps after this many
+    pub max_steps: usize,
+}
+
+impl Default for TraceOptions {
+    fn default() -> Self {
+        Self {
+            opcodes: false,
+            stack: false,
+            max_steps: DEFAULT_MAX_STEPS,
+        }
+    }
+}
+
+/// Kind of call frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CallKind {
+    Call,
+    StaticCall,
+    DelegateCall,
+    CallCode,
+    Create,
+    Create2,
+}
+
+/// A single call frame and the frames it spawned
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CallFrame {
+    pub call_type: CallKind,
+    pub from: Address,
+    /// Callee, or the created contract for CREATE frames
+    pub to: Option<Address>,
+    pub value: U256,
+    pub gas: u64,
+    pub gas_used: u64,
+    pub input: Vec<u8>,
+    pub output: Vec<u8>,
+    pub error: Option<String>,
+    /// Decoded `Error(string)` / `Panic(uint256)` payload of a revert
+    pub revert_reason: Option<String>,
+    pub calls: Vec<CallFrame>,
+}
+
+impl CallFrame {
+    pub fn new(call_type: CallKind, from: Address, to: Option<Address>, value: U256) -> Self {
+        Self {
+            call_type,
+            from,
+            to,
+            value,
+            gas: 0,
+            gas_used: 0,
+            input: Vec::new(),
+            output: Vec::new(),
+            error: None,
+            revert_reason: None,
+            calls: Vec::new(),
+        }
+    }
+}
+
+/// One executed opcode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructLog {
+    pub pc: usize,
+    pub op: String,
+    /// Gas remaining before the opcode ran
+    pub gas: u64,
+    pub gas_cost: u64,
+    pub depth: usize,
+    pub stack: Option<Vec<U256>>,
+}
+
+/// Result of re-executing a transaction with tracing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionTrace {
+    pub tx_hash: Hash,
+    pub status: bool,
+    pub gas_used: u64,
+    pub output: Vec<u8>,
+    /// Top-level frame; always present, nested frames come from the EVM
+    pub call: CallFrame,
+    pub struct_logs: Vec<StructLog>,
+}
+
+/// How an internal transaction moved value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InternalTxKind {
+    Call,
+    Create,
+    Create2,
+    SelfDestruct,
+}
+
+/// Value moved by contract code rather than by the transaction itself
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InternalTransaction {
+    pub kind: InternalTxKind,
+    pub from: Address,
+    /// Callee, created contract, or selfdestruct beneficiary
+    pub to: Address,
+    pub value: U256,
+    /// Call depth of the transfer; the transaction's own frame is 0
+    pub depth: usize,
+}
+
+/// Records value transfers made by nested calls, creates and selfdestructs.
+/// Transfers inside frames that revert are discarded with the frame.
+#[derive(Debug, Default)]
+pub struct TransferTracer {
+    transfers: Vec<InternalTransaction>,
+    /// Per open frame: where its transfers start, and its own CREATE transfer
+    frames: Vec<(usize, Option<usize>)>,
+}
+
+impl TransferTracer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn into_transfers(self) -> Vec<InternalTransaction> {
+        self.transfers
+    }
+
+    fn enter(&mut self, transfer: Option<InternalTransaction>) -> Option<usize> {
+        let start = self.transfers.len();
+        let depth = self.frames.len();
+        // The outermost frame is the transaction itself, already visible
+        let own = transfer.filter(|_| depth > 0).map(|mut t| {
+            t.depth = depth;
+            self.transfers.push(t);
+            start
+        });
+        self.frames.push((start, own));
+        own
+    }
+
+    fn exit(&mut self, success: bool, created: Option<RevmAddress>) {
+        let Some((start, own)) = self.frames.pop() else {
+            return;
+        };
+        if !success {
+            self.transfers.truncate(start);
+            return;
+        }
+        if let (Some(index), Some(address)) = (own, created) {
+            self.transfers[index].to = Address(address.0 .0);
+        }
+    }
+
+    fn record_selfdestruct(&mut self, from: Address, to: Address, value: U256) {
+        if value.is_zero() {
+            return;
+        }
+        self.transfers.push(InternalTransaction {
+            kind: InternalTxKind::SelfDestruct,
+            from,
+            to,
+            value,
+            depth: self.frames.len().saturating_sub(1),
+        });
+    }
+}
+
+impl<DB: Database> Inspector<DB> for TransferTracer {
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        // CALLCODE "transfers" to the calling contract itself
+        let transfer =
+            (inputs.transfers_value() && inputs.caller != inputs.target_address).then(|| {
+                InternalTransaction {
+                    kind: InternalTxKind::Call,
+                    from: Address(inputs.caller.0 .0),
+                    to: Address(inputs.target_address.0 .0),
+                    value: to_u256(&inputs.value.get()),
+                    depth: 0,
+                }
+            });
+        self.enter(transfer);
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.exit(outcome.result.result.is_ok(), None);
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        let kind = match inputs.scheme {
+            CreateScheme::Create => InternalTxKind::Create,
+            CreateScheme::Create2 { .. } => InternalTxKind::Create2,
+        };
+        let transfer = (inputs.value > RevmU256::ZERO).then(|| InternalTransaction {
+            kind,
+            from: Address(inputs.caller.0 .0),
+            // Filled in once the address is known
+            to: Address([0u8; 20]),
+            value: to_u256(&inputs.value),
+            depth: 0,
+        });
+        self.enter(transfer);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.exit(outcome.result.result.is_ok(), outcome.address);
+        outcome
+    }
+
+    fn selfdestruct(&mut self, contract: RevmAddress, target: RevmAddress, value: RevmU256) {
+        self.record_selfdestruct(
+            Address(contract.0 .0),
+            Address(target.0 .0),
+            to_u256(&value),
+        );
+    }
+}
+
+/// Inspector run for every revm execution: always records internal
+/// transfers, and call frames and opcodes when a trace was requested
+pub struct ExecutionInspector<'a> {
+    pub transfers: &'a mut TransferTracer,
+    pub tracer: Option<&'a mut CallTracer>,
+}
+
+impl<DB: Database> Inspector<DB> for ExecutionInspector<'_> {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        if let Some(tracer) = self.tracer.as_deref_mut() {
+            Inspector::<DB>::step(tracer, interp, context);
+        }
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        if let Some(tracer) = self.tracer.as_deref_mut() {
+            Inspector::<DB>::step_end(tracer, interp, context);
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        Inspector::<DB>::call(self.transfers, context, inputs);
+        if let Some(tracer) = self.tracer.as_deref_mut() {
+            Inspector::<DB>::call(tracer, context, inputs);
+        }
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        let outcome = Inspector::<DB>::call_end(self.transfers, context, inputs, outcome);
+        match self.tracer.as_deref_mut() {
+            Some(tracer) => Inspector::<DB>::call_end(tracer, context, inputs, outcome),
+            None => outcome,
+        }
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        Inspector::<DB>::create(self.transfers, context, inputs);
+        if let Some(tracer) = self.tracer.as_deref_mut() {
+            Inspector::<DB>::create(tracer, context, inputs);
+        }
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        let outcome = Inspector::<DB>::create_end(self.transfers, context, inputs, outcome);
+        match self.tracer.as_deref_mut() {
+            Some(tracer) => Inspector::<DB>::create_end(tracer, context, inputs, outcome),
+            None => outcome,
+        }
+    }
+
+    fn selfdestruct(&mut self, contract: RevmAddress, target: RevmAddress, value: RevmU256) {
+        Inspector::<DB>::selfdestruct(self.transfers, contract, target, value);
+    }
+}
+
+/// Collects call frames from revm and opcode steps from either VM
+#[derive(Debug, Default)]
+pub struct CallTracer {
+    options: TraceOptions,
+    frames: Vec<CallFrame>,
+    root: Option<CallFrame>,
+    struct_logs: Vec<StructLog>,
+    /// Index of the step awaiting its gas cost
+    open_step: Option<usize>,
+    error: Option<String>,
+}
+
+impl CallTracer {
+    pub fn new(options: TraceOptions) -> Self {
+        Self {
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// Record an opcode about to run; returns a handle for `end_step`
+    pub fn start_step(
+        &mut self,
+        pc: usize,
+        op: impl Into<String>,
+        gas: u64,
+        depth: usize,
+        stack: &[U256],
+    ) -> Option<usize> {
+        if !self.options.opcodes || self.struct_logs.len() >= self.options.max_steps {
+            return None;
+        }
+        self.struct_logs.push(StructLog {
+            pc,
+            op: op.into(),
+            gas,
+            gas_cost: 0,
+            depth,
+            stack: self.options.stack.then(|| stack.to_vec()),
+        });
+        Some(self.struct_logs.len() - 1)
+    }
+
+    /// Fill in the gas cost of a step once it has run
+    pub fn end_step(&mut self, step: Option<usize>, gas_remaining: u64) {
+        if let Some(log) = step.and_then(|i| self.struct_logs.get_mut(i)) {
+            log.gas_cost = log.gas.saturating_sub(gas_remaining);
+        }
+    }
+
+    /// Record the error that failed the transaction outside the EVM
+    pub fn set_error(&mut self, error: impl Into<String>) {
+        self.error = Some(error.into());
+    }
+
+    /// Build the trace, using `fallback` as the top-level frame when the
+    /// transaction never entered the EVM
+    pub fn into_trace(
+        self,
+        tx_hash: Hash,
+        status: bool,
+        gas_used: u64,
+        output: Vec<u8>,
+        fallback: CallFrame,
+    ) -> TransactionTrace {
+        let mut call = self.root.unwrap_or(fallback);
+        if call.gas_used == 0 {
+            call.gas_used = gas_used;
+        }
+        if call.output.is_empty() {
+            call.output = output.clone();
+        }
+        if !status && call.error.is_none() {
+            call.error = Some(self.error.unwrap_or_else(|| "execution failed".to_string()));
+        }
+        TransactionTrace {
+            tx_hash,
+            status,
+            gas_used,
+            output,
+            call,
+            struct_logs: self.struct_logs,
+        }
+    }
+
+    fn enter(&mut self, frame: CallFrame) {
+        self.frames.push(frame);
+    }
+
+    fn exit(
+        &mut self,
+        result: InstructionResult,
+        output: &Bytes,
+        gas_used: u64,
+        created: Option<RevmAddress>,
+    ) {
+        let Some(mut frame) = self.frames.pop() else {
+            return;
+        };
+        frame.gas_used = gas_used;
+        frame.output = output.to_vec();
+        if let Some(address) = created {
+            frame.to = Some(Address(address.0 .0));
+        }
+        if result.is_revert() {
+            frame.error = Some("execution reverted".to_string());
+            frame.revert_reason = decode_revert_reason(output);
+        } else if result.is_error() {
+            frame.error = Some(format!("{:?}", result));
+        }
+        match self.frames.last_mut() {
+            Some(parent) => parent.calls.push(frame),
+            None => self.root = Some(frame),
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for CallTracer {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        if !self.options.opcodes {
+            return;
+        }
+        let stack: Vec<U256> = if self.options.stack {
+            interp.stack.data().iter().map(to_u256).collect()
+        } else {
+            Vec::new()
+        };
+        self.open_step = self.start_step(
+            interp.program_counter(),
+            OpCode::name_by_op(interp.current_opcode()),
+            interp.gas.remaining(),
+            context.journaled_state.depth() as usize,
+            &stack,
+        );
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        let step = self.open_step.take();
+        self.end_step(step, interp.gas.remaining());
+    }
+
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        let call_type = match inputs.scheme {
+            CallScheme::Call => CallKind::Call,
+            CallScheme::StaticCall => CallKind::StaticCall,
+            CallScheme::DelegateCall => CallKind::DelegateCall,
+            CallScheme::CallCode => CallKind::CallCode,
+        };
+        let mut frame = CallFrame::new(
+            call_type,
+            Address(inputs.caller.0 .0),
+            Some(Address(inputs.target_address.0 .0)),
+            to_u256(&inputs.value.get()),
+        );
+        frame.gas = inputs.gas_limit;
+        frame.input = inputs.input.to_vec();
+        self.enter(frame);
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.exit(
+            outcome.result.result,
+            &outcome.result.output,
+            outcome.result.gas.spent(),
+            None,
+        );
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        let call_type = match inputs.scheme {
+            CreateScheme::Create => CallKind::Create,
+            CreateScheme::Create2 { .. } => CallKind::Create2,
+        };
+        let mut frame = CallFrame::new(
+            call_type,
+            Address(inputs.caller.0 .0),
+            None,
+            to_u256(&inputs.value),
+        );
+        frame.gas = inputs.gas_limit;
+        frame.input = inputs.init_code.to_vec();
+        self.enter(frame);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.exit(
+            outcome.result.result,
+            &outcome.result.output,
+            outcome.result.gas.spent(),
+            outcome.address,
+        );
+        outcome
+    }
+}
+
+/// Decode a Solidity `Error(string)` or `Panic(uint256)` revert payload
+pub fn decode_revert_reason(output: &[u8]) -> Option<String> {
+    if output.len() < 4 {
+        return None;
+    }
+    let (selector, data) = output.split_at(4);
+    if selector == PANIC_SELECTOR && data.len() >= 32 {
+        return Some(format!("panic: 0x{:x}", U256::from_big_endian(&data[..32])));
+    }
+    if selector != ERROR_SELECTOR || data.len() < 64 {
+        return None;
+    }
+    let offset = U256::from_big_endian(&data[..32]);
+    if offset > U256::from(data.len()) {
+        return None;
+    }
+    let offset = offset.as_usize();
+    let len_end = offset.checked_add(32)?;
+    let len = U256::from_big_endian(data.get(offset..len_end)?);
+    if len > U256::from(data.len()) {
+        return None;
+    }
+    let bytes = data.get(len_end..len_end.checked_add(len.as_usize())?)?;
+    Some(String::from_utf8_lossy(bytes).into_owned())
+}
+
+fn to_u256(value: &RevmU256) -> U256 {
+    U256(value.into_limbs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_payload(message: &str) -> Vec<u8> {
+        let mut out = ERROR_SELECTOR.to_vec();
+        let mut word = [0u8; 32];
+        U256::from(32).to_big_endian(&mut word);
+        out.extend_from_slice(&word);
+        U256::from(message.len()).to_big_endian(&mut word);
+        out.extend_from_slice(&word);
+        let mut data = message.as_bytes().to_vec();
+        data.resize(message.len().div_ceil(32) * 32, 0);
+        out.extend_from_slice(&data);
+        out
+    }
+
+    #[test]
+    fn test_decode_revert_reason() {
+        assert_eq!(
+            decode_revert_reason(&error_payload("insufficient allowance")).as_deref(),
+            Some("insufficient allowance")
+        );
+
+        let mut panic = PANIC_SELECTOR.to_vec();
+        let mut word = [0u8; 32];
+        U256::from(0x11).to_big_endian(&mut word);
+        panic.extend_from_slice(&word);
+        assert_eq!(decode_revert_reason(&panic).as_deref(), Some("panic: 0x11"));
+
+        assert_eq!(decode_revert_reason(&[0xde, 0xad]), None);
+        assert_eq!(decode_revert_reason(&ERROR_SELECTOR), None);
+    }
+
+    #[test]
+    fn test_frames_nest_under_parent() {
+        let mut tracer = CallTracer::new(TraceOptions::default());
+        let from = Address([1u8; 20]);
+        tracer.enter(CallFrame::new(
+            CallKind::Call,
+            from,
+            Some(Address([2u8; 20])),
+            U256::zero(),
+        ));
+        tracer.enter(CallFrame::new(
+            CallKind::StaticCall,
+            Address([2u8; 20]),
+            Some(Address([3u8; 20])),
+            U256::zero(),
+        ));
+        tracer.exit(
+            InstructionResult::Revert,
+            &Bytes::from(error_payload("nope")),
+            700,
+            None,
+        );
+        tracer.exit(InstructionResult::Return, &Bytes::new(), 21_000, None);
+
+        let fallback = CallFrame::new(CallKind::Call, from, None, U256::zero());
+        let trace = tracer.into_trace(Hash::default(), true, 21_000, vec![], fallback);
+        assert_eq!(trace.call.to, Some(Address([2u8; 20])));
+        assert_eq!(trace.call.error, None);
+        assert_eq!(trace.call.calls.len(), 1);
+        let inner = &trace.call.calls[0];
+        assert_eq!(inner.call_type, CallKind::StaticCall);
+        assert_eq!(inner.gas_used, 700);
+        assert_eq!(inner.revert_reason.as_deref(), Some("nope"));
+    }
+
+    #[test]
+    fn test_fallback_frame_carries_error() {
+        let mut tracer = CallTracer::new(TraceOptions::default());
+        tracer.set_error("Out of gas");
+        let fallback = CallFrame::new(CallKind::Call, Address([1u8; 20]), None, U256::zero());
+        let trace = tracer.into_trace(Hash::default(), false, 50_000, vec![], fallback);
+        assert_eq!(trace.call.gas_used, 50_000);
+        assert_eq!(trace.call.error.as_deref(), Some("Out of gas"));
+    }
+
+    #[test]
+    fn test_transfers_in_reverted_frames_are_dropped() {
+        let transfer = |from: u8, to: u8, value: u64| InternalTransaction {
+            kind: InternalTxKind::Call,
+            from: Address([from; 20]),
+            to: Address([to; 20]),
+            value: U256::from(value),
+            depth: 0,
+        };
+        let mut tracer = TransferTracer::new();
+
+        // Top-level value is part of the transaction, not an internal transfer
+        tracer.enter(Some(transfer(1, 2, 100)));
+        // 2 -> 3 succeeds
+        tracer.enter(Some(transfer(2, 3, 10)));
+        tracer.exit(true, None);
+        // 2 -> 4 reverts, taking its nested 4 -> 5 and selfdestruct with it
+        tracer.enter(Some(transfer(2, 4, 20)));
+        tracer.enter(Some(transfer(4, 5, 5)));
+        tracer.exit(true, None);
+        tracer.record_selfdestruct(Address([4; 20]), Address([6; 20]), U256::from(1));
+        tracer.exit(false, None);
+        // 2 creates a funded contract
+        tracer.enter(Some(InternalTransaction {
+            kind: InternalTxKind::Create2,
+            ..transfer(2, 0, 7)
+        }));
+        tracer.exit(true, Some(RevmAddress::from([9u8; 20])));
+        tracer.exit(true, None);
+
+        let transfers = tracer.into_transfers();
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(
+            transfers[0],
+            InternalTransaction {
+                depth: 1,
+                ..transfer(2, 3, 10)
+            }
+        );
+        assert_eq!(transfers[1].kind, InternalTxKind::Create2);
+        assert_eq!(transfers[1].to, Address([9; 20]));
+        assert_eq!(transfers[1].depth, 1);
+    }
+
+    #[test]
+    fn test_step_limit() {
+        let mut tracer = CallTracer::new(TraceOptions {
+            opcodes: true,
+            stack: true,
+            max_steps: 2,
+        });
+        for pc in 0..4 {
+            let step = tracer.start_step(pc, "ADD", 100 - pc as u64 * 3, 1, &[U256::one()]);
+            tracer.end_step(step, 97 - pc as u64 * 3);
+        }
+        assert_eq!(tracer.struct_logs.len(), 2);
+        assert_eq!(tracer.struct_logs[1].gas_cost, 3);
+        assert_eq!(tracer.struct_logs[0].stack, Some(vec![U256::one()]));
+    }
+}
@@ -245,6 +245,80 @@ pub enum AccessPolicy {
     PayPerUse { fee: U256 },
 }
 
+/// How a pay-per-use model's inference fee is split, in basis points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoyaltySchedule {
+    pub owner_bps: u16,
+    pub provider_bps: u16,
+    pub treasury_bps: u16,
+}
+
+impl Default for RoyaltySchedule {
+    /// 90% to the model owner and 10% to the treasury
+    fn default() -> Self {
+        Self {
+            owner_bps: 9000,
+            provider_bps: 0,
+            treasury_bps: 1000,
+        }
+    }
+}
+
+/// Amounts paid out of one inference fee
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RoyaltySplit {
+    pub owner: U256,
+    pub provider: U256,
+    pub treasury: U256,
+}
+
+impl RoyaltySchedule {
+    pub const TOTAL_BPS: u16 = 10_000;
+
+    /// A schedule is valid when its shares add up to exactly 100%
+    pub fn is_valid(&self) -> bool {
+        self.owner_bps as u32 + self.provider_bps as u32 + self.treasury_bps as u32
+            == Self::TOTAL_BPS as u32
+    }
+
+    /// Split `fee` by this schedule. Rounding dust goes to the owner, as does
+    /// the provider share when no provider served the request.
+    pub fn split(&self, fee: U256, has_provider: bool) -> RoyaltySplit {
+        let total = U256::from(Self::TOTAL_BPS);
+        let treasury = fee * U256::from(self.treasury_bps) / total;
+        let provider = if has_provider {
+            fee * U256::from(self.provider_bps) / total
+        } else {
+            U256::zero()
+        };
+        RoyaltySplit {
+            owner: fee - treasury - provider,
+            provider,
+            treasury,
+        }
+    }
+
+    /// Storage encoding: the three shares as big-endian u16s
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(6);
+        bytes.extend_from_slice(&self.owner_bps.to_be_bytes());
+        bytes.extend_from_slice(&self.provider_bps.to_be_bytes());
+        bytes.extend_from_slice(&self.treasury_bps.to_be_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 6 {
+            return None;
+        }
+        Some(Self {
+            owner_bps: u16::from_be_bytes([bytes[0], bytes[1]]),
+            provider_bps: u16::from_be_bytes([bytes[2], bytes[3]]),
+            treasury_bps: u16::from_be_bytes([bytes[4], bytes[5]]),
+        })
+    }
+}
+
 /// Model state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelState {
@@ -0,0 +1,295 @@
+// citrate/core/execution/src/units.rs
+
+// Amount parsing and formatting for wei / gwei / LATT
+//
+// All conversions are done on decimal digit strings and integers, never
+// floats, so large amounts keep full precision. Output always uses '.' as
+// the decimal separator and no digit grouping, independent of locale.
+
+use primitive_types::U256;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Wei in one gwei
+pub const WEI_PER_GWEI: u128 = 1_000_000_000;
+
+/// Wei in one LATT
+pub const WEI_PER_LATT: u128 = 1_000_000_000_000_000_000;
+
+/// Denomination of an amount
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Wei,
+    Gwei,
+    Latt,
+}
+
+impl Unit {
+    /// Decimal places between this unit and wei
+    pub fn decimals(&self) -> usize {
+        match self {
+            Unit::Wei => 0,
+            Unit::Gwei => 9,
+            Unit::Latt => 18,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::Wei => "wei",
+            Unit::Gwei => "gwei",
+            Unit::Latt => "LATT",
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+impl FromStr for Unit {
+    type Err = AmountError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "wei" => Ok(Unit::Wei),
+            "gwei" => Ok(Unit::Gwei),
+            // SALT is the display name used by the desktop app
+            "latt" | "salt" => Ok(Unit::Latt),
+            other => Err(AmountError::UnknownUnit(other.to_string())),
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AmountError {
+    #[error("Amount is empty")]
+    Empty,
+
+    #[error("Negative amounts are not allowed")]
+    Negative,
+
+    #[error("Scientific notation is not supported; write the amount out in full")]
+    ScientificNotation,
+
+    #[error("Use '.' as the decimal separator and no digit grouping")]
+    Separator,
+
+    #[error("Invalid character '{0}' in amount")]
+    InvalidCharacter(char),
+
+    #[error("Too many decimal places for {unit}: at most {max}")]
+    TooManyDecimals { unit: Unit, max: usize },
+
+    #[error("Amount is too large")]
+    Overflow,
+
+    #[error("Unknown unit '{0}'")]
+    UnknownUnit(String),
+}
+
+/// Parse a decimal amount in `unit` into wei, e.g. `parse_units("1.5", Unit::Latt)`
+pub fn parse_units(input: &str, unit: Unit) -> Result<u128, AmountError> {
+    let s = input.trim();
+    if s.is_empty() {
+        return Err(AmountError::Empty);
+    }
+    if s.starts_with('-') {
+        return Err(AmountError::Negative);
+    }
+    let s = s.strip_prefix('+').unwrap_or(s);
+
+    let (whole, fraction) = match s.split_once('.') {
+        Some((w, f)) => (w, f),
+        None => (s, ""),
+    };
+    for c in whole.chars().chain(fraction.chars()) {
+        match c {
+            '0'..='9' => {}
+            'e' | 'E' => return Err(AmountError::ScientificNotation),
+            ',' | '.' | '_' | '\'' | ' ' => return Err(AmountError::Separator),
+            other => return Err(AmountError::InvalidCharacter(other)),
+        }
+    }
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(AmountError::Empty);
+    }
+
+    let decimals = unit.decimals();
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > decimals {
+        return Err(AmountError::TooManyDecimals {
+            unit,
+            max: decimals,
+        });
+    }
+
+    let mut digits = String::with_capacity(whole.len() + decimals);
+    digits.push_str(whole);
+    digits.push_str(fraction);
+    digits.extend(std::iter::repeat_n('0', decimals - fraction.len()));
+
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    digits.parse::<u128>().map_err(|_| AmountError::Overflow)
+}
+
+/// Parse an amount with an optional unit suffix (`"1.5 LATT"`, `"20gwei"`,
+/// `"100 wei"`); bare numbers are read in `default_unit`
+pub fn parse_amount(input: &str, default_unit: Unit) -> Result<u128, AmountError> {
+    let s = input.trim();
+    let split = s
+        .find(|c: char| c.is_ascii_alphabetic() && c != 'e' && c != 'E')
+        .unwrap_or(s.len());
+    let (number, suffix) = s.split_at(split);
+    let unit = if suffix.trim().is_empty() {
+        default_unit
+    } else {
+        suffix.parse()?
+    };
+    parse_units(number, unit)
+}
+
+/// Format a wei amount in `unit` at full precision, without trailing zeros
+pub fn format_units(value: impl Into<U256>, unit: Unit) -> String {
+    let digits = value.into().to_string();
+    let decimals = unit.decimals();
+    if decimals == 0 {
+        return digits;
+    }
+
+    let padded = format!("{:0>width$}", digits, width = decimals + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// Format a wei amount in `unit` with at most `max_decimals` places,
+/// truncating rather than rounding so a balance is never overstated
+pub fn format_units_truncated(value: impl Into<U256>, unit: Unit, max_decimals: usize) -> String {
+    let full = format_units(value, unit);
+    match full.split_once('.') {
+        Some((whole, fraction)) => {
+            let fraction = &fraction[..fraction.len().min(max_decimals)];
+            let fraction = fraction.trim_end_matches('0');
+            if fraction.is_empty() {
+                whole.to_string()
+            } else {
+                format!("{}.{}", whole, fraction)
+            }
+        }
+        None => full,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_units() {
+        assert_eq!(parse_units("1", Unit::Latt).unwrap(), WEI_PER_LATT);
+        assert_eq!(
+            parse_units("1.5", Unit::Latt).unwrap(),
+            WEI_PER_LATT * 3 / 2
+        );
+        assert_eq!(parse_units(".5", Unit::Gwei).unwrap(), 500_000_000);
+        assert_eq!(parse_units("20.", Unit::Gwei).unwrap(), 20 * WEI_PER_GWEI);
+        assert_eq!(parse_units("0.000000000000000001", Unit::Latt).unwrap(), 1);
+        assert_eq!(
+            parse_units("1.10000000000000000000", Unit::Latt).unwrap(),
+            11 * WEI_PER_LATT / 10
+        );
+        assert_eq!(parse_units("000", Unit::Wei).unwrap(), 0);
+
+        // Large amounts keep every digit
+        assert_eq!(
+            parse_units("123456789012345678.123456789012345678", Unit::Latt).unwrap(),
+            123_456_789_012_345_678_123_456_789_012_345_678
+        );
+    }
+
+    #[test]
+    fn test_parse_units_rejects() {
+        assert_eq!(parse_units("", Unit::Latt), Err(AmountError::Empty));
+        assert_eq!(parse_units(".", Unit::Latt), Err(AmountError::Empty));
+        assert_eq!(parse_units("-1", Unit::Latt), Err(AmountError::Negative));
+        assert_eq!(
+            parse_units("1e18", Unit::Wei),
+            Err(AmountError::ScientificNotation)
+        );
+        assert_eq!(parse_units("1,5", Unit::Latt), Err(AmountError::Separator));
+        assert_eq!(
+            parse_units("1.000.000", Unit::Latt),
+            Err(AmountError::Separator)
+        );
+        assert_eq!(
+            parse_units("0x10", Unit::Wei),
+            Err(AmountError::InvalidCharacter('x'))
+        );
+        assert_eq!(
+            parse_units("1.5", Unit::Wei),
+            Err(AmountError::TooManyDecimals {
+                unit: Unit::Wei,
+                max: 0
+            })
+        );
+        assert_eq!(
+            parse_units("340282366920938463463.374607431768211456", Unit::Latt),
+            Err(AmountError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_parse_amount_with_unit() {
+        assert_eq!(
+            parse_amount("1.5 LATT", Unit::Wei).unwrap(),
+            WEI_PER_LATT * 3 / 2
+        );
+        assert_eq!(parse_amount("2 salt", Unit::Wei).unwrap(), 2 * WEI_PER_LATT);
+        assert_eq!(
+            parse_amount("20gwei", Unit::Latt).unwrap(),
+            20 * WEI_PER_GWEI
+        );
+        assert_eq!(parse_amount("100", Unit::Wei).unwrap(), 100);
+        assert_eq!(parse_amount("1", Unit::Latt).unwrap(), WEI_PER_LATT);
+        assert_eq!(
+            parse_amount("1e3 wei", Unit::Wei),
+            Err(AmountError::ScientificNotation)
+        );
+        assert_eq!(
+            parse_amount("1 btc", Unit::Wei),
+            Err(AmountError::UnknownUnit("btc".into()))
+        );
+    }
+
+    #[test]
+    fn test_format_units() {
+        assert_eq!(format_units(0u128, Unit::Latt), "0");
+        assert_eq!(format_units(WEI_PER_LATT, Unit::Latt), "1");
+        assert_eq!(format_units(WEI_PER_LATT * 3 / 2, Unit::Latt), "1.5");
+        assert_eq!(format_units(1u128, Unit::Latt), "0.000000000000000001");
+        assert_eq!(format_units(1_500_000_000u128, Unit::Gwei), "1.5");
+        assert_eq!(format_units(42u128, Unit::Wei), "42");
+        assert_eq!(format_units(U256::MAX, Unit::Wei), U256::MAX.to_string());
+
+        assert_eq!(format_units_truncated(1u128, Unit::Latt, 6), "0");
+        assert_eq!(
+            format_units_truncated(1_234_567_890_000_000_000u128, Unit::Latt, 6),
+            "1.234567"
+        );
+
+        // Round trip
+        let wei = parse_units("98765.4321", Unit::Latt).unwrap();
+        assert_eq!(format_units(wei, Unit::Latt), "98765.4321");
+    }
+}
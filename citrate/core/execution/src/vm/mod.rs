@@ -1,6 +1,7 @@
 // citrate/core/execution/src/vm/mod.rs
 
 // Virtual Machine for executing smart contracts and AI models
+use crate::tracer::CallTracer;
 use crate::types::{ExecutionError, GasSchedule};
 use primitive_types::U256;
 use std::collections::HashMap;
@@ -24,6 +25,8 @@ pub struct VM {
     pub gas_used: u64,
     pub gas_schedule: GasSchedule,
     pub ai_extension: AIVMExtension,
+    /// Records executed opcodes when set
+    pub tracer: Option<CallTracer>,
 }
 
 impl VM {
@@ -36,6 +39,7 @@ impl VM {
             gas_used: 0,
             gas_schedule: GasSchedule::default(),
             ai_extension: AIVMExtension::new(),
+            tracer: None,
         }
     }
 
@@ -84,46 +88,88 @@ impl VM {
         while pc < code.len() {
             let opcode = code[pc];
 
-            // Check if it's an AI opcode
-            if (0xA0..=0xDF).contains(&opcode) {
-                if let Some(ai_opcode) = self.decode_ai_opcode(opcode) {
-                    // Consume gas for AI opcode
-                    let gas_cost = self.ai_extension.gas_cost(ai_opcode);
-                    self.consume_gas(gas_cost)?;
-
-                    // Transfer required stack args to AI extension
-                    let args_needed = ai_required_args(ai_opcode);
-                    if args_needed > 0 {
-                        let mut tmp: Vec<U256> = Vec::with_capacity(args_needed);
-                        for _ in 0..args_needed {
-                            let v = self.stack.pop()?; // error if insufficient
-                            tmp.push(v);
-                        }
-                        // Preserve pop order expected by opcode: last pushed should be on top in ai stack
-                        for v in tmp.into_iter().rev() {
-                            self.ai_extension.push(v);
-                        }
+            let step = if self.tracer.is_some() {
+                let name = self.opcode_name(opcode);
+                let gas = self.gas_remaining;
+                self.tracer
+                    .as_mut()
+                    .and_then(|tracer| tracer.start_step(pc, name, gas, 1, &self.stack.data))
+            } else {
+                None
+            };
+            let result = self.execute_opcode(opcode);
+            if let Some(tracer) = self.tracer.as_mut() {
+                tracer.end_step(step, self.gas_remaining);
+            }
+            result?;
+
+            pc += 1;
+        }
+
+        Ok(vec![])
+    }
+
+    /// Execute a single opcode
+    fn execute_opcode(&mut self, opcode: u8) -> Result<(), ExecutionError> {
+        // Check if it's an AI opcode
+        if (0xA0..=0xDF).contains(&opcode) {
+            if let Some(ai_opcode) = self.decode_ai_opcode(opcode) {
+                // Consume gas for AI opcode
+                let gas_cost = self.ai_extension.gas_cost(ai_opcode);
+                self.consume_gas(gas_cost)?;
+
+                // Transfer required stack args to AI extension
+                let args_needed = ai_required_args(ai_opcode);
+                if args_needed > 0 {
+                    let mut tmp: Vec<U256> = Vec::with_capacity(args_needed);
+                    for _ in 0..args_needed {
+                        let v = self.stack.pop()?; // error if insufficient
+                        tmp.push(v);
+                    }
+                    // Preserve pop order expected by opcode: last pushed should be on top in ai stack
+                    for v in tmp.into_iter().rev() {
+                        self.ai_extension.push(v);
                     }
+                }
 
-                    // Execute AI opcode
-                    self.ai_extension.execute(ai_opcode)?;
+                // Execute AI opcode
+                self.ai_extension.execute(ai_opcode)?;
 
-                    // Transfer results back to main stack
-                    if produces_output(ai_opcode) {
-                        if let Some(value) = self.ai_extension.pop() {
-                            self.stack.push(value)?;
-                        }
+                // Transfer results back to main stack
+                if produces_output(ai_opcode) {
+                    if let Some(value) = self.ai_extension.pop() {
+                        self.stack.push(value)?;
                     }
                 }
-            } else {
-                // Execute standard EVM opcodes
-                self.execute_standard_opcode(opcode)?;
             }
-
-            pc += 1;
+        } else {
+            // Execute standard EVM opcodes
+            self.execute_standard_opcode(opcode)?;
         }
 
-        Ok(vec![])
+        Ok(())
+    }
+
+    /// Mnemonic for trace output
+    fn opcode_name(&self, opcode: u8) -> String {
+        if let Some(ai_opcode) = self.decode_ai_opcode(opcode) {
+            return format!("{:?}", ai_opcode);
+        }
+        let name = match opcode {
+            0x50 => "PUSH",
+            0x51 => "POP",
+            0x52 => "MLOAD",
+            0x53 => "MSTORE",
+            0x01 => "ADD",
+            0x02 => "MUL",
+            0x03 => "SUB",
+            0x04 => "DIV",
+            0x56 => "JUMP",
+            0x57 => "JUMPI",
+            0x00 => "STOP",
+            _ => return format!("0x{:02x}", opcode),
+        };
+        name.to_string()
     }
 
     /// Decode AI opcode
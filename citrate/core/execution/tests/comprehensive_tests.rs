@@ -29,6 +29,7 @@ fn create_test_block(height: u64) -> Block {
             base_fee_per_gas: 0,
             gas_used: 0,
             gas_limit: 30_000_000,
+            epoch_commitment: Hash::default(),
         },
         state_root: Hash::default(),
         tx_root: Hash::default(),
@@ -100,8 +101,8 @@ mod execution_tests {
 
     #[test]
     fn test_address_from_hex() {
-        let hex_with_prefix = "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb1";
-        let hex_without = "742d35Cc6634C0532925a3b844Bc9e7595f0bEb1";
+        let hex_with_prefix = "0x742d35cc6634c0532925a3b844bc9e7595f0beb1";
+        let hex_without = "742d35cc6634c0532925a3b844bc9e7595f0beb1";
 
         let addr1 = address_utils::address_from_hex(hex_with_prefix).unwrap();
         let addr2 = address_utils::address_from_hex(hex_without).unwrap();
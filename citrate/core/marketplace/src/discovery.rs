@@ -9,6 +9,7 @@ use crate::{
 };
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -50,6 +51,7 @@ pub struct DiscoveryEngine {
     recommendation_engine: Option<Arc<RecommendationEngine>>,
     config: DiscoveryConfig,
     stats: Arc<RwLock<DiscoveryStats>>,
+    bids: Arc<RwLock<HashMap<ModelId, Vec<ProviderBid>>>>,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -110,6 +112,7 @@ impl DiscoveryEngine {
             recommendation_engine,
             config,
             stats,
+            bids: Arc::new(RwLock::new(HashMap::new())),
         })
     }
 
@@ -282,6 +285,55 @@ impl DiscoveryEngine {
         Ok(())
     }
 
+    /// Publish a provider's bid for a listed model, replacing its previous one
+    pub async fn publish_bid(&self, bid: ProviderBid) -> Result<()> {
+        if self.storage.get_model(&bid.model_id).await?.is_none() {
+            return Err(MarketplaceError::ModelNotFound(bid.model_id).into());
+        }
+
+        let mut bids = self.bids.write().await;
+        let book = bids.entry(bid.model_id).or_default();
+        book.retain(|existing| existing.provider != bid.provider);
+        book.push(bid);
+        Ok(())
+    }
+
+    /// Withdraw a provider's bid, e.g. once its capacity is used up
+    pub async fn withdraw_bid(&self, provider: &Address, model_id: &ModelId) -> bool {
+        let mut bids = self.bids.write().await;
+        match bids.get_mut(model_id) {
+            Some(book) => {
+                let before = book.len();
+                book.retain(|bid| bid.provider != *provider);
+                book.len() != before
+            }
+            None => false,
+        }
+    }
+
+    /// Bids for a model at or under `max_price_per_token`, cheapest first and
+    /// the more reputable provider first at equal prices
+    pub async fn get_bids(&self, model_id: &ModelId, max_price_per_token: Option<u64>) -> Vec<ProviderBid> {
+        let mut bids: Vec<ProviderBid> = self
+            .bids
+            .read()
+            .await
+            .get(model_id)
+            .map(|book| {
+                book.iter()
+                    .filter(|bid| max_price_per_token.is_none_or(|max| bid.price_per_token <= max))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        bids.sort_by(|a, b| {
+            a.price_per_token
+                .cmp(&b.price_per_token)
+                .then(b.reputation.cmp(&a.reputation))
+        });
+        bids
+    }
+
     /// Get marketplace statistics
     pub async fn get_marketplace_stats(&self) -> Result<MarketplaceStats> {
         self.storage.get_marketplace_stats().await
@@ -177,6 +177,18 @@ pub struct ModelMetrics {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Inference capacity a compute provider offers for a model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderBid {
+    pub provider: Address,
+    pub model_id: ModelId,
+    pub price_per_token: u64, // Wei per generated token
+    pub capacity_tokens: u64, // Tokens still available at this price
+    pub reputation: u64,      // Provider success rate, 0-100
+    pub hardware: Vec<String>,
+    pub posted_at: DateTime<Utc>,
+}
+
 /// Search filters for marketplace queries
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SearchFilters {
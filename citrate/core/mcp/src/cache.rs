@@ -1,200 +1,336 @@
 // citrate/core/mcp/src/cache.rs
 
-// LRU cache for models
-use crate::execution::Model;
-use crate::types::ModelId;
-use anyhow::Result;
-use std::collections::{HashMap, VecDeque};
-use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{debug, info};
-
-/// LRU cache for models
-pub struct ModelCache {
-    cache: Arc<RwLock<HashMap<ModelId, CachedModel>>>,
-    lru_queue: Arc<RwLock<VecDeque<ModelId>>>,
-    max_size: u64,
-    current_size: Arc<RwLock<u64>>,
-}
-
-#[derive(Clone)]
-struct CachedModel {
-    model: Model,
-    size: u64,
-    last_accessed: u64,
-    access_count: u64,
+// Response cache for deterministic inference
+//
+// Results are keyed by (model, sampling parameters, prompt) and only stored
+// for requests whose output is reproducible: temperature 0 or a fixed seed.
+// Entries expire after a TTL, and the least recently used entries are
+// evicted once the entry or byte limit is reached. A request sets
+// `"cache": false` in its parameters to bypass the cache entirely.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha3::{Digest, Sha3_256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Parameter that turns caching off for a single request
+pub const CACHE_BYPASS_PARAM: &str = "cache";
+
+/// Size and lifetime limits
+#[derive(Debug, Clone)]
+pub struct InferenceCacheConfig {
+    /// How long a result is served from the cache
+    pub ttl: Duration,
+    pub max_entries: usize,
+    /// Combined size of cached outputs
+    pub max_bytes: u64,
 }
 
-impl ModelCache {
-    pub fn new(max_size: u64) -> Self {
+impl Default for InferenceCacheConfig {
+    fn default() -> Self {
         Self {
-            cache: Arc::new(RwLock::new(HashMap::new())),
-            lru_queue: Arc::new(RwLock::new(VecDeque::new())),
-            max_size,
-            current_size: Arc::new(RwLock::new(0)),
+            ttl: Duration::from_secs(600),
+            max_entries: 1024,
+            max_bytes: 64 * 1024 * 1024,
         }
     }
+}
 
-    /// Get model from cache
-    pub async fn get(&self, model_id: &ModelId) -> Option<Model> {
-        let mut cache = self.cache.write().await;
-
-        if let Some(cached) = cache.get_mut(model_id) {
-            // Update access info
-            cached.last_accessed = chrono::Utc::now().timestamp() as u64;
-            cached.access_count += 1;
-
-            // Move to front of LRU queue
-            self.update_lru(model_id).await;
-
-            debug!("Cache hit for model {:?}", hex::encode(&model_id.0[..8]));
-            return Some(cached.model.clone());
+impl InferenceCacheConfig {
+    /// Default limits with the size limit read, in megabytes, from the
+    /// environment variable `var` when it is set; 0 disables the cache
+    pub fn from_env(var: &str) -> Self {
+        let mut config = Self::default();
+        if let Some(mb) = std::env::var(var).ok().and_then(|v| v.parse::<u64>().ok()) {
+            config.max_bytes = mb * 1024 * 1024;
         }
-
-        None
+        config
     }
+}
 
-    /// Put model in cache
-    pub async fn put(&self, model_id: ModelId, model: Model) -> Result<()> {
-        let model_size = self.calculate_model_size(&model);
-
-        // Check if model fits in cache
-        if model_size > self.max_size {
-            return Err(anyhow::anyhow!("Model too large for cache"));
-        }
+/// Identifies a cached result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub model: [u8; 32],
+    pub params: [u8; 32],
+    pub prompt: [u8; 32],
+}
 
-        // Evict models if necessary
-        while *self.current_size.read().await + model_size > self.max_size {
-            self.evict_lru().await?;
+impl CacheKey {
+    /// Key for `prompt` run on `model` with sampling `params`. The bypass
+    /// flag is not part of the key.
+    pub fn new(model: &[u8], params: &Value, prompt: &[u8]) -> Self {
+        // Sort the parameters so equal ones give equal keys regardless of
+        // the order they were written in
+        let params = match params {
+            Value::Object(map) => {
+                let sorted: BTreeMap<_, _> = map
+                    .iter()
+                    .filter(|(name, _)| name.as_str() != CACHE_BYPASS_PARAM)
+                    .collect();
+                serde_json::to_string(&sorted).unwrap_or_default()
+            }
+            other => other.to_string(),
+        };
+        Self {
+            model: sha3(model),
+            params: sha3(params.as_bytes()),
+            prompt: sha3(prompt),
         }
+    }
+}
 
-        // Add to cache
-        let cached = CachedModel {
-            model,
-            size: model_size,
-            last_accessed: chrono::Utc::now().timestamp() as u64,
-            access_count: 1,
-        };
+fn sha3(data: &[u8]) -> [u8; 32] {
+    Sha3_256::digest(data).into()
+}
 
-        self.cache.write().await.insert(model_id, cached);
-        self.lru_queue.write().await.push_front(model_id);
-        *self.current_size.write().await += model_size;
+/// Whether sampling `params` give a reproducible output: temperature 0 or
+/// an explicit seed
+pub fn is_deterministic(params: &Value) -> bool {
+    params.get("temperature").and_then(Value::as_f64) == Some(0.0)
+        || params.get("seed").is_some_and(Value::is_u64)
+}
 
-        debug!(
-            "Cached model {:?} (size: {} bytes)",
-            hex::encode(&model_id.0[..8]),
-            model_size
-        );
+/// Whether `params` ask to skip the cache
+pub fn is_bypassed(params: &Value) -> bool {
+    params.get(CACHE_BYPASS_PARAM).and_then(Value::as_bool) == Some(false)
+}
 
-        Ok(())
-    }
+/// Cache statistics
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InferenceCacheStats {
+    pub entries: usize,
+    pub bytes: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
 
-    /// Remove model from cache
-    pub async fn remove(&self, model_id: &ModelId) -> Option<Model> {
-        let mut cache = self.cache.write().await;
+struct Entry<V> {
+    value: V,
+    bytes: u64,
+    inserted: Instant,
+    last_used: u64,
+}
 
-        if let Some(cached) = cache.remove(model_id) {
-            *self.current_size.write().await -= cached.size;
+struct State<V> {
+    entries: HashMap<CacheKey, Entry<V>>,
+    bytes: u64,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
 
-            // Remove from LRU queue
-            let mut queue = self.lru_queue.write().await;
-            queue.retain(|id| id != model_id);
+/// TTL and size bounded cache of inference results
+pub struct InferenceCache<V> {
+    config: InferenceCacheConfig,
+    state: Mutex<State<V>>,
+}
 
-            info!(
-                "Removed model {:?} from cache",
-                hex::encode(&model_id.0[..8])
-            );
-            return Some(cached.model);
+impl<V: Clone> InferenceCache<V> {
+    pub fn new(config: InferenceCacheConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(State {
+                entries: HashMap::new(),
+                bytes: 0,
+                clock: 0,
+                hits: 0,
+                misses: 0,
+                evictions: 0,
+            }),
         }
-
-        None
     }
 
-    /// Clear entire cache
-    pub async fn clear(&self) {
-        self.cache.write().await.clear();
-        self.lru_queue.write().await.clear();
-        *self.current_size.write().await = 0;
-
-        info!("Cache cleared");
+    /// The cached result for `key`, unless it has expired
+    pub fn get(&self, key: &CacheKey) -> Option<V> {
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let clock = state.clock;
+        let expired = match state.entries.get_mut(key) {
+            Some(entry) if entry.inserted.elapsed() < self.config.ttl => {
+                entry.last_used = clock;
+                let value = entry.value.clone();
+                state.hits += 1;
+                return Some(value);
+            }
+            Some(_) => true,
+            None => false,
+        };
+        if expired {
+            if let Some(entry) = state.entries.remove(key) {
+                state.bytes -= entry.bytes;
+            }
+        }
+        state.misses += 1;
+        None
     }
 
-    /// Get cache statistics
-    pub async fn stats(&self) -> CacheStats {
-        let cache = self.cache.read().await;
-        let current_size = *self.current_size.read().await;
-
-        let total_models = cache.len();
-        let total_accesses: u64 = cache.values().map(|c| c.access_count).sum();
-
-        CacheStats {
-            total_models,
-            current_size,
-            max_size: self.max_size,
-            utilization: (current_size as f64 / self.max_size as f64) * 100.0,
-            total_accesses,
+    /// Store a result of `bytes` size, evicting expired and then least
+    /// recently used entries to stay within the limits. Results larger than
+    /// the whole cache are not stored.
+    pub fn insert(&self, key: CacheKey, value: V, bytes: u64) {
+        if self.config.max_entries == 0
+            || self.config.max_bytes == 0
+            || bytes > self.config.max_bytes
+        {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let clock = state.clock;
+        if let Some(old) = state.entries.remove(&key) {
+            state.bytes -= old.bytes;
         }
-    }
 
-    /// Preload models into cache
-    pub async fn preload(&self, models: Vec<(ModelId, Model)>) -> Result<()> {
-        for (model_id, model) in models {
-            self.put(model_id, model).await?;
+        let ttl = self.config.ttl;
+        let before = state.entries.len();
+        state
+            .entries
+            .retain(|_, entry| entry.inserted.elapsed() < ttl);
+        state.evictions += (before - state.entries.len()) as u64;
+        state.bytes = state.entries.values().map(|entry| entry.bytes).sum();
+
+        while state.entries.len() >= self.config.max_entries
+            || state.bytes + bytes > self.config.max_bytes
+        {
+            let Some(victim) = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| *key)
+            else {
+                break;
+            };
+            if let Some(evicted) = state.entries.remove(&victim) {
+                state.bytes -= evicted.bytes;
+                state.evictions += 1;
+            }
         }
 
-        info!(
-            "Preloaded {} models into cache",
-            self.cache.read().await.len()
+        state.bytes += bytes;
+        state.entries.insert(
+            key,
+            Entry {
+                value,
+                bytes,
+                inserted: Instant::now(),
+                last_used: clock,
+            },
         );
-        Ok(())
     }
 
-    /// Update LRU queue
-    async fn update_lru(&self, model_id: &ModelId) {
-        let mut queue = self.lru_queue.write().await;
+    /// Drop the entries of one model, returning how many were dropped
+    pub fn remove_model(&self, model: &[u8]) -> usize {
+        let model = sha3(model);
+        let mut state = self.state.lock().unwrap();
+        let before = state.entries.len();
+        state.entries.retain(|key, _| key.model != model);
+        state.bytes = state.entries.values().map(|entry| entry.bytes).sum();
+        before - state.entries.len()
+    }
 
-        // Remove from current position
-        queue.retain(|id| id != model_id);
+    /// Drop every entry
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.entries.clear();
+        state.bytes = 0;
+    }
 
-        // Add to front
-        queue.push_front(*model_id);
+    pub fn stats(&self) -> InferenceCacheStats {
+        let state = self.state.lock().unwrap();
+        InferenceCacheStats {
+            entries: state.entries.len(),
+            bytes: state.bytes,
+            hits: state.hits,
+            misses: state.misses,
+            evictions: state.evictions,
+        }
     }
+}
 
-    /// Evict least recently used model
-    async fn evict_lru(&self) -> Result<()> {
-        let mut queue = self.lru_queue.write().await;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
 
-        if let Some(model_id) = queue.pop_back() {
-            if let Some(cached) = self.cache.write().await.remove(&model_id) {
-                *self.current_size.write().await -= cached.size;
+    fn key(prompt: &str) -> CacheKey {
+        CacheKey::new(b"model", &json!({ "temperature": 0.0 }), prompt.as_bytes())
+    }
 
-                debug!(
-                    "Evicted model {:?} from cache (LRU)",
-                    hex::encode(&model_id.0[..8])
-                );
-            }
-        }
+    #[test]
+    fn test_key_ignores_parameter_order_and_bypass_flag() {
+        let a = CacheKey::new(b"m", &json!({ "temperature": 0.0, "max_tokens": 8 }), b"hi");
+        let b = CacheKey::new(
+            b"m",
+            &json!({ "max_tokens": 8, "cache": true, "temperature": 0.0 }),
+            b"hi",
+        );
+        assert_eq!(a, b);
+        assert_ne!(
+            a,
+            CacheKey::new(b"m", &json!({ "temperature": 0.0, "max_tokens": 9 }), b"hi")
+        );
+        assert_ne!(
+            a,
+            CacheKey::new(b"n", &json!({ "temperature": 0.0, "max_tokens": 8 }), b"hi")
+        );
 
-        Ok(())
+        assert!(is_deterministic(&json!({ "temperature": 0 })));
+        assert!(is_deterministic(&json!({ "temperature": 0.7, "seed": 42 })));
+        assert!(!is_deterministic(&json!({ "temperature": 0.7 })));
+        assert!(is_bypassed(&json!({ "cache": false })));
+        assert!(!is_bypassed(&json!({})));
     }
 
-    /// Calculate model size
-    fn calculate_model_size(&self, model: &Model) -> u64 {
-        let mut size = 0u64;
-        size += model.architecture.len() as u64;
-        size += model.weights.len() as u64;
-        size += model.metadata.len() as u64;
-        size += 32; // ModelId size
-        size
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let cache = InferenceCache::new(InferenceCacheConfig {
+            max_entries: 2,
+            ..Default::default()
+        });
+        cache.insert(key("a"), "A".to_string(), 1);
+        cache.insert(key("b"), "B".to_string(), 1);
+        assert_eq!(cache.get(&key("a")), Some("A".to_string()));
+        cache.insert(key("c"), "C".to_string(), 1);
+
+        assert_eq!(cache.get(&key("b")), None);
+        assert_eq!(cache.get(&key("a")), Some("A".to_string()));
+        let stats = cache.stats();
+        assert_eq!(
+            (stats.entries, stats.hits, stats.misses, stats.evictions),
+            (2, 2, 1, 1)
+        );
     }
-}
 
-/// Cache statistics
-#[derive(Debug, Clone)]
-pub struct CacheStats {
-    pub total_models: usize,
-    pub current_size: u64,
-    pub max_size: u64,
-    pub utilization: f64,
-    pub total_accesses: u64,
+    #[test]
+    fn test_cache_limits_bytes_and_expires_entries() {
+        let cache = InferenceCache::new(InferenceCacheConfig {
+            max_bytes: 10,
+            ..Default::default()
+        });
+        cache.insert(key("a"), 1, 6);
+        cache.insert(key("b"), 2, 6);
+        assert_eq!(cache.get(&key("a")), None);
+        cache.insert(key("big"), 3, 11);
+        assert_eq!(cache.get(&key("big")), None);
+        assert_eq!(cache.stats().bytes, 6);
+
+        let other_model = CacheKey::new(b"other", &json!({ "temperature": 0.0 }), b"b");
+        cache.insert(other_model, 4, 1);
+        assert_eq!(cache.remove_model(b"model"), 1);
+        assert_eq!(cache.get(&key("b")), None);
+        assert_eq!(cache.get(&other_model), Some(4));
+        assert_eq!(cache.stats().bytes, 1);
+
+        let expiring = InferenceCache::new(InferenceCacheConfig {
+            ttl: Duration::ZERO,
+            ..Default::default()
+        });
+        expiring.insert(key("a"), 1, 1);
+        assert_eq!(expiring.get(&key("a")), None);
+        assert_eq!(expiring.stats().entries, 0);
+    }
 }
@@ -1,16 +1,19 @@
 // citrate/core/mcp/src/execution.rs
 
 // Model executor for running AI models
-use crate::cache::ModelCache;
-use crate::gguf_engine::{GGUFEngine, GGUFEngineConfig, ModelType as GGUFModelType};
+use crate::cache::{self, CacheKey, InferenceCache, InferenceCacheConfig, InferenceCacheStats};
+use crate::gguf_engine::{GGUFEngine, GGUFEngineConfig, ModelType as GGUFModelType, SamplingParams};
 use crate::registry::ModelRegistry;
+use crate::residency::{ModelLease, ResidencyManager, ResidencyStats, ResidentSize};
+use crate::slo::Failover;
 use crate::types::{ExecutionProof, ModelId};
-use crate::verification::ExecutionVerifier;
+use crate::verification::{derive_seed, ExecutionVerifier};
 use anyhow::{anyhow, Result};
 use hex;
 use citrate_execution::vm::VM;
-use citrate_execution::{Address, Hash};
+use citrate_execution::Address;
 use citrate_storage::ipfs::{chunking, Cid, IPFSService};
+use primitive_types::U256;
 use serde_json;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -24,26 +27,41 @@ pub struct InferenceResult {
     pub gas_used: u64,
     pub latency_ms: u64,
     pub provider: Address,
+    /// Output reused from an identical deterministic request
+    pub cached: bool,
+    /// Attempts abandoned on other providers before this one served the job
+    pub failovers: Vec<Failover>,
+    /// Per-token price of the bid that served a market-matched job
+    pub price_per_token: Option<U256>,
+}
+
+/// Output and gas of a cached inference
+#[derive(Debug, Clone)]
+struct CachedInference {
+    output: Vec<u8>,
+    gas_used: u64,
 }
 
 /// Model executor for running AI models
 pub struct ModelExecutor {
     #[allow(dead_code)]
     vm: Arc<VM>,
-    cache: Arc<ModelCache>,
+    models: Arc<ResidencyManager<ModelId, Model>>,
     verifier: Arc<ExecutionVerifier>,
     registry: Arc<ModelRegistry>,
     ipfs: Mutex<IPFSService>,
     gguf_engine: Arc<GGUFEngine>,
+    cache: InferenceCache<CachedInference>,
 }
 
 impl ModelExecutor {
     pub fn new(
         vm: Arc<VM>,
-        cache: Arc<ModelCache>,
+        models: Arc<ResidencyManager<ModelId, Model>>,
         verifier: Arc<ExecutionVerifier>,
         registry: Arc<ModelRegistry>,
         ipfs: IPFSService,
+        cache_config: InferenceCacheConfig,
     ) -> Self {
         // Initialize GGUF engine with default config
         let gguf_config = GGUFEngineConfig::default();
@@ -55,11 +73,12 @@ impl ModelExecutor {
 
         Self {
             vm,
-            cache,
+            models,
             verifier,
             registry,
             ipfs: Mutex::new(ipfs),
             gguf_engine: Arc::new(gguf_engine),
+            cache: InferenceCache::new(cache_config),
         }
     }
 
@@ -72,7 +91,7 @@ impl ModelExecutor {
     ) -> Result<InferenceResult> {
         let start_time = std::time::Instant::now();
 
-        // 1. Load model from cache or storage
+        // 1. Load model, or reuse it if resident
         let model = self.load_model(model_id).await?;
 
         // 2. Verify model integrity
@@ -81,19 +100,37 @@ impl ModelExecutor {
         // 3. Prepare execution context
         let context = self.prepare_context(&model, &input)?;
 
-        // 4. Execute inference in VM
-        let (output, gas_used) = self.execute_in_vm(&context).await?;
+        // 4. Execute inference in VM, or reuse the output of an identical
+        //    deterministic request
+        let cache_key = self.cache_key(&model, &input);
+        let (output, gas_used, cached) = match cache_key.and_then(|key| self.cache.get(&key)) {
+            Some(hit) => (hit.output, hit.gas_used, true),
+            None => {
+                let (output, gas_used) = self.execute_in_vm(&context).await?;
+                if let Some(key) = cache_key {
+                    let cached = CachedInference {
+                        output: output.clone(),
+                        gas_used,
+                    };
+                    self.cache.insert(key, cached, output.len() as u64);
+                }
+                (output, gas_used, false)
+            }
+        };
 
         // 5. Generate execution proof
-        let proof = self.generate_proof(&model, &input, &output, provider)?;
+        let proof = self
+            .verifier
+            .generate_proof(&model, &input, &output, context.seed, provider);
 
         let latency_ms = start_time.elapsed().as_millis() as u64;
 
         info!(
-            "Inference completed for model {:?} in {}ms using {} gas",
+            "Inference completed for model {:?} in {}ms using {} gas{}",
             hex::encode(&model_id.0[..8]),
             latency_ms,
-            gas_used
+            gas_used,
+            if cached { " (cached)" } else { "" }
         );
 
         Ok(InferenceResult {
@@ -102,6 +139,9 @@ impl ModelExecutor {
             gas_used,
             latency_ms,
             provider,
+            cached,
+            failovers: Vec::new(),
+            price_per_token: None,
         })
     }
 
@@ -130,6 +170,7 @@ impl ModelExecutor {
             &training_data,
             &current_weights,
             &updated_weights,
+            context.seed,
             provider,
         )?;
 
@@ -151,56 +192,89 @@ impl ModelExecutor {
         })
     }
 
-    /// Load model from cache or storage
-    async fn load_model(&self, model_id: ModelId) -> Result<Model> {
-        if let Some(model) = self.cache.get(&model_id).await {
-            debug!(
-                "Model loaded from cache: {:?}",
-                hex::encode(&model_id.0[..8])
-            );
-            return Ok(model);
+    /// Memory used by resident models
+    pub fn residency_stats(&self) -> ResidencyStats {
+        self.models.stats()
+    }
+
+    /// Hits and size of the inference cache
+    pub fn cache_stats(&self) -> InferenceCacheStats {
+        self.cache.stats()
+    }
+
+    /// Cache key for a request whose output is reproducible, or `None` if it
+    /// must run. Embeddings always are; text generation needs temperature 0
+    /// or a fixed seed. The prompt part covers the whole input, which the
+    /// sampling seed is also derived from.
+    fn cache_key(&self, model: &Model, input: &[u8]) -> Option<CacheKey> {
+        let mut params: serde_json::Value = serde_json::from_slice(input).ok()?;
+        if !params.is_object() || cache::is_bypassed(&params) {
+            return None;
         }
+        let embedding = matches!(
+            self.determine_model_type(model),
+            Ok(GGUFModelType::Embedding)
+        );
+        if !embedding && !cache::is_deterministic(&params) {
+            return None;
+        }
+        if let Some(map) = params.as_object_mut() {
+            for field in ["prompt", "text", "input"] {
+                map.remove(field);
+            }
+        }
+        Some(CacheKey::new(
+            self.verifier.hash_model(model).as_bytes(),
+            &params,
+            input,
+        ))
+    }
 
+    /// Lease the model, loading it from storage if it is not resident
+    async fn load_model(&self, model_id: ModelId) -> Result<ModelLease<Model>> {
         let record = self.registry.get_record(&model_id).await?;
-        let weight_cid = record.weight_cid.clone().ok_or_else(|| {
-            anyhow!(
-                "Model {:?} missing weight CID",
-                hex::encode(&model_id.0[..8])
-            )
-        })?;
-
-        let weights = {
-            let ipfs = self.ipfs.lock().await;
-            let cid = Cid(weight_cid.clone());
-            let raw = ipfs.retrieve_model(&cid).await?;
-            if let Ok(manifest) = serde_json::from_slice::<chunking::ChunkManifest>(&raw) {
-                let mut assembled = Vec::with_capacity(manifest.total_size as usize);
-                for chunk_cid in manifest.chunks {
-                    match ipfs.fetch_raw(&chunk_cid).await {
-                        Ok(bytes) => assembled.extend(bytes),
-                        Err(err) => {
-                            warn!("Failed to fetch chunk {}: {}", chunk_cid.0, err);
-                            return Err(err);
+        self.models
+            .acquire(model_id, record.metadata.size, || async move {
+                let weight_cid = record.weight_cid.clone().ok_or_else(|| {
+                    anyhow!(
+                        "Model {:?} missing weight CID",
+                        hex::encode(&model_id.0[..8])
+                    )
+                })?;
+
+                let weights = {
+                    let ipfs = self.ipfs.lock().await;
+                    let cid = Cid(weight_cid.clone());
+                    let raw = ipfs.retrieve_model(&cid).await?;
+                    if let Ok(manifest) = serde_json::from_slice::<chunking::ChunkManifest>(&raw) {
+                        let mut assembled = Vec::with_capacity(manifest.total_size as usize);
+                        for chunk_cid in manifest.chunks {
+                            match ipfs.fetch_raw(&chunk_cid).await {
+                                Ok(bytes) => assembled.extend(bytes),
+                                Err(err) => {
+                                    warn!("Failed to fetch chunk {}: {}", chunk_cid.0, err);
+                                    return Err(err);
+                                }
+                            }
                         }
+                        assembled
+                    } else {
+                        raw
                     }
-                }
-                assembled
-            } else {
-                raw
-            }
-        };
-
-        let metadata_bytes = serde_json::to_vec(&record.metadata)?;
-        let model = Model {
-            id: model_id,
-            architecture: Vec::new(),
-            weights,
-            metadata: metadata_bytes,
-        };
-
-        self.cache.put(model_id, model.clone()).await?;
+                };
 
-        Ok(model)
+                debug!(
+                    "Loaded model {:?} from storage",
+                    hex::encode(&model_id.0[..8])
+                );
+                Ok(Model {
+                    id: model_id,
+                    architecture: Vec::new(),
+                    weights,
+                    metadata: serde_json::to_vec(&record.metadata)?,
+                })
+            })
+            .await
     }
 
     /// Prepare execution context
@@ -208,6 +282,7 @@ impl ModelExecutor {
         Ok(ExecutionContext {
             model_id: model.id,
             input: input.to_vec(),
+            seed: self.request_seed(model, input),
             memory_limit: 1024 * 1024 * 100, // 100MB
             gas_limit: 10_000_000,
             execution_mode: ExecutionMode::Inference,
@@ -224,6 +299,7 @@ impl ModelExecutor {
         Ok(ExecutionContext {
             model_id: model.id,
             input: training_data.to_vec(),
+            seed: self.request_seed(model, training_data),
             memory_limit: 1024 * 1024 * 500, // 500MB for training
             gas_limit: 50_000_000,
             execution_mode: ExecutionMode::Training {
@@ -291,15 +367,20 @@ impl ModelExecutor {
                     .and_then(|v| v.as_u64())
                     .unwrap_or(512) as usize;
 
-                let temperature = input_json
-                    .get("temperature")
-                    .and_then(|v| v.as_f64())
-                    .unwrap_or(0.7) as f32;
+                // Temperature, top-k/top-p/min-p, repeat penalty, stop
+                // sequences and logit bias
+                let sampling = SamplingParams::from_json(&input_json)?;
 
-                // Generate text
+                // Generate text with the request's seed so re-executions match
                 let generated_text = self
                     .gguf_engine
-                    .generate_text(&model_path, prompt, max_tokens, temperature)
+                    .generate_text_with(
+                        &model_path,
+                        prompt,
+                        max_tokens,
+                        &sampling,
+                        Some(context.seed),
+                    )
                     .await?;
 
                 // Serialize response
@@ -368,55 +449,12 @@ impl ModelExecutor {
         Ok((updated_weights, metrics, gas_used))
     }
 
-    /// Generate execution proof
-    fn generate_proof(
-        &self,
-        model: &Model,
-        input: &[u8],
-        output: &[u8],
-        provider: Address,
-    ) -> Result<ExecutionProof> {
-        use sha3::{Digest, Sha3_256};
-
-        // Hash model
-        let model_hash = {
-            let mut hasher = Sha3_256::new();
-            hasher.update(&model.architecture);
-            hasher.update(&model.weights);
-            Hash::new(hasher.finalize().into())
-        };
-
-        // Hash input/output
-        let input_hash = {
-            let mut hasher = Sha3_256::new();
-            hasher.update(input);
-            Hash::new(hasher.finalize().into())
-        };
-
-        let output_hash = {
-            let mut hasher = Sha3_256::new();
-            hasher.update(output);
-            Hash::new(hasher.finalize().into())
-        };
-
-        // Create IO commitment
-        let io_commitment = {
-            let mut hasher = Sha3_256::new();
-            hasher.update(input_hash.as_bytes());
-            hasher.update(output_hash.as_bytes());
-            Hash::new(hasher.finalize().into())
-        };
-
-        Ok(ExecutionProof {
-            model_hash,
-            input_hash,
-            output_hash,
-            io_commitment,
-            statement: vec![],  // Placeholder for ZK statement
-            proof_data: vec![], // Placeholder for ZK proof
-            timestamp: chrono::Utc::now().timestamp() as u64,
-            provider,
-        })
+    /// Seed every provider uses for this model and input
+    fn request_seed(&self, model: &Model, input: &[u8]) -> u64 {
+        derive_seed(
+            &self.verifier.hash_model(model),
+            &self.verifier.hash_data(input),
+        )
     }
 
     /// Generate training proof
@@ -426,10 +464,13 @@ impl ModelExecutor {
         training_data: &[u8],
         _current_weights: &[u8],
         updated_weights: &[u8],
+        seed: u64,
         provider: Address,
     ) -> Result<ExecutionProof> {
-        // Similar to generate_proof but for training
-        self.generate_proof(model, training_data, updated_weights, provider)
+        // Same proof as inference, committing to the updated weights
+        Ok(self
+            .verifier
+            .generate_proof(model, training_data, updated_weights, seed, provider))
     }
 }
 
@@ -442,12 +483,24 @@ pub struct Model {
     pub metadata: Vec<u8>,
 }
 
+impl ResidentSize for Model {
+    fn resident_bytes(&self) -> u64 {
+        let mut size = 0u64;
+        size += self.architecture.len() as u64;
+        size += self.weights.len() as u64;
+        size += self.metadata.len() as u64;
+        size += 32; // ModelId size
+        size
+    }
+}
+
 /// Execution context
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 struct ExecutionContext {
     model_id: ModelId,
     input: Vec<u8>,
+    seed: u64,
     memory_limit: u64,
     gas_limit: u64,
     execution_mode: ExecutionMode,
@@ -2,7 +2,9 @@
 
 /// GGUF Model Inference Engine using llama.cpp
 use anyhow::{anyhow, Context, Result};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use tokio::fs;
@@ -42,6 +44,217 @@ impl Default for GGUFEngineConfig {
     }
 }
 
+/// Sampler settings for text generation. Unset options keep llama.cpp's
+/// defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SamplingParams {
+    pub temperature: f32,
+    /// Sample from the `k` most likely tokens; 0 disables the filter
+    pub top_k: Option<u32>,
+    /// Sample from the smallest set of tokens whose probability reaches `p`
+    pub top_p: Option<f32>,
+    /// Drop tokens less likely than `p` times the most likely one
+    pub min_p: Option<f32>,
+    /// Penalty for repeating recent tokens; 1.0 disables it
+    #[serde(alias = "repetition_penalty")]
+    pub repeat_penalty: Option<f32>,
+    /// Generation ends before the first occurrence of any of these
+    #[serde(deserialize_with = "one_or_many")]
+    pub stop: Vec<String>,
+    /// Bias added to the logit of each token id, from -100 (never sampled)
+    /// to 100
+    pub logit_bias: BTreeMap<u32, f32>,
+}
+
+impl Default for SamplingParams {
+    fn default() -> Self {
+        Self {
+            temperature: 0.7,
+            top_k: None,
+            top_p: None,
+            min_p: None,
+            repeat_penalty: None,
+            stop: Vec::new(),
+            logit_bias: BTreeMap::new(),
+        }
+    }
+}
+
+/// Accept a stop sequence given as a single string as well as a list
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match Option::<OneOrMany>::deserialize(deserializer)? {
+        Some(OneOrMany::One(stop)) => vec![stop],
+        Some(OneOrMany::Many(stop)) => stop,
+        None => Vec::new(),
+    })
+}
+
+impl SamplingParams {
+    /// Default settings at `temperature`
+    pub fn with_temperature(temperature: f32) -> Self {
+        Self {
+            temperature,
+            ..Self::default()
+        }
+    }
+
+    /// Settings read from a request's JSON parameters. Missing fields keep
+    /// their defaults and unrelated fields are ignored.
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let params = match value {
+            Value::Object(_) => serde_json::from_value(value.clone())
+                .context("Invalid sampling parameters")?,
+            _ => Self::default(),
+        };
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// Check every setting is within the range llama.cpp accepts
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=2.0).contains(&self.temperature) {
+            return Err(anyhow!("temperature must be between 0 and 2"));
+        }
+        if self.top_p.is_some_and(|p| !(p > 0.0 && p <= 1.0)) {
+            return Err(anyhow!("top_p must be greater than 0 and at most 1"));
+        }
+        if self.min_p.is_some_and(|p| !(0.0..=1.0).contains(&p)) {
+            return Err(anyhow!("min_p must be between 0 and 1"));
+        }
+        if self.repeat_penalty.is_some_and(|penalty| penalty.is_nan() || penalty <= 0.0) {
+            return Err(anyhow!("repeat_penalty must be greater than 0"));
+        }
+        if self.stop.iter().any(String::is_empty) {
+            return Err(anyhow!("stop sequences must not be empty"));
+        }
+        if let Some((token, _)) = self
+            .logit_bias
+            .iter()
+            .find(|(_, bias)| !(-100.0..=100.0).contains(*bias))
+        {
+            return Err(anyhow!("logit_bias for token {} must be between -100 and 100", token));
+        }
+        Ok(())
+    }
+
+    /// llama.cpp command line arguments for these settings
+    pub fn llama_args(&self) -> Vec<String> {
+        let mut args = vec!["--temp".to_string(), self.temperature.to_string()];
+        if let Some(k) = self.top_k {
+            args.extend(["--top-k".to_string(), k.to_string()]);
+        }
+        if let Some(p) = self.top_p {
+            args.extend(["--top-p".to_string(), p.to_string()]);
+        }
+        if let Some(p) = self.min_p {
+            args.extend(["--min-p".to_string(), p.to_string()]);
+        }
+        if let Some(penalty) = self.repeat_penalty {
+            args.extend(["--repeat-penalty".to_string(), penalty.to_string()]);
+        }
+        for (token, bias) in &self.logit_bias {
+            // A bias of -100 bans the token outright
+            let bias = if *bias <= -100.0 {
+                "-inf".to_string()
+            } else {
+                format!("{:+}", bias)
+            };
+            args.extend(["--logit-bias".to_string(), format!("{}{}", token, bias)]);
+        }
+        args
+    }
+
+    /// `text` cut before the first stop sequence
+    pub fn truncate_at_stop<'a>(&self, text: &'a str) -> &'a str {
+        let mut scanner = StopScanner::new(&self.stop);
+        scanner.push(text);
+        &text[..scanner.text().len()]
+    }
+}
+
+/// Finds stop sequences in text that is generated piece by piece
+pub struct StopScanner<'a> {
+    stop: &'a [String],
+    text: String,
+    emitted: usize,
+    stopped: bool,
+}
+
+impl<'a> StopScanner<'a> {
+    pub fn new(stop: &'a [String]) -> Self {
+        Self {
+            stop,
+            text: String::new(),
+            emitted: 0,
+            stopped: false,
+        }
+    }
+
+    /// Add a generated piece and return the text that can be shown so far.
+    /// A tail that could still grow into a stop sequence is held back until
+    /// later pieces settle it; nothing is returned once a stop sequence has
+    /// been found.
+    pub fn push(&mut self, piece: &str) -> &str {
+        let start = self.emitted;
+        if self.stopped {
+            return "";
+        }
+        self.text.push_str(piece);
+
+        // Anything before `start` was already checked and released
+        let found = self
+            .stop
+            .iter()
+            .filter_map(|stop| self.text[start..].find(stop.as_str()))
+            .min();
+        if let Some(at) = found {
+            self.text.truncate(start + at);
+            self.stopped = true;
+        }
+
+        let held = if self.stopped {
+            0
+        } else {
+            self.stop
+                .iter()
+                .filter_map(|stop| {
+                    (1..stop.len())
+                        .rev()
+                        .filter(|&len| stop.is_char_boundary(len))
+                        .find(|&len| self.text.ends_with(&stop[..len]))
+                })
+                .max()
+                .unwrap_or(0)
+        };
+        self.emitted = self.text.len() - held;
+        &self.text[start..self.emitted]
+    }
+
+    /// Whether a stop sequence has been generated
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Release the held back tail once generation has ended
+    pub fn finish(&mut self) -> &str {
+        let start = self.emitted;
+        self.emitted = self.text.len();
+        &self.text[start..]
+    }
+
+    /// Everything generated before the first stop sequence
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
 /// GGUF inference engine
 pub struct GGUFEngine {
     config: GGUFEngineConfig,
@@ -82,24 +295,57 @@ impl GGUFEngine {
         max_tokens: usize,
         temperature: f32,
     ) -> Result<String> {
+        self.generate_text_seeded(model_path, prompt, max_tokens, temperature, None)
+            .await
+    }
+
+    /// Execute text generation inference with a fixed sampling seed, so the
+    /// same model, prompt and seed reproduce the same text
+    pub async fn generate_text_seeded(
+        &self,
+        model_path: &Path,
+        prompt: &str,
+        max_tokens: usize,
+        temperature: f32,
+        seed: Option<u64>,
+    ) -> Result<String> {
+        let params = SamplingParams::with_temperature(temperature);
+        self.generate_text_with(model_path, prompt, max_tokens, &params, seed)
+            .await
+    }
+
+    /// Execute text generation inference with explicit sampler settings.
+    /// The output is cut before the first of the stop sequences.
+    pub async fn generate_text_with(
+        &self,
+        model_path: &Path,
+        prompt: &str,
+        max_tokens: usize,
+        params: &SamplingParams,
+        seed: Option<u64>,
+    ) -> Result<String> {
+        params.validate()?;
         info!(
-            "Generating text with model: {:?}, max_tokens: {}, temp: {}",
-            model_path, max_tokens, temperature
+            "Generating text with model: {:?}, max_tokens: {}, sampling: {:?}, seed: {:?}",
+            model_path, max_tokens, params, seed
         );
 
         // Find llama.cpp binary (try both old and new names)
         let binary = self.find_llama_binary("llama-cli", "main")?;
 
         // Build command
-        let output = Command::new(binary)
+        let mut command = Command::new(binary);
+        if let Some(seed) = seed {
+            command.arg("--seed").arg(seed.to_string());
+        }
+        let output = command
             .arg("-m")
             .arg(model_path)
             .arg("-p")
             .arg(prompt)
             .arg("-n")
             .arg(max_tokens.to_string())
-            .arg("--temp")
-            .arg(temperature.to_string())
+            .args(params.llama_args())
             .arg("-t")
             .arg(self.config.threads.to_string())
             .arg("-c")
@@ -114,7 +360,7 @@ impl GGUFEngine {
         }
 
         let text = String::from_utf8_lossy(&output.stdout);
-        Ok(text.trim().to_string())
+        Ok(params.truncate_at_stop(&text).trim().to_string())
     }
 
     /// Execute embedding inference
@@ -321,4 +567,57 @@ mod tests {
         assert!(prompt.contains("Hello"));
         assert!(prompt.contains("### Assistant:"));
     }
+
+    #[test]
+    fn test_sampling_params_from_json() {
+        let params = SamplingParams::from_json(&serde_json::json!({
+            "prompt": "ignored",
+            "temperature": 0.2,
+            "top_k": 40,
+            "repetition_penalty": 1.1,
+            "stop": "###",
+            "logit_bias": { "15043": -100, "7": 2.5 },
+        }))
+        .unwrap();
+        assert_eq!(params.top_k, Some(40));
+        assert_eq!(params.repeat_penalty, Some(1.1));
+        assert_eq!(params.stop, vec!["###".to_string()]);
+
+        let args = params.llama_args();
+        assert!(args.windows(2).any(|a| a == ["--top-k", "40"]));
+        assert!(args.windows(2).any(|a| a == ["--logit-bias", "7+2.5"]));
+        assert!(args.windows(2).any(|a| a == ["--logit-bias", "15043-inf"]));
+        assert!(!args.iter().any(|a| a == "--top-p"));
+
+        assert!(SamplingParams::from_json(&serde_json::json!({ "top_p": 0.0 })).is_err());
+        assert!(SamplingParams::from_json(&serde_json::json!({ "stop": [""] })).is_err());
+        assert!(SamplingParams::from_json(&serde_json::json!({ "logit_bias": { "1": 101 } }))
+            .is_err());
+    }
+
+    #[test]
+    fn test_stop_scanner_holds_back_partial_matches() {
+        let stop = vec!["</s>".to_string(), "\n\nUser:".to_string()];
+        let mut scanner = StopScanner::new(&stop);
+        assert_eq!(scanner.push("Hello <"), "Hello ");
+        assert_eq!(scanner.push("b> there"), "<b> there");
+        assert_eq!(scanner.push("\n\nUs"), "");
+        assert_eq!(scanner.push("ed to"), "\n\nUsed to");
+        assert_eq!(scanner.push(" it <"), " it ");
+        assert!(!scanner.is_stopped());
+        assert_eq!(scanner.finish(), "<");
+
+        let mut scanner = StopScanner::new(&stop);
+        assert_eq!(scanner.push("Done.</"), "Done.");
+        assert_eq!(scanner.push("s> more"), "");
+        assert!(scanner.is_stopped());
+        assert_eq!(scanner.finish(), "");
+        assert_eq!(scanner.text(), "Done.");
+
+        let params = SamplingParams {
+            stop: stop.clone(),
+            ..Default::default()
+        };
+        assert_eq!(params.truncate_at_stop("a\n\nUser: b"), "a");
+    }
 }
@@ -6,14 +6,18 @@ pub mod execution;
 pub mod gguf_engine;
 pub mod provider;
 pub mod registry;
+pub mod residency;
+pub mod safety;
+pub mod slo;
 pub mod types;
 pub mod verification;
 
-use crate::types::{ModelId, ModelMetadata};
-use citrate_execution::Address;
+use crate::types::{ExecutionProof, ModelId, ModelMetadata};
+use crate::verification::{ChallengeId, ChallengeResolution, ChallengeStatus};
+use citrate_execution::{Address, RoyaltySchedule};
 use citrate_storage::ipfs::IPFSService;
 use std::sync::Arc;
-use tracing::info;
+use tracing::{debug, info};
 
 /// MCP Service coordinator
 pub struct MCPService {
@@ -21,6 +25,8 @@ pub struct MCPService {
     pub provider_registry: Arc<provider::ProviderRegistry>,
     pub executor: Arc<execution::ModelExecutor>,
     pub verifier: Arc<verification::ExecutionVerifier>,
+    /// Latency SLO inference jobs are held to before failing over
+    pub slo: slo::LatencySlo,
 }
 
 impl MCPService {
@@ -30,17 +36,20 @@ impl MCPService {
     ) -> Self {
         let model_registry = Arc::new(registry::ModelRegistry::new(storage.clone()));
         let provider_registry = Arc::new(provider::ProviderRegistry::new());
-        let cache = Arc::new(cache::ModelCache::new(1024 * 1024 * 1024)); // 1GB cache
+        let models = Arc::new(residency::ResidencyManager::new(
+            residency::ResidencyConfig::from_env("CITRATE_MCP_MODEL_MEMORY_MB"),
+        ));
         let verifier = Arc::new(verification::ExecutionVerifier::new());
         let ipfs_endpoint = std::env::var("CITRATE_IPFS_API")
             .unwrap_or_else(|_| "http://127.0.0.1:5001".to_string());
         let ipfs_service = IPFSService::new(ipfs_endpoint);
         let executor = Arc::new(execution::ModelExecutor::new(
             vm,
-            cache,
+            models,
             verifier.clone(),
             model_registry.clone(),
             ipfs_service,
+            cache::InferenceCacheConfig::from_env("CITRATE_MCP_INFERENCE_CACHE_MB"),
         ));
 
         info!("MCP Service initialized");
@@ -50,6 +59,7 @@ impl MCPService {
             provider_registry,
             executor,
             verifier,
+            slo: slo::LatencySlo::from_env("CITRATE_MCP_INFERENCE_SLO_MS"),
         }
     }
 
@@ -75,6 +85,14 @@ impl MCPService {
             .await
     }
 
+    pub async fn set_model_royalty(
+        &self,
+        model_id: ModelId,
+        royalty: RoyaltySchedule,
+    ) -> anyhow::Result<()> {
+        self.model_registry.set_royalty(&model_id, royalty).await
+    }
+
     /// Execute model inference
     pub async fn execute_inference(
         &self,
@@ -86,4 +104,157 @@ impl MCPService {
             .execute_inference(model_id, input, provider)
             .await
     }
+
+    /// Execute model inference under the latency SLO, re-dispatching to the
+    /// next ranked provider when one fails or misses the deadline. Models
+    /// with no registered providers run on `local_provider`. Abandoned
+    /// attempts count against the provider and are listed in the result.
+    pub async fn execute_inference_with_failover(
+        &self,
+        model_id: ModelId,
+        input: Vec<u8>,
+        local_provider: Address,
+    ) -> anyhow::Result<execution::InferenceResult> {
+        let record = self.model_registry.get_record(&model_id).await?;
+        let providers = self
+            .provider_registry
+            .ranked_providers(&model_id, &record.metadata.compute_requirements)
+            .await
+            .unwrap_or_else(|_| vec![local_provider]);
+        self.dispatch_with_failover(model_id, input, &providers).await
+    }
+
+    /// Execute model inference on the market: providers whose bids are
+    /// within the ask's max price are tried best first, with the same SLO
+    /// failover as `execute_inference_with_failover`. The serving bid's
+    /// capacity is drawn down by the ask's token budget and its price is
+    /// returned in the result.
+    pub async fn execute_inference_at_price(
+        &self,
+        ask: provider::InferenceAsk,
+        input: Vec<u8>,
+    ) -> anyhow::Result<execution::InferenceResult> {
+        let record = self.model_registry.get_record(&ask.model_id).await?;
+        let bids = self
+            .provider_registry
+            .matching_bids(&ask, &record.metadata.compute_requirements)
+            .await?;
+        let providers: Vec<Address> = bids.iter().map(|bid| bid.provider).collect();
+
+        let mut result = self
+            .dispatch_with_failover(ask.model_id, input, &providers)
+            .await?;
+        if let Some(bid) = bids.iter().find(|bid| bid.provider == result.provider) {
+            self.provider_registry
+                .fill_bid(&bid.provider, &ask.model_id, ask.max_tokens)
+                .await?;
+            result.price_per_token = Some(bid.price_per_token);
+        }
+        Ok(result)
+    }
+
+    /// Run a job on `providers` in order until one serves it within the SLO
+    async fn dispatch_with_failover(
+        &self,
+        model_id: ModelId,
+        input: Vec<u8>,
+        providers: &[Address],
+    ) -> anyhow::Result<execution::InferenceResult> {
+        let slo = self.slo.for_request(&input);
+
+        let outcome = slo::dispatch(providers, &slo, |provider| {
+            self.executor
+                .execute_inference(model_id, input.clone(), provider)
+        })
+        .await;
+
+        for failover in &outcome.failovers {
+            let recorded = match failover.reason {
+                slo::FailoverReason::DeadlineExceeded => {
+                    self.provider_registry
+                        .record_deadline_miss(failover.provider, failover.elapsed_ms)
+                        .await
+                }
+                slo::FailoverReason::Failed(_) => {
+                    self.provider_registry
+                        .update_reputation(failover.provider, false, failover.elapsed_ms)
+                        .await
+                }
+            };
+            // The local provider may not be registered
+            if let Err(e) = recorded {
+                debug!("Failed to record failover: {}", e);
+            }
+        }
+
+        let (provider, mut result) = outcome.served.ok_or_else(|| {
+            anyhow::anyhow!(
+                "Inference failed on {} provider(s) within the {}ms SLO",
+                outcome.failovers.len(),
+                slo.deadline.as_millis()
+            )
+        })?;
+        let _ = self
+            .provider_registry
+            .update_reputation(provider, true, result.latency_ms)
+            .await;
+        result.failovers = outcome.failovers;
+        Ok(result)
+    }
+
+    /// Dispute a provider's result with the challenger's own re-execution
+    pub async fn challenge_execution(
+        &self,
+        claim: ExecutionProof,
+        counter_proof: ExecutionProof,
+    ) -> anyhow::Result<ChallengeId> {
+        self.provider_registry
+            .get_provider(&counter_proof.provider)
+            .await?;
+        self.verifier.open_challenge(claim, counter_proof).await
+    }
+
+    /// Add a registered provider's re-execution to an open challenge
+    pub async fn submit_reexecution(
+        &self,
+        challenge_id: ChallengeId,
+        proof: ExecutionProof,
+    ) -> anyhow::Result<()> {
+        self.provider_registry.get_provider(&proof.provider).await?;
+        self.verifier.submit_reexecution(&challenge_id, proof).await
+    }
+
+    /// Resolve a challenge and slash whichever side the majority ruled against
+    pub async fn resolve_challenge(
+        &self,
+        challenge_id: ChallengeId,
+    ) -> anyhow::Result<ChallengeResolution> {
+        let resolution = self.verifier.resolve_challenge(&challenge_id).await?;
+        let config = self.verifier.config();
+        let challenge = hex::encode(&challenge_id.0[..8]);
+
+        match resolution.status {
+            ChallengeStatus::ProviderAtFault => {
+                self.provider_registry
+                    .slash(
+                        resolution.provider,
+                        config.provider_slash_bps,
+                        &format!("fraud proven by challenge {}", challenge),
+                    )
+                    .await?;
+            }
+            ChallengeStatus::Rejected => {
+                self.provider_registry
+                    .slash(
+                        resolution.challenger,
+                        config.challenger_slash_bps,
+                        &format!("rejected challenge {}", challenge),
+                    )
+                    .await?;
+            }
+            ChallengeStatus::Inconclusive | ChallengeStatus::Open => {}
+        }
+
+        Ok(resolution)
+    }
 }
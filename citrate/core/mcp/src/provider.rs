@@ -4,16 +4,26 @@
 use crate::types::{ComputeCapacity, HardwareType, ModelId, ProviderInfo};
 use anyhow::Result;
 use citrate_execution::Address;
+use primitive_types::U256;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+
+/// Stake a provider must keep bonded to be offered work (1,000 SALT in wei)
+pub fn min_provider_stake() -> U256 {
+    U256::exp10(21)
+}
 
 /// Provider registry for compute providers
 pub struct ProviderRegistry {
     providers: Arc<RwLock<HashMap<Address, ProviderInfo>>>,
     model_providers: Arc<RwLock<HashMap<ModelId, Vec<Address>>>>,
     reputation_scores: Arc<RwLock<HashMap<Address, ReputationScore>>>,
+    stakes: Arc<RwLock<HashMap<Address, U256>>>,
+    slashes: Arc<RwLock<Vec<SlashRecord>>>,
+    bids: Arc<RwLock<HashMap<ModelId, Vec<ProviderBid>>>>,
 }
 
 impl Default for ProviderRegistry {
@@ -22,6 +32,9 @@ impl Default for ProviderRegistry {
             providers: Arc::new(RwLock::new(HashMap::new())),
             model_providers: Arc::new(RwLock::new(HashMap::new())),
             reputation_scores: Arc::new(RwLock::new(HashMap::new())),
+            stakes: Arc::new(RwLock::new(HashMap::new())),
+            slashes: Arc::new(RwLock::new(Vec::new())),
+            bids: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 }
@@ -34,6 +47,43 @@ pub struct ReputationScore {
     pub average_latency: u64,
     pub uptime_percentage: f64,
     pub last_active: u64,
+    pub slash_count: u64,
+    /// Jobs moved to a backup provider after missing their latency SLO
+    pub deadline_misses: u64,
+    /// Slashed below the minimum stake; not offered work until topped up
+    pub jailed: bool,
+}
+
+/// A stake penalty applied to a provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashRecord {
+    pub provider: Address,
+    pub amount: U256,
+    pub remaining_stake: U256,
+    pub reason: String,
+    pub timestamp: u64,
+}
+
+/// Capacity a provider offers for a model at a fixed price
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderBid {
+    pub provider: Address,
+    pub model_id: ModelId,
+    /// Price per generated token in wei
+    pub price_per_token: U256,
+    /// Tokens still available at this price; filled jobs draw it down
+    pub capacity_tokens: u64,
+    pub posted_at: u64,
+}
+
+/// What an inference request is willing to pay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InferenceAsk {
+    pub model_id: ModelId,
+    /// Highest acceptable price per token in wei
+    pub max_price_per_token: U256,
+    /// Tokens the job may generate; bids with less capacity are skipped
+    pub max_tokens: u64,
 }
 
 impl ProviderRegistry {
@@ -42,6 +92,9 @@ impl ProviderRegistry {
             providers: Arc::new(RwLock::new(HashMap::new())),
             model_providers: Arc::new(RwLock::new(HashMap::new())),
             reputation_scores: Arc::new(RwLock::new(HashMap::new())),
+            stakes: Arc::new(RwLock::new(HashMap::new())),
+            slashes: Arc::new(RwLock::new(Vec::new())),
+            bids: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -57,6 +110,9 @@ impl ProviderRegistry {
             average_latency: 0,
             uptime_percentage: 100.0,
             last_active: chrono::Utc::now().timestamp() as u64,
+            slash_count: 0,
+            deadline_misses: 0,
+            jailed: false,
         };
 
         self.providers.write().await.insert(address, info.clone());
@@ -85,6 +141,9 @@ impl ProviderRegistry {
         if !self.providers.read().await.contains_key(&provider) {
             return Err(anyhow::anyhow!("Provider not registered"));
         }
+        if self.is_jailed(&provider).await {
+            return Err(anyhow::anyhow!("Provider is jailed"));
+        }
 
         // Add to model providers
         let mut model_providers = self.model_providers.write().await;
@@ -108,6 +167,16 @@ impl ProviderRegistry {
         model_id: &ModelId,
         requirements: &crate::types::ComputeRequirements,
     ) -> Result<Address> {
+        Ok(self.ranked_providers(model_id, requirements).await?[0])
+    }
+
+    /// Providers able to serve a model, best first; the ones after the
+    /// first are backups for failover
+    pub async fn ranked_providers(
+        &self,
+        model_id: &ModelId,
+        requirements: &crate::types::ComputeRequirements,
+    ) -> Result<Vec<Address>> {
         // Get available providers for model
         let model_providers = self.model_providers.read().await;
         let providers = model_providers
@@ -122,6 +191,14 @@ impl ProviderRegistry {
 
         for provider_addr in providers {
             if let Some(info) = provider_infos.get(provider_addr) {
+                // Skip providers jailed for misbehaviour
+                if reputation_scores
+                    .get(provider_addr)
+                    .is_some_and(|rep| rep.jailed)
+                {
+                    continue;
+                }
+
                 // Check if provider meets requirements
                 if !self.meets_requirements(&info.capacity, requirements) {
                     continue;
@@ -139,10 +216,144 @@ impl ProviderRegistry {
             return Err(anyhow::anyhow!("No suitable providers available"));
         }
 
-        // Sort by score and select best
+        // Sort by score, best first
         candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
 
-        Ok(candidates[0].0)
+        Ok(candidates.into_iter().map(|(address, _)| address).collect())
+    }
+
+    /// Post or replace a provider's bid for a model. The provider must be
+    /// registered to serve the model and not jailed.
+    pub async fn post_bid(&self, bid: ProviderBid) -> Result<()> {
+        if !self.providers.read().await.contains_key(&bid.provider) {
+            return Err(anyhow::anyhow!("Provider not registered"));
+        }
+        if self.is_jailed(&bid.provider).await {
+            return Err(anyhow::anyhow!("Provider is jailed"));
+        }
+        if !self
+            .model_providers
+            .read()
+            .await
+            .get(&bid.model_id)
+            .is_some_and(|providers| providers.contains(&bid.provider))
+        {
+            return Err(anyhow::anyhow!("Provider does not serve this model"));
+        }
+        if bid.price_per_token.is_zero() || bid.capacity_tokens == 0 {
+            return Err(anyhow::anyhow!("Bid needs a price and capacity"));
+        }
+
+        debug!(
+            "Provider {} bid {} wei/token for {} tokens of model {}",
+            hex::encode(&bid.provider.0[..8]),
+            bid.price_per_token,
+            bid.capacity_tokens,
+            hex::encode(&bid.model_id.0[..8])
+        );
+
+        let mut bids = self.bids.write().await;
+        let book = bids.entry(bid.model_id).or_default();
+        book.retain(|existing| existing.provider != bid.provider);
+        book.push(bid);
+        Ok(())
+    }
+
+    /// Withdraw a provider's bid for a model
+    pub async fn withdraw_bid(&self, provider: &Address, model_id: &ModelId) -> bool {
+        let mut bids = self.bids.write().await;
+        let Some(book) = bids.get_mut(model_id) else {
+            return false;
+        };
+        let before = book.len();
+        book.retain(|bid| bid.provider != *provider);
+        book.len() != before
+    }
+
+    /// Open bids for a model, cheapest first
+    pub async fn bids_for_model(&self, model_id: &ModelId) -> Vec<ProviderBid> {
+        let mut bids = self
+            .bids
+            .read()
+            .await
+            .get(model_id)
+            .cloned()
+            .unwrap_or_default();
+        bids.sort_by_key(|bid| bid.price_per_token);
+        bids
+    }
+
+    /// Bids that can take an ask, best first. Reputation is weighted by how
+    /// far under the ask's max price a bid is, so a cheap provider with a
+    /// poor record doesn't beat a reliable one asking slightly more.
+    pub async fn matching_bids(
+        &self,
+        ask: &InferenceAsk,
+        requirements: &crate::types::ComputeRequirements,
+    ) -> Result<Vec<ProviderBid>> {
+        let bids = self.bids.read().await;
+        let book = bids
+            .get(&ask.model_id)
+            .ok_or_else(|| anyhow::anyhow!("No bids for model"))?;
+
+        let provider_infos = self.providers.read().await;
+        let reputation_scores = self.reputation_scores.read().await;
+
+        let mut candidates: Vec<(ProviderBid, f64)> = Vec::new();
+        for bid in book {
+            if bid.price_per_token > ask.max_price_per_token || bid.capacity_tokens < ask.max_tokens
+            {
+                continue;
+            }
+            let Some(info) = provider_infos.get(&bid.provider) else {
+                continue;
+            };
+            let reputation = reputation_scores.get(&bid.provider);
+            if reputation.is_some_and(|rep| rep.jailed)
+                || !self.meets_requirements(&info.capacity, requirements)
+            {
+                continue;
+            }
+
+            // 0 at the max price, 1 when free
+            let price_bps =
+                (bid.price_per_token * U256::from(10_000u64) / ask.max_price_per_token).low_u64();
+            let discount = 1.0 - price_bps as f64 / 10_000.0;
+            let score =
+                self.calculate_provider_score(&info.capacity, reputation) * (1.0 + discount);
+            candidates.push((bid.clone(), score));
+        }
+
+        if candidates.is_empty() {
+            return Err(anyhow::anyhow!("No bids within the asked price"));
+        }
+
+        candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        Ok(candidates.into_iter().map(|(bid, _)| bid).collect())
+    }
+
+    /// Draw down a bid's capacity after it served a job, removing it once
+    /// exhausted. Returns the capacity left.
+    pub async fn fill_bid(
+        &self,
+        provider: &Address,
+        model_id: &ModelId,
+        tokens: u64,
+    ) -> Result<u64> {
+        let mut bids = self.bids.write().await;
+        let book = bids
+            .get_mut(model_id)
+            .ok_or_else(|| anyhow::anyhow!("No bids for model"))?;
+        let bid = book
+            .iter_mut()
+            .find(|bid| bid.provider == *provider)
+            .ok_or_else(|| anyhow::anyhow!("Provider has no bid for model"))?;
+        bid.capacity_tokens = bid.capacity_tokens.saturating_sub(tokens);
+        let remaining = bid.capacity_tokens;
+        if remaining == 0 {
+            book.retain(|bid| bid.provider != *provider);
+        }
+        Ok(remaining)
     }
 
     /// Update provider reputation
@@ -178,6 +389,124 @@ impl ProviderRegistry {
         Ok(())
     }
 
+    /// Count a job the provider didn't finish within its latency SLO as a
+    /// failed job that took `elapsed_ms`
+    pub async fn record_deadline_miss(&self, provider: Address, elapsed_ms: u64) -> Result<()> {
+        self.update_reputation(provider, false, elapsed_ms).await?;
+        if let Some(score) = self.reputation_scores.write().await.get_mut(&provider) {
+            score.deadline_misses += 1;
+        }
+        Ok(())
+    }
+
+    /// Bond stake for a provider, releasing it from jail once the minimum is met
+    pub async fn deposit_stake(&self, provider: Address, amount: U256) -> Result<U256> {
+        if !self.providers.read().await.contains_key(&provider) {
+            return Err(anyhow::anyhow!("Provider not registered"));
+        }
+
+        let mut stakes = self.stakes.write().await;
+        let stake = stakes.entry(provider).or_default();
+        *stake = stake.saturating_add(amount);
+
+        if *stake >= min_provider_stake() {
+            if let Some(score) = self.reputation_scores.write().await.get_mut(&provider) {
+                score.jailed = false;
+            }
+        }
+
+        Ok(*stake)
+    }
+
+    /// Get bonded stake
+    pub async fn get_stake(&self, provider: &Address) -> U256 {
+        self.stakes
+            .read()
+            .await
+            .get(provider)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Slash `bps` basis points of a provider's stake. The slash counts as a
+    /// failed job, and a provider left below the minimum stake is jailed.
+    pub async fn slash(&self, provider: Address, bps: u64, reason: &str) -> Result<SlashRecord> {
+        if bps > 10_000 {
+            return Err(anyhow::anyhow!("Slash exceeds 100% of stake"));
+        }
+        if !self.providers.read().await.contains_key(&provider) {
+            return Err(anyhow::anyhow!("Provider not registered"));
+        }
+
+        let (amount, remaining_stake) = {
+            let mut stakes = self.stakes.write().await;
+            let stake = stakes.entry(provider).or_default();
+            let amount = *stake * U256::from(bps) / U256::from(10_000u64);
+            *stake -= amount;
+            (amount, *stake)
+        };
+
+        let jailed = remaining_stake < min_provider_stake();
+        let (reputation, total_jobs) = {
+            let mut scores = self.reputation_scores.write().await;
+            let score = scores
+                .get_mut(&provider)
+                .ok_or_else(|| anyhow::anyhow!("Provider not found"))?;
+            score.total_jobs += 1;
+            score.failed_jobs += 1;
+            score.slash_count += 1;
+            score.jailed = jailed;
+            (
+                score.successful_jobs * 100 / score.total_jobs,
+                score.total_jobs,
+            )
+        };
+
+        if let Some(info) = self.providers.write().await.get_mut(&provider) {
+            info.reputation = reputation;
+            info.total_executions = total_jobs;
+        }
+
+        let record = SlashRecord {
+            provider,
+            amount,
+            remaining_stake,
+            reason: reason.to_string(),
+            timestamp: chrono::Utc::now().timestamp() as u64,
+        };
+        self.slashes.write().await.push(record.clone());
+
+        warn!(
+            "Provider {} slashed {} ({}): {}",
+            hex::encode(&provider.0[..8]),
+            amount,
+            if jailed { "jailed" } else { "active" },
+            reason
+        );
+
+        Ok(record)
+    }
+
+    /// Slashes applied to a provider, oldest first
+    pub async fn slash_history(&self, provider: &Address) -> Vec<SlashRecord> {
+        self.slashes
+            .read()
+            .await
+            .iter()
+            .filter(|record| record.provider == *provider)
+            .cloned()
+            .collect()
+    }
+
+    /// Whether a provider is jailed
+    pub async fn is_jailed(&self, provider: &Address) -> bool {
+        self.reputation_scores
+            .read()
+            .await
+            .get(provider)
+            .is_some_and(|rep| rep.jailed)
+    }
+
     /// Check if provider meets requirements
     fn meets_requirements(
         &self,
@@ -259,3 +588,129 @@ impl ProviderRegistry {
         self.providers.read().await.values().cloned().collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider_info(address: Address) -> ProviderInfo {
+        ProviderInfo {
+            address,
+            name: "provider".to_string(),
+            endpoint: "http://localhost:8080".to_string(),
+            capacity: ComputeCapacity {
+                total_memory: 16 << 30,
+                available_memory: 16 << 30,
+                total_compute: 100,
+                available_compute: 100,
+                hardware: vec![HardwareType::CPU],
+            },
+            reputation: 100,
+            total_executions: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slash_jails_provider_below_min_stake() {
+        let registry = ProviderRegistry::new();
+        let provider = Address([1; 20]);
+        registry
+            .register_provider(provider_info(provider))
+            .await
+            .unwrap();
+        registry
+            .deposit_stake(provider, min_provider_stake())
+            .await
+            .unwrap();
+
+        let record = registry.slash(provider, 1_000, "fraud").await.unwrap();
+        assert_eq!(record.amount, min_provider_stake() / 10);
+        assert!(registry.is_jailed(&provider).await);
+        assert_eq!(
+            registry.get_provider(&provider).await.unwrap().reputation,
+            0
+        );
+        assert!(registry
+            .register_model_provider(provider, ModelId([2; 32]))
+            .await
+            .is_err());
+
+        registry
+            .deposit_stake(provider, record.amount)
+            .await
+            .unwrap();
+        assert!(!registry.is_jailed(&provider).await);
+        assert_eq!(registry.slash_history(&provider).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_matching_bids_weighs_price_and_reputation() {
+        let registry = ProviderRegistry::new();
+        let model_id = ModelId([9; 32]);
+        let (cheap, reliable, pricey) = (Address([1; 20]), Address([2; 20]), Address([3; 20]));
+        for provider in [cheap, reliable, pricey] {
+            registry
+                .register_provider(provider_info(provider))
+                .await
+                .unwrap();
+            registry
+                .register_model_provider(provider, model_id)
+                .await
+                .unwrap();
+        }
+        for _ in 0..4 {
+            registry.update_reputation(cheap, false, 100).await.unwrap();
+        }
+
+        let bid = |provider, price: u64, capacity_tokens| ProviderBid {
+            provider,
+            model_id,
+            price_per_token: U256::from(price),
+            capacity_tokens,
+            posted_at: 0,
+        };
+        registry.post_bid(bid(cheap, 10, 1_000)).await.unwrap();
+        registry.post_bid(bid(reliable, 12, 1_000)).await.unwrap();
+        registry.post_bid(bid(pricey, 50, 1_000)).await.unwrap();
+        assert!(registry
+            .post_bid(bid(Address([4; 20]), 10, 1_000))
+            .await
+            .is_err());
+
+        let requirements = crate::types::ComputeRequirements {
+            min_memory: 0,
+            min_compute: 0,
+            gpu_required: false,
+            supported_hardware: vec![],
+        };
+        let ask = InferenceAsk {
+            model_id,
+            max_price_per_token: U256::from(20u64),
+            max_tokens: 600,
+        };
+        let matched: Vec<Address> = registry
+            .matching_bids(&ask, &requirements)
+            .await
+            .unwrap()
+            .iter()
+            .map(|bid| bid.provider)
+            .collect();
+        assert_eq!(matched, vec![reliable, cheap]);
+
+        // The second job no longer fits the reliable provider's capacity
+        assert_eq!(
+            registry.fill_bid(&reliable, &model_id, 600).await.unwrap(),
+            400
+        );
+        let matched = registry.matching_bids(&ask, &requirements).await.unwrap();
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].provider, cheap);
+
+        assert_eq!(
+            registry.fill_bid(&cheap, &model_id, 1_000).await.unwrap(),
+            0
+        );
+        assert!(registry.matching_bids(&ask, &requirements).await.is_err());
+        assert_eq!(registry.bids_for_model(&model_id).await.len(), 2);
+    }
+}
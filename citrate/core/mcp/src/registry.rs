@@ -3,7 +3,7 @@
 // Model registry for tracking AI models
 use crate::types::{ExecutionRequest, ModelId, ModelMetadata, RequestId, RequestStatus};
 use anyhow::Result;
-use citrate_execution::{Address, Hash};
+use citrate_execution::{Address, Hash, RoyaltySchedule};
 use citrate_storage::StorageManager;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -28,6 +28,8 @@ pub struct ModelRecord {
     pub average_latency: u64,
     pub success_rate: f64,
     pub weight_cid: Option<String>,
+    /// Split of pay-per-use inference fees, mirrored from the chain
+    pub royalty: RoyaltySchedule,
 }
 
 impl ModelRegistry {
@@ -67,6 +69,7 @@ impl ModelRegistry {
             average_latency: 0,
             success_rate: 100.0,
             weight_cid: weight_cid.clone(),
+            royalty: RoyaltySchedule::default(),
         };
 
         // Store in memory
@@ -120,6 +123,24 @@ impl ModelRegistry {
         Ok(())
     }
 
+    /// Update the royalty schedule applied to a model's inference fees
+    pub async fn set_royalty(&self, model_id: &ModelId, royalty: RoyaltySchedule) -> Result<()> {
+        if !royalty.is_valid() {
+            return Err(anyhow::anyhow!("Royalty shares must add up to 10000 bps"));
+        }
+        {
+            let mut models = self.models.write().await;
+            let record = models
+                .get_mut(model_id)
+                .ok_or_else(|| anyhow::anyhow!("Model not found"))?;
+            record.royalty = royalty;
+        }
+
+        let record = self.get_record(model_id).await?;
+        self.persist_model(model_id, &record).await?;
+        Ok(())
+    }
+
     /// Fetch stored weight CID if present
     pub async fn get_weight_cid(&self, model_id: &ModelId) -> Result<Option<String>> {
         Ok(self
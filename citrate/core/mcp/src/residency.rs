@@ -0,0 +1,480 @@
+// citrate/core/mcp/src/residency.rs
+
+// Memory-budgeted set of resident models shared by concurrent requests
+//
+// Any number of models can be loaded at once as long as their combined size
+// fits the budget. When a model does not fit, the least recently used models
+// that no request is holding are evicted to make room. A request for a model
+// that is already being (re)loaded waits for that load instead of starting
+// another, and a request that needs memory held by in-use models waits for
+// a release, up to the queue timeout.
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::hash::Hash;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tracing::{debug, info};
+
+/// Memory a resident model occupies
+pub trait ResidentSize {
+    fn resident_bytes(&self) -> u64;
+}
+
+/// Budget and queueing limits
+#[derive(Debug, Clone)]
+pub struct ResidencyConfig {
+    /// Combined size of resident models before eviction kicks in
+    pub budget_bytes: u64,
+    /// How long a request waits for a reload or for memory to be released
+    pub queue_timeout: Duration,
+}
+
+impl Default for ResidencyConfig {
+    fn default() -> Self {
+        Self {
+            budget_bytes: 8 * 1024 * 1024 * 1024,
+            queue_timeout: Duration::from_secs(120),
+        }
+    }
+}
+
+impl ResidencyConfig {
+    /// Default limits with the budget read, in megabytes, from the
+    /// environment variable `var` when it is set
+    pub fn from_env(var: &str) -> Self {
+        let mut config = Self::default();
+        if let Some(mb) = std::env::var(var).ok().and_then(|v| v.parse::<u64>().ok()) {
+            config.budget_bytes = mb * 1024 * 1024;
+        }
+        config
+    }
+}
+
+/// Residency statistics
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResidencyStats {
+    pub budget_bytes: u64,
+    pub used_bytes: u64,
+    pub resident_models: usize,
+    /// Resident models currently held by a request
+    pub in_use: usize,
+    /// Models being loaded
+    pub loading: usize,
+    pub hits: u64,
+    pub loads: u64,
+    pub evictions: u64,
+}
+
+struct Resident<T> {
+    model: Arc<T>,
+    bytes: u64,
+    last_used: u64,
+}
+
+impl<T> Resident<T> {
+    /// Whether a request holds a lease on the model
+    fn in_use(&self) -> bool {
+        Arc::strong_count(&self.model) > 1
+    }
+}
+
+struct State<K, T> {
+    resident: HashMap<K, Resident<T>>,
+    loading: HashSet<K>,
+    /// Expected size of models being loaded
+    reserved: u64,
+    used: u64,
+    clock: u64,
+    hits: u64,
+    loads: u64,
+    evictions: u64,
+}
+
+impl<K: Eq + Hash + Clone + std::fmt::Debug, T> State<K, T> {
+    fn touch(&mut self, key: &K) -> Option<Arc<T>> {
+        self.clock += 1;
+        let clock = self.clock;
+        let resident = self.resident.get_mut(key)?;
+        resident.last_used = clock;
+        self.hits += 1;
+        Some(resident.model.clone())
+    }
+
+    /// Evict idle models, least recently used first, until `bytes` more fit
+    /// in `budget`. Returns false if models in use hold too much memory.
+    fn make_room(&mut self, bytes: u64, budget: u64) -> bool {
+        while self.used + self.reserved + bytes > budget {
+            let victim = self
+                .resident
+                .iter()
+                .filter(|(_, r)| !r.in_use())
+                .min_by_key(|(_, r)| r.last_used)
+                .map(|(key, _)| key.clone());
+            match victim {
+                Some(key) => {
+                    if let Some(evicted) = self.resident.remove(&key) {
+                        self.used -= evicted.bytes;
+                        self.evictions += 1;
+                        info!(
+                            "Evicted model {:?} ({} bytes) under memory pressure",
+                            key, evicted.bytes
+                        );
+                    }
+                }
+                None => return false,
+            }
+        }
+        true
+    }
+}
+
+/// A resident model held by a request; it cannot be evicted while leased
+pub struct ModelLease<T> {
+    model: Option<Arc<T>>,
+    released: Arc<Notify>,
+}
+
+impl<T> ModelLease<T> {
+    /// Shared handle to the model, e.g. to move into a blocking task along
+    /// with the lease
+    pub fn model(&self) -> &Arc<T> {
+        self.model
+            .as_ref()
+            .expect("lease holds its model until dropped")
+    }
+}
+
+impl<T> Deref for ModelLease<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.model()
+    }
+}
+
+impl<T> Drop for ModelLease<T> {
+    fn drop(&mut self) {
+        // Drop the reference first so woken requests see the model as idle
+        self.model.take();
+        self.released.notify_waiters();
+    }
+}
+
+/// Removes a load's reservation if the loading request goes away
+struct LoadGuard<'a, K: Eq + Hash + Clone + std::fmt::Debug, T> {
+    manager: &'a ResidencyManager<K, T>,
+    key: K,
+    bytes: u64,
+}
+
+impl<K: Eq + Hash + Clone + std::fmt::Debug, T> Drop for LoadGuard<'_, K, T> {
+    fn drop(&mut self) {
+        {
+            let mut state = self.manager.lock();
+            state.loading.remove(&self.key);
+            state.reserved -= self.bytes;
+        }
+        self.manager.changed.notify_waiters();
+    }
+}
+
+/// Keeps models resident within a memory budget
+pub struct ResidencyManager<K, T> {
+    config: ResidencyConfig,
+    state: Mutex<State<K, T>>,
+    /// Signalled when a load finishes or a lease is released
+    changed: Arc<Notify>,
+}
+
+impl<K: Eq + Hash + Clone + std::fmt::Debug, T> ResidencyManager<K, T> {
+    pub fn new(config: ResidencyConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(State {
+                resident: HashMap::new(),
+                loading: HashSet::new(),
+                reserved: 0,
+                used: 0,
+                clock: 0,
+                hits: 0,
+                loads: 0,
+                evictions: 0,
+            }),
+            changed: Arc::new(Notify::new()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State<K, T>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lease(&self, model: Arc<T>) -> ModelLease<T> {
+        ModelLease {
+            model: Some(model),
+            released: self.changed.clone(),
+        }
+    }
+
+    /// Lease the model for `key`, loading it with `load` if it is not
+    /// resident. `expected_bytes` is the size the model is expected to
+    /// occupy, used to make room before loading.
+    pub async fn acquire<F, Fut>(
+        &self,
+        key: K,
+        expected_bytes: u64,
+        load: F,
+    ) -> Result<ModelLease<T>>
+    where
+        T: ResidentSize,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        if expected_bytes > self.config.budget_bytes {
+            return Err(anyhow!(
+                "Model {:?} needs {} bytes but the memory budget is {} bytes",
+                key,
+                expected_bytes,
+                self.config.budget_bytes
+            ));
+        }
+
+        let deadline = tokio::time::Instant::now() + self.config.queue_timeout;
+        loop {
+            // Register for wake-ups before checking, so none are missed
+            let changed = self.changed.notified();
+            tokio::pin!(changed);
+            changed.as_mut().enable();
+
+            {
+                let mut state = self.lock();
+                if let Some(model) = state.touch(&key) {
+                    return Ok(self.lease(model));
+                }
+                if !state.loading.contains(&key)
+                    && state.make_room(expected_bytes, self.config.budget_bytes)
+                {
+                    state.loading.insert(key.clone());
+                    state.reserved += expected_bytes;
+                    break;
+                }
+            }
+
+            debug!("Queued request for model {:?}", key);
+            if tokio::time::timeout_at(deadline, changed).await.is_err() {
+                return Err(anyhow!(
+                    "Timed out waiting for model {:?}: memory budget is held by models in use",
+                    key
+                ));
+            }
+        }
+
+        let mut guard = LoadGuard {
+            manager: self,
+            key: key.clone(),
+            bytes: expected_bytes,
+        };
+        let model = Arc::new(load().await?);
+        let bytes = model.resident_bytes();
+
+        let mut state = self.lock();
+        state.loading.remove(&key);
+        state.reserved -= expected_bytes;
+        guard.bytes = 0;
+        state.clock += 1;
+        let last_used = state.clock;
+        state.used += bytes;
+        state.loads += 1;
+        state.resident.insert(
+            key.clone(),
+            Resident {
+                model: model.clone(),
+                bytes,
+                last_used,
+            },
+        );
+        // The model may be larger than expected; trim idle models back
+        // under budget (the new one is leased, so it stays)
+        state.make_room(0, self.config.budget_bytes + expected_bytes);
+        drop(state);
+        drop(guard);
+
+        info!("Loaded model {:?} ({} bytes)", key, bytes);
+        Ok(self.lease(model))
+    }
+
+    /// Whether the model for `key` is resident
+    pub fn is_resident(&self, key: &K) -> bool {
+        self.lock().resident.contains_key(key)
+    }
+
+    /// Drop the model for `key` from the resident set. Requests holding a
+    /// lease keep it alive until they finish.
+    pub fn evict(&self, key: &K) -> bool {
+        let mut state = self.lock();
+        match state.resident.remove(key) {
+            Some(evicted) => {
+                state.used -= evicted.bytes;
+                state.evictions += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn stats(&self) -> ResidencyStats {
+        let state = self.lock();
+        ResidencyStats {
+            budget_bytes: self.config.budget_bytes,
+            used_bytes: state.used,
+            resident_models: state.resident.len(),
+            in_use: state.resident.values().filter(|r| r.in_use()).count(),
+            loading: state.loading.len(),
+            hits: state.hits,
+            loads: state.loads,
+            evictions: state.evictions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Blob(u64);
+
+    impl ResidentSize for Blob {
+        fn resident_bytes(&self) -> u64 {
+            self.0
+        }
+    }
+
+    fn manager(budget_bytes: u64) -> Arc<ResidencyManager<&'static str, Blob>> {
+        Arc::new(ResidencyManager::new(ResidencyConfig {
+            budget_bytes,
+            queue_timeout: Duration::from_millis(200),
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_evicts_least_recently_used() {
+        let models = manager(100);
+        drop(
+            models
+                .acquire("a", 40, || async { Ok(Blob(40)) })
+                .await
+                .unwrap(),
+        );
+        drop(
+            models
+                .acquire("b", 40, || async { Ok(Blob(40)) })
+                .await
+                .unwrap(),
+        );
+        // Touch "a" so "b" becomes the least recently used
+        drop(
+            models
+                .acquire("a", 40, || async { unreachable!() })
+                .await
+                .unwrap(),
+        );
+
+        drop(
+            models
+                .acquire("c", 40, || async { Ok(Blob(40)) })
+                .await
+                .unwrap(),
+        );
+        assert!(models.is_resident(&"a"));
+        assert!(!models.is_resident(&"b"));
+        assert!(models.is_resident(&"c"));
+
+        let stats = models.stats();
+        assert_eq!((stats.used_bytes, stats.resident_models), (80, 2));
+        assert_eq!((stats.hits, stats.loads, stats.evictions), (1, 3, 1));
+
+        assert!(models
+            .acquire("huge", 101, || async { Ok(Blob(101)) })
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_in_use_models_are_not_evicted() {
+        let models = manager(100);
+        let a = models
+            .acquire("a", 60, || async { Ok(Blob(60)) })
+            .await
+            .unwrap();
+        assert_eq!(a.resident_bytes(), 60);
+
+        // "b" has to wait until "a" is released
+        let waiter = {
+            let models = models.clone();
+            tokio::spawn(async move {
+                models
+                    .acquire("b", 60, || async { Ok(Blob(60)) })
+                    .await
+                    .map(|_| ())
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished());
+        assert_eq!(models.stats().in_use, 1);
+
+        drop(a);
+        waiter.await.unwrap().unwrap();
+        assert!(!models.is_resident(&"a"));
+        assert!(models.is_resident(&"b"));
+
+        // Held leases time out instead of waiting forever
+        let _b = models
+            .acquire("b", 60, || async { unreachable!() })
+            .await
+            .unwrap();
+        assert!(models
+            .acquire("c", 60, || async { Ok(Blob(60)) })
+            .await
+            .is_err());
+        assert_eq!(models.stats().loading, 0);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_share_one_load() {
+        let models = manager(100);
+        let loads = Arc::new(AtomicUsize::new(0));
+
+        let requests: Vec<_> = (0..4)
+            .map(|_| {
+                let models = models.clone();
+                let loads = loads.clone();
+                tokio::spawn(async move {
+                    models
+                        .acquire("a", 10, || async move {
+                            loads.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(20)).await;
+                            Ok(Blob(10))
+                        })
+                        .await
+                        .map(|lease| lease.resident_bytes())
+                })
+            })
+            .collect();
+        for request in requests {
+            assert_eq!(request.await.unwrap().unwrap(), 10);
+        }
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+        // A failed load releases its reservation
+        assert!(models
+            .acquire("bad", 50, || async { Err(anyhow!("corrupt weights")) })
+            .await
+            .is_err());
+        let stats = models.stats();
+        assert_eq!((stats.loading, stats.used_bytes), (0, 10));
+    }
+}
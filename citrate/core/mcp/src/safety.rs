@@ -0,0 +1,492 @@
+// citrate/core/mcp/src/safety.rs
+
+// Content safety pipeline
+//
+// Prompts and generated outputs are run through the classifiers a
+// deployment configures: keyword lists and, optionally, a small local guard
+// model (Llama Guard style GGUF) run through llama.cpp. A text is blocked
+// when any classifier flags a category the policy blocks. Blocked requests
+// are appended to a JSON lines audit log with a hash of the content, so
+// operators can show which requests were refused and why without keeping
+// the text itself unless `log_content` is set.
+use crate::gguf_engine::{GGUFEngine, GGUFEngineConfig, SamplingParams};
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+/// Category reported when a classifier fails and the policy fails closed
+pub const CLASSIFIER_ERROR_CATEGORY: &str = "classifier_error";
+
+/// Which side of a request is being checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SafetyStage {
+    Prompt,
+    Output,
+}
+
+/// Per-deployment safety configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SafetyPolicy {
+    pub enabled: bool,
+    pub check_prompts: bool,
+    pub check_outputs: bool,
+    /// Categories that cause a block; empty blocks every flagged category
+    pub blocked_categories: Vec<String>,
+    /// Phrases flagged per category, matched case-insensitively
+    pub keywords: BTreeMap<String, Vec<String>>,
+    /// GGUF guard model answering "safe" or "unsafe" plus categories
+    pub guard_model: Option<PathBuf>,
+    /// llama.cpp build directory used to run the guard model
+    pub llama_cpp_path: Option<PathBuf>,
+    /// Block when a classifier fails instead of letting the text through
+    pub fail_closed: bool,
+    /// Text returned in place of a blocked prompt's or output's response
+    pub block_message: String,
+    /// JSON lines file blocked requests are appended to
+    pub audit_log: Option<PathBuf>,
+    /// Record the blocked text in the audit log, not only its hash
+    pub log_content: bool,
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            check_prompts: true,
+            check_outputs: true,
+            blocked_categories: Vec::new(),
+            keywords: BTreeMap::new(),
+            guard_model: None,
+            llama_cpp_path: None,
+            fail_closed: false,
+            block_message: "This request was blocked by the provider's usage policy.".to_string(),
+            audit_log: None,
+            log_content: false,
+        }
+    }
+}
+
+impl SafetyPolicy {
+    /// Read a policy from a JSON file
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read safety policy {}", path.display()))?;
+        let policy: Self = serde_json::from_str(&data)
+            .with_context(|| format!("Invalid safety policy {}", path.display()))?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    /// Check the policy can be enforced
+    pub fn validate(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.keywords.is_empty() && self.guard_model.is_none() {
+            return Err(anyhow!(
+                "Safety policy is enabled but has no keywords or guard model"
+            ));
+        }
+        if let Some(model) = &self.guard_model {
+            if !model.exists() {
+                return Err(anyhow!("Guard model {} not found", model.display()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a flagged category is blocked under this policy
+    pub fn blocks(&self, category: &str) -> bool {
+        category == CLASSIFIER_ERROR_CATEGORY
+            || self.blocked_categories.is_empty()
+            || self
+                .blocked_categories
+                .iter()
+                .any(|blocked| blocked.eq_ignore_ascii_case(category))
+    }
+}
+
+/// Flags the categories a text falls under
+#[async_trait]
+pub trait SafetyClassifier: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Categories `text` is flagged under; empty when it is safe
+    async fn classify(&self, stage: SafetyStage, text: &str) -> Result<Vec<String>>;
+}
+
+/// Flags texts containing any of a category's phrases
+pub struct KeywordClassifier {
+    /// (category, lowercased phrases)
+    rules: Vec<(String, Vec<String>)>,
+}
+
+impl KeywordClassifier {
+    pub fn new(keywords: &BTreeMap<String, Vec<String>>) -> Self {
+        let rules = keywords
+            .iter()
+            .map(|(category, phrases)| {
+                let phrases = phrases
+                    .iter()
+                    .map(|p| p.trim().to_lowercase())
+                    .filter(|p| !p.is_empty())
+                    .collect();
+                (category.clone(), phrases)
+            })
+            .collect();
+        Self { rules }
+    }
+}
+
+#[async_trait]
+impl SafetyClassifier for KeywordClassifier {
+    fn name(&self) -> &str {
+        "keywords"
+    }
+
+    async fn classify(&self, _stage: SafetyStage, text: &str) -> Result<Vec<String>> {
+        let text = text.to_lowercase();
+        Ok(self
+            .rules
+            .iter()
+            .filter(|(_, phrases)| phrases.iter().any(|p| text.contains(p.as_str())))
+            .map(|(category, _)| category.clone())
+            .collect())
+    }
+}
+
+/// Classifies with a local guard model
+pub struct LocalModelClassifier {
+    engine: GGUFEngine,
+    model_path: PathBuf,
+}
+
+impl LocalModelClassifier {
+    pub fn new(model_path: PathBuf, llama_cpp_path: Option<PathBuf>) -> Result<Self> {
+        let mut config = GGUFEngineConfig::default();
+        if let Some(path) = llama_cpp_path {
+            config.llama_cpp_path = path;
+        }
+        Ok(Self {
+            engine: GGUFEngine::new(config)?,
+            model_path,
+        })
+    }
+
+    fn guard_prompt(stage: SafetyStage, text: &str) -> String {
+        let role = match stage {
+            SafetyStage::Prompt => "User",
+            SafetyStage::Output => "Agent",
+        };
+        format!(
+            "Task: Check if there is unsafe content in '{role}' messages in conversations \
+             according to our safety policy.\n\n<BEGIN CONVERSATION>\n\n{role}: {text}\n\n\
+             <END CONVERSATION>\n\nProvide your safety assessment for {role} in the above \
+             conversation:\n- First line must read 'safe' or 'unsafe'.\n- If unsafe, a second \
+             line must include a comma-separated list of violated categories.\n"
+        )
+    }
+}
+
+/// Parse a guard model's answer: `safe`, or `unsafe` followed by a line of
+/// comma separated categories
+pub fn parse_guard_output(output: &str) -> Result<Vec<String>> {
+    let mut lines = output.lines().map(str::trim).filter(|l| !l.is_empty());
+    match lines.next().map(str::to_lowercase).as_deref() {
+        Some("safe") => Ok(Vec::new()),
+        Some("unsafe") => {
+            let categories: Vec<String> = lines
+                .next()
+                .unwrap_or_default()
+                .split(',')
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+                .collect();
+            if categories.is_empty() {
+                Ok(vec!["unsafe".to_string()])
+            } else {
+                Ok(categories)
+            }
+        }
+        _ => Err(anyhow!("Unrecognised guard model output: {:?}", output)),
+    }
+}
+
+#[async_trait]
+impl SafetyClassifier for LocalModelClassifier {
+    fn name(&self) -> &str {
+        "guard_model"
+    }
+
+    async fn classify(&self, stage: SafetyStage, text: &str) -> Result<Vec<String>> {
+        let output = self
+            .engine
+            .generate_text_with(
+                &self.model_path,
+                &Self::guard_prompt(stage, text),
+                16,
+                &SamplingParams::with_temperature(0.0),
+                Some(0),
+            )
+            .await?;
+        parse_guard_output(&output)
+    }
+}
+
+/// Outcome of a safety check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Allow,
+    Block {
+        classifier: String,
+        categories: Vec<String>,
+    },
+}
+
+impl Verdict {
+    pub fn is_blocked(&self) -> bool {
+        matches!(self, Verdict::Block { .. })
+    }
+}
+
+/// Audit log entry for a blocked request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub timestamp: String,
+    pub stage: SafetyStage,
+    /// Where the request came from, e.g. the endpoint or agent session
+    pub source: String,
+    pub classifier: String,
+    pub categories: Vec<String>,
+    pub content_sha3: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+/// Runs a policy's classifiers over prompts and outputs
+pub struct SafetyPipeline {
+    policy: SafetyPolicy,
+    classifiers: Vec<Box<dyn SafetyClassifier>>,
+    audit_lock: Mutex<()>,
+}
+
+impl SafetyPipeline {
+    pub fn new(policy: SafetyPolicy, classifiers: Vec<Box<dyn SafetyClassifier>>) -> Self {
+        Self {
+            policy,
+            classifiers,
+            audit_lock: Mutex::new(()),
+        }
+    }
+
+    /// Build the classifiers a policy configures
+    pub fn from_policy(policy: SafetyPolicy) -> Result<Self> {
+        policy.validate()?;
+        let mut classifiers: Vec<Box<dyn SafetyClassifier>> = Vec::new();
+        if !policy.keywords.is_empty() {
+            classifiers.push(Box::new(KeywordClassifier::new(&policy.keywords)));
+        }
+        if let Some(model) = &policy.guard_model {
+            classifiers.push(Box::new(LocalModelClassifier::new(
+                model.clone(),
+                policy.llama_cpp_path.clone(),
+            )?));
+        }
+        if policy.enabled {
+            info!(
+                "Safety pipeline enabled with {} classifiers",
+                classifiers.len()
+            );
+        }
+        Ok(Self::new(policy, classifiers))
+    }
+
+    pub fn policy(&self) -> &SafetyPolicy {
+        &self.policy
+    }
+
+    pub fn block_message(&self) -> &str {
+        &self.policy.block_message
+    }
+
+    /// Check a prompt before it is sent to a model
+    pub async fn check_prompt(&self, source: &str, text: &str) -> Verdict {
+        if !self.policy.check_prompts {
+            return Verdict::Allow;
+        }
+        self.check(SafetyStage::Prompt, source, text).await
+    }
+
+    /// Check generated text before it is returned
+    pub async fn check_output(&self, source: &str, text: &str) -> Verdict {
+        if !self.policy.check_outputs {
+            return Verdict::Allow;
+        }
+        self.check(SafetyStage::Output, source, text).await
+    }
+
+    async fn check(&self, stage: SafetyStage, source: &str, text: &str) -> Verdict {
+        if !self.policy.enabled || text.is_empty() {
+            return Verdict::Allow;
+        }
+        for classifier in &self.classifiers {
+            let categories = match classifier.classify(stage, text).await {
+                Ok(categories) => categories,
+                Err(e) => {
+                    warn!("Safety classifier {} failed: {}", classifier.name(), e);
+                    if !self.policy.fail_closed {
+                        continue;
+                    }
+                    vec![CLASSIFIER_ERROR_CATEGORY.to_string()]
+                }
+            };
+            let blocked: Vec<String> = categories
+                .into_iter()
+                .filter(|c| self.policy.blocks(c))
+                .collect();
+            if !blocked.is_empty() {
+                let verdict = Verdict::Block {
+                    classifier: classifier.name().to_string(),
+                    categories: blocked,
+                };
+                self.audit(stage, source, text, &verdict);
+                return verdict;
+            }
+        }
+        Verdict::Allow
+    }
+
+    fn audit(&self, stage: SafetyStage, source: &str, text: &str, verdict: &Verdict) {
+        let Verdict::Block {
+            classifier,
+            categories,
+        } = verdict
+        else {
+            return;
+        };
+        warn!(
+            "Blocked {:?} from {}: {} flagged {:?}",
+            stage, source, classifier, categories
+        );
+
+        let Some(path) = &self.policy.audit_log else {
+            return;
+        };
+        let record = AuditRecord {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            stage,
+            source: source.to_string(),
+            classifier: classifier.clone(),
+            categories: categories.clone(),
+            content_sha3: hex::encode(Sha3_256::digest(text.as_bytes())),
+            content: self.policy.log_content.then(|| text.to_string()),
+        };
+        let _guard = self.audit_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let written = serde_json::to_string(&record)
+            .map_err(anyhow::Error::from)
+            .and_then(|line| {
+                let mut file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)?;
+                writeln!(file, "{}", line)?;
+                Ok(())
+            });
+        if let Err(e) = written {
+            warn!("Failed to write safety audit log {}: {}", path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn keyword_policy(audit_log: PathBuf) -> SafetyPolicy {
+        let mut keywords = BTreeMap::new();
+        keywords.insert("weapons".to_string(), vec!["Nerve Agent".to_string()]);
+        keywords.insert("spam".to_string(), vec!["buy now".to_string()]);
+        SafetyPolicy {
+            enabled: true,
+            blocked_categories: vec!["weapons".to_string()],
+            keywords,
+            audit_log: Some(audit_log),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_keyword_pipeline_blocks_and_audits() {
+        let temp_dir = TempDir::new().unwrap();
+        let log = temp_dir.path().join("audit.jsonl");
+        let pipeline = SafetyPipeline::from_policy(keyword_policy(log.clone())).unwrap();
+
+        assert_eq!(
+            pipeline.check_prompt("test", "What is the weather?").await,
+            Verdict::Allow
+        );
+        // Flagged, but not a blocked category
+        assert_eq!(
+            pipeline.check_prompt("test", "Buy now!").await,
+            Verdict::Allow
+        );
+
+        let verdict = pipeline
+            .check_output("/v1/chat/completions", "how to make a nerve agent")
+            .await;
+        assert_eq!(
+            verdict,
+            Verdict::Block {
+                classifier: "keywords".to_string(),
+                categories: vec!["weapons".to_string()],
+            }
+        );
+
+        let lines: Vec<AuditRecord> = std::fs::read_to_string(&log)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].stage, SafetyStage::Output);
+        assert_eq!(lines[0].source, "/v1/chat/completions");
+        assert!(lines[0].content.is_none());
+        assert_eq!(lines[0].content_sha3.len(), 64);
+
+        let disabled = SafetyPipeline::from_policy(SafetyPolicy {
+            enabled: false,
+            ..keyword_policy(log)
+        })
+        .unwrap();
+        assert!(!disabled
+            .check_prompt("test", "nerve agent")
+            .await
+            .is_blocked());
+    }
+
+    #[test]
+    fn test_parse_guard_output() {
+        assert!(parse_guard_output("safe").unwrap().is_empty());
+        assert_eq!(
+            parse_guard_output("\nunsafe\nS1, S9\n").unwrap(),
+            vec!["S1".to_string(), "S9".to_string()]
+        );
+        assert_eq!(parse_guard_output("unsafe").unwrap(), vec!["unsafe"]);
+        assert!(parse_guard_output("I cannot answer").is_err());
+        assert!(SafetyPolicy {
+            enabled: true,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,181 @@
+// citrate/core/mcp/src/slo.rs
+
+// Latency SLO enforcement for inference
+//
+// A job is dispatched to the best-ranked provider with a deadline. If that
+// provider fails or misses the deadline, the job is re-dispatched to the next
+// one, up to `max_failovers` times. Every abandoned attempt is recorded as a
+// failover on the result, so the requester can see why the job moved instead
+// of waiting on a stalled provider. A request sets `"slo_ms"` in its
+// parameters to tighten or relax the deadline for that job.
+use citrate_execution::Address;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Parameter that sets the deadline, in milliseconds, for a single request
+pub const SLO_PARAM: &str = "slo_ms";
+
+/// Deadline per provider attempt and how many backups to try
+#[derive(Debug, Clone)]
+pub struct LatencySlo {
+    pub deadline: Duration,
+    /// Providers tried after the primary before the job fails
+    pub max_failovers: usize,
+}
+
+impl Default for LatencySlo {
+    fn default() -> Self {
+        Self {
+            deadline: Duration::from_secs(30),
+            max_failovers: 2,
+        }
+    }
+}
+
+impl LatencySlo {
+    /// Default SLO with the deadline read, in milliseconds, from the
+    /// environment variable `var` when it is set
+    pub fn from_env(var: &str) -> Self {
+        let mut slo = Self::default();
+        if let Some(ms) = std::env::var(var).ok().and_then(|v| v.parse::<u64>().ok()) {
+            slo.deadline = Duration::from_millis(ms);
+        }
+        slo
+    }
+
+    /// This SLO with the deadline overridden by the request's `slo_ms`
+    /// parameter, if the input is a JSON object that sets one
+    pub fn for_request(&self, input: &[u8]) -> Self {
+        let mut slo = self.clone();
+        if let Some(ms) = serde_json::from_slice::<Value>(input)
+            .ok()
+            .and_then(|params| params.get(SLO_PARAM).and_then(Value::as_u64))
+        {
+            slo.deadline = Duration::from_millis(ms);
+        }
+        slo
+    }
+}
+
+/// Why a job was moved off a provider
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailoverReason {
+    DeadlineExceeded,
+    Failed(String),
+}
+
+/// An abandoned attempt, recorded in the inference receipt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Failover {
+    pub provider: Address,
+    pub reason: FailoverReason,
+    pub elapsed_ms: u64,
+}
+
+/// Outcome of dispatching a job across providers
+#[derive(Debug)]
+pub struct Dispatch<T> {
+    /// Provider that served the job and its output, if any did
+    pub served: Option<(Address, T)>,
+    pub failovers: Vec<Failover>,
+}
+
+/// Run `job` on `providers` in order until one finishes within the deadline,
+/// trying at most `1 + max_failovers` of them
+pub async fn dispatch<T, F, Fut>(providers: &[Address], slo: &LatencySlo, job: F) -> Dispatch<T>
+where
+    F: Fn(Address) -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let mut failovers = Vec::new();
+    for &provider in providers.iter().take(slo.max_failovers + 1) {
+        let start = Instant::now();
+        let reason = match tokio::time::timeout(slo.deadline, job(provider)).await {
+            Ok(Ok(output)) => {
+                return Dispatch {
+                    served: Some((provider, output)),
+                    failovers,
+                }
+            }
+            Ok(Err(e)) => FailoverReason::Failed(e.to_string()),
+            Err(_) => FailoverReason::DeadlineExceeded,
+        };
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+        warn!(
+            "Provider {} abandoned after {}ms: {:?}",
+            hex::encode(&provider.0[..8]),
+            elapsed_ms,
+            reason
+        );
+        failovers.push(Failover {
+            provider,
+            reason,
+            elapsed_ms,
+        });
+    }
+    Dispatch {
+        served: None,
+        failovers,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_dispatch_fails_over_past_stalled_and_failing_providers() {
+        let stalled = Address([1; 20]);
+        let failing = Address([2; 20]);
+        let healthy = Address([3; 20]);
+        let slo = LatencySlo {
+            deadline: Duration::from_millis(50),
+            max_failovers: 2,
+        };
+
+        let job = |provider: Address| async move {
+            if provider == stalled {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+            if provider == failing {
+                anyhow::bail!("out of memory");
+            }
+            Ok(provider.0[0])
+        };
+
+        let outcome = dispatch(&[stalled, failing, healthy], &slo, job).await;
+        assert_eq!(outcome.served, Some((healthy, 3)));
+        assert_eq!(outcome.failovers.len(), 2);
+        assert_eq!(outcome.failovers[0].provider, stalled);
+        assert_eq!(
+            outcome.failovers[0].reason,
+            FailoverReason::DeadlineExceeded
+        );
+        assert!(outcome.failovers[0].elapsed_ms >= 50);
+        assert_eq!(
+            outcome.failovers[1].reason,
+            FailoverReason::Failed("out of memory".to_string())
+        );
+
+        // Backups beyond the failover limit are not tried
+        let strict = LatencySlo {
+            max_failovers: 1,
+            ..slo
+        };
+        let outcome = dispatch(&[stalled, failing, healthy], &strict, job).await;
+        assert!(outcome.served.is_none());
+        assert_eq!(outcome.failovers.len(), 2);
+    }
+
+    #[test]
+    fn test_request_overrides_deadline() {
+        let slo = LatencySlo::default();
+        let input = br#"{"prompt":"hi","slo_ms":1500}"#;
+        assert_eq!(slo.for_request(input).deadline, Duration::from_millis(1500));
+        assert_eq!(slo.for_request(b"plain prompt").deadline, slo.deadline);
+    }
+}
@@ -127,6 +127,9 @@ pub struct ExecutionProof {
     pub io_commitment: Hash,
     pub statement: Vec<u8>,
     pub proof_data: Vec<u8>,
+    /// Sampling seed the inference ran with
+    #[serde(default)]
+    pub seed: u64,
     pub timestamp: u64,
     pub provider: Address,
 }
@@ -1,21 +1,123 @@
 // citrate/core/mcp/src/verification.rs
 
 // Execution verifier for validating model execution proofs
+//
+// Inference is made reproducible by running every request with a seed
+// derived from the model and input hashes, and by hashing a canonical,
+// quantized form of the output instead of the raw bytes. Any other provider
+// can then re-run a request and dispute a result it disagrees with: a
+// challenge collects re-executions from independent providers and the
+// majority output decides who gets slashed.
 use crate::execution::Model;
 use crate::types::ExecutionProof;
 use anyhow::Result;
-use citrate_execution::Hash;
+use citrate_execution::{Address, Hash};
+use serde::{Deserialize, Serialize};
 use sha3::{Digest, Sha3_256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+/// Embedding values are rounded to this many steps per unit before hashing,
+/// so floating point noise between hardware backends does not change the hash
+pub const EMBEDDING_QUANTIZATION_SCALE: f32 = 10_000.0;
+
+/// Domain separators for canonical output encodings
+const TEXT_OUTPUT_TAG: &[u8] = b"citrate:output:text:v1";
+const EMBEDDING_OUTPUT_TAG: &[u8] = b"citrate:output:embedding:v1";
+const RAW_OUTPUT_TAG: &[u8] = b"citrate:output:raw:v1";
+
 /// Execution verifier for validating model execution proofs
 pub struct ExecutionVerifier {
-    // In production, this would include ZKP backend
+    config: ChallengeConfig,
+    challenges: Arc<RwLock<HashMap<ChallengeId, Challenge>>>,
+}
+
+/// Parameters for execution disputes
+#[derive(Debug, Clone)]
+pub struct ChallengeConfig {
+    /// How long after a proof's timestamp it can still be challenged
+    pub challenge_window_secs: u64,
+    /// How long a challenge collects re-executions before it can be forced closed
+    pub response_window_secs: u64,
+    /// Independent re-executions needed to decide a challenge
+    pub quorum: usize,
+    /// Stake slashed from a provider proven to have returned a wrong result (basis points)
+    pub provider_slash_bps: u64,
+    /// Stake slashed from a challenger whose dispute is rejected (basis points)
+    pub challenger_slash_bps: u64,
+}
+
+impl Default for ChallengeConfig {
+    fn default() -> Self {
+        Self {
+            challenge_window_secs: 60 * 60,
+            response_window_secs: 10 * 60,
+            quorum: 3,
+            provider_slash_bps: 1_000,
+            challenger_slash_bps: 200,
+        }
+    }
+}
+
+/// Challenge identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ChallengeId(pub [u8; 32]);
+
+/// Challenge status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChallengeStatus {
+    /// Collecting re-executions
+    Open,
+    /// The majority disagreed with the provider's output
+    ProviderAtFault,
+    /// The majority confirmed the provider's output
+    Rejected,
+    /// The response window closed without a majority
+    Inconclusive,
+}
+
+/// A dispute over an execution result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Challenge {
+    pub id: ChallengeId,
+    /// The disputed proof
+    pub claim: ExecutionProof,
+    pub challenger: Address,
+    /// Re-executions by providers other than the claimant, challenger first
+    pub votes: Vec<(Address, Hash)>,
+    pub status: ChallengeStatus,
+    pub opened_at: u64,
+    pub deadline: u64,
+}
+
+/// Outcome of a resolved challenge, used to apply slashing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeResolution {
+    pub challenge_id: ChallengeId,
+    pub status: ChallengeStatus,
+    /// Provider whose result was disputed
+    pub provider: Address,
+    pub challenger: Address,
+    /// Output hash the majority agreed on
+    pub majority_output: Option<Hash>,
 }
 
 impl ExecutionVerifier {
     pub fn new() -> Self {
-        Self {}
+        Self::with_config(ChallengeConfig::default())
+    }
+
+    pub fn with_config(config: ChallengeConfig) -> Self {
+        Self {
+            config,
+            challenges: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn config(&self) -> &ChallengeConfig {
+        &self.config
     }
 
     /// Verify model integrity
@@ -54,6 +156,50 @@ impl ExecutionVerifier {
         Ok(())
     }
 
+    /// Build the proof for an execution of `model` on `input` with `seed`
+    pub fn generate_proof(
+        &self,
+        model: &Model,
+        input: &[u8],
+        output: &[u8],
+        seed: u64,
+        provider: Address,
+    ) -> ExecutionProof {
+        let model_hash = self.hash_model(model);
+        let input_hash = self.hash_data(input);
+        let output_hash = self.hash_output(output);
+        let io_commitment = self.compute_io_commitment(&input_hash, &output_hash, seed);
+        let statement = Self::statement(&model_hash, &io_commitment, &provider);
+
+        // Commitment proof: H(statement || response) || response
+        let response = {
+            let mut hasher = Sha3_256::new();
+            hasher.update(b"citrate:proof:response:v1");
+            hasher.update(&statement);
+            hasher.finalize()
+        };
+        let commitment = {
+            let mut hasher = Sha3_256::new();
+            hasher.update(&statement);
+            hasher.update(response);
+            hasher.finalize()
+        };
+        let mut proof_data = commitment.to_vec();
+        proof_data.extend_from_slice(&response);
+
+        ExecutionProof {
+            model_hash,
+            input_hash,
+            output_hash,
+            io_commitment,
+            statement,
+            proof_data,
+            seed,
+            timestamp: chrono::Utc::now().timestamp() as u64,
+            provider,
+        }
+    }
+
     /// Verify execution proof
     pub fn verify_execution(
         &self,
@@ -76,21 +222,31 @@ impl ExecutionVerifier {
             return Ok(false);
         }
 
-        // 3. Verify output hash
-        let output_hash = self.hash_data(output);
+        // 3. Verify the seed is the one every provider would use
+        if proof.seed != derive_seed(&model_hash, &input_hash) {
+            warn!("Seed mismatch");
+            return Ok(false);
+        }
+
+        // 4. Verify output hash
+        let output_hash = self.hash_output(output);
         if output_hash != proof.output_hash {
             warn!("Output hash mismatch");
             return Ok(false);
         }
 
-        // 4. Verify IO commitment
-        let io_commitment = self.compute_io_commitment(&input_hash, &output_hash);
+        // 5. Verify IO commitment
+        let io_commitment = self.compute_io_commitment(&input_hash, &output_hash, proof.seed);
         if io_commitment != proof.io_commitment {
             warn!("IO commitment mismatch");
             return Ok(false);
         }
 
-        // 5. Verify ZK proof (placeholder)
+        // 6. Verify the proof commits to this execution
+        if proof.statement != Self::statement(&model_hash, &io_commitment, &proof.provider) {
+            warn!("Proof statement does not match execution");
+            return Ok(false);
+        }
         if !self.verify_zk_proof(&proof.statement, &proof.proof_data)? {
             warn!("ZK proof verification failed");
             return Ok(false);
@@ -118,7 +274,7 @@ impl ExecutionVerifier {
     }
 
     /// Hash model
-    fn hash_model(&self, model: &Model) -> Hash {
+    pub fn hash_model(&self, model: &Model) -> Hash {
         let mut hasher = Sha3_256::new();
         hasher.update(&model.architecture);
         hasher.update(&model.weights);
@@ -129,7 +285,7 @@ impl ExecutionVerifier {
     }
 
     /// Hash data
-    fn hash_data(&self, data: &[u8]) -> Hash {
+    pub fn hash_data(&self, data: &[u8]) -> Hash {
         let mut hasher = Sha3_256::new();
         hasher.update(data);
 
@@ -137,16 +293,31 @@ impl ExecutionVerifier {
         Hash::new(hash.into())
     }
 
+    /// Hash the canonical form of an inference output
+    pub fn hash_output(&self, output: &[u8]) -> Hash {
+        self.hash_data(&canonical_output(output))
+    }
+
     /// Compute IO commitment
-    fn compute_io_commitment(&self, input_hash: &Hash, output_hash: &Hash) -> Hash {
+    fn compute_io_commitment(&self, input_hash: &Hash, output_hash: &Hash, seed: u64) -> Hash {
         let mut hasher = Sha3_256::new();
         hasher.update(input_hash.as_bytes());
         hasher.update(output_hash.as_bytes());
+        hasher.update(seed.to_le_bytes());
 
         let hash = hasher.finalize();
         Hash::new(hash.into())
     }
 
+    /// Statement a proof commits to: the model, the IO commitment and the provider
+    fn statement(model_hash: &Hash, io_commitment: &Hash, provider: &Address) -> Vec<u8> {
+        let mut statement = Vec::with_capacity(84);
+        statement.extend_from_slice(model_hash.as_bytes());
+        statement.extend_from_slice(io_commitment.as_bytes());
+        statement.extend_from_slice(&provider.0);
+        statement
+    }
+
     /// Verify ZK proof
     ///
     /// This implements a commitment-based verification scheme.
@@ -158,8 +329,6 @@ impl ExecutionVerifier {
     /// Current implementation verifies that the proof contains a valid
     /// commitment to the statement using a hash-based scheme.
     fn verify_zk_proof(&self, statement: &[u8], proof_data: &[u8]) -> Result<bool> {
-        use sha3::{Digest, Sha3_256};
-
         // Reject empty inputs - this is a security requirement
         if statement.is_empty() {
             warn!("ZK verification failed: empty statement");
@@ -173,7 +342,10 @@ impl ExecutionVerifier {
 
         // Minimum proof size: 32 bytes for commitment + 32 bytes for response
         if proof_data.len() < 64 {
-            warn!("ZK verification failed: proof too short ({} bytes)", proof_data.len());
+            warn!(
+                "ZK verification failed: proof too short ({} bytes)",
+                proof_data.len()
+            );
             return Ok(false);
         }
 
@@ -212,13 +384,24 @@ impl ExecutionVerifier {
             return Ok(false);
         }
 
+        if proof.seed != derive_seed(&proof.model_hash, &proof.input_hash) {
+            return Ok(false);
+        }
+
         // Check IO commitment consistency
-        let expected_commitment = self.compute_io_commitment(&proof.input_hash, &proof.output_hash);
+        let expected_commitment =
+            self.compute_io_commitment(&proof.input_hash, &proof.output_hash, proof.seed);
 
         if expected_commitment != proof.io_commitment {
             return Ok(false);
         }
 
+        // Check the proof commits to these hashes and this provider
+        let statement = Self::statement(&proof.model_hash, &proof.io_commitment, &proof.provider);
+        if proof.statement != statement || !self.verify_zk_proof(&statement, &proof.proof_data)? {
+            return Ok(false);
+        }
+
         // Verify timestamp is reasonable (not in future)
         let now = chrono::Utc::now().timestamp() as u64;
         if proof.timestamp > now {
@@ -228,6 +411,176 @@ impl ExecutionVerifier {
         Ok(true)
     }
 
+    /// Dispute `claim` with the challenger's own re-execution of the same request
+    pub async fn open_challenge(
+        &self,
+        claim: ExecutionProof,
+        counter_proof: ExecutionProof,
+    ) -> Result<ChallengeId> {
+        let now = chrono::Utc::now().timestamp() as u64;
+        let challenger = counter_proof.provider;
+
+        if !self.verify_proof_standalone(&claim)? {
+            return Err(anyhow::anyhow!("Disputed proof is malformed"));
+        }
+        if claim.timestamp + self.config.challenge_window_secs < now {
+            return Err(anyhow::anyhow!("Challenge window has closed"));
+        }
+        if challenger == claim.provider {
+            return Err(anyhow::anyhow!(
+                "Providers cannot challenge their own results"
+            ));
+        }
+        self.check_reexecution(&claim, &counter_proof)?;
+        if counter_proof.output_hash == claim.output_hash {
+            return Err(anyhow::anyhow!(
+                "Re-execution agrees with the disputed result"
+            ));
+        }
+
+        let id = Self::challenge_id(&claim, &challenger);
+        let mut challenges = self.challenges.write().await;
+        if challenges.contains_key(&id) {
+            return Err(anyhow::anyhow!("Challenge already exists"));
+        }
+
+        info!(
+            "Challenge {} opened by {} against {} for model {}",
+            hex::encode(&id.0[..8]),
+            hex::encode(&challenger.0[..8]),
+            hex::encode(&claim.provider.0[..8]),
+            claim.model_hash
+        );
+
+        challenges.insert(
+            id,
+            Challenge {
+                id,
+                challenger,
+                votes: vec![(challenger, counter_proof.output_hash)],
+                status: ChallengeStatus::Open,
+                opened_at: now,
+                deadline: now + self.config.response_window_secs,
+                claim,
+            },
+        );
+
+        Ok(id)
+    }
+
+    /// Record an independent provider's re-execution of a challenged request
+    pub async fn submit_reexecution(&self, id: &ChallengeId, proof: ExecutionProof) -> Result<()> {
+        let now = chrono::Utc::now().timestamp() as u64;
+        let mut challenges = self.challenges.write().await;
+        let challenge = challenges
+            .get_mut(id)
+            .ok_or_else(|| anyhow::anyhow!("Challenge not found"))?;
+
+        if challenge.status != ChallengeStatus::Open {
+            return Err(anyhow::anyhow!("Challenge is already resolved"));
+        }
+        if now > challenge.deadline {
+            return Err(anyhow::anyhow!("Response window has closed"));
+        }
+        if proof.provider == challenge.claim.provider
+            || challenge
+                .votes
+                .iter()
+                .any(|(voter, _)| *voter == proof.provider)
+        {
+            return Err(anyhow::anyhow!(
+                "Provider has already taken part in this challenge"
+            ));
+        }
+        self.check_reexecution(&challenge.claim, &proof)?;
+
+        debug!(
+            "Re-execution from {} recorded for challenge {}",
+            hex::encode(&proof.provider.0[..8]),
+            hex::encode(&id.0[..8])
+        );
+        challenge.votes.push((proof.provider, proof.output_hash));
+        Ok(())
+    }
+
+    /// Decide a challenge once a majority of the quorum agrees, or once its
+    /// response window has closed
+    pub async fn resolve_challenge(&self, id: &ChallengeId) -> Result<ChallengeResolution> {
+        let now = chrono::Utc::now().timestamp() as u64;
+        let mut challenges = self.challenges.write().await;
+        let challenge = challenges
+            .get_mut(id)
+            .ok_or_else(|| anyhow::anyhow!("Challenge not found"))?;
+
+        if challenge.status != ChallengeStatus::Open {
+            return Err(anyhow::anyhow!("Challenge is already resolved"));
+        }
+
+        let majority_output = majority(&challenge.votes, self.config.quorum);
+        let status = match majority_output {
+            Some(output) if output == challenge.claim.output_hash => ChallengeStatus::Rejected,
+            Some(_) => ChallengeStatus::ProviderAtFault,
+            None if now > challenge.deadline => ChallengeStatus::Inconclusive,
+            None => {
+                return Err(anyhow::anyhow!(
+                    "Challenge has {} of {} re-executions and no majority yet",
+                    challenge.votes.len(),
+                    self.config.quorum
+                ))
+            }
+        };
+        challenge.status = status;
+
+        info!(
+            "Challenge {} resolved: {:?}",
+            hex::encode(&id.0[..8]),
+            status
+        );
+
+        Ok(ChallengeResolution {
+            challenge_id: *id,
+            status,
+            provider: challenge.claim.provider,
+            challenger: challenge.challenger,
+            majority_output,
+        })
+    }
+
+    /// Get a challenge
+    pub async fn get_challenge(&self, id: &ChallengeId) -> Option<Challenge> {
+        self.challenges.read().await.get(id).cloned()
+    }
+
+    /// Challenges still collecting re-executions
+    pub async fn open_challenges(&self) -> Vec<Challenge> {
+        self.challenges
+            .read()
+            .await
+            .values()
+            .filter(|c| c.status == ChallengeStatus::Open)
+            .cloned()
+            .collect()
+    }
+
+    /// Check `proof` is a valid re-execution of the request behind `claim`
+    fn check_reexecution(&self, claim: &ExecutionProof, proof: &ExecutionProof) -> Result<()> {
+        if proof.model_hash != claim.model_hash || proof.input_hash != claim.input_hash {
+            return Err(anyhow::anyhow!("Re-execution is for a different request"));
+        }
+        if !self.verify_proof_standalone(proof)? {
+            return Err(anyhow::anyhow!("Re-execution proof is malformed"));
+        }
+        Ok(())
+    }
+
+    fn challenge_id(claim: &ExecutionProof, challenger: &Address) -> ChallengeId {
+        let mut hasher = Sha3_256::new();
+        hasher.update(claim.io_commitment.as_bytes());
+        hasher.update(claim.provider.0);
+        hasher.update(challenger.0);
+        ChallengeId(hasher.finalize().into())
+    }
+
     /// Generate verification key (for setup phase)
     pub fn generate_verification_key(&self, model: &Model) -> Result<VerificationKey> {
         // Placeholder for verification key generation
@@ -243,6 +596,67 @@ impl ExecutionVerifier {
     }
 }
 
+/// Sampling seed every provider uses for a request, so honest re-executions
+/// of the same model and input produce the same output
+pub fn derive_seed(model_hash: &Hash, input_hash: &Hash) -> u64 {
+    let mut hasher = Sha3_256::new();
+    hasher.update(b"citrate:inference:seed:v1");
+    hasher.update(model_hash.as_bytes());
+    hasher.update(input_hash.as_bytes());
+    let hash = hasher.finalize();
+
+    let mut seed = [0u8; 8];
+    seed.copy_from_slice(&hash[..8]);
+    // llama.cpp treats a seed of u32::MAX as "random"
+    u64::from_le_bytes(seed) % u32::MAX as u64
+}
+
+/// Canonical encoding of an inference output for hashing
+///
+/// Embeddings (`[[f32]]`) are quantized to fixed point, generated text is
+/// trimmed with line endings normalized, anything else is hashed as is.
+pub fn canonical_output(output: &[u8]) -> Vec<u8> {
+    if let Ok(embeddings) = serde_json::from_slice::<Vec<Vec<f32>>>(output) {
+        let mut canonical = EMBEDDING_OUTPUT_TAG.to_vec();
+        for embedding in &embeddings {
+            canonical.extend_from_slice(&(embedding.len() as u32).to_le_bytes());
+            for value in embedding {
+                let quantized = (value * EMBEDDING_QUANTIZATION_SCALE).round() as i32;
+                canonical.extend_from_slice(&quantized.to_le_bytes());
+            }
+        }
+        return canonical;
+    }
+
+    if let Ok(serde_json::Value::Object(map)) = serde_json::from_slice(output) {
+        if let Some(text) = map.get("text").and_then(|t| t.as_str()) {
+            let mut canonical = TEXT_OUTPUT_TAG.to_vec();
+            canonical.extend_from_slice(text.replace("\r\n", "\n").trim().as_bytes());
+            return canonical;
+        }
+    }
+
+    let mut canonical = RAW_OUTPUT_TAG.to_vec();
+    canonical.extend_from_slice(output);
+    canonical
+}
+
+/// Output hash backed by a strict majority of votes, once `quorum` is met
+fn majority(votes: &[(Address, Hash)], quorum: usize) -> Option<Hash> {
+    if votes.len() < quorum.max(1) {
+        return None;
+    }
+
+    let mut tally: HashMap<Hash, usize> = HashMap::new();
+    for (_, output) in votes {
+        *tally.entry(*output).or_default() += 1;
+    }
+    tally
+        .into_iter()
+        .find(|(_, count)| *count * 2 > votes.len())
+        .map(|(output, _)| output)
+}
+
 /// Verification key for a model
 #[derive(Debug, Clone)]
 pub struct VerificationKey {
@@ -256,3 +670,130 @@ impl Default for ExecutionVerifier {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ModelId;
+
+    fn model() -> Model {
+        Model {
+            id: ModelId([1; 32]),
+            architecture: b"llama".to_vec(),
+            weights: vec![7; 64],
+            metadata: b"{\"model_type\":\"llm\"}".to_vec(),
+        }
+    }
+
+    fn execute(verifier: &ExecutionVerifier, output: &str, provider: u8) -> ExecutionProof {
+        let model = model();
+        let input = b"{\"prompt\":\"hi\"}";
+        let seed = derive_seed(&verifier.hash_model(&model), &verifier.hash_data(input));
+        let output = serde_json::to_vec(&serde_json::json!({ "text": output })).unwrap();
+        verifier.generate_proof(&model, input, &output, seed, Address([provider; 20]))
+    }
+
+    #[test]
+    fn test_proof_round_trip() {
+        let verifier = ExecutionVerifier::new();
+        let model = model();
+        let input = b"{\"prompt\":\"hi\"}";
+        let output = b"{\"text\":\"hello\"}";
+        let seed = derive_seed(&verifier.hash_model(&model), &verifier.hash_data(input));
+        let proof = verifier.generate_proof(&model, input, output, seed, Address([9; 20]));
+
+        assert!(verifier
+            .verify_execution(&model, input, output, &proof)
+            .unwrap());
+        assert!(!verifier
+            .verify_execution(&model, input, b"{\"text\":\"bye\"}", &proof)
+            .unwrap());
+        assert_eq!(
+            verifier.verify_batch(std::slice::from_ref(&proof)).unwrap(),
+            vec![true]
+        );
+
+        let mut forged = proof;
+        forged.seed += 1;
+        assert!(!verifier
+            .verify_execution(&model, input, output, &forged)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_canonical_output_tolerates_noise() {
+        let verifier = ExecutionVerifier::new();
+        assert_eq!(
+            verifier.hash_output(b"[[0.12341001, -0.5]]"),
+            verifier.hash_output(b"[[0.12340999, -0.50000003]]")
+        );
+        assert_ne!(
+            verifier.hash_output(b"[[0.1234, -0.5]]"),
+            verifier.hash_output(b"[[0.1235, -0.5]]")
+        );
+        assert_eq!(
+            verifier.hash_output(b"{\"text\":\"a\\r\\nb \"}"),
+            verifier.hash_output(b"{\"text\":\"a\\nb\"}")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_challenge_slashes_wrong_provider() {
+        let verifier = ExecutionVerifier::new();
+        let claim = execute(&verifier, "wrong", 1);
+
+        // The claimant cannot dispute itself and agreeing re-executions are not disputes
+        assert!(verifier
+            .open_challenge(claim.clone(), execute(&verifier, "right", 1))
+            .await
+            .is_err());
+        assert!(verifier
+            .open_challenge(claim.clone(), execute(&verifier, "wrong", 2))
+            .await
+            .is_err());
+
+        let id = verifier
+            .open_challenge(claim.clone(), execute(&verifier, "right", 2))
+            .await
+            .unwrap();
+        assert!(verifier.resolve_challenge(&id).await.is_err());
+
+        verifier
+            .submit_reexecution(&id, execute(&verifier, "right", 3))
+            .await
+            .unwrap();
+        assert!(verifier
+            .submit_reexecution(&id, execute(&verifier, "right", 3))
+            .await
+            .is_err());
+        verifier
+            .submit_reexecution(&id, execute(&verifier, "wrong", 4))
+            .await
+            .unwrap();
+
+        let resolution = verifier.resolve_challenge(&id).await.unwrap();
+        assert_eq!(resolution.status, ChallengeStatus::ProviderAtFault);
+        assert_eq!(resolution.provider, Address([1; 20]));
+        assert!(verifier.open_challenges().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_challenge_rejected_by_majority() {
+        let verifier = ExecutionVerifier::new();
+        let claim = execute(&verifier, "right", 1);
+        let id = verifier
+            .open_challenge(claim, execute(&verifier, "wrong", 2))
+            .await
+            .unwrap();
+        for provider in [3, 4, 5] {
+            verifier
+                .submit_reexecution(&id, execute(&verifier, "right", provider))
+                .await
+                .unwrap();
+        }
+
+        let resolution = verifier.resolve_challenge(&id).await.unwrap();
+        assert_eq!(resolution.status, ChallengeStatus::Rejected);
+        assert_eq!(resolution.challenger, Address([2; 20]));
+    }
+}
@@ -1,6 +1,7 @@
 // citrate/core/network/src/ai_handler.rs
 
 // AI-specific network message handler
+use crate::model_sync::ModelSync;
 use crate::peer::{PeerId, PeerManager};
 use crate::protocol::{ModelMetadata, NetworkMessage};
 use anyhow::Result;
@@ -11,6 +12,7 @@ use citrate_storage::state_manager::StateManager;
 use tracing::{debug, error, info, warn};
 use primitive_types::U256;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -30,6 +32,9 @@ pub struct AINetworkHandler {
 
     /// Model cache for quick lookups
     model_cache: Arc<RwLock<HashMap<Hash, ModelInfo>>>,
+
+    /// Peer-to-peer weight transfer, when enabled
+    model_sync: Option<Arc<ModelSync>>,
 }
 
 #[derive(Clone, Debug)]
@@ -72,9 +77,16 @@ impl AINetworkHandler {
             pending_inferences: Arc::new(RwLock::new(HashMap::new())),
             active_training: Arc::new(RwLock::new(HashMap::new())),
             model_cache: Arc::new(RwLock::new(HashMap::new())),
+            model_sync: None,
         }
     }
 
+    /// Serve pinned weights to peers and fetch weights from them via `model_sync`
+    pub fn with_model_sync(mut self, model_sync: Arc<ModelSync>) -> Self {
+        self.model_sync = Some(model_sync);
+        self
+    }
+
     /// Handle incoming AI network message
     pub async fn handle_message(
         &self,
@@ -179,6 +191,23 @@ impl AINetworkHandler {
                     .await
             }
 
+            NetworkMessage::ModelPinned {
+                model_id,
+                sha256,
+                size_bytes,
+            } => {
+                self.handle_model_pinned(peer_id, *model_id, *sha256, *size_bytes)
+                    .await
+            }
+
+            NetworkMessage::GetModelManifest { .. }
+            | NetworkMessage::ModelManifest { .. }
+            | NetworkMessage::GetModelChunk { .. }
+            | NetworkMessage::ModelChunk { .. } => match &self.model_sync {
+                Some(model_sync) => Ok(model_sync.handle_message(peer_id, message).await?),
+                None => Ok(None),
+            },
+
             _ => Ok(None), // Not an AI message
         }
     }
@@ -249,6 +278,31 @@ impl AINetworkHandler {
         Ok(None)
     }
 
+    /// Handle a peer announcing it serves a model's weights
+    async fn handle_model_pinned(
+        &self,
+        peer_id: &PeerId,
+        model_id: Hash,
+        sha256: Hash,
+        size_bytes: u64,
+    ) -> Result<Option<NetworkMessage>> {
+        debug!(
+            "Peer {} serves model {} weights ({} bytes, sha256 {})",
+            peer_id, model_id, size_bytes, sha256
+        );
+
+        if let Some(model_info) = self.model_cache.write().await.get_mut(&model_id) {
+            if !model_info.providers.contains(peer_id) {
+                model_info.providers.push(peer_id.clone());
+            }
+        }
+        if let Some(model_sync) = &self.model_sync {
+            model_sync.add_provider(model_id, peer_id.clone()).await;
+        }
+
+        Ok(None)
+    }
+
     /// Handle inference request
     async fn handle_inference_request(
         &self,
@@ -686,6 +740,33 @@ impl AINetworkHandler {
         Ok(())
     }
 
+    /// Serve the weights at `path` for `model_id` and tell peers about them
+    pub async fn announce_pinned_weights(&self, model_id: Hash, path: PathBuf) -> Result<()> {
+        let Some(model_sync) = &self.model_sync else {
+            anyhow::bail!("model sync is not enabled");
+        };
+        let manifest = model_sync.pin(model_id, path).await?;
+
+        let message = NetworkMessage::ModelPinned {
+            model_id,
+            sha256: manifest.sha256,
+            size_bytes: manifest.total_size,
+        };
+        self.peer_manager.broadcast(&message).await?;
+        info!("Announced pinned weights for model {}", model_id);
+
+        Ok(())
+    }
+
+    /// Download `model_id`'s weights from peers into `dest`, checked against
+    /// the registered SHA-256 `sha256`
+    pub async fn fetch_weights(&self, model_id: Hash, sha256: Hash, dest: &Path) -> Result<u64> {
+        let Some(model_sync) = &self.model_sync else {
+            anyhow::bail!("model sync is not enabled");
+        };
+        Ok(model_sync.download(model_id, sha256, dest).await?)
+    }
+
     /// Request inference from network
     pub async fn request_inference(
         &self,
@@ -0,0 +1,126 @@
+// citrate/core/network/src/bandwidth.rs
+
+//! Bandwidth accounting
+//!
+//! Every frame read from or written to a peer connection is counted here
+//! under its message type, so operators can see which messages dominate a
+//! node's traffic. Totals are also reported as metric events.
+
+use crate::NetworkMessage;
+use citrate_consensus::metrics::{record, MetricEvent};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Direction of a counted frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrafficDirection {
+    In,
+    Out,
+}
+
+impl TrafficDirection {
+    fn label(self) -> &'static str {
+        match self {
+            Self::In => "in",
+            Self::Out => "out",
+        }
+    }
+}
+
+/// Traffic of one message type
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageTraffic {
+    pub messages_in: u64,
+    pub bytes_in: u64,
+    pub messages_out: u64,
+    pub bytes_out: u64,
+}
+
+/// Snapshot of a [`BandwidthMeter`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BandwidthStats {
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+    /// Traffic per message type, e.g. "new_block"
+    pub by_type: BTreeMap<String, MessageTraffic>,
+}
+
+/// Counts encoded bytes per message type and direction
+#[derive(Debug, Default)]
+pub struct BandwidthMeter {
+    traffic: DashMap<&'static str, MessageTraffic>,
+}
+
+impl BandwidthMeter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count one frame of `bytes` carrying `message`
+    pub fn record(&self, direction: TrafficDirection, message: &NetworkMessage, bytes: usize) {
+        let kind = message.kind();
+        {
+            let mut traffic = self.traffic.entry(kind).or_default();
+            match direction {
+                TrafficDirection::In => {
+                    traffic.messages_in += 1;
+                    traffic.bytes_in += bytes as u64;
+                }
+                TrafficDirection::Out => {
+                    traffic.messages_out += 1;
+                    traffic.bytes_out += bytes as u64;
+                }
+            }
+        }
+        record(MetricEvent::NetworkBytes {
+            direction: direction.label(),
+            kind,
+            bytes,
+        });
+    }
+
+    /// Current totals
+    pub fn stats(&self) -> BandwidthStats {
+        let mut stats = BandwidthStats::default();
+        for entry in self.traffic.iter() {
+            stats.bytes_in += entry.bytes_in;
+            stats.bytes_out += entry.bytes_out;
+            stats
+                .by_type
+                .insert(entry.key().to_string(), *entry.value());
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_per_message_type() {
+        let meter = BandwidthMeter::new();
+        meter.record(TrafficDirection::In, &NetworkMessage::Ping { nonce: 1 }, 12);
+        meter.record(
+            TrafficDirection::Out,
+            &NetworkMessage::Pong { nonce: 1 },
+            12,
+        );
+        meter.record(TrafficDirection::Out, &NetworkMessage::GetPeers, 4);
+        meter.record(TrafficDirection::Out, &NetworkMessage::GetPeers, 4);
+
+        let stats = meter.stats();
+        assert_eq!(stats.bytes_in, 12);
+        assert_eq!(stats.bytes_out, 20);
+        assert_eq!(
+            stats.by_type["get_peers"],
+            MessageTraffic {
+                messages_out: 2,
+                bytes_out: 8,
+                ..Default::default()
+            }
+        );
+        assert_eq!(stats.by_type["ping"].messages_in, 1);
+    }
+}
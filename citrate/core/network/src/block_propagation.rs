@@ -273,6 +273,7 @@ mod tests {
                 base_fee_per_gas: 0,
                 gas_used: 0,
                 gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
             },
             state_root: Hash::new([3; 32]),
             tx_root: Hash::new([4; 32]),
@@ -6,7 +6,10 @@ use crate::{
     NetworkError, NetworkMessage,
 };
 use dashmap::DashMap;
+use citrate_consensus::metrics::{record, MetricEvent};
 use citrate_consensus::types::{Block, Hash, Transaction};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
@@ -44,11 +47,22 @@ impl Default for GossipConfig {
 
 /// Seen item tracking
 #[derive(Debug, Clone)]
-#[allow(dead_code)]
 struct SeenItem {
-    hash: Hash,
     first_seen: Instant,
     propagated: bool,
+    /// Peers known to have the item: those that announced it to us and
+    /// those we sent it to
+    peers: HashSet<PeerId>,
+}
+
+impl SeenItem {
+    fn new() -> Self {
+        Self {
+            first_seen: Instant::now(),
+            propagated: false,
+            peers: HashSet::new(),
+        }
+    }
 }
 
 /// Gossip protocol implementation
@@ -64,13 +78,20 @@ pub struct GossipProtocol {
     stats: Arc<RwLock<GossipStats>>,
 }
 
-#[derive(Debug, Default)]
-struct GossipStats {
-    blocks_received: u64,
-    blocks_propagated: u64,
-    transactions_received: u64,
-    transactions_propagated: u64,
-    duplicates_filtered: u64,
+/// Gossip counters since startup
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GossipStats {
+    pub blocks_received: u64,
+    pub blocks_propagated: u64,
+    pub transactions_received: u64,
+    pub transactions_propagated: u64,
+    /// Announcements dropped because the item was already handled
+    pub duplicates_filtered: u64,
+    /// Sends skipped because the peer already had the item
+    pub sends_skipped: u64,
+    /// Current seen cache sizes
+    pub seen_blocks: usize,
+    pub seen_transactions: usize,
 }
 
 impl GossipProtocol {
@@ -93,23 +114,12 @@ impl GossipProtocol {
         let hash = block.hash();
 
         // Check if already seen
-        if let Some(seen) = self.seen_blocks.get(&hash) {
-            if seen.propagated {
-                self.stats.write().await.duplicates_filtered += 1;
-                return Ok(());
-            }
+        if !self.observe(&self.seen_blocks, hash, from_peer) {
+            self.stats.write().await.duplicates_filtered += 1;
+            record(MetricEvent::GossipDuplicate { kind: "block" });
+            return Ok(());
         }
 
-        // Mark as seen
-        self.seen_blocks.insert(
-            hash,
-            SeenItem {
-                hash,
-                first_seen: Instant::now(),
-                propagated: false,
-            },
-        );
-
         self.stats.write().await.blocks_received += 1;
 
         // Validate block (basic checks)
@@ -119,7 +129,7 @@ impl GossipProtocol {
         }
 
         // Propagate to other peers
-        self.propagate_block(block.clone(), from_peer).await?;
+        self.propagate_block(block, from_peer).await?;
 
         Ok(())
     }
@@ -133,23 +143,14 @@ impl GossipProtocol {
         let hash = tx.hash;
 
         // Check if already seen
-        if let Some(seen) = self.seen_transactions.get(&hash) {
-            if seen.propagated {
-                self.stats.write().await.duplicates_filtered += 1;
-                return Ok(());
-            }
+        if !self.observe(&self.seen_transactions, hash, from_peer) {
+            self.stats.write().await.duplicates_filtered += 1;
+            record(MetricEvent::GossipDuplicate {
+                kind: "transaction",
+            });
+            return Ok(());
         }
 
-        // Mark as seen
-        self.seen_transactions.insert(
-            hash,
-            SeenItem {
-                hash,
-                first_seen: Instant::now(),
-                propagated: false,
-            },
-        );
-
         self.stats.write().await.transactions_received += 1;
 
         // Validate transaction (basic checks)
@@ -161,8 +162,37 @@ impl GossipProtocol {
         }
 
         // Propagate to other peers
-        self.propagate_transaction(tx.clone(), from_peer).await?;
+        self.propagate_transaction(tx, from_peer).await?;
+
+        Ok(())
+    }
 
+    /// Send a locally produced block to every connected peer, remembering
+    /// them so echoes of it are not relayed back
+    pub async fn announce_block(&self, block: Block) -> Result<(), NetworkError> {
+        let hash = block.hash();
+        let peers = self.connected_peers().await;
+        let peers = self
+            .claim_peers(&self.seen_blocks, hash, peers, usize::MAX)
+            .await;
+        let message = NetworkMessage::NewBlock { block };
+        self.send_claimed(&self.seen_blocks, hash, peers, &message)
+            .await;
+        self.stats.write().await.blocks_propagated += 1;
+        Ok(())
+    }
+
+    /// Send a locally submitted transaction to every connected peer
+    pub async fn announce_transaction(&self, tx: Transaction) -> Result<(), NetworkError> {
+        let hash = tx.hash;
+        let peers = self.connected_peers().await;
+        let peers = self
+            .claim_peers(&self.seen_transactions, hash, peers, usize::MAX)
+            .await;
+        let message = NetworkMessage::NewTransaction { transaction: tx };
+        self.send_claimed(&self.seen_transactions, hash, peers, &message)
+            .await;
+        self.stats.write().await.transactions_propagated += 1;
         Ok(())
     }
 
@@ -172,28 +202,21 @@ impl GossipProtocol {
         block: Block,
         exclude_peer: &PeerId,
     ) -> Result<(), NetworkError> {
-        let peers = self.select_gossip_peers(exclude_peer).await;
-
-        if peers.is_empty() {
-            return Ok(());
-        }
-
         let block_hash = block.hash();
-        let message = NetworkMessage::NewBlock { block };
+        let peers = self
+            .select_gossip_peers(&self.seen_blocks, block_hash, exclude_peer)
+            .await;
 
-        for peer in peers {
-            if let Err(e) = peer.send(message.clone()).await {
-                debug!("Failed to propagate block to peer: {}", e);
-            }
-        }
+        // With no peer left to tell, the item still counts as propagated
+        let sent = !peers.is_empty();
+        let message = NetworkMessage::NewBlock { block };
+        self.send_claimed(&self.seen_blocks, block_hash, peers, &message)
+            .await;
 
-        // Mark as propagated
-        if let Some(mut seen) = self.seen_blocks.get_mut(&block_hash) {
-            seen.propagated = true;
+        if sent {
+            self.stats.write().await.blocks_propagated += 1;
         }
 
-        self.stats.write().await.blocks_propagated += 1;
-
         Ok(())
     }
 
@@ -203,51 +226,121 @@ impl GossipProtocol {
         tx: Transaction,
         exclude_peer: &PeerId,
     ) -> Result<(), NetworkError> {
-        let peers = self.select_gossip_peers(exclude_peer).await;
+        let tx_hash = tx.hash;
+        let peers = self
+            .select_gossip_peers(&self.seen_transactions, tx_hash, exclude_peer)
+            .await;
 
-        if peers.is_empty() {
-            return Ok(());
+        // With no peer left to tell, the item still counts as propagated
+        let sent = !peers.is_empty();
+        let message = NetworkMessage::NewTransaction { transaction: tx };
+        self.send_claimed(&self.seen_transactions, tx_hash, peers, &message)
+            .await;
+
+        if sent {
+            self.stats.write().await.transactions_propagated += 1;
         }
 
-        let tx_hash = tx.hash;
-        let message = NetworkMessage::NewTransaction { transaction: tx };
+        Ok(())
+    }
+
+    /// Record that `from` announced `hash`. Returns whether the item still
+    /// needs handling: it is new, its entry outlived the TTL, or an earlier
+    /// copy was never propagated.
+    fn observe(&self, cache: &DashMap<Hash, SeenItem>, hash: Hash, from: &PeerId) -> bool {
+        let mut item = cache.entry(hash).or_insert_with(SeenItem::new);
+        if item.first_seen.elapsed() >= self.config.seen_cache_ttl {
+            *item = SeenItem::new();
+        }
+        let fresh = !item.propagated;
+        item.peers.insert(from.clone());
+        fresh
+    }
 
-        for peer in peers {
+    /// Send `message` to `peers`, which [`Self::claim_peers`] has already
+    /// recorded as having the item; peers the send fails to are released
+    async fn send_claimed(
+        &self,
+        cache: &DashMap<Hash, SeenItem>,
+        hash: Hash,
+        peers: Vec<(PeerId, Arc<Peer>)>,
+        message: &NetworkMessage,
+    ) {
+        for (peer_id, peer) in peers {
             if let Err(e) = peer.send(message.clone()).await {
-                debug!("Failed to propagate transaction to peer: {}", e);
+                debug!("Failed to gossip {} to peer {}: {}", hash, peer_id, e);
+                if let Some(mut item) = cache.get_mut(&hash) {
+                    item.peers.remove(&peer_id);
+                }
             }
         }
-
-        // Mark as propagated
-        if let Some(mut seen) = self.seen_transactions.get_mut(&tx_hash) {
-            seen.propagated = true;
+        if let Some(mut item) = cache.get_mut(&hash) {
+            item.propagated = true;
         }
+    }
 
-        self.stats.write().await.transactions_propagated += 1;
+    /// Select peers for gossip propagation: up to `fanout` connected peers,
+    /// other than `exclude`, that are not known to have the item
+    async fn select_gossip_peers(
+        &self,
+        cache: &DashMap<Hash, SeenItem>,
+        hash: Hash,
+        exclude: &PeerId,
+    ) -> Vec<(PeerId, Arc<Peer>)> {
+        let mut eligible: Vec<_> = self
+            .connected_peers()
+            .await
+            .into_iter()
+            .filter(|(id, _)| id != exclude)
+            .collect();
 
-        Ok(())
-    }
+        // Randomly select up to fanout peers
+        use rand::seq::SliceRandom;
+        eligible.shuffle(&mut rand::thread_rng());
 
-    /// Select peers for gossip propagation
-    async fn select_gossip_peers(&self, exclude: &PeerId) -> Vec<Arc<Peer>> {
-        let all_peers = self.peer_manager.get_all_peers();
+        self.claim_peers(cache, hash, eligible, self.config.fanout)
+            .await
+    }
 
-        let mut eligible: Vec<Arc<Peer>> = Vec::new();
+    /// Take up to `limit` of `candidates` not known to have the item and
+    /// record them as having it, so concurrent relays of the same item
+    /// pick different peers
+    async fn claim_peers(
+        &self,
+        cache: &DashMap<Hash, SeenItem>,
+        hash: Hash,
+        candidates: Vec<(PeerId, Arc<Peer>)>,
+        limit: usize,
+    ) -> Vec<(PeerId, Arc<Peer>)> {
+        let mut claimed = Vec::new();
+        let mut skipped = 0;
+        {
+            let mut item = cache.entry(hash).or_insert_with(SeenItem::new);
+            for (id, peer) in candidates {
+                if item.peers.contains(&id) {
+                    skipped += 1;
+                } else if claimed.len() < limit {
+                    item.peers.insert(id.clone());
+                    claimed.push((id, peer));
+                }
+            }
+        }
+        if skipped > 0 {
+            self.stats.write().await.sends_skipped += skipped;
+        }
+        claimed
+    }
 
-        for peer in all_peers {
+    /// Connected peers with their ids
+    async fn connected_peers(&self) -> Vec<(PeerId, Arc<Peer>)> {
+        let mut connected = Vec::new();
+        for peer in self.peer_manager.get_all_peers() {
             let info = peer.info.read().await;
-            if info.id != *exclude && info.state == crate::peer::PeerState::Connected {
-                eligible.push(peer.clone());
+            if info.state == crate::peer::PeerState::Connected {
+                connected.push((info.id.clone(), peer.clone()));
             }
         }
-
-        // Randomly select up to fanout peers
-        use rand::seq::SliceRandom;
-        let mut rng = rand::thread_rng();
-        eligible.shuffle(&mut rng);
-        eligible.truncate(self.config.fanout);
-
-        eligible
+        connected
     }
 
     /// Validate block (basic checks)
@@ -377,22 +470,56 @@ impl GossipProtocol {
     }
 
     /// Get gossip statistics
-    pub async fn get_stats(&self) -> (u64, u64, u64, u64, u64) {
-        let stats = self.stats.read().await;
-        (
-            stats.blocks_received,
-            stats.blocks_propagated,
-            stats.transactions_received,
-            stats.transactions_propagated,
-            stats.duplicates_filtered,
-        )
+    pub async fn get_stats(&self) -> GossipStats {
+        GossipStats {
+            seen_blocks: self.seen_blocks.len(),
+            seen_transactions: self.seen_transactions.len(),
+            ..self.stats.read().await.clone()
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::peer::PeerManagerConfig;
+    use crate::peer::{Direction, PeerInfo, PeerManagerConfig, PeerState};
+    use citrate_consensus::types::{PublicKey, Signature};
+    use tokio::sync::mpsc;
+
+    async fn connect(
+        peer_manager: &PeerManager,
+        name: &str,
+    ) -> (PeerId, mpsc::Receiver<NetworkMessage>) {
+        let id = PeerId::new(name.to_string());
+        let mut info = PeerInfo::new(
+            id.clone(),
+            "127.0.0.1:30303".parse().unwrap(),
+            Direction::Inbound,
+        );
+        info.state = PeerState::Connected;
+        let (send_tx, send_rx) = mpsc::channel(16);
+        let (_recv_tx, recv_rx) = mpsc::channel(16);
+        peer_manager
+            .add_peer(Arc::new(Peer::new(info, send_tx, recv_rx)))
+            .await
+            .unwrap();
+        (id, send_rx)
+    }
+
+    fn tx(nonce: u64) -> Transaction {
+        Transaction {
+            hash: Hash::new([nonce as u8 + 1; 32]),
+            nonce,
+            from: PublicKey::new([1; 32]),
+            to: None,
+            value: 0,
+            gas_limit: 21_000,
+            gas_price: 1_000_000_000,
+            data: vec![],
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        }
+    }
 
     #[tokio::test]
     async fn test_seen_cache() {
@@ -401,22 +528,83 @@ mod tests {
 
         // Use a simple hash for testing
         let hash = Hash::new([1; 32]);
+        let peer = PeerId::new("a".to_string());
 
         // First time seeing block
         assert!(gossip.seen_blocks.get(&hash).is_none());
+        assert!(gossip.observe(&gossip.seen_blocks, hash, &peer));
 
-        // Mark as seen
-        gossip.seen_blocks.insert(
-            hash,
-            SeenItem {
-                hash,
-                first_seen: Instant::now(),
-                propagated: false,
-            },
-        );
+        // Should now be in cache, remembering who sent it
+        assert!(gossip.seen_blocks.get(&hash).unwrap().peers.contains(&peer));
+
+        // Once propagated, further announcements are duplicates
+        gossip.seen_blocks.get_mut(&hash).unwrap().propagated = true;
+        assert!(!gossip.observe(&gossip.seen_blocks, hash, &peer));
+    }
+
+    #[tokio::test]
+    async fn test_seen_cache_ttl() {
+        let config = GossipConfig {
+            seen_cache_ttl: Duration::from_secs(60),
+            ..Default::default()
+        };
+        let peer_manager = Arc::new(PeerManager::new(PeerManagerConfig::default()));
+        let gossip = GossipProtocol::new(config, peer_manager);
+        let hash = Hash::new([1; 32]);
+        let peer = PeerId::new("a".to_string());
+
+        gossip.observe(&gossip.seen_blocks, hash, &peer);
+        {
+            let mut item = gossip.seen_blocks.get_mut(&hash).unwrap();
+            item.propagated = true;
+            item.first_seen = Instant::now() - Duration::from_secs(61);
+        }
+
+        // An expired entry no longer filters the item
+        assert!(gossip.observe(&gossip.seen_blocks, hash, &peer));
+        assert!(!gossip.seen_blocks.get(&hash).unwrap().propagated);
+    }
+
+    #[tokio::test]
+    async fn test_relay_skips_peers_that_have_item() {
+        let peer_manager = Arc::new(PeerManager::new(PeerManagerConfig::default()));
+        let (a, mut a_rx) = connect(&peer_manager, "a").await;
+        let (b, mut b_rx) = connect(&peer_manager, "b").await;
+        let (_c, mut c_rx) = connect(&peer_manager, "c").await;
+        let gossip = GossipProtocol::new(GossipConfig::default(), peer_manager);
 
-        // Should now be in cache
-        assert!(gossip.seen_blocks.get(&hash).is_some());
+        // Relayed to everyone but the sender
+        gossip.handle_new_transaction(tx(0), &a).await.unwrap();
+        assert!(a_rx.try_recv().is_err());
+        assert!(b_rx.try_recv().is_ok());
+        assert!(c_rx.try_recv().is_ok());
+
+        // B echoes it back: dropped without another send
+        gossip.handle_new_transaction(tx(0), &b).await.unwrap();
+        assert!(a_rx.try_recv().is_err());
+        assert!(c_rx.try_recv().is_err());
+
+        // A second relay of the same item only reaches peers without it
+        let hash = tx(0).hash;
+        let candidates = gossip.connected_peers().await;
+        assert!(gossip
+            .claim_peers(&gossip.seen_transactions, hash, candidates, usize::MAX)
+            .await
+            .is_empty());
+
+        // Our own announcement is not relayed back when echoed
+        gossip.announce_transaction(tx(1)).await.unwrap();
+        assert!(a_rx.try_recv().is_ok());
+        gossip.handle_new_transaction(tx(1), &a).await.unwrap();
+        assert!(b_rx.try_recv().is_ok());
+        assert!(b_rx.try_recv().is_err());
+
+        let stats = gossip.get_stats().await;
+        assert_eq!(stats.transactions_received, 1);
+        assert_eq!(stats.transactions_propagated, 2);
+        assert_eq!(stats.duplicates_filtered, 2);
+        assert_eq!(stats.sends_skipped, 3);
+        assert_eq!(stats.seen_transactions, 2);
     }
 
     #[tokio::test]
@@ -435,9 +623,8 @@ mod tests {
             gossip.seen_blocks.insert(
                 hash,
                 SeenItem {
-                    hash,
                     first_seen: Instant::now() - Duration::from_secs(i as u64),
-                    propagated: false,
+                    ..SeenItem::new()
                 },
             );
         }
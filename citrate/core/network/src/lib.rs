@@ -2,23 +2,37 @@
 
 // Network module for peer-to-peer communication
 pub mod ai_handler;
+pub mod bandwidth;
 pub mod block_propagation;
 pub mod discovery;
 pub mod gossip;
+pub mod model_sync;
 pub mod peer;
 pub mod protocol;
+pub mod reputation;
 pub mod sync;
+pub mod sync_pipeline;
+pub mod sync_progress;
 pub mod transaction_gossip;
 pub mod types;
 pub mod transport;
+pub mod version;
 
 pub use ai_handler::AINetworkHandler;
+pub use bandwidth::{BandwidthMeter, BandwidthStats, MessageTraffic};
 pub use block_propagation::BlockPropagation;
 pub use discovery::{Discovery, DiscoveryConfig};
-pub use gossip::{GossipConfig, GossipProtocol};
+pub use gossip::{GossipConfig, GossipProtocol, GossipStats};
+pub use model_sync::{ModelSync, ModelSyncConfig};
 pub use peer::{Peer, PeerId, PeerInfo, PeerManager, PeerManagerConfig};
-pub use protocol::{ModelMetadata, NetworkMessage, Protocol, ProtocolVersion};
+pub use protocol::{ModelMetadata, NetworkMessage, Protocol, ProtocolVersion, WeightManifest};
+pub use reputation::{PeerReputation, ReputationConfig, ReputationEvent, ReputationManager};
 pub use sync::{SyncConfig, SyncManager, SyncState};
+pub use sync_pipeline::{
+    BlockExecutor, BlockRejection, ImportOutcome, SyncPipeline, SyncPipelineConfig,
+};
+pub use sync_progress::{RateMeter, StageProgress, SyncProgress, SyncStage, SyncStages};
 pub use transaction_gossip::{GossipConfig as TxGossipConfig, TransactionGossip};
 pub use types::{NetworkConfig, NetworkError};
 pub use transport::NetworkTransport;
+pub use version::{ClientVersion, VersionDistribution, CLIENT_VERSION};
@@ -0,0 +1,634 @@
+// citrate/core/network/src/model_sync.rs
+
+// Chunked model weight transfer between peers
+//
+// Nodes that have a model's weights pinned serve them in fixed-size chunks
+// and announce that with `ModelPinned` (see `AINetworkHandler`). A node
+// missing the weights asks those peers for a manifest listing each chunk's
+// SHA-256, then downloads chunks from all of them in parallel. Every chunk
+// is checked against the manifest as it arrives, and the assembled file
+// against the SHA-256 registered for the model.
+use crate::peer::{PeerId, PeerManager};
+use crate::protocol::{NetworkMessage, WeightManifest};
+use crate::NetworkError;
+use citrate_consensus::types::Hash;
+use futures::stream::{FuturesUnordered, StreamExt};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{oneshot, Mutex, RwLock};
+use tracing::{debug, info, warn};
+
+/// Default chunk size for pinned weights
+pub const DEFAULT_CHUNK_SIZE: u32 = 512 * 1024;
+
+/// Largest chunk that still fits in one network frame with its envelope
+pub const MAX_CHUNK_SIZE: u32 = 960 * 1024;
+
+/// Score penalty for a peer that served a chunk not matching its manifest
+const BAD_CHUNK_PENALTY: i32 = -20;
+
+#[derive(Debug, Clone)]
+pub struct ModelSyncConfig {
+    /// Chunk size for weights pinned by this node
+    pub chunk_size: u32,
+
+    /// Maximum chunk requests in flight per download
+    pub max_parallel: usize,
+
+    /// Time to wait for a manifest or chunk
+    pub request_timeout: Duration,
+
+    /// Attempts per chunk before a download fails
+    pub max_retries: u32,
+}
+
+impl Default for ModelSyncConfig {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            max_parallel: 16,
+            request_timeout: Duration::from_secs(30),
+            max_retries: 5,
+        }
+    }
+}
+
+/// Weights this node serves
+struct PinnedWeights {
+    path: PathBuf,
+    manifest: WeightManifest,
+}
+
+type ManifestWaiters = HashMap<(Hash, PeerId), oneshot::Sender<Option<WeightManifest>>>;
+type ChunkWaiters = HashMap<(Hash, u32, PeerId), oneshot::Sender<Option<Vec<u8>>>>;
+
+/// Serves pinned weights to peers and downloads missing weights from them
+pub struct ModelSync {
+    config: ModelSyncConfig,
+    peer_manager: Arc<PeerManager>,
+    pinned: RwLock<HashMap<Hash, PinnedWeights>>,
+    /// Peers that announced each model's weights
+    providers: RwLock<HashMap<Hash, Vec<PeerId>>>,
+    pending_manifests: Mutex<ManifestWaiters>,
+    pending_chunks: Mutex<ChunkWaiters>,
+}
+
+impl ModelSync {
+    pub fn new(config: ModelSyncConfig, peer_manager: Arc<PeerManager>) -> Self {
+        Self {
+            config,
+            peer_manager,
+            pinned: RwLock::new(HashMap::new()),
+            providers: RwLock::new(HashMap::new()),
+            pending_manifests: Mutex::new(HashMap::new()),
+            pending_chunks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Serve the weights at `path` for `model_id`. Hashes the file and
+    /// returns its manifest.
+    pub async fn pin(&self, model_id: Hash, path: PathBuf) -> Result<WeightManifest, NetworkError> {
+        let chunk_size = self.config.chunk_size.clamp(1, MAX_CHUNK_SIZE);
+        let file_path = path.clone();
+        let manifest =
+            tokio::task::spawn_blocking(move || build_manifest(model_id, &file_path, chunk_size))
+                .await
+                .map_err(|e| NetworkError::Storage(e.to_string()))??;
+
+        info!(
+            "Pinned model {} weights for peers: {} bytes in {} chunks",
+            model_id,
+            manifest.total_size,
+            manifest.chunk_hashes.len()
+        );
+        self.pinned.write().await.insert(
+            model_id,
+            PinnedWeights {
+                path,
+                manifest: manifest.clone(),
+            },
+        );
+        Ok(manifest)
+    }
+
+    /// Stop serving `model_id`
+    pub async fn unpin(&self, model_id: &Hash) -> bool {
+        self.pinned.write().await.remove(model_id).is_some()
+    }
+
+    /// Manifest of weights this node serves
+    pub async fn manifest(&self, model_id: &Hash) -> Option<WeightManifest> {
+        self.pinned
+            .read()
+            .await
+            .get(model_id)
+            .map(|pinned| pinned.manifest.clone())
+    }
+
+    /// Record that `peer_id` serves `model_id`
+    pub async fn add_provider(&self, model_id: Hash, peer_id: PeerId) {
+        let mut providers = self.providers.write().await;
+        let peers = providers.entry(model_id).or_default();
+        if !peers.contains(&peer_id) {
+            peers.push(peer_id);
+        }
+    }
+
+    /// Connected peers that announced `model_id`
+    pub async fn providers(&self, model_id: &Hash) -> Vec<PeerId> {
+        self.providers
+            .read()
+            .await
+            .get(model_id)
+            .map(|peers| {
+                peers
+                    .iter()
+                    .filter(|peer_id| self.peer_manager.get_peer(peer_id).is_some())
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Handle a model sync message, returning the reply for requests
+    pub async fn handle_message(
+        &self,
+        peer_id: &PeerId,
+        message: &NetworkMessage,
+    ) -> Result<Option<NetworkMessage>, NetworkError> {
+        match message {
+            NetworkMessage::GetModelManifest { model_id } => {
+                Ok(Some(NetworkMessage::ModelManifest {
+                    model_id: *model_id,
+                    manifest: self.manifest(model_id).await,
+                }))
+            }
+
+            NetworkMessage::GetModelChunk { model_id, index } => {
+                let data = self.read_chunk(model_id, *index).await?;
+                Ok(Some(NetworkMessage::ModelChunk {
+                    model_id: *model_id,
+                    index: *index,
+                    data,
+                }))
+            }
+
+            NetworkMessage::ModelManifest { model_id, manifest } => {
+                let key = (*model_id, peer_id.clone());
+                match self.pending_manifests.lock().await.remove(&key) {
+                    Some(tx) => {
+                        let _ = tx.send(manifest.clone());
+                    }
+                    None => debug!("Unrequested manifest for {} from {}", model_id, peer_id),
+                }
+                Ok(None)
+            }
+
+            NetworkMessage::ModelChunk {
+                model_id,
+                index,
+                data,
+            } => {
+                let key = (*model_id, *index, peer_id.clone());
+                match self.pending_chunks.lock().await.remove(&key) {
+                    Some(tx) => {
+                        let _ = tx.send(data.clone());
+                    }
+                    None => debug!(
+                        "Unrequested chunk {} of {} from {}",
+                        index, model_id, peer_id
+                    ),
+                }
+                Ok(None)
+            }
+
+            _ => Ok(None),
+        }
+    }
+
+    async fn read_chunk(
+        &self,
+        model_id: &Hash,
+        index: u32,
+    ) -> Result<Option<Vec<u8>>, NetworkError> {
+        let (path, offset, len) = {
+            let pinned = self.pinned.read().await;
+            let Some(weights) = pinned.get(model_id) else {
+                return Ok(None);
+            };
+            let manifest = &weights.manifest;
+            if index as usize >= manifest.chunk_hashes.len() {
+                return Ok(None);
+            }
+            let offset = index as u64 * manifest.chunk_size as u64;
+            let len = (manifest.total_size - offset).min(manifest.chunk_size as u64);
+            (weights.path.clone(), offset, len as usize)
+        };
+
+        let data = tokio::task::spawn_blocking(move || -> std::io::Result<Vec<u8>> {
+            let mut file = std::fs::File::open(&path)?;
+            file.seek(SeekFrom::Start(offset))?;
+            let mut data = vec![0u8; len];
+            file.read_exact(&mut data)?;
+            Ok(data)
+        })
+        .await
+        .map_err(|e| NetworkError::Storage(e.to_string()))??;
+        Ok(Some(data))
+    }
+
+    /// Download `model_id` from the peers that announced it into `dest`.
+    /// The weights must hash to `sha256`, the SHA-256 registered for the
+    /// model. Returns the number of bytes written.
+    pub async fn download(
+        &self,
+        model_id: Hash,
+        sha256: Hash,
+        dest: &Path,
+    ) -> Result<u64, NetworkError> {
+        let mut providers = self.providers(&model_id).await;
+        if providers.is_empty() {
+            return Err(NetworkError::PeerNotFound(format!(
+                "no connected peer serves model {}",
+                model_id
+            )));
+        }
+
+        let manifest = self.fetch_manifest(model_id, sha256, &providers).await?;
+        let part = dest.with_extension("part");
+        let file = std::fs::File::create(&part)?;
+        file.set_len(manifest.total_size)?;
+        let file = std::sync::Mutex::new(file);
+
+        info!(
+            "Downloading model {} ({} bytes, {} chunks) from {} peers",
+            model_id,
+            manifest.total_size,
+            manifest.chunk_hashes.len(),
+            providers.len()
+        );
+
+        let mut queue: VecDeque<(u32, u32)> = (0..manifest.chunk_hashes.len() as u32)
+            .map(|i| (i, 0))
+            .collect();
+        let mut in_flight = FuturesUnordered::new();
+        let mut next_provider = 0usize;
+        let max_parallel = self.config.max_parallel.max(1);
+
+        while !queue.is_empty() || !in_flight.is_empty() {
+            while in_flight.len() < max_parallel {
+                let Some((index, attempts)) = queue.pop_front() else {
+                    break;
+                };
+                let peer_id = providers[next_provider % providers.len()].clone();
+                next_provider += 1;
+                in_flight.push(async move {
+                    let result = self.request_chunk(model_id, index, &peer_id).await;
+                    (index, attempts, peer_id, result)
+                });
+            }
+
+            let Some((index, attempts, peer_id, result)) = in_flight.next().await else {
+                break;
+            };
+            let error = match result {
+                Ok(data) if chunk_hash(&data) == manifest.chunk_hashes[index as usize] => {
+                    let offset = index as u64 * manifest.chunk_size as u64;
+                    let mut file = file.lock().unwrap_or_else(|e| e.into_inner());
+                    file.seek(SeekFrom::Start(offset))?;
+                    file.write_all(&data)?;
+                    continue;
+                }
+                Ok(_) => {
+                    // Stop asking a peer that serves corrupt data
+                    self.peer_manager
+                        .report_misbehavior(&peer_id, BAD_CHUNK_PENALTY, "corrupt model chunk")
+                        .await;
+                    providers.retain(|p| p != &peer_id);
+                    format!("chunk {} from {} failed its hash check", index, peer_id)
+                }
+                Err(e) => format!("chunk {} from {}: {}", index, peer_id, e),
+            };
+
+            warn!("Model {} download: {}", model_id, error);
+            if attempts + 1 >= self.config.max_retries || providers.is_empty() {
+                drop(in_flight);
+                let _ = std::fs::remove_file(&part);
+                return Err(NetworkError::SyncError(format!(
+                    "model {} download failed: {}",
+                    model_id, error
+                )));
+            }
+            queue.push_back((index, attempts + 1));
+        }
+
+        let file = file.into_inner().unwrap_or_else(|e| e.into_inner());
+        file.sync_all()?;
+        drop(file);
+
+        let check = part.clone();
+        let actual = tokio::task::spawn_blocking(move || file_hash(&check))
+            .await
+            .map_err(|e| NetworkError::Storage(e.to_string()))??;
+        if actual != sha256 {
+            let _ = std::fs::remove_file(&part);
+            return Err(NetworkError::SyncError(format!(
+                "model {} weights hash to {}, expected {}",
+                model_id, actual, sha256
+            )));
+        }
+        std::fs::rename(&part, dest)?;
+
+        info!("Downloaded model {} to {}", model_id, dest.display());
+        Ok(manifest.total_size)
+    }
+
+    /// First well-formed manifest for `sha256` from `providers`
+    async fn fetch_manifest(
+        &self,
+        model_id: Hash,
+        sha256: Hash,
+        providers: &[PeerId],
+    ) -> Result<WeightManifest, NetworkError> {
+        for peer_id in providers {
+            let (tx, rx) = oneshot::channel();
+            let key = (model_id, peer_id.clone());
+            self.pending_manifests.lock().await.insert(key.clone(), tx);
+
+            let reply = match self
+                .send(peer_id, NetworkMessage::GetModelManifest { model_id })
+                .await
+            {
+                Ok(()) => tokio::time::timeout(self.config.request_timeout, rx).await,
+                Err(e) => {
+                    debug!("Manifest request to {} failed: {}", peer_id, e);
+                    Ok(Ok(None))
+                }
+            };
+            self.pending_manifests.lock().await.remove(&key);
+
+            match reply {
+                Ok(Ok(Some(manifest))) if manifest.sha256 == sha256 => {
+                    match validate_manifest(&manifest, model_id) {
+                        Ok(()) => return Ok(manifest),
+                        Err(e) => warn!("Invalid manifest from {}: {}", peer_id, e),
+                    }
+                }
+                Ok(Ok(Some(manifest))) => debug!(
+                    "Peer {} serves model {} with hash {}, expected {}",
+                    peer_id, model_id, manifest.sha256, sha256
+                ),
+                Ok(_) => debug!("Peer {} does not serve model {}", peer_id, model_id),
+                Err(_) => debug!("Manifest request to {} timed out", peer_id),
+            }
+        }
+
+        Err(NetworkError::PeerNotFound(format!(
+            "no peer serves model {} with hash {}",
+            model_id, sha256
+        )))
+    }
+
+    async fn request_chunk(
+        &self,
+        model_id: Hash,
+        index: u32,
+        peer_id: &PeerId,
+    ) -> Result<Vec<u8>, NetworkError> {
+        let (tx, rx) = oneshot::channel();
+        let key = (model_id, index, peer_id.clone());
+        self.pending_chunks.lock().await.insert(key.clone(), tx);
+
+        let result = match self
+            .send(peer_id, NetworkMessage::GetModelChunk { model_id, index })
+            .await
+        {
+            Ok(()) => match tokio::time::timeout(self.config.request_timeout, rx).await {
+                Ok(Ok(Some(data))) => Ok(data),
+                Ok(Ok(None)) => Err(NetworkError::InvalidMessage(
+                    "peer no longer serves the model".to_string(),
+                )),
+                Ok(Err(_)) => Err(NetworkError::Shutdown),
+                Err(_) => Err(NetworkError::Timeout("chunk request".to_string())),
+            },
+            Err(e) => Err(e),
+        };
+        self.pending_chunks.lock().await.remove(&key);
+        result
+    }
+
+    async fn send(&self, peer_id: &PeerId, message: NetworkMessage) -> Result<(), NetworkError> {
+        match self.peer_manager.get_peer(peer_id) {
+            Some(peer) => peer.send(message).await,
+            None => Err(NetworkError::PeerNotFound(peer_id.to_string())),
+        }
+    }
+}
+
+fn chunk_hash(data: &[u8]) -> Hash {
+    Hash::new(Sha256::digest(data).into())
+}
+
+fn file_hash(path: &Path) -> std::io::Result<Hash> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(Hash::new(hasher.finalize().into()))
+}
+
+fn build_manifest(
+    model_id: Hash,
+    path: &Path,
+    chunk_size: u32,
+) -> Result<WeightManifest, NetworkError> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut chunk_hashes = Vec::new();
+    let mut total_size = 0u64;
+    let mut buf = vec![0u8; chunk_size as usize];
+    loop {
+        // Fill the whole chunk; reads may return less
+        let mut filled = 0;
+        while filled < buf.len() {
+            match file.read(&mut buf[filled..])? {
+                0 => break,
+                n => filled += n,
+            }
+        }
+        if filled == 0 {
+            break;
+        }
+        hasher.update(&buf[..filled]);
+        chunk_hashes.push(chunk_hash(&buf[..filled]));
+        total_size += filled as u64;
+    }
+
+    Ok(WeightManifest {
+        model_id,
+        sha256: Hash::new(hasher.finalize().into()),
+        total_size,
+        chunk_size,
+        chunk_hashes,
+    })
+}
+
+fn validate_manifest(manifest: &WeightManifest, model_id: Hash) -> Result<(), String> {
+    if manifest.model_id != model_id {
+        return Err(format!("manifest is for model {}", manifest.model_id));
+    }
+    if manifest.chunk_size == 0 || manifest.chunk_size > MAX_CHUNK_SIZE {
+        return Err(format!("chunk size {} out of range", manifest.chunk_size));
+    }
+    let chunks = manifest.total_size.div_ceil(manifest.chunk_size as u64);
+    if chunks != manifest.chunk_hashes.len() as u64 {
+        return Err(format!(
+            "{} chunk hashes for {} bytes",
+            manifest.chunk_hashes.len(),
+            manifest.total_size
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peer::{Direction, Peer, PeerInfo, PeerManagerConfig};
+    use tokio::sync::mpsc;
+
+    fn weights(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 31 % 251) as u8).collect()
+    }
+
+    fn config() -> ModelSyncConfig {
+        ModelSyncConfig {
+            chunk_size: 1000,
+            max_parallel: 4,
+            request_timeout: Duration::from_secs(5),
+            max_retries: 3,
+        }
+    }
+
+    /// Connect `client` to `server` through an in-memory peer that answers
+    /// every request with `server`'s reply, passed through `tamper`
+    async fn connect(
+        client: Arc<ModelSync>,
+        manager: &PeerManager,
+        server: Arc<ModelSync>,
+        id: &str,
+        tamper: fn(NetworkMessage) -> NetworkMessage,
+    ) -> PeerId {
+        let peer_id = PeerId::new(id.to_string());
+        let (send_tx, mut send_rx) = mpsc::channel(64);
+        let (_recv_tx, recv_rx) = mpsc::channel(1);
+        let info = PeerInfo::new(
+            peer_id.clone(),
+            "127.0.0.1:30303".parse().unwrap(),
+            Direction::Outbound,
+        );
+        manager
+            .add_peer(Arc::new(Peer::new(info, send_tx, recv_rx)))
+            .await
+            .unwrap();
+
+        let remote = peer_id.clone();
+        tokio::spawn(async move {
+            let local = PeerId::new("client".to_string());
+            while let Some(request) = send_rx.recv().await {
+                if let Ok(Some(reply)) = server.handle_message(&local, &request).await {
+                    let _ = client.handle_message(&remote, &tamper(reply)).await;
+                }
+            }
+        });
+        peer_id
+    }
+
+    #[tokio::test]
+    async fn test_parallel_download_verifies_chunks() {
+        let dir = std::env::temp_dir().join(format!("model_sync_{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let data = weights(4500);
+        let source = dir.join("source.gguf");
+        std::fs::write(&source, &data).unwrap();
+
+        let model_id = Hash::new([7; 32]);
+        let server_manager = Arc::new(PeerManager::new(PeerManagerConfig::default()));
+        let server = Arc::new(ModelSync::new(config(), server_manager));
+        let manifest = server.pin(model_id, source).await.unwrap();
+        assert_eq!(manifest.chunk_hashes.len(), 5);
+        assert_eq!(manifest.sha256, chunk_hash(&data));
+
+        let manager = Arc::new(PeerManager::new(PeerManagerConfig::default()));
+        let client = Arc::new(ModelSync::new(config(), manager.clone()));
+        let honest = connect(client.clone(), &manager, server.clone(), "honest", |m| m).await;
+        // Serves the right manifest but flips a byte in every chunk
+        let corrupt = connect(
+            client.clone(),
+            &manager,
+            server.clone(),
+            "corrupt",
+            |m| match m {
+                NetworkMessage::ModelChunk {
+                    model_id,
+                    index,
+                    data: Some(mut data),
+                } => {
+                    data[0] ^= 0xff;
+                    NetworkMessage::ModelChunk {
+                        model_id,
+                        index,
+                        data: Some(data),
+                    }
+                }
+                other => other,
+            },
+        )
+        .await;
+        client.add_provider(model_id, corrupt.clone()).await;
+        client.add_provider(model_id, honest).await;
+
+        let dest = dir.join("model.gguf");
+        let written = client
+            .download(model_id, manifest.sha256, &dest)
+            .await
+            .unwrap();
+        assert_eq!(written, 4500);
+        assert_eq!(std::fs::read(&dest).unwrap(), data);
+        assert!(!dest.with_extension("part").exists());
+        // The corrupt peer was penalized and dropped
+        assert!(manager.get_peer(&corrupt).unwrap().info.read().await.score < 0);
+
+        // A registered hash no provider matches is refused
+        let err = client
+            .download(model_id, Hash::new([1; 32]), &dir.join("other.gguf"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, NetworkError::PeerNotFound(_)));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_validate_manifest() {
+        let model_id = Hash::new([1; 32]);
+        let mut manifest = WeightManifest {
+            model_id,
+            sha256: Hash::default(),
+            total_size: 2500,
+            chunk_size: 1000,
+            chunk_hashes: vec![Hash::default(); 3],
+        };
+        assert!(validate_manifest(&manifest, model_id).is_ok());
+        assert!(validate_manifest(&manifest, Hash::new([2; 32])).is_err());
+
+        manifest.chunk_hashes.pop();
+        assert!(validate_manifest(&manifest, model_id).is_err());
+
+        manifest.chunk_size = MAX_CHUNK_SIZE + 1;
+        assert!(validate_manifest(&manifest, model_id).is_err());
+    }
+}
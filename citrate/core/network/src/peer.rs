@@ -1,6 +1,9 @@
 // citrate/core/network/src/peer.rs
 
 // Peer connection and management
+use crate::bandwidth::{BandwidthMeter, TrafficDirection};
+use crate::reputation::{ReputationConfig, ReputationManager};
+use crate::version::{ClientVersion, VersionDistribution, CLIENT_VERSION};
 use crate::{NetworkError, NetworkMessage, ProtocolVersion};
 use dashmap::DashMap;
 use futures::{SinkExt, StreamExt};
@@ -59,6 +62,8 @@ pub struct PeerInfo {
     pub state: PeerState,
     pub direction: Direction,
     pub version: Option<ProtocolVersion>,
+    /// Client software version reported in the handshake
+    pub client_version: Option<String>,
     pub head_height: u64,
     pub head_hash: Hash,
     pub connected_at: Instant,
@@ -79,6 +84,7 @@ impl PeerInfo {
             state: PeerState::Connecting,
             direction,
             version: None,
+            client_version: None,
             head_height: 0,
             head_hash: Hash::default(),
             connected_at: now,
@@ -149,8 +155,9 @@ type IncomingTx = mpsc::Sender<(PeerId, NetworkMessage)>;
 pub struct PeerManager {
     config: PeerManagerConfig,
     peers: Arc<DashMap<PeerId, Arc<Peer>>>,
-    banned_peers: Arc<RwLock<Vec<SocketAddr>>>,
+    reputation: Arc<ReputationManager>,
     stats: Arc<RwLock<PeerStats>>,
+    bandwidth: Arc<BandwidthMeter>,
     pub(crate) incoming: Arc<RwLock<Option<IncomingTx>>>,
 }
 
@@ -184,13 +191,31 @@ struct PeerStats {
     outbound_count: usize,
 }
 
+impl PeerManagerConfig {
+    /// Reputation settings derived from the ban parameters
+    pub fn reputation_config(&self) -> ReputationConfig {
+        ReputationConfig {
+            ban_threshold: self.score_threshold,
+            ban_duration: self.ban_duration,
+            ..Default::default()
+        }
+    }
+}
+
 impl PeerManager {
     pub fn new(config: PeerManagerConfig) -> Self {
+        let reputation = Arc::new(ReputationManager::new(config.reputation_config()));
+        Self::with_reputation(config, reputation)
+    }
+
+    /// Create a peer manager sharing an existing (possibly persistent) reputation store
+    pub fn with_reputation(config: PeerManagerConfig, reputation: Arc<ReputationManager>) -> Self {
         Self {
             config,
             peers: Arc::new(DashMap::new()),
-            banned_peers: Arc::new(RwLock::new(Vec::new())),
+            reputation,
             stats: Arc::new(RwLock::new(PeerStats::default())),
+            bandwidth: Arc::new(BandwidthMeter::new()),
             incoming: Arc::new(RwLock::new(None)),
         }
     }
@@ -329,30 +354,63 @@ impl PeerManager {
         )
     }
 
+    /// Client versions reported by connected peers
+    pub async fn version_distribution(&self) -> VersionDistribution {
+        let mut versions = Vec::new();
+        for peer in self.get_all_peers() {
+            versions.push(peer.info.read().await.client_version.clone());
+        }
+        VersionDistribution::from_peers(
+            ClientVersion::current(),
+            versions.iter().map(|v| v.as_deref()),
+        )
+    }
+
+    /// Persistent reputation and ban list
+    pub fn reputation(&self) -> Arc<ReputationManager> {
+        self.reputation.clone()
+    }
+
+    /// Bytes sent and received over peer connections, per message type
+    pub fn bandwidth(&self) -> Arc<BandwidthMeter> {
+        self.bandwidth.clone()
+    }
+
     /// Ban a peer
     pub async fn ban_peer(&self, addr: SocketAddr) {
-        let mut banned = self.banned_peers.write().await;
-        if !banned.contains(&addr) {
-            banned.push(addr);
-            warn!("Banned peer: {}", addr);
-        }
+        self.reputation.ban(addr.ip(), "banned");
     }
 
     /// Check if an address is banned
     pub async fn is_banned(&self, addr: &SocketAddr) -> bool {
-        self.banned_peers.read().await.contains(addr)
+        self.reputation.is_banned(&addr.ip())
     }
 
     /// Update peer score
     pub async fn update_peer_score(&self, peer_id: &PeerId, delta: i32) {
+        self.report_misbehavior(peer_id, delta, "score adjustment")
+            .await;
+    }
+
+    /// Adjust a peer's score with a reason that is kept in its reputation
+    /// history. Peers that fall below the threshold are banned and dropped.
+    pub async fn report_misbehavior(&self, peer_id: &PeerId, delta: i32, reason: &str) {
         if let Some(peer) = self.get_peer(peer_id) {
             let mut info = peer.info.write().await;
             info.score += delta;
+            let addr = info.addr;
+            let session_score = info.score;
+            drop(info);
+
+            let banned = self
+                .reputation
+                .record(addr.ip(), Some(&peer_id.0), delta, reason);
 
             // Ban if score too low
-            if info.score < self.config.score_threshold {
-                drop(info);
-                self.ban_peer(peer.info.read().await.addr).await;
+            if banned || session_score < self.config.score_threshold {
+                if !banned && !self.reputation.is_banned(&addr.ip()) {
+                    self.reputation.ban(addr.ip(), reason);
+                }
                 self.remove_peer(peer_id).await;
             }
         }
@@ -487,6 +545,9 @@ async fn handle_incoming(
     head_height: u64,
     head_hash: Hash,
 ) -> Result<(), NetworkError> {
+    if pm.is_banned(&addr).await {
+        return Err(NetworkError::ConnectionFailed("Peer is banned".to_string()));
+    }
     let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
     // Expect Hello
     let bytes = framed
@@ -496,13 +557,16 @@ async fn handle_incoming(
         .map_err(|_| NetworkError::ProtocolError("Stream closed".into()))??;
     let hello: NetworkMessage = bincode::deserialize(&bytes)
         .map_err(|e| NetworkError::DecodeError(format!("handshake decode: {}", e)))?;
-    let (peer_id_str, ver, net_ok) = match hello {
+    pm.bandwidth
+        .record(TrafficDirection::In, &hello, bytes.len());
+    let (peer_id_str, ver, net_ok, client_version) = match hello {
         NetworkMessage::Hello {
             version,
             network_id: nid,
             peer_id,
+            client_version,
             ..
-        } => (peer_id, version, nid == network_id),
+        } => (peer_id, version, nid == network_id, client_version),
         _ => return Err(NetworkError::ProtocolError("Expected Hello".into())),
     };
     if !ver.is_compatible(&ProtocolVersion::CURRENT) || !net_ok {
@@ -512,6 +576,7 @@ async fn handle_incoming(
             &NetworkMessage::Disconnect {
                 reason: "incompatible".into(),
             },
+            &pm.bandwidth,
         )
         .await;
         return Err(NetworkError::ProtocolError(
@@ -523,7 +588,9 @@ async fn handle_incoming(
     // Channels for app-level messaging
     let (send_tx, mut send_rx) = mpsc::channel(256);
     let (recv_tx_app, recv_rx) = mpsc::channel(256);
-    let info = PeerInfo::new(peer_id.clone(), addr, Direction::Inbound);
+    let mut info = PeerInfo::new(peer_id.clone(), addr, Direction::Inbound);
+    info.version = Some(ver);
+    info.client_version = Some(client_version);
     let peer = Arc::new(Peer::new(info, send_tx.clone(), recv_rx));
     pm.add_peer(peer.clone()).await?;
     // Reply HelloAck
@@ -532,13 +599,15 @@ async fn handle_incoming(
         head_height,
         head_hash,
         peer_id: peer_id.0.clone(),
+        client_version: CLIENT_VERSION.to_string(),
     };
-    send_msg(&mut framed, &ack).await?;
+    send_msg(&mut framed, &ack, &pm.bandwidth).await?;
     // Split framed into sink and stream
     let (mut sink, mut stream) = framed.split();
+    let meter = pm.bandwidth.clone();
     let writer = tokio::spawn(async move {
         while let Some(msg) = send_rx.recv().await {
-            if send_msg_sink(&mut sink, &msg).await.is_err() {
+            if send_msg_sink(&mut sink, &msg, &meter).await.is_err() {
                 break;
             }
         }
@@ -550,6 +619,7 @@ async fn handle_incoming(
             Err(_) => break,
         };
         if let Ok(msg) = bincode::deserialize::<NetworkMessage>(&bytes) {
+            pm.bandwidth.record(TrafficDirection::In, &msg, bytes.len());
             // Basic responses
             match msg {
                 NetworkMessage::Ping { nonce } => {
@@ -595,8 +665,9 @@ async fn perform_handshake_outbound(
         head_height,
         head_hash,
         peer_id: peer_id.0.clone(),
+        client_version: CLIENT_VERSION.to_string(),
     };
-    send_msg(&mut framed, &hello).await?;
+    send_msg(&mut framed, &hello, &pm.bandwidth).await?;
     // Expect Ack
     let bytes = framed
         .next()
@@ -605,23 +676,30 @@ async fn perform_handshake_outbound(
         .map_err(|_| NetworkError::ProtocolError("Stream closed".into()))??;
     let ack: NetworkMessage = bincode::deserialize(&bytes)
         .map_err(|e| NetworkError::DecodeError(format!("ack decode: {}", e)))?;
-    match ack {
-        NetworkMessage::HelloAck { version, .. }
-            if version.is_compatible(&ProtocolVersion::CURRENT) => {}
+    pm.bandwidth.record(TrafficDirection::In, &ack, bytes.len());
+    let (version, client_version) = match ack {
+        NetworkMessage::HelloAck {
+            version,
+            client_version,
+            ..
+        } if version.is_compatible(&ProtocolVersion::CURRENT) => (version, client_version),
         _ => {
             return Err(NetworkError::ProtocolError("invalid ack".into()));
         }
-    }
+    };
     // Register peer and spawn IO
     let (send_tx, mut send_rx) = mpsc::channel(256);
     let (_recv_tx, recv_rx) = mpsc::channel(256);
-    let info = PeerInfo::new(peer_id.clone(), addr, Direction::Outbound);
+    let mut info = PeerInfo::new(peer_id.clone(), addr, Direction::Outbound);
+    info.version = Some(version);
+    info.client_version = Some(client_version);
     let peer = Arc::new(Peer::new(info, send_tx.clone(), recv_rx));
     pm.add_peer(peer.clone()).await?;
     let (mut sink, mut stream) = framed.split();
+    let meter = pm.bandwidth.clone();
     let writer = tokio::spawn(async move {
         while let Some(msg) = send_rx.recv().await {
-            if let Err(_e) = send_msg_sink(&mut sink, &msg).await {
+            if let Err(_e) = send_msg_sink(&mut sink, &msg, &meter).await {
                 break;
             }
         }
@@ -631,6 +709,8 @@ async fn perform_handshake_outbound(
         while let Some(frame) = stream.next().await {
             if let Ok(bytes) = frame {
                 if let Ok(msg) = bincode::deserialize::<NetworkMessage>(&bytes) {
+                    pm2.bandwidth
+                        .record(TrafficDirection::In, &msg, bytes.len());
                     if let Some(tx) = pm2.incoming.read().await.clone() {
                         let _ = tx.send((peer_id.clone(), msg)).await;
                     }
@@ -648,17 +728,24 @@ async fn perform_handshake_outbound(
 async fn send_msg(
     framed: &mut Framed<TcpStream, LengthDelimitedCodec>,
     msg: &NetworkMessage,
+    meter: &BandwidthMeter,
 ) -> Result<(), NetworkError> {
-    let bytes = bincode::serialize(msg).map_err(|e| NetworkError::DecodeError(e.to_string()))?;
-    framed.send(bytes.into()).await.map_err(NetworkError::Io)
+    send_msg_sink(framed, msg, meter).await
 }
 
-async fn send_msg_sink<S>(sink: &mut S, msg: &NetworkMessage) -> Result<(), NetworkError>
+async fn send_msg_sink<S>(
+    sink: &mut S,
+    msg: &NetworkMessage,
+    meter: &BandwidthMeter,
+) -> Result<(), NetworkError>
 where
     S: futures::Sink<bytes::Bytes, Error = std::io::Error> + Unpin,
 {
     let bytes = bincode::serialize(msg).map_err(|e| NetworkError::DecodeError(e.to_string()))?;
-    sink.send(bytes.into()).await.map_err(NetworkError::Io)
+    let len = bytes.len();
+    sink.send(bytes.into()).await.map_err(NetworkError::Io)?;
+    meter.record(TrafficDirection::Out, msg, len);
+    Ok(())
 }
 
 #[cfg(test)]
@@ -1,6 +1,8 @@
 // citrate/core/network/src/protocol.rs
 
 // Network protocol definitions
+use citrate_consensus::checkpoint::FinalityCheckpoint;
+use citrate_consensus::epoch::EpochVote;
 use citrate_consensus::types::{Block, BlockHeader, Hash, Transaction};
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -18,6 +20,18 @@ pub struct ModelMetadata {
     pub created_at: u64,
 }
 
+/// Chunk layout of a model's weights, served by peers that have them pinned
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeightManifest {
+    pub model_id: Hash,
+    /// SHA-256 of the complete weights file
+    pub sha256: Hash,
+    pub total_size: u64,
+    pub chunk_size: u32,
+    /// SHA-256 of each chunk, in order
+    pub chunk_hashes: Vec<Hash>,
+}
+
 /// Protocol version for compatibility checking
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ProtocolVersion {
@@ -29,7 +43,7 @@ pub struct ProtocolVersion {
 impl ProtocolVersion {
     pub const CURRENT: Self = Self {
         major: 1,
-        minor: 0,
+        minor: 1,
         patch: 0,
     };
 
@@ -57,6 +71,8 @@ pub enum NetworkMessage {
         head_height: u64,
         head_hash: Hash,
         peer_id: String,
+        /// Client software version, e.g. "0.2.1"
+        client_version: String,
     },
 
     HelloAck {
@@ -64,6 +80,7 @@ pub enum NetworkMessage {
         head_height: u64,
         head_hash: Hash,
         peer_id: String,
+        client_version: String,
     },
 
     Disconnect {
@@ -116,6 +133,18 @@ pub enum NetworkMessage {
         transactions: Vec<Transaction>,
     },
 
+    // A validator's signature over a finalized block, gossiped until the
+    // checkpoint reaches quorum
+    FinalityCheckpoint {
+        checkpoint: FinalityCheckpoint,
+    },
+
+    // A validator's signature over an epoch summary, gossiped until the
+    // summary reaches quorum
+    EpochVote {
+        vote: EpochVote,
+    },
+
     // AI-specific messages for model and inference data
 
     // Model registration and updates
@@ -191,6 +220,33 @@ pub enum NetworkMessage {
         weight_delta: Vec<u8>, // Compressed weight update
     },
 
+    // Peer-to-peer model weight transfer (see `model_sync`)
+    ModelPinned {
+        model_id: Hash,
+        sha256: Hash,
+        size_bytes: u64,
+    },
+
+    GetModelManifest {
+        model_id: Hash,
+    },
+
+    ModelManifest {
+        model_id: Hash,
+        manifest: Option<WeightManifest>, // None when not pinned
+    },
+
+    GetModelChunk {
+        model_id: Hash,
+        index: u32,
+    },
+
+    ModelChunk {
+        model_id: Hash,
+        index: u32,
+        data: Option<Vec<u8>>,
+    },
+
     // AI state synchronization
     GetAIState {
         from_height: u64,
@@ -306,8 +362,10 @@ impl NetworkMessage {
             Self::Hello { .. } | Self::HelloAck { .. } => MessagePriority::Critical,
             Self::GetBlocks { .. } | Self::GetHeaders { .. } => MessagePriority::Critical,
 
-            // High priority for new blocks
-            Self::NewBlock { .. } => MessagePriority::High,
+            // High priority for new blocks and finality and epoch votes
+            Self::NewBlock { .. }
+            | Self::FinalityCheckpoint { .. }
+            | Self::EpochVote { .. } => MessagePriority::High,
 
             // Normal priority for transactions and general messages
             Self::NewTransaction { .. } => MessagePriority::Normal,
@@ -317,6 +375,9 @@ impl NetworkMessage {
             Self::GetPeers | Self::Peers { .. } => MessagePriority::Low,
             Self::Ping { .. } | Self::Pong { .. } => MessagePriority::Low,
 
+            // Bulk weight transfer must not hold up blocks
+            Self::GetModelChunk { .. } | Self::ModelChunk { .. } => MessagePriority::Low,
+
             _ => MessagePriority::Normal,
         }
     }
@@ -335,8 +396,59 @@ impl NetworkMessage {
                 | Self::GetDagInfo { .. }
                 | Self::GetState { .. }
                 | Self::GetBlocksByHeight { .. }
+                | Self::GetModelManifest { .. }
+                | Self::GetModelChunk { .. }
         )
     }
+
+    /// Message type name, used to label traffic statistics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Hello { .. } => "hello",
+            Self::HelloAck { .. } => "hello_ack",
+            Self::Disconnect { .. } => "disconnect",
+            Self::Ping { .. } => "ping",
+            Self::Pong { .. } => "pong",
+            Self::NewBlock { .. } => "new_block",
+            Self::GetBlocks { .. } => "get_blocks",
+            Self::Blocks { .. } => "blocks",
+            Self::GetHeaders { .. } => "get_headers",
+            Self::Headers { .. } => "headers",
+            Self::NewTransaction { .. } => "new_transaction",
+            Self::GetTransactions { .. } => "get_transactions",
+            Self::Transactions { .. } => "transactions",
+            Self::FinalityCheckpoint { .. } => "finality_checkpoint",
+            Self::EpochVote { .. } => "epoch_vote",
+            Self::ModelAnnounce { .. } => "model_announce",
+            Self::GetModel { .. } => "get_model",
+            Self::ModelData { .. } => "model_data",
+            Self::InferenceRequest { .. } => "inference_request",
+            Self::InferenceResponse { .. } => "inference_response",
+            Self::TrainingJobAnnounce { .. } => "training_job_announce",
+            Self::GradientSubmission { .. } => "gradient_submission",
+            Self::LoraAdapterAnnounce { .. } => "lora_adapter_announce",
+            Self::GetLoraAdapter { .. } => "get_lora_adapter",
+            Self::WeightSync { .. } => "weight_sync",
+            Self::ModelPinned { .. } => "model_pinned",
+            Self::GetModelManifest { .. } => "get_model_manifest",
+            Self::ModelManifest { .. } => "model_manifest",
+            Self::GetModelChunk { .. } => "get_model_chunk",
+            Self::ModelChunk { .. } => "model_chunk",
+            Self::GetAIState { .. } => "get_ai_state",
+            Self::AIStateUpdate { .. } => "ai_state_update",
+            Self::GetMempool => "get_mempool",
+            Self::Mempool { .. } => "mempool",
+            Self::GetBlocksByHeight { .. } => "get_blocks_by_height",
+            Self::GetState { .. } => "get_state",
+            Self::StateData { .. } => "state_data",
+            Self::GetPeers => "get_peers",
+            Self::Peers { .. } => "peers",
+            Self::GetBlueSet { .. } => "get_blue_set",
+            Self::BlueSet { .. } => "blue_set",
+            Self::GetDagInfo { .. } => "get_dag_info",
+            Self::DagInfo { .. } => "dag_info",
+        }
+    }
 }
 
 #[cfg(test)]
@@ -374,6 +486,7 @@ mod tests {
             head_height: 0,
             head_hash: Hash::default(),
             peer_id: "test".to_string(),
+            client_version: crate::version::CLIENT_VERSION.to_string(),
         };
 
         assert_eq!(hello.priority(), MessagePriority::Critical);
@@ -425,6 +538,7 @@ mod tests {
                     base_fee_per_gas: 0,
                     gas_used: 0,
                     gas_limit: 30_000_000,
+                    epoch_commitment: Hash::default(),
                 },
                 state_root: Hash::default(),
                 tx_root: Hash::default(),
@@ -0,0 +1,360 @@
+// citrate/core/network/src/reputation.rs
+
+// Persistent peer reputation and ban list
+use crate::NetworkError;
+use citrate_storage::db::{column_families::CF_PEERS, RocksDB};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+
+/// Reputation tuning parameters
+#[derive(Debug, Clone)]
+pub struct ReputationConfig {
+    /// Score below which a peer is banned
+    pub ban_threshold: i32,
+    /// Duration of a first ban; repeat offenders get doubled durations
+    pub ban_duration: Duration,
+    /// Time for a score to decay halfway back to zero
+    pub decay_half_life: Duration,
+    /// Misbehavior events kept per peer
+    pub max_events: usize,
+}
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        Self {
+            ban_threshold: -100,
+            ban_duration: Duration::from_secs(3600),
+            decay_half_life: Duration::from_secs(3600),
+            max_events: 16,
+        }
+    }
+}
+
+/// A recorded score change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReputationEvent {
+    pub reason: String,
+    pub delta: i32,
+    /// Unix timestamp (seconds)
+    pub at: u64,
+}
+
+/// Reputation of a remote host, keyed by IP so it survives reconnects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerReputation {
+    pub ip: IpAddr,
+    /// Last peer id seen from this host
+    pub peer_id: Option<String>,
+    pub score: f64,
+    /// Unix timestamp of the last decay or score change
+    pub updated_at: u64,
+    pub banned_until: Option<u64>,
+    pub ban_reason: Option<String>,
+    /// Number of bans so far, used to escalate ban durations
+    pub ban_count: u32,
+    /// Most recent events, oldest first
+    pub events: VecDeque<ReputationEvent>,
+}
+
+impl PeerReputation {
+    fn new(ip: IpAddr, now: u64) -> Self {
+        Self {
+            ip,
+            peer_id: None,
+            score: 0.0,
+            updated_at: now,
+            banned_until: None,
+            ban_reason: None,
+            ban_count: 0,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Whether a ban is in effect at `now`
+    pub fn is_banned_at(&self, now: u64) -> bool {
+        self.banned_until.is_some_and(|until| until > now)
+    }
+
+    /// Exponentially decay the score toward zero
+    fn decay(&mut self, now: u64, half_life: Duration) {
+        let elapsed = now.saturating_sub(self.updated_at);
+        if elapsed == 0 {
+            return;
+        }
+        let half_life = half_life.as_secs().max(1) as f64;
+        self.score *= 0.5f64.powf(elapsed as f64 / half_life);
+        if self.score.abs() < 0.01 {
+            self.score = 0.0;
+        }
+        self.updated_at = now;
+    }
+}
+
+/// Tracks peer scores, misbehavior history and bans, optionally persisted
+/// to the `peers` column family so they survive restarts.
+pub struct ReputationManager {
+    config: ReputationConfig,
+    records: RwLock<HashMap<IpAddr, PeerReputation>>,
+    db: Option<Arc<RocksDB>>,
+}
+
+impl ReputationManager {
+    /// In-memory reputation store
+    pub fn new(config: ReputationConfig) -> Self {
+        Self {
+            config,
+            records: RwLock::new(HashMap::new()),
+            db: None,
+        }
+    }
+
+    /// Reputation store backed by RocksDB, loading previously saved records
+    pub fn with_storage(config: ReputationConfig, db: Arc<RocksDB>) -> Result<Self, NetworkError> {
+        let mut records = HashMap::new();
+        let iter = db
+            .iter_cf(CF_PEERS)
+            .map_err(|e| NetworkError::Storage(e.to_string()))?;
+        for (key, value) in iter {
+            match bincode::deserialize::<PeerReputation>(&value) {
+                Ok(record) => {
+                    records.insert(record.ip, record);
+                }
+                Err(e) => warn!(
+                    "Skipping corrupt reputation record {}: {}",
+                    String::from_utf8_lossy(&key),
+                    e
+                ),
+            }
+        }
+        info!("Loaded {} peer reputation records", records.len());
+
+        Ok(Self {
+            config,
+            records: RwLock::new(records),
+            db: Some(db),
+        })
+    }
+
+    pub fn config(&self) -> &ReputationConfig {
+        &self.config
+    }
+
+    /// Apply a score change. Returns true if this pushed the peer into a ban.
+    pub fn record(&self, ip: IpAddr, peer_id: Option<&str>, delta: i32, reason: &str) -> bool {
+        self.record_at(ip, peer_id, delta, reason, unix_now())
+    }
+
+    fn record_at(
+        &self,
+        ip: IpAddr,
+        peer_id: Option<&str>,
+        delta: i32,
+        reason: &str,
+        now: u64,
+    ) -> bool {
+        let mut records = self.records.write();
+        let record = records
+            .entry(ip)
+            .or_insert_with(|| PeerReputation::new(ip, now));
+        record.decay(now, self.config.decay_half_life);
+        record.score += delta as f64;
+        if let Some(id) = peer_id {
+            record.peer_id = Some(id.to_string());
+        }
+        record.events.push_back(ReputationEvent {
+            reason: reason.to_string(),
+            delta,
+            at: now,
+        });
+        while record.events.len() > self.config.max_events {
+            record.events.pop_front();
+        }
+
+        let banned = !record.is_banned_at(now) && record.score < self.config.ban_threshold as f64;
+        if banned {
+            self.apply_ban(record, reason, now);
+        }
+        self.persist(record);
+        banned
+    }
+
+    /// Ban a host outright, regardless of its score
+    pub fn ban(&self, ip: IpAddr, reason: &str) {
+        let now = unix_now();
+        let mut records = self.records.write();
+        let record = records
+            .entry(ip)
+            .or_insert_with(|| PeerReputation::new(ip, now));
+        record.decay(now, self.config.decay_half_life);
+        self.apply_ban(record, reason, now);
+        self.persist(record);
+    }
+
+    /// Lift a ban and reset the score
+    pub fn unban(&self, ip: IpAddr) -> bool {
+        let mut records = self.records.write();
+        match records.get_mut(&ip) {
+            Some(record) if record.banned_until.is_some() => {
+                record.banned_until = None;
+                record.ban_reason = None;
+                record.score = 0.0;
+                record.updated_at = unix_now();
+                self.persist(record);
+                info!("Unbanned peer {}", ip);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether a host is currently banned
+    pub fn is_banned(&self, ip: &IpAddr) -> bool {
+        self.records
+            .read()
+            .get(ip)
+            .is_some_and(|r| r.is_banned_at(unix_now()))
+    }
+
+    /// Current reputation of a host with decay applied
+    pub fn get(&self, ip: &IpAddr) -> Option<PeerReputation> {
+        self.get_at(ip, unix_now())
+    }
+
+    fn get_at(&self, ip: &IpAddr, now: u64) -> Option<PeerReputation> {
+        let mut record = self.records.read().get(ip)?.clone();
+        record.decay(now, self.config.decay_half_life);
+        Some(record)
+    }
+
+    /// All known hosts, lowest score first
+    pub fn all(&self) -> Vec<PeerReputation> {
+        let now = unix_now();
+        let mut all: Vec<PeerReputation> = self
+            .records
+            .read()
+            .values()
+            .cloned()
+            .map(|mut r| {
+                r.decay(now, self.config.decay_half_life);
+                r
+            })
+            .collect();
+        all.sort_by(|a, b| a.score.total_cmp(&b.score));
+        all
+    }
+
+    /// Hosts with an active ban
+    pub fn banned(&self) -> Vec<PeerReputation> {
+        let now = unix_now();
+        self.all()
+            .into_iter()
+            .filter(|r| r.is_banned_at(now))
+            .collect()
+    }
+
+    fn apply_ban(&self, record: &mut PeerReputation, reason: &str, now: u64) {
+        let multiplier = 1u64 << record.ban_count.min(6);
+        let duration = self
+            .config
+            .ban_duration
+            .as_secs()
+            .saturating_mul(multiplier);
+        record.banned_until = Some(now.saturating_add(duration));
+        record.ban_reason = Some(reason.to_string());
+        record.ban_count = record.ban_count.saturating_add(1);
+        warn!("Banned peer {} for {}s: {}", record.ip, duration, reason);
+    }
+
+    fn persist(&self, record: &PeerReputation) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        let result = bincode::serialize(record)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| {
+                db.put_cf(CF_PEERS, record.ip.to_string().as_bytes(), &bytes)
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            debug!("Failed to persist reputation for {}: {}", record.ip, e);
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_score_decays_toward_zero() {
+        let manager = ReputationManager::new(ReputationConfig::default());
+        let host = ip("10.0.0.1");
+        manager.record_at(host, None, -40, "invalid block", 1_000);
+
+        let record = manager.get_at(&host, 1_000 + 3600).unwrap();
+        assert!((record.score + 20.0).abs() < 1e-6);
+
+        let record = manager.get_at(&host, 1_000 + 3600 * 20).unwrap();
+        assert_eq!(record.score, 0.0);
+    }
+
+    #[test]
+    fn test_ban_escalates_and_expires() {
+        let config = ReputationConfig {
+            ban_threshold: -10,
+            ban_duration: Duration::from_secs(60),
+            ..Default::default()
+        };
+        let manager = ReputationManager::new(config);
+        let host = ip("10.0.0.2");
+
+        assert!(manager.record_at(host, Some("peer_1"), -15, "bad header", 1_000));
+        let record = manager.get_at(&host, 1_000).unwrap();
+        assert_eq!(record.banned_until, Some(1_060));
+        assert_eq!(record.ban_reason.as_deref(), Some("bad header"));
+        assert_eq!(record.peer_id.as_deref(), Some("peer_1"));
+        assert!(!record.is_banned_at(1_061));
+
+        // Already banned: no new ban is reported
+        assert!(!manager.record_at(host, None, -5, "spam", 1_010));
+
+        // Second ban after expiry lasts twice as long
+        assert!(manager.record_at(host, None, -30, "spam", 1_100));
+        let record = manager.get_at(&host, 1_100).unwrap();
+        assert_eq!(record.banned_until, Some(1_220));
+        assert_eq!(record.ban_count, 2);
+        assert_eq!(record.events.len(), 3);
+    }
+
+    #[test]
+    fn test_event_history_is_bounded() {
+        let config = ReputationConfig {
+            max_events: 4,
+            ..Default::default()
+        };
+        let manager = ReputationManager::new(config);
+        let host = ip("10.0.0.3");
+        for i in 0..10 {
+            manager.record_at(host, None, -1, &format!("event {}", i), 1_000);
+        }
+        let record = manager.get_at(&host, 1_000).unwrap();
+        assert_eq!(record.events.len(), 4);
+        assert_eq!(record.events.front().unwrap().reason, "event 6");
+    }
+}
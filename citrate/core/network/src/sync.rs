@@ -3,8 +3,11 @@
 // Synchronization manager for block and header downloads
 use crate::{
     peer::{Peer, PeerId},
+    sync_pipeline::SyncPipeline,
+    sync_progress::{SyncProgress, SyncStage, SyncStages, DEFAULT_RATE_WINDOW},
     NetworkError, NetworkMessage,
 };
+use citrate_consensus::metrics::{self, MetricEvent};
 use citrate_consensus::types::{Block, BlockHeader, Hash};
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
@@ -74,6 +77,12 @@ pub struct SyncManager {
     downloaded_blocks: Arc<RwLock<Vec<Block>>>,
     last_header_hash: Arc<RwLock<Option<Hash>>>,
     last_requested_header: Arc<RwLock<Option<Hash>>>,
+
+    // Per-stage counts and rates of the current sync
+    stages: Arc<RwLock<SyncStages>>,
+
+    // Verifies, executes and stores downloaded blocks when set
+    pipeline: Option<Arc<SyncPipeline>>,
 }
 
 #[derive(Debug, Clone)]
@@ -125,9 +134,24 @@ impl SyncManager {
             downloaded_blocks: Arc::new(RwLock::new(Vec::new())),
             last_header_hash: Arc::new(RwLock::new(None)),
             last_requested_header: Arc::new(RwLock::new(None)),
+            stages: Arc::new(RwLock::new(SyncStages::new(
+                &[SyncStage::Headers, SyncStage::Blocks],
+                DEFAULT_RATE_WINDOW,
+            ))),
+            pipeline: None,
         }
     }
 
+    /// Import downloaded blocks through `pipeline` instead of only queueing them
+    pub fn with_pipeline(mut self, pipeline: Arc<SyncPipeline>) -> Self {
+        self.pipeline = Some(pipeline);
+        self.stages = Arc::new(RwLock::new(SyncStages::new(
+            &[SyncStage::Headers, SyncStage::Blocks, SyncStage::Execution],
+            DEFAULT_RATE_WINDOW,
+        )));
+        self
+    }
+
     /// Get current sync state
     pub async fn get_state(&self) -> SyncState {
         self.state.read().await.clone()
@@ -148,6 +172,7 @@ impl SyncManager {
         }
 
         *self.target_height.write().await = peer_height;
+        self.stages.write().await.reset(peer_height - current);
 
         // Start with header download
         *self.state.write().await = SyncState::DownloadingHeaders {
@@ -211,6 +236,7 @@ impl SyncManager {
 
         // Update last requested header
         *self.last_requested_header.write().await = Some(from);
+        self.report_pending().await;
 
         Ok(())
     }
@@ -246,6 +272,7 @@ impl SyncManager {
                 retries: 0,
             },
         );
+        self.report_pending().await;
 
         Ok(())
     }
@@ -257,6 +284,14 @@ impl SyncManager {
         }
 
         let count = headers.len();
+        metrics::record(MetricEvent::SyncReceived {
+            kind: "headers",
+            count,
+        });
+        self.stages
+            .write()
+            .await
+            .record(SyncStage::Headers, count as u64, Instant::now());
         let first_height = headers.first().unwrap().height;
         let last_height = headers.last().unwrap().height;
         let first_hash = headers.first().map(|h| h.block_hash).unwrap_or_default();
@@ -299,11 +334,43 @@ impl SyncManager {
         }
 
         let count = blocks.len();
+        metrics::record(MetricEvent::SyncReceived {
+            kind: "blocks",
+            count,
+        });
+        self.stages
+            .write()
+            .await
+            .record(SyncStage::Blocks, count as u64, Instant::now());
         let first_height = blocks.first().unwrap().header.height;
-        let last_height = blocks.last().unwrap().header.height;
-
-        // Store blocks
-        self.downloaded_blocks.write().await.extend(blocks);
+        let mut last_height = blocks.last().unwrap().header.height;
+
+        if let Some(pipeline) = &self.pipeline {
+            *self.state.write().await = SyncState::Verifying {
+                blocks_verified: 0,
+                total_blocks: count as u64,
+            };
+
+            let outcome = pipeline.import(blocks).await?;
+            self.stages.write().await.record(
+                SyncStage::Execution,
+                outcome.imported as u64,
+                Instant::now(),
+            );
+            if let Some(height) = outcome.last_height {
+                *self.current_height.write().await = height;
+            }
+            if let Some((hash, reason)) = outcome.rejected {
+                return Err(NetworkError::SyncError(format!(
+                    "block {} failed verification: {}",
+                    hash, reason
+                )));
+            }
+            last_height = outcome.last_height.unwrap_or(last_height);
+        } else {
+            // Store blocks
+            self.downloaded_blocks.write().await.extend(blocks);
+        }
 
         // Update progress
         let current = *self.current_height.read().await;
@@ -408,6 +475,7 @@ impl SyncManager {
 
         if !timed_out.is_empty() {
             warn!("Sync requests timed out: {} items", timed_out.len());
+            self.report_pending().await;
         }
 
         timed_out
@@ -427,6 +495,16 @@ impl SyncManager {
         (current, target, progress)
     }
 
+    /// Sync progress with a per-stage breakdown, rates and ETA
+    pub async fn progress(&self) -> SyncProgress {
+        let current = *self.current_height.read().await;
+        let target = *self.target_height.read().await;
+        self.stages
+            .read()
+            .await
+            .snapshot(current, target, Instant::now())
+    }
+
     /// Last received header hash (if any)
     pub async fn last_received_header(&self) -> Option<Hash> {
         *self.last_header_hash.read().await
@@ -441,11 +519,40 @@ impl SyncManager {
     pub async fn pending_counts(&self) -> (usize, usize) {
         (self.pending_headers.read().await.len(), self.pending_blocks.read().await.len())
     }
+
+    async fn report_pending(&self) {
+        let (headers, blocks) = self.pending_counts().await;
+        metrics::record(MetricEvent::SyncPending { headers, blocks });
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use citrate_consensus::types::{PublicKey, VrfProof};
+
+    fn header(height: u64) -> BlockHeader {
+        BlockHeader {
+            version: 1,
+            block_hash: Hash::new([height as u8; 32]),
+            selected_parent_hash: Hash::new([height.saturating_sub(1) as u8; 32]),
+            merge_parent_hashes: vec![],
+            timestamp: height,
+            height,
+            blue_score: height,
+            blue_work: height as u128,
+            pruning_point: Hash::default(),
+            proposer_pubkey: PublicKey::new([1; 32]),
+            vrf_reveal: VrfProof {
+                proof: vec![],
+                output: Hash::default(),
+            },
+            base_fee_per_gas: 0,
+            gas_used: 0,
+            gas_limit: 30_000_000,
+            epoch_commitment: Hash::default(),
+        }
+    }
 
     #[tokio::test]
     async fn test_sync_state_transitions() {
@@ -479,4 +586,22 @@ mod tests {
         assert_eq!(target, 100);
         assert_eq!(progress, 50.0);
     }
+
+    #[tokio::test]
+    async fn test_stage_progress() {
+        let sync = SyncManager::new(SyncConfig::default());
+        sync.start_sync(100, Hash::default()).await.unwrap();
+
+        let headers = (1..=100).map(header).collect();
+        sync.handle_headers(headers).await.unwrap();
+
+        let progress = sync.progress().await;
+        assert_eq!(progress.target_height, 100);
+        assert_eq!(progress.stage, Some(SyncStage::Blocks));
+        assert_eq!(progress.stages.len(), 2);
+        assert_eq!(progress.stages[0].stage, SyncStage::Headers);
+        assert_eq!(progress.stages[0].completed, 100);
+        assert_eq!(progress.stages[1].completed, 0);
+        assert_eq!(progress.eta_secs, None);
+    }
 }
@@ -0,0 +1,392 @@
+// citrate/core/network/src/sync_pipeline.rs
+
+// Pipelined block import for initial sync
+//
+// Downloaded blocks go through three stages:
+//   1. verify  - block limits, transaction signatures and VRF proofs,
+//                checked in parallel batches on a dedicated rayon pool
+//   2. execute - sequentially, in the order the blocks were received
+//   3. persist - blocks, transactions and receipts are written to storage in
+//                batched RocksDB writes
+// The next verification batch runs on the pool while the current one is
+// executed, so signature checks are mostly hidden behind execution.
+
+use crate::NetworkError;
+use async_trait::async_trait;
+use citrate_consensus::crypto;
+use citrate_consensus::limits::{BlockLimitError, BlockLimits};
+use citrate_consensus::types::{Block, Hash};
+use citrate_consensus::vrf::proof_output_matches;
+use citrate_execution::types::TransactionReceipt;
+use citrate_storage::StorageManager;
+use rayon::prelude::*;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::oneshot;
+use tracing::{debug, warn};
+
+/// Executes verified blocks on top of the local state
+#[async_trait]
+pub trait BlockExecutor: Send + Sync {
+    /// Execute a block, returning one receipt per transaction
+    async fn execute_block(&self, block: &Block) -> anyhow::Result<Vec<TransactionReceipt>>;
+
+    /// Persist state changes made since the last call. Called after every
+    /// storage write so state never runs ahead of stored blocks.
+    async fn commit(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Block limits to verify against, including governance overrides
+    fn block_limits(&self) -> BlockLimits {
+        BlockLimits::default()
+    }
+}
+
+/// Why a block failed verification
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum BlockRejection {
+    #[error("invalid signature on transaction {0}")]
+    InvalidTransactionSignature(Hash),
+
+    #[error("invalid VRF proof")]
+    InvalidVrfProof,
+
+    #[error("block limit exceeded: {0}")]
+    LimitExceeded(BlockLimitError),
+}
+
+/// A verification batch returned with one result per block
+type VerifiedBatch = (Vec<Block>, Vec<Result<(), BlockRejection>>);
+
+/// Sync pipeline configuration
+#[derive(Debug, Clone)]
+pub struct SyncPipelineConfig {
+    /// Verification threads; 0 uses one per CPU core
+    pub verify_threads: usize,
+
+    /// Blocks verified together on the pool
+    pub verify_batch_size: usize,
+
+    /// Blocks written per storage batch
+    pub write_batch_size: usize,
+}
+
+impl Default for SyncPipelineConfig {
+    fn default() -> Self {
+        Self {
+            verify_threads: 0,
+            verify_batch_size: 64,
+            write_batch_size: 256,
+        }
+    }
+}
+
+/// Result of importing a run of blocks
+#[derive(Debug, Default)]
+pub struct ImportOutcome {
+    /// Blocks executed and stored
+    pub imported: usize,
+
+    /// Height of the last stored block
+    pub last_height: Option<u64>,
+
+    /// First block that failed verification; blocks after it are skipped
+    pub rejected: Option<(Hash, BlockRejection)>,
+}
+
+/// Verify, execute and store downloaded blocks
+pub struct SyncPipeline {
+    config: SyncPipelineConfig,
+    pool: Arc<rayon::ThreadPool>,
+    storage: Arc<StorageManager>,
+    executor: Arc<dyn BlockExecutor>,
+}
+
+impl SyncPipeline {
+    pub fn new(
+        config: SyncPipelineConfig,
+        storage: Arc<StorageManager>,
+        executor: Arc<dyn BlockExecutor>,
+    ) -> Result<Self, NetworkError> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(config.verify_threads)
+            .thread_name(|i| format!("sync-verify-{}", i))
+            .build()
+            .map_err(|e| NetworkError::SyncError(e.to_string()))?;
+
+        Ok(Self {
+            config,
+            pool: Arc::new(pool),
+            storage,
+            executor,
+        })
+    }
+
+    /// Import blocks in the order given, which must be parents-first.
+    /// Stops at the first block that fails verification.
+    pub async fn import(&self, blocks: Vec<Block>) -> Result<ImportOutcome, NetworkError> {
+        let mut outcome = ImportOutcome::default();
+        let mut chunks = into_chunks(blocks, self.config.verify_batch_size.max(1)).into_iter();
+        let mut pending: Vec<(Block, Vec<TransactionReceipt>)> = Vec::new();
+
+        let mut next = chunks.next().map(|chunk| self.spawn_verify(chunk));
+        while let Some(verifying) = next.take() {
+            let (chunk, results) = verifying
+                .await
+                .map_err(|_| NetworkError::SyncError("verification pool shut down".into()))?;
+
+            // Verify ahead while this batch executes
+            next = chunks.next().map(|chunk| self.spawn_verify(chunk));
+
+            for (block, result) in chunk.into_iter().zip(results) {
+                if let Err(reason) = result {
+                    warn!(
+                        "Rejected synced block {} at height {}: {}",
+                        block.hash(),
+                        block.header.height,
+                        reason
+                    );
+                    outcome.rejected = Some((block.hash(), reason));
+                    self.persist(&mut pending, &mut outcome).await?;
+                    return Ok(outcome);
+                }
+
+                let receipts = self
+                    .executor
+                    .execute_block(&block)
+                    .await
+                    .map_err(|e| NetworkError::SyncError(e.to_string()))?;
+                pending.push((block, receipts));
+
+                if pending.len() >= self.config.write_batch_size.max(1) {
+                    self.persist(&mut pending, &mut outcome).await?;
+                }
+            }
+        }
+
+        self.persist(&mut pending, &mut outcome).await?;
+        Ok(outcome)
+    }
+
+    /// Verify a batch on the pool; the blocks are handed back with the results.
+    /// Limits are read once per batch, before the batch is executed.
+    fn spawn_verify(&self, blocks: Vec<Block>) -> oneshot::Receiver<VerifiedBatch> {
+        let (tx, rx) = oneshot::channel();
+        let limits = self.executor.block_limits();
+        self.pool.spawn(move || {
+            let results = blocks
+                .par_iter()
+                .map(|block| verify_block(block, &limits))
+                .collect();
+            let _ = tx.send((blocks, results));
+        });
+        rx
+    }
+
+    /// Write executed blocks with their transactions and receipts
+    async fn persist(
+        &self,
+        pending: &mut Vec<(Block, Vec<TransactionReceipt>)>,
+        outcome: &mut ImportOutcome,
+    ) -> Result<(), NetworkError> {
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let storage_err = |e: anyhow::Error| NetworkError::Storage(e.to_string());
+        let (blocks, receipts): (Vec<Block>, Vec<Vec<TransactionReceipt>>) =
+            std::mem::take(pending).into_iter().unzip();
+
+        let transactions: Vec<_> = blocks
+            .iter()
+            .flat_map(|block| block.transactions.iter().cloned())
+            .collect();
+        let receipt_pairs: Vec<_> = blocks
+            .iter()
+            .zip(receipts)
+            .flat_map(|(block, receipts)| {
+                block
+                    .transactions
+                    .iter()
+                    .map(|tx| tx.hash)
+                    .zip(receipts)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        self.storage
+            .blocks
+            .put_blocks(&blocks)
+            .map_err(storage_err)?;
+        if !transactions.is_empty() {
+            self.storage
+                .transactions
+                .put_transactions(&transactions)
+                .map_err(storage_err)?;
+        }
+        if !receipt_pairs.is_empty() {
+            self.storage
+                .transactions
+                .put_receipts(&receipt_pairs)
+                .map_err(storage_err)?;
+        }
+        self.executor
+            .commit()
+            .await
+            .map_err(|e| NetworkError::SyncError(e.to_string()))?;
+
+        outcome.imported += blocks.len();
+        outcome.last_height = blocks.last().map(|b| b.header.height);
+        debug!(
+            "Stored {} synced blocks up to height {:?}",
+            blocks.len(),
+            outcome.last_height
+        );
+        Ok(())
+    }
+}
+
+/// Check a block's limits, transaction signatures and VRF proof. Blocks
+/// without a VRF reveal (dev producers leave it empty) are not checked for one.
+pub fn verify_block(block: &Block, limits: &BlockLimits) -> Result<(), BlockRejection> {
+    limits.check(block).map_err(BlockRejection::LimitExceeded)?;
+
+    if !block.header.vrf_reveal.proof.is_empty() && !proof_output_matches(&block.header.vrf_reveal)
+    {
+        return Err(BlockRejection::InvalidVrfProof);
+    }
+
+    let results = crypto::verify_transactions(&block.transactions);
+    for (tx, result) in block.transactions.iter().zip(results) {
+        if !matches!(result, Ok(true)) {
+            return Err(BlockRejection::InvalidTransactionSignature(tx.hash));
+        }
+    }
+    Ok(())
+}
+
+fn into_chunks(blocks: Vec<Block>, size: usize) -> Vec<Vec<Block>> {
+    let mut chunks = Vec::with_capacity(blocks.len().div_ceil(size));
+    let mut blocks = blocks.into_iter().peekable();
+    while blocks.peek().is_some() {
+        chunks.push(blocks.by_ref().take(size).collect());
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use citrate_consensus::types::{
+        BlockHeader, GhostDagParams, PublicKey, Signature, Transaction, VrfProof,
+    };
+    use sha3::{Digest, Sha3_256};
+
+    fn block(height: u64, transactions: Vec<Transaction>) -> Block {
+        Block {
+            header: BlockHeader {
+                version: 1,
+                block_hash: Hash::new([height as u8; 32]),
+                selected_parent_hash: Hash::new([height.saturating_sub(1) as u8; 32]),
+                merge_parent_hashes: vec![],
+                timestamp: height,
+                height,
+                blue_score: height,
+                blue_work: height as u128,
+                pruning_point: Hash::default(),
+                proposer_pubkey: PublicKey::new([1; 32]),
+                vrf_reveal: VrfProof {
+                    proof: vec![],
+                    output: Hash::default(),
+                },
+                base_fee_per_gas: 0,
+                gas_used: 0,
+                gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
+            },
+            state_root: Hash::default(),
+            tx_root: Hash::default(),
+            receipt_root: Hash::default(),
+            artifact_root: Hash::default(),
+            ghostdag_params: GhostDagParams::default(),
+            transactions,
+            signature: Signature::new([0; 64]),
+            embedded_models: vec![],
+            required_pins: vec![],
+        }
+    }
+
+    fn signed_tx(nonce: u64) -> Transaction {
+        let signing_key = crypto::generate_keypair();
+        let mut tx = Transaction {
+            hash: Hash::new([nonce as u8 + 1; 32]),
+            nonce,
+            from: PublicKey::new([0; 32]),
+            to: Some(PublicKey::new([2; 32])),
+            value: 1,
+            gas_limit: 21_000,
+            gas_price: 1,
+            data: vec![],
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        };
+        crypto::sign_transaction(&mut tx, &signing_key).unwrap();
+        tx
+    }
+
+    #[test]
+    fn test_verify_block_signatures() {
+        let limits = BlockLimits::default();
+        assert!(verify_block(&block(1, vec![signed_tx(0), signed_tx(1)]), &limits).is_ok());
+
+        let mut forged = signed_tx(2);
+        forged.value = 1_000_000;
+        let hash = forged.hash;
+        assert_eq!(
+            verify_block(&block(2, vec![signed_tx(0), forged]), &limits),
+            Err(BlockRejection::InvalidTransactionSignature(hash))
+        );
+    }
+
+    #[test]
+    fn test_verify_block_vrf() {
+        let mut b = block(1, vec![]);
+        b.header.vrf_reveal.proof = vec![7; 32];
+        let limits = BlockLimits::default();
+        assert_eq!(
+            verify_block(&b, &limits),
+            Err(BlockRejection::InvalidVrfProof)
+        );
+
+        b.header.vrf_reveal.output = Hash::from_bytes(&Sha3_256::digest([7u8; 32]));
+        assert!(verify_block(&b, &limits).is_ok());
+    }
+
+    #[test]
+    fn test_verify_block_limits() {
+        let limits = BlockLimits {
+            max_tx_bytes: 300,
+            ..BlockLimits::default()
+        };
+        let b = block(1, vec![signed_tx(0), signed_tx(1)]);
+        assert!(matches!(
+            verify_block(&b, &limits),
+            Err(BlockRejection::LimitExceeded(
+                BlockLimitError::TxBytes { .. }
+            ))
+        ));
+        assert!(verify_block(&b, &BlockLimits::default()).is_ok());
+    }
+
+    #[test]
+    fn test_into_chunks_keeps_order() {
+        let blocks: Vec<Block> = (0..5).map(|h| block(h, vec![])).collect();
+        let chunks = into_chunks(blocks, 2);
+        let heights: Vec<Vec<u64>> = chunks
+            .iter()
+            .map(|c| c.iter().map(|b| b.header.height).collect())
+            .collect();
+        assert_eq!(heights, vec![vec![0, 1], vec![2, 3], vec![4]]);
+    }
+}
@@ -0,0 +1,277 @@
+// citrate/core/network/src/sync_progress.rs
+
+//! Sync progress accounting
+//!
+//! A sync moves through stages: headers are downloaded, then blocks, then
+//! blocks are executed and stored. Each stage counts the items it has
+//! completed and measures its rate over a sliding window, so a stalled
+//! stage shows up as a falling rate rather than an average diluted by the
+//! whole sync. The ETA is the remaining height divided by the rate of the
+//! stage that finishes the sync.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Window rates are measured over
+pub const DEFAULT_RATE_WINDOW: Duration = Duration::from_secs(30);
+
+/// Stage of a sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncStage {
+    /// Downloading block headers
+    Headers,
+    /// Downloading block bodies
+    Blocks,
+    /// Verifying, executing and storing downloaded blocks
+    Execution,
+}
+
+/// Progress of one stage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageProgress {
+    pub stage: SyncStage,
+    /// Items completed since the sync started
+    pub completed: u64,
+    /// Items the stage has to complete
+    pub total: u64,
+    /// Items per second over the rate window
+    pub rate: f64,
+}
+
+/// Snapshot of a sync
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncProgress {
+    pub current_height: u64,
+    pub target_height: u64,
+    /// Stage currently running, `None` once synced or idle
+    pub stage: Option<SyncStage>,
+    pub stages: Vec<StageProgress>,
+    /// Rate at which the local chain advances
+    pub blocks_per_sec: f64,
+    /// Seconds until `target_height` is reached at the current rate
+    pub eta_secs: Option<u64>,
+}
+
+impl SyncProgress {
+    /// Percentage of the way to the target height
+    pub fn percent(&self) -> f32 {
+        if self.target_height > self.current_height {
+            ((self.current_height as f32 / self.target_height as f32) * 100.0).min(100.0)
+        } else {
+            100.0
+        }
+    }
+}
+
+/// Counts completions over a sliding window
+#[derive(Debug)]
+pub struct RateMeter {
+    window: Duration,
+    started: Option<Instant>,
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl RateMeter {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            started: None,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Count `count` completions at `now`
+    pub fn record(&mut self, now: Instant, count: u64) {
+        self.started.get_or_insert(now);
+        self.samples.push_back((now, count));
+        self.expire(now);
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while let Some(&(at, _)) = self.samples.front() {
+            if now.saturating_duration_since(at) <= self.window {
+                break;
+            }
+            self.samples.pop_front();
+        }
+    }
+
+    /// Completions per second over the window ending at `now`
+    pub fn rate(&self, now: Instant) -> f64 {
+        let Some(started) = self.started else {
+            return 0.0;
+        };
+        let count: u64 = self
+            .samples
+            .iter()
+            .filter(|(at, _)| now.saturating_duration_since(*at) <= self.window)
+            .map(|(_, count)| count)
+            .sum();
+        // A meter younger than its window only covers the time since it started;
+        // short spans are floored to a second so the first batch doesn't spike
+        let span = now
+            .saturating_duration_since(started)
+            .min(self.window)
+            .max(Duration::from_secs(1));
+        count as f64 / span.as_secs_f64()
+    }
+
+    pub fn reset(&mut self) {
+        self.started = None;
+        self.samples.clear();
+    }
+}
+
+#[derive(Debug)]
+struct StageCounter {
+    stage: SyncStage,
+    completed: u64,
+    total: u64,
+    meter: RateMeter,
+}
+
+/// Per-stage counters of a sync
+#[derive(Debug)]
+pub struct SyncStages {
+    stages: Vec<StageCounter>,
+}
+
+impl SyncStages {
+    /// Track `stages`; the last one is the stage that advances the chain
+    pub fn new(stages: &[SyncStage], window: Duration) -> Self {
+        Self {
+            stages: stages
+                .iter()
+                .map(|&stage| StageCounter {
+                    stage,
+                    completed: 0,
+                    total: 0,
+                    meter: RateMeter::new(window),
+                })
+                .collect(),
+        }
+    }
+
+    fn counter(&mut self, stage: SyncStage) -> Option<&mut StageCounter> {
+        self.stages.iter_mut().find(|c| c.stage == stage)
+    }
+
+    /// Start a new sync of `total` blocks in every stage
+    pub fn reset(&mut self, total: u64) {
+        for counter in &mut self.stages {
+            counter.completed = 0;
+            counter.total = total;
+            counter.meter.reset();
+        }
+    }
+
+    /// Raise the total of every stage, e.g. when peers report a higher tip
+    pub fn extend_total(&mut self, total: u64) {
+        for counter in &mut self.stages {
+            counter.total = counter.total.max(total);
+        }
+    }
+
+    /// Count `count` items completed by `stage` at `now`
+    pub fn record(&mut self, stage: SyncStage, count: u64, now: Instant) {
+        if let Some(counter) = self.counter(stage) {
+            counter.completed += count;
+            counter.total = counter.total.max(counter.completed);
+            counter.meter.record(now, count);
+        }
+    }
+
+    /// Progress of every stage at `now`
+    pub fn progress(&self, now: Instant) -> Vec<StageProgress> {
+        self.stages
+            .iter()
+            .map(|c| StageProgress {
+                stage: c.stage,
+                completed: c.completed,
+                total: c.total,
+                rate: c.meter.rate(now),
+            })
+            .collect()
+    }
+
+    /// First stage with work left
+    pub fn active(&self) -> Option<SyncStage> {
+        self.stages
+            .iter()
+            .find(|c| c.completed < c.total)
+            .map(|c| c.stage)
+    }
+
+    /// Snapshot of a sync from `current_height` towards `target_height`
+    pub fn snapshot(&self, current_height: u64, target_height: u64, now: Instant) -> SyncProgress {
+        let stages = self.progress(now);
+        let blocks_per_sec = stages.last().map(|s| s.rate).unwrap_or(0.0);
+        let remaining = target_height.saturating_sub(current_height);
+        let eta_secs = if remaining == 0 {
+            Some(0)
+        } else if blocks_per_sec > 0.0 {
+            Some((remaining as f64 / blocks_per_sec).ceil() as u64)
+        } else {
+            None
+        };
+        SyncProgress {
+            current_height,
+            target_height,
+            stage: if remaining == 0 { None } else { self.active() },
+            stages,
+            blocks_per_sec,
+            eta_secs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_meter_window() {
+        let start = Instant::now();
+        let mut meter = RateMeter::new(Duration::from_secs(10));
+        assert_eq!(meter.rate(start), 0.0);
+
+        meter.record(start, 50);
+        // Spans shorter than a second are floored
+        assert_eq!(meter.rate(start), 50.0);
+        meter.record(start + Duration::from_secs(5), 50);
+        assert_eq!(meter.rate(start + Duration::from_secs(5)), 20.0);
+
+        // The first batch falls out of the window
+        assert_eq!(meter.rate(start + Duration::from_secs(12)), 5.0);
+        assert_eq!(meter.rate(start + Duration::from_secs(30)), 0.0);
+    }
+
+    #[test]
+    fn test_stage_snapshot_eta() {
+        let start = Instant::now();
+        let mut stages = SyncStages::new(
+            &[SyncStage::Headers, SyncStage::Blocks, SyncStage::Execution],
+            Duration::from_secs(10),
+        );
+        stages.reset(100);
+        stages.record(SyncStage::Headers, 100, start);
+        stages.record(SyncStage::Blocks, 40, start);
+        stages.record(SyncStage::Execution, 20, start);
+
+        let now = start + Duration::from_secs(2);
+        let progress = stages.snapshot(20, 100, now);
+        assert_eq!(progress.stage, Some(SyncStage::Blocks));
+        assert_eq!(progress.stages.len(), 3);
+        assert_eq!(progress.stages[0].completed, 100);
+        assert_eq!(progress.stages[1].rate, 20.0);
+        assert_eq!(progress.blocks_per_sec, 10.0);
+        assert_eq!(progress.eta_secs, Some(8));
+
+        let done = stages.snapshot(100, 100, now);
+        assert_eq!(done.stage, None);
+        assert_eq!(done.eta_secs, Some(0));
+        assert_eq!(done.percent(), 100.0);
+    }
+}
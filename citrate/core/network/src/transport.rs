@@ -1,14 +1,21 @@
 // citrate/core/network/src/transport.rs
 
+use crate::bandwidth::TrafficDirection;
 use crate::peer::{Direction, Peer, PeerId, PeerInfo, PeerManager};
 use crate::protocol::{NetworkMessage, ProtocolVersion};
+use crate::version::CLIENT_VERSION;
 use crate::NetworkError;
 use bincode;
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
+use futures::stream::{SplitSink, SplitStream};
 use futures::{SinkExt, StreamExt};
 use citrate_consensus::types::Hash;
+use snow::params::{DHChoice, NoiseParams};
+use snow::resolvers::{CryptoResolver, DefaultResolver};
+use snow::{Builder, StatelessTransportState};
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::mpsc;
 use tokio_util::codec::{Framed, LengthDelimitedCodec};
@@ -23,18 +30,131 @@ pub struct HandshakeParams {
     pub head_hash: Hash,
 }
 
-/// Simple TCP-based transport with length-delimited frames (bincode payloads)
-pub struct NetworkTransport {
-    peer_manager: Arc<PeerManager>,
-    local_id: PeerId,
+/// Noise handshake pattern and primitives used for peer connections
+const NOISE_PARAMS: &str = "Noise_XX_25519_ChaChaPoly_BLAKE2s";
+
+/// First frame of an encrypted dial, also mixed into the handshake as the
+/// prologue. A dial that opens with anything else is a plaintext Hello.
+const NOISE_PROTOCOL_ID: &[u8] = b"/citrate/noise/1";
+
+const NOISE_MAX_MESSAGE_LEN: usize = 65535;
+const NOISE_TAG_LEN: usize = 16;
+/// Largest plaintext a single Noise message carries
+const NOISE_MAX_CHUNK_LEN: usize = NOISE_MAX_MESSAGE_LEN - NOISE_TAG_LEN;
+
+const MAX_FRAME_LEN: usize = 1024 * 1024; // 1MB
+/// Largest frame on the wire: a MAX_FRAME_LEN message encrypted in chunks
+const MAX_WIRE_FRAME_LEN: usize =
+    MAX_FRAME_LEN + (MAX_FRAME_LEN / NOISE_MAX_CHUNK_LEN + 1) * NOISE_TAG_LEN;
+
+/// Time allowed for the Noise handshake and Hello exchange
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+type FrameSink = SplitSink<Framed<TcpStream, LengthDelimitedCodec>, Bytes>;
+type FrameStream = SplitStream<Framed<TcpStream, LengthDelimitedCodec>>;
+
+/// Static X25519 key a node authenticates with in Noise handshakes. The
+/// node's peer ID is derived from the public key, so a peer cannot claim
+/// another node's ID without holding its private key.
+#[derive(Clone)]
+pub struct NodeKey {
+    private: [u8; 32],
+    public: [u8; 32],
+}
+
+impl NodeKey {
+    /// Generate a new random key
+    pub fn generate() -> Self {
+        Self::from_bytes(&rand::random::<[u8; 32]>()).expect("32-byte X25519 key")
+    }
+
+    /// Key from its 32-byte private key
+    pub fn from_bytes(private: &[u8]) -> Result<Self, NetworkError> {
+        let private: [u8; 32] = private.try_into().map_err(|_| {
+            NetworkError::TransportError(format!(
+                "node key must be 32 bytes, got {}",
+                private.len()
+            ))
+        })?;
+        let mut dh = DefaultResolver
+            .resolve_dh(&DHChoice::Curve25519)
+            .ok_or_else(|| NetworkError::TransportError("X25519 unavailable".into()))?;
+        dh.set(&private);
+        let mut public = [0u8; 32];
+        public.copy_from_slice(dh.pubkey());
+        Ok(Self { private, public })
+    }
+
+    /// The private key, for persisting
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.private
+    }
+
+    pub fn public_key(&self) -> [u8; 32] {
+        self.public
+    }
+
+    /// Peer ID bound to this key
+    pub fn peer_id(&self) -> PeerId {
+        peer_id_from_static_key(&self.public)
+    }
+}
+
+impl std::fmt::Debug for NodeKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NodeKey")
+            .field("peer_id", &self.peer_id())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Peer ID of the node holding the static key `key`: its hex encoding
+pub fn peer_id_from_static_key(key: &[u8]) -> PeerId {
+    PeerId::new(key.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// This node's side of every connection
+struct LocalNode {
+    key: NodeKey,
     params: HandshakeParams,
+    allow_plaintext: bool,
 }
 
-const MAX_FRAME_LEN: usize = 1024 * 1024; // 1MB
+/// Simple TCP-based transport with length-delimited frames (bincode payloads),
+/// encrypted and authenticated with a Noise XX handshake
+pub struct NetworkTransport {
+    peer_manager: Arc<PeerManager>,
+    local: Arc<LocalNode>,
+}
 
 impl NetworkTransport {
-    pub fn new(peer_manager: Arc<PeerManager>, local_id: PeerId, params: HandshakeParams) -> Self {
-        Self { peer_manager, local_id, params }
+    pub fn new(peer_manager: Arc<PeerManager>, node_key: NodeKey, params: HandshakeParams) -> Self {
+        Self {
+            peer_manager,
+            local: Arc::new(LocalNode {
+                key: node_key,
+                params,
+                allow_plaintext: false,
+            }),
+        }
+    }
+
+    /// Dial peers in plaintext and accept plaintext peers alongside
+    /// encrypted ones. For devnets only: traffic can be read and altered,
+    /// and peer IDs are taken on trust.
+    pub fn allow_plaintext(mut self, allow: bool) -> Self {
+        let local = &self.local;
+        self.local = Arc::new(LocalNode {
+            key: local.key.clone(),
+            params: local.params.clone(),
+            allow_plaintext: allow,
+        });
+        self
+    }
+
+    /// This node's peer ID
+    pub fn local_id(&self) -> PeerId {
+        self.local.key.peer_id()
     }
 
     /// Start an async TCP listener and accept inbound peers
@@ -45,18 +165,16 @@ impl NetworkTransport {
         info!("P2P listener on {}", addr);
 
         let pm = self.peer_manager.clone();
-        let local_id = self.local_id.clone();
-        let params = self.params.clone();
+        let local = self.local.clone();
 
         tokio::spawn(async move {
             loop {
                 match listener.accept().await {
                     Ok((stream, remote)) => {
                         let pm = pm.clone();
-                        let local_id = local_id.clone();
-                        let params = params.clone();
+                        let local = local.clone();
                         tokio::spawn(async move {
-                            if let Err(e) = handle_inbound(stream, remote, pm, local_id, params).await {
+                            if let Err(e) = handle_inbound(stream, remote, pm, local).await {
                                 warn!("inbound error from {}: {}", remote, e);
                             }
                         });
@@ -77,10 +195,9 @@ impl NetworkTransport {
             .await
             .map_err(|e| NetworkError::TransportError(format!("connect {}: {}", addr, e)))?;
         let pm = self.peer_manager.clone();
-        let local_id = self.local_id.clone();
-        let params = self.params.clone();
+        let local = self.local.clone();
         tokio::spawn(async move {
-            if let Err(e) = handle_outbound(stream, addr, pm, local_id, params).await {
+            if let Err(e) = handle_outbound(stream, addr, pm, local).await {
                 warn!("outbound error to {}: {}", addr, e);
             }
         });
@@ -88,26 +205,236 @@ impl NetworkTransport {
     }
 }
 
+fn noise_error(e: snow::Error) -> NetworkError {
+    NetworkError::ProtocolError(format!("noise: {}", e))
+}
+
+/// Encodes messages for one direction of a connection, encrypting them when
+/// the connection went through a Noise handshake. Messages larger than one
+/// Noise message are encrypted in chunks within a single frame.
+enum FrameCipher {
+    Plaintext,
+    Noise {
+        session: Arc<StatelessTransportState>,
+        nonce: u64,
+    },
+}
+
+impl FrameCipher {
+    fn seal(&mut self, msg: &NetworkMessage) -> Result<Bytes, NetworkError> {
+        let ser = bincode::serialize(msg)
+            .map_err(|e| NetworkError::ProtocolError(format!("encode: {}", e)))?;
+        match self {
+            FrameCipher::Plaintext => Ok(Bytes::from(ser)),
+            FrameCipher::Noise { session, nonce } => {
+                if ser.len() > MAX_FRAME_LEN {
+                    return Err(NetworkError::ProtocolError(format!(
+                        "message of {} bytes exceeds the frame limit",
+                        ser.len()
+                    )));
+                }
+                let chunks = ser.len().div_ceil(NOISE_MAX_CHUNK_LEN);
+                let mut out = Vec::with_capacity(ser.len() + chunks * NOISE_TAG_LEN);
+                let mut buf = vec![0u8; NOISE_MAX_MESSAGE_LEN];
+                for chunk in ser.chunks(NOISE_MAX_CHUNK_LEN) {
+                    let len = session
+                        .write_message(*nonce, chunk, &mut buf)
+                        .map_err(noise_error)?;
+                    *nonce += 1;
+                    out.extend_from_slice(&buf[..len]);
+                }
+                Ok(Bytes::from(out))
+            }
+        }
+    }
+
+    fn open(&mut self, frame: &[u8]) -> Result<NetworkMessage, NetworkError> {
+        let plain = match self {
+            FrameCipher::Plaintext => return decode_message(frame),
+            FrameCipher::Noise { session, nonce } => {
+                let mut plain = Vec::with_capacity(frame.len());
+                let mut buf = vec![0u8; NOISE_MAX_MESSAGE_LEN];
+                for chunk in frame.chunks(NOISE_MAX_MESSAGE_LEN) {
+                    let len = session
+                        .read_message(*nonce, chunk, &mut buf)
+                        .map_err(noise_error)?;
+                    *nonce += 1;
+                    plain.extend_from_slice(&buf[..len]);
+                }
+                plain
+            }
+        };
+        decode_message(&plain)
+    }
+}
+
+fn decode_message(bytes: &[u8]) -> Result<NetworkMessage, NetworkError> {
+    bincode::deserialize::<NetworkMessage>(bytes)
+        .map_err(|e| NetworkError::ProtocolError(format!("decode: {}", e)))
+}
+
+/// Both directions of an established connection
+struct Session {
+    send: FrameCipher,
+    recv: FrameCipher,
+    /// Peer ID proven by the remote's static key, if the connection is
+    /// encrypted
+    remote_key_id: Option<PeerId>,
+}
+
+impl Session {
+    fn plaintext() -> Self {
+        Self {
+            send: FrameCipher::Plaintext,
+            recv: FrameCipher::Plaintext,
+            remote_key_id: None,
+        }
+    }
+
+    /// Check the peer ID a remote claims in its Hello against its static key
+    fn verify_peer_id(&self, claimed: String) -> Result<PeerId, NetworkError> {
+        match &self.remote_key_id {
+            Some(id) if id.0 != claimed => Err(NetworkError::ProtocolError(format!(
+                "peer ID {} does not match its static key",
+                claimed
+            ))),
+            Some(id) => Ok(id.clone()),
+            None => Ok(PeerId::new(claimed)),
+        }
+    }
+}
+
+fn codec() -> LengthDelimitedCodec {
+    LengthDelimitedCodec::builder()
+        .max_frame_length(MAX_WIRE_FRAME_LEN)
+        .new_codec()
+}
+
+async fn read_frame(stream: &mut FrameStream) -> Result<BytesMut, NetworkError> {
+    match stream.next().await {
+        Some(Ok(bytes)) => Ok(bytes),
+        Some(Err(e)) => Err(NetworkError::TransportError(format!("read: {}", e))),
+        None => Err(NetworkError::TransportError("eof".into())),
+    }
+}
+
+async fn write_frame(sink: &mut FrameSink, frame: Bytes) -> Result<(), NetworkError> {
+    sink.send(frame)
+        .await
+        .map_err(|e| NetworkError::TransportError(format!("write: {}", e)))
+}
+
+/// Seal and write `msg`, returning the frame length
+async fn send_message(
+    sink: &mut FrameSink,
+    cipher: &mut FrameCipher,
+    msg: &NetworkMessage,
+) -> Result<usize, NetworkError> {
+    let frame = cipher.seal(msg)?;
+    let len = frame.len();
+    write_frame(sink, frame).await?;
+    Ok(len)
+}
+
+/// Run the Noise XX handshake, authenticating both static keys
+async fn noise_handshake(
+    sink: &mut FrameSink,
+    stream: &mut FrameStream,
+    key: &NodeKey,
+    initiator: bool,
+) -> Result<Session, NetworkError> {
+    let params: NoiseParams = NOISE_PARAMS.parse().map_err(noise_error)?;
+    let builder = Builder::new(params)
+        .local_private_key(&key.private)
+        .prologue(NOISE_PROTOCOL_ID);
+    let mut handshake = if initiator {
+        builder.build_initiator()
+    } else {
+        builder.build_responder()
+    }
+    .map_err(noise_error)?;
+
+    let mut buf = vec![0u8; NOISE_MAX_MESSAGE_LEN];
+    while !handshake.is_handshake_finished() {
+        if handshake.is_my_turn() {
+            let len = handshake.write_message(&[], &mut buf).map_err(noise_error)?;
+            write_frame(sink, Bytes::copy_from_slice(&buf[..len])).await?;
+        } else {
+            let frame = read_frame(stream).await?;
+            handshake.read_message(&frame, &mut buf).map_err(noise_error)?;
+        }
+    }
+
+    let remote_key_id = handshake
+        .get_remote_static()
+        .map(peer_id_from_static_key)
+        .ok_or_else(|| NetworkError::ProtocolError("noise: no remote static key".into()))?;
+    let session = Arc::new(handshake.into_stateless_transport_mode().map_err(noise_error)?);
+    Ok(Session {
+        send: FrameCipher::Noise {
+            session: session.clone(),
+            nonce: 0,
+        },
+        recv: FrameCipher::Noise { session, nonce: 0 },
+        remote_key_id: Some(remote_key_id),
+    })
+}
+
+/// Establish a session on an inbound connection and read the remote's Hello.
+/// Plaintext dials are rejected unless plaintext is allowed, so a connection
+/// cannot be downgraded by stripping the Noise protocol ID.
+async fn accept_session(
+    sink: &mut FrameSink,
+    stream: &mut FrameStream,
+    local: &LocalNode,
+) -> Result<(Session, NetworkMessage), NetworkError> {
+    let first = read_frame(stream).await?;
+    let mut session = if first[..] == *NOISE_PROTOCOL_ID {
+        noise_handshake(sink, stream, &local.key, false).await?
+    } else if local.allow_plaintext {
+        let mut session = Session::plaintext();
+        let hello = session.recv.open(&first)?;
+        return Ok((session, hello));
+    } else {
+        return Err(NetworkError::ProtocolError(
+            "plaintext connection rejected: encryption is required".into(),
+        ));
+    };
+    let frame = read_frame(stream).await?;
+    let hello = session.recv.open(&frame)?;
+    Ok((session, hello))
+}
+
+/// Establish a session on an outbound connection
+async fn open_session(
+    sink: &mut FrameSink,
+    stream: &mut FrameStream,
+    local: &LocalNode,
+) -> Result<Session, NetworkError> {
+    if local.allow_plaintext {
+        return Ok(Session::plaintext());
+    }
+    write_frame(sink, Bytes::from_static(NOISE_PROTOCOL_ID)).await?;
+    noise_handshake(sink, stream, &local.key, true).await
+}
+
 async fn handle_inbound(
     stream: TcpStream,
     addr: SocketAddr,
     peer_manager: Arc<PeerManager>,
-    local_id: PeerId,
-    params: HandshakeParams,
+    local: Arc<LocalNode>,
 ) -> Result<(), NetworkError> {
-    let codec = LengthDelimitedCodec::builder()
-        .max_frame_length(MAX_FRAME_LEN)
-        .new_codec();
-    let framed = Framed::new(stream, codec);
-    // Expect Hello from remote
+    let framed = Framed::new(stream, codec());
     let (mut sink, mut stream) = framed.split();
-    let hello = match stream.next().await {
-        Some(Ok(bytes)) => bincode::deserialize::<NetworkMessage>(&bytes)
-            .map_err(|e| NetworkError::ProtocolError(format!("decode: {}", e)))?,
-        Some(Err(e)) => return Err(NetworkError::TransportError(format!("read: {}", e))),
-        None => return Err(NetworkError::TransportError("eof".into())),
-    };
-    let (remote_id, remote_head_height, remote_head_hash) = match hello {
+    // Establish the session and expect Hello from remote
+    let (session, hello) = tokio::time::timeout(
+        HANDSHAKE_TIMEOUT,
+        accept_session(&mut sink, &mut stream, &local),
+    )
+    .await
+    .map_err(|_| NetworkError::Timeout(format!("handshake with {}", addr)))??;
+    let params = &local.params;
+    let (remote_id, remote_head_height, remote_head_hash, remote_version, remote_client) = match hello {
         NetworkMessage::Hello {
             version,
             network_id,
@@ -115,6 +442,7 @@ async fn handle_inbound(
             head_height,
             head_hash,
             peer_id,
+            client_version,
         } => {
             if !version.is_compatible(&ProtocolVersion::CURRENT) {
                 return Err(NetworkError::ProtocolError("incompatible version".into()));
@@ -122,25 +450,21 @@ async fn handle_inbound(
             if network_id != params.network_id || genesis_hash != params.genesis_hash {
                 return Err(NetworkError::ProtocolError("network mismatch".into()));
             }
-            (PeerId::new(peer_id), head_height, head_hash)
+            (session.verify_peer_id(peer_id)?, head_height, head_hash, version, client_version)
         }
         _ => return Err(NetworkError::ProtocolError("expected Hello".into())),
     };
+    let Session { mut send, mut recv, .. } = session;
 
     // Send HelloAck
     let ack = NetworkMessage::HelloAck {
         version: ProtocolVersion::CURRENT,
         head_height: params.head_height,
         head_hash: params.head_hash,
-        peer_id: local_id.0.clone(),
+        peer_id: local.key.peer_id().0,
+        client_version: CLIENT_VERSION.to_string(),
     };
-    {
-        let ser = bincode::serialize(&ack)
-            .map_err(|e| NetworkError::ProtocolError(format!("encode: {}", e)))?;
-        sink.send(bytes::Bytes::from(ser))
-            .await
-            .map_err(|e| NetworkError::TransportError(format!("write: {}", e)))?;
-    }
+    send_message(&mut sink, &mut send, &ack).await?;
 
     // Create peer channels
     let (to_wire_tx, mut to_wire_rx) = mpsc::channel::<NetworkMessage>(256);
@@ -150,22 +474,21 @@ async fn handle_inbound(
     info.state = super::peer::PeerState::Connected;
     info.head_height = remote_head_height;
     info.head_hash = remote_head_hash;
+    info.version = Some(remote_version);
+    info.client_version = Some(remote_client);
     let peer = Arc::new(Peer::new(info, to_wire_tx.clone(), from_wire_rx));
     peer_manager.add_peer(peer.clone()).await?;
     info!("Inbound peer connected: {} from {}", remote_id, addr);
 
     // Writer: forward messages from send queue to wire
+    let bandwidth = peer_manager.bandwidth();
+    let writer_bandwidth = bandwidth.clone();
     tokio::spawn(async move {
         while let Some(msg) = to_wire_rx.recv().await {
-            match bincode::serialize(&msg) {
-                Ok(ser) => {
-                    if let Err(e) = sink.send(bytes::Bytes::from(ser)).await {
-                        warn!("send to {} failed: {}", addr, e);
-                        break;
-                    }
-                }
+            match send_message(&mut sink, &mut send, &msg).await {
+                Ok(len) => writer_bandwidth.record(TrafficDirection::Out, &msg, len),
                 Err(e) => {
-                    warn!("encode failed: {}", e);
+                    warn!("send to {} failed: {}", addr, e);
                     break;
                 }
             }
@@ -188,8 +511,9 @@ async fn handle_inbound(
             break;
         }
         match frame {
-            Ok(bytes) => match bincode::deserialize::<NetworkMessage>(&bytes) {
+            Ok(bytes) => match recv.open(&bytes) {
                 Ok(msg) => {
+                    bandwidth.record(TrafficDirection::In, &msg, bytes.len());
                     peer_manager
                         .forward_incoming(remote_id.clone(), msg)
                         .await;
@@ -213,13 +537,11 @@ async fn handle_outbound(
     stream: TcpStream,
     addr: SocketAddr,
     peer_manager: Arc<PeerManager>,
-    local_id: PeerId,
-    params: HandshakeParams,
+    local: Arc<LocalNode>,
 ) -> Result<(), NetworkError> {
-    let codec = LengthDelimitedCodec::builder()
-        .max_frame_length(MAX_FRAME_LEN)
-        .new_codec();
-    let framed = Framed::new(stream, codec);
+    let framed = Framed::new(stream, codec());
+    let (mut sink, mut stream) = framed.split();
+    let params = &local.params;
     // Send Hello
     let hello = NetworkMessage::Hello {
         version: ProtocolVersion::CURRENT,
@@ -227,34 +549,38 @@ async fn handle_outbound(
         genesis_hash: params.genesis_hash,
         head_height: params.head_height,
         head_hash: params.head_hash,
-        peer_id: local_id.0.clone(),
+        peer_id: local.key.peer_id().0,
+        client_version: CLIENT_VERSION.to_string(),
     };
-    let (mut sink, mut stream) = framed.split();
-    {
-        let ser = bincode::serialize(&hello)
-            .map_err(|e| NetworkError::ProtocolError(format!("encode: {}", e)))?;
-        sink.send(bytes::Bytes::from(ser))
-            .await
-            .map_err(|e| NetworkError::TransportError(format!("write: {}", e)))?;
-    }
 
-    // Expect HelloAck
-    let ack = match stream.next().await {
-        Some(Ok(bytes)) => bincode::deserialize::<NetworkMessage>(&bytes)
-            .map_err(|e| NetworkError::ProtocolError(format!("decode: {}", e)))?,
-        Some(Err(e)) => return Err(NetworkError::TransportError(format!("read: {}", e))),
-        None => return Err(NetworkError::TransportError("eof".into())),
-    };
-    if let NetworkMessage::HelloAck { version, peer_id, head_height, head_hash } = ack {
+    // Establish the session, send Hello and expect HelloAck
+    let (session, ack) = tokio::time::timeout(HANDSHAKE_TIMEOUT, async {
+        let mut session = open_session(&mut sink, &mut stream, &local).await?;
+        send_message(&mut sink, &mut session.send, &hello).await?;
+        let frame = read_frame(&mut stream).await?;
+        let ack = session.recv.open(&frame)?;
+        Ok::<_, NetworkError>((session, ack))
+    })
+    .await
+    .map_err(|_| NetworkError::Timeout(format!("handshake with {}", addr)))??;
+    if let NetworkMessage::HelloAck {
+        version,
+        peer_id,
+        head_height,
+        head_hash,
+        client_version,
+    } = ack
+    {
         if !version.is_compatible(&ProtocolVersion::CURRENT) {
             return Err(NetworkError::ProtocolError("incompatible ack".into()));
         }
-        let rid = if peer_id.is_empty() {
+        let rid = if peer_id.is_empty() && session.remote_key_id.is_none() {
             format!("tcp_{}", addr)
         } else {
             peer_id
         };
-        let remote_id = PeerId::new(rid);
+        let remote_id = session.verify_peer_id(rid)?;
+        let Session { mut send, mut recv, .. } = session;
         // Create peer channels
         let (to_wire_tx, mut to_wire_rx) = mpsc::channel::<NetworkMessage>(256);
         let (_from_wire_tx, from_wire_rx) = mpsc::channel::<NetworkMessage>(256);
@@ -262,22 +588,21 @@ async fn handle_outbound(
         info.state = super::peer::PeerState::Connected;
         info.head_height = head_height;
         info.head_hash = head_hash;
+        info.version = Some(version);
+        info.client_version = Some(client_version);
         let peer = Arc::new(Peer::new(info, to_wire_tx.clone(), from_wire_rx));
         peer_manager.add_peer(peer.clone()).await?;
         info!("Outbound peer connected: {} at {}", remote_id, addr);
 
         // Writer task
+        let bandwidth = peer_manager.bandwidth();
+        let writer_bandwidth = bandwidth.clone();
         tokio::spawn(async move {
             while let Some(msg) = to_wire_rx.recv().await {
-                match bincode::serialize(&msg) {
-                    Ok(ser) => {
-                        if let Err(e) = sink.send(bytes::Bytes::from(ser)).await {
-                            warn!("send to {} failed: {}", addr, e);
-                            break;
-                        }
-                    }
+                match send_message(&mut sink, &mut send, &msg).await {
+                    Ok(len) => writer_bandwidth.record(TrafficDirection::Out, &msg, len),
                     Err(e) => {
-                        warn!("encode failed: {}", e);
+                        warn!("send to {} failed: {}", addr, e);
                         break;
                     }
                 }
@@ -300,8 +625,9 @@ async fn handle_outbound(
                 break;
             }
             match frame {
-                Ok(bytes) => match bincode::deserialize::<NetworkMessage>(&bytes) {
+                Ok(bytes) => match recv.open(&bytes) {
                     Ok(msg) => {
+                        bandwidth.record(TrafficDirection::In, &msg, bytes.len());
                         peer_manager
                             .forward_incoming(remote_id.clone(), msg)
                             .await;
@@ -324,4 +650,135 @@ async fn handle_outbound(
     }
 }
 
-// helper functions removed in favor of split-based loops
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peer::PeerManagerConfig;
+
+    fn params() -> HandshakeParams {
+        HandshakeParams {
+            network_id: 1337,
+            genesis_hash: Hash::default(),
+            head_height: 0,
+            head_hash: Hash::default(),
+        }
+    }
+
+    fn free_addr() -> SocketAddr {
+        std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+    }
+
+    async fn wait_for_peer(pm: &PeerManager, id: &PeerId) -> bool {
+        for _ in 0..50 {
+            if pm.get_peer(id).is_some() {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        false
+    }
+
+    #[test]
+    fn test_node_key_peer_id() {
+        let key = NodeKey::generate();
+        let restored = NodeKey::from_bytes(&key.to_bytes()).unwrap();
+        assert_eq!(restored.public_key(), key.public_key());
+        assert_eq!(restored.peer_id(), key.peer_id());
+        assert_eq!(key.peer_id().0.len(), 64);
+        assert_ne!(NodeKey::generate().peer_id(), key.peer_id());
+        assert!(NodeKey::from_bytes(&[0u8; 31]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_connection_binds_peer_ids() {
+        let (listener_key, dialer_key) = (NodeKey::generate(), NodeKey::generate());
+        let listener_pm = Arc::new(PeerManager::new(PeerManagerConfig::default()));
+        let dialer_pm = Arc::new(PeerManager::new(PeerManagerConfig::default()));
+        let listener = NetworkTransport::new(listener_pm.clone(), listener_key.clone(), params());
+        let dialer = NetworkTransport::new(dialer_pm.clone(), dialer_key.clone(), params());
+
+        let addr = free_addr();
+        listener.start_listener(addr).await.unwrap();
+        let (tx, mut rx) = mpsc::channel(4);
+        listener_pm.set_incoming(tx).await;
+        dialer.connect_to(addr).await.unwrap();
+
+        assert!(wait_for_peer(&listener_pm, &dialer_key.peer_id()).await);
+        assert!(wait_for_peer(&dialer_pm, &listener_key.peer_id()).await);
+
+        // Messages larger than one Noise message are chunked
+        let reason = "x".repeat(200_000);
+        dialer_pm
+            .get_peer(&listener_key.peer_id())
+            .unwrap()
+            .send(NetworkMessage::Disconnect { reason: reason.clone() })
+            .await
+            .unwrap();
+        let (from, msg) = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(from, dialer_key.peer_id());
+        assert!(matches!(msg, NetworkMessage::Disconnect { reason: r } if r == reason));
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_dial_rejected_unless_allowed() {
+        let listener_pm = Arc::new(PeerManager::new(PeerManagerConfig::default()));
+        let dialer_pm = Arc::new(PeerManager::new(PeerManagerConfig::default()));
+        let dialer_key = NodeKey::generate();
+        let dialer = NetworkTransport::new(dialer_pm.clone(), dialer_key.clone(), params())
+            .allow_plaintext(true);
+
+        let addr = free_addr();
+        NetworkTransport::new(listener_pm.clone(), NodeKey::generate(), params())
+            .start_listener(addr)
+            .await
+            .unwrap();
+        dialer.connect_to(addr).await.unwrap();
+        assert!(!wait_for_peer(&listener_pm, &dialer_key.peer_id()).await);
+
+        // A devnet listener accepts the plaintext dial
+        let addr = free_addr();
+        NetworkTransport::new(listener_pm.clone(), NodeKey::generate(), params())
+            .allow_plaintext(true)
+            .start_listener(addr)
+            .await
+            .unwrap();
+        dialer.connect_to(addr).await.unwrap();
+        assert!(wait_for_peer(&listener_pm, &dialer_key.peer_id()).await);
+    }
+
+    #[tokio::test]
+    async fn test_claimed_peer_id_must_match_static_key() {
+        let (mut a, mut b) = tcp_pair().await;
+        let key = NodeKey::generate();
+        let remote = NodeKey::generate();
+        let (initiator, responder) = tokio::join!(
+            noise_handshake(&mut a.0, &mut a.1, &key, true),
+            noise_handshake(&mut b.0, &mut b.1, &remote, false),
+        );
+        let (initiator, responder) = (initiator.unwrap(), responder.unwrap());
+
+        assert_eq!(initiator.verify_peer_id(remote.peer_id().0).unwrap(), remote.peer_id());
+        assert!(initiator.verify_peer_id("peer_1".into()).is_err());
+        assert!(responder.verify_peer_id(remote.peer_id().0).is_err());
+        assert_eq!(
+            Session::plaintext().verify_peer_id("peer_1".into()).unwrap(),
+            PeerId::new("peer_1".into())
+        );
+    }
+
+    async fn tcp_pair() -> ((FrameSink, FrameStream), (FrameSink, FrameStream)) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (dialed, accepted) = tokio::join!(TcpStream::connect(addr), listener.accept());
+        (
+            Framed::new(dialed.unwrap(), codec()).split(),
+            Framed::new(accepted.unwrap().0, codec()).split(),
+        )
+    }
+}
@@ -35,6 +35,9 @@ pub enum NetworkError {
     #[error("Transport error: {0}")]
     TransportError(String),
 
+    #[error("Storage error: {0}")]
+    Storage(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
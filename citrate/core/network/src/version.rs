@@ -0,0 +1,168 @@
+// citrate/core/network/src/version.rs
+
+// Client version exchange and peer version distribution
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Version of this client, sent in the handshake
+pub const CLIENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Minimum number of peers reporting a version before suggesting an upgrade,
+/// so a single newer peer cannot trigger the nudge
+pub const MIN_PEERS_FOR_UPGRADE_NOTICE: usize = 3;
+
+/// Semantic version reported by a peer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ClientVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl ClientVersion {
+    /// Parse `major.minor.patch`, allowing a leading `v` and ignoring any
+    /// pre-release or build suffix
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().trim_start_matches('v');
+        let core = s.split(['-', '+']).next()?;
+        let mut parts = core.split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        let patch = parts.next()?.parse().ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self {
+            major,
+            minor,
+            patch,
+        })
+    }
+
+    /// Version of this client
+    pub fn current() -> Self {
+        Self::parse(CLIENT_VERSION).expect("crate version is valid semver")
+    }
+}
+
+impl fmt::Display for ClientVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Client versions of the connected peers
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionDistribution {
+    /// Number of connected peers per reported version
+    pub versions: BTreeMap<String, usize>,
+    /// Peers that reported no version or an unparseable one
+    pub unknown: usize,
+    /// Set when a majority of peers run a newer version than this client:
+    /// the highest version that a majority runs or exceeds
+    pub upgrade_available: Option<String>,
+}
+
+impl VersionDistribution {
+    /// Summarize peer versions relative to `local`
+    pub fn from_peers<'a, I>(local: ClientVersion, peers: I) -> Self
+    where
+        I: IntoIterator<Item = Option<&'a str>>,
+    {
+        let mut counts: BTreeMap<ClientVersion, usize> = BTreeMap::new();
+        let mut unknown = 0;
+        for version in peers {
+            match version.and_then(ClientVersion::parse) {
+                Some(v) => *counts.entry(v).or_default() += 1,
+                None => unknown += 1,
+            }
+        }
+
+        let known: usize = counts.values().sum();
+        let mut upgrade_available = None;
+        if known >= MIN_PEERS_FOR_UPGRADE_NOTICE {
+            // Walk from the newest version down until a majority is covered
+            let mut at_or_above = 0;
+            for (version, count) in counts.iter().rev() {
+                if *version <= local {
+                    break;
+                }
+                at_or_above += count;
+                if at_or_above * 2 > known {
+                    upgrade_available = Some(version.to_string());
+                    break;
+                }
+            }
+        }
+
+        Self {
+            versions: counts
+                .into_iter()
+                .map(|(v, n)| (v.to_string(), n))
+                .collect(),
+            unknown,
+            upgrade_available,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> ClientVersion {
+        ClientVersion::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_client_version() {
+        assert_eq!(
+            v("1.2.3"),
+            ClientVersion {
+                major: 1,
+                minor: 2,
+                patch: 3
+            }
+        );
+        assert_eq!(v("v0.4.0-rc.1"), v("0.4.0"));
+        assert_eq!(v("0.4.0+build.7"), v("0.4.0"));
+        assert!(ClientVersion::parse("").is_none());
+        assert!(ClientVersion::parse("1.2").is_none());
+        assert!(ClientVersion::parse("1.2.3.4").is_none());
+        assert!(v("0.10.0") > v("0.9.9"));
+        assert_eq!(ClientVersion::current().to_string(), CLIENT_VERSION);
+    }
+
+    #[test]
+    fn test_majority_on_newer_version() {
+        let peers = [Some("0.2.0"), Some("0.2.0"), Some("0.1.0"), None];
+        let dist = VersionDistribution::from_peers(v("0.1.0"), peers);
+        assert_eq!(dist.upgrade_available.as_deref(), Some("0.2.0"));
+        assert_eq!(dist.versions["0.2.0"], 2);
+        assert_eq!(dist.unknown, 1);
+    }
+
+    #[test]
+    fn test_majority_spread_across_newer_versions() {
+        // No single newer version has a majority, but 0.2.0-or-newer does
+        let peers = [Some("0.3.0"), Some("0.2.0"), Some("0.1.0"), Some("0.2.5")];
+        let dist = VersionDistribution::from_peers(v("0.1.0"), peers);
+        assert_eq!(dist.upgrade_available.as_deref(), Some("0.2.0"));
+    }
+
+    #[test]
+    fn test_no_upgrade_without_majority() {
+        let peers = [Some("0.2.0"), Some("0.1.0"), Some("0.1.0"), Some("0.0.9")];
+        let dist = VersionDistribution::from_peers(v("0.1.0"), peers);
+        assert!(dist.upgrade_available.is_none());
+
+        // Too few peers to trust
+        let dist = VersionDistribution::from_peers(v("0.1.0"), [Some("0.2.0"), Some("0.2.0")]);
+        assert!(dist.upgrade_available.is_none());
+
+        // Up to date
+        let dist = VersionDistribution::from_peers(v("0.2.0"), [Some("0.2.0"); 5]);
+        assert!(dist.upgrade_available.is_none());
+    }
+}
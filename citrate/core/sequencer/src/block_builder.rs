@@ -1,19 +1,26 @@
 // citrate/core/sequencer/src/block_builder.rs
 
-use crate::mempool::{Mempool, TxClass};
+use crate::mempool::{GasLaneConfig, Mempool, TxClass};
+use crate::selection::{self, AtomicBundle, SelectionLimits, SelectionMetrics, SelectionOutcome};
+use citrate_consensus::limits::{transaction_bytes, BlockLimitError, BlockLimits};
 use citrate_consensus::{
     Block, BlockHeader, GhostDagParams, Hash, PublicKey, Signature, Transaction, VrfProof,
 };
 use citrate_execution::executor::Executor;
 use citrate_execution::parallel::ParallelExecutor;
+use citrate_execution::state::{PendingStateRoot, StateRootPipeline};
 use citrate_execution::types::TransactionReceipt;
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256, Sha3_256};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use thiserror::Error;
+use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+/// Minimum base fee per gas (1 gwei)
+const MIN_BASE_FEE: u64 = 1_000_000_000;
+
 #[derive(Error, Debug)]
 pub enum BlockBuilderError {
     #[error("No transactions available")]
@@ -39,6 +46,15 @@ pub enum BlockBuilderError {
 
     #[error("Transaction execution failed: {0}")]
     ExecutionError(String),
+
+    #[error("Invalid bundle: {0}")]
+    InvalidBundle(String),
+
+    #[error("Bundle queue is full")]
+    BundleQueueFull,
+
+    #[error("Block limit exceeded: {0}")]
+    LimitExceeded(#[from] BlockLimitError),
 }
 
 /// Block builder configuration
@@ -64,6 +80,32 @@ pub struct BlockBuilderConfig {
 
     /// Bundle size for similar transactions
     pub bundle_size: usize,
+
+    /// Maximum number of atomic bundles waiting for inclusion
+    #[serde(default = "default_max_pending_bundles")]
+    pub max_pending_bundles: usize,
+
+    /// Maximum number of transactions in a single atomic bundle
+    #[serde(default = "default_max_bundle_transactions")]
+    pub max_bundle_transactions: usize,
+
+    /// Share of `max_gas_per_block` reserved for standard, inference and
+    /// model deployment transactions
+    #[serde(default)]
+    pub gas_lanes: GasLaneConfig,
+
+    /// Transaction byte, model metadata and required pin limits. Governance
+    /// values override these once an executor is attached.
+    #[serde(default)]
+    pub limits: BlockLimits,
+}
+
+fn default_max_pending_bundles() -> usize {
+    64
+}
+
+fn default_max_bundle_transactions() -> usize {
+    16
 }
 
 impl Default for BlockBuilderConfig {
@@ -76,6 +118,10 @@ impl Default for BlockBuilderConfig {
             block_time_target: 2, // 2 seconds
             enable_bundling: true,
             bundle_size: 10,
+            max_pending_bundles: default_max_pending_bundles(),
+            max_bundle_transactions: default_max_bundle_transactions(),
+            gas_lanes: GasLaneConfig::default(),
+            limits: BlockLimits::default(),
         }
     }
 }
@@ -116,8 +162,16 @@ pub struct BlockBuilder {
     mempool: Arc<Mempool>,
     proposer_key: PublicKey,
     executor: Option<Arc<Executor>>,
+    /// Hashes executed state in the background, set together with the executor
+    state_root_pipeline: Option<StateRootPipeline>,
     #[allow(dead_code)]
     parallel_executor: Arc<ParallelExecutor>,
+    /// Atomic bundles waiting for inclusion
+    bundles: RwLock<Vec<AtomicBundle>>,
+    /// Selection metrics of the most recently built block
+    last_selection: RwLock<Option<SelectionMetrics>>,
+    /// Selection metrics summed over all built blocks
+    total_selection: RwLock<SelectionMetrics>,
 }
 
 impl BlockBuilder {
@@ -127,16 +181,29 @@ impl BlockBuilder {
             mempool,
             proposer_key,
             executor: None,
+            state_root_pipeline: None,
             parallel_executor: Arc::new(ParallelExecutor::new()),
+            bundles: RwLock::new(Vec::new()),
+            last_selection: RwLock::new(None),
+            total_selection: RwLock::new(SelectionMetrics::default()),
         }
     }
 
     /// Set the executor for parallel transaction execution
     pub fn with_executor(mut self, executor: Arc<Executor>) -> Self {
+        self.state_root_pipeline = Some(StateRootPipeline::spawn(executor.state_db().clone()));
         self.executor = Some(executor);
         self
     }
 
+    /// Block limits in force, with governance overrides when an executor is set
+    pub fn block_limits(&self) -> BlockLimits {
+        match &self.executor {
+            Some(executor) => executor.governance_block_limits(self.config.limits),
+            None => self.config.limits,
+        }
+    }
+
     /// Build a new block
     ///
     /// CONSENSUS-CRITICAL: This method executes transactions and computes real state/receipt roots.
@@ -151,12 +218,14 @@ impl BlockBuilder {
     ) -> Result<Block, BlockBuilderError> {
         info!("Building new block with parent {}", selected_parent);
 
-        // Get transactions from mempool
-        let transactions = self.select_transactions().await?;
+        // Get transactions from mempool and pending bundles
+        let selection = self.select_transactions().await?;
+        let transactions = selection.transactions.clone();
 
         if transactions.is_empty() && self.config.min_transactions > 0 {
             return Err(BlockBuilderError::NoTransactions);
         }
+        self.record_selection(&selection).await;
 
         // Build block header first (needed for execution context)
         let timestamp = SystemTime::now()
@@ -180,6 +249,7 @@ impl BlockBuilder {
             base_fee_per_gas: 0,
             gas_used: 0,
             gas_limit: self.config.max_gas_per_block,
+            epoch_commitment: Hash::default(),
         };
 
         // Create preliminary block for execution context
@@ -200,10 +270,14 @@ impl BlockBuilder {
         let (executed_txs, receipts) = self.execute_transactions(&block, transactions).await?;
         block.transactions = executed_txs;
 
-        // Calculate roots from real execution results
+        // Calculate roots from real execution results. The state root is
+        // finished in the background while the other roots are computed.
+        let pending_state_root = self.state_root_pipeline.as_ref().map(|p| p.finalize());
         let tx_root = self.calculate_tx_root(&block.transactions);
-        let state_root = self.calculate_state_root_from_execution(&receipts)?;
         let receipt_root = self.calculate_receipt_root_from_receipts(&receipts)?;
+        let state_root = self
+            .calculate_state_root_from_execution(&receipts, pending_state_root)
+            .await?;
         let artifact_root = Hash::default(); // Placeholder for AI artifacts
 
         // Calculate total gas used from receipts (EIP-1559 requirement)
@@ -261,7 +335,14 @@ impl BlockBuilder {
         let mut receipts = Vec::with_capacity(transactions.len());
 
         for tx in transactions {
-            match executor.execute_transaction(block, &tx).await {
+            let result = executor.execute_transaction(block, &tx).await;
+
+            // Hash this transaction's state changes while the next one executes
+            if let Some(pipeline) = &self.state_root_pipeline {
+                pipeline.update();
+            }
+
+            match result {
                 Ok(receipt) => {
                     if receipt.status {
                         executed_txs.push(tx);
@@ -286,36 +367,132 @@ impl BlockBuilder {
     }
 
     /// Select transactions for inclusion
-    async fn select_transactions(&self) -> Result<Vec<Transaction>, BlockBuilderError> {
-        let max_gas = self.config.max_gas_per_block;
-        let max_size = self.config.max_block_size;
-        let max_count = self.config.max_transactions;
-
-        // Get best transactions from mempool
-        let transactions = self
-            .mempool
-            .get_best_transactions(max_count, max_size)
-            .await;
+    ///
+    /// Mempool transactions and pending atomic bundles are ranked by effective
+    /// fee per gas (scaled by class priority), resolving per-sender nonce
+    /// dependencies. A transaction that does not fit is skipped together with
+    /// its sender's later nonces, while other senders keep filling the block.
+    /// Each gas lane is limited to its configured share of the block.
+    async fn select_transactions(&self) -> Result<SelectionOutcome, BlockBuilderError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let bundles = {
+            let mut pending = self.bundles.write().await;
+            pending.retain(|b| !b.is_expired(now));
+            pending.clone()
+        };
 
-        // Apply gas limit
-        let mut total_gas = 0;
-        let mut selected = Vec::new();
+        let block_limits = self.block_limits();
+        let limits = SelectionLimits {
+            max_gas: self.config.max_gas_per_block,
+            max_size: self.config.max_block_size.min(block_limits.max_tx_bytes),
+            max_count: self.config.max_transactions,
+            max_model_metadata: block_limits.max_model_metadata_bytes,
+            base_fee: MIN_BASE_FEE,
+            lanes: Some(self.config.gas_lanes),
+        };
 
-        for tx in transactions {
-            if total_gas + tx.gas_limit > max_gas {
-                break;
-            }
-            total_gas += tx.gas_limit;
-            selected.push(tx);
-        }
+        let outcome = selection::select_transactions(
+            self.mempool.pending_snapshot().await,
+            &bundles,
+            limits,
+            |tx| self.classify_transaction(tx),
+        );
 
         debug!(
-            "Selected {} transactions with total gas {}",
-            selected.len(),
-            total_gas
+            "Selected {} transactions with total gas {} ({} skipped, {} bundles)",
+            outcome.metrics.selected,
+            outcome.metrics.total_gas,
+            outcome.metrics.skipped(),
+            outcome.metrics.bundles_included
+        );
+
+        Ok(outcome)
+    }
+
+    /// Store selection metrics for a built block and drop included bundles
+    async fn record_selection(&self, outcome: &SelectionOutcome) {
+        if !outcome.included_bundles.is_empty() {
+            self.bundles
+                .write()
+                .await
+                .retain(|b| !outcome.included_bundles.contains(&b.id));
+        }
+        self.total_selection
+            .write()
+            .await
+            .accumulate(&outcome.metrics);
+        *self.last_selection.write().await = Some(outcome.metrics.clone());
+    }
+
+    /// Submit an atomic bundle for inclusion in upcoming blocks
+    ///
+    /// The bundle is either included in full or not at all; it stays pending
+    /// until included or expired.
+    pub async fn submit_bundle(
+        &self,
+        mut transactions: Vec<Transaction>,
+        expires_at: u64,
+    ) -> Result<Hash, BlockBuilderError> {
+        if transactions.len() > self.config.max_bundle_transactions {
+            return Err(BlockBuilderError::InvalidBundle(format!(
+                "bundle has {} transactions, maximum is {}",
+                transactions.len(),
+                self.config.max_bundle_transactions
+            )));
+        }
+        for tx in transactions.iter_mut() {
+            tx.determine_type();
+        }
+
+        let bundle = AtomicBundle::new(transactions, expires_at)
+            .map_err(BlockBuilderError::InvalidBundle)?;
+        if bundle.total_gas() > self.config.max_gas_per_block {
+            return Err(BlockBuilderError::GasLimitExceeded);
+        }
+
+        let mut pending = self.bundles.write().await;
+        if pending.iter().any(|b| b.id == bundle.id) {
+            return Ok(bundle.id);
+        }
+        if pending.len() >= self.config.max_pending_bundles {
+            return Err(BlockBuilderError::BundleQueueFull);
+        }
+
+        let id = bundle.id;
+        info!(
+            "Accepted bundle {} with {} transactions",
+            id,
+            bundle.transactions.len()
         );
+        pending.push(bundle);
+        Ok(id)
+    }
+
+    /// Withdraw a pending bundle
+    pub async fn cancel_bundle(&self, id: &Hash) -> bool {
+        let mut pending = self.bundles.write().await;
+        let before = pending.len();
+        pending.retain(|b| b.id != *id);
+        pending.len() != before
+    }
+
+    /// Bundles waiting for inclusion
+    pub async fn pending_bundles(&self) -> Vec<AtomicBundle> {
+        self.bundles.read().await.clone()
+    }
+
+    /// Selection metrics of the most recently built block
+    pub async fn last_selection_metrics(&self) -> Option<SelectionMetrics> {
+        self.last_selection.read().await.clone()
+    }
 
-        Ok(selected)
+    /// Selection metrics summed over every block built so far
+    pub async fn total_selection_metrics(&self) -> SelectionMetrics {
+        self.total_selection.read().await.clone()
     }
 
     /// Bundle transactions by class
@@ -324,7 +501,7 @@ impl BlockBuilder {
             return Ok(Vec::new());
         }
 
-        let transactions = self.select_transactions().await?;
+        let transactions = self.select_transactions().await?.transactions;
         let mut bundles: Vec<TxBundle> = Vec::new();
 
         for tx in transactions {
@@ -380,9 +557,6 @@ impl BlockBuilder {
     /// Formula: base_fee = parent_base_fee * (1 + elasticity * (gas_used - target) / target)
     /// Where elasticity = 1/8 (12.5% max change per block)
     fn calculate_base_fee(&self, parent_base_fee: u64, gas_used: u64, gas_target: u64) -> u64 {
-        // Minimum base fee of 1 gwei
-        const MIN_BASE_FEE: u64 = 1_000_000_000;
-
         // If this is the first block or parent had no base fee, use minimum
         if parent_base_fee == 0 {
             return MIN_BASE_FEE;
@@ -425,10 +599,18 @@ impl BlockBuilder {
     ///
     /// CONSENSUS-CRITICAL: State root must come from actual committed state.
     /// Returns error if executor is unavailable (fail-loud for consensus integrity).
-    fn calculate_state_root_from_execution(
+    /// `pending` is the root being finalized by the background pipeline.
+    async fn calculate_state_root_from_execution(
         &self,
         receipts: &[TransactionReceipt],
+        pending: Option<PendingStateRoot>,
     ) -> Result<Hash, BlockBuilderError> {
+        if let Some(pending) = pending {
+            return pending.wait().await.map_err(|e| {
+                BlockBuilderError::StateRootError(format!("Failed to get state root from executor: {}", e))
+            });
+        }
+
         // For empty blocks, return deterministic empty state root
         if receipts.is_empty() {
             // If we have executor, still get real state root
@@ -522,11 +704,13 @@ impl BlockBuilder {
     ) -> Result<Block, BlockBuilderError> {
         info!("Building TEST block with parent {} (synthetic roots)", selected_parent);
 
-        let transactions = self.select_transactions().await?;
+        let selection = self.select_transactions().await?;
+        let transactions = selection.transactions.clone();
 
         if transactions.is_empty() && self.config.min_transactions > 0 {
             return Err(BlockBuilderError::NoTransactions);
         }
+        self.record_selection(&selection).await;
 
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -551,6 +735,7 @@ impl BlockBuilder {
             base_fee_per_gas: 1_000_000_000, // 1 gwei for tests
             gas_used,
             gas_limit: self.config.max_gas_per_block,
+            epoch_commitment: Hash::default(),
         };
 
         // Use legacy synthetic methods for tests
@@ -658,6 +843,9 @@ impl BlockBuilder {
             return Err(BlockBuilderError::BlockSizeExceeded);
         }
 
+        // Check transaction bytes, model metadata and required pins
+        self.block_limits().check(block)?;
+
         Ok(())
     }
 
@@ -668,7 +856,7 @@ impl BlockBuilder {
 
         // Add transaction sizes
         for tx in &block.transactions {
-            size += 32 + 8 + 32 + 32 + 16 + 8 + 8 + tx.data.len() + 64;
+            size += transaction_bytes(tx);
         }
 
         size
@@ -759,6 +947,33 @@ mod tests {
         assert_eq!(block.transactions.len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_state_root_from_background_pipeline() {
+        let (builder, _mempool) = setup_test_builder().await;
+        let state_db = Arc::new(citrate_execution::StateDB::new());
+        state_db
+            .accounts
+            .set_balance(citrate_execution::Address([7; 20]), 1_000u64.into());
+        let executor = Arc::new(Executor::new(state_db.clone()));
+
+        let mut config = builder.config.clone();
+        config.min_transactions = 0;
+        let builder = BlockBuilder::new(config, builder.mempool.clone(), builder.proposer_key)
+            .with_executor(executor.clone());
+
+        let vrf_proof = VrfProof {
+            proof: vec![0; 32],
+            output: Hash::new([0; 32]),
+        };
+        let block = builder
+            .build_block(Hash::new([0xFF; 32]), vec![], 0, 1, vrf_proof)
+            .await
+            .unwrap();
+
+        assert_ne!(block.state_root, Hash::default());
+        assert_eq!(block.state_root, executor.calculate_state_root());
+    }
+
     #[tokio::test]
     async fn test_build_block_with_transactions_requires_executor() {
         let (builder, mempool) = setup_test_builder().await;
@@ -880,6 +1095,7 @@ mod tests {
                 base_fee_per_gas: 1_000_000_000,
                 gas_used: 0,
                 gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
             },
             state_root: Hash::default(),
             tx_root: Hash::default(),
@@ -895,6 +1111,24 @@ mod tests {
         // Valid block
         assert!(builder.validate_block(&block).is_ok());
 
+        // Too many required pins for a non-genesis block
+        block.header.selected_parent_hash = Hash::new([1; 32]);
+        let pin = citrate_consensus::RequiredModel::new(
+            citrate_consensus::ModelId::from_name("pinned"),
+            "Qm".to_string(),
+            Hash::default(),
+            1,
+            0,
+        );
+        block.required_pins = vec![pin; builder.block_limits().max_required_pins + 1];
+        assert!(matches!(
+            builder.validate_block(&block),
+            Err(BlockBuilderError::LimitExceeded(
+                BlockLimitError::RequiredPins { .. }
+            ))
+        ));
+        block.required_pins.clear();
+
         // Add too many transactions
         for i in 0..6000 {
             block.transactions.push(create_test_tx(i, 1_000_000_000));
@@ -1120,6 +1354,7 @@ mod tests {
         cfg.bundle_size = 3;
         // Allow only 3 txs by gas
         cfg.max_gas_per_block = 63_000; // 3 * 21000
+        cfg.gas_lanes = GasLaneConfig::disabled();
         let builder = BlockBuilder::new(cfg, mempool.clone(), builder0.proposer_key);
 
         // Insert 6 txs across classes, distinct senders
@@ -1344,4 +1579,91 @@ mod tests {
         let total_gas: u64 = block.transactions.iter().map(|tx| tx.gas_limit).sum();
         assert_eq!(total_gas, 100_000);
     }
+
+    #[tokio::test]
+    async fn test_bundle_included_atomically_and_metrics_recorded() {
+        let (builder0, mempool) = setup_test_builder().await;
+        let mut cfg = builder0.config.clone();
+        cfg.max_gas_per_block = 63_000; // room for three transfers
+        let builder = BlockBuilder::new(cfg, mempool.clone(), builder0.proposer_key);
+
+        let mk = |nonce: u64, from_b: u8, gas_price: u64, hash_b: u8| {
+            let mut t = create_test_tx(nonce, gas_price);
+            t.from = PublicKey::new([from_b; 32]);
+            t.hash = Hash::new([hash_b; 32]);
+            t
+        };
+        for (i, b) in [0x41u8, 0x42, 0x43].iter().enumerate() {
+            mempool
+                .add_transaction(mk(0, 0x40 + i as u8, 2_000_000_000, *b), TxClass::Standard)
+                .await
+                .unwrap();
+        }
+
+        let bundle_id = builder
+            .submit_bundle(
+                vec![
+                    mk(0, 0x50, 40_000_000_000, 0x51),
+                    mk(1, 0x50, 40_000_000_000, 0x52),
+                ],
+                0,
+            )
+            .await
+            .unwrap();
+        assert_eq!(builder.pending_bundles().await.len(), 1);
+
+        let vrf = VrfProof {
+            proof: vec![0; 32],
+            output: Hash::new([0; 32]),
+        };
+        let block = builder
+            .build_block_for_testing(Hash::new([0xAB; 32]), vec![], 0, 1, vrf)
+            .await
+            .unwrap();
+
+        let hashes: Vec<Hash> = block.transactions.iter().map(|t| t.hash).collect();
+        assert_eq!(hashes.len(), 3);
+        assert_eq!(hashes[0], Hash::new([0x51; 32]));
+        assert_eq!(hashes[1], Hash::new([0x52; 32]));
+
+        let metrics = builder.last_selection_metrics().await.unwrap();
+        assert_eq!(metrics.candidates, 5);
+        assert_eq!(metrics.selected, 3);
+        assert_eq!(metrics.skipped_gas_limit, 2);
+        assert_eq!(metrics.bundles_included, 1);
+
+        // Included bundles are consumed
+        assert!(builder.pending_bundles().await.is_empty());
+        assert!(!builder.cancel_bundle(&bundle_id).await);
+        assert_eq!(builder.total_selection_metrics().await.selected, 3);
+    }
+
+    #[tokio::test]
+    async fn test_submit_bundle_limits() {
+        let (builder0, mempool) = setup_test_builder().await;
+        let mut cfg = builder0.config.clone();
+        cfg.max_bundle_transactions = 1;
+        cfg.max_pending_bundles = 1;
+        let builder = BlockBuilder::new(cfg, mempool, builder0.proposer_key);
+
+        let too_big = vec![
+            create_test_tx(0, 1_000_000_000),
+            create_test_tx(1, 1_000_000_000),
+        ];
+        assert!(matches!(
+            builder.submit_bundle(too_big, 0).await,
+            Err(BlockBuilderError::InvalidBundle(_))
+        ));
+
+        builder
+            .submit_bundle(vec![create_test_tx(0, 1_000_000_000)], 0)
+            .await
+            .unwrap();
+        assert!(matches!(
+            builder
+                .submit_bundle(vec![create_test_tx(5, 1_000_000_000)], 0)
+                .await,
+            Err(BlockBuilderError::BundleQueueFull)
+        ));
+    }
 }
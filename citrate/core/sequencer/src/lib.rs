@@ -3,8 +3,17 @@
 // Sequencer module for block building and mempool management
 pub mod block_builder;
 pub mod mempool;
+pub mod mining_schedule;
+pub mod selection;
+pub mod signing_key;
 pub mod validator;
 
 pub use block_builder::{BlockBuilder, BlockBuilderConfig, BlockBuilderError};
-pub use mempool::{Mempool, MempoolAccess, MempoolConfig, MempoolError, MempoolStats, TxClass};
+pub use mempool::{
+    EvictionReason, GasLane, GasLaneConfig, Mempool, MempoolAccess, MempoolConfig, MempoolError,
+    MempoolStats, TxClass,
+};
+pub use mining_schedule::{MiningMode, MiningSchedule};
+pub use selection::{AtomicBundle, SelectionMetrics};
+pub use signing_key::{BlockSigningKey, KeyRotation, SigningKeyError};
 pub use validator::{TxValidator, ValidationError, ValidationRules};
@@ -1,5 +1,6 @@
 // citrate/core/sequencer/src/mempool.rs
 
+use citrate_consensus::metrics::{self, MetricEvent};
 use citrate_consensus::{Hash, PublicKey, Transaction};
 use priority_queue::PriorityQueue;
 use serde::{Deserialize, Serialize};
@@ -27,6 +28,9 @@ pub enum MempoolError {
     #[error("Gas price too low: minimum {min}, got {got}")]
     GasPriceTooLow { min: u64, got: u64 },
 
+    #[error("Replacement gas price too low: minimum {min}, got {got}")]
+    ReplacementUnderpriced { min: u64, got: u64 },
+
     #[error("Sender limit exceeded")]
     SenderLimitExceeded,
 
@@ -53,6 +57,27 @@ pub enum TxClass {
     Compute,
 }
 
+/// Why a transaction was dropped from the mempool without being included
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EvictionReason {
+    /// Pushed out by a higher priority transaction while full
+    Capacity,
+    /// Pending longer than `tx_expiry_secs`
+    Expired,
+    /// Superseded by a transaction with the same sender and nonce
+    Replaced,
+}
+
+impl EvictionReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EvictionReason::Capacity => "capacity",
+            EvictionReason::Expired => "expired",
+            EvictionReason::Replaced => "replaced",
+        }
+    }
+}
+
 impl TxClass {
     /// Get priority multiplier for this class
     pub fn priority_multiplier(&self) -> u64 {
@@ -68,6 +93,130 @@ impl TxClass {
     }
 }
 
+/// Block space lane a transaction competes in for its share of block gas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GasLane {
+    /// Transfers, contract calls, storage and system transactions
+    Standard,
+    /// Inference requests, training jobs and other AI compute
+    Inference,
+    /// Model deployments, weight updates and LoRA adapters
+    ModelDeploy,
+}
+
+impl GasLane {
+    pub const ALL: [GasLane; 3] = [GasLane::Standard, GasLane::Inference, GasLane::ModelDeploy];
+
+    /// Lane for a transaction, preferring its AI transaction type over its class
+    /// since the mempool folds all AI types into `TxClass::Compute`
+    pub fn of(tx: &Transaction, class: TxClass) -> Self {
+        use citrate_consensus::types::TransactionType;
+
+        match tx.tx_type {
+            Some(
+                TransactionType::ModelDeploy
+                | TransactionType::ModelUpdate
+                | TransactionType::LoraAdapter,
+            ) => GasLane::ModelDeploy,
+            Some(TransactionType::InferenceRequest | TransactionType::TrainingJob) => {
+                GasLane::Inference
+            }
+            Some(TransactionType::Standard) | None => match class {
+                TxClass::ModelUpdate => GasLane::ModelDeploy,
+                TxClass::Inference | TxClass::Training | TxClass::Compute => GasLane::Inference,
+                TxClass::Standard | TxClass::Storage | TxClass::System => GasLane::Standard,
+            },
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GasLane::Standard => "standard",
+            GasLane::Inference => "inference",
+            GasLane::ModelDeploy => "model_deploy",
+        }
+    }
+}
+
+/// Share of block gas, in percent, allocated to each lane
+///
+/// Shares are normalized by their sum, so they need not add up to exactly
+/// 100; setting all of them to zero disables the lanes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasLaneConfig {
+    pub standard_pct: u8,
+    pub inference_pct: u8,
+    pub model_deploy_pct: u8,
+    /// Let a lane use gas the other lanes left unused once every lane has
+    /// been filled up to its share
+    pub allow_spillover: bool,
+}
+
+impl Default for GasLaneConfig {
+    fn default() -> Self {
+        Self {
+            standard_pct: 70,
+            inference_pct: 20,
+            model_deploy_pct: 10,
+            allow_spillover: true,
+        }
+    }
+}
+
+impl GasLaneConfig {
+    /// No lanes: all classes compete for the whole block
+    pub fn disabled() -> Self {
+        Self {
+            standard_pct: 0,
+            inference_pct: 0,
+            model_deploy_pct: 0,
+            allow_spillover: true,
+        }
+    }
+
+    /// Configured share of a lane
+    pub fn share(&self, lane: GasLane) -> u64 {
+        match lane {
+            GasLane::Standard => self.standard_pct as u64,
+            GasLane::Inference => self.inference_pct as u64,
+            GasLane::ModelDeploy => self.model_deploy_pct as u64,
+        }
+    }
+
+    fn total_share(&self) -> u64 {
+        GasLane::ALL.iter().map(|lane| self.share(*lane)).sum()
+    }
+
+    /// Whether any lane has a share, i.e. lanes are enforced at all
+    pub fn is_enabled(&self) -> bool {
+        self.total_share() > 0
+    }
+
+    /// Gas a lane may use out of `total_gas`
+    pub fn cap(&self, lane: GasLane, total_gas: u64) -> u64 {
+        let total_share = self.total_share();
+        if total_share == 0 {
+            return total_gas;
+        }
+        (total_gas as u128 * self.share(lane) as u128 / total_share as u128) as u64
+    }
+
+    /// Compare how far two lanes are over their share given their gas usage
+    pub fn compare_load(&self, a: (GasLane, u64), b: (GasLane, u64)) -> Ordering {
+        // gas_a / share_a vs gas_b / share_b, cross-multiplied; a lane with
+        // no share is infinitely over it as soon as it holds anything
+        let lhs = a.1 as u128 * self.share(b.0) as u128;
+        let rhs = b.1 as u128 * self.share(a.0) as u128;
+        match (self.share(a.0), self.share(b.0)) {
+            (0, 0) => a.1.cmp(&b.1),
+            (0, _) if a.1 > 0 => Ordering::Greater,
+            (_, 0) if b.1 > 0 => Ordering::Less,
+            _ => lhs.cmp(&rhs),
+        }
+    }
+}
+
 /// Transaction priority for ordering
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct TxPriority {
@@ -148,6 +297,10 @@ pub struct MempoolConfig {
 
     /// Chain ID for Ethereum-style transaction verification (EIP-155)
     pub chain_id: u64,
+
+    /// Lane shares used to pick which class of transactions to evict when full
+    #[serde(default)]
+    pub gas_lanes: GasLaneConfig,
 }
 
 impl Default for MempoolConfig {
@@ -162,6 +315,7 @@ impl Default for MempoolConfig {
             // Tighten by default; tests or devnet can disable explicitly
             require_valid_signature: true,
             chain_id: 1337,
+            gas_lanes: GasLaneConfig::default(),
         }
     }
 }
@@ -174,6 +328,15 @@ pub struct MempoolTx {
     pub priority: TxPriority,
     pub added_at: u64,
     pub size: usize,
+    /// Submitted privately: only included by this node, never gossiped
+    pub private: bool,
+}
+
+impl MempoolTx {
+    /// Gas lane this transaction is allocated block space from
+    pub fn lane(&self) -> GasLane {
+        GasLane::of(&self.tx, self.class)
+    }
 }
 
 /// Transaction mempool
@@ -198,6 +361,9 @@ pub struct Mempool {
 
     /// Total size of transactions in bytes
     total_size: Arc<RwLock<usize>>,
+
+    /// Evictions since startup, by reason
+    evictions: Arc<RwLock<HashMap<EvictionReason, u64>>>,
 }
 
 impl Mempool {
@@ -214,14 +380,67 @@ impl Mempool {
             nonces: Arc::new(RwLock::new(HashMap::new())),
             evicted: Arc::new(RwLock::new(HashSet::new())),
             total_size: Arc::new(RwLock::new(0)),
+            evictions: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
     /// Add a transaction to the mempool
     pub async fn add_transaction(
+        &self,
+        tx: Transaction,
+        class: TxClass,
+    ) -> Result<(), MempoolError> {
+        self.insert_transaction(tx, class, false, false).await
+    }
+
+    /// Add several transactions, verifying their signatures as one batch.
+    ///
+    /// Returns one result per transaction, in order. Transactions that fail
+    /// the batch go through the regular single-transaction checks so they
+    /// report the same errors as [`Mempool::add_transaction`].
+    pub async fn add_transactions(
+        &self,
+        txs: Vec<Transaction>,
+        class: TxClass,
+    ) -> Vec<Result<(), MempoolError>> {
+        let verified: Vec<bool> = if self.config.require_valid_signature {
+            citrate_consensus::crypto::verify_transactions(&txs)
+                .into_iter()
+                .map(|r| matches!(r, Ok(true)))
+                .collect()
+        } else {
+            vec![false; txs.len()]
+        };
+
+        let mut results = Vec::with_capacity(txs.len());
+        for (tx, signature_verified) in txs.into_iter().zip(verified) {
+            results.push(
+                self.insert_transaction(tx, class, false, signature_verified)
+                    .await,
+            );
+        }
+        results
+    }
+
+    /// Add a transaction to the node-local private pool.
+    ///
+    /// Private transactions are selected for blocks produced by this node like
+    /// any other, but are hidden from pending listings and must not be relayed
+    /// to peers, so they cannot be observed and front-run before inclusion.
+    pub async fn add_private_transaction(
+        &self,
+        tx: Transaction,
+        class: TxClass,
+    ) -> Result<(), MempoolError> {
+        self.insert_transaction(tx, class, true, false).await
+    }
+
+    async fn insert_transaction(
         &self,
         mut tx: Transaction,
         mut class: TxClass,
+        private: bool,
+        signature_verified: bool,
     ) -> Result<(), MempoolError> {
         // Determine transaction type from data
         tx.determine_type();
@@ -239,15 +458,26 @@ impl Mempool {
         }
 
         tracing::info!(
-            "Adding transaction to mempool: hash={:?}, from={:?}, nonce={}, type={:?}",
+            "Adding transaction to mempool: hash={:?}, from={:?}, nonce={}, type={:?}, private={}",
             tx.hash,
             tx.from,
             tx.nonce,
-            tx.tx_type
+            tx.tx_type,
+            private
         );
 
+        // A different transaction with the same sender and nonce is replaced
+        // if this one pays enough more
+        let replaced = match self.pending_by_nonce(&tx.from, tx.nonce).await {
+            Some(pending) if self.config.allow_replacement && pending.tx.hash != tx.hash => {
+                Some(pending)
+            }
+            _ => None,
+        };
+
         // Basic validation
-        self.validate_transaction(&tx).await?;
+        self.validate_transaction(&tx, signature_verified, replaced.is_some())
+            .await?;
 
         let tx_hash = tx.hash;
         let sender = tx.from;
@@ -263,6 +493,21 @@ impl Mempool {
             return Err(MempoolError::DuplicateTransaction(tx_hash));
         }
 
+        if let Some(pending) = replaced {
+            let min = self.min_replacement_price(pending.tx.gas_price);
+            if tx.gas_price < min {
+                return Err(MempoolError::ReplacementUnderpriced {
+                    min,
+                    got: tx.gas_price,
+                });
+            }
+            info!(
+                "Transaction {} replaces {} (nonce {})",
+                tx_hash, pending.tx.hash, tx.nonce
+            );
+            self.evict(&pending.tx.hash, EvictionReason::Replaced).await;
+        }
+
         // Check sender limit
         let sender_txs = self.by_sender.read().await;
         if let Some(txs) = sender_txs.get(&sender) {
@@ -275,7 +520,8 @@ impl Mempool {
         // Check mempool size limit
         if self.transactions.read().await.len() >= self.config.max_size {
             // Try to evict lower priority transaction
-            self.evict_lowest_priority().await?;
+            self.evict_lowest_priority(GasLane::of(&tx, class), tx.gas_limit)
+                .await?;
         }
 
         // Create mempool transaction with AI-aware priority
@@ -295,6 +541,7 @@ impl Mempool {
             priority,
             added_at: timestamp,
             size: tx_size,
+            private,
         };
 
         // Add to collections
@@ -309,8 +556,11 @@ impl Mempool {
             .or_insert_with(VecDeque::new)
             .push_back(tx_hash);
 
-        // Update nonce tracking
-        self.nonces.write().await.insert(sender, tx.nonce + 1);
+        // Update nonce tracking; a replacement may sit below the next nonce
+        let mut nonces = self.nonces.write().await;
+        let next_nonce = nonces.entry(sender).or_insert(0);
+        *next_nonce = (*next_nonce).max(tx.nonce + 1);
+        drop(nonces);
 
         // Update total size
         *self.total_size.write().await += tx_size;
@@ -325,8 +575,15 @@ impl Mempool {
         Ok(())
     }
 
-    /// Validate a transaction
-    async fn validate_transaction(&self, tx: &Transaction) -> Result<(), MempoolError> {
+    /// Validate a transaction. `signature_verified` skips the signature
+    /// check for transactions that already passed batch verification, and
+    /// `replacing` skips the nonce check for a same-nonce replacement.
+    async fn validate_transaction(
+        &self,
+        tx: &Transaction,
+        signature_verified: bool,
+        replacing: bool,
+    ) -> Result<(), MempoolError> {
         tracing::debug!("Validating transaction with hash: {:?}", tx.hash);
 
         // Basic sanity checks
@@ -372,7 +629,7 @@ impl Mempool {
 
         // Check nonce
         if let Some(&expected_nonce) = self.nonces.read().await.get(&tx.from) {
-            if tx.nonce < expected_nonce {
+            if tx.nonce < expected_nonce && !replacing {
                 tracing::warn!(
                     "Transaction nonce too low: {} < {}",
                     tx.nonce,
@@ -390,6 +647,9 @@ impl Mempool {
             tracing::debug!("Signature verification disabled via mempool config");
             return Ok(());
         }
+        if signature_verified {
+            return Ok(());
+        }
 
         match citrate_consensus::crypto::verify_transaction(tx) {
             Ok(true) => {
@@ -604,6 +864,35 @@ impl Mempool {
         selected
     }
 
+    /// Pending transaction from `sender` with `nonce`, if any
+    pub async fn pending_by_nonce(&self, sender: &PublicKey, nonce: u64) -> Option<MempoolTx> {
+        let by_sender = self.by_sender.read().await;
+        let transactions = self.transactions.read().await;
+        by_sender
+            .get(sender)?
+            .iter()
+            .filter_map(|hash| transactions.get(hash))
+            .find(|pending| pending.tx.nonce == nonce)
+            .cloned()
+    }
+
+    /// Lowest gas price that replaces a pending transaction paying `gas_price`
+    pub fn min_replacement_price(&self, gas_price: u64) -> u64 {
+        gas_price
+            .saturating_mul(self.config.replacement_factor)
+            .div_ceil(100)
+    }
+
+    /// Whether pending transactions can be replaced by sender and nonce
+    pub fn allows_replacement(&self) -> bool {
+        self.config.allow_replacement
+    }
+
+    /// Snapshot of all pending transactions with their class and priority
+    pub async fn pending_snapshot(&self) -> Vec<MempoolTx> {
+        self.transactions.read().await.values().cloned().collect()
+    }
+
     /// Check if transaction has the next expected nonce for sender
     #[allow(dead_code)]
     async fn is_next_nonce(&self, tx: &Transaction, included: &HashSet<Hash>) -> bool {
@@ -648,20 +937,48 @@ impl Mempool {
         }
     }
 
-    /// Evict the lowest priority transaction
-    async fn evict_lowest_priority(&self) -> Result<(), MempoolError> {
-        let priority_queue = self.priority_queue.read().await;
+    /// Evict the lowest priority transaction from the lane most over its
+    /// share of pending gas, counting the incoming transaction towards its lane
+    async fn evict_lowest_priority(
+        &self,
+        incoming_lane: GasLane,
+        incoming_gas: u64,
+    ) -> Result<(), MempoolError> {
+        let txs = self.transactions.read().await;
+
+        let mut lane_gas: HashMap<GasLane, u64> = HashMap::new();
+        for mempool_tx in txs.values() {
+            *lane_gas.entry(mempool_tx.lane()).or_insert(0) += mempool_tx.tx.gas_limit;
+        }
+        // Only lanes holding transactions can give one up
+        let occupied: Vec<GasLane> = GasLane::ALL
+            .into_iter()
+            .filter(|lane| lane_gas.contains_key(lane))
+            .collect();
+        *lane_gas.entry(incoming_lane).or_insert(0) += incoming_gas;
+
+        let lanes = &self.config.gas_lanes;
+        let target = if lanes.is_enabled() {
+            occupied.into_iter().max_by(|a, b| {
+                lanes
+                    .compare_load((*a, lane_gas[a]), (*b, lane_gas[b]))
+                    .then_with(|| b.cmp(a))
+            })
+        } else {
+            None
+        };
 
         // Find the transaction with the lowest priority
-        let lowest = priority_queue
+        let lowest = txs
             .iter()
-            .min_by_key(|(_, priority)| priority.score())
+            .filter(|(_, mempool_tx)| target.is_none_or(|lane| mempool_tx.lane() == lane))
+            .min_by_key(|(_, mempool_tx)| mempool_tx.priority.score())
             .map(|(hash, _)| *hash);
 
-        drop(priority_queue);
+        drop(txs);
 
         if let Some(hash) = lowest {
-            self.remove_transaction(&hash).await;
+            self.evict(&hash, EvictionReason::Capacity).await;
             Ok(())
         } else {
             Err(MempoolError::Full)
@@ -697,19 +1014,33 @@ impl Mempool {
 
         let count = expired.len();
         for hash in expired {
-            self.remove_transaction(&hash).await;
+            self.evict(&hash, EvictionReason::Expired).await;
         }
 
         debug!("Cleared {} expired transactions", count);
     }
 
+    /// Remove a transaction that will not be included and count why
+    async fn evict(&self, hash: &Hash, reason: EvictionReason) {
+        if self.remove_transaction(hash).await.is_some() {
+            *self.evictions.write().await.entry(reason).or_insert(0) += 1;
+            metrics::record(MetricEvent::MempoolEviction {
+                reason: reason.as_str(),
+            });
+        }
+    }
+
     /// Get mempool statistics
     pub async fn stats(&self) -> MempoolStats {
         let txs = self.transactions.read().await;
         let mut by_class = HashMap::new();
+        let mut private_transactions = 0;
 
         for mempool_tx in txs.values() {
             *by_class.entry(mempool_tx.class).or_insert(0) += 1;
+            if mempool_tx.private {
+                private_transactions += 1;
+            }
         }
 
         MempoolStats {
@@ -717,6 +1048,8 @@ impl Mempool {
             total_size: *self.total_size.read().await,
             by_class,
             unique_senders: self.by_sender.read().await.len(),
+            private_transactions,
+            evictions: self.evictions.read().await.clone(),
         }
     }
 
@@ -734,6 +1067,16 @@ impl Mempool {
         self.transactions.read().await.contains_key(hash)
     }
 
+    /// Check if a pending transaction was submitted privately
+    pub async fn is_private(&self, hash: &Hash) -> bool {
+        self.transactions
+            .read()
+            .await
+            .get(hash)
+            .map(|tx| tx.private)
+            .unwrap_or(false)
+    }
+
     /// Get multiple transactions from mempool
     pub async fn get_transactions(&self, limit: usize) -> Vec<Transaction> {
         let txs = self.transactions.read().await;
@@ -746,6 +1089,19 @@ impl Mempool {
         result
     }
 
+    /// Get pending transactions that may be shared with peers and RPC clients,
+    /// excluding the private pool
+    pub async fn get_public_transactions(&self, limit: usize) -> Vec<Transaction> {
+        self.transactions
+            .read()
+            .await
+            .values()
+            .filter(|mempool_tx| !mempool_tx.private)
+            .take(limit)
+            .map(|mempool_tx| mempool_tx.tx.clone())
+            .collect()
+    }
+
     /// Clear the mempool
     pub async fn clear(&self) {
         self.transactions.write().await.clear();
@@ -764,6 +1120,9 @@ pub struct MempoolStats {
     pub total_size: usize,
     pub by_class: HashMap<TxClass, usize>,
     pub unique_senders: usize,
+    pub private_transactions: usize,
+    /// Evictions since startup, by reason
+    pub evictions: HashMap<EvictionReason, u64>,
 }
 
 /// Trait for abstracting mempool access patterns.
@@ -781,6 +1140,13 @@ pub trait MempoolAccess: Send + Sync {
     /// Add a transaction to the mempool
     async fn add_transaction(&self, tx: Transaction, class: TxClass) -> Result<(), MempoolError>;
 
+    /// Add a transaction to the node-local private pool (never gossiped)
+    async fn add_private_transaction(
+        &self,
+        tx: Transaction,
+        class: TxClass,
+    ) -> Result<(), MempoolError>;
+
     /// Remove a transaction from the mempool
     async fn remove_transaction(&self, hash: &Hash) -> Option<Transaction>;
 
@@ -820,6 +1186,14 @@ impl MempoolAccess for Arc<Mempool> {
         Mempool::add_transaction(self, tx, class).await
     }
 
+    async fn add_private_transaction(
+        &self,
+        tx: Transaction,
+        class: TxClass,
+    ) -> Result<(), MempoolError> {
+        Mempool::add_private_transaction(self, tx, class).await
+    }
+
     async fn remove_transaction(&self, hash: &Hash) -> Option<Transaction> {
         Mempool::remove_transaction(self, hash).await
     }
@@ -873,6 +1247,14 @@ impl MempoolAccess for Arc<RwLock<Mempool>> {
         self.read().await.add_transaction(tx, class).await
     }
 
+    async fn add_private_transaction(
+        &self,
+        tx: Transaction,
+        class: TxClass,
+    ) -> Result<(), MempoolError> {
+        self.read().await.add_private_transaction(tx, class).await
+    }
+
     async fn remove_transaction(&self, hash: &Hash) -> Option<Transaction> {
         self.read().await.remove_transaction(hash).await
     }
@@ -954,6 +1336,31 @@ mod tests {
         assert_eq!(mempool.stats().await.total_transactions, 1);
     }
 
+    #[tokio::test]
+    async fn test_add_transactions_batch_verifies_signatures() {
+        let mempool = Mempool::new(MempoolConfig::default());
+
+        let mut txs: Vec<_> = (0..4u8)
+            .map(|i| {
+                let mut tx = create_test_tx(0, 2_000_000_000, [i + 1; 32]);
+                let key = citrate_consensus::crypto::generate_keypair();
+                citrate_consensus::crypto::sign_transaction(&mut tx, &key).unwrap();
+                tx
+            })
+            .collect();
+        txs[2].value += 1;
+
+        let results = mempool.add_transactions(txs.clone(), TxClass::Standard).await;
+        assert_eq!(results.len(), 4);
+        assert!(results[0].is_ok() && results[1].is_ok() && results[3].is_ok());
+        assert!(matches!(
+            results[2],
+            Err(MempoolError::InvalidTransaction(_))
+        ));
+        assert!(!mempool.contains(&txs[2].hash).await);
+        assert_eq!(mempool.stats().await.total_transactions, 3);
+    }
+
     #[tokio::test]
     async fn test_duplicate_transaction() {
         let config = MempoolConfig {
@@ -1119,6 +1526,129 @@ mod tests {
         assert!(matches!(res, Err(MempoolError::SenderLimitExceeded)));
     }
 
+    #[tokio::test]
+    async fn test_evictions_are_counted_by_reason() {
+        let config = MempoolConfig {
+            max_size: 2,
+            require_valid_signature: false,
+            ..Default::default()
+        };
+        let mempool = Mempool::new(config);
+
+        for (sender, gas_price) in [(1, 2_000_000_000), (2, 3_000_000_000), (3, 4_000_000_000)] {
+            let tx = create_test_tx(0, gas_price, [sender; 32]);
+            mempool.add_transaction(tx, TxClass::Standard).await.unwrap();
+        }
+
+        let stats = mempool.stats().await;
+        assert_eq!(stats.total_transactions, 2);
+        assert_eq!(stats.evictions.get(&EvictionReason::Capacity), Some(&1));
+        assert_eq!(stats.evictions.get(&EvictionReason::Expired), None);
+
+        // Removal on inclusion is not an eviction
+        let hash = mempool.get_transactions(1).await[0].hash;
+        mempool.remove_transaction(&hash).await;
+        assert_eq!(mempool.stats().await.evictions.values().sum::<u64>(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_replace_by_fee() {
+        let config = MempoolConfig {
+            require_valid_signature: false,
+            replacement_factor: 110,
+            ..Default::default()
+        };
+        let mempool = Mempool::new(config);
+
+        let sender = [4; 32];
+        let original = create_test_tx(0, 2_000_000_000, sender);
+        let next = create_test_tx(1, 2_000_000_000, sender);
+        mempool
+            .add_transaction(original.clone(), TxClass::Standard)
+            .await
+            .unwrap();
+        mempool
+            .add_transaction(next.clone(), TxClass::Standard)
+            .await
+            .unwrap();
+
+        // Less than a 10% bump is rejected
+        let cheap = create_test_tx(0, 2_100_000_000, sender);
+        assert!(matches!(
+            mempool.add_transaction(cheap, TxClass::Standard).await,
+            Err(MempoolError::ReplacementUnderpriced {
+                min: 2_200_000_000,
+                got: 2_100_000_000
+            })
+        ));
+
+        let bumped = create_test_tx(0, 2_200_000_000, sender);
+        mempool
+            .add_transaction(bumped.clone(), TxClass::Standard)
+            .await
+            .unwrap();
+        assert!(!mempool.contains(&original.hash).await);
+        assert_eq!(
+            mempool
+                .pending_by_nonce(&PublicKey::new(sender), 0)
+                .await
+                .map(|pending| pending.tx.hash),
+            Some(bumped.hash)
+        );
+
+        // The next nonce is unchanged and the replaced tx can't come back
+        let stats = mempool.stats().await;
+        assert_eq!(stats.total_transactions, 2);
+        assert_eq!(stats.evictions.get(&EvictionReason::Replaced), Some(&1));
+        assert!(mempool
+            .add_transaction(create_test_tx(2, 2_000_000_000, sender), TxClass::Standard)
+            .await
+            .is_ok());
+        assert!(mempool
+            .add_transaction(original, TxClass::Standard)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_eviction_targets_lane_most_over_its_share() {
+        let config = MempoolConfig {
+            max_size: 3,
+            require_valid_signature: false,
+            ..Default::default()
+        };
+        let mempool = Mempool::new(config);
+
+        // Two inference txs hold 2/3 of pending gas against a 20% share
+        let inference_low = create_test_tx(0, 2_000_000_000, [1; 32]);
+        let inference_high = create_test_tx(0, 3_000_000_000, [2; 32]);
+        let standard = create_test_tx(0, 1_000_000_000, [3; 32]);
+        mempool
+            .add_transaction(inference_low.clone(), TxClass::Inference)
+            .await
+            .unwrap();
+        mempool
+            .add_transaction(inference_high.clone(), TxClass::Inference)
+            .await
+            .unwrap();
+        mempool
+            .add_transaction(standard.clone(), TxClass::Standard)
+            .await
+            .unwrap();
+
+        // The standard tx has the lowest score, but inference is over its share
+        let incoming = create_test_tx(0, 1_000_000_000, [4; 32]);
+        mempool
+            .add_transaction(incoming.clone(), TxClass::Standard)
+            .await
+            .unwrap();
+
+        assert!(!mempool.contains(&inference_low.hash).await);
+        assert!(mempool.contains(&inference_high.hash).await);
+        assert!(mempool.contains(&standard.hash).await);
+        assert!(mempool.contains(&incoming.hash).await);
+    }
+
     #[tokio::test]
     async fn test_mixed_class_priority_with_gas_cap() {
         let config = MempoolConfig {
@@ -1173,4 +1703,43 @@ mod tests {
         assert_eq!(best[2].hash, tx_comp.hash);
         assert_eq!(best[3].hash, tx_std.hash);
     }
+
+    #[tokio::test]
+    async fn test_private_transactions_are_selected_but_not_listed() {
+        let config = MempoolConfig {
+            require_valid_signature: false,
+            ..Default::default()
+        };
+        let mempool = Mempool::new(config);
+
+        let public_tx = create_test_tx(0, 2_000_000_000, [1; 32]);
+        let private_tx = create_test_tx(0, 3_000_000_000, [2; 32]);
+        mempool
+            .add_transaction(public_tx.clone(), TxClass::Standard)
+            .await
+            .unwrap();
+        mempool
+            .add_private_transaction(private_tx.clone(), TxClass::Standard)
+            .await
+            .unwrap();
+
+        assert!(mempool.is_private(&private_tx.hash).await);
+        assert!(!mempool.is_private(&public_tx.hash).await);
+        assert_eq!(mempool.stats().await.private_transactions, 1);
+
+        let listed = mempool.get_public_transactions(10).await;
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].hash, public_tx.hash);
+
+        // Still eligible for blocks produced by this node
+        let best = mempool.get_best_transactions(10, 1_000_000).await;
+        assert_eq!(best[0].hash, private_tx.hash);
+
+        // Resubmitting publicly must not leak it out of the private pool
+        assert!(mempool
+            .add_transaction(private_tx.clone(), TxClass::Standard)
+            .await
+            .is_err());
+        assert!(mempool.is_private(&private_tx.hash).await);
+    }
 }
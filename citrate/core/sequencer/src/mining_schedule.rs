@@ -0,0 +1,162 @@
+// citrate/core/sequencer/src/mining_schedule.rs
+
+// When block producers mine their next block
+// Blocks are mined on a fixed interval, when a transaction enters the
+// mempool (automine), or both. Devnets change the mode at runtime so
+// contract developers can control block production deterministically; a
+// zero interval with automine off leaves mining to explicit requests.
+
+use crate::mempool::Mempool;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tokio::time::{sleep, Duration};
+
+/// How often automine checks the mempool for new transactions
+const AUTOMINE_POLL: Duration = Duration::from_millis(50);
+
+/// Most blocks mined by one on-demand request
+pub const MAX_MINE_BLOCKS: u64 = 1_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MiningMode {
+    /// Seconds between blocks; 0 disables interval mining
+    pub block_interval: u64,
+    /// Mine a block as soon as the mempool holds a transaction
+    pub automine: bool,
+}
+
+/// Mining mode shared between a block producer and whatever controls it
+pub struct MiningSchedule {
+    mode: watch::Sender<MiningMode>,
+}
+
+impl MiningSchedule {
+    /// Interval mining every `block_interval` seconds, automine off
+    pub fn new(block_interval: u64) -> Self {
+        let (mode, _) = watch::channel(MiningMode {
+            block_interval,
+            automine: false,
+        });
+        Self { mode }
+    }
+
+    pub fn mode(&self) -> MiningMode {
+        *self.mode.borrow()
+    }
+
+    pub fn set_block_interval(&self, block_interval: u64) {
+        self.mode
+            .send_modify(|mode| mode.block_interval = block_interval);
+    }
+
+    pub fn set_automine(&self, automine: bool) {
+        self.mode.send_modify(|mode| mode.automine = automine);
+    }
+
+    /// Wait until the next block is due: a full interval has passed, or
+    /// automine is on and the mempool holds a transaction. A mode change
+    /// restarts the wait under the new mode.
+    pub async fn wait_for_block(&self, mempool: &Mempool) {
+        let mut changes = self.mode.subscribe();
+        loop {
+            let mode = *changes.borrow_and_update();
+            tokio::select! {
+                _ = interval_elapsed(mode.block_interval) => return,
+                _ = pending_transaction(mode.automine, mempool) => return,
+                _ = changes.changed() => {}
+            }
+        }
+    }
+}
+
+async fn interval_elapsed(block_interval: u64) {
+    if block_interval == 0 {
+        std::future::pending::<()>().await;
+    }
+    sleep(Duration::from_secs(block_interval)).await;
+}
+
+async fn pending_transaction(automine: bool, mempool: &Mempool) {
+    if !automine {
+        std::future::pending::<()>().await;
+    }
+    while mempool.stats().await.total_transactions == 0 {
+        sleep(AUTOMINE_POLL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mempool::{MempoolConfig, TxClass};
+    use citrate_consensus::types::{Hash, PublicKey, Signature, Transaction};
+    use std::sync::Arc;
+    use tokio::time::timeout;
+
+    fn mempool() -> Arc<Mempool> {
+        Arc::new(Mempool::new(MempoolConfig {
+            require_valid_signature: false,
+            ..Default::default()
+        }))
+    }
+
+    fn transaction() -> Transaction {
+        Transaction {
+            hash: Hash::new([7; 32]),
+            nonce: 0,
+            from: PublicKey::new([1; 32]),
+            to: Some(PublicKey::new([2; 32])),
+            value: 1,
+            gas_limit: 21_000,
+            gas_price: 2_000_000_000,
+            data: vec![],
+            signature: Signature::new([1; 64]),
+            tx_type: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interval_mining() {
+        let schedule = MiningSchedule::new(1);
+        let mempool = mempool();
+        let wait = |ms| timeout(Duration::from_millis(ms), schedule.wait_for_block(&mempool));
+        assert!(wait(500).await.is_err());
+        assert!(wait(1_500).await.is_ok());
+
+        // No interval and no automine: nothing is ever due
+        schedule.set_block_interval(0);
+        assert!(wait(1_500).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_automine_wakes_waiting_producer() {
+        let schedule = Arc::new(MiningSchedule::new(0));
+        let mempool = mempool();
+        mempool
+            .add_transaction(transaction(), TxClass::Standard)
+            .await
+            .unwrap();
+
+        let waiting = {
+            let schedule = schedule.clone();
+            let mempool = mempool.clone();
+            tokio::spawn(async move { schedule.wait_for_block(&mempool).await })
+        };
+        sleep(Duration::from_millis(200)).await;
+        assert!(!waiting.is_finished());
+
+        schedule.set_automine(true);
+        assert_eq!(
+            schedule.mode(),
+            MiningMode {
+                block_interval: 0,
+                automine: true
+            }
+        );
+        timeout(Duration::from_secs(1), waiting)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
@@ -0,0 +1,773 @@
+// citrate/core/sequencer/src/selection.rs
+
+//! Effective-fee transaction selection for block building.
+//!
+//! Candidates are ranked by their effective priority (fee per gas scaled by
+//! transaction class, or the AI priority for AI transaction types). Each sender
+//! contributes only its lowest pending nonce to the ranking at any time, so a
+//! high-fee transaction never jumps ahead of the transaction it depends on.
+//! Atomic bundles compete in the same ranking and are included all-or-nothing.
+//!
+//! When gas lanes are configured, each lane (standard, inference, model
+//! deployment) may only fill its share of the block gas limit. Transactions
+//! held back by their lane cap are reconsidered once every lane is full, if
+//! spillover is allowed, so unused lane capacity is not wasted.
+
+use crate::mempool::{GasLane, GasLaneConfig, MempoolTx, TxClass, TxPriority};
+use citrate_consensus::limits::{model_metadata_bytes, transaction_bytes};
+use citrate_consensus::{Hash, PublicKey, Transaction};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque};
+
+/// A group of transactions that must be included together or not at all.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AtomicBundle {
+    /// Keccak-256 over the member transaction hashes
+    pub id: Hash,
+    /// Member transactions, in execution order
+    pub transactions: Vec<Transaction>,
+    /// Unix timestamp after which the bundle is dropped (0 = never)
+    pub expires_at: u64,
+}
+
+impl AtomicBundle {
+    /// Create a bundle, validating that it is non-empty, free of duplicates and
+    /// that each sender's nonces are contiguous and increasing.
+    pub fn new(transactions: Vec<Transaction>, expires_at: u64) -> Result<Self, String> {
+        if transactions.is_empty() {
+            return Err("bundle contains no transactions".to_string());
+        }
+
+        let mut seen = HashSet::new();
+        let mut last_nonce: HashMap<PublicKey, u64> = HashMap::new();
+        for tx in &transactions {
+            if !seen.insert(tx.hash) {
+                return Err(format!("duplicate transaction {} in bundle", tx.hash));
+            }
+            if let Some(prev) = last_nonce.insert(tx.from, tx.nonce) {
+                if tx.nonce != prev + 1 {
+                    return Err(format!(
+                        "non-contiguous nonce {} after {} for sender {:?}",
+                        tx.nonce, prev, tx.from
+                    ));
+                }
+            }
+        }
+
+        let mut hasher = Keccak256::new();
+        for tx in &transactions {
+            hasher.update(tx.hash.as_bytes());
+        }
+
+        Ok(Self {
+            id: Hash::from_bytes(&hasher.finalize()),
+            transactions,
+            expires_at,
+        })
+    }
+
+    /// Whether the bundle has expired at `now` (unix seconds)
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at != 0 && now > self.expires_at
+    }
+
+    /// Total gas limit of all member transactions
+    pub fn total_gas(&self) -> u64 {
+        self.transactions.iter().map(|tx| tx.gas_limit).sum()
+    }
+}
+
+/// Limits applied while selecting transactions for one block
+#[derive(Debug, Clone, Copy)]
+pub struct SelectionLimits {
+    pub max_gas: u64,
+    pub max_size: usize,
+    pub max_count: usize,
+    /// Model metadata bytes the block may carry, counted within `max_size`
+    pub max_model_metadata: usize,
+    /// Base fee per gas the block will charge; cheaper transactions are skipped
+    pub base_fee: u64,
+    /// Per-lane shares of `max_gas`; `None` lets all classes share the block
+    pub lanes: Option<GasLaneConfig>,
+}
+
+/// Per-block statistics on selected vs skipped transactions
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelectionMetrics {
+    /// Transactions considered (mempool + bundle members)
+    pub candidates: usize,
+    /// Transactions selected for the block
+    pub selected: usize,
+    /// Skipped because the remaining block gas could not fit them
+    pub skipped_gas_limit: usize,
+    /// Skipped because the remaining block size could not fit them
+    pub skipped_size_limit: usize,
+    /// Skipped because an earlier nonce from the same sender was not included
+    pub skipped_nonce_gap: usize,
+    /// Skipped because the gas price is below the block base fee
+    pub skipped_underpriced: usize,
+    /// Left over once the transaction count limit was reached
+    pub skipped_count_limit: usize,
+    /// Members of bundles that could not be included atomically
+    pub skipped_bundle: usize,
+    /// Skipped because their gas lane had used up its share of the block
+    pub skipped_lane_limit: usize,
+    /// Skipped because the block's model metadata budget could not fit them
+    pub skipped_model_limit: usize,
+    pub bundles_included: usize,
+    pub bundles_rejected: usize,
+    /// Gas limit sum of the selected transactions
+    pub total_gas: u64,
+    /// Fees above the base fee collected by the proposer, assuming full gas use
+    pub total_priority_fees: u128,
+    /// Gas limit sum of the selected transactions, by lane
+    pub lane_gas: BTreeMap<GasLane, u64>,
+}
+
+impl SelectionMetrics {
+    /// Total number of skipped transactions
+    pub fn skipped(&self) -> usize {
+        self.candidates.saturating_sub(self.selected)
+    }
+
+    /// Add another block's metrics into this running total
+    pub fn accumulate(&mut self, other: &SelectionMetrics) {
+        self.candidates += other.candidates;
+        self.selected += other.selected;
+        self.skipped_gas_limit += other.skipped_gas_limit;
+        self.skipped_size_limit += other.skipped_size_limit;
+        self.skipped_nonce_gap += other.skipped_nonce_gap;
+        self.skipped_underpriced += other.skipped_underpriced;
+        self.skipped_count_limit += other.skipped_count_limit;
+        self.skipped_bundle += other.skipped_bundle;
+        self.skipped_lane_limit += other.skipped_lane_limit;
+        self.skipped_model_limit += other.skipped_model_limit;
+        self.bundles_included += other.bundles_included;
+        self.bundles_rejected += other.bundles_rejected;
+        self.total_gas += other.total_gas;
+        self.total_priority_fees += other.total_priority_fees;
+        for (lane, gas) in &other.lane_gas {
+            *self.lane_gas.entry(*lane).or_insert(0) += gas;
+        }
+    }
+}
+
+/// Result of a selection pass
+#[derive(Debug, Clone, Default)]
+pub struct SelectionOutcome {
+    pub transactions: Vec<Transaction>,
+    pub included_bundles: Vec<Hash>,
+    pub metrics: SelectionMetrics,
+}
+
+/// Ranking score for a transaction outside the mempool, computed the same way
+/// the mempool computes `TxPriority::score`.
+pub fn priority_score(tx: &Transaction, class: TxClass) -> u64 {
+    let ai_priority = match tx.tx_type {
+        Some(citrate_consensus::types::TransactionType::Standard) | None => 0,
+        _ => tx.priority(),
+    };
+    TxPriority::new_with_ai(tx.gas_price, class, 0, ai_priority).score()
+}
+
+struct Candidate {
+    tx: Transaction,
+    score: u64,
+    added_at: u64,
+    size: usize,
+    lane: GasLane,
+}
+
+#[derive(PartialEq, Eq)]
+enum EntryKind {
+    Sender(PublicKey),
+    Bundle(usize),
+}
+
+#[derive(PartialEq, Eq)]
+struct HeapEntry {
+    score: u64,
+    added_at: u64,
+    hash: Hash,
+    kind: EntryKind,
+}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Higher score first, then older, then lowest hash for determinism
+        self.score
+            .cmp(&other.score)
+            .then_with(|| other.added_at.cmp(&self.added_at))
+            .then_with(|| other.hash.as_bytes().cmp(self.hash.as_bytes()))
+    }
+}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Select transactions by effective fee with sender-nonce dependency resolution.
+///
+/// `bundle_classify` assigns a class to bundle members, which are not in the
+/// mempool and therefore carry no class of their own.
+///
+/// With `limits.lanes` set, a transaction that fits the block but not its
+/// lane's remaining share is deferred rather than skipped, so its sender's
+/// later nonces wait with it instead of being dropped for this block.
+pub fn select_transactions(
+    pending: Vec<MempoolTx>,
+    bundles: &[AtomicBundle],
+    limits: SelectionLimits,
+    bundle_classify: impl Fn(&Transaction) -> TxClass,
+) -> SelectionOutcome {
+    let mut metrics = SelectionMetrics {
+        candidates: pending.len() + bundles.iter().map(|b| b.transactions.len()).sum::<usize>(),
+        ..Default::default()
+    };
+
+    // Group mempool transactions into per-sender nonce-ordered queues
+    let mut queues: HashMap<PublicKey, VecDeque<Candidate>> = HashMap::new();
+    for mtx in pending {
+        let lane = mtx.lane();
+        queues.entry(mtx.tx.from).or_default().push_back(Candidate {
+            lane,
+            score: mtx.priority.score(),
+            added_at: mtx.added_at,
+            size: mtx.size,
+            tx: mtx.tx,
+        });
+    }
+    for queue in queues.values_mut() {
+        queue.make_contiguous().sort_by_key(|c| c.tx.nonce);
+    }
+
+    let mut heap = BinaryHeap::new();
+    for (sender, queue) in &queues {
+        if let Some(head) = queue.front() {
+            heap.push(HeapEntry {
+                score: head.score,
+                added_at: head.added_at,
+                hash: head.tx.hash,
+                kind: EntryKind::Sender(*sender),
+            });
+        }
+    }
+    for (idx, bundle) in bundles.iter().enumerate() {
+        // Gas-weighted average score of the members
+        let total_gas = bundle.total_gas().max(1) as u128;
+        let weighted: u128 = bundle
+            .transactions
+            .iter()
+            .map(|tx| priority_score(tx, bundle_classify(tx)) as u128 * tx.gas_limit as u128)
+            .sum();
+        heap.push(HeapEntry {
+            score: (weighted / total_gas) as u64,
+            added_at: 0,
+            hash: bundle.id,
+            kind: EntryKind::Bundle(idx),
+        });
+    }
+
+    let mut selected: Vec<Transaction> = Vec::new();
+    let mut selected_hashes: HashSet<Hash> = HashSet::new();
+    let mut included_bundles: Vec<Hash> = Vec::new();
+    let mut next_nonce: HashMap<PublicKey, u64> = HashMap::new();
+    let mut total_gas: u64 = 0;
+    let mut total_size: usize = 0;
+    let mut total_model_bytes: usize = 0;
+    let mut lane_gas: BTreeMap<GasLane, u64> = BTreeMap::new();
+
+    // Entries held back by their lane cap, retried without caps on spillover
+    let lanes = limits.lanes.filter(|lanes| lanes.is_enabled());
+    let mut lane_capped = lanes.is_some();
+    let mut deferred: Vec<HeapEntry> = Vec::new();
+    let lane_cap = |lane: GasLane| lanes.map_or(u64::MAX, |lanes| lanes.cap(lane, limits.max_gas));
+    let mut count_limited = false;
+
+    loop {
+        while let Some(entry) = heap.pop() {
+            if selected.len() >= limits.max_count {
+                count_limited = true;
+                break;
+            }
+
+            match entry.kind {
+                EntryKind::Sender(sender) => {
+                    let Some(queue) = queues.get_mut(&sender) else {
+                        continue;
+                    };
+
+                    // Drop entries already covered by an included bundle
+                    while let Some(head) = queue.front() {
+                        let stale = next_nonce.get(&sender).is_some_and(|&n| head.tx.nonce < n)
+                            || selected_hashes.contains(&head.tx.hash);
+                        if !stale {
+                            break;
+                        }
+                        queue.pop_front();
+                        metrics.skipped_nonce_gap += 1;
+                    }
+                    let Some(head) = queue.front() else {
+                        continue;
+                    };
+
+                    // The head may have changed since this entry was pushed
+                    if head.tx.hash != entry.hash {
+                        heap.push(HeapEntry {
+                            score: head.score,
+                            added_at: head.added_at,
+                            hash: head.tx.hash,
+                            kind: EntryKind::Sender(sender),
+                        });
+                        continue;
+                    }
+
+                    if let Some(&expected) = next_nonce.get(&sender) {
+                        if head.tx.nonce != expected {
+                            metrics.skipped_nonce_gap += queue.len();
+                            queue.clear();
+                            continue;
+                        }
+                    }
+
+                    // A skipped transaction blocks every later nonce from its sender
+                    let skip_reason = if head.tx.gas_price < limits.base_fee {
+                        Some(&mut metrics.skipped_underpriced)
+                    } else if total_gas + head.tx.gas_limit > limits.max_gas {
+                        Some(&mut metrics.skipped_gas_limit)
+                    } else if total_size + head.size > limits.max_size {
+                        Some(&mut metrics.skipped_size_limit)
+                    } else if total_model_bytes + model_metadata_bytes(&head.tx)
+                        > limits.max_model_metadata
+                    {
+                        Some(&mut metrics.skipped_model_limit)
+                    } else {
+                        None
+                    };
+                    if let Some(counter) = skip_reason {
+                        *counter += 1;
+                        metrics.skipped_nonce_gap += queue.len() - 1;
+                        queue.clear();
+                        continue;
+                    }
+
+                    let used = lane_gas.get(&head.lane).copied().unwrap_or(0);
+                    if lane_capped && used + head.tx.gas_limit > lane_cap(head.lane) {
+                        deferred.push(entry);
+                        continue;
+                    }
+
+                    let Some(candidate) = queue.pop_front() else {
+                        continue;
+                    };
+                    *lane_gas.entry(candidate.lane).or_insert(0) += candidate.tx.gas_limit;
+                    total_gas += candidate.tx.gas_limit;
+                    total_size += candidate.size;
+                    total_model_bytes += model_metadata_bytes(&candidate.tx);
+                    metrics.total_priority_fees += priority_fee(&candidate.tx, limits.base_fee);
+                    next_nonce.insert(sender, candidate.tx.nonce + 1);
+                    selected_hashes.insert(candidate.tx.hash);
+                    selected.push(candidate.tx);
+
+                    if let Some(head) = queue.front() {
+                        heap.push(HeapEntry {
+                            score: head.score,
+                            added_at: head.added_at,
+                            hash: head.tx.hash,
+                            kind: EntryKind::Sender(sender),
+                        });
+                    }
+                }
+                EntryKind::Bundle(idx) => {
+                    let bundle = &bundles[idx];
+                    let bundle_gas = bundle.total_gas();
+                    let bundle_size: usize = bundle.transactions.iter().map(estimate_tx_size).sum();
+                    let bundle_model_bytes: usize =
+                        bundle.transactions.iter().map(model_metadata_bytes).sum();
+
+                    let fits = selected.len() + bundle.transactions.len() <= limits.max_count
+                        && total_gas + bundle_gas <= limits.max_gas
+                        && total_size + bundle_size <= limits.max_size
+                        && total_model_bytes + bundle_model_bytes <= limits.max_model_metadata;
+                    let priced = bundle
+                        .transactions
+                        .iter()
+                        .all(|tx| tx.gas_price >= limits.base_fee);
+                    let fresh = bundle
+                        .transactions
+                        .iter()
+                        .all(|tx| !selected_hashes.contains(&tx.hash));
+
+                    // Each sender's first bundle nonce must continue its selected
+                    // chain, or start at its lowest pending mempool nonce
+                    let mut first_nonce: HashMap<PublicKey, u64> = HashMap::new();
+                    for tx in &bundle.transactions {
+                        first_nonce.entry(tx.from).or_insert(tx.nonce);
+                    }
+                    let nonces_ok =
+                        first_nonce
+                            .iter()
+                            .all(|(sender, &nonce)| match next_nonce.get(sender) {
+                                Some(&expected) => nonce == expected,
+                                None => queues
+                                    .get(sender)
+                                    .and_then(|q| q.front())
+                                    .is_none_or(|head| head.tx.nonce == nonce),
+                            });
+
+                    if !(fits && priced && fresh && nonces_ok) {
+                        metrics.bundles_rejected += 1;
+                        metrics.skipped_bundle += bundle.transactions.len();
+                        continue;
+                    }
+
+                    let mut bundle_lane_gas: BTreeMap<GasLane, u64> = BTreeMap::new();
+                    for tx in &bundle.transactions {
+                        *bundle_lane_gas
+                            .entry(GasLane::of(tx, bundle_classify(tx)))
+                            .or_insert(0) += tx.gas_limit;
+                    }
+                    let over_lane = bundle_lane_gas.iter().any(|(lane, gas)| {
+                        lane_gas.get(lane).copied().unwrap_or(0) + gas > lane_cap(*lane)
+                    });
+                    if lane_capped && over_lane {
+                        deferred.push(entry);
+                        continue;
+                    }
+
+                    for (lane, gas) in bundle_lane_gas {
+                        *lane_gas.entry(lane).or_insert(0) += gas;
+                    }
+                    for tx in &bundle.transactions {
+                        total_gas += tx.gas_limit;
+                        total_size += estimate_tx_size(tx);
+                        total_model_bytes += model_metadata_bytes(tx);
+                        metrics.total_priority_fees += priority_fee(tx, limits.base_fee);
+                        next_nonce.insert(tx.from, tx.nonce + 1);
+                        selected_hashes.insert(tx.hash);
+                        selected.push(tx.clone());
+                    }
+                    metrics.bundles_included += 1;
+                    included_bundles.push(bundle.id);
+                }
+            }
+        }
+
+        let spillover = lanes.is_some_and(|lanes| lanes.allow_spillover);
+        if !lane_capped || !spillover || count_limited || deferred.is_empty() {
+            break;
+        }
+        // Every lane is full: let deferred entries use the gas left over
+        lane_capped = false;
+        heap.extend(deferred.drain(..));
+    }
+
+    // Whatever is still deferred was held back by its lane cap
+    if !count_limited {
+        for entry in deferred {
+            match entry.kind {
+                EntryKind::Sender(sender) => {
+                    let pending = queues.get(&sender).map_or(0, |q| q.len());
+                    if pending > 0 {
+                        metrics.skipped_lane_limit += 1;
+                        metrics.skipped_nonce_gap += pending - 1;
+                    }
+                }
+                EntryKind::Bundle(idx) => {
+                    metrics.bundles_rejected += 1;
+                    metrics.skipped_bundle += bundles[idx].transactions.len();
+                }
+            }
+        }
+    }
+
+    metrics.selected = selected.len();
+    metrics.total_gas = total_gas;
+    metrics.lane_gas = lane_gas;
+    let accounted = metrics.selected
+        + metrics.skipped_gas_limit
+        + metrics.skipped_size_limit
+        + metrics.skipped_nonce_gap
+        + metrics.skipped_underpriced
+        + metrics.skipped_bundle
+        + metrics.skipped_lane_limit
+        + metrics.skipped_model_limit;
+    metrics.skipped_count_limit = metrics.candidates.saturating_sub(accounted);
+
+    SelectionOutcome {
+        transactions: selected,
+        included_bundles,
+        metrics,
+    }
+}
+
+/// Fee paid above the base fee, assuming the full gas limit is consumed
+fn priority_fee(tx: &Transaction, base_fee: u64) -> u128 {
+    tx.gas_price.saturating_sub(base_fee) as u128 * tx.gas_limit as u128
+}
+
+/// Approximate encoded size, matching the mempool's estimate
+pub fn estimate_tx_size(tx: &Transaction) -> usize {
+    transaction_bytes(tx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use citrate_consensus::Signature;
+
+    fn tx(from: u8, nonce: u64, gas_price: u64, hash: u8) -> Transaction {
+        Transaction {
+            hash: Hash::new([hash; 32]),
+            nonce,
+            from: PublicKey::new([from; 32]),
+            to: Some(PublicKey::new([0xEE; 32])),
+            value: 1,
+            gas_limit: 21_000,
+            gas_price,
+            data: vec![],
+            signature: Signature::new([1; 64]),
+            tx_type: None,
+        }
+    }
+
+    fn pending(tx: Transaction) -> MempoolTx {
+        let priority = TxPriority::new(tx.gas_price, TxClass::Standard, 0);
+        MempoolTx {
+            size: estimate_tx_size(&tx),
+            tx,
+            class: TxClass::Standard,
+            priority,
+            added_at: 0,
+            private: false,
+        }
+    }
+
+    fn limits() -> SelectionLimits {
+        SelectionLimits {
+            max_gas: 30_000_000,
+            max_size: 1_000_000,
+            max_count: 100,
+            max_model_metadata: 1_000_000,
+            base_fee: 1_000_000_000,
+            lanes: None,
+        }
+    }
+
+    #[test]
+    fn test_orders_by_fee_and_respects_nonces() {
+        let a0 = tx(1, 0, 2_000_000_000, 0x10);
+        let a1 = tx(1, 1, 90_000_000_000, 0x11);
+        let b0 = tx(2, 0, 5_000_000_000, 0x20);
+
+        let out = select_transactions(
+            vec![
+                pending(a1.clone()),
+                pending(a0.clone()),
+                pending(b0.clone()),
+            ],
+            &[],
+            limits(),
+            |_| TxClass::Standard,
+        );
+
+        let hashes: Vec<Hash> = out.transactions.iter().map(|t| t.hash).collect();
+        assert_eq!(hashes, vec![b0.hash, a0.hash, a1.hash]);
+        assert_eq!(out.metrics.selected, 3);
+        assert_eq!(out.metrics.skipped(), 0);
+    }
+
+    #[test]
+    fn test_gas_skip_blocks_dependent_nonces_but_not_other_senders() {
+        let mut big = tx(1, 0, 9_000_000_000, 0x10);
+        big.gas_limit = 50_000;
+        let follow = tx(1, 1, 9_000_000_000, 0x11);
+        let other = tx(2, 0, 2_000_000_000, 0x20);
+
+        let out = select_transactions(
+            vec![pending(big), pending(follow), pending(other.clone())],
+            &[],
+            SelectionLimits {
+                max_gas: 30_000,
+                ..limits()
+            },
+            |_| TxClass::Standard,
+        );
+
+        assert_eq!(out.transactions.len(), 1);
+        assert_eq!(out.transactions[0].hash, other.hash);
+        assert_eq!(out.metrics.skipped_gas_limit, 1);
+        assert_eq!(out.metrics.skipped_nonce_gap, 1);
+    }
+
+    #[test]
+    fn test_underpriced_transactions_are_skipped() {
+        let cheap = tx(1, 0, 500_000_000, 0x10);
+        let out = select_transactions(vec![pending(cheap)], &[], limits(), |_| TxClass::Standard);
+        assert!(out.transactions.is_empty());
+        assert_eq!(out.metrics.skipped_underpriced, 1);
+    }
+
+    #[test]
+    fn test_model_metadata_budget_skips_model_transactions_only() {
+        let mut deploy = tx(1, 0, 9_000_000_000, 0x10);
+        deploy.data = vec![0x01, 0x00, 0x00, 0x00];
+        deploy.data.resize(3_000, 0xab);
+        let follow = tx(1, 1, 9_000_000_000, 0x11);
+        let transfer = tx(2, 0, 2_000_000_000, 0x20);
+
+        let out = select_transactions(
+            vec![pending(deploy), pending(follow), pending(transfer.clone())],
+            &[],
+            SelectionLimits {
+                max_model_metadata: 2_000,
+                ..limits()
+            },
+            |_| TxClass::Standard,
+        );
+
+        assert_eq!(out.transactions.len(), 1);
+        assert_eq!(out.transactions[0].hash, transfer.hash);
+        assert_eq!(out.metrics.skipped_model_limit, 1);
+        assert_eq!(out.metrics.skipped_nonce_gap, 1);
+    }
+
+    #[test]
+    fn test_bundle_is_all_or_nothing() {
+        let bundle = AtomicBundle::new(
+            vec![
+                tx(3, 0, 50_000_000_000, 0x30),
+                tx(4, 0, 50_000_000_000, 0x40),
+            ],
+            0,
+        )
+        .unwrap();
+        let single = tx(5, 0, 2_000_000_000, 0x50);
+
+        // Room for only one transaction: the bundle must not be split
+        let out = select_transactions(
+            vec![pending(single.clone())],
+            std::slice::from_ref(&bundle),
+            SelectionLimits {
+                max_gas: 21_000,
+                ..limits()
+            },
+            |_| TxClass::Standard,
+        );
+        assert_eq!(out.transactions.len(), 1);
+        assert_eq!(out.transactions[0].hash, single.hash);
+        assert_eq!(out.metrics.bundles_rejected, 1);
+        assert!(out.included_bundles.is_empty());
+
+        // With enough room the higher-paying bundle goes first, in order
+        let out = select_transactions(
+            vec![pending(single.clone())],
+            std::slice::from_ref(&bundle),
+            limits(),
+            |_| TxClass::Standard,
+        );
+        let hashes: Vec<Hash> = out.transactions.iter().map(|t| t.hash).collect();
+        assert_eq!(
+            hashes,
+            vec![Hash::new([0x30; 32]), Hash::new([0x40; 32]), single.hash]
+        );
+        assert_eq!(out.included_bundles, vec![bundle.id]);
+    }
+
+    #[test]
+    fn test_bundle_supersedes_mempool_nonce() {
+        let mempool_tx = tx(6, 0, 2_000_000_000, 0x60);
+        let mempool_next = tx(6, 1, 2_000_000_000, 0x61);
+        let bundle = AtomicBundle::new(vec![tx(6, 0, 80_000_000_000, 0x6A)], 0).unwrap();
+
+        let out = select_transactions(
+            vec![pending(mempool_tx), pending(mempool_next.clone())],
+            &[bundle],
+            limits(),
+            |_| TxClass::Standard,
+        );
+        let hashes: Vec<Hash> = out.transactions.iter().map(|t| t.hash).collect();
+        assert_eq!(hashes, vec![Hash::new([0x6A; 32]), mempool_next.hash]);
+        assert_eq!(out.metrics.skipped_nonce_gap, 1);
+    }
+
+    #[test]
+    fn test_gas_lanes_cap_classes_and_spill_over() {
+        let standard: Vec<Transaction> = (1..=4).map(|i| tx(i, 0, 2_000_000_000, i)).collect();
+        let inference = tx(9, 0, 50_000_000_000, 0x90);
+        let mut candidates: Vec<MempoolTx> = standard.iter().cloned().map(pending).collect();
+        let mut inference_pending = pending(inference.clone());
+        inference_pending.class = TxClass::Inference;
+        inference_pending.priority = TxPriority::new(inference.gas_price, TxClass::Inference, 0);
+        candidates.push(inference_pending);
+
+        // 70% of 100k fits three standard transfers, 20% fits no inference tx
+        let mut lanes = GasLaneConfig {
+            allow_spillover: false,
+            ..GasLaneConfig::default()
+        };
+        let capped = SelectionLimits {
+            max_gas: 100_000,
+            lanes: Some(lanes),
+            ..limits()
+        };
+        let out = select_transactions(candidates.clone(), &[], capped, |_| TxClass::Standard);
+        assert_eq!(out.transactions.len(), 3);
+        assert!(out.transactions.iter().all(|t| t.hash != inference.hash));
+        assert_eq!(out.metrics.skipped_lane_limit, 2);
+        assert_eq!(out.metrics.lane_gas.get(&GasLane::Standard), Some(&63_000));
+        assert_eq!(out.metrics.lane_gas.get(&GasLane::Inference), None);
+
+        // With spillover the best deferred transaction takes the leftover gas
+        lanes.allow_spillover = true;
+        let out = select_transactions(
+            candidates,
+            &[],
+            SelectionLimits {
+                lanes: Some(lanes),
+                ..capped
+            },
+            |_| TxClass::Standard,
+        );
+        assert_eq!(out.transactions.len(), 4);
+        assert_eq!(out.transactions[3].hash, inference.hash);
+        assert_eq!(out.metrics.skipped_lane_limit, 0);
+        assert_eq!(out.metrics.skipped_gas_limit, 1);
+        assert_eq!(out.metrics.lane_gas.get(&GasLane::Inference), Some(&21_000));
+    }
+
+    #[test]
+    fn test_gas_lane_prefers_ai_transaction_type() {
+        let mut deploy = tx(1, 0, 1_000_000_000, 0x10);
+        deploy.tx_type = Some(citrate_consensus::types::TransactionType::ModelDeploy);
+        assert_eq!(GasLane::of(&deploy, TxClass::Compute), GasLane::ModelDeploy);
+
+        let mut inference = tx(1, 0, 1_000_000_000, 0x11);
+        inference.tx_type = Some(citrate_consensus::types::TransactionType::InferenceRequest);
+        assert_eq!(
+            GasLane::of(&inference, TxClass::Compute),
+            GasLane::Inference
+        );
+
+        let plain = tx(1, 0, 1_000_000_000, 0x12);
+        assert_eq!(GasLane::of(&plain, TxClass::Storage), GasLane::Standard);
+        assert_eq!(
+            GasLane::of(&plain, TxClass::ModelUpdate),
+            GasLane::ModelDeploy
+        );
+    }
+
+    #[test]
+    fn test_bundle_rejects_non_contiguous_nonces() {
+        let result = AtomicBundle::new(
+            vec![tx(7, 0, 1_000_000_000, 0x70), tx(7, 2, 1_000_000_000, 0x72)],
+            0,
+        );
+        assert!(result.is_err());
+        assert!(AtomicBundle::new(vec![], 0).is_err());
+    }
+}
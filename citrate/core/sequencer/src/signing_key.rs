@@ -0,0 +1,271 @@
+// citrate/core/sequencer/src/signing_key.rs
+
+//! Block signing key kept apart from the reward address
+//!
+//! The producer signs blocks with a dedicated key stored in its own file,
+//! so a leaked signing key exposes neither rewards nor stake. Rotation is
+//! two-step: `prepare_rotation` builds the on-chain key-change record,
+//! signed by the retiring key, and `complete_rotation` persists and swaps
+//! in the new key once the record has been accepted by the mempool.
+
+use citrate_consensus::crypto::{generate_keypair, sign_message, sign_transaction};
+use citrate_consensus::{Hash, PublicKey, Signature, Transaction};
+use citrate_execution::precompiles::validator_keys::{
+    rotate_call_data, rotation_message, VALIDATOR_KEYS_PRECOMPILE,
+};
+use citrate_execution::types::Address;
+use ed25519_dalek::SigningKey;
+use sha3::{Digest, Keccak256};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use thiserror::Error;
+
+/// Gas limit of a key-change record; the retiring key pays for it
+pub const ROTATION_GAS_LIMIT: u64 = 100_000;
+
+#[derive(Error, Debug)]
+pub enum SigningKeyError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid signing key file {0}")]
+    InvalidKeyFile(PathBuf),
+
+    #[error("Signing key changed since the rotation was prepared")]
+    StaleRotation,
+}
+
+/// A prepared key change: the record to submit and the key it installs
+pub struct KeyRotation {
+    pub retiring: PublicKey,
+    pub new_key: PublicKey,
+    /// Call to the validator key registry, signed by the retiring key
+    pub transaction: Transaction,
+    replacement: SigningKey,
+}
+
+/// Hot-swappable block signing key, optionally backed by a file
+pub struct BlockSigningKey {
+    path: Option<PathBuf>,
+    key: RwLock<SigningKey>,
+}
+
+impl BlockSigningKey {
+    /// Load the hex-encoded key at `path`, generating and saving one if the
+    /// file does not exist
+    pub fn load_or_create(path: impl Into<PathBuf>) -> Result<Self, SigningKeyError> {
+        let path = path.into();
+        let key = if path.exists() {
+            let hex_key = std::fs::read_to_string(&path)?;
+            let bytes: [u8; 32] = hex::decode(hex_key.trim())
+                .ok()
+                .and_then(|b| b.try_into().ok())
+                .ok_or_else(|| SigningKeyError::InvalidKeyFile(path.clone()))?;
+            SigningKey::from_bytes(&bytes)
+        } else {
+            let key = generate_keypair();
+            write_key(&path, &key)?;
+            key
+        };
+        Ok(Self {
+            path: Some(path),
+            key: RwLock::new(key),
+        })
+    }
+
+    /// A key that lives only in memory and is lost on restart
+    pub fn ephemeral() -> Self {
+        Self {
+            path: None,
+            key: RwLock::new(generate_keypair()),
+        }
+    }
+
+    /// File the key is stored in, if any
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Public key blocks are proposed under
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::new(self.read().verifying_key().to_bytes())
+    }
+
+    /// Sign a message, usually a block hash, with the current key
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        sign_message(&self.read(), message)
+    }
+
+    /// Generate a replacement key and the registry call retiring the current
+    /// one. Nothing changes until `complete_rotation` is called.
+    pub fn prepare_rotation(&self, nonce: u64, gas_price: u64, chain_id: u64) -> KeyRotation {
+        let current = self.read().clone();
+        let retiring = PublicKey::new(current.verifying_key().to_bytes());
+        let replacement = generate_keypair();
+        let new_key = PublicKey::new(replacement.verifying_key().to_bytes());
+
+        let proof = sign_message(
+            &replacement,
+            &rotation_message(&Address::from_public_key(&retiring)),
+        );
+        let mut registry = [0u8; 32];
+        registry[..20].copy_from_slice(&VALIDATOR_KEYS_PRECOMPILE.0);
+
+        let mut transaction = Transaction {
+            hash: Hash::default(),
+            nonce,
+            from: retiring,
+            to: Some(PublicKey::new(registry)),
+            value: 0,
+            gas_limit: ROTATION_GAS_LIMIT,
+            gas_price,
+            data: rotate_call_data(&new_key, &proof),
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        };
+        transaction.hash = rotation_hash(&transaction, chain_id);
+        // Only fails on serialization, which canonical encoding cannot hit
+        let _ = sign_transaction(&mut transaction, &current);
+
+        KeyRotation {
+            retiring,
+            new_key,
+            transaction,
+            replacement,
+        }
+    }
+
+    /// Persist and switch to the key installed by `rotation`. The retired
+    /// key is kept next to the key file so an unconfirmed record can be
+    /// resubmitted.
+    pub fn complete_rotation(&self, rotation: KeyRotation) -> Result<PublicKey, SigningKeyError> {
+        let mut key = self.key.write().unwrap_or_else(|e| e.into_inner());
+        if PublicKey::new(key.verifying_key().to_bytes()) != rotation.retiring {
+            return Err(SigningKeyError::StaleRotation);
+        }
+        if let Some(path) = &self.path {
+            let mut retired = path.clone().into_os_string();
+            retired.push(format!(
+                ".retired-{}",
+                hex::encode(&rotation.retiring.as_bytes()[..4])
+            ));
+            write_key(Path::new(&retired), &key)?;
+            write_key(path, &rotation.replacement)?;
+        }
+        *key = rotation.replacement;
+        Ok(rotation.new_key)
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, SigningKey> {
+        self.key.read().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Write a key atomically, readable only by the owner
+fn write_key(path: &Path, key: &SigningKey) -> Result<(), SigningKeyError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut tmp = path.to_path_buf().into_os_string();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, hex::encode(key.to_bytes()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600))?;
+    }
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Keccak-256 over the signed fields and chain ID, as the wallet hashes
+fn rotation_hash(tx: &Transaction, chain_id: u64) -> Hash {
+    let mut hasher = Keccak256::new();
+    hasher.update(tx.nonce.to_le_bytes());
+    hasher.update(tx.gas_price.to_le_bytes());
+    hasher.update(tx.gas_limit.to_le_bytes());
+    if let Some(to) = &tx.to {
+        hasher.update(to.as_bytes());
+    }
+    hasher.update(tx.value.to_le_bytes());
+    hasher.update(&tx.data);
+    hasher.update(chain_id.to_le_bytes());
+    Hash::new(hasher.finalize().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use citrate_consensus::crypto::{verify_signature, verify_transaction};
+
+    fn temp_key_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "citrate-signing-key-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("signing.key")
+    }
+
+    #[test]
+    fn test_load_or_create_persists_key() {
+        let path = temp_key_path("persist");
+        let created = BlockSigningKey::load_or_create(&path).unwrap();
+        let loaded = BlockSigningKey::load_or_create(&path).unwrap();
+        assert_eq!(created.public_key(), loaded.public_key());
+
+        let signature = loaded.sign(b"block");
+        assert!(verify_signature(
+            &created.public_key(),
+            b"block",
+            &signature
+        ));
+
+        std::fs::write(&path, "not hex").unwrap();
+        assert!(matches!(
+            BlockSigningKey::load_or_create(&path),
+            Err(SigningKeyError::InvalidKeyFile(_))
+        ));
+    }
+
+    #[test]
+    fn test_rotation_swaps_key_and_keeps_retired_copy() {
+        let path = temp_key_path("rotate");
+        let key = BlockSigningKey::load_or_create(&path).unwrap();
+        let retiring = key.public_key();
+
+        let rotation = key.prepare_rotation(3, 1_000_000_000, 1337);
+        assert_eq!(rotation.retiring, retiring);
+        assert_eq!(rotation.transaction.from, retiring);
+        assert_eq!(rotation.transaction.nonce, 3);
+        assert!(verify_transaction(&rotation.transaction).unwrap());
+        // Preparing alone leaves the active key untouched
+        assert_eq!(key.public_key(), retiring);
+
+        let stale = key.prepare_rotation(3, 1_000_000_000, 1337);
+        let new_key = key.complete_rotation(rotation).unwrap();
+        assert_eq!(key.public_key(), new_key);
+        assert_eq!(
+            BlockSigningKey::load_or_create(&path).unwrap().public_key(),
+            new_key
+        );
+
+        let mut retired = path.clone().into_os_string();
+        retired.push(format!(
+            ".retired-{}",
+            hex::encode(&retiring.as_bytes()[..4])
+        ));
+        assert_eq!(
+            BlockSigningKey::load_or_create(PathBuf::from(retired))
+                .unwrap()
+                .public_key(),
+            retiring
+        );
+
+        assert!(matches!(
+            key.complete_rotation(stale),
+            Err(SigningKeyError::StaleRotation)
+        ));
+    }
+}
@@ -186,6 +186,16 @@ impl<S: StateProvider> TxValidator<S> {
 
     /// Validate a transaction
     pub async fn validate(&self, tx: &Transaction) -> Result<(), ValidationError> {
+        self.validate_with(tx, false).await
+    }
+
+    /// Validate a transaction, skipping the signature check when it has
+    /// already passed batch verification
+    async fn validate_with(
+        &self,
+        tx: &Transaction,
+        signature_verified: bool,
+    ) -> Result<(), ValidationError> {
         // Check blacklist
         if self.is_blacklisted(&tx.from).await {
             return Err(ValidationError::BlacklistedAddress(tx.from));
@@ -198,7 +208,7 @@ impl<S: StateProvider> TxValidator<S> {
         self.validate_basic(tx)?;
 
         // Signature validation
-        if self.rules.verify_signatures {
+        if self.rules.verify_signatures && !signature_verified {
             self.validate_signature(tx)?;
         }
 
@@ -334,15 +344,29 @@ impl<S: StateProvider> TxValidator<S> {
         Ok(())
     }
 
+    /// Batch-verify signatures. Returns, per transaction, whether its
+    /// signature is known to be valid; the others still need a single check
+    /// to report their error.
+    pub fn verify_signatures(&self, transactions: &[Transaction]) -> Vec<bool> {
+        if !self.rules.verify_signatures {
+            return vec![false; transactions.len()];
+        }
+        citrate_consensus::crypto::verify_transactions(transactions)
+            .into_iter()
+            .map(|result| matches!(result, Ok(true)))
+            .collect()
+    }
+
     /// Batch validate multiple transactions
     pub async fn validate_batch(
         &self,
         transactions: &[Transaction],
     ) -> Vec<Result<(), ValidationError>> {
+        let verified = self.verify_signatures(transactions);
         let mut results = Vec::new();
 
-        for tx in transactions {
-            results.push(self.validate(tx).await);
+        for (tx, signature_verified) in transactions.iter().zip(verified) {
+            results.push(self.validate_with(tx, signature_verified).await);
         }
 
         results
@@ -374,14 +398,18 @@ impl<S: StateProvider> ValidationPipeline<S> {
         let mut valid = Vec::new();
         let mut invalid = Vec::new();
 
+        // Signatures dominate validation cost, so check them as one batch
+        let verified = self.validator.verify_signatures(&transactions);
+
         if self.parallel_validation {
             // Parallel validation for independent checks
             let futures: Vec<_> = transactions
                 .into_iter()
-                .map(|tx| {
+                .zip(verified)
+                .map(|(tx, signature_verified)| {
                     let validator = self.validator.clone();
                     async move {
-                        match validator.validate(&tx).await {
+                        match validator.validate_with(&tx, signature_verified).await {
                             Ok(()) => (Some(tx), None),
                             Err(e) => (None, Some((tx, e))),
                         }
@@ -401,8 +429,8 @@ impl<S: StateProvider> ValidationPipeline<S> {
             }
         } else {
             // Sequential validation
-            for tx in transactions {
-                match self.validator.validate(&tx).await {
+            for (tx, signature_verified) in transactions.into_iter().zip(verified) {
+                match self.validator.validate_with(&tx, signature_verified).await {
                     Ok(()) => valid.push(tx),
                     Err(e) => invalid.push((tx, e)),
                 }
@@ -598,4 +626,31 @@ mod tests {
         let tx3 = create_test_tx(2, 2_000_000_000, 1000);
         assert!(validator.validate(&tx3).await.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_pipeline_batch_verifies_signatures() {
+        let rules = ValidationRules {
+            check_balance: false,
+            check_nonce: false,
+            ..Default::default()
+        };
+        let state_provider = Arc::new(MockStateProvider::new());
+        let validator = Arc::new(TxValidator::new(rules, state_provider));
+
+        let mut txs: Vec<_> = (0..4)
+            .map(|nonce| {
+                let mut tx = create_test_tx(nonce, 2_000_000_000, 1000);
+                let key = citrate_consensus::crypto::generate_keypair();
+                citrate_consensus::crypto::sign_transaction(&mut tx, &key).unwrap();
+                tx
+            })
+            .collect();
+        txs[1].value += 1;
+
+        let (valid, invalid) = ValidationPipeline::new(validator).process(txs).await;
+        assert_eq!(valid.len(), 3);
+        assert_eq!(invalid.len(), 1);
+        assert_eq!(invalid[0].0.nonce, 1);
+        assert!(matches!(invalid[0].1, ValidationError::InvalidSignature));
+    }
 }
@@ -25,6 +25,7 @@ fn make_block_header(parent: Hash, height: u64) -> BlockHeader {
         base_fee_per_gas: 0,
         gas_used: 0,
         gas_limit: 30_000_000,
+        epoch_commitment: Hash::default(),
     }
 }
 
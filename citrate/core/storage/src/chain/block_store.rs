@@ -1,70 +1,189 @@
 // citrate/core/storage/src/chain/block_store.rs
 
+use super::dag_stats::{DagBlockStats, DagStatsStore};
+use super::log_index::LogIndex;
 use crate::db::{column_families::*, RocksDB};
 use anyhow::Result;
 use citrate_consensus::types::{Block, BlockHeader, Hash};
-use std::collections::HashSet;
+use parking_lot::Mutex;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tracing::{debug, info};
 
 /// Block storage manager
 pub struct BlockStore {
     db: Arc<RocksDB>,
+    dag_stats: Arc<DagStatsStore>,
+    logs: Arc<LogIndex>,
+    /// Current tips, loaded on first insert and kept up to date afterwards
+    tips: Mutex<Option<HashSet<Hash>>>,
 }
 
 impl BlockStore {
     pub fn new(db: Arc<RocksDB>) -> Self {
-        Self { db }
+        Self {
+            dag_stats: Arc::new(DagStatsStore::new(db.clone())),
+            logs: Arc::new(LogIndex::new(db.clone())),
+            db,
+            tips: Mutex::new(None),
+        }
+    }
+
+    /// Per-block DAG statistics recorded as blocks are stored
+    pub fn dag_stats(&self) -> Arc<DagStatsStore> {
+        self.dag_stats.clone()
+    }
+
+    /// Contract log index, filled in as receipts are stored
+    pub fn logs(&self) -> Arc<LogIndex> {
+        self.logs.clone()
     }
 
     /// Store a complete block
     pub fn put_block(&self, block: &Block) -> Result<()> {
-        let hash = block.hash();
-        let block_bytes = bincode::serialize(block)?;
+        self.put_blocks(std::slice::from_ref(block))
+    }
+
+    /// Store several blocks with a single write batch. Blocks must be
+    /// ordered parents-first; either all of them are written or none are.
+    pub fn put_blocks(&self, blocks: &[Block]) -> Result<()> {
+        if blocks.is_empty() {
+            return Ok(());
+        }
 
         let mut batch = self.db.batch();
+        let mut tips = self.tips.lock();
+        let mut tip_set = tips.clone();
+        // Headers and child lists written earlier in this batch are not
+        // visible through the db until it is committed
+        let mut pending_headers: HashMap<Hash, &BlockHeader> = HashMap::new();
+        let mut pending_children: HashMap<Hash, Vec<Hash>> = HashMap::new();
+
+        for block in blocks {
+            let hash = block.hash();
+            let block_bytes = bincode::serialize(block)?;
+            let is_new = !pending_headers.contains_key(&hash) && !self.has_block(&hash)?;
+
+            // Store full block
+            self.db
+                .batch_put_cf(&mut batch, CF_BLOCKS, hash.as_bytes(), &block_bytes)?;
+
+            // Store header separately for quick access
+            let header_bytes = bincode::serialize(&block.header)?;
+            self.db
+                .batch_put_cf(&mut batch, CF_HEADERS, hash.as_bytes(), &header_bytes)?;
+
+            // Store height -> hash mapping
+            let height_key = height_to_key(block.header.height);
+            self.db
+                .batch_put_cf(&mut batch, CF_METADATA, &height_key, hash.as_bytes())?;
+
+            // Collect parent -> children mappings for DAG
+            let parents = block.parents();
+            for parent in &parents {
+                let children = match pending_children.entry(*parent) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => entry.insert(self.get_children(parent)?),
+                };
+                if !children.contains(&hash) {
+                    children.push(hash);
+                }
+            }
+
+            // Store blue set information
+            if block.header.blue_score > 0 {
+                let blue_score_key = blue_score_key(block.header.blue_score);
+                self.db
+                    .batch_put_cf(&mut batch, CF_BLUE_SET, &blue_score_key, hash.as_bytes())?;
+            }
+
+            if is_new {
+                // Record DAG shape statistics and advance the tip set
+                // atomically with the block write
+                if tip_set.is_none() {
+                    tip_set = Some(self.get_tips()?.into_iter().collect());
+                }
+                let current_tips = tip_set.get_or_insert_with(HashSet::new);
+                let stats =
+                    self.compute_dag_stats(block, &parents, current_tips, &pending_headers)?;
+                self.dag_stats.batch_put(&mut batch, &stats)?;
+
+                current_tips.retain(|tip| !parents.contains(tip));
+                current_tips.insert(hash);
+            }
+
+            pending_headers.insert(hash, &block.header);
+        }
 
-        // Store full block
-        self.db
-            .batch_put_cf(&mut batch, CF_BLOCKS, hash.as_bytes(), &block_bytes)?;
-
-        // Store header separately for quick access
-        let header_bytes = bincode::serialize(&block.header)?;
-        self.db
-            .batch_put_cf(&mut batch, CF_HEADERS, hash.as_bytes(), &header_bytes)?;
-
-        // Store height -> hash mapping
-        let height_key = height_to_key(block.header.height);
-        self.db
-            .batch_put_cf(&mut batch, CF_METADATA, &height_key, hash.as_bytes())?;
-
-        // Store parent -> children mappings for DAG
-        for parent in block.parents() {
-            let parent_children_key = parent_children_key(&parent);
-            let mut children = self.get_children(&parent)?;
-            children.push(hash);
-            let children_bytes = bincode::serialize(&children)?;
+        for (parent, children) in &pending_children {
+            let children_bytes = bincode::serialize(children)?;
             self.db.batch_put_cf(
                 &mut batch,
                 CF_DAG_RELATIONS,
-                &parent_children_key,
+                &parent_children_key(parent),
                 &children_bytes,
             )?;
         }
 
-        // Store blue set information
-        if block.header.blue_score > 0 {
-            let blue_score_key = blue_score_key(block.header.blue_score);
-            self.db
-                .batch_put_cf(&mut batch, CF_BLUE_SET, &blue_score_key, hash.as_bytes())?;
-        }
-
         self.db.write_batch(batch)?;
-
-        debug!("Stored block {} at height {}", hash, block.header.height);
+        *tips = tip_set;
+
+        if let (Some(first), Some(last)) = (blocks.first(), blocks.last()) {
+            debug!(
+                "Stored {} block(s) at heights {}-{}",
+                blocks.len(),
+                first.header.height,
+                last.header.height
+            );
+        }
         Ok(())
     }
 
+    fn compute_dag_stats(
+        &self,
+        block: &Block,
+        parents: &[Hash],
+        tips: &HashSet<Hash>,
+        pending_headers: &HashMap<Hash, &BlockHeader>,
+    ) -> Result<DagBlockStats> {
+        let anticone_size = tips.iter().filter(|tip| !parents.contains(tip)).count() as u32;
+        let merge_parents = block.header.merge_parent_hashes.len() as u32;
+
+        // Every blue merge parent raises the blue score by at least one over
+        // the selected parent; parents beyond that gain are counted as red
+        let red_merge_parents = if block.is_genesis() {
+            0
+        } else {
+            let selected_parent = block.selected_parent();
+            let parent_blue_score = match pending_headers.get(&selected_parent) {
+                Some(parent) => Some(parent.blue_score),
+                None => self.get_header(&selected_parent)?.map(|p| p.blue_score),
+            };
+            match parent_blue_score {
+                Some(parent_blue_score) => {
+                    let merged_blue = block
+                        .header
+                        .blue_score
+                        .saturating_sub(parent_blue_score + 1);
+                    merge_parents.saturating_sub(merged_blue.min(u32::MAX as u64) as u32)
+                }
+                None => 0,
+            }
+        };
+
+        Ok(DagBlockStats {
+            hash: block.hash(),
+            height: block.header.height,
+            timestamp: block.header.timestamp,
+            blue_score: block.header.blue_score,
+            tips_count: anticone_size + 1,
+            merge_parents,
+            anticone_size,
+            red_merge_parents,
+        })
+    }
+
     /// Get a block by hash
     pub fn get_block(&self, hash: &Hash) -> Result<Option<Block>> {
         match self.db.get_cf(CF_BLOCKS, hash.as_bytes())? {
@@ -204,7 +323,15 @@ impl BlockStore {
             self.db
                 .batch_delete_cf(&mut batch, CF_BLUE_SET, &blue_score_key)?;
 
+            self.dag_stats
+                .batch_delete(&mut batch, block.header.timestamp, hash)?;
+            self.logs
+                .batch_delete_block(&mut batch, block.header.height, hash)?;
+
             self.db.write_batch(batch)?;
+
+            // Parents may become tips again; reload lazily on the next insert
+            *self.tips.lock() = None;
             info!("Deleted block {}", hash);
         }
 
@@ -266,6 +393,7 @@ mod tests {
                 base_fee_per_gas: 0,
                 gas_used: 0,
                 gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
             },
             state_root: Hash::default(),
             tx_root: Hash::default(),
@@ -310,4 +438,75 @@ mod tests {
         assert_eq!(children.len(), 1);
         assert_eq!(children[0], block2.hash());
     }
+
+    #[test]
+    fn test_dag_stats_recorded_for_parallel_blocks() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Arc::new(RocksDB::open(temp_dir.path()).unwrap());
+        let store = BlockStore::new(db);
+
+        let genesis = create_test_block(0, Hash::default());
+        store.put_block(&genesis).unwrap();
+
+        // Two blocks built on genesis in parallel
+        let a = create_test_block(1, genesis.hash());
+        let mut b = create_test_block(1, genesis.hash());
+        b.header.block_hash = Hash::new([0xbb; 32]);
+        store.put_block(&a).unwrap();
+        store.put_block(&b).unwrap();
+
+        // Merge block referencing both; its blue score only covers one of them
+        let mut merge = create_test_block(2, a.hash());
+        merge.header.merge_parent_hashes = vec![b.hash()];
+        merge.header.blue_score = a.header.blue_score + 1;
+        store.put_block(&merge).unwrap();
+
+        // Re-storing a block does not record it twice
+        store.put_block(&merge).unwrap();
+
+        let stats = store.dag_stats().get_range(0, u64::MAX, 100).unwrap();
+        assert_eq!(stats.len(), 4);
+
+        let b_stats = stats.iter().find(|s| s.hash == b.hash()).unwrap();
+        assert_eq!(b_stats.anticone_size, 1);
+        assert_eq!(b_stats.tips_count, 2);
+
+        let merge_stats = stats.iter().find(|s| s.hash == merge.hash()).unwrap();
+        assert_eq!(merge_stats.merge_parents, 1);
+        assert_eq!(merge_stats.red_merge_parents, 1);
+        assert_eq!(merge_stats.tips_count, 1);
+    }
+    #[test]
+    fn test_put_blocks_single_batch() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Arc::new(RocksDB::open(temp_dir.path()).unwrap());
+        let store = BlockStore::new(db);
+
+        let genesis = create_test_block(0, Hash::default());
+        let a = create_test_block(1, genesis.hash());
+        let mut b = create_test_block(1, genesis.hash());
+        b.header.block_hash = Hash::new([0xbb; 32]);
+        let mut merge = create_test_block(2, a.hash());
+        merge.header.merge_parent_hashes = vec![b.hash()];
+        merge.header.blue_score = a.header.blue_score + 1;
+
+        store
+            .put_blocks(&[genesis.clone(), a.clone(), b.clone(), merge.clone()])
+            .unwrap();
+
+        // Children written earlier in the batch are not lost
+        let mut children = store.get_children(&genesis.hash()).unwrap();
+        children.sort();
+        let mut expected = vec![a.hash(), b.hash()];
+        expected.sort();
+        assert_eq!(children, expected);
+
+        // Stats see headers and tips from earlier in the same batch
+        let stats = store.dag_stats().get_range(0, u64::MAX, 100).unwrap();
+        assert_eq!(stats.len(), 4);
+        let merge_stats = stats.iter().find(|s| s.hash == merge.hash()).unwrap();
+        assert_eq!(merge_stats.red_merge_parents, 1);
+        assert_eq!(merge_stats.tips_count, 1);
+        assert_eq!(store.get_tips().unwrap(), vec![merge.hash()]);
+    }
 }
@@ -0,0 +1,221 @@
+// citrate/core/storage/src/chain/dag_stats.rs
+
+// Per-block DAG statistics and time-series queries
+use crate::db::{column_families::CF_DAG_STATS, RocksDB};
+use anyhow::Result;
+use citrate_consensus::types::Hash;
+use rocksdb::WriteBatch;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Upper bound on buckets returned by a single series query
+pub const MAX_SERIES_BUCKETS: u64 = 1000;
+
+/// Shape of the DAG observed when a block was stored
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DagBlockStats {
+    pub hash: Hash,
+    pub height: u64,
+    /// Block timestamp (unix seconds)
+    pub timestamp: u64,
+    pub blue_score: u64,
+    /// Tips after the block was added
+    pub tips_count: u32,
+    pub merge_parents: u32,
+    /// Tips the block did not reference, i.e. blocks produced in parallel with it
+    pub anticone_size: u32,
+    /// Merge parents not covered by the blue score gain over the selected
+    /// parent, counted as red
+    pub red_merge_parents: u32,
+}
+
+/// Statistics aggregated over one time bucket `[start, end)`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DagStatsBucket {
+    pub start: u64,
+    pub end: u64,
+    pub blocks: u64,
+    pub blocks_per_sec: f64,
+    pub avg_tips: f64,
+    pub max_tips: u32,
+    pub avg_merge_parents: f64,
+    pub max_merge_parents: u32,
+    pub avg_anticone_size: f64,
+    pub max_anticone_size: u32,
+    pub blue_merge_parents: u64,
+    pub red_merge_parents: u64,
+    /// Blue share of merged parents; 1.0 when nothing was merged
+    pub blue_ratio: f64,
+    pub max_blue_score: u64,
+}
+
+/// Time-ordered store of per-block DAG statistics
+pub struct DagStatsStore {
+    db: Arc<RocksDB>,
+}
+
+impl DagStatsStore {
+    pub fn new(db: Arc<RocksDB>) -> Self {
+        Self { db }
+    }
+
+    /// Add a stats record to a pending block write
+    pub(crate) fn batch_put(&self, batch: &mut WriteBatch, stats: &DagBlockStats) -> Result<()> {
+        let bytes = bincode::serialize(stats)?;
+        self.db.batch_put_cf(
+            batch,
+            CF_DAG_STATS,
+            &stats_key(stats.timestamp, &stats.hash),
+            &bytes,
+        )
+    }
+
+    /// Remove a stats record as part of a block deletion
+    pub(crate) fn batch_delete(
+        &self,
+        batch: &mut WriteBatch,
+        timestamp: u64,
+        hash: &Hash,
+    ) -> Result<()> {
+        self.db
+            .batch_delete_cf(batch, CF_DAG_STATS, &stats_key(timestamp, hash))
+    }
+
+    /// Per-block stats with `from <= timestamp < to`, oldest first
+    pub fn get_range(&self, from: u64, to: u64, limit: usize) -> Result<Vec<DagBlockStats>> {
+        let mut out = Vec::new();
+        for (key, value) in self.db.iter_cf_from(CF_DAG_STATS, &from.to_be_bytes())? {
+            if out.len() >= limit || key.len() < 8 {
+                break;
+            }
+            let timestamp = u64::from_be_bytes(key[..8].try_into()?);
+            if timestamp >= to {
+                break;
+            }
+            out.push(bincode::deserialize(&value)?);
+        }
+        Ok(out)
+    }
+
+    /// Bucketed time series over `[from, to)`. The bucket width is widened
+    /// if needed so at most `MAX_SERIES_BUCKETS` buckets are returned.
+    pub fn series(&self, from: u64, to: u64, bucket_secs: u64) -> Result<Vec<DagStatsBucket>> {
+        let stats = self.get_range(from, to, usize::MAX)?;
+        Ok(aggregate(&stats, from, to, bucket_secs))
+    }
+}
+
+/// Group per-block stats (sorted by timestamp) into fixed-width buckets,
+/// including empty ones so the series has no gaps
+pub fn aggregate(
+    stats: &[DagBlockStats],
+    from: u64,
+    to: u64,
+    bucket_secs: u64,
+) -> Vec<DagStatsBucket> {
+    if to <= from {
+        return Vec::new();
+    }
+    let span = to - from;
+    let width = bucket_secs.max(span.div_ceil(MAX_SERIES_BUCKETS)).max(1);
+
+    let mut buckets: Vec<DagStatsBucket> = (0..span.div_ceil(width))
+        .map(|i| {
+            let start = from + i * width;
+            DagStatsBucket {
+                start,
+                end: (start + width).min(to),
+                blue_ratio: 1.0,
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    for s in stats
+        .iter()
+        .filter(|s| s.timestamp >= from && s.timestamp < to)
+    {
+        let b = &mut buckets[((s.timestamp - from) / width) as usize];
+        b.blocks += 1;
+        b.avg_tips += s.tips_count as f64;
+        b.max_tips = b.max_tips.max(s.tips_count);
+        b.avg_merge_parents += s.merge_parents as f64;
+        b.max_merge_parents = b.max_merge_parents.max(s.merge_parents);
+        b.avg_anticone_size += s.anticone_size as f64;
+        b.max_anticone_size = b.max_anticone_size.max(s.anticone_size);
+        b.blue_merge_parents += s.merge_parents.saturating_sub(s.red_merge_parents) as u64;
+        b.red_merge_parents += s.red_merge_parents as u64;
+        b.max_blue_score = b.max_blue_score.max(s.blue_score);
+    }
+
+    for b in &mut buckets {
+        if b.blocks > 0 {
+            let n = b.blocks as f64;
+            b.avg_tips /= n;
+            b.avg_merge_parents /= n;
+            b.avg_anticone_size /= n;
+            b.blocks_per_sec = n / (b.end - b.start) as f64;
+        }
+        let merged = b.blue_merge_parents + b.red_merge_parents;
+        if merged > 0 {
+            b.blue_ratio = b.blue_merge_parents as f64 / merged as f64;
+        }
+    }
+    buckets
+}
+
+fn stats_key(timestamp: u64, hash: &Hash) -> Vec<u8> {
+    let mut key = Vec::with_capacity(40);
+    key.extend_from_slice(&timestamp.to_be_bytes());
+    key.extend_from_slice(hash.as_bytes());
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(timestamp: u64, tips: u32, merge_parents: u32, red: u32) -> DagBlockStats {
+        DagBlockStats {
+            hash: Hash::new([timestamp as u8; 32]),
+            height: timestamp,
+            timestamp,
+            blue_score: timestamp * 2,
+            tips_count: tips,
+            merge_parents,
+            anticone_size: tips.saturating_sub(1),
+            red_merge_parents: red,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_buckets() {
+        let data = vec![
+            stats(100, 1, 0, 0),
+            stats(105, 3, 2, 1),
+            stats(125, 2, 1, 0),
+        ];
+        let buckets = aggregate(&data, 100, 130, 10);
+        assert_eq!(buckets.len(), 3);
+
+        assert_eq!(buckets[0].blocks, 2);
+        assert_eq!(buckets[0].avg_tips, 2.0);
+        assert_eq!(buckets[0].max_tips, 3);
+        assert_eq!(buckets[0].max_anticone_size, 2);
+        assert_eq!(buckets[0].blue_ratio, 0.5);
+        assert_eq!(buckets[0].blocks_per_sec, 0.2);
+
+        // Empty buckets are kept so the series is continuous
+        assert_eq!(buckets[1].blocks, 0);
+        assert_eq!(buckets[1].blue_ratio, 1.0);
+
+        assert_eq!(buckets[2].max_blue_score, 250);
+    }
+
+    #[test]
+    fn test_aggregate_caps_bucket_count() {
+        let buckets = aggregate(&[], 0, 1_000_000, 1);
+        assert_eq!(buckets.len() as u64, MAX_SERIES_BUCKETS);
+        assert_eq!(buckets.last().unwrap().end, 1_000_000);
+    }
+}
@@ -0,0 +1,446 @@
+// citrate/core/storage/src/chain/log_index.rs
+
+// Contract event log index with per-block blooms
+use crate::db::{column_families::CF_LOGS, RocksDB};
+use anyhow::Result;
+use citrate_consensus::types::Hash;
+use citrate_execution::types::{Address, TransactionReceipt};
+use citrate_execution::Bloom;
+use rocksdb::WriteBatch;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Upper bound on logs returned by a single query
+pub const MAX_LOGS_PER_QUERY: usize = 10_000;
+
+const BLOOM_PREFIX: u8 = b'b';
+const LOG_PREFIX: u8 = b'l';
+const ADDRESS_PREFIX: u8 = b'a';
+
+/// Height, block hash, transaction index and log index
+const POSITION_LEN: usize = 8 + 32 + 4 + 4;
+
+/// A log together with its position in the chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexedLog {
+    pub address: Address,
+    pub topics: Vec<Hash>,
+    pub data: Vec<u8>,
+    pub block_number: u64,
+    pub block_hash: Hash,
+    pub tx_hash: Hash,
+    /// Position of the transaction in its block
+    pub tx_index: u32,
+    /// Position of the log in its block
+    pub log_index: u32,
+}
+
+/// Log query over an inclusive block range
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    pub from_block: u64,
+    pub to_block: u64,
+    /// Logs emitted by any of these addresses; empty matches all
+    pub addresses: Vec<Address>,
+    /// Per topic position, any of the listed topics; `None` matches all
+    pub topics: Vec<Option<Vec<Hash>>>,
+}
+
+impl LogFilter {
+    /// Exact match against a stored log
+    pub fn matches(&self, log: &IndexedLog) -> bool {
+        if log.block_number < self.from_block || log.block_number > self.to_block {
+            return false;
+        }
+        if !self.addresses.is_empty() && !self.addresses.contains(&log.address) {
+            return false;
+        }
+        self.topics
+            .iter()
+            .enumerate()
+            .all(|(i, allowed)| match allowed {
+                None => true,
+                Some(allowed) => log.topics.get(i).is_some_and(|t| allowed.contains(t)),
+            })
+    }
+
+    /// Whether a block with this bloom may contain matching logs
+    pub fn bloom_may_match(&self, bloom: &Bloom) -> bool {
+        let address_ok =
+            self.addresses.is_empty() || self.addresses.iter().any(|a| bloom.contains_input(&a.0));
+        address_ok
+            && self.topics.iter().flatten().all(|allowed| {
+                allowed.is_empty() || allowed.iter().any(|t| bloom.contains_input(t.as_bytes()))
+            })
+    }
+}
+
+/// Log storage keyed by block range, with a per-address index and a bloom
+/// per block so queries only touch blocks that can contain matches
+pub struct LogIndex {
+    db: Arc<RocksDB>,
+}
+
+impl LogIndex {
+    pub fn new(db: Arc<RocksDB>) -> Self {
+        Self { db }
+    }
+
+    /// Index the logs of receipts given in block order. Receipts of one
+    /// block must be written in a single call for transaction and log
+    /// indices to line up with the block.
+    pub(crate) fn batch_put_receipts(
+        &self,
+        batch: &mut WriteBatch,
+        receipts: &[(Hash, TransactionReceipt)],
+    ) -> Result<()> {
+        let mut blooms: HashMap<(u64, Hash), Bloom> = HashMap::new();
+        let mut tx_indices: HashMap<Hash, u32> = HashMap::new();
+        let mut log_indices: HashMap<Hash, u32> = HashMap::new();
+
+        for (tx_hash, receipt) in receipts {
+            let tx_index = tx_indices.entry(receipt.block_hash).or_insert(0);
+            let bloom = blooms
+                .entry((receipt.block_number, receipt.block_hash))
+                .or_default();
+            bloom.accrue_bloom(&receipt.logs_bloom());
+
+            for log in &receipt.logs {
+                let log_index = log_indices.entry(receipt.block_hash).or_insert(0);
+                let position = position_key(
+                    receipt.block_number,
+                    &receipt.block_hash,
+                    *tx_index,
+                    *log_index,
+                );
+                let indexed = IndexedLog {
+                    address: log.address,
+                    topics: log.topics.clone(),
+                    data: log.data.clone(),
+                    block_number: receipt.block_number,
+                    block_hash: receipt.block_hash,
+                    tx_hash: *tx_hash,
+                    tx_index: *tx_index,
+                    log_index: *log_index,
+                };
+                self.db.batch_put_cf(
+                    batch,
+                    CF_LOGS,
+                    &prefixed(LOG_PREFIX, &position),
+                    &bincode::serialize(&indexed)?,
+                )?;
+                self.db
+                    .batch_put_cf(batch, CF_LOGS, &address_key(&log.address, &position), &[])?;
+                *log_index += 1;
+            }
+            *tx_index += 1;
+        }
+
+        for ((height, block_hash), mut bloom) in blooms {
+            // Merge with anything already recorded for the block
+            if let Some(existing) = self.get_block_bloom(height, &block_hash)? {
+                bloom.accrue_bloom(&existing);
+            }
+            self.db.batch_put_cf(
+                batch,
+                CF_LOGS,
+                &bloom_key(height, &block_hash),
+                bloom.as_bytes(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Remove everything indexed for a block as part of a block deletion
+    pub(crate) fn batch_delete_block(
+        &self,
+        batch: &mut WriteBatch,
+        height: u64,
+        block_hash: &Hash,
+    ) -> Result<()> {
+        let block_prefix = prefixed(LOG_PREFIX, &block_position(height, block_hash));
+        for (key, value) in self.db.iter_cf_from(CF_LOGS, &block_prefix)? {
+            if !key.starts_with(&block_prefix) {
+                break;
+            }
+            let log: IndexedLog = bincode::deserialize(&value)?;
+            self.db
+                .batch_delete_cf(batch, CF_LOGS, &address_key(&log.address, &key[1..]))?;
+            self.db.batch_delete_cf(batch, CF_LOGS, &key)?;
+        }
+        self.db
+            .batch_delete_cf(batch, CF_LOGS, &bloom_key(height, block_hash))
+    }
+
+    /// Bloom of all logs in a block, if the block has indexed receipts
+    pub fn get_block_bloom(&self, height: u64, block_hash: &Hash) -> Result<Option<Bloom>> {
+        Ok(self
+            .db
+            .get_cf(CF_LOGS, &bloom_key(height, block_hash))?
+            .and_then(|bytes| Bloom::from_slice(&bytes)))
+    }
+
+    /// Logs matching `filter`, ordered by block, transaction and log index.
+    /// Fails if more than `limit` logs match.
+    pub fn get_logs(&self, filter: &LogFilter, limit: usize) -> Result<Vec<IndexedLog>> {
+        if filter.from_block > filter.to_block {
+            return Ok(Vec::new());
+        }
+
+        let mut logs = if filter.addresses.is_empty() {
+            self.scan_by_bloom(filter, limit)?
+        } else {
+            self.scan_by_address(filter, limit)?
+        };
+
+        logs.sort_by(|a, b| {
+            (a.block_number, a.block_hash, a.tx_index, a.log_index).cmp(&(
+                b.block_number,
+                b.block_hash,
+                b.tx_index,
+                b.log_index,
+            ))
+        });
+        Ok(logs)
+    }
+
+    /// Walk the per-block blooms in range and read only candidate blocks
+    fn scan_by_bloom(&self, filter: &LogFilter, limit: usize) -> Result<Vec<IndexedLog>> {
+        let mut candidates = Vec::new();
+        let start = prefixed(BLOOM_PREFIX, &filter.from_block.to_be_bytes());
+        for (key, value) in self.db.iter_cf_from(CF_LOGS, &start)? {
+            if key.first() != Some(&BLOOM_PREFIX) || key.len() != 1 + 8 + 32 {
+                break;
+            }
+            let height = u64::from_be_bytes(key[1..9].try_into()?);
+            if height > filter.to_block {
+                break;
+            }
+            let may_match = Bloom::from_slice(&value)
+                .map(|bloom| filter.bloom_may_match(&bloom))
+                .unwrap_or(true);
+            if may_match {
+                candidates.push(key[1..].to_vec());
+            }
+        }
+
+        let mut logs = Vec::new();
+        for block in candidates {
+            let prefix = prefixed(LOG_PREFIX, &block);
+            for (key, value) in self.db.iter_cf_from(CF_LOGS, &prefix)? {
+                if !key.starts_with(&prefix) {
+                    break;
+                }
+                let log: IndexedLog = bincode::deserialize(&value)?;
+                if filter.matches(&log) {
+                    push_limited(&mut logs, log, limit)?;
+                }
+            }
+        }
+        Ok(logs)
+    }
+
+    /// Range-scan the address index for each requested address
+    fn scan_by_address(&self, filter: &LogFilter, limit: usize) -> Result<Vec<IndexedLog>> {
+        let mut logs = Vec::new();
+        for address in &filter.addresses {
+            let prefix = prefixed(ADDRESS_PREFIX, &address.0);
+            let start = address_key(address, &filter.from_block.to_be_bytes());
+            for (key, _) in self.db.iter_cf_from(CF_LOGS, &start)? {
+                if !key.starts_with(&prefix) || key.len() != prefix.len() + POSITION_LEN {
+                    break;
+                }
+                let position = &key[prefix.len()..];
+                let height = u64::from_be_bytes(position[..8].try_into()?);
+                if height > filter.to_block {
+                    break;
+                }
+                let Some(bytes) = self.db.get_cf(CF_LOGS, &prefixed(LOG_PREFIX, position))? else {
+                    continue;
+                };
+                let log: IndexedLog = bincode::deserialize(&bytes)?;
+                if filter.matches(&log) {
+                    push_limited(&mut logs, log, limit)?;
+                }
+            }
+        }
+        Ok(logs)
+    }
+}
+
+fn push_limited(logs: &mut Vec<IndexedLog>, log: IndexedLog, limit: usize) -> Result<()> {
+    if logs.len() >= limit {
+        anyhow::bail!("query returned more than {} results", limit);
+    }
+    logs.push(log);
+    Ok(())
+}
+
+fn prefixed(prefix: u8, rest: &[u8]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(1 + rest.len());
+    key.push(prefix);
+    key.extend_from_slice(rest);
+    key
+}
+
+fn block_position(height: u64, block_hash: &Hash) -> Vec<u8> {
+    let mut key = Vec::with_capacity(40);
+    key.extend_from_slice(&height.to_be_bytes());
+    key.extend_from_slice(block_hash.as_bytes());
+    key
+}
+
+fn position_key(height: u64, block_hash: &Hash, tx_index: u32, log_index: u32) -> Vec<u8> {
+    let mut key = block_position(height, block_hash);
+    key.extend_from_slice(&tx_index.to_be_bytes());
+    key.extend_from_slice(&log_index.to_be_bytes());
+    key
+}
+
+fn bloom_key(height: u64, block_hash: &Hash) -> Vec<u8> {
+    prefixed(BLOOM_PREFIX, &block_position(height, block_hash))
+}
+
+fn address_key(address: &Address, position: &[u8]) -> Vec<u8> {
+    let mut key = prefixed(ADDRESS_PREFIX, &address.0);
+    key.extend_from_slice(position);
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use citrate_execution::types::Log;
+    use tempfile::TempDir;
+
+    fn receipt(block: u8, height: u64, logs: Vec<Log>) -> TransactionReceipt {
+        TransactionReceipt {
+            tx_hash: Hash::default(),
+            block_hash: Hash::new([block; 32]),
+            block_number: height,
+            from: Address([0; 20]),
+            to: None,
+            gas_used: 21_000,
+            status: true,
+            logs,
+            output: vec![],
+        }
+    }
+
+    fn log(address: u8, topics: &[u8]) -> Log {
+        Log {
+            address: Address([address; 20]),
+            topics: topics.iter().map(|t| Hash::new([*t; 32])).collect(),
+            data: vec![address],
+        }
+    }
+
+    fn setup() -> (TempDir, Arc<RocksDB>, LogIndex) {
+        let dir = TempDir::new().unwrap();
+        let db = Arc::new(RocksDB::open(dir.path()).unwrap());
+        let index = LogIndex::new(db.clone());
+
+        let receipts = vec![
+            (
+                Hash::new([0x11; 32]),
+                receipt(1, 1, vec![log(0xa, &[1, 2])]),
+            ),
+            (Hash::new([0x12; 32]), receipt(1, 1, vec![])),
+            (
+                Hash::new([0x13; 32]),
+                receipt(1, 1, vec![log(0xb, &[1]), log(0xa, &[3])]),
+            ),
+            (Hash::new([0x21; 32]), receipt(2, 2, vec![log(0xb, &[2])])),
+            (Hash::new([0x31; 32]), receipt(3, 3, vec![log(0xa, &[1])])),
+        ];
+        let mut batch = db.batch();
+        index.batch_put_receipts(&mut batch, &receipts).unwrap();
+        db.write_batch(batch).unwrap();
+        (dir, db, index)
+    }
+
+    fn filter(from: u64, to: u64) -> LogFilter {
+        LogFilter {
+            from_block: from,
+            to_block: to,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_positions_follow_block_order() {
+        let (_dir, _db, index) = setup();
+        let logs = index.get_logs(&filter(0, 10), MAX_LOGS_PER_QUERY).unwrap();
+
+        let positions: Vec<_> = logs
+            .iter()
+            .map(|l| (l.block_number, l.tx_index, l.log_index))
+            .collect();
+        assert_eq!(
+            positions,
+            vec![(1, 0, 0), (1, 2, 1), (1, 2, 2), (2, 0, 0), (3, 0, 0)]
+        );
+        assert_eq!(logs[1].tx_hash, Hash::new([0x13; 32]));
+    }
+
+    #[test]
+    fn test_filter_by_address_and_range() {
+        let (_dir, _db, index) = setup();
+        let mut f = filter(1, 2);
+        f.addresses = vec![Address([0xa; 20])];
+
+        let logs = index.get_logs(&f, MAX_LOGS_PER_QUERY).unwrap();
+        assert_eq!(logs.len(), 2);
+        assert!(logs.iter().all(|l| l.address == Address([0xa; 20])));
+        assert!(logs.iter().all(|l| l.block_number == 1));
+    }
+
+    #[test]
+    fn test_filter_by_topic_position() {
+        let (_dir, _db, index) = setup();
+        let mut f = filter(0, 10);
+        f.topics = vec![Some(vec![Hash::new([1; 32])])];
+        assert_eq!(index.get_logs(&f, MAX_LOGS_PER_QUERY).unwrap().len(), 3);
+
+        // Topic 2 only ever appears in position 1 of block 1
+        f.topics = vec![None, Some(vec![Hash::new([2; 32])])];
+        let logs = index.get_logs(&f, MAX_LOGS_PER_QUERY).unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].block_number, 1);
+    }
+
+    #[test]
+    fn test_block_bloom_and_limit() {
+        let (_dir, _db, index) = setup();
+        let bloom = index
+            .get_block_bloom(2, &Hash::new([2; 32]))
+            .unwrap()
+            .unwrap();
+        assert!(bloom.contains_input(&[0xb; 20]));
+        assert!(!bloom.contains_input(&[0xa; 20]));
+
+        assert!(index.get_logs(&filter(0, 10), 2).is_err());
+        assert!(index.get_logs(&filter(5, 1), 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_delete_block_removes_logs() {
+        let (_dir, db, index) = setup();
+        let mut batch = db.batch();
+        index
+            .batch_delete_block(&mut batch, 1, &Hash::new([1; 32]))
+            .unwrap();
+        db.write_batch(batch).unwrap();
+
+        let mut f = filter(0, 10);
+        f.addresses = vec![Address([0xa; 20])];
+        let logs = index.get_logs(&f, MAX_LOGS_PER_QUERY).unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].block_number, 3);
+        assert!(index
+            .get_block_bloom(1, &Hash::new([1; 32]))
+            .unwrap()
+            .is_none());
+    }
+}
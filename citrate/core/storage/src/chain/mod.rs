@@ -2,7 +2,13 @@
 
 // Chain storage module
 pub mod block_store;
+pub mod dag_stats;
+pub mod log_index;
+pub mod stats_history;
 pub mod transaction_store;
 
 pub use block_store::BlockStore;
+pub use dag_stats::{DagBlockStats, DagStatsBucket, DagStatsStore};
+pub use log_index::{IndexedLog, LogFilter, LogIndex, MAX_LOGS_PER_QUERY};
+pub use stats_history::{run_recorder, ChainStatsSnapshot, LiveStats, StatsHistoryStore};
 pub use transaction_store::TransactionStore;
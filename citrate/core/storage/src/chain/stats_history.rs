@@ -0,0 +1,238 @@
+// citrate/core/storage/src/chain/stats_history.rs
+
+// Hourly chain statistics snapshots for uptime dashboards
+use crate::chain::{BlockStore, DagBlockStats, DagStatsStore};
+use crate::db::{column_families::CF_STATS_HISTORY, RocksDB};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+/// Seconds covered by one snapshot
+pub const SNAPSHOT_INTERVAL_SECS: u64 = 3600;
+
+/// Upper bound on snapshots returned by a single range query
+pub const MAX_HISTORY_SNAPSHOTS: usize = 10_000;
+
+/// How often the recorder checks whether a new hour has started
+pub const RECORD_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Days of snapshots kept by default
+pub const DEFAULT_RETENTION_DAYS: u64 = 90;
+
+/// Chain statistics at the start of an hour, with throughput over the hour
+/// before it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainStatsSnapshot {
+    /// Start of the hour (unix seconds)
+    pub timestamp: u64,
+    pub height: u64,
+    pub tips: u32,
+    /// Blocks produced in the preceding hour
+    pub blocks: u64,
+    /// Transactions included in the preceding hour
+    pub transactions: u64,
+    pub tx_per_sec: f64,
+    pub peer_count: usize,
+    pub mempool_pending: usize,
+}
+
+/// Node values sampled when a snapshot is taken
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LiveStats {
+    pub peer_count: usize,
+    pub mempool_pending: usize,
+}
+
+/// Time-ordered store of hourly chain statistics
+pub struct StatsHistoryStore {
+    db: Arc<RocksDB>,
+    blocks: Arc<BlockStore>,
+    dag_stats: Arc<DagStatsStore>,
+}
+
+impl StatsHistoryStore {
+    pub fn new(db: Arc<RocksDB>, blocks: Arc<BlockStore>) -> Self {
+        let dag_stats = blocks.dag_stats();
+        Self {
+            db,
+            blocks,
+            dag_stats,
+        }
+    }
+
+    /// Take the snapshot for the hour containing `now`, unless it was
+    /// already taken. Returns the new snapshot.
+    pub fn record(&self, now: u64, live: LiveStats) -> Result<Option<ChainStatsSnapshot>> {
+        let timestamp = hour_start(now);
+        if self
+            .db
+            .get_cf(CF_STATS_HISTORY, &timestamp.to_be_bytes())?
+            .is_some()
+        {
+            return Ok(None);
+        }
+
+        let window = self.dag_stats.get_range(
+            timestamp.saturating_sub(SNAPSHOT_INTERVAL_SECS),
+            timestamp,
+            usize::MAX,
+        )?;
+        let mut transactions = 0u64;
+        for stats in &window {
+            if let Some(block) = self.blocks.get_block(&stats.hash)? {
+                transactions += block.transactions.len() as u64;
+            }
+        }
+        let tips = match window.last() {
+            Some(stats) => stats.tips_count,
+            None => self.blocks.get_tips()?.len() as u32,
+        };
+
+        let snapshot = build_snapshot(
+            timestamp,
+            self.blocks.get_latest_height()?,
+            tips,
+            &window,
+            transactions,
+            live,
+        );
+        self.db.put_cf(
+            CF_STATS_HISTORY,
+            &timestamp.to_be_bytes(),
+            &bincode::serialize(&snapshot)?,
+        )?;
+        Ok(Some(snapshot))
+    }
+
+    /// Snapshots with `from <= timestamp < to`, oldest first
+    pub fn get_range(&self, from: u64, to: u64) -> Result<Vec<ChainStatsSnapshot>> {
+        let mut out = Vec::new();
+        for (key, value) in self
+            .db
+            .iter_cf_from(CF_STATS_HISTORY, &from.to_be_bytes())?
+        {
+            if out.len() >= MAX_HISTORY_SNAPSHOTS || key.len() != 8 {
+                break;
+            }
+            if u64::from_be_bytes(key[..8].try_into()?) >= to {
+                break;
+            }
+            out.push(bincode::deserialize(&value)?);
+        }
+        Ok(out)
+    }
+
+    /// Delete snapshots older than `before`; returns how many were removed
+    pub fn prune_before(&self, before: u64) -> Result<usize> {
+        let keys: Vec<Box<[u8]>> = self
+            .db
+            .iter_cf(CF_STATS_HISTORY)?
+            .map(|(key, _)| key)
+            .take_while(|key| key.as_ref() < before.to_be_bytes().as_slice())
+            .collect();
+        for key in &keys {
+            self.db.delete_cf(CF_STATS_HISTORY, key)?;
+        }
+        Ok(keys.len())
+    }
+}
+
+/// Take a snapshot at the start of every hour and drop those older than
+/// `retention_days` (0 keeps all). `sample` reports the live node values;
+/// the recorder stops when it returns `None`.
+pub async fn run_recorder<F, Fut>(store: Arc<StatsHistoryStore>, retention_days: u64, sample: F)
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Option<LiveStats>>,
+{
+    let mut ticker = tokio::time::interval(RECORD_CHECK_INTERVAL);
+    loop {
+        ticker.tick().await;
+        let Some(live) = sample().await else {
+            return;
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        match store.record(now, live) {
+            Ok(Some(snapshot)) => {
+                debug!(
+                    "Recorded chain stats snapshot at {}: height {}, {} tx",
+                    snapshot.timestamp, snapshot.height, snapshot.transactions
+                );
+                if retention_days > 0 {
+                    let cutoff = now.saturating_sub(retention_days * 86_400);
+                    if let Err(e) = store.prune_before(cutoff) {
+                        warn!("Failed to prune chain stats history: {}", e);
+                    }
+                }
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Failed to record chain stats snapshot: {}", e),
+        }
+    }
+}
+
+/// Start of the hour containing `timestamp`
+pub fn hour_start(timestamp: u64) -> u64 {
+    timestamp - timestamp % SNAPSHOT_INTERVAL_SECS
+}
+
+/// Snapshot at `timestamp` from the per-block stats of the preceding hour
+pub fn build_snapshot(
+    timestamp: u64,
+    height: u64,
+    tips: u32,
+    window: &[DagBlockStats],
+    transactions: u64,
+    live: LiveStats,
+) -> ChainStatsSnapshot {
+    ChainStatsSnapshot {
+        timestamp,
+        height,
+        tips,
+        blocks: window.len() as u64,
+        transactions,
+        tx_per_sec: transactions as f64 / SNAPSHOT_INTERVAL_SECS as f64,
+        peer_count: live.peer_count,
+        mempool_pending: live.mempool_pending,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use citrate_consensus::types::Hash;
+
+    #[test]
+    fn test_build_snapshot() {
+        assert_eq!(hour_start(7_200), 7_200);
+        assert_eq!(hour_start(10_799), 7_200);
+
+        let window: Vec<DagBlockStats> = (0..3)
+            .map(|i| DagBlockStats {
+                hash: Hash::new([i; 32]),
+                height: i as u64,
+                timestamp: 4_000 + i as u64,
+                blue_score: i as u64,
+                tips_count: 1,
+                merge_parents: 0,
+                anticone_size: 0,
+                red_merge_parents: 0,
+            })
+            .collect();
+        let live = LiveStats {
+            peer_count: 4,
+            mempool_pending: 12,
+        };
+        let snapshot = build_snapshot(7_200, 2, 1, &window, 720, live);
+        assert_eq!(snapshot.blocks, 3);
+        assert_eq!(snapshot.tx_per_sec, 0.2);
+        assert_eq!(snapshot.peer_count, 4);
+        assert_eq!(snapshot.mempool_pending, 12);
+    }
+}
@@ -1,8 +1,10 @@
 // citrate/core/storage/src/chain/transaction_store.rs
 
+use super::log_index::LogIndex;
 use crate::db::{column_families::*, RocksDB};
 use anyhow::Result;
 use citrate_consensus::types::{Hash, Transaction};
+use citrate_execution::tracer::InternalTransaction;
 use citrate_execution::types::TransactionReceipt;
 use std::sync::Arc;
 use tracing::debug;
@@ -10,11 +12,17 @@ use tracing::debug;
 /// Transaction and receipt storage
 pub struct TransactionStore {
     db: Arc<RocksDB>,
+    logs: Arc<LogIndex>,
 }
 
 impl TransactionStore {
     pub fn new(db: Arc<RocksDB>) -> Self {
-        Self { db }
+        Self::with_log_index(db.clone(), Arc::new(LogIndex::new(db)))
+    }
+
+    /// Store that indexes receipt logs into a shared log index
+    pub fn with_log_index(db: Arc<RocksDB>, logs: Arc<LogIndex>) -> Self {
+        Self { db, logs }
     }
 
     /// Store a transaction
@@ -88,7 +96,8 @@ impl TransactionStore {
         Ok(())
     }
 
-    /// Store multiple receipts in batch
+    /// Store multiple receipts in batch. Receipts must be in block order;
+    /// their logs are indexed with positions taken from that order.
     pub fn put_receipts(&self, receipts: &[(Hash, TransactionReceipt)]) -> Result<()> {
         let mut batch = self.db.batch();
 
@@ -101,6 +110,7 @@ impl TransactionStore {
             self.db
                 .batch_put_cf(&mut batch, CF_METADATA, &block_tx_key, &[])?;
         }
+        self.logs.batch_put_receipts(&mut batch, receipts)?;
 
         self.db.write_batch(batch)?;
         debug!("Stored {} receipts", receipts.len());
@@ -115,6 +125,30 @@ impl TransactionStore {
         }
     }
 
+    /// Store the internal value transfers of executed transactions. Entries
+    /// without transfers are skipped, so most transactions cost nothing here.
+    pub fn put_internal_transactions(
+        &self,
+        entries: &[(Hash, Vec<InternalTransaction>)],
+    ) -> Result<()> {
+        let mut batch = self.db.batch();
+        for (tx_hash, internal) in entries.iter().filter(|(_, i)| !i.is_empty()) {
+            let bytes = bincode::serialize(internal)?;
+            self.db
+                .batch_put_cf(&mut batch, CF_INTERNAL_TXS, tx_hash.as_bytes(), &bytes)?;
+        }
+        self.db.write_batch(batch)?;
+        Ok(())
+    }
+
+    /// Internal value transfers made while executing a transaction
+    pub fn get_internal_transactions(&self, tx_hash: &Hash) -> Result<Vec<InternalTransaction>> {
+        match self.db.get_cf(CF_INTERNAL_TXS, tx_hash.as_bytes())? {
+            Some(bytes) => Ok(bincode::deserialize(&bytes)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
     /// Get all transactions in a block
     pub fn get_block_transactions(&self, block_hash: &Hash) -> Result<Vec<Hash>> {
         let prefix = block_tx_prefix(block_hash);
@@ -136,7 +170,7 @@ impl TransactionStore {
         Ok(tx_hashes)
     }
 
-    /// Delete a transaction and its receipt
+    /// Delete a transaction, its receipt and its internal transfers
     pub fn delete_transaction(&self, hash: &Hash) -> Result<()> {
         let mut batch = self.db.batch();
 
@@ -154,6 +188,8 @@ impl TransactionStore {
             self.db
                 .batch_delete_cf(&mut batch, CF_METADATA, &block_tx_key)?;
         }
+        self.db
+            .batch_delete_cf(&mut batch, CF_INTERNAL_TXS, hash.as_bytes())?;
 
         self.db.write_batch(batch)?;
         Ok(())
@@ -262,6 +298,39 @@ mod tests {
         assert_eq!(block_txs[0], tx_hash);
     }
 
+    #[test]
+    fn test_internal_transaction_storage() {
+        use citrate_execution::tracer::InternalTxKind;
+        use primitive_types::U256;
+
+        let temp_dir = TempDir::new().unwrap();
+        let db = Arc::new(RocksDB::open(temp_dir.path()).unwrap());
+        let store = TransactionStore::new(db);
+
+        let tx = create_test_transaction(1);
+        let plain = create_test_transaction(2);
+        let transfer = InternalTransaction {
+            kind: InternalTxKind::Call,
+            from: Address([2; 20]),
+            to: Address([3; 20]),
+            value: U256::from(500),
+            depth: 1,
+        };
+        store
+            .put_internal_transactions(&[
+                (tx.hash, vec![transfer.clone()]),
+                (plain.hash, vec![]),
+            ])
+            .unwrap();
+
+        assert_eq!(store.get_internal_transactions(&tx.hash).unwrap(), vec![transfer]);
+        assert!(store.get_internal_transactions(&plain.hash).unwrap().is_empty());
+
+        store.put_transaction(&tx).unwrap();
+        store.delete_transaction(&tx.hash).unwrap();
+        assert!(store.get_internal_transactions(&tx.hash).unwrap().is_empty());
+    }
+
     #[test]
     fn test_block_tx_prefix_roundtrip_multiple_blocks() {
         let temp_dir = TempDir::new().unwrap();
@@ -15,6 +15,12 @@ pub const CF_TRAINING: &str = "training";
 pub const CF_METADATA: &str = "metadata";
 pub const CF_BLUE_SET: &str = "blue_set";
 pub const CF_DAG_RELATIONS: &str = "dag_relations";
+pub const CF_PEERS: &str = "peers";
+pub const CF_DAG_STATS: &str = "dag_stats";
+pub const CF_LOGS: &str = "logs";
+pub const CF_INTERNAL_TXS: &str = "internal_txs";
+pub const CF_ARCHIVE: &str = "archive";
+pub const CF_STATS_HISTORY: &str = "stats_history";
 
 /// Get all column families
 pub fn all_column_families() -> Vec<&'static str> {
@@ -33,5 +39,11 @@ pub fn all_column_families() -> Vec<&'static str> {
         CF_METADATA,
         CF_BLUE_SET,
         CF_DAG_RELATIONS,
+        CF_PEERS,
+        CF_DAG_STATS,
+        CF_LOGS,
+        CF_INTERNAL_TXS,
+        CF_ARCHIVE,
+        CF_STATS_HISTORY,
     ]
 }
@@ -0,0 +1,301 @@
+// citrate/core/storage/src/db/db_admin.rs
+
+// Database administration: per column family size statistics, manual
+// compaction, write-stall detection and the tuning RocksDB is opened with
+use super::column_families::all_column_families;
+use super::rocks_db::RocksDB;
+use anyhow::{anyhow, Result};
+use rocksdb::properties::{self, PropName};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use tracing::{info, warn};
+
+/// Block cache, bloom filter and write buffer settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DbTuning {
+    /// Bloom filter bits per key; 0 disables bloom filters
+    pub bloom_bits_per_key: f64,
+    /// Block cache shared by all column families, in MB
+    pub block_cache_mb: usize,
+    pub block_size_kb: usize,
+    /// Memtable size per column family, in MB
+    pub write_buffer_mb: usize,
+    pub max_write_buffer_number: i32,
+    /// Level 0 file count at which writes are slowed down
+    pub level0_slowdown_writes_trigger: i32,
+    /// Level 0 file count at which writes are stopped
+    pub level0_stop_writes_trigger: i32,
+    /// Pending compaction size at which writes are slowed down, in GB
+    pub soft_pending_compaction_gb: usize,
+}
+
+impl Default for DbTuning {
+    fn default() -> Self {
+        Self {
+            bloom_bits_per_key: 10.0,
+            block_cache_mb: 256,
+            block_size_kb: 16,
+            write_buffer_mb: 128,
+            max_write_buffer_number: 3,
+            level0_slowdown_writes_trigger: 20,
+            level0_stop_writes_trigger: 36,
+            soft_pending_compaction_gb: 64,
+        }
+    }
+}
+
+impl DbTuning {
+    /// Check the settings can be applied
+    pub fn validate(&self) -> Result<()> {
+        if self.bloom_bits_per_key < 0.0 {
+            return Err(anyhow!("bloom_bits_per_key must not be negative"));
+        }
+        if self.block_size_kb == 0 || self.write_buffer_mb == 0 {
+            return Err(anyhow!("block size and write buffer size must be positive"));
+        }
+        if self.max_write_buffer_number < 2 {
+            return Err(anyhow!("max_write_buffer_number must be at least 2"));
+        }
+        if self.level0_slowdown_writes_trigger >= self.level0_stop_writes_trigger {
+            return Err(anyhow!(
+                "level0_slowdown_writes_trigger must be below level0_stop_writes_trigger"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Size and compaction state of one column family
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnFamilyStats {
+    pub name: String,
+    pub estimated_keys: u64,
+    pub live_data_bytes: u64,
+    pub sst_bytes: u64,
+    pub memtable_bytes: u64,
+    pub immutable_memtables: u64,
+    pub level0_files: u64,
+    pub pending_compaction_bytes: u64,
+    pub compaction_pending: bool,
+}
+
+/// A column family close to stalling writes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StallWarning {
+    pub column_family: String,
+    pub reason: String,
+}
+
+/// Whether writes are currently stopped or delayed, and why they may be soon
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteStallStatus {
+    pub stopped: bool,
+    /// Rate writes are throttled to in bytes per second; 0 when not delayed
+    pub delayed_write_rate: u64,
+    pub warnings: Vec<StallWarning>,
+}
+
+impl WriteStallStatus {
+    pub fn is_stalled(&self) -> bool {
+        self.stopped || self.delayed_write_rate > 0
+    }
+}
+
+/// Database statistics reported by `admin_dbStats`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbStats {
+    pub column_families: Vec<ColumnFamilyStats>,
+    pub total_sst_bytes: u64,
+    pub total_memtable_bytes: u64,
+    pub running_compactions: u64,
+    /// A compaction started through `compact` is still running
+    pub manual_compaction_running: bool,
+    pub block_cache_usage: u64,
+    pub block_cache_capacity: u64,
+    pub write_stall: WriteStallStatus,
+    pub tuning: DbTuning,
+}
+
+/// Reasons `cf` is close to stalling writes under `tuning`
+fn stall_warnings(cf: &ColumnFamilyStats, tuning: &DbTuning) -> Vec<StallWarning> {
+    let mut reasons = Vec::new();
+    if cf.level0_files >= tuning.level0_slowdown_writes_trigger as u64 {
+        reasons.push(format!(
+            "{} level 0 files (slowdown at {}, stop at {})",
+            cf.level0_files,
+            tuning.level0_slowdown_writes_trigger,
+            tuning.level0_stop_writes_trigger
+        ));
+    }
+    let soft_limit = tuning.soft_pending_compaction_gb as u64 * 1024 * 1024 * 1024;
+    if soft_limit > 0 && cf.pending_compaction_bytes >= soft_limit {
+        reasons.push(format!(
+            "{} bytes pending compaction (slowdown at {})",
+            cf.pending_compaction_bytes, soft_limit
+        ));
+    }
+    if cf.immutable_memtables + 1 >= tuning.max_write_buffer_number as u64 {
+        reasons.push(format!(
+            "{} memtables waiting to flush (limit {})",
+            cf.immutable_memtables + 1,
+            tuning.max_write_buffer_number
+        ));
+    }
+    reasons
+        .into_iter()
+        .map(|reason| StallWarning {
+            column_family: cf.name.clone(),
+            reason,
+        })
+        .collect()
+}
+
+impl RocksDB {
+    /// Per column family sizes, compaction progress and write-stall status
+    pub fn db_stats(&self) -> Result<DbStats> {
+        let mut column_families = Vec::new();
+        for name in all_column_families() {
+            let cf = self.cf_handle(name)?;
+            let int = |property: &PropName| -> Result<u64> {
+                Ok(self.db.property_int_value_cf(cf, property)?.unwrap_or(0))
+            };
+            column_families.push(ColumnFamilyStats {
+                name: name.to_string(),
+                estimated_keys: int(properties::ESTIMATE_NUM_KEYS)?,
+                live_data_bytes: int(properties::ESTIMATE_LIVE_DATA_SIZE)?,
+                sst_bytes: int(properties::TOTAL_SST_FILES_SIZE)?,
+                memtable_bytes: int(properties::CUR_SIZE_ALL_MEM_TABLES)?,
+                immutable_memtables: int(properties::NUM_IMMUTABLE_MEM_TABLE)?,
+                level0_files: int(&*properties::num_files_at_level(0))?,
+                pending_compaction_bytes: int(properties::ESTIMATE_PENDING_COMPACTION_BYTES)?,
+                compaction_pending: int(properties::COMPACTION_PENDING)? > 0,
+            });
+        }
+
+        let int = |property: &PropName| -> Result<u64> {
+            Ok(self.db.property_int_value(property)?.unwrap_or(0))
+        };
+        let write_stall = WriteStallStatus {
+            stopped: int(properties::IS_WRITE_STOPPED)? > 0,
+            delayed_write_rate: int(properties::ACTUAL_DELAYED_WRITE_RATE)?,
+            warnings: column_families
+                .iter()
+                .flat_map(|cf| stall_warnings(cf, &self.tuning))
+                .collect(),
+        };
+
+        Ok(DbStats {
+            total_sst_bytes: column_families.iter().map(|cf| cf.sst_bytes).sum(),
+            total_memtable_bytes: column_families.iter().map(|cf| cf.memtable_bytes).sum(),
+            column_families,
+            running_compactions: int(properties::NUM_RUNNING_COMPACTIONS)?,
+            manual_compaction_running: self.compacting.load(Ordering::SeqCst),
+            block_cache_usage: self.block_cache.get_usage() as u64,
+            block_cache_capacity: self.tuning.block_cache_mb as u64 * 1024 * 1024,
+            write_stall,
+            tuning: self.tuning.clone(),
+        })
+    }
+
+    /// Compact one column family, or all of them, on a background thread.
+    /// Returns the column families being compacted; fails if a manual
+    /// compaction is already running.
+    pub fn compact(&self, cf: Option<&str>) -> Result<Vec<String>> {
+        let names: Vec<String> = match cf {
+            Some(name) => {
+                self.cf_handle(name)?;
+                vec![name.to_string()]
+            }
+            None => all_column_families()
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        };
+        if self.compacting.swap(true, Ordering::SeqCst) {
+            return Err(anyhow!("A manual compaction is already running"));
+        }
+
+        let db = self.clone();
+        let compacting = names.clone();
+        std::thread::spawn(move || {
+            let started = std::time::Instant::now();
+            for name in &compacting {
+                if let Err(e) = db.compact_cf(name) {
+                    warn!("Manual compaction of {} failed: {}", name, e);
+                }
+            }
+            info!(
+                "Manual compaction of {} column families finished in {:?}",
+                compacting.len(),
+                started.elapsed()
+            );
+            db.compacting.store(false, Ordering::SeqCst);
+        });
+        Ok(names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_db_stats_and_compaction() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = RocksDB::open(temp_dir.path()).unwrap();
+        for i in 0u32..100 {
+            db.put_cf("blocks", &i.to_be_bytes(), &[0u8; 64]).unwrap();
+        }
+        db.flush().unwrap();
+
+        let stats = db.db_stats().unwrap();
+        assert_eq!(stats.column_families.len(), all_column_families().len());
+        let blocks = stats
+            .column_families
+            .iter()
+            .find(|cf| cf.name == "blocks")
+            .unwrap();
+        assert!(blocks.estimated_keys > 0);
+        assert!(blocks.sst_bytes > 0);
+        assert!(!stats.write_stall.is_stalled());
+        assert_eq!(stats.tuning, DbTuning::default());
+
+        assert!(db.compact(Some("missing")).is_err());
+        assert_eq!(
+            db.compact(Some("blocks")).unwrap(),
+            vec!["blocks".to_string()]
+        );
+        while db.db_stats().unwrap().manual_compaction_running {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_stall_warnings() {
+        let tuning = DbTuning::default();
+        let mut cf = ColumnFamilyStats {
+            name: "state".to_string(),
+            ..Default::default()
+        };
+        assert!(stall_warnings(&cf, &tuning).is_empty());
+
+        cf.level0_files = 24;
+        cf.immutable_memtables = 2;
+        let warnings = stall_warnings(&cf, &tuning);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.iter().all(|w| w.column_family == "state"));
+
+        assert!(DbTuning {
+            level0_slowdown_writes_trigger: 40,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
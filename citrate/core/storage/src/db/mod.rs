@@ -2,7 +2,9 @@
 
 // Database module
 pub mod column_families;
+pub mod db_admin;
 pub mod optimizations;
 pub mod rocks_db;
 
+pub use db_admin::{ColumnFamilyStats, DbStats, DbTuning, StallWarning, WriteStallStatus};
 pub use rocks_db::RocksDB;
@@ -1,9 +1,11 @@
 // citrate/core/storage/src/db/rocks_db.rs
 
 use super::column_families::all_column_families;
+use super::db_admin::DbTuning;
 use anyhow::Result;
-use rocksdb::{ColumnFamilyDescriptor, Options, WriteBatch, DB};
+use rocksdb::{BlockBasedOptions, Cache, ColumnFamilyDescriptor, Options, WriteBatch, DB};
 use std::path::Path;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use tracing::{debug, info};
 
@@ -12,12 +14,24 @@ type KvItem = (Box<[u8]>, Box<[u8]>);
 
 /// RocksDB wrapper for blockchain storage
 pub struct RocksDB {
-    db: Arc<DB>,
+    pub(super) db: Arc<DB>,
+    pub(super) tuning: DbTuning,
+    /// Block cache shared by all column families
+    pub(super) block_cache: Cache,
+    /// Set while a manual compaction runs
+    pub(super) compacting: Arc<AtomicBool>,
 }
 
 impl RocksDB {
     /// Open database with default options
     pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with_tuning(path, &DbTuning::default())
+    }
+
+    /// Open database with the given block cache, bloom filter and write
+    /// buffer settings
+    pub fn open_with_tuning(path: impl AsRef<Path>, tuning: &DbTuning) -> Result<Self> {
+        tuning.validate()?;
         let mut db_opts = Options::default();
         db_opts.create_if_missing(true);
         db_opts.create_missing_column_families(true);
@@ -30,18 +44,34 @@ impl RocksDB {
         db_opts.set_compression_type(compression);
 
         // Performance optimizations
-        db_opts.set_write_buffer_size(128 * 1024 * 1024); // 128MB
-        db_opts.set_max_write_buffer_number(3);
         db_opts.set_target_file_size_base(64 * 1024 * 1024); // 64MB
         db_opts.set_max_bytes_for_level_base(512 * 1024 * 1024); // 512MB
         db_opts.increase_parallelism(num_cpus::get() as i32);
 
+        let block_cache = Cache::new_lru_cache(tuning.block_cache_mb * 1024 * 1024);
+        let mut block_opts = BlockBasedOptions::default();
+        block_opts.set_block_cache(&block_cache);
+        block_opts.set_block_size(tuning.block_size_kb * 1024);
+        if tuning.bloom_bits_per_key > 0.0 {
+            block_opts.set_bloom_filter(tuning.bloom_bits_per_key, false);
+        }
+
         // Create column family descriptors
         let cfs: Vec<ColumnFamilyDescriptor> = all_column_families()
             .into_iter()
             .map(|name| {
                 let mut cf_opts = Options::default();
                 cf_opts.set_compression_type(compression);
+                cf_opts.set_block_based_table_factory(&block_opts);
+                cf_opts.set_write_buffer_size(tuning.write_buffer_mb * 1024 * 1024);
+                cf_opts.set_max_write_buffer_number(tuning.max_write_buffer_number);
+                cf_opts.set_level_zero_slowdown_writes_trigger(
+                    tuning.level0_slowdown_writes_trigger,
+                );
+                cf_opts.set_level_zero_stop_writes_trigger(tuning.level0_stop_writes_trigger);
+                cf_opts.set_soft_pending_compaction_bytes_limit(
+                    tuning.soft_pending_compaction_gb * 1024 * 1024 * 1024,
+                );
                 ColumnFamilyDescriptor::new(name, cf_opts)
             })
             .collect();
@@ -49,7 +79,12 @@ impl RocksDB {
         let db = DB::open_cf_descriptors(&db_opts, path, cfs)?;
 
         info!("RocksDB opened successfully");
-        Ok(Self { db: Arc::new(db) })
+        Ok(Self {
+            db: Arc::new(db),
+            tuning: tuning.clone(),
+            block_cache,
+            compacting: Arc::new(AtomicBool::new(false)),
+        })
     }
 
     /// Get a value from a column family
@@ -117,6 +152,22 @@ impl RocksDB {
             .map(|r| r.unwrap()))
     }
 
+    /// Get iterator over a column family starting at `start` (inclusive)
+    pub fn iter_cf_from(
+        &self,
+        cf: &str,
+        start: &[u8],
+    ) -> Result<impl Iterator<Item = KvItem> + '_> {
+        let cf_handle = self.cf_handle(cf)?;
+        Ok(self
+            .db
+            .iterator_cf(
+                &cf_handle,
+                rocksdb::IteratorMode::From(start, rocksdb::Direction::Forward),
+            )
+            .map(|r| r.unwrap()))
+    }
+
     /// Get iterator with prefix for a column family
     pub fn prefix_iter_cf(
         &self,
@@ -139,8 +190,28 @@ impl RocksDB {
         Ok(())
     }
 
+    /// Delete every key in a column family and compact it, returning the
+    /// number of keys removed
+    pub fn clear_cf(&self, cf: &str) -> Result<u64> {
+        const BATCH_KEYS: u64 = 10_000;
+
+        let mut batch = self.batch();
+        let mut removed = 0;
+        for (key, _) in self.iter_cf(cf)? {
+            self.batch_delete_cf(&mut batch, cf, &key)?;
+            removed += 1;
+            if removed % BATCH_KEYS == 0 {
+                self.write_batch(std::mem::take(&mut batch))?;
+            }
+        }
+        self.write_batch(batch)?;
+        self.compact_cf(cf)?;
+        info!("Cleared {} keys from column family {}", removed, cf);
+        Ok(removed)
+    }
+
     /// Get column family handle
-    fn cf_handle(&self, name: &str) -> Result<&rocksdb::ColumnFamily> {
+    pub(super) fn cf_handle(&self, name: &str) -> Result<&rocksdb::ColumnFamily> {
         self.db
             .cf_handle(name)
             .ok_or_else(|| anyhow::anyhow!("Column family {} not found", name))
@@ -169,6 +240,9 @@ impl Clone for RocksDB {
     fn clone(&self) -> Self {
         Self {
             db: Arc::clone(&self.db),
+            tuning: self.tuning.clone(),
+            block_cache: self.block_cache.clone(),
+            compacting: Arc::clone(&self.compacting),
         }
     }
 }
@@ -220,4 +294,18 @@ mod tests {
             Some(b"value2".to_vec())
         );
     }
+
+    #[test]
+    fn test_clear_cf() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = RocksDB::open(temp_dir.path()).unwrap();
+
+        db.put_cf("peers", b"10.0.0.1", b"banned").unwrap();
+        db.put_cf("peers", b"10.0.0.2", b"ok").unwrap();
+        db.put_cf("blocks", b"key1", b"value1").unwrap();
+
+        assert_eq!(db.clear_cf("peers").unwrap(), 2);
+        assert_eq!(db.iter_cf("peers").unwrap().count(), 0);
+        assert!(db.exists_cf("blocks", b"key1").unwrap());
+    }
 }
@@ -55,6 +55,13 @@ pub enum ModelType {
     Custom(String),
 }
 
+/// Result of `IPFSService::stat`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentStat {
+    pub size: u64,
+    pub is_directory: bool,
+}
+
 /// IPFS service for model storage and retrieval
 pub struct IPFSService {
     api_endpoint: String,
@@ -279,6 +286,56 @@ impl IPFSService {
         }
     }
 
+    /// Size and type of the content at `path` (a CID, optionally followed
+    /// by `/` and a path inside it)
+    pub async fn stat(&self, path: &str) -> Result<ContentStat> {
+        let url = format!("{}/api/v0/files/stat", self.api_endpoint);
+        let response = self
+            .client
+            .post(&url)
+            .query(&[("arg", format!("/ipfs/{}", path))])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("IPFS stat {}: {}", path, response.text().await?));
+        }
+
+        #[derive(Deserialize)]
+        struct StatResponse {
+            #[serde(rename = "Size")]
+            size: u64,
+            #[serde(rename = "Type")]
+            kind: String,
+        }
+
+        let stat: StatResponse = response.json().await?;
+        Ok(ContentStat {
+            size: stat.size,
+            is_directory: stat.kind == "directory",
+        })
+    }
+
+    /// Start reading `length` bytes (or the rest) of the file at `path`
+    /// from `offset`. The body is left unread so callers can stream it.
+    pub async fn cat_range(
+        &self,
+        path: &str,
+        offset: u64,
+        length: Option<u64>,
+    ) -> Result<reqwest::Response> {
+        let url = format!("{}/api/v0/cat", self.api_endpoint);
+        let mut query = vec![("arg", path.to_string()), ("offset", offset.to_string())];
+        if let Some(length) = length {
+            query.push(("length", length.to_string()));
+        }
+
+        let response = self.client.post(&url).query(&query).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("IPFS cat {}: {}", path, response.text().await?));
+        }
+        Ok(response)
+    }
+
     /// Record pinning information for an external provider.
     pub fn record_external_pin(
         &mut self,
@@ -10,8 +10,8 @@ pub mod state_manager;
 
 use anyhow::Result;
 use cache::Cache;
-use chain::{BlockStore, TransactionStore};
-use db::RocksDB;
+use chain::{BlockStore, DagStatsStore, LogIndex, StatsHistoryStore, TransactionStore};
+use db::{DbTuning, RocksDB};
 use citrate_consensus::types::Hash;
 use pruning::{Pruner, PruningConfig};
 use state::StateStore;
@@ -23,7 +23,10 @@ use tracing::info;
 pub struct StorageManager {
     pub db: Arc<RocksDB>,
     pub blocks: Arc<BlockStore>,
+    pub dag_stats: Arc<DagStatsStore>,
+    pub stats_history: Arc<StatsHistoryStore>,
     pub transactions: Arc<TransactionStore>,
+    pub logs: Arc<LogIndex>,
     pub state: Arc<StateStore>,
     pub pruner: Arc<Pruner>,
 
@@ -35,11 +38,39 @@ pub struct StorageManager {
 impl StorageManager {
     /// Create a new storage manager
     pub fn new(path: impl AsRef<Path>, pruning_config: PruningConfig) -> Result<Self> {
-        let db = Arc::new(RocksDB::open(path)?);
+        Self::with_archive(path, pruning_config, false)
+    }
+
+    /// Create a storage manager, keeping per-block state history when
+    /// `archive` is set
+    pub fn with_archive(
+        path: impl AsRef<Path>,
+        pruning_config: PruningConfig,
+        archive: bool,
+    ) -> Result<Self> {
+        Self::with_tuning(path, pruning_config, archive, &DbTuning::default())
+    }
+
+    /// Create a storage manager whose database uses the given block cache,
+    /// bloom filter and write buffer settings
+    pub fn with_tuning(
+        path: impl AsRef<Path>,
+        pruning_config: PruningConfig,
+        archive: bool,
+        tuning: &DbTuning,
+    ) -> Result<Self> {
+        let db = Arc::new(RocksDB::open_with_tuning(path, tuning)?);
 
         let blocks = Arc::new(BlockStore::new(db.clone()));
-        let transactions = Arc::new(TransactionStore::new(db.clone()));
-        let state = Arc::new(StateStore::new(db.clone()));
+        let dag_stats = blocks.dag_stats();
+        let stats_history = Arc::new(StatsHistoryStore::new(db.clone(), blocks.clone()));
+        let logs = blocks.logs();
+        let transactions = Arc::new(TransactionStore::with_log_index(db.clone(), logs.clone()));
+        let state = Arc::new(if archive {
+            StateStore::with_archive(db.clone())
+        } else {
+            StateStore::new(db.clone())
+        });
 
         let pruner = Arc::new(Pruner::new(
             db.clone(),
@@ -48,12 +79,15 @@ impl StorageManager {
             pruning_config,
         ));
 
-        info!("Storage manager initialized");
+        info!("Storage manager initialized (archive: {})", archive);
 
         Ok(Self {
             db,
             blocks,
+            dag_stats,
+            stats_history,
             transactions,
+            logs,
             state,
             pruner,
             block_cache: Cache::new(1000),
@@ -6,6 +6,9 @@ use crate::db::RocksDB;
 use crate::state::StateStore;
 use anyhow::Result;
 use citrate_consensus::types::Hash;
+use citrate_execution::types::TransactionReceipt;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::time::interval;
@@ -18,6 +21,9 @@ pub struct PruningConfig {
     pub keep_blocks: u64,
     /// Number of states to keep
     pub keep_states: u64,
+    /// Number of blocks to keep transaction receipts for, independently of
+    /// the blocks themselves
+    pub keep_receipts: u64,
     /// Pruning interval
     pub interval: Duration,
     /// Maximum items to prune per batch
@@ -31,6 +37,7 @@ impl Default for PruningConfig {
         Self {
             keep_blocks: 100_000,
             keep_states: 10_000,
+            keep_receipts: 100_000,
             interval: Duration::from_secs(3600), // 1 hour
             batch_size: 1000,
             auto_prune: true,
@@ -79,10 +86,12 @@ impl Pruner {
             match self.prune().await {
                 Ok(stats) => {
                     info!(
-                        "Pruning completed in {:?}: {} blocks, {} states pruned",
+                        "Pruning completed in {:?}: {} blocks, {} receipts, {} states ({} bytes)",
                         start.elapsed(),
                         stats.blocks_pruned,
-                        stats.states_pruned
+                        stats.receipts_pruned,
+                        stats.states_pruned,
+                        stats.total_bytes()
                     );
                 }
                 Err(e) => {
@@ -94,35 +103,70 @@ impl Pruner {
 
     /// Perform pruning
     pub async fn prune(&self) -> Result<PruningStats> {
-        let mut stats = PruningStats::default();
+        let stats = self.run(false).await?;
+
+        // Compact database after pruning
+        self.compact().await?;
+
+        Ok(stats)
+    }
+
+    /// Dry run: count what `prune` would remove and estimate the bytes it
+    /// would free per column family, without deleting anything
+    pub async fn preview(&self) -> Result<PruningStats> {
+        self.run(true).await
+    }
+
+    async fn run(&self, dry_run: bool) -> Result<PruningStats> {
+        let mut stats = PruningStats {
+            dry_run,
+            ..Default::default()
+        };
 
         // Get current height
         let current_height = self.block_store.get_latest_height()?;
+        stats.current_height = current_height;
 
         if current_height > self.config.keep_blocks {
             let prune_height = current_height - self.config.keep_blocks;
-            stats.blocks_pruned = self.prune_blocks_before(prune_height).await?;
+            self.prune_blocks_before(prune_height, &mut stats).await?;
         }
 
-        if current_height > self.config.keep_states {
-            let prune_state_height = current_height - self.config.keep_states;
-            stats.states_pruned = self.prune_states_before(prune_state_height).await?;
+        if current_height > self.config.keep_receipts {
+            let prune_receipt_height = current_height - self.config.keep_receipts;
+            self.prune_receipts_before(prune_receipt_height, &mut stats)
+                .await?;
         }
 
-        // Compact database after pruning
-        self.compact().await?;
+        if current_height > self.config.keep_states {
+            // States of the blocks pruned above lose their headers and go too
+            let prune_state_height = (current_height - self.config.keep_states)
+                .max(current_height.saturating_sub(self.config.keep_blocks));
+            self.prune_states_before(prune_state_height, &mut stats)
+                .await?;
+        }
 
         Ok(stats)
     }
 
     /// Prune blocks before specified height
-    async fn prune_blocks_before(&self, height: u64) -> Result<usize> {
+    async fn prune_blocks_before(&self, height: u64, stats: &mut PruningStats) -> Result<()> {
         let mut pruned = 0;
         let mut batch_count = 0;
 
         for h in 0..height {
             if let Some(hash) = self.block_store.get_block_by_height(h)? {
-                self.block_store.delete_block(&hash)?;
+                // Block, header and the height mapping
+                for cf in [CF_BLOCKS, CF_HEADERS] {
+                    if let Some(value) = self.db.get_cf(cf, hash.as_bytes())? {
+                        stats.add_bytes(cf, (hash.as_bytes().len() + value.len()) as u64);
+                    }
+                }
+                stats.add_bytes(CF_METADATA, (1 + 8 + hash.as_bytes().len()) as u64);
+
+                if !stats.dry_run {
+                    self.block_store.delete_block(&hash)?;
+                }
                 pruned += 1;
                 batch_count += 1;
 
@@ -135,16 +179,54 @@ impl Pruner {
         }
 
         debug!("Pruned {} blocks before height {}", pruned, height);
-        Ok(pruned)
+        stats.blocks_pruned = pruned;
+        Ok(())
+    }
+
+    /// Prune receipts of transactions included before specified height
+    async fn prune_receipts_before(&self, height: u64, stats: &mut PruningStats) -> Result<()> {
+        let mut pruned = 0usize;
+        let mut batch = self.db.batch();
+        let mut batch_count = 0usize;
+
+        for (key, value) in self.db.iter_cf(CF_RECEIPTS)? {
+            let Ok(receipt) = bincode::deserialize::<TransactionReceipt>(&value) else {
+                continue;
+            };
+            if receipt.block_number >= height {
+                continue;
+            }
+
+            stats.add_bytes(CF_RECEIPTS, (key.len() + value.len()) as u64);
+            if !stats.dry_run {
+                self.db.batch_delete_cf(&mut batch, CF_RECEIPTS, &key)?;
+            }
+            pruned += 1;
+            batch_count += 1;
+            if batch_count >= self.config.batch_size {
+                self.db.write_batch(batch)?;
+                batch = self.db.batch();
+                batch_count = 0;
+                tokio::task::yield_now().await;
+            }
+        }
+
+        if batch_count > 0 {
+            self.db.write_batch(batch)?;
+        }
+
+        debug!("Pruned {} receipts before height {}", pruned, height);
+        stats.receipts_pruned = pruned;
+        Ok(())
     }
 
     /// Prune state snapshots before specified height
-    async fn prune_states_before(&self, height: u64) -> Result<usize> {
+    async fn prune_states_before(&self, height: u64, stats: &mut PruningStats) -> Result<()> {
         let mut pruned = 0usize;
         let mut batch = self.db.batch();
         let mut batch_count = 0usize;
 
-        for (key, _value) in self.db.iter_cf(CF_STATE)? {
+        for (key, value) in self.db.iter_cf(CF_STATE)? {
             let key_bytes = key.as_ref();
             if key_bytes.is_empty() {
                 continue;
@@ -169,8 +251,10 @@ impl Pruner {
             };
 
             if should_prune {
-                self.db
-                    .batch_delete_cf(&mut batch, CF_STATE, key_bytes)?;
+                stats.add_bytes(CF_STATE, (key_bytes.len() + value.len()) as u64);
+                if !stats.dry_run {
+                    self.db.batch_delete_cf(&mut batch, CF_STATE, key_bytes)?;
+                }
                 pruned += 1;
                 batch_count += 1;
 
@@ -187,7 +271,8 @@ impl Pruner {
             self.db.write_batch(batch)?;
         }
 
-        Ok(pruned)
+        stats.states_pruned = pruned;
+        Ok(())
     }
 
     /// Compact the database
@@ -220,60 +305,50 @@ impl Pruner {
     }
 }
 
-/// Pruning statistics
-#[derive(Default, Debug)]
+/// Pruning statistics; for a dry run, what would be pruned
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PruningStats {
     pub blocks_pruned: usize,
     pub states_pruned: usize,
     pub transactions_pruned: usize,
+    pub receipts_pruned: usize,
+    /// Key and value bytes removed per column family. Space on disk is
+    /// reclaimed by the compaction that follows, and may differ with
+    /// compression.
+    pub bytes_by_column_family: BTreeMap<String, u64>,
+    pub current_height: u64,
+    pub dry_run: bool,
+}
+
+impl PruningStats {
+    fn add_bytes(&mut self, cf: &str, bytes: u64) {
+        *self
+            .bytes_by_column_family
+            .entry(cf.to_string())
+            .or_default() += bytes;
+    }
+
+    /// Bytes removed across all column families
+    pub fn total_bytes(&self) -> u64 {
+        self.bytes_by_column_family.values().sum()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::chain::BlockStore;
+    use crate::chain::{BlockStore, TransactionStore};
     use crate::state::StateStore;
+    use citrate_consensus::types::{
+        Block, BlockHeader, GhostDagParams, Hash, PublicKey, Signature, VrfProof,
+    };
+    use citrate_execution::types::Address;
     use tempfile::TempDir;
 
-    #[tokio::test]
-    async fn test_pruning_config() {
-        let config = PruningConfig::default();
-        assert_eq!(config.keep_blocks, 100_000);
-        assert_eq!(config.keep_states, 10_000);
-        assert!(config.auto_prune);
-    }
-
-    #[tokio::test]
-    async fn test_pruner_creation() {
-        let temp_dir = TempDir::new().unwrap();
-        let db = Arc::new(RocksDB::open(temp_dir.path()).unwrap());
-        let block_store = Arc::new(BlockStore::new(db.clone()));
-        let state_store = Arc::new(StateStore::new(db.clone()));
-
-        let config = PruningConfig {
-            keep_blocks: 1000,
-            keep_states: 100,
-            interval: Duration::from_secs(60),
-            batch_size: 100,
-            auto_prune: false,
-        };
-
-        let pruner = Pruner::new(db, block_store, state_store, config);
-        assert_eq!(pruner.get_config().keep_blocks, 1000);
-    }
-
-    #[tokio::test]
-    async fn test_prune_blocks_respects_keep_blocks() {
-        let temp_dir = TempDir::new().unwrap();
-        let db = Arc::new(RocksDB::open(temp_dir.path()).unwrap());
-        let block_store = Arc::new(BlockStore::new(db.clone()));
-        let state_store = Arc::new(StateStore::new(db.clone()));
-
-        // Seed blocks at heights 0..=4
-        use citrate_consensus::types::{
-            Block, BlockHeader, GhostDagParams, Hash, PublicKey, Signature, VrfProof,
-        };
-        for h in 0..=4u64 {
+    /// Store a chain of empty blocks at heights 0..=top
+    fn seed_blocks(block_store: &BlockStore, top: u64) {
+        for h in 0..=top {
             let block = Block {
                 header: BlockHeader {
                     version: 1,
@@ -297,6 +372,7 @@ mod tests {
                     base_fee_per_gas: 0,
                     gas_used: 0,
                     gas_limit: 30_000_000,
+                    epoch_commitment: Hash::default(),
                 },
                 state_root: Hash::default(),
                 tx_root: Hash::default(),
@@ -310,10 +386,49 @@ mod tests {
             };
             block_store.put_block(&block).unwrap();
         }
+    }
+
+    #[tokio::test]
+    async fn test_pruning_config() {
+        let config = PruningConfig::default();
+        assert_eq!(config.keep_blocks, 100_000);
+        assert_eq!(config.keep_states, 10_000);
+        assert!(config.auto_prune);
+    }
+
+    #[tokio::test]
+    async fn test_pruner_creation() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Arc::new(RocksDB::open(temp_dir.path()).unwrap());
+        let block_store = Arc::new(BlockStore::new(db.clone()));
+        let state_store = Arc::new(StateStore::new(db.clone()));
+
+        let config = PruningConfig {
+            keep_blocks: 1000,
+            keep_states: 100,
+            keep_receipts: 1000,
+            interval: Duration::from_secs(60),
+            batch_size: 100,
+            auto_prune: false,
+        };
+
+        let pruner = Pruner::new(db, block_store, state_store, config);
+        assert_eq!(pruner.get_config().keep_blocks, 1000);
+    }
+
+    #[tokio::test]
+    async fn test_prune_blocks_respects_keep_blocks() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Arc::new(RocksDB::open(temp_dir.path()).unwrap());
+        let block_store = Arc::new(BlockStore::new(db.clone()));
+        let state_store = Arc::new(StateStore::new(db.clone()));
+
+        seed_blocks(&block_store, 4);
 
         let config = PruningConfig {
             keep_blocks: 2,
             keep_states: 0,
+            keep_receipts: 2,
             interval: Duration::from_secs(60),
             batch_size: 100,
             auto_prune: false,
@@ -338,4 +453,74 @@ mod tests {
             .unwrap()
             .is_none());
     }
+
+    #[tokio::test]
+    async fn test_preview_reports_without_pruning() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Arc::new(RocksDB::open(temp_dir.path()).unwrap());
+        let block_store = Arc::new(BlockStore::new(db.clone()));
+        let state_store = Arc::new(StateStore::new(db.clone()));
+        let tx_store = TransactionStore::new(db.clone());
+
+        seed_blocks(&block_store, 6);
+        for h in 0..=6u64 {
+            let receipt = TransactionReceipt {
+                tx_hash: Hash::new([100 + h as u8; 32]),
+                block_hash: Hash::new([h as u8; 32]),
+                block_number: h,
+                from: Address([1; 20]),
+                to: None,
+                gas_used: 21_000,
+                status: true,
+                logs: vec![],
+                output: vec![],
+            };
+            tx_store.put_receipt(&receipt.tx_hash, &receipt).unwrap();
+        }
+
+        // Receipts are kept for fewer blocks than the blocks themselves
+        let config = PruningConfig {
+            keep_blocks: 4,
+            keep_states: 4,
+            keep_receipts: 2,
+            interval: Duration::from_secs(60),
+            batch_size: 2,
+            auto_prune: false,
+        };
+        let pruner = Pruner::new(db, block_store.clone(), state_store, config);
+
+        let preview = pruner.preview().await.unwrap();
+        assert!(preview.dry_run);
+        assert_eq!(preview.current_height, 6);
+        assert_eq!(preview.blocks_pruned, 2);
+        assert_eq!(preview.receipts_pruned, 4);
+        assert!(preview.bytes_by_column_family[CF_BLOCKS] > 0);
+        assert!(preview.bytes_by_column_family[CF_RECEIPTS] > 0);
+        assert!(block_store
+            .get_block(&Hash::new([0; 32]))
+            .unwrap()
+            .is_some());
+        assert!(tx_store
+            .get_receipt(&Hash::new([100; 32]))
+            .unwrap()
+            .is_some());
+
+        let stats = pruner.prune().await.unwrap();
+        assert!(!stats.dry_run);
+        assert_eq!(stats.blocks_pruned, preview.blocks_pruned);
+        assert_eq!(stats.receipts_pruned, preview.receipts_pruned);
+        assert_eq!(stats.bytes_by_column_family, preview.bytes_by_column_family);
+        assert!(tx_store
+            .get_receipt(&Hash::new([103; 32]))
+            .unwrap()
+            .is_none());
+        assert!(tx_store
+            .get_receipt(&Hash::new([104; 32]))
+            .unwrap()
+            .is_some());
+        assert!(block_store
+            .get_block(&Hash::new([2; 32]))
+            .unwrap()
+            .is_some());
+    }
 }
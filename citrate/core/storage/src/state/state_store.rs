@@ -4,13 +4,20 @@ use crate::db::{column_families::*, RocksDB};
 use anyhow::Result;
 use citrate_consensus::types::Hash;
 use citrate_execution::executor::StateStoreTrait;
+use citrate_execution::state::{BlockStateDiff, ChangedKeys};
 use citrate_execution::types::{AccountState, Address, JobId, ModelId, ModelState, TrainingJob};
+use sha3::{Digest, Keccak256};
 use std::sync::Arc;
 use tracing::{debug, info};
 
+/// Archive key holding the first and last archived heights
+const ARCHIVE_RANGE_KEY: &[u8] = b"m";
+
 /// State storage manager
 pub struct StateStore {
     db: Arc<RocksDB>,
+    /// Keep per-block state history in `CF_ARCHIVE`
+    archive: bool,
 }
 
 impl StateStoreTrait for StateStore {
@@ -33,11 +40,133 @@ impl StateStoreTrait for StateStore {
         debug!("Stored contract code with hash {}", code_hash);
         Ok(())
     }
+
+    fn is_archive(&self) -> bool {
+        self.archive
+    }
+
+    fn archive_block(&self, diff: &BlockStateDiff) -> Result<()> {
+        if !self.archive {
+            return Ok(());
+        }
+
+        let mut batch = self.db.batch();
+        for (address, account) in &diff.accounts {
+            self.db.batch_put_cf(
+                &mut batch,
+                CF_ARCHIVE,
+                &archived_account_key(address, diff.height),
+                &bincode::serialize(account)?,
+            )?;
+        }
+        for change in &diff.storage {
+            self.db.batch_put_cf(
+                &mut batch,
+                CF_ARCHIVE,
+                &archived_storage_key(&change.address, &change.key, diff.height),
+                &bincode::serialize(&change.value)?,
+            )?;
+        }
+        self.db.batch_put_cf(
+            &mut batch,
+            CF_ARCHIVE,
+            &archive_height_key(b'r', diff.height),
+            diff.state_root.as_bytes(),
+        )?;
+        self.db.batch_put_cf(
+            &mut batch,
+            CF_ARCHIVE,
+            &archive_height_key(b'c', diff.height),
+            &bincode::serialize(&ChangedKeys::of(diff))?,
+        )?;
+
+        let (first, last) = match self.archived_range()? {
+            Some((first, last)) => (first.min(diff.height), last.max(diff.height)),
+            None => (diff.height, diff.height),
+        };
+        let mut range = first.to_be_bytes().to_vec();
+        range.extend_from_slice(&last.to_be_bytes());
+        self.db
+            .batch_put_cf(&mut batch, CF_ARCHIVE, ARCHIVE_RANGE_KEY, &range)?;
+
+        self.db.write_batch(batch)?;
+        debug!(
+            "Archived state at height {} ({} accounts, {} storage slots)",
+            diff.height,
+            diff.accounts.len(),
+            diff.storage.len()
+        );
+        Ok(())
+    }
+
+    fn archived_range(&self) -> Result<Option<(u64, u64)>> {
+        match self.db.get_cf(CF_ARCHIVE, ARCHIVE_RANGE_KEY)? {
+            Some(bytes) if bytes.len() == 16 => Ok(Some((
+                u64::from_be_bytes(bytes[..8].try_into()?),
+                u64::from_be_bytes(bytes[8..].try_into()?),
+            ))),
+            _ => Ok(None),
+        }
+    }
+
+    fn state_root_at(&self, height: u64) -> Result<Option<Hash>> {
+        Ok(self
+            .db
+            .get_cf(CF_ARCHIVE, &archive_height_key(b'r', height))?
+            .map(|bytes| Hash::from_bytes(&bytes)))
+    }
+
+    fn account_at(&self, address: &Address, height: u64) -> Result<Option<AccountState>> {
+        match self.latest_version(&archived_account_prefix(address), height)? {
+            Some(bytes) => Ok(Some(bincode::deserialize(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn storage_at(&self, address: &Address, key: &[u8], height: u64) -> Result<Option<Vec<u8>>> {
+        match self.latest_version(&archived_storage_prefix(address, key), height)? {
+            Some(bytes) => Ok(bincode::deserialize(&bytes)?),
+            None => Ok(None),
+        }
+    }
+
+    fn changed_since(&self, height: u64) -> Result<ChangedKeys> {
+        let mut changed = ChangedKeys::default();
+        let start = archive_height_key(b'c', height.saturating_add(1));
+        for (key, value) in self.db.iter_cf_from(CF_ARCHIVE, &start)? {
+            if key.first() != Some(&b'c') {
+                break;
+            }
+            changed.merge(bincode::deserialize(&value)?);
+        }
+        Ok(changed)
+    }
 }
 
 impl StateStore {
     pub fn new(db: Arc<RocksDB>) -> Self {
-        Self { db }
+        Self { db, archive: false }
+    }
+
+    /// State store that also keeps per-block state history, so state at
+    /// past heights stays queryable
+    pub fn with_archive(db: Arc<RocksDB>) -> Self {
+        Self { db, archive: true }
+    }
+
+    /// Newest archived value under `prefix` written at or before `height`.
+    /// Versions are keyed by inverted height, so it is the first key at or
+    /// after `prefix ++ !height`.
+    fn latest_version(&self, prefix: &[u8], height: u64) -> Result<Option<Vec<u8>>> {
+        let mut start = prefix.to_vec();
+        start.extend_from_slice(&(!height).to_be_bytes());
+        let found = self
+            .db
+            .iter_cf_from(CF_ARCHIVE, &start)?
+            .next()
+            .filter(|(key, _)| key.len() == prefix.len() + 8 && key.starts_with(prefix))
+            .map(|(_, value)| value.to_vec());
+        Ok(found)
     }
 
     /// Store account state
@@ -161,6 +290,18 @@ impl StateStore {
         }
     }
 
+    /// Get all registered models
+    pub fn get_all_models(&self) -> Result<Vec<(ModelId, ModelState)>> {
+        let mut models = Vec::new();
+        for (key, value) in self.db.iter_cf(CF_MODELS)? {
+            if key.len() == 32 {
+                let model: ModelState = bincode::deserialize(&value)?;
+                models.push((ModelId(Hash::from_bytes(&key)), model));
+            }
+        }
+        Ok(models)
+    }
+
     /// Get models by owner
     pub fn get_models_by_owner(&self, owner: &Address) -> Result<Vec<ModelId>> {
         let prefix = owner_model_prefix(owner);
@@ -259,6 +400,7 @@ impl StateStore {
         self.db.compact_cf(CF_CODE)?;
         self.db.compact_cf(CF_MODELS)?;
         self.db.compact_cf(CF_TRAINING)?;
+        self.db.compact_cf(CF_ARCHIVE)?;
         Ok(())
     }
 }
@@ -304,9 +446,42 @@ fn snapshot_account_key(block_hash: &Hash, address: &Address) -> Vec<u8> {
     key
 }
 
+fn archive_height_key(tag: u8, height: u64) -> Vec<u8> {
+    let mut key = vec![tag];
+    key.extend_from_slice(&height.to_be_bytes());
+    key
+}
+
+fn archived_account_prefix(address: &Address) -> Vec<u8> {
+    let mut prefix = vec![b'a'];
+    prefix.extend_from_slice(&address.0);
+    prefix
+}
+
+fn archived_account_key(address: &Address, height: u64) -> Vec<u8> {
+    let mut key = archived_account_prefix(address);
+    key.extend_from_slice(&(!height).to_be_bytes());
+    key
+}
+
+// Storage keys vary in length, so they are hashed to keep prefixes unique
+fn archived_storage_prefix(address: &Address, storage_key: &[u8]) -> Vec<u8> {
+    let mut prefix = vec![b's'];
+    prefix.extend_from_slice(&address.0);
+    prefix.extend_from_slice(&Keccak256::digest(storage_key));
+    prefix
+}
+
+fn archived_storage_key(address: &Address, storage_key: &[u8], height: u64) -> Vec<u8> {
+    let mut key = archived_storage_prefix(address, storage_key);
+    key.extend_from_slice(&(!height).to_be_bytes());
+    key
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use citrate_execution::state::StorageChange;
     use citrate_execution::types::Address;
     use primitive_types::U256;
     use tempfile::TempDir;
@@ -354,4 +529,84 @@ mod tests {
         store.delete_storage(&address, b"key1").unwrap();
         assert!(store.get_storage(&address, b"key1").unwrap().is_none());
     }
+
+    #[test]
+    fn test_archive_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Arc::new(RocksDB::open(temp_dir.path()).unwrap());
+        let store = StateStore::with_archive(db);
+
+        let alice = Address([1; 20]);
+        let account = |balance: u64| AccountState {
+            balance: U256::from(balance),
+            ..Default::default()
+        };
+        let slot = |value: Option<&[u8]>| StorageChange {
+            address: alice,
+            key: b"key".to_vec(),
+            value: value.map(|v| v.to_vec()),
+        };
+
+        store
+            .archive_block(&BlockStateDiff {
+                height: 5,
+                state_root: Hash::new([5; 32]),
+                accounts: vec![(alice, account(100))],
+                storage: vec![slot(Some(b"one"))],
+            })
+            .unwrap();
+        store
+            .archive_block(&BlockStateDiff {
+                height: 8,
+                state_root: Hash::new([8; 32]),
+                accounts: vec![(alice, account(200))],
+                storage: vec![slot(None)],
+            })
+            .unwrap();
+
+        assert_eq!(store.archived_range().unwrap(), Some((5, 8)));
+        assert_eq!(store.state_root_at(5).unwrap(), Some(Hash::new([5; 32])));
+        assert!(store.state_root_at(6).unwrap().is_none());
+
+        let balance_at = |height| {
+            store
+                .account_at(&alice, height)
+                .unwrap()
+                .map(|a| a.balance.as_u64())
+        };
+        assert_eq!(balance_at(4), None);
+        assert_eq!(balance_at(5), Some(100));
+        assert_eq!(balance_at(7), Some(100));
+        assert_eq!(balance_at(8), Some(200));
+        assert_eq!(balance_at(100), Some(200));
+        assert!(store.account_at(&Address([2; 20]), 8).unwrap().is_none());
+
+        assert_eq!(
+            store.storage_at(&alice, b"key", 7).unwrap(),
+            Some(b"one".to_vec())
+        );
+        assert_eq!(store.storage_at(&alice, b"key", 8).unwrap(), None);
+
+        let changed = store.changed_since(5).unwrap();
+        assert_eq!(changed.accounts, vec![alice]);
+        assert_eq!(changed.storage, vec![(alice, b"key".to_vec())]);
+        assert_eq!(store.changed_since(8).unwrap(), ChangedKeys::default());
+    }
+
+    #[test]
+    fn test_non_archive_store_keeps_no_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Arc::new(RocksDB::open(temp_dir.path()).unwrap());
+        let store = StateStore::new(db);
+
+        store
+            .archive_block(&BlockStateDiff {
+                height: 1,
+                state_root: Hash::default(),
+                accounts: vec![(Address([1; 20]), AccountState::default())],
+                storage: vec![],
+            })
+            .unwrap();
+        assert!(store.archived_range().unwrap().is_none());
+    }
 }
@@ -30,6 +30,7 @@ fn create_test_block(num: u8, height: u64, parent: Option<Hash>) -> Block {
             base_fee_per_gas: 0,
             gas_used: 0,
             gas_limit: 30_000_000,
+            epoch_commitment: Hash::default(),
         },
         state_root: Hash::new([(height % 256) as u8; 32]),
         tx_root: Hash::new([(num + 1) as u8; 32]),
@@ -192,6 +193,7 @@ mod storage_tests {
         let config = PruningConfig {
             keep_blocks: 100,
             keep_states: 100,
+            keep_receipts: 100,
             interval: Duration::from_secs(60),
             batch_size: 1000,
             auto_prune: true,
@@ -30,6 +30,7 @@ fn create_test_block(num: u8, height: u64) -> Block {
             base_fee_per_gas: 0,
             gas_used: 0,
             gas_limit: 30_000_000,
+            epoch_commitment: Hash::default(),
         },
         state_root: Hash::default(),
         tx_root: Hash::default(),
@@ -149,6 +150,7 @@ mod storage_manager_tests {
         let config = PruningConfig {
             keep_blocks: 1000,
             keep_states: 1000,
+            keep_receipts: 1000,
             interval: std::time::Duration::from_secs(3600),
             batch_size: 1000,
             auto_prune: true,
@@ -2,22 +2,35 @@
 //!
 //! Exposes the agent module to the React frontend.
 
+use citrate_mcp::gguf_engine::SamplingParams;
+use citrate_mcp::safety::SafetyPolicy;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tauri::{Emitter, State};
+use tauri::{Manager, State};
 use tokio::sync::RwLock;
 
 use super::config::{
-    AgentConfig, AIProvider, ApiKeyManager, ApiKeyValidationResult,
+    AgentConfig, AIProvider, ApiKeyManager, ApiKeyValidationResult, RoutingStrategy,
     SecureApiKeyStore
 };
+use super::file_access::{DirectoryGrant, FileAccess, FileAuditEntry, FilePermissionManager};
 use super::intent::{Intent, IntentMatch};
 use super::llm::local::{scan_for_models, GGUFModelInfo};
+use super::llm::router::{ProviderHealth, RouteOverride};
+use super::memory::MemoryMatch;
 use super::orchestrator::{AgentOrchestrator, OrchestratorError, ProcessingResult};
+use super::prompts::{
+    ImportSummary, PromptAssignment, PromptDraft, PromptEntry, PromptLibrary, PromptSummary,
+};
+use super::scheduler::{ScheduledTask, TaskDraft, TaskRun, TaskScheduler};
 use super::session::{AgentSession, Message, PendingToolCall, SessionId, SessionState};
-use super::streaming::StreamStatus;
+use super::streaming::{run_streamed, StreamStatus};
 use super::AgentManager;
 
+use crate::event_bus::StateDeltaBus;
+use crate::i18n::localize_err;
+use crate::tr;
 use once_cell::sync::Lazy;
 
 // Global secure API key manager instance
@@ -101,7 +114,7 @@ pub async fn agent_create_session(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let orchestrator = manager.orchestrator();
     let session = orchestrator.read().await.create_session().await;
@@ -124,7 +137,7 @@ pub async fn agent_get_session(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let orchestrator = manager.orchestrator();
     let session = orchestrator.read().await.get_session(&session_id).await;
@@ -160,7 +173,7 @@ pub async fn agent_list_sessions(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let orchestrator = manager.orchestrator();
     let sessions = orchestrator.read().await.list_sessions().await;
@@ -176,7 +189,7 @@ pub async fn agent_delete_session(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let orchestrator = manager.orchestrator();
     let result = orchestrator.read().await.delete_session(&session_id).await;
@@ -188,24 +201,48 @@ pub async fn agent_delete_session(
 // =============================================================================
 
 /// Send a message to the agent and get a response
+///
+/// With a `stream_id`, the response is also streamed as it is generated on
+/// the `agent-stream:<stream_id>` event topic. The returned message holds
+/// the final response text. A `route` overrides provider routing for this
+/// message only, on top of the session's route.
 #[tauri::command]
 pub async fn agent_send_message(
+    app: tauri::AppHandle,
     state: State<'_, AgentState>,
     session_id: String,
     message: String,
+    stream_id: Option<String>,
+    route: Option<RouteOverride>,
 ) -> Result<AgentMessageResponse, String> {
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let orchestrator = manager.orchestrator();
-    let result = orchestrator
-        .read()
-        .await
-        .process_message(&session_id, &message)
-        .await
-        .map_err(|e| e.to_string())?;
+    let result = {
+        let guard = orchestrator.read().await;
+        let (agent, session, text, route) = (&*guard, &session_id, &message, route.as_ref());
+        match stream_id {
+            Some(stream_id) => {
+                run_streamed(
+                    &app,
+                    &agent.stream_manager(),
+                    session_id.clone(),
+                    stream_id,
+                    |tokens| async move {
+                        agent.process_message_streaming(session, text, route, &tokens).await
+                    },
+                )
+                .await?
+            }
+            None => agent
+                .process_message(session, text, route)
+                .await
+                .map_err(localize_err)?,
+        }
+    };
 
     // Check for pending tool approvals
     let session = orchestrator
@@ -230,6 +267,21 @@ pub async fn agent_send_message(
     })
 }
 
+/// Stop a response being streamed by `agent_send_message`
+#[tauri::command]
+pub async fn agent_cancel_stream(
+    state: State<'_, AgentState>,
+    stream_id: String,
+) -> Result<bool, String> {
+    let manager_guard = state.manager.read().await;
+    let manager = manager_guard
+        .as_ref()
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
+
+    let stream_manager = manager.orchestrator().read().await.stream_manager();
+    Ok(stream_manager.cancel_stream(&stream_id).await)
+}
+
 /// Get message history for a session
 #[tauri::command]
 pub async fn agent_get_messages(
@@ -239,7 +291,7 @@ pub async fn agent_get_messages(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let orchestrator = manager.orchestrator();
     let session = orchestrator
@@ -275,7 +327,7 @@ pub async fn agent_clear_history(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let orchestrator = manager.orchestrator();
     let session = orchestrator
@@ -302,7 +354,7 @@ pub async fn agent_get_pending_tools(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let orchestrator = manager.orchestrator();
     let session = orchestrator
@@ -338,7 +390,7 @@ pub async fn agent_approve_tool(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let orchestrator = manager.orchestrator();
     let session = orchestrator
@@ -348,8 +400,19 @@ pub async fn agent_approve_tool(
         .await
         .ok_or("Session not found")?;
 
-    let tool = session.approve_tool(&tool_id).await;
-    Ok(tool.is_some())
+    let Some(tool) = session.approve_tool(&tool_id).await else {
+        return Ok(false);
+    };
+
+    // Run the approved call; its outcome is recorded as an assistant message
+    orchestrator
+        .read()
+        .await
+        .execute_approved_tool(&session, &tool)
+        .await
+        .map_err(localize_err)?;
+
+    Ok(true)
 }
 
 /// Reject a pending tool call
@@ -362,7 +425,7 @@ pub async fn agent_reject_tool(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let orchestrator = manager.orchestrator();
     let session = orchestrator
@@ -387,7 +450,7 @@ pub async fn agent_get_config(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let cfg = config.read().await;
@@ -413,7 +476,7 @@ pub async fn agent_update_config(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let mut cfg = config.write().await;
@@ -481,7 +544,7 @@ pub async fn agent_load_local_model(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     // Update config to use local backend
     let config = manager.config();
@@ -518,7 +581,7 @@ pub async fn agent_get_active_model(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let cfg = config.read().await;
@@ -529,10 +592,72 @@ pub async fn agent_get_active_model(
         "temperature": cfg.llm.temperature,
         "max_tokens": cfg.llm.max_tokens,
         "context_size": cfg.llm.context_size,
+        "sampling": cfg.llm.sampling(),
         "has_api_key": cfg.llm.api_key.is_some(),
     }))
 }
 
+/// Set temperature, top-k/top-p/min-p, repetition penalty, stop sequences
+/// and logit bias for generation
+#[tauri::command]
+pub async fn agent_set_sampling(
+    state: State<'_, AgentState>,
+    sampling: SamplingParams,
+) -> Result<(), String> {
+    let manager_guard = state.manager.read().await;
+    let manager = manager_guard
+        .as_ref()
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
+
+    let config = manager.config();
+    let mut cfg = config.write().await;
+    cfg.llm.set_sampling(sampling)?;
+    let updated_config = cfg.clone();
+    drop(cfg);
+
+    manager.orchestrator().write().await.update_config(updated_config);
+    Ok(())
+}
+
+/// Get the content-safety policy applied to agent messages
+#[tauri::command]
+pub async fn agent_get_safety_policy(
+    state: State<'_, AgentState>,
+) -> Result<SafetyPolicy, String> {
+    let manager_guard = state.manager.read().await;
+    let manager = manager_guard
+        .as_ref()
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
+
+    let config = manager.config();
+    let policy = config.read().await.safety.clone();
+    Ok(policy)
+}
+
+/// Set the content-safety policy: keyword lists, guard model, blocked
+/// categories and audit log
+#[tauri::command]
+pub async fn agent_set_safety_policy(
+    state: State<'_, AgentState>,
+    policy: SafetyPolicy,
+) -> Result<(), String> {
+    policy.validate().map_err(localize_err)?;
+
+    let manager_guard = state.manager.read().await;
+    let manager = manager_guard
+        .as_ref()
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
+
+    let config = manager.config();
+    let mut cfg = config.write().await;
+    cfg.safety = policy;
+    let updated_config = cfg.clone();
+    drop(cfg);
+
+    manager.orchestrator().write().await.update_config(updated_config);
+    Ok(())
+}
+
 /// Set API key for cloud LLM providers
 #[tauri::command]
 pub async fn agent_set_api_key(
@@ -543,7 +668,7 @@ pub async fn agent_set_api_key(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let mut cfg = config.write().await;
@@ -580,7 +705,7 @@ pub async fn agent_set_auto_mode(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let mut cfg = config.write().await;
@@ -630,6 +755,420 @@ pub async fn agent_get_status(
     }
 }
 
+// =============================================================================
+// Prompt Library Commands
+// =============================================================================
+
+/// Get the prompt library from the initialized agent
+async fn prompt_library(state: &State<'_, AgentState>) -> Result<Arc<PromptLibrary>, String> {
+    let manager_guard = state.manager.read().await;
+    let manager = manager_guard
+        .as_ref()
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
+
+    let library = manager.orchestrator().read().await.prompt_library();
+    Ok(library)
+}
+
+/// List saved prompts and personas
+#[tauri::command]
+pub async fn agent_list_prompts(
+    state: State<'_, AgentState>,
+) -> Result<Vec<PromptSummary>, String> {
+    Ok(prompt_library(&state).await?.list())
+}
+
+/// Get a prompt with all of its versions
+#[tauri::command]
+pub async fn agent_get_prompt(
+    state: State<'_, AgentState>,
+    name: String,
+) -> Result<PromptEntry, String> {
+    prompt_library(&state)
+        .await?
+        .get(&name)
+        .map_err(localize_err)
+}
+
+/// Save a prompt as its next version; returns the current version number
+#[tauri::command]
+pub async fn agent_save_prompt(
+    state: State<'_, AgentState>,
+    prompt: PromptDraft,
+) -> Result<u32, String> {
+    prompt_library(&state)
+        .await?
+        .save(prompt)
+        .map_err(localize_err)
+}
+
+/// Delete a prompt and its history
+#[tauri::command]
+pub async fn agent_delete_prompt(
+    state: State<'_, AgentState>,
+    name: String,
+) -> Result<bool, String> {
+    prompt_library(&state)
+        .await?
+        .delete(&name)
+        .map_err(localize_err)
+}
+
+/// Set the prompt used by sessions without an assignment (None clears it)
+#[tauri::command]
+pub async fn agent_set_default_prompt(
+    state: State<'_, AgentState>,
+    name: Option<String>,
+) -> Result<(), String> {
+    prompt_library(&state)
+        .await?
+        .set_default(name.as_deref())
+        .map_err(localize_err)
+}
+
+/// Export prompts as a JSON bundle (all of them when no names are given)
+#[tauri::command]
+pub async fn agent_export_prompts(
+    state: State<'_, AgentState>,
+    names: Option<Vec<String>>,
+) -> Result<String, String> {
+    prompt_library(&state)
+        .await?
+        .export(names.as_deref())
+        .map_err(localize_err)
+}
+
+/// Merge an exported JSON bundle into the library
+#[tauri::command]
+pub async fn agent_import_prompts(
+    state: State<'_, AgentState>,
+    bundle: String,
+) -> Result<ImportSummary, String> {
+    prompt_library(&state)
+        .await?
+        .import(&bundle)
+        .map_err(localize_err)
+}
+
+/// Assign a library prompt to a session, or clear it when `name` is None.
+/// Returns the rendered prompt so the caller can preview it.
+#[tauri::command]
+pub async fn agent_set_session_prompt(
+    state: State<'_, AgentState>,
+    session_id: String,
+    name: Option<String>,
+    version: Option<u32>,
+    variables: Option<HashMap<String, String>>,
+) -> Result<Option<String>, String> {
+    let manager_guard = state.manager.read().await;
+    let manager = manager_guard
+        .as_ref()
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
+
+    let orchestrator = manager.orchestrator();
+    let orchestrator = orchestrator.read().await;
+    let session = orchestrator
+        .get_session(&session_id)
+        .await
+        .ok_or("Session not found")?;
+
+    let Some(name) = name else {
+        session.set_prompt(None).await;
+        return Ok(None);
+    };
+
+    let assignment = PromptAssignment {
+        name,
+        version,
+        variables: variables.unwrap_or_default(),
+    };
+    // Render up front so a bad version or missing variable fails here
+    let rendered = orchestrator
+        .prompt_library()
+        .render(&assignment)
+        .map_err(localize_err)?;
+    session.set_prompt(Some(assignment)).await;
+
+    Ok(Some(rendered))
+}
+
+/// Get the prompt assigned to a session
+#[tauri::command]
+pub async fn agent_get_session_prompt(
+    state: State<'_, AgentState>,
+    session_id: String,
+) -> Result<Option<PromptAssignment>, String> {
+    let manager_guard = state.manager.read().await;
+    let manager = manager_guard
+        .as_ref()
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
+
+    let orchestrator = manager.orchestrator();
+    let session = orchestrator
+        .read()
+        .await
+        .get_session(&session_id)
+        .await
+        .ok_or("Session not found")?;
+
+    Ok(session.prompt().await)
+}
+
+/// Override how a session's requests are routed over the providers.
+/// Returns the providers in the order the next request will try them.
+#[tauri::command]
+pub async fn agent_set_session_route(
+    state: State<'_, AgentState>,
+    session_id: String,
+    route: RouteOverride,
+) -> Result<Vec<AIProvider>, String> {
+    let manager_guard = state.manager.read().await;
+    let manager = manager_guard
+        .as_ref()
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
+
+    let orchestrator = manager.orchestrator();
+    let orchestrator = orchestrator.read().await;
+    let session = orchestrator
+        .get_session(&session_id)
+        .await
+        .ok_or("Session not found")?;
+    session.set_route(route).await;
+
+    orchestrator
+        .session_provider_order(&session_id)
+        .await
+        .ok_or_else(|| "Session not found".to_string())
+}
+
+/// Get a session's routing override
+#[tauri::command]
+pub async fn agent_get_session_route(
+    state: State<'_, AgentState>,
+    session_id: String,
+) -> Result<RouteOverride, String> {
+    let manager_guard = state.manager.read().await;
+    let manager = manager_guard
+        .as_ref()
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
+
+    let orchestrator = manager.orchestrator();
+    let session = orchestrator
+        .read()
+        .await
+        .get_session(&session_id)
+        .await
+        .ok_or("Session not found")?;
+
+    Ok(session.route().await)
+}
+
+/// Latency and error statistics of each configured provider
+#[tauri::command]
+pub async fn agent_get_provider_health(
+    state: State<'_, AgentState>,
+) -> Result<Vec<ProviderHealth>, String> {
+    let manager_guard = state.manager.read().await;
+    let manager = manager_guard
+        .as_ref()
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
+
+    Ok(manager.orchestrator().read().await.provider_health())
+}
+
+/// Probe every configured provider and return their updated health
+#[tauri::command]
+pub async fn agent_check_provider_health(
+    state: State<'_, AgentState>,
+) -> Result<Vec<ProviderHealth>, String> {
+    let manager_guard = state.manager.read().await;
+    let manager = manager_guard
+        .as_ref()
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
+
+    let orchestrator = manager.orchestrator();
+    let health = orchestrator.read().await.check_provider_health().await;
+    Ok(health)
+}
+
+// =============================================================================
+// Scheduled Task Commands
+// =============================================================================
+
+/// Get the task scheduler from the initialized agent
+async fn task_scheduler(state: &State<'_, AgentState>) -> Result<Arc<TaskScheduler>, String> {
+    let manager_guard = state.manager.read().await;
+    let manager = manager_guard
+        .as_ref()
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
+
+    let scheduler = manager.orchestrator().read().await.task_scheduler();
+    Ok(scheduler)
+}
+
+/// List scheduled tasks
+#[tauri::command]
+pub async fn agent_list_tasks(
+    state: State<'_, AgentState>,
+) -> Result<Vec<ScheduledTask>, String> {
+    Ok(task_scheduler(&state).await?.list())
+}
+
+/// Create a scheduled task
+#[tauri::command]
+pub async fn agent_create_task(
+    state: State<'_, AgentState>,
+    task: TaskDraft,
+) -> Result<ScheduledTask, String> {
+    task_scheduler(&state)
+        .await?
+        .create(task)
+        .map_err(localize_err)
+}
+
+/// Replace a scheduled task's settings
+#[tauri::command]
+pub async fn agent_update_task(
+    state: State<'_, AgentState>,
+    task_id: String,
+    task: TaskDraft,
+) -> Result<ScheduledTask, String> {
+    task_scheduler(&state)
+        .await?
+        .update(&task_id, task)
+        .map_err(localize_err)
+}
+
+/// Enable or pause a scheduled task
+#[tauri::command]
+pub async fn agent_set_task_enabled(
+    state: State<'_, AgentState>,
+    task_id: String,
+    enabled: bool,
+) -> Result<ScheduledTask, String> {
+    task_scheduler(&state)
+        .await?
+        .set_enabled(&task_id, enabled)
+        .map_err(localize_err)
+}
+
+/// Delete a scheduled task (its conversation is kept)
+#[tauri::command]
+pub async fn agent_delete_task(
+    state: State<'_, AgentState>,
+    task_id: String,
+) -> Result<bool, String> {
+    task_scheduler(&state)
+        .await?
+        .delete(&task_id)
+        .map_err(localize_err)
+}
+
+/// Run a scheduled task immediately, outside its schedule
+#[tauri::command]
+pub async fn agent_run_task_now(
+    state: State<'_, AgentState>,
+    task_id: String,
+) -> Result<TaskRun, String> {
+    let manager_guard = state.manager.read().await;
+    let manager = manager_guard
+        .as_ref()
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
+
+    let orchestrator = manager.orchestrator();
+    let run = orchestrator
+        .read()
+        .await
+        .run_scheduled_task(&task_id)
+        .await
+        .map_err(localize_err)?;
+    Ok(run)
+}
+
+// =============================================================================
+// Long-term Memory Commands
+// =============================================================================
+
+/// Search summaries of past conversations, most similar first
+#[tauri::command]
+pub async fn agent_search_memory(
+    state: State<'_, AgentState>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<MemoryMatch>, String> {
+    let manager_guard = state.manager.read().await;
+    let manager = manager_guard
+        .as_ref()
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
+
+    let memory = manager.orchestrator().read().await.memory();
+    memory
+        .search(&query, limit.unwrap_or(10), 0.0)
+        .await
+        .map_err(localize_err)
+}
+
+// =============================================================================
+// File Access Commands
+// =============================================================================
+
+/// Get the file permission manager from the initialized agent
+async fn file_permissions(
+    state: &State<'_, AgentState>,
+) -> Result<Arc<FilePermissionManager>, String> {
+    let manager_guard = state.manager.read().await;
+    let manager = manager_guard
+        .as_ref()
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
+
+    let permissions = manager.orchestrator().read().await.file_permissions();
+    Ok(permissions)
+}
+
+/// List the directories the agent's file tools may access
+#[tauri::command]
+pub async fn agent_list_file_grants(
+    state: State<'_, AgentState>,
+) -> Result<Vec<DirectoryGrant>, String> {
+    Ok(file_permissions(&state).await?.grants())
+}
+
+/// Grant the agent access to a directory. With `auto_approve`, writes in
+/// a read-write directory run without asking first.
+#[tauri::command]
+pub async fn agent_grant_directory(
+    state: State<'_, AgentState>,
+    path: String,
+    access: FileAccess,
+    auto_approve: Option<bool>,
+) -> Result<DirectoryGrant, String> {
+    file_permissions(&state)
+        .await?
+        .grant(&path, access, auto_approve.unwrap_or(false))
+        .map_err(localize_err)
+}
+
+/// Withdraw the agent's access to a directory
+#[tauri::command]
+pub async fn agent_revoke_directory(
+    state: State<'_, AgentState>,
+    path: String,
+) -> Result<bool, String> {
+    file_permissions(&state)
+        .await?
+        .revoke(&path)
+        .map_err(localize_err)
+}
+
+/// Recent file operations by the agent, newest first
+#[tauri::command]
+pub async fn agent_file_audit_log(
+    state: State<'_, AgentState>,
+    limit: Option<usize>,
+) -> Result<Vec<FileAuditEntry>, String> {
+    Ok(file_permissions(&state).await?.audit_log(limit.unwrap_or(100)))
+}
+
 // =============================================================================
 // Multi-Provider AI Configuration Commands
 // =============================================================================
@@ -643,6 +1182,7 @@ pub struct AIProvidersConfigResponse {
     pub xai: ProviderSettingsResponse,
     pub preferred_order: Vec<String>,
     pub local_fallback: bool,
+    pub routing: RoutingStrategy,
     pub local_model_path: Option<String>,
     pub local_model_cid: Option<String>,
     pub active_provider: Option<String>,
@@ -665,7 +1205,7 @@ pub async fn get_ai_providers_config(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let cfg = config.read().await;
@@ -704,6 +1244,7 @@ pub async fn get_ai_providers_config(
         },
         preferred_order: providers.preferred_order.iter().map(|p| format!("{:?}", p).to_lowercase()).collect(),
         local_fallback: providers.local_fallback,
+        routing: providers.routing,
         local_model_path: providers.local_model_path.clone(),
         local_model_cid: providers.local_model_cid.clone(),
         active_provider: active.map(|p| format!("{:?}", p).to_lowercase()),
@@ -718,7 +1259,7 @@ pub async fn get_ai_provider_keys(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let cfg = config.read().await;
@@ -756,7 +1297,7 @@ pub async fn update_ai_providers_config(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let mut cfg = config.write().await;
@@ -859,6 +1400,8 @@ pub struct BatchAIProvidersConfigInput {
     pub preferred_order: Vec<String>,
     pub local_fallback: bool,
     #[serde(default)]
+    pub routing: Option<RoutingStrategy>,
+    #[serde(default)]
     pub local_model_path: Option<String>,
     #[serde(default)]
     pub local_model_cid: Option<String>,
@@ -889,7 +1432,7 @@ pub async fn save_ai_providers_config(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let agent_config = manager.config();
     let mut cfg = agent_config.write().await;
@@ -936,6 +1479,9 @@ pub async fn save_ai_providers_config(
 
     // Update local model settings
     cfg.providers.local_fallback = config.local_fallback;
+    if let Some(routing) = config.routing {
+        cfg.providers.routing = routing;
+    }
     if let Some(path) = config.local_model_path {
         cfg.providers.local_model_path = Some(path);
     }
@@ -976,7 +1522,7 @@ pub async fn test_ai_provider_connection(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let mut cfg = config.write().await;
@@ -1245,7 +1791,7 @@ pub async fn pin_local_model_to_ipfs(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let mut cfg = config.write().await;
@@ -1262,6 +1808,11 @@ pub async fn pin_local_model_to_ipfs(
     tracing::info!("Pinning model to IPFS: {}", model_path);
     let result = ipfs_manager.add_file(&std::path::PathBuf::from(&model_path)).await
         .map_err(|e| format!("Failed to pin to IPFS: {}", e))?;
+    // Count the weights against the models quota
+    ipfs_manager
+        .pin_as(&result.cid, crate::ipfs::ContentCategory::Models, false)
+        .await
+        .map_err(|e| format!("Failed to pin to IPFS: {}", e))?;
 
     // Store the CID in config
     cfg.providers.local_model_cid = Some(result.cid.clone());
@@ -1285,7 +1836,7 @@ pub async fn delete_local_model(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let mut cfg = config.write().await;
@@ -1336,7 +1887,7 @@ pub async fn download_model_from_ipfs(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let mut cfg = config.write().await;
@@ -1396,7 +1947,7 @@ pub async fn set_preferred_provider_order(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let mut cfg = config.write().await;
@@ -1428,7 +1979,7 @@ pub async fn set_local_fallback(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let mut cfg = config.write().await;
@@ -1446,7 +1997,7 @@ pub async fn check_onboarding_status(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let cfg = config.read().await;
@@ -1466,7 +2017,7 @@ pub async fn complete_onboarding(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let mut cfg = config.write().await;
@@ -1489,7 +2040,7 @@ pub async fn check_first_run(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let cfg = config.read().await;
@@ -1521,7 +2072,7 @@ pub async fn setup_bundled_model(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let mut cfg = config.write().await;
@@ -1620,7 +2171,7 @@ pub async fn process_onboarding_answer(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let mut cfg = config.write().await;
@@ -1673,7 +2224,7 @@ pub async fn skip_onboarding(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let mut cfg = config.write().await;
@@ -1717,7 +2268,7 @@ pub async fn secure_store_api_key(
     let result = API_KEY_MANAGER
         .set_key(ai_provider, &api_key, validate, None)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(localize_err)?;
 
     // Also update the in-memory config if agent is initialized
     if let Some(manager) = state.manager.read().await.as_ref() {
@@ -1800,7 +2351,7 @@ pub async fn delete_secure_api_key(
 
     API_KEY_MANAGER
         .delete_key(ai_provider)
-        .map_err(|e| e.to_string())?;
+        .map_err(localize_err)?;
 
     // Also clear from in-memory config
     if let Some(manager) = state.manager.read().await.as_ref() {
@@ -1826,7 +2377,7 @@ pub async fn load_secure_api_keys(
     let manager_guard = state.manager.read().await;
     let manager = manager_guard
         .as_ref()
-        .ok_or("Agent not initialized")?;
+        .ok_or_else(|| tr!("error.agent_not_initialized"))?;
 
     let config = manager.config();
     let mut cfg = config.write().await;
@@ -1877,6 +2428,16 @@ pub async fn get_secure_api_key_status() -> Result<serde_json::Value, String> {
 // Enhanced Model Download Commands
 // =============================================================================
 
+/// Download id under which enhanced model progress appears in `state-delta` events
+const ENHANCED_MODEL_DOWNLOAD_ID: &str = "enhanced-model";
+
+/// Report enhanced model download progress through the state bus
+fn publish_download_progress(app_handle: &tauri::AppHandle, progress: serde_json::Value) {
+    if let Some(bus) = app_handle.try_state::<Arc<StateDeltaBus>>() {
+        bus.publish_download(ENHANCED_MODEL_DOWNLOAD_ID, &progress);
+    }
+}
+
 /// Download the enhanced 7B model from HuggingFace with progress reporting
 /// This is called automatically during onboarding to give users the best experience
 #[tauri::command]
@@ -1929,8 +2490,8 @@ pub async fn download_enhanced_model(
 
     tracing::info!("Starting enhanced model download from HuggingFace");
 
-    // Emit initial progress event
-    let _ = app_handle.emit("model-download-progress", serde_json::json!({
+    // Report initial progress
+    publish_download_progress(&app_handle, serde_json::json!({
         "status": "starting",
         "progress": 0,
         "message": "Connecting to HuggingFace..."
@@ -1954,8 +2515,8 @@ pub async fn download_enhanced_model(
 
     let total_size = response.content_length().unwrap_or(expected_size);
 
-    // Emit download started event
-    let _ = app_handle.emit("model-download-progress", serde_json::json!({
+    // Report download started
+    publish_download_progress(&app_handle, serde_json::json!({
         "status": "downloading",
         "progress": 0,
         "total_size_mb": total_size / (1024 * 1024),
@@ -1976,8 +2537,8 @@ pub async fn download_enhanced_model(
     let downloaded_mb = downloaded / (1024 * 1024);
     let total_mb = total_size / (1024 * 1024);
 
-    // Emit progress updates
-    let _ = app_handle.emit("model-download-progress", serde_json::json!({
+    // Report progress
+    publish_download_progress(&app_handle, serde_json::json!({
         "status": "downloading",
         "progress": progress,
         "downloaded_mb": downloaded_mb,
@@ -2008,8 +2569,8 @@ pub async fn download_enhanced_model(
         cfg.providers.local_model_path = Some(dest_path.to_string_lossy().to_string());
     }
 
-    // Emit completion event
-    let _ = app_handle.emit("model-download-progress", serde_json::json!({
+    // Report completion
+    publish_download_progress(&app_handle, serde_json::json!({
         "status": "complete",
         "progress": 100,
         "path": dest_path.to_string_lossy().to_string(),
@@ -20,8 +20,10 @@ use argon2::{
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use keyring::Entry;
 use rand::rngs::OsRng;
+use citrate_mcp::gguf_engine::SamplingParams;
+use citrate_mcp::safety::SafetyPolicy;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use tracing::{info, warn, error};
 
 // Keyring constants for API key storage
@@ -700,6 +702,18 @@ impl std::fmt::Display for AIProvider {
     }
 }
 
+/// How the agent picks among the available providers. Either way a failed
+/// request moves on to the next provider.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutingStrategy {
+    /// Fastest provider first, with recent errors counting against it
+    #[default]
+    Latency,
+    /// Providers in `preferred_order`
+    Ordered,
+}
+
 /// Provider-specific settings
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ProviderSettings {
@@ -750,6 +764,9 @@ pub struct AIProvidersConfig {
     pub preferred_order: Vec<AIProvider>,
     /// Always fallback to local model if cloud providers fail
     pub local_fallback: bool,
+    /// How requests are spread over the available providers
+    #[serde(default)]
+    pub routing: RoutingStrategy,
     /// Local model path (GGUF file)
     pub local_model_path: Option<String>,
     /// Local model IPFS CID (for re-download)
@@ -765,6 +782,7 @@ impl Default for AIProvidersConfig {
             xai: ProviderSettings::new("grok-beta"),
             preferred_order: vec![AIProvider::Local, AIProvider::OpenAI, AIProvider::Anthropic],
             local_fallback: true,
+            routing: RoutingStrategy::default(),
             local_model_path: None,
             local_model_cid: None,
         }
@@ -886,6 +904,21 @@ pub struct LLMConfig {
     pub max_tokens: u32,
     /// Top-p sampling
     pub top_p: f32,
+    /// Top-k sampling
+    #[serde(default)]
+    pub top_k: Option<u32>,
+    /// Min-p sampling
+    #[serde(default)]
+    pub min_p: Option<f32>,
+    /// Repetition penalty
+    #[serde(default)]
+    pub repeat_penalty: Option<f32>,
+    /// Sequences that end generation
+    #[serde(default)]
+    pub stop: Vec<String>,
+    /// Bias added to the logits of the given token ids, -100 to 100
+    #[serde(default)]
+    pub logit_bias: BTreeMap<u32, f32>,
     /// Context window size
     pub context_size: u32,
 }
@@ -900,11 +933,44 @@ impl Default for LLMConfig {
             temperature: 0.7,
             max_tokens: 2048,
             top_p: 0.9,
+            top_k: None,
+            min_p: None,
+            repeat_penalty: None,
+            stop: Vec::new(),
+            logit_bias: BTreeMap::new(),
             context_size: 8192,
         }
     }
 }
 
+impl LLMConfig {
+    /// Sampler settings
+    pub fn sampling(&self) -> SamplingParams {
+        SamplingParams {
+            temperature: self.temperature,
+            top_k: self.top_k,
+            top_p: Some(self.top_p),
+            min_p: self.min_p,
+            repeat_penalty: self.repeat_penalty,
+            stop: self.stop.clone(),
+            logit_bias: self.logit_bias.clone(),
+        }
+    }
+
+    /// Replace the sampler settings after checking them
+    pub fn set_sampling(&mut self, sampling: SamplingParams) -> Result<(), String> {
+        sampling.validate().map_err(|e| e.to_string())?;
+        self.temperature = sampling.temperature;
+        self.top_k = sampling.top_k;
+        self.top_p = sampling.top_p.unwrap_or(1.0);
+        self.min_p = sampling.min_p;
+        self.repeat_penalty = sampling.repeat_penalty;
+        self.stop = sampling.stop;
+        self.logit_bias = sampling.logit_bias;
+        Ok(())
+    }
+}
+
 /// Intent classification configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClassifierConfig {
@@ -996,6 +1062,37 @@ impl Default for ContextConfig {
     }
 }
 
+/// Long-term memory over past conversations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoryConfig {
+    /// Whether old messages are summarized into memory and recalled
+    pub enabled: bool,
+    /// Most recent messages sent verbatim; older ones go to memory
+    pub recent_messages: usize,
+    /// Messages summarized together into one memory
+    pub chunk_size: usize,
+    /// Memories recalled into the prompt per message
+    pub recall_limit: usize,
+    /// Minimum similarity (0-1) for a memory to be recalled
+    pub min_score: f32,
+    /// GGUF embedding model; the built-in hashing embedder is used when unset
+    pub embedding_model_path: Option<String>,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            recent_messages: 10,
+            chunk_size: 6,
+            recall_limit: 3,
+            min_score: 0.25,
+            embedding_model_path: None,
+        }
+    }
+}
+
 /// Main agent configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AgentConfig {
@@ -1012,6 +1109,12 @@ pub struct AgentConfig {
     pub streaming: StreamingConfig,
     /// Context management configuration
     pub context: ContextConfig,
+    /// Long-term memory of summarized conversation history
+    #[serde(default)]
+    pub memory: MemoryConfig,
+    /// Content-safety checks on user messages and responses
+    #[serde(default)]
+    pub safety: SafetyPolicy,
     /// System prompt to prepend to conversations
     pub system_prompt: Option<String>,
     /// Whether agent is enabled
@@ -1097,8 +1200,12 @@ When users request actions, use these tools:
 - `scaffold_dapp` - Generate dApp project (basic, defi, nft, marketplace templates)
 - `list_templates` - Show available project templates
 - `execute_command` - Run terminal commands (git, npm, cargo, python)
+- `run_in_terminal` - Run any shell command in the agent's terminal and capture its output (requires approval)
 - `change_directory` - Change working directory
 - `get_working_directory` - Show current directory
+- `list_directory` - List files in a directory the user has granted
+- `read_file` - Read a text file in a granted directory
+- `write_file` - Create or overwrite a file in a granted directory (requires approval)
 
 **Storage Tools:**
 - `upload_ipfs` - Upload files or data to IPFS
@@ -1596,11 +1703,28 @@ mod tests {
         let config: AgentConfig = serde_json::from_str(json).unwrap();
         assert!(config.enabled);
         assert_eq!(config.llm.temperature, 0.5);
+        assert!(config.llm.stop.is_empty());
         // Verify providers defaults were applied
         assert!(!config.providers.openai.enabled);
         assert!(!config.providers.anthropic.enabled);
     }
 
+    #[test]
+    fn test_llm_config_set_sampling() {
+        let mut llm = LLMConfig::default();
+        let mut sampling = llm.sampling();
+        sampling.top_k = Some(40);
+        sampling.stop = vec!["\n\nUser:".to_string()];
+        sampling.logit_bias.insert(15043, -100.0);
+        llm.set_sampling(sampling.clone()).unwrap();
+        assert_eq!(llm.sampling(), sampling);
+
+        // Out of range settings leave the config untouched
+        sampling.min_p = Some(2.0);
+        assert!(llm.set_sampling(sampling).is_err());
+        assert_eq!(llm.min_p, None);
+    }
+
     // =========================================================================
     // API Key Error Tests
     // =========================================================================
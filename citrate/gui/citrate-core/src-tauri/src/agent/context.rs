@@ -57,6 +57,9 @@ pub struct SystemContext {
     /// Number of pending transactions in mempool
     #[serde(default)]
     pub pending_transactions: Option<usize>,
+    /// Summaries of earlier conversations relevant to the current message
+    #[serde(default)]
+    pub memories: Vec<String>,
 }
 
 impl Default for SystemContext {
@@ -74,6 +77,7 @@ impl Default for SystemContext {
             is_syncing: None,
             chain_id: None,
             pending_transactions: None,
+            memories: Vec::new(),
         }
     }
 }
@@ -145,6 +149,12 @@ impl SystemContext {
             }
         }
 
+        // Long-term memory recalled for this message
+        if !self.memories.is_empty() {
+            parts.push("\n## Relevant Memories".to_string());
+            parts.extend(self.memories.iter().map(|m| format!("- {}", m)));
+        }
+
         parts.join("\n")
     }
 
@@ -423,6 +433,7 @@ mod tests {
             is_syncing: Some(false),
             chain_id: Some(1337),
             pending_transactions: Some(3),
+            memories: vec!["User prefers gas estimates in gwei".to_string()],
         };
 
         let formatted = ctx.to_context_string();
@@ -432,6 +443,7 @@ mod tests {
         assert!(formatted.contains("1337")); // chain_id
         assert!(formatted.contains("DAG Tips"));
         assert!(formatted.contains("Blue Score"));
+        assert!(formatted.contains("Relevant Memories"));
     }
 
     #[test]
@@ -30,6 +30,7 @@ impl Default for ToolConfig {
                 "send_transaction".to_string(),
                 "deploy_contract".to_string(),
                 "write_contract".to_string(),
+                "propose_contract_transaction".to_string(),
                 "deploy_model".to_string(),
             ],
             execution_timeout_ms: 30000,
@@ -85,6 +86,17 @@ pub struct ToolOutput {
     pub data: Option<serde_json::Value>,
 }
 
+impl ToolOutput {
+    /// Whether the tool prepared an action that must be approved by the user
+    pub fn awaiting_approval(&self) -> bool {
+        self.data
+            .as_ref()
+            .and_then(|d| d.get("status"))
+            .and_then(|s| s.as_str())
+            == Some("awaiting_approval")
+    }
+}
+
 /// A registered tool handler
 pub trait ToolHandler: Send + Sync {
     /// Tool name
@@ -226,6 +238,14 @@ impl ToolDispatcher {
             .contains(&tool_name.to_string())
     }
 
+    /// Whether a tool only prepares actions for the user to approve
+    pub fn needs_approval(&self, tool_name: &str) -> bool {
+        self.requires_confirmation(tool_name)
+            || self
+                .get_tool(tool_name)
+                .is_some_and(|t| t.requires_confirmation)
+    }
+
     /// List available tools
     pub fn list_tools(&self) -> &[ToolDefinition] {
         &self.definitions
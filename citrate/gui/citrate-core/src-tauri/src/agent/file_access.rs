@@ -0,0 +1,451 @@
+//! File-system permissions for the agent's file tools
+//!
+//! The agent can only touch directories the user has granted. A grant is
+//! either read-only or read-write, and a read-write grant can opt into
+//! auto-approved writes; otherwise every write is proposed to the user
+//! first. Paths are canonicalized before they are checked, so `..` and
+//! symlinks cannot reach outside a granted directory.
+//!
+//! Every check and write is appended to an audit log that is kept with the
+//! grants and can be reviewed from the frontend.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+
+/// Audit entries kept; older ones are dropped
+const MAX_AUDIT_ENTRIES: usize = 1000;
+
+/// Error type for file permission checks
+#[derive(Debug)]
+pub enum FileAccessError {
+    /// Path is outside every granted directory
+    NotGranted(PathBuf),
+    /// Directory is granted read-only
+    ReadOnly(PathBuf),
+    /// Path could not be resolved
+    InvalidPath(String),
+    /// Serialization error
+    Serialization(String),
+    /// IO error
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for FileAccessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotGranted(path) => {
+                write!(
+                    f,
+                    "{} is outside the directories granted to the agent",
+                    path.display()
+                )
+            }
+            Self::ReadOnly(path) => {
+                write!(f, "{} is in a directory granted read-only", path.display())
+            }
+            Self::InvalidPath(e) => write!(f, "Invalid path: {}", e),
+            Self::Serialization(e) => write!(f, "Serialization error: {}", e),
+            Self::Io(e) => write!(f, "IO error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FileAccessError {}
+
+impl From<std::io::Error> for FileAccessError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for FileAccessError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Serialization(e.to_string())
+    }
+}
+
+/// What a grant lets the agent do inside a directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileAccess {
+    Read,
+    ReadWrite,
+}
+
+/// A directory the user has opened to the agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryGrant {
+    /// Canonical path of the directory
+    pub path: PathBuf,
+    pub access: FileAccess,
+    /// Write without asking the user first
+    pub auto_approve: bool,
+    pub granted_at: u64,
+}
+
+/// Kind of file operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileOperation {
+    Read,
+    List,
+    Write,
+}
+
+/// How an operation ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    /// Permission check failed
+    Denied,
+    /// Write held for the user's approval
+    Proposed,
+    /// Operation ran
+    Completed,
+    /// Operation was allowed but failed
+    Failed,
+}
+
+/// One entry in the file audit log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileAuditEntry {
+    pub timestamp: u64,
+    pub operation: FileOperation,
+    pub path: String,
+    pub outcome: AuditOutcome,
+    pub detail: Option<String>,
+}
+
+/// A path that passed the permission check
+#[derive(Debug, Clone)]
+pub struct CheckedPath {
+    /// Canonical path to operate on
+    pub path: PathBuf,
+    /// Whether a write may skip approval
+    pub auto_approve: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FileAccessState {
+    grants: Vec<DirectoryGrant>,
+    audit: Vec<FileAuditEntry>,
+}
+
+/// Directory grants and the file audit log, persisted as JSON
+pub struct FilePermissionManager {
+    path: Option<PathBuf>,
+    state: RwLock<FileAccessState>,
+}
+
+impl FilePermissionManager {
+    /// Open the grants in the user's local app data directory
+    pub fn new() -> Result<Self, FileAccessError> {
+        let data_dir = dirs::data_local_dir().ok_or_else(|| {
+            FileAccessError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Could not find local data directory",
+            ))
+        })?;
+        Self::open(data_dir.join("citrate").join("file_permissions.json"))
+    }
+
+    /// Open (or create) the grants stored at `path`
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, FileAccessError> {
+        let path = path.into();
+        let state = if path.exists() {
+            serde_json::from_str(&std::fs::read_to_string(&path)?)?
+        } else {
+            FileAccessState::default()
+        };
+        Ok(Self {
+            path: Some(path),
+            state: RwLock::new(state),
+        })
+    }
+
+    /// Grants that are never written to disk
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            state: RwLock::new(FileAccessState::default()),
+        }
+    }
+
+    /// Granted directories, in the order they were granted
+    pub fn grants(&self) -> Vec<DirectoryGrant> {
+        self.read().grants.clone()
+    }
+
+    /// Open `dir` to the agent, replacing any earlier grant for it
+    pub fn grant(
+        &self,
+        dir: impl AsRef<Path>,
+        access: FileAccess,
+        auto_approve: bool,
+    ) -> Result<DirectoryGrant, FileAccessError> {
+        let dir = std::fs::canonicalize(dir.as_ref()).map_err(|e| {
+            FileAccessError::InvalidPath(format!("{}: {}", dir.as_ref().display(), e))
+        })?;
+        if !dir.is_dir() {
+            return Err(FileAccessError::InvalidPath(format!(
+                "{} is not a directory",
+                dir.display()
+            )));
+        }
+        let grant = DirectoryGrant {
+            path: dir,
+            access,
+            auto_approve: auto_approve && access == FileAccess::ReadWrite,
+            granted_at: now(),
+        };
+
+        let mut state = self.write();
+        state.grants.retain(|g| g.path != grant.path);
+        state.grants.push(grant.clone());
+        self.persist(&state)?;
+        tracing::info!(
+            "Granted agent {:?} access to {:?}",
+            grant.access,
+            grant.path
+        );
+        Ok(grant)
+    }
+
+    /// Withdraw the grant for `dir`; returns whether there was one
+    pub fn revoke(&self, dir: impl AsRef<Path>) -> Result<bool, FileAccessError> {
+        let dir = dir.as_ref();
+        let canonical = std::fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+        let mut state = self.write();
+        let before = state.grants.len();
+        state
+            .grants
+            .retain(|g| g.path != canonical && g.path != dir);
+        if state.grants.len() == before {
+            return Ok(false);
+        }
+        self.persist(&state)?;
+        Ok(true)
+    }
+
+    /// Check that `operation` is allowed on `path`. Relative paths are
+    /// taken relative to the first granted directory. Denials are audited.
+    pub fn check(
+        &self,
+        path: &str,
+        operation: FileOperation,
+    ) -> Result<CheckedPath, FileAccessError> {
+        let result = self.resolve(path, operation);
+        if let Err(e) = &result {
+            self.audit(operation, path, AuditOutcome::Denied, Some(e.to_string()));
+        }
+        result
+    }
+
+    fn resolve(
+        &self,
+        path: &str,
+        operation: FileOperation,
+    ) -> Result<CheckedPath, FileAccessError> {
+        let grants = self.grants();
+        let requested = Path::new(path.trim());
+        let requested = if requested.is_absolute() {
+            requested.to_path_buf()
+        } else {
+            let base = grants
+                .first()
+                .ok_or_else(|| FileAccessError::NotGranted(requested.to_path_buf()))?;
+            base.path.join(requested)
+        };
+        let canonical = canonicalize_target(&requested, operation)?;
+
+        // The most specific grant decides
+        let grant = grants
+            .iter()
+            .filter(|g| canonical.starts_with(&g.path))
+            .max_by_key(|g| g.path.components().count())
+            .ok_or_else(|| FileAccessError::NotGranted(canonical.clone()))?;
+        if operation == FileOperation::Write && grant.access != FileAccess::ReadWrite {
+            return Err(FileAccessError::ReadOnly(canonical));
+        }
+        Ok(CheckedPath {
+            path: canonical,
+            auto_approve: grant.auto_approve,
+        })
+    }
+
+    /// Append an entry to the audit log
+    pub fn audit(
+        &self,
+        operation: FileOperation,
+        path: impl AsRef<Path>,
+        outcome: AuditOutcome,
+        detail: Option<String>,
+    ) {
+        let entry = FileAuditEntry {
+            timestamp: now(),
+            operation,
+            path: path.as_ref().display().to_string(),
+            outcome,
+            detail,
+        };
+        let mut state = self.write();
+        state.audit.push(entry);
+        let excess = state.audit.len().saturating_sub(MAX_AUDIT_ENTRIES);
+        state.audit.drain(..excess);
+        if let Err(e) = self.persist(&state) {
+            tracing::warn!("Failed to save file audit log: {}", e);
+        }
+    }
+
+    /// The most recent audit entries, newest first
+    pub fn audit_log(&self, limit: usize) -> Vec<FileAuditEntry> {
+        self.read()
+            .audit
+            .iter()
+            .rev()
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Path of the backing file, if any
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Write grants and audit log to disk, replacing the previous file atomically
+    fn persist(&self, state: &FileAccessState) -> Result<(), FileAccessError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(state)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, FileAccessState> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, FileAccessState> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Resolve symlinks and `..` in `path`. A file that is about to be written
+/// may not exist yet, in which case its parent directory must.
+fn canonicalize_target(path: &Path, operation: FileOperation) -> Result<PathBuf, FileAccessError> {
+    let invalid =
+        |e: std::io::Error| FileAccessError::InvalidPath(format!("{}: {}", path.display(), e));
+    if operation != FileOperation::Write || path.exists() {
+        return std::fs::canonicalize(path).map_err(invalid);
+    }
+    let file_name = match path.components().next_back() {
+        Some(Component::Normal(name)) => name,
+        _ => {
+            return Err(FileAccessError::InvalidPath(format!(
+                "{} does not name a file",
+                path.display()
+            )))
+        }
+    };
+    let parent = path.parent().unwrap_or_else(|| Path::new("/"));
+    Ok(std::fs::canonicalize(parent)
+        .map_err(invalid)?
+        .join(file_name))
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grants_confine_paths() {
+        let root = tempfile::tempdir().unwrap();
+        let project = root.path().join("project");
+        let docs = project.join("docs");
+        std::fs::create_dir_all(&docs).unwrap();
+        std::fs::write(root.path().join("secret.txt"), "x").unwrap();
+        std::fs::write(docs.join("readme.md"), "hi").unwrap();
+
+        let manager = FilePermissionManager::in_memory();
+        manager
+            .grant(&project, FileAccess::ReadWrite, false)
+            .unwrap();
+        manager.grant(&docs, FileAccess::Read, false).unwrap();
+
+        // Relative paths resolve against the first grant
+        let checked = manager
+            .check("docs/readme.md", FileOperation::Read)
+            .unwrap();
+        assert_eq!(checked.path, docs.canonicalize().unwrap().join("readme.md"));
+        assert!(manager.check("new.rs", FileOperation::Write).is_ok());
+
+        // The more specific read-only grant wins
+        assert!(matches!(
+            manager.check("docs/readme.md", FileOperation::Write),
+            Err(FileAccessError::ReadOnly(_))
+        ));
+
+        // Escapes through `..` are caught after canonicalization
+        assert!(matches!(
+            manager.check("../secret.txt", FileOperation::Read),
+            Err(FileAccessError::NotGranted(_))
+        ));
+        assert!(manager
+            .check("missing/new.rs", FileOperation::Write)
+            .is_err());
+
+        let denied = manager.audit_log(10);
+        assert_eq!(denied.len(), 3);
+        assert!(denied.iter().all(|e| e.outcome == AuditOutcome::Denied));
+
+        assert!(manager.revoke(&project).unwrap());
+        assert!(manager.check("/", FileOperation::List).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_cannot_escape_a_grant() {
+        let root = tempfile::tempdir().unwrap();
+        let project = root.path().join("project");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(root.path().join("secret.txt"), "x").unwrap();
+        std::os::unix::fs::symlink(root.path().join("secret.txt"), project.join("link")).unwrap();
+
+        let manager = FilePermissionManager::in_memory();
+        manager
+            .grant(&project, FileAccess::ReadWrite, true)
+            .unwrap();
+        assert!(manager.check("link", FileOperation::Read).is_err());
+        assert!(manager.check("link", FileOperation::Write).is_err());
+    }
+
+    #[test]
+    fn test_grants_persist_with_audit() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("file_permissions.json");
+
+        let manager = FilePermissionManager::open(&file).unwrap();
+        let grant = manager.grant(dir.path(), FileAccess::Read, true).unwrap();
+        // Auto-approval only applies to writable grants
+        assert!(!grant.auto_approve);
+        manager.audit(FileOperation::Read, "a.txt", AuditOutcome::Completed, None);
+
+        let reopened = FilePermissionManager::open(&file).unwrap();
+        assert_eq!(reopened.grants().len(), 1);
+        assert_eq!(reopened.audit_log(10).len(), 1);
+    }
+}
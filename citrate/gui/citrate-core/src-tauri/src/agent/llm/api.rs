@@ -2,10 +2,30 @@
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 use super::{CompletionResponse, LLMBackend, LLMConfig, LLMError, TokenUsage};
 use crate::agent::context::ContextWindow;
 
+/// How long a health check waits for the provider's model list
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Turn the response to a health check request into its outcome
+async fn health_check_outcome(
+    response: Result<reqwest::Response, reqwest::Error>,
+) -> Result<(), LLMError> {
+    let response = response.map_err(|e| LLMError(format!("Request failed: {}", e)))?;
+    if response.status().is_success() {
+        return Ok(());
+    }
+    let status = response.status();
+    let error_text = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "Unknown error".to_string());
+    Err(LLMError(format!("API error ({}): {}", status, error_text)))
+}
+
 /// OpenAI API backend
 pub struct OpenAIBackend {
     config: LLMConfig,
@@ -63,13 +83,31 @@ impl LLMBackend for OpenAIBackend {
             }));
         }
 
-        let request_body = serde_json::json!({
+        let mut request_body = serde_json::json!({
             "model": &self.config.model,
             "messages": messages,
             "max_tokens": self.config.max_tokens,
             "temperature": self.config.temperature,
             "top_p": self.config.top_p
         });
+        if !self.config.stop.is_empty() {
+            request_body["stop"] = serde_json::json!(self.config.stop);
+        }
+        if !self.config.logit_bias.is_empty() {
+            request_body["logit_bias"] = serde_json::json!(self.config.logit_bias);
+        }
+        // Only OpenAI-compatible servers such as llama.cpp take these
+        if self.config.api_base_url.is_some() {
+            if let Some(top_k) = self.config.top_k {
+                request_body["top_k"] = top_k.into();
+            }
+            if let Some(min_p) = self.config.min_p {
+                request_body["min_p"] = min_p.into();
+            }
+            if let Some(penalty) = self.config.repeat_penalty {
+                request_body["repeat_penalty"] = penalty.into();
+            }
+        }
 
         let response = self
             .client
@@ -115,6 +153,22 @@ impl LLMBackend for OpenAIBackend {
         })
     }
 
+    async fn health_check(&self) -> Result<(), LLMError> {
+        let api_key = self
+            .config
+            .api_key
+            .as_ref()
+            .ok_or_else(|| LLMError("No API key configured".to_string()))?;
+        let response = self
+            .client
+            .get(format!("{}/models", self.base_url()))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .timeout(HEALTH_CHECK_TIMEOUT)
+            .send()
+            .await;
+        health_check_outcome(response).await
+    }
+
     fn is_available(&self) -> bool {
         self.config.api_key.is_some()
     }
@@ -177,13 +231,19 @@ impl LLMBackend for AnthropicBackend {
             })
             .collect();
 
-        let request_body = serde_json::json!({
+        let mut request_body = serde_json::json!({
             "model": &self.config.model,
             "system": system,
             "messages": messages,
             "max_tokens": self.config.max_tokens,
             "temperature": self.config.temperature
         });
+        if !self.config.stop.is_empty() {
+            request_body["stop_sequences"] = serde_json::json!(self.config.stop);
+        }
+        if let Some(top_k) = self.config.top_k {
+            request_body["top_k"] = top_k.into();
+        }
 
         let response = self
             .client
@@ -216,6 +276,23 @@ impl LLMBackend for AnthropicBackend {
         Ok(content.to_string())
     }
 
+    async fn health_check(&self) -> Result<(), LLMError> {
+        let api_key = self
+            .config
+            .api_key
+            .as_ref()
+            .ok_or_else(|| LLMError("No API key configured".to_string()))?;
+        let response = self
+            .client
+            .get(format!("{}/models", self.base_url()))
+            .header("x-api-key", api_key)
+            .header("anthropic-version", "2023-06-01")
+            .timeout(HEALTH_CHECK_TIMEOUT)
+            .send()
+            .await;
+        health_check_outcome(response).await
+    }
+
     fn is_available(&self) -> bool {
         self.config.api_key.is_some()
     }
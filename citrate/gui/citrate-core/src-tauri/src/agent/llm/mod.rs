@@ -6,13 +6,18 @@
 //! - Local GGUF models
 
 use async_trait::async_trait;
+use citrate_mcp::gguf_engine::SamplingParams;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use super::context::ContextWindow;
+use super::streaming::TokenSink;
 
 pub mod api;
 pub mod local;
+pub mod router;
 
 /// LLM configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +34,21 @@ pub struct LLMConfig {
     pub temperature: f32,
     /// Top-p sampling
     pub top_p: f32,
+    /// Top-k sampling (local models and compatible servers)
+    #[serde(default)]
+    pub top_k: Option<u32>,
+    /// Min-p sampling (local models and compatible servers)
+    #[serde(default)]
+    pub min_p: Option<f32>,
+    /// Repetition penalty (local models and compatible servers)
+    #[serde(default)]
+    pub repeat_penalty: Option<f32>,
+    /// Sequences that end generation
+    #[serde(default)]
+    pub stop: Vec<String>,
+    /// Bias added to the logits of the given token ids
+    #[serde(default)]
+    pub logit_bias: BTreeMap<u32, f32>,
     /// Whether to stream responses
     pub stream: bool,
     /// Whether to format tool results with LLM
@@ -50,6 +70,11 @@ impl Default for LLMConfig {
             max_tokens: 2048,
             temperature: 0.7,
             top_p: 1.0,
+            top_k: None,
+            min_p: None,
+            repeat_penalty: None,
+            stop: Vec::new(),
+            logit_bias: BTreeMap::new(),
             stream: true,
             format_tool_results: true,
             local_model_path: None,
@@ -59,6 +84,21 @@ impl Default for LLMConfig {
     }
 }
 
+impl LLMConfig {
+    /// Sampler settings for local GGUF inference
+    pub fn sampling(&self) -> SamplingParams {
+        SamplingParams {
+            temperature: self.temperature,
+            top_k: self.top_k,
+            top_p: (self.top_p < 1.0).then_some(self.top_p),
+            min_p: self.min_p,
+            repeat_penalty: self.repeat_penalty,
+            stop: self.stop.clone(),
+            logit_bias: self.logit_bias.clone(),
+        }
+    }
+}
+
 /// Type of LLM backend
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -85,6 +125,14 @@ impl std::fmt::Display for LLMError {
 
 impl std::error::Error for LLMError {}
 
+impl LLMError {
+    /// Whether the request never reached the provider: no connection, DNS
+    /// failure or timeout. The API backends report these as "Request failed".
+    pub fn is_network(&self) -> bool {
+        self.0.starts_with("Request failed")
+    }
+}
+
 /// Token usage information
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct TokenUsage {
@@ -129,11 +177,53 @@ pub trait LLMBackend: Send + Sync {
         })
     }
 
+    /// Complete a prompt, pushing text into `tokens` as it is generated.
+    /// Backends that cannot stream send the whole response at once.
+    async fn complete_stream(
+        &self,
+        context: &ContextWindow,
+        tokens: &TokenSink,
+    ) -> Result<String, LLMError> {
+        let text = self.complete(context).await?;
+        tokens.send(&text);
+        Ok(text)
+    }
+
+    /// Apply a LoRA adapter on top of the loaded model's weights, replacing
+    /// any adapter already applied
+    async fn apply_adapter(&self, _adapter_path: &Path, _scale: f32) -> Result<(), LLMError> {
+        Err(LLMError(format!(
+            "The {} backend does not support LoRA adapters",
+            self.name()
+        )))
+    }
+
+    /// Go back to the base weights
+    async fn remove_adapter(&self) -> Result<(), LLMError> {
+        Ok(())
+    }
+
+    /// Path of the applied LoRA adapter, if any
+    async fn active_adapter(&self) -> Option<PathBuf> {
+        None
+    }
+
     /// Check if backend is available
     fn is_available(&self) -> bool {
         true
     }
 
+    /// Check that the backend can serve a request right now, without
+    /// generating anything. Backends with nothing to probe report whether
+    /// they are available.
+    async fn health_check(&self) -> Result<(), LLMError> {
+        if self.is_available() {
+            Ok(())
+        } else {
+            Err(LLMError(format!("The {} backend is not configured", self.name())))
+        }
+    }
+
     /// Get configuration
     fn config(&self) -> &LLMConfig;
 }
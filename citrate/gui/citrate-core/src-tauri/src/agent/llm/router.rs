@@ -0,0 +1,693 @@
+//! Provider routing with health tracking and failover
+//!
+//! [`ProviderRouter`] wraps every configured backend. A request goes to the
+//! best ranked provider and fails over to the next one when it errors, so a
+//! conversation carries on when a provider goes down mid-session.
+//!
+//! Providers are ranked by their smoothed latency, weighted up by their
+//! recent error rate. A provider that keeps failing cools down for a while
+//! and is only tried after the others. A network failure on a cloud
+//! provider marks the router offline for a short grace period, during which
+//! the local GGUF model is tried first.
+//!
+//! Providers appended only as a fallback (the local model when it is not in
+//! the preferred order) rank behind every preferred provider while online,
+//! whatever the strategy, so the configured chain of preferred remote
+//! provider, secondary provider and local model is the order actually tried.
+//! [`ProviderRouter::check_health`] probes every provider without spending a
+//! completion, so a dead provider cools down before a user request hits it.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::{LLMBackend, LLMConfig, LLMError, UnconfiguredLLMBackend};
+use crate::agent::config::{AIProvider, RoutingStrategy};
+use crate::agent::context::ContextWindow;
+use crate::agent::streaming::TokenSink;
+
+/// Weight of the newest sample in the latency average
+const LATENCY_SMOOTHING: f64 = 0.3;
+
+/// Weight of the newest outcome in the error rate
+const ERROR_SMOOTHING: f64 = 0.2;
+
+/// How much a fully failing provider's latency is inflated when ranking
+const ERROR_PENALTY: f64 = 4.0;
+
+/// Cooldown after the first of a run of failures; doubles with each one
+const BASE_COOLDOWN: Duration = Duration::from_secs(15);
+
+const MAX_COOLDOWN: Duration = Duration::from_secs(300);
+
+/// How long the router assumes it is offline after a network failure
+const OFFLINE_GRACE: Duration = Duration::from_secs(30);
+
+/// Routing choices for one session
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RouteOverride {
+    /// Provider to try first, regardless of ranking
+    #[serde(default)]
+    pub pinned: Option<AIProvider>,
+    /// Providers the session never uses
+    #[serde(default)]
+    pub excluded: Vec<AIProvider>,
+    /// Ranking used instead of the configured one
+    #[serde(default)]
+    pub strategy: Option<RoutingStrategy>,
+}
+
+impl RouteOverride {
+    /// This session route with `request`'s choices applied for one request.
+    /// The request's pin and strategy win; exclusions from both apply.
+    pub fn with_request(&self, request: &RouteOverride) -> RouteOverride {
+        let mut excluded = self.excluded.clone();
+        for provider in &request.excluded {
+            if !excluded.contains(provider) {
+                excluded.push(*provider);
+            }
+        }
+        RouteOverride {
+            pinned: request.pinned.or(self.pinned),
+            excluded,
+            strategy: request.strategy.or(self.strategy),
+        }
+    }
+}
+
+/// A provider and its backend
+pub struct Route {
+    pub provider: AIProvider,
+    pub backend: Box<dyn LLMBackend + Send + Sync>,
+    /// Only tried after the preferred providers, unless the router is offline
+    pub fallback: bool,
+}
+
+/// Health of one provider, as shown in the settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderHealth {
+    pub provider: AIProvider,
+    pub backend: String,
+    pub requests: u64,
+    pub failures: u64,
+    /// Smoothed share of recent requests that failed, 0.0 - 1.0
+    pub error_rate: f64,
+    /// Smoothed latency of successful requests
+    pub latency_ms: Option<f64>,
+    /// False while cooling down after repeated failures
+    pub healthy: bool,
+    pub last_error: Option<String>,
+    /// Whether the provider is only a fallback behind the preferred ones
+    pub fallback: bool,
+}
+
+#[derive(Debug, Default)]
+struct Stats {
+    requests: u64,
+    failures: u64,
+    consecutive_failures: u32,
+    latency_ms: Option<f64>,
+    error_rate: f64,
+    last_error: Option<String>,
+    cooling_until: Option<Instant>,
+}
+
+impl Stats {
+    fn is_cooling(&self, now: Instant) -> bool {
+        self.cooling_until.is_some_and(|until| now < until)
+    }
+
+    /// Ranking score; lower is better. Unmeasured providers score 0 so they
+    /// get tried and measured.
+    fn score(&self) -> f64 {
+        self.latency_ms.unwrap_or(0.0) * (1.0 + ERROR_PENALTY * self.error_rate)
+    }
+}
+
+struct State {
+    stats: Vec<Stats>,
+    offline_until: Option<Instant>,
+}
+
+/// Routes requests over the configured providers
+pub struct ProviderRouter {
+    routes: Vec<Route>,
+    strategy: RoutingStrategy,
+    state: Mutex<State>,
+    /// Reports the setup error when no provider is configured
+    unconfigured: UnconfiguredLLMBackend,
+}
+
+impl ProviderRouter {
+    /// Router over `routes`, given in preference order
+    pub fn new(routes: Vec<Route>, strategy: RoutingStrategy) -> Self {
+        let stats = routes.iter().map(|_| Stats::default()).collect();
+        Self {
+            routes,
+            strategy,
+            state: Mutex::new(State {
+                stats,
+                offline_until: None,
+            }),
+            unconfigured: UnconfiguredLLMBackend::new(),
+        }
+    }
+
+    /// The router as seen by a session with `route` applied
+    pub fn for_route(&self, route: RouteOverride) -> SessionRoute<'_> {
+        SessionRoute {
+            router: self,
+            route,
+        }
+    }
+
+    /// Providers in the order they are tried for `route`
+    pub fn order(&self, route: &RouteOverride) -> Vec<AIProvider> {
+        self.ranked(route, Instant::now())
+            .into_iter()
+            .map(|index| self.routes[index].provider)
+            .collect()
+    }
+
+    /// Health of every provider, in preference order
+    pub fn health(&self) -> Vec<ProviderHealth> {
+        let now = Instant::now();
+        let state = self.state.lock().unwrap();
+        self.routes
+            .iter()
+            .zip(&state.stats)
+            .map(|(route, stats)| ProviderHealth {
+                provider: route.provider,
+                backend: route.backend.name().to_string(),
+                requests: stats.requests,
+                failures: stats.failures,
+                error_rate: stats.error_rate,
+                latency_ms: stats.latency_ms,
+                healthy: !stats.is_cooling(now),
+                last_error: stats.last_error.clone(),
+                fallback: route.fallback,
+            })
+            .collect()
+    }
+
+    /// Probe every provider with a health check and return the updated
+    /// health. A failed probe cools the provider down and, for a cloud
+    /// provider that cannot be reached, marks the router offline, exactly
+    /// as a failed request would.
+    pub async fn check_health(&self) -> Vec<ProviderHealth> {
+        for (index, route) in self.routes.iter().enumerate() {
+            match route.backend.health_check().await {
+                Ok(()) => self.record_success(index, None),
+                Err(e) => {
+                    tracing::warn!("Health check of {} failed: {}", route.provider, e.0);
+                    self.record_failure(index, &e, false);
+                }
+            }
+        }
+        self.health()
+    }
+
+    /// Whether a recent network failure suggests there is no connection
+    pub fn is_offline(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state
+            .offline_until
+            .is_some_and(|until| Instant::now() < until)
+    }
+
+    /// Indices of the routes to try, best first
+    fn ranked(&self, route: &RouteOverride, now: Instant) -> Vec<usize> {
+        let state = self.state.lock().unwrap();
+        let offline = state.offline_until.is_some_and(|until| now < until);
+        let strategy = route.strategy.unwrap_or(self.strategy);
+
+        let mut indices: Vec<usize> = (0..self.routes.len())
+            .filter(|&i| !route.excluded.contains(&self.routes[i].provider))
+            .collect();
+        indices.sort_by(|&a, &b| {
+            let key = |i: usize| {
+                let stats = &state.stats[i];
+                let provider = self.routes[i].provider;
+                let score = match strategy {
+                    RoutingStrategy::Latency => stats.score(),
+                    RoutingStrategy::Ordered => 0.0,
+                };
+                (
+                    route.pinned != Some(provider),
+                    stats.is_cooling(now),
+                    offline && provider != AIProvider::Local,
+                    !offline && self.routes[i].fallback,
+                    score,
+                )
+            };
+            let (ka, kb) = (key(a), key(b));
+            (ka.0, ka.1, ka.2, ka.3)
+                .cmp(&(kb.0, kb.1, kb.2, kb.3))
+                .then(ka.4.total_cmp(&kb.4))
+                .then(a.cmp(&b))
+        });
+        indices
+    }
+
+    /// Update a provider's statistics with the outcome of a request
+    fn record(&self, index: usize, result: &Result<String, LLMError>, elapsed: Duration) {
+        match result {
+            Ok(_) => self.record_success(index, Some(elapsed)),
+            Err(e) => self.record_failure(index, e, true),
+        }
+    }
+
+    /// Clear a provider's cooldown after it answered. Requests pass their
+    /// latency; health checks do not, and are not counted as requests.
+    fn record_success(&self, index: usize, elapsed: Option<Duration>) {
+        let is_local = self.routes[index].provider == AIProvider::Local;
+        let mut state = self.state.lock().unwrap();
+        let State {
+            stats,
+            offline_until,
+        } = &mut *state;
+        let stats = &mut stats[index];
+        if let Some(elapsed) = elapsed {
+            stats.requests += 1;
+            let sample = elapsed.as_secs_f64() * 1000.0;
+            stats.latency_ms = Some(match stats.latency_ms {
+                Some(avg) => avg + LATENCY_SMOOTHING * (sample - avg),
+                None => sample,
+            });
+            stats.error_rate *= 1.0 - ERROR_SMOOTHING;
+        }
+        stats.consecutive_failures = 0;
+        stats.cooling_until = None;
+        if !is_local {
+            *offline_until = None;
+        }
+    }
+
+    /// Cool a provider down after it failed. Only failed requests, not
+    /// failed health checks, count towards its statistics.
+    fn record_failure(&self, index: usize, error: &LLMError, is_request: bool) {
+        let now = Instant::now();
+        let is_local = self.routes[index].provider == AIProvider::Local;
+        let mut state = self.state.lock().unwrap();
+        let State {
+            stats,
+            offline_until,
+        } = &mut *state;
+        let stats = &mut stats[index];
+        if is_request {
+            stats.requests += 1;
+            stats.failures += 1;
+            stats.error_rate += ERROR_SMOOTHING * (1.0 - stats.error_rate);
+        }
+        stats.consecutive_failures += 1;
+        stats.last_error = Some(error.0.clone());
+        let cooldown = BASE_COOLDOWN
+            .saturating_mul(1 << (stats.consecutive_failures - 1).min(5))
+            .min(MAX_COOLDOWN);
+        stats.cooling_until = Some(now + cooldown);
+        if !is_local && error.is_network() {
+            *offline_until = Some(now + OFFLINE_GRACE);
+        }
+    }
+
+    /// Complete with the best provider for `route`, failing over to the
+    /// next one on error. When streaming, text from a failed provider is
+    /// reset before the next one starts.
+    pub async fn complete_routed(
+        &self,
+        context: &ContextWindow,
+        route: &RouteOverride,
+        tokens: Option<&TokenSink>,
+    ) -> Result<String, LLMError> {
+        if self.routes.is_empty() {
+            return self.unconfigured.complete(context).await;
+        }
+        let order = self.ranked(route, Instant::now());
+        if order.is_empty() {
+            return Err(LLMError(
+                "Every configured provider is excluded for this session".to_string(),
+            ));
+        }
+
+        let mut errors = Vec::new();
+        for (attempt, index) in order.into_iter().enumerate() {
+            let provider = self.routes[index].provider;
+            let backend = &self.routes[index].backend;
+            if attempt > 0 {
+                tracing::warn!("Failing over to {} after: {}", provider, errors.join("; "));
+                if let Some(tokens) = tokens {
+                    tokens.reset();
+                }
+            }
+
+            let start = Instant::now();
+            let result = match tokens {
+                Some(tokens) => backend.complete_stream(context, tokens).await,
+                None => backend.complete(context).await,
+            };
+            self.record(index, &result, start.elapsed());
+
+            match result {
+                Ok(text) => return Ok(text),
+                // A cancelled stream is not a provider failure to recover from
+                Err(e) if tokens.is_some_and(TokenSink::is_cancelled) => return Err(e),
+                Err(e) => errors.push(format!("{}: {}", provider, e.0)),
+            }
+        }
+
+        Err(LLMError(format!(
+            "All providers failed: {}",
+            errors.join("; ")
+        )))
+    }
+
+    /// The local model's backend, which handles LoRA adapters
+    fn local(&self) -> Option<&(dyn LLMBackend + Send + Sync)> {
+        self.routes
+            .iter()
+            .find(|route| route.provider == AIProvider::Local)
+            .map(|route| route.backend.as_ref())
+    }
+}
+
+#[async_trait]
+impl LLMBackend for ProviderRouter {
+    fn name(&self) -> &str {
+        match self.routes.as_slice() {
+            [] => self.unconfigured.name(),
+            [only] => only.backend.name(),
+            _ => "router",
+        }
+    }
+
+    async fn complete(&self, context: &ContextWindow) -> Result<String, LLMError> {
+        self.complete_routed(context, &RouteOverride::default(), None)
+            .await
+    }
+
+    async fn complete_stream(
+        &self,
+        context: &ContextWindow,
+        tokens: &TokenSink,
+    ) -> Result<String, LLMError> {
+        self.complete_routed(context, &RouteOverride::default(), Some(tokens))
+            .await
+    }
+
+    async fn apply_adapter(&self, adapter_path: &Path, scale: f32) -> Result<(), LLMError> {
+        match self.local() {
+            Some(local) => local.apply_adapter(adapter_path, scale).await,
+            None => Err(LLMError(
+                "LoRA adapters need a local model, and none is configured".to_string(),
+            )),
+        }
+    }
+
+    async fn remove_adapter(&self) -> Result<(), LLMError> {
+        match self.local() {
+            Some(local) => local.remove_adapter().await,
+            None => Ok(()),
+        }
+    }
+
+    async fn active_adapter(&self) -> Option<PathBuf> {
+        self.local()?.active_adapter().await
+    }
+
+    fn is_available(&self) -> bool {
+        self.routes.iter().any(|route| route.backend.is_available())
+    }
+
+    fn config(&self) -> &LLMConfig {
+        match self.routes.first() {
+            Some(route) => route.backend.config(),
+            None => self.unconfigured.config(),
+        }
+    }
+}
+
+/// A [`ProviderRouter`] with one session's routing choices applied
+pub struct SessionRoute<'a> {
+    router: &'a ProviderRouter,
+    route: RouteOverride,
+}
+
+#[async_trait]
+impl LLMBackend for SessionRoute<'_> {
+    fn name(&self) -> &str {
+        self.router.name()
+    }
+
+    async fn complete(&self, context: &ContextWindow) -> Result<String, LLMError> {
+        self.router
+            .complete_routed(context, &self.route, None)
+            .await
+    }
+
+    async fn complete_stream(
+        &self,
+        context: &ContextWindow,
+        tokens: &TokenSink,
+    ) -> Result<String, LLMError> {
+        self.router
+            .complete_routed(context, &self.route, Some(tokens))
+            .await
+    }
+
+    async fn apply_adapter(&self, adapter_path: &Path, scale: f32) -> Result<(), LLMError> {
+        self.router.apply_adapter(adapter_path, scale).await
+    }
+
+    async fn remove_adapter(&self) -> Result<(), LLMError> {
+        self.router.remove_adapter().await
+    }
+
+    async fn active_adapter(&self) -> Option<PathBuf> {
+        self.router.active_adapter().await
+    }
+
+    fn is_available(&self) -> bool {
+        self.router.is_available()
+    }
+
+    fn config(&self) -> &LLMConfig {
+        self.router.config()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Backend that fails with `error` until `fail_for` calls have been made
+    struct FlakyBackend {
+        config: LLMConfig,
+        reply: &'static str,
+        error: &'static str,
+        fail_for: usize,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl LLMBackend for FlakyBackend {
+        fn name(&self) -> &str {
+            self.reply
+        }
+
+        async fn complete(&self, _context: &ContextWindow) -> Result<String, LLMError> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.fail_for {
+                Err(LLMError(self.error.to_string()))
+            } else {
+                Ok(self.reply.to_string())
+            }
+        }
+
+        async fn health_check(&self) -> Result<(), LLMError> {
+            if self.calls.load(Ordering::SeqCst) < self.fail_for {
+                Err(LLMError(self.error.to_string()))
+            } else {
+                Ok(())
+            }
+        }
+
+        fn config(&self) -> &LLMConfig {
+            &self.config
+        }
+    }
+
+    fn route(
+        provider: AIProvider,
+        reply: &'static str,
+        error: &'static str,
+        fail_for: usize,
+    ) -> Route {
+        Route {
+            provider,
+            backend: Box::new(FlakyBackend {
+                config: LLMConfig::default(),
+                reply,
+                error,
+                fail_for,
+                calls: Arc::new(AtomicUsize::new(0)),
+            }),
+            fallback: false,
+        }
+    }
+
+    fn context() -> ContextWindow {
+        ContextWindow {
+            system_prompt: String::new(),
+            system_context: None,
+            messages: vec![],
+            estimated_tokens: 0,
+            was_truncated: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_and_cools_down_failing_provider() {
+        let router = ProviderRouter::new(
+            vec![
+                route(AIProvider::OpenAI, "openai", "API error: overloaded", 1),
+                route(AIProvider::Anthropic, "anthropic", "", 0),
+            ],
+            RoutingStrategy::Ordered,
+        );
+
+        let text = router.complete(&context()).await.unwrap();
+        assert_eq!(text, "anthropic");
+
+        // OpenAI is cooling down, so Anthropic now goes first
+        assert_eq!(
+            router.order(&RouteOverride::default()),
+            vec![AIProvider::Anthropic, AIProvider::OpenAI]
+        );
+        let health = router.health();
+        assert!(!health[0].healthy);
+        assert_eq!(health[0].failures, 1);
+        assert!(health[1].healthy && health[1].latency_ms.is_some());
+        assert!(!router.is_offline());
+    }
+
+    #[tokio::test]
+    async fn test_network_failure_prefers_local_model() {
+        let router = ProviderRouter::new(
+            vec![
+                route(AIProvider::OpenAI, "openai", "Request failed: dns error", 1),
+                route(
+                    AIProvider::Anthropic,
+                    "anthropic",
+                    "Request failed: dns error",
+                    1,
+                ),
+                route(AIProvider::Local, "local", "", 0),
+            ],
+            RoutingStrategy::Ordered,
+        );
+
+        assert_eq!(router.complete(&context()).await.unwrap(), "local");
+        assert!(router.is_offline());
+        assert_eq!(
+            router.order(&RouteOverride::default())[0],
+            AIProvider::Local
+        );
+    }
+
+    #[test]
+    fn test_session_override_and_latency_ranking() {
+        let router = ProviderRouter::new(
+            vec![
+                route(AIProvider::Local, "local", "", 0),
+                route(AIProvider::OpenAI, "openai", "", 0),
+                route(AIProvider::Anthropic, "anthropic", "", 0),
+            ],
+            RoutingStrategy::Latency,
+        );
+        router.record(0, &Ok(String::new()), Duration::from_millis(900));
+        router.record(1, &Ok(String::new()), Duration::from_millis(200));
+        router.record(2, &Ok(String::new()), Duration::from_millis(400));
+        assert_eq!(
+            router.order(&RouteOverride::default()),
+            vec![AIProvider::OpenAI, AIProvider::Anthropic, AIProvider::Local]
+        );
+
+        let pinned = RouteOverride {
+            pinned: Some(AIProvider::Local),
+            excluded: vec![AIProvider::OpenAI],
+            strategy: None,
+        };
+        assert_eq!(
+            router.order(&pinned),
+            vec![AIProvider::Local, AIProvider::Anthropic]
+        );
+
+        let ordered = RouteOverride {
+            strategy: Some(RoutingStrategy::Ordered),
+            ..Default::default()
+        };
+        assert_eq!(router.order(&ordered)[0], AIProvider::Local);
+    }
+
+    #[tokio::test]
+    async fn test_local_fallback_ranks_last_until_offline() {
+        let mut local = route(AIProvider::Local, "local", "", 0);
+        local.fallback = true;
+        let router = ProviderRouter::new(
+            vec![
+                route(AIProvider::OpenAI, "openai", "", 0),
+                route(
+                    AIProvider::Anthropic,
+                    "anthropic",
+                    "Request failed: timed out",
+                    1,
+                ),
+                local,
+            ],
+            RoutingStrategy::Latency,
+        );
+        router.record(0, &Ok(String::new()), Duration::from_millis(900));
+
+        // The unmeasured fallback does not jump ahead of slow preferred providers
+        assert_eq!(
+            router.order(&RouteOverride::default()),
+            vec![AIProvider::Anthropic, AIProvider::OpenAI, AIProvider::Local]
+        );
+
+        // A failed probe cools Anthropic down and takes the router offline,
+        // without counting as a request
+        let health = router.check_health().await;
+        assert!(!health[1].healthy && health[2].fallback);
+        assert_eq!(health[1].requests, 0);
+        assert!(router.is_offline());
+        assert_eq!(
+            router.order(&RouteOverride::default()),
+            vec![AIProvider::Local, AIProvider::OpenAI, AIProvider::Anthropic]
+        );
+    }
+
+    #[test]
+    fn test_request_override_layers_over_session_route() {
+        let session = RouteOverride {
+            pinned: Some(AIProvider::OpenAI),
+            excluded: vec![AIProvider::Gemini],
+            strategy: Some(RoutingStrategy::Latency),
+        };
+        let request = RouteOverride {
+            pinned: Some(AIProvider::Local),
+            excluded: vec![AIProvider::Anthropic, AIProvider::Gemini],
+            strategy: None,
+        };
+        let route = session.with_request(&request);
+        assert_eq!(route.pinned, Some(AIProvider::Local));
+        assert_eq!(
+            route.excluded,
+            vec![AIProvider::Gemini, AIProvider::Anthropic]
+        );
+        assert_eq!(route.strategy, Some(RoutingStrategy::Latency));
+        assert_eq!(session.with_request(&RouteOverride::default()), session);
+    }
+}
@@ -0,0 +1,565 @@
+//! Long-term agent memory - a vector index over past conversations
+//!
+//! Messages that scroll out of the prompt's recent-history window are
+//! summarized in chunks, embedded, and kept in an on-disk index. Before the
+//! agent answers a message, the memories closest to it are recalled into the
+//! system context, so what was said long ago (or in another session) can
+//! still inform the answer.
+//!
+//! Embeddings are computed locally: with a GGUF embedding model through
+//! llama.cpp when one is configured, otherwise with a built-in hashing
+//! embedder that needs no model file.
+
+use async_trait::async_trait;
+use citrate_mcp::gguf_engine::{GGUFEngine, GGUFEngineConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use super::session::{Message, MessageRole};
+
+/// Dimensions of the built-in hashing embedder
+pub const HASHING_DIMENSIONS: usize = 384;
+
+/// Characters of a single message kept in a fallback summary
+const SUMMARY_MESSAGE_CHARS: usize = 240;
+
+/// Error type for memory operations
+#[derive(Debug)]
+pub enum MemoryError {
+    /// The embedding model failed or returned nothing
+    Embedding(String),
+    /// IO error
+    Io(std::io::Error),
+    /// Serialization error
+    Serialization(String),
+}
+
+impl std::fmt::Display for MemoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Embedding(e) => write!(f, "Embedding failed: {}", e),
+            Self::Io(e) => write!(f, "IO error: {}", e),
+            Self::Serialization(e) => write!(f, "Serialization error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MemoryError {}
+
+impl From<std::io::Error> for MemoryError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for MemoryError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Serialization(e.to_string())
+    }
+}
+
+/// Turns text into vectors whose cosine similarity tracks relatedness
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Identifies the vector space; memories embedded by another model are
+    /// not comparable and are skipped when searching
+    fn name(&self) -> String;
+
+    /// One vector per text, in order
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, MemoryError>;
+}
+
+/// Embeds text by hashing its words and word pairs into a fixed number of
+/// buckets. Captures shared vocabulary rather than meaning, but is
+/// deterministic, instant, and works without any model file.
+#[derive(Debug, Clone)]
+pub struct HashingEmbedder {
+    dimensions: usize,
+}
+
+impl HashingEmbedder {
+    pub fn new(dimensions: usize) -> Self {
+        Self {
+            dimensions: dimensions.max(1),
+        }
+    }
+
+    fn embed_one(&self, text: &str) -> Vec<f32> {
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+
+        let mut vector = vec![0.0f32; self.dimensions];
+        let mut add = |feature: &str, weight: f32| {
+            let hash = fnv1a(feature.as_bytes());
+            let bucket = (hash % self.dimensions as u64) as usize;
+            // The top bit picks a sign so unrelated features cancel out
+            let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+            vector[bucket] += sign * weight;
+        };
+        for word in &words {
+            add(word, 1.0);
+        }
+        for pair in words.windows(2) {
+            add(&format!("{} {}", pair[0], pair[1]), 0.5);
+        }
+
+        normalize(&mut vector);
+        vector
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(HASHING_DIMENSIONS)
+    }
+}
+
+#[async_trait]
+impl Embedder for HashingEmbedder {
+    fn name(&self) -> String {
+        format!("hashing-{}", self.dimensions)
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, MemoryError> {
+        Ok(texts.iter().map(|text| self.embed_one(text)).collect())
+    }
+}
+
+/// Embeds text with a GGUF embedding model run by llama.cpp
+pub struct GgufEmbedder {
+    engine: GGUFEngine,
+    model_path: PathBuf,
+}
+
+impl GgufEmbedder {
+    pub fn new(model_path: impl Into<PathBuf>) -> Result<Self, MemoryError> {
+        let engine = GGUFEngine::new(GGUFEngineConfig::default())
+            .map_err(|e| MemoryError::Embedding(e.to_string()))?;
+        Ok(Self {
+            engine,
+            model_path: model_path.into(),
+        })
+    }
+}
+
+#[async_trait]
+impl Embedder for GgufEmbedder {
+    fn name(&self) -> String {
+        let file = self
+            .model_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        format!("gguf:{}", file)
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, MemoryError> {
+        let mut vectors = self
+            .engine
+            .generate_embeddings(&self.model_path, texts)
+            .await
+            .map_err(|e| MemoryError::Embedding(e.to_string()))?;
+        if vectors.len() != texts.len() {
+            return Err(MemoryError::Embedding(format!(
+                "expected {} embeddings, got {}",
+                texts.len(),
+                vectors.len()
+            )));
+        }
+        vectors.iter_mut().for_each(|vector| normalize(vector));
+        Ok(vectors)
+    }
+}
+
+/// A summary of part of a conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryEntry {
+    pub id: String,
+    /// Conversation the summarized messages came from
+    pub session_id: String,
+    pub summary: String,
+    /// Unix seconds
+    pub created_at: i64,
+    /// Embedder that produced `embedding`
+    pub embedder: String,
+    pub embedding: Vec<f32>,
+}
+
+/// A memory recalled for a query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryMatch {
+    pub id: String,
+    pub session_id: String,
+    pub summary: String,
+    pub created_at: i64,
+    /// Cosine similarity to the query, from -1 to 1
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct MemoryState {
+    #[serde(default)]
+    entries: Vec<MemoryEntry>,
+    /// Leading messages of each session already summarized into memory
+    #[serde(default)]
+    memorized: HashMap<String, usize>,
+}
+
+/// Persistent vector index of conversation summaries
+pub struct VectorMemory {
+    /// JSON file backing the index; `None` keeps it in memory only
+    path: Option<PathBuf>,
+    embedder: Arc<dyn Embedder>,
+    state: RwLock<MemoryState>,
+}
+
+impl VectorMemory {
+    /// Open the index in the user's local app data directory
+    pub fn new(embedder: Arc<dyn Embedder>) -> Result<Self, MemoryError> {
+        let data_dir = dirs::data_local_dir().ok_or_else(|| {
+            MemoryError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Could not find local data directory",
+            ))
+        })?;
+        Self::open(data_dir.join("citrate").join("agent_memory.json"), embedder)
+    }
+
+    /// Open (or create) the index stored at `path`
+    pub fn open(
+        path: impl Into<PathBuf>,
+        embedder: Arc<dyn Embedder>,
+    ) -> Result<Self, MemoryError> {
+        let path = path.into();
+        let state = if path.exists() {
+            serde_json::from_str(&std::fs::read_to_string(&path)?)?
+        } else {
+            MemoryState::default()
+        };
+
+        let memory = Self {
+            path: Some(path),
+            embedder,
+            state: RwLock::new(state),
+        };
+        tracing::info!("Agent memory loaded from: {:?}", memory.path);
+        Ok(memory)
+    }
+
+    /// An index that is never written to disk
+    pub fn in_memory(embedder: Arc<dyn Embedder>) -> Self {
+        Self {
+            path: None,
+            embedder,
+            state: RwLock::new(MemoryState::default()),
+        }
+    }
+
+    /// Path of the backing file, if any
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Number of stored memories
+    pub fn len(&self) -> usize {
+        self.read().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Embed and store a summary of part of `session_id`'s conversation
+    pub async fn remember(
+        &self,
+        session_id: &str,
+        summary: String,
+    ) -> Result<MemoryEntry, MemoryError> {
+        let embedding = self
+            .embedder
+            .embed(std::slice::from_ref(&summary))
+            .await?
+            .pop()
+            .ok_or_else(|| MemoryError::Embedding("no embedding returned".to_string()))?;
+
+        let entry = MemoryEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: session_id.to_string(),
+            summary,
+            created_at: chrono::Utc::now().timestamp(),
+            embedder: self.embedder.name(),
+            embedding,
+        };
+
+        let mut state = self.write();
+        state.entries.push(entry.clone());
+        self.persist(&state)?;
+        Ok(entry)
+    }
+
+    /// The `limit` memories most similar to `query` scoring at least
+    /// `min_score`, best first
+    pub async fn search(
+        &self,
+        query: &str,
+        limit: usize,
+        min_score: f32,
+    ) -> Result<Vec<MemoryMatch>, MemoryError> {
+        if limit == 0 || query.trim().is_empty() || self.is_empty() {
+            return Ok(Vec::new());
+        }
+        let query_vector = self
+            .embedder
+            .embed(&[query.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| MemoryError::Embedding("no embedding returned".to_string()))?;
+
+        let embedder = self.embedder.name();
+        let state = self.read();
+        let mut matches: Vec<MemoryMatch> = state
+            .entries
+            .iter()
+            .filter(|entry| entry.embedder == embedder)
+            .filter_map(|entry| {
+                let score = cosine_similarity(&query_vector, &entry.embedding)?;
+                (score >= min_score).then(|| MemoryMatch {
+                    id: entry.id.clone(),
+                    session_id: entry.session_id.clone(),
+                    summary: entry.summary.clone(),
+                    created_at: entry.created_at,
+                    score,
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+        matches.truncate(limit);
+        Ok(matches)
+    }
+
+    /// The next `chunk_size` messages of a session that have left the most
+    /// recent `keep_recent` and are not yet in memory, with the position
+    /// just past them. `None` until a full chunk has accumulated.
+    pub fn next_chunk(
+        &self,
+        session_id: &str,
+        messages: &[Message],
+        keep_recent: usize,
+        chunk_size: usize,
+    ) -> Option<(usize, Vec<Message>)> {
+        let chunk_size = chunk_size.max(1);
+        // A cleared history can leave the marker past the end
+        let start = self
+            .read()
+            .memorized
+            .get(session_id)
+            .copied()
+            .unwrap_or(0)
+            .min(messages.len());
+        let old = messages.len().saturating_sub(keep_recent);
+        if old < start + chunk_size {
+            return None;
+        }
+        let end = start + chunk_size;
+        Some((end, messages[start..end].to_vec()))
+    }
+
+    /// Record that the first `count` messages of a session are in memory
+    pub fn mark_memorized(&self, session_id: &str, count: usize) -> Result<(), MemoryError> {
+        let mut state = self.write();
+        state.memorized.insert(session_id.to_string(), count);
+        self.persist(&state)
+    }
+
+    /// Drop a session's memories; returns how many were removed
+    pub fn forget_session(&self, session_id: &str) -> Result<usize, MemoryError> {
+        let mut state = self.write();
+        let before = state.entries.len();
+        state.entries.retain(|entry| entry.session_id != session_id);
+        state.memorized.remove(session_id);
+        self.persist(&state)?;
+        Ok(before - state.entries.len())
+    }
+
+    /// Write the index to disk, replacing the previous file atomically
+    fn persist(&self, state: &MemoryState) -> Result<(), MemoryError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string(state)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, MemoryState> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, MemoryState> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The user and assistant turns of `messages` as a plain transcript
+pub fn transcript(messages: &[Message]) -> String {
+    turns(messages).collect::<Vec<_>>().join("\n")
+}
+
+/// Summary used when no model can write one: each turn, shortened
+pub fn extractive_summary(messages: &[Message]) -> String {
+    turns(messages)
+        .map(|turn| {
+            let turn = turn.replace('\n', " ");
+            match turn.char_indices().nth(SUMMARY_MESSAGE_CHARS) {
+                Some((cut, _)) => format!("{}...", &turn[..cut]),
+                None => turn,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn turns(messages: &[Message]) -> impl Iterator<Item = String> + '_ {
+    messages.iter().filter_map(|msg| {
+        let speaker = match msg.role {
+            MessageRole::User => "User",
+            MessageRole::Assistant => "Assistant",
+            _ => return None,
+        };
+        Some(format!("{}: {}", speaker, msg.content.trim()))
+    })
+}
+
+/// 64-bit FNV-1a, stable across platforms and releases
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+}
+
+/// Cosine similarity, or `None` for vectors of different sizes
+fn cosine_similarity(a: &[f32], b: &[f32]) -> Option<f32> {
+    if a.len() != b.len() {
+        return None;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norms =
+        a.iter().map(|v| v * v).sum::<f32>().sqrt() * b.iter().map(|v| v * v).sum::<f32>().sqrt();
+    Some(if norms > 0.0 { dot / norms } else { 0.0 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory() -> VectorMemory {
+        VectorMemory::in_memory(Arc::new(HashingEmbedder::default()))
+    }
+
+    #[tokio::test]
+    async fn test_search_ranks_related_memories_first() {
+        let memory = memory();
+        memory
+            .remember(
+                "s1",
+                "User staked 500 SALT with validator alpha".to_string(),
+            )
+            .await
+            .unwrap();
+        memory
+            .remember(
+                "s1",
+                "User deployed an ERC20 token contract named Lime".to_string(),
+            )
+            .await
+            .unwrap();
+
+        let matches = memory
+            .search("which validator did I stake with", 5, 0.1)
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert!(matches[0].summary.contains("validator alpha"));
+
+        assert!(memory.search("validator", 0, 0.0).await.unwrap().is_empty());
+        assert!(memory.search("   ", 5, 0.0).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_next_chunk_skips_recent_and_memorized_messages() {
+        let memory = memory();
+        let messages: Vec<Message> = (0..12)
+            .map(|i| Message::user(format!("message {}", i)))
+            .collect();
+
+        // 12 messages, 10 kept recent: only 2 old, not a full chunk of 4
+        assert!(memory.next_chunk("s1", &messages, 10, 4).is_none());
+
+        let (end, chunk) = memory.next_chunk("s1", &messages, 6, 4).unwrap();
+        assert_eq!(end, 4);
+        assert_eq!(chunk[0].content, "message 0");
+
+        memory.mark_memorized("s1", end).unwrap();
+        assert!(memory.next_chunk("s1", &messages, 6, 4).is_none());
+        let (end, chunk) = memory.next_chunk("s1", &messages, 4, 4).unwrap();
+        assert_eq!(end, 8);
+        assert_eq!(chunk[0].content, "message 4");
+    }
+
+    #[tokio::test]
+    async fn test_memory_persists_and_forgets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("agent_memory.json");
+        let embedder: Arc<dyn Embedder> = Arc::new(HashingEmbedder::default());
+
+        let memory = VectorMemory::open(&path, embedder.clone()).unwrap();
+        memory
+            .remember("s1", "Gas price was 20 gwei".to_string())
+            .await
+            .unwrap();
+        memory
+            .remember("s2", "Node ran out of disk".to_string())
+            .await
+            .unwrap();
+        memory.mark_memorized("s1", 6).unwrap();
+
+        let reopened = VectorMemory::open(&path, embedder).unwrap();
+        assert_eq!(reopened.len(), 2);
+        assert_eq!(reopened.forget_session("s1").unwrap(), 1);
+        assert_eq!(reopened.len(), 1);
+
+        // Memories from another embedding model are never compared
+        let other = VectorMemory::open(&path, Arc::new(HashingEmbedder::new(64))).unwrap();
+        assert!(other.search("disk", 5, -1.0).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_extractive_summary() {
+        let messages = vec![
+            Message::user("x".repeat(500)),
+            Message::system("hidden".to_string()),
+            Message::assistant("Done".to_string()),
+        ];
+        let summary = extractive_summary(&messages);
+        let lines: Vec<&str> = summary.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("..."));
+        assert_eq!(lines[1], "Assistant: Done");
+    }
+}
@@ -9,18 +9,27 @@
 // - Streaming response infrastructure
 // - Conversation context management
 // - Hybrid LLM support (API + local GGUF)
+// - Versioned prompt and persona library
+// - Scheduled tasks run unattended within a tool budget
+// - File tools confined to user-granted directories, with an audit log
+// - Shell commands run in an agent terminal session after user approval
+// - Long-term memory: old messages summarized into a local vector index
 
 pub mod classifier;
 pub mod commands;
 pub mod config;
 pub mod context;
 pub mod dispatcher;
+pub mod file_access;
 pub mod formatting;
 pub mod intent;
 pub mod llm;
+pub mod memory;
 pub mod onboarding;
 pub mod orchestrator;
+pub mod prompts;
 pub mod react;
+pub mod scheduler;
 pub mod session;
 pub mod storage;
 pub mod streaming;
@@ -32,19 +41,24 @@ pub use commands::AgentState;
 pub use config::{
     AgentConfig, AIProvider, AIProvidersConfig, ProviderSettings,
     LLMBackendType, LLMConfig, ClassifierConfig, ToolConfig,
-    StreamingConfig, ContextConfig,
+    StreamingConfig, ContextConfig, MemoryConfig,
 };
 pub use context::{ContextManager, ContextWindow, ConversationHistory};
 pub use dispatcher::ToolDispatcher;
+pub use file_access::{DirectoryGrant, FileAccess, FileAuditEntry, FilePermissionManager};
 pub use formatting::{FormattedResult, ResultCategory};
 pub use intent::{Intent, IntentMatch, IntentParams};
+pub use memory::{Embedder, HashingEmbedder, MemoryEntry, MemoryMatch, VectorMemory};
 pub use onboarding::{OnboardingManager, SkillLevel, UserAssessment, AssessmentResponse};
 pub use orchestrator::AgentOrchestrator;
+pub use prompts::{PromptAssignment, PromptEntry, PromptLibrary, PromptSummary};
 pub use session::{AgentSession, SessionId};
 pub use storage::{ConversationStorage, ConversationMetadata};
-pub use react::{ReActExecutor, ReActResult, ReActStep};
-pub use streaming::{StreamToken, StreamingResponse};
+pub use react::{ProposedAction, ReActExecutor, ReActResult, ReActStep, ToolPolicy};
+pub use scheduler::{AutoModePolicy, ScheduledTask, TaskCompleted, TaskRun, TaskScheduler};
+pub use streaming::{StreamEvent, StreamToken, StreamingResponse, TokenSink, STREAM_EVENT_PREFIX};
 
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -52,6 +66,7 @@ use crate::node::NodeManager;
 use crate::wallet::WalletManager;
 use crate::models::ModelManager;
 use crate::dag::DAGManager;
+use crate::terminal::TerminalManager;
 
 /// Agent manager that coordinates all agent components
 pub struct AgentManager {
@@ -68,6 +83,7 @@ impl AgentManager {
         wallet_manager: Arc<WalletManager>,
         model_manager: Arc<ModelManager>,
         dag_manager: Arc<RwLock<Option<Arc<DAGManager>>>>,
+        terminal_manager: Arc<RwLock<TerminalManager>>,
     ) -> Self {
         let config = AgentConfig::default();
         let orchestrator = AgentOrchestrator::new(
@@ -76,6 +92,7 @@ impl AgentManager {
             wallet_manager,
             model_manager,
             dag_manager,
+            terminal_manager,
         );
 
         Self {
@@ -113,6 +130,29 @@ impl AgentManager {
         true
     }
 
+    /// Run the local model at `model_path` with `adapter` (path and scale)
+    /// applied on top, or with no adapter. The base weights are only loaded
+    /// if `model_path` is not the model already running, so switching
+    /// between adapters of the same base model skips the reload. Returns
+    /// the adapter now applied.
+    pub async fn apply_adapter(
+        &self,
+        model_path: String,
+        adapter: Option<(PathBuf, f32)>,
+    ) -> Result<Option<PathBuf>, String> {
+        let loaded = self.config.read().await.providers.local_model_path.clone();
+        if loaded.as_deref() != Some(model_path.as_str()) {
+            self.configure_local_model(model_path).await;
+        }
+
+        let orchestrator = self.orchestrator.read().await;
+        orchestrator
+            .set_adapter(adapter.as_ref().map(|(path, scale)| (path.as_path(), *scale)))
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(orchestrator.active_adapter().await)
+    }
+
     /// Check if the agent has a working LLM backend configured
     pub async fn has_llm_backend(&self) -> bool {
         let config = self.config.read().await;
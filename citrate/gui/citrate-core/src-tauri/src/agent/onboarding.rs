@@ -8,6 +8,7 @@
 // - Personalized onboarding paths based on skill level
 // - Context-aware guidance that adapts to user responses
 
+use crate::tr;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -296,16 +297,12 @@ impl OnboardingManager {
 
     /// Get the welcome message
     pub fn get_welcome_message() -> String {
-        r#"Welcome to Citrate! I'm your AI assistant, and I'm here to help you get started.
-
-To give you the best experience, I'd like to ask you a few quick questions about your background. This will help me tailor my guidance to your skill level.
-
-Ready to begin? Just say "yes" or "let's go" to start the assessment, or say "skip" if you'd prefer to jump right in."#.to_string()
+        tr!("onboarding.welcome")
     }
 
     /// Get the assessment intro message
     pub fn get_assessment_intro() -> String {
-        "Great! I'll ask you 4 quick questions. For each one, just pick the option that best describes your experience.".to_string()
+        tr!("onboarding.intro")
     }
 
     /// Format a question for display
@@ -316,58 +313,18 @@ Ready to begin? Just say "yes" or "let's go" to start the assessment, or say "sk
             msg.push_str(&format!("{}. {}\n", i + 1, option.text));
         }
 
-        msg.push_str("\nJust reply with the number of your choice.");
+        msg.push('\n');
+        msg.push_str(&tr!("onboarding.reply_with_number"));
         msg
     }
 
     /// Get result message based on skill level
     pub fn get_result_message(level: SkillLevel) -> String {
         match level {
-            SkillLevel::Beginner => {
-                r#"**Assessment Complete!**
-
-Based on your responses, I've set you up with our **Beginner** path. Don't worry - we'll start with the basics and build up from there!
-
-Here's what we'll cover:
-1. Understanding blockchain basics
-2. Setting up your first wallet
-3. Getting test tokens
-4. Sending your first transaction
-5. Exploring the Citrate network
-
-Let's start with your wallet setup. Would you like me to guide you through creating a new wallet?"#.to_string()
-            }
-            SkillLevel::Intermediate => {
-                r#"**Assessment Complete!**
-
-Great, looks like you have some blockchain experience! I've set you up with our **Intermediate** path.
-
-Here's what we'll focus on:
-1. Wallet configuration and security
-2. Interacting with smart contracts
-3. Using the DAG explorer
-4. Running AI model inference
-5. Developer tools and SDK
-
-Would you like to start by connecting your wallet, or would you prefer to explore the smart contract tools?"#.to_string()
-            }
-            SkillLevel::Advanced => {
-                r#"**Assessment Complete!**
-
-Excellent, you're well-versed in blockchain and development! I've set you up with our **Advanced** path.
-
-Here's what I can help you with:
-1. Direct API access and SDK integration
-2. Smart contract deployment and debugging
-3. AI model deployment and training
-4. DAG architecture deep-dive
-5. Contributing to Citrate development
-
-What would you like to explore first? I can show you the API documentation, help you deploy a contract, or dive into the model marketplace."#.to_string()
-            }
-            SkillLevel::Unknown => {
-                "Let's figure out the best path for you. Ready to answer a few questions?".to_string()
-            }
+            SkillLevel::Beginner => tr!("onboarding.result.beginner"),
+            SkillLevel::Intermediate => tr!("onboarding.result.intermediate"),
+            SkillLevel::Advanced => tr!("onboarding.result.advanced"),
+            SkillLevel::Unknown => tr!("onboarding.result.unknown"),
         }
     }
 
@@ -376,89 +333,89 @@ What would you like to explore first? I can show you the API documentation, help
         vec![
             AssessmentQuestion {
                 id: "q1_wallet".to_string(),
-                question: "Have you used a blockchain wallet before (like MetaMask, Phantom, or similar)?".to_string(),
+                question: tr!("onboarding.q1_wallet.question"),
                 category: QuestionCategory::Blockchain,
                 options: vec![
                     AssessmentOption {
-                        text: "No, this would be my first time".to_string(),
+                        text: tr!("onboarding.q1_wallet.option1"),
                         skill_points: 0,
-                        follow_up: Some("No worries! I'll walk you through setting up your first wallet.".to_string()),
+                        follow_up: Some(tr!("onboarding.q1_wallet.follow_up1")),
                     },
                     AssessmentOption {
-                        text: "Yes, I've used one a few times".to_string(),
+                        text: tr!("onboarding.q1_wallet.option2"),
                         skill_points: 1,
-                        follow_up: Some("Great, you'll find Citrate's wallet familiar then.".to_string()),
+                        follow_up: Some(tr!("onboarding.q1_wallet.follow_up2")),
                     },
                     AssessmentOption {
-                        text: "Yes, I use wallets regularly for transactions and dApps".to_string(),
+                        text: tr!("onboarding.q1_wallet.option3"),
                         skill_points: 2,
-                        follow_up: Some("Excellent! You're ready to hit the ground running.".to_string()),
+                        follow_up: Some(tr!("onboarding.q1_wallet.follow_up3")),
                     },
                 ],
             },
             AssessmentQuestion {
                 id: "q2_contracts".to_string(),
-                question: "Have you written or deployed smart contracts before?".to_string(),
+                question: tr!("onboarding.q2_contracts.question"),
                 category: QuestionCategory::SmartContracts,
                 options: vec![
                     AssessmentOption {
-                        text: "No, I don't know what smart contracts are".to_string(),
+                        text: tr!("onboarding.q2_contracts.option1"),
                         skill_points: 0,
-                        follow_up: Some("Smart contracts are programs that run on the blockchain. We'll explore them together!".to_string()),
+                        follow_up: Some(tr!("onboarding.q2_contracts.follow_up1")),
                     },
                     AssessmentOption {
-                        text: "I've read about them but never written one".to_string(),
+                        text: tr!("onboarding.q2_contracts.option2"),
                         skill_points: 1,
-                        follow_up: Some("Perfect time to start! Citrate makes contract deployment straightforward.".to_string()),
+                        follow_up: Some(tr!("onboarding.q2_contracts.follow_up2")),
                     },
                     AssessmentOption {
-                        text: "Yes, I've deployed contracts on Ethereum, Solana, or similar".to_string(),
+                        text: tr!("onboarding.q2_contracts.option3"),
                         skill_points: 2,
-                        follow_up: Some("Nice! Citrate is EVM-compatible, so your Solidity skills will transfer directly.".to_string()),
+                        follow_up: Some(tr!("onboarding.q2_contracts.follow_up3")),
                     },
                 ],
             },
             AssessmentQuestion {
                 id: "q3_ai".to_string(),
-                question: "Are you familiar with AI model inference or machine learning?".to_string(),
+                question: tr!("onboarding.q3_ai.question"),
                 category: QuestionCategory::AIModels,
                 options: vec![
                     AssessmentOption {
-                        text: "No, AI is new to me".to_string(),
+                        text: tr!("onboarding.q3_ai.option1"),
                         skill_points: 0,
-                        follow_up: Some("That's okay! Citrate makes AI accessible - you can use models without being an ML expert.".to_string()),
+                        follow_up: Some(tr!("onboarding.q3_ai.follow_up1")),
                     },
                     AssessmentOption {
-                        text: "I've used AI tools like ChatGPT but don't know how they work".to_string(),
+                        text: tr!("onboarding.q3_ai.option2"),
                         skill_points: 1,
-                        follow_up: Some("Good start! You'll find running models on Citrate similar to using those tools.".to_string()),
+                        follow_up: Some(tr!("onboarding.q3_ai.follow_up2")),
                     },
                     AssessmentOption {
-                        text: "Yes, I've trained or fine-tuned models before".to_string(),
+                        text: tr!("onboarding.q3_ai.option3"),
                         skill_points: 2,
-                        follow_up: Some("Awesome! You can deploy and monetize your models on Citrate's marketplace.".to_string()),
+                        follow_up: Some(tr!("onboarding.q3_ai.follow_up3")),
                     },
                 ],
             },
             AssessmentQuestion {
                 id: "q4_dev".to_string(),
-                question: "What's your general development experience?".to_string(),
+                question: tr!("onboarding.q4_dev.question"),
                 category: QuestionCategory::Technical,
                 options: vec![
                     AssessmentOption {
-                        text: "I'm not a developer".to_string(),
+                        text: tr!("onboarding.q4_dev.option1"),
                         skill_points: 0,
-                        follow_up: Some("No problem! You can still use Citrate's features through the GUI and my guidance.".to_string()),
+                        follow_up: Some(tr!("onboarding.q4_dev.follow_up1")),
                     },
                     AssessmentOption {
-                        text: "I know some programming (Python, JavaScript, etc.)".to_string(),
+                        text: tr!("onboarding.q4_dev.option2"),
                         skill_points: 1,
-                        follow_up: Some("Great! You'll be able to use our SDKs and build on Citrate.".to_string()),
+                        follow_up: Some(tr!("onboarding.q4_dev.follow_up2")),
                     },
                     AssessmentOption {
-                        text: "I'm an experienced developer with Rust/TypeScript/Solidity experience".to_string(),
+                        text: tr!("onboarding.q4_dev.option3"),
                         skill_points: 2,
-                        follow_up: Some("Excellent! You can dive right into the codebase and contribute.".to_string()),
+                        follow_up: Some(tr!("onboarding.q4_dev.follow_up3")),
                     },
                 ],
             },
@@ -473,13 +430,13 @@ What would you like to explore first? I can show you the API documentation, help
         paths.insert(
             SkillLevel::Beginner,
             OnboardingPath {
-                name: "Beginner's Journey".to_string(),
-                description: "A gentle introduction to blockchain and Citrate".to_string(),
+                name: tr!("onboarding.path.beginner.name"),
+                description: tr!("onboarding.path.beginner.description"),
                 steps: vec![
                     OnboardingStep {
                         id: "b1".to_string(),
-                        title: "Welcome to Blockchain".to_string(),
-                        content: "Let's start with the basics. A blockchain is a shared, unchangeable ledger that records transactions. Citrate is a special blockchain that also supports AI models!".to_string(),
+                        title: tr!("onboarding.step.b1.title"),
+                        content: tr!("onboarding.step.b1.content"),
                         action: Some(OnboardingAction::OpenDocs {
                             url: "https://docs.citrate.ai/intro".to_string()
                         }),
@@ -487,43 +444,43 @@ What would you like to explore first? I can show you the API documentation, help
                     },
                     OnboardingStep {
                         id: "b1.5".to_string(),
-                        title: "Secure Your Wallet".to_string(),
-                        content: "Before we create your wallet, you need to set up a secure password. This password will encrypt your wallet and protect your funds.\n\n**Password Requirements:**\n- At least 12 characters\n- Mix of uppercase and lowercase letters\n- At least one number\n- At least one special character (!@#$%^&*...)".to_string(),
+                        title: tr!("onboarding.step.b1.5.title"),
+                        content: tr!("onboarding.step.b1.5.content"),
                         action: Some(OnboardingAction::SetupPassword),
                         optional: false,
                     },
                     OnboardingStep {
                         id: "b2".to_string(),
-                        title: "Create Your Wallet".to_string(),
-                        content: "Your wallet is like a digital identity on the blockchain. It holds your tokens and lets you interact with the network. Let's create one now.".to_string(),
+                        title: tr!("onboarding.step.b2.title"),
+                        content: tr!("onboarding.step.b2.content"),
                         action: Some(OnboardingAction::CreateWallet),
                         optional: false,
                     },
                     OnboardingStep {
                         id: "b2.5".to_string(),
-                        title: "Backup Your Recovery Phrase".to_string(),
-                        content: "Your recovery phrase is the ONLY way to restore your wallet if you lose access. Write it down on paper and store it safely.\n\n**NEVER share this phrase with anyone!**\n**NEVER store it digitally or take screenshots!**".to_string(),
+                        title: tr!("onboarding.step.b2.5.title"),
+                        content: tr!("onboarding.step.b2.5.content"),
                         action: Some(OnboardingAction::VerifyMnemonic),
                         optional: false,
                     },
                     OnboardingStep {
                         id: "b3".to_string(),
-                        title: "Get Test Tokens".to_string(),
-                        content: "Now let's get some test tokens from the faucet. These have no real value but let you practice transactions.".to_string(),
+                        title: tr!("onboarding.step.b3.title"),
+                        content: tr!("onboarding.step.b3.content"),
                         action: Some(OnboardingAction::RequestFaucet),
                         optional: false,
                     },
                     OnboardingStep {
                         id: "b4".to_string(),
-                        title: "Send Your First Transaction".to_string(),
-                        content: "Let's send a small amount to another address. This will help you understand how transactions work.".to_string(),
+                        title: tr!("onboarding.step.b4.title"),
+                        content: tr!("onboarding.step.b4.content"),
                         action: Some(OnboardingAction::SendTransaction),
                         optional: false,
                     },
                     OnboardingStep {
                         id: "b5".to_string(),
-                        title: "Explore the Network".to_string(),
-                        content: "Check out the DAG visualization to see how blocks are connected. Citrate uses a DAG (Directed Acyclic Graph) instead of a simple chain.".to_string(),
+                        title: tr!("onboarding.step.b5.title"),
+                        content: tr!("onboarding.step.b5.content"),
                         action: Some(OnboardingAction::Navigate {
                             view: "dag".to_string()
                         }),
@@ -537,34 +494,34 @@ What would you like to explore first? I can show you the API documentation, help
         paths.insert(
             SkillLevel::Intermediate,
             OnboardingPath {
-                name: "Developer Onboarding".to_string(),
-                description: "For developers ready to build on Citrate".to_string(),
+                name: tr!("onboarding.path.intermediate.name"),
+                description: tr!("onboarding.path.intermediate.description"),
                 steps: vec![
                     OnboardingStep {
                         id: "i0.5".to_string(),
-                        title: "Secure Your Wallet".to_string(),
-                        content: "First, set up a secure password to protect your wallet.\n\n**Requirements:** 12+ characters, mixed case, numbers, and special characters.".to_string(),
+                        title: tr!("onboarding.step.i0.5.title"),
+                        content: tr!("onboarding.step.i0.5.content"),
                         action: Some(OnboardingAction::SetupPassword),
                         optional: false,
                     },
                     OnboardingStep {
                         id: "i1".to_string(),
-                        title: "Connect Your Wallet".to_string(),
-                        content: "Import an existing wallet or create a new one. We recommend securing it with a hardware wallet for production use.".to_string(),
+                        title: tr!("onboarding.step.i1.title"),
+                        content: tr!("onboarding.step.i1.content"),
                         action: Some(OnboardingAction::CreateWallet),
                         optional: false,
                     },
                     OnboardingStep {
                         id: "i1.5".to_string(),
-                        title: "Verify Backup".to_string(),
-                        content: "Please verify you've backed up your recovery phrase. This is critical for wallet recovery.".to_string(),
+                        title: tr!("onboarding.step.i1.5.title"),
+                        content: tr!("onboarding.step.i1.5.content"),
                         action: Some(OnboardingAction::VerifyMnemonic),
                         optional: false,
                     },
                     OnboardingStep {
                         id: "i2".to_string(),
-                        title: "Explore Smart Contracts".to_string(),
-                        content: "Citrate is EVM-compatible. You can deploy Solidity contracts just like on Ethereum. Check out our contract templates.".to_string(),
+                        title: tr!("onboarding.step.i2.title"),
+                        content: tr!("onboarding.step.i2.content"),
                         action: Some(OnboardingAction::Navigate {
                             view: "contracts".to_string()
                         }),
@@ -572,8 +529,8 @@ What would you like to explore first? I can show you the API documentation, help
                     },
                     OnboardingStep {
                         id: "i3".to_string(),
-                        title: "Try the Model Marketplace".to_string(),
-                        content: "Browse AI models deployed on Citrate. You can run inference on-chain or license models for your own applications.".to_string(),
+                        title: tr!("onboarding.step.i3.title"),
+                        content: tr!("onboarding.step.i3.content"),
                         action: Some(OnboardingAction::Navigate {
                             view: "marketplace".to_string()
                         }),
@@ -581,15 +538,15 @@ What would you like to explore first? I can show you the API documentation, help
                     },
                     OnboardingStep {
                         id: "i4".to_string(),
-                        title: "Run Model Inference".to_string(),
-                        content: "Let's run inference on a model. This shows how Citrate integrates AI directly into blockchain transactions.".to_string(),
+                        title: tr!("onboarding.step.i4.title"),
+                        content: tr!("onboarding.step.i4.content"),
                         action: Some(OnboardingAction::RunInference),
                         optional: true,
                     },
                     OnboardingStep {
                         id: "i5".to_string(),
-                        title: "SDK Integration".to_string(),
-                        content: "Check out our JavaScript and Python SDKs to integrate Citrate into your applications.".to_string(),
+                        title: tr!("onboarding.step.i5.title"),
+                        content: tr!("onboarding.step.i5.content"),
                         action: Some(OnboardingAction::OpenDocs {
                             url: "https://docs.citrate.ai/sdk".to_string()
                         }),
@@ -603,34 +560,34 @@ What would you like to explore first? I can show you the API documentation, help
         paths.insert(
             SkillLevel::Advanced,
             OnboardingPath {
-                name: "Power User Setup".to_string(),
-                description: "Quick setup for experienced developers".to_string(),
+                name: tr!("onboarding.path.advanced.name"),
+                description: tr!("onboarding.path.advanced.description"),
                 steps: vec![
                     OnboardingStep {
                         id: "a0.5".to_string(),
-                        title: "Secure Wallet Password".to_string(),
-                        content: "Set a secure password for wallet encryption. Requirements: 12+ chars, mixed case, numbers, special characters.".to_string(),
+                        title: tr!("onboarding.step.a0.5.title"),
+                        content: tr!("onboarding.step.a0.5.content"),
                         action: Some(OnboardingAction::SetupPassword),
                         optional: false,
                     },
                     OnboardingStep {
                         id: "a1".to_string(),
-                        title: "Quick Wallet Setup".to_string(),
-                        content: "Import your existing keys or create a new wallet. Hardware wallet support available.".to_string(),
+                        title: tr!("onboarding.step.a1.title"),
+                        content: tr!("onboarding.step.a1.content"),
                         action: Some(OnboardingAction::CreateWallet),
                         optional: false,
                     },
                     OnboardingStep {
                         id: "a1.5".to_string(),
-                        title: "Verify Recovery Phrase".to_string(),
-                        content: "Confirm you've securely backed up your mnemonic phrase.".to_string(),
+                        title: tr!("onboarding.step.a1.5.title"),
+                        content: tr!("onboarding.step.a1.5.content"),
                         action: Some(OnboardingAction::VerifyMnemonic),
                         optional: false,
                     },
                     OnboardingStep {
                         id: "a2".to_string(),
-                        title: "API & SDK Access".to_string(),
-                        content: "Direct RPC access at localhost:8545 (JSON-RPC) and localhost:8546 (WebSocket). SDKs available for JS/TS and Python.".to_string(),
+                        title: tr!("onboarding.step.a2.title"),
+                        content: tr!("onboarding.step.a2.content"),
                         action: Some(OnboardingAction::OpenDocs {
                             url: "https://docs.citrate.ai/api".to_string()
                         }),
@@ -638,15 +595,15 @@ What would you like to explore first? I can show you the API documentation, help
                     },
                     OnboardingStep {
                         id: "a3".to_string(),
-                        title: "Deploy a Contract".to_string(),
-                        content: "Deploy your Solidity contracts using Foundry (forge) or Hardhat. Citrate is fully EVM-compatible.".to_string(),
+                        title: tr!("onboarding.step.a3.title"),
+                        content: tr!("onboarding.step.a3.content"),
                         action: Some(OnboardingAction::DeployContract),
                         optional: true,
                     },
                     OnboardingStep {
                         id: "a4".to_string(),
-                        title: "Model Deployment".to_string(),
-                        content: "Deploy your own AI models to the marketplace. Supports GGUF format for efficient inference.".to_string(),
+                        title: tr!("onboarding.step.a4.title"),
+                        content: tr!("onboarding.step.a4.content"),
                         action: Some(OnboardingAction::Navigate {
                             view: "model-deploy".to_string()
                         }),
@@ -654,8 +611,8 @@ What would you like to explore first? I can show you the API documentation, help
                     },
                     OnboardingStep {
                         id: "a5".to_string(),
-                        title: "Architecture Deep-Dive".to_string(),
-                        content: "Explore GhostDAG consensus, the MCP layer, and Citrate's unique architecture.".to_string(),
+                        title: tr!("onboarding.step.a5.title"),
+                        content: tr!("onboarding.step.a5.content"),
                         action: Some(OnboardingAction::OpenDocs {
                             url: "https://docs.citrate.ai/architecture".to_string()
                         }),
@@ -733,10 +690,7 @@ What would you like to explore first? I can show you the API documentation, help
             None => {
                 // Invalid response, ask again
                 AssessmentResponse::InvalidResponse {
-                    message: format!(
-                        "I didn't catch that. Please reply with a number from 1 to {}.",
-                        question.options.len()
-                    ),
+                    message: tr!("onboarding.invalid_response", count = question.options.len()),
                     question: Self::format_question(question),
                 }
             }
@@ -765,16 +719,21 @@ What would you like to explore first? I can show you the API documentation, help
     }
 
     /// Check if user wants to skip assessment
+    ///
+    /// Also accepts the replies suggested by the translated welcome message.
     pub fn wants_to_skip(response: &str) -> bool {
         let lower = response.to_lowercase();
-        lower.contains("skip") || lower.contains("jump") || lower.contains("later")
+        lower.contains("skip") || lower.contains("jump") || lower.contains("later") ||
+        lower.contains("omitir") || lower.contains("跳过")
     }
 
     /// Check if user wants to start assessment
     pub fn wants_to_start(response: &str) -> bool {
         let lower = response.to_lowercase();
         lower.contains("yes") || lower.contains("go") || lower.contains("start") ||
-        lower.contains("ready") || lower.contains("ok") || lower.contains("sure")
+        lower.contains("ready") || lower.contains("ok") || lower.contains("sure") ||
+        lower.contains("sí") || lower.contains("vamos") ||
+        lower.contains("开始") || lower.contains("好的")
     }
 }
 
@@ -807,13 +766,16 @@ impl MnemonicVerifier {
 
     /// Format verification prompt
     pub fn format_prompt(state: &MnemonicVerificationState) -> String {
-        let mut prompt = String::from("To verify you've backed up your recovery phrase, please enter the following words:\n\n");
+        let mut prompt = tr!("mnemonic.prompt");
+        prompt.push_str("\n\n");
 
         for (i, &idx) in state.verification_indices.iter().enumerate() {
-            prompt.push_str(&format!("{}. Word #{} (position {})\n", i + 1, idx + 1, idx + 1));
+            prompt.push_str(&tr!("mnemonic.prompt_word", index = i + 1, position = idx + 1));
+            prompt.push('\n');
         }
 
-        prompt.push_str("\nEnter the words separated by spaces:");
+        prompt.push('\n');
+        prompt.push_str(&tr!("mnemonic.prompt_footer"));
         prompt
     }
 
@@ -822,27 +784,23 @@ impl MnemonicVerifier {
         let user_words: Vec<&str> = response.split_whitespace().collect();
 
         if user_words.len() != state.verification_indices.len() {
-            return Err(format!(
-                "Expected {} words, got {}",
-                state.verification_indices.len(),
-                user_words.len()
+            return Err(tr!(
+                "mnemonic.word_count_mismatch",
+                expected = state.verification_indices.len(),
+                actual = user_words.len()
             ));
         }
 
         for (i, &idx) in state.verification_indices.iter().enumerate() {
             if idx >= state.mnemonic_words.len() {
-                return Err("Invalid verification state".to_string());
+                return Err(tr!("mnemonic.invalid_state"));
             }
 
             let expected = &state.mnemonic_words[idx];
             let provided = user_words[i].to_lowercase();
 
             if expected.to_lowercase() != provided {
-                return Err(format!(
-                    "Word {} (position {}) is incorrect",
-                    i + 1,
-                    idx + 1
-                ));
+                return Err(tr!("mnemonic.word_incorrect", index = i + 1, position = idx + 1));
             }
         }
 
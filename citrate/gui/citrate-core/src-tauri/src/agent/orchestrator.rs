@@ -5,28 +5,38 @@
 //! 2. Classifies intent
 //! 3. Dispatches to appropriate tools or LLM
 //! 4. Streams response back to user
-//! 5. Manages conversation context
+//! 5. Manages conversation context, recalling long-term memories of older
+//!    messages
 
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use super::classifier::IntentClassifier;
-use super::config::{AgentConfig, ClassifierConfig};
+use super::config::{AIProvider, AgentConfig, ClassifierConfig};
 use super::context::{ContextMessage, ContextWindow, ConversationHistory, SystemContext};
-use super::dispatcher::ToolDispatcher;
-use super::intent::{Intent, IntentMatch};
-use super::llm::{LLMBackend, LLMConfig, LLMFactory};
-use super::react::ReActExecutor;
-use super::session::{AgentSession, Message, MessageRole, SessionId};
+use super::dispatcher::{ToolDispatcher, ToolOutput};
+use super::file_access::FilePermissionManager;
+use super::intent::{Intent, IntentMatch, IntentParams};
+use super::llm::router::{ProviderHealth, ProviderRouter, Route, RouteOverride, SessionRoute};
+use super::llm::{LLMBackend, LLMConfig, LLMError, LLMFactory};
+use super::memory::{self, Embedder, GgufEmbedder, HashingEmbedder, VectorMemory};
+use super::prompts::{PromptAssignment, PromptLibrary};
+use super::react::{ProposedAction, ReActExecutor, ToolPolicy};
+use super::scheduler::{self, AutoModePolicy, ScheduledTask, TaskRun, TaskScheduler};
+use super::session::{AgentSession, Message, MessageRole, PendingToolCall, SessionId};
 use super::storage::{ConversationStorage, ConversationMetadata};
-use super::streaming::StreamManager;
+use super::streaming::{StreamManager, TokenSink};
 use super::tools::register_all_tools;
 
 use crate::dag::DAGManager;
 use crate::models::ModelManager;
 use crate::node::NodeManager;
+use crate::terminal::TerminalManager;
 use crate::wallet::WalletManager;
+use citrate_execution::units::{format_units_truncated, Unit, WEI_PER_LATT};
+use citrate_mcp::safety::SafetyPipeline;
 
 /// Result type for orchestrator operations
 pub type OrchestratorResult<T> = Result<T, OrchestratorError>;
@@ -102,16 +112,26 @@ pub struct AgentOrchestrator {
     sessions: RwLock<HashMap<String, Arc<AgentSession>>>,
     /// Persistent storage for conversations
     storage: Option<Arc<ConversationStorage>>,
+    /// Named, versioned system prompts and personas
+    prompts: Arc<PromptLibrary>,
+    /// Recurring tasks the agent runs unattended
+    scheduler: Arc<TaskScheduler>,
+    /// Summaries of older messages, recalled by similarity
+    memory: Arc<VectorMemory>,
+    /// Directories the file tools may access, and their audit log
+    file_permissions: Arc<FilePermissionManager>,
     /// Intent classifier
     classifier: IntentClassifier,
     /// Tool dispatcher
     dispatcher: ToolDispatcher,
-    /// LLM backend
-    llm: Box<dyn LLMBackend + Send + Sync>,
+    /// LLM backends of every configured provider
+    llm: ProviderRouter,
     /// ReAct executor for tool orchestration
     react_executor: ReActExecutor,
     /// Stream manager
     stream_manager: Arc<StreamManager>,
+    /// Content-safety checks, when enabled in the config
+    safety: Option<Arc<SafetyPipeline>>,
     /// Node manager reference
     node_manager: Arc<NodeManager>,
     /// Wallet manager reference
@@ -130,9 +150,15 @@ impl AgentOrchestrator {
         wallet_manager: Arc<WalletManager>,
         model_manager: Arc<ModelManager>,
         dag_manager: Arc<RwLock<Option<Arc<DAGManager>>>>,
+        terminal_manager: Arc<RwLock<TerminalManager>>,
     ) -> Self {
         let classifier = IntentClassifier::new(config.classifier.clone());
 
+        let file_permissions = Arc::new(FilePermissionManager::new().unwrap_or_else(|e| {
+            tracing::warn!("Failed to load file permissions: {}. Grants will not persist.", e);
+            FilePermissionManager::in_memory()
+        }));
+
         // Create dispatcher and register all tools with real manager implementations
         let mut dispatcher = ToolDispatcher::new();
         register_all_tools(
@@ -141,11 +167,14 @@ impl AgentOrchestrator {
             wallet_manager.clone(),
             model_manager.clone(),
             dag_manager.clone(),
+            file_permissions.clone(),
+            terminal_manager,
         );
 
-        // Use the config to create the appropriate LLM backend
-        // This will check for local models, API keys, and fall back to UnconfiguredLLMBackend
+        // Route over every configured provider: local models and API keys.
+        // With none configured, requests fail with setup instructions.
         let llm = Self::create_llm_from_config(&config);
+        let safety = Self::create_safety(&config);
 
         // Initialize persistent storage if enabled
         let storage = if config.context.persist_conversations {
@@ -163,15 +192,38 @@ impl AgentOrchestrator {
             None
         };
 
+        let prompts = match PromptLibrary::new() {
+            Ok(library) => library,
+            Err(e) => {
+                tracing::warn!("Failed to load prompt library: {}. Prompts will not persist.", e);
+                PromptLibrary::in_memory()
+            }
+        };
+
+        let scheduler = match TaskScheduler::new() {
+            Ok(scheduler) => scheduler,
+            Err(e) => {
+                tracing::warn!("Failed to load scheduled tasks: {}. Tasks will not persist.", e);
+                TaskScheduler::in_memory()
+            }
+        };
+
+        let memory = Self::create_memory(&config);
+
         Self {
             config,
             sessions: RwLock::new(HashMap::new()),
             storage,
+            prompts: Arc::new(prompts),
+            scheduler: Arc::new(scheduler),
+            memory: Arc::new(memory),
+            file_permissions,
             classifier,
             dispatcher,
             llm,
             react_executor: ReActExecutor::new(),
             stream_manager: Arc::new(StreamManager::new()),
+            safety,
             node_manager,
             wallet_manager,
             model_manager,
@@ -266,11 +318,36 @@ impl AgentOrchestrator {
         self.sessions.read().await.keys().cloned().collect()
     }
 
-    /// Process a user message
+    /// Process a user message. A `route` applies to this message only, on
+    /// top of the session's routing override.
     pub async fn process_message(
         &self,
         session_id: &str,
         user_message: &str,
+        route: Option<&RouteOverride>,
+    ) -> OrchestratorResult<ProcessingResult> {
+        self.process(session_id, user_message, route, None).await
+    }
+
+    /// Process a user message, streaming the response into `tokens` as it
+    /// is generated. A cancelled stream fails with a stream error and the
+    /// partial response is not recorded.
+    pub async fn process_message_streaming(
+        &self,
+        session_id: &str,
+        user_message: &str,
+        route: Option<&RouteOverride>,
+        tokens: &TokenSink,
+    ) -> OrchestratorResult<ProcessingResult> {
+        self.process(session_id, user_message, route, Some(tokens)).await
+    }
+
+    async fn process(
+        &self,
+        session_id: &str,
+        user_message: &str,
+        request_route: Option<&RouteOverride>,
+        tokens: Option<&TokenSink>,
     ) -> OrchestratorResult<ProcessingResult> {
         // Get or create session (try loading from storage first)
         let session = match self.get_session(session_id).await {
@@ -283,6 +360,10 @@ impl AgentOrchestrator {
         };
 
         let sid = session.id().clone();
+        let route = match request_route {
+            Some(request) => session.route().await.with_request(request),
+            None => session.route().await,
+        };
 
         // Create user message
         let user_msg = Message::user(user_message.to_string());
@@ -295,11 +376,30 @@ impl AgentOrchestrator {
             }
         }
 
+        // Refuse messages the safety policy blocks without calling a model
+        let source = format!("agent:{}", sid);
+        if let Some(safety) = &self.safety {
+            if safety.check_prompt(&source, user_message).await.is_blocked() {
+                let response = Message::assistant(safety.block_message().to_string());
+                if let Some(tokens) = tokens {
+                    tokens.send(&response.content);
+                }
+                self.record_message(&session, &response).await;
+                return Ok(ProcessingResult {
+                    response,
+                    intent: IntentMatch::new(Intent::Unknown, 1.0, IntentParams::default()),
+                    tool_invoked: false,
+                    tool_result: None,
+                    was_streamed: tokens.is_some(),
+                });
+            }
+        }
+
         // Classify intent
         let intent_match = self.classify_intent(user_message).await?;
 
         // Process based on intent
-        let (response, tool_invoked, tool_result) = match &intent_match.intent {
+        let (mut response, tool_invoked, tool_result) = match &intent_match.intent {
             // Direct tool intents
             Intent::QueryBalance
             | Intent::SendTransaction
@@ -313,23 +413,37 @@ impl AgentOrchestrator {
             | Intent::ListModels
             | Intent::RunInference
             | Intent::DeployModel => {
-                self.handle_tool_intent(&session, &intent_match, user_message)
+                self.handle_tool_intent(&route, &intent_match, user_message, tokens)
                     .await?
             }
 
             // Conversational intents - use LLM
             Intent::GeneralChat | Intent::Help | Intent::Unknown => {
-                self.handle_chat_intent(&session, &intent_match, user_message)
+                self.handle_chat_intent(&session, &route, &intent_match, user_message, tokens)
                     .await?
             }
 
             // Other intents
             _ => {
-                self.handle_chat_intent(&session, &intent_match, user_message)
+                self.handle_chat_intent(&session, &route, &intent_match, user_message, tokens)
                     .await?
             }
         };
 
+        if tokens.is_some_and(TokenSink::is_cancelled) {
+            return Err(OrchestratorError::StreamError("Generation cancelled".to_string()));
+        }
+
+        if let Some(safety) = &self.safety {
+            if safety.check_output(&source, &response.content).await.is_blocked() {
+                response = Message::assistant(safety.block_message().to_string());
+                if let Some(tokens) = tokens {
+                    tokens.reset();
+                    tokens.send(&response.content);
+                }
+            }
+        }
+
         // Add response to session
         session.add_message(response.clone()).await;
 
@@ -340,12 +454,14 @@ impl AgentOrchestrator {
             }
         }
 
+        self.memorize_old_messages(&session).await;
+
         Ok(ProcessingResult {
             response,
             intent: intent_match,
             tool_invoked,
             tool_result,
-            was_streamed: false,
+            was_streamed: tokens.is_some(),
         })
     }
 
@@ -360,9 +476,10 @@ impl AgentOrchestrator {
     /// Handle a tool-based intent
     async fn handle_tool_intent(
         &self,
-        _session: &Arc<AgentSession>,
+        route: &RouteOverride,
         intent: &IntentMatch,
         user_message: &str,
+        tokens: Option<&TokenSink>,
     ) -> OrchestratorResult<(Message, bool, Option<ToolResult>)> {
         // Get the tool for this intent
         let tool_name = intent.intent.tool_name().unwrap_or("unknown");
@@ -378,12 +495,22 @@ impl AgentOrchestrator {
                 };
 
                 // Format response with LLM if configured
-                let response_text = if self.config.llm.max_tokens > 0 {
-                    self.format_tool_result_with_llm(user_message, &output)
+                let formatted = if self.config.llm.max_tokens > 0 {
+                    self.format_tool_result_with_llm(route, user_message, &output, tokens)
                         .await
-                        .unwrap_or(output)
+                        .ok()
                 } else {
-                    output
+                    None
+                };
+                let response_text = match formatted {
+                    Some(text) => text,
+                    None => {
+                        if let Some(tokens) = tokens {
+                            tokens.reset();
+                            tokens.send(&output);
+                        }
+                        output
+                    }
                 };
 
                 Ok((
@@ -394,6 +521,9 @@ impl AgentOrchestrator {
             }
             Err(e) => {
                 let error_msg = format!("Tool execution failed: {}", e);
+                if let Some(tokens) = tokens {
+                    tokens.send(&error_msg);
+                }
                 Ok((Message::assistant(error_msg), true, None))
             }
         }
@@ -404,45 +534,33 @@ impl AgentOrchestrator {
     async fn handle_chat_intent(
         &self,
         session: &Arc<AgentSession>,
+        route: &RouteOverride,
         _intent: &IntentMatch,
         user_message: &str,
+        tokens: Option<&TokenSink>,
     ) -> OrchestratorResult<(Message, bool, Option<ToolResult>)> {
         tracing::debug!("handle_chat_intent starting with ReAct executor");
 
-        // Get system context for the LLM
-        let system_context = self.get_system_context().await;
-
-        // Get recent messages for conversation context
-        let recent = session.recent_messages(10).await;
-        tracing::debug!("Got {} recent messages for context", recent.len());
+        // Get system context for the LLM, with memories of older messages
+        let mut system_context = self.get_system_context().await;
+        system_context.memories = self.recall_memories(user_message).await;
 
-        // Convert to ContextMessage format for ReAct
-        let conversation_history: Vec<ContextMessage> = recent
-            .iter()
-            .filter_map(|msg| {
-                let role = match msg.role {
-                    MessageRole::User => "user",
-                    MessageRole::Assistant => "assistant",
-                    _ => return None, // Skip system messages etc.
-                };
-                Some(ContextMessage {
-                    role: role.to_string(),
-                    content: msg.content.clone(),
-                    name: None,
-                    tool_call_id: None,
-                })
-            })
-            .collect();
+        let conversation_history = self.conversation_history(session).await;
 
         tracing::debug!("Calling ReAct executor with {} history messages", conversation_history.len());
 
+        let persona = self.session_prompt(session).await;
+        let llm = self.llm.for_route(route.clone());
+
         // Execute using ReAct pattern
         let react_result = self.react_executor.execute(
             user_message,
-            self.llm.as_ref(),
+            &llm,
             &self.dispatcher,
             Some(system_context),
             &conversation_history,
+            persona.as_deref(),
+            tokens,
         ).await;
 
         tracing::debug!(
@@ -452,6 +570,8 @@ impl AgentOrchestrator {
             react_result.tools_used
         );
 
+        self.queue_proposals(session, &react_result.pending_approvals).await;
+
         // Determine if tools were used
         let tool_invoked = !react_result.tools_used.is_empty();
         let tool_result = if tool_invoked {
@@ -471,11 +591,315 @@ impl AgentOrchestrator {
         Ok((Message::assistant(react_result.response), tool_invoked, tool_result))
     }
 
+    /// Recent user and assistant messages in the format ReAct expects
+    async fn conversation_history(&self, session: &AgentSession) -> Vec<ContextMessage> {
+        let recent = session
+            .recent_messages(self.config.memory.recent_messages)
+            .await;
+        tracing::debug!("Got {} recent messages for context", recent.len());
+
+        recent
+            .iter()
+            .filter_map(|msg| {
+                let role = match msg.role {
+                    MessageRole::User => "user",
+                    MessageRole::Assistant => "assistant",
+                    _ => return None, // Skip system messages etc.
+                };
+                Some(ContextMessage {
+                    role: role.to_string(),
+                    content: msg.content.clone(),
+                    name: None,
+                    tool_call_id: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Hold proposed actions on the session until the user approves them
+    async fn queue_proposals(&self, session: &AgentSession, proposals: &[ProposedAction]) {
+        for proposal in proposals {
+            session
+                .add_pending_tool(PendingToolCall {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    tool_name: proposal.tool.clone(),
+                    params: serde_json::to_value(&proposal.params).unwrap_or_default(),
+                    description: proposal.description.clone(),
+                    high_risk: true,
+                    created_at: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_millis() as u64,
+                })
+                .await;
+        }
+    }
+
+    /// Save a message to the session and, if enabled, to storage
+    async fn record_message(&self, session: &AgentSession, message: &Message) {
+        session.add_message(message.clone()).await;
+        if let Some(ref storage) = self.storage {
+            if let Err(e) = storage.save_message(session.id(), message).await {
+                tracing::warn!("Failed to save message to storage: {}", e);
+            }
+        }
+    }
+
+    /// Summaries of older messages most related to `query`, for the prompt
+    async fn recall_memories(&self, query: &str) -> Vec<String> {
+        let settings = &self.config.memory;
+        if !settings.enabled {
+            return Vec::new();
+        }
+        match self
+            .memory
+            .search(query, settings.recall_limit, settings.min_score)
+            .await
+        {
+            Ok(matches) => matches.into_iter().map(|m| m.summary).collect(),
+            Err(e) => {
+                tracing::warn!("Failed to search agent memory: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Summarize the oldest chunk of messages that has left the
+    /// recent-history window into long-term memory. One chunk per call, so a
+    /// long restored conversation is absorbed over several turns.
+    async fn memorize_old_messages(&self, session: &AgentSession) {
+        let settings = &self.config.memory;
+        if !settings.enabled {
+            return;
+        }
+        let session_id = &session.id().0;
+        let messages = session.messages().await;
+        let Some((end, chunk)) = self.memory.next_chunk(
+            session_id,
+            &messages,
+            settings.recent_messages,
+            settings.chunk_size,
+        ) else {
+            return;
+        };
+
+        let summary = self.summarize_messages(session, &chunk).await;
+        if !summary.is_empty() {
+            if let Err(e) = self.memory.remember(session_id, summary).await {
+                tracing::warn!("Failed to store agent memory: {}", e);
+                return;
+            }
+        }
+        if let Err(e) = self.memory.mark_memorized(session_id, end) {
+            tracing::warn!("Failed to update agent memory: {}", e);
+        }
+    }
+
+    /// A short summary of `messages` written by the session's LLM, or the
+    /// shortened turns themselves when no model is available
+    async fn summarize_messages(&self, session: &AgentSession, messages: &[Message]) -> String {
+        let transcript = memory::transcript(messages);
+        if transcript.is_empty() {
+            return String::new();
+        }
+
+        let mut history = ConversationHistory::new();
+        history.add_message(Message::user(format!(
+            "Summarize this conversation excerpt in at most three sentences. \
+             Keep names, addresses, amounts and decisions.\n\n{}",
+            transcript
+        )));
+        let context = history.build_context_window(
+            "You write short factual notes about conversations for later recall.",
+            None,
+            4096,
+            1,
+        );
+
+        match self.session_llm(session).await.complete(&context).await {
+            Ok(summary) if !summary.trim().is_empty() => summary.trim().to_string(),
+            Ok(_) => memory::extractive_summary(messages),
+            Err(e) => {
+                tracing::debug!("Summarizing with the LLM failed, keeping excerpts: {}", e);
+                memory::extractive_summary(messages)
+            }
+        }
+    }
+
+    /// Get the long-term memory index
+    pub fn memory(&self) -> Arc<VectorMemory> {
+        self.memory.clone()
+    }
+
+    /// Get the task scheduler
+    pub fn task_scheduler(&self) -> Arc<TaskScheduler> {
+        self.scheduler.clone()
+    }
+
+    /// Get the file permission manager
+    pub fn file_permissions(&self) -> Arc<FilePermissionManager> {
+        self.file_permissions.clone()
+    }
+
+    /// Run every task that is due; returns how many ran
+    pub async fn run_due_tasks(&self) -> usize {
+        let due = self.scheduler.due(scheduler::now());
+        for task in &due {
+            if let Err(e) = self.run_scheduled_task(&task.id).await {
+                tracing::warn!("Scheduled task '{}' failed: {}", task.name, e);
+            }
+        }
+        due.len()
+    }
+
+    /// Run a scheduled task now, within its tool budget and auto-mode
+    /// policy. The prompt and answer are added to the task's conversation,
+    /// and the run is recorded on the task, which notifies subscribers.
+    pub async fn run_scheduled_task(&self, task_id: &str) -> OrchestratorResult<TaskRun> {
+        let task = self
+            .scheduler
+            .get(task_id)
+            .map_err(|e| OrchestratorError::Internal(e.to_string()))?;
+        let started_at = scheduler::now();
+        let session = self.task_session(&task).await;
+
+        let prompt = Message::user(format!("[Scheduled: {}] {}", task.name, task.prompt));
+        self.record_message(&session, &prompt).await;
+
+        let policy = ToolPolicy {
+            max_calls: Some(task.tool_budget),
+            read_only: task.policy == AutoModePolicy::ReadOnly,
+            allowed_tools: task.allowed_tools.clone(),
+        };
+        // Leave room for reasoning steps and a final answer after the last tool
+        let executor =
+            ReActExecutor::with_max_iterations(task.tool_budget + 2).with_tool_policy(policy);
+        let system_context = self.get_system_context().await;
+        let conversation_history = self.conversation_history(&session).await;
+        let persona = self.session_prompt(&session).await;
+        let llm = self.session_llm(&session).await;
+        let result = executor
+            .execute(
+                &task.prompt,
+                &llm,
+                &self.dispatcher,
+                Some(system_context),
+                &conversation_history,
+                persona.as_deref(),
+                None,
+            )
+            .await;
+
+        self.queue_proposals(&session, &result.pending_approvals).await;
+        self.record_message(&session, &Message::assistant(result.response.clone())).await;
+
+        let run = TaskRun {
+            started_at,
+            finished_at: scheduler::now(),
+            success: result.success,
+            response: result.response,
+            tools_used: result.tools_used,
+            pending_approvals: result.pending_approvals.len(),
+            budget_exhausted: result.budget_exhausted,
+        };
+        tracing::info!(
+            "Scheduled task '{}' finished: success={}, tools={:?}",
+            task.name,
+            run.success,
+            run.tools_used
+        );
+        self.scheduler
+            .record_run(&task.id, run.clone())
+            .map_err(|e| OrchestratorError::Internal(e.to_string()))?;
+        Ok(run)
+    }
+
+    /// The conversation a task's runs go to, created on its first run
+    async fn task_session(&self, task: &ScheduledTask) -> Arc<AgentSession> {
+        if let Some(id) = &task.session_id {
+            if let Some(session) = self.load_session(id).await {
+                return session;
+            }
+        }
+
+        let session = self.create_session().await;
+        if let Some(ref storage) = self.storage {
+            let title = format!("Scheduled: {}", task.name);
+            if let Err(e) = storage.update_title(session.id(), &title).await {
+                tracing::warn!("Failed to title scheduled task conversation: {}", e);
+            }
+        }
+        if let Err(e) = self.scheduler.set_session(&task.id, &session.id().0) {
+            tracing::warn!("Failed to link task '{}' to its session: {}", task.name, e);
+        }
+        session
+    }
+
+    /// Render the session's assigned prompt, or the library default.
+    /// A prompt that no longer renders (deleted, or a pinned version gone)
+    /// falls back to the built-in ReAct instructions.
+    async fn session_prompt(&self, session: &AgentSession) -> Option<String> {
+        let assignment = match session.prompt().await {
+            Some(assignment) => assignment,
+            None => PromptAssignment {
+                name: self.prompts.default_prompt()?,
+                ..Default::default()
+            },
+        };
+
+        match self.prompts.render(&assignment) {
+            Ok(prompt) => Some(prompt),
+            Err(e) => {
+                tracing::warn!(
+                    "Session {} prompt '{}' unavailable: {}",
+                    session.id(),
+                    assignment.name,
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Get the prompt library
+    pub fn prompt_library(&self) -> Arc<PromptLibrary> {
+        self.prompts.clone()
+    }
+
+    /// Execute a tool call the user approved and record its outcome in the session
+    pub async fn execute_approved_tool(
+        &self,
+        session: &Arc<AgentSession>,
+        tool_call: &PendingToolCall,
+    ) -> OrchestratorResult<ToolOutput> {
+        let mut params: IntentParams = serde_json::from_value(tool_call.params.clone())
+            .map_err(|e| OrchestratorError::Internal(format!("Invalid tool parameters: {}", e)))?;
+        params.extra.insert("approved".to_string(), "true".to_string());
+
+        let output = self
+            .dispatcher
+            .dispatch_confirmed(&tool_call.tool_name, &params)
+            .await
+            .map_err(|e| OrchestratorError::ToolExecutionFailed(e.to_string()))?;
+
+        let response = Message::assistant(output.message.clone());
+        session.add_message(response.clone()).await;
+        if let Some(ref storage) = self.storage {
+            if let Err(e) = storage.save_message(session.id(), &response).await {
+                tracing::warn!("Failed to save assistant message to storage: {}", e);
+            }
+        }
+
+        Ok(output)
+    }
+
     /// Format tool result with LLM for natural language
     async fn format_tool_result_with_llm(
         &self,
+        route: &RouteOverride,
         user_message: &str,
         tool_output: &str,
+        tokens: Option<&TokenSink>,
     ) -> Result<String, OrchestratorError> {
         let prompt = format!(
             "The user asked: \"{}\"\n\nThe tool returned:\n{}\n\nProvide a natural, helpful response:",
@@ -493,10 +917,12 @@ impl AgentOrchestrator {
             1,
         );
 
-        self.llm
-            .complete(&context)
-            .await
-            .map_err(|e| OrchestratorError::LLMError(e.to_string()))
+        let llm = self.llm.for_route(route.clone());
+        let result = match tokens {
+            Some(tokens) => llm.complete_stream(&context, tokens).await,
+            None => llm.complete(&context).await,
+        };
+        result.map_err(|e| OrchestratorError::LLMError(e.to_string()))
     }
 
     /// Get current system context
@@ -507,14 +933,11 @@ impl AgentOrchestrator {
         let wallet_address = first_account.map(|a| a.address.clone());
         let wallet_balance = first_account.map(|a| {
             // Format balance nicely with proper units
-            let balance_salt = a.balance as f64 / 1e18;
-            if balance_salt >= 1.0 {
-                format!("{:.4} SALT", balance_salt)
-            } else if balance_salt > 0.0 {
-                format!("{:.8} SALT", balance_salt)
-            } else {
-                "0 SALT".to_string()
-            }
+            let decimals = if a.balance >= WEI_PER_LATT { 4 } else { 8 };
+            format!(
+                "{} SALT",
+                format_units_truncated(a.balance, Unit::Latt, decimals)
+            )
         });
 
         // Get node status
@@ -559,6 +982,7 @@ impl AgentOrchestrator {
             is_syncing,
             chain_id,
             pending_transactions,
+            memories: Vec::new(),
         }
     }
 
@@ -567,6 +991,41 @@ impl AgentOrchestrator {
         self.stream_manager.clone()
     }
 
+    /// The LLM backends with the session's routing choices applied
+    async fn session_llm(&self, session: &AgentSession) -> SessionRoute<'_> {
+        self.llm.for_route(session.route().await)
+    }
+
+    /// Latency and error statistics of each configured provider
+    pub fn provider_health(&self) -> Vec<ProviderHealth> {
+        self.llm.health()
+    }
+
+    /// Probe every configured provider and return their updated health
+    pub async fn check_provider_health(&self) -> Vec<ProviderHealth> {
+        self.llm.check_health().await
+    }
+
+    /// Providers in the order a session's next request would try them
+    pub async fn session_provider_order(&self, session_id: &str) -> Option<Vec<AIProvider>> {
+        let session = self.get_session(session_id).await?;
+        Some(self.llm.order(&session.route().await))
+    }
+
+    /// Apply a LoRA adapter to the current LLM backend, or remove the applied
+    /// one when `adapter` is `None`
+    pub async fn set_adapter(&self, adapter: Option<(&Path, f32)>) -> Result<(), LLMError> {
+        match adapter {
+            Some((path, scale)) => self.llm.apply_adapter(path, scale).await,
+            None => self.llm.remove_adapter().await,
+        }
+    }
+
+    /// Path of the LoRA adapter applied to the LLM backend
+    pub async fn active_adapter(&self) -> Option<PathBuf> {
+        self.llm.active_adapter().await
+    }
+
     /// Update configuration and recreate LLM backend if needed
     pub fn update_config(&mut self, config: AgentConfig) {
         // Check if we need to recreate the LLM backend
@@ -579,114 +1038,175 @@ impl AgentOrchestrator {
         let old_anthropic_ready = self.config.providers.anthropic.is_ready();
         let new_anthropic_ready = config.providers.anthropic.is_ready();
 
+        let routing_changed = self.config.providers.preferred_order
+            != config.providers.preferred_order
+            || self.config.providers.local_fallback != config.providers.local_fallback
+            || self.config.providers.routing != config.providers.routing;
+        let sampling_changed = self.config.llm.sampling() != config.llm.sampling();
+        let safety_changed = self.config.safety != config.safety;
+        let embedder_changed = self.config.memory.embedding_model_path
+            != config.memory.embedding_model_path;
+
         self.config = config.clone();
 
         // Recreate LLM backend if provider configuration changed
         if old_model_path != new_model_path
             || old_openai_ready != new_openai_ready
             || old_anthropic_ready != new_anthropic_ready
+            || routing_changed
+            || sampling_changed
         {
             tracing::info!("Provider configuration changed, recreating LLM backend");
             self.llm = Self::create_llm_from_config(&config);
         }
+
+        if safety_changed {
+            self.safety = Self::create_safety(&config);
+        }
+
+        if embedder_changed {
+            self.memory = Arc::new(Self::create_memory(&config));
+        }
     }
 
-    /// Create LLM backend from AgentConfig
-    fn create_llm_from_config(config: &AgentConfig) -> Box<dyn LLMBackend + Send + Sync> {
-        use super::llm::{LLMBackendType as LLMType, LLMConfig as LLMCfg};
+    /// Open the memory index with the configured embedding model, falling
+    /// back to the built-in hashing embedder
+    fn create_memory(config: &AgentConfig) -> VectorMemory {
+        let embedder: Arc<dyn Embedder> = match &config.memory.embedding_model_path {
+            Some(path) => match GgufEmbedder::new(path) {
+                Ok(embedder) => Arc::new(embedder),
+                Err(e) => {
+                    tracing::warn!("Embedding model unavailable, using hashing embedder: {}", e);
+                    Arc::new(HashingEmbedder::default())
+                }
+            },
+            None => Arc::new(HashingEmbedder::default()),
+        };
+        match VectorMemory::new(embedder.clone()) {
+            Ok(memory) => memory,
+            Err(e) => {
+                tracing::warn!("Failed to load agent memory: {}. Memories will not persist.", e);
+                VectorMemory::in_memory(embedder)
+            }
+        }
+    }
 
-        // Priority order based on providers.preferred_order:
-        // 1. Local GGUF model (if available and configured) - preferred for privacy
-        // 2. Cloud APIs (OpenAI, Anthropic) as fallback
+    /// Build the safety pipeline if the config enables one
+    fn create_safety(config: &AgentConfig) -> Option<Arc<SafetyPipeline>> {
+        if !config.safety.enabled {
+            return None;
+        }
+        match SafetyPipeline::from_policy(config.safety.clone()) {
+            Ok(pipeline) => Some(Arc::new(pipeline)),
+            Err(e) => {
+                tracing::warn!("Invalid safety policy, content checks are off: {}", e);
+                None
+            }
+        }
+    }
 
-        // First, try to find a local model if local is in preferred order
+    /// Create the provider router from AgentConfig
+    ///
+    /// Providers are registered in `providers.preferred_order`. With
+    /// `providers.local_fallback` on, the local GGUF model is appended as a
+    /// fallback if one is found and not already listed, so it takes over
+    /// when the cloud providers fail or are unreachable.
+    fn create_llm_from_config(config: &AgentConfig) -> ProviderRouter {
         let local_model_path = Self::find_local_model(&config.providers.local_model_path);
 
-        // Use the active provider from the config (respects preferred_order)
-        if let Some(provider) = config.providers.get_active_provider() {
-            match provider {
-                super::config::AIProvider::Local => {
-                    if let Some(ref path) = local_model_path {
-                        tracing::info!("Creating Local GGUF backend with model: {}", path);
-                        let llm_config = LLMCfg {
-                            backend: LLMType::LocalGGUF,
-                            api_key: None,
-                            model: "local".to_string(),
-                            max_tokens: config.llm.max_tokens as usize,
-                            temperature: config.llm.temperature,
-                            top_p: config.llm.top_p,
-                            stream: config.streaming.enabled,
-                            format_tool_results: true,
-                            local_model_path: Some(path.clone()),
-                            api_base_url: None,
-                            context_size: Some(config.llm.context_size as usize),
-                        };
-                        return LLMFactory::create(llm_config);
-                    }
+        let preferred = &config.providers.preferred_order;
+        let mut order: Vec<(AIProvider, bool)> =
+            preferred.iter().map(|&provider| (provider, false)).collect();
+        if config.providers.local_fallback && !preferred.contains(&AIProvider::Local) {
+            order.push((AIProvider::Local, true));
+        }
+
+        let mut routes = Vec::new();
+        for (provider, fallback) in order {
+            if routes.iter().any(|route: &Route| route.provider == provider) {
+                continue;
+            }
+            if let Some(backend) = Self::provider_backend(config, provider, &local_model_path) {
+                routes.push(Route {
+                    provider,
+                    backend,
+                    fallback,
+                });
+            }
+        }
+
+        if routes.is_empty() {
+            tracing::info!("No LLM provider configured, using UnconfiguredLLMBackend");
+        } else {
+            tracing::info!(
+                "Routing LLM requests over: {}",
+                routes
+                    .iter()
+                    .map(|route| route.provider.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        ProviderRouter::new(routes, config.providers.routing)
+    }
+
+    /// Backend for `provider`, if it is configured and supported
+    fn provider_backend(
+        config: &AgentConfig,
+        provider: AIProvider,
+        local_model_path: &Option<String>,
+    ) -> Option<Box<dyn LLMBackend + Send + Sync>> {
+        use super::llm::{LLMBackendType as LLMType, LLMConfig as LLMCfg};
+
+        let base = LLMCfg {
+            max_tokens: config.llm.max_tokens as usize,
+            temperature: config.llm.temperature,
+            top_p: config.llm.top_p,
+            top_k: config.llm.top_k,
+            min_p: config.llm.min_p,
+            repeat_penalty: config.llm.repeat_penalty,
+            stop: config.llm.stop.clone(),
+            logit_bias: config.llm.logit_bias.clone(),
+            stream: config.streaming.enabled,
+            format_tool_results: true,
+            context_size: Some(config.llm.context_size as usize),
+            ..LLMCfg::default()
+        };
+
+        let llm_config = match provider {
+            AIProvider::Local => {
+                let path = local_model_path.as_ref()?;
+                tracing::info!("Creating Local GGUF backend with model: {}", path);
+                LLMCfg {
+                    backend: LLMType::LocalGGUF,
+                    model: "local".to_string(),
+                    local_model_path: Some(path.clone()),
+                    ..base
                 }
-                super::config::AIProvider::OpenAI => {
-                    if config.providers.openai.is_ready() {
-                        tracing::info!("Creating OpenAI backend");
-                        let llm_config = LLMCfg {
-                            backend: LLMType::OpenAI,
-                            api_key: config.providers.openai.api_key.clone(),
-                            model: config.providers.openai.model_id.clone(),
-                            max_tokens: config.llm.max_tokens as usize,
-                            temperature: config.llm.temperature,
-                            top_p: config.llm.top_p,
-                            stream: config.streaming.enabled,
-                            format_tool_results: true,
-                            local_model_path: None,
-                            api_base_url: config.providers.openai.base_url.clone(),
-                            context_size: Some(config.llm.context_size as usize),
-                        };
-                        return LLMFactory::create(llm_config);
-                    }
+            }
+            AIProvider::OpenAI if config.providers.openai.is_ready() => {
+                tracing::info!("Creating OpenAI backend");
+                LLMCfg {
+                    backend: LLMType::OpenAI,
+                    api_key: config.providers.openai.api_key.clone(),
+                    model: config.providers.openai.model_id.clone(),
+                    api_base_url: config.providers.openai.base_url.clone(),
+                    ..base
                 }
-                super::config::AIProvider::Anthropic => {
-                    if config.providers.anthropic.is_ready() {
-                        tracing::info!("Creating Anthropic backend");
-                        let llm_config = LLMCfg {
-                            backend: LLMType::Anthropic,
-                            api_key: config.providers.anthropic.api_key.clone(),
-                            model: config.providers.anthropic.model_id.clone(),
-                            max_tokens: config.llm.max_tokens as usize,
-                            temperature: config.llm.temperature,
-                            top_p: config.llm.top_p,
-                            stream: config.streaming.enabled,
-                            format_tool_results: true,
-                            local_model_path: None,
-                            api_base_url: config.providers.anthropic.base_url.clone(),
-                            context_size: Some(config.llm.context_size as usize),
-                        };
-                        return LLMFactory::create(llm_config);
-                    }
+            }
+            AIProvider::Anthropic if config.providers.anthropic.is_ready() => {
+                tracing::info!("Creating Anthropic backend");
+                LLMCfg {
+                    backend: LLMType::Anthropic,
+                    api_key: config.providers.anthropic.api_key.clone(),
+                    model: config.providers.anthropic.model_id.clone(),
+                    api_base_url: config.providers.anthropic.base_url.clone(),
+                    ..base
                 }
-                _ => {}
-            }
-        }
-
-        // Fallback: try local model even if not in preferred order
-        if let Some(ref path) = local_model_path {
-            tracing::info!("Using local GGUF model: {}", path);
-            let llm_config = LLMCfg {
-                backend: LLMType::LocalGGUF,
-                api_key: None,
-                model: "local".to_string(),
-                max_tokens: config.llm.max_tokens as usize,
-                temperature: config.llm.temperature,
-                top_p: config.llm.top_p,
-                stream: config.streaming.enabled,
-                format_tool_results: true,
-                local_model_path: Some(path.clone()),
-                api_base_url: None,
-                context_size: Some(config.llm.context_size as usize),
-            };
-            return LLMFactory::create(llm_config);
-        }
-
-        tracing::info!("No LLM provider configured, using UnconfiguredLLMBackend");
-        LLMFactory::create(LLMCfg::default())
+            }
+            _ => return None,
+        };
+        Some(LLMFactory::create(llm_config))
     }
 
     /// Find a local model path, checking config first then default locations
@@ -0,0 +1,727 @@
+//! Prompt library - named, versioned system prompts and personas
+//!
+//! Every save of a prompt adds a new version, so a session can pin the
+//! version it was tuned against while the prompt keeps evolving. Prompt text
+//! may reference `{{variable}}` placeholders, filled from per-session values
+//! or the variable's default when the prompt is rendered.
+//!
+//! The library lives in a JSON file next to the conversation database and
+//! can be exported to (and merged back from) a portable bundle.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use super::config::AgentConfig;
+
+/// Name of the built-in persona seeded into every library
+pub const BUILTIN_PROMPT: &str = "citrate-assistant";
+
+/// Bundle format written by `export` and accepted by `import`
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Error type for prompt library operations
+#[derive(Debug)]
+pub enum PromptError {
+    /// No prompt with this name
+    NotFound(String),
+    /// The prompt exists but not at this version
+    VersionNotFound(String, u32),
+    /// Name or content rejected
+    Invalid(String),
+    /// A placeholder has neither a session value nor a default
+    MissingVariable(String),
+    /// Built-in prompts cannot be deleted
+    Builtin(String),
+    /// Serialization error
+    Serialization(String),
+    /// IO error
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for PromptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound(name) => write!(f, "Prompt not found: {}", name),
+            Self::VersionNotFound(name, v) => write!(f, "Prompt {} has no version {}", name, v),
+            Self::Invalid(e) => write!(f, "Invalid prompt: {}", e),
+            Self::MissingVariable(var) => write!(f, "No value for prompt variable: {}", var),
+            Self::Builtin(name) => write!(f, "Built-in prompt cannot be deleted: {}", name),
+            Self::Serialization(e) => write!(f, "Serialization error: {}", e),
+            Self::Io(e) => write!(f, "IO error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PromptError {}
+
+impl From<std::io::Error> for PromptError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for PromptError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Serialization(e.to_string())
+    }
+}
+
+/// What a prompt is meant to be used as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptKind {
+    /// Instructions prepended to the conversation
+    #[default]
+    System,
+    /// A character or role the agent takes on
+    Persona,
+}
+
+/// A `{{name}}` placeholder in prompt text
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptVariable {
+    /// Placeholder name
+    pub name: String,
+    /// What the value is for
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Value used when the session does not supply one
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+/// One saved revision of a prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptVersion {
+    /// Version number, starting at 1
+    pub version: u32,
+    /// Prompt text with `{{variable}}` placeholders
+    pub content: String,
+    /// Placeholders used in `content`
+    #[serde(default)]
+    pub variables: Vec<PromptVariable>,
+    /// Creation timestamp (Unix ms)
+    pub created_at: u64,
+    /// What changed in this version
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+impl PromptVersion {
+    fn same_text(&self, other: &PromptVersion) -> bool {
+        self.content == other.content && self.variables == other.variables
+    }
+}
+
+/// A named prompt and all of its versions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptEntry {
+    /// Unique name (lowercase letters, digits, `-` and `_`)
+    pub name: String,
+    /// Short description shown in the library
+    #[serde(default)]
+    pub description: String,
+    /// System prompt or persona
+    #[serde(default)]
+    pub kind: PromptKind,
+    /// Versions in ascending order
+    pub versions: Vec<PromptVersion>,
+    /// Shipped with the app rather than created by the user
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+impl PromptEntry {
+    /// Most recent version
+    pub fn latest(&self) -> Option<&PromptVersion> {
+        self.versions.last()
+    }
+
+    /// A specific version, or the latest when `version` is `None`
+    pub fn version(&self, version: Option<u32>) -> Result<&PromptVersion, PromptError> {
+        match version {
+            Some(v) => self
+                .versions
+                .iter()
+                .find(|pv| pv.version == v)
+                .ok_or_else(|| PromptError::VersionNotFound(self.name.clone(), v)),
+            None => self
+                .latest()
+                .ok_or_else(|| PromptError::NotFound(self.name.clone())),
+        }
+    }
+
+    fn push_version(&mut self, mut version: PromptVersion) -> u32 {
+        version.version = self.latest().map_or(1, |v| v.version + 1);
+        let number = version.version;
+        self.versions.push(version);
+        number
+    }
+}
+
+/// Library listing entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptSummary {
+    pub name: String,
+    pub description: String,
+    pub kind: PromptKind,
+    pub latest_version: u32,
+    pub version_count: usize,
+    pub variables: Vec<PromptVariable>,
+    pub builtin: bool,
+    pub is_default: bool,
+    pub updated_at: u64,
+}
+
+/// A new version of a prompt as submitted by the user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptDraft {
+    pub name: String,
+    pub content: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub kind: Option<PromptKind>,
+    #[serde(default)]
+    pub variables: Vec<PromptVariable>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// Which prompt a session uses and the values for its variables
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PromptAssignment {
+    /// Prompt name
+    pub name: String,
+    /// Pinned version; `None` follows the latest
+    #[serde(default)]
+    pub version: Option<u32>,
+    /// Values for the prompt's placeholders
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+}
+
+/// Outcome of merging an exported bundle into the library
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportSummary {
+    /// Prompts that did not exist before
+    pub added: usize,
+    /// Existing prompts that gained versions
+    pub updated: usize,
+    /// Prompts whose versions were all already present
+    pub unchanged: usize,
+}
+
+/// Portable export format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PromptBundle {
+    format_version: u32,
+    prompts: Vec<PromptEntry>,
+}
+
+/// On-disk library state
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct LibraryState {
+    #[serde(default)]
+    prompts: BTreeMap<String, PromptEntry>,
+    /// Prompt used by sessions without an assignment
+    #[serde(default)]
+    default_prompt: Option<String>,
+}
+
+/// Persistent prompt library
+pub struct PromptLibrary {
+    /// JSON file backing the library; `None` keeps it in memory only
+    path: Option<PathBuf>,
+    state: RwLock<LibraryState>,
+}
+
+impl PromptLibrary {
+    /// Open the library in the user's local app data directory
+    pub fn new() -> Result<Self, PromptError> {
+        let data_dir = dirs::data_local_dir().ok_or_else(|| {
+            PromptError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Could not find local data directory",
+            ))
+        })?;
+        Self::open(data_dir.join("citrate").join("prompts.json"))
+    }
+
+    /// Open (or create) the library stored at `path`
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, PromptError> {
+        let path = path.into();
+        let state = if path.exists() {
+            serde_json::from_str(&std::fs::read_to_string(&path)?)?
+        } else {
+            LibraryState::default()
+        };
+
+        let library = Self {
+            path: Some(path),
+            state: RwLock::new(state),
+        };
+        library.seed_builtin()?;
+
+        tracing::info!("Prompt library loaded from: {:?}", library.path);
+        Ok(library)
+    }
+
+    /// A library that is never written to disk
+    pub fn in_memory() -> Self {
+        let library = Self {
+            path: None,
+            state: RwLock::new(LibraryState::default()),
+        };
+        // Nothing to persist, so seeding cannot fail
+        let _ = library.seed_builtin();
+        library
+    }
+
+    /// List all prompts, sorted by name
+    pub fn list(&self) -> Vec<PromptSummary> {
+        let state = self.read();
+        state
+            .prompts
+            .values()
+            .filter_map(|entry| {
+                let latest = entry.latest()?;
+                Some(PromptSummary {
+                    name: entry.name.clone(),
+                    description: entry.description.clone(),
+                    kind: entry.kind,
+                    latest_version: latest.version,
+                    version_count: entry.versions.len(),
+                    variables: latest.variables.clone(),
+                    builtin: entry.builtin,
+                    is_default: state.default_prompt.as_deref() == Some(entry.name.as_str()),
+                    updated_at: latest.created_at,
+                })
+            })
+            .collect()
+    }
+
+    /// Get a prompt with its full version history
+    pub fn get(&self, name: &str) -> Result<PromptEntry, PromptError> {
+        self.read()
+            .prompts
+            .get(name)
+            .cloned()
+            .ok_or_else(|| PromptError::NotFound(name.to_string()))
+    }
+
+    /// Save a draft as the next version of its prompt, creating the prompt
+    /// if needed. Saving text identical to the latest version only updates
+    /// the description and kind. Returns the version number now current.
+    pub fn save(&self, draft: PromptDraft) -> Result<u32, PromptError> {
+        validate_name(&draft.name)?;
+        if draft.content.trim().is_empty() {
+            return Err(PromptError::Invalid("prompt content is empty".to_string()));
+        }
+
+        let version = PromptVersion {
+            version: 0,
+            variables: declare_variables(&draft.content, draft.variables),
+            content: draft.content,
+            created_at: now(),
+            note: draft.note,
+        };
+
+        let mut state = self.write();
+        let entry = state
+            .prompts
+            .entry(draft.name.clone())
+            .or_insert_with(|| PromptEntry {
+                name: draft.name.clone(),
+                description: String::new(),
+                kind: PromptKind::default(),
+                versions: Vec::new(),
+                builtin: false,
+            });
+        if let Some(description) = draft.description {
+            entry.description = description;
+        }
+        if let Some(kind) = draft.kind {
+            entry.kind = kind;
+        }
+
+        let number = match entry.latest() {
+            Some(latest) if latest.same_text(&version) => latest.version,
+            _ => entry.push_version(version),
+        };
+        self.persist(&state)?;
+        Ok(number)
+    }
+
+    /// Delete a prompt and all its versions. Returns false if it did not exist.
+    pub fn delete(&self, name: &str) -> Result<bool, PromptError> {
+        let mut state = self.write();
+        match state.prompts.get(name) {
+            Some(entry) if entry.builtin => return Err(PromptError::Builtin(name.to_string())),
+            Some(_) => {}
+            None => return Ok(false),
+        }
+        state.prompts.remove(name);
+        if state.default_prompt.as_deref() == Some(name) {
+            state.default_prompt = None;
+        }
+        self.persist(&state)?;
+        Ok(true)
+    }
+
+    /// Prompt applied to sessions that have no assignment of their own
+    pub fn default_prompt(&self) -> Option<String> {
+        self.read().default_prompt.clone()
+    }
+
+    /// Set or clear the default prompt
+    pub fn set_default(&self, name: Option<&str>) -> Result<(), PromptError> {
+        let mut state = self.write();
+        if let Some(name) = name {
+            if !state.prompts.contains_key(name) {
+                return Err(PromptError::NotFound(name.to_string()));
+            }
+        }
+        state.default_prompt = name.map(str::to_string);
+        self.persist(&state)
+    }
+
+    /// Render an assigned prompt, substituting its variables
+    pub fn render(&self, assignment: &PromptAssignment) -> Result<String, PromptError> {
+        let state = self.read();
+        let entry = state
+            .prompts
+            .get(&assignment.name)
+            .ok_or_else(|| PromptError::NotFound(assignment.name.clone()))?;
+        render_version(entry.version(assignment.version)?, &assignment.variables)
+    }
+
+    /// Export prompts (all of them when `names` is `None`) as a JSON bundle
+    pub fn export(&self, names: Option<&[String]>) -> Result<String, PromptError> {
+        let state = self.read();
+        let prompts = match names {
+            Some(names) => names
+                .iter()
+                .map(|name| {
+                    state
+                        .prompts
+                        .get(name)
+                        .cloned()
+                        .ok_or_else(|| PromptError::NotFound(name.clone()))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None => state.prompts.values().cloned().collect(),
+        };
+
+        Ok(serde_json::to_string_pretty(&PromptBundle {
+            format_version: BUNDLE_FORMAT_VERSION,
+            prompts,
+        })?)
+    }
+
+    /// Merge an exported bundle into the library. Imported versions that
+    /// are not already present are appended after the local history, so
+    /// nothing local is overwritten and existing pins stay valid.
+    pub fn import(&self, bundle: &str) -> Result<ImportSummary, PromptError> {
+        let bundle: PromptBundle = serde_json::from_str(bundle)?;
+        if bundle.format_version > BUNDLE_FORMAT_VERSION {
+            return Err(PromptError::Invalid(format!(
+                "unsupported bundle format version {}",
+                bundle.format_version
+            )));
+        }
+        for prompt in &bundle.prompts {
+            validate_name(&prompt.name)?;
+        }
+
+        let mut summary = ImportSummary::default();
+        let mut state = self.write();
+        for mut imported in bundle.prompts {
+            imported.versions.sort_by_key(|v| v.version);
+
+            let Some(existing) = state.prompts.get_mut(&imported.name) else {
+                // Renumber so a sparse or foreign history starts at 1
+                let versions = std::mem::take(&mut imported.versions);
+                let mut entry = PromptEntry {
+                    builtin: false,
+                    ..imported
+                };
+                for version in versions {
+                    entry.push_version(version);
+                }
+                if entry.versions.is_empty() {
+                    continue;
+                }
+                state.prompts.insert(entry.name.clone(), entry);
+                summary.added += 1;
+                continue;
+            };
+
+            let mut added = false;
+            for version in imported.versions {
+                if !existing.versions.iter().any(|v| v.same_text(&version)) {
+                    existing.push_version(version);
+                    added = true;
+                }
+            }
+            if added {
+                summary.updated += 1;
+            } else {
+                summary.unchanged += 1;
+            }
+        }
+
+        self.persist(&state)?;
+        Ok(summary)
+    }
+
+    /// Add the built-in assistant persona if the library lacks it
+    fn seed_builtin(&self) -> Result<(), PromptError> {
+        let mut state = self.write();
+        if state.prompts.contains_key(BUILTIN_PROMPT) {
+            return Ok(());
+        }
+
+        let mut entry = PromptEntry {
+            name: BUILTIN_PROMPT.to_string(),
+            description: "Default Citrate assistant".to_string(),
+            kind: PromptKind::Persona,
+            versions: Vec::new(),
+            builtin: true,
+        };
+        entry.push_version(PromptVersion {
+            version: 0,
+            content: AgentConfig::default_system_prompt(),
+            variables: Vec::new(),
+            created_at: now(),
+            note: None,
+        });
+        state.prompts.insert(entry.name.clone(), entry);
+        self.persist(&state)
+    }
+
+    /// Write the library to disk, replacing the previous file atomically
+    fn persist(&self, state: &LibraryState) -> Result<(), PromptError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(state)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Path of the backing file, if any
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, LibraryState> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, LibraryState> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Substitute `{{name}}` placeholders from `values`, then variable defaults
+fn render_version(
+    version: &PromptVersion,
+    values: &HashMap<String, String>,
+) -> Result<String, PromptError> {
+    let mut rendered = String::with_capacity(version.content.len());
+    let mut rest = version.content.as_str();
+
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + len].trim();
+        let value = values.get(name).cloned().or_else(|| {
+            version
+                .variables
+                .iter()
+                .find(|v| v.name == name)
+                .and_then(|v| v.default.clone())
+        });
+
+        rendered.push_str(&rest[..start]);
+        match value {
+            Some(value) => rendered.push_str(&value),
+            None => return Err(PromptError::MissingVariable(name.to_string())),
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+/// Placeholder names used in `content`, in order of first appearance
+fn placeholders(content: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + len].trim().to_string();
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    names
+}
+
+/// Keep the declared variables that appear in `content` and declare any
+/// placeholder the draft did not describe
+fn declare_variables(content: &str, declared: Vec<PromptVariable>) -> Vec<PromptVariable> {
+    placeholders(content)
+        .into_iter()
+        .map(|name| {
+            declared
+                .iter()
+                .find(|v| v.name == name)
+                .cloned()
+                .unwrap_or(PromptVariable {
+                    name,
+                    description: None,
+                    default: None,
+                })
+        })
+        .collect()
+}
+
+fn validate_name(name: &str) -> Result<(), PromptError> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(PromptError::Invalid(format!(
+            "name '{}' must be 1-64 lowercase letters, digits, '-' or '_'",
+            name
+        )))
+    }
+}
+
+/// Get current Unix timestamp in milliseconds
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draft(name: &str, content: &str) -> PromptDraft {
+        PromptDraft {
+            name: name.to_string(),
+            content: content.to_string(),
+            description: None,
+            kind: None,
+            variables: Vec::new(),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_save_adds_versions_and_skips_identical_text() {
+        let library = PromptLibrary::in_memory();
+
+        assert_eq!(library.save(draft("reviewer", "Review code.")).unwrap(), 1);
+        assert_eq!(library.save(draft("reviewer", "Review code.")).unwrap(), 1);
+        assert_eq!(
+            library
+                .save(draft("reviewer", "Review Rust code."))
+                .unwrap(),
+            2
+        );
+
+        let entry = library.get("reviewer").unwrap();
+        assert_eq!(entry.versions.len(), 2);
+        assert_eq!(entry.version(Some(1)).unwrap().content, "Review code.");
+        assert_eq!(entry.version(None).unwrap().content, "Review Rust code.");
+        assert!(library.save(draft("Bad Name", "x")).is_err());
+    }
+
+    #[test]
+    fn test_render_uses_session_values_then_defaults() {
+        let library = PromptLibrary::in_memory();
+        let mut d = draft("tutor", "Teach {{topic}} at a {{ level }} level.");
+        d.variables.push(PromptVariable {
+            name: "level".to_string(),
+            description: None,
+            default: Some("beginner".to_string()),
+        });
+        library.save(d).unwrap();
+
+        let mut assignment = PromptAssignment {
+            name: "tutor".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            library.render(&assignment),
+            Err(PromptError::MissingVariable(v)) if v == "topic"
+        ));
+
+        assignment
+            .variables
+            .insert("topic".to_string(), "GhostDAG".to_string());
+        assert_eq!(
+            library.render(&assignment).unwrap(),
+            "Teach GhostDAG at a beginner level."
+        );
+    }
+
+    #[test]
+    fn test_export_import_merges_new_versions() {
+        let source = PromptLibrary::in_memory();
+        source.save(draft("auditor", "Audit contracts.")).unwrap();
+        source
+            .save(draft("auditor", "Audit Solidity contracts."))
+            .unwrap();
+        let bundle = source.export(Some(&["auditor".to_string()])).unwrap();
+
+        let target = PromptLibrary::in_memory();
+        target.save(draft("auditor", "Audit contracts.")).unwrap();
+        let summary = target.import(&bundle).unwrap();
+        assert_eq!(summary.updated, 1);
+        assert_eq!(target.get("auditor").unwrap().versions.len(), 2);
+
+        // Importing the same bundle again changes nothing
+        let summary = target.import(&bundle).unwrap();
+        assert_eq!(summary.unchanged, 1);
+    }
+
+    #[test]
+    fn test_builtin_is_seeded_and_protected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prompts.json");
+
+        let library = PromptLibrary::open(&path).unwrap();
+        assert!(matches!(
+            library.delete(BUILTIN_PROMPT),
+            Err(PromptError::Builtin(_))
+        ));
+        library.save(draft("mine", "Be brief.")).unwrap();
+        library.set_default(Some("mine")).unwrap();
+
+        let reopened = PromptLibrary::open(&path).unwrap();
+        assert_eq!(reopened.default_prompt().as_deref(), Some("mine"));
+        assert!(reopened.delete("mine").unwrap());
+        assert_eq!(reopened.default_prompt(), None);
+    }
+}
@@ -10,7 +10,8 @@ use std::sync::Arc;
 use super::context::{ContextMessage, ContextWindow, ConversationHistory, SystemContext};
 use super::dispatcher::{DispatchError, ToolDefinition, ToolDispatcher, ToolOutput};
 use super::intent::IntentParams;
-use super::llm::LLMBackend;
+use super::llm::{LLMBackend, LLMError};
+use super::streaming::TokenSink;
 
 /// Maximum number of ReAct iterations to prevent infinite loops
 const MAX_ITERATIONS: usize = 5;
@@ -41,12 +42,65 @@ pub struct ReActResult {
     pub success: bool,
     /// Number of iterations
     pub iterations: usize,
+    /// Actions proposed by tools that are waiting for user approval
+    pub pending_approvals: Vec<ProposedAction>,
+    /// Whether a tool call was refused because the tool budget ran out
+    pub budget_exhausted: bool,
+}
+
+/// A tool action that was prepared but must be approved before it runs
+#[derive(Debug, Clone)]
+pub struct ProposedAction {
+    /// Tool to run once approved
+    pub tool: String,
+    /// Parameters the tool was called with
+    pub params: IntentParams,
+    /// Description shown to the user
+    pub description: String,
+}
+
+/// Limits on which tools a run may call, used for unattended runs
+#[derive(Debug, Clone, Default)]
+pub struct ToolPolicy {
+    /// Maximum number of tool calls; `None` is unlimited
+    pub max_calls: Option<usize>,
+    /// Refuse tools that need user approval instead of queueing a proposal
+    pub read_only: bool,
+    /// When non-empty, only these tools may be called
+    pub allowed_tools: Vec<String>,
+}
+
+impl ToolPolicy {
+    /// Observation returned to the model instead of running `tool`, if the
+    /// call is not allowed
+    fn refusal(&self, tool: &str, needs_approval: bool, calls_made: usize) -> Option<String> {
+        if let Some(max) = self.max_calls {
+            if calls_made >= max {
+                return Some(format!(
+                    "Tool budget of {} calls is used up. Answer with the information gathered so far.",
+                    max
+                ));
+            }
+        }
+        if !self.allowed_tools.is_empty() && !self.allowed_tools.iter().any(|t| t == tool) {
+            return Some(format!("Tool '{}' is not available in this run.", tool));
+        }
+        if self.read_only && needs_approval {
+            return Some(format!(
+                "Tool '{}' needs user approval and cannot run unattended.",
+                tool
+            ));
+        }
+        None
+    }
 }
 
 /// ReAct executor that implements the reasoning-acting loop
 pub struct ReActExecutor {
     /// Maximum iterations
     max_iterations: usize,
+    /// Tool limits applied to every action
+    policy: ToolPolicy,
 }
 
 impl ReActExecutor {
@@ -54,15 +108,33 @@ impl ReActExecutor {
     pub fn new() -> Self {
         Self {
             max_iterations: MAX_ITERATIONS,
+            policy: ToolPolicy::default(),
         }
     }
 
     /// Create with custom max iterations
     pub fn with_max_iterations(max_iterations: usize) -> Self {
-        Self { max_iterations }
+        Self {
+            max_iterations,
+            policy: ToolPolicy::default(),
+        }
+    }
+
+    /// Restrict the tools this executor may call
+    pub fn with_tool_policy(mut self, policy: ToolPolicy) -> Self {
+        self.policy = policy;
+        self
     }
 
     /// Execute a ReAct loop for a user query
+    ///
+    /// `persona` replaces the default assistant introduction at the top of
+    /// the system prompt; the tool instructions are always appended.
+    ///
+    /// With `tokens`, each LLM call is streamed into the sink. Text that
+    /// turns out to be a thought or tool call is followed by a reset, so
+    /// only the answer remains on screen. A cancelled stream ends the loop.
+    #[allow(clippy::too_many_arguments)]
     pub async fn execute(
         &self,
         user_message: &str,
@@ -70,23 +142,40 @@ impl ReActExecutor {
         dispatcher: &ToolDispatcher,
         system_context: Option<SystemContext>,
         conversation_history: &[ContextMessage],
+        persona: Option<&str>,
+        tokens: Option<&TokenSink>,
     ) -> ReActResult {
         let mut steps: Vec<ReActStep> = Vec::new();
         let mut tools_used: Vec<String> = Vec::new();
+        let mut pending_approvals: Vec<ProposedAction> = Vec::new();
+        let mut budget_exhausted = false;
         let mut iterations = 0;
 
         // Build the system prompt with tool definitions
-        let system_prompt = self.build_react_system_prompt(dispatcher);
+        let system_prompt = self.build_react_system_prompt(dispatcher, persona);
 
         // Start the ReAct loop
         loop {
             iterations += 1;
 
+            if tokens.is_some_and(TokenSink::is_cancelled) {
+                tracing::info!("ReAct: Stream cancelled after {} iterations", iterations - 1);
+                return ReActResult {
+                    response: "Generation cancelled.".to_string(),
+                    steps,
+                    tools_used,
+                    success: false,
+                    iterations,
+                    pending_approvals,
+                    budget_exhausted,
+                };
+            }
+
             if iterations > self.max_iterations {
                 tracing::warn!("ReAct: Max iterations ({}) reached", self.max_iterations);
                 // Generate a final response based on what we have
                 let final_response = self
-                    .generate_final_response(user_message, &steps, llm, &system_prompt)
+                    .generate_final_response(user_message, &steps, llm, &system_prompt, tokens)
                     .await;
                 return ReActResult {
                     response: final_response,
@@ -94,6 +183,8 @@ impl ReActExecutor {
                     tools_used,
                     success: false,
                     iterations,
+                    pending_approvals,
+                    budget_exhausted,
                 };
             }
 
@@ -113,7 +204,7 @@ impl ReActExecutor {
             );
 
             // Get LLM response
-            let llm_response = match llm.complete(&context).await {
+            let llm_response = match Self::complete(llm, &context, tokens).await {
                 Ok(response) => response,
                 Err(e) => {
                     tracing::error!("ReAct: LLM error: {}", e);
@@ -123,6 +214,8 @@ impl ReActExecutor {
                         tools_used,
                         success: false,
                         iterations,
+                        pending_approvals,
+                        budget_exhausted,
                     };
                 }
             };
@@ -133,20 +226,48 @@ impl ReActExecutor {
             match self.parse_react_response(&llm_response) {
                 ParsedResponse::Thought(thought) => {
                     tracing::debug!("ReAct: Thought: {}", thought);
+                    if let Some(tokens) = tokens {
+                        tokens.reset();
+                    }
                     steps.push(ReActStep::Thought(thought));
                     // Continue to next iteration to get action
                 }
                 ParsedResponse::Action { tool, params } => {
                     tracing::info!("ReAct: Action: {} with params {:?}", tool, params);
+                    if let Some(tokens) = tokens {
+                        tokens.reset();
+                    }
                     steps.push(ReActStep::Action {
                         tool: tool.clone(),
                         params: params.clone(),
                     });
+
+                    let needs_approval = dispatcher.needs_approval(&tool);
+                    if let Some(refusal) =
+                        self.policy.refusal(&tool, needs_approval, tools_used.len())
+                    {
+                        tracing::info!("ReAct: Refused {}: {}", tool, refusal);
+                        budget_exhausted |= self
+                            .policy
+                            .max_calls
+                            .is_some_and(|max| tools_used.len() >= max);
+                        steps.push(ReActStep::Observation(refusal));
+                        continue;
+                    }
                     tools_used.push(tool.clone());
 
                     // Execute the tool
                     let observation = match dispatcher.dispatch_confirmed(&tool, &params).await {
-                        Ok(output) => output.message,
+                        Ok(output) => {
+                            if output.awaiting_approval() && !self.policy.read_only {
+                                pending_approvals.push(ProposedAction {
+                                    tool: tool.clone(),
+                                    params: params.clone(),
+                                    description: output.message.clone(),
+                                });
+                            }
+                            output.message
+                        }
                         Err(e) => format!("Tool error: {}", e),
                     };
 
@@ -163,6 +284,8 @@ impl ReActExecutor {
                         tools_used,
                         success: true,
                         iterations,
+                        pending_approvals,
+                        budget_exhausted,
                     };
                 }
                 ParsedResponse::DirectResponse(response) => {
@@ -174,6 +297,8 @@ impl ReActExecutor {
                         tools_used,
                         success: true,
                         iterations,
+                        pending_approvals,
+                        budget_exhausted,
                     };
                 }
             }
@@ -181,12 +306,15 @@ impl ReActExecutor {
     }
 
     /// Build the system prompt with tool definitions
-    fn build_react_system_prompt(&self, dispatcher: &ToolDispatcher) -> String {
+    fn build_react_system_prompt(&self, dispatcher: &ToolDispatcher, persona: Option<&str>) -> String {
         let tools = dispatcher.list_tools();
         let tool_descriptions = self.format_tool_descriptions(tools);
+        let introduction = persona.unwrap_or(
+            "You are a helpful AI assistant for the Citrate blockchain platform. You can help users with wallet operations, blockchain queries, smart contracts, and AI model management.",
+        );
 
         format!(
-            r#"You are a helpful AI assistant for the Citrate blockchain platform. You can help users with wallet operations, blockchain queries, smart contracts, and AI model management.
+            r#"{introduction}
 
 You have access to the following tools:
 
@@ -212,6 +340,7 @@ Important guidelines:
 - If a tool fails, explain what happened and try an alternative if available
 - Be concise but informative in your final answers
 - Format numbers and addresses nicely for readability"#,
+            introduction = introduction,
             tool_descriptions = tool_descriptions
         )
     }
@@ -366,26 +495,12 @@ Important guidelines:
 
         // Handle case where input continues to next line
         let json_str = if input.starts_with('{') {
-            // Find matching closing brace
-            let mut depth = 0;
-            let mut end_idx = 0;
-            for (i, c) in input.chars().enumerate() {
-                match c {
-                    '{' => depth += 1,
-                    '}' => {
-                        depth -= 1;
-                        if depth == 0 {
-                            end_idx = i + 1;
-                            break;
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            if end_idx > 0 {
-                &input[..end_idx]
-            } else {
-                input.lines().next().unwrap_or(input)
+            // Take the first JSON value; string values may contain braces and newlines
+            let mut values =
+                serde_json::Deserializer::from_str(input).into_iter::<serde_json::Value>();
+            match values.next() {
+                Some(Ok(_)) => &input[..values.byte_offset()],
+                _ => input.lines().next().unwrap_or(input),
             }
         } else {
             input.lines().next().unwrap_or(input)
@@ -413,9 +528,17 @@ Important guidelines:
                 if let Some(v) = map.get("search_query") {
                     params.search_query = v.as_str().map(String::from);
                 }
-                // Store all values as strings in extra for tools that need raw access
+                // Store all values as strings in extra for tools that need raw access.
+                // Approval is only granted by the user (agent_approve_tool), never by the model.
                 for (k, v) in map {
-                    params.extra.insert(k, v.to_string().trim_matches('"').to_string());
+                    if k == "approved" {
+                        continue;
+                    }
+                    let value = match v {
+                        serde_json::Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    params.extra.insert(k, value);
                 }
                 params
             }
@@ -433,6 +556,7 @@ Important guidelines:
         steps: &[ReActStep],
         llm: &dyn LLMBackend,
         system_prompt: &str,
+        tokens: Option<&TokenSink>,
     ) -> String {
         // Build a summary of what was accomplished
         let steps_summary: Vec<String> = steps
@@ -462,10 +586,22 @@ Important guidelines:
             was_truncated: false,
         };
 
-        llm.complete(&context)
+        Self::complete(llm, &context, tokens)
             .await
             .unwrap_or_else(|e| format!("I gathered some information but couldn't complete the analysis: {}", e))
     }
+
+    /// One LLM call, streamed when a sink is given
+    async fn complete(
+        llm: &dyn LLMBackend,
+        context: &ContextWindow,
+        tokens: Option<&TokenSink>,
+    ) -> Result<String, LLMError> {
+        match tokens {
+            Some(tokens) => llm.complete_stream(context, tokens).await,
+            None => llm.complete(context).await,
+        }
+    }
 }
 
 impl Default for ReActExecutor {
@@ -516,6 +652,55 @@ Action Input: {"address": "0x123"}"#;
         }
     }
 
+    #[test]
+    fn test_action_input_cannot_self_approve() {
+        let executor = ReActExecutor::new();
+        let params = executor.parse_action_input(
+            r#"{"contract_address": "0xabc", "function": "transfer", "approved": "true"}"#,
+        );
+        assert_eq!(params.contract_address.as_deref(), Some("0xabc"));
+        assert_eq!(params.extra.get("function").map(String::as_str), Some("transfer"));
+        assert!(!params.extra.contains_key("approved"));
+    }
+
+    #[test]
+    fn test_parse_action_input_string_values() {
+        let executor = ReActExecutor::new();
+        let params = executor.parse_action_input(
+            "{\"path\": \"src/lib.rs\", \"content\": \"fn main() {\\n    println!(\\\"}\\\");\\n}\"}\nThought: done",
+        );
+        assert_eq!(params.extra.get("path").map(String::as_str), Some("src/lib.rs"));
+        assert_eq!(
+            params.extra.get("content").map(String::as_str),
+            Some("fn main() {\n    println!(\"}\");\n}")
+        );
+    }
+
+    #[test]
+    fn test_tool_policy_refusals() {
+        let policy = ToolPolicy {
+            max_calls: Some(2),
+            read_only: true,
+            allowed_tools: vec!["get_balance".to_string(), "send_transaction".to_string()],
+        };
+        assert_eq!(policy.refusal("get_balance", false, 1), None);
+        assert!(policy
+            .refusal("get_balance", false, 2)
+            .unwrap()
+            .contains("budget"));
+        assert!(policy
+            .refusal("list_models", false, 0)
+            .unwrap()
+            .contains("not available"));
+        assert!(policy
+            .refusal("send_transaction", true, 0)
+            .unwrap()
+            .contains("approval"));
+
+        // The interactive default allows everything
+        assert_eq!(ToolPolicy::default().refusal("send_transaction", true, 99), None);
+    }
+
     #[test]
     fn test_parse_direct_response() {
         let executor = ReActExecutor::new();
@@ -0,0 +1,706 @@
+//! Scheduled agent tasks - recurring prompts the agent runs on its own
+//!
+//! A task pairs a prompt ("summarize my node's overnight logs") with a
+//! cron-style schedule, a tool-use budget and an auto-mode policy that
+//! decides what the agent may do while nobody is watching. Each task keeps
+//! its own conversation, so every run lands in session history, and a
+//! completion notice is published for the frontend to show.
+//!
+//! Schedules use the five cron fields `minute hour day-of-month month
+//! day-of-week` in local time, with `*`, lists, ranges and `/step`, plus the
+//! `@hourly`, `@daily`, `@weekly` and `@monthly` shortcuts.
+
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tokio::sync::broadcast;
+
+/// Tauri event emitted when a scheduled task run finishes
+pub const AGENT_TASK_COMPLETED_EVENT: &str = "agent-task-completed";
+
+/// Tool calls allowed per run when a task does not set a budget
+pub const DEFAULT_TOOL_BUDGET: usize = 5;
+
+/// Upper bound on a task's tool budget
+const MAX_TOOL_BUDGET: usize = 25;
+
+/// How far ahead to look for the next matching time before giving up on a
+/// schedule that never fires (e.g. `0 0 31 2 *`)
+const MAX_LOOKAHEAD_YEARS: i32 = 5;
+
+/// Error type for scheduler operations
+#[derive(Debug)]
+pub enum SchedulerError {
+    /// No task with this id
+    NotFound(String),
+    /// Cron expression could not be parsed
+    InvalidSchedule(String),
+    /// Name, prompt or budget rejected
+    Invalid(String),
+    /// Serialization error
+    Serialization(String),
+    /// IO error
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for SchedulerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound(id) => write!(f, "Scheduled task not found: {}", id),
+            Self::InvalidSchedule(e) => write!(f, "Invalid schedule: {}", e),
+            Self::Invalid(e) => write!(f, "Invalid task: {}", e),
+            Self::Serialization(e) => write!(f, "Serialization error: {}", e),
+            Self::Io(e) => write!(f, "IO error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SchedulerError {}
+
+impl From<std::io::Error> for SchedulerError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SchedulerError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Serialization(e.to_string())
+    }
+}
+
+/// One cron field as a bit set of the values it matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CronField {
+    bits: u64,
+    /// Whether the field starts with `*` (matters for day-of-month and
+    /// day-of-week)
+    any: bool,
+}
+
+impl CronField {
+    fn parse(field: &str, min: u32, max: u32) -> Result<Self, SchedulerError> {
+        let invalid = || SchedulerError::InvalidSchedule(format!("bad field '{}'", field));
+        let mut bits = 0u64;
+        for part in field.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+                None => (part, 1),
+            };
+            if step == 0 {
+                return Err(invalid());
+            }
+            let (start, end) = if range == "*" {
+                (min, max)
+            } else if let Some((a, b)) = range.split_once('-') {
+                (
+                    a.parse().map_err(|_| invalid())?,
+                    b.parse().map_err(|_| invalid())?,
+                )
+            } else {
+                let value: u32 = range.parse().map_err(|_| invalid())?;
+                // `5/15` means every 15 starting at 5
+                (value, if part.contains('/') { max } else { value })
+            };
+            if start < min || end > max || start > end {
+                return Err(invalid());
+            }
+            for value in (start..=end).step_by(step as usize) {
+                bits |= 1 << value;
+            }
+        }
+        Ok(Self {
+            bits,
+            any: field.starts_with('*'),
+        })
+    }
+
+    fn matches(&self, value: u32) -> bool {
+        self.bits & (1 << value) != 0
+    }
+}
+
+/// A parsed cron schedule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minute: CronField,
+    hour: CronField,
+    day_of_month: CronField,
+    month: CronField,
+    day_of_week: CronField,
+}
+
+impl CronSchedule {
+    /// Parse a five-field cron expression or an `@` shortcut
+    pub fn parse(expression: &str) -> Result<Self, SchedulerError> {
+        let expanded = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(SchedulerError::InvalidSchedule(format!(
+                "'{}' must have 5 fields: minute hour day-of-month month day-of-week",
+                expression
+            )));
+        }
+
+        let mut day_of_week = CronField::parse(fields[4], 0, 7)?;
+        // Both 0 and 7 are Sunday
+        if day_of_week.matches(7) {
+            day_of_week.bits = (day_of_week.bits | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minute: CronField::parse(fields[0], 0, 59)?,
+            hour: CronField::parse(fields[1], 0, 23)?,
+            day_of_month: CronField::parse(fields[2], 1, 31)?,
+            month: CronField::parse(fields[3], 1, 12)?,
+            day_of_week,
+        })
+    }
+
+    /// Whether the schedule fires on `date`. As in cron, when both day
+    /// fields are restricted a day matching either one fires.
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let dom = self.day_of_month.matches(date.day());
+        let dow = self
+            .day_of_week
+            .matches(date.weekday().num_days_from_sunday());
+        match (self.day_of_month.any, self.day_of_week.any) {
+            (false, false) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+
+    /// First matching minute strictly after `after`
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start.year() + MAX_LOOKAHEAD_YEARS;
+        let mut t = start;
+
+        while t.year() <= limit {
+            if !self.month.matches(t.month()) {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.matches_day(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.hour.matches(t.hour()) {
+                t = t.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !self.minute.matches(t.minute()) {
+                t += Duration::minutes(1);
+                continue;
+            }
+            return Some(t);
+        }
+        None
+    }
+
+    /// Next run after `after_ms` (Unix milliseconds), in local time
+    pub fn next_run_ms(&self, after_ms: u64) -> Option<u64> {
+        let after = Local.timestamp_millis_opt(after_ms as i64).single()?;
+        let mut naive = after.naive_local();
+        // A wall-clock time skipped by a DST change has no local instant;
+        // move on to the next match
+        loop {
+            naive = self.next_after(naive)?;
+            if let Some(at) = Local.from_local_datetime(&naive).earliest() {
+                return Some(at.timestamp_millis() as u64);
+            }
+        }
+    }
+}
+
+/// What a task may do without the user present
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoModePolicy {
+    /// Only tools that never need approval; anything else is refused
+    #[default]
+    ReadOnly,
+    /// Actions that need approval are prepared and left on the task's
+    /// session for the user to approve later
+    QueueApprovals,
+}
+
+/// Outcome of one run of a task
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskRun {
+    /// Unix milliseconds
+    pub started_at: u64,
+    pub finished_at: u64,
+    pub success: bool,
+    /// The agent's final answer, or the error that stopped the run
+    pub response: String,
+    pub tools_used: Vec<String>,
+    /// Proposed actions queued for approval
+    pub pending_approvals: usize,
+    /// Whether the run stopped calling tools because the budget ran out
+    pub budget_exhausted: bool,
+}
+
+/// A recurring agent task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledTask {
+    pub id: String,
+    pub name: String,
+    /// Message sent to the agent on every run
+    pub prompt: String,
+    /// Cron expression, see the module docs
+    pub schedule: String,
+    pub enabled: bool,
+    /// Maximum tool calls per run
+    pub tool_budget: usize,
+    pub policy: AutoModePolicy,
+    /// When non-empty, the only tools the task may call
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+    /// Conversation the runs are recorded in, created on the first run
+    pub session_id: Option<String>,
+    pub created_at: u64,
+    /// Unix milliseconds of the next run; `None` when disabled or the
+    /// schedule never fires again
+    pub next_run: Option<u64>,
+    pub last_run: Option<TaskRun>,
+}
+
+/// Task fields set by the user when creating or editing a task
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskDraft {
+    pub name: String,
+    pub prompt: String,
+    pub schedule: String,
+    /// Defaults to enabled
+    pub enabled: Option<bool>,
+    /// Defaults to [`DEFAULT_TOOL_BUDGET`]
+    pub tool_budget: Option<usize>,
+    pub policy: AutoModePolicy,
+    pub allowed_tools: Vec<String>,
+}
+
+/// Payload of [`AGENT_TASK_COMPLETED_EVENT`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskCompleted {
+    pub task_id: String,
+    pub task_name: String,
+    /// Conversation holding the run
+    pub session_id: Option<String>,
+    pub run: TaskRun,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SchedulerState {
+    #[serde(default)]
+    tasks: Vec<ScheduledTask>,
+}
+
+/// Persistent set of scheduled tasks
+pub struct TaskScheduler {
+    /// JSON file backing the tasks; `None` keeps them in memory only
+    path: Option<PathBuf>,
+    state: RwLock<SchedulerState>,
+    completions: broadcast::Sender<TaskCompleted>,
+}
+
+impl TaskScheduler {
+    /// Open the task list in the user's local app data directory
+    pub fn new() -> Result<Self, SchedulerError> {
+        let data_dir = dirs::data_local_dir().ok_or_else(|| {
+            SchedulerError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Could not find local data directory",
+            ))
+        })?;
+        Self::open(data_dir.join("citrate").join("scheduled_tasks.json"))
+    }
+
+    /// Open (or create) the task list stored at `path`
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, SchedulerError> {
+        let path = path.into();
+        let state = if path.exists() {
+            serde_json::from_str(&std::fs::read_to_string(&path)?)?
+        } else {
+            SchedulerState::default()
+        };
+
+        let scheduler = Self::with_state(Some(path), state);
+        tracing::info!("Scheduled tasks loaded from: {:?}", scheduler.path);
+        Ok(scheduler)
+    }
+
+    /// A task list that is never written to disk
+    pub fn in_memory() -> Self {
+        Self::with_state(None, SchedulerState::default())
+    }
+
+    fn with_state(path: Option<PathBuf>, state: SchedulerState) -> Self {
+        let (completions, _) = broadcast::channel(32);
+        Self {
+            path,
+            state: RwLock::new(state),
+            completions,
+        }
+    }
+
+    /// All tasks, in creation order
+    pub fn list(&self) -> Vec<ScheduledTask> {
+        self.read().tasks.clone()
+    }
+
+    pub fn get(&self, id: &str) -> Result<ScheduledTask, SchedulerError> {
+        self.read()
+            .tasks
+            .iter()
+            .find(|t| t.id == id)
+            .cloned()
+            .ok_or_else(|| SchedulerError::NotFound(id.to_string()))
+    }
+
+    /// Add a task; its first run is the next time the schedule matches
+    pub fn create(&self, draft: TaskDraft) -> Result<ScheduledTask, SchedulerError> {
+        let now = now();
+        let mut task = ScheduledTask {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: String::new(),
+            prompt: String::new(),
+            schedule: String::new(),
+            enabled: true,
+            tool_budget: DEFAULT_TOOL_BUDGET,
+            policy: AutoModePolicy::default(),
+            allowed_tools: Vec::new(),
+            session_id: None,
+            created_at: now,
+            next_run: None,
+            last_run: None,
+        };
+        apply_draft(&mut task, draft, now)?;
+
+        let mut state = self.write();
+        state.tasks.push(task.clone());
+        self.persist(&state)?;
+        Ok(task)
+    }
+
+    /// Replace a task's settings, keeping its id, history and session
+    pub fn update(&self, id: &str, draft: TaskDraft) -> Result<ScheduledTask, SchedulerError> {
+        let mut state = self.write();
+        let task = state
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| SchedulerError::NotFound(id.to_string()))?;
+        apply_draft(task, draft, now())?;
+        let task = task.clone();
+        self.persist(&state)?;
+        Ok(task)
+    }
+
+    /// Enable or pause a task
+    pub fn set_enabled(&self, id: &str, enabled: bool) -> Result<ScheduledTask, SchedulerError> {
+        let mut state = self.write();
+        let task = state
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| SchedulerError::NotFound(id.to_string()))?;
+        task.enabled = enabled;
+        task.next_run = if enabled {
+            CronSchedule::parse(&task.schedule)?.next_run_ms(now())
+        } else {
+            None
+        };
+        let task = task.clone();
+        self.persist(&state)?;
+        Ok(task)
+    }
+
+    /// Delete a task; its conversation is kept
+    pub fn delete(&self, id: &str) -> Result<bool, SchedulerError> {
+        let mut state = self.write();
+        let before = state.tasks.len();
+        state.tasks.retain(|t| t.id != id);
+        if state.tasks.len() == before {
+            return Ok(false);
+        }
+        self.persist(&state)?;
+        Ok(true)
+    }
+
+    /// Enabled tasks whose next run is at or before `now_ms`
+    pub fn due(&self, now_ms: u64) -> Vec<ScheduledTask> {
+        self.read()
+            .tasks
+            .iter()
+            .filter(|t| t.enabled && t.next_run.is_some_and(|at| at <= now_ms))
+            .cloned()
+            .collect()
+    }
+
+    /// Remember the conversation a task's runs are recorded in
+    pub fn set_session(&self, id: &str, session_id: &str) -> Result<(), SchedulerError> {
+        let mut state = self.write();
+        let task = state
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| SchedulerError::NotFound(id.to_string()))?;
+        task.session_id = Some(session_id.to_string());
+        self.persist(&state)
+    }
+
+    /// Store a finished run, schedule the next one and notify subscribers.
+    /// Runs missed while the app was closed are not replayed; the next run
+    /// is computed from the time the run finished.
+    pub fn record_run(&self, id: &str, run: TaskRun) -> Result<ScheduledTask, SchedulerError> {
+        let mut state = self.write();
+        let task = state
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| SchedulerError::NotFound(id.to_string()))?;
+        task.next_run = if task.enabled {
+            CronSchedule::parse(&task.schedule)?.next_run_ms(run.finished_at)
+        } else {
+            None
+        };
+        task.last_run = Some(run.clone());
+        let task = task.clone();
+        self.persist(&state)?;
+        drop(state);
+
+        // Nobody listening is fine
+        let _ = self.completions.send(TaskCompleted {
+            task_id: task.id.clone(),
+            task_name: task.name.clone(),
+            session_id: task.session_id.clone(),
+            run,
+        });
+        Ok(task)
+    }
+
+    /// Subscribe to finished runs
+    pub fn subscribe(&self) -> broadcast::Receiver<TaskCompleted> {
+        self.completions.subscribe()
+    }
+
+    /// Path of the backing file, if any
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Write the tasks to disk, replacing the previous file atomically
+    fn persist(&self, state: &SchedulerState) -> Result<(), SchedulerError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(state)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, SchedulerState> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, SchedulerState> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Validate `draft` and copy it onto `task`, rescheduling from `now_ms`
+fn apply_draft(
+    task: &mut ScheduledTask,
+    draft: TaskDraft,
+    now_ms: u64,
+) -> Result<(), SchedulerError> {
+    let name = draft.name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return Err(SchedulerError::Invalid(
+            "name must be 1-100 characters".to_string(),
+        ));
+    }
+    if draft.prompt.trim().is_empty() {
+        return Err(SchedulerError::Invalid(
+            "prompt cannot be empty".to_string(),
+        ));
+    }
+    let tool_budget = draft.tool_budget.unwrap_or(DEFAULT_TOOL_BUDGET);
+    if tool_budget > MAX_TOOL_BUDGET {
+        return Err(SchedulerError::Invalid(format!(
+            "tool budget cannot exceed {}",
+            MAX_TOOL_BUDGET
+        )));
+    }
+    let schedule = CronSchedule::parse(&draft.schedule)?;
+
+    task.name = name.to_string();
+    task.prompt = draft.prompt;
+    task.schedule = draft.schedule.trim().to_string();
+    task.enabled = draft.enabled.unwrap_or(true);
+    task.tool_budget = tool_budget;
+    task.policy = draft.policy;
+    task.allowed_tools = draft.allowed_tools;
+    task.next_run = if task.enabled {
+        schedule.next_run_ms(now_ms)
+    } else {
+        None
+    };
+    Ok(())
+}
+
+/// Get current Unix timestamp in milliseconds
+pub fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn next(expression: &str, after: &str) -> Option<NaiveDateTime> {
+        CronSchedule::parse(expression)
+            .unwrap()
+            .next_after(at(after))
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        assert_eq!(
+            next("*/15 * * * *", "2026-03-10 08:07"),
+            Some(at("2026-03-10 08:15"))
+        );
+        // Strictly after: a run at 07:00 schedules the next day's run
+        assert_eq!(
+            next("0 7 * * *", "2026-03-10 07:00"),
+            Some(at("2026-03-11 07:00"))
+        );
+        assert_eq!(
+            next("@hourly", "2026-12-31 23:30"),
+            Some(at("2027-01-01 00:00"))
+        );
+        // Weekdays only: Friday evening jumps to Monday
+        assert_eq!(
+            next("30 9 * * 1-5", "2026-03-13 18:00"),
+            Some(at("2026-03-16 09:30"))
+        );
+        // Sunday can be written as 7
+        assert_eq!(
+            next("0 0 * * 7", "2026-03-10 00:00"),
+            Some(at("2026-03-15 00:00"))
+        );
+        // Both day fields restricted: either one matches
+        assert_eq!(
+            next("0 12 1 * 3", "2026-03-02 00:00"),
+            Some(at("2026-03-04 12:00"))
+        );
+        assert_eq!(
+            next("0 0 29 2 *", "2026-03-01 00:00"),
+            Some(at("2028-02-29 00:00"))
+        );
+        assert_eq!(next("0 0 31 2 *", "2026-03-01 00:00"), None);
+    }
+
+    #[test]
+    fn test_cron_rejects_bad_expressions() {
+        for bad in [
+            "* * * *",
+            "60 * * * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+        ] {
+            assert!(CronSchedule::parse(bad).is_err(), "{}", bad);
+        }
+        assert!(CronSchedule::parse("0,30 8-18/2 * 1,6 *").is_ok());
+    }
+
+    fn draft(name: &str, schedule: &str) -> TaskDraft {
+        TaskDraft {
+            name: name.to_string(),
+            prompt: "Summarize my node's overnight logs".to_string(),
+            schedule: schedule.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_tasks_become_due_and_reschedule() {
+        let scheduler = TaskScheduler::in_memory();
+        let task = scheduler
+            .create(draft("Overnight logs", "0 7 * * *"))
+            .unwrap();
+        assert_eq!(task.tool_budget, DEFAULT_TOOL_BUDGET);
+        assert_eq!(task.policy, AutoModePolicy::ReadOnly);
+        let first = task.next_run.unwrap();
+        assert!(scheduler.due(first - 1).is_empty());
+        assert_eq!(scheduler.due(first).len(), 1);
+
+        let mut completions = scheduler.subscribe();
+        let run = TaskRun {
+            started_at: first,
+            finished_at: first + 1_000,
+            success: true,
+            response: "All quiet".to_string(),
+            ..Default::default()
+        };
+        let task = scheduler.record_run(&task.id, run).unwrap();
+        assert!(task.next_run.unwrap() > first);
+        assert!(scheduler.due(first + 1_000).is_empty());
+        assert_eq!(completions.try_recv().unwrap().run.response, "All quiet");
+
+        let task = scheduler.set_enabled(&task.id, false).unwrap();
+        assert_eq!(task.next_run, None);
+        assert!(scheduler.delete(&task.id).unwrap());
+        assert!(matches!(
+            scheduler.get(&task.id),
+            Err(SchedulerError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_tasks_persist_and_validate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scheduled_tasks.json");
+
+        let scheduler = TaskScheduler::open(&path).unwrap();
+        assert!(scheduler.create(draft("", "@daily")).is_err());
+        assert!(scheduler.create(draft("Prices", "every day")).is_err());
+        let mut over_budget = draft("Prices", "@daily");
+        over_budget.tool_budget = Some(MAX_TOOL_BUDGET + 1);
+        assert!(scheduler.create(over_budget).is_err());
+
+        let task = scheduler.create(draft("Prices", "@daily")).unwrap();
+        scheduler.set_session(&task.id, "session-1").unwrap();
+
+        let reopened = TaskScheduler::open(&path).unwrap();
+        let task = reopened.get(&task.id).unwrap();
+        assert_eq!(task.name, "Prices");
+        assert_eq!(task.session_id.as_deref(), Some("session-1"));
+    }
+}
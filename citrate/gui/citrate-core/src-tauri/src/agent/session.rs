@@ -9,6 +9,8 @@ use tokio::sync::RwLock;
 
 use super::context::ConversationHistory;
 use super::intent::IntentMatch;
+use super::llm::router::RouteOverride;
+use super::prompts::PromptAssignment;
 
 /// Unique session identifier
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -264,6 +266,10 @@ pub struct AgentSession {
     pending_tools: RwLock<Vec<PendingToolCall>>,
     /// Session metadata
     metadata: RwLock<HashMap<String, serde_json::Value>>,
+    /// Prompt from the library used instead of the default
+    prompt: RwLock<Option<PromptAssignment>>,
+    /// Provider routing choices for this session
+    route: RwLock<RouteOverride>,
     /// Created timestamp
     created_at: u64,
     /// Last activity timestamp
@@ -284,6 +290,8 @@ impl AgentSession {
             history: RwLock::new(ConversationHistory::new()),
             pending_tools: RwLock::new(Vec::new()),
             metadata: RwLock::new(HashMap::new()),
+            prompt: RwLock::new(None),
+            route: RwLock::new(RouteOverride::default()),
             created_at: now,
             last_activity: RwLock::new(now),
         }
@@ -386,6 +394,28 @@ impl AgentSession {
         self.metadata.read().await.get(key).cloned()
     }
 
+    /// Assign a library prompt to this session, or clear the assignment
+    pub async fn set_prompt(&self, assignment: Option<PromptAssignment>) {
+        *self.prompt.write().await = assignment;
+        self.touch().await;
+    }
+
+    /// Library prompt assigned to this session
+    pub async fn prompt(&self) -> Option<PromptAssignment> {
+        self.prompt.read().await.clone()
+    }
+
+    /// Override how this session's requests are routed over providers
+    pub async fn set_route(&self, route: RouteOverride) {
+        *self.route.write().await = route;
+        self.touch().await;
+    }
+
+    /// Provider routing choices for this session
+    pub async fn route(&self) -> RouteOverride {
+        self.route.read().await.clone()
+    }
+
     /// Get creation timestamp
     pub fn created_at(&self) -> u64 {
         self.created_at
@@ -2,11 +2,37 @@
 //!
 //! Provides token-by-token streaming from LLM backends to the frontend
 //! via Tauri events.
+//!
+//! Generating code pushes text into a [`TokenSink`]. The command that
+//! started the generation drains the matching [`StreamReceiver`] and emits
+//! each piece as a numbered [`StreamEvent`] on the request's own topic,
+//! `agent-stream:<stream id>`. The frontend picks the stream id, subscribes
+//! to the topic and only then invokes the command, so no event is missed.
 
 use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
 use tokio::sync::mpsc;
 
+/// Prefix of the per-request stream event topics
+pub const STREAM_EVENT_PREFIX: &str = "agent-stream";
+
+/// Event topic for the stream `stream_id`
+pub fn stream_topic(stream_id: &str) -> String {
+    format!("{}:{}", STREAM_EVENT_PREFIX, stream_id)
+}
+
+/// Whether `stream_id` can be used in an event topic
+pub fn is_valid_stream_id(stream_id: &str) -> bool {
+    !stream_id.is_empty()
+        && stream_id.len() <= 128
+        && stream_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
 /// A single streamed token
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamToken {
@@ -43,6 +69,175 @@ pub enum StreamStatus {
     },
     /// Stream was cancelled
     Cancelled { session_id: String, message_id: String },
+    /// Text streamed so far was intermediate reasoning and should be cleared
+    Reset { session_id: String, message_id: String },
+}
+
+/// Payload emitted on a stream's topic
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamEvent {
+    /// Position in the stream, starting at 0, so the frontend can order
+    /// events and notice gaps
+    pub seq: u64,
+    #[serde(flatten)]
+    pub status: StreamStatus,
+}
+
+/// Piece of output pushed through a [`TokenSink`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamChunk {
+    /// Generated text
+    Text(String),
+    /// Discard the text sent so far
+    Reset,
+}
+
+/// Producer side of a stream. Sending never blocks, so the sink can be
+/// used from the synchronous llama.cpp generation loop.
+#[derive(Debug, Clone)]
+pub struct TokenSink {
+    sender: mpsc::UnboundedSender<StreamChunk>,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Consumer side of a stream. `recv` returns `None` once every sink has
+/// been dropped.
+#[derive(Debug)]
+pub struct StreamReceiver {
+    receiver: mpsc::UnboundedReceiver<StreamChunk>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl TokenSink {
+    /// A connected sink and receiver
+    pub fn channel() -> (TokenSink, StreamReceiver) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        (
+            TokenSink {
+                sender,
+                cancelled: cancelled.clone(),
+            },
+            StreamReceiver {
+                receiver,
+                cancelled,
+            },
+        )
+    }
+
+    /// Push generated text. Returns false once the stream was cancelled or
+    /// nobody is listening, telling the producer to stop.
+    pub fn send(&self, text: &str) -> bool {
+        if self.is_cancelled() {
+            return false;
+        }
+        if text.is_empty() {
+            return true;
+        }
+        self.sender
+            .send(StreamChunk::Text(text.to_string()))
+            .is_ok()
+    }
+
+    /// Tell the frontend to discard the text sent so far
+    pub fn reset(&self) {
+        let _ = self.sender.send(StreamChunk::Reset);
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+impl StreamReceiver {
+    pub async fn recv(&mut self) -> Option<StreamChunk> {
+        self.receiver.recv().await
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Numbers the events of one stream
+pub struct StreamSequencer {
+    session_id: String,
+    message_id: String,
+    seq: u64,
+    token_count: usize,
+}
+
+impl StreamSequencer {
+    pub fn new(session_id: String, message_id: String) -> Self {
+        Self {
+            session_id,
+            message_id,
+            seq: 0,
+            token_count: 0,
+        }
+    }
+
+    fn next(&mut self, status: StreamStatus) -> StreamEvent {
+        let event = StreamEvent {
+            seq: self.seq,
+            status,
+        };
+        self.seq += 1;
+        event
+    }
+
+    pub fn started(&mut self) -> StreamEvent {
+        self.next(StreamStatus::Started {
+            session_id: self.session_id.clone(),
+            message_id: self.message_id.clone(),
+        })
+    }
+
+    pub fn chunk(&mut self, chunk: StreamChunk) -> StreamEvent {
+        match chunk {
+            StreamChunk::Text(text) => {
+                self.token_count += 1;
+                self.next(StreamStatus::Token(StreamToken {
+                    text,
+                    index: self.token_count - 1,
+                    is_final: false,
+                    cumulative: None,
+                }))
+            }
+            StreamChunk::Reset => self.next(StreamStatus::Reset {
+                session_id: self.session_id.clone(),
+                message_id: self.message_id.clone(),
+            }),
+        }
+    }
+
+    pub fn completed(&mut self, finish_reason: &str) -> StreamEvent {
+        self.next(StreamStatus::Completed {
+            session_id: self.session_id.clone(),
+            message_id: self.message_id.clone(),
+            total_tokens: self.token_count,
+            finish_reason: finish_reason.to_string(),
+        })
+    }
+
+    pub fn error(&mut self, error: String) -> StreamEvent {
+        self.next(StreamStatus::Error {
+            session_id: self.session_id.clone(),
+            message_id: self.message_id.clone(),
+            error,
+        })
+    }
+
+    pub fn cancelled(&mut self) -> StreamEvent {
+        self.next(StreamStatus::Cancelled {
+            session_id: self.session_id.clone(),
+            message_id: self.message_id.clone(),
+        })
+    }
 }
 
 /// Configuration for streaming responses
@@ -85,6 +280,8 @@ pub struct StreamingResponse {
     include_cumulative: bool,
     /// Whether the stream has been finalized
     finalized: bool,
+    /// Set when the stream is cancelled through its manager
+    cancelled: Arc<AtomicBool>,
 }
 
 impl StreamingResponse {
@@ -103,6 +300,7 @@ impl StreamingResponse {
             token_count: 0,
             include_cumulative,
             finalized: false,
+            cancelled: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -121,6 +319,9 @@ impl StreamingResponse {
         if self.finalized {
             return Err(StreamError::AlreadyFinalized);
         }
+        if self.cancelled.load(Ordering::SeqCst) {
+            return Err(StreamError::Cancelled);
+        }
 
         self.accumulated.push_str(text);
         self.token_count += 1;
@@ -192,6 +393,10 @@ pub enum StreamError {
     Timeout,
     /// Backend error
     BackendError(String),
+    /// Stream was cancelled
+    Cancelled,
+    /// A stream with this message ID is already running
+    AlreadyActive(String),
 }
 
 impl std::fmt::Display for StreamError {
@@ -201,6 +406,8 @@ impl std::fmt::Display for StreamError {
             Self::AlreadyFinalized => write!(f, "Stream already finalized"),
             Self::Timeout => write!(f, "Stream timeout"),
             Self::BackendError(e) => write!(f, "Backend error: {}", e),
+            Self::Cancelled => write!(f, "Stream cancelled"),
+            Self::AlreadyActive(id) => write!(f, "Stream already active: {}", id),
         }
     }
 }
@@ -215,8 +422,8 @@ pub struct StreamManager {
 
 /// Handle to an active stream
 pub struct StreamHandle {
-    /// Sender to cancel the stream
-    cancel_tx: mpsc::Sender<()>,
+    /// Set to cancel the stream
+    cancelled: Arc<AtomicBool>,
     /// Session ID
     pub session_id: String,
     /// Message ID
@@ -239,7 +446,6 @@ impl StreamManager {
         config: &StreamingConfig,
     ) -> (StreamingResponse, mpsc::Receiver<StreamToken>) {
         let (token_tx, token_rx) = mpsc::channel(config.buffer_size);
-        let (cancel_tx, _cancel_rx) = mpsc::channel(1);
 
         let response = StreamingResponse::new(
             session_id.clone(),
@@ -249,7 +455,7 @@ impl StreamManager {
         );
 
         let handle = StreamHandle {
-            cancel_tx,
+            cancelled: response.cancelled.clone(),
             session_id,
             message_id: message_id.clone(),
         };
@@ -262,10 +468,33 @@ impl StreamManager {
         (response, token_rx)
     }
 
+    /// Register a stream fed through a [`TokenSink`]. Fails if a stream
+    /// with the same message ID is already active.
+    pub async fn open(
+        &self,
+        session_id: String,
+        message_id: String,
+    ) -> Result<(TokenSink, StreamReceiver), StreamError> {
+        let mut streams = self.active_streams.write().await;
+        if streams.contains_key(&message_id) {
+            return Err(StreamError::AlreadyActive(message_id));
+        }
+        let (sink, receiver) = TokenSink::channel();
+        streams.insert(
+            message_id.clone(),
+            StreamHandle {
+                cancelled: sink.cancelled.clone(),
+                session_id,
+                message_id,
+            },
+        );
+        Ok((sink, receiver))
+    }
+
     /// Cancel a stream
     pub async fn cancel_stream(&self, message_id: &str) -> bool {
         if let Some(handle) = self.active_streams.write().await.remove(message_id) {
-            let _ = handle.cancel_tx.send(()).await;
+            handle.cancelled.store(true, Ordering::SeqCst);
             true
         } else {
             false
@@ -289,6 +518,63 @@ impl Default for StreamManager {
     }
 }
 
+/// Run `work` with a sink registered under `stream_id`, emitting what it
+/// produces on the stream's topic. The stream starts with a `Started` event
+/// and ends with `Completed`, `Error` or `Cancelled`.
+pub async fn run_streamed<T, E, F, Fut>(
+    app: &AppHandle,
+    manager: &StreamManager,
+    session_id: String,
+    stream_id: String,
+    work: F,
+) -> Result<T, String>
+where
+    E: std::fmt::Display,
+    F: FnOnce(TokenSink) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    if !is_valid_stream_id(&stream_id) {
+        return Err(format!("Invalid stream id: {}", stream_id));
+    }
+    let (sink, mut receiver) = manager
+        .open(session_id.clone(), stream_id.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let topic = stream_topic(&stream_id);
+    let emit = |event: StreamEvent| {
+        if let Err(e) = app.emit(&topic, event) {
+            tracing::warn!("Failed to emit stream event on {}: {}", topic, e);
+        }
+    };
+    let mut sequencer = StreamSequencer::new(session_id, stream_id.clone());
+    emit(sequencer.started());
+
+    // The channel closes when `work` finishes and drops its sink
+    let forward = async {
+        while let Some(chunk) = receiver.recv().await {
+            emit(sequencer.chunk(chunk));
+        }
+    };
+    let (result, ()) = tokio::join!(work(sink), forward);
+
+    manager.complete_stream(&stream_id).await;
+    if receiver.is_cancelled() {
+        emit(sequencer.cancelled());
+        return Err("Cancelled".to_string());
+    }
+    match result {
+        Ok(value) => {
+            emit(sequencer.completed("stop"));
+            Ok(value)
+        }
+        Err(e) => {
+            emit(sequencer.error(e.to_string()));
+            Err(e.to_string())
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -334,4 +620,52 @@ mod tests {
 
         assert_eq!(manager.active_count().await, 0);
     }
+
+    #[tokio::test]
+    async fn test_cancel_stops_token_sink() {
+        let manager = StreamManager::new();
+        let (sink, mut receiver) = manager
+            .open("session1".into(), "msg1".into())
+            .await
+            .unwrap();
+        let duplicate = manager.open("session1".into(), "msg1".into()).await;
+        assert!(duplicate.is_err());
+
+        assert!(sink.send("Hel"));
+        sink.reset();
+        assert!(manager.cancel_stream("msg1").await);
+        assert!(!sink.send("lo"));
+        assert!(receiver.is_cancelled());
+        drop(sink);
+
+        assert_eq!(receiver.recv().await, Some(StreamChunk::Text("Hel".into())));
+        assert_eq!(receiver.recv().await, Some(StreamChunk::Reset));
+        assert_eq!(receiver.recv().await, None);
+        assert_eq!(manager.active_count().await, 0);
+    }
+
+    #[test]
+    fn test_sequenced_events() {
+        let mut sequencer = StreamSequencer::new("s".into(), "m".into());
+        let started = serde_json::to_value(sequencer.started()).unwrap();
+        assert_eq!(started["seq"], 0);
+        assert_eq!(started["type"], "Started");
+
+        let token = serde_json::to_value(sequencer.chunk(StreamChunk::Text("Hi".into()))).unwrap();
+        assert_eq!(token["seq"], 1);
+        assert_eq!(token["type"], "Token");
+        assert_eq!(token["text"], "Hi");
+        assert_eq!(token["index"], 0);
+
+        sequencer.chunk(StreamChunk::Reset);
+        sequencer.chunk(StreamChunk::Text("!".into()));
+        let completed = serde_json::to_value(sequencer.completed("stop")).unwrap();
+        assert_eq!(completed["seq"], 4);
+        assert_eq!(completed["total_tokens"], 2);
+
+        assert_eq!(stream_topic("abc-1"), "agent-stream:abc-1");
+        assert!(is_valid_stream_id("abc-1_2"));
+        assert!(!is_valid_stream_id("a b"));
+        assert!(!is_valid_stream_id(""));
+    }
 }
@@ -0,0 +1,854 @@
+//! ABI-aware contract tools
+//!
+//! These tools load a contract ABI (forge/hardhat artifact or Etherscan-style
+//! JSON), encode calls against it, simulate them with `eth_call` and propose
+//! signed transactions that only go out once the user approves them.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use ethabi::token::{LenientTokenizer, Tokenizer};
+use ethabi::{Contract, Function, StateMutability, Token};
+
+use super::super::dispatcher::{DispatchError, ToolHandler, ToolOutput};
+use super::super::intent::IntentParams;
+use crate::node::NodeManager;
+use crate::wallet::WalletManager;
+
+/// Loaded contract ABIs keyed by lowercase contract address
+pub type AbiRegistry = Arc<RwLock<HashMap<String, Contract>>>;
+
+/// Create an empty ABI registry shared by the ABI-aware tools
+pub fn new_abi_registry() -> AbiRegistry {
+    Arc::new(RwLock::new(HashMap::new()))
+}
+
+/// Load ABI tool - registers a contract ABI for later calls
+pub struct LoadContractAbiTool {
+    registry: AbiRegistry,
+}
+
+impl LoadContractAbiTool {
+    pub fn new(registry: AbiRegistry) -> Self {
+        Self { registry }
+    }
+}
+
+impl ToolHandler for LoadContractAbiTool {
+    fn name(&self) -> &str {
+        "load_contract_abi"
+    }
+
+    fn description(&self) -> &str {
+        "Load a contract ABI for a contract address. Provide contract_address and abi (JSON from forge/hardhat output or Etherscan) or abi_path (path to an artifact file)."
+    }
+
+    fn execute(
+        &self,
+        params: &IntentParams,
+    ) -> Pin<Box<dyn Future<Output = Result<ToolOutput, DispatchError>> + Send + '_>> {
+        let registry = self.registry.clone();
+        let address = contract_param(params);
+        let source = params
+            .extra
+            .get("abi")
+            .or_else(|| params.extra.get("abi_path"))
+            .cloned()
+            .or_else(|| params.contract_data.clone());
+        Box::pin(async move {
+            let contract_addr = address.ok_or_else(|| {
+                DispatchError::InvalidParams("Contract address required".to_string())
+            })?;
+            let source = source.ok_or_else(|| {
+                DispatchError::InvalidParams("ABI JSON (abi) or artifact path (abi_path) required".to_string())
+            })?;
+
+            let contract = match parse_abi(&source) {
+                Ok(contract) => contract,
+                Err(e) => {
+                    return Ok(ToolOutput {
+                        tool: "load_contract_abi".to_string(),
+                        success: false,
+                        message: format!("Failed to load ABI: {}", e),
+                        data: None,
+                    });
+                }
+            };
+
+            let mut functions: Vec<&Function> = contract.functions().collect();
+            functions.sort_by_key(|f| input_signature(f));
+            let summary: Vec<serde_json::Value> = functions
+                .iter()
+                .map(|f| {
+                    serde_json::json!({
+                        "signature": input_signature(f),
+                        "selector": format!("0x{}", hex::encode(f.short_signature())),
+                        "state_mutability": mutability_str(f.state_mutability),
+                    })
+                })
+                .collect();
+            let names: Vec<String> = functions.iter().map(|f| input_signature(f)).collect();
+
+            let key = normalize_address(&contract_addr);
+            registry.write().await.insert(key.clone(), contract);
+
+            Ok(ToolOutput {
+                tool: "load_contract_abi".to_string(),
+                success: true,
+                message: format!(
+                    "Loaded ABI for {} with {} functions: {}",
+                    key,
+                    names.len(),
+                    names.join(", ")
+                ),
+                data: Some(serde_json::json!({
+                    "contract": key,
+                    "functions": summary,
+                })),
+            })
+        })
+    }
+}
+
+/// Simulate contract call tool - encodes a call from the ABI and runs eth_call
+pub struct SimulateContractCallTool {
+    node_manager: Arc<NodeManager>,
+    registry: AbiRegistry,
+}
+
+impl SimulateContractCallTool {
+    pub fn new(node_manager: Arc<NodeManager>, registry: AbiRegistry) -> Self {
+        Self {
+            node_manager,
+            registry,
+        }
+    }
+}
+
+impl ToolHandler for SimulateContractCallTool {
+    fn name(&self) -> &str {
+        "simulate_contract_call"
+    }
+
+    fn description(&self) -> &str {
+        "Simulate a contract function using its loaded ABI (read-only, via eth_call) and decode the result. Provide contract_address, function (name or signature) and args (JSON array)."
+    }
+
+    fn execute(
+        &self,
+        params: &IntentParams,
+    ) -> Pin<Box<dyn Future<Output = Result<ToolOutput, DispatchError>> + Send + '_>> {
+        let node_manager = self.node_manager.clone();
+        let registry = self.registry.clone();
+        let address = contract_param(params);
+        let function = function_param(params);
+        let args = args_param(params);
+        Box::pin(async move {
+            let contract_addr = address.ok_or_else(|| {
+                DispatchError::InvalidParams("Contract address required".to_string())
+            })?;
+            let func_name = function.ok_or_else(|| {
+                DispatchError::InvalidParams("Function name or signature required".to_string())
+            })?;
+
+            let (function, calldata) =
+                match encode_with_registry(&registry, &contract_addr, &func_name, &args).await {
+                    Ok(encoded) => encoded,
+                    Err(e) => {
+                        return Ok(ToolOutput {
+                            tool: "simulate_contract_call".to_string(),
+                            success: false,
+                            message: e,
+                            data: None,
+                        });
+                    }
+                };
+            let signature = input_signature(&function);
+            let calldata_hex = format!("0x{}", hex::encode(&calldata));
+
+            if let Some(message) = node_not_running(&node_manager).await {
+                return Ok(ToolOutput {
+                    tool: "simulate_contract_call".to_string(),
+                    success: false,
+                    message,
+                    data: None,
+                });
+            }
+
+            match simulate(&node_manager, &contract_addr, &function, &calldata_hex).await {
+                Ok((raw, decoded)) => Ok(ToolOutput {
+                    tool: "simulate_contract_call".to_string(),
+                    success: true,
+                    message: format!(
+                        "{} on {} returned: {}",
+                        signature,
+                        short_address(&contract_addr),
+                        decoded
+                    ),
+                    data: Some(serde_json::json!({
+                        "contract": contract_addr,
+                        "function": signature,
+                        "calldata": calldata_hex,
+                        "result": raw,
+                        "decoded": decoded,
+                        "status": "success"
+                    })),
+                }),
+                Err(e) => Ok(ToolOutput {
+                    tool: "simulate_contract_call".to_string(),
+                    success: false,
+                    message: format!("Simulation of {} failed: {}", signature, e),
+                    data: Some(serde_json::json!({
+                        "contract": contract_addr,
+                        "function": signature,
+                        "calldata": calldata_hex,
+                        "error": e
+                    })),
+                }),
+            }
+        })
+    }
+}
+
+/// Propose contract transaction tool - simulates a state-changing call and
+/// holds it for user approval before signing and sending it
+pub struct ProposeContractTransactionTool {
+    wallet_manager: Arc<WalletManager>,
+    node_manager: Arc<NodeManager>,
+    registry: AbiRegistry,
+}
+
+impl ProposeContractTransactionTool {
+    pub fn new(
+        wallet_manager: Arc<WalletManager>,
+        node_manager: Arc<NodeManager>,
+        registry: AbiRegistry,
+    ) -> Self {
+        Self {
+            wallet_manager,
+            node_manager,
+            registry,
+        }
+    }
+}
+
+impl ToolHandler for ProposeContractTransactionTool {
+    fn name(&self) -> &str {
+        "propose_contract_transaction"
+    }
+
+    fn description(&self) -> &str {
+        "Propose a state-changing contract call using its loaded ABI. The call is simulated first and only signed and sent after the user approves it. Provide contract_address, function, args (JSON array) and optional value."
+    }
+
+    fn execute(
+        &self,
+        params: &IntentParams,
+    ) -> Pin<Box<dyn Future<Output = Result<ToolOutput, DispatchError>> + Send + '_>> {
+        let wallet_manager = self.wallet_manager.clone();
+        let node_manager = self.node_manager.clone();
+        let registry = self.registry.clone();
+        let address = contract_param(params);
+        let function = function_param(params);
+        let args = args_param(params);
+        let value = params
+            .amount
+            .clone()
+            .or_else(|| params.extra.get("value").cloned())
+            .unwrap_or_else(|| "0".to_string());
+        let approved = params.extra.get("approved").map(|v| v == "true").unwrap_or(false);
+        let password = params.extra.get("password").cloned().unwrap_or_default();
+        Box::pin(async move {
+            let contract_addr = address.ok_or_else(|| {
+                DispatchError::InvalidParams("Contract address required".to_string())
+            })?;
+            let func_name = function.ok_or_else(|| {
+                DispatchError::InvalidParams("Function name or signature required".to_string())
+            })?;
+
+            let (function, calldata) =
+                match encode_with_registry(&registry, &contract_addr, &func_name, &args).await {
+                    Ok(encoded) => encoded,
+                    Err(e) => {
+                        return Ok(ToolOutput {
+                            tool: "propose_contract_transaction".to_string(),
+                            success: false,
+                            message: e,
+                            data: None,
+                        });
+                    }
+                };
+            let signature = input_signature(&function);
+            let calldata_hex = format!("0x{}", hex::encode(&calldata));
+
+            // Read-only functions never need a transaction
+            if matches!(
+                function.state_mutability,
+                StateMutability::View | StateMutability::Pure
+            ) {
+                return Ok(ToolOutput {
+                    tool: "propose_contract_transaction".to_string(),
+                    success: false,
+                    message: format!(
+                        "{} is read-only. Use simulate_contract_call instead.",
+                        signature
+                    ),
+                    data: None,
+                });
+            }
+            if function.state_mutability != StateMutability::Payable
+                && value.parse::<u128>().unwrap_or(0) > 0
+            {
+                return Ok(ToolOutput {
+                    tool: "propose_contract_transaction".to_string(),
+                    success: false,
+                    message: format!("{} is not payable and cannot receive value.", signature),
+                    data: None,
+                });
+            }
+
+            // Get sender
+            let accounts = wallet_manager.get_accounts().await;
+            if accounts.is_empty() {
+                return Ok(ToolOutput {
+                    tool: "propose_contract_transaction".to_string(),
+                    success: false,
+                    message: "No wallet accounts found.".to_string(),
+                    data: None,
+                });
+            }
+            let from_addr = accounts[0].address.clone();
+
+            if !approved {
+                if let Some(message) = node_not_running(&node_manager).await {
+                    return Ok(ToolOutput {
+                        tool: "propose_contract_transaction".to_string(),
+                        success: false,
+                        message,
+                        data: None,
+                    });
+                }
+
+                // Simulate before asking the user to approve anything
+                let simulation =
+                    match simulate(&node_manager, &contract_addr, &function, &calldata_hex).await {
+                        Ok((_, decoded)) => decoded,
+                        Err(e) => {
+                            return Ok(ToolOutput {
+                                tool: "propose_contract_transaction".to_string(),
+                                success: false,
+                                message: format!(
+                                    "Simulation of {} failed, transaction not proposed: {}",
+                                    signature, e
+                                ),
+                                data: Some(serde_json::json!({
+                                    "contract": contract_addr,
+                                    "function": signature,
+                                    "calldata": calldata_hex,
+                                    "error": e
+                                })),
+                            });
+                        }
+                    };
+
+                return Ok(ToolOutput {
+                    tool: "propose_contract_transaction".to_string(),
+                    success: true,
+                    message: format!(
+                        "Proposed {} on {} (value {}). Simulation returned {}. Awaiting user approval.",
+                        signature,
+                        short_address(&contract_addr),
+                        value,
+                        simulation
+                    ),
+                    data: Some(serde_json::json!({
+                        "status": "awaiting_approval",
+                        "from": from_addr,
+                        "to": contract_addr,
+                        "function": signature,
+                        "args": args,
+                        "calldata": calldata_hex,
+                        "value": value,
+                        "gas_limit": 500_000,
+                        "simulation": simulation
+                    })),
+                });
+            }
+
+            // Approved: sign with the session key (or the supplied password) and send
+            let tx_request = crate::wallet::TransactionRequest {
+                from: from_addr.clone(),
+                to: Some(contract_addr.clone()),
+                value: value.clone(),
+                data: calldata_hex.clone(),
+                gas_limit: 500_000,
+                gas_price: "1000000000".to_string(),
+            };
+
+            match wallet_manager
+                .create_signed_transaction(tx_request, &password)
+                .await
+            {
+                Ok(tx) => {
+                    let tx_hash = format!("{:?}", tx.hash);
+
+                    // Add to mempool - Mempool is internally synchronized
+                    if let Some(mempool) = node_manager.get_mempool().await {
+                        use citrate_sequencer::mempool::TxClass;
+                        let _ = mempool.add_transaction(tx.clone(), TxClass::Standard).await;
+                    }
+
+                    // Broadcast
+                    use citrate_network::NetworkMessage;
+                    let _ = node_manager
+                        .broadcast_network(NetworkMessage::NewTransaction { transaction: tx })
+                        .await;
+
+                    Ok(ToolOutput {
+                        tool: "propose_contract_transaction".to_string(),
+                        success: true,
+                        message: format!(
+                            "Contract transaction sent! TX: {}. Called {} on {}",
+                            &tx_hash,
+                            signature,
+                            short_address(&contract_addr)
+                        ),
+                        data: Some(serde_json::json!({
+                            "tx_hash": tx_hash,
+                            "from": from_addr,
+                            "to": contract_addr,
+                            "function": signature,
+                            "calldata": calldata_hex,
+                            "value": value,
+                            "status": "pending"
+                        })),
+                    })
+                }
+                Err(e) => Ok(ToolOutput {
+                    tool: "propose_contract_transaction".to_string(),
+                    success: false,
+                    message: format!("Failed to send contract transaction: {}", e),
+                    data: None,
+                }),
+            }
+        })
+    }
+
+    fn requires_confirmation(&self) -> bool {
+        true
+    }
+}
+
+/// Parse an ABI from a raw JSON array, a forge/hardhat artifact (`{"abi": [...]}`),
+/// an Etherscan `getabi` response (`{"result": "<abi json>"}`) or a path to any of these
+pub fn parse_abi(source: &str) -> Result<Contract, String> {
+    let trimmed = source.trim();
+    let text = if trimmed.starts_with('[') || trimmed.starts_with('{') {
+        trimmed.to_string()
+    } else {
+        let path = shellexpand::tilde(trimmed).to_string();
+        std::fs::read_to_string(&path).map_err(|e| format!("cannot read {}: {}", path, e))?
+    };
+
+    let value = parse_json(&text)?;
+    let abi = match value {
+        serde_json::Value::Array(_) => value,
+        serde_json::Value::Object(mut map) => match map.remove("abi").or_else(|| map.remove("result")) {
+            Some(serde_json::Value::String(inner)) => parse_json(&inner)?,
+            Some(abi @ serde_json::Value::Array(_)) => abi,
+            _ => return Err("expected an \"abi\" array or an Etherscan \"result\"".to_string()),
+        },
+        _ => return Err("expected an ABI array or object".to_string()),
+    };
+
+    serde_json::from_value(abi).map_err(|e| format!("invalid ABI: {}", e))
+}
+
+/// Resolve a function by name or full signature (e.g. `transfer(address,uint256)`).
+/// Overloads are disambiguated by argument count when only a name is given.
+pub fn resolve_function<'a>(
+    contract: &'a Contract,
+    name_or_signature: &str,
+    arg_count: usize,
+) -> Result<&'a Function, String> {
+    let wanted: String = name_or_signature.chars().filter(|c| !c.is_whitespace()).collect();
+    let name = wanted.split('(').next().unwrap_or_default();
+    let candidates = contract
+        .functions_by_name(name)
+        .map_err(|_| format!("function {} not found in ABI", name))?;
+
+    let matched: Vec<&Function> = if wanted.contains('(') {
+        candidates.iter().filter(|f| input_signature(f) == wanted).collect()
+    } else {
+        candidates.iter().filter(|f| f.inputs.len() == arg_count).collect()
+    };
+
+    match matched.as_slice() {
+        [function] => Ok(function),
+        [] => Err(format!(
+            "no overload of {} matches; available: {}",
+            name,
+            candidates.iter().map(input_signature).collect::<Vec<_>>().join(", ")
+        )),
+        _ => Err(format!(
+            "{} is ambiguous; use a full signature: {}",
+            name,
+            matched.iter().map(|f| input_signature(f)).collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
+/// Encode calldata (selector + arguments) for `function`
+pub fn encode_call(function: &Function, args: &[String]) -> Result<Vec<u8>, String> {
+    if function.inputs.len() != args.len() {
+        return Err(format!(
+            "{} expects {} arguments, got {}",
+            input_signature(function),
+            function.inputs.len(),
+            args.len()
+        ));
+    }
+
+    let tokens = function
+        .inputs
+        .iter()
+        .zip(args)
+        .map(|(param, arg)| {
+            LenientTokenizer::tokenize(&param.kind, arg)
+                .map_err(|e| format!("invalid {} for {}: {}", param.kind, param.name, e))
+        })
+        .collect::<Result<Vec<Token>, String>>()?;
+
+    function.encode_input(&tokens).map_err(|e| e.to_string())
+}
+
+/// Encode deployment data: creation `bytecode` followed by the constructor
+/// arguments
+pub fn encode_constructor(
+    contract: &Contract,
+    bytecode: Vec<u8>,
+    args: &[String],
+) -> Result<Vec<u8>, String> {
+    let Some(constructor) = &contract.constructor else {
+        if !args.is_empty() {
+            return Err(format!("constructor takes no arguments, got {}", args.len()));
+        }
+        return Ok(bytecode);
+    };
+    if constructor.inputs.len() != args.len() {
+        return Err(format!(
+            "constructor expects {} arguments, got {}",
+            constructor.inputs.len(),
+            args.len()
+        ));
+    }
+
+    let tokens = constructor
+        .inputs
+        .iter()
+        .zip(args)
+        .map(|(param, arg)| {
+            LenientTokenizer::tokenize(&param.kind, arg)
+                .map_err(|e| format!("invalid {} for {}: {}", param.kind, param.name, e))
+        })
+        .collect::<Result<Vec<Token>, String>>()?;
+
+    constructor.encode_input(bytecode, &tokens).map_err(|e| e.to_string())
+}
+
+/// Decode `eth_call` return data into JSON values
+pub fn decode_output(function: &Function, data: &[u8]) -> Result<serde_json::Value, String> {
+    let tokens = function.decode_output(data).map_err(|e| e.to_string())?;
+    Ok(serde_json::Value::Array(tokens.iter().map(token_to_json).collect()))
+}
+
+/// Function signature without outputs, e.g. `transfer(address,uint256)`
+fn input_signature(function: &Function) -> String {
+    let inputs: Vec<String> = function.inputs.iter().map(|p| p.kind.to_string()).collect();
+    format!("{}({})", function.name, inputs.join(","))
+}
+
+fn token_to_json(token: &Token) -> serde_json::Value {
+    match token {
+        Token::Address(a) => serde_json::Value::String(format!("0x{}", hex::encode(a.as_bytes()))),
+        Token::FixedBytes(b) | Token::Bytes(b) => {
+            serde_json::Value::String(format!("0x{}", hex::encode(b)))
+        }
+        Token::Uint(v) => serde_json::Value::String(v.to_string()),
+        Token::Int(v) => {
+            // Two's complement
+            if v.bit(255) {
+                let magnitude = (!*v).overflowing_add(1u8.into()).0;
+                serde_json::Value::String(format!("-{}", magnitude))
+            } else {
+                serde_json::Value::String(v.to_string())
+            }
+        }
+        Token::Bool(b) => serde_json::Value::Bool(*b),
+        Token::String(s) => serde_json::Value::String(s.clone()),
+        Token::FixedArray(items) | Token::Array(items) | Token::Tuple(items) => {
+            serde_json::Value::Array(items.iter().map(token_to_json).collect())
+        }
+    }
+}
+
+fn mutability_str(mutability: StateMutability) -> &'static str {
+    match mutability {
+        StateMutability::Pure => "pure",
+        StateMutability::View => "view",
+        StateMutability::NonPayable => "nonpayable",
+        StateMutability::Payable => "payable",
+    }
+}
+
+/// Parse JSON, accepting text whose quotes are still escaped (as produced when a
+/// JSON string value is flattened into tool parameters)
+fn parse_json(text: &str) -> Result<serde_json::Value, String> {
+    serde_json::from_str(text).or_else(|e| {
+        serde_json::from_str::<String>(&format!("\"{}\"", text))
+            .ok()
+            .and_then(|unescaped| serde_json::from_str(&unescaped).ok())
+            .ok_or_else(|| format!("invalid JSON: {}", e))
+    })
+}
+
+fn normalize_address(address: &str) -> String {
+    format!("0x{}", address.trim().trim_start_matches("0x").to_lowercase())
+}
+
+fn short_address(address: &str) -> &str {
+    if address.len() > 10 {
+        &address[..10]
+    } else {
+        address
+    }
+}
+
+fn contract_param(params: &IntentParams) -> Option<String> {
+    params
+        .contract_address
+        .clone()
+        .or_else(|| params.address.clone())
+        .or_else(|| params.extra.get("contract").cloned())
+}
+
+fn function_param(params: &IntentParams) -> Option<String> {
+    params
+        .function_name
+        .clone()
+        .or_else(|| params.extra.get("function").cloned())
+}
+
+/// Function arguments from `function_args`, or from an `args` JSON array
+/// (falling back to a comma-separated list)
+fn args_param(params: &IntentParams) -> Vec<String> {
+    if !params.function_args.is_empty() {
+        return params.function_args.clone();
+    }
+    let Some(raw) = params.extra.get("args") else {
+        return Vec::new();
+    };
+    match parse_json(raw) {
+        Ok(serde_json::Value::Array(items)) => items.iter().map(arg_to_string).collect(),
+        _ if raw.trim().is_empty() => Vec::new(),
+        _ => raw.split(',').map(|s| s.trim().to_string()).collect(),
+    }
+}
+
+/// Render a JSON argument in the format the ABI tokenizer expects
+pub fn arg_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(items) => {
+            format!("[{}]", items.iter().map(arg_to_string).collect::<Vec<_>>().join(","))
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Resolve `function` against the ABI loaded for `contract` and encode the call
+async fn encode_with_registry(
+    registry: &AbiRegistry,
+    contract: &str,
+    function: &str,
+    args: &[String],
+) -> Result<(Function, Vec<u8>), String> {
+    let registry = registry.read().await;
+    let abi = registry.get(&normalize_address(contract)).ok_or_else(|| {
+        format!(
+            "No ABI loaded for {}. Use load_contract_abi first.",
+            contract
+        )
+    })?;
+    let function = resolve_function(abi, function, args.len())?;
+    let calldata = encode_call(function, args)?;
+    Ok((function.clone(), calldata))
+}
+
+/// Run `eth_call` and decode the return data, returning (raw hex, decoded)
+async fn simulate(
+    node_manager: &NodeManager,
+    contract: &str,
+    function: &Function,
+    calldata_hex: &str,
+) -> Result<(String, serde_json::Value), String> {
+    let raw = node_manager.eth_call(contract, calldata_hex).await?;
+    let bytes = hex::decode(raw.trim_start_matches("0x")).map_err(|e| e.to_string())?;
+    let decoded = decode_output(function, &bytes)?;
+    Ok((raw, decoded))
+}
+
+async fn node_not_running(node_manager: &NodeManager) -> Option<String> {
+    match node_manager.get_status().await {
+        Ok(status) if !status.running => Some("Node is not running. Start the node first.".to_string()),
+        Err(e) => Some(format!("Failed to check node status: {}", e)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ERC20_ABI: &str = r#"[
+        {"type":"function","name":"balanceOf","stateMutability":"view",
+         "inputs":[{"name":"owner","type":"address"}],
+         "outputs":[{"name":"","type":"uint256"}]},
+        {"type":"function","name":"transfer","stateMutability":"nonpayable",
+         "inputs":[{"name":"to","type":"address"},{"name":"amount","type":"uint256"}],
+         "outputs":[{"name":"","type":"bool"}]},
+        {"type":"function","name":"transfer","stateMutability":"nonpayable",
+         "inputs":[{"name":"to","type":"address"}],
+         "outputs":[]},
+        {"type":"function","name":"delta","stateMutability":"pure",
+         "inputs":[],
+         "outputs":[{"name":"","type":"int256"},{"name":"","type":"bytes"}]}
+    ]"#;
+
+    #[test]
+    fn test_parse_abi_raw_array() {
+        let contract = parse_abi(ERC20_ABI).unwrap();
+        assert_eq!(contract.functions().count(), 4);
+    }
+
+    #[test]
+    fn test_parse_abi_forge_artifact() {
+        let artifact = format!(r#"{{"abi": {}, "bytecode": {{"object": "0x00"}}}}"#, ERC20_ABI);
+        let contract = parse_abi(&artifact).unwrap();
+        assert!(contract.function("balanceOf").is_ok());
+    }
+
+    #[test]
+    fn test_parse_abi_etherscan_result() {
+        let response = serde_json::json!({
+            "status": "1",
+            "message": "OK",
+            "result": ERC20_ABI,
+        });
+        let contract = parse_abi(&response.to_string()).unwrap();
+        assert!(contract.function("transfer").is_ok());
+    }
+
+    #[test]
+    fn test_parse_abi_escaped_json() {
+        // A JSON string value flattened into tool params the way the ReAct parser does
+        let escaped = serde_json::Value::String(ERC20_ABI.to_string()).to_string();
+        assert!(parse_abi(escaped.trim_matches('"')).is_ok());
+    }
+
+    #[test]
+    fn test_parse_abi_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Token.json");
+        std::fs::write(&path, format!(r#"{{"abi": {}}}"#, ERC20_ABI)).unwrap();
+        assert!(parse_abi(path.to_str().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_resolve_function_overloads() {
+        let contract = parse_abi(ERC20_ABI).unwrap();
+        let two = resolve_function(&contract, "transfer", 2).unwrap();
+        assert_eq!(input_signature(two), "transfer(address,uint256)");
+        let one = resolve_function(&contract, "transfer(address)", 5).unwrap();
+        assert_eq!(input_signature(one), "transfer(address)");
+        assert!(resolve_function(&contract, "transfer", 3).is_err());
+        assert!(resolve_function(&contract, "mint", 0).is_err());
+    }
+
+    #[test]
+    fn test_encode_transfer() {
+        let contract = parse_abi(ERC20_ABI).unwrap();
+        let function = resolve_function(&contract, "transfer", 2).unwrap();
+        let args = vec![
+            "0x5B38Da6a701c568545dCfcB03FcB875f56beddC4".to_string(),
+            "1000".to_string(),
+        ];
+        let calldata = encode_call(function, &args).unwrap();
+
+        // transfer(address,uint256) = 0xa9059cbb
+        assert_eq!(&calldata[..4], &[0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(calldata.len(), 4 + 64);
+        assert_eq!(
+            hex::encode(&calldata[4..36]),
+            "0000000000000000000000005b38da6a701c568545dcfcb03fcb875f56beddc4"
+        );
+        assert_eq!(calldata[67], 0xe8); // 1000 = 0x03e8
+        assert_eq!(calldata[66], 0x03);
+    }
+
+    #[test]
+    fn test_encode_rejects_bad_args() {
+        let contract = parse_abi(ERC20_ABI).unwrap();
+        let function = resolve_function(&contract, "transfer", 2).unwrap();
+        assert!(encode_call(function, &["0x1234".to_string(), "1".to_string()]).is_err());
+        assert!(encode_call(function, &["1".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_decode_output() {
+        let contract = parse_abi(ERC20_ABI).unwrap();
+        let function = resolve_function(&contract, "delta", 0).unwrap();
+        let encoded = ethabi::encode(&[
+            Token::Int((!ethabi::Uint::from(41u8)).overflowing_add(ethabi::Uint::one()).0),
+            Token::Bytes(vec![0xde, 0xad]),
+        ]);
+        let decoded = decode_output(function, &encoded).unwrap();
+        assert_eq!(decoded, serde_json::json!(["-41", "0xdead"]));
+    }
+
+    #[test]
+    fn test_encode_constructor() {
+        let contract = parse_abi(
+            r#"[{"type":"constructor","stateMutability":"nonpayable",
+                 "inputs":[{"name":"owner","type":"address"},{"name":"supply","type":"uint256"}]}]"#,
+        )
+        .unwrap();
+        let args = ["0x000000000000000000000000000000000000dead".to_string(), "7".to_string()];
+        let data = encode_constructor(&contract, vec![0x60, 0x80], &args).unwrap();
+        assert_eq!(data.len(), 2 + 64);
+        assert_eq!(&data[..2], &[0x60, 0x80]);
+        assert_eq!(&data[32..34], &[0xde, 0xad]);
+        assert_eq!(data[65], 7);
+        assert!(encode_constructor(&contract, vec![0x60], &args[..1]).is_err());
+
+        // No constructor in the ABI: the bytecode is deployed as is
+        let contract = parse_abi(ERC20_ABI).unwrap();
+        assert_eq!(encode_constructor(&contract, vec![0x60], &[]).unwrap(), vec![0x60]);
+        assert!(encode_constructor(&contract, vec![0x60], &args).is_err());
+    }
+
+    #[test]
+    fn test_args_param_formats() {
+        let mut params = IntentParams::default();
+        params
+            .extra
+            .insert("args".to_string(), r#"["0xabc",5,["1","2"]]"#.to_string());
+        assert_eq!(args_param(&params), vec!["0xabc", "5", "[1,2]"]);
+
+        params.extra.insert("args".to_string(), "0xabc, 5".to_string());
+        assert_eq!(args_param(&params), vec!["0xabc", "5"]);
+    }
+}
@@ -9,6 +9,7 @@ use std::sync::Arc;
 use super::super::dispatcher::{DispatchError, ToolHandler, ToolOutput};
 use super::super::intent::IntentParams;
 use crate::node::NodeManager;
+use citrate_execution::units::{format_units_truncated, Unit};
 
 /// Node status tool - queries real node status
 pub struct NodeStatusTool {
@@ -333,13 +334,13 @@ impl ToolHandler for AccountInfoTool {
                 Ok(balance_str) => {
                     // Parse the balance string (it's in wei)
                     let balance_wei: u128 = balance_str.parse().unwrap_or(0);
-                    let balance_ctr = balance_wei as f64 / 1e18;
+                    let balance_ctr = format_units_truncated(balance_wei, Unit::Latt, 6);
 
                     Ok(ToolOutput {
                         tool: "account_info".to_string(),
                         success: true,
                         message: format!(
-                            "Account {}: Balance {:.6} SALT",
+                            "Account {}: Balance {} SALT",
                             if addr.len() > 10 { &addr[..10] } else { &addr },
                             balance_ctr,
                         ),
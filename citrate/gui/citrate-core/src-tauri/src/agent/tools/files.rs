@@ -0,0 +1,478 @@
+//! File tools - read, list and write files in user-granted directories
+//!
+//! Every call is checked against the [`FilePermissionManager`] and audited.
+//! Writes are proposed to the user unless the directory was granted with
+//! auto-approval.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use super::super::dispatcher::{DispatchError, ToolHandler, ToolOutput};
+use super::super::file_access::{AuditOutcome, FileOperation, FilePermissionManager};
+use super::super::intent::IntentParams;
+
+/// Largest file returned by `read_file`; longer files are truncated
+const MAX_READ_BYTES: usize = 256 * 1024;
+
+/// Largest content `write_file` accepts
+const MAX_WRITE_BYTES: usize = 1024 * 1024;
+
+/// Most entries returned by `list_directory`
+const MAX_LIST_ENTRIES: usize = 500;
+
+/// The `path` parameter, falling back to the prompt
+fn path_param(params: &IntentParams) -> Option<String> {
+    params
+        .extra
+        .get("path")
+        .cloned()
+        .or_else(|| params.prompt.clone())
+        .filter(|p| !p.trim().is_empty())
+}
+
+fn failure(tool: &str, message: String) -> ToolOutput {
+    ToolOutput {
+        tool: tool.to_string(),
+        success: false,
+        message,
+        data: None,
+    }
+}
+
+/// Read a text file
+pub struct ReadFileTool {
+    permissions: Arc<FilePermissionManager>,
+}
+
+impl ReadFileTool {
+    pub fn new(permissions: Arc<FilePermissionManager>) -> Self {
+        Self { permissions }
+    }
+}
+
+impl ToolHandler for ReadFileTool {
+    fn name(&self) -> &str {
+        "read_file"
+    }
+
+    fn description(&self) -> &str {
+        "Read a text file in a directory the user has granted. Params: path"
+    }
+
+    fn execute(
+        &self,
+        params: &IntentParams,
+    ) -> Pin<Box<dyn Future<Output = Result<ToolOutput, DispatchError>> + Send + '_>> {
+        let path = path_param(params);
+        Box::pin(async move {
+            let path =
+                path.ok_or_else(|| DispatchError::InvalidParams("path required".to_string()))?;
+            let checked = match self.permissions.check(&path, FileOperation::Read) {
+                Ok(checked) => checked,
+                Err(e) => return Ok(failure("read_file", e.to_string())),
+            };
+
+            let bytes = match tokio::fs::read(&checked.path).await {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    self.permissions.audit(
+                        FileOperation::Read,
+                        &checked.path,
+                        AuditOutcome::Failed,
+                        Some(e.to_string()),
+                    );
+                    return Ok(failure(
+                        "read_file",
+                        format!("Failed to read {}: {}", path, e),
+                    ));
+                }
+            };
+            let truncated = bytes.len() > MAX_READ_BYTES;
+            let shown = &bytes[..bytes.len().min(MAX_READ_BYTES)];
+            let content = match std::str::from_utf8(shown) {
+                Ok(text) => text.to_string(),
+                // A truncation can split a multi-byte character
+                Err(e) if truncated && e.error_len().is_none() => {
+                    String::from_utf8_lossy(&shown[..e.valid_up_to()]).into_owned()
+                }
+                Err(_) => {
+                    self.permissions.audit(
+                        FileOperation::Read,
+                        &checked.path,
+                        AuditOutcome::Failed,
+                        Some("not a text file".to_string()),
+                    );
+                    return Ok(failure("read_file", format!("{} is not a text file", path)));
+                }
+            };
+            self.permissions.audit(
+                FileOperation::Read,
+                &checked.path,
+                AuditOutcome::Completed,
+                Some(format!("{} bytes", bytes.len())),
+            );
+
+            let message = if truncated {
+                format!(
+                    "{} (first {} of {} bytes):\n{}",
+                    checked.path.display(),
+                    MAX_READ_BYTES,
+                    bytes.len(),
+                    content
+                )
+            } else {
+                format!("{}:\n{}", checked.path.display(), content)
+            };
+            Ok(ToolOutput {
+                tool: "read_file".to_string(),
+                success: true,
+                message,
+                data: Some(serde_json::json!({
+                    "path": checked.path,
+                    "size": bytes.len(),
+                    "truncated": truncated,
+                    "content": content
+                })),
+            })
+        })
+    }
+}
+
+/// List a directory
+pub struct ListDirectoryTool {
+    permissions: Arc<FilePermissionManager>,
+}
+
+impl ListDirectoryTool {
+    pub fn new(permissions: Arc<FilePermissionManager>) -> Self {
+        Self { permissions }
+    }
+}
+
+impl ToolHandler for ListDirectoryTool {
+    fn name(&self) -> &str {
+        "list_directory"
+    }
+
+    fn description(&self) -> &str {
+        "List files in a directory the user has granted. Params: path (defaults to the first granted directory)"
+    }
+
+    fn execute(
+        &self,
+        params: &IntentParams,
+    ) -> Pin<Box<dyn Future<Output = Result<ToolOutput, DispatchError>> + Send + '_>> {
+        let path = path_param(params).unwrap_or_else(|| ".".to_string());
+        Box::pin(async move {
+            let checked = match self.permissions.check(&path, FileOperation::List) {
+                Ok(checked) => checked,
+                Err(e) => return Ok(failure("list_directory", e.to_string())),
+            };
+
+            let mut reader = match tokio::fs::read_dir(&checked.path).await {
+                Ok(reader) => reader,
+                Err(e) => {
+                    self.permissions.audit(
+                        FileOperation::List,
+                        &checked.path,
+                        AuditOutcome::Failed,
+                        Some(e.to_string()),
+                    );
+                    return Ok(failure(
+                        "list_directory",
+                        format!("Failed to list {}: {}", path, e),
+                    ));
+                }
+            };
+            let mut entries = Vec::new();
+            let mut total = 0;
+            while let Ok(Some(entry)) = reader.next_entry().await {
+                total += 1;
+                if entries.len() >= MAX_LIST_ENTRIES {
+                    continue;
+                }
+                let metadata = entry.metadata().await.ok();
+                entries.push((
+                    entry.file_name().to_string_lossy().into_owned(),
+                    metadata.as_ref().is_some_and(|m| m.is_dir()),
+                    metadata.map(|m| m.len()).unwrap_or(0),
+                ));
+            }
+            // Directories first, then by name
+            entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            self.permissions.audit(
+                FileOperation::List,
+                &checked.path,
+                AuditOutcome::Completed,
+                Some(format!("{} entries", total)),
+            );
+
+            let listing = entries
+                .iter()
+                .map(|(name, is_dir, size)| {
+                    if *is_dir {
+                        format!("{}/", name)
+                    } else {
+                        format!("{} ({} bytes)", name, size)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            Ok(ToolOutput {
+                tool: "list_directory".to_string(),
+                success: true,
+                message: format!(
+                    "{} ({} entries{}):\n{}",
+                    checked.path.display(),
+                    total,
+                    if total > entries.len() {
+                        ", truncated"
+                    } else {
+                        ""
+                    },
+                    listing
+                ),
+                data: Some(serde_json::json!({
+                    "path": checked.path,
+                    "total": total,
+                    "entries": entries
+                        .iter()
+                        .map(|(name, is_dir, size)| serde_json::json!({
+                            "name": name,
+                            "is_dir": is_dir,
+                            "size": size
+                        }))
+                        .collect::<Vec<_>>()
+                })),
+            })
+        })
+    }
+}
+
+/// Create or overwrite a text file
+pub struct WriteFileTool {
+    permissions: Arc<FilePermissionManager>,
+}
+
+impl WriteFileTool {
+    pub fn new(permissions: Arc<FilePermissionManager>) -> Self {
+        Self { permissions }
+    }
+}
+
+impl ToolHandler for WriteFileTool {
+    fn name(&self) -> &str {
+        "write_file"
+    }
+
+    fn description(&self) -> &str {
+        "Create or overwrite a text file in a directory the user has granted for writing. Params: path, content. Requires approval."
+    }
+
+    fn execute(
+        &self,
+        params: &IntentParams,
+    ) -> Pin<Box<dyn Future<Output = Result<ToolOutput, DispatchError>> + Send + '_>> {
+        let path = params.extra.get("path").cloned();
+        let content = params.extra.get("content").cloned();
+        let approved = params
+            .extra
+            .get("approved")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        Box::pin(async move {
+            let path =
+                path.ok_or_else(|| DispatchError::InvalidParams("path required".to_string()))?;
+            let content = content
+                .ok_or_else(|| DispatchError::InvalidParams("content required".to_string()))?;
+            if content.len() > MAX_WRITE_BYTES {
+                return Ok(failure(
+                    "write_file",
+                    format!("Content is larger than {} bytes", MAX_WRITE_BYTES),
+                ));
+            }
+            // Checked again on approval, in case the grant changed meanwhile
+            let checked = match self.permissions.check(&path, FileOperation::Write) {
+                Ok(checked) => checked,
+                Err(e) => return Ok(failure("write_file", e.to_string())),
+            };
+            let existing = tokio::fs::metadata(&checked.path).await.ok();
+            if existing.as_ref().is_some_and(|m| m.is_dir()) {
+                return Ok(failure("write_file", format!("{} is a directory", path)));
+            }
+
+            if !approved && !checked.auto_approve {
+                self.permissions.audit(
+                    FileOperation::Write,
+                    &checked.path,
+                    AuditOutcome::Proposed,
+                    Some(format!("{} bytes", content.len())),
+                );
+                return Ok(ToolOutput {
+                    tool: "write_file".to_string(),
+                    success: true,
+                    message: format!(
+                        "Proposed {} {} ({} bytes, {} lines). Awaiting user approval.",
+                        if existing.is_some() {
+                            "overwriting"
+                        } else {
+                            "creating"
+                        },
+                        checked.path.display(),
+                        content.len(),
+                        content.lines().count()
+                    ),
+                    data: Some(serde_json::json!({
+                        "status": "awaiting_approval",
+                        "path": checked.path,
+                        "bytes": content.len(),
+                        "overwrites": existing.is_some(),
+                        "previous_size": existing.map(|m| m.len())
+                    })),
+                });
+            }
+
+            match tokio::fs::write(&checked.path, content.as_bytes()).await {
+                Ok(()) => {
+                    self.permissions.audit(
+                        FileOperation::Write,
+                        &checked.path,
+                        AuditOutcome::Completed,
+                        Some(format!("{} bytes", content.len())),
+                    );
+                    Ok(ToolOutput {
+                        tool: "write_file".to_string(),
+                        success: true,
+                        message: format!(
+                            "Wrote {} bytes to {}",
+                            content.len(),
+                            checked.path.display()
+                        ),
+                        data: Some(serde_json::json!({
+                            "path": checked.path,
+                            "bytes": content.len(),
+                            "status": "written"
+                        })),
+                    })
+                }
+                Err(e) => {
+                    self.permissions.audit(
+                        FileOperation::Write,
+                        &checked.path,
+                        AuditOutcome::Failed,
+                        Some(e.to_string()),
+                    );
+                    Ok(failure(
+                        "write_file",
+                        format!("Failed to write {}: {}", path, e),
+                    ))
+                }
+            }
+        })
+    }
+
+    fn requires_confirmation(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::file_access::FileAccess;
+
+    fn params(pairs: &[(&str, &str)]) -> IntentParams {
+        let mut params = IntentParams::default();
+        for (k, v) in pairs {
+            params.extra.insert(k.to_string(), v.to_string());
+        }
+        params
+    }
+
+    #[tokio::test]
+    async fn test_write_requires_approval_unless_auto() {
+        let dir = tempfile::tempdir().unwrap();
+        let permissions = Arc::new(FilePermissionManager::in_memory());
+        permissions
+            .grant(dir.path(), FileAccess::ReadWrite, false)
+            .unwrap();
+        let tool = WriteFileTool::new(permissions.clone());
+
+        let write = params(&[("path", "notes.md"), ("content", "# Notes\n{}")]);
+        let output = tool.execute(&write).await.unwrap();
+        assert!(output.awaiting_approval());
+        assert!(!dir.path().join("notes.md").exists());
+
+        let mut approved = write.clone();
+        approved
+            .extra
+            .insert("approved".to_string(), "true".to_string());
+        let output = tool.execute(&approved).await.unwrap();
+        assert!(output.success && !output.awaiting_approval());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("notes.md")).unwrap(),
+            "# Notes\n{}"
+        );
+
+        permissions
+            .grant(dir.path(), FileAccess::ReadWrite, true)
+            .unwrap();
+        let output = tool
+            .execute(&params(&[("path", "auto.txt"), ("content", "x")]))
+            .await
+            .unwrap();
+        assert!(output.success && !output.awaiting_approval());
+
+        let outcomes: Vec<_> = permissions
+            .audit_log(10)
+            .iter()
+            .map(|e| e.outcome)
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                AuditOutcome::Completed,
+                AuditOutcome::Completed,
+                AuditOutcome::Proposed
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_and_list_stay_in_grant() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src").join("main.rs"), "fn main() {}").unwrap();
+        let permissions = Arc::new(FilePermissionManager::in_memory());
+        permissions
+            .grant(dir.path().join("src"), FileAccess::Read, false)
+            .unwrap();
+
+        let output = ReadFileTool::new(permissions.clone())
+            .execute(&params(&[("path", "main.rs")]))
+            .await
+            .unwrap();
+        assert!(output.success);
+        assert!(output.message.contains("fn main() {}"));
+
+        let output = ListDirectoryTool::new(permissions.clone())
+            .execute(&IntentParams::default())
+            .await
+            .unwrap();
+        assert!(output.success);
+        assert!(output.message.contains("main.rs"));
+
+        let output = ListDirectoryTool::new(permissions.clone())
+            .execute(&params(&[("path", "..")]))
+            .await
+            .unwrap();
+        assert!(!output.success);
+
+        let output = WriteFileTool::new(permissions)
+            .execute(&params(&[("path", "main.rs"), ("content", "")]))
+            .await
+            .unwrap();
+        assert!(!output.success);
+    }
+}
@@ -9,35 +9,41 @@ use tokio::sync::RwLock;
 use super::dispatcher::{ToolDispatcher, ToolHandler};
 
 // Sprint 3: Core Tools
+pub mod abi;
 pub mod blockchain;
 pub mod contracts;
 pub mod models;
 pub mod wallet;
 
 // Sprint 4: Advanced Tools
+pub mod files;
 pub mod generation;
 pub mod marketplace;
 pub mod scaffold;
 pub mod storage;
 pub mod terminal;
 
+use super::file_access::FilePermissionManager;
 use crate::dag::DAGManager;
 use crate::models::ModelManager;
 use crate::node::NodeManager;
+use crate::terminal::TerminalManager;
 use crate::wallet::WalletManager;
 
 // Re-export Sprint 3 tool handlers
+pub use abi::{LoadContractAbiTool, ProposeContractTransactionTool, SimulateContractCallTool};
 pub use blockchain::{AccountInfoTool, BlockInfoTool, DAGStatusTool, NodeStatusTool, TransactionInfoTool};
 pub use contracts::{CallContractTool, DeployContractTool, WriteContractTool};
 pub use models::{DeployModelTool, GetModelInfoTool, ListModelsTool, RunInferenceTool};
 pub use wallet::{BalanceTool, SendTransactionTool, TransactionHistoryTool};
 
 // Re-export Sprint 4 tool handlers
+pub use files::{ListDirectoryTool, ReadFileTool, WriteFileTool};
 pub use generation::{ApplyStyleTool, GenerateImageTool, ListImageModelsTool};
 pub use marketplace::{BrowseCategoryTool, GetListingTool, SearchMarketplaceTool};
 pub use scaffold::{ListTemplatesToolImpl, ScaffoldDappTool};
 pub use storage::{GetIPFSTool, PinIPFSTool, UploadIPFSTool};
-pub use terminal::{ChangeDirectoryTool, ExecuteCommandTool, GetWorkingDirectoryTool};
+pub use terminal::{ChangeDirectoryTool, ExecuteCommandTool, GetWorkingDirectoryTool, RunInTerminalTool};
 
 /// Tool registry that holds all initialized tools
 pub struct ToolRegistry {
@@ -79,6 +85,8 @@ pub fn register_all_tools(
     wallet_manager: Arc<WalletManager>,
     model_manager: Arc<ModelManager>,
     _dag_manager: Arc<RwLock<Option<Arc<DAGManager>>>>,
+    file_permissions: Arc<FilePermissionManager>,
+    terminal_manager: Arc<RwLock<TerminalManager>>,
 ) {
     // =====================================================================
     // Sprint 3: Core Tools
@@ -101,6 +109,16 @@ pub fn register_all_tools(
     dispatcher.register(CallContractTool::new(node_manager.clone()));
     dispatcher.register(WriteContractTool::new(wallet_manager.clone(), node_manager.clone()));
 
+    // ABI-aware contract tools (shared ABI registry)
+    let abi_registry = abi::new_abi_registry();
+    dispatcher.register(LoadContractAbiTool::new(abi_registry.clone()));
+    dispatcher.register(SimulateContractCallTool::new(node_manager.clone(), abi_registry.clone()));
+    dispatcher.register(ProposeContractTransactionTool::new(
+        wallet_manager.clone(),
+        node_manager.clone(),
+        abi_registry,
+    ));
+
     // Model tools
     dispatcher.register(ListModelsTool::new(model_manager.clone()));
     dispatcher.register(RunInferenceTool::new(model_manager.clone()));
@@ -120,7 +138,13 @@ pub fn register_all_tools(
     let working_dir = Arc::new(RwLock::new(std::env::current_dir().unwrap_or_default()));
     dispatcher.register(ExecuteCommandTool::new());
     dispatcher.register(ChangeDirectoryTool::new(working_dir.clone()));
-    dispatcher.register(GetWorkingDirectoryTool::new(working_dir));
+    dispatcher.register(GetWorkingDirectoryTool::new(working_dir.clone()));
+    dispatcher.register(RunInTerminalTool::new(terminal_manager, working_dir));
+
+    // File tools (confined to user-granted directories)
+    dispatcher.register(ReadFileTool::new(file_permissions.clone()));
+    dispatcher.register(ListDirectoryTool::new(file_permissions.clone()));
+    dispatcher.register(WriteFileTool::new(file_permissions));
 
     // Storage/IPFS tools
     dispatcher.register(UploadIPFSTool::new());
@@ -15,6 +15,7 @@ use tokio::time::timeout;
 
 use super::super::dispatcher::{DispatchError, ToolHandler, ToolOutput};
 use super::super::intent::IntentParams;
+use crate::terminal::{CommandCapture, TerminalConfig, TerminalManager};
 
 /// Allowed commands that can be executed
 const ALLOWED_COMMANDS: &[&str] = &[
@@ -84,6 +85,57 @@ const BLOCKED_PATTERNS: &[&str] = &[
     "exec",
 ];
 
+/// Default and longest run time for `run_in_terminal`
+const TERMINAL_DEFAULT_TIMEOUT_SECS: u64 = 120;
+const TERMINAL_MAX_TIMEOUT_SECS: u64 = 600;
+
+/// Stdout lines kept in the conversation: the first and the last
+const STDOUT_HEAD_LINES: usize = 40;
+const STDOUT_TAIL_LINES: usize = 160;
+
+/// Stderr lines kept in the conversation: the first and the last
+const STDERR_HEAD_LINES: usize = 20;
+const STDERR_TAIL_LINES: usize = 60;
+
+/// Longest line kept in the conversation
+const MAX_LINE_CHARS: usize = 400;
+
+/// Check a command against the blocked patterns
+fn is_command_blocked(cmd: &str) -> bool {
+    let cmd_lower = cmd.to_lowercase();
+    BLOCKED_PATTERNS
+        .iter()
+        .any(|pattern| cmd_lower.contains(pattern))
+}
+
+/// Shorten command output for the conversation: overlong lines are cut, and
+/// when there are too many lines only the first `head` and last `tail` are
+/// kept. Returns the text and whether anything was dropped.
+fn truncate_output(text: &str, head: usize, tail: usize) -> (String, bool) {
+    let mut truncated = false;
+    let lines: Vec<String> = text
+        .lines()
+        .map(|line| {
+            if line.chars().count() > MAX_LINE_CHARS {
+                truncated = true;
+                let cut: String = line.chars().take(MAX_LINE_CHARS).collect();
+                format!("{}...", cut)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+
+    if lines.len() <= head + tail {
+        return (lines.join("\n"), truncated);
+    }
+    let omitted = lines.len() - head - tail;
+    let mut kept = lines[..head].to_vec();
+    kept.push(format!("... ({} lines omitted) ...", omitted));
+    kept.extend_from_slice(&lines[lines.len() - tail..]);
+    (kept.join("\n"), true)
+}
+
 /// Execute terminal command tool
 pub struct ExecuteCommandTool {
     working_dir: Arc<RwLock<PathBuf>>,
@@ -100,13 +152,9 @@ impl ExecuteCommandTool {
 
     /// Check if command is allowed
     fn is_command_allowed(cmd: &str) -> bool {
-        let cmd_lower = cmd.to_lowercase();
-
         // Check for blocked patterns
-        for pattern in BLOCKED_PATTERNS {
-            if cmd_lower.contains(pattern) {
-                return false;
-            }
+        if is_command_blocked(cmd) {
+            return false;
         }
 
         // Extract the base command
@@ -303,6 +351,196 @@ impl ToolHandler for ExecuteCommandTool {
     }
 }
 
+/// Run a shell command in the agent's own terminal session, after the user
+/// approves it. The session shows up alongside the user's terminals, and
+/// the command's output is captured back into the conversation.
+pub struct RunInTerminalTool {
+    terminal_manager: Arc<RwLock<TerminalManager>>,
+    working_dir: Arc<RwLock<PathBuf>>,
+    /// The agent's session, created on first use
+    session_id: tokio::sync::Mutex<Option<String>>,
+}
+
+impl RunInTerminalTool {
+    pub fn new(
+        terminal_manager: Arc<RwLock<TerminalManager>>,
+        working_dir: Arc<RwLock<PathBuf>>,
+    ) -> Self {
+        Self {
+            terminal_manager,
+            working_dir,
+            session_id: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// The agent's session, starting a new one if it is missing or has exited
+    async fn session(&self) -> anyhow::Result<String> {
+        let mut session_id = self.session_id.lock().await;
+        let manager = self.terminal_manager.read().await;
+        if let Some(id) = session_id.as_ref() {
+            if manager.get_session(id).await.is_some_and(|s| s.is_active) {
+                return Ok(id.clone());
+            }
+        }
+        let config = TerminalConfig {
+            // Output capture relies on POSIX shell syntax
+            shell: Some("/bin/sh".to_string()),
+            cwd: Some(self.working_dir.read().await.to_string_lossy().to_string()),
+            ..TerminalConfig::default()
+        };
+        let info = manager.create_session(config).await?;
+        *session_id = Some(info.session_id.clone());
+        Ok(info.session_id)
+    }
+
+    fn format_capture(capture: &CommandCapture) -> (String, bool) {
+        let (stdout, stdout_truncated) =
+            truncate_output(&capture.stdout, STDOUT_HEAD_LINES, STDOUT_TAIL_LINES);
+        let (stderr, stderr_truncated) =
+            truncate_output(&capture.stderr, STDERR_HEAD_LINES, STDERR_TAIL_LINES);
+
+        let mut message = match capture.exit_code {
+            Some(code) => format!(
+                "`{}` exited with code {} after {:.1}s",
+                capture.command,
+                code,
+                capture.duration_ms as f64 / 1000.0
+            ),
+            None => format!(
+                "`{}` timed out after {:.1}s and was interrupted",
+                capture.command,
+                capture.duration_ms as f64 / 1000.0
+            ),
+        };
+        if !stdout.trim().is_empty() {
+            message.push_str(&format!("\nstdout:\n{}", stdout));
+        }
+        if !stderr.trim().is_empty() {
+            message.push_str(&format!("\nstderr:\n{}", stderr));
+        }
+        if stdout.trim().is_empty() && stderr.trim().is_empty() {
+            message.push_str(" (no output)");
+        }
+        (
+            message,
+            stdout_truncated || stderr_truncated || capture.omitted_bytes > 0,
+        )
+    }
+}
+
+impl ToolHandler for RunInTerminalTool {
+    fn name(&self) -> &str {
+        "run_in_terminal"
+    }
+
+    fn description(&self) -> &str {
+        "Run a shell command in the agent's terminal session and capture its output. Params: command, timeout_secs (optional). Requires approval."
+    }
+
+    fn execute(
+        &self,
+        params: &IntentParams,
+    ) -> Pin<Box<dyn Future<Output = Result<ToolOutput, DispatchError>> + Send + '_>> {
+        let command = params
+            .extra
+            .get("command")
+            .cloned()
+            .or_else(|| params.prompt.clone());
+        let timeout_secs = params
+            .extra
+            .get("timeout_secs")
+            .and_then(|t| t.parse::<u64>().ok())
+            .unwrap_or(TERMINAL_DEFAULT_TIMEOUT_SECS)
+            .clamp(1, TERMINAL_MAX_TIMEOUT_SECS);
+        let approved = params
+            .extra
+            .get("approved")
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        Box::pin(async move {
+            let command = command
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+                .ok_or_else(|| DispatchError::InvalidParams("command required".to_string()))?;
+
+            if is_command_blocked(&command) {
+                return Ok(ToolOutput {
+                    tool: "run_in_terminal".to_string(),
+                    success: false,
+                    message: format!("Command not allowed: '{}'", command),
+                    data: Some(serde_json::json!({ "blocked": true })),
+                });
+            }
+
+            if !approved {
+                return Ok(ToolOutput {
+                    tool: "run_in_terminal".to_string(),
+                    success: true,
+                    message: format!(
+                        "Proposed running `{}` in {}. Awaiting user approval.",
+                        command,
+                        self.working_dir.read().await.display()
+                    ),
+                    data: Some(serde_json::json!({
+                        "status": "awaiting_approval",
+                        "command": command,
+                        "timeout_secs": timeout_secs
+                    })),
+                });
+            }
+
+            let session_id = match self.session().await {
+                Ok(id) => id,
+                Err(e) => {
+                    return Ok(ToolOutput {
+                        tool: "run_in_terminal".to_string(),
+                        success: false,
+                        message: format!("Failed to start terminal session: {}", e),
+                        data: None,
+                    });
+                }
+            };
+
+            let capture = self
+                .terminal_manager
+                .read()
+                .await
+                .run_command(&session_id, &command, Duration::from_secs(timeout_secs))
+                .await;
+            match capture {
+                Ok(capture) => {
+                    let (message, truncated) = Self::format_capture(&capture);
+                    Ok(ToolOutput {
+                        tool: "run_in_terminal".to_string(),
+                        success: capture.exit_code == Some(0),
+                        message,
+                        data: Some(serde_json::json!({
+                            "command": capture.command,
+                            "session_id": capture.session_id,
+                            "exit_code": capture.exit_code,
+                            "stdout": capture.stdout,
+                            "stderr": capture.stderr,
+                            "timed_out": capture.timed_out,
+                            "truncated": truncated,
+                            "duration_ms": capture.duration_ms
+                        })),
+                    })
+                }
+                Err(e) => Ok(ToolOutput {
+                    tool: "run_in_terminal".to_string(),
+                    success: false,
+                    message: format!("Failed to run `{}`: {}", command, e),
+                    data: Some(serde_json::json!({ "session_id": session_id })),
+                }),
+            }
+        })
+    }
+
+    fn requires_confirmation(&self) -> bool {
+        true
+    }
+}
+
 /// Change working directory tool
 pub struct ChangeDirectoryTool {
     working_dir: Arc<RwLock<PathBuf>>,
@@ -456,4 +694,36 @@ mod tests {
         assert!(!ExecuteCommandTool::is_command_allowed("virus.exe"));
         assert!(!ExecuteCommandTool::is_command_allowed("random_script"));
     }
+
+    #[test]
+    fn test_truncate_output() {
+        let short = "a\nb\nc";
+        assert_eq!(truncate_output(short, 2, 2), (short.to_string(), false));
+
+        let long: Vec<String> = (0..10).map(|i| i.to_string()).collect();
+        let (text, truncated) = truncate_output(&long.join("\n"), 2, 3);
+        assert!(truncated);
+        assert_eq!(text, "0\n1\n... (5 lines omitted) ...\n7\n8\n9");
+
+        let (text, truncated) = truncate_output(&"x".repeat(MAX_LINE_CHARS + 1), 2, 2);
+        assert!(truncated);
+        assert_eq!(text.len(), MAX_LINE_CHARS + 3);
+    }
+
+    #[tokio::test]
+    async fn test_run_in_terminal_requires_approval() {
+        let tool = RunInTerminalTool::new(
+            Arc::new(RwLock::new(TerminalManager::new())),
+            Arc::new(RwLock::new(PathBuf::from("/tmp"))),
+        );
+        let mut params = IntentParams::default();
+        params.extra.insert("command".to_string(), "cargo test".to_string());
+        let output = tool.execute(&params).await.unwrap();
+        assert!(output.awaiting_approval());
+
+        params.extra.insert("command".to_string(), "sudo cargo test".to_string());
+        params.extra.insert("approved".to_string(), "true".to_string());
+        let output = tool.execute(&params).await.unwrap();
+        assert!(!output.success);
+    }
 }
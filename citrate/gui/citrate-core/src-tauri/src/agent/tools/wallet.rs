@@ -11,6 +11,9 @@ use super::super::dispatcher::{DispatchError, ToolHandler, ToolOutput};
 use super::super::intent::IntentParams;
 use crate::node::NodeManager;
 use crate::wallet::WalletManager;
+use citrate_execution::units::{
+    self, format_units, format_units_truncated, parse_units, AmountError, Unit,
+};
 
 /// Balance query tool - queries real wallet balance
 pub struct BalanceTool {
@@ -64,13 +67,13 @@ impl ToolHandler for BalanceTool {
             match node_manager.get_observed_balance(&target_address, 100).await {
                 Ok(balance_str) => {
                     let balance_wei: u128 = balance_str.parse().unwrap_or(0);
-                    let balance_salt = balance_wei as f64 / 1e18;
+                    let balance_salt = format_units_truncated(balance_wei, Unit::Latt, 6);
 
                     Ok(ToolOutput {
                         tool: "query_balance".to_string(),
                         success: true,
                         message: format!(
-                            "Balance for {}: {:.6} SALT",
+                            "Balance for {}: {} SALT",
                             if target_address.len() > 10 { &target_address[..10] } else { &target_address },
                             balance_salt
                         ),
@@ -84,12 +87,12 @@ impl ToolHandler for BalanceTool {
                 Err(_) => {
                     // Fall back to wallet manager's cached balance
                     if let Some(account) = wallet_manager.get_account(&target_address).await {
-                        let balance_salt = account.balance as f64 / 1e18;
+                        let balance_salt = format_units_truncated(account.balance, Unit::Latt, 6);
                         return Ok(ToolOutput {
                             tool: "query_balance".to_string(),
                             success: true,
                             message: format!(
-                                "Balance for {}: {:.6} SALT (cached, node may not be running)",
+                                "Balance for {}: {} SALT (cached, node may not be running)",
                                 if target_address.len() > 10 { &target_address[..10] } else { &target_address },
                                 balance_salt
                             ),
@@ -160,6 +163,13 @@ impl ToolHandler for SendTransactionTool {
             let to_addr = to_address.ok_or_else(|| {
                 DispatchError::InvalidParams("Recipient address required".to_string())
             })?;
+            // Contacts and registered names resolve to their address
+            let executor = node_manager.get_executor().await;
+            let to_addr = wallet_manager
+                .address_book()
+                .resolve(&to_addr, executor.as_deref().map(|e| e.state_db().as_ref()))
+                .map_err(|e| DispatchError::InvalidParams(e.to_string()))?
+                .address;
             let amount_str = amount.ok_or_else(|| {
                 DispatchError::InvalidParams("Amount required".to_string())
             })?;
@@ -187,9 +197,9 @@ impl ToolHandler for SendTransactionTool {
                     tool: "send_transaction".to_string(),
                     success: true,
                     message: format!(
-                        "Transaction prepared: Send {} wei ({:.6} SALT) from {} to {}. Awaiting confirmation.",
+                        "Transaction prepared: Send {} wei ({} SALT) from {} to {}. Awaiting confirmation.",
                         amount_wei,
-                        amount_wei as f64 / 1e18,
+                        format_units(amount_wei, Unit::Latt),
                         if from_addr.len() > 10 { &from_addr[..10] } else { &from_addr },
                         if to_addr.len() > 10 { &to_addr[..10] } else { &to_addr }
                     ),
@@ -198,7 +208,7 @@ impl ToolHandler for SendTransactionTool {
                         "from": from_addr,
                         "to": to_addr,
                         "value_wei": amount_wei.to_string(),
-                        "value_salt": amount_wei as f64 / 1e18,
+                        "value_salt": format_units(amount_wei, Unit::Latt),
                         "requires_password": true
                     })),
                 });
@@ -239,9 +249,9 @@ impl ToolHandler for SendTransactionTool {
                         tool: "send_transaction".to_string(),
                         success: true,
                         message: format!(
-                            "Transaction sent! Hash: {}. Sent {:.6} SALT from {} to {}",
+                            "Transaction sent! Hash: {}. Sent {} SALT from {} to {}",
                             &tx_hash,
-                            amount_wei as f64 / 1e18,
+                            format_units(amount_wei, Unit::Latt),
                             if from_addr.len() > 10 { &from_addr[..10] } else { &from_addr },
                             if to_addr.len() > 10 { &to_addr[..10] } else { &to_addr }
                         ),
@@ -250,7 +260,7 @@ impl ToolHandler for SendTransactionTool {
                             "from": from_addr,
                             "to": to_addr,
                             "value_wei": amount_wei.to_string(),
-                            "value_salt": amount_wei as f64 / 1e18,
+                            "value_salt": format_units(amount_wei, Unit::Latt),
                             "status": "pending"
                         })),
                     })
@@ -370,56 +380,21 @@ impl ToolHandler for TransactionHistoryTool {
 /// Parse amount string to wei
 /// Supports: "1.5 SALT", "1.5", "1500000000000000000 wei", "1500000000000000000"
 fn parse_amount(amount_str: &str) -> Result<u128, DispatchError> {
-    let lower = amount_str.to_lowercase().trim().to_string();
-
-    // Check for unit suffix
-    if lower.ends_with("salt") || lower.ends_with("citrate") {
-        // Parse as SALT (decimal)
-        let num_str = lower
-            .trim_end_matches("salt")
-            .trim_end_matches("citrate")
-            .trim();
-        let num: f64 = num_str.parse().map_err(|_| {
-            DispatchError::InvalidParams(format!("Invalid amount: {}", amount_str))
-        })?;
-        Ok((num * 1e18) as u128)
-    } else if lower.ends_with("gwei") {
-        // Parse as gwei (must check before "wei" since "gwei" ends with "wei")
-        let num_str = lower.trim_end_matches("gwei").trim();
-        let num: f64 = num_str.parse().map_err(|_| {
-            DispatchError::InvalidParams(format!("Invalid amount: {}", amount_str))
-        })?;
-        Ok((num * 1e9) as u128)
-    } else if lower.ends_with("wei") {
-        // Parse as wei (integer)
-        let num_str = lower.trim_end_matches("wei").trim();
-        num_str.parse().map_err(|_| {
-            DispatchError::InvalidParams(format!("Invalid amount: {}", amount_str))
-        })
-    } else if lower.ends_with("ether") || lower.ends_with("eth") {
-        // Parse as ether (for compatibility)
-        let num_str = lower
-            .trim_end_matches("ether")
-            .trim_end_matches("eth")
-            .trim();
-        let num: f64 = num_str.parse().map_err(|_| {
-            DispatchError::InvalidParams(format!("Invalid amount: {}", amount_str))
-        })?;
-        Ok((num * 1e18) as u128)
-    } else {
-        // Try parsing as decimal SALT first, then as wei
-        if lower.contains('.') {
-            let num: f64 = lower.parse().map_err(|_| {
-                DispatchError::InvalidParams(format!("Invalid amount: {}", amount_str))
-            })?;
-            Ok((num * 1e18) as u128)
-        } else {
-            // Large integer - assume wei
-            lower.parse().map_err(|_| {
-                DispatchError::InvalidParams(format!("Invalid amount: {}", amount_str))
-            })
+    let lower = amount_str.trim().to_lowercase();
+    let invalid = |e: AmountError| {
+        DispatchError::InvalidParams(format!("Invalid amount '{}': {}", amount_str, e))
+    };
+
+    // Other names for the native token
+    for alias in ["citrate", "ether", "eth"] {
+        if let Some(num_str) = lower.strip_suffix(alias) {
+            return parse_units(num_str, Unit::Latt).map_err(invalid);
         }
     }
+
+    // Without a unit, decimals are SALT and integers are wei
+    let default_unit = if lower.contains('.') { Unit::Latt } else { Unit::Wei };
+    units::parse_amount(&lower, default_unit).map_err(invalid)
 }
 
 #[cfg(test)]
@@ -435,5 +410,8 @@ mod tests {
         assert_eq!(parse_amount("1 gwei").unwrap(), 1_000_000_000);
         assert_eq!(parse_amount("1.5").unwrap(), 1_500_000_000_000_000_000); // Decimal assumes SALT
         assert_eq!(parse_amount("1000000000000000000").unwrap(), 1_000_000_000_000_000_000); // Integer assumes wei
+        assert_eq!(parse_amount("0.1 SALT").unwrap(), 100_000_000_000_000_000); // No float rounding
+        assert!(parse_amount("1e18").is_err());
+        assert!(parse_amount("1,5 SALT").is_err());
     }
 }
@@ -1,7 +1,6 @@
 use anyhow::Result;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tokio::time::{interval, Duration};
+use tokio::sync::{Mutex, RwLock};
 use tracing::{error, info, warn};
 
 use crate::wallet::WalletManager;
@@ -12,11 +11,14 @@ use citrate_consensus::{
     GhostDag,
 };
 use citrate_execution::types::{Address, TransactionReceipt};
-use citrate_execution::Executor;
+use citrate_execution::{Executor, InternalTransaction};
 use citrate_network::{NetworkMessage, PeerManager};
-use citrate_sequencer::Mempool;
+use citrate_sequencer::{Mempool, MiningMode, MiningSchedule};
 use citrate_storage::{state_manager::StateManager as AIStateManager, StorageManager};
 
+/// Seconds between blocks until changed through the devnet controls
+const DEFAULT_BLOCK_INTERVAL: u64 = 2;
+
 pub struct BlockProducer {
     ghostdag: Arc<GhostDag>,
     mempool: Arc<Mempool>,
@@ -27,6 +29,9 @@ pub struct BlockProducer {
     running: Arc<RwLock<bool>>,
     wallet_manager: Option<Arc<WalletManager>>,
     peer_manager: Option<Arc<PeerManager>>,
+    schedule: Arc<MiningSchedule>,
+    /// Held while a block is produced, so on-demand mining never races the loop
+    producing: Arc<Mutex<()>>,
 }
 
 impl BlockProducer {
@@ -52,6 +57,8 @@ impl BlockProducer {
             running: Arc::new(RwLock::new(false)),
             wallet_manager,
             peer_manager,
+            schedule: Arc::new(MiningSchedule::new(DEFAULT_BLOCK_INTERVAL)),
+            producing: Arc::new(Mutex::new(())),
         }
     }
 
@@ -89,13 +96,38 @@ impl BlockProducer {
         info!("Block producer stopped");
     }
 
+    pub fn mining_mode(&self) -> MiningMode {
+        self.schedule.mode()
+    }
+
+    /// Seconds between interval-mined blocks; 0 stops interval mining
+    pub fn set_block_interval(&self, seconds: u64) {
+        info!("Block interval set to {}s", seconds);
+        self.schedule.set_block_interval(seconds);
+    }
+
+    /// Mine a block as soon as a transaction enters the mempool
+    pub fn set_automine(&self, enabled: bool) {
+        info!("Automine {}", if enabled { "enabled" } else { "disabled" });
+        self.schedule.set_automine(enabled);
+    }
+
+    /// Mine `count` blocks now, one after another
+    pub async fn mine_blocks(&self, count: u64) -> Result<Vec<Block>> {
+        let _producing = self.producing.lock().await;
+        let mut blocks = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            blocks.push(self.produce_block().await?);
+        }
+        Ok(blocks)
+    }
+
     /// Main block production loop
     async fn production_loop(&self) {
-        let mut interval = interval(Duration::from_secs(2)); // 2 second block time
-
         while *self.running.read().await {
-            interval.tick().await;
+            self.schedule.wait_for_block(&self.mempool).await;
 
+            let _producing = self.producing.lock().await;
             match self.produce_block().await {
                 Ok(block) => {
                     info!(
@@ -135,7 +167,8 @@ impl BlockProducer {
         let height = parent_block.header.height + 1;
 
         // Execute transactions and get receipts + state root
-        let (state_root, receipts) = self.execute_transactions(&transactions, height).await?;
+        let (state_root, receipts, internal) =
+            self.execute_transactions(&transactions, height).await?;
 
         // Calculate other roots
         let tx_root = self.calculate_tx_root(&transactions);
@@ -181,6 +214,7 @@ impl BlockProducer {
             base_fee_per_gas: 1_000_000_000,
             gas_used: 0, // Updated after execution
             gas_limit: 30_000_000,
+            epoch_commitment: Hash::default(),
         };
 
         // Create block
@@ -217,6 +251,9 @@ impl BlockProducer {
             if !pairs.is_empty() {
                 self.storage.transactions.put_receipts(&pairs)?;
             }
+            self.storage
+                .transactions
+                .put_internal_transactions(&internal)?;
 
             // Remove processed transactions from mempool
             // Mempool is internally synchronized, so we can call methods directly
@@ -270,6 +307,7 @@ impl BlockProducer {
             base_fee_per_gas: 1_000_000_000, // 1 gwei initial base fee
             gas_used: 0,
             gas_limit: 30_000_000,
+            epoch_commitment: Hash::default(),
         };
 
         let genesis_block = Block {
@@ -325,9 +363,14 @@ impl BlockProducer {
         &self,
         transactions: &[Transaction],
         height: u64,
-    ) -> Result<(Hash, Vec<TransactionReceipt>)> {
+    ) -> Result<(
+        Hash,
+        Vec<TransactionReceipt>,
+        Vec<(Hash, Vec<InternalTransaction>)>,
+    )> {
         // Execute transactions using the real executor to produce receipts
         let mut receipts: Vec<TransactionReceipt> = Vec::new();
+        let mut internal = Vec::new();
 
         // Create a temporary block context for execution
         let temp_header = BlockHeader {
@@ -348,6 +391,7 @@ impl BlockProducer {
             base_fee_per_gas: 1_000_000_000,
             gas_used: 0,
             gas_limit: 30_000_000,
+            epoch_commitment: Hash::default(),
         };
         let temp_block = Block {
             header: temp_header,
@@ -363,8 +407,15 @@ impl BlockProducer {
         };
 
         for tx in transactions {
-            match self.executor.execute_transaction(&temp_block, tx).await {
-                Ok(rcpt) => receipts.push(rcpt),
+            match self
+                .executor
+                .execute_transaction_with_internal(&temp_block, tx)
+                .await
+            {
+                Ok((rcpt, transfers)) => {
+                    receipts.push(rcpt);
+                    internal.push((tx.hash, transfers));
+                }
                 Err(e) => {
                     error!("Failed to execute transaction {}: {}", tx.hash, e);
                     receipts.push(TransactionReceipt {
@@ -389,7 +440,7 @@ impl BlockProducer {
         // Calculate final state root including AI state (like node producer)
         let state_root = self.ai_state_manager.calculate_state_root().await?;
 
-        Ok((state_root, receipts))
+        Ok((state_root, receipts, internal))
     }
 
     fn calculate_tx_root(&self, transactions: &[Transaction]) -> Hash {
@@ -492,6 +543,29 @@ impl Clone for BlockProducer {
             running: self.running.clone(),
             wallet_manager: self.wallet_manager.clone(),
             peer_manager: self.peer_manager.clone(),
+            schedule: self.schedule.clone(),
+            producing: self.producing.clone(),
         }
     }
 }
+
+#[cfg(feature = "devnet")]
+#[async_trait::async_trait]
+impl citrate_api::dev_rpc::DevChainControl for BlockProducer {
+    fn mining_mode(&self) -> MiningMode {
+        BlockProducer::mining_mode(self)
+    }
+
+    fn set_block_interval(&self, seconds: u64) {
+        BlockProducer::set_block_interval(self, seconds)
+    }
+
+    fn set_automine(&self, enabled: bool) {
+        BlockProducer::set_automine(self, enabled)
+    }
+
+    async fn mine_blocks(&self, count: u64) -> Result<Vec<Hash>> {
+        let blocks = BlockProducer::mine_blocks(self, count).await?;
+        Ok(blocks.iter().map(|block| block.header.block_hash).collect())
+    }
+}
@@ -1,14 +1,30 @@
+pub mod remote;
+
 use anyhow::Result;
 use citrate_consensus::{
     types::{Block, BlockHeader, GhostDagParams, Hash, PublicKey, Signature, VrfProof},
     GhostDag,
 };
+use citrate_execution::types::Address;
+use citrate_storage::chain::DagStatsBucket;
 use citrate_storage::StorageManager;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::sync::Arc;
 use tracing::{debug, info};
 
+/// Heights scanned back from the tip for proposer, address and unindexed
+/// transaction searches
+const SEARCH_WINDOW: u64 = 2_000;
+
+/// Most blocks a scanning search returns
+const MAX_SEARCH_MATCHES: usize = 50;
+
+/// Wallet-style address of a public key, lowercase hex without `0x`
+fn block_address(key: &PublicKey) -> String {
+    hex::encode(Address::from_public_key(key).0)
+}
+
 /// Manages DAG data for visualization and analysis
 pub struct DAGManager {
     storage: Arc<StorageManager>,
@@ -56,71 +72,15 @@ impl DAGManager {
             base_height, end_height, limit
         );
 
-        // Fetch blocks from storage (build nodes, links)
-        let mut nodes_tmp: Vec<(Hash, DAGNode)> = Vec::new();
+        // Fetch blocks from storage
+        let mut blocks = Vec::new();
         for height in base_height..end_height {
             // Get block hash at this height
             match self.storage.blocks.get_block_by_height(height) {
                 Ok(Some(block_hash)) => {
                     // Get the actual block using the hash
                     match self.storage.blocks.get_block(&block_hash) {
-                        Ok(Some(block)) => {
-                            let block_hash_str = block.header.block_hash.to_hex();
-
-                            // Create node for visualization; is_blue set after computing best tip blue set
-                            let node = DAGNode {
-                                id: block_hash_str.clone(),
-                                hash: block_hash_str.clone(),
-                                height: block.header.height,
-                                timestamp: block.header.timestamp,
-                                is_blue: false,
-                                blue_score: match self
-                                    .ghostdag
-                                    .get_blue_score(&block.header.block_hash)
-                                    .await
-                                {
-                                    Ok(s) => s,
-                                    Err(_) => block.header.blue_score,
-                                },
-                                selected_parent: block.header.selected_parent_hash.to_hex(),
-                                merge_parents: block
-                                    .header
-                                    .merge_parent_hashes
-                                    .iter()
-                                    .map(|h| h.to_hex())
-                                    .collect(),
-                                transactions: block.transactions.len(),
-                                proposer: hex::encode(block.header.proposer_pubkey.as_bytes()),
-                                size: 1000 + (height as usize * 100), // Approximate size
-                            };
-
-                            // Create links for visualization
-                            let selected_parent = block.header.selected_parent_hash.to_hex();
-                            if !selected_parent.starts_with("00000000000000000000000000000000") {
-                                links.push(DAGLink {
-                                    source: selected_parent,
-                                    target: block_hash_str.clone(),
-                                    is_selected: true,
-                                    link_type: LinkType::SelectedParent,
-                                });
-                            }
-
-                            // Add merge parent links
-                            for merge_parent in &block.header.merge_parent_hashes {
-                                let merge_parent_str = merge_parent.to_hex();
-                                if !merge_parent_str.starts_with("00000000000000000000000000000000")
-                                {
-                                    links.push(DAGLink {
-                                        source: merge_parent_str,
-                                        target: block_hash_str.clone(),
-                                        is_selected: false,
-                                        link_type: LinkType::MergeParent,
-                                    });
-                                }
-                            }
-
-                            nodes_tmp.push((block.header.block_hash, node));
-                        }
+                        Ok(Some(block)) => blocks.push(block),
                         Ok(None) => {
                             debug!("Block not found for hash at height {}", height);
                         }
@@ -138,6 +98,199 @@ impl DAGManager {
             }
         }
 
+        Ok(self.build_graph(blocks).await)
+    }
+
+    /// Find blocks by block hash, transaction hash, height, proposer public
+    /// key or address, and return them with their parents and children so
+    /// the visualizer can center on the first match
+    pub async fn search_dag(&self, query: &str) -> Result<DAGSearchResult> {
+        let query = query.trim();
+        let (kind, matches) = self.find_matches(query).await?;
+
+        // Matches plus their immediate neighbourhood, without duplicates
+        let mut seen: HashSet<Hash> = HashSet::new();
+        let mut blocks = Vec::new();
+        for block in &matches {
+            let neighbours = std::iter::once(block.header.selected_parent_hash)
+                .chain(block.header.merge_parent_hashes.iter().copied())
+                .chain(
+                    self.storage
+                        .blocks
+                        .get_children(&block.header.block_hash)
+                        .unwrap_or_default(),
+                );
+            for hash in neighbours {
+                if hash == Hash::default() || !seen.insert(hash) {
+                    continue;
+                }
+                if let Ok(Some(neighbour)) = self.storage.blocks.get_block(&hash) {
+                    blocks.push(neighbour);
+                }
+            }
+            if seen.insert(block.header.block_hash) {
+                blocks.push(block.clone());
+            }
+        }
+        blocks.sort_by_key(|b| b.header.height);
+
+        let matches: Vec<String> = matches
+            .iter()
+            .map(|b| b.header.block_hash.to_hex())
+            .collect();
+        Ok(DAGSearchResult {
+            query: query.to_string(),
+            kind,
+            center: matches.first().cloned(),
+            matches,
+            graph: self.build_graph(blocks).await,
+        })
+    }
+
+    async fn find_matches(&self, query: &str) -> Result<(Option<SearchKind>, Vec<Block>)> {
+        if !query.is_empty() && query.chars().all(|c| c.is_ascii_digit()) {
+            let height: u64 = query.parse()?;
+            let block = match self.storage.blocks.get_block_by_height(height)? {
+                Some(hash) => self.storage.blocks.get_block(&hash)?,
+                None => None,
+            };
+            return Ok(match block {
+                Some(block) => (Some(SearchKind::Height), vec![block]),
+                None => (None, vec![]),
+            });
+        }
+
+        let hex_query = query.trim_start_matches("0x").to_lowercase();
+        let bytes = match hex::decode(&hex_query) {
+            Ok(bytes) if bytes.len() == 32 || bytes.len() == 20 => bytes,
+            _ => {
+                anyhow::bail!("Expected a height, block or transaction hash, public key or address")
+            }
+        };
+
+        if bytes.len() == 20 {
+            let matches = self
+                .scan_recent(|block| {
+                    block_address(&block.header.proposer_pubkey) == hex_query
+                        || block.transactions.iter().any(|tx| {
+                            block_address(&tx.from) == hex_query
+                                || tx.to.as_ref().map(block_address).as_deref()
+                                    == Some(hex_query.as_str())
+                        })
+                })
+                .await;
+            let kind = (!matches.is_empty()).then_some(SearchKind::Address);
+            return Ok((kind, matches));
+        }
+
+        let hash = Hash::from_bytes(&bytes);
+        if let Some(block) = self.storage.blocks.get_block(&hash)? {
+            return Ok((Some(SearchKind::Block), vec![block]));
+        }
+
+        // Receipts name the including block; fall back to scanning when the
+        // transaction was stored without one
+        if let Some(receipt) = self.storage.transactions.get_receipt(&hash)? {
+            if let Some(block) = self.storage.blocks.get_block(&receipt.block_hash)? {
+                return Ok((Some(SearchKind::Transaction), vec![block]));
+            }
+        }
+        let with_tx = self
+            .scan_recent(|block| block.transactions.iter().any(|tx| tx.hash == hash))
+            .await;
+        if !with_tx.is_empty() {
+            return Ok((Some(SearchKind::Transaction), with_tx));
+        }
+
+        let proposed = self
+            .scan_recent(|block| block.header.proposer_pubkey.as_bytes() == bytes.as_slice())
+            .await;
+        let kind = (!proposed.is_empty()).then_some(SearchKind::Proposer);
+        Ok((kind, proposed))
+    }
+
+    /// Newest-first blocks within the last `SEARCH_WINDOW` heights that
+    /// satisfy `predicate`, up to `MAX_SEARCH_MATCHES`
+    async fn scan_recent(&self, predicate: impl Fn(&Block) -> bool) -> Vec<Block> {
+        let latest = self.storage.blocks.get_latest_height().unwrap_or(0);
+        let oldest = latest.saturating_sub(SEARCH_WINDOW);
+        let mut matches = Vec::new();
+        for height in (oldest..=latest).rev() {
+            let Ok(Some(hash)) = self.storage.blocks.get_block_by_height(height) else {
+                continue;
+            };
+            if let Ok(Some(block)) = self.storage.blocks.get_block(&hash) {
+                if predicate(&block) {
+                    matches.push(block);
+                    if matches.len() >= MAX_SEARCH_MATCHES {
+                        break;
+                    }
+                }
+            }
+        }
+        matches
+    }
+
+    /// Nodes, links, tips and statistics for a set of blocks
+    async fn build_graph(&self, blocks: Vec<Block>) -> DAGData {
+        let mut nodes: Vec<DAGNode> = Vec::new();
+        let mut links = Vec::new();
+
+        // Build nodes and links
+        let mut nodes_tmp: Vec<(Hash, DAGNode)> = Vec::new();
+        for block in blocks {
+            let block_hash_str = block.header.block_hash.to_hex();
+
+            // Create node for visualization; is_blue set after computing best tip blue set
+            let node = DAGNode {
+                id: block_hash_str.clone(),
+                hash: block_hash_str.clone(),
+                height: block.header.height,
+                timestamp: block.header.timestamp,
+                is_blue: false,
+                blue_score: match self.ghostdag.get_blue_score(&block.header.block_hash).await {
+                    Ok(s) => s,
+                    Err(_) => block.header.blue_score,
+                },
+                selected_parent: block.header.selected_parent_hash.to_hex(),
+                merge_parents: block
+                    .header
+                    .merge_parent_hashes
+                    .iter()
+                    .map(|h| h.to_hex())
+                    .collect(),
+                transactions: block.transactions.len(),
+                proposer: hex::encode(block.header.proposer_pubkey.as_bytes()),
+                size: 1000 + (block.header.height as usize * 100), // Approximate size
+            };
+
+            // Create links for visualization
+            let selected_parent = block.header.selected_parent_hash.to_hex();
+            if !selected_parent.starts_with("00000000000000000000000000000000") {
+                links.push(DAGLink {
+                    source: selected_parent,
+                    target: block_hash_str.clone(),
+                    is_selected: true,
+                    link_type: LinkType::SelectedParent,
+                });
+            }
+
+            // Add merge parent links
+            for merge_parent in &block.header.merge_parent_hashes {
+                let merge_parent_str = merge_parent.to_hex();
+                if !merge_parent_str.starts_with("00000000000000000000000000000000") {
+                    links.push(DAGLink {
+                        source: merge_parent_str,
+                        target: block_hash_str.clone(),
+                        is_selected: false,
+                        link_type: LinkType::MergeParent,
+                    });
+                }
+            }
+
+            nodes_tmp.push((block.header.block_hash, node));
+        }
+
         // Determine blue set for the best tip and finalize nodes
         let mut blue_hashes: HashSet<Hash> = HashSet::new();
         if let Ok(best_tip_hash) = self.ghostdag.select_tip().await {
@@ -196,12 +349,12 @@ impl DAGManager {
             }
         };
 
-        Ok(DAGData {
+        DAGData {
             nodes,
             links,
             tips,
             statistics: stats,
-        })
+        }
     }
 
     /// Get detailed block information
@@ -325,6 +478,21 @@ impl DAGManager {
         Ok(tips)
     }
 
+    /// DAG health time series over `[from, to)` (unix seconds); defaults to
+    /// the last hour in one-minute buckets
+    pub async fn get_dag_stats_series(
+        &self,
+        from: Option<u64>,
+        to: Option<u64>,
+        bucket_secs: Option<u64>,
+    ) -> Result<Vec<DagStatsBucket>> {
+        let to = to.unwrap_or_else(|| chrono::Utc::now().timestamp().max(0) as u64 + 1);
+        let from = from.unwrap_or_else(|| to.saturating_sub(3600));
+        self.storage
+            .dag_stats
+            .series(from, to, bucket_secs.unwrap_or(60))
+    }
+
     /// Calculate the blue score for a block
     pub async fn calculate_blue_score(&self, block_hash: &str) -> Result<u64> {
         let h = Hash::from_bytes(&hex::decode(block_hash).unwrap_or_default());
@@ -418,6 +586,7 @@ impl DAGManager {
                 base_fee_per_gas: 1_000_000_000,
                 gas_used: 0,
                 gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
             },
             state_root: Hash::new([(height as u8 + 10); 32]),
             tx_root: Hash::new([(height as u8 + 20); 32]),
@@ -440,6 +609,29 @@ pub struct DAGData {
     pub statistics: DAGStatistics,
 }
 
+/// What a DAG search query matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchKind {
+    Block,
+    Height,
+    Transaction,
+    Proposer,
+    Address,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DAGSearchResult {
+    pub query: String,
+    /// `None` when nothing matched
+    pub kind: Option<SearchKind>,
+    /// Block the visualizer should center on
+    pub center: Option<String>,
+    /// Hashes of the matching blocks, newest first for scanning searches
+    pub matches: Vec<String>,
+    /// Matching blocks with their parents and children
+    pub graph: DAGData,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DAGNode {
     pub id: String,
@@ -0,0 +1,195 @@
+//! DAG queries against an external node
+//!
+//! When the GUI is attached to an external node there is no local storage
+//! or GhostDAG instance, so the visualizer reads blocks over RPC instead.
+//! The node doesn't report blue set membership, so blocks are shown blue and
+//! child links are left empty.
+
+use super::{
+    BlockDetails, DAGData, DAGLink, DAGNode, DAGStatistics, LinkType, TipInfo, TransactionInfo,
+};
+use crate::rpc_client::RpcClient;
+use anyhow::Result;
+use citrate_api::types::{BlockResponse, TransactionResponse};
+use citrate_api::BlockId;
+use citrate_consensus::types::Hash;
+use citrate_storage::chain::DagStatsBucket;
+use sha3::{Digest, Keccak256};
+use std::sync::Arc;
+
+/// DAG view of an external node
+pub struct RemoteDag {
+    client: Arc<RpcClient>,
+}
+
+impl RemoteDag {
+    pub fn new(client: Arc<RpcClient>) -> Self {
+        Self { client }
+    }
+
+    /// Blocks from `start_height`, or the latest `limit` blocks
+    pub async fn get_dag_data(&self, limit: usize, start_height: Option<u64>) -> Result<DAGData> {
+        let latest_height = self.client.get_node_status().await?.height;
+        let limit = limit.max(1) as u64;
+        let base_height = start_height.unwrap_or(latest_height.saturating_sub(limit - 1));
+        let end_height = (base_height + limit).min(latest_height + 1);
+
+        let mut blocks = Vec::new();
+        for height in base_height..end_height {
+            if let Some(block) = self.client.get_block(BlockId::Number(height)).await? {
+                blocks.push(block);
+            }
+        }
+        let tips = self.get_current_tips().await?;
+
+        let mut nodes = Vec::with_capacity(blocks.len());
+        let mut links = Vec::new();
+        for block in &blocks {
+            let hash = block.hash.to_hex();
+            if block.parent_hash != Hash::default() {
+                links.push(DAGLink {
+                    source: block.parent_hash.to_hex(),
+                    target: hash.clone(),
+                    is_selected: true,
+                    link_type: LinkType::SelectedParent,
+                });
+            }
+            for parent in &block.merge_parents {
+                links.push(DAGLink {
+                    source: parent.to_hex(),
+                    target: hash.clone(),
+                    is_selected: false,
+                    link_type: LinkType::MergeParent,
+                });
+            }
+            nodes.push(DAGNode {
+                id: hash.clone(),
+                hash,
+                height: block.height,
+                timestamp: block.timestamp,
+                is_blue: true,
+                blue_score: block.blue_score,
+                selected_parent: block.parent_hash.to_hex(),
+                merge_parents: block.merge_parents.iter().map(|h| h.to_hex()).collect(),
+                transactions: block.transactions.len(),
+                proposer: block.proposer.clone(),
+                size: 0,
+            });
+        }
+
+        let average_blue_score = if nodes.is_empty() {
+            0.0
+        } else {
+            nodes.iter().map(|n| n.blue_score).sum::<u64>() as f64 / nodes.len() as f64
+        };
+        let statistics = DAGStatistics {
+            total_blocks: nodes.len(),
+            blue_blocks: nodes.len(),
+            red_blocks: 0,
+            current_tips: tips.len(),
+            average_blue_score,
+            max_height: nodes.iter().map(|n| n.height).max().unwrap_or(0),
+        };
+
+        Ok(DAGData {
+            nodes,
+            links,
+            tips,
+            statistics,
+        })
+    }
+
+    pub async fn get_block_details(&self, hash: &str) -> Result<BlockDetails> {
+        let block = self
+            .client
+            .get_block(BlockId::Hash(parse_hash(hash)?))
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Block not found"))?;
+        Ok(block_details(block))
+    }
+
+    pub async fn get_current_tips(&self) -> Result<Vec<TipInfo>> {
+        let mut tips = Vec::new();
+        for tip in self.client.get_dag_tips().await? {
+            if let Some(block) = self.client.get_block(BlockId::Hash(tip)).await? {
+                tips.push(TipInfo {
+                    hash: tip.to_hex(),
+                    height: block.height,
+                    timestamp: block.timestamp,
+                    blue_score: block.blue_score,
+                    cumulative_weight: block.blue_score * 10,
+                });
+            }
+        }
+        Ok(tips)
+    }
+
+    pub async fn get_dag_stats_series(
+        &self,
+        from: Option<u64>,
+        to: Option<u64>,
+        bucket_secs: Option<u64>,
+    ) -> Result<Vec<DagStatsBucket>> {
+        self.client
+            .get_dag_stats_series(from, to, bucket_secs)
+            .await
+    }
+}
+
+fn parse_hash(hash: &str) -> Result<Hash> {
+    let bytes = hex::decode(hash.trim_start_matches("0x"))
+        .map_err(|e| anyhow::anyhow!("Invalid block hash: {}", e))?;
+    if bytes.len() != 32 {
+        return Err(anyhow::anyhow!(
+            "Invalid block hash length: {}",
+            bytes.len()
+        ));
+    }
+    Ok(Hash::from_bytes(&bytes))
+}
+
+/// Wallet-style address of a hex public key: keccak256(pubkey)[12..], or the
+/// first 20 bytes when the key is a zero-padded address
+fn key_address(key_hex: &str) -> String {
+    let bytes = hex::decode(key_hex).unwrap_or_default();
+    if bytes.len() == 32 && bytes[20..].iter().all(|&b| b == 0) {
+        return format!("0x{}", hex::encode(&bytes[..20]));
+    }
+    format!("0x{}", hex::encode(&Keccak256::digest(&bytes)[12..]))
+}
+
+fn transaction_info(tx: TransactionResponse) -> TransactionInfo {
+    TransactionInfo {
+        hash: tx.hash.to_hex(),
+        from_addr: key_address(&tx.from),
+        to_addr: tx.to.as_deref().map(key_address),
+        from: tx.from,
+        to: tx.to,
+        value: tx.value.to_string(),
+        gas_used: tx.gas_limit,
+        status: true,
+    }
+}
+
+fn block_details(block: BlockResponse) -> BlockDetails {
+    BlockDetails {
+        hash: block.hash.to_hex(),
+        height: block.height,
+        timestamp: block.timestamp,
+        is_blue: true,
+        blue_score: block.blue_score,
+        selected_parent: block.parent_hash.to_hex(),
+        merge_parents: block.merge_parents.iter().map(|h| h.to_hex()).collect(),
+        transactions: block
+            .transactions
+            .into_iter()
+            .map(transaction_info)
+            .collect(),
+        proposer: block.proposer,
+        size: 0,
+        state_root: block.state_root.to_hex(),
+        tx_root: block.tx_root.to_hex(),
+        receipt_root: block.receipt_root.to_hex(),
+        children: Vec::new(),
+    }
+}
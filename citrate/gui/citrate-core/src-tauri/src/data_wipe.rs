@@ -0,0 +1,246 @@
+//! Chain resets and network profile wipes
+//!
+//! Deleting a node data dir by hand also deletes anything else kept there,
+//! which with testnet and custom data dirs can be the profile's wallet.
+//! These wipes remove only the chain database and node settings of a
+//! profile, and its wallet only when asked to.
+//!
+//! Every wipe is planned first. The plan lists what would be removed and
+//! carries a confirmation token derived from that list; the wipe is carried
+//! out only when the same token is passed back, so a token issued before
+//! the data changed no longer works.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+use crate::profiles;
+
+/// Wallet key files of a profile, removed by a chain reset that doesn't
+/// keep the wallet
+const WALLET_KEY_FILES: &[&str] = &["keys", "accounts.json"];
+
+/// Other wallet data of a profile; never removed by a wipe
+const WALLET_DATA_FILES: &[&str] = &[
+    "address_book.json",
+    "tracked_addresses.json",
+    "scheduled_payments.json",
+    "contract_deployments.json",
+];
+
+/// Something a wipe removes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WipeTarget {
+    pub path: String,
+    pub description: String,
+    pub bytes: u64,
+}
+
+/// A planned or completed wipe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WipePlan {
+    pub profile: String,
+    pub targets: Vec<WipeTarget>,
+    /// Pass back to carry out this plan
+    pub confirmation_token: String,
+    /// Whether the targets have been removed
+    pub wiped: bool,
+}
+
+impl WipePlan {
+    fn new(profile: &str, targets: Vec<WipeTarget>) -> Self {
+        Self {
+            profile: profile.to_string(),
+            confirmation_token: confirmation_token(&targets),
+            targets,
+            wiped: false,
+        }
+    }
+
+    /// Check `token` against the plan before anything is removed
+    pub fn confirm(&self, token: &str) -> Result<(), String> {
+        if token.trim() != self.confirmation_token {
+            return Err("Confirmation token doesn't match; review the wipe again".to_string());
+        }
+        Ok(())
+    }
+
+    /// Remove the targets once `token` is confirmed
+    pub fn execute(&mut self, token: &str) -> Result<(), String> {
+        self.confirm(token)?;
+        for target in &self.targets {
+            let path = Path::new(&target.path);
+            let removed = if path.is_dir() {
+                std::fs::remove_dir_all(path)
+            } else if path.exists() {
+                std::fs::remove_file(path)
+            } else {
+                continue;
+            };
+            removed.map_err(|e| format!("Failed to remove {:?}: {}", path, e))?;
+        }
+        self.wiped = true;
+        Ok(())
+    }
+}
+
+/// Token for a list of targets: changes whenever a path or its size does
+pub fn confirmation_token(targets: &[WipeTarget]) -> String {
+    let mut hasher = Sha256::new();
+    for target in targets {
+        hasher.update(format!("{}\t{}\n", target.path, target.bytes).as_bytes());
+    }
+    hex::encode(&hasher.finalize()[..4])
+}
+
+/// Plan removing the chain database of `profile`, whose node keeps its
+/// data in `node_data_dir`, and its wallet keys unless `keep_wallet`
+pub fn plan_chain_reset(
+    profile: &str,
+    node_data_dir: &Path,
+    keep_wallet: bool,
+) -> Result<WipePlan, String> {
+    let mut targets = Vec::new();
+    push_guarded(
+        &mut targets,
+        profile,
+        node_data_dir.join("chain"),
+        "Chain database",
+    )?;
+    if !keep_wallet {
+        let data_dir = profiles::profile_data_dir(profile);
+        for name in WALLET_KEY_FILES {
+            push_existing(&mut targets, data_dir.join(name), "Wallet keys");
+        }
+    }
+    Ok(WipePlan::new(profile, targets))
+}
+
+/// Plan removing the network setup of `profile`: its chain database and
+/// node configuration. The wallet is kept. `node_data_dir` is the node data
+/// dir when already known, otherwise it is read from the profile's config.
+pub fn plan_profile_wipe(profile: &str, node_data_dir: Option<&str>) -> Result<WipePlan, String> {
+    let config_path = profiles::profile_config_dir(profile).join("config.json");
+    let node_data_dir = match node_data_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => configured_node_data_dir(&config_path)?
+            .unwrap_or_else(|| profiles::profile_node_data_dir(profile)),
+    };
+
+    let mut targets = Vec::new();
+    push_guarded(
+        &mut targets,
+        profile,
+        node_data_dir.join("chain"),
+        "Chain database",
+    )?;
+    push_existing(&mut targets, config_path, "Node configuration");
+    Ok(WipePlan::new(profile, targets))
+}
+
+/// The `dataDir` a saved node config sets, if any
+fn configured_node_data_dir(config_path: &Path) -> Result<Option<PathBuf>, String> {
+    let Ok(contents) = std::fs::read_to_string(config_path) else {
+        return Ok(None);
+    };
+    let config: serde_json::Value = serde_json::from_str(&contents)
+        .map_err(|e| format!("Failed to read {:?}: {}", config_path, e))?;
+    match config.get("dataDir").and_then(|v| v.as_str()) {
+        Some(dir) if dir.contains("${") => Err(format!(
+            "The node data dir in {:?} uses a variable; switch to the profile and wipe it there",
+            config_path
+        )),
+        Some(dir) if !dir.is_empty() => Ok(Some(PathBuf::from(dir))),
+        _ => Ok(None),
+    }
+}
+
+/// Add `path` if it exists, refusing if it holds any of the profile's
+/// wallet files
+fn push_guarded(
+    targets: &mut Vec<WipeTarget>,
+    profile: &str,
+    path: PathBuf,
+    description: &str,
+) -> Result<(), String> {
+    let data_dir = profiles::profile_data_dir(profile);
+    let wallet = WALLET_KEY_FILES.iter().chain(WALLET_DATA_FILES);
+    for name in wallet {
+        if data_dir.join(name).starts_with(&path) {
+            return Err(format!(
+                "Refusing to remove {:?}: it holds the wallet of profile '{}'",
+                path, profile
+            ));
+        }
+    }
+    push_existing(targets, path, description);
+    Ok(())
+}
+
+fn push_existing(targets: &mut Vec<WipeTarget>, path: PathBuf, description: &str) {
+    if path.exists() {
+        targets.push(WipeTarget {
+            bytes: disk_usage(&path),
+            path: path.to_string_lossy().to_string(),
+            description: description.to_string(),
+        });
+    }
+}
+
+fn disk_usage(path: &Path) -> u64 {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => std::fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| disk_usage(&entry.path()))
+                    .sum()
+            })
+            .unwrap_or(0),
+        Ok(meta) => meta.len(),
+        Err(_) => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_reset_requires_matching_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let chain = dir.path().join("chain");
+        std::fs::create_dir_all(&chain).unwrap();
+        std::fs::write(chain.join("000001.sst"), b"blocks").unwrap();
+        std::fs::write(dir.path().join("signing.key"), b"key").unwrap();
+
+        let mut plan = plan_chain_reset("wipe-test", dir.path(), true).unwrap();
+        assert_eq!(plan.targets.len(), 1);
+        assert_eq!(plan.targets[0].bytes, 6);
+        assert!(plan.execute("00000000").is_err());
+        assert!(chain.exists());
+
+        // The token no longer applies once the data changes
+        let token = plan.confirmation_token.clone();
+        std::fs::write(chain.join("000002.sst"), b"more").unwrap();
+        let mut replanned = plan_chain_reset("wipe-test", dir.path(), true).unwrap();
+        assert!(replanned.confirm(&token).is_err());
+
+        let token = replanned.confirmation_token.clone();
+        replanned.execute(&token).unwrap();
+        assert!(replanned.wiped);
+        assert!(!chain.exists());
+        assert!(dir.path().join("signing.key").exists());
+    }
+
+    #[test]
+    fn test_wipe_refuses_dirs_holding_the_wallet() {
+        // A node data dir whose chain dir is the data dir of profile "chain"
+        let node_data_dir = profiles::app_root().join("profiles");
+        let err = plan_chain_reset("chain", &node_data_dir, true).unwrap_err();
+        assert!(err.contains("wallet"));
+        assert!(plan_chain_reset("dev", &node_data_dir, true).is_ok());
+    }
+}
@@ -209,6 +209,75 @@ pub fn print_build_info() {
     }
 }
 
+/// Devnet block production controls, matching the node's `dev_*` RPC
+/// methods, so contract developers can drive block production
+/// deterministically. Only available in dev builds running a devnet node.
+pub mod block_production {
+    use super::is_dev_mode;
+    use crate::i18n::localize_err;
+    use crate::AppState;
+    use citrate_sequencer::mining_schedule::MAX_MINE_BLOCKS;
+    use citrate_sequencer::MiningMode;
+    use tauri::State;
+
+    fn require_dev_mode() -> Result<(), String> {
+        if is_dev_mode() {
+            Ok(())
+        } else {
+            Err("Block production control is only available in development builds".to_string())
+        }
+    }
+
+    /// Seconds between blocks; 0 stops interval mining
+    #[tauri::command]
+    pub async fn dev_set_block_interval(
+        state: State<'_, AppState>,
+        seconds: u64,
+    ) -> Result<MiningMode, String> {
+        require_dev_mode()?;
+        state
+            .node_manager
+            .set_block_interval(seconds)
+            .await
+            .map_err(localize_err)
+    }
+
+    /// Mine `count` blocks now, returning their hashes
+    #[tauri::command]
+    pub async fn dev_mine_blocks(
+        state: State<'_, AppState>,
+        count: Option<u64>,
+    ) -> Result<Vec<String>, String> {
+        require_dev_mode()?;
+        let count = count.unwrap_or(1);
+        if count == 0 || count > MAX_MINE_BLOCKS {
+            return Err(format!(
+                "Block count must be between 1 and {}",
+                MAX_MINE_BLOCKS
+            ));
+        }
+        state
+            .node_manager
+            .mine_blocks(count)
+            .await
+            .map_err(localize_err)
+    }
+
+    /// Mine a block as soon as a transaction enters the mempool
+    #[tauri::command]
+    pub async fn dev_set_automine(
+        state: State<'_, AppState>,
+        enabled: bool,
+    ) -> Result<MiningMode, String> {
+        require_dev_mode()?;
+        state
+            .node_manager
+            .set_automine(enabled)
+            .await
+            .map_err(localize_err)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
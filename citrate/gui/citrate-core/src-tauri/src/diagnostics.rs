@@ -0,0 +1,481 @@
+//! Node health self-diagnostics
+//!
+//! Checks what most often keeps the embedded node from starting or syncing:
+//! ports taken by another program, a full disk, a drifting clock, IPFS or
+//! bootnodes out of reach, a damaged chain database and a missing GPU
+//! driver. Each check comes back with a status and, when something is
+//! wrong, a hint on how to fix it.
+
+use citrate_storage::{pruning::PruningConfig, StorageManager};
+use serde::{Deserialize, Serialize};
+use std::net::{SocketAddr, TcpListener, UdpSocket};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinSet;
+
+use crate::gpu::{GPUBackend, GPUDevice};
+use crate::ipfs::IpfsManager;
+use crate::node::{self, NodeConfig};
+
+/// Timeout for each network probe
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Free space below which the disk check warns or fails
+const DISK_WARN_BYTES: u64 = 10 << 30;
+const DISK_FAIL_BYTES: u64 = 1 << 30;
+
+/// Clock offsets at which the clock check warns or fails
+const CLOCK_WARN_SECS: f64 = 2.0;
+const CLOCK_FAIL_SECS: f64 = 60.0;
+
+const NTP_SERVER: &str = "pool.ntp.org:123";
+
+/// Blocks below the tip read back by the database scan
+const DB_SCAN_BLOCKS: u64 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Skip,
+    Warn,
+    Fail,
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// How to fix a warning or failure
+    pub remediation: Option<String>,
+}
+
+impl DiagnosticCheck {
+    fn new(name: &str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            detail: detail.into(),
+            remediation: None,
+        }
+    }
+
+    fn hint(mut self, remediation: impl Into<String>) -> Self {
+        self.remediation = Some(remediation.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsReport {
+    pub checks: Vec<DiagnosticCheck>,
+    pub passed: usize,
+    pub warnings: usize,
+    pub failures: usize,
+    /// Unix timestamp in seconds
+    pub generated_at: u64,
+}
+
+/// Run every check for the embedded node. Ports and the database are held
+/// by the node while `node_running`, so they are reported as in use by it.
+pub async fn run(
+    config: &NodeConfig,
+    node_running: bool,
+    ipfs: &IpfsManager,
+    gpus: &[GPUDevice],
+) -> DiagnosticsReport {
+    let data_dir = Path::new(&config.data_dir);
+    let mut checks = check_ports(config, node_running);
+    checks.push(check_disk_space(data_dir));
+    checks.push(
+        tokio::task::spawn_blocking(check_clock)
+            .await
+            .unwrap_or_else(|e| DiagnosticCheck::new("Clock", CheckStatus::Skip, e.to_string())),
+    );
+    checks.push(check_ipfs(ipfs).await);
+    checks.push(check_bootnodes(&config.bootnodes).await);
+    checks.push(if node_running {
+        DiagnosticCheck::new(
+            "Database",
+            CheckStatus::Skip,
+            "In use by the running node; stop it to scan the database",
+        )
+    } else {
+        let chain_dir = data_dir.join("chain");
+        tokio::task::spawn_blocking(move || check_database(&chain_dir))
+            .await
+            .unwrap_or_else(|e| DiagnosticCheck::new("Database", CheckStatus::Skip, e.to_string()))
+    });
+    checks.push(check_gpu_driver(gpus));
+
+    let count = |status| checks.iter().filter(|c| c.status == status).count();
+    DiagnosticsReport {
+        passed: count(CheckStatus::Pass),
+        warnings: count(CheckStatus::Warn),
+        failures: count(CheckStatus::Fail),
+        generated_at: unix_now() as u64,
+        checks,
+    }
+}
+
+fn check_ports(config: &NodeConfig, node_running: bool) -> Vec<DiagnosticCheck> {
+    let mut ports = Vec::new();
+    if config.enable_network {
+        ports.push((
+            "P2P port",
+            SocketAddr::from(([0, 0, 0, 0], config.p2p_port)),
+        ));
+    }
+    if config.enable_rpc {
+        ports.push((
+            "RPC port",
+            SocketAddr::from(([127, 0, 0, 1], config.rpc_port)),
+        ));
+    }
+
+    ports
+        .into_iter()
+        .map(|(name, addr)| {
+            if node_running {
+                return DiagnosticCheck::new(
+                    name,
+                    CheckStatus::Pass,
+                    format!("{} is in use by the running node", addr.port()),
+                );
+            }
+            match TcpListener::bind(addr) {
+                Ok(_) => DiagnosticCheck::new(
+                    name,
+                    CheckStatus::Pass,
+                    format!("{} is free", addr.port()),
+                ),
+                Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => DiagnosticCheck::new(
+                    name,
+                    CheckStatus::Fail,
+                    format!("{} is in use by another program", addr.port()),
+                )
+                .hint(format!(
+                    "Close the program using port {} or choose another port in the node settings",
+                    addr.port()
+                )),
+                Err(e) => DiagnosticCheck::new(
+                    name,
+                    CheckStatus::Fail,
+                    format!("Can't bind {}: {}", addr, e),
+                )
+                .hint("Choose a port above 1024 in the node settings"),
+            }
+        })
+        .collect()
+}
+
+fn check_disk_space(data_dir: &Path) -> DiagnosticCheck {
+    const NAME: &str = "Disk space";
+    // The data dir may not exist before the node first starts
+    let Some(existing) = data_dir.ancestors().find(|p| p.exists()) else {
+        return DiagnosticCheck::new(NAME, CheckStatus::Skip, "No existing directory to check");
+    };
+    let available = match available_bytes(existing) {
+        Ok(bytes) => bytes,
+        Err(e) => return DiagnosticCheck::new(NAME, CheckStatus::Skip, e),
+    };
+
+    let detail = format!(
+        "{:.1} GiB free for {}",
+        available as f64 / (1u64 << 30) as f64,
+        data_dir.display()
+    );
+    if available < DISK_FAIL_BYTES {
+        DiagnosticCheck::new(NAME, CheckStatus::Fail, detail)
+            .hint("Free up space, or reset chain data to start over with less")
+    } else if available < DISK_WARN_BYTES {
+        DiagnosticCheck::new(NAME, CheckStatus::Warn, detail)
+            .hint("Free up space before the chain grows further")
+    } else {
+        DiagnosticCheck::new(NAME, CheckStatus::Pass, detail)
+    }
+}
+
+#[cfg(unix)]
+fn available_bytes(path: &Path) -> Result<u64, String> {
+    let output = std::process::Command::new("df")
+        .arg("-Pk")
+        .arg(path)
+        .output()
+        .map_err(|e| format!("Can't run df: {}", e))?;
+    parse_df_available(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| "Can't read df output".to_string())
+}
+
+#[cfg(not(unix))]
+fn available_bytes(_path: &Path) -> Result<u64, String> {
+    Err("Not supported on this platform".to_string())
+}
+
+/// Available bytes from POSIX `df -Pk` output
+fn parse_df_available(output: &str) -> Option<u64> {
+    let line = output.lines().nth(1)?;
+    let kib: u64 = line.split_whitespace().nth(3)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+fn check_clock() -> DiagnosticCheck {
+    const NAME: &str = "Clock";
+    let offset = match sntp_offset(NTP_SERVER) {
+        Ok(offset) => offset,
+        Err(e) => {
+            return DiagnosticCheck::new(
+                NAME,
+                CheckStatus::Skip,
+                format!("Couldn't reach {}: {}", NTP_SERVER, e),
+            )
+        }
+    };
+
+    let detail = format!(
+        "{:.2}s {} {}",
+        offset.abs(),
+        if offset >= 0.0 { "ahead of" } else { "behind" },
+        NTP_SERVER
+    );
+    let hint = "Turn on automatic date and time in the system settings";
+    if offset.abs() >= CLOCK_FAIL_SECS {
+        DiagnosticCheck::new(NAME, CheckStatus::Fail, detail).hint(hint)
+    } else if offset.abs() >= CLOCK_WARN_SECS {
+        DiagnosticCheck::new(NAME, CheckStatus::Warn, detail).hint(hint)
+    } else {
+        DiagnosticCheck::new(NAME, CheckStatus::Pass, detail)
+    }
+}
+
+/// Seconds the local clock is ahead of an SNTP server
+fn sntp_offset(server: &str) -> std::io::Result<f64> {
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    socket.set_read_timeout(Some(PROBE_TIMEOUT))?;
+    socket.connect(server)?;
+
+    // Leap indicator 0, version 3, client mode
+    let mut packet = [0u8; 48];
+    packet[0] = 0x1b;
+    let sent = unix_now();
+    socket.send(&packet)?;
+    let len = socket.recv(&mut packet)?;
+    let received = unix_now();
+    if len < 48 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "short SNTP reply",
+        ));
+    }
+
+    // Transmit timestamp of the reply, against the midpoint of the round trip
+    let server_time = ntp_to_unix(&packet[40..48]);
+    Ok((sent + received) / 2.0 - server_time)
+}
+
+/// Convert a 64-bit NTP timestamp (seconds since 1900) to Unix seconds
+fn ntp_to_unix(bytes: &[u8]) -> f64 {
+    const NTP_UNIX_OFFSET: f64 = 2_208_988_800.0;
+    let seconds = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64;
+    let fraction = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as f64;
+    seconds - NTP_UNIX_OFFSET + fraction / 4_294_967_296.0
+}
+
+fn unix_now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+async fn check_ipfs(ipfs: &IpfsManager) -> DiagnosticCheck {
+    const NAME: &str = "IPFS daemon";
+    let api_port = ipfs.get_config().await.api_port;
+    if !ipfs.is_running().await {
+        return DiagnosticCheck::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("No daemon answering on port {}", api_port),
+        )
+        .hint("Start IPFS from the Storage page, or run `ipfs daemon`");
+    }
+    let version = ipfs
+        .refresh_status()
+        .await
+        .ok()
+        .and_then(|status| status.version)
+        .unwrap_or_else(|| "Running".to_string());
+    DiagnosticCheck::new(
+        NAME,
+        CheckStatus::Pass,
+        format!("{} on port {}", version, api_port),
+    )
+}
+
+async fn check_bootnodes(bootnodes: &[String]) -> DiagnosticCheck {
+    const NAME: &str = "Bootnodes";
+    if bootnodes.is_empty() {
+        return DiagnosticCheck::new(NAME, CheckStatus::Skip, "No bootnodes configured");
+    }
+
+    let mut probes = JoinSet::new();
+    let mut unreachable = Vec::new();
+    for entry in bootnodes {
+        let Some((_, addr)) = node::parse_bootnode(entry) else {
+            unreachable.push(format!("{} (invalid)", entry));
+            continue;
+        };
+        probes.spawn(async move {
+            let connected =
+                tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect(addr)).await;
+            (addr, matches!(connected, Ok(Ok(_))))
+        });
+    }
+    while let Some(probe) = probes.join_next().await {
+        if let Ok((addr, false)) = probe {
+            unreachable.push(addr.to_string());
+        }
+    }
+    unreachable.sort();
+
+    let reachable = bootnodes.len() - unreachable.len();
+    let detail = format!("{} of {} reachable", reachable, bootnodes.len());
+    if unreachable.is_empty() {
+        return DiagnosticCheck::new(NAME, CheckStatus::Pass, detail);
+    }
+    let status = if reachable == 0 {
+        CheckStatus::Fail
+    } else {
+        CheckStatus::Warn
+    };
+    DiagnosticCheck::new(
+        NAME,
+        status,
+        format!("{}; unreachable: {}", detail, unreachable.join(", ")),
+    )
+    .hint("Check your internet connection and firewall, or update the bootnode list")
+}
+
+fn check_database(chain_dir: &Path) -> DiagnosticCheck {
+    const NAME: &str = "Database";
+    let reset_hint = "Reset chain data to resync from the network";
+    if !chain_dir.join("CURRENT").exists() {
+        return DiagnosticCheck::new(
+            NAME,
+            CheckStatus::Skip,
+            "No database yet; it is created when the node first starts",
+        );
+    }
+
+    let storage = match StorageManager::new(chain_dir, PruningConfig::default()) {
+        Ok(storage) => storage,
+        Err(e) => {
+            return DiagnosticCheck::new(NAME, CheckStatus::Fail, format!("Failed to open: {}", e))
+                .hint(reset_hint)
+        }
+    };
+    let tip = match storage.blocks.get_latest_height() {
+        Ok(tip) => tip,
+        Err(e) => {
+            return DiagnosticCheck::new(
+                NAME,
+                CheckStatus::Fail,
+                format!("Can't read the tip: {}", e),
+            )
+            .hint(reset_hint)
+        }
+    };
+
+    let start = tip.saturating_sub(DB_SCAN_BLOCKS);
+    let mut problems = Vec::new();
+    for height in start..=tip {
+        let problem = match storage.blocks.get_block_by_height(height) {
+            Ok(Some(hash)) => match storage.blocks.get_block(&hash) {
+                Ok(Some(block)) if block.header.height == height && block.hash() == hash => None,
+                Ok(Some(_)) => Some(format!(
+                    "block at height {} doesn't match its index",
+                    height
+                )),
+                Ok(None) => Some(format!("block at height {} is missing", height)),
+                Err(e) => Some(format!("block at height {} is unreadable: {}", height, e)),
+            },
+            Ok(None) => None,
+            Err(e) => Some(format!("height index {} is unreadable: {}", height, e)),
+        };
+        problems.extend(problem);
+    }
+
+    if problems.is_empty() {
+        DiagnosticCheck::new(
+            NAME,
+            CheckStatus::Pass,
+            format!("Blocks {} to {} read back intact", start, tip),
+        )
+    } else {
+        DiagnosticCheck::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("{} problem(s): {}", problems.len(), problems[0]),
+        )
+        .hint(reset_hint)
+    }
+}
+
+fn check_gpu_driver(gpus: &[GPUDevice]) -> DiagnosticCheck {
+    const NAME: &str = "GPU driver";
+    let found: Vec<String> = gpus
+        .iter()
+        .filter(|gpu| gpu.backend != GPUBackend::CPU)
+        .map(|gpu| format!("{} ({})", gpu.name, gpu.backend))
+        .collect();
+    if found.is_empty() {
+        DiagnosticCheck::new(
+            NAME,
+            CheckStatus::Warn,
+            "No GPU driver found; inference runs on the CPU",
+        )
+        .hint("Install the NVIDIA or ROCm driver if this machine has a GPU")
+    } else {
+        DiagnosticCheck::new(NAME, CheckStatus::Pass, found.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ports_held_by_running_node_pass() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let config = NodeConfig {
+            enable_network: false,
+            enable_rpc: true,
+            rpc_port: listener.local_addr().unwrap().port(),
+            ..Default::default()
+        };
+
+        let checks = check_ports(&config, false);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, CheckStatus::Fail);
+        assert!(checks[0].remediation.is_some());
+
+        let checks = check_ports(&config, true);
+        assert_eq!(checks[0].status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn test_parse_df_and_ntp_time() {
+        let output = "Filesystem 1024-blocks Used Available Capacity Mounted on\n\
+                      /dev/disk1s1 488245288 123456 2048 1% /\n";
+        assert_eq!(parse_df_available(output), Some(2048 * 1024));
+
+        let seconds = (1_704_067_200u64 + 2_208_988_800) as u32;
+        let mut bytes = seconds.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&(1u32 << 31).to_be_bytes());
+        assert_eq!(ntp_to_unix(&bytes), 1_704_067_200.5);
+    }
+}
@@ -0,0 +1,339 @@
+//! Coalesced State Event Bus
+//!
+//! Background producers publish their latest node, DAG, peer and download
+//! state here instead of emitting Tauri events directly. A single flush loop
+//! sends whatever changed since the previous flush as one `state-delta`
+//! event, so the frontend wakes up at most once per interval and not at all
+//! when nothing changed.
+//!
+//! ```text
+//! node status ─┐
+//! DAG tips ────┤
+//! peers ───────┼──► StateDeltaBus ──(every interval, if dirty)──► "state-delta"
+//! downloads ───┘
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+
+/// Tauri event carrying a [`StateDelta`]
+pub const STATE_DELTA_EVENT: &str = "state-delta";
+
+/// Shortest flush interval accepted from the frontend
+const MIN_INTERVAL_MS: u64 = 100;
+
+/// Node status fields that change on every poll and do not by themselves
+/// warrant waking the frontend
+const NODE_STATUS_VOLATILE: &[&str] = &["uptime"];
+
+/// Peer fields that change on every poll
+const PEER_VOLATILE: &[&str] = &["last_seen_secs"];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Flush settings for the state bus
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventBusConfig {
+    /// Flush interval in normal mode (milliseconds)
+    pub interval_ms: u64,
+    /// Flush interval in performance mode (milliseconds)
+    pub performance_interval_ms: u64,
+    /// Flush less often to reduce CPU usage, e.g. on battery
+    pub performance_mode: bool,
+}
+
+impl Default for EventBusConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: 1000,
+            performance_interval_ms: 5000,
+            performance_mode: false,
+        }
+    }
+}
+
+impl EventBusConfig {
+    /// Interval the flush loop currently runs at
+    pub fn effective_interval(&self) -> Duration {
+        let ms = if self.performance_mode {
+            self.performance_interval_ms
+        } else {
+            self.interval_ms
+        };
+        Duration::from_millis(ms)
+    }
+}
+
+/// Changes since the previous `state-delta` event. Sections that did not
+/// change are omitted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StateDelta {
+    /// Increments with every emitted delta
+    pub sequence: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_status: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dag: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peers: Option<Value>,
+    /// Latest progress per download or upload id
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub downloads: BTreeMap<String, Value>,
+}
+
+/// Latest value of one section, and what the frontend last saw
+#[derive(Default)]
+struct Slot {
+    latest: Option<Value>,
+    sent: Option<Value>,
+    volatile: &'static [&'static str],
+}
+
+impl Slot {
+    fn with_volatile(volatile: &'static [&'static str]) -> Self {
+        Self {
+            volatile,
+            ..Default::default()
+        }
+    }
+
+    /// Return the latest value if it differs from the last one sent
+    fn take_changed(&mut self) -> Option<Value> {
+        let latest = self.latest.as_ref()?;
+        let changed = match &self.sent {
+            Some(sent) => !same_ignoring(sent, latest, self.volatile),
+            None => true,
+        };
+        if changed {
+            self.sent = Some(latest.clone());
+            Some(latest.clone())
+        } else {
+            None
+        }
+    }
+}
+
+struct Pending {
+    sequence: u64,
+    node_status: Slot,
+    dag: Slot,
+    peers: Slot,
+    downloads: BTreeMap<String, Value>,
+}
+
+// ============================================================================
+// State Delta Bus
+// ============================================================================
+
+/// Collects state updates and hands them out as coalesced deltas
+pub struct StateDeltaBus {
+    config: RwLock<EventBusConfig>,
+    pending: Mutex<Pending>,
+}
+
+impl StateDeltaBus {
+    /// Create a bus with the given flush settings
+    pub fn new(config: EventBusConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            pending: Mutex::new(Pending {
+                sequence: 0,
+                node_status: Slot::with_volatile(NODE_STATUS_VOLATILE),
+                dag: Slot::default(),
+                peers: Slot::with_volatile(PEER_VOLATILE),
+                downloads: BTreeMap::new(),
+            }),
+        }
+    }
+
+    /// Get the current flush settings
+    pub fn config(&self) -> EventBusConfig {
+        self.config.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Update the flush settings
+    pub fn set_config(&self, config: EventBusConfig) -> Result<(), String> {
+        if config.interval_ms < MIN_INTERVAL_MS || config.performance_interval_ms < MIN_INTERVAL_MS {
+            return Err(format!("Flush interval must be at least {} ms", MIN_INTERVAL_MS));
+        }
+        *self.config.write().unwrap_or_else(|e| e.into_inner()) = config;
+        Ok(())
+    }
+
+    /// Interval the flush loop should sleep between deltas
+    pub fn interval(&self) -> Duration {
+        self.config().effective_interval()
+    }
+
+    /// Record the latest node status
+    pub fn publish_node_status<T: Serialize>(&self, status: &T) {
+        if let Ok(value) = serde_json::to_value(status) {
+            self.lock().node_status.latest = Some(value);
+        }
+    }
+
+    /// Record the latest DAG tips
+    pub fn publish_dag<T: Serialize>(&self, dag: &T) {
+        if let Ok(value) = serde_json::to_value(dag) {
+            self.lock().dag.latest = Some(value);
+        }
+    }
+
+    /// Record the latest peer list
+    pub fn publish_peers<T: Serialize>(&self, peers: &T) {
+        if let Ok(value) = serde_json::to_value(peers) {
+            self.lock().peers.latest = Some(value);
+        }
+    }
+
+    /// Record progress for a download or upload. Only the most recent
+    /// progress per id is delivered.
+    pub fn publish_download<T: Serialize>(&self, id: &str, progress: &T) {
+        if let Ok(value) = serde_json::to_value(progress) {
+            self.lock().downloads.insert(id.to_string(), value);
+        }
+    }
+
+    /// Take everything that changed since the last call, or `None` if the
+    /// frontend is already up to date
+    pub fn take_delta(&self) -> Option<StateDelta> {
+        let mut pending = self.lock();
+        let node_status = pending.node_status.take_changed();
+        let dag = pending.dag.take_changed();
+        let peers = pending.peers.take_changed();
+        let downloads = std::mem::take(&mut pending.downloads);
+
+        if node_status.is_none() && dag.is_none() && peers.is_none() && downloads.is_empty() {
+            return None;
+        }
+
+        pending.sequence += 1;
+        Some(StateDelta {
+            sequence: pending.sequence,
+            node_status,
+            dag,
+            peers,
+            downloads,
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Pending> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for StateDeltaBus {
+    fn default() -> Self {
+        Self::new(EventBusConfig::default())
+    }
+}
+
+/// Compare two values, ignoring `volatile` keys on objects (and on objects
+/// inside arrays)
+fn same_ignoring(a: &Value, b: &Value, volatile: &[&str]) -> bool {
+    if volatile.is_empty() {
+        return a == b;
+    }
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            let relevant = |(k, _): &(&String, &Value)| !volatile.contains(&k.as_str());
+            a.iter().filter(relevant).eq(b.iter().filter(relevant))
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_ignoring(a, b, volatile))
+        }
+        _ => a == b,
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_no_delta_without_updates() {
+        let bus = StateDeltaBus::default();
+        assert!(bus.take_delta().is_none());
+    }
+
+    #[test]
+    fn test_unchanged_state_is_suppressed() {
+        let bus = StateDeltaBus::default();
+        bus.publish_node_status(&json!({ "block_height": 5, "uptime": 10 }));
+        let first = bus.take_delta().unwrap();
+        assert_eq!(first.sequence, 1);
+        assert_eq!(first.node_status.unwrap()["block_height"], 5);
+
+        // Same status again, only uptime ticked
+        bus.publish_node_status(&json!({ "block_height": 5, "uptime": 11 }));
+        assert!(bus.take_delta().is_none());
+
+        bus.publish_node_status(&json!({ "block_height": 6, "uptime": 12 }));
+        let second = bus.take_delta().unwrap();
+        assert_eq!(second.sequence, 2);
+        assert_eq!(second.node_status.unwrap()["uptime"], 12);
+    }
+
+    #[test]
+    fn test_only_changed_sections_are_sent() {
+        let bus = StateDeltaBus::default();
+        bus.publish_node_status(&json!({ "block_height": 1 }));
+        bus.publish_peers(&json!([{ "id": "a", "last_seen_secs": 1 }]));
+        bus.take_delta().unwrap();
+
+        bus.publish_node_status(&json!({ "block_height": 1 }));
+        bus.publish_peers(&json!([{ "id": "a", "last_seen_secs": 2 }]));
+        bus.publish_dag(&json!([{ "hash": "0xabc" }]));
+        let delta = bus.take_delta().unwrap();
+        assert!(delta.node_status.is_none());
+        assert!(delta.peers.is_none());
+        assert!(delta.dag.is_some());
+
+        let json = serde_json::to_value(&delta).unwrap();
+        assert!(json.get("node_status").is_none());
+        assert!(json.get("downloads").is_none());
+    }
+
+    #[test]
+    fn test_download_progress_is_coalesced() {
+        let bus = StateDeltaBus::default();
+        for progress in [10, 40, 90] {
+            bus.publish_download("model", &json!({ "progress": progress }));
+        }
+        bus.publish_download("upload-1", &json!({ "progress": 5 }));
+
+        let delta = bus.take_delta().unwrap();
+        assert_eq!(delta.downloads.len(), 2);
+        assert_eq!(delta.downloads["model"]["progress"], 90);
+        assert!(bus.take_delta().is_none());
+    }
+
+    #[test]
+    fn test_performance_mode_interval() {
+        let bus = StateDeltaBus::default();
+        assert_eq!(bus.interval(), Duration::from_millis(1000));
+
+        bus.set_config(EventBusConfig {
+            performance_mode: true,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(bus.interval(), Duration::from_millis(5000));
+
+        let too_fast = EventBusConfig {
+            interval_ms: 10,
+            ..Default::default()
+        };
+        assert!(bus.set_config(too_fast).is_err());
+    }
+}
@@ -0,0 +1,183 @@
+//! Governance proposals for the desktop app
+//!
+//! Reads proposal state straight from the embedded node's proposals
+//! precompile and builds the transactions that propose, vote on, execute or
+//! cancel a proposal. Signing and submission go through the wallet like any
+//! other transaction.
+
+use citrate_execution::precompiles::proposals::{
+    self, Proposal, ProposalState, VoteSupport, PROPOSALS_PRECOMPILE,
+};
+use citrate_execution::state::StateDB;
+use serde::Serialize;
+
+use crate::wallet::TransactionRequest;
+
+/// Gas limit for proposal transactions; proposing stores the description
+const PROPOSAL_GAS_LIMIT: u64 = 300_000;
+
+/// Gas price for proposal transactions, in wei
+const PROPOSAL_GAS_PRICE: &str = "1000000000";
+
+/// Most proposals returned by one listing
+const MAX_LIST: usize = 100;
+
+/// Thresholds and periods proposals are held to
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GovernanceConfig {
+    pub precompile: String,
+    pub proposal_threshold: String,
+    pub vote_threshold: String,
+    pub voting_period: u64,
+    pub execution_delay: u64,
+    pub grace_period: u64,
+    pub quorum_percentage: u64,
+    pub approval_threshold: u64,
+}
+
+impl GovernanceConfig {
+    pub fn current() -> Self {
+        Self {
+            precompile: PROPOSALS_PRECOMPILE.to_string(),
+            proposal_threshold: proposals::proposal_threshold().to_string(),
+            vote_threshold: proposals::vote_threshold().to_string(),
+            voting_period: proposals::VOTING_PERIOD,
+            execution_delay: proposals::EXECUTION_DELAY,
+            grace_period: proposals::GRACE_PERIOD,
+            quorum_percentage: proposals::QUORUM_PERCENTAGE,
+            approval_threshold: proposals::APPROVAL_THRESHOLD,
+        }
+    }
+}
+
+/// A proposal with its tally, amounts as decimal wei strings
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalInfo {
+    pub id: u64,
+    pub proposer: String,
+    pub parameter: String,
+    pub value: String,
+    pub description: String,
+    pub state: ProposalState,
+    pub created_height: u64,
+    pub end_height: u64,
+    pub executable_height: u64,
+    pub expiry_height: u64,
+    pub executed_height: Option<u64>,
+    pub quorum: String,
+    pub votes_for: String,
+    pub votes_against: String,
+    pub votes_abstain: String,
+    pub quorum_reached: bool,
+    pub passed: bool,
+}
+
+impl ProposalInfo {
+    fn new(proposal: &Proposal, height: u64) -> Self {
+        let total = proposal.total_votes();
+        Self {
+            id: proposal.id,
+            proposer: proposal.proposer.to_string(),
+            parameter: proposal.parameter.clone(),
+            value: format!("0x{}", hex::encode(&proposal.value)),
+            description: proposal.description.clone(),
+            state: proposal.state(height),
+            created_height: proposal.created_height,
+            end_height: proposal.end_height,
+            executable_height: proposal.executable_height(),
+            expiry_height: proposal.expiry_height(),
+            executed_height: proposal.executed_height,
+            quorum: proposal.quorum.to_string(),
+            votes_for: proposal.votes_for.to_string(),
+            votes_against: proposal.votes_against.to_string(),
+            votes_abstain: proposal.votes_abstain.to_string(),
+            quorum_reached: !total.is_zero() && total >= proposal.quorum,
+            passed: proposal.passed(),
+        }
+    }
+}
+
+/// A vote cast on a proposal
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoteInfo {
+    pub support: VoteSupport,
+    pub weight: String,
+}
+
+/// Proposals newest first, skipping the `offset` newest
+pub fn list_proposals(
+    state_db: &StateDB,
+    height: u64,
+    offset: usize,
+    limit: usize,
+) -> Vec<ProposalInfo> {
+    (0..proposals::proposal_count(state_db))
+        .rev()
+        .skip(offset)
+        .take(limit.min(MAX_LIST))
+        .filter_map(|id| proposals::proposal(state_db, id))
+        .map(|p| ProposalInfo::new(&p, height))
+        .collect()
+}
+
+pub fn get_proposal(state_db: &StateDB, id: u64, height: u64) -> Option<ProposalInfo> {
+    proposals::proposal(state_db, id).map(|p| ProposalInfo::new(&p, height))
+}
+
+pub fn get_vote(state_db: &StateDB, id: u64, voter: &str) -> Result<Option<VoteInfo>, String> {
+    let voter = citrate_execution::address_utils::address_from_hex(voter.trim())
+        .map_err(|e| format!("Invalid voter address: {}", e))?;
+    Ok(
+        proposals::vote_of(state_db, id, &voter).map(|vote| VoteInfo {
+            support: vote.support,
+            weight: vote.weight.to_string(),
+        }),
+    )
+}
+
+fn request(from: String, data: Vec<u8>) -> TransactionRequest {
+    TransactionRequest {
+        from,
+        to: Some(PROPOSALS_PRECOMPILE.to_string()),
+        value: "0".to_string(),
+        gas_limit: PROPOSAL_GAS_LIMIT,
+        gas_price: PROPOSAL_GAS_PRICE.to_string(),
+        data: format!("0x{}", hex::encode(data)),
+    }
+}
+
+/// Transaction proposing to set `parameter` to the hex-encoded `value`
+pub fn propose_request(
+    from: String,
+    parameter: &str,
+    value: &str,
+    description: &str,
+) -> Result<TransactionRequest, String> {
+    let value = hex::decode(value.trim_start_matches("0x"))
+        .map_err(|e| format!("Invalid parameter value: {}", e))?;
+    if value.len() > proposals::MAX_VALUE_LEN {
+        return Err("Parameter value is too long".to_string());
+    }
+    if description.len() > proposals::MAX_DESCRIPTION_LEN {
+        return Err("Description is too long".to_string());
+    }
+    Ok(request(
+        from,
+        proposals::propose_call_data(parameter, &value, description),
+    ))
+}
+
+pub fn vote_request(from: String, id: u64, support: VoteSupport) -> TransactionRequest {
+    request(from, proposals::vote_call_data(id, support))
+}
+
+pub fn execute_request(from: String, id: u64) -> TransactionRequest {
+    request(from, proposals::execute_call_data(id))
+}
+
+pub fn cancel_request(from: String, id: u64) -> TransactionRequest {
+    request(from, proposals::cancel_call_data(id))
+}
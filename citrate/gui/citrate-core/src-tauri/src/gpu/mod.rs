@@ -10,16 +10,22 @@
 //! ├── GPU Detector (hardware enumeration)
 //! ├── Resource Manager (allocation & tracking)
 //! ├── Job Scheduler (compute job queue)
-//! └── Provider (contribute GPU to network)
+//! ├── Provider (contribute GPU to network)
+//! └── Settlement (result attestation and payment)
 //! ```
 
+pub mod settlement;
+
 use chrono::Timelike;
 use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tracing::{debug, info, warn};
 
+use settlement::{JobSettlement, ResultAttestation};
+
 // ============================================================================
 // Types
 // ============================================================================
@@ -123,11 +129,29 @@ pub enum ComputeJobType {
     ImageGeneration,
 }
 
+/// Scheduling class of a compute job. Higher classes are always scheduled
+/// first; `ComputeJob::priority` orders jobs within a class.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum JobPriorityClass {
+    /// Batch work that yields to everything else
+    Background,
+    /// Regular jobs
+    #[default]
+    Normal,
+    /// Latency-sensitive jobs; high-priority inference may preempt lower classes
+    High,
+}
+
 /// Compute job status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ComputeJobStatus {
     /// Waiting in queue
     Queued,
+    /// Stopped to make room for a higher-priority job, waiting to be rescheduled
+    Preempted {
+        preempted_at: u64,
+        preempted_by: String,
+    },
     /// Currently executing
     Running {
         started_at: u64,
@@ -173,6 +197,43 @@ pub struct ComputeJob {
     pub estimated_time: u64,
     /// Priority (higher = more priority)
     pub priority: u32,
+    /// Scheduling class
+    #[serde(default)]
+    pub priority_class: JobPriorityClass,
+    /// Device holding the job's VRAM reservation
+    #[serde(default)]
+    pub device_id: Option<String>,
+    /// Result attestation and payment of a completed job
+    #[serde(default)]
+    pub settlement: Option<JobSettlement>,
+}
+
+impl ComputeJob {
+    /// Whether the job currently holds a VRAM reservation
+    pub fn is_running(&self) -> bool {
+        matches!(self.status, ComputeJobStatus::Running { .. })
+    }
+
+    /// Whether the job may preempt running jobs of a lower class
+    pub fn can_preempt(&self) -> bool {
+        self.priority_class == JobPriorityClass::High && self.job_type == ComputeJobType::Inference
+    }
+}
+
+/// Tauri event emitted whenever a compute job changes state
+pub const GPU_JOB_STATE_CHANGED_EVENT: &str = "gpu-job-state-changed";
+
+/// Payload of [`GPU_JOB_STATE_CHANGED_EVENT`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GPUJobStateChanged {
+    /// Job identifier
+    pub job_id: String,
+    /// New status
+    pub status: ComputeJobStatus,
+    /// Device the job is reserved on, if running
+    pub device_id: Option<String>,
+    /// Timestamp of the change
+    pub timestamp: u64,
 }
 
 /// GPU allocation settings for the user
@@ -272,12 +333,18 @@ pub struct ProviderStatus {
 // ============================================================================
 
 /// Manages GPU resources for distributed compute
+///
+/// Queued jobs are ordered by priority class, then priority, then submission
+/// time. A job starts on the device with the least free VRAM that still fits
+/// it, and keeps that reservation until it completes, fails or is cancelled.
+/// High-priority inference that does not fit preempts running jobs of lower
+/// classes, which go back to the queue.
 pub struct GPUResourceManager {
     /// Detected GPU devices
     devices: Arc<RwLock<Vec<GPUDevice>>>,
-    /// Active compute jobs
+    /// Started compute jobs (running and finished)
     jobs: Arc<RwLock<HashMap<String, ComputeJob>>>,
-    /// Job queue (waiting jobs)
+    /// Job queue (waiting jobs, in scheduling order)
     queue: Arc<RwLock<Vec<ComputeJob>>>,
     /// User allocation settings
     settings: Arc<RwLock<GPUAllocationSettings>>,
@@ -285,11 +352,14 @@ pub struct GPUResourceManager {
     stats: Arc<RwLock<GPUStats>>,
     /// Provider registration status
     provider_status: Arc<RwLock<ProviderStatus>>,
+    /// Job state change notifications
+    events: broadcast::Sender<GPUJobStateChanged>,
 }
 
 impl GPUResourceManager {
     /// Create a new GPU resource manager
     pub fn new() -> Self {
+        let (events, _) = broadcast::channel(256);
         let manager = Self {
             devices: Arc::new(RwLock::new(Vec::new())),
             jobs: Arc::new(RwLock::new(HashMap::new())),
@@ -304,6 +374,7 @@ impl GPUResourceManager {
                 last_heartbeat: 0,
                 active_jobs: vec![],
             })),
+            events,
         };
 
         // Note: GPU detection is done lazily when get_devices() or refresh_devices() is called
@@ -311,6 +382,11 @@ impl GPUResourceManager {
         manager
     }
 
+    /// Subscribe to job state changes
+    pub fn subscribe(&self) -> broadcast::Receiver<GPUJobStateChanged> {
+        self.events.subscribe()
+    }
+
     /// Get all detected GPU devices (detects on first call if empty)
     pub async fn get_devices(&self) -> Vec<GPUDevice> {
         let devices = self.devices.read().await;
@@ -360,6 +436,11 @@ impl GPUResourceManager {
     pub async fn get_stats(&self) -> GPUStats {
         let mut stats = self.stats.read().await.clone();
         stats.queue_depth = self.queue.read().await.len();
+        stats.current_memory_usage = self.jobs.read().await
+            .values()
+            .filter(|j| j.is_running())
+            .map(|j| j.memory_required)
+            .sum();
         stats
     }
 
@@ -369,8 +450,8 @@ impl GPUResourceManager {
     }
 
     /// Submit a new compute job
-    pub async fn submit_job(&self, job: ComputeJob) -> Result<String, String> {
-        let settings = self.settings.read().await;
+    pub async fn submit_job(&self, mut job: ComputeJob) -> Result<String, String> {
+        let settings = self.settings.read().await.clone();
 
         if !settings.enabled {
             return Err("GPU compute is not enabled".to_string());
@@ -381,30 +462,31 @@ impl GPUResourceManager {
             return Err(format!("Job type {:?} is not allowed", job.job_type));
         }
 
-        // Check memory requirements against available
-        let devices = self.devices.read().await;
-        let total_available: u64 = devices.iter()
-            .map(|d| d.available_memory)
-            .sum();
-
-        let allocated_memory = (total_available as f64 * (settings.allocation_percentage as f64 / 100.0)) as u64;
+        // The job must fit on a single device once everything else is evicted
+        let devices = self.get_devices().await;
+        let largest_budget = device_budgets(&devices, &settings)
+            .into_iter()
+            .map(|(_, budget)| budget)
+            .max()
+            .unwrap_or(0);
 
-        if job.memory_required > allocated_memory {
+        if job.memory_required > largest_budget {
             return Err(format!(
-                "Job requires {} MB but only {} MB allocated",
+                "Job requires {} MB but only {} MB allocated on any device",
                 job.memory_required / 1024 / 1024,
-                allocated_memory / 1024 / 1024
+                largest_budget / 1024 / 1024
             ));
         }
 
+        job.status = ComputeJobStatus::Queued;
+        job.device_id = None;
         let job_id = job.id.clone();
 
         // Add to queue
         let mut queue = self.queue.write().await;
+        self.emit_state(&job);
         queue.push(job);
-
-        // Sort by priority (higher first)
-        queue.sort_by(|a, b| b.priority.cmp(&a.priority));
+        sort_queue(&mut queue);
 
         info!("Compute job {} submitted to queue", job_id);
         Ok(job_id)
@@ -439,7 +521,9 @@ impl GPUResourceManager {
         {
             let mut queue = self.queue.write().await;
             if let Some(pos) = queue.iter().position(|j| j.id == job_id) {
-                queue.remove(pos);
+                let mut job = queue.remove(pos);
+                job.status = ComputeJobStatus::Cancelled;
+                self.emit_state(&job);
                 info!("Job {} removed from queue", job_id);
                 return Ok(());
             }
@@ -449,7 +533,9 @@ impl GPUResourceManager {
         {
             let mut jobs = self.jobs.write().await;
             if let Some(job) = jobs.get_mut(job_id) {
+                // Leaving the Running state releases the VRAM reservation
                 job.status = ComputeJobStatus::Cancelled;
+                self.emit_state(job);
                 info!("Job {} cancelled", job_id);
                 return Ok(());
             }
@@ -458,19 +544,26 @@ impl GPUResourceManager {
         Err(format!("Job {} not found", job_id))
     }
 
-    /// Get available GPU memory for compute
+    /// Get GPU memory available for new compute jobs
     pub async fn get_available_compute_memory(&self) -> u64 {
-        let settings = self.settings.read().await;
+        let settings = self.settings.read().await.clone();
         if !settings.enabled {
             return 0;
         }
 
-        let devices = self.devices.read().await;
-        let total_available: u64 = devices.iter()
-            .map(|d| d.available_memory)
+        let devices = self.devices.read().await.clone();
+        let total_budget: u64 = device_budgets(&devices, &settings)
+            .into_iter()
+            .map(|(_, budget)| budget)
             .sum();
 
-        (total_available as f64 * (settings.allocation_percentage as f64 / 100.0)) as u64
+        let reserved: u64 = self.jobs.read().await
+            .values()
+            .filter(|j| j.is_running())
+            .map(|j| j.memory_required)
+            .sum();
+
+        total_budget.saturating_sub(reserved)
     }
 
     /// Check if GPU compute is within scheduled hours
@@ -494,45 +587,81 @@ impl GPUResourceManager {
         }
     }
 
-    /// Process next job in queue (called by scheduler)
+    /// Start the next job that can be placed (called by scheduler)
+    ///
+    /// Jobs are considered in queue order; a lower-priority job that fits may
+    /// start while a larger one waits. Jobs preempted to make room are moved
+    /// back to the queue and reported through [`GPU_JOB_STATE_CHANGED_EVENT`],
+    /// so their executors can stop them.
     pub async fn process_next_job(&self) -> Option<ComputeJob> {
         if !self.is_within_schedule().await {
             debug!("Outside scheduled hours, not processing jobs");
             return None;
         }
 
-        let settings = self.settings.read().await;
-        let active_count = self.jobs.read().await.len();
+        let settings = self.settings.read().await.clone();
+        let devices = self.get_devices().await;
+        let budgets = device_budgets(&devices, &settings);
 
-        if active_count >= settings.max_concurrent_jobs as usize {
-            debug!("Max concurrent jobs reached ({}/{})", active_count, settings.max_concurrent_jobs);
-            return None;
-        }
+        let mut queue = self.queue.write().await;
+        let mut jobs = self.jobs.write().await;
 
-        // Pop next job from queue
-        let job = {
-            let mut queue = self.queue.write().await;
-            if queue.is_empty() {
-                return None;
+        let (index, placement) = {
+            let running: Vec<&ComputeJob> = jobs.values().filter(|j| j.is_running()).collect();
+            let max_concurrent = settings.max_concurrent_jobs as usize;
+
+            let next = queue.iter().enumerate().find_map(|(index, job)| {
+                place_job(job, &budgets, &running, max_concurrent).map(|p| (index, p))
+            });
+
+            match next {
+                Some(next) => next,
+                None => {
+                    if !queue.is_empty() {
+                        debug!(
+                            "No queued job fits ({} running, max {})",
+                            running.len(),
+                            settings.max_concurrent_jobs
+                        );
+                    }
+                    return None;
+                }
             }
-            queue.remove(0)
         };
 
+        let now = unix_now();
+        let mut job = queue.remove(index);
+
+        for victim_id in &placement.preempt {
+            if let Some(mut victim) = jobs.remove(victim_id) {
+                victim.status = ComputeJobStatus::Preempted {
+                    preempted_at: now,
+                    preempted_by: job.id.clone(),
+                };
+                victim.device_id = None;
+                info!("Job {} preempted by {}", victim_id, job.id);
+                self.emit_state(&victim);
+                queue.push(victim);
+            }
+        }
+        sort_queue(&mut queue);
+
         // Move to active jobs
-        let job_id = job.id.clone();
-        let mut running_job = job.clone();
-        running_job.status = ComputeJobStatus::Running {
-            started_at: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs(),
+        job.status = ComputeJobStatus::Running {
+            started_at: now,
             progress: 0.0,
         };
+        job.device_id = Some(placement.device_id);
 
-        self.jobs.write().await.insert(job_id.clone(), running_job.clone());
-        info!("Started processing job {}", job_id);
+        jobs.insert(job.id.clone(), job.clone());
+        self.emit_state(&job);
+        info!(
+            "Started processing job {} on {}",
+            job.id,
+            job.device_id.as_deref().unwrap_or_default()
+        );
 
-        Some(running_job)
+        Some(job)
     }
 
     /// Mark a job as completed
@@ -540,10 +669,7 @@ impl GPUResourceManager {
         let mut jobs = self.jobs.write().await;
 
         if let Some(job) = jobs.get_mut(job_id) {
-            let now = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs();
+            let now = unix_now();
 
             let started_at = match &job.status {
                 ComputeJobStatus::Running { started_at, .. } => *started_at,
@@ -555,6 +681,7 @@ impl GPUResourceManager {
                 completed_at: now,
                 result_hash,
             };
+            self.emit_state(job);
 
             // Update stats
             let mut stats = self.stats.write().await;
@@ -562,7 +689,6 @@ impl GPUResourceManager {
             let duration = now - started_at;
             stats.total_compute_time += duration;
             stats.avg_job_duration = stats.total_compute_time as f64 / stats.jobs_completed as f64;
-            stats.tokens_earned += job.max_payment; // Simplified - actual would be based on usage
 
             info!("Job {} completed in {} seconds", job_id, duration);
             Ok(())
@@ -571,6 +697,73 @@ impl GPUResourceManager {
         }
     }
 
+    /// Attest the result of a completed job run by `provider`. The
+    /// attestation is recorded unsigned; sign its payload and pass it to
+    /// [`Self::record_settlement`].
+    pub async fn attest_job(
+        &self,
+        job_id: &str,
+        provider: &str,
+    ) -> Result<ResultAttestation, String> {
+        let devices = self.devices.read().await;
+        let mut jobs = self.jobs.write().await;
+        let job = jobs
+            .get_mut(job_id)
+            .ok_or_else(|| format!("Job {} not found in active jobs", job_id))?;
+        if job.settlement.as_ref().is_some_and(JobSettlement::is_submitted) {
+            return Err(format!("Job {} is already settled", job_id));
+        }
+
+        let device = devices.iter().find(|d| job.device_id.as_deref() == Some(d.id.as_str()));
+        let attestation = ResultAttestation::for_job(job, device, provider)?;
+        job.settlement = Some(JobSettlement {
+            attestation: attestation.clone(),
+            tx_hash: None,
+            submitted_at: None,
+            error: None,
+        });
+        Ok(attestation)
+    }
+
+    /// Record the outcome of signing and submitting a job's settlement.
+    /// Tokens are counted as earned once the settlement is submitted.
+    pub async fn record_settlement(
+        &self,
+        job_id: &str,
+        attestation: ResultAttestation,
+        outcome: Result<String, String>,
+    ) -> Result<JobSettlement, String> {
+        let mut jobs = self.jobs.write().await;
+        let job = jobs
+            .get_mut(job_id)
+            .ok_or_else(|| format!("Job {} not found in active jobs", job_id))?;
+
+        let settlement = match outcome {
+            Ok(tx_hash) => {
+                self.stats.write().await.tokens_earned += job.max_payment;
+                info!("Job {} settled in tx {}", job_id, tx_hash);
+                JobSettlement {
+                    attestation,
+                    tx_hash: Some(tx_hash),
+                    submitted_at: Some(unix_now()),
+                    error: None,
+                }
+            }
+            Err(error) => {
+                warn!("Settlement of job {} failed: {}", job_id, error);
+                JobSettlement {
+                    attestation,
+                    tx_hash: None,
+                    submitted_at: None,
+                    error: Some(error),
+                }
+            }
+        };
+        job.settlement = Some(settlement.clone());
+        self.emit_state(job);
+        Ok(settlement)
+    }
+
     /// Mark a job as failed
     pub async fn fail_job(&self, job_id: &str, error: String) -> Result<(), String> {
         let mut jobs = self.jobs.write().await;
@@ -578,11 +771,9 @@ impl GPUResourceManager {
         if let Some(job) = jobs.get_mut(job_id) {
             job.status = ComputeJobStatus::Failed {
                 error: error.clone(),
-                failed_at: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs(),
+                failed_at: unix_now(),
             };
+            self.emit_state(job);
 
             let mut stats = self.stats.write().await;
             stats.jobs_failed += 1;
@@ -593,6 +784,17 @@ impl GPUResourceManager {
             Err(format!("Job {} not found in active jobs", job_id))
         }
     }
+
+    /// Notify subscribers of a job's current state
+    fn emit_state(&self, job: &ComputeJob) {
+        // Sending only fails when nobody is subscribed
+        let _ = self.events.send(GPUJobStateChanged {
+            job_id: job.id.clone(),
+            status: job.status.clone(),
+            device_id: job.device_id.clone(),
+            timestamp: unix_now(),
+        });
+    }
 }
 
 impl Default for GPUResourceManager {
@@ -601,6 +803,125 @@ impl Default for GPUResourceManager {
     }
 }
 
+// ============================================================================
+// Scheduling
+// ============================================================================
+
+/// Where a job will run, and which running jobs must be evicted first
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Placement {
+    device_id: String,
+    preempt: Vec<String>,
+}
+
+/// VRAM each device offers to the network under the current settings
+fn device_budgets(devices: &[GPUDevice], settings: &GPUAllocationSettings) -> Vec<(String, u64)> {
+    devices
+        .iter()
+        .map(|d| {
+            let mut budget =
+                (d.available_memory as f64 * (settings.allocation_percentage as f64 / 100.0)) as u64;
+            if settings.max_memory_allocation > 0 {
+                budget = budget.min(settings.max_memory_allocation);
+            }
+            (d.id.clone(), budget)
+        })
+        .collect()
+}
+
+/// VRAM reserved by running jobs on a device
+fn reserved_on(running: &[&ComputeJob], device_id: &str) -> u64 {
+    running
+        .iter()
+        .filter(|j| j.device_id.as_deref() == Some(device_id))
+        .map(|j| j.memory_required)
+        .sum()
+}
+
+/// Find a device for `job`, preempting lower-class jobs only if it may
+fn place_job(
+    job: &ComputeJob,
+    budgets: &[(String, u64)],
+    running: &[&ComputeJob],
+    max_concurrent: usize,
+) -> Option<Placement> {
+    let at_capacity = running.len() >= max_concurrent;
+
+    if !at_capacity {
+        // Best fit: the device with the least free memory that still fits
+        let best = budgets
+            .iter()
+            .filter_map(|(device_id, budget)| {
+                let free = budget.saturating_sub(reserved_on(running, device_id));
+                (free >= job.memory_required).then_some((free, device_id))
+            })
+            .min_by_key(|(free, _)| *free);
+
+        if let Some((_, device_id)) = best {
+            return Some(Placement {
+                device_id: device_id.clone(),
+                preempt: Vec::new(),
+            });
+        }
+    }
+
+    if !job.can_preempt() {
+        return None;
+    }
+
+    // Evict the fewest jobs possible, taking the least important first
+    budgets
+        .iter()
+        .filter(|(_, budget)| *budget >= job.memory_required)
+        .filter_map(|(device_id, budget)| {
+            let mut victims: Vec<&ComputeJob> = running
+                .iter()
+                .copied()
+                .filter(|r| {
+                    r.device_id.as_deref() == Some(device_id.as_str())
+                        && r.priority_class < job.priority_class
+                })
+                .collect();
+            victims.sort_by_key(|r| (r.priority_class, r.priority, Reverse(started_at(r))));
+
+            let mut free = budget.saturating_sub(reserved_on(running, device_id));
+            let mut preempt = Vec::new();
+            for victim in victims {
+                if free >= job.memory_required && (!at_capacity || !preempt.is_empty()) {
+                    break;
+                }
+                free += victim.memory_required;
+                preempt.push(victim.id.clone());
+            }
+
+            let fits = free >= job.memory_required && (!at_capacity || !preempt.is_empty());
+            fits.then(|| Placement {
+                device_id: device_id.clone(),
+                preempt,
+            })
+        })
+        .min_by_key(|p| p.preempt.len())
+}
+
+/// Order the queue by class, then priority, then submission time
+fn sort_queue(queue: &mut [ComputeJob]) {
+    queue.sort_by_key(|j| (Reverse(j.priority_class), Reverse(j.priority), j.created_at));
+}
+
+fn started_at(job: &ComputeJob) -> u64 {
+    match job.status {
+        ComputeJobStatus::Running { started_at, .. } => started_at,
+        _ => 0,
+    }
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
 // ============================================================================
 // GPU Detection
 // ============================================================================
@@ -840,6 +1161,26 @@ fn create_cpu_fallback() -> GPUDevice {
     }
 }
 
+/// Device id for a llama.cpp backend buffer name (`CUDA0`, `ROCm1`, `Metal`,
+/// `CPU_Mapped`, ...), matching the ids [`detect_gpus`] assigns. Host
+/// buffers of any backend are system memory and count as the CPU.
+pub fn device_id_for_llama_backend(name: &str) -> String {
+    if name.starts_with("CPU") || name.ends_with("_Host") {
+        return "cpu-0".to_string();
+    }
+    if name.starts_with("Metal") || name.starts_with("MTL") {
+        return "metal-0".to_string();
+    }
+    for (prefix, id) in [("CUDA", "cuda"), ("ROCm", "rocm")] {
+        if let Some(index) = name.strip_prefix(prefix) {
+            if !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()) {
+                return format!("{}-{}", id, index);
+            }
+        }
+    }
+    name.to_lowercase()
+}
+
 /// Get system memory in bytes
 fn get_system_memory() -> Option<u64> {
     #[cfg(target_os = "macos")]
@@ -944,6 +1285,16 @@ mod tests {
         assert_eq!(parse_memory_string("1 TB"), 1024 * 1024 * 1024 * 1024);
     }
 
+    #[test]
+    fn test_device_id_for_llama_backend() {
+        assert_eq!(device_id_for_llama_backend("CUDA1"), "cuda-1");
+        assert_eq!(device_id_for_llama_backend("ROCm0"), "rocm-0");
+        assert_eq!(device_id_for_llama_backend("Metal"), "metal-0");
+        assert_eq!(device_id_for_llama_backend("CPU_Mapped"), "cpu-0");
+        assert_eq!(device_id_for_llama_backend("CUDA_Host"), "cpu-0");
+        assert_eq!(device_id_for_llama_backend("Vulkan0"), "vulkan0");
+    }
+
     #[test]
     fn test_cpu_fallback() {
         let device = create_cpu_fallback();
@@ -1014,12 +1365,198 @@ mod tests {
             memory_required: 1024 * 1024 * 1024, // 1GB
             estimated_time: 60,
             priority: 1,
+            priority_class: JobPriorityClass::Normal,
+            device_id: None,
+            settlement: None,
         };
 
         let result = manager.submit_job(job).await;
         assert!(result.is_err()); // Should fail because GPU compute is disabled
     }
 
+    const GB: u64 = 1024 * 1024 * 1024;
+
+    fn test_job(id: &str, job_type: ComputeJobType, class: JobPriorityClass, memory_gb: u64, priority: u32) -> ComputeJob {
+        ComputeJob {
+            id: id.to_string(),
+            job_type,
+            model_id: "test-model".to_string(),
+            input_hash: "hash123".to_string(),
+            requester: "0x123".to_string(),
+            max_payment: 100,
+            status: ComputeJobStatus::Queued,
+            created_at: 0,
+            memory_required: memory_gb * GB,
+            estimated_time: 60,
+            priority,
+            priority_class: class,
+            device_id: None,
+            settlement: None,
+        }
+    }
+
+    /// Manager with GPU compute enabled on a single device with `memory_gb` to share
+    async fn test_manager(memory_gb: u64, max_concurrent_jobs: u32) -> GPUResourceManager {
+        let manager = GPUResourceManager::new();
+        *manager.devices.write().await = vec![GPUDevice {
+            id: "cuda-0".to_string(),
+            name: "Test GPU".to_string(),
+            vendor: GPUVendor::NVIDIA,
+            total_memory: memory_gb * GB,
+            available_memory: memory_gb * GB,
+            compute_capability: "8.9".to_string(),
+            in_use: false,
+            backend: GPUBackend::CUDA,
+            temperature: None,
+            power_usage: None,
+            utilization: 0,
+        }];
+        manager
+            .update_settings(GPUAllocationSettings {
+                enabled: true,
+                allocation_percentage: 100,
+                max_concurrent_jobs,
+                allowed_job_types: vec![ComputeJobType::Inference, ComputeJobType::Training],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        manager
+    }
+
+    #[tokio::test]
+    async fn test_queue_orders_by_class_then_priority() {
+        let manager = test_manager(16, 4).await;
+        manager.submit_job(test_job("normal-low", ComputeJobType::Inference, JobPriorityClass::Normal, 1, 1)).await.unwrap();
+        manager.submit_job(test_job("background", ComputeJobType::Training, JobPriorityClass::Background, 1, 9)).await.unwrap();
+        manager.submit_job(test_job("high", ComputeJobType::Inference, JobPriorityClass::High, 1, 0)).await.unwrap();
+        manager.submit_job(test_job("normal-high", ComputeJobType::Inference, JobPriorityClass::Normal, 1, 5)).await.unwrap();
+
+        let order: Vec<String> = manager.queue.read().await.iter().map(|j| j.id.clone()).collect();
+        assert_eq!(order, vec!["high", "normal-high", "normal-low", "background"]);
+    }
+
+    #[tokio::test]
+    async fn test_submit_rejects_job_larger_than_any_device() {
+        let manager = test_manager(8, 2).await;
+        let result = manager
+            .submit_job(test_job("huge", ComputeJobType::Training, JobPriorityClass::Normal, 12, 1))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_vram_reservation_blocks_until_released() {
+        let manager = test_manager(10, 4).await;
+        manager.submit_job(test_job("a", ComputeJobType::Training, JobPriorityClass::Normal, 6, 1)).await.unwrap();
+        manager.submit_job(test_job("b", ComputeJobType::Training, JobPriorityClass::Normal, 6, 1)).await.unwrap();
+
+        let first = manager.process_next_job().await.unwrap();
+        assert_eq!(first.id, "a");
+        assert_eq!(first.device_id.as_deref(), Some("cuda-0"));
+        assert_eq!(manager.get_available_compute_memory().await, 4 * GB);
+
+        // Not enough VRAM left for the second job, and training cannot preempt
+        assert!(manager.process_next_job().await.is_none());
+
+        manager.complete_job("a", "result".to_string()).await.unwrap();
+        let second = manager.process_next_job().await.unwrap();
+        assert_eq!(second.id, "b");
+    }
+
+    #[tokio::test]
+    async fn test_high_priority_inference_preempts_background_job() {
+        let manager = test_manager(10, 4).await;
+        let mut events = manager.subscribe();
+
+        manager.submit_job(test_job("train", ComputeJobType::Training, JobPriorityClass::Background, 8, 1)).await.unwrap();
+        manager.process_next_job().await.unwrap();
+
+        manager.submit_job(test_job("infer", ComputeJobType::Inference, JobPriorityClass::High, 4, 1)).await.unwrap();
+        let started = manager.process_next_job().await.unwrap();
+        assert_eq!(started.id, "infer");
+
+        let preempted = manager.get_job("train").await.unwrap();
+        assert!(matches!(
+            preempted.status,
+            ComputeJobStatus::Preempted { ref preempted_by, .. } if preempted_by == "infer"
+        ));
+        assert!(preempted.device_id.is_none());
+        assert_eq!(manager.get_stats().await.queue_depth, 1);
+
+        let mut states = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            states.push((event.job_id, event.status));
+        }
+        assert!(states.iter().any(|(id, status)| id == "train" && matches!(status, ComputeJobStatus::Preempted { .. })));
+        assert!(matches!(states.last(), Some((id, ComputeJobStatus::Running { .. })) if id == "infer"));
+    }
+
+    #[tokio::test]
+    async fn test_normal_inference_does_not_preempt() {
+        let manager = test_manager(10, 4).await;
+        manager.submit_job(test_job("train", ComputeJobType::Training, JobPriorityClass::Background, 8, 1)).await.unwrap();
+        manager.process_next_job().await.unwrap();
+
+        manager.submit_job(test_job("infer", ComputeJobType::Inference, JobPriorityClass::Normal, 4, 1)).await.unwrap();
+        assert!(manager.process_next_job().await.is_none());
+        assert!(manager.get_job("train").await.unwrap().is_running());
+    }
+
+    #[tokio::test]
+    async fn test_preemption_frees_a_concurrency_slot() {
+        let manager = test_manager(16, 2).await;
+        manager.submit_job(test_job("normal", ComputeJobType::Inference, JobPriorityClass::Normal, 2, 5)).await.unwrap();
+        manager.submit_job(test_job("background", ComputeJobType::Training, JobPriorityClass::Background, 2, 1)).await.unwrap();
+        manager.process_next_job().await.unwrap();
+        manager.process_next_job().await.unwrap();
+
+        // Memory is free but both slots are taken; the background job yields
+        manager.submit_job(test_job("urgent", ComputeJobType::Inference, JobPriorityClass::High, 2, 1)).await.unwrap();
+        let started = manager.process_next_job().await.unwrap();
+        assert_eq!(started.id, "urgent");
+        assert!(manager.get_job("normal").await.unwrap().is_running());
+        assert!(matches!(
+            manager.get_job("background").await.unwrap().status,
+            ComputeJobStatus::Preempted { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_settlement_credits_tokens_once_submitted() {
+        let manager = test_manager(10, 4).await;
+        manager.submit_job(test_job("a", ComputeJobType::Inference, JobPriorityClass::Normal, 1, 1)).await.unwrap();
+        manager.process_next_job().await.unwrap();
+        let provider = "0x0000000000000000000000000000000000000052";
+
+        // Only completed jobs are attested
+        assert!(manager.attest_job("a", provider).await.is_err());
+        manager.complete_job("a", "result".to_string()).await.unwrap();
+        assert_eq!(manager.get_stats().await.tokens_earned, 0);
+
+        let attestation = manager.attest_job("a", provider).await.unwrap();
+        let devices = manager.get_devices().await;
+        assert_eq!(
+            attestation.device_fingerprint,
+            format!("0x{}", hex::encode(settlement::device_fingerprint(&devices[0]).as_bytes()))
+        );
+
+        let failed = manager
+            .record_settlement("a", attestation.clone(), Err("wallet locked".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(failed.error.as_deref(), Some("wallet locked"));
+        assert_eq!(manager.get_stats().await.tokens_earned, 0);
+
+        // A failed settlement can be retried
+        let attestation = manager.attest_job("a", provider).await.unwrap();
+        manager.record_settlement("a", attestation, Ok("0xabc".to_string())).await.unwrap();
+        assert_eq!(manager.get_stats().await.tokens_earned, 100);
+        let job = manager.get_job("a").await.unwrap();
+        assert_eq!(job.settlement.unwrap().tx_hash.as_deref(), Some("0xabc"));
+        assert!(manager.attest_job("a", provider).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_cancel_job_not_found() {
         let manager = GPUResourceManager::new();
@@ -0,0 +1,308 @@
+//! Compute Job Settlement
+//!
+//! Payment for a compute job is escrowed on chain by the requester in the
+//! node's compute precompile, naming the provider that runs the job. When
+//! the job completes, the provider builds a [`ResultAttestation`] (output
+//! hash, execution time and a fingerprint of the device that ran it), signs
+//! it with its wallet account and submits it in a settlement transaction.
+//! The precompile releases the attested amount to the provider and refunds
+//! the rest of the escrow; the settlement log it emits is what the revenue
+//! sharing module credits the provider from.
+
+use citrate_consensus::types::Hash;
+use citrate_execution::address_utils::address_from_hex;
+use citrate_execution::precompiles::compute::{self, COMPUTE_PRECOMPILE};
+use primitive_types::U256;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+use super::{ComputeJob, ComputeJobStatus, GPUDevice};
+use crate::wallet::TransactionRequest;
+
+/// Gas limit for escrow and settlement transactions
+const COMPUTE_GAS_LIMIT: u64 = 200_000;
+
+/// Gas price for escrow and settlement transactions, in wei
+const COMPUTE_GAS_PRICE: &str = "1000000000";
+
+fn keccak(bytes: &[u8]) -> Hash {
+    Hash::new(Keccak256::digest(bytes).into())
+}
+
+fn hex_hash(hash: &Hash) -> String {
+    format!("0x{}", hex::encode(hash.as_bytes()))
+}
+
+fn parse_hash(value: &str) -> Result<Hash, String> {
+    let bytes = hex::decode(value.trim_start_matches("0x")).map_err(|e| e.to_string())?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| format!("Not a 32-byte hash: {}", value))?;
+    Ok(Hash::new(bytes))
+}
+
+/// On-chain id of a job: the Keccak-256 hash of its local id
+pub fn job_hash(job_id: &str) -> Hash {
+    keccak(job_id.as_bytes())
+}
+
+/// On-chain form of a job's result hash. A 32-byte hex hash is used as is;
+/// anything else, such as an IPFS CID, is hashed with Keccak-256.
+pub fn output_hash(result_hash: &str) -> Hash {
+    parse_hash(result_hash).unwrap_or_else(|_| keccak(result_hash.as_bytes()))
+}
+
+/// Hash of the properties that identify a GPU model and its driver
+/// backend; live readings like temperature and free memory are left out so
+/// the fingerprint is stable across jobs
+pub fn device_fingerprint(device: &GPUDevice) -> Hash {
+    keccak(
+        format!(
+            "{:?}|{}|{}|{}|{:?}",
+            device.vendor,
+            device.name,
+            device.total_memory,
+            device.compute_capability,
+            device.backend
+        )
+        .as_bytes(),
+    )
+}
+
+/// A job's `max_payment`, in whole tokens, as wei
+pub fn payment_wei(max_payment: u64) -> U256 {
+    U256::from(max_payment) * U256::exp10(18)
+}
+
+/// A provider's signed statement of the result of a completed job, and the
+/// amount it claims for it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResultAttestation {
+    pub job_id: String,
+    /// On-chain id of the job, see [`job_hash`]
+    pub job_hash: String,
+    pub output_hash: String,
+    /// Seconds from start to completion
+    pub execution_time: u64,
+    pub device_fingerprint: String,
+    /// Provider account that ran the job and signs the attestation
+    pub provider: String,
+    /// Claimed payment, in wei
+    pub amount: String,
+    /// Provider's signature of [`ResultAttestation::signing_payload`];
+    /// empty until signed
+    #[serde(default)]
+    pub signature: String,
+}
+
+impl ResultAttestation {
+    /// Attest a completed job run by `provider` on `device`. The full
+    /// `max_payment` is claimed.
+    pub fn for_job(
+        job: &ComputeJob,
+        device: Option<&GPUDevice>,
+        provider: &str,
+    ) -> Result<Self, String> {
+        let (started_at, completed_at, result_hash) = match &job.status {
+            ComputeJobStatus::Completed {
+                started_at,
+                completed_at,
+                result_hash,
+            } => (*started_at, *completed_at, result_hash),
+            _ => return Err(format!("Job {} is not completed", job.id)),
+        };
+        let device = device.ok_or_else(|| format!("Device of job {} is unknown", job.id))?;
+        Ok(Self {
+            job_id: job.id.clone(),
+            job_hash: hex_hash(&job_hash(&job.id)),
+            output_hash: hex_hash(&output_hash(result_hash)),
+            execution_time: completed_at.saturating_sub(started_at),
+            device_fingerprint: hex_hash(&device_fingerprint(device)),
+            provider: provider.to_string(),
+            amount: payment_wei(job.max_payment).to_string(),
+            signature: String::new(),
+        })
+    }
+
+    pub fn is_signed(&self) -> bool {
+        !self.signature.is_empty()
+    }
+
+    /// Call data of the settlement transaction. The provider signs these
+    /// bytes, so the signature covers exactly what is settled on chain.
+    pub fn signing_payload(&self) -> Result<Vec<u8>, String> {
+        let amount = U256::from_dec_str(&self.amount).map_err(|e| format!("{:?}", e))?;
+        Ok(compute::settle_call_data(
+            &parse_hash(&self.job_hash)?,
+            &parse_hash(&self.output_hash)?,
+            self.execution_time,
+            &parse_hash(&self.device_fingerprint)?,
+            amount,
+        ))
+    }
+
+    /// The settlement transaction, sent from the provider account
+    pub fn settle_request(&self) -> Result<TransactionRequest, String> {
+        if !self.is_signed() {
+            return Err(format!("Attestation of job {} is not signed", self.job_id));
+        }
+        Ok(request(
+            self.provider.clone(),
+            U256::zero(),
+            self.signing_payload()?,
+        ))
+    }
+}
+
+/// Where a completed job is in being paid
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobSettlement {
+    pub attestation: ResultAttestation,
+    /// Hash of the settlement transaction once submitted
+    pub tx_hash: Option<String>,
+    pub submitted_at: Option<u64>,
+    /// Why signing or submitting the settlement failed; it can be retried
+    pub error: Option<String>,
+}
+
+impl JobSettlement {
+    pub fn is_submitted(&self) -> bool {
+        self.tx_hash.is_some()
+    }
+}
+
+fn request(from: String, value: U256, data: Vec<u8>) -> TransactionRequest {
+    TransactionRequest {
+        from,
+        to: Some(COMPUTE_PRECOMPILE.to_string()),
+        value: value.to_string(),
+        gas_limit: COMPUTE_GAS_LIMIT,
+        gas_price: COMPUTE_GAS_PRICE.to_string(),
+        data: format!("0x{}", hex::encode(data)),
+    }
+}
+
+/// Transaction escrowing the `max_payment` of a job for `provider`, sent
+/// from the requester account
+pub fn escrow_request(job: &ComputeJob, provider: &str) -> Result<TransactionRequest, String> {
+    let provider = address_from_hex(provider)?;
+    Ok(request(
+        job.requester.clone(),
+        payment_wei(job.max_payment),
+        compute::escrow_call_data(&job_hash(&job.id), &provider),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{ComputeJobType, GPUBackend, GPUVendor, JobPriorityClass};
+    use super::*;
+
+    fn device() -> GPUDevice {
+        GPUDevice {
+            id: "gpu-0".to_string(),
+            name: "Apple M2".to_string(),
+            vendor: GPUVendor::Apple,
+            total_memory: 16 * 1024 * 1024 * 1024,
+            available_memory: 8 * 1024 * 1024 * 1024,
+            compute_capability: "Metal 3".to_string(),
+            in_use: false,
+            backend: GPUBackend::Metal,
+            temperature: None,
+            power_usage: None,
+            utilization: 0,
+        }
+    }
+
+    fn completed_job() -> ComputeJob {
+        ComputeJob {
+            id: "job-1".to_string(),
+            job_type: ComputeJobType::Inference,
+            model_id: "model".to_string(),
+            input_hash: "input".to_string(),
+            requester: "0x0000000000000000000000000000000000000051".to_string(),
+            max_payment: 3,
+            status: ComputeJobStatus::Completed {
+                started_at: 100,
+                completed_at: 130,
+                result_hash: "QmResult".to_string(),
+            },
+            created_at: 90,
+            memory_required: 1024,
+            estimated_time: 60,
+            priority: 0,
+            priority_class: JobPriorityClass::Normal,
+            device_id: Some("gpu-0".to_string()),
+            settlement: None,
+        }
+    }
+
+    #[test]
+    fn test_attestation_matches_settlement_call() {
+        let job = completed_job();
+        let provider = "0x0000000000000000000000000000000000000052";
+        let mut attestation = ResultAttestation::for_job(&job, Some(&device()), provider).unwrap();
+        assert_eq!(attestation.execution_time, 30);
+        assert_eq!(attestation.amount, payment_wei(3).to_string());
+        assert_eq!(attestation.output_hash, hex_hash(&keccak(b"QmResult")));
+        assert!(attestation.settle_request().is_err());
+
+        attestation.signature = "0xsig".to_string();
+        let request = attestation.settle_request().unwrap();
+        assert_eq!(request.from, provider);
+        assert_eq!(request.value, "0");
+        assert_eq!(
+            request.data,
+            format!(
+                "0x{}",
+                hex::encode(compute::settle_call_data(
+                    &job_hash("job-1"),
+                    &keccak(b"QmResult"),
+                    30,
+                    &device_fingerprint(&device()),
+                    payment_wei(3),
+                ))
+            )
+        );
+
+        // Only completed jobs on a known device are attested
+        let running = ComputeJob {
+            status: ComputeJobStatus::Running {
+                started_at: 100,
+                progress: 0.5,
+            },
+            ..completed_job()
+        };
+        assert!(ResultAttestation::for_job(&running, Some(&device()), provider).is_err());
+        assert!(ResultAttestation::for_job(&job, None, provider).is_err());
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_live_readings() {
+        let busy = GPUDevice {
+            available_memory: 0,
+            in_use: true,
+            temperature: Some(80.0),
+            utilization: 100,
+            ..device()
+        };
+        assert_eq!(device_fingerprint(&busy), device_fingerprint(&device()));
+        let other = GPUDevice {
+            name: "Apple M3".to_string(),
+            ..device()
+        };
+        assert_ne!(device_fingerprint(&other), device_fingerprint(&device()));
+
+        let hash = format!("0x{}", "ab".repeat(32));
+        assert_eq!(hex_hash(&output_hash(&hash)), hash);
+    }
+
+    #[test]
+    fn test_escrow_request() {
+        let job = completed_job();
+        let request = escrow_request(&job, "0x0000000000000000000000000000000000000052").unwrap();
+        assert_eq!(request.from, job.requester);
+        assert_eq!(request.value, payment_wei(3).to_string());
+        assert!(escrow_request(&job, "not an address").is_err());
+    }
+}
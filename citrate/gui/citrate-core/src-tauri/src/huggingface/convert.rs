@@ -0,0 +1,966 @@
+//! Safetensors to GGUF Conversion
+//!
+//! Converts a model downloaded in safetensors format into a quantized GGUF
+//! file next to it, without llama.cpp's Python converter or `llama-quantize`.
+//! The model directory needs `config.json`, `tokenizer.json` and the
+//! `*.safetensors` shards; `tokenizer_config.json` is read when present for
+//! the chat template and BOS handling.
+//!
+//! Supported are Llama style decoders (Llama, Mistral) and Qwen2, with BPE
+//! tokenizers: SentencePiece vocabularies exported with byte fallback, and
+//! byte-level GPT-2 style vocabularies. Tensors are converted one at a time,
+//! so memory use is bounded by the largest tensor rather than the model.
+
+use super::{HuggingFaceManager, LocalModelInfo};
+use crate::models::quantize::{bf16_to_f32, f16_to_f32, layer_index, GgmlType};
+use crate::models::{merged_model_filename, GgufQuantization};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+/// Tauri event carrying [`ConversionProgress`]
+pub const GGUF_CONVERSION_EVENT: &str = "gguf-conversion-progress";
+
+const GGUF_MAGIC: &[u8; 4] = b"GGUF";
+const GGUF_VERSION: u32 = 3;
+/// Alignment of tensor data, the GGUF default
+const GGUF_ALIGNMENT: usize = 32;
+
+// GGUF metadata value types
+const GGUF_TYPE_UINT32: u32 = 4;
+const GGUF_TYPE_INT32: u32 = 5;
+const GGUF_TYPE_FLOAT32: u32 = 6;
+const GGUF_TYPE_BOOL: u32 = 7;
+const GGUF_TYPE_STRING: u32 = 8;
+const GGUF_TYPE_ARRAY: u32 = 9;
+
+// llama.cpp token types
+const TOKEN_NORMAL: i32 = 1;
+const TOKEN_UNKNOWN: i32 = 2;
+const TOKEN_CONTROL: i32 = 3;
+const TOKEN_USER_DEFINED: i32 = 4;
+const TOKEN_UNUSED: i32 = 5;
+const TOKEN_BYTE: i32 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConversionStatus {
+    Converting,
+    Completed,
+    Failed,
+}
+
+/// Progress of a conversion, emitted after every tensor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionProgress {
+    pub model_id: String,
+    pub quantization: GgufQuantization,
+    /// GGUF name of the tensor just written
+    pub tensor: Option<String>,
+    pub tensors_done: usize,
+    pub tensors_total: usize,
+    pub status: ConversionStatus,
+    /// Path of the GGUF file, once completed
+    pub output_path: Option<PathBuf>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Architecture {
+    Llama,
+    Qwen2,
+}
+
+impl Architecture {
+    fn from_config(architectures: &[String]) -> Result<Self, String> {
+        match architectures.first().map(String::as_str) {
+            Some("LlamaForCausalLM") | Some("MistralForCausalLM") => Ok(Architecture::Llama),
+            Some("Qwen2ForCausalLM") => Ok(Architecture::Qwen2),
+            Some(other) => Err(format!(
+                "Unsupported architecture for GGUF conversion: {}",
+                other
+            )),
+            None => Err("config.json does not name the model architecture".to_string()),
+        }
+    }
+
+    fn gguf_name(self) -> &'static str {
+        match self {
+            Architecture::Llama => "llama",
+            Architecture::Qwen2 => "qwen2",
+        }
+    }
+}
+
+/// Fields of `config.json` the conversion needs
+#[derive(Debug, Deserialize)]
+struct ModelConfig {
+    #[serde(default)]
+    architectures: Vec<String>,
+    hidden_size: usize,
+    intermediate_size: usize,
+    num_hidden_layers: usize,
+    num_attention_heads: usize,
+    num_key_value_heads: Option<usize>,
+    #[serde(default = "default_rms_norm_eps")]
+    rms_norm_eps: f32,
+    rope_theta: Option<f32>,
+    max_position_embeddings: Option<usize>,
+    vocab_size: usize,
+    bos_token_id: Option<Value>,
+    eos_token_id: Option<Value>,
+}
+
+fn default_rms_norm_eps() -> f32 {
+    1e-6
+}
+
+impl ModelConfig {
+    fn n_head_kv(&self) -> usize {
+        self.num_key_value_heads.unwrap_or(self.num_attention_heads)
+    }
+}
+
+/// Token id of a config field, which may list several (e.g. multiple EOS)
+fn token_id(value: &Option<Value>) -> Option<u32> {
+    match value.as_ref()? {
+        Value::Array(ids) => ids.first()?.as_u64().map(|id| id as u32),
+        id => id.as_u64().map(|id| id as u32),
+    }
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<T, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// A tensor stored in a safetensors shard
+#[derive(Debug, Clone)]
+struct SafetensorsTensor {
+    name: String,
+    dtype: String,
+    shape: Vec<usize>,
+    file: PathBuf,
+    /// Absolute offset of the data in `file`
+    offset: u64,
+    len: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct SafetensorsEntry {
+    dtype: String,
+    shape: Vec<usize>,
+    data_offsets: (u64, u64),
+}
+
+/// Tensors of every `*.safetensors` shard in `dir`
+fn read_safetensors_index(dir: &Path) -> Result<Vec<SafetensorsTensor>, String> {
+    let mut shards: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read model dir: {}", e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .map(|e| e == "safetensors")
+                .unwrap_or(false)
+        })
+        .collect();
+    if shards.is_empty() {
+        return Err(format!("No .safetensors files in {}", dir.display()));
+    }
+    shards.sort();
+
+    let mut tensors = Vec::new();
+    for shard in shards {
+        let mut file =
+            File::open(&shard).map_err(|e| format!("Failed to open {}: {}", shard.display(), e))?;
+        let mut len = [0u8; 8];
+        file.read_exact(&mut len)
+            .map_err(|e| format!("Failed to read {}: {}", shard.display(), e))?;
+        let header_len = u64::from_le_bytes(len);
+        let mut header = vec![0u8; header_len as usize];
+        file.read_exact(&mut header)
+            .map_err(|e| format!("Truncated safetensors header in {}: {}", shard.display(), e))?;
+        let entries: HashMap<String, Value> = serde_json::from_slice(&header)
+            .map_err(|e| format!("Invalid safetensors header in {}: {}", shard.display(), e))?;
+
+        for (name, entry) in entries {
+            if name == "__metadata__" {
+                continue;
+            }
+            let entry: SafetensorsEntry = serde_json::from_value(entry)
+                .map_err(|e| format!("Invalid safetensors entry {}: {}", name, e))?;
+            let (start, end) = entry.data_offsets;
+            tensors.push(SafetensorsTensor {
+                name,
+                dtype: entry.dtype,
+                shape: entry.shape,
+                file: shard.clone(),
+                offset: 8 + header_len + start,
+                len: end.saturating_sub(start) as usize,
+            });
+        }
+    }
+    Ok(tensors)
+}
+
+fn read_tensor_f32(tensor: &SafetensorsTensor) -> Result<Vec<f32>, String> {
+    let mut file = File::open(&tensor.file)
+        .map_err(|e| format!("Failed to open {}: {}", tensor.file.display(), e))?;
+    file.seek(SeekFrom::Start(tensor.offset))
+        .map_err(|e| format!("Failed to seek to {}: {}", tensor.name, e))?;
+    let mut raw = vec![0u8; tensor.len];
+    file.read_exact(&mut raw)
+        .map_err(|e| format!("Failed to read {}: {}", tensor.name, e))?;
+
+    let values: Vec<f32> = match tensor.dtype.as_str() {
+        "F32" => raw
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+        "F16" => raw
+            .chunks_exact(2)
+            .map(|b| f16_to_f32(u16::from_le_bytes([b[0], b[1]])))
+            .collect(),
+        "BF16" => raw
+            .chunks_exact(2)
+            .map(|b| bf16_to_f32(u16::from_le_bytes([b[0], b[1]])))
+            .collect(),
+        other => return Err(format!("Unsupported dtype {} for {}", other, tensor.name)),
+    };
+    if values.len() != tensor.shape.iter().product::<usize>() {
+        return Err(format!("Size of {} does not match its shape", tensor.name));
+    }
+    Ok(values)
+}
+
+/// GGUF name of a Hugging Face tensor; `None` for tensors llama.cpp doesn't load
+fn gguf_tensor_name(name: &str) -> Result<Option<String>, String> {
+    let mapped = match name {
+        "model.embed_tokens.weight" => "token_embd.weight".to_string(),
+        "model.norm.weight" => "output_norm.weight".to_string(),
+        "lm_head.weight" => "output.weight".to_string(),
+        _ if name.ends_with("rotary_emb.inv_freq") => return Ok(None),
+        _ => {
+            let unknown = || format!("Unknown tensor {}", name);
+            let rest = name.strip_prefix("model.layers.").ok_or_else(unknown)?;
+            let (layer, rest) = rest.split_once('.').ok_or_else(unknown)?;
+            let (module, suffix) = rest.rsplit_once('.').ok_or_else(unknown)?;
+            let gguf = match module {
+                "input_layernorm" => "attn_norm",
+                "self_attn.q_proj" => "attn_q",
+                "self_attn.k_proj" => "attn_k",
+                "self_attn.v_proj" => "attn_v",
+                "self_attn.o_proj" => "attn_output",
+                "post_attention_layernorm" => "ffn_norm",
+                "mlp.gate_proj" => "ffn_gate",
+                "mlp.up_proj" => "ffn_up",
+                "mlp.down_proj" => "ffn_down",
+                _ => return Err(unknown()),
+            };
+            format!("blk.{}.{}.{}", layer, gguf, suffix)
+        }
+    };
+    Ok(Some(mapped))
+}
+
+/// Reorder the rows of a Q or K projection from Hugging Face's rotary layout,
+/// each head's two halves one after the other, to the interleaved pairs
+/// llama.cpp's `llama` architecture expects
+fn permute_rotary(data: &[f32], n_head: usize, cols: usize) -> Vec<f32> {
+    let rows = data.len() / cols;
+    let half = rows / n_head / 2;
+    let mut out = vec![0f32; data.len()];
+    for head in 0..n_head {
+        for i in 0..half {
+            for side in 0..2 {
+                let src = head * 2 * half + side * half + i;
+                let dst = head * 2 * half + i * 2 + side;
+                out[dst * cols..(dst + 1) * cols]
+                    .copy_from_slice(&data[src * cols..(src + 1) * cols]);
+            }
+        }
+    }
+    out
+}
+
+/// Vocabulary in llama.cpp's representation
+struct Vocab {
+    model: &'static str,
+    pre: Option<&'static str>,
+    tokens: Vec<String>,
+    scores: Option<Vec<f32>>,
+    token_types: Vec<i32>,
+    merges: Vec<String>,
+    unk_id: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenizerJson {
+    #[serde(default)]
+    added_tokens: Vec<AddedToken>,
+    model: TokenizerModel,
+}
+
+#[derive(Debug, Deserialize)]
+struct AddedToken {
+    id: u32,
+    content: String,
+    #[serde(default)]
+    special: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenizerModel {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    #[serde(default)]
+    vocab: HashMap<String, u32>,
+    /// `"a b"` strings, or `["a", "b"]` pairs in newer exports
+    #[serde(default)]
+    merges: Vec<Value>,
+    #[serde(default)]
+    byte_fallback: bool,
+    unk_token: Option<String>,
+}
+
+fn is_byte_token(token: &str) -> bool {
+    token.len() == 6 && token.starts_with("<0x") && token.ends_with('>')
+}
+
+fn load_vocab(dir: &Path, arch: Architecture, vocab_size: usize) -> Result<Vocab, String> {
+    let tokenizer: TokenizerJson = read_json(&dir.join("tokenizer.json"))?;
+    let model = tokenizer.model;
+    if model.kind.as_deref() != Some("BPE") {
+        return Err(format!(
+            "Unsupported tokenizer model {}; only BPE tokenizers can be converted",
+            model.kind.as_deref().unwrap_or("unknown")
+        ));
+    }
+
+    let merges: Vec<String> = model
+        .merges
+        .iter()
+        .filter_map(|merge| match merge {
+            Value::String(merge) => Some(merge.clone()),
+            Value::Array(pair) => match pair.as_slice() {
+                [Value::String(a), Value::String(b)] => Some(format!("{} {}", a, b)),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    let size = model
+        .vocab
+        .values()
+        .chain(tokenizer.added_tokens.iter().map(|t| &t.id))
+        .map(|&id| id as usize + 1)
+        .max()
+        .unwrap_or(0)
+        .max(vocab_size);
+    let mut tokens: Vec<Option<String>> = vec![None; size];
+    let mut token_types = vec![TOKEN_NORMAL; size];
+    for (token, &id) in &model.vocab {
+        tokens[id as usize] = Some(token.clone());
+    }
+    for added in &tokenizer.added_tokens {
+        tokens[added.id as usize] = Some(added.content.clone());
+        token_types[added.id as usize] = if added.special {
+            TOKEN_CONTROL
+        } else {
+            TOKEN_USER_DEFINED
+        };
+    }
+    let tokens: Vec<String> = tokens
+        .into_iter()
+        .enumerate()
+        .map(|(id, token)| {
+            token.unwrap_or_else(|| {
+                token_types[id] = TOKEN_UNUSED;
+                format!("[PAD{}]", id)
+            })
+        })
+        .collect();
+
+    let unk_id = model
+        .unk_token
+        .as_ref()
+        .and_then(|unk| model.vocab.get(unk).copied());
+    if let Some(unk) = unk_id {
+        token_types[unk as usize] = TOKEN_UNKNOWN;
+    }
+
+    if model.byte_fallback {
+        // SentencePiece vocabulary: merges are ranked by score, so a token
+        // scores lower the later the merge that produces it
+        let ranks: HashMap<String, usize> = merges
+            .iter()
+            .enumerate()
+            .map(|(rank, merge)| (merge.replacen(' ', "", 1), rank))
+            .collect();
+        let scores = tokens
+            .iter()
+            .map(|token| ranks.get(token).map(|&rank| -(rank as f32)).unwrap_or(0.0))
+            .collect();
+        for (token, token_type) in tokens.iter().zip(token_types.iter_mut()) {
+            if *token_type == TOKEN_NORMAL && is_byte_token(token) {
+                *token_type = TOKEN_BYTE;
+            }
+        }
+        Ok(Vocab {
+            model: "llama",
+            pre: None,
+            tokens,
+            scores: Some(scores),
+            token_types,
+            merges: Vec::new(),
+            unk_id,
+        })
+    } else {
+        // Byte-level BPE; the pre-tokenizer is picked by model family
+        let pre = match arch {
+            Architecture::Qwen2 => "qwen2",
+            Architecture::Llama if tokens.len() >= 128_000 => "llama-bpe",
+            Architecture::Llama => "default",
+        };
+        Ok(Vocab {
+            model: "gpt2",
+            pre: Some(pre),
+            tokens,
+            scores: None,
+            token_types,
+            merges,
+            unk_id,
+        })
+    }
+}
+
+/// Options read from `tokenizer_config.json`
+#[derive(Debug, Default, Deserialize)]
+struct TokenizerConfig {
+    add_bos_token: Option<bool>,
+    chat_template: Option<Value>,
+}
+
+/// Metadata section of a GGUF file
+#[derive(Default)]
+struct GgufMetadata {
+    buf: Vec<u8>,
+    count: u64,
+}
+
+fn put_str(buf: &mut Vec<u8>, value: &str) {
+    buf.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    buf.extend_from_slice(value.as_bytes());
+}
+
+impl GgufMetadata {
+    fn key(&mut self, key: &str, value_type: u32) {
+        put_str(&mut self.buf, key);
+        self.buf.extend_from_slice(&value_type.to_le_bytes());
+        self.count += 1;
+    }
+
+    fn u32(&mut self, key: &str, value: u32) {
+        self.key(key, GGUF_TYPE_UINT32);
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f32(&mut self, key: &str, value: f32) {
+        self.key(key, GGUF_TYPE_FLOAT32);
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn bool(&mut self, key: &str, value: bool) {
+        self.key(key, GGUF_TYPE_BOOL);
+        self.buf.push(value as u8);
+    }
+
+    fn string(&mut self, key: &str, value: &str) {
+        self.key(key, GGUF_TYPE_STRING);
+        put_str(&mut self.buf, value);
+    }
+
+    fn array_header(&mut self, key: &str, element_type: u32, len: usize) {
+        self.key(key, GGUF_TYPE_ARRAY);
+        self.buf.extend_from_slice(&element_type.to_le_bytes());
+        self.buf.extend_from_slice(&(len as u64).to_le_bytes());
+    }
+
+    fn strings(&mut self, key: &str, values: &[String]) {
+        self.array_header(key, GGUF_TYPE_STRING, values.len());
+        for value in values {
+            put_str(&mut self.buf, value);
+        }
+    }
+
+    fn f32s(&mut self, key: &str, values: &[f32]) {
+        self.array_header(key, GGUF_TYPE_FLOAT32, values.len());
+        for value in values {
+            self.buf.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    fn i32s(&mut self, key: &str, values: &[i32]) {
+        self.array_header(key, GGUF_TYPE_INT32, values.len());
+        for value in values {
+            self.buf.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+/// A tensor as it will be written
+struct PlannedTensor {
+    source: SafetensorsTensor,
+    name: String,
+    /// Dimensions, innermost first
+    ne: Vec<usize>,
+    ggml_type: GgmlType,
+    offset: usize,
+    size: usize,
+}
+
+fn align(offset: usize) -> usize {
+    offset.div_ceil(GGUF_ALIGNMENT) * GGUF_ALIGNMENT
+}
+
+fn pad(writer: &mut impl Write, written: usize) -> std::io::Result<()> {
+    writer.write_all(&vec![0u8; align(written) - written])
+}
+
+/// Convert the safetensors model in `model_dir` into a GGUF file at
+/// `output`, calling `on_tensor` with each tensor written and the running
+/// and total counts; returns the number of tensors written
+///
+/// The file is written under a `.partial` name and renamed once complete.
+pub fn convert_model<F>(
+    model_dir: &Path,
+    output: &Path,
+    quantization: GgufQuantization,
+    mut on_tensor: F,
+) -> Result<usize, String>
+where
+    F: FnMut(&str, usize, usize),
+{
+    let config: ModelConfig = read_json(&model_dir.join("config.json"))?;
+    let arch = Architecture::from_config(&config.architectures)?;
+    let vocab = load_vocab(model_dir, arch, config.vocab_size)?;
+    let tokenizer_config: TokenizerConfig = if model_dir.join("tokenizer_config.json").exists() {
+        read_json(&model_dir.join("tokenizer_config.json"))?
+    } else {
+        TokenizerConfig::default()
+    };
+
+    let mut tensors = Vec::new();
+    for source in read_safetensors_index(model_dir)? {
+        let Some(name) = gguf_tensor_name(&source.name)? else {
+            continue;
+        };
+        let ne: Vec<usize> = source.shape.iter().rev().copied().collect();
+        let ggml_type = quantization.tensor_type(&name, &ne, config.num_hidden_layers);
+        let size = ggml_type.row_bytes(ne[0]) * ne[1..].iter().product::<usize>();
+        tensors.push(PlannedTensor {
+            source,
+            name,
+            ne,
+            ggml_type,
+            offset: 0,
+            size,
+        });
+    }
+    tensors.sort_by(|a, b| (layer_index(&a.name), &a.name).cmp(&(layer_index(&b.name), &b.name)));
+    let mut offset = 0;
+    for tensor in &mut tensors {
+        tensor.offset = offset;
+        offset = align(offset + tensor.size);
+    }
+
+    let arch_name = arch.gguf_name();
+    let mut meta = GgufMetadata::default();
+    meta.string("general.architecture", arch_name);
+    if let Some(name) = model_dir.file_name().and_then(|n| n.to_str()) {
+        meta.string("general.name", &name.replace("__", "/"));
+    }
+    meta.u32("general.file_type", quantization.file_type());
+    meta.u32("general.quantization_version", 2);
+    let key = |suffix: &str| format!("{}.{}", arch_name, suffix);
+    meta.u32(
+        &key("context_length"),
+        config.max_position_embeddings.unwrap_or(4096) as u32,
+    );
+    meta.u32(&key("embedding_length"), config.hidden_size as u32);
+    meta.u32(&key("block_count"), config.num_hidden_layers as u32);
+    meta.u32(&key("feed_forward_length"), config.intermediate_size as u32);
+    meta.u32(
+        &key("attention.head_count"),
+        config.num_attention_heads as u32,
+    );
+    meta.u32(&key("attention.head_count_kv"), config.n_head_kv() as u32);
+    meta.f32(
+        &key("attention.layer_norm_rms_epsilon"),
+        config.rms_norm_eps,
+    );
+    meta.f32(
+        &key("rope.freq_base"),
+        config.rope_theta.unwrap_or(10_000.0),
+    );
+    if arch == Architecture::Llama {
+        meta.u32(
+            &key("rope.dimension_count"),
+            (config.hidden_size / config.num_attention_heads) as u32,
+        );
+    }
+
+    meta.string("tokenizer.ggml.model", vocab.model);
+    if let Some(pre) = vocab.pre {
+        meta.string("tokenizer.ggml.pre", pre);
+    }
+    meta.strings("tokenizer.ggml.tokens", &vocab.tokens);
+    if let Some(scores) = &vocab.scores {
+        meta.f32s("tokenizer.ggml.scores", scores);
+    }
+    meta.i32s("tokenizer.ggml.token_type", &vocab.token_types);
+    if !vocab.merges.is_empty() {
+        meta.strings("tokenizer.ggml.merges", &vocab.merges);
+    }
+    if let Some(bos) = token_id(&config.bos_token_id) {
+        meta.u32("tokenizer.ggml.bos_token_id", bos);
+    }
+    if let Some(eos) = token_id(&config.eos_token_id) {
+        meta.u32("tokenizer.ggml.eos_token_id", eos);
+    }
+    if let Some(unk) = vocab.unk_id {
+        meta.u32("tokenizer.ggml.unknown_token_id", unk);
+    }
+    if let Some(add_bos) = tokenizer_config.add_bos_token {
+        meta.bool("tokenizer.ggml.add_bos_token", add_bos);
+    }
+    if let Some(Value::String(template)) = &tokenizer_config.chat_template {
+        meta.string("tokenizer.chat_template", template);
+    }
+
+    let partial = output.with_extension("gguf.partial");
+    let write_error = |e: std::io::Error| format!("Failed to write {}: {}", partial.display(), e);
+    let mut writer = BufWriter::new(File::create(&partial).map_err(write_error)?);
+
+    let mut header = Vec::new();
+    header.extend_from_slice(GGUF_MAGIC);
+    header.extend_from_slice(&GGUF_VERSION.to_le_bytes());
+    header.extend_from_slice(&(tensors.len() as u64).to_le_bytes());
+    header.extend_from_slice(&meta.count.to_le_bytes());
+    header.extend_from_slice(&meta.buf);
+    for tensor in &tensors {
+        put_str(&mut header, &tensor.name);
+        header.extend_from_slice(&(tensor.ne.len() as u32).to_le_bytes());
+        for &dim in &tensor.ne {
+            header.extend_from_slice(&(dim as u64).to_le_bytes());
+        }
+        header.extend_from_slice(&tensor.ggml_type.id().to_le_bytes());
+        header.extend_from_slice(&(tensor.offset as u64).to_le_bytes());
+    }
+    writer.write_all(&header).map_err(write_error)?;
+    pad(&mut writer, header.len()).map_err(write_error)?;
+
+    let total = tensors.len();
+    for (done, tensor) in tensors.iter().enumerate() {
+        let mut data = read_tensor_f32(&tensor.source)?;
+        let rotary_heads = match tensor.name.rsplit('.').nth(1) {
+            Some("attn_q") => Some(config.num_attention_heads),
+            Some("attn_k") => Some(config.n_head_kv()),
+            _ => None,
+        };
+        if let (Architecture::Llama, Some(n_head), true) =
+            (arch, rotary_heads, tensor.ne.len() == 2)
+        {
+            data = permute_rotary(&data, n_head, tensor.ne[0]);
+        }
+        let bytes = tensor.ggml_type.quantize(&data, tensor.ne[0]);
+        writer.write_all(&bytes).map_err(write_error)?;
+        pad(&mut writer, bytes.len()).map_err(write_error)?;
+        on_tensor(&tensor.name, done + 1, total);
+    }
+
+    writer.flush().map_err(write_error)?;
+    drop(writer);
+    std::fs::rename(&partial, output).map_err(write_error)?;
+    Ok(total)
+}
+
+impl HuggingFaceManager {
+    /// Subscribe to conversion progress
+    pub fn subscribe_conversion_progress(&self) -> broadcast::Receiver<ConversionProgress> {
+        self.conversion_events.subscribe()
+    }
+
+    /// Convert a downloaded safetensors model to GGUF at `quantization`
+    ///
+    /// The output is written next to the safetensors files as
+    /// `<repo>-<quantization>.gguf`, where the local model scan picks it up.
+    pub async fn convert_to_gguf(
+        &self,
+        model_id: &str,
+        quantization: GgufQuantization,
+    ) -> Result<LocalModelInfo, String> {
+        let (model_dir, models_dir) = {
+            let config = self.config.read().await;
+            (
+                config.models_dir.join(model_id.replace('/', "__")),
+                config.models_dir.clone(),
+            )
+        };
+        if !model_dir.is_dir() {
+            return Err(format!("Model {} has not been downloaded", model_id));
+        }
+        let repo = model_id.rsplit('/').next().unwrap_or(model_id);
+        let output = model_dir.join(merged_model_filename(repo, quantization));
+
+        let events = self.conversion_events.clone();
+        let base = ConversionProgress {
+            model_id: model_id.to_string(),
+            quantization,
+            tensor: None,
+            tensors_done: 0,
+            tensors_total: 0,
+            status: ConversionStatus::Converting,
+            output_path: None,
+            error: None,
+        };
+
+        info!("Converting {} to GGUF {}", model_id, quantization.as_str());
+        let result = {
+            let events = events.clone();
+            let base = base.clone();
+            let (model_dir, output) = (model_dir.clone(), output.clone());
+            tokio::task::spawn_blocking(move || {
+                convert_model(&model_dir, &output, quantization, |tensor, done, total| {
+                    let _ = events.send(ConversionProgress {
+                        tensor: Some(tensor.to_string()),
+                        tensors_done: done,
+                        tensors_total: total,
+                        ..base.clone()
+                    });
+                })
+            })
+            .await
+            .map_err(|e| format!("Conversion task failed: {}", e))
+            .and_then(|result| result)
+        };
+
+        let converted = result.and_then(|tensors| {
+            self.create_local_model_info(&output, &models_dir)
+                .map(|model| (model, tensors))
+                .ok_or_else(|| format!("Converted model missing at {}", output.display()))
+        });
+        match converted {
+            Ok((model, tensors)) => {
+                info!("Converted {} to {}", model_id, output.display());
+                let _ = events.send(ConversionProgress {
+                    tensors_done: tensors,
+                    tensors_total: tensors,
+                    status: ConversionStatus::Completed,
+                    output_path: Some(output),
+                    ..base
+                });
+                Ok(model)
+            }
+            Err(e) => {
+                warn!("Conversion of {} failed: {}", model_id, e);
+                let _ = std::fs::remove_file(output.with_extension("gguf.partial"));
+                let _ = events.send(ConversionProgress {
+                    status: ConversionStatus::Failed,
+                    error: Some(e.clone()),
+                    ..base
+                });
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn write_safetensors(path: &Path, tensors: &[(&str, Vec<usize>)]) {
+        let mut header = serde_json::Map::new();
+        let mut data = Vec::new();
+        for (name, shape) in tensors {
+            let count: usize = shape.iter().product();
+            let start = data.len();
+            for i in 0..count {
+                data.extend_from_slice(&((i % 17) as f32 / 17.0 - 0.5).to_le_bytes());
+            }
+            header.insert(
+                name.to_string(),
+                json!({"dtype": "F32", "shape": shape, "data_offsets": [start, data.len()]}),
+            );
+        }
+        let header = serde_json::to_vec(&header).unwrap();
+        let mut file = File::create(path).unwrap();
+        file.write_all(&(header.len() as u64).to_le_bytes())
+            .unwrap();
+        file.write_all(&header).unwrap();
+        file.write_all(&data).unwrap();
+    }
+
+    fn read_u64(bytes: &[u8], at: &mut usize) -> u64 {
+        let value = u64::from_le_bytes(bytes[*at..*at + 8].try_into().unwrap());
+        *at += 8;
+        value
+    }
+
+    fn read_string(bytes: &[u8], at: &mut usize) -> String {
+        let len = read_u64(bytes, at) as usize;
+        let value = String::from_utf8(bytes[*at..*at + len].to_vec()).unwrap();
+        *at += len;
+        value
+    }
+
+    #[test]
+    fn test_permute_rotary_interleaves_halves() {
+        // One head of 4 rows: halves [0, 1] and [2, 3] become pairs (0, 2), (1, 3)
+        let data = vec![0.0, 1.0, 2.0, 3.0];
+        assert_eq!(permute_rotary(&data, 1, 1), vec![0.0, 2.0, 1.0, 3.0]);
+    }
+
+    #[test]
+    fn test_tensor_names() {
+        assert_eq!(
+            gguf_tensor_name("model.layers.3.self_attn.k_proj.bias")
+                .unwrap()
+                .as_deref(),
+            Some("blk.3.attn_k.bias")
+        );
+        assert_eq!(
+            gguf_tensor_name("model.layers.0.mlp.down_proj.weight")
+                .unwrap()
+                .as_deref(),
+            Some("blk.0.ffn_down.weight")
+        );
+        assert_eq!(
+            gguf_tensor_name("model.layers.0.self_attn.rotary_emb.inv_freq").unwrap(),
+            None
+        );
+        assert!(gguf_tensor_name("model.layers.0.mlp.experts.weight").is_err());
+    }
+
+    #[test]
+    fn test_convert_tiny_llama() {
+        let dir = tempfile::tempdir().unwrap();
+        let model_dir = dir.path().join("test__tiny-llama");
+        std::fs::create_dir_all(&model_dir).unwrap();
+        std::fs::write(
+            model_dir.join("config.json"),
+            json!({
+                "architectures": ["LlamaForCausalLM"],
+                "hidden_size": 256,
+                "intermediate_size": 512,
+                "num_hidden_layers": 2,
+                "num_attention_heads": 4,
+                "num_key_value_heads": 2,
+                "rms_norm_eps": 1e-5,
+                "max_position_embeddings": 2048,
+                "vocab_size": 8,
+                "bos_token_id": 1,
+                "eos_token_id": [2]
+            })
+            .to_string(),
+        )
+        .unwrap();
+        std::fs::write(
+            model_dir.join("tokenizer.json"),
+            json!({
+                "added_tokens": [{"id": 1, "content": "<s>", "special": true}],
+                "model": {
+                    "type": "BPE",
+                    "byte_fallback": true,
+                    "unk_token": "<unk>",
+                    "vocab": {"<unk>": 0, "<s>": 1, "</s>": 2, "<0x0A>": 3, "▁": 4, "a": 5, "▁a": 6},
+                    "merges": ["▁ a"]
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let mut tensors = vec![
+            ("model.embed_tokens.weight", vec![8, 256]),
+            ("model.norm.weight", vec![256]),
+            ("lm_head.weight", vec![8, 256]),
+        ];
+        let names: Vec<[String; 9]> = (0..2)
+            .map(|layer| {
+                [
+                    "input_layernorm.weight",
+                    "self_attn.q_proj.weight",
+                    "self_attn.k_proj.weight",
+                    "self_attn.v_proj.weight",
+                    "self_attn.o_proj.weight",
+                    "post_attention_layernorm.weight",
+                    "mlp.gate_proj.weight",
+                    "mlp.up_proj.weight",
+                    "mlp.down_proj.weight",
+                ]
+                .map(|t| format!("model.layers.{}.{}", layer, t))
+            })
+            .collect();
+        for layer in &names {
+            tensors.extend([
+                (layer[0].as_str(), vec![256]),
+                (layer[1].as_str(), vec![256, 256]),
+                (layer[2].as_str(), vec![128, 256]),
+                (layer[3].as_str(), vec![128, 256]),
+                (layer[4].as_str(), vec![256, 256]),
+                (layer[5].as_str(), vec![256]),
+                (layer[6].as_str(), vec![512, 256]),
+                (layer[7].as_str(), vec![512, 256]),
+                (layer[8].as_str(), vec![256, 512]),
+            ]);
+        }
+        write_safetensors(&model_dir.join("model.safetensors"), &tensors);
+
+        let output = model_dir.join("tiny-llama-Q4_K_M.gguf");
+        let mut seen = Vec::new();
+        let written = convert_model(
+            &model_dir,
+            &output,
+            GgufQuantization::Q4_K_M,
+            |tensor, done, total| {
+                seen.push((tensor.to_string(), done, total));
+            },
+        )
+        .unwrap();
+        assert_eq!(written, 21);
+        assert_eq!(seen.len(), 21);
+        assert_eq!(seen.last().unwrap().1, 21);
+        assert_eq!(seen[0].0, "output.weight");
+        assert!(!output.with_extension("gguf.partial").exists());
+
+        let bytes = std::fs::read(&output).unwrap();
+        assert_eq!(&bytes[..4], b"GGUF");
+        let mut at = 8;
+        assert_eq!(read_u64(&bytes, &mut at), 21);
+        let kv_count = read_u64(&bytes, &mut at);
+        assert!(kv_count >= 18);
+        assert_eq!(read_string(&bytes, &mut at), "general.architecture");
+        assert_eq!(
+            u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap()),
+            GGUF_TYPE_STRING
+        );
+        at += 4;
+        assert_eq!(read_string(&bytes, &mut at), "llama");
+
+        // Data starts aligned and the file ends with the last tensor's padding
+        let last = seen.last().unwrap();
+        assert_eq!(last.0, "blk.1.ffn_up.weight");
+        assert_eq!(bytes.len() % GGUF_ALIGNMENT, 0);
+    }
+}
@@ -0,0 +1,885 @@
+//! HuggingFace Datasets
+//!
+//! Dataset search and download for LoRA training. Downloaded rows are
+//! converted on the fly into one of the [`DatasetFormat`]s accepted by
+//! `create_lora_job` and written one line at a time, so large datasets never
+//! have to fit in memory:
+//!
+//! - JSON Lines files in the dataset repo are streamed and converted line by line
+//! - any other layout (Parquet, CSV, sharded splits) is paged through the
+//!   datasets-server rows API
+//!
+//! The source schema is detected from the first recognisable row. Supported
+//! schemas are Alpaca style `instruction`/`input`/`output` (including Dolly's
+//! `context`/`response`), `prompt`/`completion`, `question`/`answer`, ShareGPT
+//! `conversations`, OpenAI style `messages` and plain `text`.
+
+use super::{DownloadProgress, DownloadStatus, HFModelFile, HuggingFaceManager, HF_API_BASE};
+use crate::models::DatasetFormat;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+use tracing::info;
+
+const HF_DATASETS_SERVER: &str = "https://datasets-server.huggingface.co";
+
+/// Largest page returned by the rows API
+const ROWS_PAGE_SIZE: usize = 100;
+
+/// Search parameters for datasets
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DatasetSearchParams {
+    pub search: Option<String>,
+    pub author: Option<String>,
+    pub filter: Option<String>,
+    pub sort: Option<String>,
+    pub direction: Option<String>,
+    pub limit: Option<u32>,
+}
+
+/// HuggingFace dataset info
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HFDatasetInfo {
+    pub id: String,
+    pub author: Option<String>,
+    pub sha: Option<String>,
+    #[serde(rename = "lastModified")]
+    pub last_modified: Option<String>,
+    pub private: Option<bool>,
+    pub disabled: Option<bool>,
+    /// `false`, `"auto"` or `"manual"`
+    pub gated: Option<Value>,
+    pub description: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub downloads: Option<u64>,
+    pub likes: Option<u64>,
+    #[serde(rename = "cardData")]
+    pub card_data: Option<Value>,
+    pub siblings: Option<Vec<HFModelFile>>,
+}
+
+/// What to download and how to convert it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetDownloadParams {
+    pub dataset_id: String,
+    /// JSON Lines file in the dataset repo to stream; the rows API is used when unset
+    pub file: Option<String>,
+    /// Dataset config (subset) for the rows API, defaults to the first one with `split`
+    pub config: Option<String>,
+    /// Split to download, defaults to `train`
+    pub split: Option<String>,
+    /// Training format to convert to
+    #[serde(default)]
+    pub format: DatasetFormat,
+    /// Stop after this many converted rows
+    pub max_rows: Option<usize>,
+}
+
+/// Source schemas recognised in HF datasets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DatasetSchema {
+    /// `instruction`, optional `input`/`context`, `output`/`response`
+    Instruction,
+    /// `prompt`, `completion`/`response`
+    PromptCompletion,
+    /// `question`, `answer`
+    QuestionAnswer,
+    /// `conversations` (ShareGPT) or `messages` (OpenAI chat)
+    Conversations,
+    /// `text`
+    Text,
+}
+
+/// Result of a dataset download
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetDownloadResult {
+    pub dataset_id: String,
+    /// Converted file, ready for `create_lora_job`
+    pub path: String,
+    pub format: DatasetFormat,
+    /// Schema detected in the source rows
+    pub source_schema: Option<DatasetSchema>,
+    pub rows_converted: usize,
+    /// Source rows that did not match the schema or the target format
+    pub rows_skipped: usize,
+}
+
+/// One source row in a schema-independent shape
+#[derive(Debug, Clone, PartialEq)]
+enum Example {
+    Instruction {
+        instruction: String,
+        input: String,
+        output: String,
+    },
+    /// ShareGPT style `(from, value)` turns
+    Conversation(Vec<(String, String)>),
+    Text(String),
+}
+
+/// First non-empty string among `names`
+fn field<'a>(row: &'a Value, names: &[&str]) -> Option<&'a str> {
+    names
+        .iter()
+        .filter_map(|name| row.get(*name).and_then(|v| v.as_str()))
+        .find(|s| !s.trim().is_empty())
+}
+
+fn turns(row: &Value) -> Option<&Vec<Value>> {
+    row.get("conversations")
+        .or_else(|| row.get("messages"))
+        .and_then(|v| v.as_array())
+}
+
+/// Detect the schema of a source row
+pub fn detect_schema(row: &Value) -> Option<DatasetSchema> {
+    if turns(row).is_some() {
+        Some(DatasetSchema::Conversations)
+    } else if field(row, &["instruction"]).is_some()
+        && field(row, &["output", "response"]).is_some()
+    {
+        Some(DatasetSchema::Instruction)
+    } else if field(row, &["prompt"]).is_some() && field(row, &["completion", "response"]).is_some()
+    {
+        Some(DatasetSchema::PromptCompletion)
+    } else if field(row, &["question"]).is_some() && field(row, &["answer"]).is_some() {
+        Some(DatasetSchema::QuestionAnswer)
+    } else if field(row, &["text"]).is_some() {
+        Some(DatasetSchema::Text)
+    } else {
+        None
+    }
+}
+
+/// Map OpenAI and ShareGPT speaker names onto ShareGPT's
+fn sharegpt_role(role: &str) -> Option<&'static str> {
+    match role.to_lowercase().as_str() {
+        "system" => Some("system"),
+        "human" | "user" => Some("human"),
+        "gpt" | "assistant" | "bot" | "model" => Some("gpt"),
+        _ => None,
+    }
+}
+
+fn parse_example(row: &Value, schema: DatasetSchema) -> Option<Example> {
+    let instruction = |instruction: &[&str], input: &[&str], output: &[&str]| {
+        Some(Example::Instruction {
+            instruction: field(row, instruction)?.to_string(),
+            input: field(row, input).unwrap_or_default().to_string(),
+            output: field(row, output)?.to_string(),
+        })
+    };
+
+    match schema {
+        DatasetSchema::Instruction => instruction(
+            &["instruction"],
+            &["input", "context"],
+            &["output", "response"],
+        ),
+        DatasetSchema::PromptCompletion => {
+            instruction(&["prompt"], &[], &["completion", "response"])
+        }
+        DatasetSchema::QuestionAnswer => instruction(&["question"], &["context"], &["answer"]),
+        DatasetSchema::Conversations => {
+            let conversation: Vec<(String, String)> = turns(row)?
+                .iter()
+                .filter_map(|turn| {
+                    let role = sharegpt_role(field(turn, &["from", "role"])?)?;
+                    Some((
+                        role.to_string(),
+                        field(turn, &["value", "content"])?.to_string(),
+                    ))
+                })
+                .collect();
+            conversation
+                .iter()
+                .any(|(role, _)| role == "gpt")
+                .then_some(Example::Conversation(conversation))
+        }
+        DatasetSchema::Text => field(row, &["text"]).map(|text| Example::Text(text.to_string())),
+    }
+}
+
+/// Render an example as one line of `format`, if it can be represented in it
+fn to_format(example: Example, format: &DatasetFormat) -> Option<Value> {
+    match (example, format) {
+        (
+            Example::Instruction {
+                instruction,
+                input,
+                output,
+            },
+            DatasetFormat::Alpaca | DatasetFormat::Jsonl,
+        ) => Some(json!({ "instruction": instruction, "input": input, "output": output })),
+        (
+            Example::Instruction {
+                instruction,
+                input,
+                output,
+            },
+            DatasetFormat::ShareGPT,
+        ) => {
+            let prompt = if input.is_empty() {
+                instruction
+            } else {
+                format!("{}\n\n{}", instruction, input)
+            };
+            Some(json!({ "conversations": [
+                { "from": "human", "value": prompt },
+                { "from": "gpt", "value": output },
+            ]}))
+        }
+        (Example::Conversation(turns), DatasetFormat::Alpaca) => {
+            // First exchange only; Alpaca has no room for multi-turn context
+            let human = turns.iter().position(|(role, _)| role == "human")?;
+            let (_, output) = turns[human..].iter().find(|(role, _)| role == "gpt")?;
+            Some(json!({ "instruction": turns[human].1, "input": "", "output": output }))
+        }
+        (Example::Conversation(turns), DatasetFormat::ShareGPT) => {
+            let conversations: Vec<Value> = turns
+                .into_iter()
+                .map(|(from, value)| json!({ "from": from, "value": value }))
+                .collect();
+            Some(json!({ "conversations": conversations }))
+        }
+        (Example::Conversation(turns), DatasetFormat::Jsonl) => {
+            let text: Vec<String> = turns
+                .into_iter()
+                .map(|(role, value)| {
+                    let speaker = match role.as_str() {
+                        "system" => "System",
+                        "human" => "User",
+                        _ => "Assistant",
+                    };
+                    format!("{}: {}", speaker, value)
+                })
+                .collect();
+            Some(json!({ "text": text.join("\n") }))
+        }
+        (Example::Text(text), DatasetFormat::Jsonl) => Some(json!({ "text": text })),
+        _ => None,
+    }
+}
+
+/// Converts source rows into lines of a training format, detecting the
+/// source schema from the first row that has one
+pub struct RowConverter {
+    format: DatasetFormat,
+    schema: Option<DatasetSchema>,
+    pub converted: usize,
+    pub skipped: usize,
+}
+
+impl RowConverter {
+    /// Fails for formats that are not written line by line
+    pub fn new(format: DatasetFormat) -> Result<Self, String> {
+        match format {
+            DatasetFormat::Jsonl | DatasetFormat::Alpaca | DatasetFormat::ShareGPT => Ok(Self {
+                format,
+                schema: None,
+                converted: 0,
+                skipped: 0,
+            }),
+            other => Err(format!(
+                "Cannot convert datasets to {:?}; use Jsonl, Alpaca or ShareGPT",
+                other
+            )),
+        }
+    }
+
+    pub fn schema(&self) -> Option<DatasetSchema> {
+        self.schema
+    }
+
+    /// Convert one source row
+    pub fn convert(&mut self, row: &Value) -> Option<String> {
+        if self.schema.is_none() {
+            self.schema = detect_schema(row);
+        }
+        let line = self
+            .schema
+            .and_then(|schema| parse_example(row, schema))
+            .and_then(|example| to_format(example, &self.format))
+            .map(|value| value.to_string());
+        match line {
+            Some(_) => self.converted += 1,
+            None => self.skipped += 1,
+        }
+        line
+    }
+
+    /// Convert one line of a JSON Lines source; blank lines are ignored
+    pub fn convert_line(&mut self, line: &str) -> Option<String> {
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+        match serde_json::from_str::<Value>(line) {
+            Ok(row) => self.convert(&row),
+            Err(_) => {
+                self.skipped += 1;
+                None
+            }
+        }
+    }
+}
+
+/// Page of the datasets-server rows API
+#[derive(Debug, Deserialize)]
+struct RowsPage {
+    rows: Vec<RowEntry>,
+    num_rows_total: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RowEntry {
+    row: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct SplitsResponse {
+    splits: Vec<SplitEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SplitEntry {
+    config: String,
+    split: String,
+}
+
+/// A dataset download in progress
+struct Transfer<'a> {
+    dataset_id: &'a str,
+    filename: &'a str,
+    key: &'a str,
+    max_rows: Option<usize>,
+}
+
+impl Transfer<'_> {
+    fn is_full(&self, converter: &RowConverter) -> bool {
+        self.max_rows.is_some_and(|max| converter.converted >= max)
+    }
+}
+
+async fn write_line(file: &mut tokio::fs::File, mut line: String) -> Result<(), String> {
+    line.push('\n');
+    file.write_all(line.as_bytes())
+        .await
+        .map_err(|e| format!("Write error: {}", e))
+}
+
+impl HuggingFaceManager {
+    async fn authorized_get(&self, url: &str) -> reqwest::RequestBuilder {
+        let mut request = self.http_client.get(url);
+        if let Some(ref token) = self.auth_state.read().await.token {
+            request = request.bearer_auth(&token.access_token);
+        }
+        request
+    }
+
+    async fn is_cancelled(&self, key: &str) -> bool {
+        *self
+            .download_cancellations
+            .read()
+            .await
+            .get(key)
+            .unwrap_or(&false)
+    }
+
+    /// Search datasets
+    pub async fn search_datasets(
+        &self,
+        params: DatasetSearchParams,
+    ) -> Result<Vec<HFDatasetInfo>, String> {
+        let mut url = format!("{}/datasets", HF_API_BASE);
+        let mut query_params = Vec::new();
+
+        if let Some(ref search) = params.search {
+            query_params.push(format!("search={}", urlencoding::encode(search)));
+        }
+        if let Some(ref author) = params.author {
+            query_params.push(format!("author={}", urlencoding::encode(author)));
+        }
+        if let Some(ref filter) = params.filter {
+            query_params.push(format!("filter={}", urlencoding::encode(filter)));
+        }
+        if let Some(ref sort) = params.sort {
+            query_params.push(format!("sort={}", sort));
+        }
+        if let Some(ref direction) = params.direction {
+            query_params.push(format!("direction={}", direction));
+        }
+        if let Some(limit) = params.limit {
+            query_params.push(format!("limit={}", limit));
+        }
+
+        if !query_params.is_empty() {
+            url = format!("{}?{}", url, query_params.join("&"));
+        }
+
+        let response = self
+            .authorized_get(&url)
+            .await
+            .send()
+            .await
+            .map_err(|e| format!("Dataset search failed: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Dataset search failed: {}", response.status()));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse datasets: {}", e))
+    }
+
+    /// Directory converted datasets are written to, next to the models directory
+    pub async fn get_datasets_dir(&self) -> PathBuf {
+        self.config
+            .read()
+            .await
+            .models_dir
+            .with_file_name("datasets")
+    }
+
+    /// Download a dataset split, converting it to `params.format` as it streams in
+    pub async fn download_dataset(
+        &self,
+        params: DatasetDownloadParams,
+    ) -> Result<DatasetDownloadResult, String> {
+        let mut converter = RowConverter::new(params.format.clone())?;
+        let split = params.split.clone().unwrap_or_else(|| "train".to_string());
+
+        let dataset_dir = self
+            .get_datasets_dir()
+            .await
+            .join(params.dataset_id.replace('/', "__"));
+        tokio::fs::create_dir_all(&dataset_dir)
+            .await
+            .map_err(|e| format!("Failed to create dataset directory: {}", e))?;
+
+        let filename = format!(
+            "{}.{}.jsonl",
+            split,
+            format!("{:?}", params.format).to_lowercase()
+        );
+        let file_path = dataset_dir.join(&filename);
+        let partial_path = dataset_dir.join(format!("{}.partial", filename));
+        let key = format!("{}:{}", params.dataset_id, filename);
+
+        self.download_cancellations
+            .write()
+            .await
+            .insert(key.clone(), false);
+        self.downloads.write().await.push(DownloadProgress {
+            model_id: params.dataset_id.clone(),
+            filename: filename.clone(),
+            downloaded: 0,
+            total: 0,
+            status: DownloadStatus::Pending,
+        });
+
+        let transfer = Transfer {
+            dataset_id: &params.dataset_id,
+            filename: &filename,
+            key: &key,
+            max_rows: params.max_rows,
+        };
+
+        let outcome = match tokio::fs::File::create(&partial_path).await {
+            Ok(mut file) => {
+                let streamed = match params.file {
+                    Some(ref source) => {
+                        self.stream_dataset_file(&transfer, source, &mut converter, &mut file)
+                            .await
+                    }
+                    None => {
+                        self.stream_dataset_rows(
+                            &transfer,
+                            params.config.as_deref(),
+                            &split,
+                            &mut converter,
+                            &mut file,
+                        )
+                        .await
+                    }
+                };
+                match streamed {
+                    Ok(()) if converter.converted == 0 => Err(format!(
+                        "No rows of {} could be converted to {:?}",
+                        params.dataset_id, params.format
+                    )),
+                    Ok(()) => file
+                        .flush()
+                        .await
+                        .map_err(|e| format!("Flush error: {}", e)),
+                    Err(e) => Err(e),
+                }
+            }
+            Err(e) => Err(format!("Failed to create file: {}", e)),
+        };
+
+        let cancelled = self
+            .download_cancellations
+            .write()
+            .await
+            .remove(&key)
+            .unwrap_or(false);
+        if let Err(e) = outcome {
+            let status = if cancelled {
+                DownloadStatus::Cancelled
+            } else {
+                DownloadStatus::Failed
+            };
+            self.update_download_status(&params.dataset_id, &filename, status)
+                .await;
+            let _ = tokio::fs::remove_file(&partial_path).await;
+            return Err(e);
+        }
+
+        tokio::fs::rename(&partial_path, &file_path)
+            .await
+            .map_err(|e| format!("Failed to finalize download: {}", e))?;
+        self.update_download_status(&params.dataset_id, &filename, DownloadStatus::Completed)
+            .await;
+
+        info!(
+            "Dataset {} converted to {:?}: {} rows ({} skipped) at {:?}",
+            params.dataset_id, params.format, converter.converted, converter.skipped, file_path
+        );
+
+        Ok(DatasetDownloadResult {
+            dataset_id: params.dataset_id,
+            path: file_path.to_string_lossy().to_string(),
+            format: params.format,
+            source_schema: converter.schema(),
+            rows_converted: converter.converted,
+            rows_skipped: converter.skipped,
+        })
+    }
+
+    /// Stream a JSON Lines file from the dataset repo; progress is in bytes
+    async fn stream_dataset_file(
+        &self,
+        transfer: &Transfer<'_>,
+        source: &str,
+        converter: &mut RowConverter,
+        file: &mut tokio::fs::File,
+    ) -> Result<(), String> {
+        if !source.ends_with(".jsonl") {
+            return Err(format!(
+                "Only JSON Lines files can be streamed directly ({}); leave the file unset to use the rows API",
+                source
+            ));
+        }
+
+        let url = format!(
+            "https://huggingface.co/datasets/{}/resolve/main/{}",
+            transfer.dataset_id, source
+        );
+        info!("Streaming dataset {} to {}", url, transfer.filename);
+
+        let response = self
+            .authorized_get(&url)
+            .await
+            .send()
+            .await
+            .map_err(|e| format!("Download failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Download failed: {}", response.status()));
+        }
+
+        self.update_download_total(
+            transfer.dataset_id,
+            transfer.filename,
+            response.content_length().unwrap_or(0),
+        )
+        .await;
+        self.update_download_status(
+            transfer.dataset_id,
+            transfer.filename,
+            DownloadStatus::Downloading,
+        )
+        .await;
+
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut downloaded: u64 = 0;
+        let mut stream = response.bytes_stream();
+        let mut last_progress_update = std::time::Instant::now();
+
+        while let Some(chunk_result) = stream.next().await {
+            if self.is_cancelled(transfer.key).await {
+                return Err("Download cancelled by user".to_string());
+            }
+
+            let chunk = chunk_result.map_err(|e| format!("Download error: {}", e))?;
+            downloaded += chunk.len() as u64;
+            buffer.extend_from_slice(&chunk);
+
+            while let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                if let Some(converted) = converter.convert_line(&String::from_utf8_lossy(&line)) {
+                    write_line(file, converted).await?;
+                }
+                if transfer.is_full(converter) {
+                    return Ok(());
+                }
+            }
+
+            if last_progress_update.elapsed().as_millis() >= 100 {
+                self.update_download_progress(transfer.dataset_id, transfer.filename, downloaded)
+                    .await;
+                last_progress_update = std::time::Instant::now();
+            }
+        }
+
+        // Last line without a trailing newline
+        if let Some(converted) = converter.convert_line(&String::from_utf8_lossy(&buffer)) {
+            write_line(file, converted).await?;
+        }
+        self.update_download_progress(transfer.dataset_id, transfer.filename, downloaded)
+            .await;
+        Ok(())
+    }
+
+    /// Page through a split with the rows API; progress is in rows
+    async fn stream_dataset_rows(
+        &self,
+        transfer: &Transfer<'_>,
+        config: Option<&str>,
+        split: &str,
+        converter: &mut RowConverter,
+        file: &mut tokio::fs::File,
+    ) -> Result<(), String> {
+        let config = match config {
+            Some(config) => config.to_string(),
+            None => {
+                self.dataset_config_for_split(transfer.dataset_id, split)
+                    .await?
+            }
+        };
+        info!(
+            "Paging dataset {} ({}/{}) to {}",
+            transfer.dataset_id, config, split, transfer.filename
+        );
+        self.update_download_status(
+            transfer.dataset_id,
+            transfer.filename,
+            DownloadStatus::Downloading,
+        )
+        .await;
+
+        let mut offset: u64 = 0;
+        loop {
+            if self.is_cancelled(transfer.key).await {
+                return Err("Download cancelled by user".to_string());
+            }
+
+            let url = format!(
+                "{}/rows?dataset={}&config={}&split={}&offset={}&length={}",
+                HF_DATASETS_SERVER,
+                urlencoding::encode(transfer.dataset_id),
+                urlencoding::encode(&config),
+                urlencoding::encode(split),
+                offset,
+                ROWS_PAGE_SIZE
+            );
+            let response = self
+                .authorized_get(&url)
+                .await
+                .send()
+                .await
+                .map_err(|e| format!("Download failed: {}", e))?;
+            if !response.status().is_success() {
+                return Err(format!("Download failed: {}", response.status()));
+            }
+            let page: RowsPage = response
+                .json()
+                .await
+                .map_err(|e| format!("Failed to parse dataset rows: {}", e))?;
+
+            let total = page.num_rows_total.unwrap_or(0);
+            if offset == 0 {
+                self.update_download_total(transfer.dataset_id, transfer.filename, total)
+                    .await;
+            }
+            if page.rows.is_empty() {
+                return Ok(());
+            }
+
+            offset += page.rows.len() as u64;
+            for entry in page.rows {
+                if let Some(converted) = converter.convert(&entry.row) {
+                    write_line(file, converted).await?;
+                }
+                if transfer.is_full(converter) {
+                    return Ok(());
+                }
+            }
+            self.update_download_progress(transfer.dataset_id, transfer.filename, offset)
+                .await;
+
+            if total > 0 && offset >= total {
+                return Ok(());
+            }
+        }
+    }
+
+    /// First config of the dataset that has `split`
+    async fn dataset_config_for_split(
+        &self,
+        dataset_id: &str,
+        split: &str,
+    ) -> Result<String, String> {
+        let url = format!(
+            "{}/splits?dataset={}",
+            HF_DATASETS_SERVER,
+            urlencoding::encode(dataset_id)
+        );
+        let response = self
+            .authorized_get(&url)
+            .await
+            .send()
+            .await
+            .map_err(|e| format!("Failed to get dataset splits: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Failed to get dataset splits: {}",
+                response.status()
+            ));
+        }
+        let splits: SplitsResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse dataset splits: {}", e))?;
+
+        splits
+            .splits
+            .into_iter()
+            .find(|s| s.split == split)
+            .map(|s| s.config)
+            .ok_or_else(|| format!("Split '{}' not found in dataset {}", split, dataset_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(format: DatasetFormat, rows: &[Value]) -> (Vec<Value>, RowConverter) {
+        let mut converter = RowConverter::new(format).unwrap();
+        let lines = rows
+            .iter()
+            .filter_map(|row| converter.convert(row))
+            .map(|line| serde_json::from_str(&line).unwrap())
+            .collect();
+        (lines, converter)
+    }
+
+    #[test]
+    fn test_detect_schema() {
+        let cases = [
+            (
+                json!({"instruction": "a", "input": "", "output": "b"}),
+                Some(DatasetSchema::Instruction),
+            ),
+            (
+                json!({"instruction": "a", "context": "c", "response": "b"}),
+                Some(DatasetSchema::Instruction),
+            ),
+            (
+                json!({"prompt": "a", "completion": "b"}),
+                Some(DatasetSchema::PromptCompletion),
+            ),
+            (
+                json!({"question": "a", "answer": "b"}),
+                Some(DatasetSchema::QuestionAnswer),
+            ),
+            (
+                json!({"messages": [{"role": "user", "content": "a"}]}),
+                Some(DatasetSchema::Conversations),
+            ),
+            (json!({"text": "a"}), Some(DatasetSchema::Text)),
+            (json!({"label": 1}), None),
+        ];
+        for (row, expected) in cases {
+            assert_eq!(detect_schema(&row), expected, "{}", row);
+        }
+    }
+
+    #[test]
+    fn test_instruction_rows_to_each_format() {
+        let rows =
+            [json!({"instruction": "Summarize", "context": "Long text", "response": "Short"})];
+
+        let (alpaca, _) = convert(DatasetFormat::Alpaca, &rows);
+        assert_eq!(
+            alpaca[0],
+            json!({"instruction": "Summarize", "input": "Long text", "output": "Short"})
+        );
+
+        let (sharegpt, _) = convert(DatasetFormat::ShareGPT, &rows);
+        assert_eq!(
+            sharegpt[0]["conversations"][0]["value"],
+            "Summarize\n\nLong text"
+        );
+        assert_eq!(
+            sharegpt[0]["conversations"][1],
+            json!({"from": "gpt", "value": "Short"})
+        );
+    }
+
+    #[test]
+    fn test_chat_messages_to_each_format() {
+        let rows = [json!({"messages": [
+            {"role": "system", "content": "Be brief"},
+            {"role": "user", "content": "Hi"},
+            {"role": "assistant", "content": "Hello"},
+            {"role": "tool", "content": "ignored"},
+        ]})];
+
+        let (sharegpt, _) = convert(DatasetFormat::ShareGPT, &rows);
+        let conversations = sharegpt[0]["conversations"].as_array().unwrap();
+        assert_eq!(conversations.len(), 3);
+        assert_eq!(conversations[1], json!({"from": "human", "value": "Hi"}));
+
+        let (alpaca, _) = convert(DatasetFormat::Alpaca, &rows);
+        assert_eq!(
+            alpaca[0],
+            json!({"instruction": "Hi", "input": "", "output": "Hello"})
+        );
+
+        let (jsonl, _) = convert(DatasetFormat::Jsonl, &rows);
+        assert_eq!(
+            jsonl[0]["text"],
+            "System: Be brief\nUser: Hi\nAssistant: Hello"
+        );
+    }
+
+    #[test]
+    fn test_unconvertible_rows_are_skipped() {
+        // Plain text cannot become an instruction pair
+        let (lines, converter) = convert(
+            DatasetFormat::Alpaca,
+            &[json!({"text": "a"}), json!({"text": "b"})],
+        );
+        assert!(lines.is_empty());
+        assert_eq!((converter.converted, converter.skipped), (0, 2));
+
+        // Schema comes from the first row; rows missing its fields are skipped
+        let mut converter = RowConverter::new(DatasetFormat::Jsonl).unwrap();
+        assert!(converter
+            .convert_line(r#"{"prompt": "p", "completion": "c"}"#)
+            .is_some());
+        assert!(converter.convert_line(r#"{"prompt": "p"}"#).is_none());
+        assert!(converter.convert_line("not json").is_none());
+        assert!(converter.convert_line("   ").is_none());
+        assert_eq!(converter.schema(), Some(DatasetSchema::PromptCompletion));
+        assert_eq!((converter.converted, converter.skipped), (1, 2));
+
+        assert!(RowConverter::new(DatasetFormat::Parquet).is_err());
+    }
+}
@@ -8,6 +8,8 @@
 //! - Model search with GGUF filtering
 //! - Resumable downloads with progress tracking
 //! - Auto-detection of downloaded models
+//! - Dataset search and download with conversion to LoRA training formats
+//! - Local safetensors to GGUF conversion and quantization
 //! - Tauri event emission for real-time progress updates
 
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
@@ -20,9 +22,15 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tracing::{debug, error, info, warn};
 
+pub mod convert;
+pub mod datasets;
+
+pub use convert::{ConversionProgress, ConversionStatus, GGUF_CONVERSION_EVENT};
+pub use datasets::{DatasetDownloadParams, DatasetDownloadResult, DatasetSearchParams, HFDatasetInfo};
+
 /// HuggingFace API base URL
 const HF_API_BASE: &str = "https://huggingface.co/api";
 const HF_AUTH_URL: &str = "https://huggingface.co/oauth/authorize";
@@ -312,6 +320,8 @@ pub struct HuggingFaceManager {
     pkce_challenges: Arc<RwLock<HashMap<String, PkceChallenge>>>,
     /// Active download cancellation tokens
     download_cancellations: Arc<RwLock<HashMap<String, bool>>>,
+    /// Progress of GGUF conversions
+    conversion_events: broadcast::Sender<convert::ConversionProgress>,
 }
 
 impl HuggingFaceManager {
@@ -328,6 +338,7 @@ impl HuggingFaceManager {
             .connect_timeout(std::time::Duration::from_secs(10))
             .build()
             .unwrap_or_else(|_| Client::new());
+        let (conversion_events, _) = broadcast::channel(256);
 
         Self {
             config: Arc::new(RwLock::new(config)),
@@ -336,6 +347,7 @@ impl HuggingFaceManager {
             downloads: Arc::new(RwLock::new(Vec::new())),
             pkce_challenges: Arc::new(RwLock::new(HashMap::new())),
             download_cancellations: Arc::new(RwLock::new(HashMap::new())),
+            conversion_events,
         }
     }
 
@@ -0,0 +1,513 @@
+//! Localization of user-facing backend strings
+//!
+//! Text returned by Tauri commands (onboarding questions, status messages and
+//! errors) is looked up by key in the JSON catalogs under `locales/`. A key
+//! missing from the selected locale falls back to English, and a key missing
+//! from English is returned as is.
+//!
+//! Most errors are still produced as plain English by the layers below the
+//! commands. [`localize`] recognises those messages against a table of known
+//! patterns and swaps in the catalog text, so a command only needs
+//! `.map_err(localize_err)` at its boundary. Unrecognised messages pass
+//! through unchanged.
+//!
+//! The selected locale is stored in `i18n.json` in the app config directory.
+//! Without a saved choice the system locale is used when it has a catalog.
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::PathBuf;
+
+/// Look up a catalog entry in the current locale, filling placeholders from
+/// the arguments: `tr!("key", count = n)` fills `{count}`, `tr!("key", 0 = x)`
+/// fills `{0}`
+#[macro_export]
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::i18n::translate($key, &[])
+    };
+    ($key:expr, $($name:tt = $value:expr),+ $(,)?) => {
+        $crate::i18n::translate($key, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+
+/// Locales with a catalog
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+    Zh,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::En, Locale::Es, Locale::Zh];
+
+    /// Language code, as used by the frontend
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Es => "es",
+            Locale::Zh => "zh",
+        }
+    }
+
+    /// Name of the language in that language
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::Es => "Español",
+            Locale::Zh => "中文",
+        }
+    }
+
+    /// Parse a language tag such as `es`, `es-MX` or `zh_CN.UTF-8`
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.split(['-', '_', '.']).next()?.to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.code() == language)
+    }
+
+    fn catalog_source(self) -> &'static str {
+        match self {
+            Locale::En => include_str!("locales/en.json"),
+            Locale::Es => include_str!("locales/es.json"),
+            Locale::Zh => include_str!("locales/zh.json"),
+        }
+    }
+}
+
+/// A selectable locale, for the language picker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleOption {
+    pub code: Locale,
+    pub name: String,
+}
+
+/// Current locale and the locales to choose from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleInfo {
+    pub current: Locale,
+    pub available: Vec<LocaleOption>,
+}
+
+/// Persisted locale choice
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct I18nConfig {
+    #[serde(default)]
+    locale: Option<Locale>,
+}
+
+impl I18nConfig {
+    fn path() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("citrate-core")
+            .join("i18n.json")
+    }
+
+    fn load() -> Self {
+        std::fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| e.to_string())
+    }
+}
+
+/// Raw backend messages and the catalog entries they translate to. Each `{}`
+/// matches any text, which the entry receives as `{0}`, `{1}`, ... in order.
+/// More specific patterns must come before patterns they are a prefix of.
+const MESSAGE_PATTERNS: &[(&str, &str)] = &[
+    (
+        "Node is not running. Please start the node first.",
+        "error.node_not_running_start",
+    ),
+    ("Node is not running", "error.node_not_running"),
+    (
+        "Node not started - mempool unavailable",
+        "error.mempool_unavailable",
+    ),
+    (
+        "IPFS daemon is not running. Please start IPFS first.",
+        "error.ipfs_not_running_start",
+    ),
+    ("IPFS daemon is not running", "error.ipfs_not_running"),
+    ("Agent not initialized", "error.agent_not_initialized"),
+    ("Network is disabled in config", "error.network_disabled"),
+    ("Wallet is not available", "error.wallet_unavailable"),
+    ("Wallet already has accounts", "error.wallet_has_accounts"),
+    ("Account not found", "error.account_not_found"),
+    ("Account already exists", "error.account_exists"),
+    ("Key not found for address", "error.key_not_found"),
+    ("Invalid password", "error.invalid_password"),
+    ("Invalid backup password", "error.invalid_backup_password"),
+    ("Not a Citrate wallet backup", "error.not_a_backup"),
+    (
+        "Unsupported backup version {}",
+        "error.unsupported_backup_version",
+    ),
+    ("Invalid mnemonic: {}", "error.invalid_mnemonic"),
+    (
+        "Invalid private key length",
+        "error.invalid_private_key_length",
+    ),
+    (
+        "Rate limit exceeded for {}. Please wait {} seconds.",
+        "error.rate_limited",
+    ),
+    (
+        "Account locked due to too many failed attempts. Please wait {} seconds.",
+        "error.account_locked_attempts",
+    ),
+    (
+        "Account locked due to {} failed attempts. Please wait {} seconds.",
+        "error.account_locked_count",
+    ),
+    (
+        "Account locked. Please wait {} seconds before trying again.",
+        "error.account_locked",
+    ),
+    (
+        "Session expired. Please re-enter your password to continue.",
+        "error.session_expired",
+    ),
+    (
+        "Session expired or not active. Please enter your password.",
+        "error.session_inactive",
+    ),
+    (
+        "High-value transaction requires password re-authentication.",
+        "error.high_value_reauth",
+    ),
+    (
+        "Password does not meet security requirements:",
+        "error.password_requirements",
+    ),
+    (
+        "Password must be at least 12 characters (currently {})",
+        "password.too_short",
+    ),
+    (
+        "Password must contain at least one uppercase letter",
+        "password.needs_uppercase",
+    ),
+    (
+        "Password must contain at least one lowercase letter",
+        "password.needs_lowercase",
+    ),
+    (
+        "Password must contain at least one digit",
+        "password.needs_digit",
+    ),
+    (
+        "Password must contain at least one special character (!@#$%^&*...)",
+        "password.needs_special",
+    ),
+    (
+        "Password contains common weak pattern: '{}'",
+        "password.weak_pattern",
+    ),
+    (
+        "Password should avoid sequential characters (abc, 123, etc.)",
+        "password.sequential",
+    ),
+    ("Invalid gas price: {}", "error.invalid_gas_price"),
+    ("Invalid value: {}", "error.invalid_value"),
+    ("Invalid amount: {}", "error.invalid_amount"),
+    ("Amount must be greater than zero", "error.amount_zero"),
+    ("'to' address must be 20 bytes", "error.to_address_length"),
+    (
+        "'from' address must be 20 bytes",
+        "error.from_address_length",
+    ),
+    (
+        "Transaction replacement is disabled in the mempool config",
+        "error.replacement_disabled",
+    ),
+    (
+        "No scheduled payment with id '{}'",
+        "error.payment_not_found",
+    ),
+    (
+        "Payment is not waiting for approval",
+        "error.payment_not_awaiting_approval",
+    ),
+    ("Payment is not paused", "error.payment_not_paused"),
+    ("Payment has already completed", "error.payment_completed"),
+    (
+        "End date is before the first payment",
+        "error.payment_end_before_start",
+    ),
+    (
+        "Choose the account to pay from",
+        "error.payment_choose_account",
+    ),
+    ("No contact named '{}'", "error.contact_not_found"),
+    (
+        "A contact named '{}' already exists",
+        "error.contact_exists",
+    ),
+    (
+        "Contact name cannot be an address",
+        "error.contact_name_is_address",
+    ),
+    (
+        "Start the node to resolve '{}'",
+        "error.resolve_requires_node",
+    ),
+    (
+        "No update available; check first",
+        "error.no_update_available",
+    ),
+    ("Profile '{}' not found", "error.profile_not_found"),
+    (
+        "Project directory does not exist: {}",
+        "error.project_not_found",
+    ),
+    ("Model file not found: {}", "error.model_file_not_found"),
+    ("Model must be a .gguf file", "error.model_not_gguf"),
+    ("Invalid peer address format", "error.invalid_peer_address"),
+    ("Invalid bootnode format", "error.invalid_bootnode"),
+];
+
+/// Names under which pattern captures are passed to catalog entries
+const CAPTURE_NAMES: [&str; 4] = ["0", "1", "2", "3"];
+
+static CATALOGS: Lazy<HashMap<Locale, HashMap<String, String>>> = Lazy::new(|| {
+    Locale::ALL
+        .into_iter()
+        .map(|locale| {
+            let catalog = serde_json::from_str(locale.catalog_source())
+                .unwrap_or_else(|e| panic!("invalid {} catalog: {}", locale.code(), e));
+            (locale, catalog)
+        })
+        .collect()
+});
+
+static CURRENT: Lazy<RwLock<Locale>> = Lazy::new(|| RwLock::new(initial_locale()));
+
+fn initial_locale() -> Locale {
+    // Tests compare against the English text regardless of the machine
+    if cfg!(test) {
+        return Locale::En;
+    }
+    I18nConfig::load()
+        .locale
+        .or_else(system_locale)
+        .unwrap_or_default()
+}
+
+/// Locale from the POSIX environment, if it has a catalog
+fn system_locale() -> Option<Locale> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|tag| Locale::from_tag(&tag))
+}
+
+/// The locale user-facing strings are currently returned in
+pub fn current() -> Locale {
+    *CURRENT.read()
+}
+
+/// Switch the locale and remember the choice across restarts
+pub fn set_locale(locale: Locale) -> Result<(), String> {
+    I18nConfig {
+        locale: Some(locale),
+    }
+    .save()?;
+    *CURRENT.write() = locale;
+    Ok(())
+}
+
+/// Current locale and the available ones
+pub fn locale_info() -> LocaleInfo {
+    LocaleInfo {
+        current: current(),
+        available: Locale::ALL
+            .into_iter()
+            .map(|locale| LocaleOption {
+                code: locale,
+                name: locale.native_name().to_string(),
+            })
+            .collect(),
+    }
+}
+
+/// Look up `key` in the current locale. Prefer the [`tr!`] macro.
+pub fn translate(key: &str, args: &[(&str, String)]) -> String {
+    translate_in(current(), key, args)
+}
+
+/// Look up `key` in `locale`, falling back to English and then to the key
+/// itself, and fill `{name}` placeholders from `args`
+pub fn translate_in(locale: Locale, key: &str, args: &[(&str, String)]) -> String {
+    let text = CATALOGS[&locale]
+        .get(key)
+        .or_else(|| CATALOGS[&Locale::En].get(key))
+        .map(String::as_str)
+        .unwrap_or(key);
+
+    args.iter().fold(text.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+/// Translate a raw backend message into the current locale
+pub fn localize(message: &str) -> String {
+    localize_in(current(), message)
+}
+
+/// For `.map_err(localize_err)` at a command boundary
+pub fn localize_err(error: impl Display) -> String {
+    localize(&error.to_string())
+}
+
+/// Translate a raw backend message into `locale`. Messages made of several
+/// lines, such as a heading followed by `- ` bullets, are translated line by
+/// line.
+pub fn localize_in(locale: Locale, message: &str) -> String {
+    for (pattern, key) in MESSAGE_PATTERNS {
+        if let Some(captures) = match_pattern(pattern, message) {
+            let args: Vec<(&str, String)> = CAPTURE_NAMES
+                .iter()
+                .copied()
+                .zip(captures.into_iter().map(str::to_string))
+                .collect();
+            return translate_in(locale, key, &args);
+        }
+    }
+
+    if message.contains('\n') {
+        return message
+            .lines()
+            .map(|line| match line.strip_prefix("- ") {
+                Some(item) => format!("- {}", localize_in(locale, item)),
+                None => localize_in(locale, line),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
+    message.to_string()
+}
+
+/// Match `message` against `pattern`, returning the text matched by each `{}`
+fn match_pattern<'a>(pattern: &str, message: &'a str) -> Option<Vec<&'a str>> {
+    let mut literals = pattern.split("{}");
+    let mut rest = message.strip_prefix(literals.next()?)?;
+    let literals: Vec<&str> = literals.collect();
+    let mut captures = Vec::with_capacity(literals.len());
+
+    for (i, literal) in literals.iter().enumerate() {
+        if i + 1 == literals.len() {
+            captures.push(rest.strip_suffix(literal)?);
+            rest = "";
+        } else {
+            let at = rest.find(literal)?;
+            captures.push(&rest[..at]);
+            rest = &rest[at + literal.len()..];
+        }
+    }
+
+    rest.is_empty().then_some(captures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalogs_cover_english_keys() {
+        let english = &CATALOGS[&Locale::En];
+        for locale in [Locale::Es, Locale::Zh] {
+            let missing: Vec<_> = english
+                .keys()
+                .filter(|key| !CATALOGS[&locale].contains_key(*key))
+                .collect();
+            assert!(missing.is_empty(), "{:?} is missing {:?}", locale, missing);
+        }
+        for (_, key) in MESSAGE_PATTERNS {
+            assert!(english.contains_key(*key), "no English entry for {}", key);
+        }
+    }
+
+    #[test]
+    fn test_translate_falls_back_to_english_then_key() {
+        assert_eq!(
+            translate_in(Locale::Es, "error.account_not_found", &[]),
+            "Cuenta no encontrada"
+        );
+        assert_eq!(translate_in(Locale::Zh, "no.such.key", &[]), "no.such.key");
+        assert_eq!(
+            translate_in(
+                Locale::En,
+                "onboarding.invalid_response",
+                &[("count", "3".to_string())]
+            ),
+            "I didn't catch that. Please reply with a number from 1 to 3."
+        );
+    }
+
+    #[test]
+    fn test_localize_raw_messages() {
+        assert_eq!(
+            localize_in(
+                Locale::Es,
+                "Rate limit exceeded for unlock. Please wait 30 seconds."
+            ),
+            "Demasiados intentos de unlock. Espera 30 segundos."
+        );
+        // The specific pattern wins over its prefix
+        assert_eq!(
+            localize_in(
+                Locale::Zh,
+                "Node is not running. Please start the node first."
+            ),
+            "节点未运行。请先启动节点。"
+        );
+        assert_eq!(
+            localize_in(
+                Locale::Es,
+                "Password does not meet security requirements:\n\
+                 - Password must contain at least one digit\n\
+                 - Something new"
+            ),
+            "La contraseña no cumple los requisitos de seguridad:\n\
+             - La contraseña debe contener al menos un número\n\
+             - Something new"
+        );
+        assert_eq!(localize_in(Locale::Es, "disk full"), "disk full");
+        // English output is unchanged
+        assert_eq!(
+            localize_in(Locale::En, "Invalid mnemonic: unknown word"),
+            "Invalid mnemonic: unknown word"
+        );
+    }
+
+    #[test]
+    fn test_locale_from_tag() {
+        assert_eq!(Locale::from_tag("es-MX"), Some(Locale::Es));
+        assert_eq!(Locale::from_tag("zh_CN.UTF-8"), Some(Locale::Zh));
+        assert_eq!(Locale::from_tag("EN"), Some(Locale::En));
+        assert_eq!(Locale::from_tag("C.UTF-8"), None);
+        assert_eq!(Locale::from_tag("fr_FR"), None);
+    }
+}
@@ -0,0 +1,420 @@
+//! Diffusion Pipeline
+//!
+//! Runs generation jobs with the stable-diffusion.cpp CLI (`sd`), in one of
+//! three modes picked from the request:
+//!
+//! - text-to-image: the prompt alone
+//! - img2img: an input image is noised according to `strength` and only the
+//!   remaining denoising steps run, so low strengths stay close to the input
+//! - inpainting: an input image plus a mask; only the white areas of the
+//!   mask are regenerated
+//!
+//! The CLI reports progress as `| step/total` lines, which are turned into
+//! per-step progress events carrying the latest preview image when the CLI
+//! writes one (`--preview`, available in current stable-diffusion.cpp builds).
+
+use super::{ImageGenerationRequest, Scheduler};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Tauri event emitted after every denoising step of a generation job
+pub const IMAGE_GENERATION_PROGRESS_EVENT: &str = "image-generation-progress";
+
+/// Strength used for img2img when the request does not set one
+const DEFAULT_IMG2IMG_STRENGTH: f32 = 0.75;
+
+/// Largest uploaded input image or mask
+const MAX_UPLOAD_BYTES: usize = 32 * 1024 * 1024;
+
+/// How a request is run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GenerationMode {
+    TextToImage,
+    ImageToImage,
+    Inpainting,
+}
+
+impl GenerationMode {
+    pub fn of(request: &ImageGenerationRequest) -> Result<Self, String> {
+        match (&request.input_image, &request.mask_image) {
+            (None, None) => Ok(Self::TextToImage),
+            (Some(_), None) => Ok(Self::ImageToImage),
+            (Some(_), Some(_)) => Ok(Self::Inpainting),
+            (None, Some(_)) => Err("Inpainting needs an input image for the mask".to_string()),
+        }
+    }
+}
+
+/// Denoising steps actually run for a request
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StrengthSchedule {
+    pub strength: f32,
+    /// Steps of the full schedule skipped because the input image already
+    /// stands in for them
+    pub start_step: u32,
+    /// Steps run, from `start_step` to the end of the schedule
+    pub steps: u32,
+}
+
+/// Steps run for `num_steps` at `strength`. Text-to-image always runs the
+/// whole schedule; img2img defaults to 0.75 and inpainting to 1.0.
+pub fn strength_schedule(
+    mode: GenerationMode,
+    num_steps: u32,
+    strength: Option<f32>,
+) -> Result<StrengthSchedule, String> {
+    if num_steps == 0 {
+        return Err("Number of steps must be at least 1".to_string());
+    }
+    let strength = match mode {
+        GenerationMode::TextToImage => 1.0,
+        GenerationMode::ImageToImage => strength.unwrap_or(DEFAULT_IMG2IMG_STRENGTH),
+        GenerationMode::Inpainting => strength.unwrap_or(1.0),
+    };
+    if !(strength > 0.0 && strength <= 1.0) {
+        return Err(format!("Strength must be in (0, 1], got {}", strength));
+    }
+    let steps = ((num_steps as f32 * strength).round() as u32).clamp(1, num_steps);
+    Ok(StrengthSchedule {
+        strength,
+        start_step: num_steps - steps,
+        steps,
+    })
+}
+
+/// Progress of a generation job, sent after every step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationProgress {
+    pub job_id: String,
+    pub step: u32,
+    pub total_steps: u32,
+    pub progress: f32,
+    /// Latest preview as base64 PNG, if the CLI wrote one
+    pub preview: Option<String>,
+}
+
+/// Write an uploaded image into `dir` as `name`. The upload is either the
+/// path of an existing file or base64 data, optionally as a data URL.
+pub fn stage_image(input: &str, dir: &Path, name: &str) -> Result<PathBuf, String> {
+    let path = Path::new(input);
+    if input.len() < 4096 && path.is_file() {
+        return Ok(path.to_path_buf());
+    }
+
+    let encoded = match input.split_once(";base64,") {
+        Some((_, data)) => data,
+        None => input,
+    };
+    let bytes = BASE64
+        .decode(encoded.trim())
+        .map_err(|e| format!("{} is neither a file nor base64 image data: {}", name, e))?;
+    if bytes.len() > MAX_UPLOAD_BYTES {
+        return Err(format!(
+            "{} is {} bytes, the limit is {}",
+            name,
+            bytes.len(),
+            MAX_UPLOAD_BYTES
+        ));
+    }
+    let extension = if png_dimensions(&bytes).is_some() {
+        "png"
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        "jpg"
+    } else {
+        return Err(format!("{} must be a PNG or JPEG image", name));
+    };
+
+    let staged = dir.join(format!("{}.{}", name, extension));
+    std::fs::write(&staged, bytes).map_err(|e| format!("Failed to save {}: {}", name, e))?;
+    Ok(staged)
+}
+
+/// Width and height from a PNG header
+pub(super) fn png_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if bytes.len() < 24 || !bytes.starts_with(SIGNATURE) || &bytes[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(bytes[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(bytes[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+/// Check that a mask covers the image it applies to. Only PNGs carry their
+/// size in a header that is cheap to read; other formats are left to the CLI.
+pub fn validate_mask(image: &Path, mask: &Path) -> Result<(), String> {
+    let read = |path: &Path| {
+        std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+    };
+    let image_size = png_dimensions(&read(image)?);
+    let mask_size = png_dimensions(&read(mask)?);
+    match (image_size, mask_size) {
+        (Some(image), Some(mask)) if image != mask => Err(format!(
+            "Mask is {}x{} but the input image is {}x{}",
+            mask.0, mask.1, image.0, image.1
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Sampler and sigma schedule names understood by the CLI
+fn sampling_method(scheduler: Scheduler) -> (&'static str, Option<&'static str>) {
+    match scheduler {
+        Scheduler::EulerAncestral => ("euler_a", None),
+        Scheduler::Euler => ("euler", None),
+        Scheduler::DPMPlusPlus2MKarras => ("dpm++2m", Some("karras")),
+        Scheduler::DPMPlusPlusSDEKarras => ("dpm++2s_a", Some("karras")),
+        Scheduler::DDIM => ("ddim_trailing", None),
+        Scheduler::PNDM => ("ipndm", None),
+        Scheduler::LMS => ("euler", None),
+    }
+}
+
+/// Files a CLI run reads and writes
+pub struct SdPaths {
+    pub model: PathBuf,
+    pub output: PathBuf,
+    pub preview: PathBuf,
+    pub init_image: Option<PathBuf>,
+    pub mask: Option<PathBuf>,
+    /// Folder the request's LoRA adapters are loaded from
+    pub lora_dir: Option<PathBuf>,
+}
+
+/// Prompt with the request's LoRA adapters appended as `<lora:id:weight>`
+/// tags, the form the CLI applies them in
+pub fn prompt_with_loras(request: &ImageGenerationRequest) -> String {
+    request
+        .lora_weights
+        .iter()
+        .fold(request.prompt.clone(), |prompt, lora| {
+            format!("{} <lora:{}:{}>", prompt, lora.adapter_id, lora.weight)
+        })
+}
+
+/// Command line for a request
+pub fn sd_args(
+    request: &ImageGenerationRequest,
+    schedule: &StrengthSchedule,
+    paths: &SdPaths,
+) -> Vec<String> {
+    let (sampler, sigmas) = sampling_method(request.scheduler);
+    let mut args = vec![
+        "-m".to_string(),
+        paths.model.display().to_string(),
+        "-p".to_string(),
+        prompt_with_loras(request),
+        "-W".to_string(),
+        request.resolution.width.to_string(),
+        "-H".to_string(),
+        request.resolution.height.to_string(),
+        "--steps".to_string(),
+        request.num_steps.to_string(),
+        "--cfg-scale".to_string(),
+        request.guidance_scale.to_string(),
+        "--sampling-method".to_string(),
+        sampler.to_string(),
+        "-b".to_string(),
+        request.num_images.max(1).to_string(),
+        "-s".to_string(),
+        // The CLI picks a random seed for negative values
+        request
+            .seed
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "-1".to_string()),
+        "-o".to_string(),
+        paths.output.display().to_string(),
+        "--preview".to_string(),
+        "proj".to_string(),
+        "--preview-path".to_string(),
+        paths.preview.display().to_string(),
+    ];
+    if let Some(sigmas) = sigmas {
+        args.extend(["--schedule".to_string(), sigmas.to_string()]);
+    }
+    if let Some(negative) = request.negative_prompt.as_deref().filter(|n| !n.is_empty()) {
+        args.extend(["-n".to_string(), negative.to_string()]);
+    }
+    if let Some(init) = &paths.init_image {
+        args.extend([
+            "-M".to_string(),
+            "img2img".to_string(),
+            "-i".to_string(),
+            init.display().to_string(),
+            "--strength".to_string(),
+            schedule.strength.to_string(),
+        ]);
+    }
+    if let Some(mask) = &paths.mask {
+        args.extend(["--mask".to_string(), mask.display().to_string()]);
+    }
+    if let Some(lora_dir) = &paths.lora_dir {
+        args.extend([
+            "--lora-model-dir".to_string(),
+            lora_dir.display().to_string(),
+        ]);
+    }
+    args
+}
+
+/// `(step, total)` from a CLI progress line such as
+/// `  |=====>     | 5/20 - 1.52s/it`
+pub fn parse_progress(line: &str) -> Option<(u32, u32)> {
+    let (_, after_bar) = line.rsplit_once('|')?;
+    let counter = after_bar.split_whitespace().next()?;
+    let (step, total) = counter.split_once('/')?;
+    let step = step.parse().ok()?;
+    let total: u32 = total.parse().ok()?;
+    (total > 0 && step <= total).then_some((step, total))
+}
+
+/// Files the CLI writes for a batch of `count` images: the output path for
+/// the first image and `<stem>_<n>.<ext>` for the rest
+pub fn output_paths(output: &Path, count: u32) -> Vec<PathBuf> {
+    let stem = output
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "output".to_string());
+    let extension = output
+        .extension()
+        .map(|e| e.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "png".to_string());
+    (0..count.max(1))
+        .map(|i| match i {
+            0 => output.to_path_buf(),
+            _ => output.with_file_name(format!("{}_{}.{}", stem, i + 1, extension)),
+        })
+        .collect()
+}
+
+/// Locate the stable-diffusion.cpp CLI
+pub fn find_sd_binary() -> Result<PathBuf, String> {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let candidates = [
+        home_dir.join("stable-diffusion.cpp/build/bin/sd"),
+        home_dir.join("stable-diffusion.cpp/build/bin/sd-cli"),
+    ];
+    candidates
+        .into_iter()
+        .find(|path| path.exists())
+        .ok_or_else(|| {
+            "stable-diffusion.cpp not found. Please build it in ~/stable-diffusion.cpp".to_string()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image_models::ImageResolution;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&[8, 6, 0, 0, 0]);
+        bytes
+    }
+
+    #[test]
+    fn test_strength_schedule() {
+        let text = strength_schedule(GenerationMode::TextToImage, 30, Some(0.3)).unwrap();
+        assert_eq!((text.start_step, text.steps), (0, 30));
+
+        let img2img = strength_schedule(GenerationMode::ImageToImage, 30, None).unwrap();
+        assert_eq!(img2img.strength, 0.75);
+        assert_eq!((img2img.start_step, img2img.steps), (7, 23));
+
+        let faint = strength_schedule(GenerationMode::ImageToImage, 30, Some(0.01)).unwrap();
+        assert_eq!(faint.steps, 1);
+
+        let inpaint = strength_schedule(GenerationMode::Inpainting, 20, None).unwrap();
+        assert_eq!((inpaint.start_step, inpaint.steps), (0, 20));
+
+        assert!(strength_schedule(GenerationMode::ImageToImage, 30, Some(0.0)).is_err());
+        assert!(strength_schedule(GenerationMode::ImageToImage, 30, Some(1.5)).is_err());
+    }
+
+    #[test]
+    fn test_mode_and_args() {
+        let mut request = ImageGenerationRequest {
+            prompt: "a lighthouse".to_string(),
+            resolution: ImageResolution::square_512(),
+            seed: Some(7),
+            input_image: Some("in".to_string()),
+            mask_image: Some("mask".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            GenerationMode::of(&request).unwrap(),
+            GenerationMode::Inpainting
+        );
+
+        let schedule = strength_schedule(GenerationMode::Inpainting, 30, Some(0.6)).unwrap();
+        let args = sd_args(
+            &request,
+            &schedule,
+            &SdPaths {
+                model: "/m.safetensors".into(),
+                output: "/out/image.png".into(),
+                preview: "/out/preview.png".into(),
+                init_image: Some("/out/input.png".into()),
+                mask: Some("/out/mask.png".into()),
+                lora_dir: None,
+            },
+        );
+        let joined = args.join(" ");
+        assert!(joined.contains("-M img2img -i /out/input.png --strength 0.6"));
+        assert!(joined.contains("--mask /out/mask.png"));
+        assert!(joined.contains("-s 7"));
+
+        request.input_image = None;
+        assert!(GenerationMode::of(&request).is_err());
+    }
+
+    #[test]
+    fn test_parse_progress() {
+        assert_eq!(
+            parse_progress("  |=====>     | 5/20 - 1.52s/it"),
+            Some((5, 20))
+        );
+        assert_eq!(
+            parse_progress("[INFO ] stable-diffusion.cpp:1234 - sampling"),
+            None
+        );
+        assert_eq!(parse_progress("| 21/20"), None);
+    }
+
+    #[test]
+    fn test_stage_image_and_mask() {
+        let dir = tempfile::tempdir().unwrap();
+        let encoded = format!("data:image/png;base64,{}", BASE64.encode(png(64, 32)));
+        let image = stage_image(&encoded, dir.path(), "input").unwrap();
+        assert_eq!(image, dir.path().join("input.png"));
+        // A path to an existing file is used as is
+        assert_eq!(
+            stage_image(image.to_str().unwrap(), dir.path(), "again").unwrap(),
+            image
+        );
+
+        let mask = stage_image(&BASE64.encode(png(32, 32)), dir.path(), "mask").unwrap();
+        assert!(validate_mask(&image, &image).is_ok());
+        assert!(validate_mask(&image, &mask).unwrap_err().contains("32x32"));
+
+        assert!(stage_image(&BASE64.encode(b"GIF89a"), dir.path(), "gif").is_err());
+        assert!(stage_image("not base64!", dir.path(), "junk").is_err());
+    }
+
+    #[test]
+    fn test_output_paths() {
+        let paths = output_paths(Path::new("/out/image.png"), 3);
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("/out/image.png"),
+                PathBuf::from("/out/image_2.png"),
+                PathBuf::from("/out/image_3.png"),
+            ]
+        );
+    }
+}
@@ -12,14 +12,29 @@
 //! ├── Training Manager (fine-tuning jobs)
 //! └── Gallery Manager (generated images)
 //! ```
+//!
+//! Generation jobs run through the diffusion pipeline in [`diffusion`];
+//! concept LoRA training jobs run through [`training`] and register the
+//! adapters they produce for generation.
+
+pub mod diffusion;
+pub mod training;
+
+pub use diffusion::{GenerationProgress, IMAGE_GENERATION_PROGRESS_EVENT};
+pub use training::{ConceptReport, TrainingPreview, IMAGE_TRAINING_PREVIEW_EVENT};
 
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use chrono::Utc;
+use diffusion::{GenerationMode, SdPaths};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::io::AsyncReadExt;
+use tokio::sync::{broadcast, RwLock};
 use tracing::{info, warn};
+use training::{TrainingPaths, TrainingSample};
 
 // ============================================================================
 // Types
@@ -143,10 +158,14 @@ pub struct ImageGenerationRequest {
     pub num_steps: u32,
     /// Scheduler type
     pub scheduler: Scheduler,
-    /// Optional input image for img2img
+    /// Optional input image for img2img: a file path or base64 image data
     pub input_image: Option<String>,
-    /// Denoising strength for img2img
+    /// Denoising strength for img2img, in (0, 1]
     pub strength: Option<f32>,
+    /// Optional inpainting mask for the input image, in the same form;
+    /// white areas are regenerated
+    #[serde(default)]
+    pub mask_image: Option<String>,
     /// LoRA weights to apply
     pub lora_weights: Vec<LoRAWeight>,
 }
@@ -165,6 +184,7 @@ impl Default for ImageGenerationRequest {
             scheduler: Scheduler::EulerAncestral,
             input_image: None,
             strength: None,
+            mask_image: None,
             lora_weights: vec![],
         }
     }
@@ -179,6 +199,29 @@ pub struct LoRAWeight {
     pub weight: f32,
 }
 
+/// A trained LoRA adapter generation jobs can apply
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageLora {
+    /// Adapter ID, also its file name in the LoRA directory
+    pub id: String,
+    /// Human-readable name
+    pub name: String,
+    /// Adapter file
+    pub path: String,
+    /// Model the adapter was trained on
+    pub base_model_id: String,
+    /// Architecture of the base model; the adapter only applies to it
+    pub architecture: ImageArchitecture,
+    /// Prompt that invokes the trained concept
+    pub instance_prompt: String,
+    /// LoRA rank
+    pub rank: u32,
+    /// Training job that produced the adapter
+    pub training_job_id: Option<String>,
+    /// Creation timestamp
+    pub created_at: u64,
+}
+
 /// Scheduler/sampler types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Scheduler {
@@ -284,6 +327,27 @@ pub struct ImageTrainingConfig {
     pub gradient_checkpointing: bool,
     /// Use mixed precision
     pub mixed_precision: bool,
+    /// Class images for prior preservation; missing ones are generated from
+    /// the class prompt with the base model
+    #[serde(default)]
+    pub regularization_dir: Option<String>,
+    /// Class images trained against when a class prompt is set
+    #[serde(default = "default_num_class_images")]
+    pub num_class_images: u32,
+    /// Steps between sample images; 0 turns samples off
+    #[serde(default = "default_sample_every_steps")]
+    pub sample_every_steps: u32,
+    /// Prompts sampled during training; the instance prompt when empty
+    #[serde(default)]
+    pub sample_prompts: Vec<String>,
+}
+
+fn default_num_class_images() -> u32 {
+    100
+}
+
+fn default_sample_every_steps() -> u32 {
+    100
 }
 
 impl Default for ImageTrainingConfig {
@@ -302,6 +366,10 @@ impl Default for ImageTrainingConfig {
             resolution: ImageResolution::square_512(),
             gradient_checkpointing: true,
             mixed_precision: true,
+            regularization_dir: None,
+            num_class_images: default_num_class_images(),
+            sample_every_steps: default_sample_every_steps(),
+            sample_prompts: vec![],
         }
     }
 }
@@ -342,6 +410,15 @@ pub struct ImageTrainingJob {
     pub config: ImageTrainingConfig,
     /// Current status
     pub status: TrainingStatus,
+    /// Concept validation the job was queued with
+    #[serde(default)]
+    pub report: Option<ConceptReport>,
+    /// Sample images written so far
+    #[serde(default)]
+    pub samples: Vec<TrainingSample>,
+    /// Adapter registered once training completes
+    #[serde(default)]
+    pub lora_id: Option<String>,
     /// Created timestamp
     pub created_at: u64,
     /// Completed timestamp
@@ -366,6 +443,14 @@ pub struct ImageModelManager {
     models_dir: PathBuf,
     /// Output directory
     output_dir: PathBuf,
+    /// Per-step generation progress
+    progress_events: broadcast::Sender<GenerationProgress>,
+    /// Trained LoRA adapters
+    loras: Arc<RwLock<HashMap<String, ImageLora>>>,
+    /// Directory trained adapters are kept in
+    loras_dir: PathBuf,
+    /// Sample images written during training
+    training_previews: broadcast::Sender<TrainingPreview>,
 }
 
 impl ImageModelManager {
@@ -374,14 +459,19 @@ impl ImageModelManager {
         let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
         let models_dir = home_dir.join(".citrate").join("image_models");
         let output_dir = home_dir.join(".citrate").join("generated_images");
+        let loras_dir = models_dir.join("loras");
 
         // Create directories
         let _ = std::fs::create_dir_all(&models_dir);
         let _ = std::fs::create_dir_all(&output_dir);
+        let _ = std::fs::create_dir_all(&loras_dir);
 
         // Initialize default models synchronously (no tokio runtime needed)
         let mut default_models = HashMap::new();
         Self::add_default_models(&mut default_models);
+        let (progress_events, _) = broadcast::channel(256);
+        let (training_previews, _) = broadcast::channel(64);
+        let loras = Self::load_loras(&loras_dir);
 
         Self {
             models: Arc::new(RwLock::new(default_models)),
@@ -390,9 +480,46 @@ impl ImageModelManager {
             gallery: Arc::new(RwLock::new(Vec::new())),
             models_dir,
             output_dir,
+            progress_events,
+            loras: Arc::new(RwLock::new(loras)),
+            loras_dir,
+            training_previews,
         }
     }
 
+    /// Subscribe to per-step generation progress
+    pub fn subscribe_generation_progress(&self) -> broadcast::Receiver<GenerationProgress> {
+        self.progress_events.subscribe()
+    }
+
+    /// Subscribe to sample images written during training
+    pub fn subscribe_training_previews(&self) -> broadcast::Receiver<TrainingPreview> {
+        self.training_previews.subscribe()
+    }
+
+    /// Adapters recorded by `.json` files next to them in `loras_dir`
+    fn load_loras(loras_dir: &Path) -> HashMap<String, ImageLora> {
+        let mut loras = HashMap::new();
+        for entry in std::fs::read_dir(loras_dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            match std::fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| {
+                    serde_json::from_slice::<ImageLora>(&bytes).map_err(|e| e.to_string())
+                }) {
+                Ok(lora) if Path::new(&lora.path).exists() => {
+                    loras.insert(lora.id.clone(), lora);
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Skipping LoRA record {}: {}", path.display(), e),
+            }
+        }
+        loras
+    }
+
     fn add_default_models(models: &mut HashMap<String, ImageModel>) {
         // Stable Diffusion 1.5
         models.insert(
@@ -551,12 +678,66 @@ impl ImageModelManager {
         found_models
     }
 
+    /// Get all trained LoRA adapters
+    pub async fn get_loras(&self) -> Vec<ImageLora> {
+        self.loras.read().await.values().cloned().collect()
+    }
+
+    /// Get a trained LoRA adapter by ID
+    pub async fn get_lora(&self, lora_id: &str) -> Option<ImageLora> {
+        self.loras.read().await.get(lora_id).cloned()
+    }
+
+    /// Register a trained adapter, recording it next to its file so it is
+    /// available again after a restart
+    pub async fn register_lora(&self, lora: ImageLora) -> Result<(), String> {
+        let record = Path::new(&lora.path).with_extension("json");
+        let json = serde_json::to_vec_pretty(&lora).map_err(|e| e.to_string())?;
+        std::fs::write(&record, json)
+            .map_err(|e| format!("Failed to write {}: {}", record.display(), e))?;
+        info!("Registered image LoRA: {}", lora.name);
+        self.loras.write().await.insert(lora.id.clone(), lora);
+        Ok(())
+    }
+
+    /// Check that a request's adapters exist and fit its model
+    async fn validate_loras(
+        &self,
+        request: &ImageGenerationRequest,
+        model: &ImageModel,
+    ) -> Result<(), String> {
+        for weight in &request.lora_weights {
+            let lora = self
+                .get_lora(&weight.adapter_id)
+                .await
+                .ok_or_else(|| format!("LoRA {} not found", weight.adapter_id))?;
+            if !(0.0..=2.0).contains(&weight.weight) {
+                return Err(format!(
+                    "LoRA weight must be between 0 and 2, got {}",
+                    weight.weight
+                ));
+            }
+            if lora.architecture != model.architecture {
+                return Err(format!(
+                    "LoRA {} was trained for {:?} models, {} is {:?}",
+                    lora.name, lora.architecture, model.name, model.architecture
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// Create a generation job
     pub async fn create_generation_job(&self, request: ImageGenerationRequest) -> Result<String, String> {
         // Validate model exists
-        if self.get_model(&request.model_id).await.is_none() {
-            return Err(format!("Model {} not found", request.model_id));
-        }
+        let model = self
+            .get_model(&request.model_id)
+            .await
+            .ok_or_else(|| format!("Model {} not found", request.model_id))?;
+        self.validate_loras(&request, &model).await?;
+        // Reject bad img2img settings before queueing
+        let mode = GenerationMode::of(&request)?;
+        diffusion::strength_schedule(mode, request.num_steps, request.strength)?;
 
         let job_id = uuid::Uuid::new_v4().to_string();
         let job = GenerationJob {
@@ -600,23 +781,247 @@ impl ImageModelManager {
         }
     }
 
-    /// Create a training job
-    pub async fn create_training_job(&self, config: ImageTrainingConfig) -> Result<String, String> {
-        // Validate base model exists
-        if self.get_model(&config.base_model_id).await.is_none() {
-            return Err(format!("Base model {} not found", config.base_model_id));
+    /// Run a queued generation job, reporting progress after every
+    /// denoising step. Finished images are added to the gallery.
+    pub async fn run_generation_job(&self, job_id: &str) -> Result<(), String> {
+        let result = self.execute_generation(job_id).await;
+
+        if let Some(job) = self.generation_jobs.write().await.get_mut(job_id) {
+            match &result {
+                Ok(images) => {
+                    job.status = GenerationStatus::Completed {
+                        images: images.clone(),
+                    };
+                }
+                // A cancelled job keeps its status
+                Err(_) if matches!(job.status, GenerationStatus::Cancelled) => {}
+                Err(error) => {
+                    job.status = GenerationStatus::Failed {
+                        error: error.clone(),
+                    };
+                }
+            }
+            job.completed_at = Some(Utc::now().timestamp() as u64);
+        }
+
+        for image in result? {
+            self.add_to_gallery(image).await;
+        }
+        Ok(())
+    }
+
+    async fn execute_generation(&self, job_id: &str) -> Result<Vec<GeneratedImage>, String> {
+        let started = std::time::Instant::now();
+        let request = match self.get_generation_job(job_id).await {
+            Some(job) if matches!(job.status, GenerationStatus::Queued) => job.request,
+            Some(_) => return Err(format!("Job {} is not queued", job_id)),
+            None => return Err(format!("Job {} not found", job_id)),
+        };
+        let model = self
+            .get_model(&request.model_id)
+            .await
+            .ok_or_else(|| format!("Model {} not found", request.model_id))?;
+        let model_path = model
+            .path
+            .clone()
+            .filter(|_| model.is_downloaded)
+            .ok_or_else(|| format!("Model {} is not downloaded", model.name))?;
+        let mode = GenerationMode::of(&request)?;
+        let schedule = diffusion::strength_schedule(mode, request.num_steps, request.strength)?;
+        let binary = diffusion::find_sd_binary()?;
+
+        // Uploads, the preview and the outputs are kept per job
+        let job_dir = self.output_dir.join(job_id);
+        std::fs::create_dir_all(&job_dir)
+            .map_err(|e| format!("Failed to create {}: {}", job_dir.display(), e))?;
+        let init_image = request
+            .input_image
+            .as_deref()
+            .map(|input| diffusion::stage_image(input, &job_dir, "input"))
+            .transpose()?;
+        let mask = request
+            .mask_image
+            .as_deref()
+            .map(|mask| diffusion::stage_image(mask, &job_dir, "mask"))
+            .transpose()?;
+        if let (Some(image), Some(mask)) = (&init_image, &mask) {
+            diffusion::validate_mask(image, mask)?;
+        }
+        let paths = SdPaths {
+            model: PathBuf::from(model_path),
+            output: job_dir.join("image.png"),
+            preview: job_dir.join("preview.png"),
+            init_image,
+            mask,
+            lora_dir: (!request.lora_weights.is_empty()).then(|| self.loras_dir.clone()),
+        };
+
+        if !self.set_generation_progress(job_id, 0, schedule.steps).await {
+            return Err("Generation cancelled".to_string());
+        }
+        info!(
+            "Running {:?} job {} with {} of {} steps",
+            mode, job_id, schedule.steps, request.num_steps
+        );
+
+        let mut child = tokio::process::Command::new(&binary)
+            .args(diffusion::sd_args(&request, &schedule, &paths))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to start {}: {}", binary.display(), e))?;
+        let mut stdout = child.stdout.take().ok_or("Generator has no stdout")?;
+        let mut stderr = child.stderr.take().ok_or("Generator has no stderr")?;
+        // Drain stderr alongside so the generator never blocks on it
+        let stderr_task = tokio::spawn(async move {
+            let mut output = Vec::new();
+            let _ = stderr.read_to_end(&mut output).await;
+            output
+        });
+
+        let mut pending = Vec::new();
+        let mut chunk = [0u8; 4096];
+        let mut last_step = 0;
+        loop {
+            let read = stdout
+                .read(&mut chunk)
+                .await
+                .map_err(|e| format!("Failed to read generator output: {}", e))?;
+            if read == 0 {
+                break;
+            }
+            pending.extend_from_slice(&chunk[..read]);
+
+            // The progress bar is redrawn with carriage returns
+            while let Some(end) = pending.iter().position(|b| *b == b'\r' || *b == b'\n') {
+                let line: Vec<u8> = pending.drain(..=end).collect();
+                let Some((step, total_steps)) =
+                    diffusion::parse_progress(&String::from_utf8_lossy(&line))
+                else {
+                    continue;
+                };
+                if step == last_step {
+                    continue;
+                }
+                last_step = step;
+
+                if !self.set_generation_progress(job_id, step, total_steps).await {
+                    let _ = child.kill().await;
+                    return Err("Generation cancelled".to_string());
+                }
+                let preview = std::fs::read(&paths.preview).ok().map(|bytes| BASE64.encode(bytes));
+                let _ = self.progress_events.send(GenerationProgress {
+                    job_id: job_id.to_string(),
+                    step,
+                    total_steps,
+                    progress: step as f32 / total_steps as f32,
+                    preview,
+                });
+            }
+        }
+
+        let status = child
+            .wait()
+            .await
+            .map_err(|e| format!("Generator failed: {}", e))?;
+        let stderr = stderr_task.await.unwrap_or_default();
+        if !status.success() {
+            let stderr = String::from_utf8_lossy(&stderr);
+            let tail: Vec<&str> = stderr.lines().rev().take(5).collect();
+            return Err(format!(
+                "stable-diffusion.cpp exited with {}: {}",
+                status,
+                tail.into_iter().rev().collect::<Vec<_>>().join("\n")
+            ));
+        }
+
+        // Record the staged uploads rather than their base64 data
+        let mut recorded = request.clone();
+        recorded.input_image = paths.init_image.as_ref().map(|p| p.display().to_string());
+        recorded.mask_image = paths.mask.as_ref().map(|p| p.display().to_string());
+        let generation_time_ms = started.elapsed().as_millis() as u64;
+
+        diffusion::output_paths(&paths.output, request.num_images)
+            .into_iter()
+            .map(|path| {
+                let bytes = std::fs::read(&path)
+                    .map_err(|e| format!("Missing output {}: {}", path.display(), e))?;
+                Ok(GeneratedImage {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    request: recorded.clone(),
+                    image_data: BASE64.encode(bytes),
+                    file_path: Some(path.to_string_lossy().to_string()),
+                    generated_at: Utc::now().timestamp() as u64,
+                    generation_time_ms,
+                    ipfs_cid: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Record progress on a running job; false once it has been cancelled
+    async fn set_generation_progress(&self, job_id: &str, step: u32, total_steps: u32) -> bool {
+        let mut jobs = self.generation_jobs.write().await;
+        match jobs.get_mut(job_id) {
+            Some(job) if !matches!(job.status, GenerationStatus::Cancelled) => {
+                job.status = GenerationStatus::Generating {
+                    progress: step as f32 / total_steps.max(1) as f32,
+                    current_step: step,
+                    total_steps,
+                };
+                true
+            }
+            _ => false,
         }
+    }
+
+    /// Concept images of a configuration and the regularization images
+    /// already available for it
+    fn concept_images(
+        config: &ImageTrainingConfig,
+    ) -> Result<(Vec<training::ConceptImage>, Vec<training::ConceptImage>), String> {
+        let images = training::scan_images(Path::new(&config.dataset_path))?;
+        let regularization = match &config.regularization_dir {
+            Some(dir) => training::scan_images(Path::new(dir))?,
+            None => Vec::new(),
+        };
+        Ok((images, regularization))
+    }
+
+    /// Validate a training configuration against its concept images without
+    /// queueing it
+    pub async fn validate_training_config(
+        &self,
+        config: &ImageTrainingConfig,
+    ) -> Result<ConceptReport, String> {
+        // Validate base model exists
+        let model = self
+            .get_model(&config.base_model_id)
+            .await
+            .ok_or_else(|| format!("Base model {} not found", config.base_model_id))?;
+        training::training_script(model.architecture)?;
 
         // Validate dataset path
-        if !std::path::Path::new(&config.dataset_path).exists() {
+        if !Path::new(&config.dataset_path).is_dir() {
             return Err(format!("Dataset path {} does not exist", config.dataset_path));
         }
+        let (images, regularization) = Self::concept_images(config)?;
+        training::validate_concept(config, &images, regularization.len())
+    }
+
+    /// Create a training job
+    pub async fn create_training_job(&self, config: ImageTrainingConfig) -> Result<String, String> {
+        let report = self.validate_training_config(&config).await?;
 
         let job_id = uuid::Uuid::new_v4().to_string();
         let job = ImageTrainingJob {
             id: job_id.clone(),
             config,
             status: TrainingStatus::Preparing,
+            report: Some(report),
+            samples: vec![],
+            lora_id: None,
             created_at: Utc::now().timestamp() as u64,
             completed_at: None,
         };
@@ -654,6 +1059,353 @@ impl ImageModelManager {
         }
     }
 
+    /// Run a queued training job: stage the concept and regularization
+    /// images, train, and register the adapter for generation jobs
+    pub async fn run_training_job(&self, job_id: &str) -> Result<(), String> {
+        let result = self.execute_training(job_id).await;
+
+        if let Some(job) = self.training_jobs.write().await.get_mut(job_id) {
+            match &result {
+                Ok((lora, final_loss)) => {
+                    job.status = TrainingStatus::Completed {
+                        output_path: lora.path.clone(),
+                        final_loss: *final_loss,
+                    };
+                    job.lora_id = Some(lora.id.clone());
+                }
+                // A cancelled job keeps its status
+                Err(_) if matches!(job.status, TrainingStatus::Cancelled) => {}
+                Err(error) => {
+                    job.status = TrainingStatus::Failed {
+                        error: error.clone(),
+                    };
+                }
+            }
+            job.completed_at = Some(Utc::now().timestamp() as u64);
+        }
+        result.map(|_| ())
+    }
+
+    async fn execute_training(&self, job_id: &str) -> Result<(ImageLora, f64), String> {
+        let config = match self.get_training_job(job_id).await {
+            Some(job) if matches!(job.status, TrainingStatus::Preparing) => job.config,
+            Some(_) => return Err(format!("Job {} is not queued", job_id)),
+            None => return Err(format!("Job {} not found", job_id)),
+        };
+        let model = self
+            .get_model(&config.base_model_id)
+            .await
+            .ok_or_else(|| format!("Base model {} not found", config.base_model_id))?;
+        let base_model = model
+            .path
+            .clone()
+            .filter(|_| model.is_downloaded)
+            .ok_or_else(|| format!("Model {} is not downloaded", model.name))?;
+        let script = training::training_script(model.architecture)?;
+        let (python, scripts_dir) = training::find_trainer()?;
+
+        // The dataset may have changed since the job was queued
+        let (images, regularization) = Self::concept_images(&config)?;
+        let report = training::validate_concept(&config, &images, regularization.len())?;
+
+        // Staged images, samples and trainer output are kept per job
+        let job_dir = match config.output_dir.trim() {
+            "" => self.models_dir.join("training").join(job_id),
+            dir => PathBuf::from(dir).join(job_id),
+        };
+        let train_data = job_dir.join("img");
+        training::stage_images(
+            &images,
+            &train_data.join(format!("{}_concept", report.repeats)),
+            "img",
+            &config.instance_prompt,
+        )?;
+        let class_prompt = config
+            .class_prompt
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty());
+        let regularization_dir = match class_prompt {
+            Some(class_prompt) => {
+                let class_dir = job_dir.join("reg").join("1_class");
+                training::stage_images(&regularization, &class_dir, "reg", class_prompt)?;
+                if report.class_images_to_generate > 0 {
+                    let generated = self
+                        .generate_class_images(
+                            job_id,
+                            Path::new(&base_model),
+                            &config,
+                            report.class_images_to_generate,
+                            &job_dir,
+                        )
+                        .await?;
+                    training::stage_images(&generated, &class_dir, "class", class_prompt)?;
+                }
+                Some(job_dir.join("reg"))
+            }
+            None => None,
+        };
+
+        let paths = TrainingPaths {
+            base_model: PathBuf::from(&base_model),
+            train_data,
+            regularization: regularization_dir,
+            output: job_dir.join("output"),
+            output_name: "concept".to_string(),
+            sample_prompts: job_dir.join("sample_prompts.txt"),
+        };
+        std::fs::write(&paths.sample_prompts, training::sample_prompts_file(&config))
+            .map_err(|e| format!("Failed to write sample prompts: {}", e))?;
+        let prompts = training::sample_prompts(&config);
+
+        let mut last = training::TrainingProgressLine {
+            step: 0,
+            total_steps: config.training_steps,
+            loss: None,
+            eta_seconds: None,
+        };
+        if !self.set_training_progress(job_id, &last).await {
+            return Err("Training cancelled".to_string());
+        }
+        info!(
+            "Training LoRA for job {} on {} concept images ({} repeats)",
+            job_id, report.instance_images, report.repeats
+        );
+
+        let mut child = tokio::process::Command::new(&python)
+            .arg(scripts_dir.join(script))
+            .args(training::train_args(&config, model.architecture, &paths))
+            .current_dir(&scripts_dir)
+            .env("PYTHONUNBUFFERED", "1")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to start {}: {}", python.display(), e))?;
+        let mut stdout = child.stdout.take().ok_or("Trainer has no stdout")?;
+        let mut stderr = child.stderr.take().ok_or("Trainer has no stderr")?;
+        // Progress bars go to stderr; drain stdout alongside
+        let stdout_task = tokio::spawn(async move {
+            let mut output = Vec::new();
+            let _ = stdout.read_to_end(&mut output).await;
+        });
+
+        let mut pending = Vec::new();
+        let mut chunk = [0u8; 4096];
+        let mut tail: Vec<String> = Vec::new();
+        let mut seen_samples = HashSet::new();
+        loop {
+            let read = stderr
+                .read(&mut chunk)
+                .await
+                .map_err(|e| format!("Failed to read trainer output: {}", e))?;
+            if read == 0 {
+                break;
+            }
+            pending.extend_from_slice(&chunk[..read]);
+
+            while let Some(end) = pending.iter().position(|b| *b == b'\r' || *b == b'\n') {
+                let line: Vec<u8> = pending.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&line).trim().to_string();
+                let Some(progress) = training::parse_training_progress(&line) else {
+                    if !line.is_empty() {
+                        tail.push(line);
+                        if tail.len() > 5 {
+                            tail.remove(0);
+                        }
+                    }
+                    continue;
+                };
+                if progress.step == last.step {
+                    continue;
+                }
+                last = progress;
+
+                if !self.set_training_progress(job_id, &last).await {
+                    let _ = child.kill().await;
+                    return Err("Training cancelled".to_string());
+                }
+                // Samples are written between steps, so any sample file is
+                // complete by the time the next step is reported
+                self.collect_samples(job_id, &paths, &prompts, &mut seen_samples, &last)
+                    .await;
+            }
+        }
+
+        let status = child
+            .wait()
+            .await
+            .map_err(|e| format!("Trainer failed: {}", e))?;
+        let _ = stdout_task.await;
+        if !status.success() {
+            return Err(format!("Trainer exited with {}: {}", status, tail.join("\n")));
+        }
+        self.collect_samples(job_id, &paths, &prompts, &mut seen_samples, &last)
+            .await;
+
+        if !self.set_training_status(job_id, TrainingStatus::Saving).await {
+            return Err("Training cancelled".to_string());
+        }
+        let lora_id = format!("concept-{}", job_id.split('-').next().unwrap_or(job_id));
+        let lora_path = self.loras_dir.join(format!("{}.safetensors", lora_id));
+        std::fs::copy(paths.adapter(), &lora_path).map_err(|e| {
+            format!("Trainer wrote no adapter at {}: {}", paths.adapter().display(), e)
+        })?;
+        let lora = ImageLora {
+            id: lora_id,
+            name: config.instance_prompt.clone(),
+            path: lora_path.to_string_lossy().to_string(),
+            base_model_id: config.base_model_id.clone(),
+            architecture: model.architecture,
+            instance_prompt: config.instance_prompt.clone(),
+            rank: config.lora_rank,
+            training_job_id: Some(job_id.to_string()),
+            created_at: Utc::now().timestamp() as u64,
+        };
+        self.register_lora(lora.clone()).await?;
+        Ok((lora, last.loss.unwrap_or_default()))
+    }
+
+    /// Generate `count` regularization images from the class prompt with
+    /// the base model, a few per run, into the job's `class_images` folder
+    async fn generate_class_images(
+        &self,
+        job_id: &str,
+        base_model: &Path,
+        config: &ImageTrainingConfig,
+        count: u32,
+        job_dir: &Path,
+    ) -> Result<Vec<training::ConceptImage>, String> {
+        let binary = diffusion::find_sd_binary()?;
+        let class_dir = job_dir.join("class_images");
+        std::fs::create_dir_all(&class_dir)
+            .map_err(|e| format!("Failed to create {}: {}", class_dir.display(), e))?;
+        info!("Generating {} class images for training job {}", count, job_id);
+
+        let mut generated = 0;
+        while generated < count {
+            let queued = self.get_training_job(job_id).await.map(|job| job.status);
+            if !matches!(queued, Some(TrainingStatus::Preparing)) {
+                return Err("Training cancelled".to_string());
+            }
+            let request = ImageGenerationRequest {
+                model_id: config.base_model_id.clone(),
+                prompt: config.class_prompt.clone().unwrap_or_default(),
+                resolution: config.resolution,
+                num_images: (count - generated).min(training::CLASS_IMAGE_BATCH),
+                seed: Some(generated as u64),
+                ..Default::default()
+            };
+            let schedule =
+                diffusion::strength_schedule(GenerationMode::TextToImage, request.num_steps, None)?;
+            let paths = SdPaths {
+                model: base_model.to_path_buf(),
+                output: class_dir.join(format!("class{:04}.png", generated)),
+                preview: job_dir.join("class_preview.png"),
+                init_image: None,
+                mask: None,
+                lora_dir: None,
+            };
+            let output = tokio::process::Command::new(&binary)
+                .args(diffusion::sd_args(&request, &schedule, &paths))
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true)
+                .output()
+                .await
+                .map_err(|e| format!("Failed to start {}: {}", binary.display(), e))?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let tail: Vec<&str> = stderr.lines().rev().take(5).collect();
+                return Err(format!(
+                    "Class image generation exited with {}: {}",
+                    output.status,
+                    tail.into_iter().rev().collect::<Vec<_>>().join("\n")
+                ));
+            }
+            generated += request.num_images;
+        }
+        training::scan_images(&class_dir)
+    }
+
+    /// Record progress on a running training job; false once it has been
+    /// cancelled
+    async fn set_training_progress(
+        &self,
+        job_id: &str,
+        progress: &training::TrainingProgressLine,
+    ) -> bool {
+        self.set_training_status(
+            job_id,
+            TrainingStatus::Training {
+                current_step: progress.step,
+                total_steps: progress.total_steps,
+                loss: progress.loss.unwrap_or_default(),
+                eta_seconds: progress.eta_seconds.unwrap_or_default(),
+            },
+        )
+        .await
+    }
+
+    async fn set_training_status(&self, job_id: &str, status: TrainingStatus) -> bool {
+        let mut jobs = self.training_jobs.write().await;
+        match jobs.get_mut(job_id) {
+            Some(job) if !matches!(job.status, TrainingStatus::Cancelled) => {
+                job.status = status;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Record and announce the sample images written since the last call
+    async fn collect_samples(
+        &self,
+        job_id: &str,
+        paths: &TrainingPaths,
+        prompts: &[String],
+        seen: &mut HashSet<PathBuf>,
+        progress: &training::TrainingProgressLine,
+    ) {
+        let Ok(entries) = std::fs::read_dir(paths.samples()) else {
+            return;
+        };
+        let mut written: Vec<(u32, usize, PathBuf)> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| !seen.contains(path))
+            .filter_map(|path| {
+                let name = path.file_name()?.to_str()?;
+                let (step, prompt) = training::parse_sample_name(name, &paths.output_name)?;
+                Some((step, prompt, path))
+            })
+            .collect();
+        written.sort();
+
+        for (step, prompt, path) in written {
+            let Ok(bytes) = std::fs::read(&path) else {
+                continue;
+            };
+            seen.insert(path.clone());
+            let sample = TrainingSample {
+                step,
+                prompt: prompts.get(prompt).cloned().unwrap_or_default(),
+                path: path.to_string_lossy().to_string(),
+            };
+            if let Some(job) = self.training_jobs.write().await.get_mut(job_id) {
+                job.samples.push(sample.clone());
+            }
+            let _ = self.training_previews.send(TrainingPreview {
+                job_id: job_id.to_string(),
+                step,
+                total_steps: progress.total_steps,
+                loss: progress.loss,
+                sample,
+                image: BASE64.encode(bytes),
+            });
+        }
+    }
+
     /// Get generated images gallery
     pub async fn get_gallery(&self) -> Vec<GeneratedImage> {
         self.gallery.read().await.clone()
@@ -871,6 +1623,48 @@ mod tests {
         assert!(matches!(job.status, GenerationStatus::Cancelled));
     }
 
+    #[tokio::test]
+    async fn test_generation_with_trained_lora() {
+        let manager = ImageModelManager::new();
+        let request = ImageGenerationRequest {
+            model_id: "sd-1.5".to_string(),
+            prompt: "a photo of sks dog".to_string(),
+            lora_weights: vec![LoRAWeight {
+                adapter_id: "concept-test".to_string(),
+                weight: 0.8,
+            }],
+            ..Default::default()
+        };
+        assert!(manager.create_generation_job(request.clone()).await.is_err());
+
+        manager.loras.write().await.insert(
+            "concept-test".to_string(),
+            ImageLora {
+                id: "concept-test".to_string(),
+                name: "a photo of sks dog".to_string(),
+                path: "/loras/concept-test.safetensors".to_string(),
+                base_model_id: "sd-1.5".to_string(),
+                architecture: ImageArchitecture::StableDiffusion1,
+                instance_prompt: "a photo of sks dog".to_string(),
+                rank: 8,
+                training_job_id: None,
+                created_at: 0,
+            },
+        );
+        assert!(manager.create_generation_job(request.clone()).await.is_ok());
+        assert_eq!(
+            diffusion::prompt_with_loras(&request),
+            "a photo of sks dog <lora:concept-test:0.8>"
+        );
+
+        // Adapters only apply to the architecture they were trained for
+        let sdxl = ImageGenerationRequest {
+            model_id: "sdxl-base".to_string(),
+            ..request
+        };
+        assert!(manager.create_generation_job(sdxl).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_get_gallery() {
         let manager = ImageModelManager::new();
@@ -0,0 +1,639 @@
+//! Concept Training
+//!
+//! Trains DreamBooth-style LoRA adapters on a folder of concept images with
+//! kohya-ss sd-scripts (`train_network.py`, `sdxl_train_network.py` for
+//! SDXL). A job runs in four stages:
+//!
+//! - the concept is validated: the instance prompt has to add an identifier
+//!   (such as `sks`) to the class prompt, and captions are checked for it
+//! - images are staged into the trainer's folder layout with one caption
+//!   file each; images without a caption file are captioned with the
+//!   instance prompt
+//! - for prior preservation, regularization images of the class are taken
+//!   from `regularization_dir` and any still missing are generated from the
+//!   class prompt with the base model
+//! - the trainer runs, reporting `steps: | step/total [..<eta, avr_loss=..]`
+//!   progress lines; the sample images it writes every `sample_every_steps`
+//!   are sent as preview events
+//!
+//! The finished adapter is copied into the LoRA directory and registered so
+//! generation jobs can apply it.
+
+use super::diffusion::png_dimensions;
+use super::{ImageArchitecture, ImageTrainingConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Tauri event emitted for every sample image written during training
+pub const IMAGE_TRAINING_PREVIEW_EVENT: &str = "image-training-preview";
+
+/// Fewest concept images a job trains on
+pub const MIN_CONCEPT_IMAGES: usize = 3;
+
+/// Largest LoRA rank accepted
+const MAX_LORA_RANK: u32 = 128;
+
+/// Times each concept image is seen per epoch without regularization images
+const DEFAULT_REPEATS: u32 = 10;
+
+/// Class images generated per stable-diffusion.cpp run
+pub const CLASS_IMAGE_BATCH: u32 = 4;
+
+/// Extension of caption files next to the images
+const CAPTION_EXTENSION: &str = "txt";
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp"];
+
+/// A training image and the caption it is trained with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConceptImage {
+    pub path: PathBuf,
+    /// Contents of the image's caption file, if it has one
+    pub caption: Option<String>,
+}
+
+/// Images in `dir` with their caption files, sorted by path
+pub fn scan_images(dir: &Path) -> Result<Vec<ConceptImage>, String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    let mut images: Vec<ConceptImage> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .map(|e| IMAGE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+                    .unwrap_or(false)
+        })
+        .map(|path| {
+            let caption = std::fs::read_to_string(path.with_extension(CAPTION_EXTENSION))
+                .ok()
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty());
+            ConceptImage { path, caption }
+        })
+        .collect();
+    images.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(images)
+}
+
+fn words(prompt: &str) -> Vec<String> {
+    prompt
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Words of the instance prompt that are not in the class prompt: the
+/// identifier the concept is bound to
+pub fn concept_identifier(instance_prompt: &str, class_prompt: &str) -> Vec<String> {
+    let class: BTreeSet<String> = words(class_prompt).into_iter().collect();
+    words(instance_prompt)
+        .into_iter()
+        .filter(|w| !class.contains(w))
+        .collect()
+}
+
+/// What a job will train on, and anything about it worth a second look
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConceptReport {
+    pub instance_images: usize,
+    /// Concept images with their own caption file
+    pub captioned_images: usize,
+    /// Words binding the concept, empty without a class prompt
+    pub identifier: Vec<String>,
+    /// Regularization images already in `regularization_dir`
+    pub regularization_images: usize,
+    /// Class images generated with the base model before training
+    pub class_images_to_generate: u32,
+    /// Times each concept image is seen per epoch
+    pub repeats: u32,
+    pub warnings: Vec<String>,
+}
+
+/// Check a training configuration against its concept images and the
+/// regularization images already available
+pub fn validate_concept(
+    config: &ImageTrainingConfig,
+    images: &[ConceptImage],
+    regularization_images: usize,
+) -> Result<ConceptReport, String> {
+    if !config.use_lora {
+        return Err("Only LoRA training is supported".to_string());
+    }
+    if config.training_steps == 0 || config.batch_size == 0 {
+        return Err("Training steps and batch size must be at least 1".to_string());
+    }
+    if config.lora_rank == 0 || config.lora_rank > MAX_LORA_RANK {
+        return Err(format!("LoRA rank must be between 1 and {}", MAX_LORA_RANK));
+    }
+    if !(config.learning_rate > 0.0 && config.learning_rate.is_finite()) {
+        return Err("Learning rate must be positive".to_string());
+    }
+    if words(&config.instance_prompt).is_empty() {
+        return Err("Instance prompt is empty".to_string());
+    }
+    if images.len() < MIN_CONCEPT_IMAGES {
+        return Err(format!(
+            "Found {} concept images in {}, at least {} are needed",
+            images.len(),
+            config.dataset_path,
+            MIN_CONCEPT_IMAGES
+        ));
+    }
+
+    let mut warnings = Vec::new();
+    let class_prompt = config
+        .class_prompt
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty());
+    let identifier = match class_prompt {
+        Some(class_prompt) => {
+            let identifier = concept_identifier(&config.instance_prompt, class_prompt);
+            if identifier.is_empty() {
+                return Err(format!(
+                    "Instance prompt \"{}\" must add an identifier to the class prompt \"{}\", \
+                     e.g. \"a photo of sks dog\" for \"a photo of dog\"",
+                    config.instance_prompt, class_prompt
+                ));
+            }
+            let instance: BTreeSet<String> = words(&config.instance_prompt).into_iter().collect();
+            if !words(class_prompt).iter().all(|w| instance.contains(w)) {
+                warnings.push(format!(
+                    "Instance prompt does not mention the class \"{}\"; prior preservation \
+                     works best when it does",
+                    class_prompt
+                ));
+            }
+            identifier
+        }
+        None if config.regularization_dir.is_some() => {
+            return Err("Regularization images need a class prompt".to_string());
+        }
+        None => {
+            warnings.push(
+                "No class prompt: without prior preservation the model may forget the class"
+                    .to_string(),
+            );
+            Vec::new()
+        }
+    };
+
+    let captioned: Vec<&String> = images.iter().filter_map(|i| i.caption.as_ref()).collect();
+    let unbound = captioned
+        .iter()
+        .filter(|caption| {
+            let caption: BTreeSet<String> = words(caption).into_iter().collect();
+            !identifier.iter().all(|w| caption.contains(w))
+        })
+        .count();
+    if unbound > 0 {
+        warnings.push(format!(
+            "{} of {} captions do not mention \"{}\"",
+            unbound,
+            captioned.len(),
+            identifier.join(" ")
+        ));
+    }
+
+    let (width, height) = (config.resolution.width, config.resolution.height);
+    let small = images
+        .iter()
+        .filter(|image| {
+            std::fs::read(&image.path)
+                .ok()
+                .and_then(|bytes| png_dimensions(&bytes))
+                .map(|(w, h)| w < width || h < height)
+                .unwrap_or(false)
+        })
+        .count();
+    if small > 0 {
+        warnings.push(format!(
+            "{} images are smaller than the {}x{} training resolution and will be upscaled",
+            small, width, height
+        ));
+    }
+
+    let (regularization_images, class_images_to_generate) = match class_prompt {
+        Some(_) => (
+            regularization_images,
+            config
+                .num_class_images
+                .saturating_sub(regularization_images as u32),
+        ),
+        None => (0, 0),
+    };
+    let regularization_total = regularization_images + class_images_to_generate as usize;
+    let repeats = if regularization_total > 0 {
+        regularization_total.div_ceil(images.len()).max(1) as u32
+    } else {
+        DEFAULT_REPEATS
+    };
+
+    Ok(ConceptReport {
+        instance_images: images.len(),
+        captioned_images: captioned.len(),
+        identifier,
+        regularization_images,
+        class_images_to_generate,
+        repeats,
+        warnings,
+    })
+}
+
+/// Copy `images` into `dir` with a caption file each, named `prefix` and
+/// an index so images from different folders cannot collide
+pub fn stage_images(
+    images: &[ConceptImage],
+    dir: &Path,
+    prefix: &str,
+    default_caption: &str,
+) -> Result<(), String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    for (index, image) in images.iter().enumerate() {
+        let extension = image
+            .path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("png")
+            .to_lowercase();
+        let staged = dir.join(format!("{}{:04}.{}", prefix, index, extension));
+        std::fs::copy(&image.path, &staged)
+            .map_err(|e| format!("Failed to stage {}: {}", image.path.display(), e))?;
+        let caption = image.caption.as_deref().unwrap_or(default_caption);
+        std::fs::write(staged.with_extension(CAPTION_EXTENSION), caption)
+            .map_err(|e| format!("Failed to write caption for {}: {}", staged.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Folders a training run reads and writes
+pub struct TrainingPaths {
+    pub base_model: PathBuf,
+    /// Holds `<repeats>_concept/` with the staged concept images
+    pub train_data: PathBuf,
+    /// Holds `1_class/` with the staged regularization images
+    pub regularization: Option<PathBuf>,
+    /// Trainer output; samples are written to its `sample/` folder
+    pub output: PathBuf,
+    pub output_name: String,
+    pub sample_prompts: PathBuf,
+}
+
+impl TrainingPaths {
+    /// Adapter the trainer writes when it finishes
+    pub fn adapter(&self) -> PathBuf {
+        self.output
+            .join(format!("{}.safetensors", self.output_name))
+    }
+
+    pub fn samples(&self) -> PathBuf {
+        self.output.join("sample")
+    }
+}
+
+/// Trainer script for a base model architecture
+pub fn training_script(architecture: ImageArchitecture) -> Result<&'static str, String> {
+    match architecture {
+        ImageArchitecture::StableDiffusion1
+        | ImageArchitecture::StableDiffusion2
+        | ImageArchitecture::Custom => Ok("train_network.py"),
+        ImageArchitecture::SDXL => Ok("sdxl_train_network.py"),
+        other => Err(format!(
+            "LoRA training is not supported for {:?} models",
+            other
+        )),
+    }
+}
+
+/// Prompts sampled during training
+pub fn sample_prompts(config: &ImageTrainingConfig) -> Vec<String> {
+    if config.sample_prompts.is_empty() {
+        vec![config.instance_prompt.clone()]
+    } else {
+        config.sample_prompts.clone()
+    }
+}
+
+/// Sample prompts file: one prompt per line with its size, steps and seed
+pub fn sample_prompts_file(config: &ImageTrainingConfig) -> String {
+    sample_prompts(config)
+        .iter()
+        .map(|prompt| {
+            format!(
+                "{} --w {} --h {} --s 20 --d 42\n",
+                prompt.replace('\n', " "),
+                config.resolution.width,
+                config.resolution.height
+            )
+        })
+        .collect()
+}
+
+/// Trainer arguments, after the script path
+pub fn train_args(
+    config: &ImageTrainingConfig,
+    architecture: ImageArchitecture,
+    paths: &TrainingPaths,
+) -> Vec<String> {
+    let mut args = vec![
+        "--pretrained_model_name_or_path".to_string(),
+        paths.base_model.display().to_string(),
+        "--train_data_dir".to_string(),
+        paths.train_data.display().to_string(),
+        "--output_dir".to_string(),
+        paths.output.display().to_string(),
+        "--output_name".to_string(),
+        paths.output_name.clone(),
+        "--save_model_as".to_string(),
+        "safetensors".to_string(),
+        "--resolution".to_string(),
+        format!("{},{}", config.resolution.width, config.resolution.height),
+        "--train_batch_size".to_string(),
+        config.batch_size.to_string(),
+        "--max_train_steps".to_string(),
+        config.training_steps.to_string(),
+        "--learning_rate".to_string(),
+        config.learning_rate.to_string(),
+        "--network_module".to_string(),
+        "networks.lora".to_string(),
+        "--network_dim".to_string(),
+        config.lora_rank.to_string(),
+        "--network_alpha".to_string(),
+        config.lora_rank.to_string(),
+        "--caption_extension".to_string(),
+        format!(".{}", CAPTION_EXTENSION),
+        "--mixed_precision".to_string(),
+        if config.mixed_precision { "fp16" } else { "no" }.to_string(),
+    ];
+    if architecture == ImageArchitecture::StableDiffusion2 {
+        args.push("--v2".to_string());
+    }
+    if config.gradient_checkpointing {
+        args.push("--gradient_checkpointing".to_string());
+    }
+    if let Some(regularization) = &paths.regularization {
+        args.extend([
+            "--reg_data_dir".to_string(),
+            regularization.display().to_string(),
+            "--prior_loss_weight".to_string(),
+            "1.0".to_string(),
+        ]);
+    }
+    if config.sample_every_steps > 0 {
+        args.extend([
+            "--sample_every_n_steps".to_string(),
+            config.sample_every_steps.to_string(),
+            "--sample_prompts".to_string(),
+            paths.sample_prompts.display().to_string(),
+            "--sample_sampler".to_string(),
+            "euler_a".to_string(),
+        ]);
+    }
+    args
+}
+
+/// A trainer progress report
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrainingProgressLine {
+    pub step: u32,
+    pub total_steps: u32,
+    pub loss: Option<f64>,
+    pub eta_seconds: Option<u64>,
+}
+
+/// Parse a trainer progress line such as
+/// `steps:  10%|█     | 100/1000 [01:40<15:00,  1.00it/s, avr_loss=0.0812]`.
+/// Progress bars of sample generation are not prefixed with `steps:`.
+pub fn parse_training_progress(line: &str) -> Option<TrainingProgressLine> {
+    if !line.trim_start().starts_with("steps:") {
+        return None;
+    }
+    let (step, total_steps) = super::diffusion::parse_progress(line)?;
+    let stats = line
+        .rsplit_once('[')
+        .map(|(_, stats)| stats.trim_end().trim_end_matches(']'));
+    let loss = stats.and_then(|stats| {
+        stats
+            .split(',')
+            .find_map(|field| field.trim().strip_prefix("avr_loss="))
+            .and_then(|loss| loss.parse().ok())
+    });
+    let eta_seconds = stats
+        .and_then(|stats| stats.split(',').next())
+        .and_then(|times| times.split_once('<'))
+        .and_then(|(_, eta)| parse_duration(eta.trim()));
+    Some(TrainingProgressLine {
+        step,
+        total_steps,
+        loss,
+        eta_seconds,
+    })
+}
+
+/// Seconds in a `[hh:]mm:ss` duration
+fn parse_duration(value: &str) -> Option<u64> {
+    value.split(':').try_fold(0u64, |total, part| {
+        Some(total * 60 + part.parse::<u64>().ok()?)
+    })
+}
+
+/// Step and prompt index of a sample image the trainer wrote, from its
+/// `<output name>_<step>_<prompt>_<timestamp>.png` file name
+pub fn parse_sample_name(file_name: &str, output_name: &str) -> Option<(u32, usize)> {
+    let rest = file_name.strip_prefix(output_name)?.strip_prefix('_')?;
+    let mut parts = rest.split('_');
+    let step = parts.next()?.parse().ok()?;
+    let prompt = parts.next()?.parse().ok()?;
+    Some((step, prompt))
+}
+
+/// A sample image written during training
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainingSample {
+    pub step: u32,
+    pub prompt: String,
+    pub path: String,
+}
+
+/// Sent for every sample image written during training
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainingPreview {
+    pub job_id: String,
+    pub step: u32,
+    pub total_steps: u32,
+    pub loss: Option<f64>,
+    pub sample: TrainingSample,
+    /// The sample as base64 PNG
+    pub image: String,
+}
+
+/// Locate sd-scripts and the Python interpreter of its virtualenv
+pub fn find_trainer() -> Result<(PathBuf, PathBuf), String> {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let scripts = [
+        home_dir.join("sd-scripts"),
+        home_dir.join("kohya_ss/sd-scripts"),
+    ]
+    .into_iter()
+    .find(|dir| dir.join("train_network.py").exists())
+    .ok_or_else(|| {
+        "kohya-ss sd-scripts not found. Please install it in ~/sd-scripts".to_string()
+    })?;
+    let python = [
+        scripts.join("venv/bin/python"),
+        scripts.join("../venv/bin/python"),
+    ]
+    .into_iter()
+    .find(|path| path.exists())
+    .unwrap_or_else(|| PathBuf::from("python3"));
+    Ok((python, scripts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image_models::ImageResolution;
+
+    fn config(dataset: &Path) -> ImageTrainingConfig {
+        ImageTrainingConfig {
+            base_model_id: "sd-1.5".to_string(),
+            dataset_path: dataset.display().to_string(),
+            instance_prompt: "a photo of sks dog".to_string(),
+            class_prompt: Some("a photo of dog".to_string()),
+            num_class_images: 12,
+            ..Default::default()
+        }
+    }
+
+    fn concept(dir: &Path, count: usize) -> Vec<ConceptImage> {
+        for i in 0..count {
+            std::fs::write(dir.join(format!("dog{}.jpg", i)), [0xFF, 0xD8, 0xFF]).unwrap();
+        }
+        std::fs::write(dir.join("dog0.txt"), "sks dog on a beach\n").unwrap();
+        std::fs::write(dir.join("dog1.txt"), "a dog on grass").unwrap();
+        std::fs::write(dir.join("notes.md"), "not an image").unwrap();
+        scan_images(dir).unwrap()
+    }
+
+    #[test]
+    fn test_concept_validation() {
+        let dir = tempfile::tempdir().unwrap();
+        let images = concept(dir.path(), 4);
+        assert_eq!(images.len(), 4);
+        assert_eq!(images[0].caption.as_deref(), Some("sks dog on a beach"));
+        assert_eq!(images[2].caption, None);
+
+        let report = validate_concept(&config(dir.path()), &images, 2).unwrap();
+        assert_eq!(report.identifier, vec!["sks".to_string()]);
+        assert_eq!(report.captioned_images, 2);
+        assert_eq!(report.class_images_to_generate, 10);
+        // 12 class images against 4 concept images
+        assert_eq!(report.repeats, 3);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].starts_with("1 of 2 captions"));
+
+        // The instance prompt has to add an identifier to the class prompt
+        let unbound = ImageTrainingConfig {
+            instance_prompt: "A photo of a dog".to_string(),
+            class_prompt: Some("a photo of a dog".to_string()),
+            ..config(dir.path())
+        };
+        assert!(validate_concept(&unbound, &images, 0).is_err());
+
+        let no_class = ImageTrainingConfig {
+            class_prompt: None,
+            ..config(dir.path())
+        };
+        let report = validate_concept(&no_class, &images, 0).unwrap();
+        assert_eq!((report.class_images_to_generate, report.repeats), (0, 10));
+        assert!(validate_concept(&config(dir.path()), &images[..2], 0).is_err());
+    }
+
+    #[test]
+    fn test_stage_images_writes_captions() {
+        let source = tempfile::tempdir().unwrap();
+        let images = concept(source.path(), 3);
+        let staged = tempfile::tempdir().unwrap();
+        let dir = staged.path().join("10_concept");
+        stage_images(&images, &dir, "img", "a photo of sks dog").unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.join("img0000.txt")).unwrap(),
+            "sks dog on a beach"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("img0002.txt")).unwrap(),
+            "a photo of sks dog"
+        );
+        assert!(dir.join("img0002.jpg").exists());
+        assert_eq!(scan_images(&dir).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_train_args() {
+        let config = ImageTrainingConfig {
+            resolution: ImageResolution::square_1024(),
+            sample_every_steps: 50,
+            ..config(Path::new("/data"))
+        };
+        let paths = TrainingPaths {
+            base_model: "/models/sdxl.safetensors".into(),
+            train_data: "/job/img".into(),
+            regularization: Some("/job/reg".into()),
+            output: "/job/out".into(),
+            output_name: "concept".to_string(),
+            sample_prompts: "/job/prompts.txt".into(),
+        };
+        let joined = train_args(&config, ImageArchitecture::SDXL, &paths).join(" ");
+        assert!(joined.contains("--resolution 1024,1024"));
+        assert!(joined.contains("--reg_data_dir /job/reg --prior_loss_weight 1.0"));
+        assert!(joined.contains("--sample_every_n_steps 50 --sample_prompts /job/prompts.txt"));
+        assert!(!joined.contains("--v2"));
+        assert_eq!(
+            paths.adapter(),
+            PathBuf::from("/job/out/concept.safetensors")
+        );
+
+        assert_eq!(
+            sample_prompts_file(&config),
+            "a photo of sks dog --w 1024 --h 1024 --s 20 --d 42\n"
+        );
+        assert_eq!(
+            training_script(ImageArchitecture::SDXL).unwrap(),
+            "sdxl_train_network.py"
+        );
+        assert!(training_script(ImageArchitecture::Flux).is_err());
+    }
+
+    #[test]
+    fn test_parse_training_output() {
+        let line = "steps:  10%|█     | 100/1000 [01:40<1:15:00,  1.00it/s, avr_loss=0.0812]";
+        assert_eq!(
+            parse_training_progress(line),
+            Some(TrainingProgressLine {
+                step: 100,
+                total_steps: 1000,
+                loss: Some(0.0812),
+                eta_seconds: Some(4500),
+            })
+        );
+        // Sampling progress bars are not training steps
+        assert_eq!(
+            parse_training_progress(" 40%|████  | 8/20 [00:02<00:03]"),
+            None
+        );
+
+        assert_eq!(
+            parse_sample_name("concept_000100_01_20240101120000_42.png", "concept"),
+            Some((100, 1))
+        );
+        assert_eq!(parse_sample_name("other_000100_01_x.png", "concept"), None);
+    }
+}
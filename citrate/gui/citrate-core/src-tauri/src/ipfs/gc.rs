@@ -0,0 +1,415 @@
+//! Pin quotas and garbage collection
+//!
+//! Every pin made through [`IpfsManager`](super::IpfsManager) is recorded in a
+//! ledger with its content category, size and last access time. Each category
+//! can be given a byte quota: when a new pin would push a category over its
+//! quota, the least recently used unprotected pins in that category are
+//! unpinned to make room. Protected pins are never unpinned automatically.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::warn;
+
+/// Ledger file, stored next to the IPFS repo config
+pub const LEDGER_FILE: &str = "citrate-pins.json";
+
+/// What a pinned CID holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentCategory {
+    Models,
+    Datasets,
+    Artifacts,
+    /// Anything pinned without a category; never subject to a quota
+    Other,
+}
+
+impl ContentCategory {
+    pub const ALL: [ContentCategory; 4] = [
+        ContentCategory::Models,
+        ContentCategory::Datasets,
+        ContentCategory::Artifacts,
+        ContentCategory::Other,
+    ];
+}
+
+/// Per-category storage quotas in bytes (`None` = unlimited)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PinQuotas {
+    #[serde(default)]
+    pub models: Option<u64>,
+    #[serde(default)]
+    pub datasets: Option<u64>,
+    #[serde(default)]
+    pub artifacts: Option<u64>,
+}
+
+impl PinQuotas {
+    pub fn get(&self, category: ContentCategory) -> Option<u64> {
+        match category {
+            ContentCategory::Models => self.models,
+            ContentCategory::Datasets => self.datasets,
+            ContentCategory::Artifacts => self.artifacts,
+            ContentCategory::Other => None,
+        }
+    }
+}
+
+/// A pin tracked by the ledger
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PinRecord {
+    pub cid: String,
+    pub category: ContentCategory,
+    pub size_bytes: u64,
+    /// Excluded from LRU unpinning
+    pub protected: bool,
+    /// Unix seconds
+    pub pinned_at: u64,
+    /// Unix seconds of the last pin or read
+    pub last_accessed: u64,
+}
+
+/// Disk usage of one category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryUsage {
+    pub category: ContentCategory,
+    pub pins: usize,
+    pub bytes: u64,
+    pub protected_bytes: u64,
+    pub quota: Option<u64>,
+}
+
+/// Result of `ipfs_get_usage`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageUsage {
+    pub categories: Vec<CategoryUsage>,
+    /// Sum of all tracked pins
+    pub tracked_bytes: u64,
+    /// Total repo size reported by the daemon, including untracked content
+    pub repo_size: Option<u64>,
+    /// Repo size limit configured in the daemon
+    pub storage_max: Option<u64>,
+}
+
+/// Result of `ipfs_gc_run`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GcReport {
+    /// CIDs unpinned to bring categories back under quota
+    pub unpinned: Vec<String>,
+    /// Tracked size of the unpinned content
+    pub unpinned_bytes: u64,
+    /// Ledger entries dropped because the daemon no longer pins them
+    pub stale_records: usize,
+    pub repo_size_before: Option<u64>,
+    pub repo_size_after: Option<u64>,
+}
+
+/// Persistent record of pins made through the manager
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PinLedger {
+    pins: HashMap<String, PinRecord>,
+}
+
+impl PinLedger {
+    /// Load the ledger from `path`, starting empty if it is missing or unreadable
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable pin ledger {:?}: {}", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+        }
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|e| format!("Failed to serialize pin ledger: {}", e))?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write pin ledger: {}", e))
+    }
+
+    pub fn get(&self, cid: &str) -> Option<&PinRecord> {
+        self.pins.get(cid)
+    }
+
+    /// Record a pin, keeping the original pin time and protection of a re-pin
+    pub fn insert(&mut self, mut record: PinRecord) {
+        if let Some(existing) = self.pins.get(&record.cid) {
+            record.pinned_at = existing.pinned_at;
+            record.protected |= existing.protected;
+        }
+        self.pins.insert(record.cid.clone(), record);
+    }
+
+    pub fn remove(&mut self, cid: &str) -> Option<PinRecord> {
+        self.pins.remove(cid)
+    }
+
+    /// Mark a pin as read; returns false if it is not tracked
+    pub fn touch(&mut self, cid: &str, now: u64) -> bool {
+        match self.pins.get_mut(cid) {
+            Some(record) => {
+                record.last_accessed = now;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop records the daemon no longer pins, returning how many were dropped
+    pub fn retain_pinned<F>(&mut self, mut is_pinned: F) -> usize
+    where
+        F: FnMut(&str) -> bool,
+    {
+        let before = self.pins.len();
+        self.pins.retain(|cid, _| is_pinned(cid));
+        before - self.pins.len()
+    }
+
+    pub fn category_bytes(&self, category: ContentCategory) -> u64 {
+        self.pins
+            .values()
+            .filter(|r| r.category == category)
+            .map(|r| r.size_bytes)
+            .sum()
+    }
+
+    /// Pins to unpin so that `incoming_bytes` more fit in `category` under
+    /// `quota`, least recently used first. `incoming` itself is never chosen
+    /// and its current size is not counted. Returns `None` if unprotected
+    /// pins cannot free enough space.
+    pub fn plan_eviction(
+        &self,
+        category: ContentCategory,
+        quota: u64,
+        incoming: &str,
+        incoming_bytes: u64,
+    ) -> Option<Vec<String>> {
+        let used: u64 = self
+            .pins
+            .values()
+            .filter(|r| r.category == category && r.cid != incoming)
+            .map(|r| r.size_bytes)
+            .sum();
+        let mut excess = (used + incoming_bytes).saturating_sub(quota);
+        if excess == 0 {
+            return Some(Vec::new());
+        }
+
+        let mut candidates: Vec<&PinRecord> = self
+            .pins
+            .values()
+            .filter(|r| r.category == category && !r.protected && r.cid != incoming)
+            .collect();
+        candidates.sort_by(|a, b| {
+            a.last_accessed
+                .cmp(&b.last_accessed)
+                .then_with(|| a.cid.cmp(&b.cid))
+        });
+
+        let mut victims = Vec::new();
+        for record in candidates {
+            victims.push(record.cid.clone());
+            excess = excess.saturating_sub(record.size_bytes);
+            if excess == 0 {
+                return Some(victims);
+            }
+        }
+        None
+    }
+
+    /// Pins to unpin to bring every category back under its quota. Categories
+    /// that stay over quota because of protected pins are trimmed as far as
+    /// possible.
+    pub fn over_quota(&self, quotas: &PinQuotas) -> Vec<String> {
+        let mut victims = Vec::new();
+        for category in ContentCategory::ALL {
+            let Some(quota) = quotas.get(category) else {
+                continue;
+            };
+            let mut excess = self.category_bytes(category).saturating_sub(quota);
+            if excess == 0 {
+                continue;
+            }
+
+            let mut candidates: Vec<&PinRecord> = self
+                .pins
+                .values()
+                .filter(|r| r.category == category && !r.protected)
+                .collect();
+            candidates.sort_by(|a, b| {
+                a.last_accessed
+                    .cmp(&b.last_accessed)
+                    .then_with(|| a.cid.cmp(&b.cid))
+            });
+            for record in candidates {
+                if excess == 0 {
+                    break;
+                }
+                victims.push(record.cid.clone());
+                excess = excess.saturating_sub(record.size_bytes);
+            }
+        }
+        victims
+    }
+
+    /// Usage broken down by category
+    pub fn usage(&self, quotas: &PinQuotas) -> Vec<CategoryUsage> {
+        ContentCategory::ALL
+            .into_iter()
+            .map(|category| {
+                let records = self.pins.values().filter(|r| r.category == category);
+                let mut usage = CategoryUsage {
+                    category,
+                    pins: 0,
+                    bytes: 0,
+                    protected_bytes: 0,
+                    quota: quotas.get(category),
+                };
+                for record in records {
+                    usage.pins += 1;
+                    usage.bytes += record.size_bytes;
+                    if record.protected {
+                        usage.protected_bytes += record.size_bytes;
+                    }
+                }
+                usage
+            })
+            .collect()
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.pins.values().map(|r| r.size_bytes).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(cid: &str, size: u64, last_accessed: u64, protected: bool) -> PinRecord {
+        PinRecord {
+            cid: cid.to_string(),
+            category: ContentCategory::Models,
+            size_bytes: size,
+            protected,
+            pinned_at: 0,
+            last_accessed,
+        }
+    }
+
+    fn ledger(records: Vec<PinRecord>) -> PinLedger {
+        let mut ledger = PinLedger::default();
+        for r in records {
+            ledger.insert(r);
+        }
+        ledger
+    }
+
+    #[test]
+    fn test_eviction_is_lru_and_skips_protected() {
+        let ledger = ledger(vec![
+            record("old", 40, 1, false),
+            record("genesis", 40, 0, true),
+            record("recent", 40, 5, false),
+        ]);
+
+        // 120 used + 30 incoming against 130: free 20, oldest unprotected first
+        assert_eq!(
+            ledger.plan_eviction(ContentCategory::Models, 130, "new", 30),
+            Some(vec!["old".to_string()])
+        );
+        // Needs more than both unprotected pins can free
+        assert_eq!(
+            ledger.plan_eviction(ContentCategory::Models, 100, "new", 70),
+            None
+        );
+        // Fits already
+        assert_eq!(
+            ledger.plan_eviction(ContentCategory::Models, 200, "new", 30),
+            Some(vec![])
+        );
+        // Other categories are unaffected
+        assert_eq!(
+            ledger.plan_eviction(ContentCategory::Datasets, 10, "new", 10),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn test_repin_does_not_count_twice() {
+        let ledger = ledger(vec![record("a", 60, 1, false), record("b", 40, 2, false)]);
+        assert_eq!(
+            ledger.plan_eviction(ContentCategory::Models, 100, "a", 60),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn test_over_quota_and_usage() {
+        let mut ledger = ledger(vec![
+            record("a", 50, 3, false),
+            record("b", 50, 1, false),
+            record("c", 50, 2, true),
+        ]);
+        let quotas = PinQuotas {
+            models: Some(60),
+            ..Default::default()
+        };
+
+        // Both unprotected pins go; the protected one keeps the category over
+        let mut victims = ledger.over_quota(&quotas);
+        victims.sort();
+        assert_eq!(victims, vec!["a".to_string(), "b".to_string()]);
+
+        let usage = ledger.usage(&quotas);
+        let models = usage
+            .iter()
+            .find(|u| u.category == ContentCategory::Models)
+            .unwrap();
+        assert_eq!(
+            (models.pins, models.bytes, models.protected_bytes),
+            (3, 150, 50)
+        );
+        assert_eq!(models.quota, Some(60));
+
+        assert_eq!(ledger.retain_pinned(|cid| cid != "b"), 1);
+        assert_eq!(ledger.total_bytes(), 100);
+    }
+
+    #[test]
+    fn test_repin_keeps_protection_and_pin_time() {
+        let mut ledger = PinLedger::default();
+        let mut first = record("a", 10, 1, true);
+        first.pinned_at = 1;
+        ledger.insert(first);
+        ledger.insert(PinRecord {
+            pinned_at: 9,
+            ..record("a", 10, 9, false)
+        });
+        let a = ledger.get("a").unwrap();
+        assert!(a.protected);
+        assert_eq!((a.pinned_at, a.last_accessed), (1, 9));
+    }
+
+    #[test]
+    fn test_ledger_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LEDGER_FILE);
+        let mut ledger = ledger(vec![record("a", 10, 1, false)]);
+        assert!(ledger.touch("a", 7));
+        assert!(!ledger.touch("missing", 7));
+        ledger.save(&path).unwrap();
+
+        let loaded = PinLedger::load(&path);
+        assert_eq!(loaded.get("a").unwrap().last_accessed, 7);
+        assert!(PinLedger::load(&dir.path().join("missing.json"))
+            .get("a")
+            .is_none());
+    }
+}
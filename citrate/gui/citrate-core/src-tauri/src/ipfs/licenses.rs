@@ -0,0 +1,324 @@
+//! Pins for licensed marketplace models
+//!
+//! Purchasing or deploying a marketplace model registers a license covering
+//! its weight CIDs. While the license is active the weights stay pinned as
+//! protected model content, locally and optionally on a remote pinning
+//! service, and periodic health checks record whether each pin is still in
+//! place. When the license lapses its pins are removed, except CIDs that were
+//! pinned before the license or that another active license still covers.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::Duration;
+use tracing::warn;
+
+/// License file, stored next to the pin ledger
+pub const LICENSES_FILE: &str = "citrate-licenses.json";
+
+/// How often lapsed licenses are released and pin health is checked
+pub const LICENSE_CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// How the license was obtained
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseSource {
+    Purchase,
+    Deployment,
+}
+
+/// A marketplace model the user is entitled to keep local copies of
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelLicense {
+    /// Marketplace model ID
+    pub model_id: String,
+    pub source: LicenseSource,
+    pub weight_cids: Vec<String>,
+    /// Unix seconds; `None` for a license that does not lapse
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    /// Also pin the weights to the configured remote pinning service
+    #[serde(default)]
+    pub remote: bool,
+    /// Local model ID or path the weights are served from, whose cached
+    /// inference results are dropped when the license lapses
+    #[serde(default)]
+    pub local_model: Option<String>,
+}
+
+impl ModelLicense {
+    pub fn is_active(&self, now: u64) -> bool {
+        self.expires_at.is_none_or(|expires_at| now < expires_at)
+    }
+}
+
+/// State of one pin at the last health check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PinHealth {
+    /// Not checked yet, or the remote service is still fetching the content
+    Pending,
+    Pinned,
+    /// Not pinned by the daemon
+    Missing,
+    /// The pin could not be made or checked
+    Failed,
+}
+
+/// Health of one weight CID
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CidHealth {
+    pub cid: String,
+    pub local: PinHealth,
+    /// `None` when the license is not pinned remotely
+    pub remote: Option<PinHealth>,
+    /// Unix seconds
+    pub checked_at: u64,
+    pub error: Option<String>,
+}
+
+/// A registered license and the state of its pins
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LicenseRecord {
+    pub license: ModelLicense,
+    /// Unix seconds
+    pub registered_at: u64,
+    /// CIDs that were already pinned when the license was registered; they
+    /// are left pinned when it lapses
+    pub adopted: Vec<String>,
+    pub health: Vec<CidHealth>,
+}
+
+impl LicenseRecord {
+    /// Whether every pin was confirmed at the last check
+    pub fn is_healthy(&self) -> bool {
+        self.health.len() == self.license.weight_cids.len()
+            && self.health.iter().all(|h| {
+                h.local == PinHealth::Pinned
+                    && h.remote.is_none_or(|remote| remote != PinHealth::Failed)
+            })
+    }
+}
+
+/// Persistent set of licenses, keyed by model ID
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LicenseLedger {
+    licenses: HashMap<String, LicenseRecord>,
+}
+
+impl LicenseLedger {
+    /// Load the ledger from `path`, starting empty if it is missing or unreadable
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable license ledger {:?}: {}", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+        }
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|e| format!("Failed to serialize license ledger: {}", e))?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write license ledger: {}", e))
+    }
+
+    pub fn get(&self, model_id: &str) -> Option<&LicenseRecord> {
+        self.licenses.get(model_id)
+    }
+
+    /// Licenses ordered by model ID
+    pub fn list(&self) -> Vec<LicenseRecord> {
+        let mut records: Vec<LicenseRecord> = self.licenses.values().cloned().collect();
+        records.sort_by(|a, b| a.license.model_id.cmp(&b.license.model_id));
+        records
+    }
+
+    /// Register or renew a license. `adopted` CIDs are added to those already
+    /// adopted by an earlier registration of the same model.
+    pub fn upsert(&mut self, license: ModelLicense, adopted: Vec<String>, now: u64) {
+        match self.licenses.get_mut(&license.model_id) {
+            Some(record) => {
+                for cid in adopted {
+                    if !record.adopted.contains(&cid) {
+                        record.adopted.push(cid);
+                    }
+                }
+                record
+                    .health
+                    .retain(|h| license.weight_cids.contains(&h.cid));
+                record.license = license;
+            }
+            None => {
+                self.licenses.insert(
+                    license.model_id.clone(),
+                    LicenseRecord {
+                        license,
+                        registered_at: now,
+                        adopted,
+                        health: Vec::new(),
+                    },
+                );
+            }
+        }
+    }
+
+    pub fn remove(&mut self, model_id: &str) -> Option<LicenseRecord> {
+        self.licenses.remove(model_id)
+    }
+
+    pub fn set_health(&mut self, model_id: &str, health: Vec<CidHealth>) {
+        if let Some(record) = self.licenses.get_mut(model_id) {
+            record.health = health;
+        }
+    }
+
+    /// Whether any registered license lists `cid`
+    pub fn covers(&self, cid: &str) -> bool {
+        self.licenses
+            .values()
+            .any(|record| record.license.weight_cids.iter().any(|c| c == cid))
+    }
+
+    /// Model IDs of licenses that have lapsed at `now`
+    pub fn expired(&self, now: u64) -> Vec<String> {
+        let mut expired: Vec<String> = self
+            .licenses
+            .values()
+            .filter(|record| !record.license.is_active(now))
+            .map(|record| record.license.model_id.clone())
+            .collect();
+        expired.sort();
+        expired
+    }
+
+    /// CIDs of `model_id` that may be unpinned when its license ends: not
+    /// adopted, and not covered by any other active license
+    pub fn releasable(&self, model_id: &str, now: u64) -> Vec<String> {
+        let Some(record) = self.licenses.get(model_id) else {
+            return Vec::new();
+        };
+        let covered: HashSet<&String> = self
+            .licenses
+            .values()
+            .filter(|other| other.license.model_id != model_id && other.license.is_active(now))
+            .flat_map(|other| other.license.weight_cids.iter())
+            .collect();
+        record
+            .license
+            .weight_cids
+            .iter()
+            .filter(|cid| !record.adopted.contains(cid) && !covered.contains(cid))
+            .cloned()
+            .collect()
+    }
+
+    /// Whether `cid` belongs to another active license than `model_id`
+    /// that pins it remotely
+    pub fn remote_elsewhere(&self, model_id: &str, cid: &str, now: u64) -> bool {
+        self.licenses.values().any(|other| {
+            other.license.model_id != model_id
+                && other.license.remote
+                && other.license.is_active(now)
+                && other.license.weight_cids.iter().any(|c| c == cid)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn license(model_id: &str, cids: &[&str], expires_at: Option<u64>) -> ModelLicense {
+        ModelLicense {
+            model_id: model_id.to_string(),
+            source: LicenseSource::Purchase,
+            weight_cids: cids.iter().map(|c| c.to_string()).collect(),
+            expires_at,
+            remote: false,
+            local_model: None,
+        }
+    }
+
+    #[test]
+    fn test_expiry_and_releasable_cids() {
+        let mut ledger = LicenseLedger::default();
+        ledger.upsert(
+            license("a", &["shared", "a-only", "mine"], Some(100)),
+            vec![],
+            0,
+        );
+        ledger.upsert(license("b", &["shared"], None), vec![], 0);
+        ledger.upsert(license("c", &["c-only"], Some(50)), vec![], 0);
+        ledger.upsert(
+            license("a", &["shared", "a-only", "mine"], Some(100)),
+            vec!["mine".into()],
+            5,
+        );
+
+        assert_eq!(ledger.expired(99), vec!["c".to_string()]);
+        assert_eq!(ledger.expired(100), vec!["a".to_string(), "c".to_string()]);
+        // Shared with the perpetual license b, and "mine" was pinned before
+        assert_eq!(ledger.releasable("a", 100), vec!["a-only".to_string()]);
+        assert_eq!(ledger.get("a").unwrap().registered_at, 0);
+
+        // A lapsed license no longer keeps shared content pinned
+        ledger.upsert(license("d", &["c-only"], Some(50)), vec![], 0);
+        assert_eq!(ledger.releasable("c", 60), vec!["c-only".to_string()]);
+        assert!(ledger.releasable("missing", 0).is_empty());
+    }
+
+    #[test]
+    fn test_health_and_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LICENSES_FILE);
+        let mut ledger = LicenseLedger::default();
+        let mut remote = license("a", &["x", "y"], None);
+        remote.remote = true;
+        ledger.upsert(remote, vec![], 1);
+        assert!(!ledger.get("a").unwrap().is_healthy());
+        assert!(ledger.remote_elsewhere("b", "x", 1));
+        assert!(ledger.covers("y") && !ledger.covers("z"));
+        assert!(!ledger.remote_elsewhere("a", "x", 1));
+
+        let health = |cid: &str, remote| CidHealth {
+            cid: cid.to_string(),
+            local: PinHealth::Pinned,
+            remote: Some(remote),
+            checked_at: 2,
+            error: None,
+        };
+        ledger.set_health(
+            "a",
+            vec![
+                health("x", PinHealth::Pinned),
+                health("y", PinHealth::Pending),
+            ],
+        );
+        assert!(ledger.get("a").unwrap().is_healthy());
+        ledger.set_health(
+            "a",
+            vec![
+                health("x", PinHealth::Pinned),
+                health("y", PinHealth::Failed),
+            ],
+        );
+        assert!(!ledger.get("a").unwrap().is_healthy());
+
+        // Dropping a CID from the license drops its health entry
+        ledger.upsert(license("a", &["x"], None), vec![], 3);
+        assert_eq!(ledger.get("a").unwrap().health.len(), 1);
+
+        ledger.save(&path).unwrap();
+        let loaded = LicenseLedger::load(&path);
+        assert_eq!(loaded.list(), ledger.list());
+        assert!(LicenseLedger::load(&dir.path().join("missing.json"))
+            .list()
+            .is_empty());
+    }
+}
@@ -3,13 +3,34 @@
 //! Provides embedded IPFS node management for Citrate.
 //! Handles daemon lifecycle, content operations, and gateway configuration.
 
+pub mod gc;
+pub mod licenses;
+pub mod upload;
+
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::process::{Child, Command, Stdio};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncReadExt;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
+pub use gc::{ContentCategory, GcReport, PinQuotas, StorageUsage};
+use gc::{PinLedger, PinRecord, LEDGER_FILE};
+pub use licenses::{
+    CidHealth, LicenseRecord, LicenseSource, ModelLicense, PinHealth, LICENSE_CHECK_INTERVAL,
+};
+use licenses::{LicenseLedger, LICENSES_FILE};
+pub use upload::UploadProgress;
+use upload::{build_layer, Cid, DagLink, CHUNK_SIZE, CODEC_DAG_PB, CODEC_RAW};
+
+/// Minimum interval between upload progress callbacks
+const UPLOAD_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Attempts per block before an upload is abandoned
+const BLOCK_PUT_ATTEMPTS: u32 = 3;
+
 /// IPFS daemon status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IpfsStatus {
@@ -40,6 +61,21 @@ pub struct IpfsConfig {
     pub enable_pubsub: bool,
     /// Bootstrap peers
     pub bootstrap_peers: Vec<String>,
+    /// Storage quotas per content category
+    #[serde(default)]
+    pub quotas: PinQuotas,
+    /// Remote pinning service for licensed model weights
+    #[serde(default)]
+    pub remote_pinning: Option<RemotePinningConfig>,
+}
+
+/// A service implementing the IPFS Pinning Service API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemotePinningConfig {
+    /// API base URL, e.g. `https://api.pinata.cloud/psa`
+    pub endpoint: String,
+    /// Bearer token for the service
+    pub access_token: String,
 }
 
 impl Default for IpfsConfig {
@@ -67,6 +103,8 @@ impl Default for IpfsConfig {
                 "/dnsaddr/bootstrap.libp2p.io/p2p/QmQCU2EcMqAqQPR2i9bChDtGNJchTbq5TbXJJ16u19uLTa".to_string(),
                 "/dnsaddr/bootstrap.libp2p.io/p2p/QmbLHAnMoJPWSCR5Zhtx6BHJX9KiKNN6tpvbUcqanj75Nb".to_string(),
             ],
+            quotas: PinQuotas::default(),
+            remote_pinning: None,
         }
     }
 }
@@ -95,6 +133,10 @@ pub struct IpfsManager {
     config: Arc<RwLock<IpfsConfig>>,
     daemon_process: Arc<RwLock<Option<Child>>>,
     status: Arc<RwLock<IpfsStatus>>,
+    /// Pins made through this manager, for quotas and LRU unpinning
+    ledger: Arc<RwLock<PinLedger>>,
+    /// Marketplace licenses whose weights are kept pinned
+    licenses: Arc<RwLock<LicenseLedger>>,
     http_client: reqwest::Client,
 }
 
@@ -110,6 +152,8 @@ impl IpfsManager {
             .timeout(std::time::Duration::from_secs(30))
             .build()
             .unwrap_or_else(|_| reqwest::Client::new());
+        let ledger = PinLedger::load(&config.repo_path.join(LEDGER_FILE));
+        let licenses = LicenseLedger::load(&config.repo_path.join(LICENSES_FILE));
 
         Self {
             config: Arc::new(RwLock::new(config)),
@@ -122,6 +166,8 @@ impl IpfsManager {
                 num_objects: None,
                 version: None,
             })),
+            ledger: Arc::new(RwLock::new(ledger)),
+            licenses: Arc::new(RwLock::new(licenses)),
             http_client,
         }
     }
@@ -442,6 +488,12 @@ impl IpfsManager {
             .unwrap_or(data.len() as u64);
 
         let gateway_url = format!("{}{}", config.external_gateways[0], cid);
+        let ledger_path = config.repo_path.join(LEDGER_FILE);
+        drop(config);
+
+        // `add` pins by default
+        self.record_pin(&ledger_path, &cid, ContentCategory::Other, size, false)
+            .await;
 
         Ok(IpfsAddResult {
             cid,
@@ -453,15 +505,240 @@ impl IpfsManager {
 
     /// Add file from path
     pub async fn add_file(&self, path: &std::path::Path) -> Result<IpfsAddResult, String> {
-        let data = tokio::fs::read(path)
-            .await
-            .map_err(|e| format!("Failed to read file: {}", e))?;
+        let upload_id = uuid::Uuid::new_v4().to_string();
+        self.add_file_with_progress(path, &upload_id, |_| {}).await
+    }
 
+    /// Add a file in fixed-size chunks, reporting progress as blocks are sent
+    ///
+    /// The file is streamed from disk one chunk at a time, so memory use does not
+    /// grow with file size. Blocks the daemon already holds are skipped, which
+    /// makes re-running an interrupted upload resume where it stopped.
+    pub async fn add_file_with_progress<F>(
+        &self,
+        path: &std::path::Path,
+        upload_id: &str,
+        on_progress: F,
+    ) -> Result<IpfsAddResult, String>
+    where
+        F: Fn(&UploadProgress) + Send + Sync,
+    {
         let name = path
             .file_name()
-            .map(|n| n.to_string_lossy().to_string());
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "file".to_string());
+
+        let metadata = tokio::fs::metadata(path)
+            .await
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        let total_bytes = metadata.len();
+
+        let mut progress = UploadProgress {
+            upload_id: upload_id.to_string(),
+            name: name.clone(),
+            status: "uploading".to_string(),
+            bytes_sent: 0,
+            bytes_skipped: 0,
+            total_bytes,
+            rate_bytes_per_sec: 0,
+            chunks_done: 0,
+            chunks_total: total_bytes.div_ceil(CHUNK_SIZE as u64).max(1),
+            cid: None,
+            error: None,
+        };
+        on_progress(&progress);
+
+        match self
+            .upload_chunks(path, &mut progress, &on_progress)
+            .await
+        {
+            Ok(cid) => {
+                let config = self.config.read().await;
+                progress.status = "complete".to_string();
+                progress.cid = Some(cid.clone());
+                on_progress(&progress);
+
+                info!(
+                    "Uploaded {} to IPFS as {} ({} bytes sent, {} bytes already present)",
+                    name, cid, progress.bytes_sent, progress.bytes_skipped
+                );
+                let ledger_path = config.repo_path.join(LEDGER_FILE);
+                self.record_pin(&ledger_path, &cid, ContentCategory::Other, total_bytes, false)
+                    .await;
+
+                Ok(IpfsAddResult {
+                    gateway_url: format!("{}{}", config.external_gateways[0], cid),
+                    cid,
+                    size: total_bytes,
+                    name,
+                })
+            }
+            Err(e) => {
+                error!("IPFS upload of {} failed: {}", name, e);
+                progress.status = "failed".to_string();
+                progress.error = Some(e.clone());
+                on_progress(&progress);
+                Err(e)
+            }
+        }
+    }
+
+    /// Stream the file as raw leaves, link them into a UnixFS DAG and pin the root
+    async fn upload_chunks<F>(
+        &self,
+        path: &std::path::Path,
+        progress: &mut UploadProgress,
+        on_progress: &F,
+    ) -> Result<String, String>
+    where
+        F: Fn(&UploadProgress) + Send + Sync,
+    {
+        let api_base = {
+            let config = self.config.read().await;
+            format!("http://127.0.0.1:{}/api/v0", config.api_port)
+        };
+
+        let mut file = tokio::fs::File::open(path)
+            .await
+            .map_err(|e| format!("Failed to open file: {}", e))?;
+
+        let started = Instant::now();
+        let mut last_emit = Instant::now();
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+        let mut leaves: Vec<DagLink> = Vec::with_capacity(progress.chunks_total as usize);
 
-        self.add(data, name.as_deref()).await
+        loop {
+            let read = read_chunk(&mut file, &mut buffer).await?;
+            if read == 0 && !leaves.is_empty() {
+                break;
+            }
+
+            let chunk = &buffer[..read];
+            let cid = Cid::new(CODEC_RAW, chunk);
+            if self.has_block(&api_base, &cid).await {
+                progress.bytes_skipped += read as u64;
+            } else {
+                self.put_block(&api_base, chunk, &cid).await?;
+                progress.bytes_sent += read as u64;
+            }
+            leaves.push(DagLink {
+                cid,
+                file_size: read as u64,
+                tree_size: read as u64,
+            });
+
+            progress.chunks_done += 1;
+            let elapsed = started.elapsed().as_secs_f64();
+            if elapsed > 0.0 {
+                progress.rate_bytes_per_sec = (progress.bytes_sent as f64 / elapsed) as u64;
+            }
+            if last_emit.elapsed() >= UPLOAD_PROGRESS_INTERVAL {
+                on_progress(progress);
+                last_emit = Instant::now();
+            }
+
+            if read < CHUNK_SIZE {
+                break;
+            }
+        }
+
+        // Link leaves into a balanced tree, one layer at a time
+        progress.status = "linking".to_string();
+        on_progress(progress);
+        let mut layer = leaves;
+        while layer.len() > 1 {
+            let mut parents = Vec::new();
+            for (block, link) in build_layer(&layer) {
+                if !self.has_block(&api_base, &link.cid).await {
+                    self.put_block(&api_base, &block, &link.cid).await?;
+                }
+                parents.push(link);
+            }
+            layer = parents;
+        }
+
+        let root = layer
+            .pop()
+            .ok_or_else(|| "Upload produced no blocks".to_string())?
+            .cid
+            .to_string();
+
+        progress.status = "pinning".to_string();
+        on_progress(progress);
+        let response = self
+            .http_client
+            .post(format!("{}/pin/add?arg={}", api_base, root))
+            .timeout(Duration::from_secs(600))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to pin {}: {}", root, e))?;
+        if !response.status().is_success() {
+            return Err(format!("Pin failed: {}", response.status()));
+        }
+
+        Ok(root)
+    }
+
+    /// Check whether the local daemon already stores a block
+    async fn has_block(&self, api_base: &str, cid: &Cid) -> bool {
+        self.http_client
+            .post(format!("{}/block/stat?arg={}&offline=true", api_base, cid))
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await
+            .map(|r| r.status().is_success())
+            .unwrap_or(false)
+    }
+
+    /// Store a single block, verifying the daemon derived the same CID
+    async fn put_block(&self, api_base: &str, data: &[u8], cid: &Cid) -> Result<(), String> {
+        let codec = if cid.codec == CODEC_DAG_PB { "dag-pb" } else { "raw" };
+        let url = format!(
+            "{}/block/put?cid-codec={}&mhtype=sha2-256&mhlen=32",
+            api_base, codec
+        );
+        let expected = cid.to_string();
+
+        let mut last_error = String::new();
+        for attempt in 1..=BLOCK_PUT_ATTEMPTS {
+            let part = reqwest::multipart::Part::bytes(data.to_vec()).file_name("block");
+            let form = reqwest::multipart::Form::new().part("data", part);
+
+            let result = self
+                .http_client
+                .post(&url)
+                .multipart(form)
+                .timeout(Duration::from_secs(120))
+                .send()
+                .await;
+
+            match result {
+                Ok(response) if response.status().is_success() => {
+                    let body: serde_json::Value = response
+                        .json()
+                        .await
+                        .map_err(|e| format!("Failed to parse block/put response: {}", e))?;
+                    let key = body.get("Key").and_then(|v| v.as_str()).unwrap_or_default();
+                    if key != expected {
+                        return Err(format!(
+                            "IPFS stored block as {} but {} was expected",
+                            key, expected
+                        ));
+                    }
+                    return Ok(());
+                }
+                Ok(response) => last_error = format!("block/put returned {}", response.status()),
+                Err(e) => last_error = format!("block/put failed: {}", e),
+            }
+
+            warn!(
+                "Block {} upload attempt {}/{} failed: {}",
+                expected, attempt, BLOCK_PUT_ATTEMPTS, last_error
+            );
+            tokio::time::sleep(Duration::from_millis(500 * attempt as u64)).await;
+        }
+
+        Err(last_error)
     }
 
     /// Get content from IPFS (tries local first, then gateways)
@@ -487,6 +764,7 @@ impl IpfsManager {
                     .await
                     .map_err(|e| format!("Failed to read IPFS content: {}", e))?
                     .to_vec();
+                self.touch_pin(&config.repo_path.join(LEDGER_FILE), cid).await;
 
                 return Ok(IpfsContent {
                     cid: cid.to_string(),
@@ -535,37 +813,86 @@ impl IpfsManager {
 
     /// Pin content to local node
     pub async fn pin(&self, cid: &str) -> Result<(), String> {
-        let config = self.config.read().await;
-        let api_url = format!(
-            "http://127.0.0.1:{}/api/v0/pin/add?arg={}",
-            config.api_port, cid
-        );
+        self.pin_as(cid, ContentCategory::Other, false).await
+    }
+
+    /// Pin content under a category, first unpinning least recently used
+    /// unprotected content in that category if the pin would exceed its quota
+    pub async fn pin_as(
+        &self,
+        cid: &str,
+        category: ContentCategory,
+        protected: bool,
+    ) -> Result<(), String> {
+        let (api_base, ledger_path, quota) = {
+            let config = self.config.read().await;
+            (
+                format!("http://127.0.0.1:{}/api/v0", config.api_port),
+                config.repo_path.join(LEDGER_FILE),
+                config.quotas.get(category),
+            )
+        };
+
+        let size = self.content_size(&api_base, cid).await?;
+
+        if let Some(quota) = quota {
+            let victims = self
+                .ledger
+                .read()
+                .await
+                .plan_eviction(category, quota, cid, size)
+                .ok_or_else(|| {
+                    format!(
+                        "Pinning {} ({} bytes) would exceed the {:?} quota of {} bytes",
+                        cid, size, category, quota
+                    )
+                })?;
+            for victim in victims {
+                info!("Unpinning {} to stay within the {:?} quota", victim, category);
+                self.unpin_remote(&api_base, &victim).await?;
+                self.ledger.write().await.remove(&victim);
+            }
+        }
 
         let response = self
             .http_client
-            .post(&api_url)
+            .post(format!("{}/pin/add?arg={}", api_base, cid))
             .send()
             .await
             .map_err(|e| format!("Failed to pin: {}", e))?;
 
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            Err(format!("Pin failed: {}", response.status()))
+        if !response.status().is_success() {
+            return Err(format!("Pin failed: {}", response.status()));
         }
+
+        self.record_pin(&ledger_path, cid, category, size, protected)
+            .await;
+        Ok(())
     }
 
     /// Unpin content from local node
     pub async fn unpin(&self, cid: &str) -> Result<(), String> {
-        let config = self.config.read().await;
-        let api_url = format!(
-            "http://127.0.0.1:{}/api/v0/pin/rm?arg={}",
-            config.api_port, cid
-        );
+        let (api_base, ledger_path) = {
+            let config = self.config.read().await;
+            (
+                format!("http://127.0.0.1:{}/api/v0", config.api_port),
+                config.repo_path.join(LEDGER_FILE),
+            )
+        };
+
+        self.unpin_remote(&api_base, cid).await?;
+
+        let mut ledger = self.ledger.write().await;
+        if ledger.remove(cid).is_some() {
+            ledger.save(&ledger_path)?;
+        }
+        Ok(())
+    }
 
+    async fn unpin_remote(&self, api_base: &str, cid: &str) -> Result<(), String> {
         let response = self
             .http_client
-            .post(&api_url)
+            .post(format!("{}/pin/rm?arg={}", api_base, cid))
             .send()
             .await
             .map_err(|e| format!("Failed to unpin: {}", e))?;
@@ -577,6 +904,452 @@ impl IpfsManager {
         }
     }
 
+    /// Total size of a DAG, fetching it if the daemon does not have it yet
+    async fn content_size(&self, api_base: &str, cid: &str) -> Result<u64, String> {
+        let stat: serde_json::Value = self
+            .http_client
+            .post(format!("{}/files/stat?arg=/ipfs/{}", api_base, cid))
+            .timeout(Duration::from_secs(120))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to stat {}: {}", cid, e))?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse stat of {}: {}", cid, e))?;
+
+        stat.get("CumulativeSize")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| format!("No size reported for {}", cid))
+    }
+
+    async fn record_pin(
+        &self,
+        ledger_path: &std::path::Path,
+        cid: &str,
+        category: ContentCategory,
+        size_bytes: u64,
+        protected: bool,
+    ) {
+        let now = unix_now();
+        let mut ledger = self.ledger.write().await;
+        // Keep the category of content that was already classified
+        let category = match ledger.get(cid) {
+            Some(existing) if category == ContentCategory::Other => existing.category,
+            _ => category,
+        };
+        ledger.insert(PinRecord {
+            cid: cid.to_string(),
+            category,
+            size_bytes,
+            protected,
+            pinned_at: now,
+            last_accessed: now,
+        });
+        if let Err(e) = ledger.save(ledger_path) {
+            warn!("{}", e);
+        }
+    }
+
+    async fn touch_pin(&self, ledger_path: &std::path::Path, cid: &str) {
+        let mut ledger = self.ledger.write().await;
+        if ledger.touch(cid, unix_now()) {
+            if let Err(e) = ledger.save(ledger_path) {
+                warn!("{}", e);
+            }
+        }
+    }
+
+    /// Repo size and configured maximum, if the daemon is reachable
+    async fn repo_stat(&self, api_base: &str) -> (Option<u64>, Option<u64>) {
+        let stat: serde_json::Value = match self
+            .http_client
+            .post(format!("{}/repo/stat", api_base))
+            .send()
+            .await
+        {
+            Ok(response) => response.json().await.unwrap_or_default(),
+            Err(_) => return (None, None),
+        };
+        (
+            stat.get("RepoSize").and_then(|v| v.as_u64()),
+            stat.get("StorageMax").and_then(|v| v.as_u64()),
+        )
+    }
+
+    /// Disk usage broken down by content category
+    pub async fn get_usage(&self) -> StorageUsage {
+        let (api_base, quotas) = {
+            let config = self.config.read().await;
+            (
+                format!("http://127.0.0.1:{}/api/v0", config.api_port),
+                config.quotas.clone(),
+            )
+        };
+        let (repo_size, storage_max) = self.repo_stat(&api_base).await;
+
+        let ledger = self.ledger.read().await;
+        StorageUsage {
+            categories: ledger.usage(&quotas),
+            tracked_bytes: ledger.total_bytes(),
+            repo_size,
+            storage_max,
+        }
+    }
+
+    /// Unpin least recently used content in categories over quota, drop
+    /// ledger entries pinned elsewhere, then run the daemon's repo GC
+    pub async fn gc_run(&self) -> Result<GcReport, String> {
+        let (api_base, ledger_path, quotas) = {
+            let config = self.config.read().await;
+            (
+                format!("http://127.0.0.1:{}/api/v0", config.api_port),
+                config.repo_path.join(LEDGER_FILE),
+                config.quotas.clone(),
+            )
+        };
+
+        let pinned: std::collections::HashSet<String> =
+            self.list_pins().await?.into_iter().collect();
+        let mut report = GcReport {
+            stale_records: self
+                .ledger
+                .write()
+                .await
+                .retain_pinned(|cid| pinned.contains(cid)),
+            ..Default::default()
+        };
+
+        let victims = self.ledger.read().await.over_quota(&quotas);
+        for victim in victims {
+            self.unpin_remote(&api_base, &victim).await?;
+            if let Some(record) = self.ledger.write().await.remove(&victim) {
+                report.unpinned_bytes += record.size_bytes;
+            }
+            report.unpinned.push(victim);
+        }
+        self.ledger.read().await.save(&ledger_path)?;
+
+        report.repo_size_before = self.repo_stat(&api_base).await.0;
+        let response = self
+            .http_client
+            .post(format!("{}/repo/gc", api_base))
+            .timeout(Duration::from_secs(600))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to run repo GC: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Repo GC failed: {}", response.status()));
+        }
+        // The GC response streams one line per removed block; wait for it to finish
+        let _ = response.bytes().await;
+        report.repo_size_after = self.repo_stat(&api_base).await.0;
+
+        info!(
+            "IPFS GC unpinned {} item(s) ({} bytes), repo {:?} -> {:?} bytes",
+            report.unpinned.len(),
+            report.unpinned_bytes,
+            report.repo_size_before,
+            report.repo_size_after
+        );
+        Ok(report)
+    }
+
+    /// Registered marketplace licenses and the health of their pins
+    pub async fn list_licenses(&self) -> Vec<LicenseRecord> {
+        self.licenses.read().await.list()
+    }
+
+    /// Register a purchased or deployed model and pin its weights as
+    /// protected model content, remotely too if the license asks for it.
+    /// Registering the same model again renews the license.
+    pub async fn register_license(&self, license: ModelLicense) -> Result<LicenseRecord, String> {
+        if license.weight_cids.is_empty() {
+            return Err(format!("License for {} lists no weight CIDs", license.model_id));
+        }
+        if !license.is_active(unix_now()) {
+            return Err(format!("License for {} has already lapsed", license.model_id));
+        }
+        let (licenses_path, remote_configured) = {
+            let config = self.config.read().await;
+            (
+                config.repo_path.join(LICENSES_FILE),
+                config.remote_pinning.is_some(),
+            )
+        };
+        if license.remote && !remote_configured {
+            return Err("No remote pinning service is configured".to_string());
+        }
+
+        let model_id = license.model_id.clone();
+        {
+            // Content pinned outside any license stays pinned when it lapses
+            let pins = self.ledger.read().await;
+            let mut licenses = self.licenses.write().await;
+            let adopted = license
+                .weight_cids
+                .iter()
+                .filter(|cid| pins.get(cid).is_some() && !licenses.covers(cid))
+                .cloned()
+                .collect();
+            licenses.upsert(license, adopted, unix_now());
+            licenses.save(&licenses_path)?;
+        }
+        info!("Registered license for model {}", model_id);
+
+        self.sync_license_pins(&model_id, true).await
+    }
+
+    /// Check every active license's pins, pinning licensed content again
+    /// if it has gone missing
+    pub async fn check_license_health(&self) -> Vec<LicenseRecord> {
+        let now = unix_now();
+        let model_ids: Vec<String> = self
+            .licenses
+            .read()
+            .await
+            .list()
+            .into_iter()
+            .filter(|record| record.license.is_active(now))
+            .map(|record| record.license.model_id)
+            .collect();
+        for model_id in model_ids {
+            if let Err(e) = self.sync_license_pins(&model_id, false).await {
+                warn!("License health check for {} failed: {}", model_id, e);
+            }
+        }
+        self.list_licenses().await
+    }
+
+    /// Release every license that has lapsed, returning the released ones
+    pub async fn expire_licenses(&self) -> Vec<LicenseRecord> {
+        let expired = self.licenses.read().await.expired(unix_now());
+        let mut released = Vec::new();
+        for model_id in expired {
+            match self.release_license(&model_id).await {
+                Ok(Some(record)) => released.push(record),
+                Ok(None) => {}
+                Err(e) => warn!("Failed to release lapsed license for {}: {}", model_id, e),
+            }
+        }
+        released
+    }
+
+    /// Forget a license and unpin the weights no other active license needs.
+    /// Content that was pinned before the license is left in place.
+    pub async fn release_license(&self, model_id: &str) -> Result<Option<LicenseRecord>, String> {
+        let (licenses_path, remote) = {
+            let config = self.config.read().await;
+            (
+                config.repo_path.join(LICENSES_FILE),
+                config.remote_pinning.clone(),
+            )
+        };
+        let now = unix_now();
+        let (record, releasable, remote_releasable) = {
+            let licenses = self.licenses.read().await;
+            let Some(record) = licenses.get(model_id).cloned() else {
+                return Ok(None);
+            };
+            let releasable = licenses.releasable(model_id, now);
+            let remote_releasable: Vec<String> = if record.license.remote {
+                record
+                    .license
+                    .weight_cids
+                    .iter()
+                    .filter(|cid| !licenses.remote_elsewhere(model_id, cid, now))
+                    .cloned()
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            (record, releasable, remote_releasable)
+        };
+
+        for cid in &releasable {
+            if let Err(e) = self.unpin(cid).await {
+                warn!("Failed to unpin {} of model {}: {}", cid, model_id, e);
+            }
+        }
+        if let Some(service) = &remote {
+            for cid in &remote_releasable {
+                if let Err(e) = self.remote_unpin(service, cid).await {
+                    warn!("Failed to remove remote pin {} of model {}: {}", cid, model_id, e);
+                }
+            }
+        }
+
+        let mut licenses = self.licenses.write().await;
+        licenses.remove(model_id);
+        licenses.save(&licenses_path)?;
+        info!(
+            "Released license for model {} ({} pin(s) removed)",
+            model_id,
+            releasable.len()
+        );
+        Ok(Some(record))
+    }
+
+    /// Make sure the weights of `model_id` are pinned and record the result.
+    /// With `pin_adopted` false, adopted content is only checked.
+    async fn sync_license_pins(
+        &self,
+        model_id: &str,
+        pin_adopted: bool,
+    ) -> Result<LicenseRecord, String> {
+        let (api_base, licenses_path, remote) = {
+            let config = self.config.read().await;
+            (
+                format!("http://127.0.0.1:{}/api/v0", config.api_port),
+                config.repo_path.join(LICENSES_FILE),
+                config.remote_pinning.clone(),
+            )
+        };
+        let record = self
+            .licenses
+            .read()
+            .await
+            .get(model_id)
+            .cloned()
+            .ok_or_else(|| format!("No license registered for {}", model_id))?;
+
+        let mut health = Vec::with_capacity(record.license.weight_cids.len());
+        for cid in &record.license.weight_cids {
+            let mut errors = Vec::new();
+            let adopted = record.adopted.contains(cid);
+            let local = match self.is_pinned(&api_base, cid).await {
+                Ok(true) => PinHealth::Pinned,
+                Ok(false) if adopted && !pin_adopted => PinHealth::Missing,
+                Ok(false) => match self.pin_as(cid, ContentCategory::Models, !adopted).await {
+                    Ok(()) => PinHealth::Pinned,
+                    Err(e) => {
+                        errors.push(e);
+                        PinHealth::Failed
+                    }
+                },
+                Err(e) => {
+                    errors.push(e);
+                    PinHealth::Failed
+                }
+            };
+            let remote = match (&remote, record.license.remote) {
+                (Some(service), true) => match self.remote_pin(service, cid, model_id).await {
+                    Ok(status) => Some(status),
+                    Err(e) => {
+                        errors.push(e);
+                        Some(PinHealth::Failed)
+                    }
+                },
+                (None, true) => {
+                    errors.push("No remote pinning service is configured".to_string());
+                    Some(PinHealth::Failed)
+                }
+                (_, false) => None,
+            };
+            health.push(CidHealth {
+                cid: cid.clone(),
+                local,
+                remote,
+                checked_at: unix_now(),
+                error: (!errors.is_empty()).then(|| errors.join("; ")),
+            });
+        }
+
+        let mut licenses = self.licenses.write().await;
+        licenses.set_health(model_id, health);
+        licenses.save(&licenses_path)?;
+        licenses
+            .get(model_id)
+            .cloned()
+            .ok_or_else(|| format!("License for {} was released during the check", model_id))
+    }
+
+    /// Whether the daemon holds a recursive pin of `cid`
+    async fn is_pinned(&self, api_base: &str, cid: &str) -> Result<bool, String> {
+        let response = self
+            .http_client
+            .post(format!("{}/pin/ls?arg={}&type=recursive", api_base, cid))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to check pin of {}: {}", cid, e))?;
+        // The daemon answers an error status for CIDs it does not pin
+        Ok(response.status().is_success())
+    }
+
+    /// Ask the remote service to pin `cid`, reusing an existing request
+    async fn remote_pin(
+        &self,
+        service: &RemotePinningConfig,
+        cid: &str,
+        name: &str,
+    ) -> Result<PinHealth, String> {
+        if let Some((_, status)) = self.remote_requests(service, cid).await?.into_iter().next() {
+            return Ok(status);
+        }
+
+        let response: serde_json::Value = self
+            .http_client
+            .post(format!("{}/pins", service.endpoint.trim_end_matches('/')))
+            .bearer_auth(&service.access_token)
+            .json(&serde_json::json!({ "cid": cid, "name": name }))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Remote pin of {} failed: {}", cid, e))?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse remote pin of {}: {}", cid, e))?;
+        Ok(remote_pin_health(&response))
+    }
+
+    /// Remove every remote pin request for `cid`
+    async fn remote_unpin(&self, service: &RemotePinningConfig, cid: &str) -> Result<(), String> {
+        let endpoint = service.endpoint.trim_end_matches('/');
+        for (request_id, _) in self.remote_requests(service, cid).await? {
+            self.http_client
+                .delete(format!("{}/pins/{}", endpoint, request_id))
+                .bearer_auth(&service.access_token)
+                .send()
+                .await
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| format!("Failed to remove remote pin {}: {}", request_id, e))?;
+        }
+        Ok(())
+    }
+
+    /// Request IDs and states of the remote service's pin requests for `cid`
+    async fn remote_requests(
+        &self,
+        service: &RemotePinningConfig,
+        cid: &str,
+    ) -> Result<Vec<(String, PinHealth)>, String> {
+        let response: serde_json::Value = self
+            .http_client
+            .get(format!("{}/pins", service.endpoint.trim_end_matches('/')))
+            .bearer_auth(&service.access_token)
+            .query(&[("cid", cid), ("status", "queued,pinning,pinned,failed")])
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Failed to query remote pins of {}: {}", cid, e))?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse remote pins of {}: {}", cid, e))?;
+
+        Ok(response
+            .get("results")
+            .and_then(|v| v.as_array())
+            .map(|results| {
+                results
+                    .iter()
+                    .filter_map(|result| {
+                        let request_id = result.get("requestid")?.as_str()?.to_string();
+                        Some((request_id, remote_pin_health(result)))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
     /// List pinned content
     pub async fn list_pins(&self) -> Result<Vec<String>, String> {
         let config = self.config.read().await;
@@ -640,6 +1413,38 @@ impl IpfsManager {
     }
 }
 
+/// Health of a Pinning Service API pin status object
+fn remote_pin_health(status: &serde_json::Value) -> PinHealth {
+    match status.get("status").and_then(|v| v.as_str()) {
+        Some("pinned") => PinHealth::Pinned,
+        Some("queued") | Some("pinning") => PinHealth::Pending,
+        _ => PinHealth::Failed,
+    }
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Fill `buffer` from `file`, returning fewer bytes only at end of file
+async fn read_chunk(file: &mut tokio::fs::File, buffer: &mut [u8]) -> Result<usize, String> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        let read = file
+            .read(&mut buffer[filled..])
+            .await
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        filled += read;
+    }
+    Ok(filled)
+}
+
 impl Default for IpfsManager {
     fn default() -> Self {
         Self::new()
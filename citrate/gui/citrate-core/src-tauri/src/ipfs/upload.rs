@@ -0,0 +1,255 @@
+//! Chunked, resumable IPFS uploads
+//!
+//! Large files (multi-GB GGUF weights) are split into fixed-size raw blocks whose
+//! CIDs are computed locally. Before a block is sent, the daemon is asked whether
+//! it already holds it, so an interrupted upload resumes where it stopped instead
+//! of re-sending gigabytes. The blocks are then linked into a balanced UnixFS
+//! file DAG whose root is pinned.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Size of each raw leaf block (1 MiB)
+pub const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Maximum links per intermediate DAG node (same fan-out as kubo's balanced layout)
+pub const MAX_LINKS_PER_NODE: usize = 174;
+
+/// Multicodec code for raw binary blocks
+pub const CODEC_RAW: u64 = 0x55;
+
+/// Multicodec code for dag-pb nodes
+pub const CODEC_DAG_PB: u64 = 0x70;
+
+/// Multihash code for sha2-256
+const MULTIHASH_SHA2_256: u64 = 0x12;
+
+/// Progress of a chunked upload, emitted as the `ipfs-upload-progress` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadProgress {
+    pub upload_id: String,
+    pub name: String,
+    /// "hashing" | "uploading" | "linking" | "pinning" | "complete" | "failed"
+    pub status: String,
+    /// Bytes actually transferred to the daemon
+    pub bytes_sent: u64,
+    /// Bytes skipped because the daemon already had the block
+    pub bytes_skipped: u64,
+    pub total_bytes: u64,
+    /// Transfer rate in bytes per second since the upload started
+    pub rate_bytes_per_sec: u64,
+    pub chunks_done: u64,
+    pub chunks_total: u64,
+    pub cid: Option<String>,
+    pub error: Option<String>,
+}
+
+impl UploadProgress {
+    /// Processed bytes (sent + skipped) as a percentage of the file size
+    pub fn percent(&self) -> f64 {
+        if self.total_bytes == 0 {
+            return 100.0;
+        }
+        ((self.bytes_sent + self.bytes_skipped) as f64 / self.total_bytes as f64 * 100.0).min(100.0)
+    }
+}
+
+/// A content-addressed block reference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cid {
+    pub codec: u64,
+    pub digest: [u8; 32],
+}
+
+impl Cid {
+    /// CIDv1 with a sha2-256 multihash over `data`
+    pub fn new(codec: u64, data: &[u8]) -> Self {
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(&Sha256::digest(data));
+        Self { codec, digest }
+    }
+
+    /// Binary CID: version, codec and multihash
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(36);
+        write_varint(&mut out, 1);
+        write_varint(&mut out, self.codec);
+        write_varint(&mut out, MULTIHASH_SHA2_256);
+        write_varint(&mut out, 32);
+        out.extend_from_slice(&self.digest);
+        out
+    }
+
+    /// Canonical string form: multibase base32 (lowercase, unpadded)
+    pub fn to_string_base32(&self) -> String {
+        format!("b{}", base32_lower(&self.to_bytes()))
+    }
+}
+
+impl std::fmt::Display for Cid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_string_base32())
+    }
+}
+
+/// A child of a UnixFS file node
+#[derive(Debug, Clone)]
+pub struct DagLink {
+    pub cid: Cid,
+    /// File bytes covered by the child subtree
+    pub file_size: u64,
+    /// Serialized size of the child subtree (block sizes summed)
+    pub tree_size: u64,
+}
+
+/// Encode a dag-pb UnixFS file node linking `links` in order
+pub fn encode_file_node(links: &[DagLink]) -> Vec<u8> {
+    // UnixFS Data { Type = File, filesize, blocksizes }
+    let file_size: u64 = links.iter().map(|l| l.file_size).sum();
+    let mut unixfs = Vec::new();
+    write_varint(&mut unixfs, 0x08);
+    write_varint(&mut unixfs, 2);
+    write_varint(&mut unixfs, 0x18);
+    write_varint(&mut unixfs, file_size);
+    for link in links {
+        write_varint(&mut unixfs, 0x20);
+        write_varint(&mut unixfs, link.file_size);
+    }
+
+    // PBNode: Links (field 2) are serialized before Data (field 1)
+    let mut node = Vec::new();
+    for link in links {
+        let cid = link.cid.to_bytes();
+        let mut pb_link = Vec::new();
+        write_varint(&mut pb_link, 0x0a);
+        write_varint(&mut pb_link, cid.len() as u64);
+        pb_link.extend_from_slice(&cid);
+        write_varint(&mut pb_link, 0x12);
+        write_varint(&mut pb_link, 0);
+        write_varint(&mut pb_link, 0x18);
+        write_varint(&mut pb_link, link.tree_size);
+
+        write_varint(&mut node, 0x12);
+        write_varint(&mut node, pb_link.len() as u64);
+        node.extend_from_slice(&pb_link);
+    }
+    write_varint(&mut node, 0x0a);
+    write_varint(&mut node, unixfs.len() as u64);
+    node.extend_from_slice(&unixfs);
+    node
+}
+
+/// Group links into parent nodes of at most `MAX_LINKS_PER_NODE` children.
+///
+/// Returns the encoded parent blocks together with the links pointing at them;
+/// callers repeat until a single link (the root) remains.
+pub fn build_layer(links: &[DagLink]) -> Vec<(Vec<u8>, DagLink)> {
+    links
+        .chunks(MAX_LINKS_PER_NODE)
+        .map(|children| {
+            let block = encode_file_node(children);
+            let link = DagLink {
+                cid: Cid::new(CODEC_DAG_PB, &block),
+                file_size: children.iter().map(|l| l.file_size).sum(),
+                tree_size: block.len() as u64 + children.iter().map(|l| l.tree_size).sum::<u64>(),
+            };
+            (block, link)
+        })
+        .collect()
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn base32_lower(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+    let mut out = String::with_capacity(data.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_varint_encoding() {
+        let mut out = Vec::new();
+        write_varint(&mut out, 300);
+        assert_eq!(out, vec![0xac, 0x02]);
+    }
+
+    #[test]
+    fn test_raw_cid_matches_known_value() {
+        // `echo -n hello | ipfs add --cid-version 1 --raw-leaves`
+        let cid = Cid::new(CODEC_RAW, b"hello");
+        assert_eq!(
+            cid.to_string(),
+            "bafkreibm6jg3ux5qumhcn2b3flc3tyu6dmlb4xa7u5bf44yegnrjhc4yeq"
+        );
+    }
+
+    #[test]
+    fn test_layering_reduces_to_single_root() {
+        let leaves: Vec<DagLink> = (0..(MAX_LINKS_PER_NODE * 2 + 3))
+            .map(|i| {
+                let data = vec![i as u8; 16];
+                DagLink {
+                    cid: Cid::new(CODEC_RAW, &data),
+                    file_size: data.len() as u64,
+                    tree_size: data.len() as u64,
+                }
+            })
+            .collect();
+
+        let mut layer = leaves.clone();
+        let mut depth = 0;
+        while layer.len() > 1 {
+            layer = build_layer(&layer).into_iter().map(|(_, l)| l).collect();
+            depth += 1;
+        }
+        assert_eq!(depth, 2);
+        assert_eq!(layer[0].file_size, 16 * leaves.len() as u64);
+        assert_eq!(layer[0].cid.codec, CODEC_DAG_PB);
+
+        // Encoding is deterministic, which is what makes uploads resumable
+        let again = build_layer(&leaves);
+        let first = build_layer(&leaves);
+        assert_eq!(again[0].1.cid, first[0].1.cid);
+    }
+
+    #[test]
+    fn test_progress_percent() {
+        let progress = UploadProgress {
+            upload_id: "u".into(),
+            name: "model.gguf".into(),
+            status: "uploading".into(),
+            bytes_sent: 25,
+            bytes_skipped: 25,
+            total_bytes: 200,
+            rate_bytes_per_sec: 0,
+            chunks_done: 1,
+            chunks_total: 4,
+            cid: None,
+            error: None,
+        };
+        assert_eq!(progress.percent(), 25.0);
+    }
+}
@@ -10,52 +10,104 @@ use tracing::{info, warn};
 mod agent;
 mod block_producer;
 mod dag;
+mod data_wipe;
 mod dev_mode;
+mod diagnostics;
+mod event_bus;
+mod governance;
 mod gpu;
 mod huggingface;
+mod i18n;
 mod image_models;
 mod ipfs;
 mod models;
 mod node;
+mod profiles;
 mod rpc_client;
 mod sync;
 mod terminal;
+mod updater;
 mod wallet;
+mod walletconnect;
 mod windows;
 // network_service integration is pending; module intentionally not included for now
 
 use agent::AgentState;
-use dag::{BlockDetails, DAGData, DAGManager, TipInfo};
-use citrate_network::NetworkMessage;
+use agent::scheduler::AGENT_TASK_COMPLETED_EVENT;
+use dag::remote::RemoteDag;
+use dag::{BlockDetails, DAGData, DAGManager, DAGSearchResult, TipInfo};
+use data_wipe::WipePlan;
+use diagnostics::DiagnosticsReport;
+use event_bus::{EventBusConfig, StateDeltaBus, STATE_DELTA_EVENT};
+use governance::{GovernanceConfig, ProposalInfo, VoteInfo};
+use i18n::{localize_err, Locale, LocaleInfo};
+use citrate_network::{NetworkMessage, SyncProgress};
+use citrate_execution::precompiles::proposals::VoteSupport;
 use citrate_sequencer::mempool::TxClass;
+use citrate_storage::chain::DagStatsBucket;
 use models::{
     InferenceRequest, InferenceResponse, JobStatus, ModelDeployment, ModelInfo, ModelManager,
     TrainingJob, LoraConfig, LoraTrainingConfig, LoraTrainingJob, LoraAdapterInfo,
-    DatasetFormat, DatasetValidation, LoraPreset,
+    DatasetFormat, DatasetValidation, LoraPreset, GgufQuantization, LORA_JOB_COMPLETED_EVENT,
+    DatasetReport, PreprocessConfig, BenchmarkConfig, BenchmarkResult,
 };
 use node::TxActivity;
 use node::TxOverview;
 use node::{NodeConfig, NodeManager, NodeStatus};
-use node::{PeerSummary, PendingTx};
-use wallet::{Account, FirstTimeSetupResult, TransactionRequest, WalletManager};
-use windows::{WindowManager, WindowType, WindowState};
+use node::{PeerReputationSummary, PeerSummary, PendingTx};
+use node::{UpgradeNotice, UPGRADE_AVAILABLE_EVENT};
+use node::activity::WALLET_ACTIVITY_CHANGED_EVENT;
+use node::confirmations::TX_CONFIRMED_EVENT;
+use sync::iterative_sync::SYNC_PROGRESS_EVENT;
+use profiles::{ProfileInfo, ProfileStore, PROFILE_CHANGED_EVENT};
+use wallet::{
+    Account, FirstTimeSetupResult, RemoteSignerStatus, TransactionRequest, WalletManager,
+};
+use wallet::contacts::{Contact, ContactDraft, RecentAddress, ResolvedRecipient};
+use wallet::deployments::{ContractDeployment, DeploymentStatus};
+use wallet::multisig::{
+    MultisigAccount, MultisigDraft, MultisigProposalInfo, MultisigWalletInfo,
+};
+use wallet::payments::{PaymentDraft, PaymentRun, ScheduledPayment, SCHEDULED_PAYMENT_EVENT};
+use walletconnect::{
+    PendingRequest, Session, SessionProposal, WalletConnectConfig, WalletConnectManager,
+    WALLETCONNECT_EVENT,
+};
+use updater::{
+    ReleaseInfo, UpdateChannel, UpdateStatus, UpdaterConfig, UpdaterManager, CHECK_INTERVAL,
+    UPDATER_EVENT,
+};
+use windows::{
+    AccessibilityPreferences, AccessibleWindow, FocusDirection, WindowManager, WindowType,
+    WindowState,
+};
 use terminal::{TerminalManager, TerminalConfig, TerminalInfo};
-use ipfs::{IpfsManager, IpfsStatus, IpfsConfig, IpfsAddResult, IpfsContent};
+use ipfs::{
+    ContentCategory, GcReport, IpfsAddResult, IpfsConfig, IpfsContent, IpfsManager, IpfsStatus,
+    LicenseRecord, ModelLicense, StorageUsage, LICENSE_CHECK_INTERVAL,
+};
 use huggingface::{
     HuggingFaceManager, HFConfig, HFModelInfo, HFModelFile,
     ModelSearchParams, DownloadProgress, AuthState as HFAuthState, OAuthToken,
     GGUFModelInfo, GGUFFileInfo, LocalModelInfo,
+    DatasetSearchParams, HFDatasetInfo, DatasetDownloadParams, DatasetDownloadResult,
+    GGUF_CONVERSION_EVENT,
 };
 use gpu::{
     GPUResourceManager, GPUDevice, GPUAllocationSettings, GPUStats,
-    ProviderStatus, ComputeJob, ComputeJobType, ComputeJobStatus,
+    ProviderStatus, ComputeJob, ComputeJobType, ComputeJobStatus, JobPriorityClass,
+    GPU_JOB_STATE_CHANGED_EVENT,
 };
+use gpu::settlement::JobSettlement;
 use image_models::{
     ImageModelManager, ImageModel, ImageGenerationRequest, GenerationJob,
     ImageTrainingConfig, ImageTrainingJob, GeneratedImage, ImageResolution,
-    Scheduler as ImageScheduler,
+    Scheduler as ImageScheduler, IMAGE_GENERATION_PROGRESS_EVENT,
+    ConceptReport, ImageLora, LoRAWeight, IMAGE_TRAINING_PREVIEW_EVENT,
 };
 
+use dev_mode::block_production::{dev_mine_blocks, dev_set_automine, dev_set_block_interval};
+
 // Re-export agent commands
 use agent::commands::{
     agent_approve_tool, agent_clear_history, agent_create_session, agent_delete_session,
@@ -63,6 +115,21 @@ use agent::commands::{
     agent_get_pending_tools, agent_get_session, agent_get_status, agent_is_ready,
     agent_list_sessions, agent_load_local_model, agent_reject_tool, agent_scan_local_models,
     agent_send_message, agent_set_api_key, agent_set_auto_mode, agent_update_config,
+    agent_cancel_stream, agent_set_sampling, agent_get_safety_policy, agent_set_safety_policy,
+    // Prompt library commands
+    agent_list_prompts, agent_get_prompt, agent_save_prompt, agent_delete_prompt,
+    agent_set_default_prompt, agent_export_prompts, agent_import_prompts,
+    agent_set_session_prompt, agent_get_session_prompt,
+    // Provider routing commands
+    agent_set_session_route, agent_get_session_route, agent_get_provider_health,
+    agent_check_provider_health,
+    // Scheduled task commands
+    agent_list_tasks, agent_create_task, agent_update_task, agent_set_task_enabled,
+    agent_delete_task, agent_run_task_now,
+    // Long-term memory commands
+    agent_search_memory,
+    // File access commands
+    agent_list_file_grants, agent_grant_directory, agent_revoke_directory, agent_file_audit_log,
     // Multi-provider AI configuration commands
     get_ai_providers_config, get_ai_provider_keys, update_ai_providers_config,
     save_ai_providers_config, test_ai_provider_connection, pin_local_model_to_ipfs, delete_local_model,
@@ -91,6 +158,10 @@ struct AppState {
     hf_manager: Arc<HuggingFaceManager>,
     gpu_manager: Arc<GPUResourceManager>,
     image_model_manager: Arc<ImageModelManager>,
+    event_bus: Arc<StateDeltaBus>,
+    walletconnect_manager: Arc<WalletConnectManager>,
+    updater: Arc<UpdaterManager>,
+    profiles: Arc<RwLock<ProfileStore>>,
 }
 
 // ===== Node Commands =====
@@ -169,7 +240,7 @@ async fn stop_node(state: State<'_, AppState>) -> Result<String, String> {
         .stop()
         .await
         .map(|_| "Node stopped successfully".to_string())
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -179,7 +250,54 @@ async fn get_node_status(state: State<'_, AppState>) -> Result<NodeStatus, Strin
         .node_manager
         .get_status()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
+}
+
+/// Current sync progress; updates arrive as `sync-progress` events
+#[tauri::command]
+async fn get_sync_progress(state: State<'_, AppState>) -> Result<Option<SyncProgress>, String> {
+    Ok(state.node_manager.sync_progress().await)
+}
+
+/// Check ports, disk space, clock, IPFS, bootnodes, the chain database and
+/// the GPU driver, with remediation hints for anything that needs fixing
+#[tauri::command]
+async fn run_diagnostics(state: State<'_, AppState>) -> Result<DiagnosticsReport, String> {
+    let config = state.node_manager.get_config().await;
+    let node_running = state
+        .node_manager
+        .get_status()
+        .await
+        .map(|status| status.running)
+        .unwrap_or(false);
+    let gpus = state.gpu_manager.get_devices().await;
+    Ok(diagnostics::run(&config, node_running, &state.ipfs_manager, &gpus).await)
+}
+
+/// Use an already running node as the backend instead of the embedded one,
+/// e.g. a validator on a server. Status, peers, mempool, database and DAG
+/// views then show that node.
+#[tauri::command]
+async fn node_attach_external(
+    state: State<'_, AppState>,
+    rpc_url: String,
+) -> Result<NodeStatus, String> {
+    let status = state
+        .node_manager
+        .attach_external(&rpc_url)
+        .await
+        .map_err(localize_err)?;
+    *state.dag_manager.write().await = None;
+    Ok(status)
+}
+
+#[tauri::command]
+async fn node_detach_external(state: State<'_, AppState>) -> Result<(), String> {
+    state
+        .node_manager
+        .detach_external()
+        .await
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -197,7 +315,7 @@ async fn update_node_config(
         .update_config(config)
         .await
         .map(|_| "Config updated successfully".to_string())
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 // ===== Network/Bootnode Commands =====
@@ -214,7 +332,7 @@ async fn add_bootnode(state: State<'_, AppState>, entry: String) -> Result<Strin
         .add_bootnode_entry(&entry)
         .await
         .map(|_| "Bootnode added".to_string())
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -224,7 +342,7 @@ async fn remove_bootnode(state: State<'_, AppState>, entry: String) -> Result<St
         .remove_bootnode_entry(&entry)
         .await
         .map(|_| "Bootnode removed".to_string())
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -233,7 +351,7 @@ async fn connect_bootnodes(state: State<'_, AppState>) -> Result<usize, String>
         .node_manager
         .connect_bootnodes_now()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -242,7 +360,7 @@ async fn connect_peer(state: State<'_, AppState>, entry: String) -> Result<Strin
         .node_manager
         .connect_peer(&entry)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -251,7 +369,7 @@ async fn disconnect_peer(state: State<'_, AppState>, peer_id: String) -> Result<
         .node_manager
         .disconnect_peer(&peer_id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -259,6 +377,68 @@ async fn get_peers(state: State<'_, AppState>) -> Result<Vec<PeerSummary>, Strin
     Ok(state.node_manager.get_peers_summary().await)
 }
 
+#[tauri::command]
+async fn get_peer_reputation(
+    state: State<'_, AppState>,
+) -> Result<Vec<PeerReputationSummary>, String> {
+    Ok(state.node_manager.get_peer_reputation().await)
+}
+
+#[tauri::command]
+async fn get_db_stats(state: State<'_, AppState>) -> Result<citrate_storage::db::DbStats, String> {
+    state
+        .node_manager
+        .get_db_stats()
+        .await
+        .map_err(localize_err)
+}
+
+/// What pruning would remove and free under the node's retention settings,
+/// without deleting anything
+#[tauri::command]
+async fn storage_prune_preview(
+    state: State<'_, AppState>,
+) -> Result<citrate_storage::pruning::PruningStats, String> {
+    state
+        .node_manager
+        .prune_preview()
+        .await
+        .map_err(localize_err)
+}
+
+/// Hourly chain statistics snapshots, unix seconds; defaults to the last week
+#[tauri::command]
+async fn get_stats_history(
+    state: State<'_, AppState>,
+    from: Option<u64>,
+    to: Option<u64>,
+) -> Result<Vec<citrate_storage::chain::ChainStatsSnapshot>, String> {
+    state
+        .node_manager
+        .get_stats_history(from, to)
+        .await
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn compact_database(
+    state: State<'_, AppState>,
+    column_family: Option<String>,
+) -> Result<Vec<String>, String> {
+    state
+        .node_manager
+        .compact_database(column_family.as_deref())
+        .await
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn get_peer_versions(
+    state: State<'_, AppState>,
+) -> Result<citrate_network::VersionDistribution, String> {
+    Ok(state.node_manager.get_peer_versions().await)
+}
+
 // ===== Wallet Activity =====
 
 #[tauri::command]
@@ -274,7 +454,7 @@ async fn get_account_activity(
         .node_manager
         .get_account_activity(&address, bw, lim)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -283,7 +463,7 @@ async fn get_tx_overview(state: State<'_, AppState>) -> Result<TxOverview, Strin
         .node_manager
         .get_tx_overview()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -295,7 +475,7 @@ async fn get_mempool_pending(
         .node_manager
         .get_mempool_pending(limit.unwrap_or(50))
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -308,17 +488,14 @@ async fn get_address_observed_balance(
         .node_manager
         .get_observed_balance(&address, block_window.unwrap_or(256))
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 // ===== Tracked Addresses =====
 
 /// Get the path to the tracked addresses file
 fn tracked_addresses_path() -> std::path::PathBuf {
-    dirs::data_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("citrate-core")
-        .join("tracked_addresses.json")
+    profiles::data_root().join("tracked_addresses.json")
 }
 
 #[tauri::command]
@@ -331,23 +508,189 @@ async fn get_tracked_addresses() -> Result<Vec<String>, String> {
         .map_err(|e| format!("Failed to read tracked addresses: {}", e))?;
     let addresses: Vec<String> = serde_json::from_str(&contents)
         .map_err(|e| format!("Failed to parse tracked addresses: {}", e))?;
-    Ok(addresses)
+    // Entries saved before checksums were enforced may no longer parse
+    Ok(addresses
+        .into_iter()
+        .filter(|address| {
+            let valid = citrate_execution::address_utils::is_valid_address(address.trim());
+            if !valid {
+                warn!("Dropping invalid tracked address {}", address);
+            }
+            valid
+        })
+        .collect())
 }
 
 #[tauri::command]
 async fn save_tracked_addresses(addresses: Vec<String>) -> Result<(), String> {
+    // Stored checksummed; mixed-case input that fails its checksum is rejected
+    let mut checksummed: Vec<String> = Vec::with_capacity(addresses.len());
+    for address in &addresses {
+        let parsed = citrate_execution::address_utils::address_from_hex(address.trim())?;
+        let formatted = citrate_execution::address_utils::to_checksum_address(&parsed);
+        if !checksummed.contains(&formatted) {
+            checksummed.push(formatted);
+        }
+    }
+
     let path = tracked_addresses_path();
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)
             .map_err(|e| format!("Failed to create directory: {}", e))?;
     }
-    let contents = serde_json::to_string_pretty(&addresses)
+    let contents = serde_json::to_string_pretty(&checksummed)
         .map_err(|e| format!("Failed to serialize addresses: {}", e))?;
     std::fs::write(&path, contents)
         .map_err(|e| format!("Failed to save tracked addresses: {}", e))?;
     Ok(())
 }
 
+// ===== Profiles =====
+
+#[tauri::command]
+async fn profile_list(state: State<'_, AppState>) -> Result<Vec<ProfileInfo>, String> {
+    Ok(state.profiles.read().await.list())
+}
+
+#[tauri::command]
+async fn profile_create(state: State<'_, AppState>, name: String) -> Result<ProfileInfo, String> {
+    state.profiles.write().await.create(&name)
+}
+
+/// Switch to another profile: the node is stopped and the node, wallet and
+/// DAG state are reloaded from the new profile's directories
+#[tauri::command]
+async fn profile_switch(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<ProfileInfo, String> {
+    let mut store = state.profiles.write().await;
+    if store.active() == name {
+        return store
+            .list()
+            .into_iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| format!("Profile '{}' not found", name));
+    }
+    if store.get(&name).is_none() {
+        return Err(tr!("error.profile_not_found", 0 = name));
+    }
+
+    state.node_manager.stop().await.map_err(localize_err)?;
+    *state.dag_manager.write().await = None;
+    *state.external_rpc.write().await = None;
+
+    let info = store.set_active(&name)?;
+    profiles::set_active_profile(&name);
+    state
+        .node_manager
+        .reload_config()
+        .await
+        .map_err(|e| format!("Failed to load node config for profile '{}': {}", name, e))?;
+    state
+        .wallet_manager
+        .reload()
+        .await
+        .map_err(|e| format!("Failed to load wallet for profile '{}': {}", name, e))?;
+    info!("Switched to profile '{}'", name);
+
+    let _ = app.emit(PROFILE_CHANGED_EVENT, &info);
+    Ok(info)
+}
+
+/// Delete a profile that is not active, with its keys and data
+#[tauri::command]
+async fn profile_delete(state: State<'_, AppState>, name: String) -> Result<(), String> {
+    state.profiles.write().await.remove(&name)?;
+    wallet::delete_profile_keys(&name);
+    profiles::remove_profile_dirs(&name)?;
+    info!("Deleted profile '{}'", name);
+    Ok(())
+}
+
+/// Remove the active profile's chain database so the node starts again from
+/// genesis, and its wallet keys unless `keep_wallet`. Without a matching
+/// `confirmation_token` nothing is removed and the plan is returned for
+/// review.
+#[tauri::command]
+async fn reset_chain_data(
+    state: State<'_, AppState>,
+    keep_wallet: bool,
+    confirmation_token: Option<String>,
+) -> Result<WipePlan, String> {
+    let profile = profiles::active_profile();
+    let config = state.node_manager.get_config().await;
+    let mut plan = data_wipe::plan_chain_reset(
+        &profile,
+        std::path::Path::new(&config.data_dir),
+        keep_wallet,
+    )?;
+    let Some(token) = confirmation_token else {
+        return Ok(plan);
+    };
+    plan.confirm(&token)?;
+
+    state.node_manager.stop().await.map_err(localize_err)?;
+    *state.dag_manager.write().await = None;
+    if !keep_wallet {
+        wallet::delete_profile_keys(&profile);
+    }
+    plan.execute(&token)?;
+    if !keep_wallet {
+        state
+            .wallet_manager
+            .reload()
+            .await
+            .map_err(|e| format!("Failed to reload wallet: {}", e))?;
+    }
+    info!(
+        "Reset chain data of profile '{}' ({} wallet)",
+        profile,
+        if keep_wallet { "kept" } else { "removed" }
+    );
+    Ok(plan)
+}
+
+/// Remove the chain database and node configuration of a profile, keeping
+/// its wallet. Confirmed the same way as `reset_chain_data`.
+#[tauri::command]
+async fn wipe_network_profile(
+    state: State<'_, AppState>,
+    name: String,
+    confirmation_token: Option<String>,
+) -> Result<WipePlan, String> {
+    if state.profiles.read().await.get(&name).is_none() {
+        return Err(tr!("error.profile_not_found", 0 = name));
+    }
+    let active = profiles::active_profile() == name;
+    let node_data_dir = if active {
+        Some(state.node_manager.get_config().await.data_dir)
+    } else {
+        None
+    };
+    let mut plan = data_wipe::plan_profile_wipe(&name, node_data_dir.as_deref())?;
+    let Some(token) = confirmation_token else {
+        return Ok(plan);
+    };
+    plan.confirm(&token)?;
+
+    if active {
+        state.node_manager.stop().await.map_err(localize_err)?;
+        *state.dag_manager.write().await = None;
+    }
+    plan.execute(&token)?;
+    if active {
+        state
+            .node_manager
+            .reload_config()
+            .await
+            .map_err(|e| format!("Failed to reload node config: {}", e))?;
+    }
+    info!("Wiped network data of profile '{}'", name);
+    Ok(plan)
+}
+
 #[derive(Debug, Clone, Deserialize)]
 struct JoinTestnetArgs {
     chain_id: Option<u64>,
@@ -381,7 +724,7 @@ fn copy_dir_all(src: &std::path::Path, dst: &std::path::Path) -> std::io::Result
 #[tauri::command]
 async fn join_testnet(state: State<'_, AppState>, args: JoinTestnetArgs) -> Result<String, String> {
     // Stop node if running
-    let _ = state.node_manager.stop().await.map_err(|e| e.to_string());
+    let _ = state.node_manager.stop().await.map_err(localize_err);
 
     // Load current config
     let mut cfg = state.node_manager.get_config().await;
@@ -412,11 +755,13 @@ async fn join_testnet(state: State<'_, AppState>, args: JoinTestnetArgs) -> Resu
     let is_relative = std::path::Path::new(&desired_data_dir).is_relative();
     let under_src_tauri = lower.contains("src-tauri");
     if is_relative || under_src_tauri {
-        // Use OS data dir: e.g., macOS ~/Library/Application Support/citrate-core/testnet
-        if let Some(mut base) = dirs::data_dir() {
-            base.push("citrate-core");
-            base.push("testnet");
-            desired_data_dir = base.to_string_lossy().to_string();
+        // Use the profile's data dir: e.g., macOS
+        // ~/Library/Application Support/citrate-core/testnet
+        if dirs::data_dir().is_some() {
+            desired_data_dir = profiles::data_root()
+                .join("testnet")
+                .to_string_lossy()
+                .to_string();
         } else {
             // Fallback to current working directory under gui-data/testnet
             desired_data_dir = "./gui-data/testnet".into();
@@ -432,19 +777,19 @@ async fn join_testnet(state: State<'_, AppState>, args: JoinTestnetArgs) -> Resu
         .node_manager
         .update_config(cfg.clone())
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(localize_err)?;
 
     // Prepare chain dir
     let chain_dir = std::path::PathBuf::from(&cfg.data_dir).join("chain");
     let clear_chain = args.clear_chain.unwrap_or(true);
     if clear_chain && chain_dir.exists() {
-        std::fs::remove_dir_all(&chain_dir).map_err(|e| e.to_string())?;
+        std::fs::remove_dir_all(&chain_dir).map_err(localize_err)?;
     }
     if let Some(seed) = args.seed_from.as_ref() {
         let seed_path = std::path::PathBuf::from(seed);
         if seed_path.exists() {
-            std::fs::create_dir_all(&chain_dir).map_err(|e| e.to_string())?;
-            copy_dir_all(&seed_path, &chain_dir).map_err(|e| e.to_string())?;
+            std::fs::create_dir_all(&chain_dir).map_err(localize_err)?;
+            copy_dir_all(&seed_path, &chain_dir).map_err(localize_err)?;
         }
     }
 
@@ -453,7 +798,7 @@ async fn join_testnet(state: State<'_, AppState>, args: JoinTestnetArgs) -> Resu
         .node_manager
         .start()
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(localize_err)?;
     // Auto-connect after start if bootnodes present
     let cfg_after = state.node_manager.get_config().await;
     if cfg_after.enable_network && !cfg_after.bootnodes.is_empty() {
@@ -461,7 +806,7 @@ async fn join_testnet(state: State<'_, AppState>, args: JoinTestnetArgs) -> Resu
             .node_manager
             .connect_bootnodes_now()
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(localize_err)?;
         info!("Auto-connected to {} bootnodes", ok);
     }
 
@@ -540,7 +885,7 @@ async fn switch_to_testnet(state: State<'_, AppState>) -> Result<String, String>
         .node_manager
         .update_config(config.clone())
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(localize_err)?;
 
     // DON'T auto-start node here - let start_node handle it
     // This allows external RPC to be used when configured
@@ -565,7 +910,7 @@ async fn ensure_connectivity(state: State<'_, AppState>) -> Result<String, Strin
         warn!("No peers connected. Attempting to connect to bootnodes...");
 
         // Try to connect to configured bootnodes
-        let connected = state.node_manager.connect_bootnodes_now().await.map_err(|e| e.to_string())?;
+        let connected = state.node_manager.connect_bootnodes_now().await.map_err(localize_err)?;
         info!("Connected to {} bootnode(s)", connected);
 
         let new_peer_count = state.node_manager.get_peers_summary().await.len();
@@ -644,7 +989,7 @@ async fn auto_add_bootnodes(state: State<'_, AppState>) -> Result<Vec<String>, S
     let entries: Vec<String> = ports.iter().map(|p| format!("{}:{}", ip, p)).collect();
 
     // Stop node to modify bootnodes in config
-    let _ = state.node_manager.stop().await.map_err(|e| e.to_string());
+    let _ = state.node_manager.stop().await.map_err(localize_err);
 
     // Add entries to config (dedup)
     let mut cfg = state.node_manager.get_config().await;
@@ -657,14 +1002,14 @@ async fn auto_add_bootnodes(state: State<'_, AppState>) -> Result<Vec<String>, S
         .node_manager
         .update_config(cfg.clone())
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(localize_err)?;
 
     // Start and connect
     state
         .node_manager
         .start()
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(localize_err)?;
     let _ = state.node_manager.connect_bootnodes_now().await;
 
     Ok(entries)
@@ -701,7 +1046,7 @@ async fn create_account(
         .wallet_manager
         .create_account(label, &password)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -714,7 +1059,7 @@ async fn create_account_extended(
         .wallet_manager
         .create_account_with_credentials(label, &password)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -728,7 +1073,7 @@ async fn import_account(
         .wallet_manager
         .import_account(&private_key, label, &password)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -742,7 +1087,7 @@ async fn import_account_from_mnemonic(
         .wallet_manager
         .import_account_from_mnemonic(&mnemonic, label, &password)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -762,7 +1107,7 @@ async fn delete_account(
         .wallet_manager
         .delete_account(&address, &password)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -779,7 +1124,7 @@ async fn perform_first_time_setup(
         .wallet_manager
         .perform_first_time_setup(&password)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(localize_err)?;
 
     // Automatically set the generated address as the reward address
     let reward_address = setup_result.primary_address.clone();
@@ -869,7 +1214,9 @@ async fn check_password_required(
     value: String,
 ) -> Result<bool, String> {
     // Parse value to check if high-value transaction
-    let value_u128: u128 = value.parse().unwrap_or(0);
+    let value_u128 =
+        citrate_execution::units::parse_units(&value, citrate_execution::units::Unit::Wei)
+            .map_err(|e| format!("Invalid value: {}", e))?;
     let requires_reauth = wallet::WalletManager::requires_reauth(
         value_u128,
         wallet::SensitiveOperation::SignTransaction,
@@ -888,8 +1235,45 @@ async fn check_password_required(
     Ok(!has_session || !has_cached_key)
 }
 
+/// Resolve a recipient typed by the user (address, contact name or
+/// registered `.ctr` name) to an address
+async fn resolve_recipient_input(
+    state: &State<'_, AppState>,
+    input: &str,
+) -> Result<ResolvedRecipient, String> {
+    // Registered names are read from the embedded node's state when it runs
+    let executor = state.node_manager.get_executor().await;
+    state
+        .wallet_manager
+        .address_book()
+        .resolve(input, executor.as_deref().map(|e| e.state_db().as_ref()))
+        .map_err(localize_err)
+}
+
+/// Send a transaction; the recipient may be a contact or registered name,
+/// which is resolved to an address before signing
 #[tauri::command]
 async fn send_transaction(
+    state: State<'_, AppState>,
+    mut request: TransactionRequest,
+    password: Option<String>,
+) -> Result<String, String> {
+    if let Some(to) = request.to.take() {
+        request.to = Some(resolve_recipient_input(&state, &to).await?.address);
+    }
+    let recipient = request.to.clone();
+    let tx_hash = submit_transaction(state.clone(), request, password).await?;
+
+    if let Some(to) = recipient {
+        if let Err(e) = state.wallet_manager.address_book().record_used(&to) {
+            warn!("Failed to record recent address: {}", e);
+        }
+    }
+    Ok(tx_hash)
+}
+
+/// Sign a transaction with the wallet and submit it to the embedded node
+async fn submit_transaction(
     state: State<'_, AppState>,
     request: TransactionRequest,
     password: Option<String>,
@@ -902,8 +1286,9 @@ async fn send_transaction(
         .wallet_manager
         .create_signed_transaction(request.clone(), &pwd)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(localize_err)?;
     let tx_hash_hex = hex::encode(tx.hash.as_bytes());
+    state.node_manager.track_transaction(tx.hash).await;
 
     // Add to local mempool - Mempool is internally synchronized
     if let Some(mempool) = state.node_manager.get_mempool().await {
@@ -917,6 +1302,138 @@ async fn send_transaction(
     Ok(tx_hash_hex)
 }
 
+/// Speed up a pending wallet transaction by resubmitting it with the same
+/// nonce and a higher gas price (the mempool minimum if none is given)
+#[tauri::command]
+async fn replace_transaction(
+    state: State<'_, AppState>,
+    hash: String,
+    gas_price: Option<String>,
+    password: Option<String>,
+) -> Result<String, String> {
+    replace_pending_transaction(state, &hash, gas_price, password, false).await
+}
+
+/// Cancel a pending wallet transaction by replacing it with a zero-value
+/// transfer to the sender at the same nonce
+#[tauri::command]
+async fn cancel_transaction(
+    state: State<'_, AppState>,
+    hash: String,
+    gas_price: Option<String>,
+    password: Option<String>,
+) -> Result<String, String> {
+    replace_pending_transaction(state, &hash, gas_price, password, true).await
+}
+
+/// Re-sign a pending transaction at the same nonce and submit it over the
+/// original; `cancel` turns it into an empty self-transfer
+async fn replace_pending_transaction(
+    state: State<'_, AppState>,
+    hash: &str,
+    gas_price: Option<String>,
+    password: Option<String>,
+    cancel: bool,
+) -> Result<String, String> {
+    use citrate_consensus::types::{Hash, PublicKey, Signature, Transaction};
+
+    let mempool = state
+        .node_manager
+        .get_mempool()
+        .await
+        .ok_or_else(|| "Node not started - mempool unavailable".to_string())?;
+    if !mempool.allows_replacement() {
+        return Err(tr!("error.replacement_disabled"));
+    }
+
+    let hash_bytes = hex::decode(hash.trim_start_matches("0x"))
+        .ok()
+        .filter(|bytes| bytes.len() == 32)
+        .ok_or_else(|| format!("Invalid transaction hash: {}", hash))?;
+    let pending = mempool
+        .get_transaction(&Hash::from_bytes(&hash_bytes))
+        .await
+        .ok_or_else(|| "Transaction is no longer pending".to_string())?;
+
+    let sender = hex::encode(pending.from.as_bytes());
+    let account = state
+        .wallet_manager
+        .get_accounts()
+        .await
+        .into_iter()
+        .find(|account| account.public_key.eq_ignore_ascii_case(&sender))
+        .ok_or_else(|| "Transaction was not sent from a wallet account".to_string())?;
+
+    let min_gas_price = mempool.min_replacement_price(pending.gas_price);
+    let gas_price = match gas_price {
+        Some(price) => citrate_execution::units::parse_units(
+            &price,
+            citrate_execution::units::Unit::Wei,
+        )
+        .ok()
+        .and_then(|p| u64::try_from(p).ok())
+        .ok_or_else(|| format!("Invalid gas price: {}", price))?,
+        None => min_gas_price,
+    };
+    if gas_price < min_gas_price {
+        return Err(format!(
+            "Gas price must be at least {} to replace a transaction paying {}",
+            min_gas_price, pending.gas_price
+        ));
+    }
+
+    let mut tx = Transaction {
+        hash: Hash::new([0u8; 32]),
+        nonce: pending.nonce,
+        from: PublicKey::new([0u8; 32]),
+        to: pending.to,
+        value: pending.value,
+        gas_limit: pending.gas_limit,
+        gas_price,
+        data: pending.data.clone(),
+        signature: Signature::new([0u8; 64]),
+        tx_type: None,
+    };
+    if cancel {
+        let mut to = [0u8; 32];
+        hex::decode(account.address.trim_start_matches("0x"))
+            .unwrap_or_default()
+            .iter()
+            .take(32)
+            .enumerate()
+            .for_each(|(i, b)| to[i] = *b);
+        tx.to = Some(PublicKey::new(to));
+        tx.value = 0;
+        tx.gas_limit = 21_000;
+        tx.data = Vec::new();
+    }
+
+    state
+        .wallet_manager
+        .sign_transaction(&mut tx, &account.address, &password.unwrap_or_default())
+        .await
+        .map_err(localize_err)?;
+    mempool
+        .add_transaction(tx.clone(), TxClass::Standard)
+        .await
+        .map_err(localize_err)?;
+    state.node_manager.track_transaction(tx.hash).await;
+    info!(
+        "{} transaction {} with {} at nonce {}",
+        if cancel { "Cancelled" } else { "Replaced" },
+        hash,
+        hex::encode(tx.hash.as_bytes()),
+        tx.nonce
+    );
+
+    let tx_hash_hex = hex::encode(tx.hash.as_bytes());
+    let _ = state
+        .node_manager
+        .broadcast_network(NetworkMessage::NewTransaction { transaction: tx })
+        .await;
+    Ok(tx_hash_hex)
+}
+
 #[derive(Debug, serde::Deserialize)]
 struct EthCallRequest {
     to: String,
@@ -939,7 +1456,7 @@ async fn eth_call(
     let to_bytes = hex::decode(request.to.trim_start_matches("0x"))
         .map_err(|e| format!("Invalid 'to' address: {}", e))?;
     if to_bytes.len() != 20 {
-        return Err("'to' address must be 20 bytes".to_string());
+        return Err(tr!("error.to_address_length"));
     }
 
     // Parse call data
@@ -951,7 +1468,7 @@ async fn eth_call(
         let from_bytes = hex::decode(from.trim_start_matches("0x"))
             .map_err(|e| format!("Invalid 'from' address: {}", e))?;
         if from_bytes.len() != 20 {
-            return Err("'from' address must be 20 bytes".to_string());
+            return Err(tr!("error.from_address_length"));
         }
         // Pad 20-byte address to 32-byte pubkey format
         let mut pk_bytes = [0u8; 32];
@@ -981,7 +1498,23 @@ async fn eth_call(
     };
 
     // Create a minimal block for execution context
-    let dummy_block = citrate_consensus::Block {
+    let dummy_block = call_block();
+
+    // Execute the call transaction
+    match executor.execute_transaction(&dummy_block, &call_tx).await {
+        Ok(receipt) => {
+            // Return the result data from the receipt
+            Ok(format!("0x{}", hex::encode(&receipt.output)))
+        }
+        Err(e) => Err(format!("Call execution failed: {}", e)),
+    }
+}
+
+/// Block context for calls and dry runs against the current state
+fn call_block() -> citrate_consensus::Block {
+    use citrate_consensus::types::{Hash, PublicKey, Signature};
+
+    citrate_consensus::Block {
         header: citrate_consensus::BlockHeader {
             version: 1,
             block_hash: Hash::default(),
@@ -1003,6 +1536,7 @@ async fn eth_call(
             base_fee_per_gas: 1_000_000_000,
             gas_used: 0,
             gas_limit: 30_000_000,
+            epoch_commitment: Hash::default(),
         },
         state_root: Hash::default(),
         tx_root: Hash::default(),
@@ -1013,77 +1547,705 @@ async fn eth_call(
         transactions: vec![],
         embedded_models: vec![],
         required_pins: vec![],
+    }
+}
+
+// ========== Governance Commands ==========
+
+/// State of the embedded node and its current height, for proposal reads
+async fn governance_view(
+    state: &State<'_, AppState>,
+) -> Result<(Arc<citrate_execution::Executor>, u64), String> {
+    let executor = state
+        .node_manager
+        .get_executor()
+        .await
+        .ok_or_else(|| "Node not started - executor unavailable".to_string())?;
+    let height = match state.node_manager.get_storage().await {
+        Some(storage) => storage.blocks.get_latest_height().unwrap_or(0),
+        None => 0,
     };
+    Ok((executor, height))
+}
 
-    // Execute the call transaction
-    match executor.execute_transaction(&dummy_block, &call_tx).await {
-        Ok(receipt) => {
-            // Return the result data from the receipt
-            Ok(format!("0x{}", hex::encode(&receipt.output)))
-        }
-        Err(e) => Err(format!("Call execution failed: {}", e)),
-    }
+#[tauri::command]
+async fn gov_get_config() -> Result<GovernanceConfig, String> {
+    Ok(GovernanceConfig::current())
 }
 
 #[tauri::command]
-async fn sign_message(
+async fn gov_list_proposals(
     state: State<'_, AppState>,
-    message: String,
-    address: String,
-    password: String,
-) -> Result<String, String> {
-    state
-        .wallet_manager
-        .sign_message(message.as_bytes(), &address, &password)
-        .await
-        .map_err(|e| e.to_string())
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<Vec<ProposalInfo>, String> {
+    let (executor, height) = governance_view(&state).await?;
+    Ok(governance::list_proposals(
+        executor.state_db(),
+        height,
+        offset.unwrap_or(0),
+        limit.unwrap_or(50),
+    ))
 }
 
 #[tauri::command]
-async fn verify_signature(
+async fn gov_get_proposal(
     state: State<'_, AppState>,
-    message: String,
-    signature: String,
-    address: String,
-) -> Result<bool, String> {
-    state
-        .wallet_manager
-        .verify_signature(message.as_bytes(), &signature, &address)
-        .await
-        .map_err(|e| e.to_string())
+    id: u64,
+) -> Result<Option<ProposalInfo>, String> {
+    let (executor, height) = governance_view(&state).await?;
+    Ok(governance::get_proposal(executor.state_db(), id, height))
 }
 
 #[tauri::command]
-async fn export_private_key(
+async fn gov_get_vote(
     state: State<'_, AppState>,
-    address: String,
-    password: String,
-) -> Result<String, String> {
-    state
-        .wallet_manager
-        .export_private_key(&address, &password)
-        .await
-        .map_err(|e| e.to_string())
+    id: u64,
+    voter: String,
+) -> Result<Option<VoteInfo>, String> {
+    let (executor, _) = governance_view(&state).await?;
+    governance::get_vote(executor.state_db(), id, &voter)
 }
 
 #[tauri::command]
-async fn update_balance(
+async fn gov_submit_proposal(
     state: State<'_, AppState>,
-    address: String,
-    balance: String,
-) -> Result<(), String> {
-    let balance_u128 = balance
-        .parse::<u128>()
+    from: String,
+    parameter: String,
+    value: String,
+    description: String,
+    password: Option<String>,
+) -> Result<String, String> {
+    let request = governance::propose_request(from, &parameter, &value, &description)?;
+    submit_transaction(state, request, password).await
+}
+
+#[tauri::command]
+async fn gov_vote(
+    state: State<'_, AppState>,
+    from: String,
+    id: u64,
+    support: VoteSupport,
+    password: Option<String>,
+) -> Result<String, String> {
+    submit_transaction(state, governance::vote_request(from, id, support), password).await
+}
+
+#[tauri::command]
+async fn gov_execute(
+    state: State<'_, AppState>,
+    from: String,
+    id: u64,
+    password: Option<String>,
+) -> Result<String, String> {
+    submit_transaction(state, governance::execute_request(from, id), password).await
+}
+
+#[tauri::command]
+async fn gov_cancel(
+    state: State<'_, AppState>,
+    from: String,
+    id: u64,
+    password: Option<String>,
+) -> Result<String, String> {
+    submit_transaction(state, governance::cancel_request(from, id), password).await
+}
+
+// ========== Multisig Commands ==========
+
+/// Addresses of the wallet's own accounts, for the approvals it can give
+async fn local_accounts(state: &State<'_, AppState>) -> Vec<String> {
+    state
+        .wallet_manager
+        .get_accounts()
+        .await
+        .into_iter()
+        .map(|account| account.address)
+        .collect()
+}
+
+/// Multisig wallets tracked by the wallet, newest first. Pending creations
+/// whose receipts have arrived are settled with their wallet id.
+#[tauri::command]
+async fn multisig_list(state: State<'_, AppState>) -> Result<Vec<MultisigAccount>, String> {
+    let book = state.wallet_manager.multisig();
+    if let Some(storage) = state.node_manager.get_storage().await {
+        book.settle(|tx_hash| {
+            let bytes = hex::decode(tx_hash.trim_start_matches("0x"))
+                .ok()
+                .filter(|bytes| bytes.len() == 32)?;
+            let hash = citrate_consensus::types::Hash::from_bytes(&bytes);
+            storage.transactions.get_receipt(&hash).ok().flatten()
+        })
+        .map_err(localize_err)?;
+    }
+    Ok(book.list())
+}
+
+/// Create an m-of-n wallet on chain, signed by `draft.from`
+#[tauri::command]
+async fn multisig_create(
+    state: State<'_, AppState>,
+    draft: MultisigDraft,
+    password: Option<String>,
+) -> Result<MultisigAccount, String> {
+    let (account, request) = draft
+        .prepare(wallet::payments::now())
+        .map_err(localize_err)?;
+    let tx_hash = submit_transaction(state.clone(), request, password).await?;
+    info!(
+        "Creating {}-of-{} multisig wallet in transaction {}",
+        account.threshold,
+        account.owners.len(),
+        tx_hash
+    );
+    state
+        .wallet_manager
+        .multisig()
+        .record_created(account, tx_hash)
+        .map_err(localize_err)
+}
+
+/// Track a multisig wallet that already exists, e.g. one a co-signer created
+#[tauri::command]
+async fn multisig_import(
+    state: State<'_, AppState>,
+    name: String,
+    wallet_id: u64,
+) -> Result<MultisigAccount, String> {
+    let (executor, _) = governance_view(&state).await?;
+    let on_chain = citrate_execution::precompiles::multisig::wallet(executor.state_db(), wallet_id)
+        .ok_or_else(|| format!("Multisig wallet {} does not exist", wallet_id))?;
+    state
+        .wallet_manager
+        .multisig()
+        .import(&name, &on_chain, wallet::payments::now())
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn multisig_remove(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    state
+        .wallet_manager
+        .multisig()
+        .remove(&id)
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn multisig_get_wallet(
+    state: State<'_, AppState>,
+    wallet_id: u64,
+) -> Result<Option<MultisigWalletInfo>, String> {
+    let (executor, _) = governance_view(&state).await?;
+    Ok(wallet::multisig::wallet_info(executor.state_db(), wallet_id))
+}
+
+/// Proposals made on one wallet, newest first
+#[tauri::command]
+async fn multisig_list_proposals(
+    state: State<'_, AppState>,
+    wallet_id: u64,
+) -> Result<Vec<MultisigProposalInfo>, String> {
+    let (executor, _) = governance_view(&state).await?;
+    let local = local_accounts(&state).await;
+    Ok(wallet::multisig::list_proposals(
+        executor.state_db(),
+        wallet_id,
+        &local,
+    ))
+}
+
+/// Transfers awaiting approval or execution on any tracked wallet
+#[tauri::command]
+async fn multisig_pending_proposals(
+    state: State<'_, AppState>,
+) -> Result<Vec<MultisigProposalInfo>, String> {
+    let (executor, _) = governance_view(&state).await?;
+    let local = local_accounts(&state).await;
+    let wallet_ids = state.wallet_manager.multisig().active_wallet_ids();
+    Ok(wallet::multisig::pending_proposals(
+        executor.state_db(),
+        &wallet_ids,
+        &local,
+    ))
+}
+
+#[tauri::command]
+async fn multisig_deposit(
+    state: State<'_, AppState>,
+    from: String,
+    wallet_id: u64,
+    amount: String,
+    password: Option<String>,
+) -> Result<String, String> {
+    let request =
+        wallet::multisig::deposit_request(from, wallet_id, &amount).map_err(localize_err)?;
+    submit_transaction(state, request, password).await
+}
+
+#[tauri::command]
+async fn multisig_propose(
+    state: State<'_, AppState>,
+    from: String,
+    wallet_id: u64,
+    to: String,
+    amount: String,
+    password: Option<String>,
+) -> Result<String, String> {
+    let request = wallet::multisig::propose_request(from, wallet_id, &to, &amount)
+        .map_err(localize_err)?;
+    submit_transaction(state, request, password).await
+}
+
+#[tauri::command]
+async fn multisig_approve(
+    state: State<'_, AppState>,
+    from: String,
+    proposal_id: u64,
+    password: Option<String>,
+) -> Result<String, String> {
+    let request = wallet::multisig::approve_request(from, proposal_id);
+    submit_transaction(state, request, password).await
+}
+
+#[tauri::command]
+async fn multisig_revoke(
+    state: State<'_, AppState>,
+    from: String,
+    proposal_id: u64,
+    password: Option<String>,
+) -> Result<String, String> {
+    let request = wallet::multisig::revoke_request(from, proposal_id);
+    submit_transaction(state, request, password).await
+}
+
+#[tauri::command]
+async fn multisig_execute(
+    state: State<'_, AppState>,
+    from: String,
+    proposal_id: u64,
+    password: Option<String>,
+) -> Result<String, String> {
+    let request = wallet::multisig::execute_request(from, proposal_id);
+    submit_transaction(state, request, password).await
+}
+
+#[tauri::command]
+async fn sign_message(
+    state: State<'_, AppState>,
+    message: String,
+    address: String,
+    password: String,
+) -> Result<String, String> {
+    state
+        .wallet_manager
+        .sign_message(message.as_bytes(), &address, &password)
+        .await
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn verify_signature(
+    state: State<'_, AppState>,
+    message: String,
+    signature: String,
+    address: String,
+) -> Result<bool, String> {
+    state
+        .wallet_manager
+        .verify_signature(message.as_bytes(), &signature, &address)
+        .await
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn export_private_key(
+    state: State<'_, AppState>,
+    address: String,
+    password: String,
+) -> Result<String, String> {
+    state
+        .wallet_manager
+        .export_private_key(&address, &password)
+        .await
+        .map_err(localize_err)
+}
+
+/// Write every account, its keystore, the tracked addresses and the reward
+/// address to a single file encrypted with `password`
+#[tauri::command]
+async fn wallet_export_backup(
+    state: State<'_, AppState>,
+    path: String,
+    password: String,
+) -> Result<(), String> {
+    let settings = wallet::WalletBackupSettings {
+        tracked_addresses: get_tracked_addresses().await?,
+        reward_address: state.node_manager.get_reward_address().await,
+    };
+    let archive = state
+        .wallet_manager
+        .export_backup(&password, settings)
+        .await
+        .map_err(localize_err)?;
+    std::fs::write(&path, archive).map_err(|e| format!("Failed to write backup: {}", e))
+}
+
+/// Restore a file written by `wallet_export_backup`. Tracked addresses are
+/// merged; the reward address is only restored if none is configured.
+#[tauri::command]
+async fn wallet_import_backup(
+    state: State<'_, AppState>,
+    path: String,
+    password: String,
+) -> Result<wallet::WalletBackupImport, String> {
+    let archive =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read backup: {}", e))?;
+    let result = state
+        .wallet_manager
+        .import_backup(&archive, &password)
+        .await
+        .map_err(localize_err)?;
+
+    let mut tracked = get_tracked_addresses().await?;
+    let before = tracked.len();
+    for address in &result.settings.tracked_addresses {
+        if citrate_execution::address_utils::is_valid_address(address.trim())
+            && !tracked.contains(address)
+        {
+            tracked.push(address.clone());
+        }
+    }
+    if tracked.len() != before {
+        save_tracked_addresses(tracked).await?;
+    }
+
+    if let Some(address) = &result.settings.reward_address {
+        if state.node_manager.get_reward_address().await.is_none() {
+            set_reward_address(state.clone(), address.clone()).await?;
+        }
+    }
+
+    Ok(result)
+}
+
+#[tauri::command]
+async fn update_balance(
+    state: State<'_, AppState>,
+    address: String,
+    balance: String,
+) -> Result<(), String> {
+    let balance_u128 = balance
+        .parse::<u128>()
         .map_err(|e| format!("Invalid balance: {}", e))?;
     state
         .wallet_manager
         .update_balance(&address, balance_u128)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
+}
+
+// ===== Address Book Commands =====
+
+#[tauri::command]
+async fn wallet_list_contacts(
+    state: State<'_, AppState>,
+    tag: Option<String>,
+) -> Result<Vec<Contact>, String> {
+    Ok(state.wallet_manager.address_book().list(tag.as_deref()))
+}
+
+#[tauri::command]
+async fn wallet_contact_tags(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    Ok(state.wallet_manager.address_book().tags())
+}
+
+#[tauri::command]
+async fn wallet_add_contact(
+    state: State<'_, AppState>,
+    contact: ContactDraft,
+) -> Result<Contact, String> {
+    state
+        .wallet_manager
+        .address_book()
+        .add(contact)
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn wallet_update_contact(
+    state: State<'_, AppState>,
+    name: String,
+    contact: ContactDraft,
+) -> Result<Contact, String> {
+    state
+        .wallet_manager
+        .address_book()
+        .update(&name, contact)
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn wallet_remove_contact(state: State<'_, AppState>, name: String) -> Result<(), String> {
+    state
+        .wallet_manager
+        .address_book()
+        .remove(&name)
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn wallet_recent_addresses(
+    state: State<'_, AppState>,
+) -> Result<Vec<RecentAddress>, String> {
+    Ok(state.wallet_manager.address_book().recent())
+}
+
+#[tauri::command]
+async fn wallet_resolve_recipient(
+    state: State<'_, AppState>,
+    input: String,
+) -> Result<ResolvedRecipient, String> {
+    resolve_recipient_input(&state, &input).await
+}
+
+// ===== Scheduled Payment Commands =====
+
+#[tauri::command]
+async fn wallet_list_scheduled_payments(
+    state: State<'_, AppState>,
+) -> Result<Vec<ScheduledPayment>, String> {
+    Ok(state.wallet_manager.payments().list())
+}
+
+/// Schedule a recurring payment; the recipient may be a contact or
+/// registered name, which is resolved to an address now
+#[tauri::command]
+async fn wallet_create_scheduled_payment(
+    state: State<'_, AppState>,
+    mut payment: PaymentDraft,
+) -> Result<ScheduledPayment, String> {
+    if state.wallet_manager.get_account(&payment.from).await.is_none() {
+        return Err(tr!("error.account_not_found"));
+    }
+    payment.to = resolve_recipient_input(&state, &payment.to).await?.address;
+    state
+        .wallet_manager
+        .payments()
+        .create(payment, wallet::payments::now())
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn wallet_pause_scheduled_payment(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<ScheduledPayment, String> {
+    state
+        .wallet_manager
+        .payments()
+        .pause(&id)
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn wallet_resume_scheduled_payment(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<ScheduledPayment, String> {
+    state
+        .wallet_manager
+        .payments()
+        .resume(&id, wallet::payments::now())
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn wallet_remove_scheduled_payment(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), String> {
+    state
+        .wallet_manager
+        .payments()
+        .remove(&id)
+        .map_err(localize_err)
+}
+
+/// Sign and submit a payment that came due while the wallet was locked
+#[tauri::command]
+async fn wallet_approve_scheduled_payment(
+    state: State<'_, AppState>,
+    id: String,
+    password: String,
+) -> Result<PaymentRun, String> {
+    state
+        .node_manager
+        .approve_scheduled_payment(&id, &password)
+        .await
+        .map_err(localize_err)
+}
+
+/// Executed payments, newest first, optionally of one schedule only
+#[tauri::command]
+async fn wallet_scheduled_payment_history(
+    state: State<'_, AppState>,
+    id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<PaymentRun>, String> {
+    Ok(state
+        .wallet_manager
+        .payments()
+        .history(id.as_deref(), limit.unwrap_or(100)))
+}
+
+// ===== Remote Signer Commands =====
+
+/// Connect to a remote signer (unix:///path or http://host:port) and list its accounts
+#[tauri::command]
+async fn connect_remote_signer(
+    state: State<'_, AppState>,
+    endpoint: String,
+) -> Result<Vec<Account>, String> {
+    state
+        .wallet_manager
+        .connect_remote_signer(&endpoint)
+        .await
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn disconnect_remote_signer(state: State<'_, AppState>) -> Result<(), String> {
+    state
+        .wallet_manager
+        .disconnect_remote_signer()
+        .await
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn get_remote_signer_status(
+    state: State<'_, AppState>,
+) -> Result<RemoteSignerStatus, String> {
+    Ok(state.wallet_manager.remote_signer_status().await)
+}
+
+// ===== WalletConnect Commands =====
+
+#[tauri::command]
+async fn walletconnect_get_config(
+    state: State<'_, AppState>,
+) -> Result<WalletConnectConfig, String> {
+    Ok(state.walletconnect_manager.get_config().await)
+}
+
+#[tauri::command]
+async fn walletconnect_set_project_id(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<(), String> {
+    state.walletconnect_manager.set_project_id(project_id).await;
+    Ok(())
+}
+
+/// Pair with a dapp from a `wc:` URI (pasted or read from its QR code)
+#[tauri::command]
+async fn walletconnect_pair(state: State<'_, AppState>, uri: String) -> Result<(), String> {
+    state
+        .walletconnect_manager
+        .pair(&uri)
+        .await
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn walletconnect_get_proposals(
+    state: State<'_, AppState>,
+) -> Result<Vec<SessionProposal>, String> {
+    Ok(state.walletconnect_manager.proposals().await)
+}
+
+#[tauri::command]
+async fn walletconnect_approve_session(
+    state: State<'_, AppState>,
+    proposal_id: u64,
+    accounts: Vec<String>,
+) -> Result<Session, String> {
+    state
+        .walletconnect_manager
+        .approve_session(proposal_id, accounts)
+        .await
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn walletconnect_reject_session(
+    state: State<'_, AppState>,
+    proposal_id: u64,
+) -> Result<(), String> {
+    state
+        .walletconnect_manager
+        .reject_session(proposal_id)
+        .await
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn walletconnect_get_sessions(state: State<'_, AppState>) -> Result<Vec<Session>, String> {
+    Ok(state.walletconnect_manager.sessions().await)
+}
+
+#[tauri::command]
+async fn walletconnect_disconnect(state: State<'_, AppState>, topic: String) -> Result<(), String> {
+    state
+        .walletconnect_manager
+        .disconnect(&topic)
+        .await
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn walletconnect_get_requests(
+    state: State<'_, AppState>,
+) -> Result<Vec<PendingRequest>, String> {
+    Ok(state.walletconnect_manager.pending_requests().await)
+}
+
+/// Sign and answer a dapp request; the password may be omitted while a
+/// wallet session is active, as with `send_transaction`
+#[tauri::command]
+async fn walletconnect_approve_request(
+    state: State<'_, AppState>,
+    request_id: u64,
+    password: Option<String>,
+) -> Result<String, String> {
+    state
+        .walletconnect_manager
+        .approve_request(request_id, password)
+        .await
+        .map_err(localize_err)
+}
+
+#[tauri::command]
+async fn walletconnect_reject_request(
+    state: State<'_, AppState>,
+    request_id: u64,
+) -> Result<(), String> {
+    state
+        .walletconnect_manager
+        .reject_request(request_id)
+        .await
+        .map_err(localize_err)
 }
 
 // ===== DAG Commands =====
 
+/// DAG view of the external node, when the GUI is attached to one
+async fn remote_dag(state: &AppState) -> Option<RemoteDag> {
+    state.node_manager.remote_client().await.map(RemoteDag::new)
+}
+
 #[tauri::command]
 async fn get_dag_data(
     state: State<'_, AppState>,
@@ -1095,7 +2257,12 @@ async fn get_dag_data(
         dag_manager
             .get_dag_data(limit, start_height)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(localize_err)
+    } else if let Some(remote) = remote_dag(&state).await {
+        remote
+            .get_dag_data(limit, start_height)
+            .await
+            .map_err(localize_err)
     } else {
         // Return empty data if node is not started
         Ok(DAGData {
@@ -1124,9 +2291,14 @@ async fn get_block_details(
         dag_manager
             .get_block_details(&hash)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(localize_err)
+    } else if let Some(remote) = remote_dag(&state).await {
+        remote
+            .get_block_details(&hash)
+            .await
+            .map_err(localize_err)
     } else {
-        Err("Node is not running. Please start the node first.".to_string())
+        Err(tr!("error.node_not_running_start"))
     }
 }
 
@@ -1140,9 +2312,9 @@ async fn get_blue_set(
         dag_manager
             .get_blue_set(&block_hash)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(localize_err)
     } else {
-        Err("Node is not running. Please start the node first.".to_string())
+        Err(tr!("error.node_not_running_start"))
     }
 }
 
@@ -1153,7 +2325,32 @@ async fn get_current_tips(state: State<'_, AppState>) -> Result<Vec<TipInfo>, St
         dag_manager
             .get_current_tips()
             .await
-            .map_err(|e| e.to_string())
+            .map_err(localize_err)
+    } else if let Some(remote) = remote_dag(&state).await {
+        remote.get_current_tips().await.map_err(localize_err)
+    } else {
+        Ok(vec![])
+    }
+}
+
+#[tauri::command]
+async fn get_dag_stats_series(
+    state: State<'_, AppState>,
+    from: Option<u64>,
+    to: Option<u64>,
+    bucket_secs: Option<u64>,
+) -> Result<Vec<DagStatsBucket>, String> {
+    let dag_manager_opt = state.dag_manager.read().await;
+    if let Some(dag_manager) = dag_manager_opt.as_ref() {
+        dag_manager
+            .get_dag_stats_series(from, to, bucket_secs)
+            .await
+            .map_err(localize_err)
+    } else if let Some(remote) = remote_dag(&state).await {
+        remote
+            .get_dag_stats_series(from, to, bucket_secs)
+            .await
+            .map_err(localize_err)
     } else {
         Ok(vec![])
     }
@@ -1169,9 +2366,9 @@ async fn calculate_blue_score(
         dag_manager
             .calculate_blue_score(&block_hash)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(localize_err)
     } else {
-        Err("Node is not running. Please start the node first.".to_string())
+        Err(tr!("error.node_not_running_start"))
     }
 }
 
@@ -1185,9 +2382,22 @@ async fn get_block_path(
         dag_manager
             .get_block_path(&block_hash)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(localize_err)
+    } else {
+        Err(tr!("error.node_not_running_start"))
+    }
+}
+
+#[tauri::command]
+async fn search_dag(state: State<'_, AppState>, query: String) -> Result<DAGSearchResult, String> {
+    let dag_manager_opt = state.dag_manager.read().await;
+    if let Some(dag_manager) = dag_manager_opt.as_ref() {
+        dag_manager
+            .search_dag(&query)
+            .await
+            .map_err(localize_err)
     } else {
-        Err("Node is not running. Please start the node first.".to_string())
+        Err(tr!("error.node_not_running_start"))
     }
 }
 
@@ -1202,19 +2412,46 @@ async fn deploy_model(
         .model_manager
         .deploy_model(deployment)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
+/// Run inference; with a `stream_id` the output is also streamed on the
+/// `agent-stream:<stream_id>` event topic as it is generated
 #[tauri::command]
 async fn run_inference(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
     request: InferenceRequest,
+    stream_id: Option<String>,
 ) -> Result<InferenceResponse, String> {
-    state
+    let manager = &state.model_manager;
+    match stream_id {
+        Some(stream_id) => {
+            let session_id = request.model_id.clone();
+            agent::streaming::run_streamed(
+                &app,
+                manager.inference_streams(),
+                session_id,
+                stream_id,
+                |tokens| async move { manager.request_inference_streaming(request, &tokens).await },
+            )
+            .await
+        }
+        None => manager
+            .request_inference(request)
+            .await
+            .map_err(localize_err),
+    }
+}
+
+/// Stop an inference request started with a `stream_id`
+#[tauri::command]
+async fn cancel_inference(state: State<'_, AppState>, stream_id: String) -> Result<bool, String> {
+    Ok(state
         .model_manager
-        .request_inference(request)
-        .await
-        .map_err(|e| e.to_string())
+        .inference_streams()
+        .cancel_stream(&stream_id)
+        .await)
 }
 
 #[tauri::command]
@@ -1223,7 +2460,7 @@ async fn start_training(state: State<'_, AppState>, job: TrainingJob) -> Result<
         .model_manager
         .start_training(job)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -1235,7 +2472,7 @@ async fn get_model_info(
         .model_manager
         .get_model(&model_id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -1244,7 +2481,7 @@ async fn list_models(state: State<'_, AppState>) -> Result<Vec<ModelInfo>, Strin
         .model_manager
         .get_models()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -1253,7 +2490,7 @@ async fn get_training_jobs(state: State<'_, AppState>) -> Result<Vec<TrainingJob
         .model_manager
         .get_training_jobs()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -1265,7 +2502,7 @@ async fn get_job_status(
         .model_manager
         .get_job_status(&job_id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -1274,7 +2511,7 @@ async fn get_deployments(state: State<'_, AppState>) -> Result<Vec<ModelDeployme
         .model_manager
         .get_deployments()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 // ===== LoRA Training Commands =====
@@ -1303,7 +2540,7 @@ async fn create_lora_job(
             training_config,
         )
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 /// Start a queued LoRA training job
@@ -1313,7 +2550,7 @@ async fn start_lora_training(state: State<'_, AppState>, job_id: String) -> Resu
         .model_manager
         .start_lora_training(&job_id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 /// Get a specific LoRA training job by ID
@@ -1326,7 +2563,7 @@ async fn get_lora_job(
         .model_manager
         .get_lora_job(&job_id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 /// Get all LoRA training jobs
@@ -1336,7 +2573,7 @@ async fn get_lora_jobs(state: State<'_, AppState>) -> Result<Vec<LoraTrainingJob
         .model_manager
         .get_lora_jobs()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 /// Cancel a running LoRA training job
@@ -1346,7 +2583,7 @@ async fn cancel_lora_job(state: State<'_, AppState>, job_id: String) -> Result<(
         .model_manager
         .cancel_lora_job(&job_id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 /// Delete a LoRA training job
@@ -1356,7 +2593,7 @@ async fn delete_lora_job(state: State<'_, AppState>, job_id: String) -> Result<(
         .model_manager
         .delete_lora_job(&job_id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 /// Get all saved LoRA adapters
@@ -1366,7 +2603,7 @@ async fn get_lora_adapters(state: State<'_, AppState>) -> Result<Vec<LoraAdapter
         .model_manager
         .get_lora_adapters()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 /// Delete a LoRA adapter
@@ -1376,7 +2613,7 @@ async fn delete_lora_adapter(state: State<'_, AppState>, adapter_id: String) ->
         .model_manager
         .delete_lora_adapter(&adapter_id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 /// Run inference with a LoRA adapter
@@ -1399,7 +2636,103 @@ async fn run_inference_with_lora(
             temperature.unwrap_or(0.7),
         )
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
+}
+
+/// Merge a LoRA adapter into its base model and export a standalone GGUF
+#[tauri::command]
+async fn merge_lora_adapter(
+    state: State<'_, AppState>,
+    adapter_id: String,
+    base_model_path: Option<String>,
+    output_name: Option<String>,
+    quantization: Option<GgufQuantization>,
+) -> Result<ModelInfo, String> {
+    state
+        .model_manager
+        .merge_lora_adapter(
+            &adapter_id,
+            base_model_path,
+            output_name,
+            quantization.unwrap_or_default(),
+        )
+        .await
+        .map_err(localize_err)
+}
+
+/// Benchmark a local GGUF model (optionally with a LoRA adapter) on the
+/// standard prompt suite and save the result
+#[tauri::command]
+async fn benchmark_model(
+    state: State<'_, AppState>,
+    model_id: String,
+    adapter_id: Option<String>,
+    config: Option<BenchmarkConfig>,
+) -> Result<BenchmarkResult, String> {
+    let devices = state.gpu_manager.get_devices().await;
+    state
+        .model_manager
+        .benchmark_model(
+            &model_id,
+            adapter_id.as_deref(),
+            config.unwrap_or_default(),
+            &devices,
+        )
+        .await
+        .map_err(localize_err)
+}
+
+/// Saved benchmark results, newest first, optionally for one model
+#[tauri::command]
+async fn get_model_benchmarks(
+    state: State<'_, AppState>,
+    model_id: Option<String>,
+) -> Result<Vec<BenchmarkResult>, String> {
+    Ok(state.model_manager.get_benchmarks(model_id.as_deref()))
+}
+
+/// Delete a saved benchmark result
+#[tauri::command]
+async fn delete_model_benchmark(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    state
+        .model_manager
+        .delete_benchmark(&id)
+        .map_err(localize_err)
+}
+
+/// Switch the agent's local model to `model_id` with a LoRA adapter applied,
+/// or to the bare base model when `adapter_id` is omitted. The base weights
+/// stay loaded across adapter switches. Returns the applied adapter's path.
+#[tauri::command]
+async fn apply_adapter(
+    state: State<'_, AppState>,
+    agent_state: State<'_, AgentState>,
+    model_id: String,
+    adapter_id: Option<String>,
+    scale: Option<f32>,
+) -> Result<Option<String>, String> {
+    let model_path = state
+        .model_manager
+        .resolve_model_path(&model_id)
+        .map_err(localize_err)?;
+    let adapter = match adapter_id {
+        Some(id) => {
+            let adapter = state
+                .model_manager
+                .find_lora_adapter(&id)
+                .await
+                .map_err(localize_err)?;
+            Some((std::path::PathBuf::from(adapter.path), scale.unwrap_or(1.0)))
+        }
+        None => None,
+    };
+
+    let manager = agent_state.manager.read().await;
+    let manager = manager.as_ref().ok_or("Agent not initialized")?;
+    let applied = manager
+        .apply_adapter(model_path.to_string_lossy().to_string(), adapter)
+        .await?;
+    Ok(applied.map(|path| path.to_string_lossy().to_string()))
 }
 
 /// Validate a dataset for LoRA training
@@ -1413,7 +2746,23 @@ async fn validate_dataset(
         .model_manager
         .validate_dataset(&path, &format)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
+}
+
+/// Clean a dataset and split it into train/eval files before training
+#[tauri::command]
+async fn preprocess_dataset(
+    state: State<'_, AppState>,
+    path: String,
+    format: DatasetFormat,
+    base_model_path: Option<String>,
+    config: Option<PreprocessConfig>,
+) -> Result<DatasetReport, String> {
+    state
+        .model_manager
+        .preprocess_dataset(&path, &format, &config.unwrap_or_default(), base_model_path.as_deref())
+        .await
+        .map_err(localize_err)
 }
 
 /// Get LoRA training presets
@@ -1522,6 +2871,45 @@ async fn get_window_count(state: State<'_, AppState>) -> Result<usize, String> {
     Ok(manager.window_count().await)
 }
 
+/// Open windows in keyboard focus order, with titles and roles for screen
+/// readers
+#[tauri::command]
+async fn get_accessible_windows(
+    state: State<'_, AppState>,
+) -> Result<Vec<AccessibleWindow>, String> {
+    let manager = state.window_manager.read().await;
+    Ok(manager.accessible_windows().await)
+}
+
+/// Move focus to the next or previous window, wrapping around
+#[tauri::command]
+async fn cycle_window_focus(
+    state: State<'_, AppState>,
+    direction: FocusDirection,
+) -> Result<Option<AccessibleWindow>, String> {
+    let manager = state.window_manager.read().await;
+    manager.cycle_focus(direction).await
+}
+
+#[tauri::command]
+async fn get_accessibility_preferences(
+    state: State<'_, AppState>,
+) -> Result<AccessibilityPreferences, String> {
+    let manager = state.window_manager.read().await;
+    Ok(manager.preferences().await)
+}
+
+/// Persist high-contrast, zoom and reduced-motion preferences and apply them
+/// to all windows
+#[tauri::command]
+async fn set_accessibility_preferences(
+    state: State<'_, AppState>,
+    preferences: AccessibilityPreferences,
+) -> Result<AccessibilityPreferences, String> {
+    let manager = state.window_manager.read().await;
+    manager.set_preferences(preferences).await
+}
+
 // ===== Terminal Commands =====
 
 #[derive(Debug, serde::Deserialize)]
@@ -1549,7 +2937,7 @@ async fn terminal_create(
     manager
         .create_session(config)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -1562,7 +2950,7 @@ async fn terminal_write(
     manager
         .write_input(&session_id, data.as_bytes())
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -1576,7 +2964,7 @@ async fn terminal_resize(
     manager
         .resize_session(&session_id, cols, rows)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -1585,7 +2973,7 @@ async fn terminal_close(state: State<'_, AppState>, session_id: String) -> Resul
     manager
         .close_session(&session_id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(localize_err)
 }
 
 #[tauri::command]
@@ -1652,10 +3040,23 @@ async fn ipfs_add(
     state.ipfs_manager.add(bytes, name.as_deref()).await
 }
 
+/// Upload a file in chunks, reporting progress under `upload_id` in
+/// `state-delta` events.
+/// Re-running with the same file resumes by skipping blocks already stored.
 #[tauri::command]
-async fn ipfs_add_file(state: State<'_, AppState>, path: String) -> Result<IpfsAddResult, String> {
+async fn ipfs_add_file(
+    state: State<'_, AppState>,
+    path: String,
+    upload_id: Option<String>,
+) -> Result<IpfsAddResult, String> {
     let path = std::path::PathBuf::from(path);
-    state.ipfs_manager.add_file(&path).await
+    let upload_id = upload_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    state
+        .ipfs_manager
+        .add_file_with_progress(&path, &upload_id, |progress| {
+            state.event_bus.publish_download(&upload_id, &progress);
+        })
+        .await
 }
 
 #[tauri::command]
@@ -1663,9 +3064,23 @@ async fn ipfs_get(state: State<'_, AppState>, cid: String) -> Result<IpfsContent
     state.ipfs_manager.get(&cid).await
 }
 
+/// Pin a CID under a content category. Pins over the category quota unpin
+/// the least recently used unprotected content first.
 #[tauri::command]
-async fn ipfs_pin(state: State<'_, AppState>, cid: String) -> Result<(), String> {
-    state.ipfs_manager.pin(&cid).await
+async fn ipfs_pin(
+    state: State<'_, AppState>,
+    cid: String,
+    category: Option<ContentCategory>,
+    protected: Option<bool>,
+) -> Result<(), String> {
+    state
+        .ipfs_manager
+        .pin_as(
+            &cid,
+            category.unwrap_or(ContentCategory::Other),
+            protected.unwrap_or(false),
+        )
+        .await
 }
 
 #[tauri::command]
@@ -1683,6 +3098,69 @@ async fn ipfs_get_peers(state: State<'_, AppState>) -> Result<Vec<String>, Strin
     state.ipfs_manager.get_peers().await
 }
 
+#[tauri::command]
+async fn ipfs_get_usage(state: State<'_, AppState>) -> Result<StorageUsage, String> {
+    Ok(state.ipfs_manager.get_usage().await)
+}
+
+/// Enforce category quotas and reclaim unpinned blocks
+#[tauri::command]
+async fn ipfs_gc_run(state: State<'_, AppState>) -> Result<GcReport, String> {
+    if !state.ipfs_manager.is_running().await {
+        return Err(tr!("error.ipfs_not_running"));
+    }
+    state.ipfs_manager.gc_run().await
+}
+
+/// Pin the weights of a purchased or deployed marketplace model for as long
+/// as its license is active
+#[tauri::command]
+async fn marketplace_register_license(
+    state: State<'_, AppState>,
+    license: ModelLicense,
+) -> Result<LicenseRecord, String> {
+    if !state.ipfs_manager.is_running().await {
+        return Err(tr!("error.ipfs_not_running"));
+    }
+    state.ipfs_manager.register_license(license).await
+}
+
+/// Give up a license: unpin its weights and drop cached inference results
+#[tauri::command]
+async fn marketplace_release_license(
+    state: State<'_, AppState>,
+    model_id: String,
+) -> Result<(), String> {
+    if !state.ipfs_manager.is_running().await {
+        return Err(tr!("error.ipfs_not_running"));
+    }
+    if let Some(record) = state.ipfs_manager.release_license(&model_id).await? {
+        if let Some(local_model) = &record.license.local_model {
+            state.model_manager.evict_cached_results(local_model);
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn marketplace_list_licenses(
+    state: State<'_, AppState>,
+) -> Result<Vec<LicenseRecord>, String> {
+    Ok(state.ipfs_manager.list_licenses().await)
+}
+
+/// Check the pins of every active license now instead of waiting for the
+/// periodic check
+#[tauri::command]
+async fn ipfs_check_license_health(
+    state: State<'_, AppState>,
+) -> Result<Vec<LicenseRecord>, String> {
+    if !state.ipfs_manager.is_running().await {
+        return Err(tr!("error.ipfs_not_running"));
+    }
+    Ok(state.ipfs_manager.check_license_health().await)
+}
+
 // ===== HuggingFace Commands =====
 
 #[tauri::command]
@@ -1873,6 +3351,19 @@ async fn hf_cancel_download_resumable(
     Ok(())
 }
 
+/// Convert a downloaded safetensors model to a quantized GGUF and return
+/// it as a local model
+#[tauri::command]
+async fn hf_convert_to_gguf(
+    state: State<'_, AppState>,
+    model_id: String,
+    quantization: Option<GgufQuantization>,
+) -> Result<LocalModelInfo, String> {
+    state.hf_manager
+        .convert_to_gguf(&model_id, quantization.unwrap_or_default())
+        .await
+}
+
 /// Delete a local model file
 #[tauri::command]
 async fn hf_delete_local_model(
@@ -1917,6 +3408,24 @@ async fn hf_get_download_stats(state: State<'_, AppState>) -> Result<DownloadSta
     Ok(DownloadStats { active, completed, total_downloaded })
 }
 
+/// Search datasets on HuggingFace
+#[tauri::command]
+async fn hf_search_datasets(
+    state: State<'_, AppState>,
+    params: DatasetSearchParams,
+) -> Result<Vec<HFDatasetInfo>, String> {
+    state.hf_manager.search_datasets(params).await
+}
+
+/// Download a dataset split, converted to a LoRA training format
+#[tauri::command]
+async fn hf_download_dataset(
+    state: State<'_, AppState>,
+    params: DatasetDownloadParams,
+) -> Result<DatasetDownloadResult, String> {
+    state.hf_manager.download_dataset(params).await
+}
+
 // ===== Contract Compilation Commands (Foundry CLI) =====
 
 /// Check if Foundry/forge is installed
@@ -1977,7 +3486,7 @@ async fn forge_build(
 
     let project_dir = Path::new(&project_path);
     if !project_dir.exists() {
-        return Err(format!("Project directory does not exist: {}", project_path));
+        return Err(tr!("error.project_not_found", 0 = project_path));
     }
 
     // Check for foundry.toml (used for logging)
@@ -2143,7 +3652,7 @@ async fn forge_test(
 
     let project_dir = Path::new(&project_path);
     if !project_dir.exists() {
-        return Err(format!("Project directory does not exist: {}", project_path));
+        return Err(tr!("error.project_not_found", 0 = project_path));
     }
 
     let mut cmd = Command::new("forge");
@@ -2193,6 +3702,231 @@ async fn forge_test(
     })
 }
 
+/// Constructor arguments and transaction settings for deploying a
+/// contract built by `forge_build`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DeployContractArgs {
+    /// Deploying wallet account
+    from: String,
+    /// One value per constructor input, in ABI order
+    #[serde(default)]
+    constructor_args: Vec<serde_json::Value>,
+    /// Wei sent to a payable constructor
+    value: Option<String>,
+    /// The dry-run estimate when unset
+    gas_limit: Option<u64>,
+    /// Wei; the mempool minimum when unset
+    gas_price: Option<String>,
+}
+
+/// Deployment data and gas, shown for review before signing
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeploymentEstimate {
+    /// Creation bytecode followed by the ABI-encoded constructor arguments
+    data: String,
+    gas_estimate: u64,
+    gas_limit: u64,
+    gas_price: String,
+}
+
+/// Encode the deployment of `contract` and dry-run it on a fork of the
+/// current state to estimate its gas
+async fn prepare_deployment(
+    state: &State<'_, AppState>,
+    contract: &ForgeContract,
+    args: &DeployContractArgs,
+) -> Result<DeploymentEstimate, String> {
+    use agent::tools::abi;
+    use citrate_consensus::types::{Hash, PublicKey, Signature, Transaction};
+
+    let bytecode = contract
+        .bytecode
+        .as_deref()
+        .map(|b| b.trim_start_matches("0x"))
+        .filter(|b| !b.is_empty())
+        .ok_or_else(|| {
+            format!(
+                "{} has no bytecode; abstract contracts and interfaces can't be deployed",
+                contract.name
+            )
+        })?;
+    // Unlinked library references leave `__$...$__` placeholders behind
+    let bytecode = hex::decode(bytecode).map_err(|_| {
+        format!(
+            "{} has unlinked library references; link them with forge first",
+            contract.name
+        )
+    })?;
+    let interface = match &contract.abi {
+        Some(json) => abi::parse_abi(&json.to_string())?,
+        None => ethabi::Contract::default(),
+    };
+    let constructor_args: Vec<String> =
+        args.constructor_args.iter().map(abi::arg_to_string).collect();
+    let data = abi::encode_constructor(&interface, bytecode, &constructor_args)?;
+
+    let account = state
+        .wallet_manager
+        .get_account(&args.from)
+        .await
+        .ok_or_else(|| format!("Account {} is not in the wallet", args.from))?;
+    let mut from = [0u8; 32];
+    hex::decode_to_slice(&account.public_key, &mut from)
+        .map_err(|e| format!("Invalid account key: {}", e))?;
+    let value = citrate_execution::units::parse_units(
+        args.value.as_deref().unwrap_or("0"),
+        citrate_execution::units::Unit::Wei,
+    )
+    .map_err(|e| format!("Invalid value: {}", e))?;
+
+    let executor = state
+        .node_manager
+        .get_executor()
+        .await
+        .ok_or_else(|| "Node not started - executor unavailable".to_string())?;
+    let mut tx = Transaction {
+        hash: Hash::default(),
+        nonce: account.nonce,
+        from: PublicKey::new(from),
+        to: None,
+        value,
+        gas_limit: 30_000_000,
+        gas_price: 0,
+        data: data.clone(),
+        signature: Signature::new([0u8; 64]),
+        tx_type: None,
+    };
+    tx.determine_type();
+    let simulation = executor
+        .simulate_transaction(
+            executor.state_db().fork(),
+            &call_block(),
+            &tx,
+            &Default::default(),
+        )
+        .await
+        .map_err(|e| format!("Deployment dry run failed: {}", e))?;
+    if !simulation.receipt.status {
+        return Err("Constructor reverted in a dry run; check the arguments and value".to_string());
+    }
+    // Same margin as eth_estimateGas
+    let gas_used = simulation.receipt.gas_used;
+    let gas_estimate = gas_used.saturating_add(gas_used / 10).max(21_000);
+
+    let gas_price = match &args.gas_price {
+        Some(price) => price.trim().to_string(),
+        None => state
+            .node_manager
+            .get_config()
+            .await
+            .mempool
+            .min_gas_price
+            .to_string(),
+    };
+    Ok(DeploymentEstimate {
+        data: format!("0x{}", hex::encode(&data)),
+        gas_estimate,
+        gas_limit: args.gas_limit.unwrap_or(gas_estimate),
+        gas_price,
+    })
+}
+
+/// Encode constructor arguments for a built contract and estimate the gas
+/// its deployment needs, without signing anything
+#[tauri::command]
+async fn estimate_contract_deployment(
+    state: State<'_, AppState>,
+    contract: ForgeContract,
+    args: DeployContractArgs,
+) -> Result<DeploymentEstimate, String> {
+    prepare_deployment(&state, &contract, &args).await
+}
+
+/// Deploy a contract built by `forge_build`: encode the constructor
+/// arguments, estimate gas, sign with the wallet and submit. The deployment
+/// is recorded and settled with its address by `get_contract_deployments`.
+#[tauri::command]
+async fn deploy_contract(
+    state: State<'_, AppState>,
+    contract: ForgeContract,
+    args: DeployContractArgs,
+    password: Option<String>,
+) -> Result<ContractDeployment, String> {
+    let estimate = prepare_deployment(&state, &contract, &args).await?;
+    if estimate.gas_limit < estimate.gas_estimate {
+        return Err(format!(
+            "Gas limit {} is below the estimated {} needed",
+            estimate.gas_limit, estimate.gas_estimate
+        ));
+    }
+    let value = args.value.clone().unwrap_or_else(|| "0".to_string());
+
+    let request = TransactionRequest {
+        from: args.from.clone(),
+        to: None,
+        value: value.clone(),
+        gas_limit: estimate.gas_limit,
+        gas_price: estimate.gas_price.clone(),
+        data: estimate.data.clone(),
+    };
+    let tx_hash = submit_transaction(state.clone(), request, password).await?;
+
+    let deployment = ContractDeployment {
+        tx_hash,
+        contract_name: contract.name.clone(),
+        source_file: contract.source_file.clone(),
+        from: args.from.clone(),
+        constructor_args: args
+            .constructor_args
+            .iter()
+            .map(agent::tools::abi::arg_to_string)
+            .collect(),
+        value,
+        gas_estimate: estimate.gas_estimate,
+        gas_limit: estimate.gas_limit,
+        gas_price: estimate.gas_price,
+        status: DeploymentStatus::Pending,
+        contract_address: None,
+        block_number: None,
+        gas_used: None,
+        submitted_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+    };
+    if let Err(e) = state.wallet_manager.deployments().record(deployment.clone()) {
+        warn!("Failed to record contract deployment: {}", e);
+    }
+    info!(
+        "Deploying {} from {} in transaction {}",
+        contract.name, args.from, deployment.tx_hash
+    );
+    Ok(deployment)
+}
+
+/// Contracts deployed from the wallet, newest first. Pending deployments
+/// whose receipts have arrived are settled with their contract address.
+#[tauri::command]
+async fn get_contract_deployments(
+    state: State<'_, AppState>,
+) -> Result<Vec<ContractDeployment>, String> {
+    let deployments = state.wallet_manager.deployments();
+    if let Some(storage) = state.node_manager.get_storage().await {
+        deployments
+            .settle(|tx_hash| {
+                let bytes = hex::decode(tx_hash.trim_start_matches("0x"))
+                    .ok()
+                    .filter(|bytes| bytes.len() == 32)?;
+                let hash = citrate_consensus::types::Hash::from_bytes(&bytes);
+                storage.transactions.get_receipt(&hash).ok().flatten()
+            })
+            .map_err(localize_err)?;
+    }
+    Ok(deployments.list())
+}
+
 // Helper function to find forge binary path
 fn which_forge() -> Option<String> {
     use std::process::Command;
@@ -2246,6 +3980,106 @@ struct ForgeTestResult {
     errors: Vec<String>,
 }
 
+// ===== State Event Bus Commands =====
+
+/// Get the `state-delta` flush settings
+#[tauri::command]
+async fn get_event_bus_config(state: State<'_, AppState>) -> Result<EventBusConfig, String> {
+    Ok(state.event_bus.config())
+}
+
+/// Update the `state-delta` flush settings
+#[tauri::command]
+async fn update_event_bus_config(
+    state: State<'_, AppState>,
+    config: EventBusConfig,
+) -> Result<(), String> {
+    state.event_bus.set_config(config)
+}
+
+/// Toggle performance mode (less frequent GUI updates)
+#[tauri::command]
+async fn set_performance_mode(state: State<'_, AppState>, enabled: bool) -> Result<(), String> {
+    let mut config = state.event_bus.config();
+    config.performance_mode = enabled;
+    state.event_bus.set_config(config)
+}
+
+// ===== Locale Commands =====
+
+/// Locale of backend messages and the locales available
+#[tauri::command]
+async fn get_locale() -> Result<LocaleInfo, String> {
+    Ok(i18n::locale_info())
+}
+
+/// Switch the locale of backend messages and persist the choice
+#[tauri::command]
+async fn set_locale(locale: Locale) -> Result<LocaleInfo, String> {
+    i18n::set_locale(locale)?;
+    Ok(i18n::locale_info())
+}
+
+// ===== Updater Commands =====
+
+#[tauri::command]
+async fn updater_get_status(state: State<'_, AppState>) -> Result<UpdateStatus, String> {
+    Ok(state.updater.status().await)
+}
+
+#[tauri::command]
+async fn updater_get_config(state: State<'_, AppState>) -> Result<UpdaterConfig, String> {
+    Ok(state.updater.get_config().await)
+}
+
+/// Look for a newer release on the pinned channel
+#[tauri::command]
+async fn updater_check(state: State<'_, AppState>) -> Result<Option<ReleaseInfo>, String> {
+    state.updater.check().await
+}
+
+/// Download and verify the available release in the background; progress
+/// and completion arrive as `updater-event`s
+#[tauri::command]
+async fn updater_download(state: State<'_, AppState>) -> Result<(), String> {
+    let UpdateStatus::Available(release) = state.updater.status().await else {
+        return Err(tr!("error.no_update_available"));
+    };
+    let updater = state.updater.clone();
+    tauri::async_runtime::spawn(async move {
+        let _ = updater.download(release).await;
+    });
+    Ok(())
+}
+
+/// Pin updates to the stable, beta or nightly channel
+#[tauri::command]
+async fn updater_set_channel(
+    state: State<'_, AppState>,
+    channel: UpdateChannel,
+) -> Result<(), String> {
+    state.updater.set_channel(channel).await
+}
+
+#[tauri::command]
+async fn updater_set_auto_download(
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    state.updater.set_auto_download(enabled).await
+}
+
+/// Install the staged release and restart into it
+#[tauri::command]
+async fn updater_restart(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let version = state.updater.install_staged().await?;
+    info!("Restarting into {}", version);
+    if let Err(e) = state.node_manager.stop().await {
+        warn!("Failed to stop node before restart: {}", e);
+    }
+    app.restart()
+}
+
 // ===== GPU Resource Commands =====
 
 /// Get all detected GPU devices
@@ -2299,6 +4133,7 @@ async fn gpu_submit_job(
     memory_required: u64,
     estimated_time: u64,
     priority: u32,
+    priority_class: Option<JobPriorityClass>,
 ) -> Result<String, String> {
     let job = ComputeJob {
         id: uuid::Uuid::new_v4().to_string(),
@@ -2315,6 +4150,9 @@ async fn gpu_submit_job(
         memory_required,
         estimated_time,
         priority,
+        priority_class: priority_class.unwrap_or_default(),
+        device_id: None,
+        settlement: None,
     };
     state.gpu_manager.submit_job(job).await
 }
@@ -2340,6 +4178,75 @@ async fn gpu_cancel_job(state: State<'_, AppState>, job_id: String) -> Result<()
     state.gpu_manager.cancel_job(&job_id).await
 }
 
+/// Escrow the payment of a queued or running job for the provider that
+/// runs it, from the job's requester account
+#[tauri::command]
+async fn gpu_escrow_job(
+    state: State<'_, AppState>,
+    job_id: String,
+    provider: String,
+    password: Option<String>,
+) -> Result<String, String> {
+    let job = state
+        .gpu_manager
+        .get_job(&job_id)
+        .await
+        .ok_or_else(|| format!("Job {} not found", job_id))?;
+    let request = gpu::settlement::escrow_request(&job, &provider)?;
+    submit_transaction(state, request, password).await
+}
+
+/// Mark a job completed and settle its payment to `provider`
+#[tauri::command]
+async fn gpu_complete_job(
+    state: State<'_, AppState>,
+    job_id: String,
+    result_hash: String,
+    provider: String,
+    password: String,
+) -> Result<JobSettlement, String> {
+    state.gpu_manager.complete_job(&job_id, result_hash).await?;
+    settle_compute_job(state, &job_id, &provider, password).await
+}
+
+/// Retry the settlement of a completed job whose settlement failed
+#[tauri::command]
+async fn gpu_settle_job(
+    state: State<'_, AppState>,
+    job_id: String,
+    provider: String,
+    password: String,
+) -> Result<JobSettlement, String> {
+    settle_compute_job(state, &job_id, &provider, password).await
+}
+
+/// Attest a completed job, sign the attestation with the provider account
+/// and submit it to the compute precompile. A failure to sign or submit is
+/// recorded on the job so the settlement can be retried.
+async fn settle_compute_job(
+    state: State<'_, AppState>,
+    job_id: &str,
+    provider: &str,
+    password: String,
+) -> Result<JobSettlement, String> {
+    let mut attestation = state.gpu_manager.attest_job(job_id, provider).await?;
+    let outcome: Result<String, String> = async {
+        let payload = attestation.signing_payload()?;
+        attestation.signature = state
+            .wallet_manager
+            .sign_message(&payload, provider, &password)
+            .await
+            .map_err(localize_err)?;
+        let request = attestation.settle_request()?;
+        submit_transaction(state.clone(), request, Some(password.clone())).await
+    }
+    .await;
+    state
+        .gpu_manager
+        .record_settlement(job_id, attestation, outcome)
+        .await
+}
+
 /// Get available GPU memory for compute
 #[tauri::command]
 async fn gpu_get_available_memory(state: State<'_, AppState>) -> Result<u64, String> {
@@ -2375,7 +4282,10 @@ async fn image_scan_local_models(state: State<'_, AppState>) -> Result<Vec<Image
     Ok(state.image_model_manager.scan_local_models().await)
 }
 
-/// Create an image generation job
+/// Create an image generation job and start running it. An input image
+/// turns it into img2img, and a mask on top of that into inpainting; both
+/// are file paths or base64 image data. Trained LoRA adapters are applied
+/// by ID.
 #[tauri::command]
 async fn image_create_generation_job(
     state: State<'_, AppState>,
@@ -2388,6 +4298,10 @@ async fn image_create_generation_job(
     seed: Option<u64>,
     guidance_scale: f32,
     num_steps: u32,
+    input_image: Option<String>,
+    mask_image: Option<String>,
+    strength: Option<f32>,
+    lora_weights: Option<Vec<LoRAWeight>>,
 ) -> Result<String, String> {
     let request = ImageGenerationRequest {
         model_id,
@@ -2399,11 +4313,21 @@ async fn image_create_generation_job(
         guidance_scale,
         num_steps,
         scheduler: ImageScheduler::EulerAncestral,
-        input_image: None,
-        strength: None,
-        lora_weights: vec![],
+        input_image,
+        strength,
+        mask_image,
+        lora_weights: lora_weights.unwrap_or_default(),
     };
-    state.image_model_manager.create_generation_job(request).await
+    let job_id = state.image_model_manager.create_generation_job(request).await?;
+
+    let manager = state.image_model_manager.clone();
+    let run_id = job_id.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = manager.run_generation_job(&run_id).await {
+            warn!("Image generation job {} failed: {}", run_id, e);
+        }
+    });
+    Ok(job_id)
 }
 
 /// Get generation job by ID
@@ -2430,13 +4354,34 @@ async fn image_cancel_generation_job(
     state.image_model_manager.cancel_generation_job(&job_id).await
 }
 
-/// Create an image training job
+/// Check a training configuration against its concept images: the
+/// identifier, captions, regularization images and anything worth a second
+/// look before training
+#[tauri::command]
+async fn image_validate_training_config(
+    state: State<'_, AppState>,
+    config: ImageTrainingConfig,
+) -> Result<ConceptReport, String> {
+    state.image_model_manager.validate_training_config(&config).await
+}
+
+/// Create an image training job and start running it. The trained LoRA is
+/// registered for generation jobs once it completes.
 #[tauri::command]
 async fn image_create_training_job(
     state: State<'_, AppState>,
     config: ImageTrainingConfig,
 ) -> Result<String, String> {
-    state.image_model_manager.create_training_job(config).await
+    let job_id = state.image_model_manager.create_training_job(config).await?;
+
+    let manager = state.image_model_manager.clone();
+    let run_id = job_id.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = manager.run_training_job(&run_id).await {
+            warn!("Image training job {} failed: {}", run_id, e);
+        }
+    });
+    Ok(job_id)
 }
 
 /// Get training job by ID
@@ -2463,6 +4408,12 @@ async fn image_cancel_training_job(
     state.image_model_manager.cancel_training_job(&job_id).await
 }
 
+/// Get trained LoRA adapters
+#[tauri::command]
+async fn image_get_loras(state: State<'_, AppState>) -> Result<Vec<ImageLora>, String> {
+    Ok(state.image_model_manager.get_loras().await)
+}
+
 /// Get generated images gallery
 #[tauri::command]
 async fn image_get_gallery(state: State<'_, AppState>) -> Result<Vec<GeneratedImage>, String> {
@@ -2540,6 +4491,11 @@ pub fn run() {
         .with_env_filter("info,citrate_core=debug")
         .init();
 
+    // Resolve the active profile before the managers read their directories
+    let profile_store = ProfileStore::open();
+    profiles::set_active_profile(profile_store.active());
+    info!("Using profile '{}'", profile_store.active());
+
     // Create managers
     let node_manager = Arc::new(NodeManager::new().expect("Failed to create node manager"));
     let wallet_manager = Arc::new(WalletManager::new().expect("Failed to create wallet manager"));
@@ -2558,6 +4514,16 @@ pub fn run() {
     let hf_manager = Arc::new(HuggingFaceManager::new());
     let gpu_manager = Arc::new(GPUResourceManager::new());
     let image_model_manager = Arc::new(ImageModelManager::new());
+    let event_bus = Arc::new(StateDeltaBus::default());
+    let walletconnect_manager = Arc::new(WalletConnectManager::new(
+        wallet_manager.clone(),
+        node_manager.clone(),
+    ));
+
+    let context = tauri::generate_context!();
+    let updater = Arc::new(UpdaterManager::new(
+        context.package_info().version.to_string(),
+    ));
 
     // Create agent state (initialized lazily when node starts)
     let agent_state = AgentState::new();
@@ -2576,15 +4542,25 @@ pub fn run() {
             hf_manager,
             gpu_manager,
             image_model_manager,
+            event_bus: event_bus.clone(),
+            walletconnect_manager,
+            updater,
+            profiles: Arc::new(RwLock::new(profile_store)),
         })
         .manage(agent_state)
         // Expose IPFS manager separately for agent commands
         .manage(ipfs_manager)
+        // Expose the state bus separately so agent commands can report progress
+        .manage(event_bus)
         .invoke_handler(tauri::generate_handler![
             // Node commands
             start_node,
             stop_node,
             get_node_status,
+            get_sync_progress,
+            run_diagnostics,
+            node_attach_external,
+            node_detach_external,
             get_node_config,
             update_node_config,
             join_testnet,
@@ -2594,6 +4570,10 @@ pub fn run() {
             switch_to_testnet,
             ensure_connectivity,
             check_first_time_and_setup_if_needed,
+            // Devnet block production commands
+            dev_set_block_interval,
+            dev_mine_blocks,
+            dev_set_automine,
             // Network/Bootnode commands
             get_bootnodes,
             add_bootnode,
@@ -2602,6 +4582,13 @@ pub fn run() {
             connect_peer,
             disconnect_peer,
             get_peers,
+            get_peer_reputation,
+            get_peer_versions,
+            // Storage panel
+            get_db_stats,
+            get_stats_history,
+            compact_database,
+            storage_prune_preview,
             // Wallet activity
             get_account_activity,
             get_tx_overview,
@@ -2610,6 +4597,13 @@ pub fn run() {
             // Tracked addresses
             get_tracked_addresses,
             save_tracked_addresses,
+            // Profile commands
+            profile_list,
+            profile_create,
+            profile_switch,
+            profile_delete,
+            reset_chain_data,
+            wipe_network_profile,
             set_reward_address,
             get_reward_address,
             // Wallet commands
@@ -2624,14 +4618,71 @@ pub fn run() {
             validate_password_strength,
             get_account,
             send_transaction,
+            replace_transaction,
+            cancel_transaction,
             eth_call,
+            // Governance commands
+            gov_get_config,
+            gov_list_proposals,
+            gov_get_proposal,
+            gov_get_vote,
+            gov_submit_proposal,
+            gov_vote,
+            gov_execute,
+            gov_cancel,
+            // Multisig wallet commands
+            multisig_list,
+            multisig_create,
+            multisig_import,
+            multisig_remove,
+            multisig_get_wallet,
+            multisig_list_proposals,
+            multisig_pending_proposals,
+            multisig_deposit,
+            multisig_propose,
+            multisig_approve,
+            multisig_revoke,
+            multisig_execute,
             sign_message,
             verify_signature,
             export_private_key,
+            wallet_export_backup,
+            wallet_import_backup,
+            // Address book commands
+            wallet_list_contacts,
+            wallet_contact_tags,
+            wallet_add_contact,
+            wallet_update_contact,
+            wallet_remove_contact,
+            wallet_recent_addresses,
+            wallet_resolve_recipient,
+            wallet_list_scheduled_payments,
+            wallet_create_scheduled_payment,
+            wallet_pause_scheduled_payment,
+            wallet_resume_scheduled_payment,
+            wallet_remove_scheduled_payment,
+            wallet_approve_scheduled_payment,
+            wallet_scheduled_payment_history,
             update_balance,
             // Session management commands
             get_session_remaining,
             is_session_active,
+            // Remote signer commands
+            connect_remote_signer,
+            disconnect_remote_signer,
+            get_remote_signer_status,
+            // WalletConnect commands
+            walletconnect_get_config,
+            walletconnect_set_project_id,
+            walletconnect_pair,
+            walletconnect_get_proposals,
+            walletconnect_approve_session,
+            walletconnect_reject_session,
+            walletconnect_get_sessions,
+            walletconnect_disconnect,
+            walletconnect_get_requests,
+            walletconnect_approve_request,
+            walletconnect_reject_request,
             lock_wallet,
             lock_all_wallets,
             check_password_required,
@@ -2640,11 +4691,14 @@ pub fn run() {
             get_block_details,
             get_blue_set,
             get_current_tips,
+            get_dag_stats_series,
             calculate_blue_score,
             get_block_path,
+            search_dag,
             // Model commands
             deploy_model,
             run_inference,
+            cancel_inference,
             start_training,
             get_model_info,
             list_models,
@@ -2661,7 +4715,13 @@ pub fn run() {
             get_lora_adapters,
             delete_lora_adapter,
             run_inference_with_lora,
+            merge_lora_adapter,
+            benchmark_model,
+            get_model_benchmarks,
+            delete_model_benchmark,
+            apply_adapter,
             validate_dataset,
+            preprocess_dataset,
             get_lora_presets,
             // Agent commands
             agent_create_session,
@@ -2669,6 +4729,7 @@ pub fn run() {
             agent_list_sessions,
             agent_delete_session,
             agent_send_message,
+            agent_cancel_stream,
             agent_get_messages,
             agent_clear_history,
             agent_get_pending_tools,
@@ -2684,6 +4745,36 @@ pub fn run() {
             agent_get_active_model,
             agent_set_api_key,
             agent_set_auto_mode,
+            agent_set_sampling,
+            agent_get_safety_policy,
+            agent_set_safety_policy,
+            agent_list_prompts,
+            agent_get_prompt,
+            agent_save_prompt,
+            agent_delete_prompt,
+            agent_set_default_prompt,
+            agent_export_prompts,
+            agent_import_prompts,
+            agent_set_session_prompt,
+            agent_get_session_prompt,
+            agent_set_session_route,
+            agent_get_session_route,
+            agent_get_provider_health,
+            agent_check_provider_health,
+            // Scheduled task commands
+            agent_list_tasks,
+            agent_create_task,
+            agent_update_task,
+            agent_set_task_enabled,
+            agent_delete_task,
+            agent_run_task_now,
+            // Long-term memory commands
+            agent_search_memory,
+            // File access commands
+            agent_list_file_grants,
+            agent_grant_directory,
+            agent_revoke_directory,
+            agent_file_audit_log,
             // Multi-provider AI configuration commands
             get_ai_providers_config,
             get_ai_provider_keys,
@@ -2726,6 +4817,10 @@ pub fn run() {
             get_windows_by_type,
             has_window_type,
             get_window_count,
+            get_accessible_windows,
+            cycle_window_focus,
+            get_accessibility_preferences,
+            set_accessibility_preferences,
             // Terminal commands
             terminal_create,
             terminal_write,
@@ -2746,6 +4841,12 @@ pub fn run() {
             ipfs_unpin,
             ipfs_list_pins,
             ipfs_get_peers,
+            ipfs_get_usage,
+            ipfs_gc_run,
+            ipfs_check_license_health,
+            marketplace_register_license,
+            marketplace_release_license,
+            marketplace_list_licenses,
             // HuggingFace commands
             hf_get_auth_url,
             hf_exchange_code,
@@ -2771,14 +4872,34 @@ pub fn run() {
             hf_auto_select_model,
             hf_download_file_resumable,
             hf_cancel_download_resumable,
+            hf_convert_to_gguf,
             hf_delete_local_model,
             hf_get_recommended_models,
             hf_get_download_stats,
+            hf_search_datasets,
+            hf_download_dataset,
             // Foundry/Contract compilation commands
             forge_check_installed,
             forge_build,
+            estimate_contract_deployment,
+            deploy_contract,
+            get_contract_deployments,
             forge_init,
             forge_test,
+            // State event bus commands
+            get_event_bus_config,
+            update_event_bus_config,
+            set_performance_mode,
+            // Updater commands
+            get_locale,
+            set_locale,
+            updater_get_status,
+            updater_get_config,
+            updater_check,
+            updater_download,
+            updater_set_channel,
+            updater_set_auto_download,
+            updater_restart,
             // GPU Resource commands
             gpu_get_devices,
             gpu_refresh_devices,
@@ -2792,6 +4913,9 @@ pub fn run() {
             gpu_cancel_job,
             gpu_get_available_memory,
             gpu_is_within_schedule,
+            gpu_escrow_job,
+            gpu_complete_job,
+            gpu_settle_job,
             // Image Model commands
             image_get_models,
             image_get_model,
@@ -2800,15 +4924,36 @@ pub fn run() {
             image_get_generation_job,
             image_get_generation_jobs,
             image_cancel_generation_job,
+            image_validate_training_config,
             image_create_training_job,
             image_get_training_job,
             image_get_training_jobs,
             image_cancel_training_job,
+            image_get_loras,
             image_get_gallery,
             image_delete_from_gallery,
             image_get_models_dir,
             image_get_output_dir,
         ])
+        .on_window_event(|window, event| {
+            // Keep focus and open windows in sync with the OS so keyboard
+            // focus cycling starts from the window the user is actually in
+            let label = window.label().to_string();
+            let window_manager = window.state::<AppState>().window_manager.clone();
+            match event {
+                tauri::WindowEvent::Focused(true) => {
+                    tauri::async_runtime::spawn(async move {
+                        window_manager.read().await.set_focus(&label).await;
+                    });
+                }
+                tauri::WindowEvent::Destroyed => {
+                    tauri::async_runtime::spawn(async move {
+                        window_manager.read().await.unregister_window(&label).await;
+                    });
+                }
+                _ => {}
+            }
+        })
         .setup(|app| {
             // Initialize window manager with app handle
             let app_handle = app.handle().clone();
@@ -2817,6 +4962,7 @@ pub fn run() {
                 tauri::async_runtime::block_on(async {
                     let mut wm = state.window_manager.write().await;
                     wm.set_app_handle(app_handle.clone());
+                    wm.register_main_window().await;
 
                     // Initialize terminal manager with app handle
                     let mut tm = state.terminal_manager.write().await;
@@ -2828,18 +4974,240 @@ pub fn run() {
             tauri::async_runtime::spawn(async move {
                 setup_node_components(app_handle).await;
             });
-            // Periodic node status broadcaster
+            // Periodic state broadcaster: sample node, peer and DAG state and
+            // emit whatever changed as a single coalesced delta
             let app_handle2 = app.handle().clone();
             tauri::async_runtime::spawn(async move {
+                // Newest version the user has already been told about
+                let mut notified_upgrade: Option<String> = None;
                 loop {
-                    // Emit node status periodically (1s)
                     let state = app_handle2.state::<AppState>();
+                    let bus = state.event_bus.clone();
                     if let Ok(status) = state.node_manager.get_status().await {
-                        let _ = app_handle2.emit("node-status", status);
+                        if let Some(latest) = &status.upgrade_available {
+                            if notified_upgrade.as_ref() != Some(latest) {
+                                let notice = UpgradeNotice::new(latest.clone());
+                                let _ = app_handle2.emit(UPGRADE_AVAILABLE_EVENT, notice);
+                                notified_upgrade = Some(latest.clone());
+                            }
+                        }
+                        bus.publish_node_status(&status);
+                    }
+                    bus.publish_peers(&state.node_manager.get_peers_summary().await);
+                    let dag_manager = state.dag_manager.read().await.clone();
+                    if let Some(dag_manager) = dag_manager {
+                        if let Ok(tips) = dag_manager.get_current_tips().await {
+                            bus.publish_dag(&tips);
+                        }
+                    }
+                    if let Some(delta) = bus.take_delta() {
+                        let _ = app_handle2.emit(STATE_DELTA_EVENT, delta);
+                    }
+                    sleep(bus.interval()).await;
+                }
+            });
+            // Report confirmation depth of submitted transactions
+            let app_handle_tx = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let state = app_handle_tx.state::<AppState>();
+                    for update in state.node_manager.poll_confirmations().await {
+                        let _ = app_handle_tx.emit(TX_CONFIRMED_EVENT, update);
+                    }
+                    sleep(std::time::Duration::from_secs(2)).await;
+                }
+            });
+            // Report wallet activity status changes, including reorged blocks
+            let app_handle_activity = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let state = app_handle_activity.state::<AppState>();
+                    for change in state.node_manager.poll_activity().await {
+                        let _ = app_handle_activity.emit(WALLET_ACTIVITY_CHANGED_EVENT, change);
+                    }
+                    sleep(std::time::Duration::from_secs(5)).await;
+                }
+            });
+            // Report sync progress while the node catches up, and once more
+            // when it reaches the tip
+            let app_handle_sync = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut catching_up = false;
+                loop {
+                    let state = app_handle_sync.state::<AppState>();
+                    if let Some(progress) = state.node_manager.sync_progress().await {
+                        let behind = progress.current_height < progress.target_height;
+                        if behind || catching_up {
+                            let _ = app_handle_sync.emit(SYNC_PROGRESS_EVENT, progress);
+                        }
+                        catching_up = behind;
                     }
                     sleep(std::time::Duration::from_secs(1)).await;
                 }
             });
+            // Submit scheduled wallet payments as they come due
+            let app_handle_payments = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let state = app_handle_payments.state::<AppState>();
+                    for run in state.node_manager.run_scheduled_payments().await {
+                        let _ = app_handle_payments.emit(SCHEDULED_PAYMENT_EVENT, run);
+                    }
+                    sleep(std::time::Duration::from_secs(15)).await;
+                }
+            });
+            // Forward GPU job state changes to the frontend
+            let app_handle_gpu = app.handle().clone();
+            let mut gpu_events = app_handle_gpu.state::<AppState>().gpu_manager.subscribe();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match gpu_events.recv().await {
+                        Ok(event) => {
+                            let _ = app_handle_gpu.emit(GPU_JOB_STATE_CHANGED_EVENT, event);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!("Dropped {} GPU job state events", skipped);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+            // Forward image generation progress to the generation view
+            let app_handle_image = app.handle().clone();
+            let mut image_progress = app_handle_image
+                .state::<AppState>()
+                .image_model_manager
+                .subscribe_generation_progress();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match image_progress.recv().await {
+                        Ok(progress) => {
+                            let _ = app_handle_image.emit(IMAGE_GENERATION_PROGRESS_EVENT, progress);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!("Dropped {} image generation progress events", skipped);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+            // Forward training samples to the training view
+            let app_handle_training = app.handle().clone();
+            let mut training_previews = app_handle_training
+                .state::<AppState>()
+                .image_model_manager
+                .subscribe_training_previews();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match training_previews.recv().await {
+                        Ok(preview) => {
+                            let _ = app_handle_training.emit(IMAGE_TRAINING_PREVIEW_EVENT, preview);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!("Dropped {} image training previews", skipped);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+            // Forward LoRA job completions and their eval scorecards
+            let app_handle_lora = app.handle().clone();
+            let mut lora_events = app_handle_lora
+                .state::<AppState>()
+                .model_manager
+                .subscribe_lora_events();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match lora_events.recv().await {
+                        Ok(event) => {
+                            let _ = app_handle_lora.emit(LORA_JOB_COMPLETED_EVENT, event);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!("Dropped {} LoRA job completion events", skipped);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+            // Forward safetensors to GGUF conversion progress
+            let app_handle_convert = app.handle().clone();
+            let mut conversion_progress = app_handle_convert
+                .state::<AppState>()
+                .hf_manager
+                .subscribe_conversion_progress();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match conversion_progress.recv().await {
+                        Ok(progress) => {
+                            let _ = app_handle_convert.emit(GGUF_CONVERSION_EVENT, progress);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!("Dropped {} GGUF conversion progress events", skipped);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+            // Forward WalletConnect proposals and requests for user approval
+            let app_handle_wc = app.handle().clone();
+            let mut wc_events = app_handle_wc
+                .state::<AppState>()
+                .walletconnect_manager
+                .subscribe();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match wc_events.recv().await {
+                        Ok(event) => {
+                            let _ = app_handle_wc.emit(WALLETCONNECT_EVENT, event);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!("Dropped {} WalletConnect events", skipped);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+            // Forward updater status and check for releases periodically
+            let app_handle_updater = app.handle().clone();
+            let updater = app_handle_updater.state::<AppState>().updater.clone();
+            let mut updater_events = updater.subscribe();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match updater_events.recv().await {
+                        Ok(status) => {
+                            let _ = app_handle_updater.emit(UPDATER_EVENT, status);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!("Dropped {} updater events", skipped);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    // Failures are reported through the status event
+                    let _ = updater.check_and_download().await;
+                    sleep(CHECK_INTERVAL).await;
+                }
+            });
+            // Keep licensed model weights pinned and release lapsed licenses
+            let app_handle_licenses = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    sleep(LICENSE_CHECK_INTERVAL).await;
+                    let state = app_handle_licenses.state::<AppState>();
+                    if !state.ipfs_manager.is_running().await {
+                        continue;
+                    }
+                    for record in state.ipfs_manager.expire_licenses().await {
+                        if let Some(local_model) = &record.license.local_model {
+                            state.model_manager.evict_cached_results(local_model);
+                        }
+                    }
+                    state.ipfs_manager.check_license_health().await;
+                }
+            });
             // Initialize agent with managers
             let app_handle3 = app.handle().clone();
             tauri::async_runtime::spawn(async move {
@@ -2854,6 +5222,7 @@ pub fn run() {
                     app_state.wallet_manager.clone(),
                     app_state.model_manager.clone(),
                     app_state.dag_manager.clone(),
+                    app_state.terminal_manager.clone(),
                 );
 
                 // Check for bundled model in app resources and configure if found
@@ -2887,11 +5256,46 @@ pub fn run() {
                     }
                 }
 
+                // Forward scheduled task results as notifications
+                let mut task_events = agent_manager
+                    .orchestrator()
+                    .read()
+                    .await
+                    .task_scheduler()
+                    .subscribe();
+                let app_handle_tasks = app_handle3.clone();
+                tauri::async_runtime::spawn(async move {
+                    loop {
+                        match task_events.recv().await {
+                            Ok(event) => {
+                                let _ = app_handle_tasks.emit(AGENT_TASK_COMPLETED_EVENT, event);
+                            }
+                            Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                                warn!("Dropped {} scheduled task events", skipped);
+                            }
+                            Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                });
+
                 *agent_state.manager.write().await = Some(agent_manager);
                 info!("Agent manager initialized");
+
+                // Run scheduled agent tasks as they come due
+                let manager = agent_state.manager.clone();
+                tauri::async_runtime::spawn(async move {
+                    loop {
+                        sleep(std::time::Duration::from_secs(30)).await;
+                        let orchestrator = match manager.read().await.as_ref() {
+                            Some(manager) => manager.orchestrator(),
+                            None => continue,
+                        };
+                        orchestrator.read().await.run_due_tasks().await;
+                    }
+                });
             });
             Ok(())
         })
-        .run(tauri::generate_context!())
+        .run(context)
         .expect("error while running tauri application");
 }
@@ -0,0 +1,457 @@
+//! Model Benchmarking
+//!
+//! Runs a fixed prompt suite against a local GGUF model, optionally with a
+//! LoRA adapter applied, and reports:
+//!
+//! - generation throughput in tokens per second
+//! - time to first token: prompt processing time, after the model is loaded
+//! - memory per device: the model, KV cache and compute buffers llama.cpp
+//!   allocates on each backend, which is what the run needs at its peak
+//!
+//! Timings and buffer sizes are read from the performance report llama.cpp
+//! prints to stderr. Results are kept on disk so quantizations of the same
+//! model can be compared before one is deployed.
+
+use super::find_llama_tool;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Prompts of the standard suite: a short answer, a longer explanation, code
+/// and a summary of a longer prompt, so prompt processing is exercised too
+pub const STANDARD_PROMPTS: &[&str] = &[
+    "What is the capital of France?",
+    "Explain how a blockchain reaches consensus, step by step.",
+    "Write a Python function that returns the n-th Fibonacci number.",
+    "Summarize the following text in two sentences: A directed acyclic graph \
+     of blocks lets several blocks be produced at once. Instead of discarding \
+     blocks that lose a race, the chain orders all of them, so throughput \
+     grows with the number of producers while every node still agrees on a \
+     single order of transactions.",
+];
+
+/// Benchmark settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BenchmarkConfig {
+    /// Prompts to run; the standard suite when empty
+    pub prompts: Vec<String>,
+    /// Tokens generated per prompt
+    pub max_tokens: u32,
+    /// Times each prompt is run; results are averaged
+    pub runs: u32,
+    pub context_size: u32,
+    /// Layers offloaded to the GPU; llama.cpp's default when unset
+    pub gpu_layers: Option<u32>,
+    pub threads: Option<u32>,
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        Self {
+            prompts: Vec::new(),
+            max_tokens: 128,
+            runs: 1,
+            context_size: 2048,
+            gpu_layers: None,
+            threads: None,
+        }
+    }
+}
+
+impl BenchmarkConfig {
+    fn prompts(&self) -> Vec<String> {
+        if self.prompts.is_empty() {
+            STANDARD_PROMPTS.iter().map(|p| p.to_string()).collect()
+        } else {
+            self.prompts.clone()
+        }
+    }
+}
+
+/// Memory llama.cpp allocated on one device
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceMemory {
+    /// Device id as reported by GPU detection (`cuda-0`, `metal-0`, `cpu-0`)
+    pub device_id: String,
+    /// Device name, when the device was detected
+    pub device_name: Option<String>,
+    pub bytes: u64,
+}
+
+/// Results of one benchmark run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkResult {
+    pub id: String,
+    pub model_id: String,
+    pub model_path: String,
+    /// Quantization read from the model file name, e.g. `Q4_K_M`
+    pub quantization: Option<String>,
+    pub adapter_id: Option<String>,
+    pub config: BenchmarkConfig,
+    /// Mean generation throughput
+    pub tokens_per_sec: f64,
+    /// Mean prompt processing throughput
+    pub prompt_tokens_per_sec: f64,
+    /// Mean time to first token, excluding model load
+    pub time_to_first_token_ms: f64,
+    /// Mean model load time
+    pub load_ms: f64,
+    /// Largest allocation seen on each device across runs
+    pub peak_memory: Vec<DeviceMemory>,
+    /// Prompt runs measured
+    pub samples: usize,
+    pub created_at: u64,
+}
+
+/// Timings from llama.cpp's performance report
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Timings {
+    load_ms: f64,
+    prompt_ms: f64,
+    prompt_tokens: u64,
+    eval_ms: f64,
+    eval_tokens: u64,
+}
+
+/// Milliseconds and, when present, token count of a timing line
+/// (`prompt eval time =  85.53 ms /    10 tokens (...)`)
+fn parse_timing(line: &str) -> Option<(f64, u64)> {
+    let value = line.split_once('=')?.1;
+    let ms = value.split_whitespace().next()?.parse().ok()?;
+    let count = value
+        .split_once('/')
+        .and_then(|(_, rest)| rest.split_whitespace().next()?.parse().ok())
+        .unwrap_or(0);
+    Some((ms, count))
+}
+
+/// Timings from `llama_perf_context_print` (or the older
+/// `llama_print_timings`) lines; `None` if generation timings are missing
+fn parse_timings(log: &str) -> Option<Timings> {
+    let mut timings = Timings::default();
+    let mut found_eval = false;
+    for line in log.lines() {
+        if !line.contains("llama_perf_context_print") && !line.contains("llama_print_timings") {
+            continue;
+        }
+        let Some((ms, count)) = parse_timing(line) else {
+            continue;
+        };
+        if line.contains("load time") {
+            timings.load_ms = ms;
+        } else if line.contains("prompt eval time") {
+            timings.prompt_ms = ms;
+            timings.prompt_tokens = count;
+        } else if line.contains("eval time") {
+            timings.eval_ms = ms;
+            timings.eval_tokens = count;
+            found_eval = true;
+        }
+    }
+    found_eval.then_some(timings)
+}
+
+/// Bytes allocated per device from llama.cpp's buffer report
+/// (`CUDA0 model buffer size =  3820.93 MiB`), summed over buffer kinds
+fn parse_buffer_sizes(log: &str) -> BTreeMap<String, u64> {
+    let mut devices = BTreeMap::new();
+    for line in log.lines() {
+        let Some((label, value)) = line.split_once("buffer size =") else {
+            continue;
+        };
+        let Some(backend) = label
+            .rsplit(':')
+            .next()
+            .and_then(|label| label.split_whitespace().next())
+        else {
+            continue;
+        };
+        let mut parts = value.split_whitespace();
+        let (Some(Ok(amount)), Some(unit)) = (parts.next().map(str::parse::<f64>), parts.next())
+        else {
+            continue;
+        };
+        let scale = match unit {
+            "KiB" | "KB" => 1024.0,
+            "MiB" | "MB" => 1024.0 * 1024.0,
+            "GiB" | "GB" => 1024.0 * 1024.0 * 1024.0,
+            _ => 1.0,
+        };
+        let device = crate::gpu::device_id_for_llama_backend(backend);
+        *devices.entry(device).or_insert(0) += (amount * scale) as u64;
+    }
+    devices
+}
+
+/// Quantization type in a GGUF file name (`Mistral-7B-Q4_K_M.gguf`)
+pub fn quantization_from_name(path: &Path) -> Option<String> {
+    let is_quantization = |part: &str| {
+        part.strip_prefix("IQ")
+            .or_else(|| part.strip_prefix('Q'))
+            .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+            || matches!(part, "F16" | "F32" | "FP16" | "FP32" | "BF16")
+    };
+    let stem = path.file_stem()?.to_string_lossy().to_uppercase();
+    stem.split(['-', '.'])
+        .rev()
+        .find(|part| is_quantization(part))
+        .map(str::to_string)
+}
+
+/// Run the suite against `model_path`, with `adapter_path` applied if given
+pub async fn run_benchmark(
+    config: &BenchmarkConfig,
+    model_path: &Path,
+    adapter_path: Option<&str>,
+) -> Result<BenchmarkResult> {
+    if config.runs == 0 || config.max_tokens == 0 {
+        return Err(anyhow!("Benchmark needs at least one run and one token"));
+    }
+    let cli = find_llama_tool(&["llama-cli", "main"])?;
+    let threads = config.threads.unwrap_or(num_cpus::get() as u32);
+
+    let mut samples: Vec<Timings> = Vec::new();
+    let mut peak: BTreeMap<String, u64> = BTreeMap::new();
+    for prompt in config.prompts() {
+        for _ in 0..config.runs {
+            let mut cmd = tokio::process::Command::new(&cli);
+            cmd.arg("-m")
+                .arg(model_path)
+                .arg("-p")
+                .arg(&prompt)
+                .arg("-n")
+                .arg(config.max_tokens.to_string())
+                .arg("-c")
+                .arg(config.context_size.to_string())
+                .arg("--temp")
+                .arg("0")
+                .arg("-t")
+                .arg(threads.to_string())
+                .arg("--no-display-prompt");
+            if let Some(layers) = config.gpu_layers {
+                cmd.arg("-ngl").arg(layers.to_string());
+            }
+            if let Some(adapter) = adapter_path {
+                cmd.arg("--lora").arg(adapter);
+            }
+
+            let output = cmd.output().await?;
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if !output.status.success() {
+                return Err(anyhow!("Benchmark inference failed: {}", stderr));
+            }
+            samples.push(
+                parse_timings(&stderr).ok_or_else(|| anyhow!("No timings in llama.cpp output"))?,
+            );
+            for (device, bytes) in parse_buffer_sizes(&stderr) {
+                let entry = peak.entry(device).or_insert(0);
+                *entry = (*entry).max(bytes);
+            }
+        }
+    }
+
+    let mean =
+        |value: fn(&Timings) -> f64| samples.iter().map(value).sum::<f64>() / samples.len() as f64;
+    fn rate(tokens: u64, ms: f64) -> f64 {
+        if ms > 0.0 {
+            tokens as f64 * 1000.0 / ms
+        } else {
+            0.0
+        }
+    }
+
+    Ok(BenchmarkResult {
+        id: format!(
+            "bench_{}",
+            uuid::Uuid::new_v4()
+                .to_string()
+                .split('-')
+                .next()
+                .unwrap_or("0000")
+        ),
+        model_id: String::new(),
+        model_path: model_path.to_string_lossy().to_string(),
+        quantization: quantization_from_name(model_path),
+        adapter_id: None,
+        config: config.clone(),
+        tokens_per_sec: mean(|t| rate(t.eval_tokens, t.eval_ms)),
+        prompt_tokens_per_sec: mean(|t| rate(t.prompt_tokens, t.prompt_ms)),
+        time_to_first_token_ms: mean(|t| t.prompt_ms),
+        load_ms: mean(|t| t.load_ms),
+        peak_memory: peak
+            .into_iter()
+            .map(|(device_id, bytes)| DeviceMemory {
+                device_id,
+                device_name: None,
+                bytes,
+            })
+            .collect(),
+        samples: samples.len(),
+        created_at: chrono::Utc::now().timestamp() as u64,
+    })
+}
+
+/// Benchmark results persisted as JSON
+pub struct BenchmarkStore {
+    path: Option<PathBuf>,
+    results: RwLock<Vec<BenchmarkResult>>,
+}
+
+impl BenchmarkStore {
+    /// Open the store at the default location
+    pub fn new() -> Result<Self> {
+        Self::open(crate::profiles::data_root().join("model_benchmarks.json"))
+    }
+
+    /// Open the store at `path`, starting empty if it does not exist
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let results = if path.exists() {
+            serde_json::from_str(&std::fs::read_to_string(&path)?)?
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            path: Some(path),
+            results: RwLock::new(results),
+        })
+    }
+
+    /// A store that is never written to disk
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            results: RwLock::new(Vec::new()),
+        }
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Vec<BenchmarkResult>> {
+        self.results.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Vec<BenchmarkResult>> {
+        self.results.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn persist(&self, results: &[BenchmarkResult]) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(results)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Results newest first, optionally only those of `model_id`
+    pub fn list(&self, model_id: Option<&str>) -> Vec<BenchmarkResult> {
+        let mut results: Vec<_> = self
+            .read()
+            .iter()
+            .filter(|r| model_id.map_or(true, |id| r.model_id == id))
+            .cloned()
+            .collect();
+        results.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        results
+    }
+
+    pub fn record(&self, result: BenchmarkResult) -> Result<()> {
+        let mut results = self.write();
+        results.push(result);
+        self.persist(&results)
+    }
+
+    /// Delete a result; false if it did not exist
+    pub fn delete(&self, id: &str) -> Result<bool> {
+        let mut results = self.write();
+        let before = results.len();
+        results.retain(|r| r.id != id);
+        if results.len() == before {
+            return Ok(false);
+        }
+        self.persist(&results)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "\
+load_tensors:        CUDA0 model buffer size =  3820.93 MiB
+load_tensors:   CPU_Mapped model buffer size =    70.31 MiB
+llama_kv_cache_unified:      CUDA0 KV buffer size =   256.00 MiB
+llama_context:      CUDA0 compute buffer size =   164.01 MiB
+llama_context:  CUDA_Host compute buffer size =    12.01 MiB
+llama_perf_sampler_print:    sampling time =       2.27 ms /    42 runs   (    0.05 ms per token, 18502.20 tokens per second)
+llama_perf_context_print:        load time =     578.38 ms
+llama_perf_context_print: prompt eval time =      80.00 ms /    10 tokens (    8.00 ms per token,   125.00 tokens per second)
+llama_perf_context_print:        eval time =     625.00 ms /    25 runs   (   25.00 ms per token,    40.00 tokens per second)
+llama_perf_context_print:       total time =     747.65 ms /    35 tokens
+";
+
+    #[test]
+    fn test_parse_llama_report() {
+        let timings = parse_timings(LOG).unwrap();
+        assert_eq!(timings.load_ms, 578.38);
+        assert_eq!((timings.prompt_ms, timings.prompt_tokens), (80.0, 10));
+        assert_eq!((timings.eval_ms, timings.eval_tokens), (625.0, 25));
+        assert!(parse_timings("no report").is_none());
+
+        let memory = parse_buffer_sizes(LOG);
+        let mib = |m: f64| (m * 1024.0 * 1024.0) as u64;
+        assert_eq!(memory.len(), 2);
+        assert_eq!(memory["cuda-0"], mib(3820.93) + mib(256.0) + mib(164.01));
+        assert_eq!(memory["cpu-0"], mib(70.31) + mib(12.01));
+    }
+
+    #[test]
+    fn test_quantization_from_name() {
+        let name = |n: &str| quantization_from_name(Path::new(n));
+        assert_eq!(
+            name("Mistral-7B-Instruct-v0.3-Q4_K_M.gguf").as_deref(),
+            Some("Q4_K_M")
+        );
+        assert_eq!(name("qwen2-0.5b-q4.gguf").as_deref(), Some("Q4"));
+        assert_eq!(name("bge-m3-fp16.gguf").as_deref(), Some("FP16"));
+        assert_eq!(name("llama-3-8b.IQ3_XS.gguf").as_deref(), Some("IQ3_XS"));
+        assert_eq!(name("model.gguf"), None);
+    }
+
+    #[test]
+    fn test_store_lists_newest_first() {
+        let store = BenchmarkStore::in_memory();
+        for (id, model, created_at) in [("a", "m1", 1), ("b", "m2", 2), ("c", "m1", 3)] {
+            store
+                .record(BenchmarkResult {
+                    id: id.to_string(),
+                    model_id: model.to_string(),
+                    model_path: String::new(),
+                    quantization: None,
+                    adapter_id: None,
+                    config: BenchmarkConfig::default(),
+                    tokens_per_sec: 0.0,
+                    prompt_tokens_per_sec: 0.0,
+                    time_to_first_token_ms: 0.0,
+                    load_ms: 0.0,
+                    peak_memory: Vec::new(),
+                    samples: 1,
+                    created_at,
+                })
+                .unwrap();
+        }
+        let ids =
+            |results: Vec<BenchmarkResult>| results.into_iter().map(|r| r.id).collect::<Vec<_>>();
+        assert_eq!(ids(store.list(Some("m1"))), ["c", "a"]);
+        assert_eq!(ids(store.list(None)), ["c", "b", "a"]);
+        assert!(store.delete("b").unwrap());
+        assert!(!store.delete("b").unwrap());
+    }
+}
@@ -0,0 +1,402 @@
+//! LoRA Adapter Evaluation
+//!
+//! Runs a small benchmark suite once a LoRA training job finishes, against
+//! the base model and against the base model with the new adapter applied:
+//!
+//! - held-out loss: mean token cross-entropy over a held-out text file, taken
+//!   from `llama-perplexity` as `ln(perplexity)`
+//! - multiple choice: MMLU-style questions from a JSONL file, answered by
+//!   greedily generating the letter of the chosen option
+//! - latency: mean wall time to generate a fixed number of tokens
+//!
+//! Each part is skipped when it is not configured, so a suite can be as
+//! small as a single latency prompt.
+
+use super::find_llama_tool;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Tauri event emitted when a LoRA training job finishes
+pub const LORA_JOB_COMPLETED_EVENT: &str = "lora-job-completed";
+
+/// Labels for multiple-choice options, in order
+const CHOICE_LABELS: &[char] = &['A', 'B', 'C', 'D', 'E', 'F', 'G', 'H'];
+
+/// Benchmark suite run after training
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EvalSuiteConfig {
+    /// Plain text file the held-out loss is measured on
+    pub held_out_path: Option<String>,
+    /// JSONL file of `{"question", "choices", "answer"}` items, where
+    /// `answer` is the index of the correct choice
+    pub multiple_choice_path: Option<String>,
+    /// Maximum number of multiple-choice questions asked
+    pub max_questions: usize,
+    /// Prompt used for the latency measurement
+    pub latency_prompt: String,
+    /// Tokens generated per latency run
+    pub latency_tokens: u32,
+    /// Number of latency runs averaged (0 skips the measurement)
+    pub latency_runs: u32,
+    /// Context size used for the held-out loss
+    pub context_size: u32,
+}
+
+impl Default for EvalSuiteConfig {
+    fn default() -> Self {
+        Self {
+            held_out_path: None,
+            multiple_choice_path: None,
+            max_questions: 100,
+            latency_prompt: "Explain what a blockchain is in one sentence.".to_string(),
+            latency_tokens: 32,
+            latency_runs: 3,
+            context_size: 512,
+        }
+    }
+}
+
+/// Benchmark results for one model
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EvalMetrics {
+    /// Mean token cross-entropy on the held-out set
+    pub held_out_loss: Option<f32>,
+    /// Perplexity on the held-out set
+    pub perplexity: Option<f32>,
+    /// Fraction of multiple-choice questions answered correctly
+    pub accuracy: Option<f32>,
+    /// Number of multiple-choice questions asked
+    pub questions: usize,
+    /// Mean generation latency in milliseconds
+    pub latency_ms: Option<f64>,
+}
+
+/// Base vs adapted model comparison stored with an adapter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvalScorecard {
+    pub base: EvalMetrics,
+    pub adapted: EvalMetrics,
+    /// Evaluation timestamp
+    pub evaluated_at: u64,
+}
+
+impl EvalScorecard {
+    /// Change in held-out loss; negative when the adapter improved it
+    pub fn loss_delta(&self) -> Option<f32> {
+        Some(self.adapted.held_out_loss? - self.base.held_out_loss?)
+    }
+
+    /// Change in multiple-choice accuracy
+    pub fn accuracy_delta(&self) -> Option<f32> {
+        Some(self.adapted.accuracy? - self.base.accuracy?)
+    }
+
+    /// Latency added by applying the adapter
+    pub fn latency_overhead_ms(&self) -> Option<f64> {
+        Some(self.adapted.latency_ms? - self.base.latency_ms?)
+    }
+}
+
+/// Payload of [`LORA_JOB_COMPLETED_EVENT`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoraJobCompleted {
+    /// Training job identifier
+    pub job_id: String,
+    /// Adapter that was evaluated, if any was produced
+    pub adapter_id: Option<String>,
+    /// Evaluation results, if a suite was configured and ran
+    pub scorecard: Option<EvalScorecard>,
+    /// Why the evaluation failed
+    pub eval_error: Option<String>,
+}
+
+/// One multiple-choice benchmark item
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MultipleChoiceQuestion {
+    pub question: String,
+    pub choices: Vec<String>,
+    pub answer: usize,
+}
+
+impl MultipleChoiceQuestion {
+    /// Prompt asking for the letter of the correct choice
+    fn prompt(&self) -> String {
+        let mut prompt = format!("Question: {}\n", self.question.trim());
+        for (label, choice) in CHOICE_LABELS.iter().zip(&self.choices) {
+            prompt.push_str(&format!("{}. {}\n", label, choice.trim()));
+        }
+        prompt.push_str("Answer:");
+        prompt
+    }
+}
+
+/// Parse a JSONL question file, keeping at most `max` questions
+pub fn parse_questions(contents: &str, max: usize) -> Result<Vec<MultipleChoiceQuestion>> {
+    let mut questions = Vec::new();
+    for (i, line) in contents.lines().enumerate() {
+        if questions.len() >= max {
+            break;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let question: MultipleChoiceQuestion = serde_json::from_str(line)
+            .map_err(|e| anyhow!("Invalid question on line {}: {}", i + 1, e))?;
+        if question.choices.len() < 2 || question.choices.len() > CHOICE_LABELS.len() {
+            return Err(anyhow!(
+                "Question on line {} must have 2 to {} choices",
+                i + 1,
+                CHOICE_LABELS.len()
+            ));
+        }
+        if question.answer >= question.choices.len() {
+            return Err(anyhow!("Answer out of range on line {}", i + 1));
+        }
+        questions.push(question);
+    }
+    Ok(questions)
+}
+
+/// Index of the choice picked in a model's answer: the first option letter
+/// it produces, ignoring leading whitespace and punctuation
+fn parse_choice(output: &str, choices: usize) -> Option<usize> {
+    let first = output
+        .trim_start_matches(|c: char| c.is_whitespace() || c.is_ascii_punctuation())
+        .chars()
+        .next()?
+        .to_ascii_uppercase();
+    CHOICE_LABELS[..choices]
+        .iter()
+        .position(|&label| label == first)
+}
+
+/// Final perplexity reported by `llama-perplexity`
+/// (`Final estimate: PPL = 5.4321 +/- 0.123`)
+fn parse_perplexity(output: &str) -> Option<f32> {
+    output
+        .lines()
+        .rev()
+        .find_map(|line| line.split("Final estimate: PPL =").nth(1))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Runs the suite against one base model
+struct Evaluator<'a> {
+    config: &'a EvalSuiteConfig,
+    model_path: &'a str,
+    threads: u32,
+    cli: PathBuf,
+}
+
+impl Evaluator<'_> {
+    async fn evaluate(
+        &self,
+        adapter: Option<&str>,
+        questions: &[MultipleChoiceQuestion],
+    ) -> Result<EvalMetrics> {
+        let mut metrics = EvalMetrics::default();
+
+        if let Some(path) = &self.config.held_out_path {
+            let perplexity = self.perplexity(adapter, path).await?;
+            metrics.perplexity = Some(perplexity);
+            metrics.held_out_loss = Some(perplexity.ln());
+        }
+
+        if !questions.is_empty() {
+            let mut correct = 0;
+            for question in questions {
+                let output = self.generate(adapter, &question.prompt(), 2).await?;
+                if parse_choice(&output, question.choices.len()) == Some(question.answer) {
+                    correct += 1;
+                }
+            }
+            metrics.questions = questions.len();
+            metrics.accuracy = Some(correct as f32 / questions.len() as f32);
+        }
+
+        if self.config.latency_runs > 0 {
+            let mut total_ms = 0.0;
+            for _ in 0..self.config.latency_runs {
+                let start = Instant::now();
+                self.generate(
+                    adapter,
+                    &self.config.latency_prompt,
+                    self.config.latency_tokens,
+                )
+                .await?;
+                total_ms += start.elapsed().as_secs_f64() * 1000.0;
+            }
+            metrics.latency_ms = Some(total_ms / self.config.latency_runs as f64);
+        }
+
+        Ok(metrics)
+    }
+
+    /// Greedy completion of `prompt`
+    async fn generate(&self, adapter: Option<&str>, prompt: &str, tokens: u32) -> Result<String> {
+        let mut cmd = tokio::process::Command::new(&self.cli);
+        cmd.arg("-m")
+            .arg(self.model_path)
+            .arg("-p")
+            .arg(prompt)
+            .arg("-n")
+            .arg(tokens.to_string())
+            .arg("--temp")
+            .arg("0")
+            .arg("-t")
+            .arg(self.threads.to_string())
+            .arg("--no-display-prompt");
+        if let Some(adapter) = adapter {
+            cmd.arg("--lora").arg(adapter);
+        }
+
+        let output = cmd.output().await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Evaluation inference failed: {}", stderr));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Perplexity over the held-out file
+    async fn perplexity(&self, adapter: Option<&str>, path: &str) -> Result<f32> {
+        let bin = find_llama_tool(&["llama-perplexity", "perplexity"])?;
+        let mut cmd = tokio::process::Command::new(&bin);
+        cmd.arg("-m")
+            .arg(self.model_path)
+            .arg("-f")
+            .arg(path)
+            .arg("-c")
+            .arg(self.config.context_size.to_string())
+            .arg("-t")
+            .arg(self.threads.to_string());
+        if let Some(adapter) = adapter {
+            cmd.arg("--lora").arg(adapter);
+        }
+
+        let output = cmd.output().await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Perplexity evaluation failed: {}", stderr));
+        }
+        // llama-perplexity logs its results to stderr
+        let log = format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        parse_perplexity(&log)
+            .ok_or_else(|| anyhow!("No perplexity estimate in llama-perplexity output"))
+    }
+}
+
+/// Evaluate `adapter_path` applied to `model_path` against the base model
+pub async fn run_suite(
+    config: &EvalSuiteConfig,
+    model_path: &str,
+    adapter_path: &str,
+    threads: u32,
+) -> Result<EvalScorecard> {
+    if !Path::new(adapter_path).exists() {
+        return Err(anyhow!("Adapter not found: {}", adapter_path));
+    }
+
+    let questions = match &config.multiple_choice_path {
+        Some(path) => parse_questions(
+            &tokio::fs::read_to_string(path).await?,
+            config.max_questions,
+        )?,
+        None => Vec::new(),
+    };
+
+    let evaluator = Evaluator {
+        config,
+        model_path,
+        threads,
+        cli: find_llama_tool(&["llama-cli", "main"])?,
+    };
+    let base = evaluator.evaluate(None, &questions).await?;
+    let adapted = evaluator.evaluate(Some(adapter_path), &questions).await?;
+
+    Ok(EvalScorecard {
+        base,
+        adapted,
+        evaluated_at: chrono::Utc::now().timestamp() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_questions() {
+        let contents = r#"{"question": "2 + 2?", "choices": ["3", "4"], "answer": 1}
+
+{"question": "Capital of France?", "choices": ["Paris", "Rome", "Oslo"], "answer": 0}
+{"question": "Skipped", "choices": ["a", "b"], "answer": 0}"#;
+
+        let questions = parse_questions(contents, 2).unwrap();
+        assert_eq!(questions.len(), 2);
+        assert_eq!(questions[1].answer, 0);
+        assert_eq!(
+            questions[1].prompt(),
+            "Question: Capital of France?\nA. Paris\nB. Rome\nC. Oslo\nAnswer:"
+        );
+
+        assert!(parse_questions(
+            r#"{"question": "q", "choices": ["a", "b"], "answer": 2}"#,
+            10
+        )
+        .is_err());
+        assert!(
+            parse_questions(r#"{"question": "q", "choices": ["a"], "answer": 0}"#, 10).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_model_output() {
+        assert_eq!(parse_choice(" B. 4", 2), Some(1));
+        assert_eq!(parse_choice("(c)", 3), Some(2));
+        assert_eq!(parse_choice("D", 3), None);
+        assert_eq!(parse_choice("", 3), None);
+
+        let log = "[1]5.9,[2]5.6,\nFinal estimate: PPL = 5.4321 +/- 0.12345\n";
+        assert_eq!(parse_perplexity(log), Some(5.4321));
+        assert_eq!(parse_perplexity("no estimate"), None);
+    }
+
+    #[test]
+    fn test_scorecard_deltas() {
+        let scorecard = EvalScorecard {
+            base: EvalMetrics {
+                held_out_loss: Some(2.0),
+                accuracy: Some(0.5),
+                latency_ms: Some(100.0),
+                ..Default::default()
+            },
+            adapted: EvalMetrics {
+                held_out_loss: Some(1.5),
+                accuracy: Some(0.75),
+                latency_ms: Some(110.0),
+                ..Default::default()
+            },
+            evaluated_at: 0,
+        };
+        assert_eq!(scorecard.loss_delta(), Some(-0.5));
+        assert_eq!(scorecard.accuracy_delta(), Some(0.25));
+        assert_eq!(scorecard.latency_overhead_ms(), Some(10.0));
+
+        let unmeasured = EvalScorecard {
+            base: EvalMetrics::default(),
+            ..scorecard
+        };
+        assert_eq!(unmeasured.loss_delta(), None);
+    }
+}
@@ -1,12 +1,26 @@
 use anyhow::{anyhow, Result};
+use citrate_mcp::cache::{self, CacheKey, InferenceCache, InferenceCacheConfig};
+use citrate_mcp::gguf_engine::{SamplingParams, StopScanner};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::Read;
 use std::path::PathBuf;
-use std::process::Command;
+use std::process::{Command, Stdio};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tracing::{info, warn};
 
+use crate::agent::streaming::{StreamManager, TokenSink};
+
+pub mod bench;
+pub mod eval;
+pub mod preprocess;
+pub mod quantize;
+
+pub use bench::{BenchmarkConfig, BenchmarkResult, BenchmarkStore};
+pub use eval::{EvalMetrics, EvalScorecard, EvalSuiteConfig, LoraJobCompleted, LORA_JOB_COMPLETED_EVENT};
+pub use preprocess::{DatasetReport, PreprocessConfig};
+
 /// Manages AI models in the Citrate network
 pub struct ModelManager {
     models: Arc<RwLock<HashMap<String, ModelInfo>>>,
@@ -15,10 +29,19 @@ pub struct ModelManager {
     lora_jobs: Arc<RwLock<HashMap<String, LoraTrainingJob>>>,
     lora_adapters: Arc<RwLock<Vec<LoraAdapterInfo>>>,
     active_lora_processes: Arc<RwLock<HashMap<String, tokio::process::Child>>>,
+    /// LoRA job completion notifications
+    lora_events: broadcast::Sender<LoraJobCompleted>,
+    /// Results of deterministic inference requests
+    inference_cache: InferenceCache<String>,
+    /// Inference requests streaming their output to the frontend
+    inference_streams: StreamManager,
+    /// Saved benchmark results
+    benchmarks: BenchmarkStore,
 }
 
 impl ModelManager {
     pub fn new() -> Self {
+        let (lora_events, _) = broadcast::channel(64);
         Self {
             models: Arc::new(RwLock::new(Self::load_sample_models())),
             deployments: Arc::new(RwLock::new(Vec::new())),
@@ -26,9 +49,28 @@ impl ModelManager {
             lora_jobs: Arc::new(RwLock::new(HashMap::new())),
             lora_adapters: Arc::new(RwLock::new(Vec::new())),
             active_lora_processes: Arc::new(RwLock::new(HashMap::new())),
+            lora_events,
+            inference_cache: InferenceCache::new(InferenceCacheConfig::from_env(
+                "CITRATE_INFERENCE_CACHE_MB",
+            )),
+            inference_streams: StreamManager::new(),
+            benchmarks: BenchmarkStore::new().unwrap_or_else(|e| {
+                warn!("Failed to load model benchmarks: {}. Results will not persist.", e);
+                BenchmarkStore::in_memory()
+            }),
         }
     }
 
+    /// Streams of running inference requests, for cancellation
+    pub fn inference_streams(&self) -> &StreamManager {
+        &self.inference_streams
+    }
+
+    /// Subscribe to LoRA job completions
+    pub fn subscribe_lora_events(&self) -> broadcast::Receiver<LoraJobCompleted> {
+        self.lora_events.subscribe()
+    }
+
     /// Get all registered models
     pub async fn get_models(&self) -> Result<Vec<ModelInfo>> {
         Ok(self.models.read().await.values().cloned().collect())
@@ -83,6 +125,24 @@ impl ModelManager {
 
     /// Request inference from a model
     pub async fn request_inference(&self, request: InferenceRequest) -> Result<InferenceResponse> {
+        self.infer(request, None).await
+    }
+
+    /// Run inference, pushing the output into `tokens` as it is generated.
+    /// A cached result is sent in one piece.
+    pub async fn request_inference_streaming(
+        &self,
+        request: InferenceRequest,
+        tokens: &TokenSink,
+    ) -> Result<InferenceResponse> {
+        self.infer(request, Some(tokens)).await
+    }
+
+    async fn infer(
+        &self,
+        request: InferenceRequest,
+        tokens: Option<&TokenSink>,
+    ) -> Result<InferenceResponse> {
         let start = std::time::Instant::now();
 
         // Resolve model path
@@ -93,13 +153,35 @@ impl ModelManager {
             .get("max_tokens")
             .and_then(|v| v.as_u64())
             .unwrap_or(512) as usize;
-        let temperature = request.parameters
-            .get("temperature")
-            .and_then(|v| v.as_f64())
-            .unwrap_or(0.7) as f32;
-
-        // Run inference using llama.cpp
-        let result = self.run_llama_inference(&model_path, &request.input, max_tokens, temperature).await?;
+        let seed = request.parameters
+            .get("seed")
+            .and_then(|v| v.as_u64());
+        let params = serde_json::to_value(&request.parameters)?;
+        let sampling = SamplingParams::from_json(&params)?;
+
+        // Temperature 0 and seeded requests reuse an identical earlier result
+        let cache_key = (cache::is_deterministic(&params) && !cache::is_bypassed(&params))
+            .then(|| Self::inference_cache_key(&model_path, &params, &request.input));
+        let cached = cache_key.and_then(|key| self.inference_cache.get(&key));
+
+        let result = match cached.clone() {
+            Some(result) => {
+                if let Some(tokens) = tokens {
+                    tokens.send(&result);
+                }
+                result
+            }
+            None => {
+                // Run inference using llama.cpp
+                let result = self
+                    .run_llama_inference(&model_path, &request.input, max_tokens, &sampling, seed, tokens)
+                    .await?;
+                if let Some(key) = cache_key {
+                    self.inference_cache.insert(key, result.clone(), result.len() as u64);
+                }
+                result
+            }
+        };
 
         let latency_ms = start.elapsed().as_millis() as u64;
 
@@ -110,11 +192,45 @@ impl ModelManager {
             confidence: 0.95,
             latency_ms,
             cost: 0.0, // Free for local inference
+            cached: cached.is_some(),
         })
     }
 
+    /// Drop cached inference results of a model, e.g. once the license to
+    /// use it has lapsed. Returns how many results were dropped.
+    pub fn evict_cached_results(&self, model_id: &str) -> usize {
+        match self.resolve_model_path(model_id) {
+            Ok(model_path) => self
+                .inference_cache
+                .remove_model(Self::inference_cache_model(&model_path).as_bytes()),
+            Err(_) => 0,
+        }
+    }
+
+    /// Cache key for a request
+    fn inference_cache_key(
+        model_path: &std::path::Path,
+        params: &serde_json::Value,
+        prompt: &str,
+    ) -> CacheKey {
+        let model = Self::inference_cache_model(model_path);
+        CacheKey::new(model.as_bytes(), params, prompt.as_bytes())
+    }
+
+    /// The model is identified by its path and modification time so a
+    /// replaced file is not served stale results
+    fn inference_cache_model(model_path: &std::path::Path) -> String {
+        let modified = std::fs::metadata(model_path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        format!("{}@{}", model_path.display(), modified)
+    }
+
     /// Resolve model path from model ID
-    fn resolve_model_path(&self, model_id: &str) -> Result<PathBuf> {
+    pub fn resolve_model_path(&self, model_id: &str) -> Result<PathBuf> {
         // Handle full paths
         let path = PathBuf::from(model_id);
         if path.exists() && path.extension().map_or(false, |e| e == "gguf") {
@@ -155,13 +271,16 @@ impl ModelManager {
         ))
     }
 
-    /// Run inference using llama.cpp CLI
+    /// Run inference using llama.cpp CLI. The process is stopped as soon as
+    /// it generates one of the stop sequences.
     async fn run_llama_inference(
         &self,
         model_path: &PathBuf,
         prompt: &str,
         max_tokens: usize,
-        temperature: f32
+        sampling: &SamplingParams,
+        seed: Option<u64>,
+        tokens: Option<&TokenSink>,
     ) -> Result<String> {
         // Find llama.cpp binary
         let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
@@ -179,43 +298,102 @@ impl ModelManager {
         };
 
         info!(
-            "Running inference with model: {:?}, max_tokens: {}, temp: {}",
-            model_path, max_tokens, temperature
+            "Running inference with model: {:?}, max_tokens: {}, sampling: {:?}",
+            model_path, max_tokens, sampling
         );
 
         // Build command
-        let output = tokio::task::spawn_blocking({
+        let (status, stdout, stderr, cancelled, stopped) = tokio::task::spawn_blocking({
             let binary = binary.clone();
             let model_path = model_path.clone();
             let prompt = prompt.to_string();
             let threads = num_cpus::get();
+            let tokens = tokens.cloned();
+            let sampling = sampling.clone();
 
             move || {
-                Command::new(&binary)
+                let mut command = Command::new(&binary);
+                command
                     .arg("-m")
                     .arg(&model_path)
                     .arg("-p")
                     .arg(&prompt)
                     .arg("-n")
                     .arg(max_tokens.to_string())
-                    .arg("--temp")
-                    .arg(temperature.to_string())
+                    .args(sampling.llama_args())
                     .arg("-t")
                     .arg(threads.to_string())
                     .arg("-c")
                     .arg("2048")
-                    .arg("--no-display-prompt")
-                    .output()
+                    .arg("--no-display-prompt");
+                if let Some(seed) = seed {
+                    command.arg("--seed").arg(seed.to_string());
+                }
+                let mut child = command.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+
+                // Drain stderr on its own thread so a chatty process can't block
+                let mut stderr = child.stderr.take().expect("stderr is piped");
+                let stderr_reader = std::thread::spawn(move || {
+                    let mut text = String::new();
+                    let _ = stderr.read_to_string(&mut text);
+                    text
+                });
+
+                // Forward stdout as it arrives, holding back text that may
+                // turn out to be the start of a stop sequence
+                let mut stdout = child.stdout.take().expect("stdout is piped");
+                let mut scanner = StopScanner::new(&sampling.stop);
+                let mut pending = Vec::new();
+                let mut cancelled = false;
+                let mut buf = [0u8; 4096];
+                loop {
+                    let n = stdout.read(&mut buf)?;
+                    if n == 0 {
+                        break;
+                    }
+                    pending.extend_from_slice(&buf[..n]);
+                    let (text, len) = decodable_prefix(&pending);
+                    pending.drain(..len);
+                    let shown = scanner.push(&text);
+                    if tokens.as_ref().is_some_and(|tokens| !tokens.send(shown)) {
+                        cancelled = true;
+                        let _ = child.kill();
+                        break;
+                    }
+                    if scanner.is_stopped() {
+                        let _ = child.kill();
+                        break;
+                    }
+                }
+                if !cancelled {
+                    scanner.push(&String::from_utf8_lossy(&pending));
+                    let tail = scanner.finish();
+                    if let Some(tokens) = &tokens {
+                        tokens.send(tail);
+                    }
+                }
+
+                let status = child.wait()?;
+                let stderr = stderr_reader.join().unwrap_or_default();
+                Ok::<_, std::io::Error>((
+                    status,
+                    scanner.text().to_string(),
+                    stderr,
+                    cancelled,
+                    scanner.is_stopped(),
+                ))
             }
         }).await??;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        if cancelled {
+            return Err(anyhow!("Inference cancelled"));
+        }
+        // A process killed at a stop sequence exits unsuccessfully
+        if !status.success() && !stopped {
             return Err(anyhow!("llama.cpp execution failed: {}", stderr));
         }
 
-        let text = String::from_utf8_lossy(&output.stdout);
-        Ok(text.trim().to_string())
+        Ok(stdout.trim().to_string())
     }
 
     fn load_sample_models() -> HashMap<String, ModelInfo> {
@@ -411,38 +589,34 @@ impl ModelManager {
         let lora_jobs = self.lora_jobs.clone();
         let active_processes = self.active_lora_processes.clone();
         let lora_adapters = self.lora_adapters.clone();
-        let output_dir = job.output_dir.clone();
-        let base_model = job.base_model_name.clone();
-        let lora_config = job.lora_config.clone();
+        let lora_events = self.lora_events.clone();
 
         tokio::spawn(async move {
             Self::monitor_training_progress(
-                job_id,
+                job,
                 lora_jobs,
                 active_processes,
                 lora_adapters,
-                output_dir,
-                base_model,
-                lora_config,
+                lora_events,
             ).await;
         });
 
-        info!("Started LoRA training process for job: {}", job.id);
+        info!("Started LoRA training process for job: {}", job_id);
         Ok(())
     }
 
     /// Monitor training progress and update job status
     async fn monitor_training_progress(
-        job_id: String,
+        job: LoraTrainingJob,
         lora_jobs: Arc<RwLock<HashMap<String, LoraTrainingJob>>>,
         active_processes: Arc<RwLock<HashMap<String, tokio::process::Child>>>,
         lora_adapters: Arc<RwLock<Vec<LoraAdapterInfo>>>,
-        output_dir: String,
-        base_model: String,
-        lora_config: LoraConfig,
+        lora_events: broadcast::Sender<LoraJobCompleted>,
     ) {
         use tokio::io::{AsyncBufReadExt, BufReader};
 
+        let job_id = job.id.clone();
+
         // Get the process
         let mut processes = active_processes.write().await;
         let child = match processes.remove(&job_id) {
@@ -503,39 +677,87 @@ impl ModelManager {
 
         // Training completed - update job status
         let mut jobs = lora_jobs.write().await;
+        let mut latest_adapter: Option<(std::time::SystemTime, LoraAdapterInfo)> = None;
         if let Some(job) = jobs.get_mut(&job_id) {
             job.status = JobStatus::Completed;
             job.completed_at = Some(chrono::Utc::now().timestamp() as u64);
             job.progress = 1.0;
 
             // Register the output adapter
-            let adapter_path = PathBuf::from(&output_dir);
+            let adapter_path = PathBuf::from(&job.output_dir);
             if let Ok(entries) = std::fs::read_dir(&adapter_path) {
                 for entry in entries.flatten() {
                     let path = entry.path();
                     if path.extension().map_or(false, |e| e == "bin") {
-                        let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                        let metadata = std::fs::metadata(&path).ok();
+                        let size = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
+                        let modified = metadata
+                            .and_then(|m| m.modified().ok())
+                            .unwrap_or(std::time::UNIX_EPOCH);
                         let adapter = LoraAdapterInfo {
                             id: format!("adapter_{}", chrono::Utc::now().timestamp()),
                             name: path.file_stem().unwrap_or_default().to_string_lossy().to_string(),
-                            base_model: base_model.clone(),
+                            base_model: job.base_model_name.clone(),
                             path: path.to_string_lossy().to_string(),
                             size_bytes: size,
-                            rank: lora_config.rank,
-                            alpha: lora_config.alpha,
-                            target_modules: lora_config.target_modules.clone(),
+                            rank: job.lora_config.rank,
+                            alpha: job.lora_config.alpha,
+                            target_modules: job.lora_config.target_modules.clone(),
                             created_at: chrono::Utc::now().timestamp() as u64,
                             training_job_id: Some(job_id.clone()),
                             description: None,
                             tags: Vec::new(),
+                            eval: None,
                         };
+                        if latest_adapter.as_ref().map_or(true, |(t, _)| modified >= *t) {
+                            latest_adapter = Some((modified, adapter.clone()));
+                        }
                         lora_adapters.write().await.push(adapter);
                     }
                 }
             }
         }
+        drop(jobs);
 
         info!("LoRA training completed for job: {}", job_id);
+
+        // Score the final checkpoint against the base model
+        let adapter = latest_adapter.map(|(_, adapter)| adapter);
+        let mut completed = LoraJobCompleted {
+            job_id: job_id.clone(),
+            adapter_id: adapter.as_ref().map(|a| a.id.clone()),
+            scorecard: None,
+            eval_error: None,
+        };
+        if let (Some(suite), Some(adapter)) = (&job.training_config.eval_suite, &adapter) {
+            match eval::run_suite(
+                suite,
+                &job.base_model_path,
+                &adapter.path,
+                job.training_config.num_threads,
+            ).await {
+                Ok(scorecard) => {
+                    info!(
+                        "LoRA adapter {} evaluated: loss delta {:?}, accuracy delta {:?}, latency overhead {:?}ms",
+                        adapter.id,
+                        scorecard.loss_delta(),
+                        scorecard.accuracy_delta(),
+                        scorecard.latency_overhead_ms()
+                    );
+                    let mut adapters = lora_adapters.write().await;
+                    if let Some(stored) = adapters.iter_mut().find(|a| a.path == adapter.path) {
+                        stored.eval = Some(scorecard.clone());
+                    }
+                    completed.scorecard = Some(scorecard);
+                }
+                Err(e) => {
+                    warn!("Evaluation of LoRA adapter {} failed: {}", adapter.id, e);
+                    completed.eval_error = Some(e.to_string());
+                }
+            }
+        }
+
+        let _ = lora_events.send(completed);
     }
 
     /// Count lines in a dataset file
@@ -631,6 +853,7 @@ impl ModelManager {
                                     training_job_id: None,
                                     description: None,
                                     tags: Vec::new(),
+                                    eval: None,
                                 });
                             }
                         }
@@ -699,6 +922,183 @@ impl ModelManager {
         Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
     }
 
+    /// Look up a saved LoRA adapter by ID
+    pub async fn find_lora_adapter(&self, adapter_id: &str) -> Result<LoraAdapterInfo> {
+        self.get_lora_adapters().await?
+            .into_iter()
+            .find(|a| a.id == adapter_id)
+            .ok_or_else(|| anyhow!("LoRA adapter not found: {}", adapter_id))
+    }
+
+    /// Benchmark a local model, with a saved LoRA adapter applied if given,
+    /// and keep the result for comparison. Memory is labelled with the names
+    /// of the detected `devices`.
+    pub async fn benchmark_model(
+        &self,
+        model_id: &str,
+        adapter_id: Option<&str>,
+        config: BenchmarkConfig,
+        devices: &[crate::gpu::GPUDevice],
+    ) -> Result<BenchmarkResult> {
+        let model_path = self.resolve_model_path(model_id)?;
+        let adapter_path = match adapter_id {
+            Some(id) => Some(self.find_lora_adapter(id).await?.path),
+            None => None,
+        };
+
+        info!("Benchmarking model {} ({:?})", model_id, model_path);
+        let mut result =
+            bench::run_benchmark(&config, &model_path, adapter_path.as_deref()).await?;
+        result.model_id = model_id.to_string();
+        result.adapter_id = adapter_id.map(str::to_string);
+        for memory in &mut result.peak_memory {
+            memory.device_name = devices
+                .iter()
+                .find(|device| device.id == memory.device_id)
+                .map(|device| device.name.clone());
+        }
+        info!(
+            "Benchmark of {}: {:.1} tokens/s, {:.0}ms to first token",
+            model_id, result.tokens_per_sec, result.time_to_first_token_ms
+        );
+
+        self.benchmarks.record(result.clone())?;
+        Ok(result)
+    }
+
+    /// Saved benchmark results, newest first, optionally for one model
+    pub fn get_benchmarks(&self, model_id: Option<&str>) -> Vec<BenchmarkResult> {
+        self.benchmarks.list(model_id)
+    }
+
+    /// Delete a saved benchmark result
+    pub fn delete_benchmark(&self, id: &str) -> Result<()> {
+        if !self.benchmarks.delete(id)? {
+            return Err(anyhow!("Benchmark not found: {}", id));
+        }
+        Ok(())
+    }
+
+    /// Merge a LoRA adapter into its base model and export a standalone GGUF
+    /// at the chosen quantization, registered as a new local model
+    pub async fn merge_lora_adapter(
+        &self,
+        adapter_id: &str,
+        base_model_path: Option<String>,
+        output_name: Option<String>,
+        quantization: GgufQuantization,
+    ) -> Result<ModelInfo> {
+        let adapter = self.find_lora_adapter(adapter_id).await?;
+
+        // Default to the base model the adapter was trained on
+        let base_model_path = match base_model_path {
+            Some(path) => path,
+            None => {
+                let job_id = adapter.training_job_id.as_ref().ok_or_else(|| {
+                    anyhow!("Adapter {} has no training job; specify the base model", adapter_id)
+                })?;
+                self.lora_jobs.read().await
+                    .get(job_id)
+                    .map(|job| job.base_model_path.clone())
+                    .ok_or_else(|| anyhow!("Training job not found: {}", job_id))?
+            }
+        };
+        if !PathBuf::from(&base_model_path).exists() {
+            return Err(anyhow!("Base model not found: {}", base_model_path));
+        }
+
+        let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        let models_dir = home_dir.join(".citrate/models");
+        std::fs::create_dir_all(&models_dir)?;
+
+        let name = output_name.unwrap_or_else(|| format!("{}-merged", adapter.name));
+        let file_name = merged_model_filename(&name, quantization);
+        let output_path = models_dir.join(&file_name);
+        if output_path.exists() {
+            return Err(anyhow!("Model already exists: {}", output_path.display()));
+        }
+
+        // export-lora writes the merged weights at the base model's precision;
+        // quantize afterwards unless F16 was asked for
+        let merged_path = if quantization == GgufQuantization::F16 {
+            output_path.clone()
+        } else {
+            models_dir.join(format!("{}.merge-tmp.gguf", file_name.trim_end_matches(".gguf")))
+        };
+
+        let export_bin = find_llama_tool(&["llama-export-lora", "export-lora"])?;
+        info!("Merging adapter {} into {}", adapter.path, base_model_path);
+        let output = tokio::process::Command::new(&export_bin)
+            .arg("-m").arg(&base_model_path)
+            .arg("--lora").arg(&adapter.path)
+            .arg("-o").arg(&merged_path)
+            .arg("-t").arg(num_cpus::get().to_string())
+            .output()
+            .await?;
+        if !output.status.success() {
+            let _ = std::fs::remove_file(&merged_path);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("LoRA merge failed: {}", stderr));
+        }
+
+        if merged_path != output_path {
+            let quantize_bin = find_llama_tool(&["llama-quantize", "quantize"])?;
+            info!("Quantizing merged model to {}", quantization.as_str());
+            let output = tokio::process::Command::new(&quantize_bin)
+                .arg("--allow-requantize")
+                .arg(&merged_path)
+                .arg(&output_path)
+                .arg(quantization.as_str())
+                .arg(num_cpus::get().to_string())
+                .output()
+                .await;
+            let _ = std::fs::remove_file(&merged_path);
+            let output = output?;
+            if !output.status.success() {
+                let _ = std::fs::remove_file(&output_path);
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(anyhow!("Quantization failed: {}", stderr));
+            }
+        }
+
+        let size_bytes = std::fs::metadata(&output_path)?.len();
+        let hash = {
+            use sha2::{Digest, Sha256};
+            let mut hasher = Sha256::new();
+            let mut file = std::fs::File::open(&output_path)?;
+            std::io::copy(&mut file, &mut hasher)?;
+            hex::encode(hasher.finalize())
+        };
+
+        let mut metadata = HashMap::new();
+        metadata.insert("path".to_string(), output_path.to_string_lossy().to_string());
+        metadata.insert("base_model".to_string(), base_model_path);
+        metadata.insert("lora_adapter".to_string(), adapter.path.clone());
+        metadata.insert("quantization".to_string(), quantization.as_str().to_string());
+
+        let now = chrono::Utc::now().timestamp() as u64;
+        let model = ModelInfo {
+            // The file name doubles as the ID so inference can resolve it
+            id: file_name.clone(),
+            name,
+            description: format!("{} merged with LoRA adapter {}", adapter.base_model, adapter.name),
+            model_type: ModelType::Language,
+            version: "1.0.0".to_string(),
+            size_mb: size_bytes / (1024 * 1024),
+            parameters: 0,
+            architecture: "GGUF".to_string(),
+            owner: "local".to_string(),
+            created_at: now,
+            updated_at: now,
+            hash,
+            metadata,
+        };
+
+        self.models.write().await.insert(model.id.clone(), model.clone());
+        info!("Registered merged model {} at {}", model.id, output_path.display());
+        Ok(model)
+    }
+
     /// Validate dataset format
     pub async fn validate_dataset(&self, path: &str, format: &DatasetFormat) -> Result<DatasetValidation> {
         let file_path = PathBuf::from(path);
@@ -794,6 +1194,30 @@ impl ModelManager {
         })
     }
 
+    /// Deduplicate, length-filter and split a dataset into train/eval files,
+    /// returning a report to review before the job starts. Token counts use
+    /// the tokenizer of `tokenizer_model` (the job's base model) if given.
+    pub async fn preprocess_dataset(
+        &self,
+        path: &str,
+        format: &DatasetFormat,
+        config: &PreprocessConfig,
+        tokenizer_model: Option<&str>,
+    ) -> Result<DatasetReport> {
+        let report = preprocess::preprocess(path, format, config, tokenizer_model).await?;
+        info!(
+            "Preprocessed dataset {}: {} of {} examples kept ({} duplicates, {} invalid, {} too short, {} too long)",
+            path,
+            report.train_examples + report.eval_examples,
+            report.total_examples,
+            report.duplicates,
+            report.invalid,
+            report.too_short,
+            report.too_long
+        );
+        Ok(report)
+    }
+
     /// Get default LoRA presets for different model sizes
     pub fn get_lora_presets() -> Vec<LoraPreset> {
         vec![
@@ -1143,6 +1567,9 @@ pub struct LoraTrainingConfig {
     pub use_gpu: bool,
     /// GPU layers to offload
     pub n_gpu_layers: u32,
+    /// Benchmark suite run once training completes
+    #[serde(default)]
+    pub eval_suite: Option<EvalSuiteConfig>,
 }
 
 impl Default for LoraTrainingConfig {
@@ -1168,6 +1595,7 @@ impl Default for LoraTrainingConfig {
             num_threads: num_cpus::get() as u32,
             use_gpu: false,
             n_gpu_layers: 0,
+            eval_suite: None,
         }
     }
 }
@@ -1215,6 +1643,75 @@ pub struct LoraAdapterInfo {
     pub training_job_id: Option<String>,
     pub description: Option<String>,
     pub tags: Vec<String>,
+    /// Post-training benchmark results
+    #[serde(default)]
+    pub eval: Option<EvalScorecard>,
+}
+
+/// Quantization of a merged GGUF export, named as llama.cpp names them
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum GgufQuantization {
+    F16,
+    Q8_0,
+    Q6_K,
+    Q5_K_M,
+    #[default]
+    Q4_K_M,
+    Q4_0,
+}
+
+impl GgufQuantization {
+    /// Type name passed to `llama-quantize`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GgufQuantization::F16 => "F16",
+            GgufQuantization::Q8_0 => "Q8_0",
+            GgufQuantization::Q6_K => "Q6_K",
+            GgufQuantization::Q5_K_M => "Q5_K_M",
+            GgufQuantization::Q4_K_M => "Q4_K_M",
+            GgufQuantization::Q4_0 => "Q4_0",
+        }
+    }
+}
+
+/// File name for a GGUF export, e.g. `my-model-Q4_K_M.gguf`
+pub(crate) fn merged_model_filename(name: &str, quantization: GgufQuantization) -> String {
+    let stem: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '-' })
+        .collect();
+    format!("{}-{}.gguf", stem.trim_matches('-'), quantization.as_str())
+}
+
+/// Locate a llama.cpp tool binary in the usual install locations
+fn find_llama_tool(names: &[&str]) -> Result<PathBuf> {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    names
+        .iter()
+        .flat_map(|name| {
+            vec![
+                home_dir.join("llama.cpp/build/bin").join(name),
+                home_dir.join("llama.cpp").join(name),
+                PathBuf::from("/usr/local/bin").join(name),
+            ]
+        })
+        .find(|p| p.exists())
+        .ok_or_else(|| anyhow!("llama.cpp {} not found. Please install llama.cpp.", names[0]))
+}
+
+/// Longest decodable prefix of `bytes` and its length, leaving a character
+/// split across reads for the next one
+fn decodable_prefix(bytes: &[u8]) -> (String, usize) {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), bytes.len()),
+        Err(e) if e.error_len().is_none() => {
+            let len = e.valid_up_to();
+            (String::from_utf8_lossy(&bytes[..len]).into_owned(), len)
+        }
+        Err(_) => (String::from_utf8_lossy(bytes).into_owned(), bytes.len()),
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1241,6 +1738,9 @@ pub struct InferenceResponse {
     pub confidence: f32,
     pub latency_ms: u64,
     pub cost: f64,
+    /// Served from the inference cache
+    #[serde(default)]
+    pub cached: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1287,6 +1787,31 @@ mod tests {
         assert_eq!(config.gradient_accumulation_steps, 4);
     }
 
+    #[test]
+    fn test_merged_model_filename() {
+        assert_eq!(
+            merged_model_filename("My Model/v2", GgufQuantization::Q4_K_M),
+            "My-Model-v2-Q4_K_M.gguf"
+        );
+        assert_eq!(
+            merged_model_filename("llama-lora", GgufQuantization::F16),
+            "llama-lora-F16.gguf"
+        );
+        assert_eq!(GgufQuantization::default(), GgufQuantization::Q4_K_M);
+        assert_eq!(
+            serde_json::to_string(&GgufQuantization::Q5_K_M).unwrap(),
+            "\"Q5_K_M\""
+        );
+    }
+
+    #[test]
+    fn test_decodable_prefix_holds_back_split_characters() {
+        let bytes = "héllo".as_bytes();
+        assert_eq!(decodable_prefix(&bytes[..2]), ("h".to_string(), 1));
+        assert_eq!(decodable_prefix(&bytes[1..]), ("éllo".to_string(), 5));
+        assert_eq!(decodable_prefix(b"a\xffb"), ("a\u{fffd}b".to_string(), 3));
+    }
+
     #[test]
     fn test_lora_presets() {
         let presets = ModelManager::get_lora_presets();
@@ -1694,6 +2219,7 @@ mod tests {
             training_job_id: Some("job-456".to_string()),
             description: Some("Test adapter description".to_string()),
             tags: vec!["test".to_string(), "demo".to_string()],
+            eval: None,
         };
 
         let json = serde_json::to_string(&adapter).unwrap();
@@ -0,0 +1,579 @@
+//! LoRA Dataset Preprocessing
+//!
+//! Cleans a training dataset before a LoRA job is started and reports what
+//! it found, so bad data is caught before any GPU time is spent:
+//!
+//! - deduplication of examples that are identical up to whitespace
+//! - length filtering on estimated token counts
+//! - token counts per example, calibrated against the base model's tokenizer
+//!   (`llama-tokenize` on a sample of examples); a 4 characters per token
+//!   heuristic is used when no tokenizer is available
+//! - a seeded train/eval split, written next to the source dataset
+//!
+//! Output files keep the source lines untouched, so they can be passed to
+//! `create_lora_job` with the same [`DatasetFormat`].
+
+use super::{find_llama_tool, DatasetFormat};
+use anyhow::{anyhow, Result};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Characters per token assumed when no tokenizer is available
+const DEFAULT_CHARS_PER_TOKEN: f32 = 4.0;
+
+/// Characters of each example shown in the report preview
+const PREVIEW_CHARS: usize = 200;
+
+/// Dataset preprocessing options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreprocessConfig {
+    /// Drop examples identical to an earlier one up to whitespace
+    pub dedup: bool,
+    /// Drop examples with fewer tokens than this
+    pub min_tokens: usize,
+    /// Drop examples with more tokens than this (usually the training
+    /// `max_seq_length`)
+    pub max_tokens: Option<usize>,
+    /// Fraction of kept examples held out for evaluation
+    pub eval_fraction: f32,
+    /// Seed for the train/eval shuffle
+    pub seed: u64,
+    /// Examples tokenized exactly to calibrate the estimate for the rest
+    pub tokenizer_samples: usize,
+    /// Examples included in the report preview
+    pub preview_examples: usize,
+    /// Only report; don't write the train/eval files
+    pub dry_run: bool,
+}
+
+impl Default for PreprocessConfig {
+    fn default() -> Self {
+        Self {
+            dedup: true,
+            min_tokens: 8,
+            max_tokens: Some(2048),
+            eval_fraction: 0.1,
+            seed: 42,
+            tokenizer_samples: 200,
+            preview_examples: 5,
+            dry_run: false,
+        }
+    }
+}
+
+/// Token count distribution over the kept examples
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenStats {
+    pub total: usize,
+    pub min: usize,
+    pub max: usize,
+    pub mean: f32,
+    pub p50: usize,
+    pub p95: usize,
+}
+
+impl TokenStats {
+    fn from_counts(counts: &[usize]) -> Self {
+        if counts.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = counts.to_vec();
+        sorted.sort_unstable();
+        let total: usize = sorted.iter().sum();
+        let percentile = |p: usize| sorted[((sorted.len() - 1) * p) / 100];
+        Self {
+            total,
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            mean: total as f32 / sorted.len() as f32,
+            p50: percentile(50),
+            p95: percentile(95),
+        }
+    }
+}
+
+/// One kept example as shown in the report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExamplePreview {
+    pub line: usize,
+    pub text: String,
+    pub tokens: usize,
+}
+
+/// What preprocessing found and did, returned before the job starts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetReport {
+    pub total_examples: usize,
+    /// Examples that could not be parsed for the dataset format
+    pub invalid: usize,
+    pub duplicates: usize,
+    pub too_short: usize,
+    pub too_long: usize,
+    pub train_examples: usize,
+    pub eval_examples: usize,
+    /// Cleaned training file, unless this was a dry run
+    pub train_path: Option<String>,
+    /// Held-out evaluation file, unless this was a dry run or no examples
+    /// were held out
+    pub eval_path: Option<String>,
+    /// Token counts of the kept examples
+    pub tokens: TokenStats,
+    /// Model whose tokenizer calibrated the counts; `None` when the
+    /// characters-per-token heuristic was used
+    pub tokenizer_model: Option<String>,
+    pub chars_per_token: f32,
+    pub preview: Vec<ExamplePreview>,
+    pub warnings: Vec<String>,
+    /// First parse errors, with line numbers
+    pub errors: Vec<String>,
+}
+
+/// A parsed dataset example
+#[derive(Debug, Clone)]
+struct Example {
+    /// 1-based line in the source file
+    line: usize,
+    /// Source line, written out unchanged
+    raw: String,
+    /// Text the model is trained on
+    text: String,
+    tokens: usize,
+}
+
+/// Training text of one dataset line
+fn example_text(line: &str, format: &DatasetFormat) -> Result<String, String> {
+    let field = |obj: &serde_json::Value, name: &str| {
+        obj.get(name).and_then(|v| v.as_str()).map(str::to_string)
+    };
+    let parse = || {
+        serde_json::from_str::<serde_json::Value>(line).map_err(|e| format!("invalid JSON - {}", e))
+    };
+
+    match format {
+        DatasetFormat::Jsonl => {
+            let obj = parse()?;
+            if let Some(text) = field(&obj, "text") {
+                return Ok(text);
+            }
+            let parts: Vec<String> = [
+                "instruction",
+                "input",
+                "output",
+                "prompt",
+                "completion",
+                "response",
+            ]
+            .iter()
+            .filter_map(|name| field(&obj, name))
+            .collect();
+            if parts.is_empty() {
+                return Err("missing required field (text/instruction/prompt)".to_string());
+            }
+            Ok(parts.join("\n"))
+        }
+        DatasetFormat::Alpaca => {
+            let obj = parse()?;
+            match (field(&obj, "instruction"), field(&obj, "output")) {
+                (Some(instruction), Some(output)) => Ok(match field(&obj, "input") {
+                    Some(input) if !input.is_empty() => {
+                        format!("{}\n{}\n{}", instruction, input, output)
+                    }
+                    _ => format!("{}\n{}", instruction, output),
+                }),
+                _ => Err("missing instruction or output field".to_string()),
+            }
+        }
+        DatasetFormat::ShareGPT => {
+            let obj = parse()?;
+            let turns = obj
+                .get("conversations")
+                .and_then(|c| c.as_array())
+                .ok_or_else(|| "missing conversations array".to_string())?;
+            let text: Vec<&str> = turns
+                .iter()
+                .filter_map(|turn| turn.get("value").and_then(|v| v.as_str()))
+                .collect();
+            if text.is_empty() {
+                return Err("conversation has no messages".to_string());
+            }
+            Ok(text.join("\n"))
+        }
+        _ => Ok(line.to_string()),
+    }
+}
+
+/// Whitespace-insensitive key used for deduplication
+fn dedup_key(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Estimated tokens in `text` at `chars_per_token`
+fn estimate_tokens(text: &str, chars_per_token: f32) -> usize {
+    ((text.chars().count() as f32 / chars_per_token).ceil() as usize).max(1)
+}
+
+/// Token count printed by `llama-tokenize --show-count`
+fn parse_token_count(output: &str) -> Option<usize> {
+    output
+        .lines()
+        .find_map(|line| line.split("Total number of tokens:").nth(1))?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Exact token count of `text` with the tokenizer of `model_path`
+async fn count_tokens(tokenizer: &Path, model_path: &str, text: &str) -> Result<usize> {
+    let output = tokio::process::Command::new(tokenizer)
+        .arg("-m")
+        .arg(model_path)
+        .arg("-p")
+        .arg(text)
+        .arg("--show-count")
+        .arg("--log-disable")
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("Tokenization failed: {}", stderr));
+    }
+    parse_token_count(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| anyhow!("No token count in llama-tokenize output"))
+}
+
+/// Characters per token measured on up to `samples` evenly spaced examples
+async fn calibrate(examples: &[Example], model_path: &str, samples: usize) -> Result<f32> {
+    let tokenizer = find_llama_tool(&["llama-tokenize", "tokenize"])?;
+    let step = (examples.len() / samples.max(1)).max(1);
+
+    let (mut chars, mut tokens) = (0usize, 0usize);
+    for example in examples.iter().step_by(step).take(samples) {
+        chars += example.text.chars().count();
+        tokens += count_tokens(&tokenizer, model_path, &example.text).await?;
+    }
+    if tokens == 0 {
+        return Err(anyhow!("Tokenizer produced no tokens"));
+    }
+    Ok(chars as f32 / tokens as f32)
+}
+
+/// Parse, deduplicate and length-filter the dataset lines, returning the
+/// kept examples and a report without split or output paths filled in
+fn filter_examples(
+    content: &str,
+    format: &DatasetFormat,
+    config: &PreprocessConfig,
+    chars_per_token: f32,
+) -> (Vec<Example>, DatasetReport) {
+    let mut report = DatasetReport {
+        total_examples: 0,
+        invalid: 0,
+        duplicates: 0,
+        too_short: 0,
+        too_long: 0,
+        train_examples: 0,
+        eval_examples: 0,
+        train_path: None,
+        eval_path: None,
+        tokens: TokenStats::default(),
+        tokenizer_model: None,
+        chars_per_token,
+        preview: Vec::new(),
+        warnings: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    // CSV keeps its header line out of the examples
+    let skip = usize::from(matches!(format, DatasetFormat::Csv));
+    let mut seen = HashSet::new();
+    let mut kept = Vec::new();
+
+    for (i, line) in content.lines().enumerate().skip(skip) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        report.total_examples += 1;
+
+        let text = match example_text(line, format) {
+            Ok(text) => text,
+            Err(e) => {
+                report.invalid += 1;
+                if report.errors.len() < 10 {
+                    report.errors.push(format!("Line {}: {}", i + 1, e));
+                }
+                continue;
+            }
+        };
+        if config.dedup && !seen.insert(dedup_key(&text)) {
+            report.duplicates += 1;
+            continue;
+        }
+
+        let tokens = estimate_tokens(&text, chars_per_token);
+        if tokens < config.min_tokens {
+            report.too_short += 1;
+            continue;
+        }
+        if config.max_tokens.is_some_and(|max| tokens > max) {
+            report.too_long += 1;
+            continue;
+        }
+
+        kept.push(Example {
+            line: i + 1,
+            raw: line.to_string(),
+            text,
+            tokens,
+        });
+    }
+
+    let counts: Vec<usize> = kept.iter().map(|e| e.tokens).collect();
+    report.tokens = TokenStats::from_counts(&counts);
+    report.preview = kept
+        .iter()
+        .take(config.preview_examples)
+        .map(|e| ExamplePreview {
+            line: e.line,
+            text: e.text.chars().take(PREVIEW_CHARS).collect(),
+            tokens: e.tokens,
+        })
+        .collect();
+
+    (kept, report)
+}
+
+/// Shuffle with `seed` and hold out `eval_fraction` of the examples
+fn split(
+    mut examples: Vec<Example>,
+    eval_fraction: f32,
+    seed: u64,
+) -> (Vec<Example>, Vec<Example>) {
+    examples.shuffle(&mut StdRng::seed_from_u64(seed));
+    let eval_count = if examples.len() < 2 || eval_fraction <= 0.0 {
+        0
+    } else {
+        ((examples.len() as f32 * eval_fraction).round() as usize).clamp(1, examples.len() - 1)
+    };
+    let train = examples.split_off(eval_count);
+    (train, examples)
+}
+
+/// `<stem>.<suffix>.<ext>` next to the source dataset
+fn output_path(source: &Path, suffix: &str) -> PathBuf {
+    let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match source.extension() {
+        Some(ext) => format!("{}.{}.{}", stem, suffix, ext.to_string_lossy()),
+        None => format!("{}.{}", stem, suffix),
+    };
+    source.with_file_name(name)
+}
+
+async fn write_examples(path: &Path, header: Option<&str>, examples: &[Example]) -> Result<()> {
+    let mut content = String::new();
+    for line in header
+        .into_iter()
+        .chain(examples.iter().map(|e| e.raw.as_str()))
+    {
+        content.push_str(line);
+        content.push('\n');
+    }
+    tokio::fs::write(path, content).await?;
+    Ok(())
+}
+
+/// Preprocess the dataset at `path`, calibrating token counts with the
+/// tokenizer of `tokenizer_model` (normally the job's base model) when given
+pub async fn preprocess(
+    path: &str,
+    format: &DatasetFormat,
+    config: &PreprocessConfig,
+    tokenizer_model: Option<&str>,
+) -> Result<DatasetReport> {
+    let source = PathBuf::from(path);
+    if !source.exists() {
+        return Err(anyhow!("Dataset file not found: {}", path));
+    }
+    let content = tokio::fs::read_to_string(&source).await?;
+
+    // Calibrate on the parseable examples, then filter with the measured rate
+    let (mut chars_per_token, mut calibrated_with) = (DEFAULT_CHARS_PER_TOKEN, None);
+    let mut warnings = Vec::new();
+    if let Some(model) = tokenizer_model {
+        let unfiltered = PreprocessConfig {
+            min_tokens: 0,
+            max_tokens: None,
+            ..config.clone()
+        };
+        let (examples, _) = filter_examples(&content, format, &unfiltered, DEFAULT_CHARS_PER_TOKEN);
+        if !examples.is_empty() {
+            match calibrate(&examples, model, config.tokenizer_samples).await {
+                Ok(rate) => {
+                    chars_per_token = rate;
+                    calibrated_with = Some(model.to_string());
+                }
+                Err(e) => warnings.push(format!("Token counts are estimated: {}", e)),
+            }
+        }
+    }
+
+    let (kept, mut report) = filter_examples(&content, format, config, chars_per_token);
+    report.tokenizer_model = calibrated_with;
+    report.warnings = warnings;
+
+    let (train, eval) = split(kept, config.eval_fraction, config.seed);
+    report.train_examples = train.len();
+    report.eval_examples = eval.len();
+
+    if report.total_examples > 0 && report.duplicates * 10 > report.total_examples {
+        report.warnings.push(format!(
+            "{} of {} examples are duplicates",
+            report.duplicates, report.total_examples
+        ));
+    }
+    if report.total_examples > 0 && report.invalid * 10 > report.total_examples {
+        report.warnings.push(format!(
+            "{} of {} examples could not be parsed as {:?}",
+            report.invalid, report.total_examples, format
+        ));
+    }
+    if train.is_empty() {
+        report
+            .warnings
+            .push("No training examples left after preprocessing".to_string());
+    }
+
+    if !config.dry_run && !train.is_empty() {
+        let header = match format {
+            DatasetFormat::Csv => content.lines().next(),
+            _ => None,
+        };
+        let train_path = output_path(&source, "train");
+        write_examples(&train_path, header, &train).await?;
+        report.train_path = Some(train_path.to_string_lossy().to_string());
+
+        if !eval.is_empty() {
+            let eval_path = output_path(&source, "eval");
+            write_examples(&eval_path, header, &eval).await?;
+            report.eval_path = Some(eval_path.to_string_lossy().to_string());
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_and_dedup() {
+        let content = [
+            r#"{"text": "The quick brown fox jumps over the lazy dog again and again"}"#,
+            r#"{"text": "The quick  brown fox jumps over the lazy dog   again and again"}"#,
+            r#"{"text": "short"}"#,
+            r#"{"instruction": "Summarize the following paragraph", "output": "It says nothing much at all"}"#,
+            r#"{"unrelated": 1}"#,
+            "not json",
+            "",
+        ]
+        .join("\n");
+        let config = PreprocessConfig {
+            min_tokens: 4,
+            max_tokens: Some(100),
+            ..Default::default()
+        };
+
+        let (kept, report) = filter_examples(&content, &DatasetFormat::Jsonl, &config, 4.0);
+        assert_eq!(report.total_examples, 6);
+        assert_eq!(report.invalid, 2);
+        assert_eq!(report.duplicates, 1);
+        assert_eq!(report.too_short, 1);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[1].line, 4);
+        assert_eq!(
+            kept[1].text,
+            "Summarize the following paragraph\nIt says nothing much at all"
+        );
+        assert_eq!(report.errors.len(), 2);
+        assert_eq!(
+            report.tokens.total,
+            kept.iter().map(|e| e.tokens).sum::<usize>()
+        );
+
+        let strict = PreprocessConfig {
+            max_tokens: Some(10),
+            ..config
+        };
+        let (kept, report) = filter_examples(&content, &DatasetFormat::Jsonl, &strict, 4.0);
+        assert_eq!(report.too_long, 2);
+        assert!(kept.is_empty());
+    }
+
+    #[test]
+    fn test_split_is_seeded() {
+        let examples: Vec<Example> = (0..20)
+            .map(|i| Example {
+                line: i + 1,
+                raw: i.to_string(),
+                text: i.to_string(),
+                tokens: 1,
+            })
+            .collect();
+
+        let (train, eval) = split(examples.clone(), 0.1, 7);
+        assert_eq!((train.len(), eval.len()), (18, 2));
+        let (_, again) = split(examples.clone(), 0.1, 7);
+        assert_eq!(
+            eval.iter().map(|e| e.line).collect::<Vec<_>>(),
+            again.iter().map(|e| e.line).collect::<Vec<_>>()
+        );
+
+        let (train, eval) = split(examples[..1].to_vec(), 0.5, 7);
+        assert_eq!((train.len(), eval.len()), (1, 0));
+    }
+
+    #[test]
+    fn test_token_helpers() {
+        assert_eq!(parse_token_count("Total number of tokens: 42\n"), Some(42));
+        assert_eq!(parse_token_count("[1, 2, 3]"), None);
+        assert_eq!(estimate_tokens("abcdefgh", 4.0), 2);
+        assert_eq!(estimate_tokens("", 4.0), 1);
+
+        let stats = TokenStats::from_counts(&[10, 1, 5, 7]);
+        assert_eq!(
+            (stats.total, stats.min, stats.max, stats.p50),
+            (23, 1, 10, 5)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_preprocess_writes_split() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv");
+        let rows: Vec<String> = (0..10)
+            .map(|i| format!("example number {} with enough words to keep", i))
+            .collect();
+        std::fs::write(&path, format!("text\n{}\n", rows.join("\n"))).unwrap();
+
+        let config = PreprocessConfig {
+            eval_fraction: 0.2,
+            ..Default::default()
+        };
+        let report = preprocess(path.to_str().unwrap(), &DatasetFormat::Csv, &config, None)
+            .await
+            .unwrap();
+        assert_eq!((report.train_examples, report.eval_examples), (8, 2));
+        assert_eq!(report.tokenizer_model, None);
+
+        let train = std::fs::read_to_string(report.train_path.unwrap()).unwrap();
+        assert!(train.starts_with("text\n"));
+        assert_eq!(train.lines().count(), 9);
+        assert!(dir.path().join("data.eval.csv").exists());
+    }
+}
@@ -0,0 +1,608 @@
+//! GGUF Tensor Quantization
+//!
+//! Native implementations of the ggml block formats a [`GgufQuantization`]
+//! is made of, so models can be quantized without llama.cpp's tools:
+//!
+//! - `Q8_0` and `Q4_0`: 32 weights per block sharing one f16 scale
+//! - `Q4_K` and `Q5_K`: 256 weights per super-block, split into 8 sub-blocks
+//!   with their own 6-bit scale and minimum
+//! - `Q6_K`: 256 weights per super-block, split into 16 sub-blocks with
+//!   8-bit scales
+//!
+//! Blocks are laid out byte for byte as ggml reads them. Scales are fitted
+//! per block from its range rather than by llama.cpp's error-minimising
+//! search, so outputs load everywhere llama.cpp's do at a slightly higher
+//! quantization error.
+
+use super::GgufQuantization;
+
+/// Weights per k-quant super-block
+const QK_K: usize = 256;
+
+/// Weights per `Q4_0`/`Q8_0` block
+const QK: usize = 32;
+
+/// Tensor data types of the GGUF format, with their ggml type ids
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum GgmlType {
+    F32,
+    F16,
+    Q4_0,
+    Q8_0,
+    Q4_K,
+    Q5_K,
+    Q6_K,
+}
+
+impl GgmlType {
+    /// Type id written to GGUF tensor infos
+    pub fn id(self) -> u32 {
+        match self {
+            GgmlType::F32 => 0,
+            GgmlType::F16 => 1,
+            GgmlType::Q4_0 => 2,
+            GgmlType::Q8_0 => 8,
+            GgmlType::Q4_K => 12,
+            GgmlType::Q5_K => 13,
+            GgmlType::Q6_K => 14,
+        }
+    }
+
+    /// Weights per block
+    pub fn block_size(self) -> usize {
+        match self {
+            GgmlType::F32 | GgmlType::F16 => 1,
+            GgmlType::Q4_0 | GgmlType::Q8_0 => QK,
+            GgmlType::Q4_K | GgmlType::Q5_K | GgmlType::Q6_K => QK_K,
+        }
+    }
+
+    /// Bytes per block
+    pub fn type_size(self) -> usize {
+        match self {
+            GgmlType::F32 => 4,
+            GgmlType::F16 => 2,
+            GgmlType::Q4_0 => 18,
+            GgmlType::Q8_0 => 34,
+            GgmlType::Q4_K => 144,
+            GgmlType::Q5_K => 176,
+            GgmlType::Q6_K => 210,
+        }
+    }
+
+    /// Bytes taken by a row of `ne0` weights; `ne0` must be a multiple of
+    /// the block size
+    pub fn row_bytes(self, ne0: usize) -> usize {
+        ne0 / self.block_size() * self.type_size()
+    }
+
+    /// Encode rows of `ne0` weights, spreading the rows over the CPUs
+    pub fn quantize(self, data: &[f32], ne0: usize) -> Vec<u8> {
+        let rows = data.len() / ne0;
+        let row_bytes = self.row_bytes(ne0);
+        let mut out = vec![0u8; rows * row_bytes];
+        if rows == 0 {
+            return out;
+        }
+
+        let threads = num_cpus::get().clamp(1, rows);
+        let rows_per_thread = rows.div_ceil(threads);
+        std::thread::scope(|scope| {
+            for (src, dst) in data
+                .chunks(rows_per_thread * ne0)
+                .zip(out.chunks_mut(rows_per_thread * row_bytes))
+            {
+                scope.spawn(move || {
+                    for (row, encoded) in src.chunks(ne0).zip(dst.chunks_mut(row_bytes)) {
+                        self.quantize_row(row, encoded);
+                    }
+                });
+            }
+        });
+        out
+    }
+
+    fn quantize_row(self, row: &[f32], out: &mut [u8]) {
+        let blocks = row
+            .chunks(self.block_size())
+            .zip(out.chunks_mut(self.type_size()));
+        match self {
+            GgmlType::F32 => {
+                for (x, y) in blocks {
+                    y.copy_from_slice(&x[0].to_le_bytes());
+                }
+            }
+            GgmlType::F16 => {
+                for (x, y) in blocks {
+                    y.copy_from_slice(&f32_to_f16(x[0]).to_le_bytes());
+                }
+            }
+            GgmlType::Q4_0 => blocks.for_each(|(x, y)| quantize_block_q4_0(x, y)),
+            GgmlType::Q8_0 => blocks.for_each(|(x, y)| quantize_block_q8_0(x, y)),
+            GgmlType::Q4_K => blocks.for_each(|(x, y)| quantize_block_k(x, y, false)),
+            GgmlType::Q5_K => blocks.for_each(|(x, y)| quantize_block_k(x, y, true)),
+            GgmlType::Q6_K => blocks.for_each(|(x, y)| quantize_block_q6_k(x, y)),
+        }
+    }
+}
+
+impl GgufQuantization {
+    /// `general.file_type` of a model exported at this quantization
+    pub fn file_type(&self) -> u32 {
+        match self {
+            GgufQuantization::F16 => 1,
+            GgufQuantization::Q4_0 => 2,
+            GgufQuantization::Q8_0 => 7,
+            GgufQuantization::Q4_K_M => 15,
+            GgufQuantization::Q5_K_M => 17,
+            GgufQuantization::Q6_K => 18,
+        }
+    }
+
+    /// Type the tensor `name` of shape `ne` (innermost dimension first) is
+    /// stored as in a model of `n_layers` layers
+    ///
+    /// Mirrors llama.cpp's mixes: norms and biases stay f32, the output
+    /// projection gets 6 bits, and the `_M` k-quants give `attn_v` and
+    /// `ffn_down` 6 bits in the layers that matter most. Rows that don't
+    /// divide into blocks fall back to `Q8_0`, or f16 if they can't.
+    pub fn tensor_type(&self, name: &str, ne: &[usize], n_layers: usize) -> GgmlType {
+        if ne.len() < 2 {
+            return GgmlType::F32;
+        }
+
+        let base = match self {
+            GgufQuantization::F16 => GgmlType::F16,
+            GgufQuantization::Q8_0 => GgmlType::Q8_0,
+            GgufQuantization::Q6_K => GgmlType::Q6_K,
+            GgufQuantization::Q5_K_M => GgmlType::Q5_K,
+            GgufQuantization::Q4_K_M => GgmlType::Q4_K,
+            GgufQuantization::Q4_0 => GgmlType::Q4_0,
+        };
+        let k_mix = matches!(self, GgufQuantization::Q5_K_M | GgufQuantization::Q4_K_M);
+        let output = name == "output.weight" && base != GgmlType::F16 && base != GgmlType::Q8_0;
+        let more_bits = k_mix
+            && (name.contains("attn_v.weight") || name.contains("ffn_down.weight"))
+            && layer_index(name).is_some_and(|layer| use_more_bits(layer, n_layers));
+        let wanted = if output || more_bits {
+            GgmlType::Q6_K
+        } else {
+            base
+        };
+
+        if ne[0].is_multiple_of(wanted.block_size()) {
+            wanted
+        } else if ne[0].is_multiple_of(QK) && wanted != GgmlType::F16 {
+            GgmlType::Q8_0
+        } else {
+            GgmlType::F16
+        }
+    }
+}
+
+/// Layer of a `blk.N.*` tensor
+pub(crate) fn layer_index(name: &str) -> Option<usize> {
+    name.strip_prefix("blk.")?.split('.').next()?.parse().ok()
+}
+
+/// The first and last eighth of the layers, and every third layer between
+fn use_more_bits(layer: usize, n_layers: usize) -> bool {
+    layer < n_layers / 8 || layer >= 7 * n_layers / 8 || (layer - n_layers / 8) % 3 == 2
+}
+
+/// IEEE half precision bits of `value`, rounded to nearest even
+pub fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+
+    let half_exponent = exponent - 127 + 15;
+    if half_exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+
+    let (half, rest, halfway) = if half_exponent <= 0 {
+        // Subnormal in half precision
+        if half_exponent < -10 {
+            return sign;
+        }
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - half_exponent) as u32;
+        (
+            mantissa >> shift,
+            mantissa & ((1 << shift) - 1),
+            1 << (shift - 1),
+        )
+    } else {
+        (
+            ((half_exponent as u32) << 10) | (mantissa >> 13),
+            mantissa & 0x1fff,
+            0x1000,
+        )
+    };
+    // A carry out of the mantissa correctly bumps the exponent
+    let rounded = if rest > halfway || (rest == halfway && half & 1 == 1) {
+        half + 1
+    } else {
+        half
+    };
+    sign | rounded as u16
+}
+
+/// Value of IEEE half precision `bits`
+pub fn f16_to_f32(bits: u16) -> f32 {
+    let negative = bits & 0x8000 != 0;
+    let sign = ((bits & 0x8000) as u32) << 16;
+    let exponent = ((bits >> 10) & 0x1f) as u32;
+    let mantissa = (bits & 0x3ff) as u32;
+    match exponent {
+        0 => {
+            let magnitude = mantissa as f32 * 2f32.powi(-24);
+            if negative {
+                -magnitude
+            } else {
+                magnitude
+            }
+        }
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13)),
+        _ => f32::from_bits(sign | ((exponent + 112) << 23) | (mantissa << 13)),
+    }
+}
+
+/// Value of bfloat16 `bits`
+pub fn bf16_to_f32(bits: u16) -> f32 {
+    f32::from_bits((bits as u32) << 16)
+}
+
+fn nearest_int(value: f32) -> i32 {
+    value.round() as i32
+}
+
+fn quantize_block_q8_0(x: &[f32], y: &mut [u8]) {
+    let amax = x.iter().fold(0f32, |m, v| m.max(v.abs()));
+    let d = amax / 127.0;
+    let id = if d != 0.0 { 1.0 / d } else { 0.0 };
+    y[..2].copy_from_slice(&f32_to_f16(d).to_le_bytes());
+    for (q, v) in y[2..].iter_mut().zip(x) {
+        *q = nearest_int(v * id) as i8 as u8;
+    }
+}
+
+fn quantize_block_q4_0(x: &[f32], y: &mut [u8]) {
+    // The value of largest magnitude maps to -8, so its sign picks which end
+    // of the range gets the extra level
+    let max = x
+        .iter()
+        .fold(0f32, |m, &v| if v.abs() > m.abs() { v } else { m });
+    let d = max / -8.0;
+    let id = if d != 0.0 { 1.0 / d } else { 0.0 };
+    y[..2].copy_from_slice(&f32_to_f16(d).to_le_bytes());
+    let level = |v: f32| ((v * id + 8.5) as i32).clamp(0, 15) as u8;
+    for j in 0..QK / 2 {
+        y[2 + j] = level(x[j]) | (level(x[j + QK / 2]) << 4);
+    }
+}
+
+/// Pack the 6-bit scale and minimum of sub-block `j` into the 12 scale bytes
+/// of a `Q4_K`/`Q5_K` super-block
+fn pack_scale_min_k4(j: usize, scale: u8, min: u8, scales: &mut [u8]) {
+    if j < 4 {
+        scales[j] = scale;
+        scales[j + 4] = min;
+    } else {
+        scales[j + 4] = (scale & 0xf) | ((min & 0xf) << 4);
+        scales[j - 4] |= (scale >> 4) << 6;
+        scales[j] |= (min >> 4) << 6;
+    }
+}
+
+/// `Q4_K` or, with `five_bits`, `Q5_K` super-block
+fn quantize_block_k(x: &[f32], y: &mut [u8], five_bits: bool) {
+    let max_level = if five_bits { 31.0 } else { 15.0 };
+
+    // Affine fit per sub-block: x ~ scale * level - min, with min >= 0
+    let mut scales = [0f32; QK_K / 32];
+    let mut mins = [0f32; QK_K / 32];
+    for (j, sub) in x.chunks(32).enumerate() {
+        let lo = sub.iter().fold(0f32, |m, &v| m.min(v));
+        let hi = sub.iter().fold(f32::MIN, |m, &v| m.max(v));
+        scales[j] = (hi - lo) / max_level;
+        mins[j] = -lo;
+    }
+
+    let max_scale = scales.iter().fold(0f32, |m, &v| m.max(v));
+    let max_min = mins.iter().fold(0f32, |m, &v| m.max(v));
+    let inv_scale = if max_scale > 0.0 {
+        63.0 / max_scale
+    } else {
+        0.0
+    };
+    let inv_min = if max_min > 0.0 { 63.0 / max_min } else { 0.0 };
+    let d = max_scale / 63.0;
+    let dmin = max_min / 63.0;
+
+    let (header, rest) = y.split_at_mut(16);
+    header[..2].copy_from_slice(&f32_to_f16(d).to_le_bytes());
+    header[2..4].copy_from_slice(&f32_to_f16(dmin).to_le_bytes());
+    let packed = &mut header[4..16];
+    packed.fill(0);
+
+    // Levels are recomputed against the scales as they are stored
+    let d = f16_to_f32(f32_to_f16(d));
+    let dmin = f16_to_f32(f32_to_f16(dmin));
+    let mut levels = [0u8; QK_K];
+    for (j, sub) in x.chunks(32).enumerate() {
+        let ls = nearest_int(inv_scale * scales[j]).clamp(0, 63) as u8;
+        let lm = nearest_int(inv_min * mins[j]).clamp(0, 63) as u8;
+        pack_scale_min_k4(j, ls, lm, packed);
+
+        let step = d * ls as f32;
+        let offset = dmin * lm as f32;
+        if step == 0.0 {
+            continue;
+        }
+        for (l, &v) in sub.iter().enumerate() {
+            levels[32 * j + l] = nearest_int((v + offset) / step).clamp(0, max_level as i32) as u8;
+        }
+    }
+
+    // Each 64 weights share 32 bytes: low nibbles hold the first 32, high
+    // nibbles the next. Q5_K keeps the fifth bits in 32 more bytes, two bits
+    // per 64 weights
+    let (qh, qs) = if five_bits {
+        let (qh, qs) = rest.split_at_mut(QK_K / 8);
+        qh.fill(0);
+        (Some(qh), qs)
+    } else {
+        (None, rest)
+    };
+    let mut qh = qh;
+    for (chunk, j) in (0..QK_K).step_by(64).enumerate() {
+        for l in 0..32 {
+            let lo = levels[j + l];
+            let hi = levels[j + l + 32];
+            qs[j / 2 + l] = (lo & 0xf) | ((hi & 0xf) << 4);
+            if let Some(qh) = qh.as_deref_mut() {
+                qh[l] |= ((lo >> 4) << (2 * chunk)) | ((hi >> 4) << (2 * chunk + 1));
+            }
+        }
+    }
+}
+
+fn quantize_block_q6_k(x: &[f32], y: &mut [u8]) {
+    // Symmetric fit per 16 weights, the largest magnitude mapping to -32
+    let mut scales = [0f32; QK_K / 16];
+    for (j, sub) in x.chunks(16).enumerate() {
+        let max = sub
+            .iter()
+            .fold(0f32, |m, &v| if v.abs() > m.abs() { v } else { m });
+        scales[j] = max / -32.0;
+    }
+    let max_scale = scales
+        .iter()
+        .fold(0f32, |m, &v| if v.abs() > m.abs() { v } else { m });
+
+    let (ql, rest) = y.split_at_mut(QK_K / 2);
+    let (qh, rest) = rest.split_at_mut(QK_K / 4);
+    let (sc, d_bytes) = rest.split_at_mut(QK_K / 16);
+    if max_scale == 0.0 {
+        ql.fill(0);
+        qh.fill(0);
+        sc.fill(0);
+        d_bytes.copy_from_slice(&0u16.to_le_bytes());
+        return;
+    }
+
+    let iscale = -128.0 / max_scale;
+    let d = 1.0 / iscale;
+    d_bytes.copy_from_slice(&f32_to_f16(d).to_le_bytes());
+    let d = f16_to_f32(f32_to_f16(d));
+
+    let mut levels = [32u8; QK_K];
+    for (j, sub) in x.chunks(16).enumerate() {
+        let scale = nearest_int(iscale * scales[j]).clamp(-128, 127) as i8;
+        sc[j] = scale as u8;
+        let step = d * scale as f32;
+        if step == 0.0 {
+            continue;
+        }
+        for (l, &v) in sub.iter().enumerate() {
+            levels[16 * j + l] = (nearest_int(v / step).clamp(-32, 31) + 32) as u8;
+        }
+    }
+
+    // Per 128 weights: 64 bytes of low nibbles and 32 bytes of high bit pairs
+    for (half, j) in (0..QK_K).step_by(128).enumerate() {
+        let ql = &mut ql[64 * half..64 * (half + 1)];
+        let qh = &mut qh[32 * half..32 * (half + 1)];
+        for l in 0..32 {
+            let q1 = levels[j + l];
+            let q2 = levels[j + l + 32];
+            let q3 = levels[j + l + 64];
+            let q4 = levels[j + l + 96];
+            ql[l] = (q1 & 0xf) | ((q3 & 0xf) << 4);
+            ql[l + 32] = (q2 & 0xf) | ((q4 & 0xf) << 4);
+            qh[l] = (q1 >> 4) | ((q2 >> 4) << 2) | ((q3 >> 4) << 4) | ((q4 >> 4) << 6);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f16_at(bytes: &[u8], at: usize) -> f32 {
+        f16_to_f32(u16::from_le_bytes([bytes[at], bytes[at + 1]]))
+    }
+
+    fn scale_min_k4(j: usize, q: &[u8]) -> (f32, f32) {
+        if j < 4 {
+            ((q[j] & 63) as f32, (q[j + 4] & 63) as f32)
+        } else {
+            (
+                ((q[j + 4] & 0xf) | ((q[j - 4] >> 6) << 4)) as f32,
+                ((q[j + 4] >> 4) | ((q[j] >> 6) << 4)) as f32,
+            )
+        }
+    }
+
+    /// Decoders following ggml's reference dequantization
+    fn dequantize(ty: GgmlType, bytes: &[u8]) -> Vec<f32> {
+        let mut out = Vec::new();
+        for b in bytes.chunks(ty.type_size()) {
+            match ty {
+                GgmlType::Q8_0 => {
+                    let d = f16_at(b, 0);
+                    out.extend(b[2..].iter().map(|&q| d * q as i8 as f32));
+                }
+                GgmlType::Q4_0 => {
+                    let d = f16_at(b, 0);
+                    out.extend(b[2..].iter().map(|&q| d * ((q & 0xf) as f32 - 8.0)));
+                    out.extend(b[2..].iter().map(|&q| d * ((q >> 4) as f32 - 8.0)));
+                }
+                GgmlType::Q4_K | GgmlType::Q5_K => {
+                    let five = ty == GgmlType::Q5_K;
+                    let (d, dmin) = (f16_at(b, 0), f16_at(b, 2));
+                    let scales = &b[4..16];
+                    let (qh, qs) = if five {
+                        b[16..].split_at(32)
+                    } else {
+                        (&b[..0], &b[16..])
+                    };
+                    for chunk in 0..4 {
+                        let q = &qs[32 * chunk..32 * (chunk + 1)];
+                        for (half, shift) in [(0, 0), (1, 4)] {
+                            let (sc, m) = scale_min_k4(2 * chunk + half, scales);
+                            for l in 0..32 {
+                                let mut level = (q[l] >> shift) & 0xf;
+                                if five && qh[l] & (1 << (2 * chunk + half)) != 0 {
+                                    level += 16;
+                                }
+                                out.push(d * sc * level as f32 - dmin * m);
+                            }
+                        }
+                    }
+                }
+                GgmlType::Q6_K => {
+                    let (ql, qh, sc) = (&b[..128], &b[128..192], &b[192..208]);
+                    let d = f16_at(b, 208);
+                    for n in 0..2 {
+                        let (ql, qh, sc) = (&ql[64 * n..], &qh[32 * n..], &sc[8 * n..]);
+                        let mut y = [0f32; 128];
+                        for l in 0..32 {
+                            let is = l / 16;
+                            let q1 = ((ql[l] & 0xf) | (qh[l] & 3) << 4) as i32 - 32;
+                            let q2 = ((ql[l + 32] & 0xf) | ((qh[l] >> 2) & 3) << 4) as i32 - 32;
+                            let q3 = ((ql[l] >> 4) | ((qh[l] >> 4) & 3) << 4) as i32 - 32;
+                            let q4 = ((ql[l + 32] >> 4) | ((qh[l] >> 6) & 3) << 4) as i32 - 32;
+                            let s = |i: usize| d * sc[i] as i8 as f32;
+                            y[l] = s(is) * q1 as f32;
+                            y[l + 32] = s(is + 2) * q2 as f32;
+                            y[l + 64] = s(is + 4) * q3 as f32;
+                            y[l + 96] = s(is + 6) * q4 as f32;
+                        }
+                        out.extend(y);
+                    }
+                }
+                GgmlType::F32 | GgmlType::F16 => unreachable!(),
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_f16_conversion() {
+        for value in [
+            0.0f32,
+            1.0,
+            -2.5,
+            65504.0,
+            6.1035156e-5,
+            5.9604645e-8,
+            -0.33325195,
+        ] {
+            assert_eq!(f16_to_f32(f32_to_f16(value)), value);
+        }
+        assert_eq!(f32_to_f16(1.0), 0x3c00);
+        assert_eq!(f32_to_f16(1e6), 0x7c00);
+        // Ties round to even
+        assert_eq!(f32_to_f16(1.0 + 2f32.powi(-11)), 0x3c00);
+        assert_eq!(f32_to_f16(1.0 + 3.0 * 2f32.powi(-11)), 0x3c02);
+        assert!(f16_to_f32(f32_to_f16(f32::NAN)).is_nan());
+        assert_eq!(bf16_to_f32(0x3f80), 1.0);
+    }
+
+    #[test]
+    fn test_quantize_round_trip() {
+        let ne0 = 512;
+        let data: Vec<f32> = (0..ne0 * 3)
+            .map(|i| ((i * 7919 % 1000) as f32 / 500.0 - 1.0) * (0.5 + (i / 256 % 3) as f32 * 0.25))
+            .collect();
+        for (ty, tolerance) in [
+            (GgmlType::Q8_0, 0.01),
+            (GgmlType::Q6_K, 0.03),
+            (GgmlType::Q5_K, 0.04),
+            (GgmlType::Q4_K, 0.08),
+            // The value of largest magnitude takes the end with the extra level
+            (GgmlType::Q4_0, 0.13),
+        ] {
+            let bytes = ty.quantize(&data, ne0);
+            assert_eq!(bytes.len(), 3 * ty.row_bytes(ne0), "{:?}", ty);
+            let decoded = dequantize(ty, &bytes);
+            let max_error = data
+                .iter()
+                .zip(&decoded)
+                .fold(0f32, |m, (a, b)| m.max((a - b).abs()));
+            assert!(max_error < tolerance, "{:?} error {}", ty, max_error);
+        }
+    }
+
+    #[test]
+    fn test_tensor_type_mix() {
+        let q = GgufQuantization::Q4_K_M;
+        assert_eq!(
+            q.tensor_type("blk.0.attn_norm.weight", &[4096], 32),
+            GgmlType::F32
+        );
+        assert_eq!(
+            q.tensor_type("output.weight", &[4096, 32000], 32),
+            GgmlType::Q6_K
+        );
+        assert_eq!(
+            q.tensor_type("blk.0.attn_v.weight", &[4096, 1024], 32),
+            GgmlType::Q6_K
+        );
+        assert_eq!(
+            q.tensor_type("blk.4.attn_v.weight", &[4096, 1024], 32),
+            GgmlType::Q4_K
+        );
+        assert_eq!(
+            q.tensor_type("blk.6.ffn_down.weight", &[11008, 4096], 32),
+            GgmlType::Q6_K
+        );
+        assert_eq!(
+            q.tensor_type("blk.5.attn_q.weight", &[4096, 4096], 32),
+            GgmlType::Q4_K
+        );
+        // 896 is not a multiple of 256
+        assert_eq!(
+            q.tensor_type("blk.5.attn_q.weight", &[896, 896], 24),
+            GgmlType::Q8_0
+        );
+        assert_eq!(
+            GgufQuantization::Q8_0.tensor_type("output.weight", &[4096, 32000], 32),
+            GgmlType::Q8_0
+        );
+        assert_eq!(
+            GgufQuantization::F16.tensor_type("blk.0.attn_q.weight", &[30, 30], 1),
+            GgmlType::F16
+        );
+    }
+}
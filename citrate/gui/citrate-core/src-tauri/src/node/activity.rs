@@ -0,0 +1,317 @@
+//! Reorg-Aware Wallet Activity
+//!
+//! Account activity is rebuilt from the mempool and the blocks accepted by
+//! the selected chain on every scan. This module remembers what the previous
+//! scan of each address returned, bound to the block each entry was found
+//! in, so a later scan can tell a reorg apart from a transaction that was
+//! never there:
+//!
+//! - an entry whose block is still inside the scanned window but no longer
+//!   accepted is kept with status `reorged` until it is re-included or falls
+//!   out of the window
+//! - an entry found in a different block is reported as reorged out of the
+//!   old block and confirmed in the new one
+//! - a pending entry that leaves the mempool without being included is
+//!   reported as `dropped`
+//!
+//! Every status change is queued as a [`WalletActivityChanged`] for the UI.
+
+use super::TxActivity;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Tauri event carrying a [`WalletActivityChanged`]
+pub const WALLET_ACTIVITY_CHANGED_EVENT: &str = "wallet-activity-changed";
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_REORGED: &str = "reorged";
+pub const STATUS_DROPPED: &str = "dropped";
+
+/// A status change of one activity entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityTransition {
+    pub tx_hash: String,
+    /// Transfer made by a contract during the tx
+    pub internal: bool,
+    /// `None` for activity seen for the first time
+    pub from_status: Option<String>,
+    pub to_status: String,
+    /// Block the entry is bound to after the change; for `reorged`, the
+    /// block it left
+    pub block_hash: Option<String>,
+    pub block_height: Option<u64>,
+}
+
+/// Payload of [`WALLET_ACTIVITY_CHANGED_EVENT`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletActivityChanged {
+    pub address: String,
+    pub transitions: Vec<ActivityTransition>,
+}
+
+/// Key of an activity entry. Internal transfers share their parent's hash,
+/// so they are keyed apart.
+fn entry_key(item: &TxActivity) -> String {
+    if item.internal {
+        format!(
+            "{}:{}:{}",
+            item.hash,
+            item.from,
+            item.to.as_deref().unwrap_or("")
+        )
+    } else {
+        item.hash.clone()
+    }
+}
+
+/// Sort newest first with pending entries on top, keeping one entry per
+/// key. A tx that is included but not yet evicted from the mempool shows as
+/// included.
+pub fn sort_and_dedup(mut activity: Vec<TxActivity>) -> Vec<TxActivity> {
+    let included: HashSet<String> = activity
+        .iter()
+        .filter(|item| item.block_hash.is_some())
+        .map(entry_key)
+        .collect();
+    activity.retain(|item| item.block_hash.is_some() || !included.contains(&entry_key(item)));
+    activity.sort_by(|a, b| {
+        let at = a.timestamp.unwrap_or(u64::MAX);
+        let bt = b.timestamp.unwrap_or(u64::MAX);
+        bt.cmp(&at)
+    });
+    let mut seen = HashSet::new();
+    activity.retain(|item| seen.insert(entry_key(item)));
+    activity
+}
+
+fn transition(item: &TxActivity, from_status: Option<&str>, to_status: &str) -> ActivityTransition {
+    ActivityTransition {
+        tx_hash: item.hash.clone(),
+        internal: item.internal,
+        from_status: from_status.map(str::to_string),
+        to_status: to_status.to_string(),
+        block_hash: item.block_hash.clone(),
+        block_height: item.block_height,
+    }
+}
+
+struct WatchedAddress {
+    block_window: u64,
+    entries: HashMap<String, TxActivity>,
+}
+
+/// Last known activity of the addresses the wallet has looked at
+#[derive(Default)]
+pub struct ActivityTracker {
+    addresses: HashMap<String, WatchedAddress>,
+    changes: Vec<WalletActivityChanged>,
+}
+
+impl ActivityTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Addresses to rescan, with the block window they were scanned over
+    pub fn watched(&self) -> Vec<(String, u64)> {
+        self.addresses
+            .iter()
+            .map(|(address, watched)| (address.clone(), watched.block_window))
+            .collect()
+    }
+
+    /// Status changes since the last call
+    pub fn take_changes(&mut self) -> Vec<WalletActivityChanged> {
+        std::mem::take(&mut self.changes)
+    }
+
+    /// Merge a fresh scan of `address` into its history and return the
+    /// activity to show, newest first
+    ///
+    /// `scan` holds the entries found in the mempool and in blocks from
+    /// height `window_start` up, each bound to its block hash.
+    pub fn reconcile(
+        &mut self,
+        address: &str,
+        block_window: u64,
+        window_start: u64,
+        scan: Vec<TxActivity>,
+    ) -> Vec<TxActivity> {
+        let address = address.to_lowercase();
+        let first_scan = !self.addresses.contains_key(&address);
+        let watched = self
+            .addresses
+            .entry(address.clone())
+            .or_insert_with(|| WatchedAddress {
+                block_window,
+                entries: HashMap::new(),
+            });
+        watched.block_window = watched.block_window.max(block_window);
+
+        let mut previous = std::mem::take(&mut watched.entries);
+        let mut transitions = Vec::new();
+        let mut entries = HashMap::new();
+
+        for item in sort_and_dedup(scan) {
+            let key = entry_key(&item);
+            match previous.remove(&key) {
+                Some(prev) if prev.block_hash.is_some() && prev.block_hash != item.block_hash => {
+                    // Moved to another block, or back to the mempool
+                    if prev.status != STATUS_REORGED {
+                        transitions.push(transition(&prev, Some(&prev.status), STATUS_REORGED));
+                    }
+                    transitions.push(transition(&item, Some(STATUS_REORGED), &item.status));
+                }
+                Some(prev) if prev.status != item.status => {
+                    transitions.push(transition(&item, Some(&prev.status), &item.status));
+                }
+                Some(_) => {}
+                None if !first_scan => transitions.push(transition(&item, None, &item.status)),
+                None => {}
+            }
+            entries.insert(key, item);
+        }
+
+        // Entries the scan no longer finds
+        for (key, mut prev) in previous {
+            match prev.block_height {
+                Some(height) if height >= window_start => {
+                    if prev.status != STATUS_REORGED {
+                        transitions.push(transition(&prev, Some(&prev.status), STATUS_REORGED));
+                        prev.status = STATUS_REORGED.to_string();
+                    }
+                    entries.insert(key, prev);
+                }
+                // Slid out of the window
+                Some(_) => {}
+                None => transitions.push(transition(&prev, Some(&prev.status), STATUS_DROPPED)),
+            }
+        }
+
+        let activity = sort_and_dedup(entries.values().cloned().collect());
+        watched.entries = entries;
+        if !transitions.is_empty() {
+            self.changes.push(WalletActivityChanged {
+                address,
+                transitions,
+            });
+        }
+        activity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "0xAbC0000000000000000000000000000000000001";
+
+    fn pending(hash: &str) -> TxActivity {
+        TxActivity {
+            hash: hash.to_string(),
+            from: ADDRESS.to_lowercase(),
+            to: Some("0x02".to_string()),
+            value: "1".to_string(),
+            nonce: 0,
+            status: STATUS_PENDING.to_string(),
+            block_hash: None,
+            block_height: None,
+            timestamp: None,
+            internal: false,
+        }
+    }
+
+    fn confirmed(hash: &str, block: &str, height: u64) -> TxActivity {
+        TxActivity {
+            status: "confirmed".to_string(),
+            block_hash: Some(block.to_string()),
+            block_height: Some(height),
+            timestamp: Some(1_000 + height),
+            ..pending(hash)
+        }
+    }
+
+    #[test]
+    fn test_pending_to_confirmed_to_reorged() {
+        let mut tracker = ActivityTracker::new();
+        let shown = tracker.reconcile(ADDRESS, 10, 0, vec![pending("aa")]);
+        assert_eq!(shown.len(), 1);
+        // The first scan is the baseline, not a change
+        assert!(tracker.take_changes().is_empty());
+
+        tracker.reconcile(ADDRESS, 10, 0, vec![confirmed("aa", "b1", 5)]);
+        let changes = tracker.take_changes();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].address, ADDRESS.to_lowercase());
+        assert_eq!(
+            changes[0].transitions[0].from_status.as_deref(),
+            Some("pending")
+        );
+        assert_eq!(changes[0].transitions[0].to_status, "confirmed");
+
+        // Block b1 leaves the selected chain while still inside the window
+        let shown = tracker.reconcile(ADDRESS, 10, 0, vec![]);
+        assert_eq!(shown.len(), 1);
+        assert_eq!(shown[0].status, STATUS_REORGED);
+        assert_eq!(shown[0].block_hash.as_deref(), Some("b1"));
+        let changes = tracker.take_changes();
+        assert_eq!(changes[0].transitions[0].to_status, STATUS_REORGED);
+        assert_eq!(changes[0].transitions[0].block_hash.as_deref(), Some("b1"));
+
+        // Reported once
+        tracker.reconcile(ADDRESS, 10, 0, vec![]);
+        assert!(tracker.take_changes().is_empty());
+
+        // Re-included elsewhere
+        let shown = tracker.reconcile(ADDRESS, 10, 0, vec![confirmed("aa", "b2", 6)]);
+        assert_eq!(shown[0].status, "confirmed");
+        let transitions = &tracker.take_changes()[0].transitions;
+        assert_eq!(transitions.len(), 1);
+        assert_eq!(transitions[0].from_status.as_deref(), Some(STATUS_REORGED));
+        assert_eq!(transitions[0].block_hash.as_deref(), Some("b2"));
+    }
+
+    #[test]
+    fn test_moved_block_and_window_expiry() {
+        let mut tracker = ActivityTracker::new();
+        tracker.reconcile(
+            ADDRESS,
+            10,
+            0,
+            vec![confirmed("aa", "b1", 5), pending("bb")],
+        );
+
+        let shown = tracker.reconcile(ADDRESS, 10, 0, vec![confirmed("aa", "b2", 5)]);
+        assert_eq!(shown.len(), 1);
+        let transitions = &tracker.take_changes()[0].transitions;
+        let statuses: Vec<_> = transitions
+            .iter()
+            .map(|t| (t.tx_hash.as_str(), t.to_status.as_str()))
+            .collect();
+        assert!(statuses.contains(&("aa", STATUS_REORGED)));
+        assert!(statuses.contains(&("aa", "confirmed")));
+        assert!(statuses.contains(&("bb", STATUS_DROPPED)));
+
+        // Old entries that slide out of the window are forgotten quietly
+        let shown = tracker.reconcile(ADDRESS, 10, 6, vec![]);
+        assert!(shown.is_empty());
+        assert!(tracker.take_changes().is_empty());
+        assert_eq!(tracker.watched(), vec![(ADDRESS.to_lowercase(), 10)]);
+    }
+
+    #[test]
+    fn test_included_tx_still_in_mempool_shows_included() {
+        let mut tracker = ActivityTracker::new();
+        let shown = tracker.reconcile(
+            ADDRESS,
+            10,
+            0,
+            vec![pending("aa"), confirmed("aa", "b1", 5)],
+        );
+        assert_eq!(shown.len(), 1);
+        assert_eq!(shown[0].block_hash.as_deref(), Some("b1"));
+
+        tracker.reconcile(ADDRESS, 10, 0, vec![confirmed("aa", "b1", 5)]);
+        assert!(tracker.take_changes().is_empty());
+    }
+}
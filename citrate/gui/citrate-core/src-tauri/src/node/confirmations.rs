@@ -0,0 +1,269 @@
+//! Transaction Confirmation Tracking
+//!
+//! Watches transactions submitted from the GUI and reports when they reach
+//! 1, 3 and 6 confirmations. Confirmations are measured in blue score: a
+//! transaction included in a block with blue score `s` has
+//! `tip_blue_score - s + 1` confirmations.
+//!
+//! If the block holding a transaction drops out of the DAG (or the receipt
+//! moves to another block) a `reorged` update is emitted and tracking starts
+//! over. Transactions that never land are reported as `dropped` after
+//! [`PENDING_TIMEOUT`].
+
+use citrate_consensus::types::Hash;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Tauri event carrying a [`TxConfirmation`]
+pub const TX_CONFIRMED_EVENT: &str = "tx-confirmed";
+
+/// Confirmation depths that trigger an update
+pub const CONFIRMATION_MILESTONES: [u64; 3] = [1, 3, 6];
+
+/// How long to wait for a transaction to be included before giving up
+pub const PENDING_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Where a tracked transaction was found on this poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inclusion {
+    pub block_hash: Hash,
+    pub block_height: u64,
+    pub blue_score: u64,
+    /// Receipt status
+    pub success: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfirmationStatus {
+    /// Reached a confirmation milestone
+    Confirmed,
+    /// Previously included block is no longer part of the DAG
+    Reorged,
+    /// Never included within the pending timeout
+    Dropped,
+}
+
+/// Payload of [`TX_CONFIRMED_EVENT`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxConfirmation {
+    pub tx_hash: String,
+    pub status: ConfirmationStatus,
+    /// Blue-score depth of the including block (0 when not included)
+    pub confirmations: u64,
+    pub block_hash: Option<String>,
+    pub block_height: Option<u64>,
+    /// Receipt status, when included
+    pub success: Option<bool>,
+}
+
+struct Tracked {
+    submitted_at: Instant,
+    inclusion: Option<Inclusion>,
+    /// Highest milestone already reported for the current inclusion
+    reported: u64,
+}
+
+/// Set of transactions being watched for confirmations
+#[derive(Default)]
+pub struct ConfirmationTracker {
+    tracked: HashMap<Hash, Tracked>,
+}
+
+impl ConfirmationTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start watching a submitted transaction
+    pub fn track(&mut self, tx_hash: Hash) {
+        self.tracked.entry(tx_hash).or_insert(Tracked {
+            submitted_at: Instant::now(),
+            inclusion: None,
+            reported: 0,
+        });
+    }
+
+    /// Transactions currently being watched
+    pub fn pending(&self) -> Vec<Hash> {
+        self.tracked.keys().copied().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tracked.is_empty()
+    }
+
+    /// Apply the latest chain view and return the updates to emit.
+    /// `observe` reports where each transaction is currently included.
+    pub fn update<F>(
+        &mut self,
+        tip_blue_score: u64,
+        now: Instant,
+        mut observe: F,
+    ) -> Vec<TxConfirmation>
+    where
+        F: FnMut(&Hash) -> Option<Inclusion>,
+    {
+        let final_depth = *CONFIRMATION_MILESTONES.last().unwrap_or(&1);
+        let mut updates = Vec::new();
+        let mut finished = Vec::new();
+
+        for (hash, tracked) in self.tracked.iter_mut() {
+            let current = observe(hash);
+
+            // The block we reported is gone, or the tx moved to another block
+            if let Some(previous) = &tracked.inclusion {
+                if current.as_ref().map(|c| &c.block_hash) != Some(&previous.block_hash) {
+                    updates.push(TxConfirmation {
+                        tx_hash: hash.to_hex(),
+                        status: ConfirmationStatus::Reorged,
+                        confirmations: 0,
+                        block_hash: Some(previous.block_hash.to_hex()),
+                        block_height: Some(previous.block_height),
+                        success: None,
+                    });
+                    tracked.reported = 0;
+                    // Restart the pending timeout from the reorg
+                    tracked.submitted_at = now;
+                }
+            }
+            tracked.inclusion = current;
+
+            let Some(inclusion) = &tracked.inclusion else {
+                if now.duration_since(tracked.submitted_at) >= PENDING_TIMEOUT {
+                    updates.push(TxConfirmation {
+                        tx_hash: hash.to_hex(),
+                        status: ConfirmationStatus::Dropped,
+                        confirmations: 0,
+                        block_hash: None,
+                        block_height: None,
+                        success: None,
+                    });
+                    finished.push(*hash);
+                }
+                continue;
+            };
+
+            let confirmations = tip_blue_score.saturating_sub(inclusion.blue_score) + 1;
+            let milestone = CONFIRMATION_MILESTONES
+                .iter()
+                .copied()
+                .filter(|m| *m <= confirmations)
+                .max()
+                .unwrap_or(0);
+            if milestone > tracked.reported {
+                tracked.reported = milestone;
+                updates.push(TxConfirmation {
+                    tx_hash: hash.to_hex(),
+                    status: ConfirmationStatus::Confirmed,
+                    confirmations,
+                    block_hash: Some(inclusion.block_hash.to_hex()),
+                    block_height: Some(inclusion.block_height),
+                    success: Some(inclusion.success),
+                });
+            }
+            if tracked.reported >= final_depth {
+                finished.push(*hash);
+            }
+        }
+
+        for hash in finished {
+            self.tracked.remove(&hash);
+        }
+        updates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inclusion(block: u8, blue_score: u64) -> Inclusion {
+        Inclusion {
+            block_hash: Hash::new([block; 32]),
+            block_height: blue_score,
+            blue_score,
+            success: true,
+        }
+    }
+
+    #[test]
+    fn test_reports_each_milestone_once() {
+        let tx = Hash::new([1; 32]);
+        let mut tracker = ConfirmationTracker::new();
+        tracker.track(tx);
+        let now = Instant::now();
+
+        assert!(tracker.update(9, now, |_| None).is_empty());
+
+        let updates = tracker.update(10, now, |_| Some(inclusion(7, 10)));
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].status, ConfirmationStatus::Confirmed);
+        assert_eq!(updates[0].confirmations, 1);
+
+        assert!(tracker
+            .update(11, now, |_| Some(inclusion(7, 10)))
+            .is_empty());
+        assert_eq!(
+            tracker.update(12, now, |_| Some(inclusion(7, 10)))[0].confirmations,
+            3
+        );
+
+        // Jumping past 6 reports once and stops tracking
+        let updates = tracker.update(20, now, |_| Some(inclusion(7, 10)));
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].confirmations, 11);
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn test_reorg_resets_confirmations() {
+        let tx = Hash::new([1; 32]);
+        let mut tracker = ConfirmationTracker::new();
+        tracker.track(tx);
+        let now = Instant::now();
+
+        tracker.update(12, now, |_| Some(inclusion(7, 10)));
+
+        // Block 7 disappears; tx is back in the mempool
+        let updates = tracker.update(12, now, |_| None);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].status, ConfirmationStatus::Reorged);
+        assert_eq!(updates[0].block_hash, Some(Hash::new([7; 32]).to_hex()));
+
+        // Re-included in a different block: milestones start over
+        let updates = tracker.update(13, now, |_| Some(inclusion(8, 13)));
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].status, ConfirmationStatus::Confirmed);
+        assert_eq!(updates[0].confirmations, 1);
+    }
+
+    #[test]
+    fn test_moved_to_other_block_is_reorg() {
+        let tx = Hash::new([1; 32]);
+        let mut tracker = ConfirmationTracker::new();
+        tracker.track(tx);
+        let now = Instant::now();
+
+        tracker.update(10, now, |_| Some(inclusion(7, 10)));
+        let updates = tracker.update(11, now, |_| Some(inclusion(8, 11)));
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].status, ConfirmationStatus::Reorged);
+        assert_eq!(updates[1].status, ConfirmationStatus::Confirmed);
+        assert_eq!(updates[1].block_hash, Some(Hash::new([8; 32]).to_hex()));
+    }
+
+    #[test]
+    fn test_unincluded_tx_is_dropped_after_timeout() {
+        let tx = Hash::new([1; 32]);
+        let mut tracker = ConfirmationTracker::new();
+        tracker.track(tx);
+        let later = Instant::now() + PENDING_TIMEOUT;
+
+        let updates = tracker.update(5, later, |_| None);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].status, ConfirmationStatus::Dropped);
+        assert!(tracker.is_empty());
+    }
+}
@@ -1,5 +1,9 @@
+pub mod activity;
+pub mod confirmations;
+
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -14,16 +18,31 @@ use citrate_consensus::{
 use citrate_execution::{state::StateDB, Executor};
 use citrate_network::peer::{Direction as PeerDirection, PeerId, PeerState as NetPeerState};
 use citrate_network::NetworkMessage;
-use citrate_network::{PeerManager, PeerManagerConfig};
+use citrate_network::{
+    PeerManager, PeerManagerConfig, ReputationEvent, ReputationManager, VersionDistribution,
+    CLIENT_VERSION,
+};
 use citrate_sequencer::mempool::{Mempool, MempoolConfig};
+use citrate_sequencer::MiningMode;
+use citrate_wallet::config_secrets;
+use citrate_storage::chain::stats_history::{
+    run_recorder, ChainStatsSnapshot, LiveStats, DEFAULT_RETENTION_DAYS,
+};
+use citrate_storage::db::{DbStats, DbTuning};
+use citrate_storage::pruning::PruningStats;
 use citrate_storage::StorageManager;
-use citrate_api::{RpcServer, RpcConfig, RpcCloseHandle};
+use citrate_api::{BlockId, RpcServer, RpcConfig, RpcCloseHandle};
+use crate::rpc_client::RpcClient;
 use crate::sync::iterative_sync::{IterativeSyncManager, SyncConfig};
-use crate::wallet::WalletManager;
+use crate::wallet::payments::{self, PaymentRun, PaymentStatus, RunOutcome, ScheduledPayment};
+use crate::wallet::{TransactionRequest, WalletManager};
+use activity::{ActivityTracker, WalletActivityChanged};
+use confirmations::{ConfirmationTracker, Inclusion, TxConfirmation};
 use sha3::{Digest, Sha3_256};
 use tokio::task::JoinHandle;
 
-/// Manages the embedded Citrate node
+/// Manages the embedded Citrate node, or an external node the GUI is
+/// attached to as a dashboard
 pub struct NodeManager {
     node: Arc<RwLock<Option<CitrateNode>>>,
     config: Arc<RwLock<NodeConfig>>,
@@ -32,6 +51,11 @@ pub struct NodeManager {
     sync_manager: Arc<RwLock<Option<Arc<IterativeSyncManager>>>>,
     reward_address: Arc<RwLock<Option<String>>>,
     wallet_manager: Arc<RwLock<Option<Arc<WalletManager>>>>,
+    confirmations: Arc<RwLock<ConfirmationTracker>>,
+    /// Last known activity of the addresses the wallet has looked at
+    activity: Arc<RwLock<ActivityTracker>>,
+    /// External node queried over its RPC instead of the embedded node
+    remote: Arc<RwLock<Option<Arc<RpcClient>>>>,
 }
 
 impl NodeManager {
@@ -45,6 +69,9 @@ impl NodeManager {
             sync_manager: Arc::new(RwLock::new(None)),
             reward_address: Arc::new(RwLock::new(None)),
             wallet_manager: Arc::new(RwLock::new(None)),
+            confirmations: Arc::new(RwLock::new(ConfirmationTracker::new())),
+            activity: Arc::new(RwLock::new(ActivityTracker::new())),
+            remote: Arc::new(RwLock::new(None)),
         })
     }
 
@@ -53,6 +80,18 @@ impl NodeManager {
     }
 
     pub async fn start(&self) -> Result<()> {
+        if self.remote.read().await.is_some() {
+            info!("Attached to an external node, not starting the embedded node");
+            return Ok(());
+        }
+        let external_rpc = self.config.read().await.external_rpc.clone();
+        if let Some(url) = external_rpc {
+            self.attach_external(&url).await.map_err(|e| {
+                anyhow::anyhow!("{} (detach to run the embedded node instead)", e)
+            })?;
+            return Ok(());
+        }
+
         info!("Starting Citrate node");
 
         // Check if node is already running - if so, just return success (idempotent)
@@ -116,15 +155,18 @@ impl NodeManager {
             }
         }
 
-        let storage = Arc::new(StorageManager::new(
+        let storage = Arc::new(StorageManager::with_tuning(
             storage_path.clone(),
             citrate_storage::pruning::PruningConfig {
                 keep_blocks: 10000,
                 keep_states: 1000,
+                keep_receipts: 10000,
                 auto_prune: true,
                 batch_size: 100,
                 interval: std::time::Duration::from_secs(3600),
             },
+            false,
+            &config.storage_tuning,
         )?);
 
         // Create simplified GhostDAG setup
@@ -155,6 +197,7 @@ impl NodeManager {
             replacement_factor: cfg_mempool.replacement_factor,
             require_valid_signature: cfg_mempool.require_valid_signature,
             tx_expiry_secs: cfg_mempool.tx_expiry_secs,
+            gas_lanes: Default::default(),
         };
         // Create mempool - Mempool is internally synchronized via internal RwLocks,
         // so we use Arc<Mempool> directly instead of Arc<RwLock<Mempool>>
@@ -170,7 +213,12 @@ impl NodeManager {
             score_threshold: -100,
         };
 
-        let peer_manager = Arc::new(PeerManager::new(peer_config));
+        // Scores and bans persist across restarts in the node database
+        let reputation = Arc::new(ReputationManager::with_storage(
+            peer_config.reputation_config(),
+            storage.db.clone(),
+        )?);
+        let peer_manager = Arc::new(PeerManager::with_reputation(peer_config, reputation));
 
         // Initialize iterative sync manager to avoid stack overflow
         let sync_config = SyncConfig {
@@ -615,36 +663,37 @@ impl NodeManager {
         let should_produce_blocks = reward_address.is_some() &&
             (config.network == "devnet" || config.network == "testnet");
 
-        let (block_producer_handle, block_producer_running) = if should_produce_blocks {
-            if let Some(addr) = reward_address {
-                info!(
-                    "Starting block producer for {} with reward address {}",
-                    config.network, addr
-                );
-                let wm = self.wallet_manager.read().await.clone();
-                let producer = crate::block_producer::BlockProducer::new(
-                    ghostdag.clone(),
-                    mempool.clone(),
-                    executor.clone(),
-                    storage.clone(),
-                    Arc::new(RwLock::new(Some(addr))),
-                    wm,
-                    if config.enable_network {
-                        Some(peer_manager.clone())
-                    } else {
-                        None
-                    },
-                );
-                let running_flag = producer.running_flag();
-                let handle = producer.start().await.ok();
-                (handle, Some(running_flag))
+        let (block_producer, block_producer_handle, block_producer_running) =
+            if should_produce_blocks {
+                if let Some(addr) = reward_address {
+                    info!(
+                        "Starting block producer for {} with reward address {}",
+                        config.network, addr
+                    );
+                    let wm = self.wallet_manager.read().await.clone();
+                    let producer = crate::block_producer::BlockProducer::new(
+                        ghostdag.clone(),
+                        mempool.clone(),
+                        executor.clone(),
+                        storage.clone(),
+                        Arc::new(RwLock::new(Some(addr))),
+                        wm,
+                        if config.enable_network {
+                            Some(peer_manager.clone())
+                        } else {
+                            None
+                        },
+                    );
+                    let running_flag = producer.running_flag();
+                    let handle = producer.start().await.ok();
+                    (Some(producer), handle, Some(running_flag))
+                } else {
+                    (None, None, None)
+                }
             } else {
-                (None, None)
-            }
-        } else {
-            warn!("No reward address set, block production disabled. Please configure a wallet to start earning rewards.");
-            (None, None)
-        };
+                warn!("No reward address set, block production disabled. Please configure a wallet to start earning rewards.");
+                (None, None, None)
+            };
 
         // RPC server initialization - enabled for external JSON-RPC access
         let rpc_handles = if config.enable_rpc {
@@ -657,6 +706,8 @@ impl NodeManager {
                 max_connections: 100,
                 cors_domains: vec!["*".to_string()],
                 threads: 4,
+                accounts: Vec::new(),
+                ..Default::default()
             };
 
             let rpc_server = RpcServer::new(
@@ -667,6 +718,13 @@ impl NodeManager {
                 executor.clone(),
                 config.mempool.chain_id,
             );
+            #[cfg(feature = "devnet")]
+            let rpc_server = match &block_producer {
+                Some(producer) if config.network == "devnet" => {
+                    rpc_server.with_dev_control(Arc::new(producer.clone()))
+                }
+                _ => rpc_server,
+            };
 
             match rpc_server.spawn() {
                 Ok((close_handle, join_handle)) => {
@@ -683,6 +741,32 @@ impl NodeManager {
             None
         };
 
+        // Hourly chain statistics snapshots, until the node stops
+        {
+            let running = running.clone();
+            let pm = peer_manager.clone();
+            let mempool = mempool.clone();
+            tokio::spawn(run_recorder(
+                storage.stats_history.clone(),
+                DEFAULT_RETENTION_DAYS,
+                move || {
+                    let running = running.clone();
+                    let pm = pm.clone();
+                    let mempool = mempool.clone();
+                    async move {
+                        if !*running.read().await {
+                            return None;
+                        }
+                        let (peer_count, _, _) = pm.get_peer_counts().await;
+                        Some(LiveStats {
+                            peer_count,
+                            mempool_pending: mempool.stats().await.total_transactions,
+                        })
+                    }
+                },
+            ));
+        }
+
         let node = CitrateNode {
             storage,
             executor,
@@ -691,6 +775,7 @@ impl NodeManager {
             peer_manager,
             _running: running,
             start_time: std::time::Instant::now(),
+            block_producer,
             block_producer_handle,
             block_producer_running,
             rpc_handles,
@@ -774,6 +859,56 @@ impl NodeManager {
         Ok(())
     }
 
+    /// Attach to an external node at `url` instead of running the embedded
+    /// node. The embedded node is stopped, and status, peer, mempool,
+    /// database and DAG queries go to the external node until
+    /// `detach_external`. The URL is saved so later starts attach again.
+    pub async fn attach_external(&self, url: &str) -> Result<NodeStatus> {
+        let client = Arc::new(RpcClient::new(url.to_string()));
+        // The admin methods must be reachable, not just the eth ones
+        client
+            .get_node_status()
+            .await
+            .map_err(|e| anyhow::anyhow!("Cannot attach to node at {}: {}", url, e))?;
+
+        self.stop().await?;
+        *self.remote.write().await = Some(client);
+        {
+            let mut config = self.config.write().await;
+            config.external_rpc = Some(url.to_string());
+            config.save()?;
+        }
+        info!("Attached to external node at {}", url);
+        self.get_status().await
+    }
+
+    /// Stop using the external node; the embedded node can be started again
+    pub async fn detach_external(&self) -> Result<()> {
+        *self.remote.write().await = None;
+        let mut config = self.config.write().await;
+        if let Some(url) = config.external_rpc.take() {
+            config.save()?;
+            info!("Detached from external node at {}", url);
+        }
+        Ok(())
+    }
+
+    /// Client for the external node, when attached to one
+    pub async fn remote_client(&self) -> Option<Arc<RpcClient>> {
+        self.remote.read().await.clone()
+    }
+
+    /// Stop the node and load the configuration of the active profile
+    pub async fn reload_config(&self) -> Result<()> {
+        self.stop().await?;
+        *self.remote.write().await = None;
+        *self.config.write().await = NodeConfig::load_or_default()?;
+        *self.reward_address.write().await = None;
+        *self.confirmations.write().await = ConfirmationTracker::new();
+        *self.activity.write().await = ActivityTracker::new();
+        Ok(())
+    }
+
     pub async fn get_storage(&self) -> Option<Arc<StorageManager>> {
         self.storage.read().await.clone()
     }
@@ -803,6 +938,26 @@ impl NodeManager {
 
     /// Return current peer summaries
     pub async fn get_peers_summary(&self) -> Vec<PeerSummary> {
+        if let Some(client) = self.remote_client().await {
+            return match client.get_peer_info().await {
+                Ok(peers) => peers
+                    .into_iter()
+                    .map(|p| PeerSummary {
+                        id: p.id,
+                        addr: p.addr,
+                        direction: p.direction,
+                        state: p.state,
+                        score: p.score,
+                        last_seen_secs: p.last_seen_secs,
+                        client_version: p.client_version,
+                    })
+                    .collect(),
+                Err(e) => {
+                    warn!("Failed to fetch peers from external node: {}", e);
+                    vec![]
+                }
+            };
+        }
         if let Some(node) = self.node.read().await.as_ref() {
             let peers = node.peer_manager.get_all_peers();
             let mut out = Vec::with_capacity(peers.len());
@@ -824,6 +979,7 @@ impl NodeManager {
                     },
                     score: info.score,
                     last_seen_secs: info.last_seen.elapsed().as_secs(),
+                    client_version: info.client_version.clone(),
                 });
             }
             out
@@ -832,6 +988,307 @@ impl NodeManager {
         }
     }
 
+    /// Sync progress of the embedded node, `None` while it isn't running
+    pub async fn sync_progress(&self) -> Option<citrate_network::SyncProgress> {
+        let sync_manager = self.sync_manager.read().await.clone()?;
+        Some(sync_manager.progress().await)
+    }
+
+    /// Watch a submitted transaction for confirmations
+    pub async fn track_transaction(&self, tx_hash: Hash) {
+        self.confirmations.write().await.track(tx_hash);
+    }
+
+    /// Check tracked transactions against the DAG and return confirmation
+    /// updates to emit
+    pub async fn poll_confirmations(&self) -> Vec<TxConfirmation> {
+        let pending = self.confirmations.read().await.pending();
+        if pending.is_empty() {
+            return Vec::new();
+        }
+        let node_guard = self.node.read().await;
+        let Some(node) = node_guard.as_ref() else {
+            return Vec::new();
+        };
+
+        let tip_blue_score = match node.ghostdag.select_tip().await {
+            Ok(tip) => node.ghostdag.get_blue_score(&tip).await.unwrap_or(0),
+            Err(_) => 0,
+        };
+
+        // A transaction counts as included while its receipt points at a
+        // block that is still stored and known to GhostDAG
+        let mut inclusions = HashMap::new();
+        for tx_hash in pending {
+            let Ok(Some(receipt)) = node.storage.transactions.get_receipt(&tx_hash) else {
+                continue;
+            };
+            let Ok(Some(block)) = node.storage.blocks.get_block(&receipt.block_hash) else {
+                continue;
+            };
+            let Ok(blue_score) = node.ghostdag.get_blue_score(&receipt.block_hash).await else {
+                continue;
+            };
+            inclusions.insert(
+                tx_hash,
+                Inclusion {
+                    block_hash: receipt.block_hash,
+                    block_height: block.header.height,
+                    blue_score,
+                    success: receipt.status,
+                },
+            );
+        }
+        drop(node_guard);
+
+        self.confirmations
+            .write()
+            .await
+            .update(tip_blue_score, std::time::Instant::now(), |hash| {
+                inclusions.get(hash).cloned()
+            })
+    }
+
+    /// Submit wallet payments that have come due and return the runs to
+    /// report. Payments whose account cannot sign unattended are held for
+    /// approval. Nothing runs while the embedded node is stopped, so due
+    /// payments wait for it.
+    pub async fn run_scheduled_payments(&self) -> Vec<PaymentRun> {
+        let Some(wallet) = self.wallet_manager.read().await.clone() else {
+            return Vec::new();
+        };
+        if self.get_mempool().await.is_none() {
+            return Vec::new();
+        }
+
+        let schedule = wallet.payments();
+        let now = payments::now();
+        let mut runs = Vec::new();
+        for payment in schedule.due(now) {
+            let value = payment.amount.parse::<u128>().unwrap_or(u128::MAX);
+            let run = if wallet.can_sign_unattended(&payment.from, value).await {
+                self.submit_scheduled_payment(&wallet, &payment, "", now)
+                    .await
+            } else {
+                PaymentRun::new(&payment, now, RunOutcome::NeedsApproval)
+            };
+            if let Err(e) = schedule.record(run.clone()) {
+                warn!("Failed to record scheduled payment {}: {}", payment.id, e);
+            }
+            runs.push(run);
+        }
+        runs
+    }
+
+    /// Sign and submit a payment held for approval with the account password
+    pub async fn approve_scheduled_payment(&self, id: &str, password: &str) -> Result<PaymentRun> {
+        let wallet = self
+            .wallet_manager
+            .read()
+            .await
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Wallet is not available"))?;
+        if self.get_mempool().await.is_none() {
+            return Err(anyhow::anyhow!("Node not started - mempool unavailable"));
+        }
+        let schedule = wallet.payments();
+        let payment = schedule
+            .get(id)
+            .ok_or_else(|| anyhow::anyhow!("No scheduled payment with id '{}'", id))?;
+        if payment.status != PaymentStatus::AwaitingApproval {
+            return Err(anyhow::anyhow!("Payment is not waiting for approval"));
+        }
+
+        let run = self
+            .submit_scheduled_payment(&wallet, &payment, password, payments::now())
+            .await;
+        schedule.record(run.clone())?;
+        Ok(run)
+    }
+
+    async fn submit_scheduled_payment(
+        &self,
+        wallet: &WalletManager,
+        payment: &ScheduledPayment,
+        password: &str,
+        now: u64,
+    ) -> PaymentRun {
+        let gas_price = match &payment.gas_price {
+            Some(price) => price.clone(),
+            None => self.config.read().await.mempool.min_gas_price.to_string(),
+        };
+        let request = TransactionRequest {
+            from: payment.from.clone(),
+            to: Some(payment.to.clone()),
+            value: payment.amount.clone(),
+            gas_limit: 21_000,
+            gas_price,
+            data: String::new(),
+        };
+
+        let submitted = async {
+            let tx = wallet.create_signed_transaction(request, password).await?;
+            let mempool = self
+                .get_mempool()
+                .await
+                .ok_or_else(|| anyhow::anyhow!("Node not started - mempool unavailable"))?;
+            mempool
+                .add_transaction(tx.clone(), citrate_sequencer::mempool::TxClass::Standard)
+                .await
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+            self.track_transaction(tx.hash).await;
+            let hash = tx.hash;
+            let _ = self
+                .broadcast_network(NetworkMessage::NewTransaction { transaction: tx })
+                .await;
+            Ok::<_, anyhow::Error>(hash)
+        }
+        .await;
+
+        match submitted {
+            Ok(hash) => {
+                let tx_hash = hex::encode(hash.as_bytes());
+                info!("Submitted scheduled payment {} as 0x{}", payment.id, tx_hash);
+                PaymentRun {
+                    tx_hash: Some(tx_hash),
+                    ..PaymentRun::new(payment, now, RunOutcome::Submitted)
+                }
+            }
+            Err(e) => {
+                warn!("Scheduled payment {} failed: {}", payment.id, e);
+                PaymentRun {
+                    error: Some(e.to_string()),
+                    ..PaymentRun::new(payment, now, RunOutcome::Failed)
+                }
+            }
+        }
+    }
+
+    /// Client versions reported by connected peers
+    pub async fn get_peer_versions(&self) -> VersionDistribution {
+        match self.node.read().await.as_ref() {
+            Some(node) => node.peer_manager.version_distribution().await,
+            None => VersionDistribution::default(),
+        }
+    }
+
+    /// Return persisted peer reputation, lowest score first
+    pub async fn get_peer_reputation(&self) -> Vec<PeerReputationSummary> {
+        if let Some(client) = self.remote_client().await {
+            let records = match client.get_peer_reputation().await {
+                Ok(records) => records,
+                Err(e) => {
+                    warn!("Failed to fetch peer reputation from external node: {}", e);
+                    return vec![];
+                }
+            };
+            return records
+                .into_iter()
+                .map(|r| PeerReputationSummary {
+                    ip: r.ip,
+                    peer_id: r.peer_id,
+                    score: r.score,
+                    connected: r.connected,
+                    banned: r.banned,
+                    banned_until: r.banned_until,
+                    ban_reason: r.ban_reason,
+                    ban_count: r.ban_count,
+                    events: r.events,
+                })
+                .collect();
+        }
+        let Some(peer_manager) = self
+            .node
+            .read()
+            .await
+            .as_ref()
+            .map(|node| node.peer_manager.clone())
+        else {
+            return vec![];
+        };
+        let mut connected = std::collections::HashSet::new();
+        for p in peer_manager.get_all_peers() {
+            connected.insert(p.info.read().await.addr.ip());
+        }
+        let now = chrono::Utc::now().timestamp().max(0) as u64;
+        peer_manager
+            .reputation()
+            .all()
+            .into_iter()
+            .map(|r| PeerReputationSummary {
+                ip: r.ip.to_string(),
+                connected: connected.contains(&r.ip),
+                banned: r.is_banned_at(now),
+                peer_id: r.peer_id,
+                score: r.score,
+                banned_until: r.banned_until,
+                ban_reason: r.ban_reason,
+                ban_count: r.ban_count,
+                events: r.events.into_iter().collect(),
+            })
+            .collect()
+    }
+
+    /// Database size, compaction and write-stall statistics for the storage
+    /// panel
+    pub async fn get_db_stats(&self) -> Result<DbStats> {
+        if let Some(client) = self.remote_client().await {
+            return client.get_db_stats().await;
+        }
+        let storage = self
+            .get_storage()
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Node is not running"))?;
+        storage.db.db_stats()
+    }
+
+    /// Dry run of the next prune: blocks, receipts and states that would be
+    /// removed and the bytes freed per column family
+    pub async fn prune_preview(&self) -> Result<PruningStats> {
+        if let Some(client) = self.remote_client().await {
+            return client.prune_preview().await;
+        }
+        let storage = self
+            .get_storage()
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Node is not running"))?;
+        storage.pruner.preview().await
+    }
+
+    /// Hourly chain statistics snapshots in `[from, to)`, unix seconds.
+    /// Defaults to the last seven days.
+    pub async fn get_stats_history(
+        &self,
+        from: Option<u64>,
+        to: Option<u64>,
+    ) -> Result<Vec<ChainStatsSnapshot>> {
+        if let Some(client) = self.remote_client().await {
+            return client.get_stats_history(from, to).await;
+        }
+        let storage = self
+            .get_storage()
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Node is not running"))?;
+        let to = to.unwrap_or_else(|| chrono::Utc::now().timestamp().max(0) as u64 + 1);
+        let from = from.unwrap_or_else(|| to.saturating_sub(7 * 24 * 3600));
+        if from >= to {
+            return Err(anyhow::anyhow!("from must be earlier than to"));
+        }
+        storage.stats_history.get_range(from, to)
+    }
+
+    /// Start compacting one column family, or all of them, in the background
+    pub async fn compact_database(&self, column_family: Option<&str>) -> Result<Vec<String>> {
+        if let Some(client) = self.remote_client().await {
+            return client.compact_db(column_family).await;
+        }
+        let storage = self
+            .get_storage()
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Node is not running"))?;
+        storage.db.compact(column_family)
+    }
+
     /// Connect to all configured bootnodes now (if network is enabled)
     pub async fn connect_bootnodes_now(&self) -> Result<usize> {
         let node_guard = self.node.read().await;
@@ -1002,29 +1459,107 @@ impl NodeManager {
     }
 
     /// Get pending and confirmed transactions for the given account address
+    ///
+    /// Confirmed activity comes from the blocks accepted by the selected
+    /// chain within `block_window` of the tip. Entries whose block has left
+    /// the chain since an earlier call are kept as `reorged`.
     pub async fn get_account_activity(
         &self,
         address: &str,
         block_window: u64,
         limit: usize,
     ) -> Result<Vec<TxActivity>> {
+        let Some((scan, window_start)) = self.scan_account_activity(address, block_window).await
+        else {
+            return Ok(Vec::new());
+        };
+        let mut activity = self
+            .activity
+            .write()
+            .await
+            .reconcile(address, block_window, window_start, scan);
+        activity.truncate(limit);
+        Ok(activity)
+    }
+
+    /// Rescan the addresses whose activity has been requested and return
+    /// the status changes to emit
+    pub async fn poll_activity(&self) -> Vec<WalletActivityChanged> {
+        let watched = self.activity.read().await.watched();
+        for (address, block_window) in watched {
+            if let Some((scan, window_start)) =
+                self.scan_account_activity(&address, block_window).await
+            {
+                self.activity
+                    .write()
+                    .await
+                    .reconcile(&address, block_window, window_start, scan);
+            }
+        }
+        self.activity.write().await.take_changes()
+    }
+
+    /// Blocks accepted by the selected chain down to height `start`: the
+    /// chain blocks themselves and the blocks they merge, tip first
+    async fn accepted_blocks(node: &CitrateNode, start: u64) -> Vec<Block> {
+        let storage = &node.storage;
+        let tip = match node.ghostdag.select_tip().await {
+            Ok(tip) => Some(tip),
+            Err(_) => storage
+                .blocks
+                .get_latest_height()
+                .ok()
+                .and_then(|h| storage.blocks.get_block_by_height(h).ok().flatten()),
+        };
+
+        let mut blocks = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        let mut next = tip;
+        while let Some(hash) = next.take() {
+            let Ok(Some(block)) = storage.blocks.get_block(&hash) else {
+                break;
+            };
+            if block.header.height < start {
+                break;
+            }
+            for merged in &block.header.merge_parent_hashes {
+                if !seen.insert(*merged) {
+                    continue;
+                }
+                if let Ok(Some(merged)) = storage.blocks.get_block(merged) {
+                    if merged.header.height >= start {
+                        blocks.push(merged);
+                    }
+                }
+            }
+            if block.header.height > 0 {
+                next = Some(block.header.selected_parent_hash);
+            }
+            if seen.insert(hash) {
+                blocks.push(block);
+            }
+        }
+        blocks
+    }
+
+    /// Pending and accepted activity of `address`, unsorted, with the lowest
+    /// height scanned. `None` while the embedded node is not running.
+    async fn scan_account_activity(
+        &self,
+        address: &str,
+        block_window: u64,
+    ) -> Option<(Vec<TxActivity>, u64)> {
         let mut activity: Vec<TxActivity> = Vec::new();
         let addr_lc = address.to_lowercase();
 
-        // Snapshot handles from node and drop the lock to avoid holding across await
-        let (storage, mempool) = {
-            let guard = self.node.read().await;
-            let node = match guard.as_ref() {
-                Some(n) => n,
-                None => return Ok(activity),
-            };
-            (node.storage.clone(), node.mempool.clone())
-        };
+        let guard = self.node.read().await;
+        let node = guard.as_ref()?;
+        let storage = node.storage.clone();
 
         // Collect pending from mempool (outgoing and incoming)
         // Mempool is internally synchronized - call methods directly
         {
-            let memtx = mempool.get_transactions(1000).await; // coarse upper bound
+            let memtx = node.mempool.get_transactions(1000).await; // coarse upper bound
             for tx in memtx {
                 let from_addr = Self::pk_to_address_hex(&tx.from).to_lowercase();
                 let to_addr = tx
@@ -1043,82 +1578,96 @@ impl NodeManager {
                         block_hash: None,
                         block_height: None,
                         timestamp: None,
+                        internal: false,
                     });
                 }
             }
         }
 
-        // Collect confirmed from recent blocks (use receipts to surface status)
+        // Collect confirmed from recent accepted blocks (use receipts to
+        // surface status)
         let latest = storage.blocks.get_latest_height().unwrap_or(0);
-        if latest > 0 {
-            let start = latest.saturating_sub(block_window);
-            let mut h = latest;
-            while h >= start {
-                if let Ok(Some(bh)) = storage.blocks.get_block_by_height(h) {
-                    if let Ok(Some(block)) = storage.blocks.get_block(&bh) {
-                        for tx in &block.transactions {
-                            let from_addr = Self::pk_to_address_hex(&tx.from).to_lowercase();
-                            let to_addr = tx
-                                .to
-                                .as_ref()
-                                .map(|p| Self::to_field_as_address_hex(p).to_lowercase());
-                            if from_addr == addr_lc || to_addr.as_deref() == Some(&addr_lc) {
-                                let to_hex = tx.to.as_ref().map(Self::to_field_as_address_hex);
-                                let status = match storage.transactions.get_receipt(&tx.hash) {
-                                    Ok(Some(r)) => {
-                                        if r.status {
-                                            "confirmed"
-                                        } else {
-                                            "failed"
-                                        }
-                                    }
-                                    _ => "confirmed",
-                                };
-                                activity.push(TxActivity {
-                                    hash: hex::encode(tx.hash.as_bytes()),
-                                    from: Self::pk_to_address_hex(&tx.from),
-                                    to: to_hex,
-                                    value: tx.value.to_string(),
-                                    nonce: tx.nonce,
-                                    status: status.into(),
-                                    block_hash: Some(block.header.block_hash.to_hex()),
-                                    block_height: Some(block.header.height),
-                                    timestamp: Some(block.header.timestamp),
-                                });
+        let start = latest.saturating_sub(block_window);
+        for block in Self::accepted_blocks(node, start).await {
+            for tx in &block.transactions {
+                let from_addr = Self::pk_to_address_hex(&tx.from).to_lowercase();
+                let to_addr = tx
+                    .to
+                    .as_ref()
+                    .map(|p| Self::to_field_as_address_hex(p).to_lowercase());
+                if from_addr == addr_lc || to_addr.as_deref() == Some(&addr_lc) {
+                    let to_hex = tx.to.as_ref().map(Self::to_field_as_address_hex);
+                    let status = match storage.transactions.get_receipt(&tx.hash) {
+                        Ok(Some(r)) => {
+                            if r.status {
+                                "confirmed"
+                            } else {
+                                "failed"
                             }
                         }
-                    }
+                        _ => "confirmed",
+                    };
+                    activity.push(TxActivity {
+                        hash: hex::encode(tx.hash.as_bytes()),
+                        from: Self::pk_to_address_hex(&tx.from),
+                        to: to_hex,
+                        value: tx.value.to_string(),
+                        nonce: tx.nonce,
+                        status: status.into(),
+                        block_hash: Some(block.header.block_hash.to_hex()),
+                        block_height: Some(block.header.height),
+                        timestamp: Some(block.header.timestamp),
+                        internal: false,
+                    });
                 }
-                if h == 0 {
-                    break;
+
+                // Value moved by contracts on behalf of this tx
+                let internal = storage
+                    .transactions
+                    .get_internal_transactions(&tx.hash)
+                    .unwrap_or_default();
+                for itx in internal {
+                    let itx_from = format!("0x{}", hex::encode(itx.from.0));
+                    let itx_to = format!("0x{}", hex::encode(itx.to.0));
+                    if itx_from == addr_lc || itx_to == addr_lc {
+                        activity.push(TxActivity {
+                            hash: hex::encode(tx.hash.as_bytes()),
+                            from: itx_from,
+                            to: Some(itx_to),
+                            value: itx.value.to_string(),
+                            nonce: tx.nonce,
+                            status: "confirmed".into(),
+                            block_hash: Some(block.header.block_hash.to_hex()),
+                            block_height: Some(block.header.height),
+                            timestamp: Some(block.header.timestamp),
+                            internal: true,
+                        });
+                    }
                 }
-                h -= 1;
             }
         }
+        drop(guard);
 
-        // Sort by (timestamp desc, pending on top if no timestamp)
-        activity.sort_by(|a, b| {
-            let at = a.timestamp.unwrap_or(u64::MAX);
-            let bt = b.timestamp.unwrap_or(u64::MAX);
-            bt.cmp(&at)
-        });
-
-        // Deduplicate by hash, prefer pending first then confirmed latest
-        let mut seen = std::collections::HashSet::new();
-        let mut dedup: Vec<TxActivity> = Vec::new();
-        for item in activity.into_iter() {
-            if seen.insert(item.hash.clone()) {
-                dedup.push(item);
-            }
-            if dedup.len() >= limit {
-                break;
-            }
-        }
-        Ok(dedup)
+        Some((activity, start))
     }
 
     /// Get global tx overview: pending mempool count and tx count in latest block
     pub async fn get_tx_overview(&self) -> Result<TxOverview> {
+        if let Some(client) = self.remote_client().await {
+            let status = client.get_node_status().await?;
+            let last_block = match status.height {
+                0 => 0,
+                height => client
+                    .get_block(BlockId::Number(height))
+                    .await?
+                    .map_or(0, |block| block.transactions.len()),
+            };
+            return Ok(TxOverview {
+                pending: status.mempool_pending,
+                last_block,
+            });
+        }
+
         let mut pending = 0usize;
         let mut last_block = 0usize;
 
@@ -1143,6 +1692,29 @@ impl NodeManager {
 
     /// Snapshot current mempool pending txs (best-effort, limited)
     pub async fn get_mempool_pending(&self, limit: usize) -> Result<Vec<PendingTx>> {
+        if let Some(client) = self.remote_client().await {
+            let txs = client.get_mempool_pending(limit).await?;
+            return Ok(txs
+                .into_iter()
+                .map(|tx| {
+                    let key = |hex_key: &str| {
+                        let bytes = hex::decode(hex_key).unwrap_or_default();
+                        let mut key = [0u8; 32];
+                        if bytes.len() == 32 {
+                            key.copy_from_slice(&bytes);
+                        }
+                        PublicKey::new(key)
+                    };
+                    PendingTx {
+                        hash: hex::encode(tx.hash.as_bytes()),
+                        from: Self::pk_to_address_hex(&key(&tx.from)),
+                        to: tx.to.as_deref().map(|to| Self::to_field_as_address_hex(&key(to))),
+                        value: tx.value.to_string(),
+                        nonce: tx.nonce,
+                    }
+                })
+                .collect());
+        }
         if let Some(node) = self.node.read().await.as_ref() {
             // Mempool is internally synchronized - call methods directly
             let txs = node.mempool.get_transactions(limit).await;
@@ -1204,6 +1776,37 @@ impl NodeManager {
     }
 
     pub async fn get_status(&self) -> Result<NodeStatus> {
+        if let Some(client) = self.remote_client().await {
+            let (url, network) = {
+                let config = self.config.read().await;
+                (config.external_rpc.clone(), config.network.clone())
+            };
+            return Ok(match client.get_node_status().await {
+                Ok(status) => NodeStatus {
+                    running: true,
+                    syncing: status.syncing,
+                    block_height: status.height,
+                    peer_count: status.peer_count,
+                    network_id: status.chain_id.to_string(),
+                    version: status.version,
+                    uptime: status.uptime_secs,
+                    dag_tips: status.dag_tips,
+                    blue_score: status.blue_score,
+                    last_block_hash: status.head_hash,
+                    last_block_timestamp: status.head_timestamp,
+                    upgrade_available: status.upgrade_available,
+                    external_rpc: url,
+                },
+                Err(e) => {
+                    warn!("External node is unreachable: {}", e);
+                    NodeStatus {
+                        external_rpc: url,
+                        ..NodeStatus::stopped(network)
+                    }
+                }
+            });
+        }
+
         let node_guard = self.node.read().await;
 
         if let Some(node) = node_guard.as_ref() {
@@ -1236,6 +1839,11 @@ impl NodeManager {
             } else {
                 0
             };
+            let upgrade_available = node
+                .peer_manager
+                .version_distribution()
+                .await
+                .upgrade_available;
 
             Ok(NodeStatus {
                 running: true,
@@ -1249,21 +1857,11 @@ impl NodeManager {
                 blue_score,
                 last_block_hash: last_hash,
                 last_block_timestamp: last_ts,
+                upgrade_available,
+                external_rpc: None,
             })
         } else {
-            Ok(NodeStatus {
-                running: false,
-                syncing: false,
-                block_height: 0,
-                peer_count: 0,
-                network_id: self.config.read().await.network.clone(),
-                version: env!("CARGO_PKG_VERSION").to_string(),
-                uptime: 0,
-                dag_tips: 0,
-                blue_score: 0,
-                last_block_hash: None,
-                last_block_timestamp: None,
-            })
+            Ok(NodeStatus::stopped(self.config.read().await.network.clone()))
         }
     }
 
@@ -1310,6 +1908,7 @@ impl NodeManager {
                 );
                 node.block_producer_running = Some(producer.running_flag());
                 node.block_producer_handle = producer.start().await.ok();
+                node.block_producer = Some(producer);
                 info!("Block producer started after setting reward address");
             }
         }
@@ -1320,6 +1919,43 @@ impl NodeManager {
         self.reward_address.read().await.clone()
     }
 
+    /// Block producer of a running devnet node
+    async fn devnet_producer(&self) -> Result<crate::block_producer::BlockProducer> {
+        if self.config.read().await.network != "devnet" {
+            return Err(anyhow::anyhow!("Block production control is only available on devnet"));
+        }
+        self.node
+            .read()
+            .await
+            .as_ref()
+            .and_then(|node| node.block_producer.clone())
+            .ok_or_else(|| anyhow::anyhow!("Block producer is not running"))
+    }
+
+    /// Seconds between devnet blocks; 0 stops interval mining
+    pub async fn set_block_interval(&self, seconds: u64) -> Result<MiningMode> {
+        let producer = self.devnet_producer().await?;
+        producer.set_block_interval(seconds);
+        Ok(producer.mining_mode())
+    }
+
+    /// Mine a devnet block as soon as a transaction enters the mempool
+    pub async fn set_automine(&self, enabled: bool) -> Result<MiningMode> {
+        let producer = self.devnet_producer().await?;
+        producer.set_automine(enabled);
+        Ok(producer.mining_mode())
+    }
+
+    /// Mine `count` devnet blocks now, returning their hashes
+    pub async fn mine_blocks(&self, count: u64) -> Result<Vec<String>> {
+        let producer = self.devnet_producer().await?;
+        let blocks = producer.mine_blocks(count).await?;
+        Ok(blocks
+            .iter()
+            .map(|block| format!("0x{}", hex::encode(block.header.block_hash.as_bytes())))
+            .collect())
+    }
+
     /// Execute an eth_call against the current state
     /// This is a read-only call that doesn't modify state
     pub async fn eth_call(&self, to: &str, data: &str) -> Result<String, String> {
@@ -1370,6 +2006,7 @@ impl NodeManager {
                 base_fee_per_gas: 1_000_000_000, // 1 gwei
                 gas_used: 0,
                 gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
             },
             state_root: Hash::default(),
             tx_root: Hash::default(),
@@ -1426,7 +2063,7 @@ impl NodeManager {
 /// Parse bootnode strings in formats like:
 /// - peer123@203.0.113.10:30303
 /// - 203.0.113.10:30303 (peer id will be generated)
-fn parse_bootnode(s: &str) -> Option<(citrate_network::peer::PeerId, SocketAddr)> {
+pub(crate) fn parse_bootnode(s: &str) -> Option<(citrate_network::peer::PeerId, SocketAddr)> {
     let (peer_part, addr_part) = if let Some((pid, rest)) = s.split_once('@') {
         (Some(pid.trim()), rest.trim())
     } else {
@@ -1495,6 +2132,7 @@ fn create_genesis_block() -> Block {
         base_fee_per_gas: 1_000_000_000, // 1 gwei initial base fee
         gas_used: 0,
         gas_limit: 30_000_000,
+        epoch_commitment: Hash::default(),
     };
 
     Block {
@@ -1610,6 +2248,7 @@ async fn run_block_producer(
             base_fee_per_gas: 1_000_000_000, // 1 gwei
             gas_used: 0,
             gas_limit: 30_000_000,
+            epoch_commitment: Hash::default(),
         };
 
         // Calculate block hash
@@ -1665,6 +2304,7 @@ struct CitrateNode {
     peer_manager: Arc<PeerManager>,
     _running: Arc<RwLock<bool>>,
     start_time: std::time::Instant,
+    block_producer: Option<crate::block_producer::BlockProducer>,
     block_producer_handle: Option<JoinHandle<()>>,
     block_producer_running: Option<Arc<RwLock<bool>>>,
     rpc_handles: Option<RpcHandles>,
@@ -1689,6 +2329,50 @@ pub struct NodeStatus {
     pub blue_score: u64,
     pub last_block_hash: Option<String>,
     pub last_block_timestamp: Option<u64>,
+    /// Newer client version that a majority of peers run, if any
+    pub upgrade_available: Option<String>,
+    /// RPC URL of the external node the GUI is attached to
+    #[serde(default)]
+    pub external_rpc: Option<String>,
+}
+
+impl NodeStatus {
+    fn stopped(network_id: String) -> Self {
+        Self {
+            running: false,
+            syncing: false,
+            block_height: 0,
+            peer_count: 0,
+            network_id,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            uptime: 0,
+            dag_tips: 0,
+            blue_score: 0,
+            last_block_hash: None,
+            last_block_timestamp: None,
+            upgrade_available: None,
+            external_rpc: None,
+        }
+    }
+}
+
+/// Tauri event emitted when a majority of peers run a newer client
+pub const UPGRADE_AVAILABLE_EVENT: &str = "upgrade-available";
+
+/// Payload of [`UPGRADE_AVAILABLE_EVENT`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpgradeNotice {
+    pub current_version: String,
+    pub latest_version: String,
+}
+
+impl UpgradeNotice {
+    pub fn new(latest_version: String) -> Self {
+        Self {
+            current_version: CLIENT_VERSION.to_string(),
+            latest_version,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1699,6 +2383,20 @@ pub struct PeerSummary {
     pub state: String,
     pub score: i32,
     pub last_seen_secs: u64,
+    pub client_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerReputationSummary {
+    pub ip: String,
+    pub peer_id: Option<String>,
+    pub score: f64,
+    pub connected: bool,
+    pub banned: bool,
+    pub banned_until: Option<u64>,
+    pub ban_reason: Option<String>,
+    pub ban_count: u32,
+    pub events: Vec<ReputationEvent>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1708,10 +2406,13 @@ pub struct TxActivity {
     pub to: Option<String>,
     pub value: String,
     pub nonce: u64,
-    pub status: String, // "pending" | "confirmed"
+    pub status: String, // "pending" | "confirmed" | "failed" | "reorged"
     pub block_hash: Option<String>,
     pub block_height: Option<u64>,
     pub timestamp: Option<u64>,
+    /// Value transfer made by a contract during this tx rather than the tx itself
+    #[serde(default)]
+    pub internal: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1756,6 +2457,9 @@ pub struct NodeConfig {
     #[serde(default)]
     pub mempool: MempoolSettings,
     pub consensus: ConsensusConfig,
+    /// RocksDB block cache, bloom filter and write buffer settings
+    #[serde(default)]
+    pub storage_tuning: DbTuning,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1801,8 +2505,15 @@ impl NodeConfig {
         if config_path.exists() {
             let config_str = std::fs::read_to_string(&config_path)?;
 
+            // Expand `${VAR}` and `${secret:name}` references first; a missing
+            // variable or passphrase is an error, not a reason to reset the config
+            let mut value = serde_json::from_str::<serde_json::Value>(&config_str)?;
+            let passphrase = config_secrets::passphrase_from_env()?;
+            config_secrets::resolve(&mut value, passphrase.as_deref())
+                .map_err(|e| anyhow::anyhow!("{}: {}", config_path.display(), e))?;
+
             // Try to load the config, if it fails due to missing fields, use default and save new format
-            match serde_json::from_str::<Self>(&config_str) {
+            match serde_json::from_value::<Self>(value) {
                 Ok(config) => Ok(config),
                 Err(e) => {
                     warn!("Failed to parse existing config: {}. Using default config and backing up old one.", e);
@@ -1832,16 +2543,27 @@ impl NodeConfig {
         if let Some(parent) = config_path.parent() {
             std::fs::create_dir_all(parent)?;
         }
-        let config_str = serde_json::to_string_pretty(self)?;
+        let mut value = serde_json::to_value(self)?;
+        // Keep references from the file on disk so resolved environment
+        // values and decrypted secrets are never written back out
+        if let Some(raw) = std::fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        {
+            let mut resolved = raw.clone();
+            let passphrase = config_secrets::passphrase_from_env().ok().flatten();
+            if config_secrets::resolve(&mut resolved, passphrase.as_deref()).is_err() {
+                resolved = raw.clone();
+            }
+            config_secrets::preserve_references(&raw, &resolved, &mut value);
+        }
+        let config_str = serde_json::to_string_pretty(&value)?;
         std::fs::write(config_path, config_str)?;
         Ok(())
     }
 
     fn config_path() -> PathBuf {
-        dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("citrate-core")
-            .join("config.json")
+        crate::profiles::config_root().join("config.json")
     }
 
     /// Configure node for testnet connection
@@ -1930,9 +2652,7 @@ impl NodeConfig {
 impl Default for NodeConfig {
     fn default() -> Self {
         Self {
-            data_dir: dirs::data_dir()
-                .unwrap_or_else(|| PathBuf::from("."))
-                .join("citrate-gui")
+            data_dir: crate::profiles::default_node_data_dir()
                 .to_string_lossy()
                 .to_string(),
             network: "devnet".to_string(),
@@ -1963,6 +2683,7 @@ impl Default for NodeConfig {
                 block_time_seconds: 2,
                 finality_depth: 100,
             },
+            storage_tuning: DbTuning::default(),
         }
     }
 }
@@ -0,0 +1,353 @@
+//! Named wallet profiles
+//!
+//! Each profile (e.g. "mainnet", "testnet-dev") has its own wallet keystore,
+//! address book, tracked addresses, node configuration and node data dir.
+//! The "default" profile keeps the locations used before profiles existed,
+//! so existing installs carry on as the default profile. Other profiles live
+//! under `profiles/<name>` in the app data and config dirs, and keep their
+//! OS keychain entries under a service of their own.
+//!
+//! Path helpers resolve against the active profile, so managers pick up the
+//! new locations when they are reloaded after a switch.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Emitted with the new `ProfileInfo` after a profile switch
+pub const PROFILE_CHANGED_EVENT: &str = "profile-changed";
+
+/// Profile list, stored in the app data dir
+const PROFILES_FILE: &str = "profiles.json";
+
+const APP_DIR: &str = "citrate-core";
+const KEYRING_SERVICE: &str = "citrate-core";
+
+/// Longest accepted profile name
+const MAX_PROFILE_NAME_LEN: usize = 32;
+
+static ACTIVE_PROFILE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Name of the active profile
+pub fn active_profile() -> String {
+    ACTIVE_PROFILE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+/// Make `name` the profile the path helpers resolve against
+pub fn set_active_profile(name: &str) {
+    *ACTIVE_PROFILE.write().unwrap_or_else(|e| e.into_inner()) = Some(name.to_string());
+}
+
+/// App data dir shared by all profiles
+pub fn app_root() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(APP_DIR)
+}
+
+/// Data dir of `profile`: keystore, accounts, contacts, tracked addresses
+pub fn profile_data_dir(profile: &str) -> PathBuf {
+    if profile == DEFAULT_PROFILE {
+        app_root()
+    } else {
+        app_root().join("profiles").join(profile)
+    }
+}
+
+/// Config dir of `profile`, holding its node configuration
+pub fn profile_config_dir(profile: &str) -> PathBuf {
+    let root = dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(APP_DIR);
+    if profile == DEFAULT_PROFILE {
+        root
+    } else {
+        root.join("profiles").join(profile)
+    }
+}
+
+/// Data dir of the active profile
+pub fn data_root() -> PathBuf {
+    profile_data_dir(&active_profile())
+}
+
+/// Config dir of the active profile
+pub fn config_root() -> PathBuf {
+    profile_config_dir(&active_profile())
+}
+
+/// Node data dir used when the active profile's node config doesn't set one
+pub fn default_node_data_dir() -> PathBuf {
+    profile_node_data_dir(&active_profile())
+}
+
+/// Node data dir used when the node config of `profile` doesn't set one
+pub fn profile_node_data_dir(profile: &str) -> PathBuf {
+    if profile == DEFAULT_PROFILE {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("citrate-gui")
+    } else {
+        profile_data_dir(profile).join("node")
+    }
+}
+
+/// OS keychain service the keys of `profile` are stored under
+pub fn keyring_service(profile: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        KEYRING_SERVICE.to_string()
+    } else {
+        format!("{}.{}", KEYRING_SERVICE, profile)
+    }
+}
+
+/// Lowercase letters, digits, `-` and `_`, starting with a letter or digit
+pub fn validate_profile_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > MAX_PROFILE_NAME_LEN {
+        return Err(format!(
+            "Profile name must be 1 to {} characters",
+            MAX_PROFILE_NAME_LEN
+        ));
+    }
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if !valid_chars || name.starts_with(['-', '_']) {
+        return Err(format!(
+            "Invalid profile name '{}': use lowercase letters, digits, '-' and '_'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// A named profile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub name: String,
+    /// Unix seconds
+    pub created_at: u64,
+}
+
+/// A profile as reported to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileInfo {
+    pub name: String,
+    pub created_at: u64,
+    pub active: bool,
+    pub data_dir: String,
+    pub config_dir: String,
+}
+
+impl ProfileInfo {
+    fn new(profile: &Profile, active: bool) -> Self {
+        Self {
+            name: profile.name.clone(),
+            created_at: profile.created_at,
+            active,
+            data_dir: profile_data_dir(&profile.name).to_string_lossy().to_string(),
+            config_dir: profile_config_dir(&profile.name)
+                .to_string_lossy()
+                .to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProfileIndex {
+    active: String,
+    profiles: Vec<Profile>,
+}
+
+impl Default for ProfileIndex {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_PROFILE.to_string(),
+            profiles: vec![Profile {
+                name: DEFAULT_PROFILE.to_string(),
+                created_at: 0,
+            }],
+        }
+    }
+}
+
+/// The list of profiles and which one is active
+pub struct ProfileStore {
+    path: PathBuf,
+    index: ProfileIndex,
+}
+
+impl ProfileStore {
+    /// Open the profile list in the app data dir
+    pub fn open() -> Self {
+        Self::load(app_root().join(PROFILES_FILE))
+    }
+
+    /// Load the profile list at `path`, starting with only the default
+    /// profile if it is missing or unreadable
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let mut index: ProfileIndex = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable profile list {:?}: {}", path, e);
+                ProfileIndex::default()
+            }),
+            Err(_) => ProfileIndex::default(),
+        };
+        if !index.profiles.iter().any(|p| p.name == DEFAULT_PROFILE) {
+            index.profiles.insert(0, ProfileIndex::default().profiles.remove(0));
+        }
+        if !index.profiles.iter().any(|p| p.name == index.active) {
+            warn!("Active profile '{}' no longer exists", index.active);
+            index.active = DEFAULT_PROFILE.to_string();
+        }
+        Self { path, index }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+        }
+        let json = serde_json::to_vec_pretty(&self.index)
+            .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+        std::fs::write(&self.path, json).map_err(|e| format!("Failed to save profiles: {}", e))
+    }
+
+    pub fn active(&self) -> &str {
+        &self.index.active
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.index.profiles.iter().find(|p| p.name == name)
+    }
+
+    pub fn list(&self) -> Vec<ProfileInfo> {
+        self.index
+            .profiles
+            .iter()
+            .map(|p| ProfileInfo::new(p, p.name == self.index.active))
+            .collect()
+    }
+
+    pub fn create(&mut self, name: &str) -> Result<ProfileInfo, String> {
+        validate_profile_name(name)?;
+        if self.get(name).is_some() {
+            return Err(format!("Profile '{}' already exists", name));
+        }
+        let profile = Profile {
+            name: name.to_string(),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        };
+        self.index.profiles.push(profile.clone());
+        self.save()?;
+        Ok(ProfileInfo::new(&profile, false))
+    }
+
+    /// Record `name` as the active profile. The caller reloads the managers.
+    pub fn set_active(&mut self, name: &str) -> Result<ProfileInfo, String> {
+        let profile = self
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("Profile '{}' not found", name))?;
+        self.index.active = profile.name.clone();
+        self.save()?;
+        Ok(ProfileInfo::new(&profile, true))
+    }
+
+    /// Forget `name`. Its files are left to the caller, see
+    /// `remove_profile_dirs`.
+    pub fn remove(&mut self, name: &str) -> Result<Profile, String> {
+        if name == DEFAULT_PROFILE {
+            return Err("The default profile can't be deleted".to_string());
+        }
+        if name == self.index.active {
+            return Err("Switch to another profile before deleting this one".to_string());
+        }
+        let position = self
+            .index
+            .profiles
+            .iter()
+            .position(|p| p.name == name)
+            .ok_or_else(|| format!("Profile '{}' not found", name))?;
+        let profile = self.index.profiles.remove(position);
+        self.save()?;
+        Ok(profile)
+    }
+}
+
+/// Delete the data and config dirs of a non-default profile
+pub fn remove_profile_dirs(name: &str) -> Result<(), String> {
+    if name == DEFAULT_PROFILE {
+        return Err("The default profile's data is shared and can't be removed".to_string());
+    }
+    for dir in [profile_data_dir(name), profile_config_dir(name)] {
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)
+                .map_err(|e| format!("Failed to remove {:?}: {}", dir, e))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_names_and_locations() {
+        assert!(validate_profile_name("testnet-dev").is_ok());
+        assert!(validate_profile_name("main_2").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("Mainnet").is_err());
+        assert!(validate_profile_name("../x").is_err());
+        assert!(validate_profile_name("-x").is_err());
+        assert!(validate_profile_name(&"a".repeat(33)).is_err());
+
+        assert_eq!(profile_data_dir(DEFAULT_PROFILE), app_root());
+        assert_eq!(
+            profile_data_dir("dev"),
+            app_root().join("profiles").join("dev")
+        );
+        assert_eq!(keyring_service(DEFAULT_PROFILE), "citrate-core");
+        assert_eq!(keyring_service("dev"), "citrate-core.dev");
+    }
+
+    #[test]
+    fn test_create_switch_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PROFILES_FILE);
+        let mut store = ProfileStore::load(&path);
+        assert_eq!(store.active(), DEFAULT_PROFILE);
+        assert_eq!(store.list().len(), 1);
+
+        store.create("mainnet").unwrap();
+        assert!(store.create("mainnet").is_err());
+        assert!(store.set_active("missing").is_err());
+        assert!(store.set_active("mainnet").unwrap().active);
+
+        let reloaded = ProfileStore::load(&path);
+        assert_eq!(reloaded.active(), "mainnet");
+        let names: Vec<String> = reloaded.list().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["default", "mainnet"]);
+
+        assert!(store.remove("mainnet").is_err());
+        assert!(store.remove(DEFAULT_PROFILE).is_err());
+        store.set_active(DEFAULT_PROFILE).unwrap();
+        store.remove("mainnet").unwrap();
+        assert!(ProfileStore::load(&path).get("mainnet").is_none());
+    }
+}
@@ -1,5 +1,14 @@
 #![allow(dead_code)]
 use anyhow::{anyhow, Result};
+use citrate_api::types::{
+    BlockId, BlockResponse, NodeStatusResponse, PeerInfoResponse, PeerReputationResponse,
+    TransactionResponse,
+};
+use citrate_consensus::types::Hash;
+use citrate_storage::chain::{ChainStatsSnapshot, DagStatsBucket};
+use citrate_storage::db::DbStats;
+use citrate_storage::pruning::PruningStats;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde_json::{json, Value};
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -191,6 +200,96 @@ impl RpcClient {
         Ok(result_hex.to_string())
     }
 
+    async fn call_as<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let result = self.call(method, params).await?;
+        serde_json::from_value(result)
+            .map_err(|e| anyhow!("Invalid {} response: {}", method, e))
+    }
+
+    /// Head, DAG, peer and mempool summary of the node
+    pub async fn get_node_status(&self) -> Result<NodeStatusResponse> {
+        self.call_as("admin_nodeStatus", json!([])).await
+    }
+
+    pub async fn get_peer_info(&self) -> Result<Vec<PeerInfoResponse>> {
+        self.call_as("net_peerInfo", json!([])).await
+    }
+
+    pub async fn get_peer_reputation(&self) -> Result<Vec<PeerReputationResponse>> {
+        self.call_as("admin_peerReputation", json!([])).await
+    }
+
+    pub async fn get_mempool_pending(&self, limit: usize) -> Result<Vec<TransactionResponse>> {
+        self.call_as("mempool_getPending", json!([limit])).await
+    }
+
+    pub async fn get_dag_tips(&self) -> Result<Vec<Hash>> {
+        self.call_as("chain_getTips", json!([])).await
+    }
+
+    /// Block by hash or height, `None` if the node doesn't have it
+    pub async fn get_block(&self, id: BlockId) -> Result<Option<BlockResponse>> {
+        // chain_getBlock answers null for unknown blocks, which `call` rejects
+        match self.call("chain_getBlock", json!([id])).await {
+            Ok(value) => serde_json::from_value(value)
+                .map_err(|e| anyhow!("Invalid chain_getBlock response: {}", e)),
+            Err(e) if e.to_string() == "Empty RPC response" => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn get_dag_stats_series(
+        &self,
+        from: Option<u64>,
+        to: Option<u64>,
+        bucket_secs: Option<u64>,
+    ) -> Result<Vec<DagStatsBucket>> {
+        // Absent fields take the node's defaults; null is rejected
+        let mut params = serde_json::Map::new();
+        for (key, value) in [("from", from), ("to", to), ("bucketSecs", bucket_secs)] {
+            if let Some(value) = value {
+                params.insert(key.to_string(), json!(value));
+            }
+        }
+        self.call_as("citrate_getDagStatsSeries", Value::Object(params))
+            .await
+    }
+
+    pub async fn get_stats_history(
+        &self,
+        from: Option<u64>,
+        to: Option<u64>,
+    ) -> Result<Vec<ChainStatsSnapshot>> {
+        let mut params = serde_json::Map::new();
+        for (key, value) in [("from", from), ("to", to)] {
+            if let Some(value) = value {
+                params.insert(key.to_string(), json!(value));
+            }
+        }
+        self.call_as("citrate_getStatsHistory", Value::Object(params))
+            .await
+    }
+
+    pub async fn get_db_stats(&self) -> Result<DbStats> {
+        self.call_as("admin_dbStats", json!([])).await
+    }
+
+    /// Start compacting one column family, or all of them, on the node
+    pub async fn compact_db(&self, column_family: Option<&str>) -> Result<Vec<String>> {
+        let params = match column_family {
+            Some(cf) => json!([cf]),
+            None => json!([]),
+        };
+        let result: Value = self.call_as("admin_compactDb", params).await?;
+        serde_json::from_value(result["compacting"].clone())
+            .map_err(|e| anyhow!("Invalid admin_compactDb response: {}", e))
+    }
+
+    /// What the node's next prune would remove, without pruning
+    pub async fn prune_preview(&self) -> Result<PruningStats> {
+        self.call_as("admin_prunePreview", json!([])).await
+    }
+
     /// Check if the RPC endpoint is accessible
     pub async fn health_check(&self) -> Result<()> {
         // Try to get chain ID as a simple health check
@@ -3,13 +3,18 @@
 
 use anyhow::Result;
 use citrate_consensus::types::{Block, Hash};
-use citrate_network::{NetworkMessage, PeerManager};
+use citrate_network::sync_progress::DEFAULT_RATE_WINDOW;
+use citrate_network::{NetworkMessage, PeerManager, SyncProgress, SyncStage, SyncStages};
 use citrate_storage::StorageManager;
 use std::collections::{HashSet, VecDeque};
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+/// Tauri event carrying a [`SyncProgress`] while the node catches up
+pub const SYNC_PROGRESS_EVENT: &str = "sync-progress";
+
 /// Configuration for sync behavior
 #[derive(Clone)]
 pub struct SyncConfig {
@@ -59,12 +64,16 @@ pub struct IterativeSyncManager {
     sync_state: Arc<RwLock<SyncState>>,
     /// Failed blocks for retry
     failed_blocks: Arc<RwLock<VecDeque<BlockWithRetry>>>,
+    /// Blocks received and stored, with rates
+    stages: Arc<RwLock<SyncStages>>,
 }
 
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct SyncState {
     pub syncing: bool,
+    /// Local height when syncing started
+    pub start_height: u64,
     pub current_height: u64,
     pub target_height: u64,
     pub processed_blocks: usize,
@@ -86,6 +95,7 @@ impl IterativeSyncManager {
             seen_blocks: Arc::new(RwLock::new(HashSet::new())),
             sync_state: Arc::new(RwLock::new(SyncState {
                 syncing: false,
+                start_height: 0,
                 current_height: 0,
                 target_height: 0,
                 processed_blocks: 0,
@@ -93,6 +103,10 @@ impl IterativeSyncManager {
                 pending_count: 0,
             })),
             failed_blocks: Arc::new(RwLock::new(VecDeque::new())),
+            stages: Arc::new(RwLock::new(SyncStages::new(
+                &[SyncStage::Blocks, SyncStage::Execution],
+                DEFAULT_RATE_WINDOW,
+            ))),
         }
     }
 
@@ -105,6 +119,8 @@ impl IterativeSyncManager {
             let mut state = self.sync_state.write().await;
             state.syncing = true;
             state.current_height = self.storage.blocks.get_latest_height().unwrap_or(0);
+            state.start_height = state.current_height;
+            self.stages.write().await.reset(0);
         }
 
         // Start sync loop
@@ -115,6 +131,7 @@ impl IterativeSyncManager {
         let seen_blocks = self.seen_blocks.clone();
         let sync_state = self.sync_state.clone();
         let failed_blocks = self.failed_blocks.clone();
+        let stages = self.stages.clone();
 
         tokio::spawn(async move {
             loop {
@@ -126,6 +143,7 @@ impl IterativeSyncManager {
                     seen_blocks.clone(),
                     sync_state.clone(),
                     failed_blocks.clone(),
+                    stages.clone(),
                 )
                 .await
                 {
@@ -143,6 +161,7 @@ impl IterativeSyncManager {
     }
 
     /// Single sync iteration - request blocks from peers
+    #[allow(clippy::too_many_arguments)]
     async fn sync_iteration(
         storage: Arc<StorageManager>,
         peer_manager: Arc<PeerManager>,
@@ -151,14 +170,26 @@ impl IterativeSyncManager {
         _seen_blocks: Arc<RwLock<HashSet<Hash>>>,
         sync_state: Arc<RwLock<SyncState>>,
         failed_blocks: Arc<RwLock<VecDeque<BlockWithRetry>>>,
+        stages: Arc<RwLock<SyncStages>>,
     ) -> Result<()> {
         let current_height = storage.blocks.get_latest_height().unwrap_or(0);
 
+        // Peers report their tip in the handshake
+        let mut peer_height = 0;
+        for peer in peer_manager.get_all_peers() {
+            peer_height = peer_height.max(peer.info.read().await.head_height);
+        }
+
         // Update sync state
         {
             let mut state = sync_state.write().await;
             state.current_height = current_height;
+            state.target_height = state.target_height.max(peer_height).max(current_height);
             state.pending_count = pending_blocks.read().await.len();
+            stages
+                .write()
+                .await
+                .extend_total(state.target_height.saturating_sub(state.start_height));
         }
 
         // Check if we need more blocks
@@ -223,6 +254,7 @@ impl IterativeSyncManager {
         let seen_blocks = self.seen_blocks.clone();
         let sync_state = self.sync_state.clone();
         let failed_blocks = self.failed_blocks.clone();
+        let stages = self.stages.clone();
         let max_retries = self.config.max_retries;
 
         tokio::spawn(async move {
@@ -239,6 +271,11 @@ impl IterativeSyncManager {
                     {
                         Ok(processed) => {
                             if processed {
+                                stages.write().await.record(
+                                    SyncStage::Execution,
+                                    1,
+                                    Instant::now(),
+                                );
                                 let mut state = sync_state.write().await;
                                 state.processed_blocks += 1;
                                 debug!(
@@ -401,10 +438,14 @@ impl IterativeSyncManager {
     pub async fn handle_blocks(&self, blocks: Vec<Block>) -> Result<()> {
         let mut pending = self.pending_blocks.write().await;
         let mut seen = self.seen_blocks.write().await;
+        let mut received = 0;
+        let mut highest = 0;
 
         for block in blocks {
             if !seen.contains(&block.header.block_hash) {
                 seen.insert(block.header.block_hash);
+                received += 1;
+                highest = highest.max(block.header.height);
                 pending.push_back(BlockWithRetry { block, retries: 0 });
 
                 // Limit queue size
@@ -415,6 +456,17 @@ impl IterativeSyncManager {
             }
         }
 
+        drop(seen);
+        drop(pending);
+
+        if received > 0 {
+            let mut state = self.sync_state.write().await;
+            state.target_height = state.target_height.max(highest);
+            let mut stages = self.stages.write().await;
+            stages.extend_total(state.target_height.saturating_sub(state.start_height));
+            stages.record(SyncStage::Blocks, received, Instant::now());
+        }
+
         Ok(())
     }
 
@@ -424,6 +476,18 @@ impl IterativeSyncManager {
         self.sync_state.read().await.clone()
     }
 
+    /// Progress towards the highest height seen from peers, with the
+    /// received and stored stages broken out
+    pub async fn progress(&self) -> SyncProgress {
+        let current_height = self.storage.blocks.get_latest_height().unwrap_or(0);
+        let target_height = self.sync_state.read().await.target_height;
+        self.stages.read().await.snapshot(
+            current_height,
+            target_height.max(current_height),
+            Instant::now(),
+        )
+    }
+
     /// Stop syncing
     #[allow(dead_code)]
     pub async fn stop_sync(&self) {
@@ -0,0 +1,217 @@
+//! Command Capture
+//!
+//! Runs a single command inside an interactive shell session and recovers
+//! its output. The command is wrapped between two marker lines so its output
+//! can be cut out of the PTY stream, which also carries the shell's echo and
+//! prompts. Stderr is redirected to a temporary file so it can be reported
+//! separately from stdout.
+//!
+//! The wrapping assumes a POSIX shell.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Bytes of PTY output kept from the start of a command
+const HEAD_BYTES: usize = 256 * 1024;
+
+/// Bytes of PTY output kept from the end of a command
+const TAIL_BYTES: usize = 256 * 1024;
+
+/// Output of a command run through [`super::TerminalManager::run_command`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandCapture {
+    pub session_id: String,
+    pub command: String,
+    /// Exit status; `None` if the command timed out
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub timed_out: bool,
+    /// Bytes dropped from the middle of a very long output
+    pub omitted_bytes: usize,
+    pub duration_ms: u64,
+}
+
+/// Markers delimiting one command's output
+pub(crate) struct Markers {
+    begin: String,
+    end: String,
+}
+
+impl Markers {
+    pub(crate) fn new(nonce: &str) -> Self {
+        Self {
+            begin: format!("__CITRATE_BEGIN_{}", nonce),
+            end: format!("__CITRATE_END_{}", nonce),
+        }
+    }
+
+    /// The line written to the shell. The markers are printed in two parts
+    /// so the shell's echo of this line never matches them.
+    pub(crate) fn wrap(&self, command: &str, stderr_path: &Path) -> String {
+        let (begin_tag, nonce) = self.begin.rsplit_once('_').unwrap_or((&self.begin, ""));
+        let (end_tag, _) = self.end.rsplit_once('_').unwrap_or((&self.end, ""));
+        format!(
+            "printf '%s_%s\\n' {} {}; {{ {}\n}} 2>'{}'; printf '\\n%s_%s %d\\n' {} {} $?\n",
+            begin_tag,
+            nonce,
+            command,
+            stderr_path.display().to_string().replace('\'', "'\\''"),
+            end_tag,
+            nonce
+        )
+    }
+
+    /// The exit code, once the end marker line has arrived in `text`
+    pub(crate) fn exit_code(&self, text: &str) -> Option<i32> {
+        let start = text.find(&format!("{} ", self.end))? + self.end.len() + 1;
+        let rest = &text[start..];
+        let line_end = rest.find(['\r', '\n'])?;
+        rest[..line_end].trim().parse().ok()
+    }
+
+    /// The command's stdout out of the cleaned PTY stream
+    pub(crate) fn stdout<'a>(&self, text: &'a str) -> &'a str {
+        let start = text
+            .find(&format!("{}\n", self.begin))
+            .map(|i| i + self.begin.len() + 1)
+            .unwrap_or(0);
+        let end = text[start..]
+            .find(&format!("\n{} ", self.end))
+            .map(|i| start + i)
+            .unwrap_or(text.len());
+        &text[start..end]
+    }
+}
+
+/// PTY output collected while a command runs. Very long outputs keep their
+/// beginning and end and drop the middle.
+#[derive(Default)]
+pub(crate) struct CaptureBuffer {
+    head: Vec<u8>,
+    tail: Vec<u8>,
+    omitted: usize,
+}
+
+impl CaptureBuffer {
+    pub(crate) fn push(&mut self, mut data: &[u8]) {
+        if self.head.len() < HEAD_BYTES {
+            let take = data.len().min(HEAD_BYTES - self.head.len());
+            self.head.extend_from_slice(&data[..take]);
+            data = &data[take..];
+        }
+        self.tail.extend_from_slice(data);
+        if self.tail.len() > TAIL_BYTES {
+            let excess = self.tail.len() - TAIL_BYTES;
+            self.tail.drain(..excess);
+            self.omitted += excess;
+        }
+    }
+
+    /// The last `n` bytes received
+    pub(crate) fn recent(&self, n: usize) -> String {
+        let mut bytes = Vec::with_capacity(n);
+        let from_tail = self.tail.len().min(n);
+        let from_head = (n - from_tail).min(self.head.len());
+        bytes.extend_from_slice(&self.head[self.head.len() - from_head..]);
+        bytes.extend_from_slice(&self.tail[self.tail.len() - from_tail..]);
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    pub(crate) fn omitted(&self) -> usize {
+        self.omitted
+    }
+
+    /// Everything kept, cleaned of terminal control sequences
+    pub(crate) fn text(&self) -> String {
+        let mut text = strip_ansi(&String::from_utf8_lossy(&self.head));
+        if self.omitted > 0 {
+            text.push_str(&format!("\n... ({} bytes omitted) ...\n", self.omitted));
+        }
+        text.push_str(&strip_ansi(&String::from_utf8_lossy(&self.tail)));
+        text
+    }
+}
+
+/// Remove ANSI escape sequences and carriage returns
+pub fn strip_ansi(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => match chars.next() {
+                // CSI: parameters, then a final byte in @..~
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: terminated by BEL or ESC \
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\u{7}' {
+                            break;
+                        }
+                        if c == '\u{1b}' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\r' => {}
+            c if c.is_control() && c != '\n' && c != '\t' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markers_cut_out_command_output() {
+        let markers = Markers::new("ab12");
+        let wrapped = markers.wrap("cargo build", Path::new("/tmp/x.err"));
+        // The echoed command line must not look like either marker
+        assert!(!wrapped.contains("__CITRATE_BEGIN_ab12"));
+        assert!(!wrapped.contains("__CITRATE_END_ab12"));
+
+        let stream = format!(
+            "$ {}\x1b[32m__CITRATE_BEGIN_ab12\r\nCompiling\r\nFinished\r\n\r\n__CITRATE_END_ab12 101\r\n$ ",
+            wrapped
+        );
+        let text = strip_ansi(&stream);
+        assert_eq!(markers.exit_code(&text), Some(101));
+        assert_eq!(markers.stdout(&text), "Compiling\nFinished\n");
+
+        // Not finished until the whole end line has arrived
+        assert_eq!(markers.exit_code("__CITRATE_END_ab12 1"), None);
+    }
+
+    #[test]
+    fn test_capture_buffer_keeps_both_ends() {
+        let mut buffer = CaptureBuffer::default();
+        buffer.push(&vec![b'a'; HEAD_BYTES]);
+        buffer.push(&vec![b'b'; TAIL_BYTES + 10]);
+        buffer.push(b"end");
+        assert_eq!(buffer.omitted(), 13);
+        assert!(buffer.recent(5).ends_with("bbend"));
+        let text = buffer.text();
+        assert!(text.starts_with('a') && text.ends_with("end"));
+        assert!(text.contains("(13 bytes omitted)"));
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(
+            strip_ansi("\x1b[1;31merror\x1b[0m: x\r\n\x1b]0;title\x07ok\x08"),
+            "error: x\nok"
+        );
+    }
+}
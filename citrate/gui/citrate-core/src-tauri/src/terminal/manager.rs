@@ -2,19 +2,28 @@
 //!
 //! Manages multiple terminal sessions.
 
+use super::capture::{CaptureBuffer, CommandCapture, Markers};
 use super::{TerminalConfig, TerminalInfo, TerminalOutput, TerminalSession};
 use anyhow::{anyhow, Result};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
-use tokio::sync::RwLock;
-use tracing::{debug, error, info};
+use tokio::sync::{mpsc, RwLock};
+use tracing::{debug, error, info, warn};
+
+/// Largest stderr read back from a captured command
+const MAX_STDERR_BYTES: usize = 256 * 1024;
+
+/// Copies of a session's output, for a command being captured
+type OutputTaps = Arc<RwLock<HashMap<String, mpsc::UnboundedSender<Vec<u8>>>>>;
 
 /// Terminal Manager
 ///
 /// Manages all terminal sessions and their lifecycle.
 pub struct TerminalManager {
     sessions: Arc<RwLock<HashMap<String, Arc<TerminalSession>>>>,
+    taps: OutputTaps,
     app_handle: Option<AppHandle>,
 }
 
@@ -23,6 +32,7 @@ impl TerminalManager {
     pub fn new() -> Self {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            taps: Arc::new(RwLock::new(HashMap::new())),
             app_handle: None,
         }
     }
@@ -76,12 +86,16 @@ impl TerminalManager {
     /// Start forwarding output from terminal to frontend
     fn start_output_forwarding(&self, session: Arc<TerminalSession>) {
         let app_handle = self.app_handle.clone();
+        let taps = self.taps.clone();
         let session_id = session.id.clone();
 
         tokio::spawn(async move {
             loop {
                 match session.recv_output().await {
                     Some(data) => {
+                        if let Some(tap) = taps.read().await.get(&session_id) {
+                            let _ = tap.send(data.clone());
+                        }
                         let output = TerminalOutput::new(&session_id, &data);
 
                         // Emit to frontend
@@ -118,6 +132,97 @@ impl TerminalManager {
         session.write(data).await
     }
 
+    /// Run `command` in a session and capture its output. The session's
+    /// output is still forwarded to the frontend while the command runs, so
+    /// the user can watch it. On timeout the command is interrupted.
+    pub async fn run_command(
+        &self,
+        session_id: &str,
+        command: &str,
+        timeout: Duration,
+    ) -> Result<CommandCapture> {
+        if cfg!(windows) {
+            return Err(anyhow!("Capturing commands requires a POSIX shell"));
+        }
+        let session = self
+            .sessions
+            .read()
+            .await
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Session not found: {}", session_id))?;
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        {
+            let mut taps = self.taps.write().await;
+            if taps.contains_key(session_id) {
+                return Err(anyhow!(
+                    "Session {} is already running a command",
+                    session_id
+                ));
+            }
+            taps.insert(session_id.to_string(), tx);
+        }
+
+        let nonce = format!("{:08x}", rand::random::<u32>());
+        let markers = Markers::new(&nonce);
+        let stderr_path =
+            std::env::temp_dir().join(format!("citrate-{}-{}.err", session_id, nonce));
+        let started = Instant::now();
+        let mut buffer = CaptureBuffer::default();
+
+        let result = async {
+            session
+                .write(markers.wrap(command, &stderr_path).as_bytes())
+                .await?;
+            let finished = tokio::time::timeout(timeout, async {
+                while let Some(data) = rx.recv().await {
+                    buffer.push(&data);
+                    // The end marker is short, so only recent output needs a look
+                    if let Some(code) = markers.exit_code(&buffer.recent(data.len() + 256)) {
+                        return Some(code);
+                    }
+                }
+                None
+            })
+            .await;
+            Ok::<_, anyhow::Error>(finished)
+        }
+        .await;
+        self.taps.write().await.remove(session_id);
+
+        let exit_code = match result? {
+            Ok(Some(code)) => Some(code),
+            Ok(None) => return Err(anyhow!("Session {} closed", session_id)),
+            Err(_) => {
+                warn!("Command in {} timed out: {}", session_id, command);
+                // Ctrl-C
+                let _ = session.write(&[0x03]).await;
+                None
+            }
+        };
+
+        let stdout = markers.stdout(&buffer.text()).to_string();
+        let stderr = match tokio::fs::read(&stderr_path).await {
+            Ok(bytes) => {
+                let _ = tokio::fs::remove_file(&stderr_path).await;
+                String::from_utf8_lossy(&bytes[..bytes.len().min(MAX_STDERR_BYTES)]).into_owned()
+            }
+            Err(_) => String::new(),
+        };
+
+        Ok(CommandCapture {
+            session_id: session_id.to_string(),
+            command: command.to_string(),
+            exit_code,
+            stdout,
+            stderr,
+            timed_out: exit_code.is_none(),
+            omitted_bytes: buffer.omitted(),
+            duration_ms: started.elapsed().as_millis() as u64,
+        })
+    }
+
     /// Resize a terminal session
     pub async fn resize_session(&self, session_id: &str, cols: u16, rows: u16) -> Result<()> {
         let sessions = self.sessions.read().await;
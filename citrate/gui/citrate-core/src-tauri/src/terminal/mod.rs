@@ -4,9 +4,11 @@
 //!
 //! Provides PTY-based terminal functionality using portable-pty.
 
+pub mod capture;
 pub mod manager;
 pub mod session;
 
+pub use capture::CommandCapture;
 pub use manager::TerminalManager;
 pub use session::{TerminalSession, TerminalConfig};
 
@@ -0,0 +1,549 @@
+//! Signed auto-updates for the desktop app
+//!
+//! The updater polls the release manifest of the pinned channel, downloads
+//! the binary for this platform in the background and checks an ed25519
+//! signature over it against the release key compiled into the app. A
+//! verified binary is staged and swapped in when the user restarts.
+//!
+//! Manifests live at `<manifest_base>/<channel>.json`:
+//!
+//! ```json
+//! {
+//!   "version": "1.2.0",
+//!   "notes": "Release notes",
+//!   "pub_date": "2025-06-01T00:00:00Z",
+//!   "platforms": {
+//!     "linux-x86_64": { "url": "https://...", "signature": "<hex>", "size": 1234 }
+//!   }
+//! }
+//! ```
+//!
+//! Builds without `CITRATE_RELEASE_PUBLIC_KEY` set at compile time can
+//! check for updates but refuse to install them.
+
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use futures::StreamExt;
+use reqwest::Client;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{broadcast, Mutex, RwLock};
+use tracing::{info, warn};
+
+/// Tauri event carrying [`UpdateStatus`] changes to the frontend
+pub const UPDATER_EVENT: &str = "updater-event";
+
+/// How often the background task looks for a new release
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Hex-encoded ed25519 key release binaries are signed with
+const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("CITRATE_RELEASE_PUBLIC_KEY");
+
+/// Progress is reported at most once per this many bytes
+const PROGRESS_STEP: u64 = 512 * 1024;
+
+fn default_manifest_base() -> String {
+    option_env!("CITRATE_UPDATE_URL")
+        .unwrap_or("https://releases.citrate.ai/core")
+        .to_string()
+}
+
+fn default_auto_download() -> bool {
+    true
+}
+
+/// Release channel the app follows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+    Nightly,
+}
+
+impl UpdateChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "stable",
+            UpdateChannel::Beta => "beta",
+            UpdateChannel::Nightly => "nightly",
+        }
+    }
+}
+
+/// Persisted updater settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdaterConfig {
+    #[serde(default)]
+    pub channel: UpdateChannel,
+    /// Base URL of the per-channel manifests
+    #[serde(default = "default_manifest_base")]
+    pub manifest_base: String,
+    /// Download new releases as soon as they are found
+    #[serde(default = "default_auto_download")]
+    pub auto_download: bool,
+}
+
+impl Default for UpdaterConfig {
+    fn default() -> Self {
+        Self {
+            channel: UpdateChannel::default(),
+            manifest_base: default_manifest_base(),
+            auto_download: default_auto_download(),
+        }
+    }
+}
+
+impl UpdaterConfig {
+    fn path() -> PathBuf {
+        updater_dir().join("updater.json")
+    }
+
+    fn load() -> Self {
+        std::fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| e.to_string())
+    }
+}
+
+fn updater_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("citrate-core")
+}
+
+/// Manifest key for this build, e.g. `linux-x86_64`
+pub fn platform_key() -> String {
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseManifest {
+    version: String,
+    #[serde(default)]
+    notes: String,
+    #[serde(default)]
+    pub_date: Option<String>,
+    platforms: HashMap<String, PlatformRelease>,
+}
+
+/// Binary published for one platform
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlatformRelease {
+    pub url: String,
+    /// Hex-encoded ed25519 signature over the binary
+    pub signature: String,
+    #[serde(default)]
+    pub size: Option<u64>,
+}
+
+/// A release newer than the running app
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseInfo {
+    pub version: String,
+    pub current_version: String,
+    pub channel: UpdateChannel,
+    pub notes: String,
+    pub pub_date: Option<String>,
+    pub artifact: PlatformRelease,
+}
+
+/// Where the updater is; emitted on every change
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum UpdateStatus {
+    Idle,
+    Checking,
+    UpToDate {
+        version: String,
+    },
+    Available(ReleaseInfo),
+    Downloading {
+        version: String,
+        downloaded: u64,
+        total: Option<u64>,
+    },
+    /// Verified and staged; installed on restart
+    Ready(ReleaseInfo),
+    Failed {
+        error: String,
+    },
+}
+
+/// Check an ed25519 signature over a release binary
+pub fn verify_release(binary: &[u8], signature: &str, public_key: &str) -> Result<(), String> {
+    let key: [u8; 32] = hex::decode(public_key.trim_start_matches("0x"))
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or("Invalid release public key")?;
+    let key = VerifyingKey::from_bytes(&key).map_err(|_| "Invalid release public key")?;
+    let signature: [u8; 64] = hex::decode(signature.trim_start_matches("0x"))
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or("Malformed release signature")?;
+    key.verify(binary, &Signature::from_bytes(&signature))
+        .map_err(|_| "Release signature does not match".to_string())
+}
+
+/// Checks, downloads and stages signed releases
+pub struct UpdaterManager {
+    current_version: String,
+    config: RwLock<UpdaterConfig>,
+    status: RwLock<UpdateStatus>,
+    staged: RwLock<Option<(ReleaseInfo, PathBuf)>>,
+    /// Held while checking or downloading so runs never overlap
+    busy: Mutex<()>,
+    http: Client,
+    events: broadcast::Sender<UpdateStatus>,
+}
+
+impl UpdaterManager {
+    pub fn new(current_version: impl Into<String>) -> Self {
+        let (events, _) = broadcast::channel(64);
+        let http = Client::builder()
+            .user_agent("Citrate-GUI/1.0")
+            .connect_timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        Self {
+            current_version: current_version.into(),
+            config: RwLock::new(UpdaterConfig::load()),
+            status: RwLock::new(UpdateStatus::Idle),
+            staged: RwLock::new(None),
+            busy: Mutex::new(()),
+            http,
+            events,
+        }
+    }
+
+    /// Subscribe to status changes
+    pub fn subscribe(&self) -> broadcast::Receiver<UpdateStatus> {
+        self.events.subscribe()
+    }
+
+    pub async fn status(&self) -> UpdateStatus {
+        self.status.read().await.clone()
+    }
+
+    pub async fn get_config(&self) -> UpdaterConfig {
+        self.config.read().await.clone()
+    }
+
+    /// Pin the app to a release channel. A release staged from another
+    /// channel is discarded.
+    pub async fn set_channel(&self, channel: UpdateChannel) -> Result<(), String> {
+        let mut config = self.config.write().await;
+        if config.channel == channel {
+            return Ok(());
+        }
+        config.channel = channel;
+        config.save()?;
+        drop(config);
+
+        if let Some((_, path)) = self.staged.write().await.take() {
+            let _ = std::fs::remove_file(path);
+        }
+        self.set_status(UpdateStatus::Idle).await;
+        info!("Update channel pinned to {}", channel.as_str());
+        Ok(())
+    }
+
+    pub async fn set_auto_download(&self, enabled: bool) -> Result<(), String> {
+        let mut config = self.config.write().await;
+        config.auto_download = enabled;
+        config.save()
+    }
+
+    /// Fetch the channel manifest; returns the release if it is newer than
+    /// the running app and has a binary for this platform
+    pub async fn check(&self) -> Result<Option<ReleaseInfo>, String> {
+        let _busy = self.busy.lock().await;
+        self.set_status(UpdateStatus::Checking).await;
+        match self.fetch_release().await {
+            Ok(Some(release)) => {
+                self.set_status(UpdateStatus::Available(release.clone()))
+                    .await;
+                Ok(Some(release))
+            }
+            Ok(None) => {
+                self.set_status(UpdateStatus::UpToDate {
+                    version: self.current_version.clone(),
+                })
+                .await;
+                Ok(None)
+            }
+            Err(e) => {
+                self.fail(&e).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Download and verify `release`, then stage it for the next restart
+    pub async fn download(&self, release: ReleaseInfo) -> Result<(), String> {
+        let _busy = self.busy.lock().await;
+        let staged = self.staged.read().await.clone();
+        if let Some((staged, _)) = staged.filter(|(s, _)| s.version == release.version) {
+            self.set_status(UpdateStatus::Ready(staged)).await;
+            return Ok(());
+        }
+        match self.fetch_and_verify(&release).await {
+            Ok(path) => {
+                info!("Update {} verified and staged", release.version);
+                *self.staged.write().await = Some((release.clone(), path));
+                self.set_status(UpdateStatus::Ready(release)).await;
+                Ok(())
+            }
+            Err(e) => {
+                self.fail(&e).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Check for a release and, if auto-download is on, fetch it
+    pub async fn check_and_download(&self) -> Result<(), String> {
+        if let Some(release) = self.check().await? {
+            if self.config.read().await.auto_download {
+                self.download(release).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace the running executable with the staged release. The caller
+    /// restarts the app afterwards.
+    pub async fn install_staged(&self) -> Result<String, String> {
+        let _busy = self.busy.lock().await;
+        let (release, staged) = self
+            .staged
+            .read()
+            .await
+            .clone()
+            .ok_or("No verified update is staged")?;
+        let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+        replace_executable(&exe, &staged).map_err(|e| format!("Install failed: {}", e))?;
+        self.staged.write().await.take();
+        info!("Installed update {}", release.version);
+        Ok(release.version)
+    }
+
+    async fn fetch_release(&self) -> Result<Option<ReleaseInfo>, String> {
+        let config = self.get_config().await;
+        let url = format!(
+            "{}/{}.json",
+            config.manifest_base.trim_end_matches('/'),
+            config.channel.as_str()
+        );
+        let manifest: ReleaseManifest = self
+            .http
+            .get(&url)
+            .timeout(Duration::from_secs(30))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Failed to fetch {}: {}", url, e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid release manifest: {}", e))?;
+
+        let latest = parse_version(&manifest.version)?;
+        if latest <= parse_version(&self.current_version)? {
+            return Ok(None);
+        }
+        let Some(artifact) = manifest.platforms.get(&platform_key()).cloned() else {
+            warn!(
+                "Release {} has no binary for {}",
+                manifest.version,
+                platform_key()
+            );
+            return Ok(None);
+        };
+        Ok(Some(ReleaseInfo {
+            version: manifest.version,
+            current_version: self.current_version.clone(),
+            channel: config.channel,
+            notes: manifest.notes,
+            pub_date: manifest.pub_date,
+            artifact,
+        }))
+    }
+
+    async fn fetch_and_verify(&self, release: &ReleaseInfo) -> Result<PathBuf, String> {
+        let public_key =
+            RELEASE_PUBLIC_KEY.ok_or("This build has no release key; updates are disabled")?;
+
+        let dir = updater_dir().join("updates");
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .map_err(|e| e.to_string())?;
+        let part = dir.join(format!("citrate-core-{}.part", release.version));
+        let path = dir.join(format!("citrate-core-{}", release.version));
+
+        let response = self
+            .http
+            .get(&release.artifact.url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Download failed: {}", e))?;
+        let total = release.artifact.size.or(response.content_length());
+
+        let mut file = tokio::fs::File::create(&part)
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut stream = response.bytes_stream();
+        let mut downloaded = 0u64;
+        let mut reported = 0u64;
+        self.set_status(UpdateStatus::Downloading {
+            version: release.version.clone(),
+            downloaded,
+            total,
+        })
+        .await;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| format!("Download error: {}", e))?;
+            file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+            downloaded += chunk.len() as u64;
+            if downloaded - reported >= PROGRESS_STEP {
+                reported = downloaded;
+                self.set_status(UpdateStatus::Downloading {
+                    version: release.version.clone(),
+                    downloaded,
+                    total,
+                })
+                .await;
+            }
+        }
+        file.flush().await.map_err(|e| e.to_string())?;
+        drop(file);
+
+        if let Some(size) = release.artifact.size {
+            if size != downloaded {
+                let _ = tokio::fs::remove_file(&part).await;
+                return Err(format!("Expected {} bytes, got {}", size, downloaded));
+            }
+        }
+        let binary = tokio::fs::read(&part).await.map_err(|e| e.to_string())?;
+        if let Err(e) = verify_release(&binary, &release.artifact.signature, public_key) {
+            let _ = tokio::fs::remove_file(&part).await;
+            return Err(e);
+        }
+        tokio::fs::rename(&part, &path)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(path)
+    }
+
+    async fn set_status(&self, status: UpdateStatus) {
+        *self.status.write().await = status.clone();
+        let _ = self.events.send(status);
+    }
+
+    async fn fail(&self, error: &str) {
+        warn!("Update failed: {}", error);
+        self.set_status(UpdateStatus::Failed {
+            error: error.to_string(),
+        })
+        .await;
+    }
+}
+
+fn parse_version(version: &str) -> Result<Version, String> {
+    Version::parse(version.trim_start_matches('v'))
+        .map_err(|e| format!("Invalid version {}: {}", version, e))
+}
+
+/// Swap `staged` in for `exe`, keeping the old binary as `<exe>.old` until
+/// the next install. Renaming works on a running executable on all
+/// supported platforms.
+fn replace_executable(exe: &Path, staged: &Path) -> std::io::Result<()> {
+    let mut old = exe.as_os_str().to_os_string();
+    old.push(".old");
+    let _ = std::fs::remove_file(&old);
+    std::fs::rename(exe, &old)?;
+    if let Err(e) = std::fs::copy(staged, exe) {
+        // Put the original back so the app still starts
+        let _ = std::fs::rename(&old, exe);
+        return Err(e);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(exe, std::fs::Permissions::from_mode(0o755))?;
+    }
+    let _ = std::fs::remove_file(staged);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    #[test]
+    fn test_verify_release_signature() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = hex::encode(key.verifying_key().to_bytes());
+        let binary = b"\x7fELF release";
+        let signature = hex::encode(key.sign(binary).to_bytes());
+
+        assert!(verify_release(binary, &signature, &public_key).is_ok());
+        assert!(verify_release(b"\x7fELF tampered", &signature, &public_key).is_err());
+        assert!(verify_release(binary, "00", &public_key).is_err());
+
+        let other = hex::encode(
+            SigningKey::from_bytes(&[8u8; 32])
+                .verifying_key()
+                .to_bytes(),
+        );
+        assert!(verify_release(binary, &signature, &other).is_err());
+    }
+
+    #[test]
+    fn test_channel_and_manifest_parsing() {
+        let config: UpdaterConfig = serde_json::from_str(r#"{"channel":"nightly"}"#).unwrap();
+        assert_eq!(config.channel, UpdateChannel::Nightly);
+        assert!(config.auto_download);
+
+        let manifest: ReleaseManifest = serde_json::from_str(
+            r#"{"version":"v1.2.0-beta.1","platforms":{"linux-x86_64":{"url":"u","signature":"s"}}}"#,
+        )
+        .unwrap();
+        let version = parse_version(&manifest.version).unwrap();
+        assert!(version > parse_version("1.1.9").unwrap());
+        assert!(version < parse_version("1.2.0").unwrap());
+        assert!(manifest.platforms["linux-x86_64"].size.is_none());
+    }
+
+    #[test]
+    fn test_replace_executable_keeps_old_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join("citrate-core");
+        let staged = dir.path().join("citrate-core-1.2.0");
+        std::fs::write(&exe, b"old").unwrap();
+        std::fs::write(&staged, b"new").unwrap();
+
+        replace_executable(&exe, &staged).unwrap();
+        assert_eq!(std::fs::read(&exe).unwrap(), b"new");
+        assert_eq!(
+            std::fs::read(dir.path().join("citrate-core.old")).unwrap(),
+            b"old"
+        );
+        assert!(!staged.exists());
+    }
+}
@@ -0,0 +1,461 @@
+//! Address book for the desktop wallet
+//!
+//! Named recipients with tags and notes, plus the addresses most recently
+//! sent to. A recipient typed into the send form is resolved in order: a hex
+//! address is used as given, a name ending in `.ctr` is looked up in the
+//! on-chain name registry, anything else is matched against contact names
+//! before falling back to the registry.
+
+use anyhow::Result;
+use citrate_execution::address_utils::{address_from_hex, to_checksum_address};
+use citrate_execution::precompiles::names::{self, NAME_SUFFIX};
+use citrate_execution::state::StateDB;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Longest accepted contact name
+const MAX_CONTACT_NAME_LEN: usize = 64;
+
+/// Most tags on one contact
+const MAX_TAGS: usize = 16;
+
+/// Recently used addresses kept
+const MAX_RECENT: usize = 20;
+
+/// A named recipient
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Contact {
+    pub name: String,
+    /// EIP-55 checksummed address
+    pub address: String,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+    pub created_at: u64,
+    pub last_used: Option<u64>,
+}
+
+/// Fields supplied when adding or editing a contact
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ContactDraft {
+    pub name: String,
+    pub address: String,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+}
+
+/// An address recently sent to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentAddress {
+    pub address: String,
+    pub last_used: u64,
+    pub times_used: u32,
+}
+
+/// Where a resolved recipient came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecipientSource {
+    Address,
+    Contact,
+    Registry,
+}
+
+/// A recipient resolved to an address
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedRecipient {
+    pub input: String,
+    pub address: String,
+    pub source: RecipientSource,
+    /// Contact or registered name the address was found under
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AddressBookData {
+    contacts: Vec<Contact>,
+    recent: Vec<RecentAddress>,
+}
+
+/// Contacts and recent recipients, persisted as JSON
+pub struct AddressBook {
+    path: Option<PathBuf>,
+    data: RwLock<AddressBookData>,
+}
+
+impl AddressBook {
+    /// Open the address book at the default location
+    pub fn new() -> Result<Self> {
+        Self::open(Self::default_path())
+    }
+
+    /// Open the address book stored at `path`, starting empty if it does
+    /// not exist yet
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let data = if path.exists() {
+            serde_json::from_str(&std::fs::read_to_string(&path)?)?
+        } else {
+            AddressBookData::default()
+        };
+        Ok(Self {
+            path: Some(path),
+            data: RwLock::new(data),
+        })
+    }
+
+    /// An address book that is never written to disk
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            data: RwLock::new(AddressBookData::default()),
+        }
+    }
+
+    fn default_path() -> PathBuf {
+        crate::profiles::data_root().join("address_book.json")
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, AddressBookData> {
+        self.data.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, AddressBookData> {
+        self.data.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn persist(&self, data: &AddressBookData) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(data)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Contacts sorted by name, optionally only those with `tag`
+    pub fn list(&self, tag: Option<&str>) -> Vec<Contact> {
+        let tag = tag.map(|t| t.trim().to_lowercase());
+        let mut contacts: Vec<Contact> = self
+            .read()
+            .contacts
+            .iter()
+            .filter(|c| tag.as_ref().is_none_or(|t| c.tags.contains(t)))
+            .cloned()
+            .collect();
+        contacts.sort_by_key(|c| c.name.to_lowercase());
+        contacts
+    }
+
+    /// Look a contact up by name, ignoring case
+    pub fn find(&self, name: &str) -> Option<Contact> {
+        let name = name.trim();
+        self.read()
+            .contacts
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    /// Every tag in use, sorted
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .read()
+            .contacts
+            .iter()
+            .flat_map(|c| c.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    pub fn add(&self, draft: ContactDraft) -> Result<Contact> {
+        let mut data = self.write();
+        let mut contact = Contact {
+            name: String::new(),
+            address: String::new(),
+            tags: Vec::new(),
+            note: None,
+            created_at: now(),
+            last_used: None,
+        };
+        apply_draft(&mut contact, draft, &data.contacts, None)?;
+        data.contacts.push(contact.clone());
+        self.persist(&data)?;
+        Ok(contact)
+    }
+
+    /// Replace the contact called `name`; the draft may rename it
+    pub fn update(&self, name: &str, draft: ContactDraft) -> Result<Contact> {
+        let mut data = self.write();
+        let index = data
+            .contacts
+            .iter()
+            .position(|c| c.name.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| anyhow::anyhow!("No contact named '{}'", name))?;
+        let mut contact = data.contacts[index].clone();
+        apply_draft(&mut contact, draft, &data.contacts, Some(index))?;
+        data.contacts[index] = contact.clone();
+        self.persist(&data)?;
+        Ok(contact)
+    }
+
+    pub fn remove(&self, name: &str) -> Result<()> {
+        let mut data = self.write();
+        let before = data.contacts.len();
+        data.contacts
+            .retain(|c| !c.name.eq_ignore_ascii_case(name.trim()));
+        if data.contacts.len() == before {
+            return Err(anyhow::anyhow!("No contact named '{}'", name));
+        }
+        self.persist(&data)
+    }
+
+    /// Recently used addresses, most recent first
+    pub fn recent(&self) -> Vec<RecentAddress> {
+        self.read().recent.clone()
+    }
+
+    /// Note that a transaction was sent to `address`
+    pub fn record_used(&self, address: &str) -> Result<()> {
+        let Ok(parsed) = address_from_hex(address.trim()) else {
+            // Only 20-byte recipients are tracked
+            return Ok(());
+        };
+        let address = to_checksum_address(&parsed);
+        let used_at = now();
+
+        let mut data = self.write();
+        let times_used = match data.recent.iter().position(|r| r.address == address) {
+            Some(index) => data.recent.remove(index).times_used + 1,
+            None => 1,
+        };
+        data.recent.insert(
+            0,
+            RecentAddress {
+                address: address.clone(),
+                last_used: used_at,
+                times_used,
+            },
+        );
+        data.recent.truncate(MAX_RECENT);
+        for contact in data.contacts.iter_mut().filter(|c| c.address == address) {
+            contact.last_used = Some(used_at);
+        }
+        self.persist(&data)
+    }
+
+    /// Resolve a recipient typed by the user to an address. `state_db` is
+    /// the embedded node's state, used for registered names; without it
+    /// only addresses and contacts resolve.
+    pub fn resolve(&self, input: &str, state_db: Option<&StateDB>) -> Result<ResolvedRecipient> {
+        let trimmed = input.trim();
+        let resolved = |address: String, source, name| ResolvedRecipient {
+            input: input.to_string(),
+            address,
+            source,
+            name,
+        };
+
+        if is_hex_address(trimmed) {
+            // Longer hex recipients (public keys) are passed through as is
+            if trimmed.trim_start_matches("0x").len() == 40 {
+                address_from_hex(trimmed).map_err(anyhow::Error::msg)?;
+            }
+            return Ok(resolved(
+                trimmed.to_string(),
+                RecipientSource::Address,
+                None,
+            ));
+        }
+
+        let explicit = trimmed.to_lowercase().ends_with(NAME_SUFFIX);
+        if !explicit {
+            if let Some(contact) = self.find(trimmed) {
+                return Ok(resolved(
+                    contact.address,
+                    RecipientSource::Contact,
+                    Some(contact.name),
+                ));
+            }
+        }
+
+        if let Some(name) = names::normalize_name(trimmed) {
+            let state_db = state_db
+                .ok_or_else(|| anyhow::anyhow!("Start the node to resolve '{}'", trimmed))?;
+            if let Some(address) = names::resolve(state_db, &name) {
+                return Ok(resolved(
+                    to_checksum_address(&address),
+                    RecipientSource::Registry,
+                    Some(format!("{}{}", name, NAME_SUFFIX)),
+                ));
+            }
+        }
+
+        Err(anyhow::anyhow!(
+            "'{}' is not an address, a contact or a registered name",
+            trimmed
+        ))
+    }
+}
+
+fn is_hex_address(input: &str) -> bool {
+    let hex = input.trim_start_matches("0x");
+    (input.starts_with("0x") || hex.len() == 40 || hex.len() == 64)
+        && !hex.is_empty()
+        && hex.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn apply_draft(
+    contact: &mut Contact,
+    draft: ContactDraft,
+    existing: &[Contact],
+    index: Option<usize>,
+) -> Result<()> {
+    let name = draft.name.trim();
+    if name.is_empty() || name.len() > MAX_CONTACT_NAME_LEN {
+        return Err(anyhow::anyhow!(
+            "Contact name must be 1-{} characters",
+            MAX_CONTACT_NAME_LEN
+        ));
+    }
+    if is_hex_address(name) {
+        return Err(anyhow::anyhow!("Contact name cannot be an address"));
+    }
+    let taken = existing
+        .iter()
+        .enumerate()
+        .any(|(i, c)| Some(i) != index && c.name.eq_ignore_ascii_case(name));
+    if taken {
+        return Err(anyhow::anyhow!("A contact named '{}' already exists", name));
+    }
+    let address = address_from_hex(draft.address.trim()).map_err(anyhow::Error::msg)?;
+
+    let mut tags: Vec<String> = draft
+        .tags
+        .iter()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    if tags.len() > MAX_TAGS {
+        return Err(anyhow::anyhow!(
+            "A contact can have at most {} tags",
+            MAX_TAGS
+        ));
+    }
+
+    contact.name = name.to_string();
+    contact.address = to_checksum_address(&address);
+    contact.tags = tags;
+    contact.note = draft
+        .note
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty());
+    Ok(())
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+
+    fn draft(name: &str, address: &str, tags: &[&str]) -> ContactDraft {
+        ContactDraft {
+            name: name.to_string(),
+            address: address.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_contacts_crud() {
+        let book = AddressBook::in_memory();
+        let alice = book
+            .add(draft("Alice", ALICE, &["Team", " team", "friends"]))
+            .unwrap();
+        assert_eq!(alice.address, "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed");
+        assert_eq!(alice.tags, vec!["friends", "team"]);
+
+        assert!(book.add(draft("alice", ALICE, &[])).is_err());
+        assert!(book.add(draft("Bob", "0x1234", &[])).is_err());
+        assert!(book.add(draft(ALICE, ALICE, &[])).is_err());
+
+        book.update("ALICE", draft("Alice Cooper", ALICE, &["music"]))
+            .unwrap();
+        assert!(book.find("alice").is_none());
+        assert_eq!(book.list(Some("music")).len(), 1);
+        assert!(book.list(Some("team")).is_empty());
+
+        book.remove("alice cooper").unwrap();
+        assert!(book.list(None).is_empty());
+        assert!(book.remove("alice cooper").is_err());
+    }
+
+    #[test]
+    fn test_recent_addresses() {
+        let book = AddressBook::in_memory();
+        book.add(draft("Alice", ALICE, &[])).unwrap();
+        let other = format!("0x{}", "11".repeat(20));
+
+        book.record_used(ALICE).unwrap();
+        book.record_used(&other).unwrap();
+        book.record_used(&ALICE.to_uppercase().replace("0X", "0x"))
+            .unwrap();
+
+        let recent = book.recent();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].address, book.find("alice").unwrap().address);
+        assert_eq!(recent[0].times_used, 2);
+        assert!(book.find("alice").unwrap().last_used.is_some());
+    }
+
+    #[test]
+    fn test_resolve_recipient() {
+        let book = AddressBook::in_memory();
+        book.add(draft("Alice", ALICE, &[])).unwrap();
+        let state_db = StateDB::new();
+
+        let r = book.resolve(ALICE, None).unwrap();
+        assert_eq!(r.source, RecipientSource::Address);
+        assert!(book
+            .resolve("0x5AaEB6053f3e94c9b9a09f33669435e7ef1beaed", None)
+            .is_err());
+
+        let r = book.resolve(" alice ", None).unwrap();
+        assert_eq!(r.source, RecipientSource::Contact);
+        assert_eq!(r.name.as_deref(), Some("Alice"));
+        assert_eq!(
+            r.address,
+            to_checksum_address(&address_from_hex(ALICE).unwrap())
+        );
+
+        // An explicit suffix skips contacts and needs the node's state
+        assert!(book.resolve("alice.ctr", None).is_err());
+        assert!(book.resolve("alice.ctr", Some(&state_db)).is_err());
+        assert!(book.resolve("carol", Some(&state_db)).is_err());
+    }
+}
@@ -0,0 +1,247 @@
+//! Contracts deployed from the desktop wallet
+//!
+//! Every deployment sent by the contract wizard is recorded here with the
+//! constructor arguments and gas it was sent with. Pending deployments are
+//! settled from their receipts: a successful receipt carries the address
+//! the contract was created at, a reverted one marks the deployment failed.
+
+use anyhow::Result;
+use citrate_execution::address_utils::to_checksum_address;
+use citrate_execution::{Address, TransactionReceipt};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Deployments kept in the log
+const MAX_DEPLOYMENTS: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeploymentStatus {
+    /// Submitted, no receipt yet
+    Pending,
+    /// Included and the constructor succeeded
+    Deployed,
+    /// Included but the constructor reverted or ran out of gas
+    Failed,
+}
+
+/// A contract deployment sent from a wallet account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractDeployment {
+    pub tx_hash: String,
+    pub contract_name: String,
+    pub source_file: String,
+    /// Deploying wallet account
+    pub from: String,
+    /// Constructor arguments as passed to the ABI encoder
+    pub constructor_args: Vec<String>,
+    /// Value sent to a payable constructor, in wei
+    pub value: String,
+    /// Gas used by a dry run of the deployment
+    pub gas_estimate: u64,
+    pub gas_limit: u64,
+    /// Gas price in wei
+    pub gas_price: String,
+    pub status: DeploymentStatus,
+    /// EIP-55 checksummed address of the created contract
+    pub contract_address: Option<String>,
+    pub block_number: Option<u64>,
+    pub gas_used: Option<u64>,
+    pub submitted_at: u64,
+}
+
+impl ContractDeployment {
+    /// Settle a pending deployment from its receipt
+    pub fn apply_receipt(&mut self, receipt: &TransactionReceipt) {
+        self.block_number = Some(receipt.block_number);
+        self.gas_used = Some(receipt.gas_used);
+        // Creations return the new contract's address as their output
+        let created = (receipt.status && receipt.output.len() == 20).then(|| {
+            let mut address = [0u8; 20];
+            address.copy_from_slice(&receipt.output);
+            to_checksum_address(&Address(address))
+        });
+        self.status = if created.is_some() {
+            DeploymentStatus::Deployed
+        } else {
+            DeploymentStatus::Failed
+        };
+        self.contract_address = created;
+    }
+}
+
+/// Deployment log, persisted as JSON
+pub struct DeploymentLog {
+    path: Option<PathBuf>,
+    /// Oldest first
+    deployments: RwLock<Vec<ContractDeployment>>,
+}
+
+impl DeploymentLog {
+    /// Open the log at the default location
+    pub fn new() -> Result<Self> {
+        Self::open(Self::default_path())
+    }
+
+    /// Open the log stored at `path`, starting empty if it does not exist yet
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let deployments = if path.exists() {
+            serde_json::from_str(&std::fs::read_to_string(&path)?)?
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            path: Some(path),
+            deployments: RwLock::new(deployments),
+        })
+    }
+
+    /// A log that is never written to disk
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            deployments: RwLock::new(Vec::new()),
+        }
+    }
+
+    fn default_path() -> PathBuf {
+        crate::profiles::data_root().join("contract_deployments.json")
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Vec<ContractDeployment>> {
+        self.deployments.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Vec<ContractDeployment>> {
+        self.deployments.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn persist(&self, deployments: &[ContractDeployment]) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(deployments)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Deployments, newest first
+    pub fn list(&self) -> Vec<ContractDeployment> {
+        self.read().iter().rev().cloned().collect()
+    }
+
+    /// Hashes of deployments still waiting for a receipt
+    pub fn pending(&self) -> Vec<String> {
+        self.read()
+            .iter()
+            .filter(|d| d.status == DeploymentStatus::Pending)
+            .map(|d| d.tx_hash.clone())
+            .collect()
+    }
+
+    pub fn record(&self, deployment: ContractDeployment) -> Result<()> {
+        let mut deployments = self.write();
+        deployments.push(deployment);
+        let excess = deployments.len().saturating_sub(MAX_DEPLOYMENTS);
+        deployments.drain(..excess);
+        self.persist(&deployments)
+    }
+
+    /// Settle pending deployments whose receipts `lookup` finds, returning
+    /// the ones that changed
+    pub fn settle<F>(&self, mut lookup: F) -> Result<Vec<ContractDeployment>>
+    where
+        F: FnMut(&str) -> Option<TransactionReceipt>,
+    {
+        let mut deployments = self.write();
+        let mut settled = Vec::new();
+        for deployment in deployments
+            .iter_mut()
+            .filter(|d| d.status == DeploymentStatus::Pending)
+        {
+            if let Some(receipt) = lookup(&deployment.tx_hash) {
+                deployment.apply_receipt(&receipt);
+                settled.push(deployment.clone());
+            }
+        }
+        if !settled.is_empty() {
+            self.persist(&deployments)?;
+        }
+        Ok(settled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use citrate_execution::Hash;
+
+    fn deployment(tx_hash: &str) -> ContractDeployment {
+        ContractDeployment {
+            tx_hash: tx_hash.to_string(),
+            contract_name: "Token".to_string(),
+            source_file: "Token.sol".to_string(),
+            from: "0xabc".to_string(),
+            constructor_args: vec!["1000".to_string()],
+            value: "0".to_string(),
+            gas_estimate: 90_000,
+            gas_limit: 99_000,
+            gas_price: "1000000000".to_string(),
+            status: DeploymentStatus::Pending,
+            contract_address: None,
+            block_number: None,
+            gas_used: None,
+            submitted_at: 1_000,
+        }
+    }
+
+    fn receipt(status: bool, output: Vec<u8>) -> TransactionReceipt {
+        TransactionReceipt {
+            tx_hash: Hash::default(),
+            block_hash: Hash::default(),
+            block_number: 42,
+            from: Address([1u8; 20]),
+            to: None,
+            gas_used: 88_000,
+            status,
+            logs: vec![],
+            output,
+        }
+    }
+
+    #[test]
+    fn test_settle_records_address_or_failure() {
+        let log = DeploymentLog::in_memory();
+        log.record(deployment("aa")).unwrap();
+        log.record(deployment("bb")).unwrap();
+        log.record(deployment("cc")).unwrap();
+
+        let contract = hex::decode("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap();
+        let settled = log
+            .settle(|hash| match hash {
+                "aa" => Some(receipt(true, contract.clone())),
+                "bb" => Some(receipt(false, vec![])),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(settled.len(), 2);
+        assert_eq!(settled[0].status, DeploymentStatus::Deployed);
+        assert_eq!(
+            settled[0].contract_address.as_deref(),
+            Some("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")
+        );
+        assert_eq!(settled[0].block_number, Some(42));
+        assert_eq!(settled[1].status, DeploymentStatus::Failed);
+        assert!(settled[1].contract_address.is_none());
+
+        assert_eq!(log.pending(), vec!["cc".to_string()]);
+        assert_eq!(log.list()[0].tx_hash, "cc");
+    }
+}
@@ -1,3 +1,8 @@
+pub mod contacts;
+pub mod deployments;
+pub mod multisig;
+pub mod payments;
+
 use aes_gcm::{
     aead::{Aead, AeadCore, KeyInit},
     Aes256Gcm, Key, Nonce,
@@ -13,6 +18,8 @@ use ed25519_dalek::{Signature as Ed25519Signature, Signer, SigningKey, Verifying
 use hmac::{Hmac, Mac};
 use keyring::Entry;
 use citrate_consensus::types::{Hash, PublicKey, Signature, Transaction};
+use citrate_execution::units::{parse_units, Unit};
+use citrate_wallet::RemoteSigner;
 use rand::rngs::OsRng;
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
@@ -23,7 +30,12 @@ use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{info, warn, error};
 
-const KEYRING_SERVICE: &str = "citrate-core";
+use crate::tr;
+use contacts::AddressBook;
+use deployments::DeploymentLog;
+use multisig::MultisigBook;
+use payments::PaymentScheduler;
+
 const KEYRING_USER: &str = "wallet";
 
 // BIP32/BIP44 constants
@@ -467,22 +479,128 @@ pub struct WalletManager {
     active_account: Arc<RwLock<Option<usize>>>,
     rate_limiter: Arc<RwLock<RateLimiter>>,
     session_manager: Arc<RwLock<SessionManager>>,
+    remote_signer: Arc<RwLock<Option<RemoteSignerConnection>>>,
+    address_book: std::sync::RwLock<Arc<AddressBook>>,
+    payments: std::sync::RwLock<Arc<PaymentScheduler>>,
+    deployments: std::sync::RwLock<Arc<DeploymentLog>>,
+    multisig: std::sync::RwLock<Arc<MultisigBook>>,
+}
+
+/// Connected remote signer and the addresses of the accounts it holds
+struct RemoteSignerConnection {
+    signer: Arc<RemoteSigner>,
+    addresses: Vec<String>,
+}
+
+/// Remote signer state reported to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteSignerStatus {
+    pub connected: bool,
+    pub endpoint: Option<String>,
+    pub accounts: Vec<String>,
 }
 
 impl WalletManager {
     pub fn new() -> Result<Self> {
         let keystore = Arc::new(SecureKeyStore::new()?);
         let accounts = Arc::new(RwLock::new(Self::load_accounts(&keystore)?));
-
         Ok(Self {
             accounts,
             keystore,
             active_account: Arc::new(RwLock::new(None)),
             rate_limiter: Arc::new(RwLock::new(RateLimiter::new())),
             session_manager: Arc::new(RwLock::new(SessionManager::new())),
+            remote_signer: Arc::new(RwLock::new(None)),
+            address_book: std::sync::RwLock::new(Self::open_address_book()),
+            payments: std::sync::RwLock::new(Self::open_payments()),
+            deployments: std::sync::RwLock::new(Self::open_deployments()),
+            multisig: std::sync::RwLock::new(Self::open_multisig()),
         })
     }
 
+    fn open_address_book() -> Arc<AddressBook> {
+        let address_book = AddressBook::new().unwrap_or_else(|e| {
+            warn!("Failed to load address book: {}. Contacts will not persist.", e);
+            AddressBook::in_memory()
+        });
+        Arc::new(address_book)
+    }
+
+    fn open_payments() -> Arc<PaymentScheduler> {
+        let payments = PaymentScheduler::new().unwrap_or_else(|e| {
+            warn!("Failed to load scheduled payments: {}. Schedules will not persist.", e);
+            PaymentScheduler::in_memory()
+        });
+        Arc::new(payments)
+    }
+
+    fn open_deployments() -> Arc<DeploymentLog> {
+        let deployments = DeploymentLog::new().unwrap_or_else(|e| {
+            warn!("Failed to load contract deployments: {}. Deployments will not persist.", e);
+            DeploymentLog::in_memory()
+        });
+        Arc::new(deployments)
+    }
+
+    fn open_multisig() -> Arc<MultisigBook> {
+        let multisig = MultisigBook::new().unwrap_or_else(|e| {
+            warn!("Failed to load multisig wallets: {}. Multisig wallets will not persist.", e);
+            MultisigBook::in_memory()
+        });
+        Arc::new(multisig)
+    }
+
+    /// Contacts and recently used recipients
+    pub fn address_book(&self) -> Arc<AddressBook> {
+        self.address_book
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Scheduled and recurring payments
+    pub fn payments(&self) -> Arc<PaymentScheduler> {
+        self.payments
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Contracts deployed from wallet accounts
+    pub fn deployments(&self) -> Arc<DeploymentLog> {
+        self.deployments
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Multisig wallets created or imported by the user
+    pub fn multisig(&self) -> Arc<MultisigBook> {
+        self.multisig
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Reload accounts, contacts, scheduled payments, deployments and
+    /// multisig wallets after the active profile changed. Open sessions end
+    /// and a connected remote signer is dropped.
+    pub async fn reload(&self) -> Result<()> {
+        let accounts = Self::load_accounts(&self.keystore)?;
+        *self.accounts.write().await = accounts;
+        *self.active_account.write().await = None;
+        *self.rate_limiter.write().await = RateLimiter::new();
+        *self.session_manager.write().await = SessionManager::new();
+        *self.remote_signer.write().await = None;
+        *self.address_book.write().unwrap_or_else(|e| e.into_inner()) =
+            Self::open_address_book();
+        *self.payments.write().unwrap_or_else(|e| e.into_inner()) = Self::open_payments();
+        *self.deployments.write().unwrap_or_else(|e| e.into_inner()) =
+            Self::open_deployments();
+        *self.multisig.write().unwrap_or_else(|e| e.into_inner()) = Self::open_multisig();
+        Ok(())
+    }
+
     // ========== Security: Rate Limiting & Session Management ==========
 
     /// Check rate limit for a sensitive operation
@@ -651,7 +769,7 @@ impl WalletManager {
         let setup_result = FirstTimeSetupResult {
             primary_address: primary_account.address.clone(),
             mnemonic,
-            warning_message: "IMPORTANT: Save your recovery phrase securely. This is the ONLY way to recover your wallet if you lose access. Never share it with anyone.".to_string(),
+            warning_message: tr!("wallet.recovery_phrase_warning"),
         };
 
         info!("First-time setup completed. Primary address: {}", primary_account.address);
@@ -995,10 +1113,22 @@ impl WalletManager {
             .await
             .ok_or_else(|| anyhow::anyhow!("Account not found"))?;
 
+        // 20-byte recipients must pass their EIP-55 checksum when mixed-case
+        if let Some(to) = &request.to {
+            if to.trim_start_matches("0x").len() == 40 {
+                citrate_execution::address_utils::address_from_hex(to)
+                    .map_err(anyhow::Error::msg)?;
+            }
+        }
+
         // Create transaction
         // Parse numeric fields from strings
-        let value_u128: u128 = request.value.parse().unwrap_or(0);
-        let gas_price_u64: u64 = request.gas_price.parse().unwrap_or(0);
+        let value_u128 = parse_units(&request.value, Unit::Wei)
+            .map_err(|e| anyhow::anyhow!("Invalid value: {}", e))?;
+        let gas_price_u64 = parse_units(&request.gas_price, Unit::Wei)
+            .ok()
+            .and_then(|p| u64::try_from(p).ok())
+            .ok_or_else(|| anyhow::anyhow!("Invalid gas price: {}", request.gas_price))?;
 
         let mut tx = Transaction {
             hash: Hash::new([0u8; 32]), // Will be computed after signing
@@ -1053,6 +1183,23 @@ impl WalletManager {
         // Check rate limit for signing
         self.check_rate_limit(address, SensitiveOperation::SignTransaction).await?;
 
+        // Accounts held by a remote signer are signed there, under its policy
+        if let Some(signer) = self.remote_signer_for(address).await {
+            let account = self
+                .get_account(address)
+                .await
+                .ok_or_else(|| anyhow::anyhow!("Account not found"))?;
+            let mut from = [0u8; 32];
+            hex::decode_to_slice(&account.public_key, &mut from)?;
+            tx.from = PublicKey::new(from);
+            signer.sign_transaction(tx).await?;
+
+            use sha3::{Digest, Keccak256};
+            tx.hash = Hash::from_bytes(&Keccak256::digest(self.canonical_tx_bytes(tx)));
+            info!("Transaction signed by remote signer for address: {}, value: {}", address, tx.value);
+            return Ok(());
+        }
+
         // Check if high-value transaction requires re-authentication
         let requires_reauth = Self::requires_reauth(tx.value, SensitiveOperation::SignTransaction);
         if requires_reauth {
@@ -1196,6 +1343,94 @@ impl WalletManager {
         Ok(verifying_key.verify_strict(message, &signature).is_ok())
     }
 
+    // ========== Remote Signer ==========
+
+    /// Connect to a remote signer and add the accounts it holds
+    pub async fn connect_remote_signer(&self, endpoint: &str) -> Result<Vec<Account>> {
+        let signer = RemoteSigner::new(endpoint, "citrate-core")?;
+        let keys = signer.list_accounts().await?;
+
+        let mut remote = Vec::new();
+        let mut accounts = self.accounts.write().await;
+        for key in keys {
+            let verifying_key = VerifyingKey::from_bytes(key.as_bytes())?;
+            let address = self.derive_address(&verifying_key);
+            let account = match accounts.iter().find(|a| a.address == address) {
+                Some(existing) => existing.clone(),
+                None => {
+                    let account = Account {
+                        address: address.clone(),
+                        label: "Remote signer".to_string(),
+                        public_key: hex::encode(key.as_bytes()),
+                        balance: 0,
+                        nonce: 0,
+                        created_at: chrono::Utc::now().timestamp() as u64,
+                    };
+                    accounts.push(account.clone());
+                    account
+                }
+            };
+            remote.push(account);
+        }
+        drop(accounts);
+        self.save_accounts().await?;
+
+        info!("Connected remote signer {} with {} accounts", endpoint, remote.len());
+        let previous = self.remote_signer.write().await.replace(RemoteSignerConnection {
+            signer: Arc::new(signer),
+            addresses: remote.iter().map(|a| a.address.clone()).collect(),
+        });
+        if let Some(previous) = previous {
+            let _ = previous.signer.close().await;
+        }
+        Ok(remote)
+    }
+
+    /// Disconnect the remote signer; its accounts stay listed but cannot sign
+    pub async fn disconnect_remote_signer(&self) -> Result<()> {
+        if let Some(connection) = self.remote_signer.write().await.take() {
+            connection.signer.close().await?;
+            info!("Disconnected remote signer {}", connection.signer.endpoint());
+        }
+        Ok(())
+    }
+
+    pub async fn remote_signer_status(&self) -> RemoteSignerStatus {
+        match self.remote_signer.read().await.as_ref() {
+            Some(connection) => RemoteSignerStatus {
+                connected: true,
+                endpoint: Some(connection.signer.endpoint().to_string()),
+                accounts: connection.addresses.clone(),
+            },
+            None => RemoteSignerStatus {
+                connected: false,
+                endpoint: None,
+                accounts: Vec::new(),
+            },
+        }
+    }
+
+    /// Whether a transaction of `value` from `address` can be signed without
+    /// a password: the account is held by a connected remote signer, or an
+    /// unlocked session has its key cached and the value is below the
+    /// re-authentication threshold
+    pub async fn can_sign_unattended(&self, address: &str, value: u128) -> bool {
+        if self.remote_signer_for(address).await.is_some() {
+            return true;
+        }
+        !Self::requires_reauth(value, SensitiveOperation::SignTransaction)
+            && self.get_cached_signing_key(address).await.is_some()
+    }
+
+    async fn remote_signer_for(&self, address: &str) -> Option<Arc<RemoteSigner>> {
+        self.remote_signer
+            .read()
+            .await
+            .as_ref()
+            .filter(|c| c.addresses.iter().any(|a| a.eq_ignore_ascii_case(address)))
+            .map(|c| c.signer.clone())
+    }
+
     pub async fn update_balance(&self, address: &str, balance: u128) -> Result<()> {
         let mut accounts = self.accounts.write().await;
         if let Some(account) = accounts.iter_mut().find(|a| a.address == address) {
@@ -1260,6 +1495,88 @@ impl WalletManager {
         Ok(())
     }
 
+    /// Export every account, its encrypted keystore and `settings` as a single
+    /// archive encrypted with `backup_password`
+    /// Keystores stay encrypted with their account passwords inside the archive
+    pub async fn export_backup(
+        &self,
+        backup_password: &str,
+        settings: WalletBackupSettings,
+    ) -> Result<String> {
+        Self::validate_password(backup_password)?;
+        self.check_rate_limit(BACKUP_RATE_LIMIT_KEY, SensitiveOperation::KeyExport).await?;
+
+        let accounts = self.accounts.read().await.clone();
+        let mut entries = Vec::with_capacity(accounts.len());
+        for account in accounts {
+            let keystore = self.keystore.get_record(&account.address).map_err(|e| {
+                anyhow::anyhow!("Failed to read keystore for {}: {}", account.address, e)
+            })?;
+            entries.push(BackupAccount { account, keystore });
+        }
+
+        let payload = WalletBackupPayload {
+            created_at: chrono::Utc::now().timestamp() as u64,
+            accounts: entries,
+            settings,
+        };
+        let archive = seal_backup(&payload, backup_password)?;
+
+        warn!("Wallet backup exported with {} account(s)", payload.accounts.len());
+        Ok(archive)
+    }
+
+    /// Restore accounts from a backup created by `export_backup`
+    /// Accounts already in this wallet are left untouched; restored accounts
+    /// keep the passwords they had when the backup was made
+    pub async fn import_backup(
+        &self,
+        archive: &str,
+        backup_password: &str,
+    ) -> Result<WalletBackupImport> {
+        self.check_rate_limit(BACKUP_RATE_LIMIT_KEY, SensitiveOperation::PasswordAttempt).await?;
+        let payload = open_backup(archive, backup_password)?;
+
+        let mut imported = Vec::new();
+        let mut skipped = Vec::new();
+        for entry in payload.accounts {
+            let address = entry.account.address.clone();
+            if self.accounts.read().await.iter().any(|a| a.address == address) {
+                skipped.push(address);
+                continue;
+            }
+
+            // The stored public key must belong to the stored address
+            let public_key: [u8; 32] = hex::decode(&entry.account.public_key)
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| anyhow::anyhow!("Invalid public key for {}", address))?;
+            let verifying_key = VerifyingKey::from_bytes(&public_key)?;
+            if self.derive_address(&verifying_key) != address {
+                return Err(anyhow::anyhow!("Public key does not match address {}", address));
+            }
+
+            self.keystore.store_record(&address, &entry.keystore)?;
+            self.accounts.write().await.push(entry.account.clone());
+            imported.push(entry.account);
+        }
+
+        if !imported.is_empty() {
+            self.save_accounts().await?;
+        }
+
+        info!(
+            "Restored wallet backup: {} imported, {} already present",
+            imported.len(),
+            skipped.len()
+        );
+        Ok(WalletBackupImport {
+            imported,
+            skipped,
+            settings: payload.settings,
+        })
+    }
+
     fn derive_address(&self, public_key: &VerifyingKey) -> String {
         // Use keccak256 hash of public key for Ethereum-compatible address
         use sha3::{Digest, Keccak256};
@@ -1307,13 +1624,147 @@ impl WalletManager {
     }
 
     fn accounts_path() -> std::path::PathBuf {
-        dirs::data_dir()
-            .unwrap_or_else(|| std::path::PathBuf::from("."))
-            .join("citrate-core")
-            .join("accounts.json")
+        crate::profiles::data_root().join("accounts.json")
+    }
+}
+
+/// OS keychain service of the active profile
+fn keyring_service() -> String {
+    crate::profiles::keyring_service(&crate::profiles::active_profile())
+}
+
+/// Delete the OS keychain entries of the accounts of a profile that is
+/// being removed. Its key files go with its data dir.
+pub fn delete_profile_keys(profile: &str) {
+    let accounts_path = crate::profiles::profile_data_dir(profile).join("accounts.json");
+    let accounts: Vec<Account> = std::fs::read_to_string(&accounts_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+    let service = crate::profiles::keyring_service(profile);
+    for account in accounts {
+        if let Ok(entry) = Entry::new(&service, &format!("wallet_{}", account.address)) {
+            let _ = entry.delete_password();
+        }
     }
 }
 
+/// Derive an AES-256-GCM cipher from a password with Argon2
+fn password_cipher(password: &str, salt: &SaltString) -> Result<Aes256Gcm> {
+    let argon2 = Argon2::default();
+    let password_hash = argon2
+        .hash_password(password.as_bytes(), salt)
+        .map_err(|e| anyhow::anyhow!("Failed to hash password: {}", e))?;
+    let hash_output = password_hash
+        .hash
+        .ok_or_else(|| anyhow::anyhow!("Argon2 produced no hash output"))?;
+    let key_bytes = hash_output.as_bytes();
+    let key = Key::<Aes256Gcm>::from_slice(&key_bytes[..32]);
+    Ok(Aes256Gcm::new(key))
+}
+
+// ========== Wallet Backup ==========
+
+/// Format tag written into wallet backup files
+const BACKUP_FORMAT: &str = "citrate-wallet-backup";
+const BACKUP_VERSION: u8 = 1;
+
+/// Rate-limit key for backup operations, which span all accounts
+const BACKUP_RATE_LIMIT_KEY: &str = "wallet_backup";
+
+/// Profile settings carried in a wallet backup alongside the accounts
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WalletBackupSettings {
+    pub tracked_addresses: Vec<String>,
+    pub reward_address: Option<String>,
+}
+
+/// Result of restoring a wallet backup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletBackupImport {
+    /// Accounts added to this wallet
+    pub imported: Vec<Account>,
+    /// Addresses skipped because they already exist here
+    pub skipped: Vec<String>,
+    /// Settings stored in the backup, for the caller to apply
+    pub settings: WalletBackupSettings,
+}
+
+/// Decrypted contents of a backup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WalletBackupPayload {
+    created_at: u64,
+    accounts: Vec<BackupAccount>,
+    settings: WalletBackupSettings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BackupAccount {
+    account: Account,
+    /// Keystore record, still encrypted with the account's own password
+    keystore: String,
+}
+
+/// Backup file as written to disk
+#[derive(Serialize, Deserialize)]
+struct WalletBackupFile {
+    format: String,
+    version: u8,
+    salt: String,  // PHC salt string
+    nonce: String, // base64
+    ct: String,    // base64
+}
+
+/// Encrypt a backup payload with the backup password
+fn seal_backup(payload: &WalletBackupPayload, password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let cipher = password_cipher(password, &salt)?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let plaintext = serde_json::to_vec(payload)?;
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_ref())
+        .map_err(|e| anyhow::anyhow!("Encryption failed: {}", e))?;
+
+    let file = WalletBackupFile {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        salt: salt.as_str().to_string(),
+        nonce: BASE64.encode(nonce),
+        ct: BASE64.encode(&ciphertext),
+    };
+    Ok(serde_json::to_string_pretty(&file)?)
+}
+
+/// Decrypt a backup file with the backup password
+fn open_backup(data: &str, password: &str) -> Result<WalletBackupPayload> {
+    let file: WalletBackupFile = serde_json::from_str(data)
+        .map_err(|_| anyhow::anyhow!("Not a Citrate wallet backup"))?;
+    if file.format != BACKUP_FORMAT {
+        return Err(anyhow::anyhow!("Not a Citrate wallet backup"));
+    }
+    if file.version != BACKUP_VERSION {
+        return Err(anyhow::anyhow!("Unsupported backup version {}", file.version));
+    }
+
+    let salt = SaltString::from_b64(&file.salt)
+        .map_err(|e| anyhow::anyhow!("Invalid backup salt: {}", e))?;
+    let cipher = password_cipher(password, &salt)?;
+    let nonce_bytes = BASE64
+        .decode(&file.nonce)
+        .map_err(|_| anyhow::anyhow!("Invalid backup nonce"))?;
+    if nonce_bytes.len() != 12 {
+        return Err(anyhow::anyhow!("Invalid nonce length"));
+    }
+    let ciphertext = BASE64
+        .decode(&file.ct)
+        .map_err(|_| anyhow::anyhow!("Invalid backup ciphertext"))?;
+
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce_bytes), ciphertext.as_ref())
+        .map_err(|_| anyhow::anyhow!("Invalid backup password"))?;
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
 /// Secure key storage with OS keychain and file-based fallback
 /// Uses OS keychain when available, falls back to encrypted file storage in dev mode
 #[allow(dead_code)]
@@ -1325,7 +1776,7 @@ struct SecureKeyStore {
 impl SecureKeyStore {
     fn new() -> Result<Self> {
         // Try OS keychain first, fall back to file storage if it fails
-        match Entry::new(KEYRING_SERVICE, KEYRING_USER) {
+        match Entry::new(&keyring_service(), KEYRING_USER) {
             Ok(entry) => {
                 // Test if keychain is actually accessible
                 let test_result = entry.get_password();
@@ -1347,10 +1798,7 @@ impl SecureKeyStore {
     }
 
     fn keys_dir() -> std::path::PathBuf {
-        dirs::data_dir()
-            .unwrap_or_else(|| std::path::PathBuf::from("."))
-            .join("citrate-core")
-            .join("keys")
+        crate::profiles::data_root().join("keys")
     }
 
     fn key_file_path(address: &str) -> std::path::PathBuf {
@@ -1360,18 +1808,9 @@ impl SecureKeyStore {
     fn store_key(&self, address: &str, signing_key: &SigningKey, password: &str) -> Result<()> {
         // Derive encryption key from password with a per-key random salt
         let salt = SaltString::generate(&mut OsRng);
-        let argon2 = Argon2::default();
-        let password_hash = argon2
-            .hash_password(password.as_bytes(), &salt)
-            .map_err(|e| anyhow::anyhow!("Failed to hash password: {}", e))?;
-        let hash_output = password_hash
-            .hash
-            .ok_or_else(|| anyhow::anyhow!("Argon2 produced no hash output"))?;
-        let key_bytes = hash_output.as_bytes();
-        let key = Key::<Aes256Gcm>::from_slice(&key_bytes[..32]);
+        let cipher = password_cipher(password, &salt)?;
 
         // Encrypt private key
-        let cipher = Aes256Gcm::new(key);
         let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
         let ciphertext = cipher
             .encrypt(&nonce, signing_key.to_bytes().as_ref())
@@ -1393,10 +1832,15 @@ impl SecureKeyStore {
         };
         let encoded = serde_json::to_string(&record)?;
 
+        self.store_record(address, &encoded)
+    }
+
+    /// Store an already-encrypted key record
+    fn store_record(&self, address: &str, encoded: &str) -> Result<()> {
         // Try OS keychain first, then fall back to file
         if !self.use_file_fallback {
-            if let Ok(entry) = Entry::new(KEYRING_SERVICE, &format!("wallet_{}", address)) {
-                if entry.set_password(&encoded).is_ok() {
+            if let Ok(entry) = Entry::new(&keyring_service(), &format!("wallet_{}", address)) {
+                if entry.set_password(encoded).is_ok() {
                     return Ok(());
                 }
                 info!("Keychain store failed, falling back to file storage");
@@ -1407,16 +1851,17 @@ impl SecureKeyStore {
         let keys_dir = Self::keys_dir();
         std::fs::create_dir_all(&keys_dir)?;
         let key_path = Self::key_file_path(address);
-        std::fs::write(&key_path, &encoded)?;
+        std::fs::write(&key_path, encoded)?;
         info!("Stored encrypted key to file for address: {}", address);
 
         Ok(())
     }
 
-    fn get_key(&self, address: &str, password: &str) -> Result<SigningKey> {
+    /// Load the encrypted key record without decrypting it
+    fn get_record(&self, address: &str) -> Result<String> {
         // Try to retrieve from keychain first
         let stored = if !self.use_file_fallback {
-            if let Ok(entry) = Entry::new(KEYRING_SERVICE, &format!("wallet_{}", address)) {
+            if let Ok(entry) = Entry::new(&keyring_service(), &format!("wallet_{}", address)) {
                 match entry.get_password() {
                     Ok(s) => Some(s),
                     Err(_) => None,
@@ -1429,17 +1874,21 @@ impl SecureKeyStore {
         };
 
         // Fall back to file storage if keychain didn't work
-        let stored = match stored {
-            Some(s) => s,
+        match stored {
+            Some(s) => Ok(s),
             None => {
                 let key_path = Self::key_file_path(address);
                 if key_path.exists() {
-                    std::fs::read_to_string(&key_path)?
+                    Ok(std::fs::read_to_string(&key_path)?)
                 } else {
-                    return Err(anyhow::anyhow!("Key not found for address"));
+                    Err(anyhow::anyhow!("Key not found for address"))
                 }
             }
-        };
+        }
+    }
+
+    fn get_key(&self, address: &str, password: &str) -> Result<SigningKey> {
+        let stored = self.get_record(address)?;
 
         // Try JSON format first
         #[derive(Deserialize)]
@@ -1452,17 +1901,9 @@ impl SecureKeyStore {
         }
         if let Ok(record) = serde_json::from_str::<StoredKeyOwned>(&stored) {
             // Derive key using stored salt
-            let argon2 = Argon2::default();
             let salt = SaltString::from_b64(&record.salt)
                 .map_err(|e| anyhow::anyhow!("Invalid stored salt: {}", e))?;
-            let password_hash = argon2
-                .hash_password(password.as_bytes(), &salt)
-                .map_err(|e| anyhow::anyhow!("Failed to hash password: {}", e))?;
-            let hash_output = password_hash
-                .hash
-                .ok_or_else(|| anyhow::anyhow!("Argon2 produced no hash output"))?;
-            let key_bytes = hash_output.as_bytes();
-            let key = Key::<Aes256Gcm>::from_slice(&key_bytes[..32]);
+            let cipher = password_cipher(password, &salt)?;
 
             // Decrypt
             let nonce_bytes = BASE64
@@ -1476,7 +1917,6 @@ impl SecureKeyStore {
                 .decode(&record.ct)
                 .map_err(|_| anyhow::anyhow!("Invalid stored ciphertext"))?;
 
-            let cipher = Aes256Gcm::new(key);
             let plaintext = cipher
                 .decrypt(nonce, ciphertext.as_ref())
                 .map_err(|_| anyhow::anyhow!("Invalid password"))?;
@@ -1495,7 +1935,7 @@ impl SecureKeyStore {
     fn delete_key(&self, address: &str) -> Result<()> {
         // Try to delete from keychain
         if !self.use_file_fallback {
-            if let Ok(entry) = Entry::new(KEYRING_SERVICE, &format!("wallet_{}", address)) {
+            if let Ok(entry) = Entry::new(&keyring_service(), &format!("wallet_{}", address)) {
                 let _ = entry.delete_password(); // Ignore errors, we'll also try file
             }
         }
@@ -1901,4 +2341,53 @@ mod tests {
             10_000_000_000_000_000_000u128
         );
     }
+
+    // ========== Wallet Backup Tests ==========
+
+    fn sample_backup() -> WalletBackupPayload {
+        WalletBackupPayload {
+            created_at: 1_700_000_000,
+            accounts: vec![BackupAccount {
+                account: Account {
+                    address: "0xabc".to_string(),
+                    label: "Main".to_string(),
+                    public_key: "00".repeat(32),
+                    balance: 0,
+                    nonce: 0,
+                    created_at: 1_700_000_000,
+                },
+                keystore: "{\"salt\":\"s\",\"nonce\":\"n\",\"ct\":\"c\"}".to_string(),
+            }],
+            settings: WalletBackupSettings {
+                tracked_addresses: vec!["0xdef".to_string()],
+                reward_address: Some("0xabc".to_string()),
+            },
+        }
+    }
+
+    #[test]
+    fn test_backup_roundtrip() {
+        let archive = seal_backup(&sample_backup(), "Backup-Pass-9x!").unwrap();
+        assert!(!archive.contains("Main"), "Backup contents must be encrypted");
+
+        let restored = open_backup(&archive, "Backup-Pass-9x!").unwrap();
+        assert_eq!(restored.accounts.len(), 1);
+        assert_eq!(restored.accounts[0].account.label, "Main");
+        assert_eq!(restored.accounts[0].keystore, sample_backup().accounts[0].keystore);
+        assert_eq!(restored.settings, sample_backup().settings);
+    }
+
+    #[test]
+    fn test_backup_wrong_password_rejected() {
+        let archive = seal_backup(&sample_backup(), "Backup-Pass-9x!").unwrap();
+        let err = open_backup(&archive, "Wrong-Pass-9x!").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid backup password");
+    }
+
+    #[test]
+    fn test_backup_rejects_other_files() {
+        assert!(open_backup("not json", "Backup-Pass-9x!").is_err());
+        let other = r#"{"format":"other","version":1,"salt":"","nonce":"","ct":""}"#;
+        assert!(open_backup(other, "Backup-Pass-9x!").is_err());
+    }
 }
@@ -0,0 +1,586 @@
+//! Multi-signature wallets for the desktop wallet
+//!
+//! An m-of-n wallet lives in the node's multisig precompile; this module
+//! keeps the ones the user created or imported, with a local name. A wallet
+//! created here stays pending until the receipt of its creation carries the
+//! on-chain wallet id. Proposals and approvals are read from chain state, so
+//! approvals given by co-signers on other machines show up as soon as their
+//! transactions are included. Proposing, approving and executing are
+//! ordinary transactions signed by one of the owners' wallet accounts.
+
+use anyhow::Result;
+use citrate_execution::address_utils::{address_from_hex, to_checksum_address};
+use citrate_execution::precompiles::multisig::{
+    self, MultisigProposal, MultisigWallet, MAX_OWNERS, MULTISIG_PRECOMPILE,
+};
+use citrate_execution::state::StateDB;
+use citrate_execution::units::{parse_units, Unit};
+use citrate_execution::{Address, TransactionReceipt};
+use primitive_types::U256;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use super::TransactionRequest;
+
+/// Gas limit for multisig transactions; creation stores the owner list
+const MULTISIG_GAS_LIMIT: u64 = 300_000;
+
+/// Gas price for multisig transactions, in wei
+const MULTISIG_GAS_PRICE: &str = "1000000000";
+
+/// Longest accepted wallet name
+const MAX_NAME_LEN: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MultisigStatus {
+    /// Creation submitted, no receipt yet
+    Pending,
+    /// Exists on chain
+    Active,
+    /// Creation was included but reverted
+    Failed,
+}
+
+/// A multisig wallet tracked by this wallet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultisigAccount {
+    pub id: String,
+    pub name: String,
+    /// EIP-55 checksummed owners
+    pub owners: Vec<String>,
+    pub threshold: u64,
+    /// Id in the multisig precompile, once the wallet exists on chain
+    pub wallet_id: Option<u64>,
+    /// Transaction that created the wallet; `None` for imported wallets
+    pub creation_tx: Option<String>,
+    pub status: MultisigStatus,
+    pub created_at: u64,
+}
+
+/// Fields supplied when creating a multisig wallet
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MultisigDraft {
+    pub name: String,
+    /// Creating account, which pays for the creation and any deposit
+    pub from: String,
+    pub owners: Vec<String>,
+    pub threshold: u64,
+    /// Funds deposited at creation, in wei
+    pub deposit: Option<String>,
+}
+
+fn valid_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(anyhow::anyhow!(
+            "Name must be 1 to {} characters",
+            MAX_NAME_LEN
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn wei(amount: &str) -> Result<U256> {
+    parse_units(amount.trim(), Unit::Wei)
+        .map(U256::from)
+        .map_err(|e| anyhow::anyhow!("Invalid amount: {}", e))
+}
+
+fn request(from: String, value: U256, data: Vec<u8>) -> TransactionRequest {
+    TransactionRequest {
+        from,
+        to: Some(MULTISIG_PRECOMPILE.to_string()),
+        value: value.to_string(),
+        gas_limit: MULTISIG_GAS_LIMIT,
+        gas_price: MULTISIG_GAS_PRICE.to_string(),
+        data: format!("0x{}", hex::encode(data)),
+    }
+}
+
+impl MultisigDraft {
+    /// Validate the draft and build the account to track and the
+    /// transaction that creates the wallet
+    pub fn prepare(&self, now: u64) -> Result<(MultisigAccount, TransactionRequest)> {
+        let name = valid_name(&self.name)?;
+        let mut owners: Vec<Address> = Vec::with_capacity(self.owners.len());
+        for owner in &self.owners {
+            let owner = address_from_hex(owner.trim()).map_err(anyhow::Error::msg)?;
+            if owner == Address::zero() {
+                return Err(anyhow::anyhow!("Owner cannot be the zero address"));
+            }
+            if owners.contains(&owner) {
+                return Err(anyhow::anyhow!(
+                    "{} is listed twice",
+                    to_checksum_address(&owner)
+                ));
+            }
+            owners.push(owner);
+        }
+        if owners.is_empty() || owners.len() > MAX_OWNERS {
+            return Err(anyhow::anyhow!(
+                "A multisig needs 1 to {} owners",
+                MAX_OWNERS
+            ));
+        }
+        if self.threshold == 0 || self.threshold > owners.len() as u64 {
+            return Err(anyhow::anyhow!(
+                "Threshold must be between 1 and {}",
+                owners.len()
+            ));
+        }
+        let deposit = match self.deposit.as_deref().map(str::trim) {
+            Some(amount) if !amount.is_empty() => wei(amount)?,
+            _ => U256::zero(),
+        };
+
+        let account = MultisigAccount {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            owners: owners.iter().map(to_checksum_address).collect(),
+            threshold: self.threshold,
+            wallet_id: None,
+            creation_tx: None,
+            status: MultisigStatus::Pending,
+            created_at: now,
+        };
+        let request = request(
+            self.from.trim().to_string(),
+            deposit,
+            multisig::create_call_data(&owners, self.threshold),
+        );
+        Ok((account, request))
+    }
+}
+
+/// An on-chain wallet, amounts as decimal wei strings
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultisigWalletInfo {
+    pub wallet_id: u64,
+    pub owners: Vec<String>,
+    pub threshold: u64,
+    pub balance: String,
+    pub proposal_count: u64,
+    pub created_height: u64,
+}
+
+impl From<&MultisigWallet> for MultisigWalletInfo {
+    fn from(wallet: &MultisigWallet) -> Self {
+        Self {
+            wallet_id: wallet.id,
+            owners: wallet.owners.iter().map(to_checksum_address).collect(),
+            threshold: wallet.threshold,
+            balance: wallet.balance.to_string(),
+            proposal_count: wallet.proposal_count,
+            created_height: wallet.created_height,
+        }
+    }
+}
+
+/// A proposed transfer with the approvals it has and still needs
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultisigProposalInfo {
+    pub id: u64,
+    pub wallet_id: u64,
+    pub proposer: String,
+    pub to: String,
+    pub value: String,
+    pub approvals: Vec<String>,
+    /// Owners that have not approved yet
+    pub awaiting: Vec<String>,
+    /// Awaiting owners held by this wallet, whose approval can be given here
+    pub local_signers: Vec<String>,
+    /// Approvals still missing before the transfer can be executed
+    pub approvals_needed: u64,
+    /// Approved by enough owners and funded
+    pub ready: bool,
+    pub created_height: u64,
+    pub executed_height: Option<u64>,
+}
+
+impl MultisigProposalInfo {
+    fn new(proposal: &MultisigProposal, wallet: &MultisigWallet, local: &[Address]) -> Self {
+        let awaiting: Vec<&Address> = wallet
+            .owners
+            .iter()
+            .filter(|owner| !proposal.approved_by(owner))
+            .collect();
+        let approvals_needed = wallet
+            .threshold
+            .saturating_sub(proposal.approvals.len() as u64);
+        Self {
+            id: proposal.id,
+            wallet_id: proposal.wallet,
+            proposer: to_checksum_address(&proposal.proposer),
+            to: to_checksum_address(&proposal.to),
+            value: proposal.value.to_string(),
+            approvals: proposal.approvals.iter().map(to_checksum_address).collect(),
+            awaiting: awaiting
+                .iter()
+                .map(|owner| to_checksum_address(owner))
+                .collect(),
+            local_signers: awaiting
+                .iter()
+                .filter(|owner| local.contains(owner))
+                .map(|owner| to_checksum_address(owner))
+                .collect(),
+            approvals_needed,
+            ready: !proposal.is_executed()
+                && approvals_needed == 0
+                && wallet.balance >= proposal.value,
+            created_height: proposal.created_height,
+            executed_height: proposal.executed_height,
+        }
+    }
+}
+
+fn parse_local(local_accounts: &[String]) -> Vec<Address> {
+    local_accounts
+        .iter()
+        .filter_map(|address| address_from_hex(address.trim()).ok())
+        .collect()
+}
+
+pub fn wallet_info(state_db: &StateDB, wallet_id: u64) -> Option<MultisigWalletInfo> {
+    multisig::wallet(state_db, wallet_id).map(|wallet| MultisigWalletInfo::from(&wallet))
+}
+
+/// Proposals made on one wallet, newest first
+pub fn list_proposals(
+    state_db: &StateDB,
+    wallet_id: u64,
+    local_accounts: &[String],
+) -> Vec<MultisigProposalInfo> {
+    let Some(wallet) = multisig::wallet(state_db, wallet_id) else {
+        return Vec::new();
+    };
+    let local = parse_local(local_accounts);
+    multisig::proposals_of(state_db, &wallet)
+        .iter()
+        .rev()
+        .map(|proposal| MultisigProposalInfo::new(proposal, &wallet, &local))
+        .collect()
+}
+
+/// Proposals not yet executed on any of `wallet_ids`, newest first
+pub fn pending_proposals(
+    state_db: &StateDB,
+    wallet_ids: &[u64],
+    local_accounts: &[String],
+) -> Vec<MultisigProposalInfo> {
+    let local = parse_local(local_accounts);
+    let mut pending: Vec<MultisigProposalInfo> = wallet_ids
+        .iter()
+        .filter_map(|id| multisig::wallet(state_db, *id))
+        .flat_map(|wallet| {
+            multisig::proposals_of(state_db, &wallet)
+                .into_iter()
+                .filter(|proposal| !proposal.is_executed())
+                .map(|proposal| MultisigProposalInfo::new(&proposal, &wallet, &local))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    pending.sort_by_key(|p| std::cmp::Reverse(p.id));
+    pending
+}
+
+/// Transaction adding `amount` wei to a wallet
+pub fn deposit_request(from: String, wallet_id: u64, amount: &str) -> Result<TransactionRequest> {
+    let amount = wei(amount)?;
+    if amount.is_zero() {
+        return Err(anyhow::anyhow!("Amount must be greater than zero"));
+    }
+    Ok(request(
+        from,
+        amount,
+        multisig::deposit_call_data(wallet_id),
+    ))
+}
+
+/// Transaction proposing to send `amount` wei out of a wallet
+pub fn propose_request(
+    from: String,
+    wallet_id: u64,
+    to: &str,
+    amount: &str,
+) -> Result<TransactionRequest> {
+    let to = address_from_hex(to.trim()).map_err(anyhow::Error::msg)?;
+    let amount = wei(amount)?;
+    if amount.is_zero() {
+        return Err(anyhow::anyhow!("Amount must be greater than zero"));
+    }
+    Ok(request(
+        from,
+        U256::zero(),
+        multisig::propose_call_data(wallet_id, &to, amount),
+    ))
+}
+
+pub fn approve_request(from: String, proposal_id: u64) -> TransactionRequest {
+    request(from, U256::zero(), multisig::approve_call_data(proposal_id))
+}
+
+pub fn revoke_request(from: String, proposal_id: u64) -> TransactionRequest {
+    request(from, U256::zero(), multisig::revoke_call_data(proposal_id))
+}
+
+pub fn execute_request(from: String, proposal_id: u64) -> TransactionRequest {
+    request(from, U256::zero(), multisig::execute_call_data(proposal_id))
+}
+
+/// Multisig wallets tracked by the wallet, persisted as JSON
+pub struct MultisigBook {
+    path: Option<PathBuf>,
+    /// Oldest first
+    accounts: RwLock<Vec<MultisigAccount>>,
+}
+
+impl MultisigBook {
+    /// Open the book at the default location
+    pub fn new() -> Result<Self> {
+        Self::open(Self::default_path())
+    }
+
+    /// Open the book stored at `path`, starting empty if it does not exist yet
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let accounts = if path.exists() {
+            serde_json::from_str(&std::fs::read_to_string(&path)?)?
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            path: Some(path),
+            accounts: RwLock::new(accounts),
+        })
+    }
+
+    /// A book that is never written to disk
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            accounts: RwLock::new(Vec::new()),
+        }
+    }
+
+    fn default_path() -> PathBuf {
+        crate::profiles::data_root().join("multisig_wallets.json")
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Vec<MultisigAccount>> {
+        self.accounts.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Vec<MultisigAccount>> {
+        self.accounts.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn persist(&self, accounts: &[MultisigAccount]) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(accounts)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Tracked wallets, newest first
+    pub fn list(&self) -> Vec<MultisigAccount> {
+        self.read().iter().rev().cloned().collect()
+    }
+
+    /// On-chain ids of the wallets that exist
+    pub fn active_wallet_ids(&self) -> Vec<u64> {
+        self.read()
+            .iter()
+            .filter(|a| a.status == MultisigStatus::Active)
+            .filter_map(|a| a.wallet_id)
+            .collect()
+    }
+
+    /// Track a wallet whose creation was submitted in `tx_hash`
+    pub fn record_created(
+        &self,
+        mut account: MultisigAccount,
+        tx_hash: String,
+    ) -> Result<MultisigAccount> {
+        account.creation_tx = Some(tx_hash);
+        let mut accounts = self.write();
+        accounts.push(account.clone());
+        self.persist(&accounts)?;
+        Ok(account)
+    }
+
+    /// Track an existing wallet, e.g. one created by a co-signer
+    pub fn import(&self, name: &str, wallet: &MultisigWallet, now: u64) -> Result<MultisigAccount> {
+        let name = valid_name(name)?;
+        let mut accounts = self.write();
+        if accounts.iter().any(|a| a.wallet_id == Some(wallet.id)) {
+            return Err(anyhow::anyhow!(
+                "Multisig wallet {} is already tracked",
+                wallet.id
+            ));
+        }
+        let account = MultisigAccount {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            owners: wallet.owners.iter().map(to_checksum_address).collect(),
+            threshold: wallet.threshold,
+            wallet_id: Some(wallet.id),
+            creation_tx: None,
+            status: MultisigStatus::Active,
+            created_at: now,
+        };
+        accounts.push(account.clone());
+        self.persist(&accounts)?;
+        Ok(account)
+    }
+
+    /// Stop tracking a wallet. The wallet itself stays on chain.
+    pub fn remove(&self, id: &str) -> Result<()> {
+        let mut accounts = self.write();
+        let before = accounts.len();
+        accounts.retain(|a| a.id != id);
+        if accounts.len() == before {
+            return Err(anyhow::anyhow!("Multisig wallet not found"));
+        }
+        self.persist(&accounts)
+    }
+
+    /// Settle pending creations whose receipts `lookup` finds, returning the
+    /// ones that changed
+    pub fn settle<F>(&self, mut lookup: F) -> Result<Vec<MultisigAccount>>
+    where
+        F: FnMut(&str) -> Option<TransactionReceipt>,
+    {
+        let mut accounts = self.write();
+        let mut settled = Vec::new();
+        for account in accounts
+            .iter_mut()
+            .filter(|a| a.status == MultisigStatus::Pending)
+        {
+            let Some(receipt) = account.creation_tx.as_deref().and_then(&mut lookup) else {
+                continue;
+            };
+            // createWallet returns the new wallet id as a uint256
+            account.wallet_id = (receipt.status && receipt.output.len() == 32)
+                .then(|| U256::from_big_endian(&receipt.output).try_into().ok())
+                .flatten();
+            account.status = if account.wallet_id.is_some() {
+                MultisigStatus::Active
+            } else {
+                MultisigStatus::Failed
+            };
+            settled.push(account.clone());
+        }
+        if !settled.is_empty() {
+            self.persist(&accounts)?;
+        }
+        Ok(settled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use citrate_execution::Hash;
+
+    const ALICE: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+    const BOB: &str = "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359";
+
+    fn draft(owners: &[&str], threshold: u64) -> MultisigDraft {
+        MultisigDraft {
+            name: "Treasury".to_string(),
+            from: ALICE.to_string(),
+            owners: owners.iter().map(|o| o.to_lowercase()).collect(),
+            threshold,
+            deposit: Some("1000".to_string()),
+        }
+    }
+
+    fn receipt(status: bool, output: Vec<u8>) -> TransactionReceipt {
+        TransactionReceipt {
+            tx_hash: Hash::default(),
+            block_hash: Hash::default(),
+            block_number: 7,
+            from: Address([1u8; 20]),
+            to: Some(MULTISIG_PRECOMPILE),
+            gas_used: 50_000,
+            status,
+            logs: vec![],
+            output,
+        }
+    }
+
+    #[test]
+    fn test_prepare_validates_owners_and_threshold() {
+        assert!(draft(&[ALICE, BOB], 3).prepare(1).is_err());
+        assert!(draft(&[ALICE, BOB], 0).prepare(1).is_err());
+        assert!(draft(&[ALICE, ALICE], 1).prepare(1).is_err());
+        assert!(draft(&[], 1).prepare(1).is_err());
+
+        let (account, request) = draft(&[ALICE, BOB], 2).prepare(1).unwrap();
+        assert_eq!(account.owners, vec![ALICE.to_string(), BOB.to_string()]);
+        assert_eq!(account.status, MultisigStatus::Pending);
+        assert_eq!(request.value, "1000");
+        assert_eq!(request.to, Some(MULTISIG_PRECOMPILE.to_string()));
+        let owners: Vec<Address> = [ALICE, BOB]
+            .iter()
+            .map(|o| address_from_hex(o).unwrap())
+            .collect();
+        assert_eq!(
+            request.data,
+            format!("0x{}", hex::encode(multisig::create_call_data(&owners, 2)))
+        );
+    }
+
+    #[test]
+    fn test_settle_records_wallet_id_or_failure() {
+        let book = MultisigBook::in_memory();
+        for tx in ["aa", "bb", "cc"] {
+            let (account, _) = draft(&[ALICE, BOB], 2).prepare(1).unwrap();
+            book.record_created(account, tx.to_string()).unwrap();
+        }
+
+        let mut id = vec![0u8; 32];
+        id[31] = 3;
+        let settled = book
+            .settle(|hash| match hash {
+                "aa" => Some(receipt(true, id.clone())),
+                "bb" => Some(receipt(false, vec![])),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(settled.len(), 2);
+        assert_eq!(settled[0].status, MultisigStatus::Active);
+        assert_eq!(settled[0].wallet_id, Some(3));
+        assert_eq!(settled[1].status, MultisigStatus::Failed);
+        assert_eq!(book.active_wallet_ids(), vec![3]);
+
+        // Importing a tracked wallet twice is refused
+        let wallet = MultisigWallet {
+            id: 3,
+            owners: vec![Address([2u8; 20])],
+            threshold: 1,
+            balance: U256::zero(),
+            proposal_count: 0,
+            created_height: 7,
+        };
+        assert!(book.import("Shared", &wallet, 2).is_err());
+        let imported = book
+            .import("Shared", &MultisigWallet { id: 4, ..wallet }, 2)
+            .unwrap();
+        assert_eq!(imported.status, MultisigStatus::Active);
+        assert_eq!(book.active_wallet_ids(), vec![3, 4]);
+        assert_eq!(book.list()[0].wallet_id, Some(4));
+    }
+}
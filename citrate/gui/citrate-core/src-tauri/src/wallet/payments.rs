@@ -0,0 +1,482 @@
+//! Scheduled and recurring payments for the desktop wallet
+//!
+//! A scheduled payment sends a fixed amount from a wallet account to one
+//! recipient every `interval_secs`, starting at `start_at` and stopping
+//! after `end_at` if one is set. The node manager polls for payments that
+//! are due and submits them. Signing happens without the user only while
+//! the account's key is available unattended (an unlocked session or a
+//! remote signer); otherwise the payment waits for approval and the user is
+//! asked for the password.
+//!
+//! Periods missed while the wallet was closed or the payment was paused are
+//! skipped rather than paid in bulk, so reopening the app never sends a
+//! burst of back payments.
+
+use anyhow::Result;
+use citrate_execution::address_utils::{address_from_hex, to_checksum_address};
+use citrate_execution::units::{parse_units, Unit};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Tauri event emitted for every scheduled payment run
+pub const SCHEDULED_PAYMENT_EVENT: &str = "scheduled-payment";
+
+/// Shortest accepted interval between payments
+const MIN_INTERVAL_SECS: u64 = 60;
+
+/// Longest accepted payment label
+const MAX_LABEL_LEN: usize = 64;
+
+/// Runs kept in the payment history
+const MAX_HISTORY: usize = 500;
+
+/// Where a scheduled payment is in its lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentStatus {
+    Active,
+    Paused,
+    /// Due, but the account could not sign without the password
+    AwaitingApproval,
+    /// Past its end date
+    Completed,
+}
+
+/// A recurring transfer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledPayment {
+    pub id: String,
+    pub label: Option<String>,
+    /// Sending wallet account
+    pub from: String,
+    /// EIP-55 checksummed recipient
+    pub to: String,
+    /// Amount per payment in wei, as a decimal string
+    pub amount: String,
+    /// Gas price in wei; the mempool minimum when unset
+    pub gas_price: Option<String>,
+    pub interval_secs: u64,
+    pub start_at: u64,
+    pub end_at: Option<u64>,
+    /// When the next payment is due
+    pub next_run: u64,
+    pub status: PaymentStatus,
+    /// Payments submitted so far
+    pub runs: u32,
+    pub last_run: Option<u64>,
+    pub created_at: u64,
+}
+
+/// Fields supplied when scheduling a payment
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PaymentDraft {
+    pub label: Option<String>,
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+    pub gas_price: Option<String>,
+    pub interval_secs: u64,
+    /// First payment time; now when unset
+    pub start_at: Option<u64>,
+    pub end_at: Option<u64>,
+}
+
+/// What happened when a payment came due
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunOutcome {
+    /// Signed and handed to the mempool
+    Submitted,
+    /// Signing or submission failed; the period is skipped
+    Failed,
+    /// Waiting for the user to approve with the password
+    NeedsApproval,
+}
+
+/// One execution of a scheduled payment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentRun {
+    pub payment_id: String,
+    pub label: Option<String>,
+    pub to: String,
+    pub amount: String,
+    /// When the payment was due
+    pub due_at: u64,
+    pub executed_at: u64,
+    pub outcome: RunOutcome,
+    pub tx_hash: Option<String>,
+    pub error: Option<String>,
+}
+
+impl PaymentRun {
+    /// A run of `payment` at `executed_at` without a result yet
+    pub fn new(payment: &ScheduledPayment, executed_at: u64, outcome: RunOutcome) -> Self {
+        Self {
+            payment_id: payment.id.clone(),
+            label: payment.label.clone(),
+            to: payment.to.clone(),
+            amount: payment.amount.clone(),
+            due_at: payment.next_run,
+            executed_at,
+            outcome,
+            tx_hash: None,
+            error: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ScheduleData {
+    payments: Vec<ScheduledPayment>,
+    /// Oldest first
+    history: Vec<PaymentRun>,
+}
+
+/// Scheduled payments and their run history, persisted as JSON
+pub struct PaymentScheduler {
+    path: Option<PathBuf>,
+    data: RwLock<ScheduleData>,
+}
+
+impl PaymentScheduler {
+    /// Open the schedule at the default location
+    pub fn new() -> Result<Self> {
+        Self::open(Self::default_path())
+    }
+
+    /// Open the schedule stored at `path`, starting empty if it does not
+    /// exist yet
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let data = if path.exists() {
+            serde_json::from_str(&std::fs::read_to_string(&path)?)?
+        } else {
+            ScheduleData::default()
+        };
+        Ok(Self {
+            path: Some(path),
+            data: RwLock::new(data),
+        })
+    }
+
+    /// A schedule that is never written to disk
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            data: RwLock::new(ScheduleData::default()),
+        }
+    }
+
+    fn default_path() -> PathBuf {
+        crate::profiles::data_root().join("scheduled_payments.json")
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, ScheduleData> {
+        self.data.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, ScheduleData> {
+        self.data.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn persist(&self, data: &ScheduleData) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(data)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Scheduled payments, soonest first
+    pub fn list(&self) -> Vec<ScheduledPayment> {
+        let mut payments = self.read().payments.clone();
+        payments.sort_by_key(|p| (p.status == PaymentStatus::Completed, p.next_run));
+        payments
+    }
+
+    pub fn get(&self, id: &str) -> Option<ScheduledPayment> {
+        self.read().payments.iter().find(|p| p.id == id).cloned()
+    }
+
+    /// Schedule a payment. The recipient must already be resolved to an
+    /// address.
+    pub fn create(&self, draft: PaymentDraft, now: u64) -> Result<ScheduledPayment> {
+        let label = draft
+            .label
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty());
+        if label.as_ref().is_some_and(|l| l.len() > MAX_LABEL_LEN) {
+            return Err(anyhow::anyhow!(
+                "Payment label must be at most {} characters",
+                MAX_LABEL_LEN
+            ));
+        }
+        if draft.from.trim().is_empty() {
+            return Err(anyhow::anyhow!("Choose the account to pay from"));
+        }
+        let to = address_from_hex(draft.to.trim()).map_err(anyhow::Error::msg)?;
+        let amount = parse_units(draft.amount.trim(), Unit::Wei)
+            .map_err(|e| anyhow::anyhow!("Invalid amount: {}", e))?;
+        if amount == 0 {
+            return Err(anyhow::anyhow!("Amount must be greater than zero"));
+        }
+        let gas_price = match draft.gas_price.map(|p| p.trim().to_string()) {
+            Some(price) if !price.is_empty() => {
+                parse_units(&price, Unit::Wei)
+                    .ok()
+                    .and_then(|p| u64::try_from(p).ok())
+                    .ok_or_else(|| anyhow::anyhow!("Invalid gas price: {}", price))?;
+                Some(price)
+            }
+            _ => None,
+        };
+        if draft.interval_secs < MIN_INTERVAL_SECS {
+            return Err(anyhow::anyhow!(
+                "Interval must be at least {} seconds",
+                MIN_INTERVAL_SECS
+            ));
+        }
+        let start_at = draft.start_at.unwrap_or(now).max(now);
+        if draft.end_at.is_some_and(|end| end < start_at) {
+            return Err(anyhow::anyhow!("End date is before the first payment"));
+        }
+
+        let payment = ScheduledPayment {
+            id: uuid::Uuid::new_v4().to_string(),
+            label,
+            from: draft.from.trim().to_string(),
+            to: to_checksum_address(&to),
+            amount: amount.to_string(),
+            gas_price,
+            interval_secs: draft.interval_secs,
+            start_at,
+            end_at: draft.end_at,
+            next_run: start_at,
+            status: PaymentStatus::Active,
+            runs: 0,
+            last_run: None,
+            created_at: now,
+        };
+        let mut data = self.write();
+        data.payments.push(payment.clone());
+        self.persist(&data)?;
+        Ok(payment)
+    }
+
+    /// Stop a payment from running until it is resumed
+    pub fn pause(&self, id: &str) -> Result<ScheduledPayment> {
+        self.update(id, |payment| match payment.status {
+            PaymentStatus::Completed => Err(anyhow::anyhow!("Payment has already completed")),
+            _ => {
+                payment.status = PaymentStatus::Paused;
+                Ok(())
+            }
+        })
+    }
+
+    /// Resume a paused payment. Periods missed while paused are skipped.
+    pub fn resume(&self, id: &str, now: u64) -> Result<ScheduledPayment> {
+        self.update(id, |payment| {
+            if payment.status != PaymentStatus::Paused {
+                return Err(anyhow::anyhow!("Payment is not paused"));
+            }
+            payment.status = PaymentStatus::Active;
+            if payment.next_run < now {
+                advance(payment, now);
+            }
+            Ok(())
+        })
+    }
+
+    pub fn remove(&self, id: &str) -> Result<()> {
+        let mut data = self.write();
+        let before = data.payments.len();
+        data.payments.retain(|p| p.id != id);
+        if data.payments.len() == before {
+            return Err(anyhow::anyhow!("No scheduled payment with id '{}'", id));
+        }
+        self.persist(&data)
+    }
+
+    /// Active payments due at `now`
+    pub fn due(&self, now: u64) -> Vec<ScheduledPayment> {
+        self.read()
+            .payments
+            .iter()
+            .filter(|p| p.status == PaymentStatus::Active && p.next_run <= now)
+            .cloned()
+            .collect()
+    }
+
+    /// Record a run and move its payment on. Submitted and failed runs
+    /// schedule the next period; a run needing approval holds the payment
+    /// until it is approved.
+    pub fn record(&self, run: PaymentRun) -> Result<ScheduledPayment> {
+        let mut data = self.write();
+        let payment = data
+            .payments
+            .iter_mut()
+            .find(|p| p.id == run.payment_id)
+            .ok_or_else(|| anyhow::anyhow!("No scheduled payment with id '{}'", run.payment_id))?;
+        match run.outcome {
+            RunOutcome::Submitted | RunOutcome::Failed => {
+                if run.outcome == RunOutcome::Submitted {
+                    payment.runs += 1;
+                    payment.last_run = Some(run.executed_at);
+                }
+                payment.status = PaymentStatus::Active;
+                advance(payment, run.executed_at);
+            }
+            RunOutcome::NeedsApproval => payment.status = PaymentStatus::AwaitingApproval,
+        }
+        let payment = payment.clone();
+
+        data.history.push(run);
+        let overflow = data.history.len().saturating_sub(MAX_HISTORY);
+        data.history.drain(..overflow);
+        self.persist(&data)?;
+        Ok(payment)
+    }
+
+    /// Past runs, newest first, optionally of one payment only
+    pub fn history(&self, id: Option<&str>, limit: usize) -> Vec<PaymentRun> {
+        self.read()
+            .history
+            .iter()
+            .rev()
+            .filter(|run| id.is_none_or(|id| run.payment_id == id))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    fn update(
+        &self,
+        id: &str,
+        apply: impl FnOnce(&mut ScheduledPayment) -> Result<()>,
+    ) -> Result<ScheduledPayment> {
+        let mut data = self.write();
+        let payment = data
+            .payments
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| anyhow::anyhow!("No scheduled payment with id '{}'", id))?;
+        apply(payment)?;
+        let payment = payment.clone();
+        self.persist(&data)?;
+        Ok(payment)
+    }
+}
+
+/// Move `next_run` to the first period after `now`, completing the payment
+/// once that is past its end date
+fn advance(payment: &mut ScheduledPayment, now: u64) {
+    let interval = payment.interval_secs.max(1);
+    if payment.next_run <= now {
+        let missed = (now - payment.next_run) / interval + 1;
+        payment.next_run = payment
+            .next_run
+            .saturating_add(missed.saturating_mul(interval));
+    }
+    if payment.end_at.is_some_and(|end| payment.next_run > end) {
+        payment.status = PaymentStatus::Completed;
+    }
+}
+
+pub fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOB: &str = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+
+    fn draft(interval_secs: u64, end_at: Option<u64>) -> PaymentDraft {
+        PaymentDraft {
+            label: Some(" Rent ".to_string()),
+            from: "0xabc".to_string(),
+            to: BOB.to_string(),
+            amount: "1000".to_string(),
+            interval_secs,
+            start_at: Some(1_000),
+            end_at,
+            ..Default::default()
+        }
+    }
+
+    fn run(payment: &ScheduledPayment, at: u64, outcome: RunOutcome) -> PaymentRun {
+        PaymentRun::new(payment, at, outcome)
+    }
+
+    #[test]
+    fn test_create_validates_draft() {
+        let schedule = PaymentScheduler::in_memory();
+        let payment = schedule.create(draft(3_600, None), 500).unwrap();
+        assert_eq!(payment.label.as_deref(), Some("Rent"));
+        assert_eq!(payment.to, "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed");
+        assert_eq!(payment.next_run, 1_000);
+
+        assert!(schedule.create(draft(10, None), 500).is_err());
+        assert!(schedule.create(draft(3_600, Some(900)), 500).is_err());
+        let mut zero = draft(3_600, None);
+        zero.amount = "0".to_string();
+        assert!(schedule.create(zero, 500).is_err());
+        let mut bad_recipient = draft(3_600, None);
+        bad_recipient.to = "0x1234".to_string();
+        assert!(schedule.create(bad_recipient, 500).is_err());
+        assert_eq!(schedule.list().len(), 1);
+    }
+
+    #[test]
+    fn test_runs_advance_pause_and_complete() {
+        let schedule = PaymentScheduler::in_memory();
+        let payment = schedule.create(draft(100, Some(1_250)), 0).unwrap();
+        assert!(schedule.due(999).is_empty());
+        assert_eq!(schedule.due(1_000).len(), 1);
+
+        // Without an unlocked key the payment waits for approval
+        let held = schedule
+            .record(run(&payment, 1_000, RunOutcome::NeedsApproval))
+            .unwrap();
+        assert_eq!(held.status, PaymentStatus::AwaitingApproval);
+        assert!(schedule.due(1_050).is_empty());
+
+        // Approved late: the periods missed meanwhile are skipped
+        let paid = schedule
+            .record(run(&held, 1_150, RunOutcome::Submitted))
+            .unwrap();
+        assert_eq!(paid.status, PaymentStatus::Active);
+        assert_eq!(paid.runs, 1);
+        assert_eq!(paid.next_run, 1_200);
+
+        schedule.pause(&payment.id).unwrap();
+        assert!(schedule.due(1_200).is_empty());
+        let resumed = schedule.resume(&payment.id, 1_230).unwrap();
+        assert_eq!(resumed.next_run, 1_300);
+        assert_eq!(resumed.status, PaymentStatus::Completed);
+        assert!(schedule.pause(&payment.id).is_err());
+
+        let history = schedule.history(Some(&payment.id), 10);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].outcome, RunOutcome::Submitted);
+        assert_eq!(history[1].due_at, 1_000);
+    }
+}
@@ -0,0 +1,109 @@
+//! WalletConnect v2 message encryption
+//!
+//! Messages are ChaCha20-Poly1305 sealed and base64 encoded in a type 0
+//! envelope: `[0x00][12-byte IV][ciphertext + tag]`. Session keys come from
+//! an X25519 exchange with the dapp, expanded with HKDF-SHA256, and every
+//! topic is the SHA-256 of its symmetric key.
+
+use super::WalletConnectError;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hkdf::Hkdf;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+const ENVELOPE_TYPE_0: u8 = 0;
+const IV_LENGTH: usize = 12;
+
+/// Seal `plaintext` with `sym_key` into a type 0 envelope
+pub fn encrypt(sym_key: &[u8; 32], plaintext: &[u8]) -> Result<String, WalletConnectError> {
+    let mut iv = [0u8; IV_LENGTH];
+    OsRng.fill_bytes(&mut iv);
+
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(sym_key));
+    let sealed = cipher
+        .encrypt(Nonce::from_slice(&iv), plaintext)
+        .map_err(|_| WalletConnectError::Crypto("encryption failed".into()))?;
+
+    let mut envelope = Vec::with_capacity(1 + IV_LENGTH + sealed.len());
+    envelope.push(ENVELOPE_TYPE_0);
+    envelope.extend_from_slice(&iv);
+    envelope.extend_from_slice(&sealed);
+    Ok(BASE64.encode(envelope))
+}
+
+/// Open a type 0 envelope sealed with `sym_key`
+pub fn decrypt(sym_key: &[u8; 32], message: &str) -> Result<Vec<u8>, WalletConnectError> {
+    let envelope = BASE64
+        .decode(message)
+        .map_err(|_| WalletConnectError::Crypto("envelope is not base64".into()))?;
+    if envelope.len() <= 1 + IV_LENGTH {
+        return Err(WalletConnectError::Crypto("envelope too short".into()));
+    }
+    if envelope[0] != ENVELOPE_TYPE_0 {
+        return Err(WalletConnectError::Crypto(format!(
+            "unsupported envelope type {}",
+            envelope[0]
+        )));
+    }
+
+    let (iv, sealed) = envelope[1..].split_at(IV_LENGTH);
+    ChaCha20Poly1305::new(Key::from_slice(sym_key))
+        .decrypt(Nonce::from_slice(iv), sealed)
+        .map_err(|_| WalletConnectError::Crypto("decryption failed".into()))
+}
+
+/// Topic messages sealed with `sym_key` are published on
+pub fn topic_for(sym_key: &[u8; 32]) -> String {
+    hex::encode(Sha256::digest(sym_key))
+}
+
+/// X25519 key pair used to agree on a session key with a dapp
+pub struct KeyPair {
+    secret: StaticSecret,
+    pub public: [u8; 32],
+}
+
+impl KeyPair {
+    pub fn generate() -> Self {
+        let secret = StaticSecret::random_from_rng(OsRng);
+        let public = PublicKey::from(&secret).to_bytes();
+        Self { secret, public }
+    }
+
+    /// Session key shared with the holder of `peer_public`
+    pub fn derive_sym_key(&self, peer_public: &[u8; 32]) -> [u8; 32] {
+        let shared = self.secret.diffie_hellman(&PublicKey::from(*peer_public));
+        let mut sym_key = [0u8; 32];
+        Hkdf::<Sha256>::new(None, shared.as_bytes())
+            .expand(&[], &mut sym_key)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        sym_key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_round_trip() {
+        let key = [7u8; 32];
+        let sealed = encrypt(&key, b"{\"id\":1}").unwrap();
+        assert_eq!(decrypt(&key, &sealed).unwrap(), b"{\"id\":1}");
+        assert!(decrypt(&[8u8; 32], &sealed).is_err());
+        assert!(decrypt(&key, "AAAA").is_err());
+    }
+
+    #[test]
+    fn test_key_agreement() {
+        let wallet = KeyPair::generate();
+        let dapp = KeyPair::generate();
+        let key = wallet.derive_sym_key(&dapp.public);
+        assert_eq!(key, dapp.derive_sym_key(&wallet.public));
+        assert_eq!(topic_for(&key).len(), 64);
+    }
+}
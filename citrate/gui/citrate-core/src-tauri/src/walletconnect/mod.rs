@@ -0,0 +1,1054 @@
+//! WalletConnect v2 dApp connector
+//!
+//! Lets external dapps pair with the GUI wallet through the WalletConnect
+//! relay and request signatures and transactions.
+//!
+//! Flow:
+//! - The user pastes (or scans) a `wc:` pairing URI; we subscribe to the
+//!   pairing topic and wait for the dapp's session proposal
+//! - Approving a proposal grants the session a set of accounts, chains and
+//!   methods; anything outside those permissions is refused without
+//!   prompting the user
+//! - `eth_sendTransaction` and `personal_sign` requests are queued for
+//!   approval and then signed through `WalletManager`, so the usual
+//!   password, session and re-authentication rules apply
+//!
+//! Sessions live in memory and end when the app closes.
+
+pub mod crypto;
+pub mod relay;
+pub mod uri;
+
+use crate::node::NodeManager;
+use crate::wallet::{TransactionRequest, WalletManager};
+use citrate_network::NetworkMessage;
+use citrate_sequencer::mempool::TxClass;
+use crypto::KeyPair;
+use relay::{RelayClient, RelayMessage, DEFAULT_RELAY_URL};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::{broadcast, RwLock};
+use tracing::{debug, info, warn};
+use uri::PairingUri;
+
+/// Tauri event carrying [`WalletConnectEvent`]s to the frontend
+pub const WALLETCONNECT_EVENT: &str = "walletconnect-event";
+
+/// Methods dapps may be granted
+pub const SUPPORTED_METHODS: &[&str] = &["eth_sendTransaction", "personal_sign"];
+
+/// Events dapps may subscribe to
+const SUPPORTED_EVENTS: &[&str] = &["accountsChanged", "chainChanged"];
+
+/// How long an approved session lasts
+const SESSION_EXPIRY_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Relay message tags and TTLs from the WalletConnect v2 sign spec
+mod tags {
+    pub const SESSION_PROPOSE_APPROVE: u32 = 1101;
+    pub const SESSION_PROPOSE_REJECT: u32 = 1120;
+    pub const SESSION_SETTLE: u32 = 1102;
+    pub const SESSION_REQUEST_RESPONSE: u32 = 1109;
+    pub const SESSION_DELETE: u32 = 1112;
+    pub const SESSION_DELETE_RESPONSE: u32 = 1113;
+    pub const SESSION_PING_RESPONSE: u32 = 1115;
+    pub const PAIRING_DELETE_RESPONSE: u32 = 1001;
+    pub const PAIRING_PING_RESPONSE: u32 = 1003;
+
+    pub const TTL_FIVE_MINUTES: u64 = 300;
+    pub const TTL_ONE_DAY: u64 = 86_400;
+}
+
+/// JSON-RPC error codes returned to dapps
+mod codes {
+    pub const USER_REJECTED: i64 = 5000;
+    pub const UNSUPPORTED_CHAINS: i64 = 5100;
+    pub const UNSUPPORTED_METHODS: i64 = 5101;
+    pub const UNAUTHORIZED_ACCOUNT: i64 = 4100;
+    pub const USER_DISCONNECTED: i64 = 6000;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+}
+
+#[derive(Error, Debug)]
+pub enum WalletConnectError {
+    #[error("Invalid WalletConnect URI: {0}")]
+    InvalidUri(String),
+
+    #[error("WalletConnect encryption error: {0}")]
+    Crypto(String),
+
+    #[error("WalletConnect relay error: {0}")]
+    Relay(String),
+
+    #[error("WalletConnect project ID is not configured")]
+    NotConfigured,
+
+    #[error("Pairing URI has expired")]
+    Expired,
+
+    #[error("Unknown session proposal {0}")]
+    UnknownProposal(u64),
+
+    #[error("Unknown session {0}")]
+    UnknownSession(String),
+
+    #[error("Unknown session request {0}")]
+    UnknownRequest(u64),
+
+    #[error("{0}")]
+    Rejected(String),
+
+    #[error("Wallet error: {0}")]
+    Wallet(String),
+}
+
+/// Connector settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletConnectConfig {
+    /// Project ID from the WalletConnect cloud dashboard
+    pub project_id: Option<String>,
+    pub relay_url: String,
+    /// How the wallet presents itself to dapps
+    pub metadata: AppMetadata,
+}
+
+impl Default for WalletConnectConfig {
+    fn default() -> Self {
+        Self {
+            project_id: std::env::var("CITRATE_WALLETCONNECT_PROJECT_ID").ok(),
+            relay_url: DEFAULT_RELAY_URL.to_string(),
+            metadata: AppMetadata {
+                name: "Citrate Wallet".to_string(),
+                description: "Citrate desktop wallet".to_string(),
+                url: "https://citrate.ai".to_string(),
+                icons: vec![],
+            },
+        }
+    }
+}
+
+/// Name and branding of a WalletConnect peer
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppMetadata {
+    pub name: String,
+    pub description: String,
+    pub url: String,
+    pub icons: Vec<String>,
+}
+
+/// A dapp asking to open a session
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionProposal {
+    pub id: u64,
+    pub pairing_topic: String,
+    pub proposer: AppMetadata,
+    /// Chains the dapp cannot work without, e.g. `eip155:1337`
+    pub required_chains: Vec<String>,
+    pub optional_chains: Vec<String>,
+    pub methods: Vec<String>,
+    pub events: Vec<String>,
+    #[serde(skip)]
+    proposer_public_key: [u8; 32],
+}
+
+/// What a session is allowed to do
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionPermissions {
+    /// Wallet addresses exposed to the dapp
+    pub accounts: Vec<String>,
+    /// CAIP-2 chain IDs
+    pub chains: Vec<String>,
+    pub methods: Vec<String>,
+    pub events: Vec<String>,
+}
+
+impl SessionPermissions {
+    fn allows_account(&self, address: &str) -> bool {
+        self.accounts
+            .iter()
+            .any(|a| a.eq_ignore_ascii_case(address))
+    }
+
+    /// CAIP-10 account IDs for every chain/address pair
+    fn caip_accounts(&self) -> Vec<String> {
+        self.chains
+            .iter()
+            .flat_map(|chain| {
+                self.accounts
+                    .iter()
+                    .map(move |account| format!("{}:{}", chain, account))
+            })
+            .collect()
+    }
+}
+
+/// An approved dapp session
+#[derive(Debug, Clone, Serialize)]
+pub struct Session {
+    pub topic: String,
+    pub peer: AppMetadata,
+    pub permissions: SessionPermissions,
+    pub expiry: u64,
+    #[serde(skip)]
+    sym_key: [u8; 32],
+}
+
+/// A dapp request waiting for the user
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingRequest {
+    pub id: u64,
+    pub topic: String,
+    pub peer: AppMetadata,
+    pub chain_id: String,
+    pub method: String,
+    pub params: Value,
+}
+
+/// Notifications for the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WalletConnectEvent {
+    SessionProposal(SessionProposal),
+    SessionRequest(PendingRequest),
+    SessionDeleted { topic: String },
+}
+
+/// Manages pairings, sessions and dapp requests
+pub struct WalletConnectManager {
+    config: RwLock<WalletConnectConfig>,
+    relay: RwLock<Option<Arc<RelayClient>>>,
+    /// Pairing topic -> symmetric key
+    pairings: RwLock<HashMap<String, [u8; 32]>>,
+    proposals: RwLock<HashMap<u64, SessionProposal>>,
+    sessions: RwLock<HashMap<String, Session>>,
+    requests: RwLock<HashMap<u64, PendingRequest>>,
+    wallet_manager: Arc<WalletManager>,
+    node_manager: Arc<NodeManager>,
+    events: broadcast::Sender<WalletConnectEvent>,
+    next_id: AtomicU64,
+}
+
+impl WalletConnectManager {
+    pub fn new(wallet_manager: Arc<WalletManager>, node_manager: Arc<NodeManager>) -> Self {
+        let (events, _) = broadcast::channel(64);
+        Self {
+            config: RwLock::new(WalletConnectConfig::default()),
+            relay: RwLock::new(None),
+            pairings: RwLock::new(HashMap::new()),
+            proposals: RwLock::new(HashMap::new()),
+            sessions: RwLock::new(HashMap::new()),
+            requests: RwLock::new(HashMap::new()),
+            wallet_manager,
+            node_manager,
+            events,
+            next_id: AtomicU64::new(now_secs() * 1_000_000),
+        }
+    }
+
+    /// Subscribe to proposal, request and disconnect notifications
+    pub fn subscribe(&self) -> broadcast::Receiver<WalletConnectEvent> {
+        self.events.subscribe()
+    }
+
+    pub async fn get_config(&self) -> WalletConnectConfig {
+        self.config.read().await.clone()
+    }
+
+    pub async fn set_project_id(&self, project_id: String) {
+        let project_id = project_id.trim().to_string();
+        self.config.write().await.project_id = (!project_id.is_empty()).then_some(project_id);
+    }
+
+    /// Pair with a dapp from its `wc:` URI
+    pub async fn pair(self: &Arc<Self>, uri: &str) -> Result<(), WalletConnectError> {
+        let pairing = PairingUri::parse(uri)?;
+        if pairing.is_expired(now_secs()) {
+            return Err(WalletConnectError::Expired);
+        }
+
+        let relay = self.relay().await?;
+        self.pairings
+            .write()
+            .await
+            .insert(pairing.topic.clone(), pairing.sym_key);
+        relay.subscribe(&pairing.topic).await?;
+        info!("WalletConnect pairing {} established", &pairing.topic[..8]);
+        Ok(())
+    }
+
+    pub async fn proposals(&self) -> Vec<SessionProposal> {
+        self.proposals.read().await.values().cloned().collect()
+    }
+
+    pub async fn sessions(&self) -> Vec<Session> {
+        self.sessions.read().await.values().cloned().collect()
+    }
+
+    pub async fn pending_requests(&self) -> Vec<PendingRequest> {
+        let mut requests: Vec<_> = self.requests.read().await.values().cloned().collect();
+        requests.sort_by_key(|r| r.id);
+        requests
+    }
+
+    /// Approve a proposal, exposing `accounts` on the node's chain
+    pub async fn approve_session(
+        self: &Arc<Self>,
+        proposal_id: u64,
+        accounts: Vec<String>,
+    ) -> Result<Session, WalletConnectError> {
+        let proposal = self
+            .proposals
+            .read()
+            .await
+            .get(&proposal_id)
+            .cloned()
+            .ok_or(WalletConnectError::UnknownProposal(proposal_id))?;
+
+        if accounts.is_empty() {
+            return Err(WalletConnectError::Rejected(
+                "Select at least one account".into(),
+            ));
+        }
+        for account in &accounts {
+            if self.wallet_manager.get_account(account).await.is_none() {
+                return Err(WalletConnectError::Wallet(format!(
+                    "Account {} is not in this wallet",
+                    account
+                )));
+            }
+        }
+
+        let chain = format!(
+            "eip155:{}",
+            self.node_manager.get_config().await.mempool.chain_id
+        );
+        if let Some(missing) = proposal.required_chains.iter().find(|c| **c != chain) {
+            return Err(WalletConnectError::Rejected(format!(
+                "Dapp requires {} but this wallet is on {}",
+                missing, chain
+            )));
+        }
+
+        let permissions = SessionPermissions {
+            accounts,
+            chains: vec![chain],
+            methods: proposal
+                .methods
+                .iter()
+                .filter(|m| SUPPORTED_METHODS.contains(&m.as_str()))
+                .cloned()
+                .collect(),
+            events: proposal
+                .events
+                .iter()
+                .filter(|e| SUPPORTED_EVENTS.contains(&e.as_str()))
+                .cloned()
+                .collect(),
+        };
+
+        let pairing_key = self.pairing_key(&proposal.pairing_topic).await?;
+        let key_pair = KeyPair::generate();
+        let sym_key = key_pair.derive_sym_key(&proposal.proposer_public_key);
+        let topic = crypto::topic_for(&sym_key);
+        let session = Session {
+            topic: topic.clone(),
+            peer: proposal.proposer.clone(),
+            permissions,
+            expiry: now_secs() + SESSION_EXPIRY_SECS,
+            sym_key,
+        };
+
+        let relay = self.relay().await?;
+        relay.subscribe(&topic).await?;
+        self.sessions
+            .write()
+            .await
+            .insert(topic.clone(), session.clone());
+
+        self.send(
+            &proposal.pairing_topic,
+            &pairing_key,
+            json!({
+                "id": proposal.id,
+                "jsonrpc": "2.0",
+                "result": {
+                    "relay": { "protocol": "irn" },
+                    "responderPublicKey": hex::encode(key_pair.public),
+                },
+            }),
+            tags::SESSION_PROPOSE_APPROVE,
+            tags::TTL_FIVE_MINUTES,
+        )
+        .await?;
+
+        let metadata = self.config.read().await.metadata.clone();
+        self.send(
+            &topic,
+            &sym_key,
+            json!({
+                "id": self.payload_id(),
+                "jsonrpc": "2.0",
+                "method": "wc_sessionSettle",
+                "params": {
+                    "relay": { "protocol": "irn" },
+                    "namespaces": {
+                        "eip155": {
+                            "chains": session.permissions.chains,
+                            "accounts": session.permissions.caip_accounts(),
+                            "methods": session.permissions.methods,
+                            "events": session.permissions.events,
+                        },
+                    },
+                    "controller": {
+                        "publicKey": hex::encode(key_pair.public),
+                        "metadata": metadata,
+                    },
+                    "expiry": session.expiry,
+                },
+            }),
+            tags::SESSION_SETTLE,
+            tags::TTL_FIVE_MINUTES,
+        )
+        .await?;
+
+        self.proposals.write().await.remove(&proposal_id);
+        info!(
+            "WalletConnect session with {} approved for {} account(s)",
+            session.peer.name,
+            session.permissions.accounts.len()
+        );
+        Ok(session)
+    }
+
+    pub async fn reject_session(&self, proposal_id: u64) -> Result<(), WalletConnectError> {
+        let proposal = self
+            .proposals
+            .write()
+            .await
+            .remove(&proposal_id)
+            .ok_or(WalletConnectError::UnknownProposal(proposal_id))?;
+        let pairing_key = self.pairing_key(&proposal.pairing_topic).await?;
+        self.send(
+            &proposal.pairing_topic,
+            &pairing_key,
+            error_response(proposal.id, codes::USER_REJECTED, "User rejected."),
+            tags::SESSION_PROPOSE_REJECT,
+            tags::TTL_FIVE_MINUTES,
+        )
+        .await
+    }
+
+    /// End a session from the wallet side
+    pub async fn disconnect(&self, topic: &str) -> Result<(), WalletConnectError> {
+        let session = self
+            .sessions
+            .write()
+            .await
+            .remove(topic)
+            .ok_or_else(|| WalletConnectError::UnknownSession(topic.to_string()))?;
+        self.drop_requests(topic).await;
+
+        let result = self
+            .send(
+                topic,
+                &session.sym_key,
+                json!({
+                    "id": self.payload_id(),
+                    "jsonrpc": "2.0",
+                    "method": "wc_sessionDelete",
+                    "params": { "code": codes::USER_DISCONNECTED, "message": "User disconnected." },
+                }),
+                tags::SESSION_DELETE,
+                tags::TTL_ONE_DAY,
+            )
+            .await;
+        if let Some(relay) = self.relay.read().await.clone() {
+            let _ = relay.unsubscribe(topic).await;
+        }
+        result
+    }
+
+    /// Sign and answer a queued request. On a wallet error (e.g. wrong
+    /// password) the request stays queued so the user can retry.
+    pub async fn approve_request(
+        &self,
+        request_id: u64,
+        password: Option<String>,
+    ) -> Result<String, WalletConnectError> {
+        let request = self
+            .requests
+            .write()
+            .await
+            .remove(&request_id)
+            .ok_or(WalletConnectError::UnknownRequest(request_id))?;
+        let sym_key = self.session_key(&request.topic).await?;
+        let password = password.unwrap_or_default();
+
+        let outcome = match request.method.as_str() {
+            "eth_sendTransaction" => self.send_transaction(&request, &password).await,
+            "personal_sign" => self.personal_sign(&request, &password).await,
+            other => Err(WalletConnectError::Rejected(format!(
+                "Unsupported method {}",
+                other
+            ))),
+        };
+
+        match outcome {
+            Ok(result) => {
+                self.send(
+                    &request.topic,
+                    &sym_key,
+                    json!({ "id": request.id, "jsonrpc": "2.0", "result": result }),
+                    tags::SESSION_REQUEST_RESPONSE,
+                    tags::TTL_FIVE_MINUTES,
+                )
+                .await?;
+                Ok(result)
+            }
+            Err(e) => {
+                self.requests.write().await.insert(request.id, request);
+                Err(e)
+            }
+        }
+    }
+
+    pub async fn reject_request(&self, request_id: u64) -> Result<(), WalletConnectError> {
+        let request = self
+            .requests
+            .write()
+            .await
+            .remove(&request_id)
+            .ok_or(WalletConnectError::UnknownRequest(request_id))?;
+        let sym_key = self.session_key(&request.topic).await?;
+        self.send(
+            &request.topic,
+            &sym_key,
+            error_response(request.id, codes::USER_REJECTED, "User rejected."),
+            tags::SESSION_REQUEST_RESPONSE,
+            tags::TTL_FIVE_MINUTES,
+        )
+        .await
+    }
+
+    async fn send_transaction(
+        &self,
+        request: &PendingRequest,
+        password: &str,
+    ) -> Result<String, WalletConnectError> {
+        let tx = transaction_request(&request.params)?;
+        let session = self
+            .sessions
+            .read()
+            .await
+            .get(&request.topic)
+            .cloned()
+            .ok_or_else(|| WalletConnectError::UnknownSession(request.topic.clone()))?;
+        if !session.permissions.allows_account(&tx.from) {
+            return Err(WalletConnectError::Rejected(format!(
+                "Account {} is not shared with this dapp",
+                tx.from
+            )));
+        }
+
+        let tx = self
+            .wallet_manager
+            .create_signed_transaction(tx, password)
+            .await
+            .map_err(|e| WalletConnectError::Wallet(e.to_string()))?;
+        let tx_hash = format!("0x{}", hex::encode(tx.hash.as_bytes()));
+
+        self.node_manager.track_transaction(tx.hash).await;
+        if let Some(mempool) = self.node_manager.get_mempool().await {
+            let _ = mempool.add_transaction(tx.clone(), TxClass::Standard).await;
+        }
+        let _ = self
+            .node_manager
+            .broadcast_network(NetworkMessage::NewTransaction { transaction: tx })
+            .await;
+        info!(
+            "WalletConnect transaction {} submitted for {}",
+            tx_hash, request.peer.name
+        );
+        Ok(tx_hash)
+    }
+
+    /// Signs with the account's Ed25519 key; the message is hex (`0x...`)
+    /// or UTF-8 text
+    async fn personal_sign(
+        &self,
+        request: &PendingRequest,
+        password: &str,
+    ) -> Result<String, WalletConnectError> {
+        let (message, address) = personal_sign_params(&request.params)?;
+        let session = self
+            .sessions
+            .read()
+            .await
+            .get(&request.topic)
+            .cloned()
+            .ok_or_else(|| WalletConnectError::UnknownSession(request.topic.clone()))?;
+        if !session.permissions.allows_account(&address) {
+            return Err(WalletConnectError::Rejected(format!(
+                "Account {} is not shared with this dapp",
+                address
+            )));
+        }
+
+        let signature = self
+            .wallet_manager
+            .sign_message(&message, &address, password)
+            .await
+            .map_err(|e| WalletConnectError::Wallet(e.to_string()))?;
+        Ok(format!("0x{}", signature))
+    }
+
+    /// Relay connection, connecting on first use
+    async fn relay(self: &Arc<Self>) -> Result<Arc<RelayClient>, WalletConnectError> {
+        let mut relay = self.relay.write().await;
+        if let Some(client) = relay.as_ref().filter(|c| c.is_connected()) {
+            return Ok(client.clone());
+        }
+
+        let config = self.config.read().await.clone();
+        let project_id = config.project_id.ok_or(WalletConnectError::NotConfigured)?;
+        let (client, mut incoming) = RelayClient::connect(&config.relay_url, &project_id).await?;
+        let client = Arc::new(client);
+
+        // Resubscribe after a reconnect
+        let topics: Vec<String> = self
+            .pairings
+            .read()
+            .await
+            .keys()
+            .chain(self.sessions.read().await.keys())
+            .cloned()
+            .collect();
+        for topic in topics {
+            client.subscribe(&topic).await?;
+        }
+
+        let manager = self.clone();
+        tokio::spawn(async move {
+            while let Some(message) = incoming.recv().await {
+                if let Err(e) = manager.handle_message(message).await {
+                    warn!("WalletConnect message dropped: {}", e);
+                }
+            }
+        });
+
+        *relay = Some(client.clone());
+        Ok(client)
+    }
+
+    async fn handle_message(&self, message: RelayMessage) -> Result<(), WalletConnectError> {
+        let (sym_key, is_session) = match self.sessions.read().await.get(&message.topic) {
+            Some(session) => (session.sym_key, true),
+            None => (self.pairing_key(&message.topic).await?, false),
+        };
+        let payload: Value = serde_json::from_slice(&crypto::decrypt(&sym_key, &message.message)?)
+            .map_err(|e| WalletConnectError::Crypto(e.to_string()))?;
+        let id = payload["id"].as_u64().unwrap_or_default();
+
+        let Some(method) = payload["method"].as_str() else {
+            // Responses to our own settle/delete messages
+            if let Some(error) = payload.get("error") {
+                warn!("WalletConnect peer returned an error: {}", error);
+            }
+            return Ok(());
+        };
+        debug!("WalletConnect {} on {}", method, &message.topic[..8]);
+
+        match method {
+            "wc_sessionPropose" if !is_session => {
+                let proposal = parse_proposal(id, &message.topic, &payload["params"])?;
+                self.proposals.write().await.insert(id, proposal.clone());
+                let _ = self
+                    .events
+                    .send(WalletConnectEvent::SessionProposal(proposal));
+                Ok(())
+            }
+            "wc_sessionRequest" if is_session => {
+                self.queue_request(id, &message.topic, &sym_key, &payload["params"])
+                    .await
+            }
+            "wc_sessionDelete" if is_session => {
+                self.sessions.write().await.remove(&message.topic);
+                self.drop_requests(&message.topic).await;
+                if let Some(relay) = self.relay.read().await.clone() {
+                    let _ = relay.unsubscribe(&message.topic).await;
+                }
+                let _ = self.events.send(WalletConnectEvent::SessionDeleted {
+                    topic: message.topic.clone(),
+                });
+                self.reply(&message.topic, &sym_key, id, tags::SESSION_DELETE_RESPONSE)
+                    .await
+            }
+            "wc_sessionPing" => {
+                self.reply(&message.topic, &sym_key, id, tags::SESSION_PING_RESPONSE)
+                    .await
+            }
+            "wc_pairingPing" => {
+                self.reply(&message.topic, &sym_key, id, tags::PAIRING_PING_RESPONSE)
+                    .await
+            }
+            "wc_pairingDelete" => {
+                self.pairings.write().await.remove(&message.topic);
+                self.reply(&message.topic, &sym_key, id, tags::PAIRING_DELETE_RESPONSE)
+                    .await
+            }
+            _ => {
+                self.send(
+                    &message.topic,
+                    &sym_key,
+                    error_response(id, codes::METHOD_NOT_FOUND, "Method not found"),
+                    tags::SESSION_REQUEST_RESPONSE,
+                    tags::TTL_FIVE_MINUTES,
+                )
+                .await
+            }
+        }
+    }
+
+    /// Queue a session request for the user, or refuse it outright when it
+    /// falls outside the session's permissions
+    async fn queue_request(
+        &self,
+        id: u64,
+        topic: &str,
+        sym_key: &[u8; 32],
+        params: &Value,
+    ) -> Result<(), WalletConnectError> {
+        let Some(session) = self.sessions.read().await.get(topic).cloned() else {
+            return Err(WalletConnectError::UnknownSession(topic.to_string()));
+        };
+        let chain_id = params["chainId"].as_str().unwrap_or_default().to_string();
+        let method = params["request"]["method"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let request_params = params["request"]["params"].clone();
+
+        let refusal = if !session.permissions.chains.contains(&chain_id) {
+            Some((
+                codes::UNSUPPORTED_CHAINS,
+                format!("Chain {} is not approved", chain_id),
+            ))
+        } else if !session.permissions.methods.contains(&method) {
+            Some((
+                codes::UNSUPPORTED_METHODS,
+                format!("Method {} is not approved", method),
+            ))
+        } else {
+            let account = match method.as_str() {
+                "eth_sendTransaction" => request_params[0]["from"].as_str(),
+                "personal_sign" => request_params[1].as_str(),
+                _ => None,
+            };
+            match account {
+                Some(account) if session.permissions.allows_account(account) => None,
+                _ => Some((
+                    codes::UNAUTHORIZED_ACCOUNT,
+                    "Account is not shared with this dapp".to_string(),
+                )),
+            }
+        };
+        if let Some((code, message)) = refusal {
+            debug!(
+                "Refused WalletConnect {} from {}: {}",
+                method, session.peer.name, message
+            );
+            return self
+                .send(
+                    topic,
+                    sym_key,
+                    error_response(id, code, &message),
+                    tags::SESSION_REQUEST_RESPONSE,
+                    tags::TTL_FIVE_MINUTES,
+                )
+                .await;
+        }
+
+        let request = PendingRequest {
+            id,
+            topic: topic.to_string(),
+            peer: session.peer,
+            chain_id,
+            method,
+            params: request_params,
+        };
+        self.requests.write().await.insert(id, request.clone());
+        let _ = self
+            .events
+            .send(WalletConnectEvent::SessionRequest(request));
+        Ok(())
+    }
+
+    async fn drop_requests(&self, topic: &str) {
+        self.requests.write().await.retain(|_, r| r.topic != topic);
+    }
+
+    async fn pairing_key(&self, topic: &str) -> Result<[u8; 32], WalletConnectError> {
+        self.pairings
+            .read()
+            .await
+            .get(topic)
+            .copied()
+            .ok_or_else(|| WalletConnectError::UnknownSession(topic.to_string()))
+    }
+
+    async fn session_key(&self, topic: &str) -> Result<[u8; 32], WalletConnectError> {
+        self.sessions
+            .read()
+            .await
+            .get(topic)
+            .map(|s| s.sym_key)
+            .ok_or_else(|| WalletConnectError::UnknownSession(topic.to_string()))
+    }
+
+    async fn reply(
+        &self,
+        topic: &str,
+        sym_key: &[u8; 32],
+        id: u64,
+        tag: u32,
+    ) -> Result<(), WalletConnectError> {
+        self.send(
+            topic,
+            sym_key,
+            json!({ "id": id, "jsonrpc": "2.0", "result": true }),
+            tag,
+            tags::TTL_FIVE_MINUTES,
+        )
+        .await
+    }
+
+    async fn send(
+        &self,
+        topic: &str,
+        sym_key: &[u8; 32],
+        payload: Value,
+        tag: u32,
+        ttl_secs: u64,
+    ) -> Result<(), WalletConnectError> {
+        let relay = self
+            .relay
+            .read()
+            .await
+            .clone()
+            .ok_or_else(|| WalletConnectError::Relay("not connected".into()))?;
+        let message = crypto::encrypt(sym_key, payload.to_string().as_bytes())?;
+        relay.publish(topic, &message, tag, ttl_secs).await
+    }
+
+    fn payload_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+/// Read a `wc_sessionPropose` payload
+fn parse_proposal(
+    id: u64,
+    pairing_topic: &str,
+    params: &Value,
+) -> Result<SessionProposal, WalletConnectError> {
+    let public_key = params["proposer"]["publicKey"]
+        .as_str()
+        .and_then(|k| hex::decode(k).ok())
+        .and_then(|k| <[u8; 32]>::try_from(k.as_slice()).ok())
+        .ok_or_else(|| WalletConnectError::Crypto("proposal has no proposer key".into()))?;
+
+    let strings = |value: &Value| -> Vec<String> {
+        value
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut proposal = SessionProposal {
+        id,
+        pairing_topic: pairing_topic.to_string(),
+        proposer: serde_json::from_value(params["proposer"]["metadata"].clone())
+            .unwrap_or_default(),
+        required_chains: vec![],
+        optional_chains: vec![],
+        methods: vec![],
+        events: vec![],
+        proposer_public_key: public_key,
+    };
+    for (field, required) in [("requiredNamespaces", true), ("optionalNamespaces", false)] {
+        let Some(namespaces) = params[field].as_object() else {
+            continue;
+        };
+        for (name, namespace) in namespaces {
+            // Namespaces are keyed by "eip155" or directly by a chain ID
+            let chains = if name.contains(':') {
+                vec![name.clone()]
+            } else {
+                strings(&namespace["chains"])
+            };
+            if required {
+                proposal.required_chains.extend(chains);
+            } else {
+                proposal.optional_chains.extend(chains);
+            }
+            proposal.methods.extend(strings(&namespace["methods"]));
+            proposal.events.extend(strings(&namespace["events"]));
+        }
+    }
+    proposal.methods.sort();
+    proposal.methods.dedup();
+    proposal.events.sort();
+    proposal.events.dedup();
+    Ok(proposal)
+}
+
+/// Map `eth_sendTransaction` params onto a wallet transaction request
+fn transaction_request(params: &Value) -> Result<TransactionRequest, WalletConnectError> {
+    let tx = &params[0];
+    let invalid =
+        |field: &str| WalletConnectError::Rejected(format!("Invalid {} in transaction", field));
+    let quantity = |field: &str| -> Result<Option<u128>, WalletConnectError> {
+        match tx[field].as_str() {
+            None => Ok(None),
+            Some(value) => {
+                let digits = value.strip_prefix("0x").ok_or_else(|| invalid(field))?;
+                u128::from_str_radix(digits, 16)
+                    .map(Some)
+                    .map_err(|_| invalid(field))
+            }
+        }
+    };
+
+    let gas_limit = quantity("gas")?.unwrap_or(21_000);
+    Ok(TransactionRequest {
+        from: tx["from"]
+            .as_str()
+            .ok_or_else(|| invalid("from"))?
+            .to_string(),
+        to: tx["to"].as_str().map(str::to_string),
+        value: quantity("value")?.unwrap_or(0).to_string(),
+        gas_limit: u64::try_from(gas_limit).map_err(|_| invalid("gas"))?,
+        gas_price: quantity("gasPrice")?.unwrap_or(1_000_000_000).to_string(),
+        data: tx["data"]
+            .as_str()
+            .or_else(|| tx["input"].as_str())
+            .unwrap_or_default()
+            .to_string(),
+    })
+}
+
+/// `personal_sign` params are `[message, address]`
+fn personal_sign_params(params: &Value) -> Result<(Vec<u8>, String), WalletConnectError> {
+    let invalid = || WalletConnectError::Rejected("Invalid personal_sign params".into());
+    let message = params[0].as_str().ok_or_else(invalid)?;
+    let address = params[1].as_str().ok_or_else(invalid)?;
+
+    let bytes = match message.strip_prefix("0x") {
+        Some(hex_message) => hex::decode(hex_message).map_err(|_| invalid())?,
+        None => message.as_bytes().to_vec(),
+    };
+    Ok((bytes, address.to_string()))
+}
+
+fn error_response(id: u64, code: i64, message: &str) -> Value {
+    json!({ "id": id, "jsonrpc": "2.0", "error": { "code": code, "message": message } })
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proposal() {
+        let params = json!({
+            "proposer": {
+                "publicKey": hex::encode([9u8; 32]),
+                "metadata": { "name": "Example dapp", "url": "https://dapp.example" },
+            },
+            "requiredNamespaces": {
+                "eip155": {
+                    "chains": ["eip155:1337"],
+                    "methods": ["eth_sendTransaction", "personal_sign"],
+                    "events": ["accountsChanged"],
+                },
+            },
+            "optionalNamespaces": {
+                "eip155:1": { "methods": ["eth_signTypedData_v4"], "events": [] },
+            },
+        });
+        let proposal = parse_proposal(42, "topic", &params).unwrap();
+        assert_eq!(proposal.proposer.name, "Example dapp");
+        assert_eq!(proposal.required_chains, vec!["eip155:1337"]);
+        assert_eq!(proposal.optional_chains, vec!["eip155:1"]);
+        assert_eq!(
+            proposal.methods,
+            vec![
+                "eth_sendTransaction",
+                "eth_signTypedData_v4",
+                "personal_sign"
+            ]
+        );
+        assert_eq!(proposal.proposer_public_key, [9u8; 32]);
+    }
+
+    #[test]
+    fn test_session_permissions() {
+        let permissions = SessionPermissions {
+            accounts: vec!["0xAbC0000000000000000000000000000000000001".into()],
+            chains: vec!["eip155:1337".into()],
+            methods: vec!["personal_sign".into()],
+            events: vec![],
+        };
+        assert!(permissions.allows_account("0xabc0000000000000000000000000000000000001"));
+        assert!(!permissions.allows_account("0xabc0000000000000000000000000000000000002"));
+        assert_eq!(
+            permissions.caip_accounts(),
+            vec!["eip155:1337:0xAbC0000000000000000000000000000000000001"]
+        );
+    }
+
+    #[test]
+    fn test_request_params() {
+        let tx = transaction_request(&json!([{
+            "from": "0x1111111111111111111111111111111111111111",
+            "to": "0x2222222222222222222222222222222222222222",
+            "value": "0xde0b6b3a7640000",
+            "gas": "0x5208",
+            "data": "0x",
+        }]))
+        .unwrap();
+        assert_eq!(tx.value, "1000000000000000000");
+        assert_eq!(tx.gas_limit, 21_000);
+        assert_eq!(tx.gas_price, "1000000000");
+        assert!(transaction_request(&json!([{ "from": "0x11", "value": "12" }])).is_err());
+
+        let (message, address) = personal_sign_params(&json!([
+            "0x68656c6c6f",
+            "0x1111111111111111111111111111111111111111"
+        ]))
+        .unwrap();
+        assert_eq!(message, b"hello");
+        assert_eq!(address, "0x1111111111111111111111111111111111111111");
+        assert_eq!(
+            personal_sign_params(&json!(["hi", "0x11"])).unwrap().0,
+            b"hi"
+        );
+    }
+}
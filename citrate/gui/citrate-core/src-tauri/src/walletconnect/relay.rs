@@ -0,0 +1,292 @@
+//! WalletConnect relay client
+//!
+//! Speaks the relay's JSON-RPC (`irn_subscribe`, `irn_publish`, ...) over a
+//! WebSocket. Messages delivered on subscribed topics are acknowledged and
+//! handed to the caller through a channel; payloads stay encrypted here.
+
+use super::WalletConnectError;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ed25519_dalek::{Signer, SigningKey};
+use futures::{SinkExt, StreamExt};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, Mutex};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, warn};
+
+/// Public WalletConnect relay
+pub const DEFAULT_RELAY_URL: &str = "wss://relay.walletconnect.org";
+
+/// How long relay requests wait for an answer
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Lifetime of the relay auth token
+const AUTH_TOKEN_TTL_SECS: u64 = 24 * 60 * 60;
+
+type PendingReplies = Arc<Mutex<HashMap<u64, oneshot::Sender<Result<Value, String>>>>>;
+
+/// An encrypted message received on a subscribed topic
+#[derive(Debug, Clone)]
+pub struct RelayMessage {
+    pub topic: String,
+    pub message: String,
+    pub tag: u32,
+}
+
+/// Connection to the relay
+pub struct RelayClient {
+    outgoing: mpsc::UnboundedSender<Message>,
+    pending: PendingReplies,
+    subscriptions: Mutex<HashMap<String, String>>,
+    next_id: AtomicU64,
+    connected: Arc<AtomicBool>,
+}
+
+impl RelayClient {
+    /// Connect and authenticate; incoming topic messages arrive on the receiver
+    pub async fn connect(
+        relay_url: &str,
+        project_id: &str,
+    ) -> Result<(Self, mpsc::UnboundedReceiver<RelayMessage>), WalletConnectError> {
+        let url = format!(
+            "{}/?auth={}&projectId={}",
+            relay_url.trim_end_matches('/'),
+            auth_token(relay_url),
+            urlencoding::encode(project_id)
+        );
+        let (socket, _) = tokio_tungstenite::connect_async(url)
+            .await
+            .map_err(|e| WalletConnectError::Relay(e.to_string()))?;
+        let (mut sink, mut stream) = socket.split();
+
+        let (outgoing, mut outgoing_rx) = mpsc::unbounded_channel::<Message>();
+        let (incoming, incoming_rx) = mpsc::unbounded_channel();
+        let pending: PendingReplies = Arc::new(Mutex::new(HashMap::new()));
+        let connected = Arc::new(AtomicBool::new(true));
+
+        let writer_connected = connected.clone();
+        tokio::spawn(async move {
+            while let Some(message) = outgoing_rx.recv().await {
+                if let Err(e) = sink.send(message).await {
+                    warn!("WalletConnect relay write failed: {}", e);
+                    break;
+                }
+            }
+            writer_connected.store(false, Ordering::Relaxed);
+        });
+
+        let replies = pending.clone();
+        let acks = outgoing.clone();
+        let reader_connected = connected.clone();
+        tokio::spawn(async move {
+            while let Some(Ok(message)) = stream.next().await {
+                let text = match message {
+                    Message::Text(text) => text,
+                    Message::Ping(payload) => {
+                        let _ = acks.send(Message::Pong(payload));
+                        continue;
+                    }
+                    Message::Close(_) => break,
+                    _ => continue,
+                };
+                let Ok(value) = serde_json::from_str::<Value>(&text) else {
+                    continue;
+                };
+
+                if value.get("method").and_then(|m| m.as_str()) == Some("irn_subscription") {
+                    let _ = acks.send(Message::Text(
+                        json!({ "id": value["id"], "jsonrpc": "2.0", "result": true }).to_string(),
+                    ));
+                    let data = &value["params"]["data"];
+                    if let (Some(topic), Some(message)) =
+                        (data["topic"].as_str(), data["message"].as_str())
+                    {
+                        let _ = incoming.send(RelayMessage {
+                            topic: topic.to_string(),
+                            message: message.to_string(),
+                            tag: data["tag"].as_u64().unwrap_or(0) as u32,
+                        });
+                    }
+                } else if let Some(id) = value.get("id").and_then(|id| id.as_u64()) {
+                    if let Some(reply) = replies.lock().await.remove(&id) {
+                        let result = match value.get("error") {
+                            Some(error) => Err(error.to_string()),
+                            None => Ok(value.get("result").cloned().unwrap_or(Value::Null)),
+                        };
+                        let _ = reply.send(result);
+                    }
+                }
+            }
+            debug!("WalletConnect relay connection closed");
+            reader_connected.store(false, Ordering::Relaxed);
+            // Dropping the senders fails any request still waiting
+            replies.lock().await.clear();
+        });
+
+        let client = Self {
+            outgoing,
+            pending,
+            subscriptions: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(now_millis() * 1000),
+            connected,
+        };
+        Ok((client, incoming_rx))
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    pub async fn subscribe(&self, topic: &str) -> Result<(), WalletConnectError> {
+        let result = self
+            .request("irn_subscribe", json!({ "topic": topic }))
+            .await?;
+        if let Some(id) = result.as_str() {
+            self.subscriptions
+                .lock()
+                .await
+                .insert(topic.to_string(), id.to_string());
+        }
+        Ok(())
+    }
+
+    pub async fn unsubscribe(&self, topic: &str) -> Result<(), WalletConnectError> {
+        let Some(id) = self.subscriptions.lock().await.remove(topic) else {
+            return Ok(());
+        };
+        self.request("irn_unsubscribe", json!({ "topic": topic, "id": id }))
+            .await
+            .map(|_| ())
+    }
+
+    /// Publish an already encrypted message
+    pub async fn publish(
+        &self,
+        topic: &str,
+        message: &str,
+        tag: u32,
+        ttl_secs: u64,
+    ) -> Result<(), WalletConnectError> {
+        self.request(
+            "irn_publish",
+            json!({ "topic": topic, "message": message, "ttl": ttl_secs, "tag": tag, "prompt": false }),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value, WalletConnectError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (reply, response) = oneshot::channel();
+        self.pending.lock().await.insert(id, reply);
+
+        let body = json!({ "id": id, "jsonrpc": "2.0", "method": method, "params": params });
+        if self.outgoing.send(Message::Text(body.to_string())).is_err() {
+            self.pending.lock().await.remove(&id);
+            return Err(WalletConnectError::Relay("not connected".into()));
+        }
+
+        match tokio::time::timeout(REQUEST_TIMEOUT, response).await {
+            Ok(Ok(result)) => result.map_err(WalletConnectError::Relay),
+            Ok(Err(_)) => Err(WalletConnectError::Relay("connection closed".into())),
+            Err(_) => {
+                self.pending.lock().await.remove(&id);
+                Err(WalletConnectError::Relay(format!("{} timed out", method)))
+            }
+        }
+    }
+}
+
+/// Relay auth token: a JWT signed by a fresh Ed25519 client key, identified
+/// by its `did:key`
+fn auth_token(audience: &str) -> String {
+    let mut seed = [0u8; 32];
+    OsRng.fill_bytes(&mut seed);
+    let signing_key = SigningKey::from_bytes(&seed);
+
+    let mut multicodec = vec![0xed, 0x01];
+    multicodec.extend_from_slice(signing_key.verifying_key().as_bytes());
+    let mut subject = [0u8; 32];
+    OsRng.fill_bytes(&mut subject);
+
+    let issued_at = now_millis() / 1000;
+    let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"EdDSA","typ":"JWT"}"#);
+    let claims = URL_SAFE_NO_PAD.encode(
+        json!({
+            "iss": format!("did:key:z{}", base58_encode(&multicodec)),
+            "sub": hex::encode(subject),
+            "aud": audience,
+            "iat": issued_at,
+            "exp": issued_at + AUTH_TOKEN_TTL_SECS,
+        })
+        .to_string(),
+    );
+    let signature = signing_key.sign(format!("{}.{}", header, claims).as_bytes());
+    format!(
+        "{}.{}.{}",
+        header,
+        claims,
+        URL_SAFE_NO_PAD.encode(signature.to_bytes())
+    )
+}
+
+/// Bitcoin-alphabet base58, as used by `did:key`
+fn base58_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+    let mut digits: Vec<u8> = Vec::new();
+    for &byte in bytes {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let zeros = bytes.iter().take_while(|b| **b == 0).count();
+    std::iter::repeat_n('1', zeros)
+        .chain(digits.iter().rev().map(|d| ALPHABET[*d as usize] as char))
+        .collect()
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base58_encode() {
+        assert_eq!(base58_encode(b""), "");
+        assert_eq!(base58_encode(&[0, 0, 1]), "112");
+        assert_eq!(base58_encode(b"hello world"), "StV1DL6CwTryKyV");
+    }
+
+    #[test]
+    fn test_auth_token_did_key() {
+        let token = auth_token(DEFAULT_RELAY_URL);
+        let parts: Vec<&str> = token.split('.').collect();
+        assert_eq!(parts.len(), 3);
+
+        let claims: Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(parts[1]).unwrap()).unwrap();
+        // Ed25519 did:keys always start with z6Mk
+        assert!(claims["iss"].as_str().unwrap().starts_with("did:key:z6Mk"));
+        assert_eq!(claims["aud"], DEFAULT_RELAY_URL);
+    }
+}
@@ -0,0 +1,116 @@
+//! WalletConnect v2 pairing URIs
+//!
+//! `wc:{topic}@2?relay-protocol=irn&symKey={hex}&expiryTimestamp={secs}`
+//! QR codes shown by dapps encode the same string.
+
+use super::WalletConnectError;
+
+/// A parsed pairing URI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairingUri {
+    /// Pairing topic (64 hex chars)
+    pub topic: String,
+    /// Relay protocol, always `irn` for the public relay
+    pub relay_protocol: String,
+    /// Symmetric key for messages on the pairing topic
+    pub sym_key: [u8; 32],
+    /// Unix time after which the pairing is no longer valid
+    pub expiry: Option<u64>,
+}
+
+impl PairingUri {
+    pub fn parse(uri: &str) -> Result<Self, WalletConnectError> {
+        let invalid = |reason: &str| WalletConnectError::InvalidUri(reason.to_string());
+
+        let rest = uri
+            .trim()
+            .strip_prefix("wc:")
+            .ok_or_else(|| invalid("missing wc: scheme"))?;
+        let (path, query) = rest
+            .split_once('?')
+            .ok_or_else(|| invalid("missing parameters"))?;
+        let (topic, version) = path
+            .split_once('@')
+            .ok_or_else(|| invalid("missing version"))?;
+
+        if version != "2" {
+            return Err(invalid("only WalletConnect v2 is supported"));
+        }
+        if topic.len() != 64 || !topic.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid("malformed topic"));
+        }
+
+        let mut relay_protocol = None;
+        let mut sym_key = None;
+        let mut expiry = None;
+        for pair in query.split('&') {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = urlencoding::decode(value).map_err(|_| invalid("bad encoding"))?;
+            match key {
+                "relay-protocol" => relay_protocol = Some(value.into_owned()),
+                "symKey" => {
+                    let bytes =
+                        hex::decode(value.as_ref()).map_err(|_| invalid("symKey is not hex"))?;
+                    sym_key = Some(
+                        <[u8; 32]>::try_from(bytes.as_slice())
+                            .map_err(|_| invalid("symKey must be 32 bytes"))?,
+                    );
+                }
+                "expiryTimestamp" => {
+                    expiry = Some(value.parse().map_err(|_| invalid("bad expiryTimestamp"))?);
+                }
+                _ => {}
+            }
+        }
+
+        let relay_protocol = relay_protocol.ok_or_else(|| invalid("missing relay-protocol"))?;
+        if relay_protocol != "irn" {
+            return Err(invalid("unsupported relay protocol"));
+        }
+
+        Ok(Self {
+            topic: topic.to_lowercase(),
+            relay_protocol,
+            sym_key: sym_key.ok_or_else(|| invalid("missing symKey"))?,
+            expiry,
+        })
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expiry.is_some_and(|expiry| expiry <= now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOPIC: &str = "7f6e504bfad60b485450578e05678ed3e8e8c4751d3c6160be17160d63ec90f9";
+    const SYM_KEY: &str = "587d5484ce2a2a6ee3ba1962fdd7e8588e06200c46823bd18fbd67def96ad303";
+
+    #[test]
+    fn test_parse_pairing_uri() {
+        let uri = format!(
+            "wc:{}@2?relay-protocol=irn&symKey={}&expiryTimestamp=1705000000&methods=%5Bwc_sessionPropose%5D",
+            TOPIC, SYM_KEY
+        );
+        let parsed = PairingUri::parse(&uri).unwrap();
+        assert_eq!(parsed.topic, TOPIC);
+        assert_eq!(parsed.relay_protocol, "irn");
+        assert_eq!(hex::encode(parsed.sym_key), SYM_KEY);
+        assert_eq!(parsed.expiry, Some(1705000000));
+        assert!(parsed.is_expired(1705000000));
+        assert!(!parsed.is_expired(1704999999));
+    }
+
+    #[test]
+    fn test_reject_bad_uris() {
+        let v1 = format!("wc:{}@1?bridge=https%3A%2F%2Fbridge&key={}", TOPIC, SYM_KEY);
+        assert!(PairingUri::parse(&v1).is_err());
+        assert!(PairingUri::parse(&format!("wc:{}@2?relay-protocol=irn", TOPIC)).is_err());
+        assert!(
+            PairingUri::parse(&format!("wc:abc@2?relay-protocol=irn&symKey={}", SYM_KEY)).is_err()
+        );
+        assert!(PairingUri::parse("https://example.com").is_err());
+    }
+}
@@ -0,0 +1,246 @@
+//! Accessibility
+//!
+//! Screen-reader metadata for open windows, keyboard focus cycling order and
+//! the display preferences (high contrast, zoom, reduced motion) that apply
+//! to every window. Preferences are stored in `accessibility.json` in the app
+//! config directory.
+
+use super::{WindowState, WindowType};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Smallest zoom factor accepted
+pub const MIN_ZOOM: f64 = 0.5;
+/// Largest zoom factor accepted
+pub const MAX_ZOOM: f64 = 3.0;
+
+/// Display preferences applied to all windows
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessibilityPreferences {
+    pub high_contrast: bool,
+    /// Webview zoom factor, 1.0 is 100%
+    pub zoom: f64,
+    pub reduced_motion: bool,
+}
+
+impl Default for AccessibilityPreferences {
+    fn default() -> Self {
+        Self {
+            high_contrast: false,
+            zoom: 1.0,
+            reduced_motion: false,
+        }
+    }
+}
+
+impl AccessibilityPreferences {
+    fn path() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("citrate-core")
+            .join("accessibility.json")
+    }
+
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|s| serde_json::from_str::<Self>(&s).ok())
+            .map(Self::normalized)
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| e.to_string())
+    }
+
+    /// Clamp the zoom into the supported range
+    pub fn normalized(mut self) -> Self {
+        self.zoom = if self.zoom.is_finite() {
+            self.zoom.clamp(MIN_ZOOM, MAX_ZOOM)
+        } else {
+            1.0
+        };
+        self
+    }
+}
+
+/// An open window as presented to assistive technology
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessibleWindow {
+    pub id: String,
+    pub window_type: WindowType,
+    /// Title to announce, numbered when several windows share a title
+    pub accessible_title: String,
+    /// ARIA role of the window's root element
+    pub role: String,
+    pub description: String,
+    /// 1-based place in the focus cycle
+    pub position: usize,
+    /// Number of windows in the focus cycle
+    pub total: usize,
+    pub is_focused: bool,
+}
+
+/// Direction to move keyboard focus between windows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FocusDirection {
+    Next,
+    Previous,
+}
+
+/// Describe open windows in focus cycle order: the main window first, then
+/// the others in the order they were opened
+pub fn describe_windows(windows: &[WindowState]) -> Vec<AccessibleWindow> {
+    let mut open: Vec<&WindowState> = windows.iter().filter(|w| w.is_open).collect();
+    open.sort_by(|a, b| {
+        (a.window_type != WindowType::Main, a.created_at, &a.id).cmp(&(
+            b.window_type != WindowType::Main,
+            b.created_at,
+            &b.id,
+        ))
+    });
+
+    let mut title_counts: HashMap<&str, usize> = HashMap::new();
+    for window in &open {
+        *title_counts.entry(window.title.as_str()).or_default() += 1;
+    }
+
+    let total = open.len();
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    open.iter()
+        .enumerate()
+        .map(|(i, window)| {
+            let title = window.title.as_str();
+            let accessible_title = if title_counts[title] > 1 {
+                let n = seen.entry(title).or_default();
+                *n += 1;
+                format!("{} {}", title, n)
+            } else {
+                title.to_string()
+            };
+
+            AccessibleWindow {
+                id: window.id.clone(),
+                window_type: window.window_type,
+                accessible_title,
+                role: window.window_type.accessible_role().to_string(),
+                description: window.window_type.accessible_description().to_string(),
+                position: i + 1,
+                total,
+                is_focused: window.is_focused,
+            }
+        })
+        .collect()
+}
+
+/// The window that should take focus when moving in `direction` from
+/// `current`, wrapping around at either end
+pub fn cycle_target<'a>(
+    windows: &'a [AccessibleWindow],
+    current: Option<&str>,
+    direction: FocusDirection,
+) -> Option<&'a AccessibleWindow> {
+    if windows.is_empty() {
+        return None;
+    }
+    let len = windows.len();
+    let index = match current.and_then(|id| windows.iter().position(|w| w.id == id)) {
+        Some(i) => match direction {
+            FocusDirection::Next => (i + 1) % len,
+            FocusDirection::Previous => (i + len - 1) % len,
+        },
+        None => match direction {
+            FocusDirection::Next => 0,
+            FocusDirection::Previous => len - 1,
+        },
+    };
+    windows.get(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(id: &str, window_type: WindowType, title: &str, created_at: u64) -> WindowState {
+        WindowState {
+            id: id.to_string(),
+            window_type,
+            title: title.to_string(),
+            is_open: true,
+            is_focused: false,
+            position: None,
+            size: None,
+            data: None,
+            created_at,
+        }
+    }
+
+    #[test]
+    fn test_describe_orders_and_numbers_windows() {
+        let windows = vec![
+            window("term_2", WindowType::Terminal, "Terminal", 30),
+            window("edit_1", WindowType::Editor, "Code Editor", 20),
+            window("term_1", WindowType::Terminal, "Terminal", 10),
+            window("main", WindowType::Main, "Citrate", 40),
+        ];
+
+        let described = describe_windows(&windows);
+        let titles: Vec<_> = described
+            .iter()
+            .map(|w| w.accessible_title.as_str())
+            .collect();
+        assert_eq!(
+            titles,
+            ["Citrate", "Terminal 1", "Code Editor", "Terminal 2"]
+        );
+        assert_eq!(described[2].position, 3);
+        assert!(described.iter().all(|w| w.total == 4));
+        assert_eq!(described[0].role, "application");
+    }
+
+    #[test]
+    fn test_cycle_target_wraps() {
+        let windows = describe_windows(&[
+            window("main", WindowType::Main, "Citrate", 0),
+            window("term_1", WindowType::Terminal, "Terminal", 1),
+            window("edit_1", WindowType::Editor, "Code Editor", 2),
+        ]);
+
+        let next =
+            |current, direction| cycle_target(&windows, current, direction).map(|w| w.id.as_str());
+        assert_eq!(next(Some("main"), FocusDirection::Next), Some("term_1"));
+        assert_eq!(next(Some("edit_1"), FocusDirection::Next), Some("main"));
+        assert_eq!(next(Some("main"), FocusDirection::Previous), Some("edit_1"));
+        assert_eq!(next(None, FocusDirection::Next), Some("main"));
+        assert_eq!(cycle_target(&[], Some("main"), FocusDirection::Next), None);
+    }
+
+    #[test]
+    fn test_preferences_clamp_zoom() {
+        let prefs = AccessibilityPreferences {
+            zoom: 10.0,
+            ..Default::default()
+        };
+        assert_eq!(prefs.normalized().zoom, MAX_ZOOM);
+
+        let prefs = AccessibilityPreferences {
+            zoom: f64::NAN,
+            ..Default::default()
+        };
+        assert_eq!(prefs.normalized().zoom, 1.0);
+
+        // Missing fields fall back to defaults
+        let prefs: AccessibilityPreferences =
+            serde_json::from_str(r#"{"high_contrast": true}"#).unwrap();
+        assert!(prefs.high_contrast);
+        assert_eq!(prefs.zoom, 1.0);
+    }
+}
@@ -2,7 +2,11 @@
 //!
 //! Centralized management of all application windows.
 
-use super::{WindowEvent, WindowState, WindowType};
+use super::accessibility::{cycle_target, describe_windows};
+use super::{
+    AccessibilityPreferences, AccessibleWindow, FocusDirection, WindowEvent, WindowState,
+    WindowType,
+};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::{AppHandle, Manager};
@@ -16,6 +20,8 @@ pub struct WindowManager {
     windows: Arc<RwLock<HashMap<String, WindowState>>>,
     /// App handle for window operations
     app_handle: Option<AppHandle>,
+    /// Display preferences applied to every window
+    preferences: Arc<RwLock<AccessibilityPreferences>>,
 }
 
 impl WindowManager {
@@ -24,6 +30,7 @@ impl WindowManager {
         Self {
             windows: Arc::new(RwLock::new(HashMap::new())),
             app_handle: None,
+            preferences: Arc::new(RwLock::new(AccessibilityPreferences::load())),
         }
     }
 
@@ -37,6 +44,35 @@ impl WindowManager {
         self.app_handle.as_ref()
     }
 
+    /// Register the main window, which is created from the app config
+    /// rather than through [`Self::create_window`]
+    pub async fn register_main_window(&self) {
+        let Some(window) = self
+            .app_handle
+            .as_ref()
+            .and_then(|app| app.get_webview_window("main"))
+        else {
+            return;
+        };
+        let zoom = self.preferences.read().await.zoom;
+        if zoom != 1.0 {
+            let _ = window.set_zoom(zoom);
+        }
+
+        self.register_window(WindowState {
+            id: "main".to_string(),
+            window_type: WindowType::Main,
+            title: WindowType::Main.default_title().to_string(),
+            is_open: true,
+            is_focused: window.is_focused().unwrap_or(true),
+            position: None,
+            size: None,
+            data: None,
+            created_at: 0,
+        })
+        .await;
+    }
+
     /// Register a window
     pub async fn register_window(&self, state: WindowState) {
         let mut windows = self.windows.write().await;
@@ -100,6 +136,65 @@ impl WindowManager {
         }
     }
 
+    /// Open windows in focus cycle order, described for screen readers
+    pub async fn accessible_windows(&self) -> Vec<AccessibleWindow> {
+        let windows = self.get_all_windows().await;
+        describe_windows(&windows)
+    }
+
+    /// Screen-reader description of one open window
+    pub async fn accessible_window(&self, window_id: &str) -> Option<AccessibleWindow> {
+        self.accessible_windows()
+            .await
+            .into_iter()
+            .find(|w| w.id == window_id)
+    }
+
+    /// Move focus to the next or previous open window, wrapping around.
+    /// Returns the newly focused window, or `None` if no window is open.
+    pub async fn cycle_focus(
+        &self,
+        direction: FocusDirection,
+    ) -> Result<Option<AccessibleWindow>, String> {
+        let windows = self.accessible_windows().await;
+        let current = windows.iter().find(|w| w.is_focused).map(|w| w.id.as_str());
+        let Some(target) = cycle_target(&windows, current, direction) else {
+            return Ok(None);
+        };
+
+        self.focus_window(&target.id).await?;
+        Ok(self.accessible_window(&target.id).await)
+    }
+
+    /// Current display preferences
+    pub async fn preferences(&self) -> AccessibilityPreferences {
+        *self.preferences.read().await
+    }
+
+    /// Persist display preferences, apply the zoom to every open window and
+    /// notify all windows so they can switch styles
+    pub async fn set_preferences(
+        &self,
+        preferences: AccessibilityPreferences,
+    ) -> Result<AccessibilityPreferences, String> {
+        let preferences = preferences.normalized();
+        preferences.save()?;
+        *self.preferences.write().await = preferences;
+
+        if let Some(app) = &self.app_handle {
+            for window_id in app.webview_windows().keys() {
+                super::set_window_zoom(app, window_id, preferences.zoom)?;
+            }
+            super::broadcast_to_all(
+                app,
+                "accessibility-changed",
+                serde_json::to_value(preferences).unwrap(),
+            )?;
+        }
+
+        Ok(preferences)
+    }
+
     /// Check if any window of type is open
     pub async fn has_window_type(&self, window_type: WindowType) -> bool {
         let windows = self.windows.read().await;
@@ -133,6 +228,10 @@ impl WindowManager {
 
         // Create the actual window
         super::create_window(app, window_id, window_type, title, width, height, x, y)?;
+        let zoom = self.preferences.read().await.zoom;
+        if zoom != 1.0 {
+            super::set_window_zoom(app, window_id, zoom)?;
+        }
 
         // Create state
         let state = WindowState {
@@ -150,11 +249,13 @@ impl WindowManager {
                 .as_millis() as u64,
         };
 
-        // Register
+        // Register; the new window takes focus
         self.register_window(state.clone()).await;
+        self.set_focus(window_id).await;
 
         // Emit event
-        let event = WindowEvent::new("window:opened", window_id);
+        let event = WindowEvent::new("window:opened", window_id)
+            .with_accessible(self.accessible_window(window_id).await);
         super::broadcast_to_all(app, "window-opened", serde_json::to_value(&event).unwrap())?;
 
         Ok(state)
@@ -194,7 +295,8 @@ impl WindowManager {
         self.set_focus(window_id).await;
 
         // Emit event
-        let event = WindowEvent::new("window:focused", window_id);
+        let event = WindowEvent::new("window:focused", window_id)
+            .with_accessible(self.accessible_window(window_id).await);
         super::broadcast_to_all(app, "window-focused", serde_json::to_value(&event).unwrap())?;
 
         Ok(())
@@ -5,8 +5,10 @@
 //! This module provides multi-window management for the Citrate GUI,
 //! enabling terminal, preview, and editor windows.
 
+pub mod accessibility;
 pub mod manager;
 
+pub use accessibility::{AccessibilityPreferences, AccessibleWindow, FocusDirection};
 pub use manager::WindowManager;
 
 use serde::{Deserialize, Serialize};
@@ -55,6 +57,26 @@ impl WindowType {
             WindowType::Editor => "Code Editor",
         }
     }
+
+    /// ARIA role of the window's root element
+    pub fn accessible_role(&self) -> &'static str {
+        match self {
+            WindowType::Main => "application",
+            WindowType::Terminal => "log",
+            WindowType::Preview => "document",
+            WindowType::Editor => "region",
+        }
+    }
+
+    /// What the window is, for screen readers
+    pub fn accessible_description(&self) -> &'static str {
+        match self {
+            WindowType::Main => "Main Citrate window",
+            WindowType::Terminal => "Interactive terminal session",
+            WindowType::Preview => "Preview of the app being built",
+            WindowType::Editor => "Source code editor",
+        }
+    }
 }
 
 impl std::str::FromStr for WindowType {
@@ -92,6 +114,9 @@ pub struct WindowEvent {
     pub window_id: String,
     pub timestamp: u64,
     pub data: Option<serde_json::Value>,
+    /// How to announce the window, when it is still open
+    #[serde(default)]
+    pub accessible: Option<AccessibleWindow>,
 }
 
 impl WindowEvent {
@@ -104,6 +129,7 @@ impl WindowEvent {
                 .unwrap()
                 .as_millis() as u64,
             data: None,
+            accessible: None,
         }
     }
 
@@ -111,6 +137,11 @@ impl WindowEvent {
         self.data = Some(data);
         self
     }
+
+    pub fn with_accessible(mut self, accessible: Option<AccessibleWindow>) -> Self {
+        self.accessible = accessible;
+        self
+    }
 }
 
 /// Create a new window
@@ -178,6 +209,14 @@ pub fn focus_window(app: &AppHandle, window_id: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Set the zoom factor of a window's webview
+pub fn set_window_zoom(app: &AppHandle, window_id: &str, zoom: f64) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(window_id) {
+        window.set_zoom(zoom).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
 /// Send a message to a window
 pub fn send_to_window(
     app: &AppHandle,
@@ -20,6 +20,8 @@ import { ThemeProvider } from './contexts/ThemeContext';
 import { ErrorProvider, useError } from './contexts/ErrorContext';
 import { ErrorNotification } from './components/common/ErrorNotification';
 import { DevModeIndicator } from './components/common/DevModeIndicator';
+import { UpgradeBanner } from './components/common/UpgradeBanner';
+import { UpdatePrompt } from './components/common/UpdatePrompt';
 import { useKeyboardShortcuts, KeyboardShortcut } from './hooks/useKeyboardShortcuts';
 import KeyboardShortcutsHelp from './components/KeyboardShortcutsHelp';
 
@@ -274,6 +276,12 @@ function AppInner() {
 
       {/* Dev Mode Indicator (only shown in development builds) */}
       <DevModeIndicator position="bottom-left" />
+
+      {/* Shown when most peers run a newer client */}
+      <UpgradeBanner />
+
+      {/* Download progress and restart prompt for signed app updates */}
+      <UpdatePrompt />
     </div>
     </>
   );
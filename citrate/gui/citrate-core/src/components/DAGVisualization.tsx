@@ -1,6 +1,7 @@
 import React, { useState, useEffect, useCallback, useRef, useMemo } from 'react';
 import { dagService } from '../services/tauri';
-import { DAGData, DAGNode } from '../types';
+import { DAGData, DAGNode, DAGStatsBucket } from '../types';
+import { formatUnits, SALT_DECIMALS } from '../utils/units';
 import ForceGraph2D, { ForceGraphMethods } from 'react-force-graph-2d';
 import {
   Network,
@@ -37,6 +38,7 @@ interface GraphLink {
 
 export const DAGVisualization: React.FC = () => {
   const [dagData, setDagData] = useState<DAGData | null>(null);
+  const [healthSeries, setHealthSeries] = useState<DAGStatsBucket[]>([]);
   const [selectedNode, setSelectedNode] = useState<DAGNode | null>(null);
   const [blockDetails, setBlockDetails] = useState<any | null>(null);
   const [showTxs, setShowTxs] = useState(false);
@@ -48,6 +50,26 @@ export const DAGVisualization: React.FC = () => {
   const containerRef = useRef<HTMLDivElement>(null);
   const [dimensions, setDimensions] = useState({ width: 800, height: 600 });
 
+  // Summarize the last five one-minute buckets of the health series
+  const recentHealth = useMemo(() => {
+    const recent = healthSeries.slice(-5).filter(b => b.blocks > 0);
+    if (!recent.length) return null;
+    const blocks = recent.reduce((n, b) => n + b.blocks, 0);
+    const span = recent.length * 60;
+    const weighted = (f: (b: DAGStatsBucket) => number) =>
+      recent.reduce((sum, b) => sum + f(b) * b.blocks, 0) / blocks;
+    const blue = recent.reduce((n, b) => n + b.blueMergeParents, 0);
+    const red = recent.reduce((n, b) => n + b.redMergeParents, 0);
+    return {
+      blocksPerSec: blocks / span,
+      avgTips: weighted(b => b.avgTips),
+      avgMergeParents: weighted(b => b.avgMergeParents),
+      avgAnticoneSize: weighted(b => b.avgAnticoneSize),
+      maxAnticoneSize: Math.max(...recent.map(b => b.maxAnticoneSize)),
+      blueRatio: blue + red > 0 ? blue / (blue + red) : 1,
+    };
+  }, [healthSeries]);
+
   // Convert DAG data to force-graph format
   const graphData = useMemo(() => {
     if (!dagData || !dagData.nodes.length) {
@@ -183,6 +205,7 @@ export const DAGVisualization: React.FC = () => {
         // Try to get DAG data from embedded node
         const data = await dagService.getData(100);
         setDagData(data);
+        dagService.getStatsSeries().then(setHealthSeries).catch(() => setHealthSeries([]));
 
         // If a focus hash is set, try to focus it
         try {
@@ -345,6 +368,31 @@ export const DAGVisualization: React.FC = () => {
         </div>
       )}
 
+      {recentHealth && (
+        <div className="dag-stats">
+          <div className="stat">
+            <Activity size={16} />
+            <span>Blocks/s (5m): {recentHealth.blocksPerSec.toFixed(2)}</span>
+          </div>
+          <div className="stat">
+            <GitBranch size={16} />
+            <span>Avg Tips: {recentHealth.avgTips.toFixed(2)}</span>
+          </div>
+          <div className="stat">
+            <Share2 size={16} />
+            <span>Avg Merge Parents: {recentHealth.avgMergeParents.toFixed(2)}</span>
+          </div>
+          <div className="stat">
+            <Layers size={16} />
+            <span>Anticone: {recentHealth.avgAnticoneSize.toFixed(2)} avg / {recentHealth.maxAnticoneSize} max</span>
+          </div>
+          <div className="stat blue">
+            <Network size={16} />
+            <span>Blue Ratio: {(recentHealth.blueRatio * 100).toFixed(1)}%</span>
+          </div>
+        </div>
+      )}
+
       <div className="dag-container" ref={containerRef}>
         {error && (
           <div className="error-message">
@@ -509,7 +557,7 @@ export const DAGVisualization: React.FC = () => {
                     <div className="tx-row"><span className="tx-label">Hash:</span><span className="tx-mono">{tx.hash.slice(0, 16)}…</span></div>
                     <div className="tx-row"><span className="tx-label">From:</span><span className="tx-mono">{tx.from_addr || tx.fromAddr || tx.from?.slice(0,16) + '…'}</span></div>
                     <div className="tx-row"><span className="tx-label">To:</span><span className="tx-mono">{tx.to_addr || tx.toAddr || (tx.to ? tx.to.slice(0,16) + '…' : '—')}</span></div>
-                    <div className="tx-row"><span className="tx-label">Value:</span><span>{(Number(tx.value)/1e18).toFixed(4)} SALT</span></div>
+                    <div className="tx-row"><span className="tx-label">Value:</span><span>{formatUnits(BigInt(tx.value ?? 0), SALT_DECIMALS, 4)} SALT</span></div>
                   </div>
                 ))}
               </div>
@@ -41,8 +41,9 @@ export const Dashboard: React.FC = () => {
       });
     });
 
-    // Fallback polling in case events are missed
-    const poll = setInterval(fetchStatus, 1000);
+    // Fallback polling in case events are missed; status changes arrive
+    // through state-delta events
+    const poll = setInterval(fetchStatus, 10000);
 
     // Cleanup
     return () => {
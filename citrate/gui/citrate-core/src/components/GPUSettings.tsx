@@ -1,5 +1,6 @@
 import { useState, useEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 
 // ============================================================================
 // Types
@@ -51,14 +52,35 @@ interface ProviderStatus {
   active_jobs: string[];
 }
 
+type JobPriorityClass = 'Background' | 'Normal' | 'High';
+
 interface ComputeJobStatus {
   Queued?: null;
+  Preempted?: { preempted_at: number; preempted_by: string };
   Running?: { started_at: number; progress: number };
   Completed?: { started_at: number; completed_at: number; result_hash: string };
   Failed?: { error: string; failed_at: number };
   Cancelled?: null;
 }
 
+interface ResultAttestation {
+  job_id: string;
+  job_hash: string;
+  output_hash: string;
+  execution_time: number;
+  device_fingerprint: string;
+  provider: string;
+  amount: string;
+  signature: string;
+}
+
+interface JobSettlement {
+  attestation: ResultAttestation;
+  tx_hash: string | null;
+  submitted_at: number | null;
+  error: string | null;
+}
+
 interface ComputeJob {
   id: string;
   job_type: ComputeJobType;
@@ -71,6 +93,9 @@ interface ComputeJob {
   memory_required: number;
   estimated_time: number;
   priority: number;
+  priority_class: JobPriorityClass;
+  device_id: string | null;
+  settlement: JobSettlement | null;
 }
 
 // ============================================================================
@@ -95,6 +120,7 @@ function formatDuration(seconds: number): string {
 
 function getJobStatusLabel(status: ComputeJobStatus): string {
   if ('Queued' in status) return 'Queued';
+  if ('Preempted' in status) return 'Preempted';
   if ('Running' in status) return `Running (${Math.round((status.Running?.progress || 0) * 100)}%)`;
   if ('Completed' in status) return 'Completed';
   if ('Failed' in status) return 'Failed';
@@ -104,6 +130,7 @@ function getJobStatusLabel(status: ComputeJobStatus): string {
 
 function getJobStatusColor(status: ComputeJobStatus): string {
   if ('Queued' in status) return 'text-yellow-400';
+  if ('Preempted' in status) return 'text-orange-400';
   if ('Running' in status) return 'text-blue-400';
   if ('Completed' in status) return 'text-green-400';
   if ('Failed' in status) return 'text-red-400';
@@ -166,7 +193,14 @@ export function GPUSettings() {
     fetchData();
     // Refresh every 5 seconds
     const interval = setInterval(fetchData, 5000);
-    return () => clearInterval(interval);
+    // Refresh immediately when the scheduler starts, preempts or finishes a job
+    const unlisten = listen('gpu-job-state-changed', () => {
+      fetchData();
+    });
+    return () => {
+      clearInterval(interval);
+      unlisten.then((fn) => fn());
+    };
   }, [fetchData]);
 
   // Refresh devices
@@ -529,6 +563,22 @@ export function GPUSettings() {
                         <p>{job.max_payment} tokens</p>
                       </div>
                     </div>
+                    {job.settlement && (
+                      <p className="mt-3 text-sm">
+                        {job.settlement.tx_hash ? (
+                          <span className="text-green-400">
+                            Settled in{' '}
+                            <span className="font-mono">
+                              {job.settlement.tx_hash.substring(0, 10)}...
+                            </span>
+                          </span>
+                        ) : (
+                          <span className="text-red-400">
+                            Settlement failed: {job.settlement.error}
+                          </span>
+                        )}
+                      </p>
+                    )}
                     {('Running' in job.status || 'Queued' in job.status) && (
                       <div className="mt-3 flex justify-end">
                         <button
@@ -42,6 +42,38 @@ interface IpfsConfig {
   external_gateways: string[];
   enable_pubsub: boolean;
   bootstrap_peers: string[];
+  quotas: PinQuotas;
+}
+
+type ContentCategory = 'models' | 'datasets' | 'artifacts' | 'other';
+
+interface PinQuotas {
+  models: number | null;
+  datasets: number | null;
+  artifacts: number | null;
+}
+
+interface CategoryUsage {
+  category: ContentCategory;
+  pins: number;
+  bytes: number;
+  protected_bytes: number;
+  quota: number | null;
+}
+
+interface StorageUsage {
+  categories: CategoryUsage[];
+  tracked_bytes: number;
+  repo_size: number | null;
+  storage_max: number | null;
+}
+
+interface GcReport {
+  unpinned: string[];
+  unpinned_bytes: number;
+  stale_records: number;
+  repo_size_before: number | null;
+  repo_size_after: number | null;
 }
 
 interface IpfsAddResult {
@@ -75,6 +107,9 @@ export const IPFS: React.FC = () => {
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [activeTab, setActiveTab] = useState<'files' | 'pins' | 'peers'>('files');
+  const [usage, setUsage] = useState<StorageUsage | null>(null);
+  const [gcRunning, setGcRunning] = useState(false);
+  const [gcResult, setGcResult] = useState<string | null>(null);
 
   // Load IPFS status
   const loadStatus = useCallback(async () => {
@@ -139,6 +174,36 @@ export const IPFS: React.FC = () => {
     }
   }, []);
 
+  // Load disk usage per content category
+  const loadUsage = useCallback(async () => {
+    try {
+      setUsage(await invoke<StorageUsage>('ipfs_get_usage'));
+    } catch (err: any) {
+      console.error('Failed to load IPFS usage:', err);
+    }
+  }, []);
+
+  // Unpin over-quota content and reclaim disk space
+  const runGc = async () => {
+    setGcRunning(true);
+    setGcResult(null);
+    try {
+      const report = await invoke<GcReport>('ipfs_gc_run');
+      const freed =
+        report.repo_size_before !== null && report.repo_size_after !== null
+          ? Math.max(report.repo_size_before - report.repo_size_after, 0)
+          : 0;
+      setGcResult(
+        `Unpinned ${report.unpinned.length} item(s), freed ${formatSize(freed)}`
+      );
+      await Promise.all([loadUsage(), loadPins(), loadStatus()]);
+    } catch (err: any) {
+      setError(`Garbage collection failed: ${err}`);
+    } finally {
+      setGcRunning(false);
+    }
+  };
+
   // Start IPFS daemon
   const startDaemon = async () => {
     setLoading(true);
@@ -225,6 +290,7 @@ export const IPFS: React.FC = () => {
     try {
       await invoke('ipfs_pin', { cid });
       await loadPins();
+      await loadUsage();
 
       // Update file status
       setFiles(prev =>
@@ -240,6 +306,7 @@ export const IPFS: React.FC = () => {
     try {
       await invoke('ipfs_unpin', { cid });
       await loadPins();
+      await loadUsage();
 
       // Update file status
       setFiles(prev =>
@@ -283,8 +350,9 @@ export const IPFS: React.FC = () => {
     if (status?.running) {
       loadPins();
       loadPeers();
+      loadUsage();
     }
-  }, [status?.running, loadPins, loadPeers]);
+  }, [status?.running, loadPins, loadPeers, loadUsage]);
 
   // Periodic status updates
   useEffect(() => {
@@ -418,11 +486,36 @@ export const IPFS: React.FC = () => {
               <div
                 className="usage-fill"
                 style={{
-                  width: `${Math.min((status.repo_size / (10 * 1024 * 1024 * 1024)) * 100, 100)}%`,
+                  width: `${Math.min((status.repo_size / (usage?.storage_max || 10 * 1024 * 1024 * 1024)) * 100, 100)}%`,
                 }}
               />
             </div>
-            <span className="usage-text">{formatSize(status.repo_size)} used</span>
+            <span className="usage-text">
+              {formatSize(status.repo_size)} used
+              {usage?.storage_max ? ` of ${formatSize(usage.storage_max)}` : ''}
+            </span>
+            <button className="btn btn-secondary" onClick={runGc} disabled={gcRunning}>
+              <RefreshCw size={14} className={gcRunning ? 'spin' : ''} />
+              Run GC
+            </button>
+          </div>
+        )}
+
+        {status?.running && usage && (
+          <div className="category-usage">
+            {usage.categories
+              .filter(c => c.pins > 0 || c.quota !== null)
+              .map(c => (
+                <div key={c.category} className="category-row">
+                  <span className="category-name">{c.category}</span>
+                  <span>
+                    {c.pins} pin{c.pins === 1 ? '' : 's'} · {formatSize(c.bytes)}
+                    {c.quota !== null && ` / ${formatSize(c.quota)}`}
+                    {c.protected_bytes > 0 && ` (${formatSize(c.protected_bytes)} protected)`}
+                  </span>
+                </div>
+              ))}
+            {gcResult && <div className="gc-result">{gcResult}</div>}
           </div>
         )}
       </div>
@@ -850,6 +943,29 @@ export const IPFS: React.FC = () => {
           white-space: nowrap;
         }
 
+        .category-usage {
+          margin-top: 0.75rem;
+          display: flex;
+          flex-direction: column;
+          gap: 0.25rem;
+          font-size: 0.8125rem;
+          color: #6b7280;
+        }
+
+        .category-row {
+          display: flex;
+          justify-content: space-between;
+        }
+
+        .category-name {
+          text-transform: capitalize;
+          color: #374151;
+        }
+
+        .gc-result {
+          color: #059669;
+        }
+
         .node-setup {
           background: white;
           border-radius: 1rem;
@@ -371,13 +371,17 @@ export const OnboardingModal: React.FC<OnboardingModalProps> = ({ onComplete })
 
     const setupListener = async () => {
       unlisten = await listen<{
-        status: string;
-        progress: number;
-        message: string;
-        downloaded_mb?: number;
-        total_size_mb?: number;
-      }>('model-download-progress', (event) => {
-        const { status, progress, message } = event.payload;
+        downloads?: Record<string, {
+          status: string;
+          progress: number;
+          message: string;
+          downloaded_mb?: number;
+          total_size_mb?: number;
+        }>;
+      }>('state-delta', (event) => {
+        const download = event.payload.downloads?.['enhanced-model'];
+        if (!download) return;
+        const { status, progress, message } = download;
         setDownloadProgress(progress);
         setDownloadMessage(message);
 
@@ -0,0 +1,253 @@
+/**
+ * ScheduledPayments Component
+ *
+ * Schedules recurring transfers from a wallet account and lists them with
+ * pause/resume and their run history. Payments that came due while the
+ * wallet was locked wait here until approved with the password.
+ */
+
+import React, { useState, useEffect, useCallback } from 'react';
+import { walletService, PaymentRun, ScheduledPayment } from '../services/tauri';
+import { Account } from '../types';
+import { formatUnits, parseUnits, SALT_DECIMALS } from '../utils/units';
+
+interface ScheduledPaymentsPanelProps {
+  accounts: Account[];
+}
+
+const INTERVALS: { label: string; secs: number }[] = [
+  { label: 'Hourly', secs: 3600 },
+  { label: 'Daily', secs: 86400 },
+  { label: 'Weekly', secs: 7 * 86400 },
+  { label: 'Every 30 days', secs: 30 * 86400 },
+];
+
+const describeInterval = (secs: number): string =>
+  INTERVALS.find(i => i.secs === secs)?.label ?? `Every ${secs}s`;
+
+const formatTime = (secs: number): string => new Date(secs * 1000).toLocaleString();
+
+export const ScheduledPaymentsPanel: React.FC<ScheduledPaymentsPanelProps> = ({ accounts }) => {
+  const [payments, setPayments] = useState<ScheduledPayment[]>([]);
+  const [history, setHistory] = useState<PaymentRun[]>([]);
+  const [from, setFrom] = useState('');
+  const [to, setTo] = useState('');
+  const [amount, setAmount] = useState('');
+  const [label, setLabel] = useState('');
+  const [intervalSecs, setIntervalSecs] = useState(INTERVALS[2].secs);
+  const [endDate, setEndDate] = useState('');
+  const [passwords, setPasswords] = useState<Record<string, string>>({});
+  const [error, setError] = useState('');
+  const [busy, setBusy] = useState(false);
+
+  const refresh = useCallback(async () => {
+    try {
+      const [list, runs] = await Promise.all([
+        walletService.listScheduledPayments(),
+        walletService.getScheduledPaymentHistory(undefined, 20),
+      ]);
+      setPayments(list);
+      setHistory(runs);
+    } catch (err) {
+      console.error('Failed to load scheduled payments:', err);
+    }
+  }, []);
+
+  useEffect(() => {
+    refresh();
+    const unlisten = walletService.onScheduledPayment(() => refresh());
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [refresh]);
+
+  useEffect(() => {
+    if (!from && accounts.length > 0) {
+      setFrom(accounts[0].address);
+    }
+  }, [accounts, from]);
+
+  const run = async (action: () => Promise<unknown>) => {
+    setBusy(true);
+    setError('');
+    try {
+      await action();
+      await refresh();
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const schedule = () => run(async () => {
+    const endAt = endDate ? Math.floor(new Date(`${endDate}T23:59:59`).getTime() / 1000) : null;
+    await walletService.createScheduledPayment({
+      label: label.trim() || null,
+      from,
+      to: to.trim(),
+      amount: parseUnits(amount.trim(), SALT_DECIMALS).toString(),
+      intervalSecs,
+      endAt,
+    });
+    setTo('');
+    setAmount('');
+    setLabel('');
+    setEndDate('');
+  });
+
+  const approve = (id: string) => run(async () => {
+    const outcome = await walletService.approveScheduledPayment(id, passwords[id] ?? '');
+    setPasswords(prev => ({ ...prev, [id]: '' }));
+    if (outcome.error) {
+      throw new Error(outcome.error);
+    }
+  });
+
+  return (
+    <div className="activity" style={{ marginTop: '2rem' }}>
+      <h3>Scheduled Payments</h3>
+
+      <div className="form-group" style={{ display: 'flex', gap: '0.5rem', flexWrap: 'wrap' }}>
+        <select value={from} onChange={e => setFrom(e.target.value)}>
+          {accounts.map(account => (
+            <option key={account.address} value={account.address}>
+              {account.label} ({account.address.slice(0, 10)}…)
+            </option>
+          ))}
+        </select>
+        <input
+          type="text"
+          placeholder="Recipient address, contact or .ctr name"
+          value={to}
+          onChange={e => setTo(e.target.value)}
+        />
+        <input
+          type="text"
+          placeholder="Amount (SALT)"
+          value={amount}
+          onChange={e => setAmount(e.target.value)}
+        />
+        <select value={intervalSecs} onChange={e => setIntervalSecs(Number(e.target.value))}>
+          {INTERVALS.map(i => (
+            <option key={i.secs} value={i.secs}>{i.label}</option>
+          ))}
+        </select>
+        <input
+          type="date"
+          title="Last payment date (optional)"
+          value={endDate}
+          onChange={e => setEndDate(e.target.value)}
+        />
+        <input
+          type="text"
+          placeholder="Label (optional)"
+          value={label}
+          onChange={e => setLabel(e.target.value)}
+        />
+        <button
+          className="btn btn-primary"
+          disabled={busy || !from || !to.trim() || !amount.trim()}
+          onClick={schedule}
+        >
+          Schedule
+        </button>
+      </div>
+      {error && <div className="error-text">{error}</div>}
+
+      <div className="activity-list">
+        {payments.length === 0 && <div className="muted">No scheduled payments</div>}
+        {payments.map(payment => (
+          <div
+            key={payment.id}
+            className={`tx ${payment.status === 'awaiting_approval' ? 'pending' : payment.status === 'completed' ? 'confirmed' : ''}`}
+          >
+            <div className="left">
+              <div className="hash">
+                {payment.label || 'Payment'}: {formatUnits(BigInt(payment.amount), SALT_DECIMALS)} SALT to{' '}
+                <span className="mono">{payment.to.slice(0, 10)}…</span>
+              </div>
+              <div className="meta">
+                <span className="badge">{payment.status.replace('_', ' ')}</span>
+                <span className="muted">
+                  {describeInterval(payment.intervalSecs)} • {payment.runs} sent
+                  {payment.status !== 'completed' && ` • next ${formatTime(payment.nextRun)}`}
+                  {payment.endAt !== null && ` • until ${formatTime(payment.endAt)}`}
+                </span>
+              </div>
+              {payment.status === 'awaiting_approval' && (
+                <input
+                  type="password"
+                  placeholder="Password to approve this payment"
+                  value={passwords[payment.id] ?? ''}
+                  onChange={e => setPasswords(prev => ({ ...prev, [payment.id]: e.target.value }))}
+                  style={{ marginTop: '0.5rem' }}
+                />
+              )}
+            </div>
+            <div className="right" style={{ display: 'flex', gap: '0.5rem', alignItems: 'center' }}>
+              {payment.status === 'awaiting_approval' && (
+                <button
+                  className="btn btn-primary btn-sm"
+                  disabled={busy || !passwords[payment.id]}
+                  onClick={() => approve(payment.id)}
+                >
+                  Approve
+                </button>
+              )}
+              {payment.status === 'paused' ? (
+                <button
+                  className="btn btn-secondary btn-sm"
+                  disabled={busy}
+                  onClick={() => run(() => walletService.resumeScheduledPayment(payment.id))}
+                >
+                  Resume
+                </button>
+              ) : payment.status !== 'completed' && (
+                <button
+                  className="btn btn-secondary btn-sm"
+                  disabled={busy}
+                  onClick={() => run(() => walletService.pauseScheduledPayment(payment.id))}
+                >
+                  Pause
+                </button>
+              )}
+              <button
+                className="btn btn-secondary btn-sm"
+                disabled={busy}
+                onClick={() => run(() => walletService.removeScheduledPayment(payment.id))}
+              >
+                Remove
+              </button>
+            </div>
+          </div>
+        ))}
+      </div>
+
+      {history.length > 0 && (
+        <>
+          <h4 style={{ marginTop: '1rem' }}>History</h4>
+          <div className="activity-list">
+            {history.map(entry => (
+              <div key={`${entry.paymentId}-${entry.executedAt}-${entry.outcome}`} className="tx">
+                <div className="left">
+                  <div className="hash">
+                    {entry.label || 'Payment'}: {formatUnits(BigInt(entry.amount), SALT_DECIMALS)} SALT
+                  </div>
+                  <div className="meta">
+                    <span className="badge">{entry.outcome.replace('_', ' ')}</span>
+                    <span className="muted">{formatTime(entry.executedAt)}</span>
+                    {entry.txHash && <span className="mono">0x{entry.txHash.slice(0, 12)}…</span>}
+                    {entry.error && <span className="error-text">{entry.error}</span>}
+                  </div>
+                </div>
+              </div>
+            ))}
+          </div>
+        </>
+      )}
+    </div>
+  );
+};
+
+export default ScheduledPaymentsPanel;
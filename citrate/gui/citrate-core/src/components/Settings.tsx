@@ -1,7 +1,7 @@
 import React, { useEffect, useMemo, useState, useCallback } from 'react';
-import { nodeService, walletService } from '../services/tauri';
+import { nodeService, walletService, stateService, localeService, LocaleCode, LocaleInfo } from '../services/tauri';
 import { invoke } from '@tauri-apps/api/core';
-import type { NodeConfig, NodeStatus, PeerInfoSummary } from '../types';
+import type { NodeConfig, NodeStatus, PeerInfoSummary, PeerReputation } from '../types';
 import { validateIPv4, validatePort, ValidationResult } from '../utils/validation';
 import { useTheme } from '../contexts/ThemeContext';
 import { Sun, Moon, Monitor, Bot, Shield, Lock } from 'lucide-react';
@@ -19,6 +19,7 @@ export const Settings: React.FC = () => {
   const [bootnodes, setBootnodes] = useState<string[]>([]);
   const [newBootnode, setNewBootnode] = useState('');
   const [peers, setPeers] = useState<PeerInfoSummary[]>([]);
+  const [reputation, setReputation] = useState<PeerReputation[]>([]);
   const [connectEntry, setConnectEntry] = useState('');
   const [netLoading, setNetLoading] = useState(false);
   const [importHost, setImportHost] = useState('127.0.0.1');
@@ -31,6 +32,8 @@ export const Settings: React.FC = () => {
   // Session management state
   const [activeSessions, setActiveSessions] = useState<number>(0);
   const [lockingAll, setLockingAll] = useState(false);
+  const [performanceMode, setPerformanceMode] = useState(false);
+  const [localeInfo, setLocaleInfo] = useState<LocaleInfo | null>(null);
 
   // Load active session count
   const loadSessionCount = useCallback(async () => {
@@ -47,6 +50,35 @@ export const Settings: React.FC = () => {
     }
   }, []);
 
+  useEffect(() => {
+    stateService.getConfig()
+      .then(cfg => setPerformanceMode(cfg.performance_mode))
+      .catch(() => {});
+  }, []);
+
+  useEffect(() => {
+    localeService.get().then(setLocaleInfo).catch(() => {});
+  }, []);
+
+  // Language of messages and errors returned by the backend
+  const handleLocaleChange = async (locale: LocaleCode) => {
+    try {
+      setLocaleInfo(await localeService.set(locale));
+    } catch (e: any) {
+      setError(e?.message || String(e));
+    }
+  };
+
+  // Performance mode: fewer GUI updates to save CPU and battery
+  const handleTogglePerformanceMode = async (enabled: boolean) => {
+    try {
+      await stateService.setPerformanceMode(enabled);
+      setPerformanceMode(enabled);
+    } catch (e: any) {
+      setError(e?.message || String(e));
+    }
+  };
+
   // Lock all wallets handler
   const handleLockAllWallets = async () => {
     setLockingAll(true);
@@ -65,11 +97,12 @@ export const Settings: React.FC = () => {
     const load = async () => {
       try {
         setLoading(true);
-        const [cfg, stat, bn, ps] = await Promise.all([
+        const [cfg, stat, bn, ps, rep] = await Promise.all([
           nodeService.getConfig().catch(() => null),
           nodeService.getStatus().catch(() => null),
           nodeService.getBootnodes().catch(() => []),
           nodeService.getPeers().catch(() => []),
+          nodeService.getPeerReputation().catch(() => []),
         ]);
         if (cfg) {
           // Ensure consensus field exists with defaults
@@ -104,6 +137,7 @@ export const Settings: React.FC = () => {
         if (stat) setStatus(stat);
         setBootnodes(bn || []);
         setPeers(ps || []);
+        setReputation(rep || []);
       } catch (e: any) {
         setError(e?.message || String(e));
       } finally {
@@ -154,6 +188,7 @@ export const Settings: React.FC = () => {
 
   const reloadPeers = async () => {
     try { setPeers(await nodeService.getPeers()); } catch {}
+    try { setReputation(await nodeService.getPeerReputation()); } catch {}
   };
   const reloadBootnodes = async () => {
     try { setBootnodes(await nodeService.getBootnodes()); } catch {}
@@ -504,6 +539,24 @@ export const Settings: React.FC = () => {
             </button>
           </div>
         </div>
+        <div className="form-grid">
+          <label>
+            <span>Performance Mode</span>
+            <input type="checkbox" checked={performanceMode}
+              onChange={e => handleTogglePerformanceMode(e.target.checked)} />
+          </label>
+          {localeInfo && (
+            <label>
+              <span>Language</span>
+              <select value={localeInfo.current}
+                onChange={e => handleLocaleChange(e.target.value as LocaleCode)}>
+                {localeInfo.available.map(option => (
+                  <option key={option.code} value={option.code}>{option.name}</option>
+                ))}
+              </select>
+            </label>
+          )}
+        </div>
       </div>
 
       {/* Session Security Section */}
@@ -807,12 +860,34 @@ export const Settings: React.FC = () => {
                     <span className="mono">{p.addr}</span>
                     <span className={`badge ${p.direction === 'inbound' ? 'badge-blue' : 'badge-purple'}`}>{p.direction}</span>
                     <span className="badge">{p.state}</span>
-                    <span className="muted">score {p.score} • last {p.lastSeenSecs}s</span>
+                    <span className="muted">{p.clientVersion ? `v${p.clientVersion} • ` : ''}score {p.score} • last {p.lastSeenSecs}s</span>
                     <button className="btn btn-secondary btn-sm" onClick={() => handleDisconnectPeer(p.id)} disabled={netLoading || !status?.running}>Disconnect</button>
                   </div>
                 ))}
               </div>
             </div>
+            {reputation.length > 0 && (
+              <div className="peers" style={{ marginTop: '1rem' }}>
+                <div className="peers-header">
+                  <span>Peer Reputation ({reputation.filter(r => r.banned).length} banned)</span>
+                </div>
+                <div className="peer-list">
+                  {reputation.map(r => (
+                    <div className="peer" key={r.ip}>
+                      <span className="mono">{r.ip}</span>
+                      {r.peerId && <span className="mono">{r.peerId}</span>}
+                      {r.banned && <span className="badge">banned</span>}
+                      {r.connected && <span className="badge badge-blue">connected</span>}
+                      <span className="muted">
+                        score {r.score.toFixed(1)}
+                        {r.banned && r.bannedUntil ? ` • until ${new Date(r.bannedUntil * 1000).toLocaleString()}` : ''}
+                        {r.banReason ? ` • ${r.banReason}` : r.events.length > 0 ? ` • ${r.events[r.events.length - 1].reason}` : ''}
+                      </span>
+                    </div>
+                  ))}
+                </div>
+              </div>
+            )}
           </>
         ) : (
           <div className="alert alert-warning">Networking disabled — enable to use peering.</div>
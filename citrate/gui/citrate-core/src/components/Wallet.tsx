@@ -1,7 +1,8 @@
 import React, { useState, useEffect } from 'react';
-import { walletService, nodeService } from '../services/tauri';
+import { walletService, nodeService, TxConfirmation } from '../services/tauri';
 import { Account, TxActivity } from '../types';
-import { validateAddress, validateAmount, validatePrivateKey, validateMnemonic } from '../utils/validation';
+import { validateAddress, validateAmount, validatePrivateKey, validateMnemonic, toChecksumAddress } from '../utils/validation';
+import { formatUnits, parseUnits, SALT_DECIMALS } from '../utils/units';
 import { useRecentAddresses } from '../contexts/AppContext';
 import {
   Wallet as WalletIcon,
@@ -15,6 +16,8 @@ import {
 } from 'lucide-react';
 import { SkeletonCard, SkeletonList } from './Skeleton';
 import { SessionStatus } from './SessionStatus';
+import { WalletConnectPanel } from './WalletConnect';
+import { ScheduledPaymentsPanel } from './ScheduledPayments';
 
 export const Wallet: React.FC = () => {
   const [accounts, setAccounts] = useState<Account[]>([]);
@@ -26,6 +29,8 @@ export const Wallet: React.FC = () => {
   const [exportAccount, setExportAccount] = useState<Account | null>(null);
   const [copied, setCopied] = useState<string | null>(null);
   const [activity, setActivity] = useState<TxActivity[]>([]);
+  // Latest confirmation depth per sent tx hash, from `tx-confirmed` events
+  const [confirmations, setConfirmations] = useState<Record<string, TxConfirmation>>({});
   const [expanded, setExpanded] = useState<Record<string, boolean>>({});
   const [initialLoading, setInitialLoading] = useState(true);
   const [activityLoading, setActivityLoading] = useState(true);
@@ -39,6 +44,7 @@ export const Wallet: React.FC = () => {
   // Tracked external addresses (persisted in backend)
   const [tracked, setTracked] = useState<string[]>([]);
   const [trackedInput, setTrackedInput] = useState('');
+  const [trackedError, setTrackedError] = useState('');
   const [trackedData, setTrackedData] = useState<Record<string, { balance: string; activity: TxActivity[] }>>({});
 
   // Load tracked addresses from backend on mount
@@ -54,6 +60,37 @@ export const Wallet: React.FC = () => {
     loadTrackedAddresses();
   }, []);
 
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    walletService
+      .onTxConfirmed((update) => {
+        setConfirmations(prev => ({ ...prev, [update.tx_hash.toLowerCase()]: update }));
+        loadActivity();
+      })
+      .then((fn: () => void) => {
+        unlisten = fn;
+      });
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+
+  // Refresh as soon as activity changes status, e.g. when a block is reorged out
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    walletService
+      .onActivityChanged(() => {
+        loadActivity();
+        refreshTracked();
+      })
+      .then((fn: () => void) => {
+        unlisten = fn;
+      });
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+
   useEffect(() => {
     loadAccounts();
     // Auto-refresh balances every ~2.5s
@@ -116,9 +153,16 @@ export const Wallet: React.FC = () => {
   };
 
   const addTracked = async () => {
-    const addr = trackedInput.trim();
-    if (!addr) return;
-    if (tracked.includes(addr)) return;
+    const input = trackedInput.trim();
+    if (!input) return;
+    const validation = validateAddress(input);
+    if (!validation.isValid) {
+      setTrackedError(validation.error || 'Invalid address');
+      return;
+    }
+    setTrackedError('');
+    const addr = toChecksumAddress(input);
+    if (tracked.some(a => a.toLowerCase() === addr.toLowerCase())) return;
     const list = [...tracked, addr];
     await persistTracked(list);
     setTrackedInput('');
@@ -191,9 +235,7 @@ export const Wallet: React.FC = () => {
   };
 
   const formatBalance = (balance: bigint) => {
-    // Convert from wei to ETH (assuming 18 decimals)
-    const eth = Number(balance) / 1e18;
-    return eth.toFixed(4);
+    return formatUnits(balance, SALT_DECIMALS, 4);
   };
 
   return (
@@ -366,12 +408,20 @@ export const Wallet: React.FC = () => {
               <>
                 {activity.length === 0 && <div className="muted">No activity yet</div>}
                 {activity.map(tx => (
-              <div key={tx.hash} className={`tx ${tx.status}`}>
+              <div key={tx.internal ? `${tx.hash}:${tx.from}:${tx.to}` : tx.hash} className={`tx ${tx.status}`}>
                 <div className="left">
                   <div className="hash mono">{tx.hash.slice(0, 10)}…</div>
                   <div className="meta">
-                    <span className={`badge ${tx.status === 'pending' ? 'badge-yellow' : 'badge-green'}`}>{tx.status}</span>
+                    <span className={`badge ${tx.status === 'pending' ? 'badge-yellow' : tx.status === 'confirmed' ? 'badge-green' : 'badge-red'}`}>{tx.status}</span>
+                    {tx.internal && <span className="badge badge-yellow">internal</span>}
                     {tx.blockHeight !== undefined && <span className="muted">h{tx.blockHeight}</span>}
+                    {confirmations[tx.hash.toLowerCase()] && (
+                      <span className="muted">
+                        {confirmations[tx.hash.toLowerCase()].status === 'confirmed'
+                          ? `${confirmations[tx.hash.toLowerCase()].confirmations} conf`
+                          : confirmations[tx.hash.toLowerCase()].status}
+                      </span>
+                    )}
                     {tx.timestamp && <span className="muted">{new Date(tx.timestamp).toLocaleTimeString()}</span>}
                   </div>
                 </div>
@@ -413,10 +463,11 @@ export const Wallet: React.FC = () => {
       <div className="activity" style={{ marginTop: '2rem' }}>
         <h3>Tracked Addresses</h3>
         <div className="form-group" style={{ display: 'flex', gap: '0.5rem' }}>
-          <input type="text" placeholder="0x... external address" value={trackedInput} onChange={e => setTrackedInput(e.target.value)} />
+          <input type="text" placeholder="0x... external address" value={trackedInput} onChange={e => { setTrackedInput(e.target.value); setTrackedError(''); }} />
           <button className="btn btn-secondary" onClick={addTracked} disabled={!trackedInput.trim()}>Track</button>
           <button className="btn btn-secondary" onClick={refreshTracked} disabled={tracked.length === 0}>Refresh</button>
         </div>
+        {trackedError && <div className="error-text">{trackedError}</div>}
         <div className="activity-list">
           {tracked.length === 0 && <div className="muted">No tracked addresses</div>}
           {tracked.map(addr => (
@@ -436,7 +487,7 @@ export const Wallet: React.FC = () => {
               {trackedData[addr]?.activity && trackedData[addr].activity.length > 0 && (
                 <div style={{ marginTop: '0.5rem' }}>
                   {trackedData[addr].activity.slice(0,5).map(tx => (
-                    <div key={tx.hash} className="tx-item mono" style={{ fontSize: '0.8rem' }}>
+                    <div key={tx.internal ? `${tx.hash}:${tx.from}:${tx.to}` : tx.hash} className="tx-item mono" style={{ fontSize: '0.8rem' }}>
                       <span className="muted">{tx.status}</span> • {tx.hash.slice(0,10)}… • {formatSalt(tx.value)}
                     </div>
                   ))}
@@ -447,6 +498,12 @@ export const Wallet: React.FC = () => {
         </div>
       </div>
 
+      {/* Recurring transfers */}
+      <ScheduledPaymentsPanel accounts={accounts} />
+
+      {/* Connected dapps */}
+      <WalletConnectPanel accounts={accounts} />
+
       {/* Sign / Verify */}
       <div className="sign-verify">
         <h3>Sign &amp; Verify</h3>
@@ -710,6 +767,7 @@ export const Wallet: React.FC = () => {
         .badge { padding: 0.15rem 0.5rem; border-radius: 0.25rem; font-size: 0.75rem; font-weight: 600; }
         .badge-yellow { background: var(--warning-bg); color: var(--warning); }
         .badge-green { background: var(--success-bg); color: var(--success); }
+        .badge-red { background: var(--error-bg); color: var(--error); }
         .sign-verify { margin-top: 2rem; display: grid; grid-template-columns: 1fr 1fr; gap: 1rem; }
         .sign-verify h3 { grid-column: 1 / -1; margin: 0; color: var(--text-primary); }
         .sign-box, .verify-box { background: var(--bg-primary); border-radius: 0.75rem; padding: 1rem; box-shadow: 0 1px 2px var(--shadow); border: 1px solid var(--border-primary); }
@@ -1363,15 +1421,20 @@ const SendTransactionModal: React.FC<{
   // Check if password is required when amount changes
   useEffect(() => {
     const checkPassword = async () => {
-      if (!amount || parseFloat(amount) <= 0) {
+      let valueWei: bigint;
+      try {
+        valueWei = parseUnits(amount);
+      } catch {
+        valueWei = 0n;
+      }
+      if (valueWei <= 0n) {
         setPasswordRequired(null);
         return;
       }
 
       setCheckingSession(true);
       try {
-        const valueWei = BigInt(Math.floor(parseFloat(amount) * 1e18)).toString();
-        const required = await walletService.checkPasswordRequired(account.address, valueWei);
+        const required = await walletService.checkPasswordRequired(account.address, valueWei.toString());
         setPasswordRequired(required);
         // Clear password if not required
         if (!required) {
@@ -1393,7 +1456,7 @@ const SendTransactionModal: React.FC<{
     setError(null);
 
     try {
-      const value = BigInt(Math.floor(parseFloat(amount) * 1e18));
+      const value = parseUnits(amount);
       const txRequest = {
         from: account.address,
         to,
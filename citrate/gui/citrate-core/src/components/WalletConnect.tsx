@@ -0,0 +1,251 @@
+/**
+ * WalletConnect Component
+ *
+ * Pairs external dapps with the wallet over WalletConnect v2, approves
+ * session proposals with a chosen set of accounts, and lets the user
+ * approve or reject the transactions and signatures dapps request.
+ */
+
+import React, { useState, useEffect, useCallback } from 'react';
+import {
+  walletConnectService,
+  WalletConnectProposal,
+  WalletConnectRequest,
+  WalletConnectSession,
+} from '../services/tauri';
+import { Account } from '../types';
+import { formatUnits, SALT_DECIMALS } from '../utils/units';
+
+interface WalletConnectPanelProps {
+  accounts: Account[];
+}
+
+const describeRequest = (request: WalletConnectRequest): string => {
+  if (request.method === 'eth_sendTransaction') {
+    const tx = request.params?.[0] ?? {};
+    const value = formatUnits(BigInt(tx.value ?? '0x0'), SALT_DECIMALS);
+    return `Send ${value} SALT to ${tx.to ?? 'new contract'}`;
+  }
+  if (request.method === 'personal_sign') {
+    const message: string = request.params?.[0] ?? '';
+    if (message.startsWith('0x')) {
+      try {
+        const bytes = message.slice(2).match(/.{1,2}/g)?.map(b => parseInt(b, 16)) ?? [];
+        return `Sign message: ${new TextDecoder().decode(new Uint8Array(bytes))}`;
+      } catch {
+        return `Sign data: ${message.slice(0, 42)}…`;
+      }
+    }
+    return `Sign message: ${message}`;
+  }
+  return request.method;
+};
+
+export const WalletConnectPanel: React.FC<WalletConnectPanelProps> = ({ accounts }) => {
+  const [uri, setUri] = useState('');
+  const [projectId, setProjectId] = useState<string | null>(null);
+  const [projectInput, setProjectInput] = useState('');
+  const [proposals, setProposals] = useState<WalletConnectProposal[]>([]);
+  const [sessions, setSessions] = useState<WalletConnectSession[]>([]);
+  const [requests, setRequests] = useState<WalletConnectRequest[]>([]);
+  const [selected, setSelected] = useState<Record<number, string[]>>({});
+  const [passwords, setPasswords] = useState<Record<number, string>>({});
+  const [error, setError] = useState('');
+  const [busy, setBusy] = useState(false);
+
+  const refresh = useCallback(async () => {
+    try {
+      const [config, props, sess, reqs] = await Promise.all([
+        walletConnectService.getConfig(),
+        walletConnectService.getProposals(),
+        walletConnectService.getSessions(),
+        walletConnectService.getRequests(),
+      ]);
+      setProjectId(config.project_id);
+      setProposals(props);
+      setSessions(sess);
+      setRequests(reqs);
+    } catch (err) {
+      console.error('Failed to load WalletConnect state:', err);
+    }
+  }, []);
+
+  useEffect(() => {
+    refresh();
+    const unlisten = walletConnectService.onEvent(() => refresh());
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [refresh]);
+
+  const run = async (action: () => Promise<unknown>) => {
+    setBusy(true);
+    setError('');
+    try {
+      await action();
+      await refresh();
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const toggleAccount = (proposalId: number, address: string) => {
+    setSelected(prev => {
+      const current = prev[proposalId] ?? [];
+      const next = current.includes(address)
+        ? current.filter(a => a !== address)
+        : [...current, address];
+      return { ...prev, [proposalId]: next };
+    });
+  };
+
+  return (
+    <div className="activity" style={{ marginTop: '2rem' }}>
+      <h3>WalletConnect</h3>
+
+      {projectId === null ? (
+        <div className="form-group" style={{ display: 'flex', gap: '0.5rem' }}>
+          <input
+            type="text"
+            placeholder="WalletConnect project ID"
+            value={projectInput}
+            onChange={e => setProjectInput(e.target.value)}
+          />
+          <button
+            className="btn btn-secondary"
+            disabled={!projectInput.trim() || busy}
+            onClick={() => run(() => walletConnectService.setProjectId(projectInput))}
+          >
+            Save
+          </button>
+        </div>
+      ) : (
+        <div className="form-group" style={{ display: 'flex', gap: '0.5rem' }}>
+          <input
+            type="text"
+            placeholder="wc:… pairing URI from the dapp's QR code"
+            value={uri}
+            onChange={e => setUri(e.target.value)}
+          />
+          <button
+            className="btn btn-primary"
+            disabled={!uri.trim().startsWith('wc:') || busy}
+            onClick={() => run(async () => {
+              await walletConnectService.pair(uri.trim());
+              setUri('');
+            })}
+          >
+            Connect
+          </button>
+        </div>
+      )}
+      {error && <div className="error-text">{error}</div>}
+
+      <div className="activity-list">
+        {proposals.map(proposal => (
+          <div key={proposal.id} className="tx pending">
+            <div className="left">
+              <div className="hash">{proposal.proposer.name || 'Unknown dapp'}</div>
+              <div className="meta">
+                <span className="muted">{proposal.proposer.url}</span>
+                <span className="badge">{proposal.methods.join(', ') || 'no methods'}</span>
+              </div>
+              <div style={{ marginTop: '0.5rem' }}>
+                {accounts.map(account => (
+                  <label key={account.address} className="mono" style={{ display: 'block', fontSize: '0.8rem' }}>
+                    <input
+                      type="checkbox"
+                      checked={(selected[proposal.id] ?? []).includes(account.address)}
+                      onChange={() => toggleAccount(proposal.id, account.address)}
+                    />{' '}
+                    {account.label} ({account.address.slice(0, 10)}…)
+                  </label>
+                ))}
+              </div>
+            </div>
+            <div className="right" style={{ display: 'flex', gap: '0.5rem', alignItems: 'center' }}>
+              <button
+                className="btn btn-primary btn-sm"
+                disabled={busy || !(selected[proposal.id]?.length)}
+                onClick={() => run(() => walletConnectService.approveSession(proposal.id, selected[proposal.id]))}
+              >
+                Approve
+              </button>
+              <button
+                className="btn btn-secondary btn-sm"
+                disabled={busy}
+                onClick={() => run(() => walletConnectService.rejectSession(proposal.id))}
+              >
+                Reject
+              </button>
+            </div>
+          </div>
+        ))}
+
+        {requests.map(request => (
+          <div key={request.id} className="tx pending">
+            <div className="left">
+              <div className="hash">{request.peer.name || 'Dapp'} requests {request.method}</div>
+              <div className="meta">
+                <span className="muted">{describeRequest(request)}</span>
+              </div>
+              <input
+                type="password"
+                placeholder="Password (optional while unlocked)"
+                value={passwords[request.id] ?? ''}
+                onChange={e => setPasswords(prev => ({ ...prev, [request.id]: e.target.value }))}
+                style={{ marginTop: '0.5rem' }}
+              />
+            </div>
+            <div className="right" style={{ display: 'flex', gap: '0.5rem', alignItems: 'center' }}>
+              <button
+                className="btn btn-primary btn-sm"
+                disabled={busy}
+                onClick={() => run(() => walletConnectService.approveRequest(request.id, passwords[request.id]))}
+              >
+                Approve
+              </button>
+              <button
+                className="btn btn-secondary btn-sm"
+                disabled={busy}
+                onClick={() => run(() => walletConnectService.rejectRequest(request.id))}
+              >
+                Reject
+              </button>
+            </div>
+          </div>
+        ))}
+
+        {sessions.length === 0 && proposals.length === 0 && (
+          <div className="muted">No connected dapps</div>
+        )}
+        {sessions.map(session => (
+          <div key={session.topic} className="tx confirmed">
+            <div className="left">
+              <div className="hash">{session.peer.name || 'Dapp'}</div>
+              <div className="meta">
+                <span className="badge">{session.permissions.chains.join(', ')}</span>
+                <span className="muted">
+                  {session.permissions.accounts.length} account(s) • {session.permissions.methods.join(', ')}
+                </span>
+              </div>
+            </div>
+            <div className="right">
+              <button
+                className="btn btn-secondary btn-sm"
+                disabled={busy}
+                onClick={() => run(() => walletConnectService.disconnect(session.topic))}
+              >
+                Disconnect
+              </button>
+            </div>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+};
+
+export default WalletConnectPanel;
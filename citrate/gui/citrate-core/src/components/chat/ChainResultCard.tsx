@@ -32,6 +32,7 @@ import {
   AccountResult,
   ContractResult,
 } from '../../types/agent';
+import { formatUnits, SALT_DECIMALS } from '../../utils/units';
 
 interface ChainResultCardProps {
   result: ChainResult;
@@ -223,8 +224,7 @@ interface ContentProps<T> {
 
 const BalanceContent: React.FC<ContentProps<BalanceResult>> = ({ data, copyValue, copied }) => {
   const formatBalance = (balance: string) => {
-    const num = Number(BigInt(balance)) / 1e18;
-    return num.toLocaleString(undefined, { minimumFractionDigits: 0, maximumFractionDigits: 6 });
+    return formatUnits(balance, SALT_DECIMALS, 6);
   };
 
   return (
@@ -378,8 +378,7 @@ const BlockContent: React.FC<ContentProps<BlockResult>> = ({ data, copyValue, co
 
 const TransactionContent: React.FC<ContentProps<TransactionResult>> = ({ data, copyValue, copied, expanded }) => {
   const formatValue = (value: string) => {
-    const num = Number(BigInt(value)) / 1e18;
-    return num.toLocaleString(undefined, { minimumFractionDigits: 0, maximumFractionDigits: 6 }) + ' CIT';
+    return formatUnits(value, SALT_DECIMALS, 6) + ' CIT';
   };
 
   const getStatusBadge = () => {
@@ -586,8 +585,7 @@ const ReceiptContent: React.FC<ContentProps<ReceiptResult>> = ({ data, copyValue
 
 const AccountContent: React.FC<ContentProps<AccountResult>> = ({ data, copyValue, copied }) => {
   const formatBalance = (balance: string) => {
-    const num = Number(BigInt(balance)) / 1e18;
-    return num.toLocaleString(undefined, { minimumFractionDigits: 0, maximumFractionDigits: 6 }) + ' CIT';
+    return formatUnits(balance, SALT_DECIMALS, 6) + ' CIT';
   };
 
   return (
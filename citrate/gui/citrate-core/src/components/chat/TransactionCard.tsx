@@ -19,6 +19,7 @@ import {
   Shield,
 } from 'lucide-react';
 import { PendingTransaction, TransactionSimulation } from '../../types/agent';
+import { formatUnits, SALT_DECIMALS } from '../../utils/units';
 
 interface TransactionCardProps {
   transaction: PendingTransaction;
@@ -83,13 +84,7 @@ export const TransactionCard: React.FC<TransactionCardProps> = ({
 
   const formatValue = (value: string | undefined) => {
     if (!value) return '0';
-    // Convert from wei to ETH (simplified)
-    const numValue = BigInt(value);
-    const ethValue = Number(numValue) / 1e18;
-    return ethValue.toLocaleString(undefined, {
-      minimumFractionDigits: 0,
-      maximumFractionDigits: 6,
-    });
+    return formatUnits(value, SALT_DECIMALS, 6);
   };
 
   const formatGas = (gas: string | undefined) => {
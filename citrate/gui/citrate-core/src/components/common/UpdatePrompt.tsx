@@ -0,0 +1,150 @@
+/**
+ * Update Prompt Component
+ *
+ * Shows download progress for a new release of the app and, once its
+ * signature has been verified, offers to restart into it. Driven by the
+ * backend's `updater-event`s; the restart can be postponed until the next
+ * release.
+ */
+
+import { useEffect, useState } from 'react';
+import { updaterService, UpdateStatus } from '../../services/tauri';
+
+// ============================================================================
+// Styles
+// ============================================================================
+
+const styles = {
+  prompt: {
+    position: 'fixed' as const,
+    bottom: '16px',
+    right: '16px',
+    zIndex: 9997,
+    display: 'flex',
+    flexDirection: 'column' as const,
+    gap: '8px',
+    backgroundColor: '#f0fdf4',
+    border: '1px solid #86efac',
+    borderRadius: '8px',
+    padding: '12px 14px',
+    boxShadow: '0 4px 16px rgba(0, 0, 0, 0.1)',
+    fontSize: '13px',
+    color: '#14532d',
+    width: '320px',
+  },
+  version: {
+    fontFamily: 'monospace',
+    fontWeight: 600,
+  },
+  progress: {
+    height: '4px',
+    borderRadius: '2px',
+    backgroundColor: '#bbf7d0',
+    overflow: 'hidden' as const,
+  },
+  bar: {
+    height: '100%',
+    backgroundColor: '#16a34a',
+  },
+  actions: {
+    display: 'flex',
+    justifyContent: 'flex-end',
+    gap: '8px',
+  },
+  later: {
+    background: 'none',
+    border: 'none',
+    color: '#166534',
+    cursor: 'pointer',
+  },
+  restart: {
+    backgroundColor: '#16a34a',
+    border: 'none',
+    borderRadius: '4px',
+    color: '#fff',
+    cursor: 'pointer',
+    padding: '4px 10px',
+  },
+  error: {
+    color: '#991b1b',
+  },
+};
+
+// ============================================================================
+// Component
+// ============================================================================
+
+export function UpdatePrompt() {
+  const [status, setStatus] = useState<UpdateStatus | null>(null);
+  const [postponed, setPostponed] = useState<string | null>(null);
+  const [restartError, setRestartError] = useState<string | null>(null);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    // Pick up a release that was staged before this component mounted
+    updaterService
+      .getStatus()
+      .then((current) => current && setStatus(current))
+      .catch(() => {});
+
+    updaterService
+      .onStatus((next) => setStatus(next))
+      .then((fn: () => void) => {
+        unlisten = fn;
+      });
+
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+
+  const restart = async () => {
+    setRestartError(null);
+    try {
+      await updaterService.restart();
+    } catch (e) {
+      setRestartError(String(e));
+    }
+  };
+
+  if (status?.state === 'downloading') {
+    const percent = status.total ? Math.min(100, (status.downloaded / status.total) * 100) : null;
+    return (
+      <div style={styles.prompt} role="status" aria-live="polite">
+        <span>
+          Downloading Citrate <span style={styles.version}>{status.version}</span>
+          {percent !== null && ` (${percent.toFixed(0)}%)`}
+        </span>
+        <div style={styles.progress}>
+          <div style={{ ...styles.bar, width: `${percent ?? 0}%` }} />
+        </div>
+      </div>
+    );
+  }
+
+  if (status?.state !== 'ready' || postponed === status.version) {
+    return null;
+  }
+
+  return (
+    <div style={styles.prompt} role="alertdialog" aria-label="Update ready">
+      <span>
+        Citrate <span style={styles.version}>{status.version}</span> is downloaded and verified
+        {' '}(you have <span style={styles.version}>{status.current_version}</span>).
+        Restart to finish updating.
+      </span>
+      {restartError && <span style={styles.error}>{restartError}</span>}
+      <div style={styles.actions}>
+        <button style={styles.later} onClick={() => setPostponed(status.version)}>
+          Later
+        </button>
+        <button style={styles.restart} onClick={restart}>
+          Restart now
+        </button>
+      </div>
+    </div>
+  );
+}
+
+export default UpdatePrompt;
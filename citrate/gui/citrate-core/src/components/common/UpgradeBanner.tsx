@@ -0,0 +1,103 @@
+/**
+ * Upgrade Banner Component
+ *
+ * Shown when a majority of connected peers run a newer client version than
+ * this node. The backend emits `upgrade-available` once per new version; the
+ * banner can be dismissed until a newer version appears.
+ */
+
+import { useEffect, useState } from 'react';
+import { nodeService, UpgradeNotice } from '../../services/tauri';
+
+// ============================================================================
+// Styles
+// ============================================================================
+
+const styles = {
+  banner: {
+    position: 'fixed' as const,
+    top: '16px',
+    right: '16px',
+    zIndex: 9997,
+    display: 'flex',
+    alignItems: 'center',
+    gap: '12px',
+    backgroundColor: '#fff7ed',
+    border: '1px solid #fdba74',
+    borderRadius: '8px',
+    padding: '10px 14px',
+    boxShadow: '0 4px 16px rgba(0, 0, 0, 0.1)',
+    fontSize: '13px',
+    color: '#7c2d12',
+    maxWidth: '420px',
+  },
+  version: {
+    fontFamily: 'monospace',
+    fontWeight: 600,
+  },
+  dismiss: {
+    marginLeft: 'auto',
+    background: 'none',
+    border: 'none',
+    color: '#9a3412',
+    cursor: 'pointer',
+    fontSize: '16px',
+    lineHeight: 1,
+  },
+};
+
+// ============================================================================
+// Component
+// ============================================================================
+
+export function UpgradeBanner() {
+  const [notice, setNotice] = useState<UpgradeNotice | null>(null);
+  const [dismissed, setDismissed] = useState<string | null>(null);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    // Pick up a notice that fired before this component mounted
+    nodeService
+      .getStatus()
+      .then((status) => {
+        if (status?.upgradeAvailable) {
+          setNotice({ current_version: status.version, latest_version: status.upgradeAvailable });
+        }
+      })
+      .catch(() => {});
+
+    nodeService
+      .onUpgradeAvailable((next) => setNotice(next))
+      .then((fn: () => void) => {
+        unlisten = fn;
+      });
+
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+
+  if (!notice || dismissed === notice.latest_version) {
+    return null;
+  }
+
+  return (
+    <div style={styles.banner} role="status" aria-live="polite">
+      <span>
+        Most peers run Citrate <span style={styles.version}>{notice.latest_version}</span>
+        {' '}(you have <span style={styles.version}>{notice.current_version}</span>).
+        Consider upgrading.
+      </span>
+      <button
+        style={styles.dismiss}
+        onClick={() => setDismissed(notice.latest_version)}
+        aria-label="Dismiss upgrade notice"
+      >
+        ×
+      </button>
+    </div>
+  );
+}
+
+export default UpgradeBanner;
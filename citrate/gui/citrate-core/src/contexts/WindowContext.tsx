@@ -7,7 +7,7 @@
  * methods for opening, closing, and communicating between windows.
  */
 
-import React, { createContext, useContext, useReducer, useCallback, useEffect } from 'react';
+import React, { createContext, useContext, useReducer, useCallback, useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { listen, emit } from '@tauri-apps/api/event';
 import {
@@ -20,8 +20,17 @@ import {
   DEFAULT_WINDOW_SIZES,
   DEFAULT_WINDOW_TITLES,
   WindowEvent,
+  AccessibleWindow,
+  AccessibilityPreferences,
+  FocusDirection,
 } from '../types/window';
 
+const DEFAULT_ACCESSIBILITY: AccessibilityPreferences = {
+  high_contrast: false,
+  zoom: 1,
+  reduced_motion: false,
+};
+
 // Generate unique window ID
 function generateWindowId(type: WindowType): string {
   return `${type}_${Date.now()}_${Math.random().toString(36).substr(2, 6)}`;
@@ -127,6 +136,8 @@ interface WindowProviderProps {
  */
 export function WindowProvider({ children }: WindowProviderProps) {
   const [state, dispatch] = useReducer(windowReducer, initialState);
+  const [accessibility, setAccessibilityState] =
+    useState<AccessibilityPreferences>(DEFAULT_ACCESSIBILITY);
 
   // Listen for window events from Tauri
   useEffect(() => {
@@ -143,8 +154,15 @@ export function WindowProvider({ children }: WindowProviderProps) {
       listen<WindowEvent>('window-blurred', (event) => {
         dispatch({ type: 'BLUR_WINDOW', id: event.payload.windowId });
       }),
+      listen<AccessibilityPreferences>('accessibility-changed', (event) => {
+        setAccessibilityState(event.payload);
+      }),
     ]);
 
+    invoke<AccessibilityPreferences>('get_accessibility_preferences')
+      .then(setAccessibilityState)
+      .catch(() => {});
+
     return () => {
       unlisten.then((listeners) => listeners.forEach((u) => u()));
     };
@@ -250,6 +268,33 @@ export function WindowProvider({ children }: WindowProviderProps) {
     [state.windows]
   );
 
+  // Keyboard focus cycling (e.g. Ctrl+` / Ctrl+Shift+`)
+  const cycleFocus = useCallback(
+    async (direction: FocusDirection): Promise<AccessibleWindow | null> => {
+      const focused = await invoke<AccessibleWindow | null>('cycle_window_focus', { direction });
+      if (focused) {
+        dispatch({ type: 'FOCUS_WINDOW', id: focused.id });
+      }
+      return focused;
+    },
+    []
+  );
+
+  const listAccessibleWindows = useCallback(
+    (): Promise<AccessibleWindow[]> => invoke<AccessibleWindow[]>('get_accessible_windows'),
+    []
+  );
+
+  const setAccessibility = useCallback(
+    async (preferences: AccessibilityPreferences): Promise<void> => {
+      const saved = await invoke<AccessibilityPreferences>('set_accessibility_preferences', {
+        preferences,
+      });
+      setAccessibilityState(saved);
+    },
+    []
+  );
+
   const contextValue: WindowContextType = {
     state,
     openWindow,
@@ -259,6 +304,10 @@ export function WindowProvider({ children }: WindowProviderProps) {
     getWindowsByType,
     updateWindowData,
     hasOpenWindow,
+    cycleFocus,
+    listAccessibleWindows,
+    accessibility,
+    setAccessibility,
   };
 
   return <WindowContext.Provider value={contextValue}>{children}</WindowContext.Provider>;
@@ -1,10 +1,15 @@
 import type { 
   NodeStatus, 
   NodeConfig, 
+  MiningMode, 
+  PrunePreview,
   Account, 
   DAGData, 
   DAGNode,
   DAGLink,
+  DAGStatsBucket,
+  ChainStatsSnapshot,
+  DAGSearchResult,
   BlockDetails,
   TipInfo,
   ModelDeployment,
@@ -13,6 +18,7 @@ import type {
   ModelInfo,
   TransactionRequest,
   PeerInfoSummary,
+  PeerReputation,
   TxActivity
 } from '../types';
 import { rpcClient } from './rpc-client';
@@ -101,6 +107,8 @@ const mapNodeStatusFromNative = (raw: any): NodeStatus => ({
     const n = Number(ts);
     return n < 2_000_000_000 ? n * 1000 : n;
   })(),
+  upgradeAvailable: raw.upgrade_available ?? raw.upgradeAvailable ?? null,
+  externalRpc: raw.external_rpc ?? raw.externalRpc ?? null,
 });
 
 const mapDAGDataFromNative = (raw: any): DAGData => {
@@ -373,6 +381,30 @@ const webImplementations = {
   },
   
   // DAG
+  get_dag_stats_series: async (args: { from?: number, to?: number, bucketSecs?: number }) => {
+    try {
+      const data = await (rpcClient as any).sendRequest(
+        'citrate_getDagStatsSeries',
+        [args.from ?? null, args.to ?? null, args.bucketSecs ?? null]
+      );
+      return data || [];
+    } catch {
+      return [];
+    }
+  },
+
+  get_stats_history: async (args: { from?: number, to?: number }) => {
+    try {
+      const params: Record<string, number> = {};
+      if (args.from !== undefined) params.from = args.from;
+      if (args.to !== undefined) params.to = args.to;
+      const data = await (rpcClient as any).sendRequest('citrate_getStatsHistory', params);
+      return data || [];
+    } catch {
+      return [];
+    }
+  },
+
   get_dag_data: async (args: { limit: number, startHeight?: number }) => {
     try {
       // Prefer custom DAG RPC if available; otherwise build minimal DAG
@@ -622,13 +654,60 @@ const safeInvoke = async <T>(cmd: string, args?: any): Promise<T> => {
   }
 };
 
+// Node health self-diagnostics
+export type DiagnosticStatus = 'pass' | 'skip' | 'warn' | 'fail';
+
+export interface DiagnosticCheck {
+  name: string;
+  status: DiagnosticStatus;
+  detail: string;
+  remediation?: string | null;
+}
+
+export interface DiagnosticsReport {
+  checks: DiagnosticCheck[];
+  passed: number;
+  warnings: number;
+  failures: number;
+  generatedAt: number;
+}
+
+// Payload of `sync-progress`, emitted every second while the node is
+// behind its peers and once more when it catches up
+export type SyncStage = 'headers' | 'blocks' | 'execution';
+
+export interface SyncStageProgress {
+  stage: SyncStage;
+  completed: number;
+  total: number;
+  // Items per second over the last 30 seconds
+  rate: number;
+}
+
+export interface SyncProgress {
+  current_height: number;
+  target_height: number;
+  // Stage still running, null once synced
+  stage: SyncStage | null;
+  stages: SyncStageProgress[];
+  blocks_per_sec: number;
+  eta_secs: number | null;
+}
+
 // Node Management
 export const nodeService = {
   start: () => safeInvoke<string>('start_node'),
   stop: () => safeInvoke<string>('stop_node'),
   getStatus: () => safeInvoke<NodeStatus>('get_node_status'),
+  // Ports, disk, clock, IPFS, bootnodes, database and GPU driver checks
+  runDiagnostics: () => safeInvoke<DiagnosticsReport>('run_diagnostics'),
+  getSyncProgress: () => safeInvoke<SyncProgress | null>('get_sync_progress'),
   updateConfig: (config: NodeConfig) => safeInvoke<string>('update_node_config', { config }),
   getConfig: () => safeInvoke<NodeConfig>('get_node_config'),
+  // Use an already running node (e.g. a server validator) instead of the embedded one
+  attachExternal: async (rpcUrl: string) =>
+    mapNodeStatusFromNative(await safeInvoke<any>('node_attach_external', { rpcUrl })),
+  detachExternal: () => safeInvoke<void>('node_detach_external'),
   getTxOverview: () => safeInvoke<{ pending: number; last_block: number }>('get_tx_overview'),
   getMempoolPending: (limit = 50) => safeInvoke<any[]>('get_mempool_pending', { limit }),
   joinTestnet: (args: {
@@ -672,27 +751,231 @@ export const nodeService = {
       state: (p.state as string) as 'connecting' | 'handshaking' | 'connected' | 'disconnecting' | 'disconnected',
       score: Number(p.score ?? 0),
       lastSeenSecs: Number(p.last_seen_secs ?? p.lastSeenSecs ?? 0),
+      clientVersion: p.client_version ?? p.clientVersion ?? null,
     })) as PeerInfoSummary[];
   },
+  getPeerVersions: () => safeInvoke<PeerVersionDistribution>('get_peer_versions'),
+  getPeerReputation: async () => {
+    const raw = await safeInvoke<any[]>('get_peer_reputation');
+    return (raw || []).map((r: any) => ({
+      ip: String(r.ip),
+      peerId: r.peer_id ?? null,
+      score: Number(r.score ?? 0),
+      connected: Boolean(r.connected),
+      banned: Boolean(r.banned),
+      bannedUntil: r.banned_until ?? null,
+      banReason: r.ban_reason ?? null,
+      banCount: Number(r.ban_count ?? 0),
+      events: (r.events || []).map((e: any) => ({
+        reason: String(e.reason),
+        delta: Number(e.delta),
+        at: Number(e.at),
+      })),
+    })) as PeerReputation[];
+  },
+  // Blocks, receipts and states the next prune would remove, and bytes freed
+  getPrunePreview: () => safeInvoke<PrunePreview>('storage_prune_preview'),
   setRewardAddress: (address: string) => safeInvoke<string>('set_reward_address', { address }),
   getRewardAddress: () => safeInvoke<string | null>('get_reward_address'),
+
+  // Devnet block production control (dev builds only)
+  setBlockInterval: (seconds: number) =>
+    safeInvoke<MiningMode>('dev_set_block_interval', { seconds }),
+  mineBlocks: (count?: number) => safeInvoke<string[]>('dev_mine_blocks', { count }),
+  setAutomine: (enabled: boolean) => safeInvoke<MiningMode>('dev_set_automine', { enabled }),
   
-  // Listen to status updates
-  onStatusUpdate: (callback: (status: NodeStatus) => void) => {
+  // Fired when a majority of peers run a newer client version
+  onUpgradeAvailable: (callback: (notice: UpgradeNotice) => void) => {
     if (!isTauri() || !listen) {
-      // Return a dummy unsubscribe function
       return Promise.resolve(() => {});
     }
-    return listen('node-status', (event: any) => {
+    return listen('upgrade-available', (event: any) => {
+      callback(event.payload as UpgradeNotice);
+    });
+  },
+
+  // Fired while syncing, with per-stage rates and an ETA
+  onSyncProgress: (callback: (progress: SyncProgress) => void) => {
+    if (!isTauri() || !listen) {
+      return Promise.resolve(() => {});
+    }
+    return listen('sync-progress', (event: any) => {
+      callback(event.payload as SyncProgress);
+    });
+  },
+
+  // Listen to status updates (delivered only when the status changed)
+  onStatusUpdate: (callback: (status: NodeStatus) => void) => {
+    return stateService.onStateDelta((delta) => {
+      if (!delta.node_status) return;
       try {
-        callback(mapNodeStatusFromNative(event.payload));
+        callback(mapNodeStatusFromNative(delta.node_status));
       } catch {
-        callback(event.payload as NodeStatus);
+        callback(delta.node_status as NodeStatus);
       }
     });
   }
 };
 
+export interface PeerVersionDistribution {
+  versions: Record<string, number>;
+  unknown: number;
+  upgrade_available: string | null;
+}
+
+export interface UpgradeNotice {
+  current_version: string;
+  latest_version: string;
+}
+
+// Coalesced state updates
+export interface StateDelta {
+  sequence: number;
+  node_status?: any;
+  dag?: TipInfo[];
+  peers?: PeerInfoSummary[];
+  downloads?: Record<string, any>;
+}
+
+export interface EventBusConfig {
+  interval_ms: number;
+  performance_interval_ms: number;
+  performance_mode: boolean;
+}
+
+export const stateService = {
+  // Listen to `state-delta` events; sections that did not change are omitted
+  onStateDelta: (callback: (delta: StateDelta) => void) => {
+    if (!isTauri() || !listen) {
+      // Return a dummy unsubscribe function
+      return Promise.resolve(() => {});
+    }
+    return listen('state-delta', (event: any) => {
+      callback(event.payload as StateDelta);
+    });
+  },
+
+  getConfig: () => safeInvoke<EventBusConfig>('get_event_bus_config'),
+  updateConfig: (config: EventBusConfig) =>
+    safeInvoke<void>('update_event_bus_config', { config }),
+  setPerformanceMode: (enabled: boolean) =>
+    safeInvoke<void>('set_performance_mode', { enabled }),
+};
+
+// Payload of `tx-confirmed`, emitted at 1/3/6 confirmations and on reorg
+export interface TxConfirmation {
+  tx_hash: string;
+  status: 'confirmed' | 'reorged' | 'dropped';
+  confirmations: number;
+  block_hash: string | null;
+  block_height: number | null;
+  success: boolean | null;
+}
+
+// One status change in `wallet-activity-changed`
+export interface ActivityTransition {
+  tx_hash: string;
+  internal: boolean;
+  // null for activity seen for the first time
+  from_status: string | null;
+  to_status: 'pending' | 'confirmed' | 'failed' | 'reorged' | 'dropped';
+  block_hash: string | null;
+  block_height: number | null;
+}
+
+// Payload of `wallet-activity-changed`, emitted when activity of an address
+// shown in the wallet changes status, including blocks that leave the chain
+export interface WalletActivityChanged {
+  address: string;
+  transitions: ActivityTransition[];
+}
+
+export interface WalletBackupImport {
+  imported: { address: string; label: string }[];
+  skipped: string[];
+  settings: {
+    tracked_addresses: string[];
+    reward_address: string | null;
+  };
+}
+
+// Address book: named recipients and recently used addresses
+export interface Contact {
+  name: string;
+  address: string;
+  tags: string[];
+  note: string | null;
+  createdAt: number;
+  lastUsed: number | null;
+}
+
+export interface ContactDraft {
+  name: string;
+  address: string;
+  tags?: string[];
+  note?: string | null;
+}
+
+export interface RecentAddress {
+  address: string;
+  lastUsed: number;
+  timesUsed: number;
+}
+
+export interface ResolvedRecipient {
+  input: string;
+  address: string;
+  source: 'address' | 'contact' | 'registry';
+  name: string | null;
+}
+
+// Scheduled payments: recurring transfers submitted by the node manager.
+// Amounts and gas prices are wei decimal strings, times unix seconds.
+export type PaymentStatus = 'active' | 'paused' | 'awaiting_approval' | 'completed';
+
+export interface ScheduledPayment {
+  id: string;
+  label: string | null;
+  from: string;
+  to: string;
+  amount: string;
+  gasPrice: string | null;
+  intervalSecs: number;
+  startAt: number;
+  endAt: number | null;
+  nextRun: number;
+  status: PaymentStatus;
+  runs: number;
+  lastRun: number | null;
+  createdAt: number;
+}
+
+export interface PaymentDraft {
+  label?: string | null;
+  from: string;
+  // Address, contact or `.ctr` name
+  to: string;
+  amount: string;
+  gasPrice?: string | null;
+  intervalSecs: number;
+  startAt?: number | null;
+  endAt?: number | null;
+}
+
+// Payload of `scheduled-payment`; `needs_approval` means the wallet was
+// locked and `approveScheduledPayment` must be called with the password
+export interface PaymentRun {
+  paymentId: string;
+  label: string | null;
+  to: string;
+  amount: string;
+  dueAt: number;
+  executedAt: number;
+  outcome: 'submitted' | 'failed' | 'needs_approval';
+  txHash: string | null;
+  error: string | null;
+}
+
 // Wallet Management
 export const walletService = {
   createAccount: (label: string, password: string) => 
@@ -720,6 +1003,20 @@ export const walletService = {
     safeInvoke<boolean>('verify_signature', { message, signature, address }),
   exportPrivateKey: (address: string, password: string) =>
     safeInvoke<string>('export_private_key', { address, password }),
+  // Confirmation updates for transactions sent from this wallet
+  onTxConfirmed: (callback: (update: TxConfirmation) => void) => {
+    if (!isTauri() || !listen) {
+      return Promise.resolve(() => {});
+    }
+    return listen('tx-confirmed', (event: any) => {
+      callback(event.payload as TxConfirmation);
+    });
+  },
+  // Single-file encrypted backup of all accounts, tracked addresses and reward address
+  exportBackup: (path: string, password: string) =>
+    safeInvoke<void>('wallet_export_backup', { path, password }),
+  importBackup: (path: string, password: string) =>
+    safeInvoke<WalletBackupImport>('wallet_import_backup', { path, password }),
   getObservedBalance: (address: string, blockWindow = 256) =>
     safeInvoke<string>('get_address_observed_balance', { address, blockWindow }),
   
@@ -742,9 +1039,32 @@ export const walletService = {
       password: password || null
     }),
 
+  // Speed up or cancel a pending transaction at the same nonce; gasPrice (wei)
+  // defaults to the lowest price the mempool accepts as a replacement
+  replaceTransaction: (hash: string, gasPrice?: string, password?: string) =>
+    safeInvoke<string>('replace_transaction', {
+      hash,
+      gasPrice: gasPrice || null,
+      password: password || null
+    }),
+  cancelTransaction: (hash: string, gasPrice?: string, password?: string) =>
+    safeInvoke<string>('cancel_transaction', {
+      hash,
+      gasPrice: gasPrice || null,
+      password: password || null
+    }),
+
   // Wallet activity
   getAccountActivity: (address: string, blockWindow = 256, limit = 100) =>
     safeInvoke<TxActivity[]>('get_account_activity', { address, blockWindow, limit }),
+  onActivityChanged: (callback: (change: WalletActivityChanged) => void) => {
+    if (!isTauri() || !listen) {
+      return Promise.resolve(() => {});
+    }
+    return listen('wallet-activity-changed', (event: any) => {
+      callback(event.payload as WalletActivityChanged);
+    });
+  },
 
   // Tracked addresses (persisted in backend)
   getTrackedAddresses: () =>
@@ -752,6 +1072,491 @@ export const walletService = {
 
   saveTrackedAddresses: (addresses: string[]) =>
     safeInvoke<void>('save_tracked_addresses', { addresses }),
+
+  // Address book - `sendTransaction` also accepts a contact or `.ctr` name as recipient
+  listContacts: (tag?: string) =>
+    safeInvoke<Contact[]>('wallet_list_contacts', { tag: tag ?? null }),
+  getContactTags: () =>
+    safeInvoke<string[]>('wallet_contact_tags'),
+  addContact: (contact: ContactDraft) =>
+    safeInvoke<Contact>('wallet_add_contact', { contact }),
+  updateContact: (name: string, contact: ContactDraft) =>
+    safeInvoke<Contact>('wallet_update_contact', { name, contact }),
+  removeContact: (name: string) =>
+    safeInvoke<void>('wallet_remove_contact', { name }),
+  getRecentAddresses: () =>
+    safeInvoke<RecentAddress[]>('wallet_recent_addresses'),
+  resolveRecipient: (input: string) =>
+    safeInvoke<ResolvedRecipient>('wallet_resolve_recipient', { input }),
+
+  // Scheduled payments
+  listScheduledPayments: () =>
+    safeInvoke<ScheduledPayment[]>('wallet_list_scheduled_payments'),
+  createScheduledPayment: (payment: PaymentDraft) =>
+    safeInvoke<ScheduledPayment>('wallet_create_scheduled_payment', { payment }),
+  pauseScheduledPayment: (id: string) =>
+    safeInvoke<ScheduledPayment>('wallet_pause_scheduled_payment', { id }),
+  resumeScheduledPayment: (id: string) =>
+    safeInvoke<ScheduledPayment>('wallet_resume_scheduled_payment', { id }),
+  removeScheduledPayment: (id: string) =>
+    safeInvoke<void>('wallet_remove_scheduled_payment', { id }),
+  approveScheduledPayment: (id: string, password: string) =>
+    safeInvoke<PaymentRun>('wallet_approve_scheduled_payment', { id, password }),
+  getScheduledPaymentHistory: (id?: string, limit?: number) =>
+    safeInvoke<PaymentRun[]>('wallet_scheduled_payment_history', {
+      id: id ?? null,
+      limit: limit ?? null,
+    }),
+  onScheduledPayment: (callback: (run: PaymentRun) => void) => {
+    if (!isTauri() || !listen) {
+      return Promise.resolve(() => {});
+    }
+    return listen('scheduled-payment', (event: any) => {
+      callback(event.payload as PaymentRun);
+    });
+  },
+};
+
+// Named profiles, each with its own wallet, tracked addresses and node data
+export interface ProfileInfo {
+  name: string;
+  createdAt: number;
+  active: boolean;
+  dataDir: string;
+  configDir: string;
+}
+
+// Chain data removed by a reset or network profile wipe
+export interface WipeTarget {
+  path: string;
+  description: string;
+  bytes: number;
+}
+
+export interface WipePlan {
+  profile: string;
+  targets: WipeTarget[];
+  confirmationToken: string;
+  wiped: boolean;
+}
+
+export const profileService = {
+  list: () =>
+    safeInvoke<ProfileInfo[]>('profile_list'),
+  create: (name: string) =>
+    safeInvoke<ProfileInfo>('profile_create', { name }),
+  // Stops the node and reloads the wallet and node config of `name`
+  switch: (name: string) =>
+    safeInvoke<ProfileInfo>('profile_switch', { name }),
+  delete: (name: string) =>
+    safeInvoke<void>('profile_delete', { name }),
+  // Without a confirmation token these return the plan for review; pass
+  // its confirmationToken back to carry it out
+  resetChainData: (keepWallet: boolean, confirmationToken?: string) =>
+    safeInvoke<WipePlan>('reset_chain_data', {
+      keepWallet,
+      confirmationToken: confirmationToken ?? null,
+    }),
+  wipeNetworkProfile: (name: string, confirmationToken?: string) =>
+    safeInvoke<WipePlan>('wipe_network_profile', {
+      name,
+      confirmationToken: confirmationToken ?? null,
+    }),
+  onChanged: (callback: (profile: ProfileInfo) => void) => {
+    if (!isTauri() || !listen) {
+      return Promise.resolve(() => {});
+    }
+    return listen('profile-changed', (event: any) => {
+      callback(event.payload as ProfileInfo);
+    });
+  },
+};
+
+// Remote signer (keys held by an external signing service)
+export interface RemoteSignerStatus {
+  connected: boolean;
+  endpoint: string | null;
+  accounts: string[];
+}
+
+export const remoteSignerService = {
+  connect: (endpoint: string) =>
+    safeInvoke<Account[]>('connect_remote_signer', { endpoint }),
+  disconnect: () =>
+    safeInvoke<void>('disconnect_remote_signer'),
+  getStatus: () =>
+    safeInvoke<RemoteSignerStatus>('get_remote_signer_status'),
+};
+
+// WalletConnect v2 dApp connector
+export interface WalletConnectMetadata {
+  name: string;
+  description: string;
+  url: string;
+  icons: string[];
+}
+
+export interface WalletConnectProposal {
+  id: number;
+  pairing_topic: string;
+  proposer: WalletConnectMetadata;
+  required_chains: string[];
+  optional_chains: string[];
+  methods: string[];
+  events: string[];
+}
+
+export interface WalletConnectSession {
+  topic: string;
+  peer: WalletConnectMetadata;
+  permissions: {
+    accounts: string[];
+    chains: string[];
+    methods: string[];
+    events: string[];
+  };
+  expiry: number;
+}
+
+export interface WalletConnectRequest {
+  id: number;
+  topic: string;
+  peer: WalletConnectMetadata;
+  chain_id: string;
+  method: 'eth_sendTransaction' | 'personal_sign' | string;
+  params: any;
+}
+
+export type WalletConnectEvent =
+  | ({ type: 'session_proposal' } & WalletConnectProposal)
+  | ({ type: 'session_request' } & WalletConnectRequest)
+  | { type: 'session_deleted'; topic: string };
+
+export const walletConnectService = {
+  getConfig: () =>
+    safeInvoke<{ project_id: string | null; relay_url: string }>('walletconnect_get_config'),
+  setProjectId: (projectId: string) =>
+    safeInvoke<void>('walletconnect_set_project_id', { projectId }),
+  // Pair from a wc: URI, pasted or read from the dapp's QR code
+  pair: (uri: string) =>
+    safeInvoke<void>('walletconnect_pair', { uri }),
+  getProposals: () =>
+    safeInvoke<WalletConnectProposal[]>('walletconnect_get_proposals'),
+  approveSession: (proposalId: number, accounts: string[]) =>
+    safeInvoke<WalletConnectSession>('walletconnect_approve_session', { proposalId, accounts }),
+  rejectSession: (proposalId: number) =>
+    safeInvoke<void>('walletconnect_reject_session', { proposalId }),
+  getSessions: () =>
+    safeInvoke<WalletConnectSession[]>('walletconnect_get_sessions'),
+  disconnect: (topic: string) =>
+    safeInvoke<void>('walletconnect_disconnect', { topic }),
+  getRequests: () =>
+    safeInvoke<WalletConnectRequest[]>('walletconnect_get_requests'),
+  // Password is optional while a wallet session is active
+  approveRequest: (requestId: number, password?: string) =>
+    safeInvoke<string>('walletconnect_approve_request', { requestId, password: password || null }),
+  rejectRequest: (requestId: number) =>
+    safeInvoke<void>('walletconnect_reject_request', { requestId }),
+  onEvent: (callback: (event: WalletConnectEvent) => void) => {
+    if (!isTauri() || !listen) {
+      return Promise.resolve(() => {});
+    }
+    return listen('walletconnect-event', (event: any) => {
+      callback(event.payload as WalletConnectEvent);
+    });
+  },
+};
+
+// Signed app updates
+export type UpdateChannel = 'stable' | 'beta' | 'nightly';
+
+export interface ReleaseInfo {
+  version: string;
+  current_version: string;
+  channel: UpdateChannel;
+  notes: string;
+  pub_date: string | null;
+  artifact: { url: string; signature: string; size: number | null };
+}
+
+export type UpdateStatus =
+  | { state: 'idle' }
+  | { state: 'checking' }
+  | { state: 'up_to_date'; version: string }
+  | ({ state: 'available' } & ReleaseInfo)
+  | { state: 'downloading'; version: string; downloaded: number; total: number | null }
+  | ({ state: 'ready' } & ReleaseInfo)
+  | { state: 'failed'; error: string };
+
+export interface UpdaterConfig {
+  channel: UpdateChannel;
+  manifest_base: string;
+  auto_download: boolean;
+}
+
+export const updaterService = {
+  getStatus: () => safeInvoke<UpdateStatus>('updater_get_status'),
+  getConfig: () => safeInvoke<UpdaterConfig>('updater_get_config'),
+  check: () => safeInvoke<ReleaseInfo | null>('updater_check'),
+  // Runs in the background; follow progress with onStatus
+  download: () => safeInvoke<void>('updater_download'),
+  setChannel: (channel: UpdateChannel) =>
+    safeInvoke<void>('updater_set_channel', { channel }),
+  setAutoDownload: (enabled: boolean) =>
+    safeInvoke<void>('updater_set_auto_download', { enabled }),
+  // Installs the verified release and restarts the app
+  restart: () => safeInvoke<void>('updater_restart'),
+  onStatus: (callback: (status: UpdateStatus) => void) => {
+    if (!isTauri() || !listen) {
+      return Promise.resolve(() => {});
+    }
+    return listen('updater-event', (event: any) => {
+      callback(event.payload as UpdateStatus);
+    });
+  },
+};
+
+// Language of backend messages and errors
+export type LocaleCode = 'en' | 'es' | 'zh';
+
+export interface LocaleInfo {
+  current: LocaleCode;
+  available: { code: LocaleCode; name: string }[];
+}
+
+export const localeService = {
+  get: () => safeInvoke<LocaleInfo>('get_locale'),
+  // Persisted across restarts
+  set: (locale: LocaleCode) => safeInvoke<LocaleInfo>('set_locale', { locale }),
+};
+
+// Contract deployment from forge build artifacts (amounts are decimal wei strings)
+export interface ForgeContract {
+  name: string;
+  source_file: string;
+  bytecode: string | null;
+  deployed_bytecode: string | null;
+  abi: unknown[] | null;
+}
+
+export interface DeployContractArgs {
+  from: string;
+  // One value per constructor input, in ABI order
+  constructorArgs: unknown[];
+  value?: string;
+  // The estimate when omitted
+  gasLimit?: number;
+  // The mempool minimum when omitted
+  gasPrice?: string;
+}
+
+export interface DeploymentEstimate {
+  data: string;
+  gasEstimate: number;
+  gasLimit: number;
+  gasPrice: string;
+}
+
+export type DeploymentStatus = 'pending' | 'deployed' | 'failed';
+
+export interface ContractDeployment {
+  txHash: string;
+  contractName: string;
+  sourceFile: string;
+  from: string;
+  constructorArgs: string[];
+  value: string;
+  gasEstimate: number;
+  gasLimit: number;
+  gasPrice: string;
+  status: DeploymentStatus;
+  contractAddress: string | null;
+  blockNumber: number | null;
+  gasUsed: number | null;
+  submittedAt: number;
+}
+
+export const contractService = {
+  // Encodes the constructor arguments and dry-runs the deployment; nothing is signed
+  estimateDeployment: (contract: ForgeContract, args: DeployContractArgs) =>
+    safeInvoke<DeploymentEstimate>('estimate_contract_deployment', { contract, args }),
+  deploy: (contract: ForgeContract, args: DeployContractArgs, password?: string) =>
+    safeInvoke<ContractDeployment>('deploy_contract', {
+      contract,
+      args,
+      password: password ?? null,
+    }),
+  // Newest first; pending deployments are settled with their address once included
+  getDeployments: () =>
+    safeInvoke<ContractDeployment[]>('get_contract_deployments'),
+};
+
+// Governance proposals (amounts are decimal wei strings)
+export type ProposalState =
+  | 'active'
+  | 'queued'
+  | 'succeeded'
+  | 'failed'
+  | 'canceled'
+  | 'expired'
+  | 'executed';
+
+export type VoteSupport = 'for' | 'against' | 'abstain';
+
+export interface GovernanceConfig {
+  precompile: string;
+  proposalThreshold: string;
+  voteThreshold: string;
+  votingPeriod: number;
+  executionDelay: number;
+  gracePeriod: number;
+  quorumPercentage: number;
+  approvalThreshold: number;
+}
+
+export interface ProposalInfo {
+  id: number;
+  proposer: string;
+  parameter: string;
+  value: string;
+  description: string;
+  state: ProposalState;
+  createdHeight: number;
+  endHeight: number;
+  executableHeight: number;
+  expiryHeight: number;
+  executedHeight: number | null;
+  quorum: string;
+  votesFor: string;
+  votesAgainst: string;
+  votesAbstain: string;
+  quorumReached: boolean;
+  passed: boolean;
+}
+
+export interface VoteInfo {
+  support: VoteSupport;
+  weight: string;
+}
+
+export const governanceService = {
+  getConfig: () => safeInvoke<GovernanceConfig>('gov_get_config'),
+  // Newest first
+  listProposals: (offset?: number, limit?: number) =>
+    safeInvoke<ProposalInfo[]>('gov_list_proposals', {
+      offset: offset ?? null,
+      limit: limit ?? null,
+    }),
+  getProposal: (id: number) => safeInvoke<ProposalInfo | null>('gov_get_proposal', { id }),
+  getVote: (id: number, voter: string) =>
+    safeInvoke<VoteInfo | null>('gov_get_vote', { id, voter }),
+  // The write calls return the transaction hash
+  submitProposal: (
+    from: string,
+    parameter: string,
+    value: string,
+    description: string,
+    password?: string
+  ) =>
+    safeInvoke<string>('gov_submit_proposal', {
+      from,
+      parameter,
+      value,
+      description,
+      password: password || null,
+    }),
+  vote: (from: string, id: number, support: VoteSupport, password?: string) =>
+    safeInvoke<string>('gov_vote', { from, id, support, password: password || null }),
+  execute: (from: string, id: number, password?: string) =>
+    safeInvoke<string>('gov_execute', { from, id, password: password || null }),
+  cancel: (from: string, id: number, password?: string) =>
+    safeInvoke<string>('gov_cancel', { from, id, password: password || null }),
+};
+
+export type MultisigStatus = 'pending' | 'active' | 'failed';
+
+export interface MultisigAccount {
+  id: string;
+  name: string;
+  owners: string[];
+  threshold: number;
+  walletId: number | null;
+  creationTx: string | null;
+  status: MultisigStatus;
+  createdAt: number;
+}
+
+export interface MultisigDraft {
+  name: string;
+  // Account that signs and pays for the creation
+  from: string;
+  owners: string[];
+  threshold: number;
+  // Wei deposited at creation
+  deposit?: string;
+}
+
+export interface MultisigWalletInfo {
+  walletId: number;
+  owners: string[];
+  threshold: number;
+  balance: string;
+  proposalCount: number;
+  createdHeight: number;
+}
+
+export interface MultisigProposalInfo {
+  id: number;
+  walletId: number;
+  proposer: string;
+  to: string;
+  value: string;
+  approvals: string[];
+  // Owners that have not approved yet
+  awaiting: string[];
+  // Awaiting owners held by this wallet
+  localSigners: string[];
+  approvalsNeeded: number;
+  ready: boolean;
+  createdHeight: number;
+  executedHeight: number | null;
+}
+
+export const multisigService = {
+  list: () => safeInvoke<MultisigAccount[]>('multisig_list'),
+  create: (draft: MultisigDraft, password?: string) =>
+    safeInvoke<MultisigAccount>('multisig_create', { draft, password: password || null }),
+  import: (name: string, walletId: number) =>
+    safeInvoke<MultisigAccount>('multisig_import', { name, walletId }),
+  remove: (id: string) => safeInvoke<void>('multisig_remove', { id }),
+  getWallet: (walletId: number) =>
+    safeInvoke<MultisigWalletInfo | null>('multisig_get_wallet', { walletId }),
+  // Newest first
+  listProposals: (walletId: number) =>
+    safeInvoke<MultisigProposalInfo[]>('multisig_list_proposals', { walletId }),
+  pendingProposals: () => safeInvoke<MultisigProposalInfo[]>('multisig_pending_proposals'),
+  // The write calls return the transaction hash
+  deposit: (from: string, walletId: number, amount: string, password?: string) =>
+    safeInvoke<string>('multisig_deposit', {
+      from,
+      walletId,
+      amount,
+      password: password || null,
+    }),
+  propose: (from: string, walletId: number, to: string, amount: string, password?: string) =>
+    safeInvoke<string>('multisig_propose', {
+      from,
+      walletId,
+      to,
+      amount,
+      password: password || null,
+    }),
+  approve: (from: string, proposalId: number, password?: string) =>
+    safeInvoke<string>('multisig_approve', { from, proposalId, password: password || null }),
+  revoke: (from: string, proposalId: number, password?: string) =>
+    safeInvoke<string>('multisig_revoke', { from, proposalId, password: password || null }),
+  execute: (from: string, proposalId: number, password?: string) =>
+    safeInvoke<string>('multisig_execute', { from, proposalId, password: password || null }),
 };
 
 // DAG Management
@@ -767,12 +1572,59 @@ export const dagService = {
   
   getCurrentTips: () =>
     safeInvoke<TipInfo[]>('get_current_tips'),
+
+  getStatsSeries: async (from?: number, to?: number, bucketSecs?: number) => {
+    const raw = await safeInvoke<any[]>('get_dag_stats_series', { from, to, bucketSecs });
+    return (raw || []).map((b: any) => ({
+      start: Number(b.start),
+      end: Number(b.end),
+      blocks: Number(b.blocks ?? 0),
+      blocksPerSec: Number(b.blocks_per_sec ?? 0),
+      avgTips: Number(b.avg_tips ?? 0),
+      maxTips: Number(b.max_tips ?? 0),
+      avgMergeParents: Number(b.avg_merge_parents ?? 0),
+      maxMergeParents: Number(b.max_merge_parents ?? 0),
+      avgAnticoneSize: Number(b.avg_anticone_size ?? 0),
+      maxAnticoneSize: Number(b.max_anticone_size ?? 0),
+      blueMergeParents: Number(b.blue_merge_parents ?? 0),
+      redMergeParents: Number(b.red_merge_parents ?? 0),
+      blueRatio: Number(b.blue_ratio ?? 1),
+      maxBlueScore: Number(b.max_blue_score ?? 0),
+    })) as DAGStatsBucket[];
+  },
+
+  // Hourly snapshots, oldest first; defaults to the last week
+  getStatsHistory: async (from?: number, to?: number) => {
+    const raw = await safeInvoke<any[]>('get_stats_history', { from, to });
+    return (raw || []).map((s: any) => ({
+      timestamp: Number(s.timestamp),
+      height: Number(s.height ?? 0),
+      tips: Number(s.tips ?? 0),
+      blocks: Number(s.blocks ?? 0),
+      transactions: Number(s.transactions ?? 0),
+      txPerSec: Number(s.tx_per_sec ?? 0),
+      peerCount: Number(s.peer_count ?? 0),
+      mempoolPending: Number(s.mempool_pending ?? 0),
+    })) as ChainStatsSnapshot[];
+  },
   
   calculateBlueScore: (blockHash: string) =>
     safeInvoke<number>('calculate_blue_score', { blockHash }),
   
   getBlockPath: (blockHash: string) =>
-    safeInvoke<string[]>('get_block_path', { blockHash })
+    safeInvoke<string[]>('get_block_path', { blockHash }),
+
+  // Block hash, transaction hash, height, proposer public key or address
+  search: async (query: string): Promise<DAGSearchResult> => {
+    const raw = await safeInvoke<any>('search_dag', { query });
+    return {
+      query: String(raw?.query ?? query),
+      kind: raw?.kind ?? null,
+      center: raw?.center ?? null,
+      matches: (raw?.matches ?? []).map((h: any) => String(h)),
+      graph: mapDAGDataFromNative(raw?.graph ?? {}),
+    };
+  }
 };
 
 // Model Management
@@ -846,6 +1698,78 @@ export interface IpfsConfig {
   external_gateways: string[];
   enable_pubsub: boolean;
   bootstrap_peers: string[];
+  quotas: PinQuotas;
+  remote_pinning?: RemotePinningConfig | null;
+}
+
+/** A service implementing the IPFS Pinning Service API */
+export interface RemotePinningConfig {
+  endpoint: string;
+  access_token: string;
+}
+
+export type ContentCategory = 'models' | 'datasets' | 'artifacts' | 'other';
+
+/** Storage quotas in bytes per category (null = unlimited) */
+export interface PinQuotas {
+  models?: number | null;
+  datasets?: number | null;
+  artifacts?: number | null;
+}
+
+export interface CategoryUsage {
+  category: ContentCategory;
+  pins: number;
+  bytes: number;
+  protected_bytes: number;
+  quota?: number | null;
+}
+
+export interface StorageUsage {
+  categories: CategoryUsage[];
+  tracked_bytes: number;
+  repo_size?: number;
+  storage_max?: number;
+}
+
+export interface GcReport {
+  unpinned: string[];
+  unpinned_bytes: number;
+  stale_records: number;
+  repo_size_before?: number;
+  repo_size_after?: number;
+}
+
+export type LicenseSource = 'purchase' | 'deployment';
+
+/** A purchased or deployed marketplace model whose weights are kept pinned */
+export interface ModelLicense {
+  model_id: string;
+  source: LicenseSource;
+  weight_cids: string[];
+  /** Unix seconds; omitted for a license that does not lapse */
+  expires_at?: number | null;
+  /** Also pin to the configured remote pinning service */
+  remote?: boolean;
+  /** Local model whose cached inference results are dropped when the license lapses */
+  local_model?: string | null;
+}
+
+export type PinHealth = 'pending' | 'pinned' | 'missing' | 'failed';
+
+export interface CidHealth {
+  cid: string;
+  local: PinHealth;
+  remote?: PinHealth | null;
+  checked_at: number;
+  error?: string | null;
+}
+
+export interface LicenseRecord {
+  license: ModelLicense;
+  registered_at: number;
+  adopted: string[];
+  health: CidHealth[];
 }
 
 export interface IpfsAddResult {
@@ -900,10 +1824,23 @@ export const ipfsService = {
   },
 
   // Pinning
-  pin: (cid: string) => safeInvoke<void>('ipfs_pin', { cid }),
+  pin: (cid: string, category?: ContentCategory, isProtected?: boolean) =>
+    safeInvoke<void>('ipfs_pin', { cid, category, protected: isProtected }),
   unpin: (cid: string) => safeInvoke<void>('ipfs_unpin', { cid }),
   listPins: () => safeInvoke<string[]>('ipfs_list_pins'),
 
+  // Storage quotas
+  getUsage: () => safeInvoke<StorageUsage>('ipfs_get_usage'),
+  runGc: () => safeInvoke<GcReport>('ipfs_gc_run'),
+
+  // Marketplace licenses
+  registerLicense: (license: ModelLicense) =>
+    safeInvoke<LicenseRecord>('marketplace_register_license', { license }),
+  releaseLicense: (modelId: string) =>
+    safeInvoke<void>('marketplace_release_license', { modelId }),
+  listLicenses: () => safeInvoke<LicenseRecord[]>('marketplace_list_licenses'),
+  checkLicenseHealth: () => safeInvoke<LicenseRecord[]>('ipfs_check_license_health'),
+
   // Network
   getPeers: () => safeInvoke<string[]>('ipfs_get_peers'),
 };
@@ -1009,12 +1946,77 @@ export interface LocalModelInfo {
   loaded: boolean;
 }
 
+export type ConversionStatus = 'converting' | 'completed' | 'failed';
+
+// Progress of a safetensors to GGUF conversion, emitted after every tensor
+export interface ConversionProgress {
+  model_id: string;
+  quantization: GgufQuantization;
+  tensor?: string;
+  tensors_done: number;
+  tensors_total: number;
+  status: ConversionStatus;
+  output_path?: string;
+  error?: string;
+}
+
 export interface RecommendedModel {
   model_id: string;
   name: string;
   description: string;
 }
 
+export interface HFDatasetInfo {
+  id: string;
+  author?: string;
+  sha?: string;
+  lastModified?: string;
+  private?: boolean;
+  disabled?: boolean;
+  gated?: boolean | string;
+  description?: string;
+  tags?: string[];
+  downloads?: number;
+  likes?: number;
+  cardData?: any;
+  siblings?: HFModelFile[];
+}
+
+export interface DatasetSearchParams {
+  search?: string;
+  author?: string;
+  filter?: string;
+  sort?: string;
+  direction?: string;
+  limit?: number;
+}
+
+export interface DatasetDownloadParams {
+  dataset_id: string;
+  // JSON Lines file to stream; the rows API is used when omitted
+  file?: string;
+  config?: string;
+  split?: string;
+  format: 'Jsonl' | 'Alpaca' | 'ShareGPT';
+  max_rows?: number;
+}
+
+export type DatasetSchema =
+  | 'Instruction'
+  | 'PromptCompletion'
+  | 'QuestionAnswer'
+  | 'Conversations'
+  | 'Text';
+
+export interface DatasetDownloadResult {
+  dataset_id: string;
+  path: string;
+  format: DatasetFormat;
+  source_schema?: DatasetSchema;
+  rows_converted: number;
+  rows_skipped: number;
+}
+
 // HuggingFace Management
 export const huggingFaceService = {
   // Authentication - Enhanced with PKCE support
@@ -1073,6 +2075,16 @@ export const huggingFaceService = {
   autoSelectModel: () => safeInvoke<LocalModelInfo | null>('hf_auto_select_model'),
   deleteLocalModel: (path: string) => safeInvoke<void>('hf_delete_local_model', { path }),
 
+  // Convert a downloaded safetensors model to a quantized GGUF
+  convertToGguf: (modelId: string, quantization: GgufQuantization = 'Q4_K_M') =>
+    safeInvoke<LocalModelInfo>('hf_convert_to_gguf', { modelId, quantization }),
+
+  // Datasets - converted to LoRA training formats on download
+  searchDatasets: (params: DatasetSearchParams) =>
+    safeInvoke<HFDatasetInfo[]>('hf_search_datasets', { params }),
+  downloadDataset: (params: DatasetDownloadParams) =>
+    safeInvoke<DatasetDownloadResult>('hf_download_dataset', { params }),
+
   // Listen to download progress events
   onDownloadProgress: (callback: (progress: DownloadProgress) => void) => {
     if (!isTauri() || !listen) {
@@ -1082,6 +2094,16 @@ export const huggingFaceService = {
       callback(event.payload as DownloadProgress);
     });
   },
+
+  // Listen to GGUF conversion progress events
+  onConversionProgress: (callback: (progress: ConversionProgress) => void) => {
+    if (!isTauri() || !listen) {
+      return Promise.resolve(() => {});
+    }
+    return listen('gguf-conversion-progress', (event: any) => {
+      callback(event.payload as ConversionProgress);
+    });
+  },
 };
 
 // ==========================================
@@ -1143,6 +2165,41 @@ export interface LoraTrainingConfig {
   num_threads: number;
   use_gpu: boolean;
   n_gpu_layers: number;
+  eval_suite?: EvalSuiteConfig;
+}
+
+// Benchmark suite run after LoRA training
+export interface EvalSuiteConfig {
+  held_out_path?: string;
+  multiple_choice_path?: string;
+  max_questions: number;
+  latency_prompt: string;
+  latency_tokens: number;
+  latency_runs: number;
+  context_size: number;
+}
+
+export interface EvalMetrics {
+  held_out_loss?: number;
+  perplexity?: number;
+  accuracy?: number;
+  questions: number;
+  latency_ms?: number;
+}
+
+// Base vs adapted model benchmark results
+export interface EvalScorecard {
+  base: EvalMetrics;
+  adapted: EvalMetrics;
+  evaluated_at: number;
+}
+
+// Payload of the 'lora-job-completed' event
+export interface LoraJobCompleted {
+  job_id: string;
+  adapter_id?: string;
+  scorecard?: EvalScorecard;
+  eval_error?: string;
 }
 
 // Training metrics point for progress tracking
@@ -1193,6 +2250,7 @@ export interface LoraAdapterInfo {
   training_job_id?: string;
   description?: string;
   tags: string[];
+  eval?: EvalScorecard;
 }
 
 // Dataset validation result
@@ -1204,6 +2262,46 @@ export interface DatasetValidation {
   estimated_tokens: number;
 }
 
+// Dataset preprocessing options
+export interface PreprocessConfig {
+  dedup: boolean;
+  min_tokens: number;
+  max_tokens?: number;
+  eval_fraction: number;
+  seed: number;
+  tokenizer_samples: number;
+  preview_examples: number;
+  dry_run: boolean;
+}
+
+export interface TokenStats {
+  total: number;
+  min: number;
+  max: number;
+  mean: number;
+  p50: number;
+  p95: number;
+}
+
+// Preprocessing report shown before a LoRA job starts
+export interface DatasetReport {
+  total_examples: number;
+  invalid: number;
+  duplicates: number;
+  too_short: number;
+  too_long: number;
+  train_examples: number;
+  eval_examples: number;
+  train_path?: string;
+  eval_path?: string;
+  tokens: TokenStats;
+  tokenizer_model?: string;
+  chars_per_token: number;
+  preview: { line: number; text: string; tokens: number }[];
+  warnings: string[];
+  errors: string[];
+}
+
 // LoRA training preset
 export interface LoraPreset {
   name: string;
@@ -1213,6 +2311,60 @@ export interface LoraPreset {
   recommended_vram_gb: number;
 }
 
+// Quantization for merged GGUF exports
+export type GgufQuantization = 'F16' | 'Q8_0' | 'Q6_K' | 'Q5_K_M' | 'Q4_K_M' | 'Q4_0';
+
+// Local model registered after merging an adapter into its base model
+export interface MergedModelInfo {
+  id: string;
+  name: string;
+  description: string;
+  model_type: string;
+  version: string;
+  size_mb: number;
+  parameters: number;
+  architecture: string;
+  owner: string;
+  created_at: number;
+  updated_at: number;
+  hash: string;
+  metadata: Record<string, string>;
+}
+
+// Benchmark settings; an empty prompt list runs the standard suite
+export interface BenchmarkConfig {
+  prompts?: string[];
+  max_tokens?: number;
+  runs?: number;
+  context_size?: number;
+  gpu_layers?: number | null;
+  threads?: number | null;
+}
+
+// Memory llama.cpp allocated on one device during a benchmark
+export interface DeviceMemory {
+  device_id: string;
+  device_name: string | null;
+  bytes: number;
+}
+
+// Saved benchmark of a local model, optionally with a LoRA adapter
+export interface BenchmarkResult {
+  id: string;
+  model_id: string;
+  model_path: string;
+  quantization: string | null;
+  adapter_id: string | null;
+  config: Required<BenchmarkConfig>;
+  tokens_per_sec: number;
+  prompt_tokens_per_sec: number;
+  time_to_first_token_ms: number;
+  load_ms: number;
+  peak_memory: DeviceMemory[];
+  samples: number;
+  created_at: number;
+}
+
 // LoRA Training Service
 export const loraTrainingService = {
   // Job Management
@@ -1255,6 +2407,20 @@ export const loraTrainingService = {
   deleteAdapter: (adapter_id: string) =>
     safeInvoke<void>('delete_lora_adapter', { adapter_id }),
 
+  // Merge an adapter into its base model and export a standalone GGUF
+  mergeAdapter: (
+    adapter_id: string,
+    quantization: GgufQuantization = 'Q4_K_M',
+    output_name?: string,
+    base_model_path?: string
+  ) =>
+    safeInvoke<MergedModelInfo>('merge_lora_adapter', {
+      adapter_id,
+      base_model_path,
+      output_name,
+      quantization,
+    }),
+
   // Inference with LoRA
   runInferenceWithLora: (
     model_path: string,
@@ -1271,9 +2437,30 @@ export const loraTrainingService = {
       temperature,
     }),
 
+  // Switch the agent's local model to an adapter without reloading the base weights
+  applyAdapter: (modelId: string, adapterId?: string, scale?: number) =>
+    safeInvoke<string | null>('apply_adapter', { modelId, adapterId, scale }),
+
+  // Benchmark a model (or model + adapter) and compare saved results
+  benchmarkModel: (modelId: string, adapterId?: string, config?: BenchmarkConfig) =>
+    safeInvoke<BenchmarkResult>('benchmark_model', { modelId, adapterId, config }),
+
+  getBenchmarks: (modelId?: string) =>
+    safeInvoke<BenchmarkResult[]>('get_model_benchmarks', { modelId }),
+
+  deleteBenchmark: (id: string) =>
+    safeInvoke<void>('delete_model_benchmark', { id }),
+
   // Dataset Validation
   validateDataset: (path: string, format: DatasetFormat) =>
     safeInvoke<DatasetValidation>('validate_dataset', { path, format }),
+  preprocessDataset: (
+    path: string,
+    format: DatasetFormat,
+    baseModelPath?: string,
+    config?: Partial<PreprocessConfig>
+  ) =>
+    safeInvoke<DatasetReport>('preprocess_dataset', { path, format, baseModelPath, config }),
 
   // Presets
   getPresets: () => safeInvoke<LoraPreset[]>('get_lora_presets'),
@@ -1343,6 +2530,28 @@ export interface AgentMessageResponse {
   pending_approval: boolean;
 }
 
+export type AgentProvider = 'openai' | 'anthropic' | 'gemini' | 'xai' | 'local';
+
+/** Provider routing choices for a session or a single message */
+export interface RouteOverride {
+  pinned?: AgentProvider | null;
+  excluded?: AgentProvider[];
+  strategy?: 'latency' | 'ordered' | null;
+}
+
+export interface ProviderHealth {
+  provider: AgentProvider;
+  backend: string;
+  requests: number;
+  failures: number;
+  error_rate: number;
+  latency_ms: number | null;
+  healthy: boolean;
+  last_error: string | null;
+  /** Only tried after the preferred providers, unless offline */
+  fallback: boolean;
+}
+
 export interface PendingTool {
   id: string;
   tool_name: string;
@@ -1384,6 +2593,137 @@ export interface AgentStatusResponse {
   streaming_enabled?: boolean;
 }
 
+export type PromptKind = 'system' | 'persona';
+
+export interface PromptVariable {
+  name: string;
+  description?: string | null;
+  default?: string | null;
+}
+
+export interface PromptVersion {
+  version: number;
+  content: string;
+  variables: PromptVariable[];
+  created_at: number;
+  note?: string | null;
+}
+
+export interface PromptEntry {
+  name: string;
+  description: string;
+  kind: PromptKind;
+  versions: PromptVersion[];
+  builtin: boolean;
+}
+
+export interface PromptSummary {
+  name: string;
+  description: string;
+  kind: PromptKind;
+  latest_version: number;
+  version_count: number;
+  variables: PromptVariable[];
+  builtin: boolean;
+  is_default: boolean;
+  updated_at: number;
+}
+
+export interface PromptDraft {
+  name: string;
+  content: string;
+  description?: string;
+  kind?: PromptKind;
+  variables?: PromptVariable[];
+  note?: string;
+}
+
+export interface PromptAssignment {
+  name: string;
+  version?: number | null;
+  variables: Record<string, string>;
+}
+
+export interface PromptImportSummary {
+  added: number;
+  updated: number;
+  unchanged: number;
+}
+
+export type AutoModePolicy = 'read_only' | 'queue_approvals';
+
+export interface ScheduledTaskRun {
+  started_at: number;
+  finished_at: number;
+  success: boolean;
+  response: string;
+  tools_used: string[];
+  pending_approvals: number;
+  budget_exhausted: boolean;
+}
+
+export interface ScheduledTask {
+  id: string;
+  name: string;
+  prompt: string;
+  schedule: string;
+  enabled: boolean;
+  tool_budget: number;
+  policy: AutoModePolicy;
+  allowed_tools: string[];
+  session_id: string | null;
+  created_at: number;
+  next_run: number | null;
+  last_run: ScheduledTaskRun | null;
+}
+
+export interface ScheduledTaskDraft {
+  name: string;
+  prompt: string;
+  // Cron expression (minute hour day-of-month month day-of-week) or @daily etc.
+  schedule: string;
+  enabled?: boolean;
+  tool_budget?: number;
+  policy?: AutoModePolicy;
+  allowed_tools?: string[];
+}
+
+// Payload of the 'agent-task-completed' event
+export interface ScheduledTaskCompleted {
+  task_id: string;
+  task_name: string;
+  session_id: string | null;
+  run: ScheduledTaskRun;
+}
+
+// Directory the agent's file tools may access
+export type FileAccess = 'read' | 'read_write';
+
+export interface DirectoryGrant {
+  path: string;
+  access: FileAccess;
+  auto_approve: boolean;
+  granted_at: number;
+}
+
+export interface FileAuditEntry {
+  timestamp: number;
+  operation: 'read' | 'list' | 'write';
+  path: string;
+  outcome: 'denied' | 'proposed' | 'completed' | 'failed';
+  detail: string | null;
+}
+
+/** A summary of past conversation recalled by `agent_search_memory` */
+export interface MemoryMatch {
+  id: string;
+  session_id: string;
+  summary: string;
+  created_at: number;
+  /** Cosine similarity to the query, from -1 to 1 */
+  score: number;
+}
+
 // Agent Service
 export const agentService = {
   // Session management
@@ -1395,8 +2735,8 @@ export const agentService = {
     safeInvoke<boolean>('agent_delete_session', { sessionId }),
 
   // Messages
-  sendMessage: (sessionId: string, message: string) =>
-    safeInvoke<AgentMessageResponse>('agent_send_message', { sessionId, message }),
+  sendMessage: (sessionId: string, message: string, route?: RouteOverride) =>
+    safeInvoke<AgentMessageResponse>('agent_send_message', { sessionId, message, route }),
   getMessages: (sessionId: string) =>
     safeInvoke<AgentMessage[]>('agent_get_messages', { sessionId }),
   clearHistory: (sessionId: string) =>
@@ -1430,6 +2770,55 @@ export const agentService = {
     safeInvoke<void>('agent_set_api_key', { provider, apiKey }),
   setAutoMode: () => safeInvoke<void>('agent_set_auto_mode'),
 
+  // Prompt library
+  listPrompts: () => safeInvoke<PromptSummary[]>('agent_list_prompts'),
+  getPrompt: (name: string) => safeInvoke<PromptEntry>('agent_get_prompt', { name }),
+  savePrompt: (prompt: PromptDraft) => safeInvoke<number>('agent_save_prompt', { prompt }),
+  deletePrompt: (name: string) => safeInvoke<boolean>('agent_delete_prompt', { name }),
+  setDefaultPrompt: (name: string | null) =>
+    safeInvoke<void>('agent_set_default_prompt', { name }),
+  exportPrompts: (names?: string[]) => safeInvoke<string>('agent_export_prompts', { names }),
+  importPrompts: (bundle: string) =>
+    safeInvoke<PromptImportSummary>('agent_import_prompts', { bundle }),
+  setSessionPrompt: (
+    sessionId: string,
+    name: string | null,
+    version?: number,
+    variables?: Record<string, string>
+  ) =>
+    safeInvoke<string | null>('agent_set_session_prompt', { sessionId, name, version, variables }),
+  getSessionPrompt: (sessionId: string) =>
+    safeInvoke<PromptAssignment | null>('agent_get_session_prompt', { sessionId }),
+
+  // Provider routing
+  setSessionRoute: (sessionId: string, route: RouteOverride) =>
+    safeInvoke<AgentProvider[]>('agent_set_session_route', { sessionId, route }),
+  getSessionRoute: (sessionId: string) =>
+    safeInvoke<RouteOverride>('agent_get_session_route', { sessionId }),
+  getProviderHealth: () => safeInvoke<ProviderHealth[]>('agent_get_provider_health'),
+  checkProviderHealth: () => safeInvoke<ProviderHealth[]>('agent_check_provider_health'),
+
+  // Scheduled tasks
+  listTasks: () => safeInvoke<ScheduledTask[]>('agent_list_tasks'),
+  createTask: (task: ScheduledTaskDraft) =>
+    safeInvoke<ScheduledTask>('agent_create_task', { task }),
+  updateTask: (taskId: string, task: ScheduledTaskDraft) =>
+    safeInvoke<ScheduledTask>('agent_update_task', { taskId, task }),
+  setTaskEnabled: (taskId: string, enabled: boolean) =>
+    safeInvoke<ScheduledTask>('agent_set_task_enabled', { taskId, enabled }),
+  deleteTask: (taskId: string) => safeInvoke<boolean>('agent_delete_task', { taskId }),
+  runTaskNow: (taskId: string) => safeInvoke<ScheduledTaskRun>('agent_run_task_now', { taskId }),
+  // File access for the read_file / list_directory / write_file tools
+  listFileGrants: () => safeInvoke<DirectoryGrant[]>('agent_list_file_grants'),
+  grantDirectory: (path: string, access: FileAccess, autoApprove = false) =>
+    safeInvoke<DirectoryGrant>('agent_grant_directory', { path, access, autoApprove }),
+  revokeDirectory: (path: string) => safeInvoke<boolean>('agent_revoke_directory', { path }),
+  getFileAuditLog: (limit?: number) =>
+    safeInvoke<FileAuditEntry[]>('agent_file_audit_log', { limit: limit ?? null }),
+  // Long-term memory of summarized conversations
+  searchMemory: (query: string, limit?: number) =>
+    safeInvoke<MemoryMatch[]>('agent_search_memory', { query, limit: limit ?? null }),
+
   // Status
   isReady: () => safeInvoke<boolean>('agent_is_ready'),
   getStatus: () => safeInvoke<AgentStatusResponse>('agent_get_status'),
@@ -1470,4 +2859,13 @@ export const agentService = {
       callback(event.payload);
     });
   },
+
+  onTaskCompleted: (callback: (event: ScheduledTaskCompleted) => void) => {
+    if (!isTauri() || !listen) {
+      return Promise.resolve(() => {});
+    }
+    return listen('agent-task-completed', (event: any) => {
+      callback(event.payload);
+    });
+  },
 };
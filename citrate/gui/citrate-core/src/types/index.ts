@@ -11,6 +11,25 @@ export interface NodeStatus {
   blueScore?: number;
   lastBlockHash?: string | null;
   lastBlockTimestamp?: number | null; // ms
+  upgradeAvailable?: string | null; // newer version run by most peers
+  externalRpc?: string | null; // RPC URL of the external node the GUI is attached to
+}
+
+// Devnet block production mode
+export interface MiningMode {
+  blockInterval: number; // seconds between blocks; 0 = interval mining off
+  automine: boolean; // mine a block as soon as a transaction arrives
+}
+
+// What a prune would remove under the node's retention settings (dry run)
+export interface PrunePreview {
+  blocksPruned: number;
+  statesPruned: number;
+  transactionsPruned: number;
+  receiptsPruned: number;
+  bytesByColumnFamily: Record<string, number>; // key + value bytes, before compaction
+  currentHeight: number;
+  dryRun: boolean;
 }
 
 // Network / Peers
@@ -21,6 +40,25 @@ export interface PeerInfoSummary {
   state: 'connecting' | 'handshaking' | 'connected' | 'disconnecting' | 'disconnected';
   score: number;
   lastSeenSecs: number;
+  clientVersion?: string | null;
+}
+
+export interface PeerReputationEvent {
+  reason: string;
+  delta: number;
+  at: number;
+}
+
+export interface PeerReputation {
+  ip: string;
+  peerId: string | null;
+  score: number;
+  connected: boolean;
+  banned: boolean;
+  bannedUntil: number | null;
+  banReason: string | null;
+  banCount: number;
+  events: PeerReputationEvent[];
 }
 
 export interface NodeConfig {
@@ -73,10 +111,11 @@ export interface TxActivity {
   to?: string;
   value: string;
   nonce: number;
-  status: 'pending' | 'confirmed';
+  status: 'pending' | 'confirmed' | 'failed' | 'reorged';
   blockHash?: string;
   blockHeight?: number;
   timestamp?: number;
+  internal?: boolean;
 }
 
 export interface TransactionRequest {
@@ -137,6 +176,48 @@ export interface DAGStatistics {
   maxHeight: number;
 }
 
+export interface DAGStatsBucket {
+  start: number;
+  end: number;
+  blocks: number;
+  blocksPerSec: number;
+  avgTips: number;
+  maxTips: number;
+  avgMergeParents: number;
+  maxMergeParents: number;
+  avgAnticoneSize: number;
+  maxAnticoneSize: number;
+  blueMergeParents: number;
+  redMergeParents: number;
+  blueRatio: number;
+  maxBlueScore: number;
+}
+
+// Hourly chain statistics; throughput covers the hour before `timestamp`
+export interface ChainStatsSnapshot {
+  timestamp: number;
+  height: number;
+  tips: number;
+  blocks: number;
+  transactions: number;
+  txPerSec: number;
+  peerCount: number;
+  mempoolPending: number;
+}
+
+export type DAGSearchKind = 'Block' | 'Height' | 'Transaction' | 'Proposer' | 'Address';
+
+export interface DAGSearchResult {
+  query: string;
+  // null when nothing matched
+  kind: DAGSearchKind | null;
+  // Block hash the visualizer should center on
+  center: string | null;
+  matches: string[];
+  // Matching blocks with their parents and children
+  graph: DAGData;
+}
+
 export interface BlockDetails {
   hash: string;
   height: number;
@@ -129,8 +129,35 @@ export interface WindowEvent {
   windowId: string;
   timestamp: number;
   data?: unknown;
+  /** How to announce the window, when it is still open */
+  accessible?: AccessibleWindow | null;
 }
 
+/** An open window as presented to assistive technology */
+export interface AccessibleWindow {
+  id: string;
+  window_type: WindowType;
+  /** Title to announce, numbered when several windows share a title */
+  accessible_title: string;
+  /** ARIA role of the window's root element */
+  role: string;
+  description: string;
+  /** 1-based place in the focus cycle */
+  position: number;
+  total: number;
+  is_focused: boolean;
+}
+
+/** Display preferences applied to all windows */
+export interface AccessibilityPreferences {
+  high_contrast: boolean;
+  /** Webview zoom factor, 1.0 is 100% */
+  zoom: number;
+  reduced_motion: boolean;
+}
+
+export type FocusDirection = 'next' | 'previous';
+
 /** Window manager state */
 export interface WindowManagerState {
   windows: WindowState[];
@@ -163,4 +190,16 @@ export interface WindowContextType {
 
   /** Check if a window type is open */
   hasOpenWindow: (type: WindowType) => boolean;
+
+  /** Move keyboard focus to the next or previous window */
+  cycleFocus: (direction: FocusDirection) => Promise<AccessibleWindow | null>;
+
+  /** Open windows in focus order, with titles and roles for screen readers */
+  listAccessibleWindows: () => Promise<AccessibleWindow[]>;
+
+  /** Current display preferences */
+  accessibility: AccessibilityPreferences;
+
+  /** Persist display preferences and apply them to all windows */
+  setAccessibility: (preferences: AccessibilityPreferences) => Promise<void>;
 }
@@ -0,0 +1,71 @@
+/**
+ * Amount parsing and formatting for wei / gwei / SALT
+ *
+ * Mirrors `citrate_execution::units` on the Rust side. Amounts are handled
+ * as decimal strings and bigints, never floats, so large values keep full
+ * precision. Output always uses '.' and no digit grouping.
+ */
+
+export const GWEI_DECIMALS = 9;
+export const SALT_DECIMALS = 18;
+
+export class AmountError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = 'AmountError';
+  }
+}
+
+/**
+ * Parse a decimal amount into wei, e.g. parseUnits('1.5') for 1.5 SALT.
+ * Throws AmountError on empty, negative, scientific or locale-formatted input.
+ */
+export function parseUnits(amount: string, decimals: number = SALT_DECIMALS): bigint {
+  const s = amount.trim().replace(/^\+/, '');
+  if (s === '' || s === '.') {
+    throw new AmountError('Amount is required');
+  }
+  if (s.startsWith('-')) {
+    throw new AmountError('Negative amounts are not allowed');
+  }
+  if (/^[\d.]*[eE][+-]?\d*$/.test(s)) {
+    throw new AmountError('Scientific notation is not supported; write the amount out in full');
+  }
+  if (/[,_' ]/.test(s) || s.split('.').length > 2) {
+    throw new AmountError("Use '.' as the decimal separator and no digit grouping");
+  }
+  if (!/^\d*\.?\d*$/.test(s)) {
+    throw new AmountError('Amount must be a valid number');
+  }
+
+  const [whole, rawFraction = ''] = s.split('.');
+  const fraction = rawFraction.replace(/0+$/, '');
+  if (fraction.length > decimals) {
+    throw new AmountError(`Too many decimal places (max ${decimals})`);
+  }
+  return BigInt((whole || '0') + fraction.padEnd(decimals, '0'));
+}
+
+/**
+ * Format a wei amount, without trailing zeros. With `maxDecimals` the
+ * fraction is truncated rather than rounded so balances are never overstated.
+ */
+export function formatUnits(
+  value: bigint | string,
+  decimals: number = SALT_DECIMALS,
+  maxDecimals?: number
+): string {
+  const digits = BigInt(value).toString();
+  if (decimals === 0) {
+    return digits;
+  }
+
+  const padded = digits.padStart(decimals + 1, '0');
+  const whole = padded.slice(0, -decimals);
+  let fraction = padded.slice(-decimals);
+  if (maxDecimals !== undefined) {
+    fraction = fraction.slice(0, maxDecimals);
+  }
+  fraction = fraction.replace(/0+$/, '');
+  return fraction ? `${whole}.${fraction}` : whole;
+}
@@ -10,14 +10,32 @@
  * - Network bootnodes (multiaddr format)
  */
 
+import { keccak_256 } from '@noble/hashes/sha3';
+import { bytesToHex, utf8ToBytes } from '@noble/hashes/utils';
+import { parseUnits } from './units';
+
 export interface ValidationResult {
   isValid: boolean;
   error?: string;
 }
 
+/**
+ * Format an address with its EIP-55 mixed-case checksum
+ */
+export function toChecksumAddress(address: string): string {
+  const lower = address.trim().replace(/^0x/i, '').toLowerCase();
+  const hash = bytesToHex(keccak_256(utf8ToBytes(lower)));
+  let out = '0x';
+  for (let i = 0; i < lower.length; i++) {
+    out += parseInt(hash[i], 16) >= 8 ? lower[i].toUpperCase() : lower[i];
+  }
+  return out;
+}
+
 /**
  * Validate Ethereum-compatible address (20 bytes hex)
- * Accepts with or without '0x' prefix
+ * Accepts with or without '0x' prefix. Mixed-case addresses must match
+ * their EIP-55 checksum; all-lowercase or all-uppercase ones carry none.
  */
 export function validateAddress(address: string): ValidationResult {
   if (!address || address.trim() === '') {
@@ -42,8 +60,13 @@ export function validateAddress(address: string): ValidationResult {
     };
   }
 
-  // Optional: Add checksum validation here if needed
-  // For now, basic format validation is sufficient
+  const mixedCase = /[a-f]/.test(addr) && /[A-F]/.test(addr);
+  if (mixedCase && toChecksumAddress(addr).slice(2) !== addr) {
+    return {
+      isValid: false,
+      error: 'Address checksum is invalid; check for typos'
+    };
+  }
 
   return { isValid: true };
 }
@@ -64,23 +87,19 @@ export function validateAmount(
     return { isValid: false, error: 'Amount is required' };
   }
 
-  const num = parseFloat(amount);
-
-  if (isNaN(num)) {
-    return { isValid: false, error: 'Amount must be a valid number' };
+  let wei: bigint;
+  try {
+    wei = parseUnits(amount);
+  } catch (err) {
+    return { isValid: false, error: (err as Error).message };
   }
 
-  if (num <= 0) {
+  if (wei === 0n) {
     return { isValid: false, error: 'Amount must be greater than zero' };
   }
 
-  if (!isFinite(num)) {
-    return { isValid: false, error: 'Amount must be a finite number' };
-  }
-
   // Check against maximum supply
-  const maxSupplyNum = parseFloat(maxSupply);
-  if (num > maxSupplyNum) {
+  if (wei > parseUnits(maxSupply)) {
     return {
       isValid: false,
       error: `Amount exceeds maximum supply (${maxSupply})`
@@ -89,24 +108,18 @@ export function validateAmount(
 
   // Check against user balance if provided
   if (balance) {
-    const balanceNum = parseFloat(balance);
-    if (!isNaN(balanceNum) && num > balanceNum) {
-      return {
-        isValid: false,
-        error: `Insufficient balance (available: ${balance})`
-      };
+    try {
+      if (wei > parseUnits(balance)) {
+        return {
+          isValid: false,
+          error: `Insufficient balance (available: ${balance})`
+        };
+      }
+    } catch {
+      // Unparseable balance; skip the check
     }
   }
 
-  // Check for reasonable decimal places (max 18 for EVM compatibility)
-  const decimalPlaces = (amount.split('.')[1] || '').length;
-  if (decimalPlaces > 18) {
-    return {
-      isValid: false,
-      error: 'Amount has too many decimal places (maximum: 18)'
-    };
-  }
-
   return { isValid: true };
 }
 
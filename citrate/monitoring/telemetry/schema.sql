@@ -0,0 +1,61 @@
+-- Citrate telemetry aggregation schema (PostgreSQL)
+--
+-- The collector validates incoming reports against report.schema.json and
+-- upserts them into telemetry_reports. A node reporting again within the
+-- same hour replaces its earlier row, so each install counts once per hour.
+
+CREATE TABLE IF NOT EXISTS telemetry_reports (
+    install_id     CHAR(32)     NOT NULL,
+    reported_at    TIMESTAMPTZ  NOT NULL,
+    schema_version INTEGER      NOT NULL,
+    version        VARCHAR(64)  NOT NULL,
+    chain_id       BIGINT       NOT NULL,
+    peer_count     INTEGER      NOT NULL,
+    sync_height    BIGINT       NOT NULL,
+    os             VARCHAR(32)  NOT NULL,
+    arch           VARCHAR(32)  NOT NULL,
+    PRIMARY KEY (install_id, reported_at)
+);
+
+CREATE INDEX IF NOT EXISTS telemetry_reports_chain_time
+    ON telemetry_reports (chain_id, reported_at);
+
+-- Example upsert used by the collector:
+-- INSERT INTO telemetry_reports VALUES ($1, to_timestamp($2), $3, $4, $5, $6, $7, $8, $9)
+-- ON CONFLICT (install_id, reported_at) DO UPDATE SET
+--     version = EXCLUDED.version, peer_count = EXCLUDED.peer_count,
+--     sync_height = EXCLUDED.sync_height, os = EXCLUDED.os, arch = EXCLUDED.arch;
+
+-- Latest report per install over the last day
+CREATE OR REPLACE VIEW telemetry_active_nodes AS
+SELECT DISTINCT ON (install_id) *
+FROM telemetry_reports
+WHERE reported_at > now() - INTERVAL '24 hours'
+ORDER BY install_id, reported_at DESC;
+
+-- Version distribution per chain, used for fork planning
+CREATE OR REPLACE VIEW telemetry_version_distribution AS
+SELECT
+    chain_id,
+    version,
+    COUNT(*) AS nodes,
+    ROUND(100.0 * COUNT(*) / SUM(COUNT(*)) OVER (PARTITION BY chain_id), 2) AS percent
+FROM telemetry_active_nodes
+GROUP BY chain_id, version;
+
+-- Platform distribution per chain
+CREATE OR REPLACE VIEW telemetry_platform_distribution AS
+SELECT chain_id, os, arch, COUNT(*) AS nodes
+FROM telemetry_active_nodes
+GROUP BY chain_id, os, arch;
+
+-- Network health per chain: how far nodes lag the best reported height
+CREATE OR REPLACE VIEW telemetry_network_summary AS
+SELECT
+    chain_id,
+    COUNT(*)                     AS nodes,
+    MAX(sync_height)             AS best_height,
+    percentile_cont(0.5) WITHIN GROUP (ORDER BY sync_height) AS median_height,
+    AVG(peer_count)::NUMERIC(10, 2) AS avg_peers
+FROM telemetry_active_nodes
+GROUP BY chain_id;
@@ -5,8 +5,10 @@ use std::sync::Arc;
 use tracing::{error, info};
 
 use axum::{response::IntoResponse, routing::get, Router};
+use citrate_api::billing::{BillingLedger, BillingPricing};
 use citrate_api::{ApiService, RpcConfig};
 use citrate_execution::{Executor, StateDB};
+use citrate_mcp::safety::{SafetyPipeline, SafetyPolicy};
 use citrate_network::peer::{PeerManager, PeerManagerConfig};
 use citrate_sequencer::mempool::{Mempool, MempoolConfig};
 use citrate_storage::pruning::PruningConfig;
@@ -33,6 +35,22 @@ fn metrics_addr() -> SocketAddr {
         .unwrap_or_else(|| "0.0.0.0:9100".parse().unwrap())
 }
 
+/// Content-safety policy file for the REST API, if one is configured
+fn safety_policy() -> Result<Option<SafetyPolicy>> {
+    match std::env::var_os("CITRATE_SAFETY_POLICY") {
+        Some(path) => Ok(Some(SafetyPolicy::load(&PathBuf::from(path))?)),
+        None => Ok(None),
+    }
+}
+
+/// Token pricing for REST API billing; usage is only recorded when set
+fn billing_pricing() -> Result<Option<BillingPricing>> {
+    match std::env::var_os("CITRATE_BILLING_PRICING") {
+        Some(path) => Ok(Some(BillingPricing::load(&PathBuf::from(path))?)),
+        None => Ok(None),
+    }
+}
+
 async fn metrics_handler() -> impl IntoResponse {
     let encoder = TextEncoder::new();
     let metric_families = gather();
@@ -97,7 +115,7 @@ async fn main() -> Result<()> {
     // API service (WebSocket and REST addresses)
     let ws_addr: SocketAddr = "0.0.0.0:8546".parse().unwrap();
     let rest_addr: SocketAddr = "0.0.0.0:3000".parse().unwrap();
-    let api = ApiService::new(
+    let mut api = ApiService::new(
         rpc_cfg,
         ws_addr,
         rest_addr,
@@ -107,6 +125,15 @@ async fn main() -> Result<()> {
         executor,
         1,
     );
+    if let Some(policy) = safety_policy()? {
+        info!("Applying content-safety policy (enabled={})", policy.enabled);
+        api = api.with_safety(Arc::new(SafetyPipeline::from_policy(policy)?));
+    }
+    if let Some(pricing) = billing_pricing()? {
+        let ledger = BillingLedger::open(data_dir.join("billing").join("usage.jsonl"), pricing)?;
+        info!("Recording API usage in {:?}", ledger.path());
+        api = api.with_billing(Arc::new(ledger));
+    }
 
     // Start
     if let Err(e) = api.start().await {
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use anyhow::Result;
 use async_trait::async_trait;
 use citrate_execution::executor::{AIModelStorage, ModelRegistryAdapter};
-use citrate_execution::{ModelId, ModelState};
+use citrate_execution::{ModelId, ModelState, RoyaltySchedule};
 use citrate_mcp::{
     types::{ComputeRequirements, Currency, ModelMetadata, PricingModel, ModelId as MCPModelId},
     MCPService,
@@ -110,4 +110,16 @@ impl ModelRegistryAdapter for MCPRegistryBridge {
         }
         Ok(())
     }
+
+    async fn set_royalty_schedule(
+        &self,
+        model_id: ModelId,
+        schedule: &RoyaltySchedule,
+    ) -> Result<()> {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(model_id.0.as_bytes());
+        self.mcp
+            .set_model_royalty(MCPModelId(bytes), *schedule)
+            .await
+    }
 }
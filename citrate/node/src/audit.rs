@@ -0,0 +1,522 @@
+//! Audit export
+//!
+//! `citrate audit export` writes a signed, timestamped snapshot of account
+//! balances, the model registry and governance state after a chosen block,
+//! for operators whose chain state is subject to audit. Past heights are read
+//! from the state archive, so they need a node run with `storage.archive`.
+//!
+//! The archive is one JSON document with a `manifest`, one member per section
+//! (`accounts`, `models`, `governance`) and a `signature`. Each section is
+//! hashed with SHA3-256 over its canonical JSON (object keys sorted, no
+//! whitespace) and the digests are listed in the manifest. The signature is
+//! ed25519 over the canonical manifest, made with the node's block signing
+//! key, so auditors can check an archive without a node using
+//! `citrate-cli audit verify`.
+
+use anyhow::{anyhow, bail, Context, Result};
+use citrate_consensus::types::Hash;
+use citrate_execution::executor::StateStoreTrait;
+use citrate_execution::precompiles::proposals::{
+    self, ProposalState, GOVERNANCE_PARAMS, NEXT_ID_KEY, PROPOSALS_PRECOMPILE,
+};
+use citrate_execution::types::{AccessPolicy, AccountState, Address};
+use citrate_execution::StateDB;
+use citrate_storage::state::StateStore;
+use citrate_storage::StorageManager;
+use ed25519_dalek::{Signer, SigningKey};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha3::{Digest, Sha3_256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Archive layout version, bumped on incompatible changes
+pub const FORMAT_VERSION: u32 = 1;
+
+/// An account and its balance after the exported block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditAccount {
+    pub address: String,
+    /// Balance in wei, as a decimal string
+    pub balance: String,
+    pub nonce: u64,
+    pub code_hash: String,
+}
+
+/// How a model may be used
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuditAccess {
+    Public,
+    Private,
+    Restricted {
+        allowed: Vec<String>,
+    },
+    /// Fee in wei, as a decimal string
+    PayPerUse {
+        fee: String,
+    },
+}
+
+/// A model registry entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditModel {
+    pub model_id: String,
+    pub owner: String,
+    pub model_hash: String,
+    pub name: String,
+    pub version: String,
+    pub registry_version: u32,
+    pub framework: String,
+    pub size_bytes: u64,
+    pub created_at: u64,
+    pub access: AuditAccess,
+}
+
+/// A governance proposal and its state at the exported block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditProposal {
+    pub id: u64,
+    pub proposer: String,
+    pub parameter: String,
+    /// Raw proposed value, hex encoded
+    pub value: String,
+    pub description: String,
+    pub created_height: u64,
+    pub end_height: u64,
+    pub quorum: String,
+    pub votes_for: String,
+    pub votes_against: String,
+    pub votes_abstain: String,
+    pub state: ProposalState,
+    pub executed_height: Option<u64>,
+}
+
+/// Governance proposals and the parameter values they control
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuditGovernance {
+    pub proposals: Vec<AuditProposal>,
+    /// Current value of every parameter named by a proposal, hex encoded;
+    /// parameters never set are left out
+    pub parameters: BTreeMap<String, String>,
+}
+
+/// Digest of one archive section
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectionDigest {
+    pub name: String,
+    pub entries: usize,
+    /// SHA3-256 of the section's canonical JSON, hex encoded
+    pub sha3_256: String,
+}
+
+/// What was exported, from where and when; this is what gets signed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditManifest {
+    pub format_version: u32,
+    pub chain_id: u64,
+    pub height: u64,
+    pub block_hash: String,
+    /// Block timestamp, seconds since the Unix epoch
+    pub block_timestamp: u64,
+    pub state_root: String,
+    /// Time of export, RFC 3339
+    pub exported_at: String,
+    pub sections: Vec<SectionDigest>,
+    /// Caveats an auditor should know about
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditSignature {
+    pub algorithm: String,
+    pub public_key: String,
+    pub signature: String,
+}
+
+/// A signed audit archive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditArchive {
+    pub manifest: AuditManifest,
+    pub accounts: Vec<AuditAccount>,
+    pub models: Vec<AuditModel>,
+    pub governance: AuditGovernance,
+    pub signature: AuditSignature,
+}
+
+/// JSON with object keys sorted and no whitespace, so digests do not depend
+/// on how the document was written
+pub fn canonical_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let keys: BTreeSet<&String> = map.keys().collect();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+fn section_digest<T: Serialize>(name: &str, section: &T, entries: usize) -> Result<SectionDigest> {
+    let canonical = canonical_json(&serde_json::to_value(section)?);
+    Ok(SectionDigest {
+        name: name.to_string(),
+        entries,
+        sha3_256: hex::encode(Sha3_256::digest(canonical.as_bytes())),
+    })
+}
+
+/// Fill in the manifest's section digests and sign it
+pub fn seal(
+    mut manifest: AuditManifest,
+    accounts: Vec<AuditAccount>,
+    models: Vec<AuditModel>,
+    governance: AuditGovernance,
+    key: &SigningKey,
+) -> Result<AuditArchive> {
+    manifest.sections = vec![
+        section_digest("accounts", &accounts, accounts.len())?,
+        section_digest("models", &models, models.len())?,
+        section_digest("governance", &governance, governance.proposals.len())?,
+    ];
+
+    let canonical = canonical_json(&serde_json::to_value(&manifest)?);
+    let signature = key.sign(canonical.as_bytes());
+
+    Ok(AuditArchive {
+        manifest,
+        accounts,
+        models,
+        governance,
+        signature: AuditSignature {
+            algorithm: "ed25519".to_string(),
+            public_key: hex::encode(key.verifying_key().to_bytes()),
+            signature: hex::encode(signature.to_bytes()),
+        },
+    })
+}
+
+/// Load a hex-encoded ed25519 key, in the format of the block signing key file
+pub fn load_signing_key(path: &Path) -> Result<SigningKey> {
+    let hex_key = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read signing key {}", path.display()))?;
+    let bytes: [u8; 32] = hex::decode(hex_key.trim())
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| anyhow!("Invalid signing key file {}", path.display()))?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+fn hash_hex(hash: &Hash) -> String {
+    format!("0x{}", hash.to_hex())
+}
+
+/// State after the block at `height`: read from the archive for past heights
+/// and from current state at or past the archive's last block
+struct StateAt<'a> {
+    store: &'a StateStore,
+    height: u64,
+    archived: bool,
+}
+
+impl<'a> StateAt<'a> {
+    fn new(store: &'a StateStore, height: u64, tip: u64) -> Result<Self> {
+        let archived = if height >= tip {
+            false
+        } else {
+            match store.archived_range()? {
+                Some((_, last)) if height >= last => false,
+                Some((first, _)) if height >= first => true,
+                Some((first, _)) => bail!(
+                    "State at block {} is not available; the archive starts at block {}",
+                    height,
+                    first
+                ),
+                None => bail!(
+                    "State at block {} is not available; exporting past blocks requires archive mode",
+                    height
+                ),
+            }
+        };
+        Ok(Self {
+            store,
+            height,
+            archived,
+        })
+    }
+
+    fn account(&self, address: &Address) -> Result<Option<AccountState>> {
+        if self.archived {
+            self.store.account_at(address, self.height)
+        } else {
+            self.store.get_account(address)
+        }
+    }
+
+    fn storage(&self, address: &Address, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        if self.archived {
+            self.store.storage_at(address, key, self.height)
+        } else {
+            self.store.get_storage(address, key)
+        }
+    }
+
+    /// Every address that may hold an account at the height: current accounts
+    /// plus, for past heights, accounts changed (or removed) since
+    fn addresses(&self) -> Result<BTreeSet<[u8; 20]>> {
+        let mut addresses: BTreeSet<[u8; 20]> = self
+            .store
+            .get_all_accounts()?
+            .into_iter()
+            .map(|(address, _)| address.0)
+            .collect();
+        if self.archived {
+            addresses.extend(
+                self.store
+                    .changed_since(self.height)?
+                    .accounts
+                    .into_iter()
+                    .map(|address| address.0),
+            );
+        }
+        Ok(addresses)
+    }
+}
+
+fn collect_accounts(state: &StateAt) -> Result<Vec<AuditAccount>> {
+    let mut accounts = Vec::new();
+    for address in state.addresses()? {
+        let address = Address(address);
+        if let Some(account) = state.account(&address)? {
+            accounts.push(AuditAccount {
+                address: address.to_string(),
+                balance: account.balance.to_string(),
+                nonce: account.nonce,
+                code_hash: hash_hex(&account.code_hash),
+            });
+        }
+    }
+    Ok(accounts)
+}
+
+/// Models are not archived per block, so the registry as it is now is
+/// filtered to models created at or before the block
+fn collect_models(store: &StateStore, block_timestamp: u64) -> Result<Vec<AuditModel>> {
+    let mut models: Vec<AuditModel> = store
+        .get_all_models()?
+        .into_iter()
+        .filter(|(_, model)| model.metadata.created_at <= block_timestamp)
+        .map(|(id, model)| AuditModel {
+            model_id: hash_hex(&id.0),
+            owner: model.owner.to_string(),
+            model_hash: hash_hex(&model.model_hash),
+            name: model.metadata.name,
+            version: model.metadata.version,
+            registry_version: model.version,
+            framework: model.metadata.framework,
+            size_bytes: model.metadata.size_bytes,
+            created_at: model.metadata.created_at,
+            access: match model.access_policy {
+                AccessPolicy::Public => AuditAccess::Public,
+                AccessPolicy::Private => AuditAccess::Private,
+                AccessPolicy::Restricted(allowed) => AuditAccess::Restricted {
+                    allowed: allowed.iter().map(|a| a.to_string()).collect(),
+                },
+                AccessPolicy::PayPerUse { fee } => AuditAccess::PayPerUse {
+                    fee: fee.to_string(),
+                },
+            },
+        })
+        .collect();
+    models.sort_by(|a, b| a.model_id.cmp(&b.model_id));
+    Ok(models)
+}
+
+fn collect_governance(state: &StateAt) -> Result<AuditGovernance> {
+    // Copy the proposal slots into a scratch state so the precompile's own
+    // decoding is used
+    let scratch = StateDB::new();
+    if let Some(next_id) = state.storage(&PROPOSALS_PRECOMPILE, NEXT_ID_KEY)? {
+        scratch.set_storage(PROPOSALS_PRECOMPILE, NEXT_ID_KEY.to_vec(), next_id);
+    }
+
+    let mut governance = AuditGovernance::default();
+    for id in 0..proposals::proposal_count(&scratch) {
+        let key = proposals::proposal_key(id);
+        if let Some(encoded) = state.storage(&PROPOSALS_PRECOMPILE, &key)? {
+            scratch.set_storage(PROPOSALS_PRECOMPILE, key, encoded);
+        }
+        let Some(proposal) = proposals::proposal(&scratch, id) else {
+            continue;
+        };
+
+        if !governance.parameters.contains_key(&proposal.parameter) {
+            let param_key = format!("PARAM:{}", proposal.parameter);
+            if let Some(value) = state.storage(&GOVERNANCE_PARAMS, param_key.as_bytes())? {
+                governance
+                    .parameters
+                    .insert(proposal.parameter.clone(), hex::encode(value));
+            }
+        }
+
+        governance.proposals.push(AuditProposal {
+            id,
+            proposer: proposal.proposer.to_string(),
+            value: hex::encode(&proposal.value),
+            description: proposal.description.clone(),
+            created_height: proposal.created_height,
+            end_height: proposal.end_height,
+            quorum: proposal.quorum.to_string(),
+            votes_for: proposal.votes_for.to_string(),
+            votes_against: proposal.votes_against.to_string(),
+            votes_abstain: proposal.votes_abstain.to_string(),
+            state: proposal.state(state.height),
+            executed_height: proposal.executed_height,
+            parameter: proposal.parameter,
+        });
+    }
+    Ok(governance)
+}
+
+/// Export state after the block at `height` (the chain tip if unset) and
+/// sign it with `key`
+pub fn export(
+    storage: &StorageManager,
+    chain_id: u64,
+    height: Option<u64>,
+    key: &SigningKey,
+) -> Result<AuditArchive> {
+    let tip = storage.blocks.get_latest_height()?;
+    let height = height.unwrap_or(tip);
+    if height > tip {
+        bail!("Block {} is past the chain tip {}", height, tip);
+    }
+    let block = storage
+        .blocks
+        .get_block_by_height(height)?
+        .and_then(|hash| storage.blocks.get_block(&hash).transpose())
+        .transpose()?
+        .ok_or_else(|| anyhow!("No block at height {}", height))?;
+
+    let state = StateAt::new(&storage.state, height, tip)?;
+    let accounts = collect_accounts(&state)?;
+    let models = collect_models(&storage.state, block.header.timestamp)?;
+    let governance = collect_governance(&state)?;
+
+    let manifest = AuditManifest {
+        format_version: FORMAT_VERSION,
+        chain_id,
+        height,
+        block_hash: hash_hex(&block.hash()),
+        block_timestamp: block.header.timestamp,
+        state_root: hash_hex(&block.state_root),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        sections: Vec::new(),
+        notes: vec![
+            "Balances and governance state are as of the exported block".to_string(),
+            "Models are not archived per block: the registry at export time, limited to models created at or before the block timestamp".to_string(),
+        ],
+    };
+
+    seal(manifest, accounts, models, governance, key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, Verifier};
+
+    fn manifest() -> AuditManifest {
+        AuditManifest {
+            format_version: FORMAT_VERSION,
+            chain_id: 1337,
+            height: 42,
+            block_hash: hash_hex(&Hash::new([1; 32])),
+            block_timestamp: 1_700_000_000,
+            state_root: hash_hex(&Hash::new([2; 32])),
+            exported_at: "2026-01-01T00:00:00+00:00".to_string(),
+            sections: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_canonical_json_sorts_keys() {
+        let value: Value =
+            serde_json::from_str(r#"{"b": 1, "a": [{"d": "x", "c": null}]}"#).unwrap();
+        assert_eq!(
+            canonical_json(&value),
+            r#"{"a":[{"c":null,"d":"x"}],"b":1}"#
+        );
+    }
+
+    #[test]
+    fn test_sealed_archive_verifies_after_round_trip() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let accounts = vec![AuditAccount {
+            address: Address([3; 20]).to_string(),
+            balance: "1000000000000000000".to_string(),
+            nonce: 4,
+            code_hash: hash_hex(&Hash::default()),
+        }];
+        let archive = seal(
+            manifest(),
+            accounts,
+            Vec::new(),
+            AuditGovernance::default(),
+            &key,
+        )
+        .unwrap();
+
+        // What the verifier sees: the written file, parsed without the types
+        let written: Value =
+            serde_json::from_str(&serde_json::to_string_pretty(&archive).unwrap()).unwrap();
+        for section in &archive.manifest.sections {
+            let canonical = canonical_json(&written[section.name.as_str()]);
+            assert_eq!(
+                hex::encode(Sha3_256::digest(canonical.as_bytes())),
+                section.sha3_256
+            );
+        }
+        assert_eq!(archive.manifest.sections[0].entries, 1);
+
+        let signature =
+            Signature::from_slice(&hex::decode(&archive.signature.signature).unwrap()).unwrap();
+        let manifest_bytes = canonical_json(&written["manifest"]);
+        assert!(key
+            .verifying_key()
+            .verify(manifest_bytes.as_bytes(), &signature)
+            .is_ok());
+
+        let mut tampered = written["manifest"].clone();
+        tampered["height"] = Value::from(43);
+        assert!(key
+            .verifying_key()
+            .verify(canonical_json(&tampered).as_bytes(), &signature)
+            .is_err());
+    }
+}
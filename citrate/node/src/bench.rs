@@ -0,0 +1,538 @@
+//! Node benchmarks
+//!
+//! Standardized micro and macro benchmarks behind `citrate bench`. Each
+//! benchmark reports a throughput and a score relative to a reference
+//! machine (1000 = reference); the overall hardware score is the geometric
+//! mean of the individual scores, so it can be compared across machines and
+//! across releases.
+
+use anyhow::Result;
+use citrate_consensus::crypto;
+use citrate_consensus::types::{
+    Block, BlockHeader, GhostDagParams, Hash, PublicKey, Signature, Transaction, VrfProof,
+};
+use citrate_execution::types::Address;
+use citrate_execution::{Executor, StateDB};
+use citrate_network::NetworkMessage;
+use citrate_storage::db::column_families::CF_METADATA;
+use citrate_storage::{pruning::PruningConfig, StorageManager};
+use primitive_types::U256;
+use serde::Serialize;
+use sha3::{Digest, Sha3_256};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Score of the reference machine
+const REFERENCE_SCORE: f64 = 1000.0;
+
+// Reference throughputs (8-core x86_64, NVMe SSD)
+const REF_SIG_VERIFY_PER_SEC: f64 = 20_000.0;
+const REF_TX_EXEC_PER_SEC: f64 = 25_000.0;
+const REF_BLOCK_IMPORT_PER_SEC: f64 = 150.0;
+const REF_DB_WRITE_OPS: f64 = 150_000.0;
+const REF_DB_READ_OPS: f64 = 300_000.0;
+const REF_MSG_CODEC_PER_SEC: f64 = 100_000.0;
+
+/// Benchmark groups that can be selected on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BenchSuite {
+    /// Signature verification
+    Crypto,
+    /// Transaction execution and block import
+    Execution,
+    /// RocksDB reads and writes
+    Storage,
+    /// Network message encoding
+    Network,
+}
+
+/// Workload sizes
+#[derive(Debug, Clone)]
+pub struct BenchConfig {
+    /// Signatures to verify
+    pub signatures: usize,
+    /// Transfers to execute
+    pub transactions: usize,
+    /// Blocks to import
+    pub blocks: usize,
+    /// Transactions per imported block
+    pub txs_per_block: usize,
+    /// Database operations per direction
+    pub db_ops: usize,
+    /// Size of each database value in bytes
+    pub db_value_size: usize,
+    /// Messages to encode and decode
+    pub messages: usize,
+    /// Directory for scratch databases; removed afterwards
+    pub work_dir: PathBuf,
+    /// Suites to run; empty runs all of them
+    pub suites: Vec<BenchSuite>,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            signatures: 20_000,
+            transactions: 20_000,
+            blocks: 200,
+            txs_per_block: 100,
+            db_ops: 100_000,
+            db_value_size: 256,
+            messages: 50_000,
+            work_dir: std::env::temp_dir().join(format!("citrate-bench-{}", std::process::id())),
+            suites: Vec::new(),
+        }
+    }
+}
+
+impl BenchConfig {
+    /// A tenth of the default workload, for a fast sanity check
+    pub fn quick() -> Self {
+        let default = Self::default();
+        Self {
+            signatures: default.signatures / 10,
+            transactions: default.transactions / 10,
+            blocks: default.blocks / 10,
+            db_ops: default.db_ops / 10,
+            messages: default.messages / 10,
+            ..default
+        }
+    }
+
+    fn includes(&self, suite: BenchSuite) -> bool {
+        self.suites.is_empty() || self.suites.contains(&suite)
+    }
+}
+
+/// Outcome of a single benchmark
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchResult {
+    pub name: &'static str,
+    pub unit: &'static str,
+    pub operations: u64,
+    pub elapsed_ms: f64,
+    pub per_second: f64,
+    pub score: f64,
+}
+
+impl BenchResult {
+    fn new(
+        name: &'static str,
+        unit: &'static str,
+        operations: usize,
+        elapsed: Duration,
+        reference: f64,
+    ) -> Self {
+        let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+        let per_second = operations as f64 / secs;
+        Self {
+            name,
+            unit,
+            operations: operations as u64,
+            elapsed_ms: secs * 1000.0,
+            per_second,
+            score: per_second / reference * REFERENCE_SCORE,
+        }
+    }
+}
+
+/// All results of a run plus the combined hardware score
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub version: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub cpus: usize,
+    pub results: Vec<BenchResult>,
+    pub hardware_score: f64,
+}
+
+impl BenchReport {
+    fn new(results: Vec<BenchResult>) -> Self {
+        let hardware_score = geometric_mean(results.iter().map(|r| r.score));
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            cpus: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+            results,
+            hardware_score,
+        }
+    }
+
+    /// Human readable table
+    pub fn print(&self) {
+        println!("=========================================");
+        println!("Citrate Node Benchmark v{}", self.version);
+        println!("=========================================");
+        println!("Platform: {}/{} ({} CPUs)", self.os, self.arch, self.cpus);
+        println!();
+        println!(
+            "{:<22} {:>14} {:>12} {:>10} {:>8}",
+            "Benchmark", "Throughput", "Unit", "Time (ms)", "Score"
+        );
+        println!("{}", "-".repeat(70));
+        for result in &self.results {
+            println!(
+                "{:<22} {:>14.1} {:>12} {:>10.1} {:>8.0}",
+                result.name, result.per_second, result.unit, result.elapsed_ms, result.score
+            );
+        }
+        println!("{}", "-".repeat(70));
+        println!(
+            "Hardware score: {:.0} (reference machine = {:.0})",
+            self.hardware_score, REFERENCE_SCORE
+        );
+    }
+}
+
+/// Run the selected benchmarks
+pub async fn run(config: &BenchConfig) -> Result<BenchReport> {
+    let mut results = Vec::new();
+
+    if config.includes(BenchSuite::Crypto) {
+        results.push(bench_signature_verification(config.signatures)?);
+    }
+    if config.includes(BenchSuite::Execution) {
+        results.push(bench_tx_execution(config.transactions).await?);
+    }
+    if config.includes(BenchSuite::Storage) || config.includes(BenchSuite::Execution) {
+        std::fs::create_dir_all(&config.work_dir)?;
+        let outcome = run_storage_benches(config).await;
+        let _ = std::fs::remove_dir_all(&config.work_dir);
+        results.extend(outcome?);
+    }
+    if config.includes(BenchSuite::Network) {
+        results.push(bench_message_codec(config.messages)?);
+    }
+
+    Ok(BenchReport::new(results))
+}
+
+async fn run_storage_benches(config: &BenchConfig) -> Result<Vec<BenchResult>> {
+    let mut results = Vec::new();
+    if config.includes(BenchSuite::Execution) {
+        let import =
+            bench_block_import(&config.work_dir, config.blocks, config.txs_per_block).await?;
+        results.push(import);
+    }
+    if config.includes(BenchSuite::Storage) {
+        results.extend(bench_db_iops(
+            &config.work_dir,
+            config.db_ops,
+            config.db_value_size,
+        )?);
+    }
+    Ok(results)
+}
+
+/// Ed25519 transaction signature verification
+fn bench_signature_verification(count: usize) -> Result<BenchResult> {
+    let key = crypto::generate_keypair();
+    let recipient = PublicKey::new([2; 32]);
+    let txs = (0..count)
+        .map(|nonce| signed_transfer(&key, recipient, nonce as u64))
+        .collect::<Result<Vec<_>>>()?;
+
+    let start = Instant::now();
+    for tx in &txs {
+        if !crypto::verify_transaction(tx)? {
+            anyhow::bail!("signature verification failed for {}", tx.hash);
+        }
+    }
+
+    Ok(BenchResult::new(
+        "signature_verify",
+        "sig/s",
+        count,
+        start.elapsed(),
+        REF_SIG_VERIFY_PER_SEC,
+    ))
+}
+
+/// Plain value transfers through the executor
+async fn bench_tx_execution(count: usize) -> Result<BenchResult> {
+    let executor = Executor::new(Arc::new(StateDB::new()));
+    let key = crypto::generate_keypair();
+    let sender = PublicKey::new(key.verifying_key().to_bytes());
+    executor.set_balance(&Address::from_public_key(&sender), U256::MAX >> 1);
+
+    let block = bench_block(Hash::default(), 1, Vec::new());
+    let txs = (0..count)
+        .map(|nonce| signed_transfer(&key, recipient_for(nonce), nonce as u64))
+        .collect::<Result<Vec<_>>>()?;
+
+    let start = Instant::now();
+    for tx in &txs {
+        executor.execute_transaction(&block, tx).await?;
+    }
+
+    Ok(BenchResult::new(
+        "tx_execution",
+        "tx/s",
+        count,
+        start.elapsed(),
+        REF_TX_EXEC_PER_SEC,
+    ))
+}
+
+/// Full block import: signature checks, execution, state root and persistence
+async fn bench_block_import(
+    work_dir: &std::path::Path,
+    blocks: usize,
+    txs_per_block: usize,
+) -> Result<BenchResult> {
+    let storage = StorageManager::new(work_dir.join("blocks"), PruningConfig::default())?;
+    let executor = Executor::new(Arc::new(StateDB::new()));
+    let key = crypto::generate_keypair();
+    let sender = PublicKey::new(key.verifying_key().to_bytes());
+    executor.set_balance(&Address::from_public_key(&sender), U256::MAX >> 1);
+
+    let mut parent = Hash::default();
+    let mut pending = Vec::with_capacity(blocks);
+    for height in 1..=blocks as u64 {
+        let first_nonce = (height - 1) * txs_per_block as u64;
+        let txs = (0..txs_per_block as u64)
+            .map(|i| signed_transfer(&key, recipient_for(i as usize), first_nonce + i))
+            .collect::<Result<Vec<_>>>()?;
+        let block = bench_block(parent, height, txs);
+        parent = block.header.block_hash;
+        pending.push(block);
+    }
+
+    let start = Instant::now();
+    for mut block in pending {
+        for tx in &block.transactions {
+            if !crypto::verify_transaction(tx)? {
+                anyhow::bail!("signature verification failed for {}", tx.hash);
+            }
+            executor.execute_transaction(&block, tx).await?;
+        }
+        block.state_root = executor.calculate_state_root();
+        storage.blocks.put_block(&block)?;
+    }
+    storage.flush()?;
+
+    Ok(BenchResult::new(
+        "block_import",
+        "block/s",
+        blocks,
+        start.elapsed(),
+        REF_BLOCK_IMPORT_PER_SEC,
+    ))
+}
+
+/// Random-key RocksDB writes followed by random-key reads
+fn bench_db_iops(
+    work_dir: &std::path::Path,
+    ops: usize,
+    value_size: usize,
+) -> Result<Vec<BenchResult>> {
+    let storage = StorageManager::new(work_dir.join("iops"), PruningConfig::default())?;
+    let keys: Vec<[u8; 32]> = (0..ops as u64).map(bench_key).collect();
+    let value = vec![0xab; value_size];
+
+    let start = Instant::now();
+    for key in &keys {
+        storage.db.put_cf(CF_METADATA, key, &value)?;
+    }
+    storage.flush()?;
+    let write = BenchResult::new("db_write", "ops/s", ops, start.elapsed(), REF_DB_WRITE_OPS);
+
+    let start = Instant::now();
+    for key in keys.iter().rev() {
+        if storage.db.get_cf(CF_METADATA, key)?.is_none() {
+            anyhow::bail!("benchmark key missing from database");
+        }
+    }
+    let read = BenchResult::new("db_read", "ops/s", ops, start.elapsed(), REF_DB_READ_OPS);
+
+    Ok(vec![write, read])
+}
+
+/// Wire encoding and decoding of transaction gossip
+fn bench_message_codec(count: usize) -> Result<BenchResult> {
+    let key = crypto::generate_keypair();
+    let message = NetworkMessage::NewTransaction {
+        transaction: signed_transfer(&key, PublicKey::new([2; 32]), 0)?,
+    };
+
+    let start = Instant::now();
+    for _ in 0..count {
+        let bytes = bincode::serialize(&message)?;
+        let decoded: NetworkMessage = bincode::deserialize(&bytes)?;
+        std::hint::black_box(decoded);
+    }
+
+    Ok(BenchResult::new(
+        "message_codec",
+        "msg/s",
+        count,
+        start.elapsed(),
+        REF_MSG_CODEC_PER_SEC,
+    ))
+}
+
+fn signed_transfer(
+    key: &ed25519_dalek::SigningKey,
+    to: PublicKey,
+    nonce: u64,
+) -> Result<Transaction> {
+    let mut tx = Transaction {
+        hash: Hash::default(),
+        nonce,
+        from: PublicKey::new(key.verifying_key().to_bytes()),
+        to: Some(to),
+        value: 1,
+        gas_limit: 21_000,
+        gas_price: 1,
+        data: Vec::new(),
+        signature: Signature::new([0; 64]),
+        tx_type: None,
+    };
+    // Set explicitly: bincode cannot round-trip a skipped `None` type
+    tx.determine_type();
+    crypto::sign_transaction(&mut tx, key)?;
+    tx.hash = Hash::new(sha3_256(tx.signature.as_bytes()));
+    Ok(tx)
+}
+
+fn bench_block(parent: Hash, height: u64, transactions: Vec<Transaction>) -> Block {
+    let mut header = BlockHeader {
+        version: 1,
+        block_hash: Hash::default(),
+        selected_parent_hash: parent,
+        merge_parent_hashes: vec![],
+        timestamp: height,
+        height,
+        blue_score: height,
+        blue_work: height as u128,
+        pruning_point: Hash::default(),
+        proposer_pubkey: PublicKey::new([0; 32]),
+        vrf_reveal: VrfProof {
+            proof: vec![],
+            output: Hash::default(),
+        },
+        base_fee_per_gas: 1,
+        gas_used: 0,
+        gas_limit: 30_000_000,
+        epoch_commitment: Hash::default(),
+    };
+
+    let mut preimage = parent.as_bytes().to_vec();
+    preimage.extend_from_slice(&height.to_le_bytes());
+    header.block_hash = Hash::new(sha3_256(&preimage));
+
+    Block {
+        header,
+        state_root: Hash::default(),
+        tx_root: Hash::default(),
+        receipt_root: Hash::default(),
+        artifact_root: Hash::default(),
+        ghostdag_params: GhostDagParams::default(),
+        transactions,
+        signature: Signature::new([0; 64]),
+        embedded_models: vec![],
+        required_pins: vec![],
+    }
+}
+
+/// Spread transfers over a fixed set of recipients so state keeps growing
+/// without every transfer hitting a brand new account
+fn recipient_for(i: usize) -> PublicKey {
+    let mut bytes = [0u8; 32];
+    bytes[..8].copy_from_slice(&((i % 1024) as u64 + 1).to_be_bytes());
+    bytes[31] = 0x01;
+    PublicKey::new(bytes)
+}
+
+/// Pseudo-random but reproducible database key
+fn bench_key(i: u64) -> [u8; 32] {
+    sha3_256(&i.to_le_bytes())
+}
+
+fn sha3_256(data: &[u8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&Sha3_256::digest(data));
+    out
+}
+
+fn geometric_mean(scores: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = scores
+        .filter(|s| *s > 0.0)
+        .fold((0.0, 0usize), |(sum, count), s| (sum + s.ln(), count + 1));
+    if count == 0 {
+        0.0
+    } else {
+        (sum / count as f64).exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tiny_config(work_dir: PathBuf) -> BenchConfig {
+        BenchConfig {
+            signatures: 20,
+            transactions: 20,
+            blocks: 3,
+            txs_per_block: 5,
+            db_ops: 50,
+            db_value_size: 32,
+            messages: 20,
+            work_dir,
+            suites: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_geometric_mean() {
+        assert_eq!(geometric_mean(std::iter::empty()), 0.0);
+        let mean = geometric_mean([500.0, 2000.0].into_iter());
+        assert!((mean - 1000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_result_score_is_relative_to_reference() {
+        let result = BenchResult::new("x", "op/s", 100, Duration::from_secs(1), 50.0);
+        assert!((result.per_second - 100.0).abs() < 1e-9);
+        assert!((result.score - 2000.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_run_all_suites() {
+        let dir = tempfile::tempdir().unwrap();
+        let work_dir = dir.path().join("bench");
+        let report = run(&tiny_config(work_dir.clone())).await.unwrap();
+
+        let names: Vec<_> = report.results.iter().map(|r| r.name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "signature_verify",
+                "tx_execution",
+                "block_import",
+                "db_write",
+                "db_read",
+                "message_codec"
+            ]
+        );
+        assert!(report.hardware_score > 0.0);
+        assert!(!work_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_run_selected_suite() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = tiny_config(dir.path().join("bench"));
+        config.suites = vec![BenchSuite::Network];
+
+        let report = run(&config).await.unwrap();
+        assert_eq!(report.results.len(), 1);
+        assert_eq!(report.results[0].name, "message_codec");
+    }
+}
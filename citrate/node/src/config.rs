@@ -1,6 +1,15 @@
+use crate::dev_accounts::DevAccountsConfig;
+use citrate_api::AccessConfig;
+use citrate_storage::db::DbTuning;
+use citrate_wallet::config_secrets;
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Prefix of environment overrides; `__` separates the path to a field,
+/// e.g. `CITRATE_RPC__LISTEN_ADDR=0.0.0.0:8545`
+pub const ENV_PREFIX: &str = "CITRATE_";
+const ENV_SEPARATOR: &str = "__";
 
 /// Node configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +32,216 @@ pub struct NodeConfig {
     /// Validator configuration
     #[serde(default)]
     pub validator: ValidatorConfig,
+
+    /// Anonymous telemetry (opt-in)
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+
+    /// Webhooks notified of model registry events
+    #[serde(default)]
+    pub webhooks: WebhooksConfig,
+
+    /// HTTP gateway serving content from the IPFS daemon
+    #[serde(default)]
+    pub ipfs_gateway: IpfsGatewayConfig,
+
+    /// Pre-funded accounts created in genesis and listed by `eth_accounts`.
+    /// Development only; their private keys are printed at startup.
+    #[serde(default)]
+    pub dev_accounts: Option<DevAccountsConfig>,
+}
+
+/// Opt-in telemetry configuration
+///
+/// When enabled, the node periodically submits an anonymized report
+/// (version, chain ID, peer count, sync height, OS/arch) to `endpoint`.
+/// Disabled by default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    /// Submit telemetry reports
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// HTTP(S) endpoint reports are POSTed to
+    #[serde(default)]
+    pub endpoint: String,
+
+    /// Seconds between reports
+    #[serde(default = "default_telemetry_interval")]
+    pub interval_secs: u64,
+}
+
+fn default_telemetry_interval() -> u64 {
+    3600 // 1 hour
+}
+
+/// Shortest allowed telemetry interval, to keep load on the collector low
+const MIN_TELEMETRY_INTERVAL_SECS: u64 = 300;
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: String::new(),
+            interval_secs: default_telemetry_interval(),
+        }
+    }
+}
+
+impl TelemetryConfig {
+    /// Validate configuration; only checked when telemetry is enabled
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if !(self.endpoint.starts_with("http://") || self.endpoint.starts_with("https://")) {
+            return Err(
+                "telemetry.enabled=true requires an http(s) telemetry.endpoint".to_string(),
+            );
+        }
+        if self.interval_secs < MIN_TELEMETRY_INTERVAL_SECS {
+            return Err(format!(
+                "telemetry.interval_secs must be at least {}",
+                MIN_TELEMETRY_INTERVAL_SECS
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Model registry webhook configuration
+///
+/// When enabled, model registered / updated / deprecated events are POSTed
+/// as JSON to every endpoint whose filters match. Disabled by default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhooksConfig {
+    /// Deliver model registry events
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Blocks an event must be buried under before it is delivered
+    #[serde(default)]
+    pub confirmations: u64,
+
+    /// Seconds between checks for new blocks
+    #[serde(default = "default_webhook_poll_interval")]
+    pub poll_interval_secs: u64,
+
+    /// Where events are delivered
+    #[serde(default)]
+    pub endpoints: Vec<WebhookEndpointConfig>,
+}
+
+fn default_webhook_poll_interval() -> u64 {
+    5
+}
+
+impl Default for WebhooksConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            confirmations: 0,
+            poll_interval_secs: default_webhook_poll_interval(),
+            endpoints: Vec::new(),
+        }
+    }
+}
+
+/// A webhook receiver and the events it wants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEndpointConfig {
+    /// HTTP(S) URL events are POSTed to
+    pub url: String,
+
+    /// Event names to deliver (`model.registered`, `model.updated`,
+    /// `model.deprecated`); empty delivers all
+    #[serde(default)]
+    pub events: Vec<String>,
+
+    /// Only deliver events for models owned by these addresses; empty
+    /// delivers events for every owner
+    #[serde(default)]
+    pub owners: Vec<String>,
+
+    /// Key for the `X-Citrate-Signature` HMAC-SHA256 header. Use a
+    /// `${secret:name}` reference rather than a plaintext value.
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+impl WebhooksConfig {
+    /// Validate configuration; only checked when webhooks are enabled
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.endpoints.is_empty() {
+            return Err(
+                "webhooks.enabled=true requires at least one webhooks.endpoints entry".to_string(),
+            );
+        }
+        if self.poll_interval_secs == 0 {
+            return Err("webhooks.poll_interval_secs must be at least 1".to_string());
+        }
+        for endpoint in &self.endpoints {
+            crate::webhooks::Endpoint::from_config(endpoint)?;
+        }
+        Ok(())
+    }
+}
+
+/// Embedded IPFS HTTP gateway
+///
+/// When enabled, `GET /ipfs/<cid>` on `listen_addr` serves content from the
+/// IPFS daemon at `ipfs_api_url`, so model cards and generated images open
+/// in a browser without a separate gateway. Disabled by default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpfsGatewayConfig {
+    /// Serve the gateway
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Address the gateway listens on
+    #[serde(default = "default_ipfs_gateway_addr")]
+    pub listen_addr: String,
+
+    /// IPFS API endpoint content is read from
+    #[serde(default = "default_ipfs_url")]
+    pub ipfs_api_url: String,
+}
+
+fn default_ipfs_gateway_addr() -> String {
+    "127.0.0.1:8080".to_string()
+}
+
+impl Default for IpfsGatewayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen_addr: default_ipfs_gateway_addr(),
+            ipfs_api_url: default_ipfs_url(),
+        }
+    }
+}
+
+impl IpfsGatewayConfig {
+    /// Validate configuration; only checked when the gateway is enabled
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.listen_addr.parse::<std::net::SocketAddr>().is_err() {
+            return Err(format!(
+                "ipfs_gateway.listen_addr {:?} is not a socket address",
+                self.listen_addr
+            ));
+        }
+        if !(self.ipfs_api_url.starts_with("http://") || self.ipfs_api_url.starts_with("https://"))
+        {
+            return Err("ipfs_gateway.ipfs_api_url must be an http(s) URL".to_string());
+        }
+        Ok(())
+    }
 }
 
 /// Validator and production mode configuration
@@ -50,6 +269,21 @@ pub struct ValidatorConfig {
     /// Grace period before slashing in hours
     #[serde(default = "default_grace_period")]
     pub grace_period_hours: u64,
+
+    /// Blocks between signed finality checkpoints; 0 disables checkpoints
+    #[serde(default)]
+    pub checkpoint_interval: u64,
+
+    /// Validator signatures needed to enforce a checkpoint; 0 means more
+    /// than two thirds of `validators`
+    #[serde(default)]
+    pub checkpoint_quorum: usize,
+
+    /// Blocks per epoch; validators sign a summary of the chain at each
+    /// epoch boundary, with the same quorum as checkpoints. 0 disables
+    /// epoch summaries.
+    #[serde(default)]
+    pub epoch_length: u64,
 }
 
 fn default_ipfs_url() -> String {
@@ -72,6 +306,9 @@ impl Default for ValidatorConfig {
             ipfs_api_url: default_ipfs_url(),
             check_interval_secs: default_check_interval(),
             grace_period_hours: default_grace_period(),
+            checkpoint_interval: 0,
+            checkpoint_quorum: 0,
+            epoch_length: 0,
         }
     }
 }
@@ -85,6 +322,9 @@ impl ValidatorConfig {
             ipfs_api_url: default_ipfs_url(),
             check_interval_secs: default_check_interval(),
             grace_period_hours: default_grace_period(),
+            checkpoint_interval: 0,
+            checkpoint_quorum: 0,
+            epoch_length: 0,
         }
     }
 
@@ -96,8 +336,79 @@ impl ValidatorConfig {
                  Configure validators in [validator] section or set production_mode=false for development.".to_string()
             );
         }
+        if self.checkpoint_interval > 0 || self.epoch_length > 0 {
+            let validators = self.validator_keys()?;
+            if validators.is_empty() {
+                let field = if self.checkpoint_interval > 0 {
+                    "checkpoint_interval"
+                } else {
+                    "epoch_length"
+                };
+                return Err(format!("validator.{} requires validators", field));
+            }
+            if self.checkpoint_quorum > validators.len() {
+                return Err(format!(
+                    "validator.checkpoint_quorum {} exceeds the {} configured validators",
+                    self.checkpoint_quorum,
+                    validators.len()
+                ));
+            }
+        }
         Ok(())
     }
+
+    /// Parse the hex-encoded validator public keys
+    pub fn validator_keys(&self) -> Result<Vec<citrate_consensus::PublicKey>, String> {
+        self.validators
+            .iter()
+            .map(|key| {
+                let bytes = hex::decode(key.trim_start_matches("0x"))
+                    .map_err(|e| format!("Invalid validator key {}: {}", key, e))?;
+                let bytes: [u8; 32] = bytes
+                    .try_into()
+                    .map_err(|_| format!("Validator key {} is not 32 bytes", key))?;
+                Ok(citrate_consensus::PublicKey::new(bytes))
+            })
+            .collect()
+    }
+
+    /// Checkpoint settings, if checkpoints are enabled
+    pub fn checkpoint_config(
+        &self,
+        chain_id: u64,
+    ) -> Result<Option<citrate_consensus::CheckpointConfig>, String> {
+        if self.checkpoint_interval == 0 {
+            return Ok(None);
+        }
+        let mut config = citrate_consensus::CheckpointConfig::new(
+            chain_id,
+            self.checkpoint_interval,
+            self.validator_keys()?,
+        );
+        if self.checkpoint_quorum > 0 {
+            config.quorum = self.checkpoint_quorum;
+        }
+        Ok(Some(config))
+    }
+
+    /// Epoch summary settings, if epoch summaries are enabled
+    pub fn epoch_config(
+        &self,
+        chain_id: u64,
+    ) -> Result<Option<citrate_consensus::EpochConfig>, String> {
+        if self.epoch_length == 0 {
+            return Ok(None);
+        }
+        let mut config = citrate_consensus::EpochConfig::new(
+            chain_id,
+            self.epoch_length,
+            self.validator_keys()?,
+        );
+        if self.checkpoint_quorum > 0 {
+            config.quorum = self.checkpoint_quorum;
+        }
+        Ok(Some(config))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -113,6 +424,11 @@ pub struct ChainConfig {
 
     /// GhostDAG K parameter
     pub ghostdag_k: u16,
+
+    /// Genesis model manifest written by `citrate genesis add-model`.
+    /// Only read when the chain is first initialized.
+    #[serde(default)]
+    pub genesis_models: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -125,6 +441,11 @@ pub struct NetworkConfig {
 
     /// Max peers
     pub max_peers: usize,
+
+    /// Dial peers without Noise encryption and accept plaintext peers.
+    /// Devnets only: traffic is readable and peer IDs are not authenticated.
+    #[serde(default)]
+    pub allow_plaintext: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -137,6 +458,18 @@ pub struct RpcConfig {
 
     /// WebSocket listen address
     pub ws_addr: SocketAddr,
+
+    /// API keys, rate limits and the methods served on a public address
+    #[serde(default)]
+    pub access: AccessConfig,
+
+    /// Calls allowed in one batch JSON-RPC request
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: usize,
+}
+
+fn default_max_batch_size() -> usize {
+    citrate_api::batch::DEFAULT_MAX_BATCH_SIZE
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -149,6 +482,28 @@ pub struct StorageConfig {
 
     /// Blocks to keep if pruning
     pub keep_blocks: u64,
+
+    /// Blocks to keep transaction receipts for if pruning; defaults to
+    /// `keep_blocks`
+    #[serde(default)]
+    pub keep_receipts: Option<u64>,
+
+    /// Keep per-block state history so `eth_call`, `eth_getBalance`,
+    /// `eth_getStorageAt` and `eth_getProof` can be queried at past blocks
+    #[serde(default)]
+    pub archive: bool,
+
+    /// RocksDB block cache, bloom filter and write buffer settings
+    #[serde(default)]
+    pub tuning: DbTuning,
+
+    /// Days of hourly chain statistics snapshots to keep; 0 keeps them all
+    #[serde(default = "default_stats_history_days")]
+    pub stats_history_days: u64,
+}
+
+fn default_stats_history_days() -> u64 {
+    90
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -156,7 +511,8 @@ pub struct MiningConfig {
     /// Enable mining
     pub enabled: bool,
 
-    /// Coinbase address (hex)
+    /// Coinbase address (hex); receives block rewards and is never used
+    /// to sign
     pub coinbase: String,
 
     /// Target block time (seconds)
@@ -164,6 +520,23 @@ pub struct MiningConfig {
 
     /// Min gas price
     pub min_gas_price: u64,
+
+    /// Remote signer that holds the block signing key
+    /// (`unix:///path/to/signer.ipc` or `http://host:port`); takes
+    /// precedence over `signing_key`
+    #[serde(default)]
+    pub remote_signer: Option<String>,
+
+    /// File holding the hex-encoded block signing key, created on first
+    /// start; defaults to `signing.key` in the data directory
+    #[serde(default)]
+    pub signing_key: Option<PathBuf>,
+
+    /// Serve block templates over RPC (`citrate_getBlockTemplate`,
+    /// `citrate_submitBlock`) so blocks can be produced outside the node;
+    /// works with or without `enabled`
+    #[serde(default)]
+    pub block_templates: bool,
 }
 
 impl Default for NodeConfig {
@@ -180,16 +553,20 @@ impl Default for NodeConfig {
                 genesis_hash: None,
                 block_time: 5,
                 ghostdag_k: 18,
+                genesis_models: None,
             },
             network: NetworkConfig {
                 listen_addr: "127.0.0.1:30303".parse().unwrap(),
                 bootstrap_nodes: vec![],
                 max_peers: 50,
+                allow_plaintext: false,
             },
             rpc: RpcConfig {
                 enabled: true,
                 listen_addr: "127.0.0.1:8545".parse().unwrap(),
                 ws_addr: "127.0.0.1:8546".parse().unwrap(),
+                access: AccessConfig::default(),
+                max_batch_size: default_max_batch_size(),
             },
             storage: StorageConfig {
                 data_dir: dirs::home_dir()
@@ -197,14 +574,25 @@ impl Default for NodeConfig {
                     .join(".citrate"),
                 pruning: false,
                 keep_blocks: 100000,
+                keep_receipts: None,
+                archive: false,
+                tuning: DbTuning::default(),
+                stats_history_days: default_stats_history_days(),
             },
             mining: MiningConfig {
                 enabled: true,
                 coinbase: "0x0000000000000000000000000000000000000000".to_string(),
                 target_block_time: 5,
                 min_gas_price: 1_000_000_000,
+                remote_signer: None,
+                signing_key: None,
+                block_templates: false,
             },
             validator: ValidatorConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            webhooks: WebhooksConfig::default(),
+            ipfs_gateway: IpfsGatewayConfig::default(),
+            dev_accounts: None,
         }
     }
 }
@@ -215,6 +603,21 @@ impl NodeConfig {
     pub fn validate(&self) -> Result<(), String> {
         // Validate validator configuration (fail-closed in production)
         self.validator.validate()?;
+        self.telemetry.validate()?;
+        self.webhooks.validate()?;
+        self.ipfs_gateway.validate()?;
+        if self.dev_accounts.is_some() && self.validator.production_mode {
+            return Err(
+                "dev_accounts have publicly known keys and cannot be used with production_mode=true"
+                    .to_string(),
+            );
+        }
+        if self.network.allow_plaintext && self.validator.production_mode {
+            return Err(
+                "network.allow_plaintext disables P2P encryption and cannot be used with production_mode=true"
+                    .to_string(),
+            );
+        }
         Ok(())
     }
 
@@ -228,22 +631,178 @@ impl NodeConfig {
         }
         config.mining.enabled = true;
         config.mining.target_block_time = 2; // Fast blocks for testing
+        config.dev_accounts = Some(DevAccountsConfig::default());
         config
     }
 
-    /// Load from file
-    pub fn from_file(path: &PathBuf) -> anyhow::Result<Self> {
-        let content = std::fs::read_to_string(path)?;
-        let config: NodeConfig = toml::from_str(&content)?;
+    /// Effective configuration: the file at `path` (or the defaults), then
+    /// `CITRATE_<SECTION>__<FIELD>` environment overrides
+    pub fn load(path: Option<&Path>) -> anyhow::Result<Self> {
+        let defaults = serde_json::to_value(Self::default())?;
+        let mut value = match path {
+            Some(path) => Self::read_file(path)?,
+            None => defaults.clone(),
+        };
+        apply_env_overrides(&mut value, &defaults, std::env::vars())?;
+        let config: NodeConfig = serde_json::from_value(value)?;
         Ok(config)
     }
 
+    /// Parse a config file, expanding `${VAR}` and `${secret:name}`
+    /// references (see `citrate_wallet::config_secrets`)
+    fn read_file(path: &Path) -> anyhow::Result<serde_json::Value> {
+        let content = std::fs::read_to_string(path)?;
+        let mut value = serde_json::to_value(toml::from_str::<toml::Value>(&content)?)?;
+        let passphrase = config_secrets::passphrase_from_env()?;
+        config_secrets::resolve(&mut value, passphrase.as_deref())
+            .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+        Ok(value)
+    }
+
+    /// Render as TOML, as `--print-config` shows it
+    pub fn to_toml(&self) -> anyhow::Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
     /// Save to file
     #[allow(dead_code)]
     pub fn save(&self, path: &PathBuf) -> anyhow::Result<()> {
-        let content = toml::to_string_pretty(self)?;
+        let content = self.to_toml()?;
         std::fs::create_dir_all(path.parent().unwrap())?;
         std::fs::write(path, content)?;
         Ok(())
     }
 }
+
+/// Apply `CITRATE_<SECTION>__<FIELD>` variables to a config tree. Values
+/// are coerced to the type of the field in `defaults`: numbers and booleans
+/// are parsed, lists take JSON or comma-separated values, and strings are
+/// used verbatim. Missing sections are created.
+fn apply_env_overrides(
+    config: &mut serde_json::Value,
+    defaults: &serde_json::Value,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> anyhow::Result<()> {
+    use serde_json::Value;
+
+    for (name, raw) in vars {
+        let Some(path) = name.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        if !path.contains(ENV_SEPARATOR) {
+            continue;
+        }
+        let segments: Vec<String> = path
+            .split(ENV_SEPARATOR)
+            .map(|s| s.to_ascii_lowercase())
+            .collect();
+
+        let hint = segments
+            .iter()
+            .try_fold(defaults, |node, key| node.get(key.as_str()));
+        let value = match hint {
+            Some(Value::String(_)) => Value::String(raw),
+            Some(Value::Bool(_)) | Some(Value::Number(_)) => serde_json::from_str(&raw)
+                .map_err(|_| anyhow::anyhow!("{}: expected a number or boolean", name))?,
+            Some(Value::Array(_)) => serde_json::from_str(&raw).unwrap_or_else(|_| {
+                Value::Array(
+                    raw.split(',')
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(|s| Value::String(s.to_string()))
+                        .collect(),
+                )
+            }),
+            // Unset optional fields carry no type; take JSON scalars as-is
+            _ => serde_json::from_str(&raw)
+                .ok()
+                .filter(|v: &Value| !v.is_object() && !v.is_array())
+                .unwrap_or(Value::String(raw)),
+        };
+
+        let mut node = &mut *config;
+        for key in segments {
+            if !node.is_object() {
+                *node = Value::Object(Default::default());
+            }
+            node = node
+                .as_object_mut()
+                .expect("just made an object")
+                .entry(key)
+                .or_insert(Value::Null);
+        }
+        *node = value;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_env_overrides_every_kind_of_field() {
+        let defaults = serde_json::to_value(NodeConfig::default()).unwrap();
+        let mut value = defaults.clone();
+        apply_env_overrides(
+            &mut value,
+            &defaults,
+            vars(&[
+                ("CITRATE_RPC__LISTEN_ADDR", "0.0.0.0:9545"),
+                ("CITRATE_RPC__ENABLED", "false"),
+                ("CITRATE_NETWORK__MAX_PEERS", "12"),
+                (
+                    "CITRATE_NETWORK__BOOTSTRAP_NODES",
+                    "a@1.2.3.4:30303, 5.6.7.8:30303",
+                ),
+                ("CITRATE_MINING__COINBASE", "1234"),
+                ("CITRATE_MINING__SIGNING_KEY", "/keys/signing.key"),
+                ("CITRATE_DEV_ACCOUNTS__COUNT", "3"),
+                // Not overrides: no separator, or not ours
+                ("CITRATE_CHAIN_ID", "7"),
+                ("OTHER__RPC__ENABLED", "true"),
+            ]),
+        )
+        .unwrap();
+
+        let config: NodeConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.rpc.listen_addr.port(), 9545);
+        assert!(!config.rpc.enabled);
+        assert_eq!(config.network.max_peers, 12);
+        assert_eq!(
+            config.network.bootstrap_nodes,
+            vec!["a@1.2.3.4:30303", "5.6.7.8:30303"]
+        );
+        assert_eq!(config.mining.coinbase, "1234");
+        assert_eq!(
+            config.mining.signing_key,
+            Some(PathBuf::from("/keys/signing.key"))
+        );
+        assert_eq!(config.dev_accounts.unwrap().count, 3);
+        assert_eq!(config.chain.chain_id, NodeConfig::default().chain.chain_id);
+
+        let mut value = defaults.clone();
+        let err = apply_env_overrides(
+            &mut value,
+            &defaults,
+            vars(&[("CITRATE_NETWORK__MAX_PEERS", "lots")]),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("CITRATE_NETWORK__MAX_PEERS"));
+    }
+
+    #[test]
+    fn test_effective_config_round_trips_through_toml() {
+        let config = NodeConfig::default();
+        let parsed: NodeConfig = toml::from_str(&config.to_toml().unwrap()).unwrap();
+        assert_eq!(parsed.rpc.listen_addr, config.rpc.listen_addr);
+        assert_eq!(parsed.storage.data_dir, config.storage.data_dir);
+    }
+}
@@ -0,0 +1,241 @@
+// citrate/node/src/dev_accounts.rs
+
+//! Pre-funded development accounts
+//!
+//! Accounts are derived from a BIP-39 mnemonic along `m/44'/60'/0'/0/N` with
+//! secp256k1 keys, the same scheme Hardhat and Foundry use. With the default
+//! mnemonic the accounts and private keys match `npx hardhat node` and
+//! `anvil`, so existing scripts and wallet imports work unchanged.
+
+use anyhow::{anyhow, Result};
+use bip39::{Language, Mnemonic};
+use citrate_consensus::types::PublicKey;
+use citrate_execution::types::Address;
+use hmac::{Hmac, Mac};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::elliptic_curve::PrimeField;
+use k256::{Scalar, SecretKey};
+use serde::{Deserialize, Serialize};
+use sha2::Sha512;
+use sha3::{Digest, Keccak256};
+
+/// Mnemonic used by Hardhat and Foundry for their default accounts
+pub const DEFAULT_DEV_MNEMONIC: &str =
+    "test test test test test test test test test test test junk";
+
+const HARDENED: u32 = 0x8000_0000;
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// Dev accounts created in genesis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevAccountsConfig {
+    /// Mnemonic the accounts are derived from
+    #[serde(default = "default_mnemonic")]
+    pub mnemonic: String,
+
+    /// Number of accounts to derive
+    #[serde(default = "default_count")]
+    pub count: u32,
+
+    /// Genesis balance of each account, in LATT
+    #[serde(default = "default_balance")]
+    pub balance_latt: u64,
+}
+
+fn default_mnemonic() -> String {
+    DEFAULT_DEV_MNEMONIC.to_string()
+}
+
+fn default_count() -> u32 {
+    10
+}
+
+fn default_balance() -> u64 {
+    10_000
+}
+
+impl Default for DevAccountsConfig {
+    fn default() -> Self {
+        Self {
+            mnemonic: default_mnemonic(),
+            count: default_count(),
+            balance_latt: default_balance(),
+        }
+    }
+}
+
+impl DevAccountsConfig {
+    /// Genesis balance of each account in wei
+    pub fn balance_wei(&self) -> u128 {
+        self.balance_latt as u128 * 1_000_000_000_000_000_000
+    }
+}
+
+/// A derived dev account
+#[derive(Debug, Clone)]
+pub struct DevAccount {
+    pub address: Address,
+    pub private_key: [u8; 32],
+}
+
+impl DevAccount {
+    /// Genesis key for this account: ECDSA accounts are keyed by their
+    /// 20-byte address followed by zeros
+    pub fn genesis_key(&self) -> PublicKey {
+        let mut key = [0u8; 32];
+        key[..20].copy_from_slice(&self.address.0);
+        PublicKey::new(key)
+    }
+}
+
+/// Derive the configured dev accounts
+pub fn derive_dev_accounts(config: &DevAccountsConfig) -> Result<Vec<DevAccount>> {
+    let mnemonic = Mnemonic::parse_in_normalized(Language::English, config.mnemonic.trim())
+        .map_err(|e| anyhow!("Invalid dev account mnemonic: {}", e))?;
+    let seed = mnemonic.to_seed("");
+
+    (0..config.count)
+        .map(|index| {
+            let path = [44 | HARDENED, 60 | HARDENED, HARDENED, 0, index];
+            let private_key = derive_secp256k1(&seed, &path)?;
+            Ok(DevAccount {
+                address: address_of(&private_key)?,
+                private_key,
+            })
+        })
+        .collect()
+}
+
+/// Genesis allocations for the configured dev accounts
+pub fn genesis_allocations(config: &DevAccountsConfig) -> Result<Vec<(PublicKey, u128)>> {
+    Ok(derive_dev_accounts(config)?
+        .iter()
+        .map(|account| (account.genesis_key(), config.balance_wei()))
+        .collect())
+}
+
+/// Print the accounts the way `hardhat node` does
+pub fn print_dev_accounts(accounts: &[DevAccount], config: &DevAccountsConfig) {
+    println!("Accounts");
+    println!("========");
+    println!();
+    println!("WARNING: These accounts, and their private keys, are publicly known.");
+    println!("Any funds sent to them on a public network WILL BE LOST.");
+    println!();
+    for (i, account) in accounts.iter().enumerate() {
+        println!(
+            "Account #{}: 0x{} ({} LATT)",
+            i,
+            hex::encode(account.address.0),
+            config.balance_latt
+        );
+        println!("Private Key: 0x{}", hex::encode(account.private_key));
+        println!();
+    }
+}
+
+/// BIP-32 private key derivation over secp256k1
+fn derive_secp256k1(seed: &[u8], path: &[u32]) -> Result<[u8; 32]> {
+    let (mut key, mut chain_code) = hmac_split(b"Bitcoin seed", &[seed]);
+
+    for &index in path {
+        let (tweak, next_chain_code) = if index & HARDENED != 0 {
+            hmac_split(&chain_code, &[&[0x00], &key, &index.to_be_bytes()])
+        } else {
+            let secret = SecretKey::from_slice(&key)?;
+            let point = secret.public_key().to_sec1_bytes();
+            hmac_split(&chain_code, &[&point, &index.to_be_bytes()])
+        };
+
+        let parent = Option::<Scalar>::from(Scalar::from_repr(key.into()))
+            .ok_or_else(|| anyhow!("Invalid parent key"))?;
+        let tweak = Option::<Scalar>::from(Scalar::from_repr(tweak.into()))
+            .ok_or_else(|| anyhow!("Derived key out of range"))?;
+        let child = parent + tweak;
+        if bool::from(child.is_zero()) {
+            return Err(anyhow!("Derived key is zero"));
+        }
+
+        key = child.to_bytes().into();
+        chain_code = next_chain_code;
+    }
+
+    Ok(key)
+}
+
+/// Ethereum address of a secp256k1 private key
+fn address_of(private_key: &[u8; 32]) -> Result<Address> {
+    let secret = SecretKey::from_slice(private_key)?;
+    let point = secret.public_key().to_encoded_point(false);
+    let hash = Keccak256::digest(&point.as_bytes()[1..]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    Ok(Address(address))
+}
+
+/// HMAC-SHA512 over `data`, split into (key, chain code)
+fn hmac_split(hmac_key: &[u8], data: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+    let mut mac =
+        <HmacSha512 as Mac>::new_from_slice(hmac_key).expect("HMAC accepts keys of any length");
+    for chunk in data {
+        mac.update(chunk);
+    }
+    let result = mac.finalize().into_bytes();
+
+    let mut key = [0u8; 32];
+    let mut chain_code = [0u8; 32];
+    key.copy_from_slice(&result[..32]);
+    chain_code.copy_from_slice(&result[32..]);
+    (key, chain_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_accounts_match_hardhat() {
+        let config = DevAccountsConfig {
+            count: 2,
+            ..Default::default()
+        };
+        let accounts = derive_dev_accounts(&config).unwrap();
+        assert_eq!(accounts.len(), 2);
+
+        assert_eq!(
+            hex::encode(accounts[0].address.0),
+            "f39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+        );
+        assert_eq!(
+            hex::encode(accounts[0].private_key),
+            "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+        );
+        assert_eq!(
+            hex::encode(accounts[1].address.0),
+            "70997970c51812dc3a010c7d01b50e0d17dc79c8"
+        );
+    }
+
+    #[test]
+    fn test_genesis_key_and_balance() {
+        let accounts = derive_dev_accounts(&DevAccountsConfig {
+            count: 1,
+            ..Default::default()
+        })
+        .unwrap();
+        let key = accounts[0].genesis_key();
+        assert_eq!(&key.0[..20], &accounts[0].address.0);
+        assert!(key.0[20..].iter().all(|b| *b == 0));
+
+        assert_eq!(
+            DevAccountsConfig::default().balance_wei(),
+            10_000 * 1_000_000_000_000_000_000
+        );
+        assert!(derive_dev_accounts(&DevAccountsConfig {
+            mnemonic: "not a mnemonic".into(),
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
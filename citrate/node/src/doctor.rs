@@ -0,0 +1,521 @@
+//! Node health self-diagnostics
+//!
+//! `citrate doctor` checks the things that most often keep a node from
+//! starting or syncing: ports already taken, a full disk, a drifting clock,
+//! an unreachable IPFS daemon or bootnodes, a damaged database and a missing
+//! GPU driver. Each check reports pass, warn, fail or skip with a hint on how
+//! to fix it.
+
+use crate::config::NodeConfig;
+use citrate_storage::{pruning::PruningConfig, StorageManager};
+use serde::Serialize;
+use std::net::{SocketAddr, TcpListener, UdpSocket};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinSet;
+
+/// Timeout for each network probe
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Free space below which the disk check warns or fails
+const DISK_WARN_BYTES: u64 = 10 << 30;
+const DISK_FAIL_BYTES: u64 = 1 << 30;
+
+/// Clock offsets at which the clock check warns or fails. Peers drop blocks
+/// stamped more than 15 minutes ahead; block production needs far less.
+const CLOCK_WARN_SECS: f64 = 2.0;
+const CLOCK_FAIL_SECS: f64 = 60.0;
+
+const NTP_SERVER: &str = "pool.ntp.org:123";
+
+/// Blocks below the tip read back by the database scan
+const DB_SCAN_BLOCKS: u64 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Skip,
+    Warn,
+    Fail,
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// How to fix a warning or failure
+    pub remediation: Option<String>,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+            remediation: None,
+        }
+    }
+
+    fn hint(mut self, remediation: impl Into<String>) -> Self {
+        self.remediation = Some(remediation.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    pub generated_at: String,
+    pub checks: Vec<Check>,
+}
+
+impl DoctorReport {
+    pub fn failures(&self) -> usize {
+        self.checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Fail)
+            .count()
+    }
+
+    pub fn print(&self) {
+        println!("Node diagnostics");
+        for check in &self.checks {
+            let mark = match check.status {
+                CheckStatus::Pass => "[ok]  ",
+                CheckStatus::Skip => "[skip]",
+                CheckStatus::Warn => "[warn]",
+                CheckStatus::Fail => "[FAIL]",
+            };
+            println!("  {} {:<18} {}", mark, check.name, check.detail);
+            if let Some(remediation) = &check.remediation {
+                println!("         {:<18} -> {}", "", remediation);
+            }
+        }
+    }
+}
+
+/// Run every check against `config`
+pub async fn run(config: &NodeConfig) -> DoctorReport {
+    let mut checks = check_ports(config);
+    checks.push(check_disk_space(&config.storage.data_dir));
+    checks.push(
+        tokio::task::spawn_blocking(check_clock)
+            .await
+            .unwrap_or_else(|e| Check::new("Clock", CheckStatus::Skip, e.to_string())),
+    );
+    checks.push(check_ipfs(&config.validator.ipfs_api_url).await);
+    checks.push(check_bootnodes(&config.network.bootstrap_nodes).await);
+    checks.push(check_database(&config.storage.data_dir));
+    checks.push(check_gpu_driver());
+
+    DoctorReport {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        checks,
+    }
+}
+
+fn check_ports(config: &NodeConfig) -> Vec<Check> {
+    let mut ports = vec![(
+        "P2P port",
+        config.network.listen_addr,
+        "network.listen_addr",
+    )];
+    if config.rpc.enabled {
+        ports.push(("RPC port", config.rpc.listen_addr, "rpc.listen_addr"));
+        ports.push(("WebSocket port", config.rpc.ws_addr, "rpc.ws_addr"));
+    }
+    if config.ipfs_gateway.enabled {
+        if let Ok(addr) = config.ipfs_gateway.listen_addr.parse::<SocketAddr>() {
+            ports.push(("IPFS gateway port", addr, "ipfs_gateway.listen_addr"));
+        }
+    }
+
+    ports
+        .into_iter()
+        .map(|(name, addr, setting)| match TcpListener::bind(addr) {
+            Ok(_) => Check::new(name, CheckStatus::Pass, format!("{} is free", addr)),
+            Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => Check::new(
+                name,
+                CheckStatus::Fail,
+                format!("{} is already in use", addr),
+            )
+            .hint(format!(
+                "Expected if this node is running; otherwise stop the process using port {} or change {}",
+                addr.port(),
+                setting
+            )),
+            Err(e) => Check::new(
+                name,
+                CheckStatus::Fail,
+                format!("Can't bind {}: {}", addr, e),
+            )
+            .hint(format!("Use an address of this machine in {}", setting)),
+        })
+        .collect()
+}
+
+fn check_disk_space(data_dir: &Path) -> Check {
+    const NAME: &str = "Disk space";
+    // The data dir may not exist before the first run
+    let Some(existing) = data_dir.ancestors().find(|p| p.exists()) else {
+        return Check::new(NAME, CheckStatus::Skip, "No existing directory to check");
+    };
+    let available = match available_bytes(existing) {
+        Ok(bytes) => bytes,
+        Err(e) => return Check::new(NAME, CheckStatus::Skip, e),
+    };
+
+    let detail = format!(
+        "{:.1} GiB free for {}",
+        available as f64 / (1u64 << 30) as f64,
+        data_dir.display()
+    );
+    if available < DISK_FAIL_BYTES {
+        Check::new(NAME, CheckStatus::Fail, detail)
+            .hint("Free up space or move storage.data_dir to a larger disk")
+    } else if available < DISK_WARN_BYTES {
+        Check::new(NAME, CheckStatus::Warn, detail)
+            .hint("Enable storage.pruning or free up space before the chain grows")
+    } else {
+        Check::new(NAME, CheckStatus::Pass, detail)
+    }
+}
+
+#[cfg(unix)]
+fn available_bytes(path: &Path) -> Result<u64, String> {
+    let output = std::process::Command::new("df")
+        .arg("-Pk")
+        .arg(path)
+        .output()
+        .map_err(|e| format!("Can't run df: {}", e))?;
+    parse_df_available(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| "Can't read df output".to_string())
+}
+
+#[cfg(not(unix))]
+fn available_bytes(_path: &Path) -> Result<u64, String> {
+    Err("Not supported on this platform".to_string())
+}
+
+/// Available bytes from POSIX `df -Pk` output
+fn parse_df_available(output: &str) -> Option<u64> {
+    let line = output.lines().nth(1)?;
+    let kib: u64 = line.split_whitespace().nth(3)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+fn check_clock() -> Check {
+    const NAME: &str = "Clock";
+    let offset = match sntp_offset(NTP_SERVER) {
+        Ok(offset) => offset,
+        Err(e) => {
+            return Check::new(
+                NAME,
+                CheckStatus::Skip,
+                format!("Couldn't reach {}: {}", NTP_SERVER, e),
+            )
+        }
+    };
+
+    let detail = format!(
+        "{:.2}s {} {}",
+        offset.abs(),
+        if offset >= 0.0 { "ahead of" } else { "behind" },
+        NTP_SERVER
+    );
+    let hint = "Enable time synchronisation (e.g. systemd-timesyncd, chrony or ntpd)";
+    if offset.abs() >= CLOCK_FAIL_SECS {
+        Check::new(NAME, CheckStatus::Fail, detail).hint(hint)
+    } else if offset.abs() >= CLOCK_WARN_SECS {
+        Check::new(NAME, CheckStatus::Warn, detail).hint(hint)
+    } else {
+        Check::new(NAME, CheckStatus::Pass, detail)
+    }
+}
+
+/// Seconds the local clock is ahead of an SNTP server
+fn sntp_offset(server: &str) -> std::io::Result<f64> {
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    socket.set_read_timeout(Some(PROBE_TIMEOUT))?;
+    socket.connect(server)?;
+
+    // Leap indicator 0, version 3, client mode
+    let mut packet = [0u8; 48];
+    packet[0] = 0x1b;
+    let sent = unix_now();
+    socket.send(&packet)?;
+    let len = socket.recv(&mut packet)?;
+    let received = unix_now();
+    if len < 48 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "short SNTP reply",
+        ));
+    }
+
+    // Transmit timestamp of the reply, against the midpoint of the round trip
+    let server_time = ntp_to_unix(&packet[40..48]);
+    Ok((sent + received) / 2.0 - server_time)
+}
+
+/// Convert a 64-bit NTP timestamp (seconds since 1900) to Unix seconds
+fn ntp_to_unix(bytes: &[u8]) -> f64 {
+    const NTP_UNIX_OFFSET: f64 = 2_208_988_800.0;
+    let seconds = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64;
+    let fraction = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as f64;
+    seconds - NTP_UNIX_OFFSET + fraction / 4_294_967_296.0
+}
+
+fn unix_now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+async fn check_ipfs(api_url: &str) -> Check {
+    const NAME: &str = "IPFS daemon";
+    let url = format!("{}/api/v0/version", api_url.trim_end_matches('/'));
+    let client = match reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => return Check::new(NAME, CheckStatus::Skip, e.to_string()),
+    };
+
+    let version = match client.post(&url).send().await {
+        Ok(response) if response.status().is_success() => response
+            .json::<serde_json::Value>()
+            .await
+            .ok()
+            .and_then(|v| {
+                v.get("Version")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            }),
+        Ok(response) => return Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("{} answered {}", api_url, response.status()),
+        )
+        .hint(
+            "Check that validator.ipfs_api_url points at the daemon's API port, not its gateway",
+        ),
+        Err(e) => {
+            return Check::new(
+                NAME,
+                CheckStatus::Fail,
+                format!("Can't reach {}: {}", api_url, e),
+            )
+            .hint("Start the daemon with `ipfs daemon` or set validator.ipfs_api_url")
+        }
+    };
+    Check::new(
+        NAME,
+        CheckStatus::Pass,
+        format!(
+            "{} at {}",
+            version.unwrap_or_else(|| "Running".to_string()),
+            api_url
+        ),
+    )
+}
+
+/// `ip:port` part of a bootnode entry (`peer_id@ip:port` or `ip:port`)
+fn bootnode_address(entry: &str) -> &str {
+    entry.rsplit_once('@').map_or(entry, |(_, addr)| addr)
+}
+
+async fn check_bootnodes(bootnodes: &[String]) -> Check {
+    const NAME: &str = "Bootnodes";
+    if bootnodes.is_empty() {
+        return Check::new(NAME, CheckStatus::Skip, "No bootstrap nodes configured");
+    }
+
+    let mut probes = JoinSet::new();
+    for entry in bootnodes {
+        let addr = bootnode_address(entry).to_string();
+        probes.spawn(async move {
+            let connected =
+                tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect(&addr)).await;
+            (addr, matches!(connected, Ok(Ok(_))))
+        });
+    }
+    let mut unreachable = Vec::new();
+    while let Some(probe) = probes.join_next().await {
+        if let Ok((addr, false)) = probe {
+            unreachable.push(addr);
+        }
+    }
+    unreachable.sort();
+
+    let reachable = bootnodes.len() - unreachable.len();
+    let detail = format!("{} of {} reachable", reachable, bootnodes.len());
+    if unreachable.is_empty() {
+        return Check::new(NAME, CheckStatus::Pass, detail);
+    }
+    let detail = format!("{}; unreachable: {}", detail, unreachable.join(", "));
+    let status = if reachable == 0 {
+        CheckStatus::Fail
+    } else {
+        CheckStatus::Warn
+    };
+    Check::new(NAME, status, detail)
+        .hint("Check the firewall allows outbound connections, or update network.bootstrap_nodes")
+}
+
+fn check_database(data_dir: &Path) -> Check {
+    const NAME: &str = "Database";
+    if !data_dir.join("CURRENT").exists() {
+        return Check::new(
+            NAME,
+            CheckStatus::Skip,
+            "No database yet; it is created on first start",
+        );
+    }
+
+    let storage = match StorageManager::new(data_dir, PruningConfig::default()) {
+        Ok(storage) => storage,
+        Err(e) if e.to_string().contains("lock") => {
+            return Check::new(
+                NAME,
+                CheckStatus::Skip,
+                "In use by a running node; stop it to scan the database",
+            )
+        }
+        Err(e) => {
+            return Check::new(NAME, CheckStatus::Fail, format!("Failed to open: {}", e)).hint(
+                "Restore the data dir from a backup, or run `citrate purge --chain` and resync",
+            )
+        }
+    };
+
+    let tip = match storage.blocks.get_latest_height() {
+        Ok(tip) => tip,
+        Err(e) => {
+            return Check::new(
+                NAME,
+                CheckStatus::Fail,
+                format!("Can't read the tip: {}", e),
+            )
+            .hint("Run `citrate purge --chain` and resync")
+        }
+    };
+    let mut problems = Vec::new();
+    let start = tip.saturating_sub(DB_SCAN_BLOCKS);
+    for height in start..=tip {
+        let problem = match storage.blocks.get_block_by_height(height) {
+            Ok(Some(hash)) => match storage.blocks.get_block(&hash) {
+                Ok(Some(block)) if block.header.height == height && block.hash() == hash => None,
+                Ok(Some(_)) => Some(format!(
+                    "block at height {} doesn't match its index",
+                    height
+                )),
+                Ok(None) => Some(format!("block at height {} is missing", height)),
+                Err(e) => Some(format!("block at height {} is unreadable: {}", height, e)),
+            },
+            Ok(None) => None,
+            Err(e) => Some(format!("height index {} is unreadable: {}", height, e)),
+        };
+        problems.extend(problem);
+    }
+
+    if problems.is_empty() {
+        Check::new(
+            NAME,
+            CheckStatus::Pass,
+            format!("Blocks {} to {} read back intact", start, tip),
+        )
+    } else {
+        Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("{} problem(s): {}", problems.len(), problems[0]),
+        )
+        .hint("Restore the data dir from a backup, or run `citrate purge --chain` and resync")
+    }
+}
+
+fn check_gpu_driver() -> Check {
+    const NAME: &str = "GPU driver";
+    if cfg!(target_os = "macos") {
+        return Check::new(NAME, CheckStatus::Pass, "Metal (built into macOS)");
+    }
+
+    match std::process::Command::new("nvidia-smi")
+        .args(["--query-gpu=name,driver_version", "--format=csv,noheader"])
+        .output()
+    {
+        Ok(output) if output.status.success() => {
+            let gpus: Vec<String> = String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(|line| line.trim().replace(", ", " driver "))
+                .filter(|line| !line.is_empty())
+                .collect();
+            Check::new(NAME, CheckStatus::Pass, gpus.join("; "))
+        }
+        Ok(output) => Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!(
+                "nvidia-smi failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        )
+        .hint("Reboot after a driver update, or reinstall the NVIDIA driver"),
+        Err(_) => match std::process::Command::new("rocm-smi")
+            .arg("--showdriverversion")
+            .output()
+        {
+            Ok(output) if output.status.success() => {
+                Check::new(NAME, CheckStatus::Pass, "AMD ROCm driver installed")
+            }
+            _ => Check::new(
+                NAME,
+                CheckStatus::Warn,
+                "No GPU driver found; inference runs on the CPU",
+            )
+            .hint("Install the NVIDIA or ROCm driver if this machine has a GPU"),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_df_available() {
+        let output = "Filesystem     1024-blocks      Used Available Capacity Mounted on\n\
+                      /dev/nvme0n1p2   490617784 123456789 342000000      27% /\n";
+        assert_eq!(parse_df_available(output), Some(342_000_000 * 1024));
+        assert_eq!(parse_df_available(""), None);
+    }
+
+    #[test]
+    fn test_ntp_to_unix_and_bootnode_address() {
+        // 2024-01-01T00:00:00Z and a half second
+        let seconds = (1_704_067_200u64 + 2_208_988_800) as u32;
+        let mut bytes = seconds.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&(1u32 << 31).to_be_bytes());
+        assert_eq!(ntp_to_unix(&bytes), 1_704_067_200.5);
+
+        assert_eq!(bootnode_address("12D3Koo@10.0.0.1:30303"), "10.0.0.1:30303");
+        assert_eq!(bootnode_address("10.0.0.1:30303"), "10.0.0.1:30303");
+    }
+
+    #[test]
+    fn test_port_in_use_fails() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut config = NodeConfig::default();
+        config.network.listen_addr = listener.local_addr().unwrap();
+        config.rpc.enabled = false;
+        config.ipfs_gateway.enabled = false;
+
+        let checks = check_ports(&config);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, CheckStatus::Fail);
+        assert!(checks[0].remediation.is_some());
+    }
+}
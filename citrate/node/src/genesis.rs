@@ -10,6 +10,7 @@ use citrate_execution::types::{
     AccessPolicy, Address, ModelId, ModelMetadata, ModelState, UsageStats,
 };
 use citrate_storage::StorageManager;
+use crate::genesis_models::GenesisModels;
 use primitive_types::U256;
 use sha3::{Digest, Sha3_256};
 use std::sync::Arc;
@@ -48,6 +49,8 @@ pub struct GenesisConfig {
     pub chain_id: u64,
     pub timestamp: u64,
     pub initial_accounts: Vec<(PublicKey, u128)>, // (address, balance)
+    /// Genesis model set from a manifest; `None` uses the built-in models
+    pub models: Option<GenesisModels>,
 }
 
 impl Default for GenesisConfig {
@@ -77,6 +80,7 @@ impl Default for GenesisConfig {
                     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
                 ]), 100_000_000_000_000_000_000), // 100 ETH for testing
             ],
+            models: None,
         }
     }
 }
@@ -146,13 +150,18 @@ pub fn create_genesis_block(config: &GenesisConfig) -> Block {
         base_fee_per_gas: 1_000_000_000, // 1 gwei initial base fee
         gas_used: 0,                      // No transactions in genesis
         gas_limit: 30_000_000,            // 30M gas limit
+        epoch_commitment: Hash::default(), // No epochs signed yet
     };
 
-    // Create embedded models for genesis
-    let embedded_models = vec![create_embedded_bge_m3()];
-
-    // Create required pin models (validators must pin these)
-    let required_pins = vec![create_required_mistral_7b()];
+    // Use the curated model set when one was loaded, otherwise the built-ins:
+    // embedded models live in the block, required pins must be kept by validators
+    let (embedded_models, required_pins) = match &config.models {
+        Some(models) => (models.embedded_models.clone(), models.required_pins.clone()),
+        None => (
+            vec![create_embedded_bge_m3()],
+            vec![create_required_mistral_7b()],
+        ),
+    };
 
     tracing::info!("Creating genesis block with {} embedded models ({} MB total)",
         embedded_models.len(),
@@ -0,0 +1,427 @@
+// citrate/node/src/genesis_models.rs
+
+//! Genesis model manifest
+//!
+//! Private networks can curate the AI models placed in their genesis block
+//! with `citrate genesis add-model` instead of editing `genesis.rs`. Each call
+//! hashes a local model file, uploads it to IPFS and records an entry in a
+//! JSON manifest. Point `chain.genesis_models` in the node config at the
+//! manifest before the chain is initialized.
+//!
+//! Embedded models are stored inside the genesis block, so they are size
+//! limited; their weights are read from `path` (relative to the manifest)
+//! and checked against the recorded hash. Required pins only carry the CID
+//! and hash, and validators must pin the file.
+
+use anyhow::{anyhow, bail, Context, Result};
+use citrate_consensus::types::{
+    EmbeddedModel, Hash, ModelId, ModelMetadata, ModelType, RequiredModel,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Manifest file name used when none is given
+pub const DEFAULT_MANIFEST: &str = "genesis-models.json";
+
+/// Largest model that may be embedded in the genesis block
+pub const MAX_EMBEDDED_MODEL_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Largest total size of all embedded models
+pub const MAX_EMBEDDED_TOTAL_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Largest model validators can be required to pin
+pub const MAX_PINNED_MODEL_BYTES: u64 = 64 * 1024 * 1024 * 1024;
+
+/// Licenses accepted without `--allow-custom-license`
+pub const KNOWN_LICENSES: &[&str] = &[
+    "MIT",
+    "Apache-2.0",
+    "BSD-2-Clause",
+    "BSD-3-Clause",
+    "CC-BY-4.0",
+    "CC-BY-SA-4.0",
+    "CC0-1.0",
+    "OpenRAIL-M",
+    "Llama-3.1",
+    "Llama-3.2",
+    "Gemma",
+];
+
+/// Models placed in the genesis block
+#[derive(Debug, Clone, Default)]
+pub struct GenesisModels {
+    pub embedded_models: Vec<EmbeddedModel>,
+    pub required_pins: Vec<RequiredModel>,
+}
+
+/// On-disk list of genesis models
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenesisModelManifest {
+    #[serde(default)]
+    pub embedded_models: Vec<EmbeddedModelEntry>,
+    #[serde(default)]
+    pub required_pins: Vec<RequiredPinEntry>,
+}
+
+/// Model whose weights go into the genesis block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddedModelEntry {
+    pub model_id: String,
+    pub model_type: ModelType,
+    /// Weights file, relative to the manifest
+    pub path: PathBuf,
+    /// SHA-256 of the weights, hex
+    pub sha256: String,
+    pub size_bytes: u64,
+    /// IPFS copy of the weights, if uploaded
+    #[serde(default)]
+    pub ipfs_cid: Option<String>,
+    pub metadata: ModelMetadata,
+}
+
+/// Model validators must keep pinned on IPFS
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequiredPinEntry {
+    pub model_id: String,
+    pub model_type: ModelType,
+    pub ipfs_cid: String,
+    /// SHA-256 of the model file, hex
+    pub sha256: String,
+    pub size_bytes: u64,
+    /// Penalty for not pinning, in wei (decimal string)
+    pub slash_penalty: String,
+    pub grace_period_hours: u64,
+    pub metadata: ModelMetadata,
+}
+
+impl GenesisModelManifest {
+    /// Read a manifest, or start an empty one if the file does not exist
+    pub fn load_or_default(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::load(path)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read genesis model manifest {:?}", path))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse genesis model manifest {:?}", path))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")
+            .with_context(|| format!("Failed to write genesis model manifest {:?}", path))
+    }
+
+    /// Add an embedded model, replacing any entry with the same ID
+    pub fn upsert_embedded(&mut self, entry: EmbeddedModelEntry) -> Result<()> {
+        self.remove(&entry.model_id);
+        let total = self.embedded_total_bytes() + entry.size_bytes;
+        if total > MAX_EMBEDDED_TOTAL_BYTES {
+            bail!(
+                "Embedded models would total {} MB, above the {} MB limit; add it as a required pin instead",
+                total / 1_000_000,
+                MAX_EMBEDDED_TOTAL_BYTES / 1_000_000
+            );
+        }
+        self.embedded_models.push(entry);
+        Ok(())
+    }
+
+    /// Add a required pin, replacing any entry with the same ID
+    pub fn upsert_pin(&mut self, entry: RequiredPinEntry) {
+        self.remove(&entry.model_id);
+        self.required_pins.push(entry);
+    }
+
+    /// Remove a model from either list; returns whether it was present
+    pub fn remove(&mut self, model_id: &str) -> bool {
+        let before = self.embedded_models.len() + self.required_pins.len();
+        self.embedded_models.retain(|m| m.model_id != model_id);
+        self.required_pins.retain(|m| m.model_id != model_id);
+        before != self.embedded_models.len() + self.required_pins.len()
+    }
+
+    pub fn embedded_total_bytes(&self) -> u64 {
+        self.embedded_models.iter().map(|m| m.size_bytes).sum()
+    }
+
+    /// Build the genesis entries, reading and verifying embedded weights.
+    /// Relative weight paths are resolved against `base_dir`.
+    pub fn to_genesis_models(&self, base_dir: &Path) -> Result<GenesisModels> {
+        let mut models = GenesisModels::default();
+
+        for entry in &self.embedded_models {
+            let path = base_dir.join(&entry.path);
+            let weights = std::fs::read(&path)
+                .with_context(|| format!("Failed to read weights for {} from {:?}", entry.model_id, path))?;
+            if weights.len() as u64 != entry.size_bytes {
+                bail!(
+                    "Weights for {} are {} bytes, manifest says {}",
+                    entry.model_id,
+                    weights.len(),
+                    entry.size_bytes
+                );
+            }
+            let model = EmbeddedModel {
+                model_id: ModelId::from_name(&entry.model_id),
+                model_type: entry.model_type,
+                weights,
+                metadata: entry.metadata.clone(),
+            };
+            if hex::encode(model.weights_hash().as_bytes()) != entry.sha256.to_lowercase() {
+                bail!("Weights for {} do not match the manifest hash", entry.model_id);
+            }
+            models.embedded_models.push(model);
+        }
+
+        for entry in &self.required_pins {
+            let slash_penalty = entry
+                .slash_penalty
+                .parse::<u128>()
+                .with_context(|| format!("Invalid slash penalty for {}", entry.model_id))?;
+            let mut model = RequiredModel::new(
+                ModelId::from_name(&entry.model_id),
+                entry.ipfs_cid.clone(),
+                parse_sha256(&entry.sha256)?,
+                entry.size_bytes,
+                slash_penalty,
+            );
+            model.grace_period_hours = entry.grace_period_hours;
+            models.required_pins.push(model);
+        }
+
+        Ok(models)
+    }
+}
+
+/// Load the genesis models listed in the manifest at `path`
+pub fn load_genesis_models(path: &Path) -> Result<GenesisModels> {
+    let manifest = GenesisModelManifest::load(path)?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let models = manifest.to_genesis_models(base_dir)?;
+    info!(
+        "Loaded {} embedded models and {} required pins from {:?}",
+        models.embedded_models.len(),
+        models.required_pins.len(),
+        path
+    );
+    Ok(models)
+}
+
+/// Parse a model type name such as `embeddings` or `general-llm`
+pub fn parse_model_type(s: &str) -> Result<ModelType, String> {
+    match s.to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+        "embeddings" | "embedding" => Ok(ModelType::Embeddings),
+        "tinyllm" => Ok(ModelType::TinyLLM),
+        "generalllm" | "llm" => Ok(ModelType::GeneralLLM),
+        "codellm" => Ok(ModelType::CodeLLM),
+        "visionllm" => Ok(ModelType::VisionLLM),
+        "diffusion" => Ok(ModelType::Diffusion),
+        _ => Err(format!(
+            "unknown model type '{}' (expected embeddings, tiny-llm, general-llm, code-llm, vision-llm or diffusion)",
+            s
+        )),
+    }
+}
+
+/// Check a license against [`KNOWN_LICENSES`]
+pub fn check_license(license: &str, allow_custom: bool) -> Result<()> {
+    if license.trim().is_empty() {
+        bail!("A license is required for genesis models");
+    }
+    if !allow_custom && !KNOWN_LICENSES.iter().any(|l| l.eq_ignore_ascii_case(license)) {
+        bail!(
+            "Unrecognized license '{}'; use one of {} or pass --allow-custom-license",
+            license,
+            KNOWN_LICENSES.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Check a model's size against the limit for how it is included
+pub fn check_size(size_bytes: u64, embed: bool) -> Result<()> {
+    let limit = if embed {
+        MAX_EMBEDDED_MODEL_BYTES
+    } else {
+        MAX_PINNED_MODEL_BYTES
+    };
+    if size_bytes == 0 {
+        bail!("Model file is empty");
+    }
+    if size_bytes > limit {
+        bail!(
+            "Model is {} MB, above the {} MB limit for {}",
+            size_bytes / 1_000_000,
+            limit / 1_000_000,
+            if embed { "embedded models" } else { "required pins" }
+        );
+    }
+    Ok(())
+}
+
+/// SHA-256 and size of a file, read in chunks
+pub fn hash_file(path: &Path) -> Result<(Hash, u64)> {
+    let mut file =
+        std::fs::File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1024 * 1024];
+    let mut size = 0u64;
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        size += n as u64;
+    }
+    Ok((Hash::from_bytes(&hasher.finalize()), size))
+}
+
+/// Add a file to IPFS through the HTTP API and pin it; returns the CID
+pub async fn upload_to_ipfs(api: &str, path: &Path) -> Result<String> {
+    let file = tokio::fs::File::open(path)
+        .await
+        .with_context(|| format!("Failed to open {:?}", path))?;
+    let size = file.metadata().await?.len();
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "model.bin".to_string());
+
+    let part = reqwest::multipart::Part::stream_with_length(reqwest::Body::from(file), size)
+        .file_name(file_name);
+    let form = reqwest::multipart::Form::new().part("file", part);
+    let url = format!("{}/api/v0/add?pin=true", api.trim_end_matches('/'));
+    let resp = reqwest::Client::new()
+        .post(&url)
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|e| anyhow!("IPFS add failed ({}): {}", api, e))?;
+    if !resp.status().is_success() {
+        bail!("IPFS add returned {}", resp.status());
+    }
+    let json: serde_json::Value = resp.json().await?;
+    json["Hash"]
+        .as_str()
+        .filter(|cid| !cid.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("IPFS add returned no CID"))
+}
+
+fn parse_sha256(s: &str) -> Result<Hash> {
+    let bytes = hex::decode(s.trim_start_matches("0x"))?;
+    if bytes.len() != 32 {
+        bail!("SHA-256 must be 32 bytes, got {}", bytes.len());
+    }
+    Ok(Hash::from_bytes(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> ModelMetadata {
+        ModelMetadata {
+            name: "Test".to_string(),
+            version: "1.0.0".to_string(),
+            context_length: 512,
+            embedding_dim: Some(8),
+            license: "MIT".to_string(),
+            framework: Some("GGUF".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_manifest_roundtrip_verifies_weights() {
+        let dir = tempfile::tempdir().unwrap();
+        let weights_path = dir.path().join("tiny.gguf");
+        std::fs::write(&weights_path, b"weights").unwrap();
+        let (hash, size) = hash_file(&weights_path).unwrap();
+
+        let mut manifest = GenesisModelManifest::default();
+        manifest
+            .upsert_embedded(EmbeddedModelEntry {
+                model_id: "tiny".to_string(),
+                model_type: ModelType::Embeddings,
+                path: PathBuf::from("tiny.gguf"),
+                sha256: hex::encode(hash.as_bytes()),
+                size_bytes: size,
+                ipfs_cid: None,
+                metadata: metadata(),
+            })
+            .unwrap();
+        manifest.upsert_pin(RequiredPinEntry {
+            model_id: "big".to_string(),
+            model_type: ModelType::GeneralLLM,
+            ipfs_cid: "QmTest".to_string(),
+            sha256: hex::encode([7u8; 32]),
+            size_bytes: 4_000_000_000,
+            slash_penalty: "1000000000000000000000".to_string(),
+            grace_period_hours: 48,
+            metadata: metadata(),
+        });
+        let manifest_path = dir.path().join(DEFAULT_MANIFEST);
+        manifest.save(&manifest_path).unwrap();
+
+        let models = load_genesis_models(&manifest_path).unwrap();
+        assert_eq!(models.embedded_models[0].weights, b"weights");
+        assert_eq!(models.required_pins[0].grace_period_hours, 48);
+        assert_eq!(
+            models.required_pins[0].slash_penalty,
+            1_000_000_000_000_000_000_000
+        );
+
+        // Tampered weights are rejected
+        std::fs::write(&weights_path, b"WEIGHTS").unwrap();
+        assert!(load_genesis_models(&manifest_path).is_err());
+    }
+
+    #[test]
+    fn test_upsert_replaces_existing_entry() {
+        let mut manifest = GenesisModelManifest::default();
+        let pin = RequiredPinEntry {
+            model_id: "m".to_string(),
+            model_type: ModelType::GeneralLLM,
+            ipfs_cid: "QmOld".to_string(),
+            sha256: hex::encode([1u8; 32]),
+            size_bytes: 1,
+            slash_penalty: "0".to_string(),
+            grace_period_hours: 24,
+            metadata: metadata(),
+        };
+        manifest.upsert_pin(pin.clone());
+        manifest.upsert_pin(RequiredPinEntry {
+            ipfs_cid: "QmNew".to_string(),
+            ..pin
+        });
+        assert_eq!(manifest.required_pins.len(), 1);
+        assert_eq!(manifest.required_pins[0].ipfs_cid, "QmNew");
+    }
+
+    #[test]
+    fn test_size_and_license_checks() {
+        assert!(check_size(0, false).is_err());
+        assert!(check_size(MAX_EMBEDDED_MODEL_BYTES, true).is_ok());
+        assert!(check_size(MAX_EMBEDDED_MODEL_BYTES + 1, true).is_err());
+        assert!(check_size(MAX_EMBEDDED_MODEL_BYTES + 1, false).is_ok());
+
+        assert!(check_license("apache-2.0", false).is_ok());
+        assert!(check_license("Proprietary", false).is_err());
+        assert!(check_license("Proprietary", true).is_ok());
+        assert!(check_license(" ", true).is_err());
+
+        assert_eq!(parse_model_type("general-llm").unwrap(), ModelType::GeneralLLM);
+        assert!(parse_model_type("unknown").is_err());
+    }
+}
@@ -32,27 +32,42 @@ impl InferenceService for NodeInferenceService {
     {
         // Convert execution ModelId(Hash) to MCP ModelId([u8;32])
         let mcp_model_id = citrate_mcp::types::ModelId::from_hash(&model_id.0);
+        // Registered providers are held to the latency SLO and failed over;
+        // otherwise the model runs locally as this node's provider
         let result = self
             .mcp
-            .executor
-            .execute_inference(mcp_model_id, input, self.provider)
+            .execute_inference_with_failover(mcp_model_id, input, self.provider)
             .await
             .map_err(|e| citrate_execution::ExecutionError::Reverted(e.to_string()))?;
 
+        // The receipt lists providers the job was moved off and why
+        let failovers: Vec<_> = result
+            .failovers
+            .iter()
+            .map(|f| {
+                serde_json::json!({
+                    "provider": hex::encode(f.provider.0),
+                    "reason": f.reason,
+                    "elapsed_ms": f.elapsed_ms,
+                })
+            })
+            .collect();
         let proof_bytes = serde_json::to_vec(&serde_json::json!({
             "model_hash": hex::encode(result.proof.model_hash.as_bytes()),
             "input_hash": hex::encode(result.proof.input_hash.as_bytes()),
             "output_hash": hex::encode(result.proof.output_hash.as_bytes()),
             "io_commitment": hex::encode(result.proof.io_commitment.as_bytes()),
-            "provider": hex::encode(self.provider.0),
+            "provider": hex::encode(result.provider.0),
             "timestamp": result.proof.timestamp,
+            "latency_ms": result.latency_ms,
+            "failovers": failovers,
         }))
         .ok();
 
         Ok((
             result.output,
             result.gas_used,
-            self.provider,
+            result.provider,
             self.provider_fee_wei,
             proof_bytes,
         ))
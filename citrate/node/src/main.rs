@@ -1,12 +1,14 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
-use citrate_api::{RpcConfig, RpcServer};
+use citrate_api::{RateLimit, RpcConfig, RpcServer};
 use citrate_consensus::crypto;
 use citrate_execution::{Executor, StateDB};
 use citrate_economics::{UnifiedEconomicsManager, UnifiedEconomicsConfig, StakeholderType};
 use citrate_network::peer::PeerId;
 use citrate_network::peer::{PeerManager, PeerManagerConfig};
+use citrate_network::ReputationManager;
 use citrate_network::{NetworkTransport, GossipProtocol, GossipConfig, Discovery, DiscoveryConfig, SyncManager, SyncConfig};
+use citrate_network::{SyncPipeline, SyncPipelineConfig};
 use citrate_sequencer::mempool::{Mempool, MempoolConfig};
 use citrate_storage::{pruning::PruningConfig, StorageManager};
 use std::path::PathBuf;
@@ -17,15 +19,23 @@ use tracing_subscriber::EnvFilter;
 
 mod adapters;
 mod artifact;
+mod audit;
+mod bench;
 mod config;
+mod dev_accounts;
+mod doctor;
 mod genesis;
+mod genesis_models;
 mod inference;
 pub mod logging;
 pub mod metrics;
 mod model_manager;
 mod model_verifier;
 mod producer;
+mod purge;
 mod sync;
+mod telemetry;
+mod webhooks;
 
 use config::NodeConfig;
 use genesis::{initialize_genesis_state, GenesisConfig};
@@ -61,17 +71,22 @@ struct Cli {
     rpc_addr: Option<String>,
 
     /// Maximum number of peers
-    #[arg(long, default_value = "50")]
-    max_peers: usize,
+    #[arg(long)]
+    max_peers: Option<usize>,
 
     /// Chain ID
-    #[arg(long, default_value = "1337")]
-    chain_id: u64,
+    #[arg(long)]
+    chain_id: Option<u64>,
 
     /// Coinbase address for mining rewards (hex)
     #[arg(long)]
     coinbase: Option<String>,
 
+    /// Remote signer holding the block signing key
+    /// (unix:///path/to/signer.ipc or http://host:port)
+    #[arg(long, value_name = "ENDPOINT")]
+    remote_signer: Option<String>,
+
     /// Disable RPC server
     #[arg(long)]
     no_rpc: bool,
@@ -80,6 +95,11 @@ struct Cli {
     #[arg(long)]
     bootstrap: bool,
 
+    /// Print the effective configuration (file, then CITRATE_<SECTION>__<FIELD>
+    /// environment overrides, then flags) as TOML and exit
+    #[arg(long)]
+    print_config: bool,
+
     /// Subcommands
     #[command(subcommand)]
     command: Option<Commands>,
@@ -100,6 +120,22 @@ enum Commands {
     /// Generate a new keypair for signing
     Keygen,
 
+    /// Issue an API key for the RPC and REST servers and print the entry to
+    /// add to the node config
+    ApiKey {
+        /// Label to recognize the key by
+        #[arg(long, default_value = "")]
+        label: String,
+
+        /// Requests allowed per window, instead of `rpc.access.per_key`
+        #[arg(long)]
+        requests: Option<u32>,
+
+        /// Rate limit window in seconds
+        #[arg(long, default_value = "60")]
+        window_secs: u64,
+    },
+
     /// Manage AI models (download, pin, list)
     Model {
         #[command(subcommand)]
@@ -107,7 +143,74 @@ enum Commands {
     },
 
     /// Show genesis block information
-    GenesisInfo,
+    GenesisInfo {
+        /// Genesis model manifest to use instead of the built-in models
+        #[arg(long, value_name = "FILE")]
+        models: Option<PathBuf>,
+    },
+
+    /// Curate the genesis model set
+    Genesis {
+        #[command(subcommand)]
+        command: GenesisCommands,
+    },
+
+    /// Run node benchmarks and print a hardware score
+    Bench {
+        /// Run a reduced workload
+        #[arg(long)]
+        quick: bool,
+
+        /// Only run these suites (can be specified multiple times)
+        #[arg(long, value_enum)]
+        only: Vec<bench::BenchSuite>,
+
+        /// Print results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Export signed state archives for audits
+    Audit {
+        #[command(subcommand)]
+        command: AuditCommands,
+    },
+
+    /// Remove selected node data, keeping the signing and node keys; lists
+    /// what would be removed unless confirmed
+    Purge {
+        /// Chain database: blocks, state and peer records
+        #[arg(long)]
+        chain: bool,
+
+        /// Per-block state history kept in archive mode
+        #[arg(long)]
+        state: bool,
+
+        /// Peer reputation records and bans
+        #[arg(long)]
+        peers: bool,
+
+        /// Pinned model weights
+        #[arg(long)]
+        models: bool,
+
+        /// Conversation history of the desktop app's agent
+        #[arg(long)]
+        agent_history: bool,
+
+        /// Token printed when reviewing the purge
+        #[arg(long, value_name = "TOKEN")]
+        confirm: Option<String>,
+    },
+
+    /// Check ports, disk space, clock, IPFS, bootnodes, database and GPU
+    /// driver, with hints for anything that needs fixing
+    Doctor {
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -138,6 +241,100 @@ enum ModelCommands {
         /// Data directory
         #[arg(short, long, value_name = "DIR")]
         data_dir: Option<PathBuf>,
+
+        /// Genesis model manifest to use instead of the built-in models
+        #[arg(long, value_name = "FILE")]
+        genesis_models: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum GenesisCommands {
+    /// Hash a local model file, upload it to IPFS and add it to a genesis model manifest
+    AddModel {
+        /// Model file (e.g. GGUF)
+        file: PathBuf,
+
+        /// Model identifier recorded in genesis (e.g. "bge-m3")
+        #[arg(long)]
+        id: String,
+
+        /// Model type: embeddings, tiny-llm, general-llm, code-llm, vision-llm, diffusion
+        #[arg(long, value_parser = genesis_models::parse_model_type)]
+        model_type: citrate_consensus::types::ModelType,
+
+        /// Store the weights in the genesis block instead of requiring validators to pin them
+        #[arg(long)]
+        embed: bool,
+
+        /// Human-readable name (defaults to the ID)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Model version
+        #[arg(long, default_value = "1.0.0")]
+        version: String,
+
+        /// License identifier (e.g. MIT, Apache-2.0)
+        #[arg(long)]
+        license: String,
+
+        /// Accept a license outside the known list
+        #[arg(long)]
+        allow_custom_license: bool,
+
+        /// Context length in tokens
+        #[arg(long, default_value = "4096")]
+        context_length: u32,
+
+        /// Embedding dimension (embedding models)
+        #[arg(long)]
+        embedding_dim: Option<u32>,
+
+        /// Model framework
+        #[arg(long, default_value = "GGUF")]
+        framework: String,
+
+        /// Penalty in wei for validators not pinning the model
+        #[arg(long, default_value = "1000000000000000000000")]
+        slash_penalty: u128,
+
+        /// Hours new validators have to pin the model
+        #[arg(long, default_value = "24")]
+        grace_period_hours: u64,
+
+        /// IPFS HTTP API endpoint
+        #[arg(long, default_value = "http://127.0.0.1:5001")]
+        ipfs_api: String,
+
+        /// Use this CID instead of uploading the file
+        #[arg(long)]
+        cid: Option<String>,
+
+        /// Manifest to update
+        #[arg(long, default_value = genesis_models::DEFAULT_MANIFEST)]
+        manifest: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuditCommands {
+    /// Write balances, model registry and governance state at a block to a
+    /// signed archive; run against a stopped node's data directory
+    Export {
+        /// Block height to export (defaults to the chain tip); past heights
+        /// need archive mode
+        #[arg(long)]
+        height: Option<u64>,
+
+        /// Archive file to write
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Hex-encoded ed25519 key to sign with (defaults to the block
+        /// signing key)
+        #[arg(long, value_name = "FILE")]
+        key: Option<PathBuf>,
     },
 }
 
@@ -175,12 +372,54 @@ async fn main() -> Result<()> {
             generate_keypair();
             return Ok(());
         }
+        Some(Commands::ApiKey {
+            label,
+            requests,
+            window_secs,
+        }) => {
+            issue_api_key(&label, requests, window_secs)?;
+            return Ok(());
+        }
         Some(Commands::Model { command }) => {
             handle_model_command(command, cli.data_dir.clone()).await?;
             return Ok(());
         }
-        Some(Commands::GenesisInfo) => {
-            show_genesis_info()?;
+        Some(Commands::GenesisInfo { models }) => {
+            show_genesis_info(models)?;
+            return Ok(());
+        }
+        Some(Commands::Genesis { command }) => {
+            handle_genesis_command(command).await?;
+            return Ok(());
+        }
+        Some(Commands::Bench { quick, only, json }) => {
+            run_bench(quick, only, json, cli.data_dir.clone()).await?;
+            return Ok(());
+        }
+        Some(Commands::Audit { command }) => {
+            handle_audit_command(command, cli.config.as_deref(), cli.data_dir.clone())?;
+            return Ok(());
+        }
+        Some(Commands::Purge {
+            chain,
+            state,
+            peers,
+            models,
+            agent_history,
+            confirm,
+        }) => {
+            let selection = purge::PurgeSelection {
+                chain,
+                state,
+                peers,
+                models,
+                agent_history,
+            };
+            handle_purge_command(selection, confirm, cli.config.as_deref(), cli.data_dir.clone())?;
+            return Ok(());
+        }
+        Some(Commands::Doctor { json }) => {
+            run_doctor(json, cli.config.as_deref(), cli.data_dir.clone()).await?;
             return Ok(());
         }
         None => {
@@ -188,16 +427,10 @@ async fn main() -> Result<()> {
         }
     }
 
-    // Load or create config
-    let has_config_file = cli.config.is_some();
-    let config = if let Some(config_path) = cli.config {
-        NodeConfig::from_file(&config_path)?
-    } else {
-        NodeConfig::default()
-    };
+    // Load config file and environment overrides
+    let mut config = NodeConfig::load(cli.config.as_deref())?;
 
     // Override with CLI args
-    let mut config = config;
     if let Some(data_dir) = cli.data_dir {
         config.storage.data_dir = data_dir;
     }
@@ -219,18 +452,19 @@ async fn main() -> Result<()> {
             .parse()
             .map_err(|e| anyhow::anyhow!("Invalid RPC address: {}", e))?;
     }
-    config.network.max_peers = cli.max_peers;
-
-    // Only override chain_id if no config file was provided
-    // This allows config file to set chain_id when using --config flag
-    if !has_config_file {
-        // No config file provided, use CLI arg (or its default)
-        config.chain.chain_id = cli.chain_id;
+    if let Some(max_peers) = cli.max_peers {
+        config.network.max_peers = max_peers;
+    }
+    if let Some(chain_id) = cli.chain_id {
+        config.chain.chain_id = chain_id;
     }
 
     if let Some(coinbase) = cli.coinbase {
         config.mining.coinbase = coinbase;
     }
+    if let Some(endpoint) = cli.remote_signer {
+        config.mining.remote_signer = Some(endpoint);
+    }
     if cli.no_rpc {
         config.rpc.enabled = false;
     }
@@ -244,6 +478,11 @@ async fn main() -> Result<()> {
         );
     }
 
+    if cli.print_config {
+        print!("{}", config.to_toml()?);
+        return Ok(());
+    }
+
     // Validate configuration (fail-closed for production mode)
     // This catches production_mode=true with empty validators early
     if let Err(e) = config.validate() {
@@ -267,10 +506,21 @@ async fn main() -> Result<()> {
             Some(storage.state.clone()),
         ));
 
-        let genesis_config = genesis::GenesisConfig {
+        let mut genesis_config = genesis::GenesisConfig {
             chain_id: config.chain.chain_id,
+            models: config
+                .chain
+                .genesis_models
+                .as_deref()
+                .map(genesis_models::load_genesis_models)
+                .transpose()?,
             ..Default::default()
         };
+        if let Some(dev) = &config.dev_accounts {
+            genesis_config
+                .initial_accounts
+                .extend(dev_accounts::genesis_allocations(dev)?);
+        }
 
         genesis::initialize_genesis_state(storage, executor, &genesis_config).await?;
         info!("Genesis state initialized for chain ID {}", config.chain.chain_id);
@@ -347,7 +597,10 @@ async fn handle_model_command(command: ModelCommands, data_dir: Option<PathBuf>)
             println!("Successfully unpinned model {}", cid);
         }
 
-        ModelCommands::AutoPin { data_dir: cmd_data_dir } => {
+        ModelCommands::AutoPin {
+            data_dir: cmd_data_dir,
+            genesis_models: models_manifest,
+        } => {
             let data_dir = cmd_data_dir
                 .or(data_dir)
                 .unwrap_or_else(|| dirs::home_dir().unwrap().join(".citrate"));
@@ -359,6 +612,10 @@ async fn handle_model_command(command: ModelCommands, data_dir: Option<PathBuf>)
                 timestamp: 0,
                 chain_id: 1337,
                 initial_accounts: vec![],
+                models: models_manifest
+                    .as_deref()
+                    .map(genesis_models::load_genesis_models)
+                    .transpose()?,
             };
 
             let genesis_block = genesis::create_genesis_block(&genesis_config);
@@ -457,10 +714,21 @@ async fn run_devnet() -> Result<()> {
             Some(storage.state.clone()),
         ));
 
-        let genesis_config = GenesisConfig {
+        let mut genesis_config = GenesisConfig {
             chain_id: config.chain.chain_id,
+            models: config
+                .chain
+                .genesis_models
+                .as_deref()
+                .map(genesis_models::load_genesis_models)
+                .transpose()?,
             ..Default::default()
         };
+        if let Some(dev) = &config.dev_accounts {
+            genesis_config
+                .initial_accounts
+                .extend(dev_accounts::genesis_allocations(dev)?);
+        }
 
         initialize_genesis_state(storage, executor, &genesis_config).await?;
         info!("Devnet chain initialized");
@@ -479,7 +747,30 @@ fn generate_keypair() {
     println!("Public key:  {}", hex::encode(verifying_key.to_bytes()));
 }
 
-fn show_genesis_info() -> Result<()> {
+fn issue_api_key(label: &str, requests: Option<u32>, window_secs: u64) -> Result<()> {
+    let rate_limit = requests.map(|requests| RateLimit {
+        requests,
+        window_secs,
+    });
+    let mut rpc_config = RpcConfig::default();
+    let key = rpc_config.issue_api_key(label, rate_limit);
+
+    // Only the key's hash goes in the config
+    let entry = std::collections::BTreeMap::from([(
+        "rpc",
+        std::collections::BTreeMap::from([(
+            "access",
+            std::collections::BTreeMap::from([("api_keys", rpc_config.access.api_keys)]),
+        )]),
+    )]);
+    println!("API key: {}", key);
+    println!("It can't be shown again. Add this to the node config:");
+    println!();
+    print!("{}", toml::to_string(&entry)?);
+    Ok(())
+}
+
+fn show_genesis_info(models_manifest: Option<PathBuf>) -> Result<()> {
     println!("=========================================");
     println!("Genesis Block Information");
     println!("=========================================");
@@ -490,6 +781,10 @@ fn show_genesis_info() -> Result<()> {
         timestamp: 0,
         chain_id: 1337,
         initial_accounts: vec![],
+        models: models_manifest
+            .as_deref()
+            .map(genesis_models::load_genesis_models)
+            .transpose()?,
     };
 
     let genesis = genesis::create_genesis_block(&genesis_config);
@@ -549,11 +844,270 @@ fn show_genesis_info() -> Result<()> {
     Ok(())
 }
 
+async fn handle_genesis_command(command: GenesisCommands) -> Result<()> {
+    use citrate_consensus::types::ModelMetadata;
+    use genesis_models::{EmbeddedModelEntry, GenesisModelManifest, RequiredPinEntry};
+
+    match command {
+        GenesisCommands::AddModel {
+            file,
+            id,
+            model_type,
+            embed,
+            name,
+            version,
+            license,
+            allow_custom_license,
+            context_length,
+            embedding_dim,
+            framework,
+            slash_penalty,
+            grace_period_hours,
+            ipfs_api,
+            cid,
+            manifest: manifest_path,
+        } => {
+            genesis_models::check_license(&license, allow_custom_license)?;
+
+            println!("Hashing {}...", file.display());
+            let (sha256, size_bytes) = genesis_models::hash_file(&file)?;
+            genesis_models::check_size(size_bytes, embed)?;
+
+            let cid = match cid {
+                Some(cid) => cid,
+                None => {
+                    println!(
+                        "Uploading {} MB to IPFS at {}...",
+                        size_bytes / 1_000_000,
+                        ipfs_api
+                    );
+                    genesis_models::upload_to_ipfs(&ipfs_api, &file).await?
+                }
+            };
+
+            let metadata = ModelMetadata {
+                name: name.unwrap_or_else(|| id.clone()),
+                version,
+                context_length,
+                embedding_dim,
+                license,
+                framework: Some(framework),
+            };
+
+            let mut manifest = GenesisModelManifest::load_or_default(&manifest_path)?;
+            let entry = if embed {
+                // Weights are read relative to the manifest when genesis is built
+                let manifest_dir = manifest_path
+                    .parent()
+                    .filter(|p| !p.as_os_str().is_empty())
+                    .unwrap_or_else(|| std::path::Path::new("."))
+                    .canonicalize()?;
+                let file = file.canonicalize()?;
+                let path = file
+                    .strip_prefix(&manifest_dir)
+                    .map(PathBuf::from)
+                    .unwrap_or(file);
+                let entry = EmbeddedModelEntry {
+                    model_id: id,
+                    model_type,
+                    path,
+                    sha256: hex::encode(sha256.as_bytes()),
+                    size_bytes,
+                    ipfs_cid: Some(cid),
+                    metadata,
+                };
+                manifest.upsert_embedded(entry.clone())?;
+                serde_json::to_string_pretty(&entry)?
+            } else {
+                let entry = RequiredPinEntry {
+                    model_id: id,
+                    model_type,
+                    ipfs_cid: cid,
+                    sha256: hex::encode(sha256.as_bytes()),
+                    size_bytes,
+                    slash_penalty: slash_penalty.to_string(),
+                    grace_period_hours,
+                    metadata,
+                };
+                manifest.upsert_pin(entry.clone());
+                serde_json::to_string_pretty(&entry)?
+            };
+            manifest.save(&manifest_path)?;
+
+            println!(
+                "\nAdded {} entry to {}:",
+                if embed { "embedded_models" } else { "required_pins" },
+                manifest_path.display()
+            );
+            println!("{}", entry);
+            println!(
+                "\nSet `genesis_models = \"{}\"` under [chain] in the node config before initializing the chain.",
+                manifest_path.display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_bench(
+    quick: bool,
+    suites: Vec<bench::BenchSuite>,
+    json: bool,
+    data_dir: Option<PathBuf>,
+) -> Result<()> {
+    let mut config = if quick {
+        bench::BenchConfig::quick()
+    } else {
+        bench::BenchConfig::default()
+    };
+    config.suites = suites;
+    // Benchmark storage on the disk the node will actually use
+    if let Some(data_dir) = data_dir {
+        config.work_dir = data_dir.join("bench-tmp");
+    }
+
+    let report = bench::run(&config).await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        report.print();
+    }
+
+    Ok(())
+}
+
+fn handle_purge_command(
+    selection: purge::PurgeSelection,
+    confirm: Option<String>,
+    config_path: Option<&std::path::Path>,
+    data_dir: Option<PathBuf>,
+) -> Result<()> {
+    if selection.is_empty() {
+        anyhow::bail!(
+            "Nothing selected; pass one or more of --chain, --state, --peers, --models, --agent-history"
+        );
+    }
+
+    // Pinned models live where `citrate model` puts them
+    let models_dir = data_dir
+        .clone()
+        .unwrap_or_else(|| dirs::home_dir().unwrap().join(".citrate"))
+        .join("models");
+    let mut config = NodeConfig::load(config_path)?;
+    if let Some(data_dir) = data_dir {
+        config.storage.data_dir = data_dir;
+    }
+
+    let agent_history = purge::default_agent_history();
+    let plan = purge::plan(
+        selection,
+        &config.storage.data_dir,
+        &models_dir,
+        agent_history.as_deref(),
+    )?;
+    plan.print();
+    if plan.items.is_empty() {
+        return Ok(());
+    }
+
+    match confirm {
+        Some(token) => {
+            plan.execute(&token)?;
+            println!("Purged.");
+        }
+        None => {
+            println!();
+            println!("Nothing has been removed. To remove it, run again with:");
+            println!("  --confirm {}", plan.token());
+        }
+    }
+    Ok(())
+}
+
+async fn run_doctor(
+    json: bool,
+    config_path: Option<&std::path::Path>,
+    data_dir: Option<PathBuf>,
+) -> Result<()> {
+    let mut config = NodeConfig::load(config_path)?;
+    if let Some(data_dir) = data_dir {
+        config.storage.data_dir = data_dir;
+    }
+
+    let report = doctor::run(&config).await;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        report.print();
+    }
+    if report.failures() > 0 {
+        anyhow::bail!("{} check(s) failed", report.failures());
+    }
+    Ok(())
+}
+
+fn handle_audit_command(
+    command: AuditCommands,
+    config_path: Option<&std::path::Path>,
+    data_dir: Option<PathBuf>,
+) -> Result<()> {
+    let mut config = NodeConfig::load(config_path)?;
+    if let Some(data_dir) = data_dir {
+        config.storage.data_dir = data_dir;
+    }
+
+    match command {
+        AuditCommands::Export { height, output, key } => {
+            let key_path = key
+                .or_else(|| config.mining.signing_key.clone())
+                .unwrap_or_else(|| config.storage.data_dir.join("signing.key"));
+            let key = audit::load_signing_key(&key_path)?;
+
+            let storage = StorageManager::with_archive(
+                &config.storage.data_dir,
+                PruningConfig::default(),
+                config.storage.archive,
+            )?;
+            let archive = audit::export(&storage, config.chain.chain_id, height, &key)?;
+            std::fs::write(&output, serde_json::to_string_pretty(&archive)?)?;
+
+            let manifest = &archive.manifest;
+            println!(
+                "Exported block {} (0x{}...) to {}",
+                manifest.height,
+                &manifest.block_hash[2..10],
+                output.display()
+            );
+            for section in &manifest.sections {
+                println!(
+                    "  {:<10} {:>8} entries  sha3-256 {}",
+                    section.name, section.entries, section.sha3_256
+                );
+            }
+            println!("Signed by {}", archive.signature.public_key);
+            println!("Verify with: citrate-cli audit verify {}", output.display());
+        }
+    }
+
+    Ok(())
+}
+
 async fn start_node(config: NodeConfig) -> Result<()> {
     info!("Starting Citrate node...");
     info!("Chain ID: {}", config.chain.chain_id);
     info!("Data directory: {:?}", config.storage.data_dir);
 
+    // Dev accounts are funded in genesis; print their keys and list them in eth_accounts
+    let dev_accounts = match &config.dev_accounts {
+        Some(dev) => {
+            let accounts = dev_accounts::derive_dev_accounts(dev)?;
+            dev_accounts::print_dev_accounts(&accounts, dev);
+            accounts.into_iter().map(|a| a.address).collect()
+        }
+        None => Vec::new(),
+    };
+
     // Initialize metrics server
     let metrics_addr = std::env::var("CITRATE_METRICS_ADDR")
         .unwrap_or_else(|_| "127.0.0.1:9090".to_string());
@@ -567,15 +1121,18 @@ async fn start_node(config: NodeConfig) -> Result<()> {
     let node_start_time = std::time::Instant::now();
 
     // Create storage
-    let storage = Arc::new(StorageManager::new(
+    let storage = Arc::new(StorageManager::with_tuning(
         &config.storage.data_dir,
         PruningConfig {
             keep_blocks: config.storage.keep_blocks,
             keep_states: config.storage.keep_blocks,
+            keep_receipts: config.storage.keep_receipts.unwrap_or(config.storage.keep_blocks),
             interval: Duration::from_secs(3600),
             batch_size: 1000,
             auto_prune: config.storage.pruning,
         },
+        config.storage.archive,
+        &config.storage.tuning,
     )?);
 
     // Create state DB and executor with persistent storage
@@ -718,17 +1275,23 @@ async fn start_node(config: NodeConfig) -> Result<()> {
         replacement_factor: 110,
         require_valid_signature,
         chain_id: config.chain.chain_id,
+        gas_lanes: Default::default(),
     }));
 
-    // Create peer manager
-    let peer_manager = Arc::new(PeerManager::new(PeerManagerConfig {
+    // Create peer manager with reputation persisted alongside chain data
+    let peer_manager_config = PeerManagerConfig {
         max_peers: config.network.max_peers,
         max_inbound: config.network.max_peers / 2,
         max_outbound: config.network.max_peers / 2,
         peer_timeout: std::time::Duration::from_secs(30),
         ban_duration: std::time::Duration::from_secs(3600),
         score_threshold: -100,
-    }));
+    };
+    let reputation = Arc::new(ReputationManager::with_storage(
+        peer_manager_config.reputation_config(),
+        storage.db.clone(),
+    )?);
+    let peer_manager = Arc::new(PeerManager::with_reputation(peer_manager_config, reputation));
 
     // Optionally start Prometheus metrics server
     let metrics_enabled = std::env::var("CITRATE_METRICS")
@@ -738,8 +1301,13 @@ async fn start_node(config: NodeConfig) -> Result<()> {
         let addr_str =
             std::env::var("CITRATE_METRICS_ADDR").unwrap_or_else(|_| "0.0.0.0:9100".to_string());
         let addr: std::net::SocketAddr = addr_str.parse().unwrap();
+        let dag_stats = storage.dag_stats.clone();
+        let mempool_for_metrics = mempool.clone();
+        citrate_api::metrics::install();
         tokio::spawn(async move {
             if let Err(e) = citrate_api::metrics_server::MetricsServer::new(addr)
+                .with_dag_stats(dag_stats)
+                .with_mempool(mempool_for_metrics)
                 .start()
                 .await
             {
@@ -749,6 +1317,124 @@ async fn start_node(config: NodeConfig) -> Result<()> {
         info!("Metrics server enabled at {}", addr);
     }
 
+    // Opt-in anonymized telemetry
+    if config.telemetry.enabled {
+        match telemetry::load_or_create_install_id(&config.storage.data_dir) {
+            Ok(install_id) => {
+                let pm = peer_manager.clone();
+                let storage_for_telemetry = storage.clone();
+                telemetry::spawn(
+                    config.telemetry.clone(),
+                    install_id,
+                    config.chain.chain_id,
+                    move || {
+                        let pm = pm.clone();
+                        let storage = storage_for_telemetry.clone();
+                        async move {
+                            let (peer_count, _, _) = pm.get_peer_counts().await;
+                            telemetry::NodeStats {
+                                peer_count,
+                                sync_height: storage.blocks.get_latest_height().unwrap_or(0),
+                            }
+                        }
+                    },
+                );
+            }
+            Err(e) => warn!("Telemetry disabled: failed to load install ID: {}", e),
+        }
+    }
+
+    // Hourly chain statistics snapshots
+    {
+        let pm = peer_manager.clone();
+        let mempool_for_stats = mempool.clone();
+        tokio::spawn(citrate_storage::chain::run_recorder(
+            storage.stats_history.clone(),
+            config.storage.stats_history_days,
+            move || {
+                let pm = pm.clone();
+                let mempool = mempool_for_stats.clone();
+                async move {
+                    let (peer_count, _, _) = pm.get_peer_counts().await;
+                    Some(citrate_storage::chain::LiveStats {
+                        peer_count,
+                        mempool_pending: mempool.stats().await.total_transactions,
+                    })
+                }
+            },
+        ));
+    }
+
+    // Model registry webhooks
+    if config.webhooks.enabled {
+        webhooks::spawn(
+            config.webhooks.clone(),
+            config.chain.chain_id,
+            &config.storage.data_dir,
+            storage.clone(),
+        );
+    }
+
+    // Embedded IPFS gateway
+    if config.ipfs_gateway.enabled {
+        let addr: std::net::SocketAddr = config.ipfs_gateway.listen_addr.parse()?;
+        let ipfs = Arc::new(citrate_storage::ipfs::IPFSService::new(
+            config.ipfs_gateway.ipfs_api_url.clone(),
+        ));
+        tokio::spawn(async move {
+            if let Err(e) = citrate_api::ipfs_gateway::IpfsGateway::new(addr, ipfs)
+                .start()
+                .await
+            {
+                tracing::warn!("IPFS gateway failed: {}", e);
+            }
+        });
+        info!("IPFS gateway enabled at http://{}/ipfs/", addr);
+    }
+
+    // Finality checkpoints signed by the validator set
+    let checkpoints = config
+        .validator
+        .checkpoint_config(config.chain.chain_id)
+        .map_err(anyhow::Error::msg)?
+        .map(|checkpoint_config| {
+            info!(
+                "Finality checkpoints every {} blocks, quorum {} of {} validators",
+                checkpoint_config.interval,
+                checkpoint_config.quorum,
+                checkpoint_config.validators.len()
+            );
+            Arc::new(citrate_consensus::CheckpointPool::new(checkpoint_config))
+        });
+
+    // Epoch summaries signed by the validator set
+    let epochs = config
+        .validator
+        .epoch_config(config.chain.chain_id)
+        .map_err(anyhow::Error::msg)?
+        .map(|epoch_config| {
+            info!(
+                "Epoch summaries every {} blocks, quorum {} of {} validators",
+                epoch_config.length,
+                epoch_config.quorum,
+                epoch_config.validators.len()
+            );
+            Arc::new(citrate_consensus::EpochSummaryPool::new(epoch_config))
+        });
+
+    // Gossip relay, shared by the message handler, the producer and admin RPC
+    let gossip = Arc::new(GossipProtocol::new(GossipConfig::default(), peer_manager.clone()));
+    {
+        let gossip = gossip.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                gossip.cleanup_seen_cache().await;
+            }
+        });
+    }
+
     // Start P2P listener and connect to bootstrap nodes
     {
         // Prepare head info
@@ -778,24 +1464,38 @@ async fn start_node(config: NodeConfig) -> Result<()> {
         let pm_for_rx = peer_manager.clone();
         let storage_for_handler = storage.clone();
         let mempool_for_handler = mempool.clone();
-        let gossip = Arc::new(GossipProtocol::new(GossipConfig::default(), peer_manager.clone()));
+        let checkpoints_for_handler = checkpoints.clone();
+        let epochs_for_handler = epochs.clone();
+        let executor_for_handler = executor.clone();
         let gossip_for_rx = gossip.clone();
         // Sync manager (basic integration)
-        let sync = Arc::new(SyncManager::new(SyncConfig::default()));
+        let sync_pipeline = SyncPipeline::new(
+            SyncPipelineConfig::default(),
+            storage.clone(),
+            Arc::new(sync::SyncBlockExecutor::new(executor.clone())),
+        )?;
+        let sync = Arc::new(
+            SyncManager::new(SyncConfig::default()).with_pipeline(Arc::new(sync_pipeline)),
+        );
         let sync_for_rx = sync.clone();
 
         // Start transport listener and connect to bootstrap nodes
-        let local_peer_id = load_or_create_peer_id(&config.storage.data_dir)?;
+        let node_key = load_or_create_node_key(&config.storage.data_dir)?;
         let transport = NetworkTransport::new(
             peer_manager.clone(),
-            local_peer_id,
+            node_key,
             citrate_network::transport::HandshakeParams {
                 network_id,
                 genesis_hash,
                 head_height,
                 head_hash,
             },
-        );
+        )
+        .allow_plaintext(config.network.allow_plaintext);
+        if config.network.allow_plaintext {
+            warn!("P2P encryption disabled: accepting plaintext peers (devnet only)");
+        }
+        info!("Local peer ID: {}", transport.local_id());
         let listen_addr = config.network.listen_addr;
         transport
             .start_listener(listen_addr)
@@ -927,13 +1627,13 @@ async fn start_node(config: NodeConfig) -> Result<()> {
                     let pf = peer_failures.entry(key.clone()).or_insert(0);
                     *pf = pf.saturating_add(1);
                     // Lower peer score
-                    pm_for_sync.update_peer_score(&pid, -5).await;
+                    pm_for_sync.report_misbehavior(&pid, -5, "sync request timed out").await;
                     // Remove peer if too many failures
                     if *pf >= 5 {
                         if let Some(p) = pm_for_sync.get_peer(&pid) {
                             let addr = p.info.read().await.addr;
                             pm_for_sync.remove_peer(&pid).await;
-                            pm_for_sync.ban_peer(addr).await;
+                            pm_for_sync.reputation().ban(addr.ip(), "repeated sync timeouts");
                             tracing::warn!("Banned peer {} due to repeated sync timeouts", pid.0);
                         }
                     }
@@ -1118,6 +1818,34 @@ async fn start_node(config: NodeConfig) -> Result<()> {
                             .await;
                     }
                     NetworkMessage::NewBlock { block } => {
+                        // Neither store nor relay blocks over the governed limits
+                        let limits = executor_for_handler
+                            .governance_block_limits(citrate_consensus::BlockLimits::default());
+                        if let Err(e) = limits.check(&block) {
+                            warn!(
+                                "Rejecting block {} from {}: {}",
+                                block.header.block_hash, pid.0, e
+                            );
+                            continue;
+                        }
+                        // Never replace a block below an enforced checkpoint
+                        if let Some(pool) = &checkpoints_for_handler {
+                            let height = block.header.height;
+                            let local = storage_for_handler
+                                .blocks
+                                .get_block_by_height(height)
+                                .ok()
+                                .flatten();
+                            if height <= pool.enforced_height().await
+                                && local.is_some_and(|h| h != block.header.block_hash)
+                            {
+                                debug!(
+                                    "Ignoring block {} from {} below finality checkpoint",
+                                    block.header.block_hash, pid.0
+                                );
+                                continue;
+                            }
+                        }
                         // Store block if we don't have it
                         let have = storage_for_handler
                             .blocks
@@ -1133,10 +1861,48 @@ async fn start_node(config: NodeConfig) -> Result<()> {
                         let _ = sync_for_rx.handle_blocks(blocks).await;
                     }
                     NetworkMessage::Transactions { transactions } => {
-                        for tx in transactions {
-                            let _ = mempool_for_handler
-                                .add_transaction(tx, TxClass::Standard)
-                                .await;
+                        let _ = mempool_for_handler
+                            .add_transactions(transactions, TxClass::Standard)
+                            .await;
+                    }
+                    NetworkMessage::FinalityCheckpoint { checkpoint } => {
+                        let Some(pool) = &checkpoints_for_handler else {
+                            continue;
+                        };
+                        match pool.add_vote(checkpoint.clone()).await {
+                            Ok(_) => {
+                                // Relay new votes so they reach validators we
+                                // aren't connected to
+                                let _ = pm_for_rx
+                                    .broadcast(&NetworkMessage::FinalityCheckpoint { checkpoint })
+                                    .await;
+                            }
+                            Err(
+                                citrate_consensus::CheckpointError::Duplicate
+                                | citrate_consensus::CheckpointError::Stale(_),
+                            ) => {}
+                            Err(e) => {
+                                debug!("Rejected finality checkpoint from {}: {}", pid.0, e);
+                            }
+                        }
+                    }
+                    NetworkMessage::EpochVote { vote } => {
+                        let Some(pool) = &epochs_for_handler else {
+                            continue;
+                        };
+                        match pool.add_vote(vote.clone()).await {
+                            Ok(_) => {
+                                let _ = pm_for_rx
+                                    .broadcast(&NetworkMessage::EpochVote { vote })
+                                    .await;
+                            }
+                            Err(
+                                citrate_consensus::EpochError::Duplicate
+                                | citrate_consensus::EpochError::Stale(_),
+                            ) => {}
+                            Err(e) => {
+                                debug!("Rejected epoch vote from {}: {}", pid.0, e);
+                            }
                         }
                     }
                     _ => {
@@ -1162,6 +1928,208 @@ async fn start_node(config: NodeConfig) -> Result<()> {
 
     let economics_manager = Arc::new(economics_manager_temp);
 
+    // Local block signing key, unless a remote signer holds it
+    let signing_key = if config.mining.enabled && config.mining.remote_signer.is_none() {
+        let path = config
+            .mining
+            .signing_key
+            .clone()
+            .unwrap_or_else(|| config.storage.data_dir.join("signing.key"));
+        let key = citrate_sequencer::BlockSigningKey::load_or_create(&path)?;
+        info!(
+            "Block signing key 0x{} loaded from {}",
+            hex::encode(key.public_key().as_bytes()),
+            path.display()
+        );
+        Some(Arc::new(key))
+    } else {
+        None
+    };
+
+    // Sign a checkpoint for each finalized checkpoint height while this node
+    // is in the validator set
+    if let (Some(pool), Some(key)) = (&checkpoints, &signing_key) {
+        if pool.is_validator(&key.public_key()) {
+            let pool = pool.clone();
+            let key = key.clone();
+            let storage = storage.clone();
+            let peer_manager = peer_manager.clone();
+            let depth = citrate_consensus::FinalityConfig::default().confirmation_depth;
+            let tick = Duration::from_secs(config.mining.target_block_time.max(1));
+            tokio::spawn(async move {
+                let mut latest: Option<citrate_consensus::FinalityCheckpoint> = None;
+                let mut interval = tokio::time::interval(tick);
+                loop {
+                    interval.tick().await;
+                    let head = storage.blocks.get_latest_height().unwrap_or(0);
+                    let due = pool
+                        .config()
+                        .checkpoint_at_or_below(head.saturating_sub(depth))
+                        .filter(|h| latest.as_ref().is_none_or(|v| v.height < *h));
+                    if let Some(height) = due {
+                        let Some(block_hash) =
+                            storage.blocks.get_block_by_height(height).ok().flatten()
+                        else {
+                            continue;
+                        };
+                        let vote = citrate_consensus::FinalityCheckpoint::sign_with(
+                            pool.config().chain_id,
+                            height,
+                            block_hash,
+                            key.public_key(),
+                            |message| key.sign(message),
+                        );
+                        if let Err(e) = pool.add_vote(vote.clone()).await {
+                            warn!("Own finality checkpoint at {} rejected: {}", height, e);
+                        }
+                        latest = Some(vote);
+                    }
+                    // Repeat the vote until it is enforced, for peers that
+                    // connected after it was first sent
+                    if let Some(vote) = &latest {
+                        if pool.enforced_height().await < vote.height {
+                            let _ = peer_manager
+                                .broadcast(&citrate_network::NetworkMessage::FinalityCheckpoint {
+                                    checkpoint: vote.clone(),
+                                })
+                                .await;
+                        }
+                    }
+                }
+            });
+        } else {
+            warn!("Block signing key is not a checkpoint validator; not signing checkpoints");
+        }
+    }
+
+    // Sign the summary of each finalized epoch boundary while this node is in
+    // the validator set
+    if let (Some(pool), Some(key)) = (&epochs, &signing_key) {
+        if pool.is_validator(&key.public_key()) {
+            let pool = pool.clone();
+            let key = key.clone();
+            let storage = storage.clone();
+            let peer_manager = peer_manager.clone();
+            let depth = citrate_consensus::FinalityConfig::default().confirmation_depth;
+            let tick = Duration::from_secs(config.mining.target_block_time.max(1));
+            tokio::spawn(async move {
+                let mut latest: Option<citrate_consensus::EpochVote> = None;
+                let mut interval = tokio::time::interval(tick);
+                loop {
+                    interval.tick().await;
+                    let head = storage.blocks.get_latest_height().unwrap_or(0);
+                    let due = pool
+                        .config()
+                        .boundary_at_or_below(head.saturating_sub(depth))
+                        .filter(|h| latest.as_ref().is_none_or(|v| v.summary.height < *h));
+                    if let Some(height) = due {
+                        let Some(block) = storage
+                            .blocks
+                            .get_block_by_height(height)
+                            .ok()
+                            .flatten()
+                            .and_then(|hash| storage.blocks.get_block(&hash).ok().flatten())
+                        else {
+                            continue;
+                        };
+                        // The validator set is static, so it also signs the
+                        // next epoch
+                        let summary = match citrate_consensus::EpochSummary::for_block(
+                            pool.config(),
+                            &block,
+                            &pool.config().validators,
+                        ) {
+                            Ok(summary) => summary,
+                            Err(e) => {
+                                warn!("Cannot summarize epoch at {}: {}", height, e);
+                                continue;
+                            }
+                        };
+                        let vote = citrate_consensus::EpochVote::sign_with(
+                            summary,
+                            key.public_key(),
+                            |message| key.sign(message),
+                        );
+                        if let Err(e) = pool.add_vote(vote.clone()).await {
+                            warn!("Own epoch vote at {} rejected: {}", height, e);
+                        }
+                        latest = Some(vote);
+                    }
+                    // Repeat the vote until the summary is signed, for peers
+                    // that connected after it was first sent
+                    if let Some(vote) = &latest {
+                        if pool.get(vote.summary.epoch).await.is_none() {
+                            let _ = peer_manager
+                                .broadcast(&citrate_network::NetworkMessage::EpochVote {
+                                    vote: vote.clone(),
+                                })
+                                .await;
+                        }
+                    }
+                }
+            });
+        } else {
+            warn!("Block signing key is not an epoch validator; not signing epoch summaries");
+        }
+    }
+
+    // Block producer, also needed to serve block templates to external producers
+    let producer = if config.mining.enabled || config.mining.block_templates {
+        // Parse coinbase address
+        let coinbase_bytes = hex::decode(&config.mining.coinbase).unwrap_or_else(|_| vec![0; 32]);
+        let mut coinbase = [0u8; 32];
+        coinbase.copy_from_slice(&coinbase_bytes[..32.min(coinbase_bytes.len())]);
+
+        // Always use peer manager if we have one (network is already setup above)
+        let producer_peer_manager = Some(peer_manager.clone());
+
+        // Treasury percentage from governance
+        let mut _treasury_percentage = 10u8;
+        if let Some(bytes) = executor
+            .state_db()
+            .get_storage(&governance_addr, b"PARAM:treasury_percentage")
+        {
+            if !bytes.is_empty() {
+                _treasury_percentage = bytes[0];
+            }
+        }
+
+        // Use the economics manager created earlier
+        let mut producer = BlockProducer::with_economics(
+            storage.clone(),
+            executor.clone(),
+            mempool.clone(),
+            producer_peer_manager,
+            citrate_consensus::PublicKey::new(coinbase),
+            config.mining.target_block_time,
+            economics_manager.clone(),
+        );
+
+        if let Some(endpoint) = config.mining.remote_signer.as_ref().filter(|_| config.mining.enabled) {
+            let signer = citrate_wallet::RemoteSigner::new(endpoint, "citrate-node")?;
+            let account = *signer
+                .list_accounts()
+                .await?
+                .first()
+                .ok_or_else(|| anyhow::anyhow!("Remote signer {} has no accounts", endpoint))?;
+            info!(
+                "Signing blocks with remote signer {} as 0x{}",
+                endpoint,
+                hex::encode(account.as_bytes())
+            );
+            producer = producer.with_remote_signer(Arc::new(signer), account);
+        } else if let Some(key) = &signing_key {
+            producer = producer.with_signing_key(key.clone());
+        }
+        if let Some(pool) = &epochs {
+            producer = producer.with_epochs(pool.clone());
+        }
+        producer = producer.with_gossip(gossip.clone());
+        Some(Arc::new(producer))
+    } else {
+        None
+    };
+
     // Start RPC server if enabled
     let rpc_handle = if config.rpc.enabled {
         info!("Starting RPC server on {}", config.rpc.listen_addr);
@@ -1171,6 +2139,9 @@ async fn start_node(config: NodeConfig) -> Result<()> {
             max_connections: 100,
             cors_domains: vec!["*".to_string()],
             threads: 4,
+            accounts: dev_accounts.clone(),
+            access: config.rpc.access.clone(),
+            max_batch_size: config.rpc.max_batch_size,
         };
 
         let rpc_server = RpcServer::with_economics(
@@ -1183,6 +2154,40 @@ async fn start_node(config: NodeConfig) -> Result<()> {
             Some(economics_manager.clone()),
         );
 
+        // Key rotation is only offered to callers on this host
+        let rpc_server = match &signing_key {
+            Some(key) if config.rpc.listen_addr.ip().is_loopback() => {
+                let chain_id = config.chain.chain_id;
+                rpc_server.with_signing_key(key.clone(), chain_id, config.mining.min_gas_price)
+            }
+            Some(_) => {
+                warn!(
+                    "RPC listens on {}; admin_rotateSigningKey is only enabled on loopback",
+                    config.rpc.listen_addr
+                );
+                rpc_server
+            }
+            None => rpc_server,
+        };
+
+        let rpc_server = rpc_server.with_gossip(gossip.clone());
+
+        let rpc_server = match &producer {
+            Some(producer) if config.mining.block_templates => {
+                info!("Serving block templates to external producers");
+                rpc_server.with_block_templates(producer.clone())
+            }
+            _ => rpc_server,
+        };
+        #[cfg(feature = "devnet")]
+        let rpc_server = match &producer {
+            Some(producer) => {
+                info!("Devnet block production control enabled");
+                rpc_server.with_dev_control(producer.clone())
+            }
+            None => rpc_server,
+        };
+
         Some(tokio::spawn(async move {
             match rpc_server.spawn() {
                 Ok((close_handle, join_handle)) => {
@@ -1207,39 +2212,8 @@ async fn start_node(config: NodeConfig) -> Result<()> {
     };
 
     // Start block producer if mining is enabled
-    if config.mining.enabled {
+    if let Some(producer) = producer.filter(|_| config.mining.enabled) {
         info!("Starting block producer...");
-
-        // Parse coinbase address
-        let coinbase_bytes = hex::decode(&config.mining.coinbase).unwrap_or_else(|_| vec![0; 32]);
-        let mut coinbase = [0u8; 32];
-        coinbase.copy_from_slice(&coinbase_bytes[..32.min(coinbase_bytes.len())]);
-
-        // Always use peer manager if we have one (network is already setup above)
-        let producer_peer_manager = Some(peer_manager.clone());
-
-        // Treasury percentage from governance
-        let mut _treasury_percentage = 10u8;
-        if let Some(bytes) = executor
-            .state_db()
-            .get_storage(&governance_addr, b"PARAM:treasury_percentage")
-        {
-            if !bytes.is_empty() {
-                _treasury_percentage = bytes[0];
-            }
-        }
-
-        // Use the economics manager created earlier
-        let producer = Arc::new(BlockProducer::with_economics(
-            storage.clone(),
-            executor.clone(),
-            mempool.clone(),
-            producer_peer_manager,
-            citrate_consensus::PublicKey::new(coinbase),
-            config.mining.target_block_time,
-            economics_manager,
-        ));
-
         tokio::spawn(async move {
             producer.start().await;
         });
@@ -1259,23 +2233,30 @@ async fn start_node(config: NodeConfig) -> Result<()> {
     Ok(())
 }
 
-fn load_or_create_peer_id(data_dir: &std::path::Path) -> anyhow::Result<citrate_network::peer::PeerId> {
+/// Static key for the P2P Noise handshake; the node's peer ID derives from it
+fn load_or_create_node_key(
+    data_dir: &std::path::Path,
+) -> anyhow::Result<citrate_network::transport::NodeKey> {
+    use citrate_network::transport::NodeKey;
     use std::fs;
-    use std::io::Write;
-    let path = data_dir.join("peer.id");
+    let path = data_dir.join("node.key");
     if let Ok(s) = fs::read_to_string(&path) {
-        let id = s.trim().to_string();
-        if !id.is_empty() {
-            return Ok(citrate_network::peer::PeerId::new(id));
-        }
+        let bytes = hex::decode(s.trim())
+            .map_err(|e| anyhow::anyhow!("Invalid node key {}: {}", path.display(), e))?;
+        return NodeKey::from_bytes(&bytes)
+            .map_err(|e| anyhow::anyhow!("Invalid node key {}: {}", path.display(), e));
     }
-    let id = format!("peer_{}", rand::random::<u64>());
+    let key = NodeKey::generate();
     if let Some(parent) = path.parent() {
         let _ = fs::create_dir_all(parent);
     }
-    let mut f = fs::File::create(&path)?;
-    writeln!(f, "{}", id)?;
-    Ok(citrate_network::peer::PeerId::new(id))
+    fs::write(&path, hex::encode(key.to_bytes()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(key)
 }
 
 /// Parse bootnode strings in formats like:
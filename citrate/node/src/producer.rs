@@ -1,23 +1,41 @@
+use async_trait::async_trait;
+#[cfg(feature = "devnet")]
+use citrate_api::dev_rpc::DevChainControl;
+use citrate_api::{BlockSubmission, BlockSubmitError, BlockTemplate, BlockTemplateProvider};
 use citrate_consensus::chain_selection::ChainSelector;
+use citrate_consensus::crypto;
 use citrate_consensus::dag_store::DagStore;
+use citrate_consensus::epoch::EpochSummaryPool;
+use citrate_consensus::metrics::{self, MetricEvent};
 use citrate_consensus::ghostdag::GhostDag;
 use citrate_consensus::tip_selection::TipSelector;
 use citrate_consensus::types::{
     Block, BlockHeader, GhostDagParams, Hash, PublicKey, Signature, Transaction, VrfProof,
 };
+use citrate_consensus::vrf::{proof_output_matches, vrf_challenge};
 use citrate_economics::{
     RewardCalculator, RewardConfig, UnifiedEconomicsManager,
 };
-use citrate_execution::Executor;
-use citrate_network::{NetworkMessage, PeerManager};
+use citrate_execution::{Executor, InternalTransaction};
+use citrate_network::{GossipProtocol, NetworkMessage, PeerManager};
 use citrate_sequencer::mempool::Mempool;
+use citrate_sequencer::{BlockSigningKey, MiningSchedule};
 use citrate_storage::{state_manager::StateManager as AIStateManager, StorageManager};
+use citrate_wallet::remote_signer::protocol::content_types;
+use citrate_wallet::RemoteSigner;
 use primitive_types::U256;
 use sha3::{Digest, Sha3_256};
+use std::collections::VecDeque;
 use std::sync::Arc;
-use tokio::time::{interval, Duration};
+use tokio::sync::Mutex;
 use tracing::{error, info};
 
+/// Seconds a block template can be submitted for
+const TEMPLATE_TTL_SECS: u64 = 120;
+
+/// Templates kept for submission; the oldest are dropped first
+const MAX_OPEN_TEMPLATES: usize = 64;
+
 /// Calculate block header hash using SHA3-256
 fn calculate_block_hash_header(header: &BlockHeader) -> Hash {
     let mut hasher = Sha3_256::new();
@@ -33,6 +51,10 @@ fn calculate_block_hash_header(header: &BlockHeader) -> Hash {
     hasher.update(header.blue_score.to_le_bytes());
     hasher.update(header.blue_work.to_le_bytes());
     hasher.update(header.pruning_point.as_bytes());
+    // Only once epochs are signed, so earlier block hashes are unchanged
+    if header.epoch_commitment != Hash::default() {
+        hasher.update(header.epoch_commitment.as_bytes());
+    }
 
     let hash_bytes = hasher.finalize();
     let mut hash_array = [0u8; 32];
@@ -40,6 +62,38 @@ fn calculate_block_hash_header(header: &BlockHeader) -> Hash {
     Hash::new(hash_array)
 }
 
+/// Header for a template, proposed by `proposer` with `vrf_reveal`
+fn template_header(template: &BlockTemplate, proposer: PublicKey, vrf_reveal: VrfProof) -> BlockHeader {
+    BlockHeader {
+        version: 1,
+        block_hash: template.header_hash,
+        selected_parent_hash: template.selected_parent,
+        merge_parent_hashes: template.merge_parents.clone(),
+        timestamp: template.timestamp,
+        height: template.height,
+        blue_score: template.blue_score,
+        blue_work: template.blue_work,
+        pruning_point: template.pruning_point,
+        proposer_pubkey: proposer,
+        vrf_reveal,
+        base_fee_per_gas: template.base_fee_per_gas,
+        gas_used: 0, // Will be updated after execution
+        gas_limit: template.gas_limit,
+        epoch_commitment: template.epoch_commitment,
+    }
+}
+
+/// Template id: the header hash together with the ordered transactions,
+/// which the header hash does not cover
+fn template_id(template: &BlockTemplate) -> Hash {
+    let mut hasher = Sha3_256::new();
+    hasher.update(template.header_hash.as_bytes());
+    for tx in &template.transactions {
+        hasher.update(tx.hash.as_bytes());
+    }
+    Hash::from_bytes(&hasher.finalize())
+}
+
 /// Block producer for mining new blocks
 pub struct BlockProducer {
     storage: Arc<StorageManager>,
@@ -53,9 +107,22 @@ pub struct BlockProducer {
     ai_state_manager: Arc<AIStateManager>,
     peer_manager: Option<Arc<PeerManager>>,
     coinbase: PublicKey,
-    target_block_time: u64,
+    /// When the next block is mined
+    schedule: MiningSchedule,
     reward_calculator: RewardCalculator,
     economics_manager: Option<Arc<UnifiedEconomicsManager>>,
+    /// Remote signer and the account it signs blocks with
+    remote_signer: Option<(Arc<RemoteSigner>, PublicKey)>,
+    /// Local block signing key, kept apart from the coinbase
+    signing_key: Option<Arc<BlockSigningKey>>,
+    /// Signed epoch summaries, committed to in new headers
+    epochs: Option<Arc<EpochSummaryPool>>,
+    /// Gossip relay new blocks are announced through
+    gossip: Option<Arc<GossipProtocol>>,
+    /// Templates handed out to external producers, oldest first
+    templates: Mutex<VecDeque<BlockTemplate>>,
+    /// Held while a block is built or a submitted one is inserted
+    insert_lock: Mutex<()>,
 }
 
 impl BlockProducer {
@@ -109,9 +176,15 @@ impl BlockProducer {
             ai_state_manager,
             peer_manager: None,
             coinbase,
-            target_block_time,
+            schedule: MiningSchedule::new(target_block_time),
             reward_calculator,
             economics_manager: None,
+            remote_signer: None,
+            signing_key: None,
+            epochs: None,
+            gossip: None,
+            templates: Mutex::new(VecDeque::new()),
+            insert_lock: Mutex::new(()),
         }
     }
 
@@ -166,9 +239,15 @@ impl BlockProducer {
             ai_state_manager,
             peer_manager,
             coinbase,
-            target_block_time,
+            schedule: MiningSchedule::new(target_block_time),
             reward_calculator,
             economics_manager: None,
+            remote_signer: None,
+            signing_key: None,
+            epochs: None,
+            gossip: None,
+            templates: Mutex::new(VecDeque::new()),
+            insert_lock: Mutex::new(()),
         }
     }
 
@@ -214,9 +293,15 @@ impl BlockProducer {
             ai_state_manager,
             peer_manager,
             coinbase,
-            target_block_time,
+            schedule: MiningSchedule::new(target_block_time),
             reward_calculator,
             economics_manager: None,
+            remote_signer: None,
+            signing_key: None,
+            epochs: None,
+            gossip: None,
+            templates: Mutex::new(VecDeque::new()),
+            insert_lock: Mutex::new(()),
         }
     }
 
@@ -270,19 +355,58 @@ impl BlockProducer {
             ai_state_manager,
             peer_manager,
             coinbase,
-            target_block_time,
+            schedule: MiningSchedule::new(target_block_time),
             reward_calculator,
             economics_manager: Some(economics_manager),
+            remote_signer: None,
+            signing_key: None,
+            epochs: None,
+            gossip: None,
+            templates: Mutex::new(VecDeque::new()),
+            insert_lock: Mutex::new(()),
+        }
+    }
+
+    /// Sign blocks through a remote signer; `account` becomes the proposer key
+    pub fn with_remote_signer(mut self, signer: Arc<RemoteSigner>, account: PublicKey) -> Self {
+        self.remote_signer = Some((signer, account));
+        self
+    }
+
+    /// Sign blocks with a local key that can be rotated while running;
+    /// rewards still go to the coinbase
+    pub fn with_signing_key(mut self, signing_key: Arc<BlockSigningKey>) -> Self {
+        self.signing_key = Some(signing_key);
+        self
+    }
+
+    /// Commit to the latest epoch summary signed in `pool`
+    pub fn with_epochs(mut self, pool: Arc<EpochSummaryPool>) -> Self {
+        self.epochs = Some(pool);
+        self
+    }
+
+    /// Announce new blocks through `gossip` instead of a plain broadcast
+    pub fn with_gossip(mut self, gossip: Arc<GossipProtocol>) -> Self {
+        self.gossip = Some(gossip);
+        self
+    }
+
+    /// Key blocks are proposed under
+    fn proposer(&self) -> PublicKey {
+        match (&self.remote_signer, &self.signing_key) {
+            (Some((_, account)), _) => *account,
+            (None, Some(key)) => key.public_key(),
+            (None, None) => self.coinbase,
         }
     }
 
     /// Start block production loop
     pub async fn start(self: Arc<Self>) {
-        let mut interval = interval(Duration::from_secs(self.target_block_time));
         let mut block_count = 0u64;
 
         loop {
-            interval.tick().await;
+            self.schedule.wait_for_block(&self.mempool).await;
 
             match self.produce_block().await {
                 Ok(block_hash) => {
@@ -303,6 +427,42 @@ impl BlockProducer {
 
     /// Produce a single block
     async fn produce_block(&self) -> anyhow::Result<Hash> {
+        let started = std::time::Instant::now();
+        let _inserting = self.insert_lock.lock().await;
+
+        let template = self.next_template().await?;
+        let header = template_header(
+            &template,
+            self.proposer(),
+            VrfProof {
+                proof: vec![],
+                output: Hash::default(),
+            },
+        );
+
+        // Sign before executing so a refused signature leaves state untouched
+        let signature = match &self.remote_signer {
+            Some((signer, account)) => {
+                signer
+                    .sign_data(account, content_types::BLOCK, header.block_hash.as_bytes())
+                    .await?
+            }
+            None => match &self.signing_key {
+                // Recheck the proposer in case the key rotated mid-block
+                Some(key) if key.public_key() == header.proposer_pubkey => {
+                    key.sign(header.block_hash.as_bytes())
+                }
+                Some(_) => anyhow::bail!("Block signing key rotated while building block"),
+                None => Signature::new([1; 64]), // Dummy signature for devnet
+            },
+        };
+
+        self.insert_block(header, signature, template.transactions, self.coinbase, started)
+            .await
+    }
+
+    /// Parents, scores and transactions for a block on top of the current tips
+    async fn next_template(&self) -> anyhow::Result<BlockTemplate> {
         // Get current tips for parent selection
         let tips = self.dag_store.get_tips().await;
 
@@ -315,19 +475,82 @@ impl BlockProducer {
             self.select_parents_with_ghostdag(&tips).await?
         };
 
-        // Calculate blue set for the new block
-        let temp_block = citrate_consensus::types::Block {
-            header: citrate_consensus::types::BlockHeader {
+        let timestamp = chrono::Utc::now().timestamp() as u64;
+        let parents_block = self.parents_block(selected_parent, merge_parents.clone(), timestamp);
+        let blue_set = self.ghostdag.calculate_blue_set(&parents_block).await?;
+        let blue_score = self.ghostdag.calculate_blue_score(&parents_block).await?;
+
+        // Get last block height and VRF output from selected parent
+        let parent = if selected_parent != Hash::default() {
+            self.storage
+                .blocks
+                .get_block(&selected_parent)
+                .ok()
+                .flatten()
+        } else {
+            None
+        };
+        let last_height = parent.as_ref().map(|b| b.header.height).unwrap_or(0);
+        let previous_vrf = parent
+            .map(|b| b.header.vrf_reveal.output)
+            .unwrap_or_default();
+
+        // Get transactions from mempool with AI priority
+        let transactions = self.select_transactions_with_ai_priority().await?;
+
+        // Blue score and work are already calculated above
+        let blue_work = self.calculate_blue_work(&blue_set, blue_score)?;
+
+        // Commit to the current pruning point so peers can verify finality
+        let pruning_point = self.current_pruning_point().await;
+
+        let mut template = BlockTemplate {
+            id: Hash::default(),
+            selected_parent,
+            merge_parents,
+            height: last_height + 1,
+            blue_score,
+            blue_work,
+            pruning_point,
+            timestamp,
+            base_fee_per_gas: 1_000_000_000, // 1 gwei - TODO: calculate from parent
+            gas_limit: 30_000_000,           // 30M gas default
+            vrf_challenge: vrf_challenge(&previous_vrf, last_height + 1),
+            epoch_commitment: match &self.epochs {
+                Some(pool) => pool.header_commitment().await,
+                None => Hash::default(),
+            },
+            header_hash: Hash::default(),
+            transactions,
+            expires_at: timestamp + TEMPLATE_TTL_SECS,
+        };
+        // The proposer and VRF reveal are not part of the header hash
+        template.header_hash = calculate_block_hash_header(&template_header(
+            &template,
+            self.proposer(),
+            VrfProof {
+                proof: vec![],
+                output: Hash::default(),
+            },
+        ));
+        template.id = template_id(&template);
+        Ok(template)
+    }
+
+    /// Block shell with only parents and timestamp set, for GhostDAG scoring
+    fn parents_block(&self, selected_parent: Hash, merge_parents: Vec<Hash>, timestamp: u64) -> Block {
+        Block {
+            header: BlockHeader {
                 version: 1,
                 block_hash: Hash::default(),
                 selected_parent_hash: selected_parent,
-                merge_parent_hashes: merge_parents.clone(),
-                timestamp: chrono::Utc::now().timestamp() as u64,
+                merge_parent_hashes: merge_parents,
+                timestamp,
                 height: 0,     // Will be calculated
                 blue_score: 0, // Will be calculated
                 blue_work: 0,  // Will be calculated
                 pruning_point: Hash::default(),
-                proposer_pubkey: self.coinbase,
+                proposer_pubkey: self.proposer(),
                 vrf_reveal: VrfProof {
                     proof: vec![],
                     output: Hash::default(),
@@ -335,66 +558,108 @@ impl BlockProducer {
                 base_fee_per_gas: 1_000_000_000, // 1 gwei
                 gas_used: 0,
                 gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
             },
             state_root: Hash::default(),
             tx_root: Hash::default(),
             receipt_root: Hash::default(),
             artifact_root: Hash::default(),
-            ghostdag_params: citrate_consensus::types::GhostDagParams::default(),
+            ghostdag_params: GhostDagParams::default(),
             transactions: vec![],
             signature: Signature::new([0; 64]),
             embedded_models: vec![],
             required_pins: vec![],
-        };
-
-        let blue_set = self.ghostdag.calculate_blue_set(&temp_block).await?;
-        let blue_score = self.ghostdag.calculate_blue_score(&temp_block).await?;
+        }
+    }
 
-        // Get last block height from selected parent
-        let last_height = if selected_parent != Hash::default() {
-            // Get parent block from storage to determine height
-            self.storage
-                .blocks
-                .get_block(&selected_parent)
-                .ok()
-                .and_then(|b| b.map(|block| block.header.height))
-                .unwrap_or(0)
-        } else {
-            0
-        };
+    async fn current_pruning_point(&self) -> Hash {
+        self.ghostdag
+            .pruning_point()
+            .await
+            .map(|p| p.hash)
+            .unwrap_or_default()
+    }
 
-        // Get transactions from mempool with AI priority
-        let transactions = self.select_transactions_with_ai_priority().await?;
+    /// Check that a template handed out earlier still fits the DAG and
+    /// state: its parents are known and still score the same, the pruning
+    /// point has not moved, and none of its transactions were included or
+    /// overtaken by another block since.
+    async fn revalidate_template(&self, template: &BlockTemplate) -> Result<(), BlockSubmitError> {
+        if self.storage.blocks.has_block(&template.header_hash).unwrap_or(false) {
+            return Err(BlockSubmitError::Stale("block already inserted".to_string()));
+        }
 
-        // Blue score and work are already calculated above
-        let blue_work = self.calculate_blue_work(&blue_set, blue_score)?;
+        let parents = std::iter::once(&template.selected_parent)
+            .chain(&template.merge_parents)
+            .filter(|p| **p != Hash::default());
+        for parent in parents {
+            if !self.dag_store.has_block(parent).await {
+                return Err(BlockSubmitError::Stale(format!("parent {} is unknown", parent)));
+            }
+        }
 
-        // Create block header with GhostDAG consensus data
-        let mut header = BlockHeader {
-            version: 1,
-            block_hash: Hash::default(), // Will be computed
-            selected_parent_hash: selected_parent,
-            merge_parent_hashes: merge_parents,
-            timestamp: chrono::Utc::now().timestamp() as u64,
-            height: last_height + 1,
-            blue_score,
-            blue_work,
-            pruning_point: Hash::default(),
-            proposer_pubkey: self.coinbase,
-            vrf_reveal: VrfProof {
-                proof: vec![],
-                output: Hash::default(),
-            },
-            base_fee_per_gas: 1_000_000_000, // 1 gwei - TODO: calculate from parent
-            gas_used: 0, // Will be updated after execution
-            gas_limit: 30_000_000, // 30M gas default
-        };
+        let parents_block = self.parents_block(
+            template.selected_parent,
+            template.merge_parents.clone(),
+            template.timestamp,
+        );
+        let blue_score = self
+            .ghostdag
+            .calculate_blue_score(&parents_block)
+            .await
+            .map_err(|e| BlockSubmitError::Internal(e.to_string()))?;
+        if blue_score != template.blue_score {
+            return Err(BlockSubmitError::Stale(format!(
+                "blue score is now {}, template has {}",
+                blue_score, template.blue_score
+            )));
+        }
+        if self.current_pruning_point().await != template.pruning_point {
+            return Err(BlockSubmitError::Stale("pruning point moved".to_string()));
+        }
 
-        // Compute block hash (simplified)
-        header.block_hash = calculate_block_hash_header(&header);
+        let signatures = crypto::verify_transactions(&template.transactions);
+        for (tx, valid) in template.transactions.iter().zip(signatures) {
+            if !matches!(valid, Ok(true)) {
+                return Err(BlockSubmitError::Invalid(format!(
+                    "invalid signature on transaction {}",
+                    tx.hash
+                )));
+            }
+            if self
+                .storage
+                .transactions
+                .has_transaction(&tx.hash)
+                .unwrap_or(false)
+            {
+                return Err(BlockSubmitError::Stale(format!(
+                    "transaction {} is already included",
+                    tx.hash
+                )));
+            }
+            let sender = citrate_execution::types::Address::from_public_key(&tx.from);
+            if self.executor.get_nonce(&sender) > tx.nonce {
+                return Err(BlockSubmitError::Stale(format!(
+                    "transaction {} nonce was already used",
+                    tx.hash
+                )));
+            }
+        }
+        Ok(())
+    }
 
+    /// Execute a signed block, pay its rewards to `beneficiary`, then store
+    /// and broadcast it. Callers hold `insert_lock`.
+    async fn insert_block(
+        &self,
+        header: BlockHeader,
+        signature: Signature,
+        transactions: Vec<Transaction>,
+        beneficiary: PublicKey,
+        started: std::time::Instant,
+    ) -> anyhow::Result<Hash> {
         // Execute transactions and calculate state roots
-        let (state_root, receipts) = self
+        let (state_root, receipts, internal) = self
             .execute_block_transactions(&transactions, &header)
             .await?;
         let tx_root = self.calculate_tx_root(&transactions)?;
@@ -410,7 +675,7 @@ impl BlockProducer {
             artifact_root,
             ghostdag_params: self.ghostdag.params().clone(),
             transactions,
-            signature: Signature::new([1; 64]), // Dummy signature for devnet
+            signature,
             embedded_models: vec![],
             required_pins: vec![],
         };
@@ -425,7 +690,7 @@ impl BlockProducer {
 
             // Apply economics-based rewards to validator
             let validator_address = citrate_execution::types::Address(
-                self.coinbase.0[0..20].try_into().unwrap_or([0; 20])
+                beneficiary.0[0..20].try_into().unwrap_or([0; 20])
             );
 
             // Calculate rewards based on economics config and network participation
@@ -471,11 +736,14 @@ impl BlockProducer {
             // Use basic reward system as fallback
             let reward = self.reward_calculator.calculate_reward(&block);
             let validator_address = citrate_execution::types::Address(
-                self.coinbase.0[0..20].try_into().unwrap_or([0; 20])
+                beneficiary.0[0..20].try_into().unwrap_or([0; 20])
             );
             self.apply_basic_rewards(&reward, &validator_address);
         }
 
+        // Record the block's state in the archive (archive mode only)
+        self.executor.archive_block_state(block.header.height)?;
+
         // Persist state changes from executed transactions to storage
         info!("Persisting state changes to storage...");
         let modified_count = self.executor.persist_state_changes()?;
@@ -485,7 +753,17 @@ impl BlockProducer {
         self.storage.blocks.put_block(&block)?;
 
         // Broadcast block to connected peers
-        if let Some(peer_manager) = &self.peer_manager {
+        if let Some(gossip) = &self.gossip {
+            let gossip = gossip.clone();
+            let block = block.clone();
+            tokio::spawn(async move {
+                if let Err(e) = gossip.announce_block(block).await {
+                    tracing::warn!("Failed to announce block to peers: {}", e);
+                } else {
+                    tracing::info!("Broadcasted new block to peers");
+                }
+            });
+        } else if let Some(peer_manager) = &self.peer_manager {
             let block_msg = NetworkMessage::NewBlock {
                 block: block.clone(),
             };
@@ -518,6 +796,9 @@ impl BlockProducer {
             if !pairs.is_empty() {
                 self.storage.transactions.put_receipts(&pairs)?;
             }
+            self.storage
+                .transactions
+                .put_internal_transactions(&internal)?;
 
             // Remove included transactions from mempool
             for tx in &block.transactions {
@@ -528,6 +809,10 @@ impl BlockProducer {
         // Update DAG store
         self.dag_store.store_block(block.clone()).await?;
 
+        metrics::record(MetricEvent::BlockBuilt {
+            seconds: started.elapsed().as_secs_f64(),
+        });
+
         Ok(header.block_hash)
     }
 
@@ -572,6 +857,28 @@ impl BlockProducer {
             .await;
         selected.extend(standard_txs);
 
+        // Keep within the governed byte and model metadata limits. A skipped
+        // transaction holds back its sender's later nonces.
+        let limits = self
+            .executor
+            .governance_block_limits(citrate_consensus::BlockLimits::default());
+        let mut usage = citrate_consensus::BlockUsage::default();
+        let mut held_back = std::collections::HashSet::new();
+        selected.retain(|tx| {
+            if held_back.contains(&tx.from) {
+                return false;
+            }
+            let mut next = usage;
+            next.add(tx);
+            if limits.allows(&next) {
+                usage = next;
+                true
+            } else {
+                held_back.insert(tx.from);
+                false
+            }
+        });
+
         Ok(selected)
     }
 
@@ -580,8 +887,13 @@ impl BlockProducer {
         &self,
         transactions: &[Transaction],
         header: &BlockHeader,
-    ) -> anyhow::Result<(Hash, Vec<citrate_execution::types::TransactionReceipt>)> {
+    ) -> anyhow::Result<(
+        Hash,
+        Vec<citrate_execution::types::TransactionReceipt>,
+        Vec<(Hash, Vec<InternalTransaction>)>,
+    )> {
         let mut receipts = Vec::new();
+        let mut internal = Vec::new();
 
         // Create a temporary block for execution context
         let temp_block = Block {
@@ -599,8 +911,15 @@ impl BlockProducer {
 
         // Execute each transaction
         for tx in transactions {
-            match self.executor.execute_transaction(&temp_block, tx).await {
-                Ok(receipt) => receipts.push(receipt),
+            match self
+                .executor
+                .execute_transaction_with_internal(&temp_block, tx)
+                .await
+            {
+                Ok((receipt, transfers)) => {
+                    receipts.push(receipt);
+                    internal.push((tx.hash, transfers));
+                }
                 Err(e) => {
                     error!("Failed to execute transaction {}: {}", tx.hash, e);
                     // Create failed receipt
@@ -624,7 +943,7 @@ impl BlockProducer {
         // Calculate final state root including AI state
         let state_root = self.ai_state_manager.calculate_state_root().await?;
 
-        Ok((state_root, receipts))
+        Ok((state_root, receipts, internal))
     }
 
     /// Calculate transaction root
@@ -725,3 +1044,99 @@ impl BlockProducer {
         }
     }
 }
+
+#[async_trait]
+impl BlockTemplateProvider for BlockProducer {
+    async fn block_template(&self) -> anyhow::Result<BlockTemplate> {
+        let template = self.next_template().await?;
+        let now = chrono::Utc::now().timestamp() as u64;
+        let mut templates = self.templates.lock().await;
+        templates.retain(|t| t.expires_at >= now);
+        if templates.len() >= MAX_OPEN_TEMPLATES {
+            templates.pop_front();
+        }
+        templates.push_back(template.clone());
+        Ok(template)
+    }
+
+    async fn submit_block(&self, submission: BlockSubmission) -> Result<Hash, BlockSubmitError> {
+        let started = std::time::Instant::now();
+
+        // Templates are single use
+        let template = {
+            let mut templates = self.templates.lock().await;
+            let index = templates
+                .iter()
+                .position(|t| t.id == submission.template_id)
+                .ok_or(BlockSubmitError::UnknownTemplate)?;
+            templates
+                .remove(index)
+                .ok_or(BlockSubmitError::UnknownTemplate)?
+        };
+        if chrono::Utc::now().timestamp() as u64 > template.expires_at {
+            return Err(BlockSubmitError::Stale("template expired".to_string()));
+        }
+        if !proof_output_matches(&submission.vrf_proof) {
+            return Err(BlockSubmitError::Invalid(
+                "VRF output does not match the proof".to_string(),
+            ));
+        }
+        if !crypto::verify_signature(
+            &submission.proposer,
+            template.header_hash.as_bytes(),
+            &submission.signature,
+        ) {
+            return Err(BlockSubmitError::Invalid(
+                "signature does not match the proposer".to_string(),
+            ));
+        }
+
+        let _inserting = self.insert_lock.lock().await;
+        self.revalidate_template(&template).await?;
+
+        let header = template_header(&template, submission.proposer, submission.vrf_proof);
+        let hash = self
+            .insert_block(
+                header,
+                submission.signature,
+                template.transactions,
+                submission.proposer,
+                started,
+            )
+            .await
+            .map_err(|e| BlockSubmitError::Internal(e.to_string()))?;
+        info!(
+            "Inserted externally produced block {} at height {}",
+            hex::encode(&hash.as_bytes()[..8]),
+            template.height
+        );
+        Ok(hash)
+    }
+}
+
+#[cfg(feature = "devnet")]
+#[async_trait]
+impl DevChainControl for BlockProducer {
+    fn mining_mode(&self) -> citrate_sequencer::MiningMode {
+        self.schedule.mode()
+    }
+
+    fn set_block_interval(&self, seconds: u64) {
+        info!("Devnet block interval set to {}s", seconds);
+        self.schedule.set_block_interval(seconds);
+    }
+
+    fn set_automine(&self, enabled: bool) {
+        info!("Devnet automine {}", if enabled { "enabled" } else { "disabled" });
+        self.schedule.set_automine(enabled);
+    }
+
+    async fn mine_blocks(&self, count: u64) -> anyhow::Result<Vec<Hash>> {
+        let mut hashes = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            hashes.push(self.produce_block().await?);
+        }
+        info!("Mined {} block(s) on request", hashes.len());
+        Ok(hashes)
+    }
+}
@@ -0,0 +1,379 @@
+//! Selective removal of node data
+//!
+//! Deleting the data directory by hand also deletes the block signing key,
+//! the P2P node key and, with the default layout, pinned models. A purge
+//! removes only the categories asked for: the chain database is removed file
+//! by file, leaving everything else in the directory alone.
+//!
+//! A purge is reviewed before it runs. Planning lists what would be removed
+//! and derives a confirmation token from that list; the purge is carried out
+//! only with the same token, so one issued before the data changed no
+//! longer works.
+
+use anyhow::{bail, Context, Result};
+use citrate_storage::db::column_families::{CF_ARCHIVE, CF_PEERS};
+use citrate_storage::db::RocksDB;
+use sha3::{Digest, Sha3_256};
+use std::path::{Path, PathBuf};
+
+/// Categories of data to remove
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PurgeSelection {
+    /// The whole chain database: blocks, state and peer records
+    pub chain: bool,
+    /// Per-block state history kept in archive mode
+    pub state: bool,
+    /// Peer reputation records and bans
+    pub peers: bool,
+    /// Pinned model weights
+    pub models: bool,
+    /// Conversation history of the desktop app's agent
+    pub agent_history: bool,
+}
+
+impl PurgeSelection {
+    pub fn is_empty(&self) -> bool {
+        !(self.chain || self.state || self.peers || self.models || self.agent_history)
+    }
+}
+
+/// How an item is removed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PurgeAction {
+    /// Delete files or directories
+    Remove(Vec<PathBuf>),
+    /// Delete every key of a column family in the chain database
+    Clear(&'static str),
+}
+
+/// One thing a purge removes
+#[derive(Debug, Clone)]
+pub struct PurgeItem {
+    pub category: &'static str,
+    pub description: String,
+    /// Bytes on disk, or keys for a column family
+    pub size: u64,
+    pub action: PurgeAction,
+}
+
+/// What a purge of a data directory would remove
+#[derive(Debug)]
+pub struct PurgePlan {
+    pub data_dir: PathBuf,
+    pub items: Vec<PurgeItem>,
+}
+
+impl PurgePlan {
+    /// Token to confirm this plan with
+    pub fn token(&self) -> String {
+        let mut hasher = Sha3_256::new();
+        hasher.update(self.data_dir.to_string_lossy().as_bytes());
+        for item in &self.items {
+            hasher.update(format!(
+                "\n{}\t{}\t{}",
+                item.category, item.description, item.size
+            ));
+        }
+        hex::encode(&hasher.finalize()[..4])
+    }
+
+    pub fn print(&self) {
+        if self.items.is_empty() {
+            println!("Nothing to purge in {}", self.data_dir.display());
+            return;
+        }
+        println!("Purge would remove:");
+        for item in &self.items {
+            let size = match item.action {
+                PurgeAction::Remove(_) => format_bytes(item.size),
+                PurgeAction::Clear(_) => format!("{} entries", item.size),
+            };
+            println!("  {:<14} {} ({})", item.category, item.description, size);
+        }
+    }
+
+    /// Remove everything in the plan once `token` matches it
+    pub fn execute(&self, token: &str) -> Result<()> {
+        if token.trim() != self.token() {
+            bail!(
+                "Confirmation token doesn't match; run without --confirm to review the purge again"
+            );
+        }
+
+        let clears: Vec<&str> = self
+            .items
+            .iter()
+            .filter_map(|item| match item.action {
+                PurgeAction::Clear(cf) => Some(cf),
+                PurgeAction::Remove(_) => None,
+            })
+            .collect();
+        if !clears.is_empty() {
+            let db = open_database(&self.data_dir)?;
+            for cf in clears {
+                db.clear_cf(cf)?;
+            }
+        }
+
+        for item in &self.items {
+            if let PurgeAction::Remove(paths) = &item.action {
+                for path in paths {
+                    let removed = if path.is_dir() {
+                        std::fs::remove_dir_all(path)
+                    } else {
+                        std::fs::remove_file(path)
+                    };
+                    match removed {
+                        Ok(()) => {}
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                        Err(e) => {
+                            return Err(e)
+                                .with_context(|| format!("Failed to remove {}", path.display()))
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Plan a purge of the node data in `data_dir`. `models_dir` holds pinned
+/// models and `agent_history` the desktop app's conversation database.
+pub fn plan(
+    selection: PurgeSelection,
+    data_dir: &Path,
+    models_dir: &Path,
+    agent_history: Option<&Path>,
+) -> Result<PurgePlan> {
+    let mut items = Vec::new();
+    let has_database = data_dir.join("CURRENT").exists();
+
+    if selection.chain && has_database {
+        // Fails while a node holds the database open
+        drop(open_database(data_dir)?);
+        let files = database_files(data_dir)?;
+        items.push(PurgeItem {
+            category: "chain",
+            description: format!(
+                "{} database files in {} (blocks, state and peer records)",
+                files.len(),
+                data_dir.display()
+            ),
+            size: files.iter().map(|path| disk_usage(path)).sum(),
+            action: PurgeAction::Remove(files),
+        });
+    } else if (selection.state || selection.peers) && has_database {
+        let db = open_database(data_dir)?;
+        let columns = [
+            (
+                selection.state,
+                "state",
+                CF_ARCHIVE,
+                "archived state history",
+            ),
+            (
+                selection.peers,
+                "peers",
+                CF_PEERS,
+                "peer reputation and bans",
+            ),
+        ];
+        for (_, category, cf, description) in columns.into_iter().filter(|c| c.0) {
+            let keys = db.iter_cf(cf)?.count() as u64;
+            if keys > 0 {
+                items.push(PurgeItem {
+                    category,
+                    description: description.to_string(),
+                    size: keys,
+                    action: PurgeAction::Clear(cf),
+                });
+            }
+        }
+    }
+
+    if selection.models && models_dir.exists() {
+        items.push(PurgeItem {
+            category: "models",
+            description: models_dir.display().to_string(),
+            size: disk_usage(models_dir),
+            action: PurgeAction::Remove(vec![models_dir.to_path_buf()]),
+        });
+    }
+
+    if let Some(database) = agent_history.filter(|_| selection.agent_history) {
+        // SQLite keeps its journal beside the database
+        let files: Vec<PathBuf> = ["", "-wal", "-shm"]
+            .iter()
+            .map(|suffix| PathBuf::from(format!("{}{}", database.display(), suffix)))
+            .filter(|path| path.exists())
+            .collect();
+        if !files.is_empty() {
+            items.push(PurgeItem {
+                category: "agent history",
+                description: database.display().to_string(),
+                size: files.iter().map(|path| disk_usage(path)).sum(),
+                action: PurgeAction::Remove(files),
+            });
+        }
+    }
+
+    Ok(PurgePlan {
+        data_dir: data_dir.to_path_buf(),
+        items,
+    })
+}
+
+/// Conversation database of the desktop app's agent
+pub fn default_agent_history() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("citrate").join("conversations.db"))
+}
+
+fn open_database(data_dir: &Path) -> Result<RocksDB> {
+    RocksDB::open(data_dir).with_context(|| {
+        format!(
+            "Failed to open the chain database in {}; stop the node first",
+            data_dir.display()
+        )
+    })
+}
+
+/// Files of the RocksDB database kept directly in `data_dir`
+fn database_files(data_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(data_dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if entry.file_type()?.is_file() && is_database_file(&name.to_string_lossy()) {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn is_database_file(name: &str) -> bool {
+    let numbered = |ext: &str| {
+        name.strip_suffix(ext)
+            .is_some_and(|stem| !stem.is_empty() && stem.bytes().all(|b| b.is_ascii_digit()))
+    };
+    matches!(name, "CURRENT" | "IDENTITY" | "LOCK" | "LOG")
+        || name.starts_with("LOG.old.")
+        || name.starts_with("MANIFEST-")
+        || name.starts_with("OPTIONS-")
+        || [".sst", ".log", ".blob", ".dbtmp"]
+            .into_iter()
+            .any(numbered)
+}
+
+fn disk_usage(path: &Path) -> u64 {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => std::fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| disk_usage(&entry.path()))
+                    .sum()
+            })
+            .unwrap_or(0),
+        Ok(meta) => meta.len(),
+        Err(_) => 0,
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_database_file() {
+        for name in [
+            "CURRENT",
+            "LOCK",
+            "000012.sst",
+            "000013.log",
+            "MANIFEST-000005",
+            "OPTIONS-000007",
+            "LOG.old.1700000000",
+        ] {
+            assert!(is_database_file(name), "{}", name);
+        }
+        for name in [
+            "signing.key",
+            "node.key",
+            "telemetry.id",
+            "webhooks.cursor",
+            "node.log",
+            "models",
+        ] {
+            assert!(!is_database_file(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_chain_purge_keeps_keys_and_models() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = dir.path();
+        drop(RocksDB::open(data_dir).unwrap());
+        std::fs::write(data_dir.join("signing.key"), "00").unwrap();
+        std::fs::write(data_dir.join("node.key"), "00").unwrap();
+        let models_dir = data_dir.join("models");
+        std::fs::create_dir_all(&models_dir).unwrap();
+        std::fs::write(models_dir.join("pinned_models.json"), "{}").unwrap();
+
+        let selection = PurgeSelection {
+            chain: true,
+            ..Default::default()
+        };
+        let plan = plan(selection, data_dir, &models_dir, None).unwrap();
+        assert_eq!(plan.items.len(), 1);
+        assert!(plan.execute("00000000").is_err());
+        assert!(data_dir.join("CURRENT").exists());
+
+        plan.execute(&plan.token()).unwrap();
+        assert!(!data_dir.join("CURRENT").exists());
+        assert!(data_dir.join("signing.key").exists());
+        assert!(data_dir.join("node.key").exists());
+        assert!(models_dir.join("pinned_models.json").exists());
+    }
+
+    #[test]
+    fn test_peer_purge_clears_only_peers() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let db = RocksDB::open(dir.path()).unwrap();
+            db.put_cf(CF_PEERS, b"10.0.0.1", b"banned").unwrap();
+            db.put_cf("blocks", b"block", b"data").unwrap();
+        }
+
+        let selection = PurgeSelection {
+            peers: true,
+            state: true,
+            ..Default::default()
+        };
+        let plan = plan(selection, dir.path(), &dir.path().join("models"), None).unwrap();
+        // The archive is empty, so only peers are listed
+        assert_eq!(plan.items.len(), 1);
+        assert_eq!(plan.items[0].action, PurgeAction::Clear(CF_PEERS));
+        plan.execute(&plan.token()).unwrap();
+
+        let db = RocksDB::open(dir.path()).unwrap();
+        assert_eq!(db.iter_cf(CF_PEERS).unwrap().count(), 0);
+        assert!(db.exists_cf("blocks", b"block").unwrap());
+    }
+}
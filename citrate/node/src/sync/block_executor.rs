@@ -0,0 +1,64 @@
+// citrate/node/src/sync/block_executor.rs
+
+//! Executes blocks imported by the network sync pipeline.
+
+use async_trait::async_trait;
+use citrate_consensus::limits::BlockLimits;
+use citrate_consensus::types::Block;
+use citrate_execution::types::{Address, TransactionReceipt};
+use citrate_execution::Executor;
+use citrate_network::BlockExecutor;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// Replays synced blocks on the node's executor
+pub struct SyncBlockExecutor {
+    executor: Arc<Executor>,
+}
+
+impl SyncBlockExecutor {
+    pub fn new(executor: Arc<Executor>) -> Self {
+        Self { executor }
+    }
+}
+
+#[async_trait]
+impl BlockExecutor for SyncBlockExecutor {
+    async fn execute_block(&self, block: &Block) -> anyhow::Result<Vec<TransactionReceipt>> {
+        let mut receipts = Vec::with_capacity(block.transactions.len());
+        for tx in &block.transactions {
+            match self.executor.execute_transaction(block, tx).await {
+                Ok(receipt) => receipts.push(receipt),
+                Err(e) => {
+                    // Same failed receipt the producer records
+                    warn!("Synced transaction {} failed: {}", tx.hash, e);
+                    receipts.push(TransactionReceipt {
+                        tx_hash: tx.hash,
+                        block_hash: block.hash(),
+                        block_number: block.header.height,
+                        from: Address::from_public_key(&tx.from),
+                        to: tx.to.map(|pk| Address::from_public_key(&pk)),
+                        gas_used: tx.gas_limit,
+                        status: false,
+                        logs: vec![],
+                        output: vec![],
+                    });
+                }
+            }
+        }
+        // Archive per block; commits span whole batches
+        self.executor.archive_block_state(block.header.height)?;
+        Ok(receipts)
+    }
+
+    async fn commit(&self) -> anyhow::Result<()> {
+        let modified = self.executor.persist_state_changes()?;
+        debug!("Persisted {} modified accounts after sync batch", modified);
+        Ok(())
+    }
+
+    fn block_limits(&self) -> BlockLimits {
+        self.executor
+            .governance_block_limits(BlockLimits::default())
+    }
+}
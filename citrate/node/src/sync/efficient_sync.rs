@@ -3,6 +3,7 @@
 // Efficient, non-recursive sync implementation for GhostDAG
 // Avoids stack overflow by using iterative processing and bounded queues
 
+use citrate_consensus::crypto;
 use citrate_consensus::types::{Block, BlockHeader, Hash};
 use citrate_consensus::GhostDag;
 use citrate_storage::StorageManager;
@@ -101,9 +102,20 @@ impl EfficientSyncManager {
         let mut skipped = 0;
         let mut errors = 0;
 
+        // Verify every transaction signature in the batch at once
+        let valid_signatures = verify_block_signatures(blocks);
+
         // Add blocks to queue if not seen
-        for block in blocks {
+        for (block, signatures_ok) in blocks.iter().zip(valid_signatures) {
             let block_hash = block.hash();
+            if !signatures_ok {
+                warn!(
+                    "Block {} contains transactions with invalid signatures",
+                    block_hash.to_hex()
+                );
+                errors += 1;
+                continue;
+            }
             if !self.seen_blocks.contains(&block_hash) {
                 let block_size = self.estimate_block_size(block);
 
@@ -463,6 +475,24 @@ struct BatchResult {
     errors: usize,
 }
 
+/// Check the transaction signatures of several blocks with one batch
+/// verification. Returns, per block, whether all of its signatures are valid.
+fn verify_block_signatures(blocks: &[Block]) -> Vec<bool> {
+    let mut results =
+        crypto::verify_transactions(blocks.iter().flat_map(|block| &block.transactions)).into_iter();
+
+    blocks
+        .iter()
+        .map(|block| {
+            // Consume the whole block so the next one lines up
+            results
+                .by_ref()
+                .take(block.transactions.len())
+                .fold(true, |ok, result| ok & matches!(result, Ok(true)))
+        })
+        .collect()
+}
+
 /// Parallel sync coordinator for multiple peers
 pub struct ParallelSyncCoordinator {
     storage: Arc<StorageManager>,
@@ -673,7 +703,7 @@ impl ParallelSyncCoordinator {
 mod tests {
     use super::*;
     use citrate_consensus::dag_store::DagStore;
-    use citrate_consensus::types::{GhostDagParams, PublicKey, Signature, VrfProof};
+    use citrate_consensus::types::{GhostDagParams, PublicKey, Signature, Transaction, VrfProof};
     use citrate_storage::pruning::PruningConfig;
     use tempfile::TempDir;
 
@@ -698,6 +728,7 @@ mod tests {
                 base_fee_per_gas: 0,
                 gas_used: 0,
                 gas_limit: 30_000_000,
+                epoch_commitment: Hash::default(),
             },
             state_root: Hash::default(),
             tx_root: Hash::default(),
@@ -715,6 +746,38 @@ mod tests {
         create_test_block(0, Hash::default())
     }
 
+    fn create_signed_tx(nonce: u64) -> Transaction {
+        let mut tx = Transaction {
+            hash: Hash::new([nonce as u8; 32]),
+            nonce,
+            from: PublicKey::new([0; 32]),
+            to: Some(PublicKey::new([2; 32])),
+            value: 1000,
+            gas_limit: 21000,
+            gas_price: 1_000_000_000,
+            data: vec![],
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        };
+        crypto::sign_transaction(&mut tx, &crypto::generate_keypair()).unwrap();
+        tx
+    }
+
+    #[test]
+    fn test_verify_block_signatures_flags_bad_blocks() {
+        let mut blocks: Vec<Block> = (1..4)
+            .map(|height| {
+                let mut block = create_test_block(height, Hash::default());
+                block.transactions = vec![create_signed_tx(height), create_signed_tx(height + 10)];
+                block
+            })
+            .collect();
+        blocks.push(create_test_block(4, Hash::default()));
+        blocks[1].transactions[1].value += 1;
+
+        assert_eq!(verify_block_signatures(&blocks), vec![true, false, true, true]);
+    }
+
     #[tokio::test]
     async fn test_efficient_sync_no_recursion() {
         // Test that sync handles deep chains without stack overflow
@@ -866,6 +929,7 @@ mod tests {
             base_fee_per_gas: 0,
             gas_used: 0,
             gas_limit: 30_000_000,
+            epoch_commitment: Hash::default(),
         };
         assert!(manager.validate_block_header(&genesis_header).unwrap());
 
@@ -888,6 +952,7 @@ mod tests {
             base_fee_per_gas: 0,
             gas_used: 0,
             gas_limit: 30_000_000,
+            epoch_commitment: Hash::default(),
         };
         assert!(!manager.validate_block_header(&invalid_header).unwrap());
 
@@ -913,6 +978,7 @@ mod tests {
             base_fee_per_gas: 0,
             gas_used: 0,
             gas_limit: 30_000_000,
+            epoch_commitment: Hash::default(),
         };
         assert!(manager.validate_block_header(&valid_header).unwrap());
     }
@@ -5,6 +5,8 @@
 //! This module provides non-recursive, memory-bounded block synchronization
 //! that can handle deep chains and large block ranges without stack overflow.
 
+mod block_executor;
 mod efficient_sync;
 
+pub use block_executor::SyncBlockExecutor;
 pub use efficient_sync::{EfficientSyncManager, ParallelSyncCoordinator, SyncResult};
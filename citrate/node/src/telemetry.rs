@@ -0,0 +1,187 @@
+//! Opt-in Telemetry Module
+//!
+//! Periodically submits an anonymized node report so maintainers can see the
+//! network-wide version and platform distribution before planning forks.
+//! Disabled unless `[telemetry] enabled = true` is set in the node config.
+//!
+//! # What is reported
+//! - Random install ID (generated locally, not derived from keys or peer ID)
+//! - Node version, chain ID, OS and architecture
+//! - Connected peer count and local sync height
+//! - Report time, rounded down to the hour
+//!
+//! No addresses, keys, peer IDs or IPs are included. The collector's schema
+//! lives in `monitoring/telemetry/`.
+
+use crate::config::TelemetryConfig;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::Path;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Version of the report format; bump when fields change
+pub const TELEMETRY_SCHEMA_VERSION: u32 = 1;
+
+/// File in the data directory holding the install ID
+const INSTALL_ID_FILE: &str = "telemetry.id";
+
+/// Request timeout for report submission
+const SUBMIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Live node values sampled for each report
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NodeStats {
+    pub peer_count: usize,
+    pub sync_height: u64,
+}
+
+/// Anonymized report submitted to the telemetry endpoint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TelemetryReport {
+    pub schema_version: u32,
+    pub install_id: String,
+    pub version: String,
+    pub chain_id: u64,
+    pub peer_count: usize,
+    pub sync_height: u64,
+    pub os: String,
+    pub arch: String,
+    /// Unix seconds, rounded down to the hour
+    pub reported_at: u64,
+}
+
+impl TelemetryReport {
+    /// Build a report for this node
+    pub fn new(install_id: &str, chain_id: u64, stats: NodeStats, now_secs: u64) -> Self {
+        Self {
+            schema_version: TELEMETRY_SCHEMA_VERSION,
+            install_id: install_id.to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            chain_id,
+            peer_count: stats.peer_count,
+            sync_height: stats.sync_height,
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            reported_at: now_secs - now_secs % 3600,
+        }
+    }
+}
+
+/// Load the install ID from `data_dir`, creating a random one on first use
+pub fn load_or_create_install_id(data_dir: &Path) -> anyhow::Result<String> {
+    let path = data_dir.join(INSTALL_ID_FILE);
+    if let Ok(s) = std::fs::read_to_string(&path) {
+        let id = s.trim().to_string();
+        if !id.is_empty() {
+            return Ok(id);
+        }
+    }
+    let id = hex::encode(rand::random::<[u8; 16]>());
+    std::fs::create_dir_all(data_dir)?;
+    std::fs::write(&path, format!("{}\n", id))?;
+    Ok(id)
+}
+
+/// Start the reporting loop. `collect` samples the node on every tick.
+pub fn spawn<F, Fut>(
+    config: TelemetryConfig,
+    install_id: String,
+    chain_id: u64,
+    collect: F,
+) -> tokio::task::JoinHandle<()>
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = NodeStats> + Send,
+{
+    info!(
+        "Telemetry enabled: reporting to {} every {}s",
+        config.endpoint, config.interval_secs
+    );
+
+    tokio::spawn(async move {
+        let client = match reqwest::Client::builder().timeout(SUBMIT_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(e) => {
+                warn!("Telemetry disabled: failed to create HTTP client: {}", e);
+                return;
+            }
+        };
+        let mut ticker = tokio::time::interval(Duration::from_secs(config.interval_secs));
+
+        loop {
+            ticker.tick().await;
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            let report = TelemetryReport::new(&install_id, chain_id, collect().await, now);
+
+            // Failures are never fatal; the next tick simply tries again
+            match client.post(&config.endpoint).json(&report).send().await {
+                Ok(resp) if resp.status().is_success() => {
+                    debug!("Telemetry report submitted (height {})", report.sync_height);
+                }
+                Ok(resp) => debug!("Telemetry endpoint returned {}", resp.status()),
+                Err(e) => debug!("Telemetry submission failed: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_contains_only_anonymous_fields() {
+        let stats = NodeStats {
+            peer_count: 8,
+            sync_height: 1234,
+        };
+        let report = TelemetryReport::new("abcd", 1337, stats, 7_200 + 59);
+        assert_eq!(report.reported_at, 7_200);
+        assert_eq!(report.schema_version, TELEMETRY_SCHEMA_VERSION);
+
+        let json = serde_json::to_value(&report).unwrap();
+        let mut keys: Vec<&str> = json.as_object().unwrap().keys().map(|k| k.as_str()).collect();
+        keys.sort_unstable();
+        assert_eq!(
+            keys,
+            vec![
+                "arch",
+                "chain_id",
+                "install_id",
+                "os",
+                "peer_count",
+                "reported_at",
+                "schema_version",
+                "sync_height",
+                "version",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_install_id_is_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = load_or_create_install_id(dir.path()).unwrap();
+        assert_eq!(first.len(), 32);
+        assert_eq!(load_or_create_install_id(dir.path()).unwrap(), first);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let mut config = TelemetryConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.enabled = true;
+        assert!(config.validate().is_err());
+
+        config.endpoint = "https://telemetry.example.org/v1/report".to_string();
+        assert!(config.validate().is_ok());
+
+        config.interval_secs = 10;
+        assert!(config.validate().is_err());
+    }
+}
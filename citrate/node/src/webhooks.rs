@@ -0,0 +1,431 @@
+//! Model Registry Webhooks
+//!
+//! Follows the chain for model registry logs and POSTs each event as JSON to
+//! the endpoints configured under `[webhooks]`, so ML teams can trigger
+//! deployment pipelines when a new model version lands on-chain. Each
+//! endpoint can narrow delivery to certain events and owner addresses.
+//!
+//! # Delivery
+//! - Events are delivered in chain order once `confirmations` blocks deep
+//! - Requests carry `X-Citrate-Event` and `X-Citrate-Delivery` headers, and
+//!   `X-Citrate-Signature: sha256=<hex>` (HMAC-SHA256 of the body) when the
+//!   endpoint has a secret
+//! - Failed deliveries are retried with backoff, then dropped with a warning
+//! - The last processed height is kept in the data directory, so a restarted
+//!   node resumes where it stopped instead of replaying or skipping events
+
+use crate::config::{WebhookEndpointConfig, WebhooksConfig};
+use citrate_consensus::types::Hash;
+use citrate_execution::executor::{
+    MODEL_DEPRECATED_TOPIC, MODEL_REGISTERED_TOPIC, MODEL_UPDATED_TOPIC,
+};
+use citrate_execution::types::Address;
+use citrate_storage::chain::{IndexedLog, LogFilter, MAX_LOGS_PER_QUERY};
+use citrate_storage::StorageManager;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// File in the data directory holding the last processed height
+const CURSOR_FILE: &str = "webhooks.cursor";
+
+/// Request timeout for a single delivery attempt
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Attempts per delivery before it is dropped
+const MAX_ATTEMPTS: u32 = 4;
+
+/// Most blocks scanned for events per poll
+const MAX_BLOCKS_PER_POLL: u64 = 1_000;
+
+/// Model registry event kinds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModelEventKind {
+    #[serde(rename = "model.registered")]
+    Registered,
+    #[serde(rename = "model.updated")]
+    Updated,
+    #[serde(rename = "model.deprecated")]
+    Deprecated,
+}
+
+impl ModelEventKind {
+    pub const ALL: [ModelEventKind; 3] = [Self::Registered, Self::Updated, Self::Deprecated];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Registered => "model.registered",
+            Self::Updated => "model.updated",
+            Self::Deprecated => "model.deprecated",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    fn topic(self) -> Hash {
+        Hash::new(match self {
+            Self::Registered => MODEL_REGISTERED_TOPIC,
+            Self::Updated => MODEL_UPDATED_TOPIC,
+            Self::Deprecated => MODEL_DEPRECATED_TOPIC,
+        })
+    }
+
+    fn from_topic(topic: &Hash) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.topic() == *topic)
+    }
+}
+
+/// Body of a webhook delivery
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelEvent {
+    /// Stable delivery ID: transaction hash and log position
+    pub id: String,
+    pub event: ModelEventKind,
+    pub chain_id: u64,
+    pub model_hash: String,
+    pub owner: String,
+    /// Model version after the event; absent for deprecations
+    pub version: Option<u32>,
+    pub artifact_cid: Option<String>,
+    pub block_number: u64,
+    pub block_hash: String,
+    pub tx_hash: String,
+}
+
+impl ModelEvent {
+    /// Decode a model registry log; other logs yield `None`
+    pub fn from_log(log: &IndexedLog, chain_id: u64) -> Option<Self> {
+        let event = ModelEventKind::from_topic(log.topics.first()?)?;
+        let model_hash = log.topics.get(1)?;
+
+        let (version, artifact_cid) = match event {
+            ModelEventKind::Deprecated => (None, None),
+            _ if log.data.len() >= 32 => {
+                let mut version = [0u8; 4];
+                version.copy_from_slice(&log.data[28..32]);
+                let cid = String::from_utf8_lossy(&log.data[32..]).to_string();
+                (
+                    Some(u32::from_be_bytes(version)),
+                    Some(cid).filter(|c| !c.is_empty()),
+                )
+            }
+            _ => (None, None),
+        };
+
+        Some(Self {
+            id: format!(
+                "0x{}-{}",
+                hex::encode(log.tx_hash.as_bytes()),
+                log.log_index
+            ),
+            event,
+            chain_id,
+            model_hash: format!("0x{}", hex::encode(model_hash.as_bytes())),
+            owner: log.address.to_string(),
+            version,
+            artifact_cid,
+            block_number: log.block_number,
+            block_hash: format!("0x{}", hex::encode(log.block_hash.as_bytes())),
+            tx_hash: format!("0x{}", hex::encode(log.tx_hash.as_bytes())),
+        })
+    }
+}
+
+/// A parsed webhook endpoint
+#[derive(Debug, Clone)]
+pub struct Endpoint {
+    pub url: String,
+    events: Vec<ModelEventKind>,
+    owners: Vec<Address>,
+    secret: Option<String>,
+}
+
+impl Endpoint {
+    pub fn from_config(config: &WebhookEndpointConfig) -> Result<Self, String> {
+        if !(config.url.starts_with("http://") || config.url.starts_with("https://")) {
+            return Err(format!("webhook url `{}` must be http(s)", config.url));
+        }
+        let events = config
+            .events
+            .iter()
+            .map(|name| {
+                ModelEventKind::from_name(name)
+                    .ok_or_else(|| format!("unknown webhook event `{}`", name))
+            })
+            .collect::<Result<_, _>>()?;
+        let owners = config
+            .owners
+            .iter()
+            .map(|owner| {
+                citrate_execution::address_utils::address_from_hex(owner.trim())
+                    .map_err(|e| format!("invalid webhook owner `{}`: {}", owner, e))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            url: config.url.clone(),
+            events,
+            owners,
+            secret: config.secret.clone().filter(|s| !s.is_empty()),
+        })
+    }
+
+    /// Whether this endpoint wants `event`, emitted under `owner`
+    pub fn matches(&self, event: ModelEventKind, owner: &Address) -> bool {
+        (self.events.is_empty() || self.events.contains(&event))
+            && (self.owners.is_empty() || self.owners.contains(owner))
+    }
+}
+
+/// `X-Citrate-Signature` value for `body`
+pub fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Filter for model registry logs in `from..=to`
+fn model_log_filter(from_block: u64, to_block: u64) -> LogFilter {
+    LogFilter {
+        from_block,
+        to_block,
+        addresses: Vec::new(),
+        topics: vec![Some(
+            ModelEventKind::ALL
+                .iter()
+                .map(|kind| kind.topic())
+                .collect(),
+        )],
+    }
+}
+
+fn load_cursor(path: &Path) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn save_cursor(path: &Path, height: u64) {
+    if let Err(e) = std::fs::write(path, format!("{}\n", height)) {
+        warn!("Failed to save webhook cursor: {}", e);
+    }
+}
+
+async fn deliver(client: &reqwest::Client, endpoint: &Endpoint, event: &ModelEvent) {
+    let body = match serde_json::to_vec(event) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("Failed to encode webhook event {}: {}", event.id, e);
+            return;
+        }
+    };
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let mut request = client
+            .post(&endpoint.url)
+            .header("Content-Type", "application/json")
+            .header("X-Citrate-Event", event.event.name())
+            .header("X-Citrate-Delivery", &event.id)
+            .body(body.clone());
+        if let Some(secret) = &endpoint.secret {
+            request = request.header("X-Citrate-Signature", signature(secret, &body));
+        }
+
+        match request.send().await {
+            Ok(resp) if resp.status().is_success() => {
+                debug!("Delivered {} to {}", event.id, endpoint.url);
+                return;
+            }
+            Ok(resp) => debug!(
+                "Webhook {} returned {} (attempt {})",
+                endpoint.url,
+                resp.status(),
+                attempt
+            ),
+            Err(e) => debug!(
+                "Webhook {} failed: {} (attempt {})",
+                endpoint.url, e, attempt
+            ),
+        }
+        if attempt < MAX_ATTEMPTS {
+            tokio::time::sleep(Duration::from_secs(1 << attempt)).await;
+        }
+    }
+    warn!(
+        "Dropping webhook delivery {} to {} after {} attempts",
+        event.id, endpoint.url, MAX_ATTEMPTS
+    );
+}
+
+/// Start the delivery loop. Endpoints must already have passed
+/// [`WebhooksConfig::validate`].
+pub fn spawn(
+    config: WebhooksConfig,
+    chain_id: u64,
+    data_dir: &Path,
+    storage: Arc<StorageManager>,
+) -> tokio::task::JoinHandle<()> {
+    let endpoints: Vec<Endpoint> = config
+        .endpoints
+        .iter()
+        .filter_map(|e| Endpoint::from_config(e).ok())
+        .collect();
+    let cursor_path: PathBuf = data_dir.join(CURSOR_FILE);
+    info!(
+        "Model registry webhooks enabled for {} endpoint(s)",
+        endpoints.len()
+    );
+
+    tokio::spawn(async move {
+        let client = match reqwest::Client::builder().timeout(DELIVERY_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(e) => {
+                warn!("Webhooks disabled: failed to create HTTP client: {}", e);
+                return;
+            }
+        };
+        // Without a saved cursor, start from the current head rather than
+        // replaying the whole chain
+        let mut cursor = load_cursor(&cursor_path).unwrap_or_else(|| {
+            storage
+                .blocks
+                .get_latest_height()
+                .unwrap_or(0)
+                .saturating_sub(config.confirmations)
+        });
+        let mut ticker = tokio::time::interval(Duration::from_secs(config.poll_interval_secs));
+
+        loop {
+            ticker.tick().await;
+            let head = storage.blocks.get_latest_height().unwrap_or(0);
+            let target = head.saturating_sub(config.confirmations);
+            if target <= cursor {
+                continue;
+            }
+            let to_block = target.min(cursor + MAX_BLOCKS_PER_POLL);
+            let mut logs = match storage
+                .logs
+                .get_logs(&model_log_filter(cursor + 1, to_block), MAX_LOGS_PER_QUERY)
+            {
+                Ok(logs) => logs,
+                Err(e) => {
+                    warn!("Webhook log query failed: {}", e);
+                    continue;
+                }
+            };
+            // A full page may have cut a block short; leave it for next poll
+            let mut processed_to = to_block;
+            if logs.len() == MAX_LOGS_PER_QUERY {
+                let last = logs.last().map(|l| l.block_number).unwrap_or(to_block);
+                if last > cursor + 1 {
+                    logs.retain(|l| l.block_number < last);
+                    processed_to = last - 1;
+                }
+            }
+
+            for log in &logs {
+                let Some(event) = ModelEvent::from_log(log, chain_id) else {
+                    continue;
+                };
+                for endpoint in endpoints
+                    .iter()
+                    .filter(|e| e.matches(event.event, &log.address))
+                {
+                    deliver(&client, endpoint, &event).await;
+                }
+            }
+            cursor = processed_to;
+            save_cursor(&cursor_path, cursor);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use citrate_execution::executor::model_event_data;
+
+    fn log(topic: [u8; 32], owner: Address, data: Vec<u8>) -> IndexedLog {
+        IndexedLog {
+            address: owner,
+            topics: vec![Hash::new(topic), Hash::new([0xab; 32])],
+            data,
+            block_number: 42,
+            block_hash: Hash::new([1; 32]),
+            tx_hash: Hash::new([2; 32]),
+            tx_index: 0,
+            log_index: 3,
+        }
+    }
+
+    #[test]
+    fn test_model_event_from_log() {
+        let owner = Address([0x11; 20]);
+        let event = ModelEvent::from_log(
+            &log(
+                MODEL_UPDATED_TOPIC,
+                owner,
+                model_event_data(2, Some("bafyweights")),
+            ),
+            1337,
+        )
+        .unwrap();
+        assert_eq!(event.event, ModelEventKind::Updated);
+        assert_eq!(event.version, Some(2));
+        assert_eq!(event.artifact_cid.as_deref(), Some("bafyweights"));
+        assert_eq!(event.owner, owner.to_string());
+        assert_eq!(event.model_hash, format!("0x{}", "ab".repeat(32)));
+        assert_eq!(event.id, format!("0x{}-3", "02".repeat(32)));
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "model.updated");
+
+        let deprecated =
+            ModelEvent::from_log(&log(MODEL_DEPRECATED_TOPIC, owner, Vec::new()), 1337).unwrap();
+        assert_eq!(deprecated.event, ModelEventKind::Deprecated);
+        assert_eq!(deprecated.version, None);
+
+        let mut other = log(MODEL_UPDATED_TOPIC, owner, Vec::new());
+        other.topics[0] = Hash::new([9; 32]);
+        assert!(ModelEvent::from_log(&other, 1337).is_none());
+    }
+
+    #[test]
+    fn test_endpoint_filters() {
+        let owner = Address([0x11; 20]);
+        let mut config = WebhookEndpointConfig {
+            url: "https://ci.example.org/hooks/models".to_string(),
+            events: vec!["model.registered".to_string(), "model.updated".to_string()],
+            owners: vec![owner.to_string()],
+            secret: None,
+        };
+        let endpoint = Endpoint::from_config(&config).unwrap();
+        assert!(endpoint.matches(ModelEventKind::Updated, &owner));
+        assert!(!endpoint.matches(ModelEventKind::Deprecated, &owner));
+        assert!(!endpoint.matches(ModelEventKind::Updated, &Address([0x22; 20])));
+
+        config.events.clear();
+        config.owners.clear();
+        let endpoint = Endpoint::from_config(&config).unwrap();
+        assert!(endpoint.matches(ModelEventKind::Deprecated, &Address([0x22; 20])));
+
+        config.events = vec!["model.deleted".to_string()];
+        assert!(Endpoint::from_config(&config).is_err());
+        config.events.clear();
+        config.url = "ftp://ci.example.org".to_string();
+        assert!(Endpoint::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_signature() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            signature("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! Environment interpolation and sealed secrets for config files
+//!
+//! Any string in a node or GUI config may reference the environment or the
+//! config's own `secrets` table:
+//!
+//! - `${VAR}` expands to an environment variable, `${VAR:-fallback}` uses
+//!   the fallback when it is unset
+//! - `${secret:name}` expands to the decrypted `secrets.name` entry
+//! - `$${` is a literal `${`
+//!
+//! Secrets are sealed with [`seal`] under a passphrase taken from
+//! `CITRATE_SECRETS_PASSPHRASE`, or from the file named by
+//! `CITRATE_SECRETS_PASSPHRASE_FILE`, so the file itself can be committed.
+
+use crate::errors::WalletError;
+use crate::keystore::{decrypt_bytes, encrypt_bytes};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Prefix of a sealed secret
+pub const SEALED_PREFIX: &str = "enc:v1:";
+
+/// Environment variable holding the secrets passphrase
+pub const PASSPHRASE_ENV: &str = "CITRATE_SECRETS_PASSPHRASE";
+
+/// Environment variable naming a file that holds the passphrase
+pub const PASSPHRASE_FILE_ENV: &str = "CITRATE_SECRETS_PASSPHRASE_FILE";
+
+/// Config table holding sealed secrets
+pub const SECRETS_KEY: &str = "secrets";
+
+/// Encrypt `plaintext` as `enc:v1:<salt>:<nonce>:<ciphertext>`
+pub fn seal(plaintext: &str, passphrase: &str) -> Result<String, WalletError> {
+    let (ciphertext, salt, nonce) = encrypt_bytes(plaintext.as_bytes(), passphrase)?;
+    Ok(format!(
+        "{}{}:{}:{}",
+        SEALED_PREFIX,
+        salt,
+        hex::encode(nonce),
+        hex::encode(ciphertext)
+    ))
+}
+
+/// Decrypt a value produced by [`seal`]
+pub fn open(sealed: &str, passphrase: &str) -> Result<String, WalletError> {
+    let parts: Vec<&str> = sealed
+        .strip_prefix(SEALED_PREFIX)
+        .ok_or_else(|| WalletError::Config("secret is not sealed".to_string()))?
+        .split(':')
+        .collect();
+    let [salt, nonce, ciphertext] = parts[..] else {
+        return Err(WalletError::Config("malformed sealed secret".to_string()));
+    };
+    let plaintext = decrypt_bytes(
+        &hex::decode(ciphertext)?,
+        salt,
+        &hex::decode(nonce)?,
+        passphrase,
+    )?;
+    String::from_utf8(plaintext).map_err(|e| WalletError::Decryption(e.to_string()))
+}
+
+/// Passphrase from the environment, if one is configured
+pub fn passphrase_from_env() -> Result<Option<String>, WalletError> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        return Ok(Some(passphrase));
+    }
+    match std::env::var(PASSPHRASE_FILE_ENV) {
+        Ok(path) => Ok(Some(
+            std::fs::read_to_string(path)?
+                .trim_end_matches(['\r', '\n'])
+                .to_string(),
+        )),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Expand references in every string of a parsed config. The `secrets`
+/// table is decrypted first and left in place, still sealed.
+pub fn resolve(config: &mut Value, passphrase: Option<&str>) -> Result<(), WalletError> {
+    let secrets = open_secrets(config, passphrase)?;
+    if let Value::Object(map) = config {
+        for (key, value) in map.iter_mut() {
+            if key != SECRETS_KEY {
+                resolve_value(value, &secrets)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Put references from `raw` back into `updated` wherever the value they
+/// resolved to is unchanged, so saving a loaded config never writes out
+/// environment values or decrypted secrets. `resolved` is `raw` after
+/// [`resolve`].
+pub fn preserve_references(raw: &Value, resolved: &Value, updated: &mut Value) {
+    match (raw, resolved, updated) {
+        (Value::Object(raw), Value::Object(resolved), Value::Object(updated)) => {
+            for (key, raw_value) in raw {
+                if key == SECRETS_KEY && raw_value.is_object() {
+                    updated.insert(key.clone(), raw_value.clone());
+                    continue;
+                }
+                if let (Some(resolved_value), Some(updated_value)) =
+                    (resolved.get(key), updated.get_mut(key))
+                {
+                    preserve_references(raw_value, resolved_value, updated_value);
+                }
+            }
+        }
+        (Value::Array(raw), Value::Array(resolved), Value::Array(updated))
+            if raw.len() == updated.len() =>
+        {
+            for ((r, s), u) in raw.iter().zip(resolved).zip(updated.iter_mut()) {
+                preserve_references(r, s, u);
+            }
+        }
+        (Value::String(raw), resolved, updated) if raw.contains("${") && resolved == updated => {
+            *updated = Value::String(raw.clone());
+        }
+        _ => {}
+    }
+}
+
+fn open_secrets(
+    config: &Value,
+    passphrase: Option<&str>,
+) -> Result<HashMap<String, String>, WalletError> {
+    let Some(table) = config.get(SECRETS_KEY) else {
+        return Ok(HashMap::new());
+    };
+    let table: &Map<String, Value> = table
+        .as_object()
+        .ok_or_else(|| WalletError::Config("`secrets` must be a table".to_string()))?;
+
+    let mut secrets = HashMap::new();
+    for (name, value) in table {
+        let value = value
+            .as_str()
+            .ok_or_else(|| WalletError::Config(format!("secret `{}` must be a string", name)))?;
+        // Secrets may themselves come from the environment
+        let value = expand(value, &HashMap::new())?;
+        let value = if value.starts_with(SEALED_PREFIX) {
+            let passphrase = passphrase.ok_or_else(|| {
+                WalletError::Config(format!(
+                    "secret `{}` is sealed but neither {} nor {} is set",
+                    name, PASSPHRASE_ENV, PASSPHRASE_FILE_ENV
+                ))
+            })?;
+            open(&value, passphrase)
+                .map_err(|e| WalletError::Config(format!("secret `{}`: {}", name, e)))?
+        } else {
+            value
+        };
+        secrets.insert(name.clone(), value);
+    }
+    Ok(secrets)
+}
+
+fn resolve_value(value: &mut Value, secrets: &HashMap<String, String>) -> Result<(), WalletError> {
+    match value {
+        Value::String(s) => *s = expand(s, secrets)?,
+        Value::Array(items) => {
+            for item in items {
+                resolve_value(item, secrets)?;
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values_mut() {
+                resolve_value(item, secrets)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Expand `${...}` references in one string
+pub fn expand(input: &str, secrets: &HashMap<String, String>) -> Result<String, WalletError> {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find('$') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        if let Some(after) = tail.strip_prefix("$${") {
+            out.push_str("${");
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix("${") {
+            let end = after.find('}').ok_or_else(|| {
+                WalletError::Config(format!("unterminated reference in `{}`", input))
+            })?;
+            out.push_str(&lookup(&after[..end], secrets)?);
+            rest = &after[end + 1..];
+        } else {
+            out.push('$');
+            rest = &tail[1..];
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn lookup(reference: &str, secrets: &HashMap<String, String>) -> Result<String, WalletError> {
+    if let Some(name) = reference.strip_prefix("secret:") {
+        return secrets
+            .get(name)
+            .cloned()
+            .ok_or_else(|| WalletError::Config(format!("unknown secret `{}`", name)));
+    }
+    let (name, fallback) = match reference.split_once(":-") {
+        Some((name, fallback)) => (name, Some(fallback)),
+        None => (reference, None),
+    };
+    match (std::env::var(name), fallback) {
+        (Ok(value), _) => Ok(value),
+        (Err(_), Some(fallback)) => Ok(fallback.to_string()),
+        (Err(_), None) => Err(WalletError::Config(format!(
+            "environment variable {} is not set",
+            name
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_seal_round_trip() {
+        let sealed = seal("0xdeadbeef", "hunter2").unwrap();
+        assert!(sealed.starts_with(SEALED_PREFIX));
+        assert_eq!(open(&sealed, "hunter2").unwrap(), "0xdeadbeef");
+        assert!(matches!(
+            open(&sealed, "wrong"),
+            Err(WalletError::InvalidPassword)
+        ));
+        assert!(open("enc:v1:nope", "hunter2").is_err());
+    }
+
+    #[test]
+    fn test_expand_env_and_escapes() {
+        std::env::set_var("CITRATE_TEST_EXPAND_HOST", "10.0.0.1");
+        let secrets = HashMap::from([("token".to_string(), "s3cret".to_string())]);
+
+        assert_eq!(
+            expand("${CITRATE_TEST_EXPAND_HOST}:8545", &secrets).unwrap(),
+            "10.0.0.1:8545"
+        );
+        assert_eq!(
+            expand("${CITRATE_TEST_EXPAND_UNSET:-127.0.0.1}", &secrets).unwrap(),
+            "127.0.0.1"
+        );
+        assert_eq!(
+            expand("Bearer ${secret:token}", &secrets).unwrap(),
+            "Bearer s3cret"
+        );
+        assert_eq!(
+            expand("cost $5, $${HOME}", &secrets).unwrap(),
+            "cost $5, ${HOME}"
+        );
+        assert!(expand("${CITRATE_TEST_EXPAND_UNSET}", &secrets).is_err());
+        assert!(expand("${secret:missing}", &secrets).is_err());
+        assert!(expand("${CITRATE_TEST_EXPAND_HOST", &secrets).is_err());
+    }
+
+    #[test]
+    fn test_resolve_config_and_preserve_references() {
+        std::env::set_var("CITRATE_TEST_RESOLVE_PORT", "9000");
+        let raw = json!({
+            "secrets": { "coinbase": seal("ab".repeat(32).as_str(), "pw").unwrap() },
+            "mining": { "coinbase": "${secret:coinbase}", "enabled": true },
+            "rpc": { "listen_addr": "127.0.0.1:${CITRATE_TEST_RESOLVE_PORT}" },
+            "peers": ["${CITRATE_TEST_RESOLVE_PORT}"]
+        });
+
+        let mut no_passphrase = raw.clone();
+        assert!(matches!(
+            resolve(&mut no_passphrase, None),
+            Err(WalletError::Config(_))
+        ));
+
+        let mut resolved = raw.clone();
+        resolve(&mut resolved, Some("pw")).unwrap();
+        assert_eq!(resolved["mining"]["coinbase"], json!("ab".repeat(32)));
+        assert_eq!(resolved["rpc"]["listen_addr"], json!("127.0.0.1:9000"));
+        assert_eq!(resolved["peers"][0], json!("9000"));
+        assert_eq!(resolved["secrets"], raw["secrets"]);
+
+        // Saving writes references back unless the value was changed
+        let mut updated = resolved.clone();
+        updated["rpc"]["listen_addr"] = json!("0.0.0.0:8545");
+        updated.as_object_mut().unwrap().remove("secrets");
+        preserve_references(&raw, &resolved, &mut updated);
+        assert_eq!(updated["mining"]["coinbase"], json!("${secret:coinbase}"));
+        assert_eq!(updated["rpc"]["listen_addr"], json!("0.0.0.0:8545"));
+        assert_eq!(updated["secrets"], raw["secrets"]);
+    }
+}
@@ -44,6 +44,27 @@ pub enum WalletError {
     #[error("Wallet already exists at path")]
     WalletExists,
 
+    #[error("Invalid mnemonic: {0}")]
+    InvalidMnemonic(String),
+
+    #[error("Invalid derivation path: {0}")]
+    InvalidDerivationPath(String),
+
+    #[error("Wallet has no mnemonic")]
+    NoMnemonic,
+
+    #[error("Remote signer error: {0}")]
+    RemoteSigner(String),
+
+    #[error("Signing request rejected: {0}")]
+    SigningRejected(String),
+
+    #[error("Config error: {0}")]
+    Config(String),
+
+    #[error("Epoch verification failed: {0}")]
+    Epoch(#[from] citrate_consensus::EpochError),
+
     #[error("Other error: {0}")]
     Other(String),
 }
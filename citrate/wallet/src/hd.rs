@@ -0,0 +1,191 @@
+use bip39::{Language, Mnemonic};
+use ed25519_dalek::SigningKey;
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, RngCore};
+use sha2::Sha512;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::errors::WalletError;
+
+/// BIP-44 purpose field
+pub const BIP44_PURPOSE: u32 = 44;
+/// Coin type used for Citrate accounts (shared with Ethereum tooling)
+pub const CITRATE_COIN_TYPE: u32 = 60;
+/// Bit marking a hardened path element
+pub const HARDENED_OFFSET: u32 = 0x8000_0000;
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// Hierarchical derivation path such as `m/44'/60'/0'/0'/3'`
+///
+/// Ed25519 only supports hardened derivation (SLIP-0010), so every element
+/// is hardened when keys are derived. Unhardened elements are accepted when
+/// parsing and treated as hardened, which is how `m/44'/60'/0'/0/N` maps onto
+/// Ed25519 keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    /// Path of the `n`th Citrate account: `m/44'/60'/0'/0'/n'`
+    pub fn bip44(index: u32) -> Self {
+        Self(vec![BIP44_PURPOSE, CITRATE_COIN_TYPE, 0, 0, index])
+    }
+
+    /// Path elements without the hardened bit
+    pub fn indices(&self) -> &[u32] {
+        &self.0
+    }
+
+    /// Last path element, i.e. the address index for BIP-44 paths
+    pub fn account_index(&self) -> Option<u32> {
+        self.0.last().copied()
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        for index in &self.0 {
+            write!(f, "/{}'", index)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = WalletError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || WalletError::InvalidDerivationPath(s.to_string());
+
+        let mut parts = s.trim().split('/');
+        if parts.next() != Some("m") {
+            return Err(invalid());
+        }
+
+        let mut indices = Vec::new();
+        for part in parts {
+            let digits = part
+                .strip_suffix('\'')
+                .or_else(|| part.strip_suffix('h'))
+                .unwrap_or(part);
+            let index: u32 = digits.parse().map_err(|_| invalid())?;
+            if index >= HARDENED_OFFSET {
+                return Err(invalid());
+            }
+            indices.push(index);
+        }
+
+        if indices.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self(indices))
+    }
+}
+
+/// Generate a new 12-word English mnemonic
+pub fn generate_mnemonic() -> Result<Mnemonic, WalletError> {
+    let mut entropy = [0u8; 16];
+    OsRng.fill_bytes(&mut entropy);
+    Mnemonic::from_entropy_in(Language::English, &entropy)
+        .map_err(|e| WalletError::InvalidMnemonic(e.to_string()))
+}
+
+/// Parse and validate an English mnemonic phrase
+pub fn parse_mnemonic(phrase: &str) -> Result<Mnemonic, WalletError> {
+    Mnemonic::parse_in_normalized(Language::English, phrase.trim())
+        .map_err(|e| WalletError::InvalidMnemonic(e.to_string()))
+}
+
+/// BIP-39 seed of a mnemonic. The passphrase is always empty, matching the
+/// GUI wallet, so the same phrase restores the same keys in both.
+pub fn mnemonic_to_seed(mnemonic: &Mnemonic) -> [u8; 64] {
+    mnemonic.to_seed("")
+}
+
+/// Derive an Ed25519 signing key from a seed following SLIP-0010
+pub fn derive_signing_key(seed: &[u8], path: &DerivationPath) -> SigningKey {
+    let (mut key, mut chain_code) = hmac_split(b"ed25519 seed", &[seed]);
+
+    for index in path.indices() {
+        let hardened = (index | HARDENED_OFFSET).to_be_bytes();
+        (key, chain_code) = hmac_split(&chain_code, &[&[0x00], &key, &hardened]);
+    }
+
+    SigningKey::from_bytes(&key)
+}
+
+/// HMAC-SHA512 over `data`, split into (key, chain code)
+fn hmac_split(hmac_key: &[u8], data: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+    let mut mac =
+        <HmacSha512 as Mac>::new_from_slice(hmac_key).expect("HMAC accepts keys of any length");
+    for chunk in data {
+        mac.update(chunk);
+    }
+    let result = mac.finalize().into_bytes();
+
+    let mut key = [0u8; 32];
+    let mut chain_code = [0u8; 32];
+    key.copy_from_slice(&result[..32]);
+    chain_code.copy_from_slice(&result[32..]);
+    (key, chain_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // SLIP-0010 test vector 1 for ed25519
+    const SEED: &str = "000102030405060708090a0b0c0d0e0f";
+
+    fn derive_hex(path: &str) -> String {
+        let seed = hex::decode(SEED).unwrap();
+        let path: DerivationPath = path.parse().unwrap();
+        hex::encode(derive_signing_key(&seed, &path).to_bytes())
+    }
+
+    #[test]
+    fn test_slip10_vectors() {
+        assert_eq!(
+            derive_hex("m/0'"),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+        assert_eq!(
+            derive_hex("m/0'/1'/2'/2'/1000000000'"),
+            "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793"
+        );
+    }
+
+    #[test]
+    fn test_path_parsing() {
+        let path: DerivationPath = "m/44'/60'/0'/0/7".parse().unwrap();
+        assert_eq!(path, DerivationPath::bip44(7));
+        assert_eq!(path.to_string(), "m/44'/60'/0'/0'/7'");
+        assert_eq!(path.account_index(), Some(7));
+
+        assert!("44'/60'".parse::<DerivationPath>().is_err());
+        assert!("m".parse::<DerivationPath>().is_err());
+        assert!("m/x'".parse::<DerivationPath>().is_err());
+        assert!("m/2147483648".parse::<DerivationPath>().is_err());
+    }
+
+    #[test]
+    fn test_mnemonic_derivation_is_deterministic() {
+        let mnemonic = generate_mnemonic().unwrap();
+        assert_eq!(mnemonic.word_count(), 12);
+
+        let restored = parse_mnemonic(&mnemonic.to_string()).unwrap();
+        let seed = mnemonic_to_seed(&mnemonic);
+        let restored_seed = mnemonic_to_seed(&restored);
+
+        let a = derive_signing_key(&seed, &DerivationPath::bip44(0));
+        let b = derive_signing_key(&restored_seed, &DerivationPath::bip44(0));
+        let c = derive_signing_key(&seed, &DerivationPath::bip44(1));
+        assert_eq!(a.to_bytes(), b.to_bytes());
+        assert_ne!(a.to_bytes(), c.to_bytes());
+
+        assert!(parse_mnemonic("not a valid phrase").is_err());
+    }
+}
@@ -6,12 +6,14 @@ use argon2::{
     password_hash::{PasswordHasher, SaltString},
     Argon2,
 };
+use bip39::Mnemonic;
 use ed25519_dalek::{SigningKey, VerifyingKey};
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
 use crate::errors::WalletError;
+use crate::hd::{self, DerivationPath};
 
 /// Encrypted key storage
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,12 +28,44 @@ pub struct EncryptedKey {
     pub public_key: Vec<u8>,
     /// Optional key alias
     pub alias: Option<String>,
+    /// Derivation path for keys derived from the wallet mnemonic
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub derivation_path: Option<String>,
+}
+
+/// Encrypted BIP-39 mnemonic phrase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedMnemonic {
+    /// Encrypted phrase
+    pub ciphertext: Vec<u8>,
+    /// Salt for key derivation
+    pub salt: String,
+    /// Nonce for AES-GCM
+    pub nonce: Vec<u8>,
+}
+
+/// On-disk keystore layout
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct KeystoreFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mnemonic: Option<EncryptedMnemonic>,
+    keys: Vec<EncryptedKey>,
+}
+
+/// Keystores written before HD support are a bare list of keys
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredKeystore {
+    Hd(KeystoreFile),
+    Legacy(Vec<EncryptedKey>),
 }
 
 /// Key store for managing encrypted keys
 pub struct KeyStore {
     /// Path to keystore file
     path: PathBuf,
+    /// Encrypted mnemonic the HD keys are derived from
+    mnemonic: Option<EncryptedMnemonic>,
     /// Encrypted keys
     keys: Vec<EncryptedKey>,
     /// Decrypted keys (in memory when unlocked)
@@ -46,16 +80,23 @@ impl KeyStore {
         let path = path.as_ref().to_path_buf();
 
         // Load existing keys if file exists
-        let keys = if path.exists() {
+        let file = if path.exists() {
             let data = std::fs::read(&path)?;
-            serde_json::from_slice(&data)?
+            match serde_json::from_slice(&data)? {
+                StoredKeystore::Hd(file) => file,
+                StoredKeystore::Legacy(keys) => KeystoreFile {
+                    mnemonic: None,
+                    keys,
+                },
+            }
         } else {
-            Vec::new()
+            KeystoreFile::default()
         };
 
         Ok(Self {
             path,
-            keys,
+            mnemonic: file.mnemonic,
+            keys: file.keys,
             unlocked: Vec::new(),
             locked: true,
         })
@@ -130,6 +171,89 @@ impl KeyStore {
         Ok(verifying_key)
     }
 
+    /// Whether the keystore holds a mnemonic for HD derivation
+    pub fn has_mnemonic(&self) -> bool {
+        self.mnemonic.is_some()
+    }
+
+    /// Store the mnemonic that HD keys are derived from
+    pub fn set_mnemonic(&mut self, mnemonic: &Mnemonic, password: &str) -> Result<(), WalletError> {
+        if self.mnemonic.is_some() {
+            return Err(WalletError::WalletExists);
+        }
+
+        // All entries must share one password for unlock to work
+        if let Some(existing) = self.keys.first() {
+            self.decrypt_key(existing, password)?;
+        }
+
+        let (ciphertext, salt, nonce) = encrypt_bytes(mnemonic.to_string().as_bytes(), password)?;
+        self.mnemonic = Some(EncryptedMnemonic {
+            ciphertext,
+            salt,
+            nonce,
+        });
+
+        self.save()
+    }
+
+    /// Decrypt the stored mnemonic
+    pub fn export_mnemonic(&self, password: &str) -> Result<Mnemonic, WalletError> {
+        let encrypted = self.mnemonic.as_ref().ok_or(WalletError::NoMnemonic)?;
+        let plaintext = decrypt_bytes(
+            &encrypted.ciphertext,
+            &encrypted.salt,
+            &encrypted.nonce,
+            password,
+        )?;
+        let phrase =
+            String::from_utf8(plaintext).map_err(|e| WalletError::Decryption(e.to_string()))?;
+
+        hd::parse_mnemonic(&phrase)
+    }
+
+    /// Derive a key from the stored mnemonic and add it to the keystore
+    pub fn derive_key(
+        &mut self,
+        path: &DerivationPath,
+        password: &str,
+        alias: Option<String>,
+    ) -> Result<VerifyingKey, WalletError> {
+        let mnemonic = self.export_mnemonic(password)?;
+        let seed = hd::mnemonic_to_seed(&mnemonic);
+        let signing_key = hd::derive_signing_key(&seed, path);
+        let verifying_key = signing_key.verifying_key();
+
+        let mut encrypted = self.encrypt_key(&signing_key, password)?;
+        encrypted.alias = alias;
+        encrypted.derivation_path = Some(path.to_string());
+
+        self.keys.push(encrypted);
+
+        // Save to disk
+        self.save()?;
+
+        // Add to unlocked if keystore is unlocked
+        if !self.locked {
+            self.unlocked.push(signing_key);
+        }
+
+        Ok(verifying_key)
+    }
+
+    /// Index of the key derived at `path`, if any
+    pub fn find_derived(&self, path: &DerivationPath) -> Option<usize> {
+        let path = path.to_string();
+        self.keys
+            .iter()
+            .position(|k| k.derivation_path.as_deref() == Some(path.as_str()))
+    }
+
+    /// Derivation path of the key at `index`; `None` for imported keys
+    pub fn derivation_path(&self, index: usize) -> Option<&str> {
+        self.keys.get(index)?.derivation_path.as_deref()
+    }
+
     /// Unlock keystore with password
     pub fn unlock(&mut self, password: &str) -> Result<(), WalletError> {
         // Try to decrypt all keys
@@ -195,44 +319,15 @@ impl KeyStore {
         signing_key: &SigningKey,
         password: &str,
     ) -> Result<EncryptedKey, WalletError> {
-        // Generate salt
-        let salt = SaltString::generate(&mut OsRng);
-
-        // Derive key from password
-        let argon2 = Argon2::default();
-        let password_hash = argon2
-            .hash_password(password.as_bytes(), &salt)
-            .map_err(|e| WalletError::Encryption(e.to_string()))?;
-
-        // Get the hash bytes for AES key
-        let hash_bytes = password_hash.hash.unwrap();
-        let key_bytes = hash_bytes.as_bytes();
-
-        // Ensure we have exactly 32 bytes for AES-256
-        let mut aes_key = [0u8; 32];
-        aes_key.copy_from_slice(&key_bytes[..32]);
-
-        // Create cipher
-        let key = Key::<Aes256Gcm>::from_slice(&aes_key);
-        let cipher = Aes256Gcm::new(key);
-
-        // Generate nonce
-        let mut nonce_bytes = [0u8; 12];
-        OsRng.fill_bytes(&mut nonce_bytes);
-        let nonce = Nonce::from_slice(&nonce_bytes);
-
-        // Encrypt private key
-        let plaintext = signing_key.to_bytes();
-        let ciphertext = cipher
-            .encrypt(nonce, plaintext.as_ref())
-            .map_err(|e| WalletError::Encryption(e.to_string()))?;
+        let (ciphertext, salt, nonce) = encrypt_bytes(&signing_key.to_bytes(), password)?;
 
         Ok(EncryptedKey {
             ciphertext,
-            salt: salt.to_string(),
-            nonce: nonce_bytes.to_vec(),
+            salt,
+            nonce,
             public_key: signing_key.verifying_key().to_bytes().to_vec(),
             alias: None,
+            derivation_path: None,
         })
     }
 
@@ -242,33 +337,12 @@ impl KeyStore {
         encrypted: &EncryptedKey,
         password: &str,
     ) -> Result<SigningKey, WalletError> {
-        // Parse salt
-        let salt = SaltString::from_b64(&encrypted.salt)
-            .map_err(|e| WalletError::Decryption(e.to_string()))?;
-
-        // Derive key from password
-        let argon2 = Argon2::default();
-        let password_hash = argon2
-            .hash_password(password.as_bytes(), &salt)
-            .map_err(|e| WalletError::Decryption(e.to_string()))?;
-
-        // Get the hash bytes for AES key
-        let hash_bytes = password_hash.hash.unwrap();
-        let key_bytes = hash_bytes.as_bytes();
-
-        // Ensure we have exactly 32 bytes for AES-256
-        let mut aes_key = [0u8; 32];
-        aes_key.copy_from_slice(&key_bytes[..32]);
-
-        // Create cipher
-        let key = Key::<Aes256Gcm>::from_slice(&aes_key);
-        let cipher = Aes256Gcm::new(key);
-
-        // Decrypt
-        let nonce = Nonce::from_slice(&encrypted.nonce);
-        let plaintext = cipher
-            .decrypt(nonce, encrypted.ciphertext.as_ref())
-            .map_err(|_| WalletError::InvalidPassword)?;
+        let plaintext = decrypt_bytes(
+            &encrypted.ciphertext,
+            &encrypted.salt,
+            &encrypted.nonce,
+            password,
+        )?;
 
         // Convert to signing key
         let mut key_bytes = [0u8; 32];
@@ -279,7 +353,11 @@ impl KeyStore {
 
     /// Save keystore to disk
     fn save(&self) -> Result<(), WalletError> {
-        let data = serde_json::to_vec_pretty(&self.keys)?;
+        let file = KeystoreFile {
+            mnemonic: self.mnemonic.clone(),
+            keys: self.keys.clone(),
+        };
+        let data = serde_json::to_vec_pretty(&file)?;
         std::fs::write(&self.path, data)?;
         Ok(())
     }
@@ -294,3 +372,60 @@ impl KeyStore {
         Ok(hex::encode(signing_key.to_bytes()))
     }
 }
+
+/// Derive the AES-256 key for `password` and `salt`
+fn derive_cipher(password: &str, salt: &SaltString) -> Result<Aes256Gcm, WalletError> {
+    let argon2 = Argon2::default();
+    let password_hash = argon2
+        .hash_password(password.as_bytes(), salt)
+        .map_err(|e| WalletError::Encryption(e.to_string()))?;
+
+    // Get the hash bytes for AES key
+    let hash_bytes = password_hash
+        .hash
+        .ok_or_else(|| WalletError::Encryption("Missing password hash".to_string()))?;
+    let key_bytes = hash_bytes.as_bytes();
+
+    // Ensure we have exactly 32 bytes for AES-256
+    let mut aes_key = [0u8; 32];
+    aes_key.copy_from_slice(&key_bytes[..32]);
+
+    let key = Key::<Aes256Gcm>::from_slice(&aes_key);
+    Ok(Aes256Gcm::new(key))
+}
+
+/// Encrypt `plaintext`, returning (ciphertext, salt, nonce)
+pub(crate) fn encrypt_bytes(
+    plaintext: &[u8],
+    password: &str,
+) -> Result<(Vec<u8>, String, Vec<u8>), WalletError> {
+    // Generate salt
+    let salt = SaltString::generate(&mut OsRng);
+    let cipher = derive_cipher(password, &salt)?;
+
+    // Generate nonce
+    let mut nonce_bytes = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce_bytes);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext = cipher
+        .encrypt(nonce, plaintext)
+        .map_err(|e| WalletError::Encryption(e.to_string()))?;
+
+    Ok((ciphertext, salt.to_string(), nonce_bytes.to_vec()))
+}
+
+/// Decrypt data produced by `encrypt_bytes`
+pub(crate) fn decrypt_bytes(
+    ciphertext: &[u8],
+    salt: &str,
+    nonce: &[u8],
+    password: &str,
+) -> Result<Vec<u8>, WalletError> {
+    let salt = SaltString::from_b64(salt).map_err(|e| WalletError::Decryption(e.to_string()))?;
+    let cipher = derive_cipher(password, &salt)?;
+
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| WalletError::InvalidPassword)
+}
@@ -1,11 +1,18 @@
+pub mod config_secrets;
 pub mod errors;
+pub mod hd;
 pub mod keystore;
+pub mod light;
+pub mod remote_signer;
 pub mod rpc_client;
 pub mod transaction;
 pub mod wallet;
 
 pub use errors::WalletError;
+pub use hd::DerivationPath;
 pub use keystore::{EncryptedKey, KeyStore};
+pub use light::EpochVerifier;
+pub use remote_signer::{ApprovalPolicy, RemoteSigner, SignerService};
 pub use rpc_client::RpcClient;
 pub use transaction::{SignedTransaction, TransactionBuilder};
 pub use wallet::{Account, Wallet, WalletConfig};
@@ -0,0 +1,190 @@
+//! Light client verification of epoch summaries
+//!
+//! A light wallet doesn't download blocks. It starts from a validator set it
+//! trusts (the genesis set, shipped with the app) and follows the chain one
+//! epoch at a time: each signed summary must carry a quorum of signatures from
+//! the current set, and names the hash of the set that signs the next one.
+//! The latest verified summary gives a trusted state root that account proofs
+//! can be checked against, and headers can be matched against it through
+//! their epoch commitment.
+
+use citrate_consensus::types::{BlockHeader, Hash, PublicKey};
+use citrate_consensus::{validator_set_hash, EpochError, EpochSummary, SignedEpochSummary};
+
+use crate::errors::WalletError;
+
+/// Follows signed epoch summaries from a trusted validator set
+#[derive(Debug, Clone)]
+pub struct EpochVerifier {
+    chain_id: u64,
+    validators: Vec<PublicKey>,
+    /// Fixed quorum; more than two thirds of the current set if unset
+    quorum: Option<usize>,
+    latest: Option<EpochSummary>,
+}
+
+impl EpochVerifier {
+    /// Start from `validators`, trusted to sign the first summary applied
+    pub fn new(chain_id: u64, validators: Vec<PublicKey>) -> Self {
+        Self {
+            chain_id,
+            validators,
+            quorum: None,
+            latest: None,
+        }
+    }
+
+    /// Require `quorum` signatures per summary instead of two thirds
+    pub fn with_quorum(mut self, quorum: usize) -> Self {
+        self.quorum = Some(quorum);
+        self
+    }
+
+    /// Validator set trusted to sign the next summary
+    pub fn validators(&self) -> &[PublicKey] {
+        &self.validators
+    }
+
+    pub fn quorum(&self) -> usize {
+        self.quorum.unwrap_or(self.validators.len() * 2 / 3 + 1)
+    }
+
+    /// Latest verified summary
+    pub fn latest(&self) -> Option<&EpochSummary> {
+        self.latest.as_ref()
+    }
+
+    /// State root of the latest verified epoch boundary block
+    pub fn trusted_state_root(&self) -> Option<Hash> {
+        self.latest.as_ref().map(|summary| summary.state_root)
+    }
+
+    /// Verify `signed` against the current validator set and move on to
+    /// `next_validators`, which must match the set hash it commits to.
+    /// After the first summary, epochs must be applied in order.
+    pub fn apply(
+        &mut self,
+        signed: &SignedEpochSummary,
+        next_validators: &[PublicKey],
+    ) -> Result<&EpochSummary, WalletError> {
+        let summary = &signed.summary;
+        if summary.chain_id != self.chain_id {
+            return Err(EpochError::WrongChain(summary.chain_id).into());
+        }
+        if let Some(latest) = &self.latest {
+            if summary.epoch != latest.epoch + 1 {
+                return Err(EpochError::OutOfOrder {
+                    expected: latest.epoch + 1,
+                    got: summary.epoch,
+                }
+                .into());
+            }
+        }
+        signed.verify(&self.validators, self.quorum())?;
+        if validator_set_hash(next_validators) != summary.validator_set_hash {
+            return Err(EpochError::ValidatorSetMismatch(summary.epoch).into());
+        }
+
+        self.validators = next_validators.to_vec();
+        Ok(self.latest.insert(summary.clone()))
+    }
+
+    /// Check that `header` commits to the latest verified summary
+    pub fn verify_header(&self, header: &BlockHeader) -> Result<(), WalletError> {
+        let expected = self
+            .latest
+            .as_ref()
+            .map(EpochSummary::commitment)
+            .unwrap_or_default();
+        if header.epoch_commitment != expected {
+            return Err(EpochError::CommitmentMismatch {
+                expected,
+                got: header.epoch_commitment,
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use citrate_consensus::crypto::{generate_keypair, sign_message};
+    use citrate_consensus::types::Signature;
+    use ed25519_dalek::SigningKey;
+
+    fn public(key: &SigningKey) -> PublicKey {
+        PublicKey::new(key.verifying_key().to_bytes())
+    }
+
+    fn signed(epoch: u64, signers: &[SigningKey], next: &[PublicKey]) -> SignedEpochSummary {
+        let summary = EpochSummary {
+            chain_id: 7,
+            epoch,
+            height: epoch * 100,
+            block_hash: Hash::new([epoch as u8; 32]),
+            state_root: Hash::new([epoch as u8 + 100; 32]),
+            blue_score: epoch * 100,
+            validator_set_hash: validator_set_hash(next),
+        };
+        let commitment = summary.commitment();
+        SignedEpochSummary {
+            signatures: signers
+                .iter()
+                .map(|key| (public(key), sign_message(key, commitment.as_bytes())))
+                .collect(),
+            summary,
+        }
+    }
+
+    #[test]
+    fn test_follow_epochs_across_validator_change() {
+        let genesis: Vec<SigningKey> = (0..3).map(|_| generate_keypair()).collect();
+        let genesis_set: Vec<PublicKey> = genesis.iter().map(public).collect();
+        let rotated: Vec<SigningKey> = (0..3).map(|_| generate_keypair()).collect();
+        let rotated_set: Vec<PublicKey> = rotated.iter().map(public).collect();
+
+        let mut verifier = EpochVerifier::new(7, genesis_set.clone());
+        assert_eq!(verifier.quorum(), 3);
+        assert!(verifier.trusted_state_root().is_none());
+
+        // Two of three genesis validators is not enough
+        let short = signed(1, &genesis[..2], &genesis_set);
+        assert!(matches!(
+            verifier.apply(&short, &genesis_set),
+            Err(WalletError::Epoch(EpochError::QuorumNotMet { .. }))
+        ));
+
+        // Epoch 1 hands over to a new set, which must match the committed hash
+        let handover = signed(1, &genesis, &rotated_set);
+        assert!(matches!(
+            verifier.apply(&handover, &genesis_set),
+            Err(WalletError::Epoch(EpochError::ValidatorSetMismatch(1)))
+        ));
+        verifier.apply(&handover, &rotated_set).unwrap();
+        assert_eq!(verifier.trusted_state_root(), Some(Hash::new([101; 32])));
+
+        // The old set can no longer sign, and epochs can't be skipped
+        let stale_signers = signed(2, &genesis, &rotated_set);
+        assert!(verifier.apply(&stale_signers, &rotated_set).is_err());
+        let skipped = signed(3, &rotated, &rotated_set);
+        assert!(matches!(
+            verifier.apply(&skipped, &rotated_set),
+            Err(WalletError::Epoch(EpochError::OutOfOrder {
+                expected: 2,
+                got: 3
+            }))
+        ));
+        let next = signed(2, &rotated, &rotated_set);
+        verifier.apply(&next, &rotated_set).unwrap();
+        assert_eq!(verifier.latest().unwrap().epoch, 2);
+
+        let mut forged = signed(3, &rotated, &rotated_set);
+        forged.summary.state_root = Hash::new([0xee; 32]);
+        forged
+            .signatures
+            .push((public(&rotated[0]), Signature::new([0; 64])));
+        assert!(verifier.apply(&forged, &rotated_set).is_err());
+    }
+}
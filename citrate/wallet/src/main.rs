@@ -2,12 +2,17 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use colored::*;
 use console::Term;
-use dialoguer::{Input, Password, Select};
+use dialoguer::{Confirm, Input, Password, Select};
 use indicatif::{ProgressBar, ProgressStyle};
-use citrate_execution::types::Address;
-use citrate_wallet::{Wallet, WalletConfig};
+use citrate_execution::address_utils::{address_from_hex, to_checksum_address};
+use citrate_execution::units::{format_units, format_units_truncated, parse_amount, Unit};
+use citrate_wallet::config_secrets;
+use citrate_wallet::remote_signer::{ApprovalRequest, Approver};
+use citrate_wallet::{ApprovalPolicy, DerivationPath, SignerService, Wallet, WalletConfig};
 use primitive_types::U256;
+use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
 #[derive(Parser)]
@@ -50,6 +55,31 @@ enum Commands {
         alias: Option<String>,
     },
 
+    /// Create a mnemonic for HD account derivation
+    Init,
+
+    /// Restore HD account derivation from a mnemonic
+    Restore {
+        /// Mnemonic phrase
+        #[arg(short, long)]
+        phrase: Option<String>,
+    },
+
+    /// Derive an account from the wallet mnemonic
+    Derive {
+        /// Account number N in m/44'/60'/0'/0'/N'
+        #[arg(default_value = "0", conflicts_with = "path")]
+        index: u32,
+
+        /// Full derivation path, e.g. m/44'/60'/0'/0'/3'
+        #[arg(short, long)]
+        path: Option<String>,
+
+        /// Account alias
+        #[arg(short, long)]
+        alias: Option<String>,
+    },
+
     /// List all accounts
     List,
 
@@ -88,11 +118,32 @@ enum Commands {
         index: usize,
     },
 
+    /// Export the wallet mnemonic
+    ExportMnemonic,
+
     /// Show wallet info
     Info,
 
     /// Interactive mode
     Interactive,
+
+    /// Run a remote signer for the wallet's accounts
+    ServeSigner {
+        /// Unix socket to listen on
+        #[arg(long, required_unless_present = "http")]
+        socket: Option<PathBuf>,
+
+        /// HTTP address to listen on, e.g. 127.0.0.1:8550
+        #[arg(long)]
+        http: Option<SocketAddr>,
+
+        /// JSON approval policy; defaults allow every account and auto-sign blocks only
+        #[arg(long)]
+        policy: Option<PathBuf>,
+    },
+
+    /// Encrypt a value for the `[secrets]` table of a node or GUI config
+    SealSecret,
 }
 
 #[tokio::main]
@@ -120,6 +171,19 @@ async fn main() -> Result<()> {
         Commands::Import { key, alias } => {
             import_account(&mut wallet, key, alias).await?;
         }
+        Commands::Init => {
+            init_mnemonic(&mut wallet).await?;
+        }
+        Commands::Restore { phrase } => {
+            restore_mnemonic(&mut wallet, phrase).await?;
+        }
+        Commands::Derive { index, path, alias } => {
+            let path = match path {
+                Some(path) => path.parse::<DerivationPath>()?,
+                None => DerivationPath::bip44(index),
+            };
+            derive_account(&mut wallet, &path, alias).await?;
+        }
         Commands::List => {
             list_accounts(&mut wallet).await?;
         }
@@ -138,17 +202,52 @@ async fn main() -> Result<()> {
         Commands::Export { index } => {
             export_key(&mut wallet, index).await?;
         }
+        Commands::ExportMnemonic => {
+            export_mnemonic(&wallet).await?;
+        }
         Commands::Info => {
             show_info(&wallet).await?;
         }
         Commands::Interactive => {
             interactive_mode(&mut wallet).await?;
         }
+        Commands::ServeSigner {
+            socket,
+            http,
+            policy,
+        } => {
+            serve_signer(&mut wallet, socket, http, policy).await?;
+        }
+        Commands::SealSecret => {
+            seal_secret()?;
+        }
     }
 
     Ok(())
 }
 
+fn seal_secret() -> Result<()> {
+    let secret = Password::new()
+        .with_prompt("Enter secret value")
+        .interact()?;
+
+    let passphrase = match config_secrets::passphrase_from_env()? {
+        Some(passphrase) => passphrase,
+        None => Password::new()
+            .with_prompt("Enter secrets passphrase")
+            .with_confirmation("Confirm passphrase", "Passphrases do not match")
+            .interact()?,
+    };
+
+    let sealed = config_secrets::seal(&secret, &passphrase)?;
+
+    println!("{}", "✓ Secret sealed".green());
+    println!("  Add it under [secrets] and reference it as ${{secret:<name>}}:");
+    println!("  {}", sealed);
+
+    Ok(())
+}
+
 async fn create_account(wallet: &mut Wallet, alias: Option<String>) -> Result<()> {
     println!("{}", "Creating new account...".bright_cyan());
 
@@ -161,7 +260,7 @@ async fn create_account(wallet: &mut Wallet, alias: Option<String>) -> Result<()
 
     println!("{}", "✓ Account created successfully!".green());
     println!("  Index:   {}", account.index);
-    println!("  Address: 0x{}", hex::encode(account.address.0));
+    println!("  Address: {}", to_checksum_address(&account.address));
     println!(
         "  Public:  0x{}",
         hex::encode(account.public_key.as_bytes())
@@ -198,7 +297,84 @@ async fn import_account(
 
     println!("{}", "✓ Account imported successfully!".green());
     println!("  Index:   {}", account.index);
-    println!("  Address: 0x{}", hex::encode(account.address.0));
+    println!("  Address: {}", to_checksum_address(&account.address));
+
+    if let Some(alias) = &account.alias {
+        println!("  Alias:   {}", alias);
+    }
+
+    Ok(())
+}
+
+async fn init_mnemonic(wallet: &mut Wallet) -> Result<()> {
+    println!("{}", "Creating HD wallet...".bright_cyan());
+
+    let password = Password::new()
+        .with_prompt("Enter wallet password")
+        .with_confirmation("Confirm password", "Passwords do not match")
+        .interact()?;
+
+    let phrase = wallet.create_mnemonic(&password)?;
+
+    println!("{}", "✓ Mnemonic created successfully!".green());
+    println!(
+        "{}",
+        "⚠ Write these words down. They are the only backup of derived accounts.".bright_red()
+    );
+    println!("  {}", phrase.bright_yellow());
+
+    // Derive the first account so the wallet is usable right away
+    let account = wallet.derive_account(0, &password, None)?;
+    println!("  Index:   {}", account.index);
+    println!("  Address: {}", to_checksum_address(&account.address));
+    if let Some(path) = &account.derivation_path {
+        println!("  Path:    {}", path);
+    }
+
+    Ok(())
+}
+
+async fn restore_mnemonic(wallet: &mut Wallet, phrase: Option<String>) -> Result<()> {
+    println!("{}", "Restoring HD wallet...".bright_cyan());
+
+    let phrase = if let Some(phrase) = phrase {
+        phrase
+    } else {
+        Password::new()
+            .with_prompt("Enter mnemonic phrase")
+            .interact()?
+    };
+
+    let password = Password::new()
+        .with_prompt("Enter wallet password")
+        .with_confirmation("Confirm password", "Passwords do not match")
+        .interact()?;
+
+    wallet.restore_mnemonic(&phrase, &password)?;
+
+    println!("{}", "✓ Mnemonic restored successfully!".green());
+    println!("  Derive accounts with 'wallet derive <N>'");
+
+    Ok(())
+}
+
+async fn derive_account(
+    wallet: &mut Wallet,
+    path: &DerivationPath,
+    alias: Option<String>,
+) -> Result<()> {
+    println!("{}", format!("Deriving account {}...", path).bright_cyan());
+
+    let password = Password::new()
+        .with_prompt("Enter wallet password")
+        .interact()?;
+
+    let account = wallet.derive_account_at(path, &password, alias)?;
+
+    println!("{}", "✓ Account derived successfully!".green());
+    println!("  Index:   {}", account.index);
+    println!("  Address: {}", to_checksum_address(&account.address));
+    println!("  Path:    {}", path);
 
     if let Some(alias) = &account.alias {
         println!("  Alias:   {}", alias);
@@ -261,7 +437,11 @@ async fn list_accounts(wallet: &mut Wallet) -> Result<()> {
                 .unwrap_or(&"<no alias>".to_string())
                 .bright_yellow()
         );
-        println!("      Address: 0x{}", hex::encode(account.address.0));
+        println!("      Address: {}", to_checksum_address(&account.address));
+        println!(
+            "      Path:    {}",
+            account.derivation_path.as_deref().unwrap_or("<imported>")
+        );
 
         if unlocked {
             let balance_latt = format_latt(account.balance);
@@ -297,7 +477,7 @@ async fn show_balance(wallet: &mut Wallet, account: Option<String>) -> Result<()
             pb.finish_and_clear();
 
             println!("{}", "Account Balance:".bright_cyan());
-            println!("  Address: 0x{}", hex::encode(account.address.0));
+            println!("  Address: {}", to_checksum_address(&account.address));
 
             if let Some(alias) = &account.alias {
                 println!("  Alias:   {}", alias);
@@ -305,15 +485,9 @@ async fn show_balance(wallet: &mut Wallet, account: Option<String>) -> Result<()
 
             println!("  Balance: {} LATT", format_latt(balance).bright_green());
             println!("  Nonce:   {}", nonce);
-        } else if let Some(stripped) = acc.strip_prefix("0x") {
+        } else if acc.starts_with("0x") {
             // Show any address balance
-            let addr_bytes = hex::decode(stripped)?;
-            if addr_bytes.len() != 20 {
-                anyhow::bail!("Invalid address length");
-            }
-            let mut addr_array = [0u8; 20];
-            addr_array.copy_from_slice(&addr_bytes);
-            let address = Address(addr_array);
+            let address = address_from_hex(&acc).map_err(anyhow::Error::msg)?;
 
             // Check if it's a wallet account
             wallet.refresh_accounts()?;
@@ -331,7 +505,7 @@ async fn show_balance(wallet: &mut Wallet, account: Option<String>) -> Result<()
             pb.finish_and_clear();
 
             println!("{}", "Address Balance:".bright_cyan());
-            println!("  Address: 0x{}", hex::encode(address.0));
+            println!("  Address: {}", to_checksum_address(&address));
 
             if let Some(account) = account_info {
                 if let Some(alias) = &account.alias {
@@ -361,17 +535,13 @@ async fn send_transaction(
     gas_limit: Option<u64>,
 ) -> Result<()> {
     // Parse recipient address
-    let to_bytes = hex::decode(to.trim_start_matches("0x"))?;
-    if to_bytes.len() != 20 {
-        anyhow::bail!("Invalid recipient address");
-    }
-    let mut to_array = [0u8; 20];
-    to_array.copy_from_slice(&to_bytes);
-    let to_address = Address(to_array);
+    let to_address = address_from_hex(to)
+        .map_err(|e| anyhow::anyhow!("Invalid recipient address: {}", e))?;
 
     // Parse amount
-    let amount_latt = amount.parse::<f64>()?;
-    let amount_wei = latt_to_wei(amount_latt);
+    let amount_wei = U256::from(
+        parse_amount(amount, Unit::Latt).map_err(|e| anyhow::anyhow!("Invalid amount: {}", e))?,
+    );
 
     // Unlock wallet
     let password = Password::new()
@@ -386,8 +556,8 @@ async fn send_transaction(
     // Show transaction details
     println!("{}", "Transaction Details:".bright_cyan());
     println!("  From:   Account #{}", from);
-    println!("  To:     0x{}", hex::encode(to_address.0));
-    println!("  Amount: {} LATT", amount);
+    println!("  To:     {}", to_checksum_address(&to_address));
+    println!("  Amount: {} LATT", format_units(amount_wei, Unit::Latt));
 
     if let Some(gp) = gas_price {
         println!("  Gas Price: {} gwei", gp);
@@ -479,6 +649,32 @@ async fn export_key(wallet: &mut Wallet, index: usize) -> Result<()> {
     println!("{}", "Private key:".bright_cyan());
     println!("  {}", private_key.bright_yellow());
 
+    wallet.refresh_accounts()?;
+    if let Some(path) = wallet
+        .get_account(index)
+        .and_then(|a| a.derivation_path.as_ref())
+    {
+        println!("  Path: {}", path);
+    }
+
+    Ok(())
+}
+
+async fn export_mnemonic(wallet: &Wallet) -> Result<()> {
+    println!(
+        "{}",
+        "⚠ WARNING: Anyone with your mnemonic controls every derived account!".bright_red()
+    );
+
+    let password = Password::new()
+        .with_prompt("Enter wallet password")
+        .interact()?;
+
+    let phrase = wallet.export_mnemonic(&password)?;
+
+    println!("{}", "Mnemonic:".bright_cyan());
+    println!("  {}", phrase.bright_yellow());
+
     Ok(())
 }
 
@@ -502,6 +698,86 @@ async fn show_info(wallet: &Wallet) -> Result<()> {
     Ok(())
 }
 
+/// Asks on the terminal before signing requests the policy holds back
+struct ConsoleApprover;
+
+impl Approver for ConsoleApprover {
+    fn confirm(&self, request: &ApprovalRequest) -> bool {
+        println!();
+        println!("{}", "Signing request needs approval".bright_yellow());
+        println!("  Client:  {}", request.client);
+        println!("  Account: 0x{}", request.account);
+        println!("  Request: {}", request.summary);
+        println!("  Reason:  {}", request.reason);
+
+        Confirm::new()
+            .with_prompt("Sign?")
+            .default(false)
+            .interact()
+            .unwrap_or(false)
+    }
+}
+
+async fn serve_signer(
+    wallet: &mut Wallet,
+    socket: Option<PathBuf>,
+    http: Option<SocketAddr>,
+    policy: Option<PathBuf>,
+) -> Result<()> {
+    let policy = match policy {
+        Some(path) => ApprovalPolicy::load(path)?,
+        None => ApprovalPolicy::default(),
+    };
+
+    let password = Password::new()
+        .with_prompt("Enter password to unlock wallet")
+        .interact()?;
+    wallet.unlock(&password)?;
+
+    let service = Arc::new(SignerService::new(
+        wallet.unlocked_keys()?,
+        policy,
+        Arc::new(ConsoleApprover),
+    ));
+
+    println!("{}", "Remote signer accounts:".bright_cyan());
+    for account in service.accounts() {
+        println!("  0x{}", account);
+    }
+
+    let unix = async {
+        match socket {
+            #[cfg(unix)]
+            Some(path) => {
+                println!("Listening on unix://{}", path.display());
+                service.clone().serve_unix(path).await
+            }
+            #[cfg(not(unix))]
+            Some(_) => Err(citrate_wallet::WalletError::RemoteSigner(
+                "unix sockets are not supported on this platform".to_string(),
+            )),
+            None => std::future::pending().await,
+        }
+    };
+    let tcp = async {
+        match http {
+            Some(addr) => {
+                println!("Listening on http://{}", addr);
+                service.clone().serve_http(addr).await
+            }
+            None => std::future::pending().await,
+        }
+    };
+
+    tokio::select! {
+        result = unix => result?,
+        result = tcp => result?,
+        _ = tokio::signal::ctrl_c() => println!("Remote signer stopped"),
+    }
+
+    Ok(())
+}
+
 async fn interactive_mode(wallet: &mut Wallet) -> Result<()> {
     let term = Term::stdout();
 
@@ -602,30 +878,7 @@ async fn interactive_mode(wallet: &mut Wallet) -> Result<()> {
     Ok(())
 }
 
-/// Format U256 wei to LATT string
+/// Format U256 wei to LATT string with up to 6 decimal places
 fn format_latt(wei: U256) -> String {
-    let decimals = U256::from(10).pow(U256::from(18));
-    let whole = wei / decimals;
-    let fraction = wei % decimals;
-
-    // Format with up to 6 decimal places
-    let fraction_str = format!("{:018}", fraction);
-    let fraction_trimmed = if fraction_str.len() >= 6 {
-        fraction_str[..6].trim_end_matches('0')
-    } else {
-        fraction_str.trim_end_matches('0')
-    };
-
-    if fraction_trimmed.is_empty() {
-        format!("{}", whole)
-    } else {
-        format!("{}.{}", whole, fraction_trimmed)
-    }
-}
-
-/// Convert LATT to wei
-fn latt_to_wei(latt: f64) -> U256 {
-    let wei_per_latt = 1_000_000_000_000_000_000u128; // 10^18
-    let wei = (latt * wei_per_latt as f64) as u128;
-    U256::from(wei)
+    format_units_truncated(wei, Unit::Latt, 6)
 }
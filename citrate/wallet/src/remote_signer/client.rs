@@ -0,0 +1,303 @@
+//! Client for a remote signing service
+//!
+//! Opens a session on first use and reopens it when the signer reports it
+//! expired. Every signature returned is verified before it is used.
+
+use super::protocol::{
+    error_codes, methods, OpenSessionParams, RpcRequest, RpcResponse, SessionInfo, SignatureResult,
+};
+use crate::errors::WalletError;
+use citrate_consensus::crypto as consensus_crypto;
+use citrate_consensus::types::{PublicKey, Signature, Transaction};
+use ed25519_dalek::{Verifier, VerifyingKey};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::Mutex;
+
+/// Where the signing service listens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerEndpoint {
+    /// `unix:///path/to/signer.ipc` or a bare filesystem path
+    Unix(PathBuf),
+    /// `http://host:port` or `https://host:port`
+    Http(String),
+}
+
+impl SignerEndpoint {
+    pub fn parse(endpoint: &str) -> Result<Self, WalletError> {
+        let endpoint = endpoint.trim();
+        if endpoint.starts_with("http://") || endpoint.starts_with("https://") {
+            Ok(Self::Http(endpoint.to_string()))
+        } else if let Some(path) = endpoint.strip_prefix("unix://") {
+            Ok(Self::Unix(PathBuf::from(path)))
+        } else if endpoint.starts_with('/') || endpoint.starts_with('.') {
+            Ok(Self::Unix(PathBuf::from(endpoint)))
+        } else {
+            Err(WalletError::RemoteSigner(format!(
+                "unsupported signer endpoint: {}",
+                endpoint
+            )))
+        }
+    }
+}
+
+impl std::fmt::Display for SignerEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unix(path) => write!(f, "unix://{}", path.display()),
+            Self::Http(url) => write!(f, "{}", url),
+        }
+    }
+}
+
+/// Connection to a remote signer
+pub struct RemoteSigner {
+    endpoint: SignerEndpoint,
+    client_name: String,
+    http: reqwest::Client,
+    session: Mutex<Option<SessionInfo>>,
+    next_id: AtomicU64,
+}
+
+impl RemoteSigner {
+    /// `client_name` is shown to the signer's operator when a request needs confirming
+    pub fn new(endpoint: &str, client_name: &str) -> Result<Self, WalletError> {
+        Ok(Self {
+            endpoint: SignerEndpoint::parse(endpoint)?,
+            client_name: client_name.to_string(),
+            http: reqwest::Client::new(),
+            session: Mutex::new(None),
+            next_id: AtomicU64::new(1),
+        })
+    }
+
+    pub fn endpoint(&self) -> &SignerEndpoint {
+        &self.endpoint
+    }
+
+    /// Protocol version of the signer
+    pub async fn version(&self) -> Result<String, WalletError> {
+        let version = self.call(methods::VERSION, json!([])).await?;
+        Ok(version.as_str().unwrap_or_default().to_string())
+    }
+
+    /// Accounts the signer will sign for
+    pub async fn list_accounts(&self) -> Result<Vec<PublicKey>, WalletError> {
+        let accounts: Vec<String> =
+            serde_json::from_value(self.call(methods::LIST, json!([])).await?)?;
+        accounts.iter().map(|a| parse_public_key(a)).collect()
+    }
+
+    /// Sign `tx` with the account in `tx.from`
+    pub async fn sign_transaction(&self, tx: &mut Transaction) -> Result<(), WalletError> {
+        let transaction = serde_json::to_value(&*tx)?;
+        let result = self
+            .call_in_session(
+                methods::SIGN_TRANSACTION,
+                |session| json!({ "session": session, "transaction": transaction }),
+            )
+            .await?;
+
+        tx.signature = decode_signature(result)?;
+        match consensus_crypto::verify_transaction(tx) {
+            Ok(true) => Ok(()),
+            _ => Err(WalletError::RemoteSigner(
+                "signer returned an invalid transaction signature".to_string(),
+            )),
+        }
+    }
+
+    /// Sign `data` of the given content type with `account`
+    pub async fn sign_data(
+        &self,
+        account: &PublicKey,
+        content_type: &str,
+        data: &[u8],
+    ) -> Result<Signature, WalletError> {
+        let result = self
+            .call_in_session(methods::SIGN_DATA, |session| {
+                json!({
+                    "session": session,
+                    "account": hex::encode(account.as_bytes()),
+                    "contentType": content_type,
+                    "data": hex::encode(data),
+                })
+            })
+            .await?;
+
+        let signature = decode_signature(result)?;
+        let key = VerifyingKey::from_bytes(account.as_bytes())
+            .map_err(|e| WalletError::RemoteSigner(e.to_string()))?;
+        key.verify(
+            data,
+            &ed25519_dalek::Signature::from_bytes(signature.as_bytes()),
+        )
+        .map_err(|_| {
+            WalletError::RemoteSigner("signer returned an invalid signature".to_string())
+        })?;
+        Ok(signature)
+    }
+
+    /// End the current session, if any
+    pub async fn close(&self) -> Result<(), WalletError> {
+        if let Some(session) = self.session.lock().await.take() {
+            self.call(
+                methods::CLOSE_SESSION,
+                json!({ "session": session.session }),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Call a signing method, opening a session first and retrying once if
+    /// the signer no longer knows it
+    async fn call_in_session(
+        &self,
+        method: &str,
+        params: impl Fn(&str) -> Value,
+    ) -> Result<Value, WalletError> {
+        for attempt in 0..2 {
+            let session = self.session().await?;
+            match self.call_raw(method, params(&session)).await? {
+                Ok(result) => return Ok(result),
+                Err(error) if error.code == error_codes::INVALID_SESSION && attempt == 0 => {
+                    *self.session.lock().await = None;
+                }
+                Err(error) => return Err(rpc_error(error)),
+            }
+        }
+        unreachable!("the second attempt always returns")
+    }
+
+    async fn session(&self) -> Result<String, WalletError> {
+        let mut current = self.session.lock().await;
+        let now = chrono::Utc::now().timestamp() as u64;
+        if let Some(session) = current.as_ref().filter(|s| s.expires_at > now) {
+            return Ok(session.session.clone());
+        }
+
+        let params = OpenSessionParams {
+            client: self.client_name.clone(),
+            accounts: Vec::new(),
+            ttl_secs: None,
+        };
+        let info: SessionInfo = serde_json::from_value(
+            self.call(methods::OPEN_SESSION, serde_json::to_value(params)?)
+                .await?,
+        )?;
+        let session = info.session.clone();
+        *current = Some(info);
+        Ok(session)
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, WalletError> {
+        self.call_raw(method, params).await?.map_err(rpc_error)
+    }
+
+    async fn call_raw(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<Result<Value, super::protocol::RpcError>, WalletError> {
+        let request = RpcRequest::new(self.next_id.fetch_add(1, Ordering::Relaxed), method, params);
+        let transport = |e: &dyn std::fmt::Display| {
+            WalletError::RemoteSigner(format!("{}: {}", self.endpoint, e))
+        };
+
+        let response: RpcResponse = match &self.endpoint {
+            SignerEndpoint::Http(url) => self
+                .http
+                .post(url)
+                .json(&request)
+                .send()
+                .await
+                .map_err(|e| transport(&e))?
+                .json()
+                .await
+                .map_err(|e| transport(&e))?,
+            #[cfg(unix)]
+            SignerEndpoint::Unix(path) => {
+                use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+                let stream = tokio::net::UnixStream::connect(path)
+                    .await
+                    .map_err(|e| transport(&e))?;
+                let (reader, mut writer) = stream.into_split();
+                let mut line = serde_json::to_string(&request)?;
+                line.push('\n');
+                writer
+                    .write_all(line.as_bytes())
+                    .await
+                    .map_err(|e| transport(&e))?;
+
+                let mut response = String::new();
+                BufReader::new(reader)
+                    .read_line(&mut response)
+                    .await
+                    .map_err(|e| transport(&e))?;
+                serde_json::from_str(&response)?
+            }
+            #[cfg(not(unix))]
+            SignerEndpoint::Unix(_) => {
+                return Err(WalletError::RemoteSigner(
+                    "unix sockets are not supported on this platform".to_string(),
+                ))
+            }
+        };
+
+        Ok(match (response.result, response.error) {
+            (_, Some(error)) => Err(error),
+            (Some(result), None) => Ok(result),
+            (None, None) => Ok(Value::Null),
+        })
+    }
+}
+
+fn rpc_error(error: super::protocol::RpcError) -> WalletError {
+    if error.code == error_codes::REJECTED {
+        WalletError::SigningRejected(error.message)
+    } else {
+        WalletError::RemoteSigner(format!("{} ({})", error.message, error.code))
+    }
+}
+
+fn decode_signature(result: Value) -> Result<Signature, WalletError> {
+    let result: SignatureResult = serde_json::from_value(result)?;
+    let bytes = hex::decode(result.signature.trim_start_matches("0x"))?;
+    let bytes: [u8; 64] = bytes
+        .try_into()
+        .map_err(|_| WalletError::RemoteSigner("signature must be 64 bytes".to_string()))?;
+    Ok(Signature::new(bytes))
+}
+
+fn parse_public_key(account: &str) -> Result<PublicKey, WalletError> {
+    let bytes = hex::decode(account.trim_start_matches("0x"))?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| WalletError::RemoteSigner(format!("bad account key {}", account)))?;
+    Ok(PublicKey::new(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_endpoint() {
+        assert_eq!(
+            SignerEndpoint::parse("unix:///run/citrate/signer.ipc").unwrap(),
+            SignerEndpoint::Unix(PathBuf::from("/run/citrate/signer.ipc"))
+        );
+        assert_eq!(
+            SignerEndpoint::parse("./signer.ipc").unwrap(),
+            SignerEndpoint::Unix(PathBuf::from("./signer.ipc"))
+        );
+        assert_eq!(
+            SignerEndpoint::parse("http://127.0.0.1:8550").unwrap(),
+            SignerEndpoint::Http("http://127.0.0.1:8550".to_string())
+        );
+        assert!(SignerEndpoint::parse("ftp://signer").is_err());
+    }
+}
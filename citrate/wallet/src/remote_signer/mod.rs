@@ -0,0 +1,16 @@
+//! Remote signer support
+//!
+//! Lets keys live in a dedicated signing service instead of the process
+//! that needs signatures. Clients (the node's block producer, the GUI
+//! wallet) talk to the service with [`RemoteSigner`]; the service
+//! ([`SignerService`], run by `wallet serve-signer`) applies an
+//! [`ApprovalPolicy`] to every request before signing.
+
+pub mod client;
+pub mod policy;
+pub mod protocol;
+pub mod server;
+
+pub use client::{RemoteSigner, SignerEndpoint};
+pub use policy::{ApprovalPolicy, Decision};
+pub use server::{ApprovalRequest, Approver, RejectAll, SignerService};
@@ -0,0 +1,301 @@
+//! Approval rules the signing service applies before using a key
+//!
+//! A request is either signed straight away, held for the operator to
+//! confirm, or rejected. Policies are loaded from JSON; amounts may be
+//! written as wei integers or with a unit, e.g. `"250 SALT"`.
+
+use super::protocol::content_types;
+use crate::errors::WalletError;
+use citrate_consensus::types::Transaction;
+use citrate_execution::address_utils::address_from_hex;
+use citrate_execution::types::Address;
+use citrate_execution::units::{parse_amount, Unit};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::path::Path;
+
+/// What to do with a signing request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    Approve,
+    /// Ask the operator, with the reason confirmation is needed
+    Confirm(String),
+    Reject(String),
+}
+
+/// Signing policy for a remote signer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ApprovalPolicy {
+    /// Hex public keys clients may use; empty allows every unlocked account
+    pub accounts: Vec<String>,
+    /// Largest value a single transaction may move (wei)
+    #[serde(deserialize_with = "deserialize_amount")]
+    pub max_value_per_tx: Option<u128>,
+    /// Largest total value one session may move (wei)
+    #[serde(deserialize_with = "deserialize_amount")]
+    pub max_value_per_session: Option<u128>,
+    /// Transactions moving more than this need operator confirmation (wei)
+    #[serde(deserialize_with = "deserialize_amount")]
+    pub confirm_above: Option<u128>,
+    /// Addresses transactions may be sent to; empty allows any
+    pub recipients: Vec<String>,
+    /// Whether transactions without a recipient may be signed
+    pub allow_contract_creation: bool,
+    /// Content types `account_signData` accepts
+    pub content_types: Vec<String>,
+    /// Content types signed without asking the operator
+    pub auto_sign_content_types: Vec<String>,
+    /// Longest session a client may open
+    pub max_session_ttl_secs: u64,
+    /// Signing requests allowed per session
+    pub max_requests_per_session: Option<u64>,
+}
+
+impl Default for ApprovalPolicy {
+    fn default() -> Self {
+        Self {
+            accounts: Vec::new(),
+            max_value_per_tx: None,
+            max_value_per_session: None,
+            confirm_above: None,
+            recipients: Vec::new(),
+            allow_contract_creation: true,
+            content_types: vec![
+                content_types::BLOCK.to_string(),
+                content_types::TEXT.to_string(),
+            ],
+            auto_sign_content_types: vec![content_types::BLOCK.to_string()],
+            max_session_ttl_secs: 60 * 60,
+            max_requests_per_session: None,
+        }
+    }
+}
+
+impl ApprovalPolicy {
+    /// Load and validate a JSON policy file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, WalletError> {
+        let policy: Self = serde_json::from_slice(&std::fs::read(path)?)?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    /// Check account keys and recipient addresses are well formed
+    pub fn validate(&self) -> Result<(), WalletError> {
+        for account in &self.accounts {
+            let key = hex::decode(account.trim_start_matches("0x"))?;
+            if key.len() != 32 {
+                return Err(WalletError::Other(format!(
+                    "Policy account {} is not a 32-byte public key",
+                    account
+                )));
+            }
+        }
+        for recipient in &self.recipients {
+            address_from_hex(recipient).map_err(WalletError::InvalidAddress)?;
+        }
+        if self.max_session_ttl_secs == 0 {
+            return Err(WalletError::Other(
+                "maxSessionTtlSecs must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether clients may use the account with this hex public key
+    pub fn allows_account(&self, account: &str) -> bool {
+        self.accounts.is_empty()
+            || self
+                .accounts
+                .iter()
+                .any(|a| normalize_hex(a) == normalize_hex(account))
+    }
+
+    /// Decide on a transaction, given what the session has already signed
+    pub fn check_transaction(
+        &self,
+        tx: &Transaction,
+        session_spent: u128,
+        session_requests: u64,
+    ) -> Decision {
+        if let Some(max) = self.max_requests_per_session {
+            if session_requests >= max {
+                return Decision::Reject(format!("session request limit of {} reached", max));
+            }
+        }
+
+        match &tx.to {
+            None if !self.allow_contract_creation => {
+                return Decision::Reject("contract creation is not allowed".to_string());
+            }
+            None => {}
+            Some(to) if !self.recipients.is_empty() => {
+                let to = Address::from_public_key(to);
+                let allowed = self
+                    .recipients
+                    .iter()
+                    .filter_map(|r| address_from_hex(r).ok())
+                    .any(|r| r == to);
+                if !allowed {
+                    return Decision::Reject(format!(
+                        "recipient 0x{} is not allowed",
+                        hex::encode(to.0)
+                    ));
+                }
+            }
+            Some(_) => {}
+        }
+
+        if let Some(max) = self.max_value_per_tx {
+            if tx.value > max {
+                return Decision::Reject(format!(
+                    "value {} exceeds the per-transaction limit of {}",
+                    tx.value, max
+                ));
+            }
+        }
+        if let Some(max) = self.max_value_per_session {
+            if session_spent.saturating_add(tx.value) > max {
+                return Decision::Reject(format!(
+                    "value {} exceeds the session limit of {} ({} already signed)",
+                    tx.value, max, session_spent
+                ));
+            }
+        }
+
+        match self.confirm_above {
+            Some(threshold) if tx.value > threshold => {
+                Decision::Confirm(format!("value {} is above {}", tx.value, threshold))
+            }
+            _ => Decision::Approve,
+        }
+    }
+
+    /// Decide on an `account_signData` request
+    pub fn check_data(&self, content_type: &str, session_requests: u64) -> Decision {
+        if let Some(max) = self.max_requests_per_session {
+            if session_requests >= max {
+                return Decision::Reject(format!("session request limit of {} reached", max));
+            }
+        }
+        if !self.content_types.iter().any(|c| c == content_type) {
+            return Decision::Reject(format!("content type {} is not allowed", content_type));
+        }
+        if self
+            .auto_sign_content_types
+            .iter()
+            .any(|c| c == content_type)
+        {
+            Decision::Approve
+        } else {
+            Decision::Confirm(format!("signing {} data", content_type))
+        }
+    }
+}
+
+fn normalize_hex(value: &str) -> String {
+    value.trim_start_matches("0x").to_ascii_lowercase()
+}
+
+/// Accept `null`, a wei integer, or a string with an optional unit
+fn deserialize_amount<'de, D>(deserializer: D) -> Result<Option<u128>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Amount {
+        Wei(u128),
+        Text(String),
+    }
+
+    match Option::<Amount>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Amount::Wei(wei)) => Ok(Some(wei)),
+        Some(Amount::Text(text)) => parse_amount(&text, Unit::Wei)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use citrate_consensus::types::{Hash, PublicKey, Signature};
+
+    fn transfer(to: Option<[u8; 20]>, value: u128) -> Transaction {
+        Transaction {
+            hash: Hash::default(),
+            nonce: 0,
+            from: PublicKey::new([1; 32]),
+            to: to.map(|addr| {
+                let mut key = [0u8; 32];
+                key[..20].copy_from_slice(&addr);
+                PublicKey::new(key)
+            }),
+            value,
+            gas_limit: 21_000,
+            gas_price: 1_000_000_000,
+            data: Vec::new(),
+            signature: Signature::new([0; 64]),
+            tx_type: None,
+        }
+    }
+
+    #[test]
+    fn test_policy_limits() {
+        let policy: ApprovalPolicy = serde_json::from_str(
+            r#"{
+                "maxValuePerTx": "10 SALT",
+                "maxValuePerSession": "15 SALT",
+                "confirmAbove": "1 SALT",
+                "recipients": ["0x1111111111111111111111111111111111111111"],
+                "allowContractCreation": false,
+                "maxRequestsPerSession": 3
+            }"#,
+        )
+        .unwrap();
+        policy.validate().unwrap();
+        let salt = 1_000_000_000_000_000_000u128;
+
+        assert_eq!(
+            policy.check_transaction(&transfer(Some([0x11; 20]), salt / 2), 0, 0),
+            Decision::Approve
+        );
+        assert!(matches!(
+            policy.check_transaction(&transfer(Some([0x11; 20]), 2 * salt), 0, 0),
+            Decision::Confirm(_)
+        ));
+        for rejected in [
+            policy.check_transaction(&transfer(Some([0x22; 20]), 1), 0, 0),
+            policy.check_transaction(&transfer(None, 1), 0, 0),
+            policy.check_transaction(&transfer(Some([0x11; 20]), 11 * salt), 0, 0),
+            policy.check_transaction(&transfer(Some([0x11; 20]), 6 * salt), 10 * salt, 1),
+            policy.check_transaction(&transfer(Some([0x11; 20]), 1), 0, 3),
+        ] {
+            assert!(matches!(rejected, Decision::Reject(_)), "{:?}", rejected);
+        }
+    }
+
+    #[test]
+    fn test_data_and_accounts() {
+        let policy = ApprovalPolicy {
+            accounts: vec![format!("0x{}", "AB".repeat(32))],
+            ..Default::default()
+        };
+        assert!(policy.allows_account(&"ab".repeat(32)));
+        assert!(!policy.allows_account(&"cd".repeat(32)));
+
+        assert_eq!(
+            policy.check_data(content_types::BLOCK, 0),
+            Decision::Approve
+        );
+        assert!(matches!(
+            policy.check_data(content_types::TEXT, 0),
+            Decision::Confirm(_)
+        ));
+        assert!(matches!(
+            policy.check_data("application/octet-stream", 0),
+            Decision::Reject(_)
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+//! Wire format of the remote signer
+//!
+//! JSON-RPC 2.0, one request per HTTP POST body or per line on the unix
+//! socket. Method names follow Clef (`account_list`, `account_signData`, ...)
+//! with two additions for sessions: every signing call carries a session id
+//! obtained from `signer_openSession`, scoped to a set of accounts and an
+//! expiry.
+
+use citrate_consensus::types::Transaction;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Protocol version reported by `account_version`
+pub const PROTOCOL_VERSION: &str = "1.0.0";
+
+pub mod methods {
+    pub const VERSION: &str = "account_version";
+    pub const LIST: &str = "account_list";
+    pub const OPEN_SESSION: &str = "signer_openSession";
+    pub const CLOSE_SESSION: &str = "signer_closeSession";
+    pub const SIGN_TRANSACTION: &str = "account_signTransaction";
+    pub const SIGN_DATA: &str = "account_signData";
+}
+
+/// Content types accepted by `account_signData`
+pub mod content_types {
+    /// A block hash, signed by a block producer
+    pub const BLOCK: &str = "application/x-citrate-block";
+    /// An arbitrary message shown to the user
+    pub const TEXT: &str = "text/plain";
+}
+
+pub mod error_codes {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+    /// Unknown or expired session
+    pub const INVALID_SESSION: i64 = -32001;
+    /// Denied by policy or by the operator
+    pub const REJECTED: i64 = -32002;
+    /// Account is not held by the signer or not in the session
+    pub const UNKNOWN_ACCOUNT: i64 = -32003;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcRequest {
+    pub jsonrpc: String,
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+impl RpcRequest {
+    pub fn new(id: u64, method: &str, params: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id: Value::from(id),
+            method: method.to_string(),
+            params,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcResponse {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl RpcResponse {
+    pub fn success(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn failure(id: Value, code: i64, message: impl Into<String>) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(RpcError {
+                code,
+                message: message.into(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+/// `signer_openSession` parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenSessionParams {
+    /// Name of the client, shown when the operator is asked to confirm
+    pub client: String,
+    /// Hex public keys the session may sign for; empty asks for every
+    /// account the policy allows
+    #[serde(default)]
+    pub accounts: Vec<String>,
+    /// Requested lifetime, capped by the policy
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+}
+
+/// `signer_openSession` result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionInfo {
+    pub session: String,
+    /// Unix time the session expires at
+    pub expires_at: u64,
+    /// Hex public keys granted to the session
+    pub accounts: Vec<String>,
+}
+
+/// `account_signTransaction` parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignTransactionParams {
+    pub session: String,
+    /// Unsigned transaction; `from` selects the signing account
+    pub transaction: Transaction,
+}
+
+/// `account_signData` parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignDataParams {
+    pub session: String,
+    /// Hex public key of the signing account
+    pub account: String,
+    pub content_type: String,
+    /// Hex encoded payload
+    pub data: String,
+}
+
+/// Result of both signing calls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureResult {
+    /// Hex encoded Ed25519 signature
+    pub signature: String,
+}
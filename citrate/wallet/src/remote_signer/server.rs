@@ -0,0 +1,438 @@
+//! Signing service holding the keys
+//!
+//! Requests are checked against the [`ApprovalPolicy`] and, where the policy
+//! asks for it, confirmed by an [`Approver`] before a key is used. Served
+//! over a unix socket (newline-delimited JSON-RPC) and/or HTTP.
+
+use super::policy::{ApprovalPolicy, Decision};
+use super::protocol::{
+    content_types, error_codes, methods, OpenSessionParams, RpcRequest, RpcResponse, SessionInfo,
+    SignDataParams, SignTransactionParams, SignatureResult, PROTOCOL_VERSION,
+};
+use crate::errors::WalletError;
+use axum::{extract::State, routing::post, Json, Router};
+use citrate_consensus::crypto as consensus_crypto;
+use ed25519_dalek::{Signer, SigningKey};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// A request the policy wants the operator to confirm
+#[derive(Debug, Clone)]
+pub struct ApprovalRequest {
+    /// Client name given when the session was opened
+    pub client: String,
+    /// Hex public key of the signing account
+    pub account: String,
+    /// What is being signed
+    pub summary: String,
+    /// Why confirmation is needed
+    pub reason: String,
+}
+
+/// Asks the operator about requests the policy does not approve outright
+pub trait Approver: Send + Sync {
+    /// Called on a blocking thread; return `true` to sign
+    fn confirm(&self, request: &ApprovalRequest) -> bool;
+}
+
+/// Approver for unattended signers: anything needing confirmation is refused
+pub struct RejectAll;
+
+impl Approver for RejectAll {
+    fn confirm(&self, _request: &ApprovalRequest) -> bool {
+        false
+    }
+}
+
+struct Session {
+    client: String,
+    accounts: Vec<String>,
+    expires_at: u64,
+    requests: u64,
+    spent: u128,
+}
+
+/// Remote signing service
+pub struct SignerService {
+    keys: HashMap<String, SigningKey>,
+    policy: ApprovalPolicy,
+    approver: Arc<dyn Approver>,
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+type RpcResult = Result<Value, (i64, String)>;
+
+impl SignerService {
+    pub fn new(keys: Vec<SigningKey>, policy: ApprovalPolicy, approver: Arc<dyn Approver>) -> Self {
+        let keys = keys
+            .into_iter()
+            .map(|key| (hex::encode(key.verifying_key().as_bytes()), key))
+            .collect();
+        Self {
+            keys,
+            policy,
+            approver,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Hex public keys clients may use
+    pub fn accounts(&self) -> Vec<String> {
+        let mut accounts: Vec<String> = self
+            .keys
+            .keys()
+            .filter(|account| self.policy.allows_account(account))
+            .cloned()
+            .collect();
+        accounts.sort();
+        accounts
+    }
+
+    /// Handle one JSON-RPC request
+    pub async fn handle(&self, request: RpcRequest) -> RpcResponse {
+        let id = request.id.clone();
+        if request.jsonrpc != "2.0" {
+            return RpcResponse::failure(id, error_codes::INVALID_REQUEST, "jsonrpc must be 2.0");
+        }
+
+        let result = match request.method.as_str() {
+            methods::VERSION => Ok(json!(PROTOCOL_VERSION)),
+            methods::LIST => Ok(json!(self.accounts())),
+            methods::OPEN_SESSION => match params(request.params) {
+                Ok(params) => self.open_session(params).await,
+                Err(e) => Err(e),
+            },
+            methods::CLOSE_SESSION => match params::<Value>(request.params) {
+                Ok(params) => {
+                    let session = params["session"].as_str().unwrap_or_default();
+                    Ok(json!(self.sessions.lock().await.remove(session).is_some()))
+                }
+                Err(e) => Err(e),
+            },
+            methods::SIGN_TRANSACTION => match params(request.params) {
+                Ok(params) => self.sign_transaction(params).await,
+                Err(e) => Err(e),
+            },
+            methods::SIGN_DATA => match params(request.params) {
+                Ok(params) => self.sign_data(params).await,
+                Err(e) => Err(e),
+            },
+            other => Err((
+                error_codes::METHOD_NOT_FOUND,
+                format!("method {} not found", other),
+            )),
+        };
+
+        match result {
+            Ok(value) => RpcResponse::success(id, value),
+            Err((code, message)) => RpcResponse::failure(id, code, message),
+        }
+    }
+
+    /// Handle a raw request body, answering malformed JSON with a parse error
+    pub async fn handle_raw(&self, body: &str) -> String {
+        let response = match serde_json::from_str::<RpcRequest>(body) {
+            Ok(request) => self.handle(request).await,
+            Err(e) => RpcResponse::failure(Value::Null, error_codes::PARSE_ERROR, e.to_string()),
+        };
+        serde_json::to_string(&response).unwrap_or_default()
+    }
+
+    async fn open_session(&self, params: OpenSessionParams) -> RpcResult {
+        let allowed = self.accounts();
+        let accounts = if params.accounts.is_empty() {
+            allowed
+        } else {
+            let mut accounts = Vec::with_capacity(params.accounts.len());
+            for account in &params.accounts {
+                let account = account.trim_start_matches("0x").to_ascii_lowercase();
+                if !allowed.contains(&account) {
+                    return Err((
+                        error_codes::UNKNOWN_ACCOUNT,
+                        format!("account {} is not available", account),
+                    ));
+                }
+                accounts.push(account);
+            }
+            accounts
+        };
+
+        let ttl = params
+            .ttl_secs
+            .unwrap_or(self.policy.max_session_ttl_secs)
+            .min(self.policy.max_session_ttl_secs);
+        let mut id = [0u8; 32];
+        OsRng.fill_bytes(&mut id);
+        let info = SessionInfo {
+            session: hex::encode(id),
+            expires_at: now() + ttl,
+            accounts: accounts.clone(),
+        };
+
+        let mut sessions = self.sessions.lock().await;
+        let now = now();
+        sessions.retain(|_, session| session.expires_at > now);
+        sessions.insert(
+            info.session.clone(),
+            Session {
+                client: params.client.clone(),
+                accounts,
+                expires_at: info.expires_at,
+                requests: 0,
+                spent: 0,
+            },
+        );
+
+        info!(
+            "Remote signer session opened for {} ({} accounts, {}s)",
+            params.client,
+            info.accounts.len(),
+            ttl
+        );
+        Ok(json!(info))
+    }
+
+    async fn sign_transaction(&self, params: SignTransactionParams) -> RpcResult {
+        let mut tx = params.transaction;
+        let account = hex::encode(tx.from.as_bytes());
+        let key = self.key(&account)?;
+
+        let (client, decision) = {
+            let mut sessions = self.sessions.lock().await;
+            let session = session(&mut sessions, &params.session, &account)?;
+            let decision = self
+                .policy
+                .check_transaction(&tx, session.spent, session.requests);
+            // Reserve the request against the session's limits while the
+            // operator decides
+            if !matches!(decision, Decision::Reject(_)) {
+                session.requests += 1;
+                session.spent = session.spent.saturating_add(tx.value);
+            }
+            (session.client.clone(), decision)
+        };
+        let reserved = !matches!(decision, Decision::Reject(_));
+
+        let to = tx
+            .to
+            .map(|to| format!("0x{}", hex::encode(to.as_bytes())))
+            .unwrap_or_else(|| "contract creation".to_string());
+        let summary = format!(
+            "transaction nonce {} to {} value {} wei gas {} @ {}",
+            tx.nonce, to, tx.value, tx.gas_limit, tx.gas_price
+        );
+        if let Err(e) = self.approve(decision, &client, &account, summary).await {
+            if reserved {
+                let mut sessions = self.sessions.lock().await;
+                if let Some(session) = sessions.get_mut(&params.session) {
+                    session.requests = session.requests.saturating_sub(1);
+                    session.spent = session.spent.saturating_sub(tx.value);
+                }
+            }
+            return Err(e);
+        }
+
+        consensus_crypto::sign_transaction(&mut tx, &key)
+            .map_err(|e| (error_codes::INTERNAL_ERROR, e.to_string()))?;
+        Ok(json!(SignatureResult {
+            signature: hex::encode(tx.signature.as_bytes()),
+        }))
+    }
+
+    async fn sign_data(&self, params: SignDataParams) -> RpcResult {
+        let account = params.account.trim_start_matches("0x").to_ascii_lowercase();
+        let key = self.key(&account)?;
+        let data = hex::decode(params.data.trim_start_matches("0x"))
+            .map_err(|e| (error_codes::INVALID_PARAMS, format!("data: {}", e)))?;
+        if params.content_type == content_types::BLOCK && data.len() != 32 {
+            return Err((
+                error_codes::INVALID_PARAMS,
+                "block data must be a 32-byte hash".to_string(),
+            ));
+        }
+
+        let (client, decision) = {
+            let mut sessions = self.sessions.lock().await;
+            let session = session(&mut sessions, &params.session, &account)?;
+            let decision = self
+                .policy
+                .check_data(&params.content_type, session.requests);
+            if !matches!(decision, Decision::Reject(_)) {
+                session.requests += 1;
+            }
+            (session.client.clone(), decision)
+        };
+        let reserved = !matches!(decision, Decision::Reject(_));
+
+        let summary = match params.content_type.as_str() {
+            content_types::TEXT => format!("message: {}", String::from_utf8_lossy(&data)),
+            _ => format!("{}: 0x{}", params.content_type, hex::encode(&data)),
+        };
+        if let Err(e) = self.approve(decision, &client, &account, summary).await {
+            if reserved {
+                if let Some(session) = self.sessions.lock().await.get_mut(&params.session) {
+                    session.requests = session.requests.saturating_sub(1);
+                }
+            }
+            return Err(e);
+        }
+
+        Ok(json!(SignatureResult {
+            signature: hex::encode(key.sign(&data).to_bytes()),
+        }))
+    }
+
+    fn key(&self, account: &str) -> Result<SigningKey, (i64, String)> {
+        self.keys
+            .get(account)
+            .filter(|_| self.policy.allows_account(account))
+            .cloned()
+            .ok_or_else(|| {
+                (
+                    error_codes::UNKNOWN_ACCOUNT,
+                    format!("account {} is not available", account),
+                )
+            })
+    }
+
+    async fn approve(
+        &self,
+        decision: Decision,
+        client: &str,
+        account: &str,
+        summary: String,
+    ) -> Result<(), (i64, String)> {
+        let reason = match decision {
+            Decision::Approve => return Ok(()),
+            Decision::Reject(reason) => {
+                warn!(
+                    "Remote signer rejected {} for {}: {}",
+                    summary, client, reason
+                );
+                return Err((error_codes::REJECTED, reason));
+            }
+            Decision::Confirm(reason) => reason,
+        };
+
+        let request = ApprovalRequest {
+            client: client.to_string(),
+            account: account.to_string(),
+            summary,
+            reason,
+        };
+        let approver = self.approver.clone();
+        let confirmed = tokio::task::spawn_blocking(move || approver.confirm(&request))
+            .await
+            .unwrap_or(false);
+        if confirmed {
+            Ok(())
+        } else {
+            Err((error_codes::REJECTED, "declined by operator".to_string()))
+        }
+    }
+
+    /// Serve newline-delimited JSON-RPC on a unix socket only the owner can open
+    #[cfg(unix)]
+    pub async fn serve_unix(
+        self: Arc<Self>,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<(), WalletError> {
+        use std::os::unix::fs::PermissionsExt;
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let path = path.as_ref();
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        let listener = tokio::net::UnixListener::bind(path)?;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        info!("Remote signer listening on {}", path.display());
+
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let service = self.clone();
+            tokio::spawn(async move {
+                let (reader, mut writer) = stream.into_split();
+                let mut lines = BufReader::new(reader).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let mut response = service.handle_raw(&line).await;
+                    response.push('\n');
+                    if writer.write_all(response.as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    }
+
+    /// Serve JSON-RPC over HTTP POST
+    pub async fn serve_http(self: Arc<Self>, addr: SocketAddr) -> Result<(), WalletError> {
+        if !addr.ip().is_loopback() {
+            warn!(
+                "Remote signer HTTP endpoint {} is reachable from other hosts; \
+                 put it behind TLS and an authenticating proxy",
+                addr
+            );
+        }
+
+        let app = Router::new()
+            .route("/", post(http_handler))
+            .with_state(self);
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        info!("Remote signer listening on http://{}", addr);
+        axum::serve(listener, app).await?;
+        Ok(())
+    }
+}
+
+async fn http_handler(State(service): State<Arc<SignerService>>, body: String) -> Json<Value> {
+    let response = service.handle_raw(&body).await;
+    Json(serde_json::from_str(&response).unwrap_or(Value::Null))
+}
+
+/// Look up a live session that may sign for `account`
+fn session<'a>(
+    sessions: &'a mut HashMap<String, Session>,
+    id: &str,
+    account: &str,
+) -> Result<&'a mut Session, (i64, String)> {
+    let session = sessions
+        .get_mut(id)
+        .filter(|session| session.expires_at > now())
+        .ok_or_else(|| {
+            (
+                error_codes::INVALID_SESSION,
+                "unknown or expired session".to_string(),
+            )
+        })?;
+    if !session.accounts.iter().any(|a| a == account) {
+        return Err((
+            error_codes::UNKNOWN_ACCOUNT,
+            format!("account {} is not in this session", account),
+        ));
+    }
+    Ok(session)
+}
+
+/// Accept params as an object or as a single-element positional array
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, (i64, String)> {
+    let params = match params {
+        Value::Array(mut items) if items.len() == 1 => items.remove(0),
+        other => other,
+    };
+    serde_json::from_value(params).map_err(|e| (error_codes::INVALID_PARAMS, e.to_string()))
+}
+
+fn now() -> u64 {
+    chrono::Utc::now().timestamp() as u64
+}
@@ -1,8 +1,10 @@
 use crate::errors::WalletError;
+use crate::hd::{self, DerivationPath};
 use crate::keystore::KeyStore;
 use crate::rpc_client::RpcClient;
 use citrate_consensus::types::{Hash, PublicKey};
 use citrate_execution::types::Address;
+use citrate_execution::units::{format_units_truncated, Unit};
 use primitive_types::U256;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -14,6 +16,8 @@ pub struct Account {
     pub address: Address,
     pub public_key: PublicKey,
     pub alias: Option<String>,
+    /// HD derivation path; `None` for imported keys
+    pub derivation_path: Option<String>,
     pub balance: U256,
     pub nonce: u64,
 }
@@ -88,6 +92,7 @@ impl Wallet {
             address,
             public_key,
             alias,
+            derivation_path: None,
             balance: U256::zero(),
             nonce: 0,
         };
@@ -117,6 +122,78 @@ impl Wallet {
             address,
             public_key,
             alias,
+            derivation_path: None,
+            balance: U256::zero(),
+            nonce: 0,
+        };
+
+        self.accounts.push(account.clone());
+
+        Ok(account)
+    }
+
+    /// Generate a mnemonic for HD derivation and return its phrase. The
+    /// phrase is the only backup of derived accounts.
+    pub fn create_mnemonic(&mut self, password: &str) -> Result<String, WalletError> {
+        let mnemonic = hd::generate_mnemonic()?;
+        self.keystore.set_mnemonic(&mnemonic, password)?;
+        Ok(mnemonic.to_string())
+    }
+
+    /// Restore HD derivation from an existing mnemonic phrase
+    pub fn restore_mnemonic(&mut self, phrase: &str, password: &str) -> Result<(), WalletError> {
+        let mnemonic = hd::parse_mnemonic(phrase)?;
+        self.keystore.set_mnemonic(&mnemonic, password)
+    }
+
+    /// Whether accounts can be derived from a mnemonic
+    pub fn has_mnemonic(&self) -> bool {
+        self.keystore.has_mnemonic()
+    }
+
+    /// Export the wallet mnemonic phrase
+    pub fn export_mnemonic(&self, password: &str) -> Result<String, WalletError> {
+        Ok(self.keystore.export_mnemonic(password)?.to_string())
+    }
+
+    /// Derive the `n`th account at `m/44'/60'/0'/0'/n'`
+    pub fn derive_account(
+        &mut self,
+        n: u32,
+        password: &str,
+        alias: Option<String>,
+    ) -> Result<Account, WalletError> {
+        self.derive_account_at(&DerivationPath::bip44(n), password, alias)
+    }
+
+    /// Derive the account at an arbitrary path. Deriving a path twice
+    /// returns the existing account.
+    pub fn derive_account_at(
+        &mut self,
+        path: &DerivationPath,
+        password: &str,
+        alias: Option<String>,
+    ) -> Result<Account, WalletError> {
+        if let Some(index) = self.keystore.find_derived(path) {
+            self.refresh_accounts()?;
+            return self
+                .get_account(index)
+                .cloned()
+                .ok_or_else(|| WalletError::AccountNotFound(path.to_string()));
+        }
+
+        let verifying_key = self.keystore.derive_key(path, password, alias.clone())?;
+
+        let public_key = PublicKey::new(verifying_key.to_bytes());
+        let address = Address::from_public_key(&public_key);
+
+        let index = self.accounts.len();
+        let account = Account {
+            index,
+            address,
+            public_key,
+            alias,
+            derivation_path: Some(path.to_string()),
             balance: U256::zero(),
             nonce: 0,
         };
@@ -153,6 +230,7 @@ impl Wallet {
                 address,
                 public_key,
                 alias,
+                derivation_path: self.keystore.derivation_path(index).map(str::to_string),
                 balance: U256::zero(),
                 nonce: 0,
             });
@@ -262,6 +340,15 @@ impl Wallet {
         self.keystore.export_private_key(index)
     }
 
+    /// Signing keys of every account; the wallet must be unlocked
+    pub fn unlocked_keys(&self) -> Result<Vec<ed25519_dalek::SigningKey>, WalletError> {
+        self.keystore
+            .list_accounts()
+            .into_iter()
+            .map(|(index, _, _)| self.keystore.get_signing_key(index).cloned())
+            .collect()
+    }
+
     /// Get config
     pub fn config(&self) -> &WalletConfig {
         &self.config
@@ -275,17 +362,5 @@ impl Wallet {
 
 /// Format U256 as LATT with decimals
 fn format_latt(value: U256) -> String {
-    let decimals = U256::from(10).pow(U256::from(18));
-    let whole = value / decimals;
-    let fraction = value % decimals;
-
-    // Format with up to 6 decimal places
-    let fraction_str = format!("{:018}", fraction);
-    let fraction_trimmed = fraction_str[..6].trim_end_matches('0');
-
-    if fraction_trimmed.is_empty() {
-        format!("{}", whole)
-    } else {
-        format!("{}.{}", whole, fraction_trimmed)
-    }
+    format_units_truncated(value, Unit::Latt, 6)
 }
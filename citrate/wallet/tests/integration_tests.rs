@@ -451,6 +451,185 @@ fn test_different_keys_different_addresses() {
     );
 }
 
+// ============================================================================
+// HD Derivation Tests
+// ============================================================================
+
+#[test]
+fn test_derive_accounts_from_mnemonic() {
+    if should_skip_tests() {
+        return;
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let mut wallet = create_test_wallet(&temp_dir);
+    let password = "test_password_123";
+
+    let phrase = wallet.create_mnemonic(password).unwrap();
+    assert_eq!(phrase.split_whitespace().count(), 12);
+    assert!(wallet.has_mnemonic());
+
+    let first = wallet.derive_account(0, password, None).unwrap();
+    let second = wallet.derive_account(1, password, Some("savings".into())).unwrap();
+
+    assert_eq!(first.derivation_path.as_deref(), Some("m/44'/60'/0'/0'/0'"));
+    assert_eq!(second.derivation_path.as_deref(), Some("m/44'/60'/0'/0'/1'"));
+    assert_ne!(first.address, second.address);
+
+    // Deriving the same path again returns the existing account
+    let again = wallet.derive_account(1, password, None).unwrap();
+    assert_eq!(again.index, second.index);
+    assert_eq!(wallet.list_accounts().len(), 2);
+
+    // Paths survive a reload from disk
+    let mut reloaded = create_test_wallet(&temp_dir);
+    reloaded.refresh_accounts().unwrap();
+    assert_eq!(
+        reloaded.get_account(1).unwrap().derivation_path.as_deref(),
+        Some("m/44'/60'/0'/0'/1'")
+    );
+    assert_eq!(reloaded.export_mnemonic(password).unwrap(), phrase);
+}
+
+#[test]
+fn test_restore_mnemonic_reproduces_accounts() {
+    if should_skip_tests() {
+        return;
+    }
+
+    let password = "test_password_123";
+
+    let temp_dir = TempDir::new().unwrap();
+    let mut wallet = create_test_wallet(&temp_dir);
+    let phrase = wallet.create_mnemonic(password).unwrap();
+    let original = wallet.derive_account(3, password, None).unwrap();
+
+    let temp_dir2 = TempDir::new().unwrap();
+    let mut restored = create_test_wallet(&temp_dir2);
+    restored.restore_mnemonic(&phrase, "other_password_456").unwrap();
+    let account = restored
+        .derive_account(3, "other_password_456", None)
+        .unwrap();
+
+    assert_eq!(account.address, original.address);
+
+    // Derived keys unlock and export like any other key
+    wallet.unlock(password).unwrap();
+    restored.unlock("other_password_456").unwrap();
+    assert_eq!(
+        restored.export_private_key(0).unwrap(),
+        wallet.export_private_key(0).unwrap()
+    );
+}
+
+#[test]
+fn test_mnemonic_errors() {
+    if should_skip_tests() {
+        return;
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let mut wallet = create_test_wallet(&temp_dir);
+    let password = "test_password_123";
+
+    // No mnemonic yet
+    assert!(wallet.derive_account(0, password, None).is_err());
+    assert!(wallet.restore_mnemonic("not a mnemonic", password).is_err());
+
+    // Mixed wallets must keep a single password
+    wallet.import_account(TEST_PRIVATE_KEY, password, None).unwrap();
+    assert!(wallet.create_mnemonic("wrong_password").is_err());
+
+    wallet.create_mnemonic(password).unwrap();
+    assert!(wallet.create_mnemonic(password).is_err());
+    assert!(wallet.derive_account(0, "wrong_password", None).is_err());
+
+    let derived = wallet.derive_account(0, password, None).unwrap();
+    assert_eq!(derived.index, 1);
+    wallet.refresh_accounts().unwrap();
+    assert!(wallet.get_account(0).unwrap().derivation_path.is_none());
+}
+
+// ============================================================================
+// Remote Signer Tests
+// ============================================================================
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_remote_signer_over_unix_socket() {
+    use citrate_consensus::types::{Hash, PublicKey, Signature, Transaction};
+    use citrate_wallet::remote_signer::protocol::content_types;
+    use citrate_wallet::remote_signer::RejectAll;
+    use citrate_wallet::{ApprovalPolicy, RemoteSigner, SignerService, WalletError};
+    use std::sync::Arc;
+
+    if should_skip_tests() {
+        return;
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let mut wallet = create_test_wallet(&temp_dir);
+    let password = "test_password_123";
+    let account = wallet
+        .import_account(TEST_PRIVATE_KEY, password, None)
+        .unwrap();
+    wallet.unlock(password).unwrap();
+
+    let policy = ApprovalPolicy {
+        max_value_per_tx: Some(1_000),
+        ..Default::default()
+    };
+    let service = Arc::new(SignerService::new(
+        wallet.unlocked_keys().unwrap(),
+        policy,
+        Arc::new(RejectAll),
+    ));
+    let socket = temp_dir.path().join("signer.ipc");
+    tokio::spawn(service.serve_unix(socket.clone()));
+    while !socket.exists() {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+
+    let signer = RemoteSigner::new(socket.to_str().unwrap(), "integration-test").unwrap();
+    assert_eq!(signer.list_accounts().await.unwrap(), vec![account.public_key]);
+
+    let mut tx = Transaction {
+        hash: Hash::default(),
+        nonce: 0,
+        from: account.public_key,
+        to: Some(PublicKey::new([0x11; 32])),
+        value: 500,
+        gas_limit: 21_000,
+        gas_price: 1_000_000_000,
+        data: Vec::new(),
+        signature: Signature::new([0; 64]),
+        tx_type: None,
+    };
+    signer.sign_transaction(&mut tx).await.unwrap();
+    assert!(citrate_consensus::crypto::verify_transaction(&tx).unwrap());
+
+    // Over the policy limit
+    tx.value = 5_000;
+    assert!(matches!(
+        signer.sign_transaction(&mut tx).await,
+        Err(WalletError::SigningRejected(_))
+    ));
+
+    // Block hashes are auto-signed, text needs an operator and RejectAll refuses
+    signer
+        .sign_data(&account.public_key, content_types::BLOCK, &[7; 32])
+        .await
+        .unwrap();
+    assert!(matches!(
+        signer
+            .sign_data(&account.public_key, content_types::TEXT, b"hello")
+            .await,
+        Err(WalletError::SigningRejected(_))
+    ));
+
+    signer.close().await.unwrap();
+}
+
 // ============================================================================
 // Config Tests
 // ============================================================================